@@ -25,25 +25,39 @@ from .shared_utils import make_deterministic_seed
 class AutonomousMinerAgent(BaseAgent):
     """Mining agent using Poisson distribution timing for autonomous block generation."""
     
-    def __init__(self, agent_id: str, **kwargs):
+    def __init__(self, agent_id: str, warm_start: bool = False,
+                 block_interval: Optional[float] = None, pool: Optional[str] = None,
+                 pool_registry: Optional[str] = None, **kwargs):
         """
         Initialize autonomous miner agent.
         
         Args:
             agent_id: Unique identifier for this agent
+            warm_start: The daemon starts on an existing chain (fresh_blockchain: false)
+            block_interval: Target seconds between blocks across all miners
+                (block_controller.block_interval); Monero's 120s when unset
+            pool: Mining pool this miner belongs to (block_controller.pools)
+            pool_registry: The pool's miners_<pool>.json
             **kwargs: Additional arguments passed to BaseAgent
         """
         super().__init__(agent_id=agent_id, **kwargs)
+        self.warm_start = warm_start
+        self.block_interval = block_interval or TARGET_BLOCK_TIME_SECS
+        self.pool = pool
+        self.pool_registry = pool_registry
+        self.pool_miners = []  # Agent ids in the pool, from pool_registry
+        self.pool_share = None  # The pool's combined network share
         
         # Mining parameters
         self.hashrate_pct = 0.0  # This miner's hashrate weight
         self.current_difficulty = None
         self.baseline_difficulty = 1  # Fixed baseline for consistent scaling across all miners
         self.last_block_height = 0
+        self.start_height = 0  # Chain height when mining started (non-zero on warm start)
         
         # Deterministic seeding for reproducibility
         self.global_seed = int(os.getenv('SIMULATION_SEED', str(DEFAULT_SIMULATION_SEED)))
-        self.agent_seed = make_deterministic_seed(agent_id)
+        self.agent_seed = make_deterministic_seed(agent_id, self.random_seed)
         random.seed(self.agent_seed)
 
         # Difficulty caching to reduce RPC calls
@@ -72,6 +86,7 @@ class AutonomousMinerAgent(BaseAgent):
         # Log deterministic seeding information
         self.logger.info(f"Global seed: {self.global_seed}, Agent seed: {self.agent_seed}")
         self.logger.info(f"Configured hashrate weight: {self.hashrate_pct}")
+        self._load_pool()
 
         # Wait for daemon to be ready
         if not self.daemon_rpc:
@@ -83,6 +98,11 @@ class AutonomousMinerAgent(BaseAgent):
             self.daemon_rpc.wait_until_ready(max_wait=120)
             info = self.daemon_rpc.get_info()
             self.logger.info(f"Daemon ready at height {info.get('height', 0)}")
+            if self.warm_start:
+                # Continue the preserved chain rather than counting from genesis
+                self.start_height = info.get('height', 0)
+                self.last_block_height = self.start_height
+                self.logger.info(f"Warm start: continuing the chain from height {self.start_height}")
 
             # Use fixed baseline difficulty of 1 for all miners
             # This ensures consistent timing regardless of when miners join:
@@ -109,9 +129,24 @@ class AutonomousMinerAgent(BaseAgent):
         self.mining_start_time = time.time()
         self.logger.info(f"Mining activated with hashrate weight {self.hashrate_pct}")
         self.logger.info(f"Using difficulty-only mode: timing scales with LWMA difficulty adjustments")
-        self.logger.info(f"Base expected block time: {120.0 / (self.hashrate_pct / 100.0):.1f}s "
+        self.logger.info(f"Base expected block time: {self.block_interval / (self.hashrate_pct / 100.0):.1f}s "
                         f"(at baseline difficulty {self.baseline_difficulty})")
         
+    def _load_pool(self):
+        """Read the pool's members and combined share from its registry"""
+        if not self.pool or not self.pool_registry:
+            return
+        try:
+            with open(self.pool_registry, 'r') as f:
+                miners = json.load(f).get("miners", [])
+        except (OSError, ValueError) as e:
+            self.logger.warning(f"Cannot read pool registry {self.pool_registry}: {e}")
+            return
+        self.pool_miners = [m["agent_id"] for m in miners]
+        self.pool_share = sum(m.get("share", 0.0) for m in miners)
+        self.logger.info(f"Mining in pool '{self.pool}' with {len(self.pool_miners)} miners "
+                         f"({self.pool_share:.1%} of the network)")
+
     def _get_mining_address(self) -> Optional[str]:
         """
         Poll for wallet address from miner_info file (created by regular_user.py).
@@ -289,7 +324,7 @@ class AutonomousMinerAgent(BaseAgent):
         Returns:
             Time in seconds until next block discovery attempt
         """
-        TARGET_BLOCK_TIME = TARGET_BLOCK_TIME_SECS
+        TARGET_BLOCK_TIME = self.block_interval
 
         # Use hashrate_pct as a fraction of 100 (baseline assumption)
         # This means if weights sum to 100, blocks arrive at 120s average
@@ -409,6 +444,8 @@ class AutonomousMinerAgent(BaseAgent):
         stats = {
             "agent_id": self.agent_id,
             "hashrate_weight": self.hashrate_pct,
+            "pool": self.pool,
+            "pool_share": self.pool_share,
             "baseline_difficulty": self.baseline_difficulty,
             "current_difficulty": current_difficulty,
             "difficulty_factor": difficulty_factor,
@@ -491,12 +528,15 @@ class AutonomousMinerAgent(BaseAgent):
             summary = {
                 "agent_id": self.agent_id,
                 "hashrate_weight": self.hashrate_pct,
+                "pool": self.pool,
+                "pool_share": self.pool_share,
                 "baseline_difficulty": self.baseline_difficulty,
                 "final_difficulty": final_difficulty,
                 "difficulty_factor": difficulty_factor,
                 "total_blocks_generated": self.blocks_generated,
                 "total_runtime_seconds": total_runtime,
                 "avg_block_time": total_runtime / max(self.blocks_generated, 1),
+                "start_height": self.start_height,
                 "final_height": self.last_block_height,
                 "timestamp": time.time()
             }
@@ -516,6 +556,14 @@ def main():
     parser = AutonomousMinerAgent.create_argument_parser(
         "Autonomous Miner Agent for Monerosim"
     )
+    parser.add_argument('--warm-start', action='store_true',
+                        help='The daemon starts on an existing chain (fresh_blockchain: false)')
+    parser.add_argument('--block-interval', type=float,
+                        help='Target seconds between blocks across all miners (block_controller.block_interval)')
+    parser.add_argument('--pool',
+                        help='Mining pool this miner belongs to (block_controller.pools)')
+    parser.add_argument('--pool-registry',
+                        help="The pool's miners_<pool>.json")
     
     args = parser.parse_args()
     
@@ -528,13 +576,20 @@ def main():
     # Create and run agent
     agent = AutonomousMinerAgent(
         agent_id=args.id,
+        stop_time=args.stop_time,
         shared_dir=args.shared_dir,
         daemon_rpc_port=args.daemon_rpc_port,
         wallet_rpc_port=args.wallet_rpc_port,
         p2p_port=args.p2p_port,
         rpc_host=args.rpc_host,
         log_level=args.log_level,
-        attributes=args.attributes
+        attributes=args.attributes,
+        random_seed=args.random_seed,
+        network=args.network,
+        warm_start=args.warm_start,
+        block_interval=args.block_interval,
+        pool=args.pool,
+        pool_registry=args.pool_registry
     )
     
     agent.run()
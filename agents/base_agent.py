@@ -79,7 +79,10 @@ class BaseAgent(ABC):
                  hash_rate: Optional[int] = None,
                  tx_frequency: Optional[int] = None,
                  remote_daemon: Optional[str] = None,
-                 daemon_selection_strategy: Optional[str] = None):
+                 daemon_selection_strategy: Optional[str] = None,
+                 random_seed: Optional[int] = None,
+                 network: str = "regtest",
+                 stop_time: Optional[int] = None):
         self.agent_id = agent_id
         self._shared_dir = shared_dir
         self.daemon_rpc_port = daemon_rpc_port
@@ -92,6 +95,9 @@ class BaseAgent(ABC):
         self.tx_frequency = tx_frequency
         self.remote_daemon = remote_daemon  # Remote daemon address or "auto"
         self.daemon_selection_strategy = daemon_selection_strategy  # Strategy for auto-discovery
+        self.random_seed = random_seed  # Generator-derived RNG seed, see make_deterministic_seed
+        self.network = network  # general.chain: regtest, testnet or stagenet
+        self.stop_time = stop_time  # Simulated second to leave the run loop (--stop-time)
         self.running = True
         self._is_miner = False  # Default to False
         self._is_wallet_only = False  # Will be set in setup if no local daemon
@@ -264,6 +270,9 @@ class BaseAgent(ABC):
             self.logger.info("Starting main agent loop")
 
             while self.running:
+                if self.past_stop_time():
+                    self.logger.info(f"Reached stop time {self.stop_time}s, stopping")
+                    break
                 self.logger.debug("Starting agent iteration")
                 try:
                     sleep_duration = self.run_iteration()
@@ -464,13 +473,18 @@ class BaseAgent(ABC):
 
         return None
 
+    def past_stop_time(self) -> bool:
+        """Whether the simulated clock has reached ``--stop-time``."""
+        return self.stop_time is not None and time.time() - SHADOW_EPOCH >= self.stop_time
+
     def interruptible_sleep(self, duration: float) -> None:
         """Sleep for *duration* seconds, checking ``self.running`` every second.
 
-        Returns early if ``self.running`` becomes ``False``.
+        Returns early if ``self.running`` becomes ``False`` or the stop time
+        is reached.
         """
         remaining = duration
-        while remaining > 0 and self.running:
+        while remaining > 0 and self.running and not self.past_stop_time():
             time.sleep(min(remaining, 1.0))
             remaining -= 1.0
 
@@ -646,6 +660,52 @@ class BaseAgent(ABC):
             self.logger.error(f"Failed to append to shared list {filename}: {e}")
             raise
         
+    def record_conflict_set(self, tx_hashes: List[str], conflict_id: Optional[str] = None):
+        """Record transactions spending the same inputs in conflicts.json.
+
+        Agents attempting a double spend call this once per set they
+        broadcast; `tx-analyzer conflicts` reads the file back.
+        """
+        record = {
+            'agent_id': self.agent_id,
+            'tx_hashes': list(tx_hashes),
+            'timestamp': time.time(),
+        }
+        if conflict_id is not None:
+            record['conflict_id'] = conflict_id
+        self.append_shared_list('conflicts.json', record)
+
+    def record_tx_attempt(self, succeeded: bool, tx_hash: Optional[str] = None,
+                          reason: Optional[str] = None):
+        """Append one send attempt to tx_attempts.jsonl.
+
+        Agents call this for every transaction they try to send, whether it
+        went through or not; `tx-analyzer tx-success` reads the file back.
+        Lines are appended under the file's lock rather than rewriting the
+        file, since every sending agent writes to it.
+        """
+        record = {
+            'agent_id': self.agent_id,
+            'timestamp': time.time(),
+            'outcome': 'success' if succeeded else 'failure',
+        }
+        if tx_hash is not None:
+            record['tx_hash'] = tx_hash
+        if reason is not None:
+            record['reason'] = reason
+        filepath = self.shared_dir / 'tx_attempts.jsonl'
+        try:
+            with open(filepath.with_suffix('.lock'), 'w') as lock_f:
+                fcntl.flock(lock_f, fcntl.LOCK_EX)
+                try:
+                    with open(filepath, 'a') as f:
+                        f.write(json.dumps(record) + '\n')
+                finally:
+                    fcntl.flock(lock_f, fcntl.LOCK_UN)
+        except (OSError, TypeError, ValueError) as e:
+            # Losing an attempt record must not stop the agent sending
+            self.logger.warning(f"Failed to record transaction attempt: {e}")
+
     def read_shared_list(self, filename: str) -> List[Any]:
         """Read a shared list file"""
         data = self.read_shared_state(filename)
@@ -780,4 +840,14 @@ class BaseAgent(ABC):
         parser.add_argument('--remote-daemon', type=str, help='Remote daemon address (ip:port) or "auto" for public node discovery')
         parser.add_argument('--daemon-selection-strategy', type=str, choices=['random', 'first', 'round_robin'],
                           default='random', help='Strategy for selecting a daemon when using auto-discovery')
+        parser.add_argument('--network', default='regtest', choices=['regtest', 'testnet', 'stagenet'],
+                          help='Monero network the daemons run (general.chain)')
+        parser.add_argument('--random-seed', type=int,
+                          help='Per-agent RNG seed derived from the simulation seed by the generator')
+        parser.add_argument('--stop-time', type=int,
+                          help='Simulated second at which the agent leaves its run loop and exits')
+        parser.add_argument('--workload-file', type=str,
+                          help='JSON send times of the agent\'s workload profile, written by the generator')
+        parser.add_argument('--wallet-keys-file', type=str,
+                          help='JSON keys of the agent\'s deterministic wallet, written by the generator')
         return parser
\ No newline at end of file
@@ -8,6 +8,7 @@ module with a directory of submodules grouped by concern:
   - config.py    : configuration parsing helpers
   - discovery.py : miner-wallet discovery helpers
   - funding.py   : funding-cycle helpers (batch sizing)
+  - plan.py      : distribution_plan.json helpers (payout factors)
   - selection.py : miner-selection strategy helpers
   - state.py     : persisted funding-status schema
 
@@ -27,6 +27,7 @@ from ..shared_utils import is_valid_monero_address, make_deterministic_seed, xmr
 from .config import parse_time_duration
 from .discovery import query_miner_wallet_address
 from .funding import fit_batch_to_unlocked_balance
+from .plan import DISTRIBUTION_PLAN_FILE, output_amount, payout_factors
 from .selection import select_miner_by_balance, select_miner_by_weight
 from .state import FUNDING_STATUS_FILE, empty_funding_status
 
@@ -47,7 +48,7 @@ class MinerDistributorAgent(BaseAgent):
 
         # Deterministic seeding for reproducibility
         self.global_seed = int(os.getenv('SIMULATION_SEED', str(DEFAULT_SIMULATION_SEED)))
-        self.agent_seed = make_deterministic_seed(agent_id)
+        self.agent_seed = make_deterministic_seed(agent_id, self.random_seed)
         random.seed(self.agent_seed)
 
         # Initialize transaction-specific parameters
@@ -100,10 +101,16 @@ class MinerDistributorAgent(BaseAgent):
         self._last_funding_cycle_time = 0  # Last time we ran a funding cycle
         self.md_funding_cycle_interval = 300  # Run funding cycle every 5 minutes (configurable)
 
+        # Payout plan from distribution_plan.json (None without one: every
+        # agent with can_receive_distributions gets the base payout)
+        self.payout_factors: Optional[Dict[str, float]] = None
+        self.min_payout: Optional[float] = None
+
     def _setup_agent(self):
         """Initialize the miner distributor agent"""
         # Parse configuration attributes
         self._parse_configuration()
+        self._load_distribution_plan()
 
         # Register in agent registry
         self._register_as_miner_distributor_agent()
@@ -130,6 +137,38 @@ class MinerDistributorAgent(BaseAgent):
         for attr_name, (type_name, field_name, *args) in config_mappings.items():
             self._parse_single_attribute(attr_name, type_name, field_name, *args)
 
+    def _load_distribution_plan(self):
+        """Apply distribution_plan.json: recipients, payout factors, interval"""
+        plan = self.read_shared_state(DISTRIBUTION_PLAN_FILE)
+        if not plan:
+            return
+        self.payout_factors = payout_factors(plan, self.logger)
+        self.min_payout = plan.get("min_payout")
+        self.md_funding_cycle_interval = plan.get("payout_interval", self.md_funding_cycle_interval)
+        self.logger.info(
+            f"Distribution plan: {plan.get('policy')} payouts to {len(self.payout_factors)} "
+            f"recipients every {self.md_funding_cycle_interval}s"
+        )
+
+    def _is_recipient(self, agent: Dict[str, Any], miner_ids: set) -> bool:
+        """Whether the distributor pays this registry agent"""
+        if self.payout_factors is not None:
+            return agent.get("id") in self.payout_factors
+        # Without a plan, miners are never paid and agents opt in
+        if agent.get("id") in miner_ids:
+            return False
+        return self.parse_bool(
+            agent.get("attributes", {}).get("can_receive_distributions", "false")
+        )
+
+    def _recipient_output_amount(self, recipient_id: str, base_amount: float, outputs: int) -> float:
+        """XMR per output for one recipient under the distribution plan"""
+        if self.payout_factors is None:
+            return base_amount
+        return output_amount(
+            base_amount, outputs, self.payout_factors.get(recipient_id, 1.0), self.min_payout
+        )
+
     def _parse_single_attribute(self, attr_name: str, type_name: str, field_name: str, *args):
         """Parse a single configuration attribute"""
         if attr_name not in self.attributes:
@@ -448,7 +487,7 @@ class MinerDistributorAgent(BaseAgent):
             self.logger.warning("Agent registry not found, cannot perform initial funding")
             return None
 
-        # Get miner IDs to exclude from recipients
+        # Miners are not paid unless the distribution plan names them
         miner_ids = {m.get("agent_id") for m in self.miners}
 
         # Build list of eligible recipients. Permanently_failed agents stay
@@ -459,20 +498,11 @@ class MinerDistributorAgent(BaseAgent):
         all_eligible = []
         unfunded_recipients = []
         for agent in agent_registry.get("agents", []):
-            # Skip miners
-            if agent.get("id") in miner_ids:
-                continue
-
             # Check if agent has wallet
             if not agent.get("wallet_rpc_port"):
                 continue
 
-            # Check if agent can receive distributions
-            can_receive = self.parse_bool(
-                agent.get("attributes", {}).get("can_receive_distributions", "false")
-            )
-
-            if can_receive:
+            if self._is_recipient(agent, miner_ids):
                 all_eligible.append(agent)
                 agent_id = agent.get("id")
                 if agent_id in already_funded:
@@ -1215,27 +1245,39 @@ class MinerDistributorAgent(BaseAgent):
             self.logger.error("No valid recipients with addresses for batch transaction")
             return False, [], [r.get('id') for r in recipients]
 
+        # Per-output amount of each recipient, scaled by the distribution plan
+        recipient_amounts = {
+            recipient.get('id'): self._recipient_output_amount(
+                recipient.get('id'), per_output_amount, num_outputs_per_recipient
+            )
+            for recipient, _ in valid_recipients
+        }
+
         # Convert XMR to atomic units
-        try:
-            amount_atomic = xmr_to_atomic(per_output_amount)
-            if amount_atomic <= 0:
-                self.logger.error(f"Invalid atomic unit conversion: {per_output_amount} XMR -> {amount_atomic} atomic units")
+        recipient_atomic = {}
+        for recipient_id, amount in recipient_amounts.items():
+            try:
+                amount_atomic = xmr_to_atomic(amount)
+                if amount_atomic <= 0:
+                    self.logger.error(f"Invalid atomic unit conversion: {amount} XMR -> {amount_atomic} atomic units")
+                    return False, [], [r.get('id') for r in recipients]
+            except (ValueError, OverflowError) as e:
+                self.logger.error(f"Failed to convert amount {amount} to atomic units: {e}")
                 return False, [], [r.get('id') for r in recipients]
-        except (ValueError, OverflowError) as e:
-            self.logger.error(f"Failed to convert amount {per_output_amount} to atomic units: {e}")
-            return False, [], [r.get('id') for r in recipients]
+            recipient_atomic[recipient_id] = amount_atomic
 
         # Build destinations: md_out_per_tx outputs for EACH recipient
         for recipient, recipient_address in valid_recipients:
+            recipient_id = recipient.get('id')
             # Validate params for this recipient (skip max check since md_output_amount is explicit)
-            if not self._validate_transaction_params(recipient_address, per_output_amount, skip_max_check=True):
-                self.logger.warning(f"Invalid params for recipient {recipient.get('id')}, skipping")
-                failed_recipients.append(recipient.get('id'))
+            if not self._validate_transaction_params(recipient_address, recipient_amounts[recipient_id], skip_max_check=True):
+                self.logger.warning(f"Invalid params for recipient {recipient_id}, skipping")
+                failed_recipients.append(recipient_id)
                 continue
 
             # Add multiple outputs to same recipient address
             for _ in range(num_outputs_per_recipient):
-                destinations.append({'address': recipient_address, 'amount': amount_atomic})
+                destinations.append({'address': recipient_address, 'amount': recipient_atomic[recipient_id]})
 
         if not destinations:
             self.logger.error("No valid destinations after validation")
@@ -1243,7 +1285,11 @@ class MinerDistributorAgent(BaseAgent):
 
         num_recipients = len(valid_recipients) - len([r for r in failed_recipients if r in [v[0].get('id') for v in valid_recipients]])
         num_outputs = len(destinations)
-        total_amount = per_output_amount * num_outputs
+        total_amount = sum(
+            recipient_amounts[r.get('id')] * num_outputs_per_recipient
+            for r, _ in valid_recipients
+            if r.get('id') not in failed_recipients
+        )
         per_recipient_total = per_output_amount * num_outputs_per_recipient
 
         # Connect to miner's wallet RPC with retries
@@ -1260,7 +1306,7 @@ class MinerDistributorAgent(BaseAgent):
                 }
 
                 self.logger.debug(f"Batch transaction parameters: {json.dumps(tx_params, indent=2)}")
-                self.logger.info(f"Preparing batch transaction: {num_recipients} recipients x {num_outputs_per_recipient} outputs x {per_output_amount} XMR = {total_amount} XMR total")
+                self.logger.info(f"Preparing batch transaction: {num_recipients} recipients x {num_outputs_per_recipient} outputs x {per_output_amount} XMR base = {total_amount} XMR total")
 
                 # Send transaction using transfer_split to automatically handle large transactions
                 tx = miner_rpc.transfer_split(**tx_params)
@@ -1274,6 +1320,7 @@ class MinerDistributorAgent(BaseAgent):
                         tx_hash_list = [single_hash]
                     else:
                         self.logger.error(f"Transaction response missing tx_hash_list: {tx}")
+                        self.record_tx_attempt(False, reason="missing tx_hash")
                         return False, [], [r.get('id') for r in recipients]
 
                 tx_hash = tx_hash_list[0]  # Use first hash for recording
@@ -1294,14 +1341,15 @@ class MinerDistributorAgent(BaseAgent):
                         tx_hash=tx_hash,
                         sender_id=miner.get("agent_id"),
                         recipient_id=recipient_id,
-                        amount=per_recipient_total,
+                        amount=recipient_amounts[recipient_id] * num_outputs_per_recipient,
                         num_outputs=num_outputs_per_recipient,
-                        amount_per_output=per_output_amount
+                        amount_per_output=recipient_amounts[recipient_id]
                     )
 
                 self.logger.info(f"Batch transaction sent successfully: {tx_hash} "
                               f"({num_splits} split(s)) from {miner.get('agent_id')} to {len(funded_recipient_ids)} recipients "
-                              f"for {total_amount} XMR ({per_recipient_total} XMR each = {num_outputs_per_recipient} x {per_output_amount})")
+                              f"for {total_amount} XMR ({per_recipient_total} XMR base each = {num_outputs_per_recipient} x {per_output_amount})")
+                self.record_tx_attempt(True, tx_hash=tx_hash)
                 return True, funded_recipient_ids, failed_recipients
 
             except Exception as e:
@@ -1320,11 +1368,13 @@ class MinerDistributorAgent(BaseAgent):
                 ):
                     self.logger.warning(f"Batch transaction attempt {attempt + 1}/{self.max_retries} failed: Insufficient (unlocked) funds in miner wallet")
                     # Don't retry insufficient funds - caller should try different miner
+                    self.record_tx_attempt(False, reason=str(e))
                     return False, [], [r.get('id') for r in recipients]
 
                 elif "invalid params" in error_msg:
                     self.logger.error(f"Batch transaction attempt {attempt + 1}/{self.max_retries} failed: Invalid parameters")
-                    self.logger.error(f"Invalid parameters detected - {num_outputs} outputs x {per_output_amount} XMR ({amount_atomic} atomic units each)")
+                    self.logger.error(f"Invalid parameters detected - {num_outputs} outputs totalling {total_amount} XMR")
+                    self.record_tx_attempt(False, reason=str(e))
                     return False, [], [r.get('id') for r in recipients]
 
                 elif "wallet is not ready" in error_msg or "wallet not ready" in error_msg:
@@ -1334,6 +1384,7 @@ class MinerDistributorAgent(BaseAgent):
                         continue
                     else:
                         self.logger.error(f"Wallet still not ready after {self.max_retries} attempts")
+                        self.record_tx_attempt(False, reason=str(e))
                         return False, [], [r.get('id') for r in recipients]
 
                 else:
@@ -1343,6 +1394,7 @@ class MinerDistributorAgent(BaseAgent):
                         continue
                     else:
                         self.logger.error(f"Failed to send batch transaction after {self.max_retries} attempts: {e}")
+                        self.record_tx_attempt(False, reason=str(e))
                         return False, [], [r.get('id') for r in recipients]
 
         # Should not reach here, but return failure if we do
@@ -1382,13 +1434,16 @@ def main():
     # Create and run agent
     agent = MinerDistributorAgent(
         agent_id=args.id,
+        stop_time=args.stop_time,
         shared_dir=args.shared_dir,
         rpc_host=args.rpc_host,
         daemon_rpc_port=args.daemon_rpc_port,
         wallet_rpc_port=args.wallet_rpc_port,
         p2p_port=args.p2p_port,
         log_level=args.log_level,
-        attributes=args.attributes
+        attributes=args.attributes,
+        random_seed=args.random_seed,
+        network=args.network
     )
 
     agent.run()
@@ -0,0 +1,51 @@
+"""
+Distribution-plan helpers for MinerDistributorAgent.
+
+When the config has a ``miner_distributor:`` section, monerosim writes
+``distribution_plan.json`` to the shared dir. Schema:
+  - policy (str): equal | proportional | custom_script
+  - payout_interval (int): seconds between funding cycles
+  - min_payout (float | None): smallest payout per recipient, XMR
+  - script (str, custom_script only): module whose
+    ``payout_factors(plan) -> Dict[str, float]`` replaces the factors below
+  - recipients (list): agent_id, sends_per_hour, payout_factor; the
+    recipient's payout is payout_factor x md_out_per_tx x md_output_amount
+"""
+
+import importlib
+import logging
+from typing import Any, Dict, Optional
+
+DISTRIBUTION_PLAN_FILE = "distribution_plan.json"
+
+
+def payout_factors(plan: Dict[str, Any], logger: logging.Logger) -> Dict[str, float]:
+    """Each planned recipient's payout factor; a custom script's factors
+    win, and recipients it leaves out get 1.0."""
+    factors = {
+        r["agent_id"]: float(r.get("payout_factor", 1.0))
+        for r in plan.get("recipients", [])
+    }
+    if plan.get("policy") != "custom_script":
+        return factors
+    script = plan.get("script")
+    try:
+        custom = importlib.import_module(script).payout_factors(plan)
+    except Exception as e:
+        logger.error(f"Payout script {script} failed, paying everyone equally: {e}")
+        return factors
+    return {agent_id: float(custom.get(agent_id, 1.0)) for agent_id in factors}
+
+
+def output_amount(
+    base_amount: float,
+    outputs: int,
+    factor: float,
+    min_payout: Optional[float],
+) -> float:
+    """XMR per output for a recipient, raised so the recipient's payout
+    (``outputs`` of them) is at least ``min_payout``."""
+    amount = base_amount * factor
+    if min_payout is not None and outputs > 0:
+        amount = max(amount, min_payout / outputs)
+    return amount
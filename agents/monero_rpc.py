@@ -289,6 +289,22 @@ class MoneroRPC(BaseRPC):
             return response.json()
         except requests.exceptions.RequestException as e:
             raise RPCError(f"Failed to get transaction pool: {e}")
+
+    def get_net_stats(self) -> Dict[str, Any]:
+        """
+        Get the daemon's network traffic totals.
+
+        Returns:
+            Dictionary with total_bytes_in and total_bytes_out since start
+        """
+        # This endpoint uses a different path, not json_rpc
+        try:
+            url = self.url.replace("/json_rpc", "/get_net_stats")
+            response = self.session.get(url, timeout=self.timeout)
+            response.raise_for_status()
+            return response.json()
+        except requests.exceptions.RequestException as e:
+            raise RPCError(f"Failed to get network stats: {e}")
         
     def generate_block(self, wallet_address: str, amount_of_blocks: int = 1) -> Dict[str, Any]:
         """
@@ -388,6 +404,24 @@ class WalletRPC(BaseRPC):
                 return self.open_wallet(filename, password)
             raise WalletError(f"Failed to create wallet '{filename}': {e}")
         
+    def generate_from_keys(self, filename: str, address: str, spendkey: str, viewkey: str,
+                           password: str = "", restore_height: int = 0) -> Dict[str, Any]:
+        """Restore a wallet from its secret keys and open it"""
+        params = {
+            "filename": filename,
+            "address": address,
+            "spendkey": spendkey,
+            "viewkey": viewkey,
+            "password": password,
+            "restore_height": restore_height,
+        }
+        try:
+            result = self._make_request("generate_from_keys", params)
+            self.current_wallet = filename
+            return result
+        except RPCError as e:
+            raise WalletError(f"Failed to restore wallet '{filename}': {e}")
+
     def open_wallet(self, filename: str, password: str = "") -> Dict[str, Any]:
         """Open an existing wallet"""
         params = {
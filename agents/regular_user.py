@@ -7,6 +7,7 @@ It sets up a wallet, registers itself in shared state, and periodically sends
 transactions to other agents (and serves the passive miner role when is_miner is set).
 """
 
+import json
 import logging
 import os
 import time
@@ -21,7 +22,9 @@ from .shared_utils import make_deterministic_seed, xmr_to_atomic
 class RegularUserAgent(BaseAgent):
     """Agent that simulates regular user behavior in the Monero network"""
     
-    def __init__(self, agent_id: str, tx_frequency: Optional[int] = None, hash_rate: Optional[int] = None, **kwargs):
+    def __init__(self, agent_id: str, tx_frequency: Optional[int] = None, hash_rate: Optional[int] = None,
+                 workload_file: Optional[str] = None, wallet_keys_file: Optional[str] = None,
+                 **kwargs):
         """
         Initialize the RegularUserAgent.
         
@@ -29,14 +32,23 @@ class RegularUserAgent(BaseAgent):
             agent_id: Unique identifier for this agent
             tx_frequency: Transaction frequency in seconds
             hash_rate: Hash rate for mining (if applicable)
+            workload_file: JSON file with the send times of the agent's workload
+                profile; replaces the interval and send probability
+            wallet_keys_file: JSON keys the wallet is restored from instead of
+                being created with a random seed
             **kwargs: Additional arguments passed to BaseAgent
         """
         # Call parent constructor
         super().__init__(agent_id=agent_id, tx_frequency=tx_frequency, hash_rate=hash_rate, **kwargs)
+        self.workload_file = workload_file
+        self.wallet_keys_file = wallet_keys_file
+        # Unix send times from the workload file (None without one)
+        self.workload_times: Optional[List[float]] = None
+        self._next_workload_send = 0
 
         # Deterministic seeding for reproducibility
         self.global_seed = int(os.getenv('SIMULATION_SEED', str(DEFAULT_SIMULATION_SEED)))
-        self.agent_seed = make_deterministic_seed(agent_id)
+        self.agent_seed = make_deterministic_seed(agent_id, self.random_seed)
         random.seed(self.agent_seed)
 
         # Wallet refresh and error recovery state
@@ -81,6 +93,10 @@ class RegularUserAgent(BaseAgent):
 
     def _ensure_wallet_exists(self, wallet_name: str) -> Optional[str]:
         """Ensure a wallet exists and return its address"""
+        if self.wallet_keys_file:
+            address = self._restore_wallet(wallet_name)
+            if address:
+                return address
         try:
             self.logger.info(f"Attempting to open wallet '{wallet_name}' for {self.agent_id}")
             self.wallet_rpc.wait_until_ready(max_wait=180)
@@ -110,6 +126,35 @@ class RegularUserAgent(BaseAgent):
                 self.logger.error(f"Failed to get address: {addr_err}")
                 return None
 
+    def _restore_wallet(self, wallet_name: str) -> Optional[str]:
+        """Restore the wallet from the generator's deterministic keys, or
+        open it if an earlier run of the agent already did. Returns None to
+        fall back to a randomly seeded wallet."""
+        try:
+            with open(self.wallet_keys_file) as f:
+                keys = json.load(f)
+            self.wallet_rpc.wait_until_ready(max_wait=180)
+            try:
+                self.wallet_rpc.generate_from_keys(
+                    wallet_name, keys["address"], keys["spendkey"], keys["viewkey"],
+                    password=keys.get("password", ""),
+                    restore_height=keys.get("scan_from_height", 0),
+                )
+                self.logger.info(f"Restored wallet '{wallet_name}' from {self.wallet_keys_file}")
+            except Exception as e:
+                if "already exists" not in str(e).lower():
+                    raise
+                self.wallet_rpc.open_wallet(wallet_name, password=keys.get("password", ""))
+            address = self.wallet_rpc.get_address()
+            if address != keys["address"]:
+                self.logger.warning(
+                    f"Wallet '{wallet_name}' has address {address}, expected {keys['address']}"
+                )
+            return address
+        except Exception as e:
+            self.logger.warning(f"Could not restore wallet from {self.wallet_keys_file}: {e}")
+            return None
+
     def _setup_miner(self):
         """Setup logic for miner agents"""
         self._setup_wallet("miner")
@@ -173,6 +218,8 @@ class RegularUserAgent(BaseAgent):
         self.max_tx_amount = float(self.attributes.get('max_transaction_amount', '1.0'))
         self.tx_interval = int(self.attributes.get('transaction_interval', '60'))
         self.tx_send_probability = float(self.attributes.get('tx_send_probability', '0.75'))
+        if self.workload_file:
+            self._load_workload()
 
         # Activity start time: config value is in simulation seconds (e.g. 108000 for 30h).
         # Shadow's time.time() returns Unix timestamps starting from 2000-01-01 00:00:00 UTC
@@ -195,6 +242,26 @@ class RegularUserAgent(BaseAgent):
             self.waiting_for_activity_start = False
             self.logger.info(f"Transaction parameters: min={self.min_tx_amount}, max={self.max_tx_amount}, interval={self.tx_interval}")
         
+    def _load_workload(self):
+        """Load the send times (simulation seconds) written by the generator."""
+        with open(self.workload_file) as f:
+            workload = json.load(f)
+        self.workload_times = sorted(SHADOW_EPOCH + t for t in workload.get('send_times', []))
+        self._next_workload_send = 0
+        profile = workload.get('profile', {}).get('profile', 'unknown')
+        self.logger.info(f"Workload: {profile} profile, {len(self.workload_times)} scheduled transactions")
+
+    def _workload_due(self) -> bool:
+        """Whether the next scheduled send time has passed."""
+        return (self._next_workload_send < len(self.workload_times)
+                and time.time() >= self.workload_times[self._next_workload_send])
+
+    def _workload_sleep(self) -> float:
+        """Seconds until the next scheduled send, capped so the wallet keeps refreshing."""
+        if self._next_workload_send >= len(self.workload_times):
+            return 300.0
+        return min(300.0, max(0.0, self.workload_times[self._next_workload_send] - time.time()))
+
     def run_iteration(self) -> Optional[float]:
         """
         Single iteration of agent behavior.
@@ -330,6 +397,17 @@ class RegularUserAgent(BaseAgent):
         balance_info = self.wallet_rpc.get_balance()
         unlocked_balance = balance_info.get('unlocked_balance', 0)
 
+        # A workload sends at its scheduled times; one missed for lack of
+        # funds is skipped rather than sent late
+        if self.workload_times is not None:
+            if self._workload_due():
+                self._next_workload_send += 1
+                if unlocked_balance > 0:
+                    self._send_random_transaction()
+                else:
+                    self.logger.warning("Skipping scheduled transaction: no unlocked balance")
+            return
+
         # Only send transactions if we have sufficient balance
         if unlocked_balance > 0:
             self.logger.debug(f"User has unlocked balance: {unlocked_balance}")
@@ -345,6 +423,9 @@ class RegularUserAgent(BaseAgent):
             self.logger.info(f"Recovered after {self._consecutive_errors} consecutive errors")
         self._consecutive_errors = 0
 
+        if self.workload_times is not None:
+            return self._workload_sleep()
+
         # Use configured transaction interval
         return getattr(self, 'tx_interval', 60.0)
 
@@ -414,15 +495,18 @@ class RegularUserAgent(BaseAgent):
 
             if not tx_hash:
                 self.logger.error(f"Transaction response missing tx_hash: {response}")
+                self.record_tx_attempt(False, reason="missing tx_hash")
                 return
 
             self.logger.info(f"Sent transaction: {tx_hash} to {recipient.get('id')} for {amount} XMR")
+            self.record_tx_attempt(True, tx_hash=tx_hash)
 
             # Record transaction in shared state
             self._record_transaction(tx_hash, recipient.get('id'), amount)
             
         except Exception as e:
             self.logger.error(f"Failed to send transaction: {e}")
+            self.record_tx_attempt(False, reason=str(e))
     
     def _get_other_agents(self) -> List[Dict[str, Any]]:
         """
@@ -492,6 +576,7 @@ def main():
     # Create and run agent
     agent = RegularUserAgent(
         agent_id=args.id,
+        stop_time=args.stop_time,
         shared_dir=args.shared_dir,
         rpc_host=args.rpc_host,
         daemon_rpc_port=args.daemon_rpc_port,
@@ -500,7 +585,11 @@ def main():
         log_level=args.log_level,
         attributes=args.attributes,
         tx_frequency=args.tx_frequency,
-        hash_rate=args.hash_rate
+        hash_rate=args.hash_rate,
+        random_seed=args.random_seed,
+        network=args.network,
+        workload_file=args.workload_file,
+        wallet_keys_file=args.wallet_keys_file
     )
     
     agent.run()
@@ -59,12 +59,17 @@ def atomic_to_xmr(amount: int) -> float:
     return amount / ATOMIC_UNITS_PER_XMR
 
 
-def make_deterministic_seed(agent_id: str) -> int:
+def make_deterministic_seed(agent_id: str, random_seed: Optional[int] = None) -> int:
     """Derive a deterministic per-agent seed from ``SIMULATION_SEED`` env var
     (or ``DEFAULT_SIMULATION_SEED``) and the agent's ID.
 
+    ``random_seed`` (the generator's ``--random-seed``, already derived from
+    the simulation seed and the agent ID) wins when given.
+
     The returned seed is suitable for passing to ``random.seed()``.
     """
+    if random_seed is not None:
+        return random_seed
     global_seed = int(os.getenv('SIMULATION_SEED', str(DEFAULT_SIMULATION_SEED)))
     agent_hash = int(hashlib.sha256(agent_id.encode()).hexdigest(), 16) % SEED_HASH_MODULUS
     return global_seed + agent_hash
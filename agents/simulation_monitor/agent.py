@@ -23,6 +23,7 @@ from ..monero_rpc import MoneroRPC, WalletRPC, RPCError
 
 from .alerts import check_alerts, write_alerts
 from .log_parser import parse_mining_events
+from .metrics_writer import append_samples, parse_metrics, sample_rows
 from .metadata import get_git_commit_hash, get_config_metadata
 from .status_paths import find_shadow_data_hosts
 
@@ -42,6 +43,9 @@ class SimulationMonitorAgent(BaseAgent):
                  status_file: str = "monerosim_monitor.log",
                  enable_alerts: bool = True,
                  detailed_logging: bool = False,
+                 metrics: Optional[List[str]] = None,
+                 output_format: str = "jsonl",
+                 metrics_file: Optional[str] = None,
                  log_level: str = "INFO",
                  **kwargs):
         """
@@ -55,6 +59,9 @@ class SimulationMonitorAgent(BaseAgent):
             status_file: Path to the real-time status file
             enable_alerts: Whether to enable alert generation
             detailed_logging: Whether to enable detailed logging
+            metrics: Metrics recorded per node in the time-series file
+            output_format: Format of the time-series file, jsonl or csv
+            metrics_file: Path of the time-series file; none writes no file
             log_level: Logging level
             **kwargs: Additional arguments passed to BaseAgent
         """
@@ -72,6 +79,11 @@ class SimulationMonitorAgent(BaseAgent):
             self.logger.setLevel(logging.DEBUG)
         self.cycle_count = 0
 
+        # Time-series file: one sample per node per polling cycle
+        self.metrics = metrics if metrics is not None else []
+        self.output_format = output_format
+        self.metrics_file = Path(metrics_file) if metrics_file else None
+
         # Initialize agent discovery
         self.discovery = AgentDiscovery(str(self.shared_dir))
 
@@ -436,6 +448,9 @@ class SimulationMonitorAgent(BaseAgent):
             # Collect data from all nodes
             node_data = self._collect_node_data()
 
+            # Append this cycle's samples to the time-series file
+            self._write_metrics_samples(node_data)
+
             # Analyze network status
             network_metrics = self._analyze_network_health(node_data)
 
@@ -535,6 +550,16 @@ class SimulationMonitorAgent(BaseAgent):
             self.logger.error(f"Failed to collect node data: {e}")
             return {}
 
+    def _write_metrics_samples(self, node_data: Dict[str, Any]):
+        """Append one sample per node to the time-series file, if enabled."""
+        if not self.metrics_file:
+            return
+        try:
+            rows = sample_rows(node_data, self.cycle_count, time.time(), self.metrics)
+            append_samples(self.metrics_file, self.output_format, self.metrics, rows)
+        except Exception as e:
+            self.logger.warning(f"Failed to write metrics samples: {e}")
+
     def _get_agent_rpc_info(self, agent: Dict[str, Any]) -> Optional[Dict[str, Any]]:
         """
         Extract RPC connection information from agent data.
@@ -598,7 +623,8 @@ class SimulationMonitorAgent(BaseAgent):
                     "target_height": info.get("target_height", 0),
                     "incoming_connections": info.get("incoming_connections_count", 0),
                     "outgoing_connections": info.get("outgoing_connections_count", 0),
-                    "network_height": info.get("height_without_bootstrap", 0)
+                    "network_height": info.get("height_without_bootstrap", 0),
+                    "tx_pool_size": info.get("tx_pool_size", 0)
                 })
 
                 # Update network difficulty
@@ -608,6 +634,14 @@ class SimulationMonitorAgent(BaseAgent):
             except Exception as e:
                 data["info_error"] = str(e)
 
+            if self.metrics_file and "bandwidth" in self.metrics:
+                try:
+                    net_stats = daemon_rpc.get_net_stats()
+                    data["bytes_in"] = net_stats.get("total_bytes_in")
+                    data["bytes_out"] = net_stats.get("total_bytes_out")
+                except Exception as e:
+                    data["net_stats_error"] = str(e)
+
             # Determine mining status from registry and actual log parsing
             is_registered_miner = agent_id in self.miner_registry
             miner_info = self.miner_registry.get(agent_id, {})
@@ -1452,6 +1486,15 @@ class SimulationMonitorAgent(BaseAgent):
                                'attribute actually controls alerting.')
         parser.add_argument('--detailed-logging', action='store_true', default=False,
                           help='Enable detailed logging')
+        parser.add_argument('--metrics', type=parse_metrics,
+                          default=['height', 'mempool', 'peers', 'bandwidth'],
+                          help='Comma-separated metrics for the time-series file: '
+                               'height, mempool, peers, bandwidth (default: all)')
+        parser.add_argument('--output-format', choices=['jsonl', 'csv'],
+                          default='jsonl',
+                          help='Format of the time-series file (default: jsonl)')
+        parser.add_argument('--metrics-file', type=str, default=None,
+                          help='Time-series file; none is written without it')
 
         return parser
 
@@ -1470,6 +1513,7 @@ def main():
         # Create and run the agent
         agent = SimulationMonitorAgent(
             agent_id=args.id,
+            stop_time=args.stop_time,
             shared_dir=args.shared_dir,
             daemon_rpc_port=args.daemon_rpc_port,
             wallet_rpc_port=args.wallet_rpc_port,
@@ -1481,7 +1525,10 @@ def main():
             output_dir=args.output_dir,
             status_file=args.status_file,
             enable_alerts=args.enable_alerts,
-            detailed_logging=args.detailed_logging
+            detailed_logging=args.detailed_logging,
+            metrics=args.metrics,
+            output_format=args.output_format,
+            metrics_file=args.metrics_file
         )
 
         agent.run()
@@ -0,0 +1,100 @@
+"""
+Time-series file for SimulationMonitorAgent.
+
+Each polling cycle appends one sample per node to the file named by
+--metrics-file, as JSON lines or CSV. Only the columns of the selected
+--metrics are written, after `time` (simulated epoch seconds), `poll` (the
+monitor's cycle number) and `node`. A value the node did not report is
+null in JSON and empty in CSV. `tx-analyzer monitor` reads the file back.
+"""
+
+import csv
+import json
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+OUTPUT_FORMATS = ("jsonl", "csv")
+
+# Metric name -> columns it adds
+METRIC_COLUMNS = {
+    "height": ["height"],
+    "mempool": ["mempool"],
+    "peers": ["peers"],
+    "bandwidth": ["bytes_in", "bytes_out"],
+}
+
+SAMPLE_COLUMNS = ["time", "poll", "node"]
+
+
+def parse_metrics(spec: str) -> List[str]:
+    """
+    Parse a comma-separated metric list, keeping its order.
+
+    Raises:
+        ValueError: for a name not in METRIC_COLUMNS
+    """
+    metrics = []
+    for name in (part.strip() for part in spec.split(",")):
+        if not name:
+            continue
+        if name not in METRIC_COLUMNS:
+            raise ValueError(
+                f"unknown metric '{name}' (expected one of: {', '.join(METRIC_COLUMNS)})")
+        if name not in metrics:
+            metrics.append(name)
+    return metrics
+
+
+def columns(metrics: List[str]) -> List[str]:
+    """Columns of a sample with the given metrics"""
+    return SAMPLE_COLUMNS + [c for m in metrics for c in METRIC_COLUMNS[m]]
+
+
+def sample_rows(node_data: Dict[str, Any], poll: int, now: float,
+                metrics: List[str]) -> List[Dict[str, Any]]:
+    """
+    One row per node of a polling cycle's node data.
+
+    Args:
+        node_data: agent id -> {"daemon": {...}} as collected by the monitor
+        poll: monitor cycle number
+        now: simulated time of the poll
+        metrics: selected metric names
+    """
+    rows = []
+    for node in sorted(node_data):
+        daemon = node_data[node].get("daemon") or {}
+        values = {
+            "height": daemon.get("height"),
+            "mempool": daemon.get("tx_pool_size"),
+            "peers": daemon.get("connections"),
+            "bytes_in": daemon.get("bytes_in"),
+            "bytes_out": daemon.get("bytes_out"),
+        }
+        row = {"time": round(now, 3), "poll": poll, "node": node}
+        for column in columns(metrics)[len(SAMPLE_COLUMNS):]:
+            row[column] = values[column]
+        rows.append(row)
+    return rows
+
+
+def append_samples(path: Path, output_format: str, metrics: List[str],
+                   rows: List[Dict[str, Any]]) -> None:
+    """Append rows to the time-series file, starting a CSV with its header"""
+    path = Path(path)
+    if output_format == "csv":
+        fresh = not path.exists() or path.stat().st_size == 0
+        with open(path, "a", newline="") as f:
+            writer = csv.DictWriter(f, fieldnames=columns(metrics))
+            if fresh:
+                writer.writeheader()
+            for row in rows:
+                writer.writerow({k: _csv_value(v) for k, v in row.items()})
+    else:
+        with open(path, "a") as f:
+            for row in rows:
+                f.write(json.dumps(row) + "\n")
+
+
+def _csv_value(value: Optional[Any]) -> Any:
+    return "" if value is None else value
@@ -77,3 +77,58 @@ def test_make_deterministic_seed_is_deterministic_per_id(monkeypatch):
     # Different agent_ids must yield different seeds.
     c = make_deterministic_seed("miner-008")
     assert c != a
+
+
+def test_random_seed_arg_overrides_env_derivation(monkeypatch, shared_dir):
+    """The generator's --random-seed is used as-is when passed."""
+    monkeypatch.setenv("SIMULATION_SEED", "42")
+    assert make_deterministic_seed("miner-007", 987) == 987
+    agent = AutonomousMinerAgent(
+        agent_id="miner-007",
+        shared_dir=shared_dir,
+        random_seed=987,
+    )
+    assert agent.agent_seed == 987
+
+
+def test_warm_start_continues_from_the_daemon_height(shared_dir):
+    """--warm-start takes the preserved chain's height as the starting point."""
+    from unittest.mock import MagicMock
+
+    agent = AutonomousMinerAgent(
+        agent_id="miner-009",
+        shared_dir=shared_dir,
+        attributes=[["hashrate", "50"]],
+        warm_start=True,
+    )
+    agent.daemon_rpc = MagicMock()
+    agent.daemon_rpc.get_info.return_value = {"height": 1500, "difficulty": 1}
+    agent._get_mining_address = lambda: "address"
+    agent._setup_agent()
+    assert agent.start_height == 1500
+    assert agent.last_block_height == 1500
+
+
+def test_block_interval_defaults_to_monero_target(shared_dir):
+    """--block-interval replaces the 120s target the miners aim at together."""
+    default = AutonomousMinerAgent(agent_id="miner-010", shared_dir=shared_dir)
+    assert default.block_interval == 120.0
+    fast = AutonomousMinerAgent(agent_id="miner-011", shared_dir=shared_dir, block_interval=30)
+    assert fast.block_interval == 30
+
+
+def test_pool_registry_sets_members_and_share(shared_dir):
+    """--pool-registry gives the pool's members and combined network share."""
+    import json
+
+    registry = shared_dir / "miners_east.json"
+    registry.write_text(json.dumps({"miners": [
+        {"agent_id": "miner-012", "share": 0.25},
+        {"agent_id": "miner-013", "share": 0.5},
+    ]}))
+    agent = AutonomousMinerAgent(
+        agent_id="miner-012", shared_dir=shared_dir, pool="east", pool_registry=str(registry),
+    )
+    agent._load_pool()
+    assert agent.pool_miners == ["miner-012", "miner-013"]
+    assert agent.pool_share == pytest.approx(0.75)
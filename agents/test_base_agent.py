@@ -3,9 +3,11 @@
 Covers the static/utility helpers that don't require live RPC: parse_bool,
 retry_with_backoff, and write/read_shared_state round-trip.
 """
+import json
+
 import pytest
 
-from agents.base_agent import BaseAgent, retry_with_backoff
+from agents.base_agent import SHADOW_EPOCH, BaseAgent, retry_with_backoff
 
 
 # ---------------------------------------------------------------------------
@@ -111,3 +113,39 @@ def test_shared_state_round_trip(shared_dir):
 def test_shared_state_read_missing_returns_none(shared_dir):
     agent = _MinimalAgent(agent_id="t2", shared_dir=shared_dir)
     assert agent.read_shared_state("does_not_exist.json") is None
+
+
+# ---------------------------------------------------------------------------
+# --stop-time: the run loop ends at the simulated stop time
+# ---------------------------------------------------------------------------
+
+def test_stop_time_argument_is_parsed():
+    parser = BaseAgent.create_argument_parser("test")
+    assert parser.parse_args(["--id", "a", "--stop-time", "5400"]).stop_time == 5400
+    assert parser.parse_args(["--id", "a"]).stop_time is None
+
+
+def test_past_stop_time_uses_simulated_seconds(shared_dir, mocker):
+    mocker.patch("agents.base_agent.time.time", return_value=SHADOW_EPOCH + 3600)
+    assert _MinimalAgent(agent_id="s1", shared_dir=shared_dir, stop_time=3600).past_stop_time()
+    assert not _MinimalAgent(agent_id="s2", shared_dir=shared_dir, stop_time=3601).past_stop_time()
+    assert not _MinimalAgent(agent_id="s3", shared_dir=shared_dir).past_stop_time()
+
+
+# ---------------------------------------------------------------------------
+# record_tx_attempt: one JSON line per send attempt
+# ---------------------------------------------------------------------------
+
+def test_tx_attempts_are_appended_as_json_lines(shared_dir, mocker):
+    mocker.patch("agents.base_agent.time.time", return_value=SHADOW_EPOCH + 60)
+    agent = _MinimalAgent(agent_id="u1", shared_dir=shared_dir)
+    agent.record_tx_attempt(False, reason="not enough unlocked money")
+    agent.record_tx_attempt(True, tx_hash="ab" * 32)
+
+    lines = (shared_dir / "tx_attempts.jsonl").read_text().splitlines()
+    assert [json.loads(line) for line in lines] == [
+        {"agent_id": "u1", "timestamp": SHADOW_EPOCH + 60, "outcome": "failure",
+         "reason": "not enough unlocked money"},
+        {"agent_id": "u1", "timestamp": SHADOW_EPOCH + 60, "outcome": "success",
+         "tx_hash": "ab" * 32},
+    ]
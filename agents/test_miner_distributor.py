@@ -1,9 +1,11 @@
 """Smoke tests for agents.miner_distributor.
 
 Covers the constructor, _parse_configuration's type dispatch (int_min,
-int_range, float_min, choice, time_duration), and the _parse_time_duration
-helper. We don't drive the run loop or touch any RPC.
+int_range, float_min, choice, time_duration), the _parse_time_duration
+helper and how distribution_plan.json picks and scales recipients. We don't drive the run loop or touch any RPC.
 """
+import json
+
 import pytest
 
 from agents.miner_distributor import MinerDistributorAgent
@@ -98,3 +100,49 @@ def test_parse_time_duration_invalid_returns_none(shared_dir):
     )
     assert agent._parse_time_duration("abc") is None
     assert agent._parse_time_duration("12x") is None
+
+
+def test_distribution_plan_picks_and_scales_recipients(shared_dir):
+    """distribution_plan.json replaces the can_receive_distributions scan,
+    scales payouts by payout_factor and raises them to min_payout."""
+    plan = {
+        "policy": "proportional",
+        "payout_interval": 600,
+        "min_payout": 4.0,
+        "recipients": [
+            {"agent_id": "miner-001", "sends_per_hour": None, "payout_factor": 1.5},
+            {"agent_id": "user-001", "sends_per_hour": 20.0, "payout_factor": 0.25},
+        ],
+    }
+    (shared_dir / "distribution_plan.json").write_text(json.dumps(plan))
+    agent = MinerDistributorAgent(
+        agent_id="miner-distributor",
+        shared_dir=shared_dir,
+        attributes=[],
+    )
+    agent._load_distribution_plan()
+
+    assert agent.md_funding_cycle_interval == 600
+    miners = {"miner-001"}
+    assert agent._is_recipient({"id": "miner-001"}, miners)
+    assert not agent._is_recipient(
+        {"id": "user-002", "attributes": {"can_receive_distributions": "true"}}, miners
+    )
+    # 2 outputs of 5.0 XMR base
+    assert agent._recipient_output_amount("miner-001", 5.0, 2) == pytest.approx(7.5)
+    assert agent._recipient_output_amount("user-001", 5.0, 2) == pytest.approx(2.0)
+
+
+def test_without_a_plan_recipients_opt_in(shared_dir):
+    """Without distribution_plan.json, non-miners with
+    can_receive_distributions get the base payout."""
+    agent = MinerDistributorAgent(
+        agent_id="miner-distributor",
+        shared_dir=shared_dir,
+        attributes=[],
+    )
+    agent._load_distribution_plan()
+    opted_in = {"id": "miner-001", "attributes": {"can_receive_distributions": "true"}}
+    assert not agent._is_recipient(opted_in, {"miner-001"})
+    assert agent._is_recipient(opted_in, set())
+    assert agent._recipient_output_amount("miner-001", 5.0, 2) == 5.0
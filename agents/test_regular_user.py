@@ -99,3 +99,60 @@ def test_activity_start_time_in_past_does_not_wait(shared_dir, mocker):
     )
     agent._setup_transaction_parameters()
     assert agent.waiting_for_activity_start is False
+
+
+def test_workload_file_schedules_sends(shared_dir, mocker):
+    """With a workload file the agent sends at its scheduled times, once each,
+    and sleeps until the next one."""
+    workload = shared_dir / "user-06_workload.json"
+    workload.write_text(
+        '{"profile": {"profile": "constant", "interval": 60}, "send_times": [60.0, 120.0]}'
+    )
+    now = mocker.patch("agents.regular_user.time.time", return_value=SHADOW_EPOCH + 30.0)
+    agent = RegularUserAgent(
+        agent_id="user-06",
+        shared_dir=shared_dir,
+        attributes=[],
+        workload_file=str(workload),
+    )
+    agent._setup_transaction_parameters()
+    assert agent.workload_times == [SHADOW_EPOCH + 60.0, SHADOW_EPOCH + 120.0]
+    assert agent._workload_due() is False
+    assert agent._workload_sleep() == pytest.approx(30.0)
+
+    agent.wallet_rpc = mocker.Mock()
+    agent.wallet_rpc.get_balance.return_value = {"unlocked_balance": 10}
+    send = mocker.patch.object(agent, "_send_random_transaction")
+    now.return_value = SHADOW_EPOCH + 61.0
+    agent._maybe_send_transaction()
+    agent._maybe_send_transaction()
+    assert send.call_count == 1
+    assert agent._workload_sleep() == pytest.approx(59.0)
+
+
+def test_wallet_keys_file_restores_deterministic_wallet(shared_dir, mocker):
+    """With a wallet keys file the wallet is restored from its keys, and an
+    existing wallet from an earlier run is opened instead."""
+    keys = shared_dir / "user-07_wallet_keys.json"
+    keys.write_text(
+        '{"version": 1, "filename": "user-07_wallet", "scan_from_height": 0,'
+        ' "password": "", "spendkey": "aa", "viewkey": "bb", "address": "4Addr"}'
+    )
+    agent = RegularUserAgent(
+        agent_id="user-07",
+        shared_dir=shared_dir,
+        attributes=[],
+        wallet_keys_file=str(keys),
+    )
+    agent.wallet_rpc = mocker.Mock()
+    agent.wallet_rpc.get_address.return_value = "4Addr"
+
+    assert agent._ensure_wallet_exists("user-07_wallet") == "4Addr"
+    agent.wallet_rpc.generate_from_keys.assert_called_once_with(
+        "user-07_wallet", "4Addr", "aa", "bb", password="", restore_height=0
+    )
+    agent.wallet_rpc.create_wallet.assert_not_called()
+
+    agent.wallet_rpc.generate_from_keys.side_effect = Exception("Wallet already exists.")
+    assert agent._ensure_wallet_exists("user-07_wallet") == "4Addr"
+    agent.wallet_rpc.open_wallet.assert_called_with("user-07_wallet", password="")
@@ -117,3 +117,39 @@ def test_transaction_status_pool_delta_reads_nested_key(shared_dir):
     out = buf.getvalue()
     # With the bug (top-level lookup -> curr_pool=0) this line read "10".
     assert "Transactions Processed: 7" in out
+
+
+def test_metrics_file_rows_follow_selected_metrics(shared_dir):
+    """Each cycle appends one row per node with only the selected columns;
+    CSV gets its header once."""
+    import csv
+    import json
+
+    from agents.simulation_monitor.metrics_writer import append_samples, parse_metrics, sample_rows
+
+    node_data = {
+        "user-001": {"daemon": {"height": 12, "tx_pool_size": 3, "connections": 8}},
+        "miner-001": {"daemon": {"error": "timeout"}},
+    }
+    metrics = parse_metrics("height,mempool")
+    rows = sample_rows(node_data, 1, 946688400.0, metrics)
+    assert rows[0] == {"time": 946688400.0, "poll": 1, "node": "miner-001",
+                       "height": None, "mempool": None}
+
+    path = shared_dir / "monitor_metrics.csv"
+    append_samples(path, "csv", metrics, rows)
+    append_samples(path, "csv", metrics, sample_rows(node_data, 2, 946688460.0, metrics))
+    with open(path) as f:
+        lines = list(csv.reader(f))
+    assert lines[0] == ["time", "poll", "node", "height", "mempool"]
+    assert lines[2] == ["946688400.0", "1", "user-001", "12", "3"]
+    assert len(lines) == 5
+
+    path = shared_dir / "monitor_metrics.jsonl"
+    append_samples(path, "jsonl", ["peers"], sample_rows(node_data, 1, 946688400.0, ["peers"]))
+    with open(path) as f:
+        assert json.loads(f.readlines()[1]) == {
+            "time": 946688400.0, "poll": 1, "node": "user-001", "peers": 8}
+
+    with pytest.raises(ValueError):
+        parse_metrics("height,latency")
@@ -214,6 +214,8 @@ if [[ -z "$RUN_NAME" ]]; then
 fi
 
 [[ -z "$DATA_DIR" ]] && DATA_DIR="$SCRIPT_DIR/shadow.data"
+# Absolute: Shadow runs from $SHADOW_OUTPUT (see run_simulation)
+DATA_DIR="$(realpath -m "$DATA_DIR")"
 
 SHADOW_BIN="$HOME/.monerosim/bin/shadow"
 MONEROSIM_BIN="$SCRIPT_DIR/target/release/monerosim"
@@ -778,6 +780,9 @@ build_and_generate() {
         log_err "shadow_agents.yaml not generated!"
         exit 1
     fi
+    [[ -f "$SHADOW_OUTPUT/manifest.json" ]] && cp "$SHADOW_OUTPUT/manifest.json" "$ARCHIVE_DIR/manifest.json"
+    # GML topologies: the graph shadow_agents.yaml refers to by file name
+    [[ -f "$SHADOW_OUTPUT/topology.gml" ]] && cp "$SHADOW_OUTPUT/topology.gml" "$ARCHIVE_DIR/topology.gml"
 
     # Resolve the ACTUAL paths the generator baked in — a YAML config that
     # sets general.daemon_data_dir/shared_dir explicitly, or --ramdisk's
@@ -833,7 +838,9 @@ run_simulation() {
     # Start Shadow in its own process group (via setsid) so Ctrl+C won't reach it
     SHADOW_LOG="$ARCHIVE_DIR/shadow_run.log"
     log_info "Starting Shadow (data dir: $DATA_DIR)..."
-    setsid "$SHADOW_BIN" -d "$DATA_DIR" "$SHADOW_OUTPUT/shadow_agents.yaml" > "$SHADOW_LOG" 2>&1 &
+    # From the output directory: Shadow resolves the config's relative
+    # topology.gml against its working directory, not the config file
+    (cd "$SHADOW_OUTPUT" && exec setsid "$SHADOW_BIN" -d "$DATA_DIR" shadow_agents.yaml) > "$SHADOW_LOG" 2>&1 &
     SHADOW_PID=$!
     START_TIME=$(date +%s)
     START_TIME_FMT=$(date '+%Y-%m-%d %H:%M:%S')
@@ -1239,6 +1246,7 @@ archive_results() {
     # Each step is guarded so a failure in one doesn't skip the rest
     archive_blockchain_snapshots  || log_warn "Blockchain snapshot archiving failed"
     archive_daemon_logs           || log_warn "Daemon log archiving failed"
+    archive_pcaps                 || log_warn "pcap trace archiving failed"
     archive_transaction_registry  || log_warn "Transaction registry archiving failed"
     generate_summary_report       || log_warn "Summary report generation failed"
 
@@ -1419,6 +1427,37 @@ archive_daemon_logs() {
     # the registry/wallet archiving and summary report have read shared/.
 }
 
+archive_pcaps() {
+    # Traces of agents with capture_pcap go to general.pcap_capture_dir
+    # (relative paths under the archive), read back from the manifest.
+    # Without one they stay in shadow.data/hosts/<agent>/.
+    local manifest="$ARCHIVE_DIR/manifest.json"
+    [[ -f "$manifest" ]] || return 0
+    local capture_dir
+    capture_dir=$(python3 -c "import json, sys; c = json.load(open(sys.argv[1])).get('pcap_capture') or {}; print(c.get('capture_dir') or '')" "$manifest") || return 1
+    [[ -n "$capture_dir" ]] || return 0
+    [[ "$capture_dir" == /* ]] || capture_dir="$ARCHIVE_DIR/$capture_dir"
+
+    log_info "Collecting pcap traces into $capture_dir..."
+    local count=0
+    for pcap in "$ARCHIVE_DIR"/shadow.data/hosts/*/*.pcap; do
+        [[ -f "$pcap" ]] || continue
+        local host
+        host=$(basename "$(dirname "$pcap")")
+        mkdir -p "$capture_dir/$host"
+        mv "$pcap" "$capture_dir/$host/"
+        count=$((count + 1))
+    done
+
+    if [[ $count -gt 0 ]]; then
+        local total_size
+        total_size=$(du -sh "$capture_dir" 2>/dev/null | cut -f1)
+        log_ok "pcap traces: $count files collected ($total_size total)"
+    else
+        log_warn "No pcap traces found in $ARCHIVE_DIR/shadow.data/hosts/"
+    fi
+}
+
 archive_transaction_registry() {
     log_info "Archiving transaction registry..."
 
@@ -301,7 +301,8 @@ run_test() {
     local start_time=$(date +%s)
 
     # Start Shadow in background
-    timeout "$TIMEOUT" "$SHADOW_BIN" "$shadow_dir/shadow_agents.yaml" \
+    # From the config's directory, which Shadow resolves topology.gml against
+    (cd "$shadow_dir" && exec timeout "$TIMEOUT" "$SHADOW_BIN" -d "$PROJECT_ROOT/shadow.data" shadow_agents.yaml) \
         > "$log_file" 2>&1 &
     local shadow_pid=$!
 
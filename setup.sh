@@ -988,6 +988,11 @@ if [[ ${#MONERO_WALLET_BINARIES[@]} -gt 0 ]]; then
     chmod +x "$MONEROSIM_BIN/monero-wallet-rpc"
 fi
 
+# Install the RPC readiness check the agent wrapper scripts run
+log_info "Installing monerosim-healthcheck -> $MONEROSIM_BIN/monerosim-healthcheck"
+cp ./target/release/monerosim-healthcheck "$MONEROSIM_BIN/monerosim-healthcheck"
+chmod +x "$MONEROSIM_BIN/monerosim-healthcheck"
+
 # Verify the binaries work
 if "$MONEROSIM_BIN/monerod" --version >/dev/null 2>&1; then
     log_ok "Successfully installed monerod to $MONEROSIM_BIN/"
@@ -0,0 +1,246 @@
+//! Miner distributor payout plans.
+//!
+//! With a `miner_distributor:` section, generation decides who the
+//! distributor pays and in what proportion, and writes the result to
+//! `distribution_plan.json` in the shared directory. The distributor reads
+//! the file in place of its `can_receive_distributions` registry scan and
+//! scales each recipient's base payout (`md_out_per_tx` outputs of
+//! `md_output_amount` XMR) by the recipient's `payout_factor`.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent::workload::AgentWorkload;
+use crate::config::{AgentDefinitions, DistributionPolicy, MinerDistributorConfig};
+use crate::validation_report::ValidationReport;
+
+/// Payout plan file in the shared directory
+pub const DISTRIBUTION_PLAN_FILE: &str = "distribution_plan.json";
+
+/// The resolved `miner_distributor` section, as written to the plan file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistributionPlan {
+    pub policy: DistributionPolicy,
+    /// Seconds between payout rounds
+    pub payout_interval: u64,
+    /// XMR; smaller payouts are raised to it
+    pub min_payout: Option<f64>,
+    /// Module computing the payout factors, for `custom_script`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+    /// Sorted by agent id
+    pub recipients: Vec<PlannedRecipient>,
+}
+
+/// One agent the distributor pays
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlannedRecipient {
+    pub agent_id: String,
+    /// From the agent's `workload` or `transaction_interval`; `None` when
+    /// it has neither
+    pub sends_per_hour: Option<f64>,
+    /// Multiplies the base payout
+    pub payout_factor: f64,
+}
+
+/// Set each agent's `can_receive_distributions` from `recipients`. A value
+/// set on the agent that disagrees is reported and replaced.
+pub fn apply_recipient_selector(
+    agents: &mut AgentDefinitions,
+    distributor: &MinerDistributorConfig,
+    report: &mut ValidationReport,
+) {
+    for (agent_id, agent) in agents.agents.iter_mut() {
+        let selected = distributor.recipients.selects(agent);
+        if agent.can_receive_distributions == Some(!selected) {
+            report.warn(
+                "distribution",
+                format!(
+                    "Agent '{}': can_receive_distributions: {} is replaced by \
+                     miner_distributor.recipients",
+                    agent_id, !selected
+                ),
+            );
+        }
+        agent.can_receive_distributions = Some(selected);
+    }
+}
+
+/// Plan the payouts to the agents `apply_recipient_selector` selected
+pub fn build_distribution_plan(
+    distributor: &MinerDistributorConfig,
+    agents: &AgentDefinitions,
+    workloads: &BTreeMap<String, AgentWorkload>,
+    report: &mut ValidationReport,
+) -> Result<DistributionPlan, String> {
+    let rates: Vec<(&String, Option<f64>)> = agents
+        .agents
+        .iter()
+        .filter(|(_, agent)| agent.can_receive_distributions())
+        .map(|(agent_id, agent)| {
+            let rate = match workloads.get(agent_id) {
+                Some(w) if w.active_until > w.active_from => {
+                    Some(w.expected_transactions * 3600.0 / (w.active_until - w.active_from) as f64)
+                }
+                Some(_) => Some(0.0),
+                None => agent
+                    .transaction_interval
+                    .filter(|&interval| interval > 0)
+                    .map(|interval| 3600.0 / interval as f64),
+            };
+            (agent_id, rate)
+        })
+        .collect();
+    if rates.is_empty() {
+        report.warn(
+            "distribution",
+            "miner_distributor.recipients selects no agent with a wallet",
+        );
+    }
+
+    // Agents without a known rate count as the average one
+    let known: Vec<f64> = rates.iter().filter_map(|(_, rate)| *rate).collect();
+    let typical = if known.is_empty() {
+        1.0
+    } else {
+        known.iter().sum::<f64>() / known.len() as f64
+    };
+    let weights: Vec<f64> = rates
+        .iter()
+        .map(|(_, rate)| rate.unwrap_or(typical))
+        .collect();
+    let mean_weight = weights.iter().sum::<f64>() / weights.len().max(1) as f64;
+
+    let recipients = rates
+        .iter()
+        .zip(&weights)
+        .map(|((agent_id, rate), weight)| PlannedRecipient {
+            agent_id: agent_id.to_string(),
+            sends_per_hour: *rate,
+            payout_factor: match distributor.policy {
+                DistributionPolicy::Proportional if mean_weight > 0.0 => weight / mean_weight,
+                _ => 1.0,
+            },
+        })
+        .collect();
+
+    Ok(DistributionPlan {
+        policy: distributor.policy,
+        payout_interval: distributor.payout_interval_secs()?,
+        min_payout: distributor.min_payout,
+        script: distributor.script.clone(),
+        recipients,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AgentConfig, WorkloadProfile};
+
+    const AGENTS: &str = r#"
+miner: {daemon: monerod, wallet: monero-wallet-rpc, hashrate: 100}
+busy: {daemon: monerod, wallet: monero-wallet-rpc, transaction_interval: 60, attributes: {tier: gold}}
+idle: {daemon: monerod, wallet: monero-wallet-rpc, transaction_interval: 180, can_receive_distributions: false}
+quiet: {daemon: monerod, wallet: monero-wallet-rpc}
+relay: {daemon: monerod}
+"#;
+
+    fn agents() -> AgentDefinitions {
+        AgentDefinitions {
+            agents: serde_yaml::from_str::<BTreeMap<String, AgentConfig>>(AGENTS).unwrap(),
+        }
+    }
+
+    fn plan(yaml: &str) -> (DistributionPlan, ValidationReport) {
+        let distributor: MinerDistributorConfig = serde_yaml::from_str(yaml).unwrap();
+        let mut agents = agents();
+        let mut report = ValidationReport::default();
+        apply_recipient_selector(&mut agents, &distributor, &mut report);
+        let plan =
+            build_distribution_plan(&distributor, &agents, &BTreeMap::new(), &mut report).unwrap();
+        (plan, report)
+    }
+
+    fn factors(plan: &DistributionPlan) -> Vec<(&str, f64)> {
+        plan.recipients
+            .iter()
+            .map(|r| (r.agent_id.as_str(), r.payout_factor))
+            .collect()
+    }
+
+    #[test]
+    fn equal_pays_every_non_miner_the_base_payout() {
+        let (plan, report) = plan("{}");
+        assert_eq!(plan.policy, DistributionPolicy::Equal);
+        assert_eq!(plan.payout_interval, 300);
+        assert_eq!(
+            factors(&plan),
+            vec![("busy", 1.0), ("idle", 1.0), ("quiet", 1.0)]
+        );
+        // idle's explicit `false` is replaced
+        assert_eq!(report.count(crate::validation_report::Severity::Warning), 1);
+    }
+
+    #[test]
+    fn proportional_scales_by_sending_rate() {
+        let (plan, _) = plan("{policy: proportional, payout_interval: 10m, min_payout: 0.5}");
+        assert_eq!(plan.payout_interval, 600);
+        assert_eq!(plan.min_payout, Some(0.5));
+        // 60/h and 20/h; quiet has no rate and counts as the average, 40/h
+        assert_eq!(
+            factors(&plan),
+            vec![("busy", 1.5), ("idle", 0.5), ("quiet", 1.0)]
+        );
+        assert_eq!(plan.recipients[2].sends_per_hour, None);
+        let total: f64 = plan.recipients.iter().map(|r| r.payout_factor).sum();
+        assert_eq!(total, plan.recipients.len() as f64);
+    }
+
+    #[test]
+    fn proportional_follows_workloads() {
+        let distributor: MinerDistributorConfig =
+            serde_yaml::from_str("{policy: proportional, recipients: all_users}").unwrap();
+        let mut agents = agents();
+        let mut report = ValidationReport::default();
+        apply_recipient_selector(&mut agents, &distributor, &mut report);
+        let workloads = BTreeMap::from([(
+            "miner".to_string(),
+            AgentWorkload {
+                agent_id: "miner".to_string(),
+                profile: WorkloadProfile::Poisson { rate: 120.0 },
+                active_from: 0,
+                active_until: 3600,
+                expected_transactions: 120.0,
+                send_times: Vec::new(),
+            },
+        )]);
+        let plan = build_distribution_plan(&distributor, &agents, &workloads, &mut report).unwrap();
+        // 120/h, 60/h, 20/h and the 200/3 average for quiet
+        let weights = [120.0, 60.0, 20.0, 200.0 / 3.0];
+        let mean = weights.iter().sum::<f64>() / 4.0;
+        assert_eq!(plan.recipients[0].agent_id, "busy");
+        assert_eq!(plan.recipients[2].agent_id, "miner");
+        assert!((plan.recipients[2].payout_factor - 120.0 / mean).abs() < 1e-9);
+    }
+
+    #[test]
+    fn custom_script_leaves_the_factors_to_the_script() {
+        let (plan, _) = plan(
+            "{policy: custom_script, script: scenarios.payouts, recipients: {attribute: {tier: gold}}}",
+        );
+        assert_eq!(plan.script.as_deref(), Some("scenarios.payouts"));
+        assert_eq!(factors(&plan), vec![("busy", 1.0)]);
+    }
+
+    #[test]
+    fn empty_selections_are_reported() {
+        let (plan, report) = plan("{recipients: {attribute: {tier: platinum}}}");
+        assert!(plan.recipients.is_empty());
+        assert!(report
+            .issues
+            .iter()
+            .any(|i| i.message.contains("selects no agent")));
+    }
+}
@@ -24,8 +24,11 @@
 use std::collections::BTreeMap;
 use std::path::Path;
 
-use crate::config::{AgentConfig, AgentDefinitions, DaemonConfig, FallbackSeedsMode};
+use crate::config::{
+    AgentConfig, AgentDefinitions, DaemonConfig, FallbackSeedsMode, UserAgentAttributes,
+};
 use crate::ip::GlobalIpRegistry;
+use crate::utils::duration::SimDuration;
 use crate::utils::extract_mainnet_seed_ips_from_repo;
 use crate::{fallback_seed_agent_id, MONERO_FALLBACK_SEED_IPS};
 
@@ -167,12 +170,13 @@ fn prepare_custom(
     (agents, pinned)
 }
 
-/// Stamp `is_seed_node = "true"` onto the agent's attributes so the
+/// Stamp `is_seed_node` onto the agent's attributes so the
 /// agent registry exposes it and the DNS server can prefer these hosts
 /// over miners when answering Monero seed-domain queries.
 fn mark_as_seed_node(cfg: &mut AgentConfig) {
-    let attrs = cfg.attributes.get_or_insert_with(BTreeMap::new);
-    attrs.insert("is_seed_node".to_string(), "true".to_string());
+    cfg.attributes
+        .get_or_insert_with(Default::default)
+        .is_seed_node = true;
 }
 
 /// Build a daemon-only `AgentConfig` for a synthesized seed host.
@@ -181,18 +185,19 @@ fn mark_as_seed_node(cfg: &mut AgentConfig) {
 /// daemon start times so they come up on consecutive simulated seconds
 /// rather than all on tick 0.
 fn build_seed_agent(seed_index: usize) -> AgentConfig {
-    let mut attrs = BTreeMap::new();
-    attrs.insert("is_seed_node".to_string(), "true".to_string());
     AgentConfig {
         daemon: Some(DaemonConfig::Local("monerod".to_string())),
         wallet: None,
         script: None,
         daemon_options: None,
         wallet_options: None,
-        start_time: Some(format!("{}s", seed_index)),
+        start_time: Some(SimDuration::from_secs(seed_index as u64)),
+        shutdown_time: None,
+        lifetime: None,
         hashrate: None,
         transaction_interval: None,
         activity_start_time: None,
+        workload: None,
         can_receive_distributions: None,
         wait_time: None,
         initial_fund_amount: None,
@@ -205,14 +210,34 @@ fn build_seed_agent(seed_index: usize) -> AgentConfig {
         status_file: None,
         enable_alerts: None,
         detailed_logging: None,
+        metrics: None,
+        output_format: None,
         daemon_phases: None,
         wallet_phases: None,
         daemon_args: None,
         wallet_args: None,
         daemon_env: None,
         wallet_env: None,
-        attributes: Some(attrs),
+        attributes: Some(UserAgentAttributes {
+            is_seed_node: true,
+            ..Default::default()
+        }),
         subnet_group: None,
+        bandwidth_down: None,
+        bandwidth_up: None,
+        ip_version: None,
+        p2p_port: None,
+        rpc_port: None,
+        wallet_rpc_port: None,
+        out_peers: None,
+        in_peers: None,
+        discovery: None,
+        placement: None,
+        colocate_with: None,
+        memory_limit: None,
+        cpu_weight: None,
+        capture_pcap: None,
+        restarts: None,
     }
 }
 
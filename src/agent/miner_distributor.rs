@@ -8,11 +8,29 @@
 use crate::config::{AgentConfig, AgentDefinitions, PeerMode};
 use crate::gml_parser::GmlGraph;
 use crate::ip::{get_agent_ip, AgentType, AsSubnetManager, GlobalIpRegistry};
+use crate::process::{
+    log_level_arg, network_arg, random_seed_arg, script_final_state, stop_time_arg,
+    StartupScheduler,
+};
 use crate::shadow::ShadowHost;
-use crate::utils::script::write_wrapper_script;
+use crate::utils::bandwidth::host_bandwidth;
+use crate::utils::duration::SimDuration;
+use crate::utils::options::shell_quote;
+use crate::utils::script::{write_wrapper_script, ScriptSet};
 use std::collections::BTreeMap;
 use std::path::Path;
 
+/// The miner distributor agent: the first whose id or script names it
+pub fn find_miner_distributor(agents: &AgentDefinitions) -> Option<(&String, &AgentConfig)> {
+    agents.agents.iter().find(|(id, config)| {
+        id.contains("miner_distributor")
+            || config
+                .script
+                .as_ref()
+                .map_or(false, |s| s.contains("miner_distributor"))
+    })
+}
+
 /// Process miner distributor agent
 pub fn process_miner_distributor(
     agents: &AgentDefinitions,
@@ -22,31 +40,20 @@ pub fn process_miner_distributor(
     environment: &BTreeMap<String, String>,
     shared_dir: &Path,
     current_dir: &str,
-    _stop_time: &str,
+    stop_secs: u64,
     gml_graph: Option<&GmlGraph>,
     using_gml_topology: bool,
-    agent_offset: usize,
     _peer_mode: &PeerMode,
-    scripts_dir: &Path,
+    scripts: &ScriptSet,
+    startup: &mut StartupScheduler,
 ) -> color_eyre::eyre::Result<()> {
-    // Find miner_distributor agent in the named agents map
-    let miner_distributor: Option<(&String, &AgentConfig)> =
-        agents.agents.iter().find(|(id, config)| {
-            id.contains("miner_distributor")
-                || config
-                    .script
-                    .as_ref()
-                    .map_or(false, |s| s.contains("miner_distributor"))
-        });
-
-    if let Some((agent_id, miner_distributor_config)) = miner_distributor {
+    if let Some((agent_id, miner_distributor_config)) = find_miner_distributor(agents) {
         let miner_distributor_id = agent_id.as_str();
         // Assign miner distributor to node 0 (which has bandwidth info in GML)
         let network_node_id = 0;
         let miner_distributor_ip = get_agent_ip(
             AgentType::MinerDistributor,
             miner_distributor_id,
-            agent_offset,
             network_node_id,
             gml_graph,
             using_gml_topology,
@@ -56,11 +63,15 @@ pub fn process_miner_distributor(
         )?;
         let mut processes = Vec::new();
 
+        let script_stop = miner_distributor_config.script_stop_secs(stop_secs);
         let mut agent_args = vec![
             format!("--id {}", miner_distributor_id),
             format!("--shared-dir {}", shared_dir.to_string_lossy()),
-            format!("--log-level DEBUG"),
+            log_level_arg(environment),
+            network_arg(environment),
+            stop_time_arg(script_stop),
         ];
+        agent_args.extend(random_seed_arg(environment, miner_distributor_id));
 
         // Pass all known miner distributor config fields as attributes
         // These are read by the Python agent via --attributes key value
@@ -85,10 +96,12 @@ pub fn process_miner_distributor(
         }
 
         // Add any custom attributes from config
-        if let Some(attrs) = &miner_distributor_config.attributes {
-            for (key, value) in attrs {
-                agent_args.push(format!("--attributes {} {}", key, value));
-            }
+        for (key, value) in miner_distributor_config.attribute_map() {
+            agent_args.push(format!(
+                "--attributes {} {}",
+                shell_quote(&key),
+                shell_quote(&value)
+            ));
         }
 
         // `exec` so bash is replaced by python3 — see add_user_agent_process.
@@ -127,22 +140,26 @@ export PATH="$PATH:{}/.monerosim/bin"
             current_dir, current_dir, venv_sp, home_dir, python_cmd
         );
 
-        // Determine execution start time from config's wait_time field
-        // Default: 14400s (4h) to ensure sufficient blocks for unlock and ring signatures
-        let wait_time_seconds = miner_distributor_config.wait_time.unwrap_or(14400);
-        let start_time = format!("{}s", wait_time_seconds);
+        // Starts at the config's wait_time, else once mined outputs are
+        // spendable (see StartupScheduler::distributor)
+        let start_time = SimDuration::from_secs(startup.distributor(
+            miner_distributor_id,
+            miner_distributor_config.wait_time.map(u64::from),
+        ));
 
         let process = write_wrapper_script(
-            scripts_dir,
+            scripts,
             &format!("{}_wrapper.sh", miner_distributor_id),
             &wrapper_script,
             environment,
             start_time,
             None,
-            Some(crate::shadow::ExpectedFinalState::Running),
+            Some(script_final_state(script_stop, stop_secs)),
         )?;
         processes.push(process);
 
+        let (bandwidth_down, bandwidth_up) = host_bandwidth(miner_distributor_config)
+            .map_err(|e| color_eyre::eyre::eyre!("Agent '{}': {}", agent_id, e))?;
         hosts.insert(
             miner_distributor_id.to_string(),
             ShadowHost {
@@ -150,8 +167,9 @@ export PATH="$PATH:{}/.monerosim/bin"
                 ip_addr: Some(miner_distributor_ip),
                 blocked_inbound_ports: None,
                 processes,
-                bandwidth_down: Some("1000000000".to_string()), // 1 Gbit/s
-                bandwidth_up: Some("1000000000".to_string()),   // 1 Gbit/s
+                bandwidth_down: Some(bandwidth_down),
+                bandwidth_up: Some(bandwidth_up),
+                host_options: None,
             },
         );
         // Note: next_ip is already incremented in get_agent_ip function
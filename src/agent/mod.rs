@@ -1,13 +1,16 @@
 //! Agent configuration and processing for user agents, miners, and scripts.
 
+pub mod distribution_plan;
 pub mod fallback_seeds;
 pub mod miner_distributor;
 pub mod pure_scripts;
 pub mod simulation_monitor;
 pub mod user_agents;
+pub mod wallet_keys;
+pub mod workload;
 
 pub use fallback_seeds::prepare_fallback_seeds;
-pub use miner_distributor::process_miner_distributor;
+pub use miner_distributor::{find_miner_distributor, process_miner_distributor};
 pub use pure_scripts::process_pure_script_agents;
 pub use simulation_monitor::process_simulation_monitor;
 pub use user_agents::{process_user_agents, UserAgentProcessContext};
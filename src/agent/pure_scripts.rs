@@ -8,8 +8,12 @@
 use crate::config::AgentDefinitions;
 use crate::gml_parser::GmlGraph;
 use crate::ip::{get_agent_ip, AgentType, AsSubnetManager, GlobalIpRegistry};
+use crate::process::{log_level_arg, script_final_state, stop_time_arg, StartupScheduler};
 use crate::shadow::ShadowHost;
-use crate::utils::script::write_wrapper_script;
+use crate::utils::bandwidth::host_bandwidth;
+use crate::utils::duration::SimDuration;
+use crate::utils::options::shell_quote;
+use crate::utils::script::{write_wrapper_script, ScriptSet};
 use std::collections::BTreeMap;
 use std::path::Path;
 
@@ -23,11 +27,11 @@ pub fn process_pure_script_agents(
     environment: &BTreeMap<String, String>,
     shared_dir: &Path,
     current_dir: &str,
-    _stop_time: &str,
+    stop_secs: u64,
     gml_graph: Option<&GmlGraph>,
     using_gml_topology: bool,
-    agent_offset: usize,
-    scripts_dir: &Path,
+    scripts: &ScriptSet,
+    startup: &mut StartupScheduler,
 ) -> color_eyre::eyre::Result<()> {
     // Find pure script agents (script-only, no daemon/wallet)
     // Exclude miner_distributor and simulation_monitor which have their own processing
@@ -49,14 +53,13 @@ pub fn process_pure_script_agents(
         })
         .collect();
 
-    for (i, (agent_id, pure_script_config)) in pure_scripts.iter().enumerate() {
+    for (agent_id, pure_script_config) in &pure_scripts {
         let script_id = agent_id.as_str();
         // Assign pure scripts to node 0 (which has bandwidth info in GML)
         let network_node_id = 0;
         let script_ip = get_agent_ip(
             AgentType::PureScriptAgent,
             script_id,
-            agent_offset + i,
             network_node_id,
             gml_graph,
             using_gml_topology,
@@ -65,17 +68,21 @@ pub fn process_pure_script_agents(
             None,
         )?;
 
+        let script_stop = pure_script_config.script_stop_secs(stop_secs);
         let mut script_args = vec![
             format!("--id {}", script_id),
             format!("--shared-dir {}", shared_dir.to_string_lossy()),
-            format!("--log-level DEBUG"),
+            log_level_arg(environment),
+            stop_time_arg(script_stop),
         ];
 
         // Add attributes as arguments
-        if let Some(attrs) = &pure_script_config.attributes {
-            for (key, value) in attrs {
-                script_args.push(format!("--{} {}", key, value));
-            }
+        for (key, value) in pure_script_config.attribute_map() {
+            script_args.push(format!(
+                "{} {}",
+                shell_quote(&format!("--{}", key)),
+                shell_quote(&value)
+            ));
         }
 
         // Get script path
@@ -115,17 +122,19 @@ echo "Starting pure script agent {}..."
             current_dir, current_dir, venv_sp, home_dir, script_id, python_cmd
         );
 
-        let start_time = format!("{}s", 6 + i * 2);
+        let start_time = SimDuration::from_secs(startup.script_agent(script_id));
         let process = write_wrapper_script(
-            scripts_dir,
+            scripts,
             &format!("{}_wrapper.sh", script_id),
             &wrapper_content,
             environment,
             start_time,
             None,
-            Some(crate::shadow::ExpectedFinalState::Running),
+            Some(script_final_state(script_stop, stop_secs)),
         )?;
 
+        let (bandwidth_down, bandwidth_up) = host_bandwidth(pure_script_config)
+            .map_err(|e| color_eyre::eyre::eyre!("Agent '{}': {}", agent_id, e))?;
         hosts.insert(
             script_id.to_string(),
             ShadowHost {
@@ -133,8 +142,9 @@ echo "Starting pure script agent {}..."
                 ip_addr: Some(script_ip),
                 blocked_inbound_ports: None,
                 processes: vec![process],
-                bandwidth_down: Some("1000000000".to_string()), // 1 Gbit/s
-                bandwidth_up: Some("1000000000".to_string()),   // 1 Gbit/s
+                bandwidth_down: Some(bandwidth_down),
+                bandwidth_up: Some(bandwidth_up),
+                host_options: None,
             },
         );
     }
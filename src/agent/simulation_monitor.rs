@@ -5,14 +5,21 @@
 //! track simulation state, performance metrics, and can trigger alerts based on
 //! configurable conditions.
 
-use crate::config::{AgentConfig, AgentDefinitions};
+use crate::config::{AgentConfig, AgentDefinitions, MonitorMetric};
 use crate::gml_parser::GmlGraph;
 use crate::ip::{get_agent_ip, AgentType, AsSubnetManager, GlobalIpRegistry};
+use crate::process::{log_level_arg, script_final_state, stop_time_arg, StartupScheduler};
 use crate::shadow::ShadowHost;
-use crate::utils::script::write_wrapper_script;
+use crate::utils::bandwidth::host_bandwidth;
+use crate::utils::duration::SimDuration;
+use crate::utils::options::shell_quote;
+use crate::utils::script::{write_wrapper_script, ScriptSet};
 use std::collections::BTreeMap;
 use std::path::Path;
 
+/// Time-series file in the shared directory, without its format extension
+pub const MONITOR_METRICS_STEM: &str = "monitor_metrics";
+
 /// Process simulation monitor agent
 ///
 /// Creates a Shadow host configuration for the simulation monitor agent,
@@ -27,10 +34,10 @@ use std::path::Path;
 /// - `shared_dir`: Shared directory for inter-agent communication
 /// - `current_dir`: Current working directory
 /// - `output_dir`: Shadow output directory for daemon logs and status file
-/// - `_stop_time`: Simulation stop time (unused)
+/// - `stop_secs`: Simulation stop time in seconds; the script stops at its `lifetime` if earlier
 /// - `gml_graph`: Optional GML topology graph
 /// - `using_gml_topology`: Whether GML topology is being used
-/// - `agent_offset`: Offset for IP allocation to avoid conflicts
+/// - `startup`: Start time scheduler; user agents must be scheduled first
 ///
 /// # Returns
 /// Result indicating success or failure of simulation monitor processing
@@ -43,11 +50,11 @@ pub fn process_simulation_monitor(
     shared_dir: &Path,
     current_dir: &str,
     output_dir: &Path,
-    _stop_time: &str,
+    stop_secs: u64,
     gml_graph: Option<&GmlGraph>,
     using_gml_topology: bool,
-    agent_offset: usize,
-    scripts_dir: &Path,
+    scripts: &ScriptSet,
+    startup: &mut StartupScheduler,
 ) -> color_eyre::eyre::Result<()> {
     // Find simulation_monitor agent in the named agents map
     let simulation_monitor: Option<(&String, &AgentConfig)> =
@@ -66,7 +73,6 @@ pub fn process_simulation_monitor(
         let simulation_monitor_ip = get_agent_ip(
             AgentType::PureScriptAgent,
             simulation_monitor_id,
-            agent_offset,
             network_node_id,
             gml_graph,
             using_gml_topology,
@@ -79,11 +85,13 @@ pub fn process_simulation_monitor(
         // Convert output_dir to absolute path string
         let output_dir_str = output_dir.to_string_lossy();
 
+        let script_stop = simulation_monitor_config.script_stop_secs(stop_secs);
         let mut agent_args = vec![
             format!("--id {}", simulation_monitor_id),
             format!("--shared-dir {}", shared_dir.to_string_lossy()),
             format!("--output-dir {}", output_dir_str),
-            format!("--log-level DEBUG"),
+            log_level_arg(environment),
+            stop_time_arg(script_stop),
         ];
 
         // Add configuration-specific arguments from AgentConfig fields
@@ -120,11 +128,33 @@ pub fn process_simulation_monitor(
             agent_args.push("--detailed-logging".to_string());
         }
 
+        // Time-series file, read back by `tx-analyzer monitor`
+        if simulation_monitor_config.metrics.is_some()
+            || simulation_monitor_config.output_format.is_some()
+        {
+            let metrics = simulation_monitor_config
+                .metrics
+                .as_deref()
+                .unwrap_or(&MonitorMetric::ALL);
+            let format = simulation_monitor_config.output_format.unwrap_or_default();
+            let names: Vec<&str> = metrics.iter().map(|m| m.as_str()).collect();
+            agent_args.push(format!("--metrics {}", names.join(",")));
+            agent_args.push(format!("--output-format {}", format.extension()));
+            agent_args.push(format!(
+                "--metrics-file {}/{}.{}",
+                shared_dir.to_string_lossy(),
+                MONITOR_METRICS_STEM,
+                format.extension()
+            ));
+        }
+
         // Add any additional arguments from attributes
-        if let Some(attrs) = &simulation_monitor_config.attributes {
-            for (key, value) in attrs {
-                agent_args.push(format!("--{} {}", key, value));
-            }
+        for (key, value) in simulation_monitor_config.attribute_map() {
+            agent_args.push(format!(
+                "{} {}",
+                shell_quote(&format!("--{}", key)),
+                shell_quote(&value)
+            ));
         }
 
         // Get script path
@@ -166,16 +196,19 @@ export PATH="$PATH:{}/.monerosim/bin"
         );
 
         let process = write_wrapper_script(
-            scripts_dir,
+            scripts,
             &format!("{}_wrapper.sh", simulation_monitor_id),
             &wrapper_script,
             environment,
-            "5s".to_string(), // Start early to monitor from beginning
+            // Up with the first agent script to monitor from the beginning
+            SimDuration::from_secs(startup.monitor(simulation_monitor_id)),
             None,
-            Some(crate::shadow::ExpectedFinalState::Running),
+            Some(script_final_state(script_stop, stop_secs)),
         )?;
         processes.push(process);
 
+        let (bandwidth_down, bandwidth_up) = host_bandwidth(simulation_monitor_config)
+            .map_err(|e| color_eyre::eyre::eyre!("Agent '{}': {}", agent_id, e))?;
         hosts.insert(
             simulation_monitor_id.to_string(),
             ShadowHost {
@@ -183,8 +216,9 @@ export PATH="$PATH:{}/.monerosim/bin"
                 ip_addr: Some(simulation_monitor_ip),
                 blocked_inbound_ports: None,
                 processes,
-                bandwidth_down: Some("1000000000".to_string()), // 1 Gbit/s
-                bandwidth_up: Some("1000000000".to_string()),   // 1 Gbit/s
+                bandwidth_down: Some(bandwidth_down),
+                bandwidth_up: Some(bandwidth_up),
+                host_options: None,
             },
         );
         // Note: next_ip is already incremented in get_agent_ip function
@@ -5,21 +5,37 @@
 //! It manages peer discovery, IP allocation, and process configuration for
 //! user agents within the Shadow network simulator environment.
 
-use crate::config::{AgentConfig, AgentDefinitions, DaemonConfig, OptionValue, PeerMode};
-use crate::gml_parser::GmlGraph;
-use crate::ip::{AsSubnetManager, GlobalIpRegistry};
+use crate::agent::wallet_keys::wallet_keys_file;
+use crate::agent::workload::workload_file;
+use crate::config::{
+    AgentConfig, AgentDefinitions, AgentPlacement, Chain, ConnectionEnforcement, DaemonConfig,
+    DistributionStrategy, NetworkSettings, OptionValue, PeerDiscovery, PeerMode,
+};
+use crate::generation_trace::{GenerationTrace, STAGE_PLACEMENT};
+use crate::gml_parser::{GmlGraph, GmlNode};
+use crate::ip::as_manager::AsRegion;
+use crate::ip::{assign_geographic_regions, AsSubnetManager, GlobalIpRegistry};
 use crate::process::{
     add_user_agent_process, add_wallet_process, build_wallet_args, create_mining_agent_process,
-    DaemonAddress, MiningAgentProcessArgs, UserAgentProcessArgs, WalletProcessArgs,
+    AgentPorts, DaemonAddress, MiningAgentProcessArgs, StartupRole, StartupScheduler,
+    UserAgentProcessArgs, WalletProcessArgs,
 };
-use crate::shadow::{ExpectedFinalState, ShadowHost};
+use crate::shadow::{AgentSchedule, ExpectedFinalState, ShadowHost, ShadowProcess};
 use crate::topology::{
     build_peer_topology, distribute_agents_across_topology, generate_topology_connections,
-    PeerTopology, Topology,
+    select_hybrid_peers, AgentEntry, DistributionKey, DistributionWeights, MinerConstraint,
+    MinerLocation, PeerAssignment, PeerSource, PeerTopology, PeerTopologyContext, PinConstraint,
+    RealizedDistribution, SeedChoice, SpyTargets, Topology,
+};
+use crate::utils::bandwidth::host_bandwidth;
+use crate::utils::binary::BinaryResolver;
+use crate::utils::duration::SimDuration;
+use crate::utils::options::{
+    apply_option_overrides, flag_name, merge_args, merge_options, options_to_args,
+    translate_daemon_log_level,
 };
-use crate::utils::binary::resolve_binary_path_for_shadow;
-use crate::utils::duration::parse_duration_to_seconds;
-use crate::utils::options::{merge_options, options_to_args, translate_daemon_log_level};
+use crate::utils::script::ScriptSet;
+use crate::utils::seed::{seeded_hash, shuffle_for_agent};
 use std::collections::{BTreeMap, HashSet};
 use std::path::Path;
 
@@ -32,20 +48,50 @@ pub struct UserAgentProcessContext<'a> {
     pub ip_registry: &'a mut GlobalIpRegistry,
     pub monerod_path: &'a str,
     pub wallet_path: &'a str,
+    /// Resolves per-agent / per-phase binary specs (general.binaries + PATH).
+    pub binaries: &'a BinaryResolver,
     pub environment: &'a BTreeMap<String, String>,
     pub monero_environment: &'a BTreeMap<String, String>,
     pub shared_dir: &'a Path,
     pub current_dir: &'a str,
     pub gml_graph: Option<&'a GmlGraph>,
     pub using_gml_topology: bool,
-    pub peer_mode: &'a PeerMode,
-    pub topology: Option<&'a Topology>,
+    /// Peer mode, seeds, topology, placement and peer limits from
+    /// `network:`, defaults applied
+    pub network: &'a NetworkSettings,
+    /// Receives the seeds regular agents bootstrap from and why each was
+    /// chosen
+    pub seeds: &'a mut Vec<SeedChoice>,
+    /// Receives each regular agent's fixed peers and their source when
+    /// `network.hybrid_options` is set
+    pub peer_sources: &'a mut BTreeMap<String, Vec<PeerAssignment>>,
+    /// Receives each daemon's template peers (agent ids) when the topology
+    /// is wired in Hardcoded peer mode
+    pub topology_connections: &'a mut BTreeMap<String, Vec<String>>,
+    /// Receives each wallet's effective argv (the last phase's for phased
+    /// wallets), recorded in the agent registry
+    pub wallet_args: &'a mut BTreeMap<String, Vec<String>>,
+    /// Receives each daemon's effective argv (the last phase's for phased
+    /// daemons), the source of `intended_topology.json`
+    pub daemon_args: &'a mut BTreeMap<String, Vec<String>>,
+    /// Receives each agent's start and shutdown time, recorded in the
+    /// agent registry
+    pub schedules: &'a mut BTreeMap<String, AgentSchedule>,
+    /// Receives each miner's GML node and AS (GML topologies only)
+    pub miner_locations: &'a mut BTreeMap<String, MinerLocation>,
+    /// Receives the GML node and AS of each agent with a `placement`
+    pub pinned_locations: &'a mut BTreeMap<String, MinerLocation>,
+    /// Start times of every daemon, wallet and script
+    pub startup: &'a mut StartupScheduler,
+    /// Per-agent ports from `assign_agent_ports`
+    pub agent_ports: &'a BTreeMap<String, AgentPorts>,
     pub enable_dns_server: bool,
     pub daemon_defaults: Option<&'a BTreeMap<String, OptionValue>>,
     pub wallet_defaults: Option<&'a BTreeMap<String, OptionValue>>,
-    pub distribution_strategy: Option<&'a crate::config::DistributionStrategy>,
-    pub distribution_weights: Option<&'a crate::config::RegionWeights>,
-    pub scripts_dir: &'a Path,
+    /// Receives the agents per region or AS under
+    /// `network.geographic_distribution` or `network.as_weights`
+    pub agent_distribution: &'a mut Option<RealizedDistribution>,
+    pub scripts: &'a ScriptSet,
     pub daemon_data_dir: &'a str,
     /// Deterministic seed for selecting which nodes are unreachable.
     pub simulation_seed: u64,
@@ -55,23 +101,56 @@ pub struct UserAgentProcessContext<'a> {
     pub reachable_by_role: Option<&'a BTreeMap<String, f64>>,
     /// Global fraction of non-seed nodes that run `--hide-my-port` (0.0 = none).
     pub hidden_fraction: f64,
+    /// `general.chain`, selected on every daemon
+    pub chain: Chain,
+    /// `general.fixed_difficulty`, passed to every daemon
+    pub fixed_difficulty: Option<u64>,
+    /// `block_controller.block_interval` in seconds, passed to the miners
+    pub block_interval: u64,
     /// Simulation stop time in seconds — bounds turnover session generation.
     pub simulation_stop_secs: u64,
     /// Peer-turnover config (None = no turnover; relays stay always-on).
     pub turnover: Option<&'a crate::config::TurnoverConfig>,
+    /// `fresh_blockchain: false`: the daemons start on existing chain data,
+    /// so mining agents get `--warm-start`
+    pub warm_start: bool,
+    /// Each miner's resolved hashrate weight, passed as its `hashrate`
+    pub miner_weights: &'a BTreeMap<String, f64>,
+    /// Each mining pool's miners, passed to them as `--pool`
+    pub mining_pools: &'a BTreeMap<String, Vec<String>>,
+    /// Receives each agent's GML node and why it landed there
+    pub trace: &'a mut GenerationTrace,
 }
 
-/// Process user agents
-/// Stable FNV-1a hash of (seed, id) — deterministic and reproducible
-/// without depending on std's (unstable across versions) hasher, so the
-/// same binary + seed always selects the same unreachable nodes.
-fn seeded_hash(seed: u64, s: &str) -> u64 {
-    let mut h: u64 = 0xcbf2_9ce4_8422_2325 ^ seed;
-    for b in s.bytes() {
-        h ^= b as u64;
-        h = h.wrapping_mul(0x0000_0100_0000_01b3);
+/// Whether `arg` tells a daemon to dial one of `addrs`
+fn dials_any(arg: &str, addrs: &HashSet<String>) -> bool {
+    matches!(
+        flag_name(arg),
+        Some("seed-node" | "add-priority-node" | "add-exclusive-node")
+    ) && arg
+        .split_once('=')
+        .is_some_and(|(_, addr)| addrs.contains(addr))
+}
+
+/// In exclusive mode a daemon dials only its `--add-exclusive-node` peers,
+/// so one without any would be cut off from the network.
+fn require_exclusive_peers(
+    agent_id: &str,
+    daemon_args: &[String],
+    enforcement: ConnectionEnforcement,
+) -> color_eyre::eyre::Result<()> {
+    if enforcement == ConnectionEnforcement::Exclusive
+        && !daemon_args
+            .iter()
+            .any(|arg| flag_name(arg) == Some("add-exclusive-node"))
+    {
+        return Err(color_eyre::eyre::eyre!(
+            "Agent '{}' has no exclusive peers under connection_enforcement exclusive and \
+             would be isolated; give it --add-exclusive-node via daemon_args",
+            agent_id
+        ));
     }
-    h
+    Ok(())
 }
 
 /// Decide which non-seed agents are unreachable (get `--hide-my-port`).
@@ -88,15 +167,9 @@ fn compute_unreachable_set(
 ) -> HashSet<String> {
     let mut by_role_ids: BTreeMap<&str, Vec<String>> = BTreeMap::new();
     for (id, cfg) in user_agents {
-        let is_miner = cfg.is_miner();
-        let is_seed = is_miner
-            || cfg
-                .attributes
-                .as_ref()
-                .map(|a| a.get("is_seed_node").map_or(false, |v| v == "true"))
-                .unwrap_or(false);
-        if is_seed {
-            continue; // seeds + miners always reachable
+        let is_seed = cfg.is_seed_node();
+        if is_seed || cfg.is_spy() {
+            continue; // seeds + miners (and spies) always reachable
         }
         let role = if cfg.has_wallet() { "user" } else { "relay" };
         by_role_ids.entry(role).or_default().push(id.to_string());
@@ -171,13 +244,9 @@ fn compute_turnover_set(
         if cfg.is_miner() {
             continue;
         }
-        let is_seed = cfg
-            .attributes
-            .as_ref()
-            .map(|a| a.get("is_seed_node").map_or(false, |v| v == "true"))
-            .unwrap_or(false);
-        if is_seed {
-            continue; // seeds stay always-on (bootstrap backbone)
+        let is_seed = cfg.is_seed_node();
+        if is_seed || cfg.is_spy() {
+            continue; // seeds stay always-on (bootstrap backbone), spies keep observing
         }
         // NOTE: users (has_wallet) take part too now — only the *daemon* cycles;
         // the wallet-rpc + agent stay up and reconnect. Miners are already
@@ -257,6 +326,82 @@ fn build_turnover_schedule(
     out
 }
 
+/// Stop an agent's processes at `shutdown` (seconds). Processes that would
+/// only start by then are dropped, and anything still running then gets a
+/// `shutdown_time`: SIGTERM, on which monerod and the Python agents exit 0,
+/// or SIGKILL for the wallets in `wallet_processes`, which can ignore
+/// SIGTERM (see docs/UPGRADE_WALLET_SIGKILL.md). Scripts ending earlier at
+/// their `lifetime` exit by themselves and are left alone.
+fn apply_shutdown_time(
+    processes: &mut Vec<ShadowProcess>,
+    shutdown: u64,
+    wallet_processes: std::ops::Range<usize>,
+) {
+    let shutdown = SimDuration::from_secs(shutdown);
+    let mut index = 0;
+    processes.retain_mut(|process| {
+        let is_wallet = wallet_processes.contains(&index);
+        index += 1;
+        if process.start_time >= shutdown {
+            return false;
+        }
+        if process.shutdown_time.is_none()
+            && matches!(
+                process.expected_final_state,
+                Some(ExpectedFinalState::Exited(0))
+            )
+        {
+            return true;
+        }
+        if process.shutdown_time.map_or(true, |t| t > shutdown) {
+            process.shutdown_time = Some(shutdown);
+            if is_wallet {
+                process.shutdown_signal = Some("SIGKILL".to_string());
+                process.expected_final_state =
+                    Some(ExpectedFinalState::Signaled("SIGKILL".to_string()));
+            } else if matches!(
+                process.expected_final_state,
+                Some(ExpectedFinalState::Running)
+            ) {
+                process.expected_final_state = Some(ExpectedFinalState::Exited(0));
+            }
+        }
+        true
+    });
+}
+
+/// Agents per AS that will take an address from its subnet: a co-located
+/// agent shares its host's IP, a subnet group or an IP pinned before
+/// allocation replaces it, and a GML node with a valid `ip` gives that
+/// address to the first agent placed on it.
+fn as_address_demand(
+    user_agents: &[(&String, &AgentConfig)],
+    assignments: &[Option<usize>],
+    as_numbers: &[Option<String>],
+    gml: &GmlGraph,
+    ip_registry: &GlobalIpRegistry,
+) -> BTreeMap<String, usize> {
+    let mut demand: BTreeMap<String, usize> = BTreeMap::new();
+    let mut node_ip_taken: HashSet<usize> = HashSet::new();
+    for ((agent_id, config), node) in user_agents.iter().zip(assignments) {
+        let Some(idx) = *node else { continue };
+        if config.colocate_with.is_some()
+            || config.subnet_group.is_some()
+            || ip_registry.get_ip_for_agent(agent_id).is_some()
+        {
+            continue;
+        }
+        if gml.nodes[idx].get_ip().is_some_and(GmlNode::is_valid_ip) && node_ip_taken.insert(idx) {
+            continue;
+        }
+        if let Some(as_number) = &as_numbers[idx] {
+            *demand.entry(as_number.clone()).or_insert(0) += 1;
+        }
+    }
+    demand
+}
+
+/// Process user agents
 pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre::Result<()> {
     let UserAgentProcessContext {
         agents,
@@ -266,28 +411,54 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
         ip_registry,
         monerod_path,
         wallet_path,
+        binaries,
         environment,
         monero_environment,
         shared_dir,
         current_dir,
         gml_graph,
         using_gml_topology,
-        peer_mode,
-        topology,
+        network,
+        seeds: selected_seeds,
+        peer_sources,
+        topology_connections,
+        wallet_args: effective_wallet_args,
+        daemon_args: effective_daemon_args,
+        schedules,
+        miner_locations,
+        pinned_locations,
+        startup,
+        agent_ports,
         enable_dns_server,
         daemon_defaults,
         wallet_defaults,
-        distribution_strategy,
-        distribution_weights,
-        scripts_dir,
+        agent_distribution,
+        scripts,
         daemon_data_dir,
         simulation_seed,
         reachable_fraction,
         reachable_by_role,
         hidden_fraction,
+        chain,
+        fixed_difficulty,
+        block_interval,
         simulation_stop_secs,
         turnover,
+        warm_start,
+        miner_weights,
+        mining_pools,
+        trace,
     } = ctx;
+    let peer_mode = &network.peer_mode;
+    let topology = network.topology.as_ref();
+    let hybrid_options = network.hybrid_options.as_ref();
+    let connection_enforcement = network.connection_enforcement;
+    let distribution_strategy = network.distribution_strategy.as_ref();
+    let distribution_weights = network.distribution_weights.as_ref();
+    let miner_placement = network.miner_placement.as_ref();
+    let geographic_distribution = network.geographic_distribution.as_ref();
+    let as_weights = network.as_weights.as_ref();
+    let (out_peers, in_peers) = (network.out_peers, network.in_peers);
 
     // Filter agents that have daemon or wallet (user agents, not script-only)
     let user_agents: Vec<(&String, &AgentConfig)> = agents
@@ -307,6 +478,14 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
     // 1. Shadow requires sequential node IDs for efficient graph traversal
     // 2. Real AS numbers are sparse with huge gaps
     // 3. Simplifies region mapping without external AS-to-country databases
+    //
+    // `network.geographic_distribution` gives each agent a region up front:
+    // on a link-profile switch that is the continent's node, on a plain
+    // switch the region of its dynamic IP.
+    let geographic_regions: Option<Vec<AsRegion>> = geographic_distribution.map(|shares| {
+        let ids: Vec<&str> = user_agents.iter().map(|(id, _)| id.as_str()).collect();
+        assign_geographic_regions(&ids, shares, simulation_seed)
+    });
     let agent_node_assignments = if let Some(gml) = gml_graph {
         if !user_agents.is_empty() {
             if using_gml_topology {
@@ -322,16 +501,110 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                             .cloned()
                     })
                     .collect::<Vec<Option<String>>>();
-                distribute_agents_across_topology(
+                let is_miner: Vec<bool> = user_agents.iter().map(|(_, c)| c.is_miner()).collect();
+                let node_ids: Vec<u32> = gml.nodes.iter().map(|n| n.id).collect();
+                let placements: Vec<Option<(&str, &AgentPlacement)>> = user_agents
+                    .iter()
+                    .map(|(id, c)| c.placement.as_ref().map(|p| (id.as_str(), p)))
+                    .collect();
+                let mut assignments = distribute_agents_across_topology(
                     Some(Path::new("")),
                     user_agents.len(),
                     &as_numbers,
                     distribution_strategy,
-                    distribution_weights,
+                    DistributionWeights {
+                        regions: distribution_weights,
+                        as_shares: as_weights,
+                    },
+                    miner_placement.map(|placement| MinerConstraint {
+                        placement,
+                        is_miner: &is_miner,
+                        node_ids: &node_ids,
+                    }),
+                    Some(PinConstraint {
+                        placements: &placements,
+                        node_ids: &node_ids,
+                    }),
                 )
-                .into_iter()
-                .map(|opt_idx| opt_idx.map_or(0, |idx| idx as u32))
-                .collect()
+                .map_err(|e| color_eyre::eyre::eyre!("{}", e))?;
+                // The link-profile graph has one node per continent, in
+                // region order
+                if let Some(regions) = &geographic_regions {
+                    for ((node, region), (_, config)) in
+                        assignments.iter_mut().zip(regions).zip(&user_agents)
+                    {
+                        if config.placement.is_none() {
+                            *node = Some(region.index());
+                        }
+                    }
+                }
+                if let Some(shares) = as_weights {
+                    let mut counts: BTreeMap<String, usize> =
+                        shares.keys().map(|asn| (asn.clone(), 0)).collect();
+                    for idx in assignments.iter().flatten() {
+                        let asn = as_numbers[*idx].as_deref().unwrap_or("none");
+                        *counts.entry(asn.to_string()).or_default() += 1;
+                    }
+                    *agent_distribution = Some(RealizedDistribution {
+                        by: DistributionKey::As,
+                        shares: shares.clone(),
+                        counts,
+                    });
+                }
+                let demand =
+                    as_address_demand(&user_agents, &assignments, &as_numbers, gml, ip_registry);
+                subnet_manager
+                    .check_capacity(&demand)
+                    .map_err(|e| color_eyre::eyre::eyre!("IP allocation error: {}", e))?;
+                // Where each miner and pinned agent landed, for the summary
+                // and the manifest
+                for ((agent_id, config), node) in user_agents.iter().zip(&assignments) {
+                    let Some(idx) = *node else { continue };
+                    let location = MinerLocation {
+                        node: gml.nodes[idx].id,
+                        as_number: as_numbers[idx].clone(),
+                    };
+                    let reason = match &config.placement {
+                        Some(AgentPlacement::NodeId(id)) => format!("placement node_id {}", id),
+                        Some(AgentPlacement::As(as_number)) => {
+                            format!("placement as {}", as_number)
+                        }
+                        Some(AgentPlacement::Region(region)) => {
+                            format!("placement region {}", region)
+                        }
+                        None if config.is_miner() && miner_placement.is_some() => {
+                            "network.miner_placement".to_string()
+                        }
+                        None if geographic_regions.is_some() => {
+                            "network.geographic_distribution".to_string()
+                        }
+                        None if as_weights.is_some() => "network.as_weights".to_string(),
+                        None => format!(
+                            "{:?} distribution",
+                            distribution_strategy.unwrap_or(&DistributionStrategy::Global)
+                        ),
+                    };
+                    trace.record(
+                        STAGE_PLACEMENT,
+                        agent_id.as_str(),
+                        format!(
+                            "GML node {} (AS {})",
+                            location.node,
+                            location.as_number.as_deref().unwrap_or("none")
+                        ),
+                        reason,
+                    );
+                    if config.placement.is_some() {
+                        pinned_locations.insert(agent_id.to_string(), location.clone());
+                    }
+                    if config.is_miner() {
+                        miner_locations.insert(agent_id.to_string(), location);
+                    }
+                }
+                assignments
+                    .into_iter()
+                    .map(|opt_idx| opt_idx.map_or(0, |idx| idx as u32))
+                    .collect()
             } else {
                 // If we're not using GML topology (fallback to switch), all agents go to node 0
                 vec![0; user_agents.len()]
@@ -342,6 +615,17 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
     } else {
         Vec::new()
     };
+    if let Some(regions) = geographic_regions.as_ref().filter(|_| !using_gml_topology) {
+        for ((agent_id, _), region) in user_agents.iter().zip(regions) {
+            ip_registry.set_region(agent_id, *region);
+            trace.record(
+                STAGE_PLACEMENT,
+                agent_id.as_str(),
+                format!("{} IP range", region.name()),
+                "network.geographic_distribution",
+            );
+        }
+    }
 
     // No phase validation needed for new AgentConfig (simpler structure)
 
@@ -352,23 +636,54 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
     // IPs that downstream regular agents bootstrap against).
     let PeerTopology {
         agent_info,
-        miners,
         seed_nodes,
-        regular_agents,
         all_agent_ips,
         miner_connections,
         seed_connections,
+        seeds,
+        ..
     } = build_peer_topology(
         &user_agents,
         &agent_node_assignments,
-        peer_mode,
-        gml_graph,
-        using_gml_topology,
-        subnet_manager,
-        ip_registry,
-        seed_agents,
+        network,
+        PeerTopologyContext {
+            gml_graph,
+            using_gml_topology,
+            subnet_manager,
+            ip_registry,
+            seed_agents,
+            agent_ports,
+        },
     )?;
 
+    // Regions as the agents' IPs ended up, pinned fallback-seed IPs included
+    if let Some(shares) = geographic_distribution {
+        let mut counts: BTreeMap<String, usize> = shares
+            .keys()
+            .filter_map(|name| AsRegion::from_key(name).and_then(|r| r.key()))
+            .map(|key| (key.to_string(), 0))
+            .collect();
+        for (agent_id, _) in &user_agents {
+            let region = ip_registry
+                .get_ip_for_agent(agent_id)
+                .map_or(AsRegion::Unknown, |ip| AsRegion::from_ip(ip));
+            *counts
+                .entry(region.key().unwrap_or("unknown").to_string())
+                .or_default() += 1;
+        }
+        *agent_distribution = Some(RealizedDistribution {
+            by: DistributionKey::Region,
+            shares: shares
+                .iter()
+                .filter_map(|(name, share)| Some((AsRegion::from_key(name)?.key()?, *share)))
+                .fold(BTreeMap::new(), |mut by_key, (key, share)| {
+                    *by_key.entry(key.to_string()).or_default() += share;
+                    by_key
+                }),
+            counts,
+        });
+    }
+
     // Regular agents will use seed nodes for --seed-node
 
     // Deterministically select which non-seed nodes are UNREACHABLE, i.e.
@@ -376,12 +691,16 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
     // blocked_inbound_ports, to mimic mainnet's NAT majority. Seeds and
     // miners are always reachable (bootstrap backbone).
     // See docs/20260618_mainnet_topology_targets.md.
-    let unreachable_agents = compute_unreachable_set(
+    let mut unreachable_agents = compute_unreachable_set(
         &user_agents,
         simulation_seed,
         reachable_fraction,
         reachable_by_role,
     );
+    // Seeds picked by policy rather than by is_seed_node stay reachable too
+    for seed in &seeds {
+        unreachable_agents.remove(&seed.id);
+    }
     if !unreachable_agents.is_empty() {
         log::info!(
             "Reachability: {} node(s) marked unreachable via blocked_inbound_ports \
@@ -396,53 +715,51 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
     // (1 - hidden_fraction) selects the first `hidden_fraction` of them in the
     // SAME order — hence hidden ⊆ firewalled when hidden_fraction ≤
     // 1 - reachable_fraction. Default hidden_fraction 0.0 => empty set.
-    let hidden_agents = compute_unreachable_set(
+    let mut hidden_agents = compute_unreachable_set(
         &user_agents,
         simulation_seed,
         1.0 - hidden_fraction,
         None,
     );
+    for seed in &seeds {
+        hidden_agents.remove(&seed.id);
+    }
+
+    // Agents marked is_unreachable are firewalled and hidden on top of the
+    // fractions, and no one else is given their address to dial
+    let explicit_unreachable: Vec<&AgentEntry> = agent_info
+        .iter()
+        .zip(&user_agents)
+        .filter(|(_, (_, cfg))| cfg.is_unreachable())
+        .map(|(e, _)| e)
+        .collect();
+    unreachable_agents.extend(explicit_unreachable.iter().map(|e| e.id.clone()));
+    hidden_agents.extend(explicit_unreachable.iter().map(|e| e.id.clone()));
+    let unreachable_addrs: HashSet<String> = explicit_unreachable
+        .iter()
+        .map(|e| format!("{}:{}", e.ip, e.port))
+        .collect();
 
     // Deterministically select which NODES cycle offline/online (turnover) and
     // pre-parse the turnover timing knobs once. See compute_turnover_set + the
     // per-session emission in the daemon loop below. Empty / None when turnover
     // is disabled (no [general.turnover] and no --turnover-* flag).
-    let turnover_set = match turnover {
+    let mut turnover_set = match turnover {
         Some(c) => compute_turnover_set(&user_agents, simulation_seed, c.fraction),
         None => HashSet::new(),
     };
+    for seed in &seeds {
+        turnover_set.remove(&seed.id);
+    }
     let turnover_params: Option<(f64, f64, f64, f64, f64)> = match turnover {
         Some(c) => {
-            let mean_session = parse_duration_to_seconds(&c.mean_session).map_err(|e| {
-                color_eyre::eyre::eyre!("turnover.mean_session '{}': {}", c.mean_session, e)
-            })? as f64;
-            let mean_downtime = parse_duration_to_seconds(&c.mean_downtime).map_err(|e| {
-                color_eyre::eyre::eyre!("turnover.mean_downtime '{}': {}", c.mean_downtime, e)
-            })? as f64;
-            let min_session = match &c.min_session {
-                Some(s) => parse_duration_to_seconds(s)
-                    .map_err(|e| color_eyre::eyre::eyre!("turnover.min_session '{}': {}", s, e))?
-                    as f64,
-                None => 300.0,
-            };
-            let max_session = match &c.max_session {
-                Some(s) => parse_duration_to_seconds(s)
-                    .map_err(|e| color_eyre::eyre::eyre!("turnover.max_session '{}': {}", s, e))?
-                    as f64,
-                None => f64::INFINITY,
-            };
-            let min_downtime = match &c.min_downtime {
-                Some(s) => parse_duration_to_seconds(s)
-                    .map_err(|e| color_eyre::eyre::eyre!("turnover.min_downtime '{}': {}", s, e))?
-                    as f64,
-                None => 30.0,
-            };
+            let secs = |d: SimDuration| d.as_secs() as f64;
             Some((
-                mean_session,
-                mean_downtime,
-                min_session,
-                max_session,
-                min_downtime,
+                secs(c.mean_session),
+                secs(c.mean_downtime),
+                c.min_session.map_or(300.0, secs),
+                c.max_session.map_or(f64::INFINITY, secs),
+                c.min_downtime.map_or(30.0, secs),
             ))
         }
         None => None,
@@ -460,106 +777,125 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
         }
     }
 
+    // Daemons in agent order: the nodes a Hardcoded Tree / SmallWorld
+    // template is laid over. Wallet-only agents have nothing to connect to
+    // and targeted spies pick their own peers.
+    let template_nodes: Vec<&AgentEntry> = agent_info
+        .iter()
+        .zip(&user_agents)
+        .filter(|(_, (_, cfg))| {
+            (cfg.has_local_daemon() || cfg.has_daemon_phases())
+                && !(cfg.is_spy() && cfg.spy_targets().is_some())
+        })
+        .map(|(e, _)| e)
+        .collect();
+    let template_ips: Vec<String> = template_nodes
+        .iter()
+        .map(|e| format!("{}:{}", e.ip, e.port))
+        .collect();
+
+    // RPC address of every local daemon, for wallet-only agents naming
+    // their remote daemon by agent id
+    let daemon_rpc_addresses: BTreeMap<&str, String> = agent_info
+        .iter()
+        .zip(&user_agents)
+        .filter(|(_, (_, cfg))| cfg.has_local_daemon())
+        .map(|(entry, (id, _))| {
+            let ports = agent_ports.get(id.as_str()).copied().unwrap_or_default();
+            (id.as_str(), format!("{}:{}", entry.ip, ports.rpc))
+        })
+        .collect();
+
+    // Processes (and blocked P2P port) of co-located agents, by host
+    let mut colocated: Vec<(String, Vec<ShadowProcess>, Option<Vec<u16>>)> = Vec::new();
+
     // Now process all user agents with staggered start times
     for (i, (agent_id, user_agent_config)) in user_agents.iter().enumerate() {
         // Determine agent type and start time
         let is_miner = user_agent_config.is_miner();
-        let is_seed_node = is_miner
-            || user_agent_config
-                .attributes
-                .as_ref()
-                .map(|attrs| attrs.get("is_seed_node").map_or(false, |v| v == "true"))
-                .unwrap_or(false);
-
-        // Parse start_time if present (e.g., "2h", "7200s", "30m"). We
-        // keep this as Option so we can distinguish "user explicitly
-        // set 0s" from "user didn't set start_time at all" — the
-        // previous version coerced both to 0 and then bumped to a
-        // calculated default, silently overriding any user-supplied 0s
-        // or otherwise-default-looking value. A bad-format string is
-        // still treated as "not set" but emits a warning, since a hard
-        // parse error here would require plumbing config-validation
-        // upstream.
-        let explicit_start_time: Option<u64> = match user_agent_config.start_time.as_ref() {
-            None => None,
-            Some(s) => match parse_duration_to_seconds(s) {
-                Ok(v) => Some(v),
-                Err(e) => {
-                    log::warn!(
-                        "Agent '{}': could not parse start_time={:?} ({}); falling back to calculated default",
-                        agent_id, s, e
-                    );
-                    None
-                }
-            },
-        };
-
-        let base_start_time_seconds = if matches!(peer_mode, PeerMode::Dynamic) {
-            if is_miner {
-                if i == 0 {
-                    0u64
-                } else {
-                    1 + i as u64
-                }
-            } else {
-                let user_index = i.saturating_sub(miners.len());
-                crate::BLOCK_MATURITY_SECONDS + user_index as u64
-            }
+        let is_seed_node = user_agent_config.is_seed_node();
+
+        // start_time stays an Option so an explicit "0s" is taken as
+        // given rather than replaced by the calculated default
+        let explicit_start_time = user_agent_config.start_time.map(SimDuration::as_secs);
+
+        // Miners come up first; in Hardcoded mode seed nodes all start at
+        // block maturity, everyone else is staggered after it
+        let role = if is_miner {
+            StartupRole::Miner
+        } else if !matches!(peer_mode, PeerMode::Dynamic)
+            && (is_seed_node || seed_nodes.iter().any(|e| e.is_seed_node && e.index == i))
+        {
+            StartupRole::Seed
         } else {
-            if is_miner {
-                i as u64
-            } else if is_seed_node || seed_nodes.iter().any(|e| e.is_seed_node && e.index == i) {
-                crate::BLOCK_MATURITY_SECONDS
-            } else {
-                let user_index = regular_agents
-                    .iter()
-                    .position(|e| e.index == i)
-                    .unwrap_or(0);
-                crate::BLOCK_MATURITY_SECONDS + user_index as u64
-            }
+            StartupRole::User
         };
+        let agent_startup = startup.agent(agent_id, role, explicit_start_time);
+        let effective_start_time = agent_startup.daemon;
+        let start_time_daemon = SimDuration::from_secs(effective_start_time);
+
+        // shutdown_time (validated against stop_time at load) stops every
+        // process of the agent; it has to follow the computed start too
+        let shutdown_secs = user_agent_config.shutdown_time.map(SimDuration::as_secs);
+        if let Some(shutdown) = shutdown_secs.filter(|&t| t <= effective_start_time) {
+            return Err(color_eyre::eyre::eyre!(
+                "Agent '{}': shutdown_time {}s is not after its start at {}s",
+                agent_id,
+                shutdown,
+                effective_start_time
+            ));
+        }
+        // The script leaves its loop at `lifetime`; the rest of the agent
+        // runs on to its shutdown_time or the end of the simulation
+        let agent_end = shutdown_secs.unwrap_or(simulation_stop_secs);
+        let script_stop = user_agent_config.script_stop_secs(agent_end);
+        if user_agent_config.lifetime.is_some() && script_stop <= agent_startup.script {
+            return Err(color_eyre::eyre::eyre!(
+                "Agent '{}': lifetime {}s is not after its script start at {}s",
+                agent_id,
+                script_stop,
+                agent_startup.script
+            ));
+        }
+        schedules.insert(
+            agent_id.to_string(),
+            AgentSchedule {
+                start_time: effective_start_time,
+                shutdown_time: shutdown_secs,
+            },
+        );
 
-        // Honor any explicit start_time, including 0. Only fall
-        // through to the calculated default when the user didn't
-        // supply one at all (or it failed to parse — see warning above).
-        let effective_start_time = explicit_start_time.unwrap_or(base_start_time_seconds);
-        let start_time_daemon = format!("{}s", effective_start_time);
-
-        // Wallet starts after daemon; agent starts after wallet
-        let wallet_start_time =
-            if let Ok(daemon_seconds) = parse_duration_to_seconds(&start_time_daemon) {
-                format!("{}s", daemon_seconds + crate::WALLET_STARTUP_DELAY_SECS)
-            } else {
-                format!("{}s", crate::WALLET_STARTUP_DELAY_SECS + i as u64)
-            };
-
-        let agent_start_time =
-            if let Ok(wallet_seconds) = parse_duration_to_seconds(&wallet_start_time) {
-                format!("{}s", wallet_seconds + crate::AGENT_STARTUP_DELAY_SECS)
-            } else {
-                format!(
-                    "{}s",
-                    crate::WALLET_STARTUP_DELAY_SECS + crate::AGENT_STARTUP_DELAY_SECS + i as u64
-                )
-            };
+        let wallet_start_time = SimDuration::from_secs(agent_startup.wallet);
 
         // Reuse the agent IP from the first pass (stored in agent_info)
         // This avoids calling get_agent_ip twice which would increment the host counter
         let agent_ip = agent_info[i].ip.clone();
-        // Use standard Monero ports (mainnet ports for FAKECHAIN/regtest)
-        // Since each agent has its own IP address, they can all use the same ports
-        let daemon_rpc_port = crate::MONERO_RPC_PORT;
-        let wallet_rpc_port = crate::MONERO_WALLET_RPC_PORT;
-        let p2p_port = crate::MONERO_P2P_PORT;
+        // Standard Monero ports unless the agent shares a host or sets its
+        // own (see assign_agent_ports)
+        let ports = agent_ports
+            .get(agent_id.as_str())
+            .copied()
+            .unwrap_or_default();
+        let daemon_rpc_port = ports.rpc;
+        let wallet_rpc_port = ports.wallet_rpc;
+        let p2p_port = ports.p2p;
 
         let mut processes = Vec::new();
 
         // Determine agent type
         let has_local_daemon = user_agent_config.has_local_daemon();
         let has_remote_daemon = user_agent_config.has_remote_daemon();
+        // A remote daemon named by agent id resolves to its RPC address now;
+        // "auto" is left to the agent's runtime discovery
+        let remote_daemon = user_agent_config
+            .remote_daemon_address()
+            .map(|addr| daemon_rpc_addresses.get(addr).map_or(addr, String::as_str));
         let has_wallet = user_agent_config.has_wallet();
-        let has_daemon_phases = user_agent_config.has_daemon_phases();
+        // `restarts` run through the same phase machinery as upgrades
+        let daemon_phases = user_agent_config.effective_daemon_phases();
+        let has_daemon_phases = daemon_phases.is_some();
         let has_wallet_phases = user_agent_config.has_wallet_phases();
+        let wallet_keys_path = has_wallet.then(|| wallet_keys_file(shared_dir, agent_id));
 
         // Get process_threads from environment (convenience setting)
         let process_threads: u32 = monero_environment
@@ -606,6 +942,126 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                 .or_insert(OptionValue::Bool(true));
         }
 
+        // `discovery: seeds` opts this daemon out of the DNS server;
+        // `discovery: dns` drops its generated seed links below
+        let discovery = user_agent_config.discovery.unwrap_or_default();
+        let uses_dns = enable_dns_server && discovery != PeerDiscovery::Seeds;
+        let mut daemon_base_env = monero_environment.clone();
+        if enable_dns_server && !uses_dns {
+            daemon_base_env.remove("DNS_PUBLIC");
+            daemon_base_env.insert("MONERO_DISABLE_DNS".to_string(), "1".to_string());
+        }
+
+        // Peer limits: the agent's own out_peers / in_peers beat everything,
+        // the network's beat daemon_defaults but not the agent's own
+        // daemon_options (validation rejects the remaining overlaps)
+        let peer_limits = [
+            ("out-peers", user_agent_config.out_peers, out_peers),
+            ("in-peers", user_agent_config.in_peers, in_peers),
+        ];
+        for (flag, agent_limit, network_limit) in peer_limits {
+            let own_option = user_agent_config
+                .daemon_options
+                .as_ref()
+                .is_some_and(|opts| opts.contains_key(flag));
+            let limit = agent_limit.or(network_limit.filter(|_| !own_option));
+            if let Some(limit) = limit {
+                merged_daemon_options.insert(flag.to_string(), OptionValue::Number(limit.into()));
+            }
+        }
+
+        // A spy with spy_targets connects to exactly those daemons instead of
+        // the regular seed / topology wiring
+        let spy_peers = match user_agent_config.spy_targets() {
+            Some(targets) if user_agent_config.is_spy() => {
+                let targets: SpyTargets = targets
+                    .parse()
+                    .map_err(|e| color_eyre::eyre::eyre!("Agent '{}': {}", agent_id, e))?;
+                let candidates: Vec<_> = agent_info
+                    .iter()
+                    .zip(&user_agents)
+                    .filter(|(e, (_, cfg))| e.index != i && cfg.has_local_daemon())
+                    .map(|(e, _)| e)
+                    .collect();
+                let peers = targets.resolve(&candidates, seed_agents, |id| {
+                    seeded_hash(simulation_seed, &format!("spy:{}:{}", agent_id, id))
+                });
+                if peers.is_empty() {
+                    log::warn!(
+                        "Spy '{}': spy_targets '{}' resolved to no peers",
+                        agent_id,
+                        targets
+                    );
+                }
+                Some(peers)
+            }
+            _ => None,
+        };
+
+        let is_actual_seed_node = seed_nodes.iter().any(|e| e.index == i);
+        let hybrid_peers = match (peer_mode, hybrid_options) {
+            (PeerMode::Hybrid, Some(opts))
+                if !is_miner && !is_actual_seed_node && spy_peers.is_none() =>
+            {
+                let peers = select_hybrid_peers(
+                    opts,
+                    topology,
+                    i,
+                    seed_agents,
+                    &all_agent_ips,
+                    &agent_ip,
+                    simulation_seed,
+                );
+                let peers: Vec<PeerAssignment> = peers
+                    .into_iter()
+                    .filter(|p| !unreachable_addrs.contains(&p.peer))
+                    .collect();
+                peer_sources.insert(agent_id.to_string(), peers.clone());
+                Some(peers)
+            }
+            _ => None,
+        };
+
+        // The agent's own daemon_options (log-level already translated)
+        let agent_daemon_overrides: BTreeMap<String, OptionValue> = user_agent_config
+            .daemon_options
+            .iter()
+            .flat_map(|opts| opts.keys())
+            .filter_map(|k| Some((k.clone(), merged_daemon_options.get(k)?.clone())))
+            .collect();
+
+        // Hardcoded Tree / SmallWorld neighbours, recorded for
+        // topology_connections.json
+        let template_index = template_nodes.iter().position(|e| e.index == i);
+        let template_connections = match (peer_mode, topology, template_index) {
+            (
+                PeerMode::Hardcoded,
+                Some(topo @ (Topology::Tree { .. } | Topology::SmallWorld { .. })),
+                Some(index),
+            ) => {
+                let conns: Vec<String> = generate_topology_connections(
+                    topo,
+                    index,
+                    &template_ips,
+                    &agent_ip,
+                    simulation_seed,
+                    connection_enforcement,
+                )
+                .into_iter()
+                .filter(|conn| !dials_any(conn, &unreachable_addrs))
+                .collect();
+                let peer_ids = conns
+                    .iter()
+                    .filter_map(|conn| conn.split_once('='))
+                    .filter_map(|(_, peer)| template_ips.iter().position(|p| p == peer))
+                    .map(|j| template_nodes[j].id.clone())
+                    .collect();
+                topology_connections.insert(agent_id.to_string(), peer_ids);
+                Some(conns)
+            }
+            _ => None,
+        };
+
         let build_daemon_args_base = |phase_args: Option<&Vec<String>>| -> Vec<String> {
             // Start with required/injected flags that cannot be overridden.
             //
@@ -622,9 +1078,8 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
             let mut args = vec![
                 format!("--data-dir={}", data_dir),
                 format!("--log-file={}/bitmonero.log", data_dir),
-                "--regtest".to_string(),
-                "--keep-fakechain".to_string(),
             ];
+            args.extend(chain.daemon_flags().iter().map(|f| f.to_string()));
 
             // Add process_threads flags if set and not overridden in daemon_defaults
             if process_threads > 0 {
@@ -636,6 +1091,12 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                 }
             }
 
+            if let Some(difficulty) = fixed_difficulty {
+                if !merged_daemon_options.contains_key("fixed-difficulty") {
+                    args.push(format!("--fixed-difficulty={}", difficulty));
+                }
+            }
+
             // Add configurable options from merged daemon_defaults + daemon_options
             args.extend(options_to_args(&merged_daemon_options));
 
@@ -650,21 +1111,25 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
             ]);
 
             // Add DNS and seed node settings
-            if !enable_dns_server {
+            if !uses_dns {
                 args.push("--disable-dns-checkpoints".to_string());
             }
-            if is_miner && !enable_dns_server {
+            if is_miner && !uses_dns {
                 args.push("--disable-seed-nodes".to_string());
             }
 
-            // Add initial fixed connections
+            // Add initial fixed connections. A miner promoted to fill the
+            // seed set takes both its miner ring and its seed ring links.
             if is_miner {
                 if let Some(conns) = miner_connections.get(*agent_id) {
                     for conn in conns {
                         args.push(conn.clone());
                     }
                 }
-            } else if is_seed_node || seed_nodes.iter().any(|e| e.is_seed_node && e.index == i) {
+            }
+            if (is_seed_node && !is_miner)
+                || seed_nodes.iter().any(|e| e.is_seed_node && e.index == i)
+            {
                 if let Some(conns) = seed_connections.get(*agent_id) {
                     for conn in conns {
                         args.push(conn.clone());
@@ -673,65 +1138,94 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
             }
 
             // Add peer connections for regular agents
-            let is_actual_seed_node = seed_nodes.iter().any(|e| e.index == i);
-            if !is_miner && !is_actual_seed_node {
-                for seed_node in seed_agents.iter() {
+            if let Some(peers) = &spy_peers {
+                for peer in peers {
+                    args.push(format!("{}={}", connection_enforcement.flag(), peer));
+                }
+            } else if let Some(peers) = &hybrid_peers {
+                // Same flags as the unlimited path below, just fewer of them
+                for p in peers {
+                    args.push(match p.source {
+                        PeerSource::Seed => {
+                            format!("{}={}", connection_enforcement.flag(), p.peer)
+                        }
+                        PeerSource::Topology => {
+                            format!("{}={}", connection_enforcement.seed_flag(), p.peer)
+                        }
+                    });
+                }
+            } else if !is_miner && !is_actual_seed_node {
+                // Each daemon dials the seeds in its own seed-derived order;
+                // a `discovery: dns` daemon asks the DNS server instead
+                let mut ordered_seeds = match discovery {
+                    PeerDiscovery::Dns => Vec::new(),
+                    PeerDiscovery::Seeds | PeerDiscovery::Both => seed_agents.clone(),
+                };
+                shuffle_for_agent(&mut ordered_seeds, simulation_seed, agent_id);
+                for seed_node in &ordered_seeds {
                     if !seed_node.starts_with(&format!("{}:", agent_ip)) {
                         let peer_arg = if matches!(peer_mode, PeerMode::Dynamic) {
                             format!("--seed-node={}", seed_node)
                         } else {
-                            format!("--add-priority-node={}", seed_node)
+                            format!("{}={}", connection_enforcement.flag(), seed_node)
                         };
                         args.push(peer_arg);
                     }
                 }
                 if matches!(peer_mode, PeerMode::Hybrid) {
                     if let Some(topo) = topology {
-                        let topology_connections =
-                            generate_topology_connections(topo, i, &all_agent_ips, &agent_ip);
-                        for conn in topology_connections {
-                            args.push(conn);
-                        }
+                        args.extend(generate_topology_connections(
+                            topo,
+                            i,
+                            &all_agent_ips,
+                            &agent_ip,
+                            simulation_seed,
+                            connection_enforcement,
+                        ));
                     }
                 }
             }
 
-            // Add phase-specific args
-            if let Some(custom_args) = phase_args {
-                for arg in custom_args {
-                    args.push(arg.clone());
-                }
+            // Hardcoded Tree / SmallWorld: every daemon, miners and seeds
+            // included, also links to its template neighbours
+            if let Some(conns) = &template_connections {
+                args.extend(conns.iter().cloned());
             }
 
-            args
+            args.retain(|arg| !dials_any(arg, &unreachable_addrs));
+
+            // daemon_args / phase args replace generated flags of the same
+            // name, and this agent's own daemon_options beat both
+            let args = merge_args(args, phase_args.map_or(&[], |a| a.as_slice()));
+            apply_option_overrides(args, &agent_daemon_overrides)
         };
 
         // Add Monero daemon process(es) - either simple or phase-based
         if has_daemon_phases {
             // Phase-based daemon configuration (upgrade scenario).
             // `has_daemon_phases` already verified daemon_phases is Some and non-empty.
-            let phases = user_agent_config
-                .daemon_phases
+            let phases = daemon_phases
                 .as_ref()
-                .expect("invariant: has_daemon_phases() == true implies daemon_phases.is_some()");
+                .expect("invariant: has_daemon_phases == true implies daemon_phases.is_some()");
             let phase_count = phases.len();
 
             for (phase_num, phase) in phases {
                 let daemon_args = build_daemon_args_base(phase.args.as_ref());
+                require_exclusive_peers(agent_id, &daemon_args, connection_enforcement)?;
+                effective_daemon_args.insert(agent_id.to_string(), daemon_args.clone());
 
                 // Resolve binary path for this phase
-                let daemon_binary_path =
-                    resolve_binary_path_for_shadow(&phase.path).map_err(|e| {
-                        color_eyre::eyre::eyre!(
-                            "Agent '{}': failed to resolve daemon phase binary path '{}': {}",
-                            agent_id,
-                            phase.path,
-                            e
-                        )
-                    })?;
+                let daemon_binary_path = binaries.resolve(&phase.path).map_err(|e| {
+                    color_eyre::eyre::eyre!(
+                        "Agent '{}': failed to resolve daemon phase binary path '{}': {}",
+                        agent_id,
+                        phase.path,
+                        e
+                    )
+                })?;
 
                 // Build environment for this phase
-                let mut daemon_env = monero_environment.clone();
+                let mut daemon_env = daemon_base_env.clone();
                 if let Some(custom_env) = &phase.env {
                     for (key, value) in custom_env {
                         daemon_env.insert(key.clone(), value.clone());
@@ -739,13 +1233,13 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                 }
 
                 // Determine start time
-                let start_time = if let Some(start) = &phase.start {
-                    start.clone()
+                let start_time = if let Some(start) = phase.start {
+                    start
                 } else if *phase_num == 0 {
-                    start_time_daemon.clone()
+                    start_time_daemon
                 } else {
                     // Should have been caught by validation
-                    start_time_daemon.clone()
+                    start_time_daemon
                 };
 
                 // Determine shutdown time and expected final state
@@ -754,7 +1248,7 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                     // Not the last phase - needs shutdown
                     // Shadow sends SIGTERM at shutdown_time; monerod handles it gracefully
                     // and exits with code 0 (not killed by signal)
-                    (phase.stop.clone(), Some(ExpectedFinalState::Exited(0)))
+                    (phase.stop, Some(ExpectedFinalState::Exited(0)))
                 } else {
                     // Last phase - runs until simulation end
                     (None, Some(ExpectedFinalState::Running))
@@ -776,24 +1270,24 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
         } else if has_local_daemon {
             // Simple daemon configuration (single binary)
             let daemon_args = build_daemon_args_base(user_agent_config.daemon_args.as_ref());
+            require_exclusive_peers(agent_id, &daemon_args, connection_enforcement)?;
+            effective_daemon_args.insert(agent_id.to_string(), daemon_args.clone());
 
             // Get daemon binary path from config, fall back to default
             let daemon_binary_path = match &user_agent_config.daemon {
-                Some(DaemonConfig::Local(path)) => {
-                    resolve_binary_path_for_shadow(path).map_err(|e| {
-                        color_eyre::eyre::eyre!(
-                            "Agent '{}': failed to resolve daemon binary path '{}': {}",
-                            agent_id,
-                            path,
-                            e
-                        )
-                    })?
-                }
+                Some(DaemonConfig::Local(path)) => binaries.resolve(path).map_err(|e| {
+                    color_eyre::eyre::eyre!(
+                        "Agent '{}': failed to resolve daemon binary path '{}': {}",
+                        agent_id,
+                        path,
+                        e
+                    )
+                })?,
                 _ => monerod_path.to_string(),
             };
 
             // Merge custom environment from config with base environment
-            let mut daemon_env = monero_environment.clone();
+            let mut daemon_env = daemon_base_env.clone();
             if let Some(custom_env) = &user_agent_config.daemon_env {
                 for (key, value) in custom_env {
                     daemon_env.insert(key.clone(), value.clone());
@@ -813,7 +1307,7 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                         simulation_seed,
                         agent_id,
                         effective_start_time,
-                        simulation_stop_secs,
+                        shutdown_secs.unwrap_or(simulation_stop_secs),
                         *ms,
                         *md,
                         *mins,
@@ -827,7 +1321,7 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                     for (start, stop_opt) in schedule {
                         let (shutdown_time, expected_final_state) = match stop_opt {
                             Some(stop) => (
-                                Some(format!("{}s", stop)),
+                                Some(SimDuration::from_secs(stop)),
                                 Some(ExpectedFinalState::Exited(0)),
                             ),
                             None => (None, Some(ExpectedFinalState::Running)),
@@ -836,7 +1330,7 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                             path: daemon_binary_path.clone(),
                             args: crate::shadow::ProcessArgs::List(daemon_args.clone()),
                             environment: daemon_env.clone(),
-                            start_time: format!("{}s", start),
+                            start_time: SimDuration::from_secs(start),
                             shutdown_time,
                             shutdown_signal: None,
                             expected_final_state,
@@ -849,7 +1343,7 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                         path: daemon_binary_path,
                         args: crate::shadow::ProcessArgs::List(daemon_args),
                         environment: daemon_env,
-                        start_time: start_time_daemon.clone(),
+                        start_time: start_time_daemon,
                         shutdown_time: None,
                         shutdown_signal: None,
                         expected_final_state: Some(ExpectedFinalState::Running),
@@ -857,6 +1351,7 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                 }
             }
         } // End of daemon configuration
+        let daemon_process_count = processes.len();
 
         // Add wallet process based on agent type.
         // Wallet-arg construction (defaults merge, log-level translation, and
@@ -899,15 +1394,14 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                 );
 
                 // Resolve binary path for this phase
-                let wallet_binary_path =
-                    resolve_binary_path_for_shadow(&phase.path).map_err(|e| {
-                        color_eyre::eyre::eyre!(
-                            "Agent '{}': failed to resolve wallet phase binary path '{}': {}",
-                            agent_id,
-                            phase.path,
-                            e
-                        )
-                    })?;
+                let wallet_binary_path = binaries.resolve(&phase.path).map_err(|e| {
+                    color_eyre::eyre::eyre!(
+                        "Agent '{}': failed to resolve wallet phase binary path '{}': {}",
+                        agent_id,
+                        phase.path,
+                        e
+                    )
+                })?;
 
                 // Build environment for this phase
                 let mut wallet_env = environment.clone();
@@ -918,13 +1412,13 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                 }
 
                 // Determine start time
-                let start_time = if let Some(start) = &phase.start {
-                    start.clone()
+                let start_time = if let Some(start) = phase.start {
+                    start
                 } else if *phase_num == 0 {
-                    wallet_start_time.clone()
+                    wallet_start_time
                 } else {
                     // Should have been caught by validation
-                    wallet_start_time.clone()
+                    wallet_start_time
                 };
 
                 // Determine shutdown time, signal, and expected final state.
@@ -941,7 +1435,7 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                 let (shutdown_time, shutdown_signal, expected_final_state) =
                     if *phase_num < (phase_count as u32 - 1) {
                         (
-                            phase.stop.clone(),
+                            phase.stop,
                             Some("SIGKILL".to_string()),
                             Some(ExpectedFinalState::Signaled("SIGKILL".to_string())),
                         )
@@ -961,6 +1455,7 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                     crate::utils::options::shell_quote_args(&wallet_args),
                 );
 
+                effective_wallet_args.insert(agent_id.to_string(), wallet_args.clone());
                 processes.push(crate::shadow::ShadowProcess {
                     path: wallet_binary_path,
                     args: crate::shadow::ProcessArgs::List(wallet_args),
@@ -977,7 +1472,7 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
         } else if has_wallet {
             // Simple wallet configuration (single binary)
             let wallet_binary_path = if let Some(wallet_spec) = &user_agent_config.wallet {
-                resolve_binary_path_for_shadow(wallet_spec).map_err(|e| {
+                binaries.resolve(wallet_spec).map_err(|e| {
                     color_eyre::eyre::eyre!(
                         "Agent '{}': failed to resolve wallet binary path '{}': {}",
                         agent_id,
@@ -995,14 +1490,12 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                     daemon_rpc_port,
                 })
             } else if has_remote_daemon {
-                Some(DaemonAddress::Remote(
-                    user_agent_config.remote_daemon_address(),
-                ))
+                Some(DaemonAddress::Remote(remote_daemon))
             } else {
                 None
             };
             if let Some(daemon) = daemon {
-                wallet_rpc_cmd = Some(add_wallet_process(WalletProcessArgs {
+                let (wallet_cmd, wallet_args) = add_wallet_process(WalletProcessArgs {
                     processes: &mut processes,
                     agent_id: &agent_id,
                     agent_ip: &agent_ip,
@@ -1010,16 +1503,20 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                     wallet_rpc_port,
                     wallet_binary_path: &wallet_binary_path,
                     environment,
-                    wallet_start_time: &wallet_start_time,
+                    wallet_start_time,
                     custom_args: user_agent_config.wallet_args.as_ref(),
                     custom_env: user_agent_config.wallet_env.as_ref(),
                     wallet_defaults,
                     wallet_options: user_agent_config.wallet_options.as_ref(),
                     shared_dir: &shared_dir.to_string_lossy(),
-                }));
+                });
+                wallet_rpc_cmd = Some(wallet_cmd);
+                effective_wallet_args.insert(agent_id.to_string(), wallet_args);
             }
         }
 
+        let wallet_processes = daemon_process_count..processes.len();
+
         // Add agent scripts (skip entirely for daemon-only relay agents)
         if !user_agent_config.is_daemon_only() {
             let script = user_agent_config
@@ -1031,11 +1528,10 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                 // HYBRID APPROACH for miners: Run both regular_user (for wallet) AND mining_script
 
                 // Build merged attributes that include typed fields (hashrate, is_miner, can_receive_distributions)
-                let mut merged_attributes =
-                    user_agent_config.attributes.clone().unwrap_or_default();
+                let mut merged_attributes = user_agent_config.attribute_map();
                 merged_attributes.insert("is_miner".to_string(), "true".to_string());
-                if let Some(hashrate) = user_agent_config.hashrate {
-                    merged_attributes.insert("hashrate".to_string(), hashrate.to_string());
+                if let Some(weight) = miner_weights.get(*agent_id) {
+                    merged_attributes.insert("hashrate".to_string(), weight.to_string());
                 }
                 if user_agent_config.can_receive_distributions() {
                     merged_attributes
@@ -1067,27 +1563,21 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                     environment,
                     shared_dir,
                     current_dir,
-                    index: i,
-                    stop_time: environment
-                        .get("stop_time")
-                        .map(|s| s.as_str())
-                        .unwrap_or("1800"),
-                    custom_start_time: Some(&agent_start_time),
-                    remote_daemon: user_agent_config.remote_daemon_address(),
+                    start_time: agent_startup.script,
+                    stop_time: script_stop,
+                    end_time: agent_end,
+                    remote_daemon,
                     daemon_selection_strategy: user_agent_config
                         .daemon_selection_strategy()
                         .map(|s| s.as_str()),
-                    scripts_dir,
+                    scripts,
                     wallet_rpc_cmd: wallet_rpc_cmd.as_deref(),
+                    workload_file: None,
+                    wallet_keys_file: wallet_keys_path.as_deref(),
                 });
 
                 // Step 2: Run mining_script (autonomous_miner.py)
-                let mining_start_time =
-                    if let Ok(agent_seconds) = parse_duration_to_seconds(&agent_start_time) {
-                        format!("{}s", agent_seconds + 10)
-                    } else {
-                        format!("{}s", 75 + i * 2)
-                    };
+                let mining_start_time = startup.mining_script(agent_id, agent_startup.script);
 
                 let mining_wallet_port = if user_agent_config.wallet.is_some() {
                     Some(wallet_rpc_port)
@@ -1105,17 +1595,23 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                     environment,
                     shared_dir,
                     current_dir,
-                    index: i,
-                    custom_start_time: Some(&mining_start_time),
-                    scripts_dir,
+                    start_time: mining_start_time,
+                    stop_time: script_stop,
+                    end_time: agent_end,
+                    scripts,
                     wallet_rpc_cmd: wallet_rpc_cmd.as_deref(),
+                    warm_start,
+                    block_interval,
+                    pool: mining_pools
+                        .iter()
+                        .find(|(_, miners)| miners.contains(*agent_id))
+                        .map(|(name, _)| name.as_str()),
                 });
                 processes.extend(mining_processes);
             } else if !script.is_empty() {
                 // Regular user agent script
                 // Build merged attributes that include typed config fields
-                let mut merged_attributes =
-                    user_agent_config.attributes.clone().unwrap_or_default();
+                let mut merged_attributes = user_agent_config.attribute_map();
                 if let Some(activity_start_time) = user_agent_config.activity_start_time {
                     merged_attributes.insert(
                         "activity_start_time".to_string(),
@@ -1132,6 +1628,10 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                     merged_attributes
                         .insert("can_receive_distributions".to_string(), "true".to_string());
                 }
+                let workload_path = user_agent_config
+                    .workload
+                    .as_ref()
+                    .map(|_| workload_file(shared_dir, agent_id));
 
                 add_user_agent_process(UserAgentProcessArgs {
                     processes: &mut processes,
@@ -1157,22 +1657,25 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                     environment,
                     shared_dir,
                     current_dir,
-                    index: i,
-                    stop_time: environment
-                        .get("stop_time")
-                        .map(|s| s.as_str())
-                        .unwrap_or("1800"),
-                    custom_start_time: Some(&agent_start_time),
-                    remote_daemon: user_agent_config.remote_daemon_address(),
+                    start_time: agent_startup.script,
+                    stop_time: script_stop,
+                    end_time: agent_end,
+                    remote_daemon,
                     daemon_selection_strategy: user_agent_config
                         .daemon_selection_strategy()
                         .map(|s| s.as_str()),
-                    scripts_dir,
+                    scripts,
                     wallet_rpc_cmd: wallet_rpc_cmd.as_deref(),
+                    workload_file: workload_path.as_deref(),
+                    wallet_keys_file: wallet_keys_path.as_deref(),
                 });
             }
         } // end daemon-only guard
 
+        if let Some(shutdown) = shutdown_secs {
+            apply_shutdown_time(&mut processes, shutdown, wallet_processes);
+        }
+
         // Only add the host if it has any processes
         if !processes.is_empty() {
             // Determine network node ID based on GML assignment or fallback
@@ -1182,25 +1685,48 @@ pub fn process_user_agents(ctx: UserAgentProcessContext<'_>) -> color_eyre::eyre
                 0 // Fallback to node 0 for switch-based networks
             };
 
+            let blocked_inbound_ports = unreachable_agents
+                .contains(agent_id.as_str())
+                .then(|| vec![p2p_port]);
+
+            // Co-located agents join their host once every host exists
+            if let Some(host_id) = &user_agent_config.colocate_with {
+                colocated.push((host_id.clone(), processes, blocked_inbound_ports));
+                continue;
+            }
+
+            let (bandwidth_down, bandwidth_up) = host_bandwidth(user_agent_config)
+                .map_err(|e| color_eyre::eyre::eyre!("Agent '{}': {}", agent_id, e))?;
             hosts.insert(
                 agent_id.to_string(),
                 ShadowHost {
                     network_node_id,
                     ip_addr: Some(agent_ip.clone()),
-                    blocked_inbound_ports: if unreachable_agents.contains(agent_id.as_str()) {
-                        Some(vec![crate::MONERO_P2P_PORT])
-                    } else {
-                        None
-                    },
+                    blocked_inbound_ports,
                     processes,
-                    bandwidth_down: Some(crate::DEFAULT_BANDWIDTH_BPS.to_string()),
-                    bandwidth_up: Some(crate::DEFAULT_BANDWIDTH_BPS.to_string()),
+                    bandwidth_down: Some(bandwidth_down),
+                    bandwidth_up: Some(bandwidth_up),
+                    host_options: None,
                 },
             );
             // Note: next_ip is already incremented in get_agent_ip function
         }
     }
 
+    for (host_id, processes, blocked_inbound_ports) in colocated {
+        let host = hosts.get_mut(&host_id).ok_or_else(|| {
+            color_eyre::eyre::eyre!("colocate_with '{}' has no Shadow host", host_id)
+        })?;
+        host.processes.extend(processes);
+        if let Some(ports) = blocked_inbound_ports {
+            host.blocked_inbound_ports
+                .get_or_insert_with(Vec::new)
+                .extend(ports);
+        }
+    }
+
+    *selected_seeds = seeds;
+
     Ok(())
 }
 
@@ -0,0 +1,84 @@
+//! Deterministic wallets.
+//!
+//! monero-wallet-rpc creates wallets from random seeds, so without help
+//! every run gets different addresses. Each agent with a wallet instead
+//! gets `{agent_id}_wallet_keys.json` in the shared directory, holding keys
+//! derived from the simulation seed and the agent id (see
+//! `utils::monero_keys`). The file uses monero-wallet-rpc's
+//! `--generate-from-json` format; the agent script reads it through
+//! `--wallet-keys-file` and restores the wallet with `generate_from_keys`,
+//! since `--generate-from-json` cannot be combined with the `--wallet-dir`
+//! the agents use. The same address is recorded in the registries.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{AgentDefinitions, Chain};
+use crate::utils::monero_keys::{to_hex, WalletKeys};
+
+/// `{shared_dir}/{agent_id}_wallet_keys.json`
+pub fn wallet_keys_file(shared_dir: &Path, agent_id: &str) -> PathBuf {
+    shared_dir.join(format!("{}_wallet_keys.json", agent_id))
+}
+
+/// Name of the agent's wallet in its wallet directory
+pub fn wallet_name(agent_id: &str) -> String {
+    format!("{}_wallet", agent_id)
+}
+
+/// One agent's wallet, as written to its wallet keys file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WalletBootstrap {
+    pub version: u32,
+    pub filename: String,
+    pub scan_from_height: u64,
+    pub password: String,
+    /// Secret spend key, hex
+    pub spendkey: String,
+    /// Secret view key, hex
+    pub viewkey: String,
+    /// Primary address the keys restore to
+    pub address: String,
+}
+
+impl WalletBootstrap {
+    /// The wallet `agent_id` restores under `simulation_seed` on `chain`
+    pub fn derive(simulation_seed: u64, agent_id: &str, chain: Chain) -> Self {
+        let keys = WalletKeys::derive(simulation_seed, agent_id);
+        Self {
+            version: 1,
+            filename: wallet_name(agent_id),
+            scan_from_height: 0,
+            password: String::new(),
+            spendkey: to_hex(&keys.spend_secret),
+            viewkey: to_hex(&keys.view_secret),
+            address: keys.address(chain),
+        }
+    }
+}
+
+/// Primary address of the wallet `agent_id` restores, for the registries
+pub fn wallet_address(simulation_seed: u64, agent_id: &str, chain: Chain) -> String {
+    WalletKeys::derive(simulation_seed, agent_id).address(chain)
+}
+
+/// The wallet of every agent with one, by agent id
+pub fn build_wallet_bootstraps(
+    agents: &AgentDefinitions,
+    simulation_seed: u64,
+    chain: Chain,
+) -> BTreeMap<String, WalletBootstrap> {
+    agents
+        .agents
+        .iter()
+        .filter(|(_, agent)| agent.has_wallet())
+        .map(|(agent_id, _)| {
+            (
+                agent_id.clone(),
+                WalletBootstrap::derive(simulation_seed, agent_id, chain),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,182 @@
+//! Per-agent transaction workloads.
+//!
+//! Each agent with a `workload` gets `{agent_id}_workload.json` in the
+//! shared directory: its profile and the send times drawn from it over the
+//! agent's active window. The agent script reads the file through
+//! `--workload-file`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{AgentDefinitions, WorkloadProfile};
+use crate::shadow::AgentSchedule;
+use crate::utils::seed::seeded_hash;
+use crate::validation_report::ValidationReport;
+
+/// `{shared_dir}/{agent_id}_workload.json`
+pub fn workload_file(shared_dir: &Path, agent_id: &str) -> PathBuf {
+    shared_dir.join(format!("{}_workload.json", agent_id))
+}
+
+/// The resolved workload of one agent, as written to its workload file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentWorkload {
+    pub agent_id: String,
+    pub profile: WorkloadProfile,
+    /// Active window in simulation seconds: from the agent's start (or
+    /// `activity_start_time`) to its shutdown or the end of the simulation
+    pub active_from: u64,
+    pub active_until: u64,
+    pub expected_transactions: f64,
+    /// Simulation seconds at which to send
+    pub send_times: Vec<f64>,
+}
+
+/// Expected transactions across all workloads
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WorkloadSummary {
+    pub agents: usize,
+    pub expected_transactions: f64,
+    /// Aggregate rate over the whole simulation
+    pub expected_per_hour: f64,
+}
+
+/// Resolve every agent's `workload` against its schedule. Send times come
+/// from an RNG seeded by the simulation seed and the agent id, so they are
+/// reproducible. Workloads that send nothing in their window are reported.
+pub fn build_workloads(
+    agents: &AgentDefinitions,
+    schedules: &BTreeMap<String, AgentSchedule>,
+    simulation_seed: u64,
+    stop_secs: u64,
+    report: &mut ValidationReport,
+) -> BTreeMap<String, AgentWorkload> {
+    let mut workloads = BTreeMap::new();
+    for (agent_id, agent) in &agents.agents {
+        let (Some(profile), Some(schedule)) = (&agent.workload, schedules.get(agent_id)) else {
+            continue;
+        };
+        let active_from = schedule
+            .start_time
+            .max(agent.activity_start_time.unwrap_or(0) as u64);
+        let active_until = schedule.shutdown_time.unwrap_or(stop_secs);
+        let mut rng = StdRng::seed_from_u64(seeded_hash(
+            simulation_seed,
+            &format!("workload:{}", agent_id),
+        ));
+        let send_times = profile.send_times(active_from, active_until, &mut rng);
+        let expected_transactions = profile.expected_count(active_from, active_until);
+        if send_times.is_empty() {
+            report.warn(
+                "workload",
+                format!(
+                    "Agent '{}': {} workload sends no transactions between {}s and {}s",
+                    agent_id,
+                    profile.name(),
+                    active_from,
+                    active_until
+                ),
+            );
+        }
+        workloads.insert(
+            agent_id.clone(),
+            AgentWorkload {
+                agent_id: agent_id.clone(),
+                profile: profile.clone(),
+                active_from,
+                active_until,
+                expected_transactions,
+                send_times,
+            },
+        );
+    }
+    workloads
+}
+
+/// Sum the expected transactions of `workloads` over a `stop_secs` run
+pub fn summarize_workloads(
+    workloads: &BTreeMap<String, AgentWorkload>,
+    stop_secs: u64,
+) -> WorkloadSummary {
+    let expected_transactions: f64 = workloads.values().map(|w| w.expected_transactions).sum();
+    WorkloadSummary {
+        agents: workloads.len(),
+        expected_transactions,
+        expected_per_hour: if stop_secs == 0 {
+            0.0
+        } else {
+            expected_transactions * 3600.0 / stop_secs as f64
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AgentConfig;
+
+    fn agents(workload: WorkloadProfile) -> AgentDefinitions {
+        let agent: AgentConfig = serde_yaml::from_str(
+            "{daemon: monerod, wallet: monero-wallet-rpc, activity_start_time: 600}",
+        )
+        .unwrap();
+        AgentDefinitions {
+            agents: BTreeMap::from([(
+                "user-001".to_string(),
+                AgentConfig {
+                    workload: Some(workload),
+                    ..agent
+                },
+            )]),
+        }
+    }
+
+    fn schedules(shutdown_time: Option<u64>) -> BTreeMap<String, AgentSchedule> {
+        BTreeMap::from([(
+            "user-001".to_string(),
+            AgentSchedule {
+                start_time: 60,
+                shutdown_time,
+            },
+        )])
+    }
+
+    #[test]
+    fn workloads_cover_the_active_window_reproducibly() {
+        let agents = agents(WorkloadProfile::Poisson { rate: 120.0 });
+        let mut report = ValidationReport::default();
+        let build = |report: &mut ValidationReport| {
+            build_workloads(&agents, &schedules(Some(4200)), 42, 7200, report)
+        };
+        let workloads = build(&mut report);
+        let workload = &workloads["user-001"];
+        assert_eq!((workload.active_from, workload.active_until), (600, 4200));
+        assert_eq!(workload.expected_transactions, 120.0);
+        assert!(workload
+            .send_times
+            .iter()
+            .all(|&t| (600.0..4200.0).contains(&t)));
+        assert_eq!(
+            build(&mut report)["user-001"].send_times,
+            workload.send_times
+        );
+        assert_eq!(report.count(crate::validation_report::Severity::Warning), 0);
+
+        let summary = summarize_workloads(&workloads, 7200);
+        assert_eq!(summary.agents, 1);
+        assert_eq!(summary.expected_per_hour, 60.0);
+    }
+
+    #[test]
+    fn empty_workloads_are_reported() {
+        let agents = agents(WorkloadProfile::Constant { interval: 3600 });
+        let mut report = ValidationReport::default();
+        let workloads = build_workloads(&agents, &schedules(None), 42, 1800, &mut report);
+        assert!(workloads["user-001"].send_times.is_empty());
+        assert_eq!(report.count(crate::validation_report::Severity::Warning), 1);
+    }
+}
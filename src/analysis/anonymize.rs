@@ -0,0 +1,1843 @@
+//! Report anonymization for sharing results outside the project.
+//!
+//! Agent ids become stable pseudonyms (seeded hash), raw IPs are dropped,
+//! absolute timestamps are floored to a configurable granularity and timing
+//! metrics (durations, latencies, delays) are scaled by seeded, bounded
+//! noise. Each report
+//! type implements [`Anonymize`] by destructuring itself exhaustively, so a
+//! field added to a report later is a compile error here until someone decides
+//! how it should be scrubbed.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use super::network_graph::{
+    ConnectionChurnStats, IntendedComparison, NetworkGraphReport, NetworkSnapshot,
+    NetworkValidation, NodeDegree, NodeEdgeDiff,
+};
+use super::types::*;
+use crate::manifest::SimulationManifest;
+
+/// Anonymization parameters, recorded in report metadata.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnonymizationInfo {
+    /// Seed mixed into the pseudonym hash; same seed => same pseudonyms
+    pub pseudonym_seed: u64,
+    /// Absolute timestamps are floored to a multiple of this (0 = unchanged)
+    pub time_granularity_sec: f64,
+    /// Timing metrics are each scaled by a factor drawn uniformly from
+    /// `[1 - timing_noise, 1 + timing_noise]`, seeded by `pseudonym_seed`
+    /// (0 = unchanged)
+    #[serde(default)]
+    pub timing_noise: f64,
+    pub ips_dropped: bool,
+}
+
+/// Applies one consistent id/IP/time mapping across every report of a run.
+pub struct Anonymizer {
+    info: AnonymizationInfo,
+    /// (needle, replacement) for scrubbing free text, longest needle first
+    /// so `user-10` is replaced before `user-1`.
+    known: Vec<(String, String)>,
+    ip_owner: HashMap<String, String>,
+    /// Noise factors drawn so far; each timing metric takes the next one
+    draws: AtomicU64,
+}
+
+impl Anonymizer {
+    pub fn new(
+        pseudonym_seed: u64,
+        time_granularity_sec: f64,
+        timing_noise: f64,
+        agents: &[AnalysisAgentInfo],
+    ) -> Self {
+        let mut anonymizer = Self {
+            info: AnonymizationInfo {
+                pseudonym_seed,
+                time_granularity_sec,
+                timing_noise,
+                ips_dropped: true,
+            },
+            known: Vec::new(),
+            ip_owner: HashMap::new(),
+            draws: AtomicU64::new(0),
+        };
+        for agent in agents {
+            let pseudonym = anonymizer.id(&agent.id);
+            anonymizer.known.push((agent.id.clone(), pseudonym.clone()));
+            if !agent.ip_addr.is_empty() {
+                anonymizer
+                    .known
+                    .push((agent.ip_addr.clone(), "[ip]".to_string()));
+                anonymizer.ip_owner.insert(agent.ip_addr.clone(), pseudonym);
+            }
+        }
+        anonymizer
+            .known
+            .sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+        anonymizer
+    }
+
+    pub fn info(&self) -> &AnonymizationInfo {
+        &self.info
+    }
+
+    /// Stable pseudonym for an agent id (FNV-1a over seed + id).
+    pub fn id(&self, id: &str) -> String {
+        if id.is_empty() {
+            return String::new();
+        }
+        let mut h: u64 = 0xcbf2_9ce4_8422_2325 ^ self.info.pseudonym_seed;
+        for b in id.bytes() {
+            h ^= b as u64;
+            h = h.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        format!("node-{:012x}", h >> 16)
+    }
+
+    /// Pseudonym of the agent owning `ip`, or empty if unknown. Used where a
+    /// peer address is the only link between nodes (graph exports).
+    pub fn peer(&self, ip: &str) -> String {
+        self.ip_owner.get(ip).cloned().unwrap_or_default()
+    }
+
+    pub fn time(&self, t: SimTime) -> SimTime {
+        let g = self.info.time_granularity_sec;
+        if g > 0.0 {
+            (t / g).floor() * g
+        } else {
+            t
+        }
+    }
+
+    /// `value` scaled by the next seeded factor in `[1 - n, 1 + n]`, n being
+    /// `timing_noise`; the same seed and report order give the same values
+    pub fn noise(&self, value: f64) -> f64 {
+        let n = self.info.timing_noise;
+        if n <= 0.0 || !value.is_finite() {
+            return value;
+        }
+        // splitmix64 over seed + draw index
+        let draw = self.draws.fetch_add(1, Ordering::Relaxed);
+        let mut z = self.info.pseudonym_seed ^ draw.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        let unit = (z >> 11) as f64 / (1u64 << 53) as f64;
+        value * (1.0 + n * (2.0 * unit - 1.0))
+    }
+
+    /// Replace every known id / IP occurring in free text.
+    pub fn text(&self, s: &str) -> String {
+        let mut out = s.to_string();
+        for (needle, replacement) in &self.known {
+            if out.contains(needle.as_str()) {
+                out = out.replace(needle.as_str(), replacement);
+            }
+        }
+        out
+    }
+
+    fn id_in_place(&self, id: &mut String) {
+        *id = self.id(id);
+    }
+
+    fn ids(&self, ids: &mut [String]) {
+        for id in ids {
+            self.id_in_place(id);
+        }
+    }
+
+    fn texts(&self, texts: &mut [String]) {
+        for t in texts {
+            *t = self.text(t);
+        }
+    }
+
+    fn time_in_place(&self, t: &mut SimTime) {
+        *t = self.time(*t);
+    }
+
+    fn keys<V>(&self, map: &mut HashMap<String, V>) {
+        *map = map.drain().map(|(k, v)| (self.id(&k), v)).collect();
+    }
+
+    fn timing<T: Timing>(&self, value: &mut T) {
+        value.add_noise(self);
+    }
+}
+
+/// A timing metric, or a collection of them, that `--anonymize` adds noise to
+trait Timing {
+    fn add_noise(&mut self, a: &Anonymizer);
+}
+
+impl Timing for f64 {
+    fn add_noise(&mut self, a: &Anonymizer) {
+        *self = a.noise(*self);
+    }
+}
+
+impl<T: Timing> Timing for Option<T> {
+    fn add_noise(&mut self, a: &Anonymizer) {
+        if let Some(inner) = self {
+            inner.add_noise(a);
+        }
+    }
+}
+
+impl<T: Timing> Timing for Vec<T> {
+    fn add_noise(&mut self, a: &Anonymizer) {
+        for item in self {
+            item.add_noise(a);
+        }
+    }
+}
+
+impl Timing for CoveragePoint {
+    fn add_noise(&mut self, a: &Anonymizer) {
+        let Self {
+            percentile: _,
+            time_ms,
+        } = self;
+        a.timing(time_ms);
+    }
+}
+
+impl Timing for CoverageCurvePoint {
+    fn add_noise(&mut self, a: &Anonymizer) {
+        let Self {
+            percentile: _,
+            median_ms,
+            transactions: _,
+        } = self;
+        a.timing(median_ms);
+    }
+}
+
+impl Timing for HopLatencyStats {
+    fn add_noise(&mut self, a: &Anonymizer) {
+        let Self {
+            pairs: _,
+            intra_node_pairs: _,
+            intra_node_fraction: _,
+            placement_known: _,
+            intra_node_excluded: _,
+            average_ms,
+            median_ms,
+            p95_ms,
+            intra_node_average_ms,
+        } = self;
+        a.timing(average_ms);
+        a.timing(median_ms);
+        a.timing(p95_ms);
+        a.timing(intra_node_average_ms);
+    }
+}
+
+impl Timing for ContinentPropagation {
+    fn add_noise(&mut self, a: &Anonymizer) {
+        let Self {
+            continent: _,
+            nodes: _,
+            observations: _,
+            average_delay_ms,
+            median_delay_ms,
+            p95_delay_ms,
+        } = self;
+        a.timing(average_delay_ms);
+        a.timing(median_delay_ms);
+        a.timing(p95_delay_ms);
+    }
+}
+
+impl Timing for GeoPairDelay {
+    fn add_noise(&mut self, a: &Anonymizer) {
+        let Self {
+            from: _,
+            to: _,
+            hops: _,
+            median_ms,
+        } = self;
+        a.timing(median_ms);
+    }
+}
+
+impl Timing for ConnectionChurnStats {
+    fn add_noise(&mut self, a: &Anonymizer) {
+        let Self {
+            total_opens: _,
+            total_closes: _,
+            avg_duration_sec,
+            median_duration_sec,
+            long_lived_connections: _,
+            short_lived_connections: _,
+        } = self;
+        a.timing(avg_duration_sec);
+        a.timing(median_duration_sec);
+    }
+}
+
+/// A report (or report component) that can be scrubbed in place.
+pub trait Anonymize {
+    fn anonymize(&mut self, a: &Anonymizer);
+}
+
+impl<T: Anonymize> Anonymize for Option<T> {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        if let Some(inner) = self {
+            inner.anonymize(a);
+        }
+    }
+}
+
+impl<T: Anonymize> Anonymize for Vec<T> {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        for item in self {
+            item.anonymize(a);
+        }
+    }
+}
+
+impl Anonymize for AnalysisAgentInfo {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            id,
+            ip_addr,
+            rpc_port: _,
+            script_type: _,
+            wallet_address,
+            network_node_id: _,
+            continent: _,
+            as_number: _,
+            role: _,
+            reachable: _,
+            out_peers: _,
+        } = self;
+        a.id_in_place(id);
+        ip_addr.clear();
+        *wallet_address = None;
+    }
+}
+
+// --- full report (spy / propagation / resilience) ---
+
+impl Anonymize for FullAnalysisReport {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            metadata,
+            spy_node_analysis,
+            propagation_analysis,
+            resilience_analysis,
+        } = self;
+        metadata.anonymize(a);
+        spy_node_analysis.anonymize(a);
+        propagation_analysis.anonymize(a);
+        resilience_analysis.anonymize(a);
+    }
+}
+
+impl Anonymize for AnalysisMetadata {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            report_schema_version: _,
+            analysis_timestamp: _,
+            simulation_data_dir,
+            total_nodes: _,
+            total_transactions: _,
+            total_blocks: _,
+            anonymization,
+            manifest,
+        } = self;
+        *simulation_data_dir = "(anonymized)".to_string();
+        *anonymization = Some(a.info().clone());
+        manifest.anonymize(a);
+    }
+}
+
+impl Anonymize for SimulationManifest {
+    /// Hashes and versions stay so the report remains traceable; paths and
+    /// the resolved config (agent ids, IPs) go, and miner ids are mapped
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            generator_version: _,
+            schema_version: _,
+            generated_at: _,
+            config,
+            gml,
+            simulation_seed: _,
+            stop_time: _,
+            chain: _,
+            block_interval: _,
+            binary_versions,
+            resources: _,
+            miner_placement,
+            agent_distribution: _,
+            network_events,
+            pcap_capture,
+            resolved_config,
+        } = self;
+        for file in config.iter_mut().chain(gml.iter_mut()) {
+            file.path = "(anonymized)".to_string();
+        }
+        for binary in binary_versions {
+            binary.path = "(anonymized)".to_string();
+        }
+        *miner_placement = std::mem::take(miner_placement)
+            .into_iter()
+            .map(|(id, location)| (a.id(&id), location))
+            .collect();
+        for id in network_events
+            .iter_mut()
+            .flat_map(|e| e.groups.iter_mut().flatten())
+        {
+            a.id_in_place(id);
+        }
+        if let Some(capture) = pcap_capture {
+            for id in &mut capture.agents {
+                a.id_in_place(id);
+            }
+            if capture.capture_dir.is_some() {
+                capture.capture_dir = Some("(anonymized)".to_string());
+            }
+        }
+        *resolved_config = serde_json::Value::Null;
+    }
+}
+
+impl Anonymize for SpyNodeReport {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            total_transactions: _,
+            analyzable_transactions: _,
+            inference_accuracy: _,
+            timing_spread_distribution: _,
+            vulnerable_senders,
+            per_tx_analysis,
+            observers,
+            per_spy,
+            avg_anonymity_set_size: _,
+        } = self;
+        vulnerable_senders.anonymize(a);
+        per_tx_analysis.anonymize(a);
+        for id in observers {
+            a.id_in_place(id);
+        }
+        per_spy.anonymize(a);
+    }
+}
+
+impl Anonymize for SpyObserverStats {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            node_id,
+            transactions_observed: _,
+            first_observations: _,
+            first_observation_share: _,
+            accuracy: _,
+        } = self;
+        a.id_in_place(node_id);
+    }
+}
+
+impl Anonymize for VulnerableSender {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            sender_id,
+            high_confidence_inferences: _,
+            accuracy: _,
+        } = self;
+        a.id_in_place(sender_id);
+    }
+}
+
+impl Anonymize for SpyNodeTxAnalysis {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            tx_hash: _,
+            true_sender,
+            true_sender_ip,
+            first_seen_by,
+            correlation_confidence: _,
+            timing_spread_ms,
+            inferred_originator_ip,
+            inference_correct: _,
+            anonymity_set_size: _,
+        } = self;
+        a.timing(timing_spread_ms);
+        a.id_in_place(true_sender);
+        *true_sender_ip = None;
+        *inferred_originator_ip = None;
+        first_seen_by.anonymize(a);
+    }
+}
+
+impl Anonymize for FirstSeenEntry {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            node_id,
+            timestamp,
+            delta_from_first_ms,
+            source_ip,
+        } = self;
+        a.timing(delta_from_first_ms);
+        a.id_in_place(node_id);
+        a.time_in_place(timestamp);
+        source_ip.clear();
+    }
+}
+
+impl Anonymize for PropagationReport {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            total_transactions: _,
+            analyzed_transactions: _,
+            average_propagation_ms,
+            median_propagation_ms,
+            p95_propagation_ms,
+            average_confirmation_delay_sec,
+            coverage_curve,
+            hop_latency,
+            by_continent,
+            by_region,
+            bottleneck_nodes,
+            per_tx_analysis,
+            latency_overhead,
+        } = self;
+        a.timing(average_propagation_ms);
+        a.timing(median_propagation_ms);
+        a.timing(p95_propagation_ms);
+        a.timing(average_confirmation_delay_sec);
+        a.timing(coverage_curve);
+        a.timing(hop_latency);
+        a.timing(by_continent);
+        by_region.anonymize(a);
+        bottleneck_nodes.anonymize(a);
+        per_tx_analysis.anonymize(a);
+        latency_overhead.anonymize(a);
+    }
+}
+
+impl Anonymize for RegionBreakdown {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            region_hops: _,
+            intra_region_hops: _,
+            intra_region_median_ms,
+            inter_region_hops: _,
+            inter_region_median_ms,
+            region_matrix,
+            as_matrix,
+            as_bottlenecks,
+        } = self;
+        a.timing(intra_region_median_ms);
+        a.timing(inter_region_median_ms);
+        a.timing(region_matrix);
+        a.timing(as_matrix);
+        as_bottlenecks.anonymize(a);
+    }
+}
+
+impl Anonymize for AsBoundaryBottleneck {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            tx_hash: _,
+            from,
+            to,
+            from_as: _,
+            to_as: _,
+            delay_ms,
+            share: _,
+        } = self;
+        a.timing(delay_ms);
+        a.id_in_place(from);
+        a.id_in_place(to);
+    }
+}
+
+impl Anonymize for LatencyOverhead {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            hops: _,
+            unmatched_hops: _,
+            average_overhead_ms,
+            median_overhead_ms,
+            p95_overhead_ms,
+            anomaly_threshold_ms: _,
+            anomalous_pairs,
+            per_tx,
+        } = self;
+        a.timing(average_overhead_ms);
+        a.timing(median_overhead_ms);
+        a.timing(p95_overhead_ms);
+        anomalous_pairs.anonymize(a);
+        per_tx.anonymize(a);
+    }
+}
+
+impl Anonymize for PairOverhead {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            from,
+            to,
+            hops: _,
+            theoretical_ms,
+            median_observed_ms,
+            median_overhead_ms,
+        } = self;
+        a.timing(theoretical_ms);
+        a.timing(median_observed_ms);
+        a.timing(median_overhead_ms);
+        a.id_in_place(from);
+        a.id_in_place(to);
+    }
+}
+
+impl Anonymize for TxHopOverhead {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self { tx_hash: _, hops } = self;
+        hops.anonymize(a);
+    }
+}
+
+impl Anonymize for HopOverhead {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            from,
+            to,
+            observed_ms,
+            theoretical_ms,
+            overhead_ms,
+        } = self;
+        a.timing(observed_ms);
+        a.timing(theoretical_ms);
+        a.timing(overhead_ms);
+        a.id_in_place(from);
+        a.id_in_place(to);
+    }
+}
+
+impl Anonymize for BottleneckNode {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            node_id,
+            average_delay_ms,
+            observations: _,
+        } = self;
+        a.timing(average_delay_ms);
+        a.id_in_place(node_id);
+    }
+}
+
+impl Anonymize for PropagationAnalysis {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            tx_hash: _,
+            creation_time,
+            first_seen_time,
+            block_inclusion_time,
+            confirmation_delay_sec,
+            network_propagation_time_ms,
+            median_propagation_ms,
+            p95_propagation_ms,
+            nodes_observed: _,
+            total_nodes: _,
+            propagation_coverage: _,
+            coverage,
+        } = self;
+        a.timing(confirmation_delay_sec);
+        a.timing(network_propagation_time_ms);
+        a.timing(median_propagation_ms);
+        a.timing(p95_propagation_ms);
+        a.timing(coverage);
+        a.time_in_place(creation_time);
+        if let Some(t) = first_seen_time {
+            a.time_in_place(t);
+        }
+        if let Some(t) = block_inclusion_time {
+            a.time_in_place(t);
+        }
+    }
+}
+
+impl Anonymize for ResilienceMetrics {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            connectivity,
+            centralization,
+            partition_risk,
+            partition_events,
+        } = self;
+        let ConnectivityMetrics {
+            total_nodes: _,
+            average_peer_count: _,
+            min_peer_count: _,
+            max_peer_count: _,
+            isolated_nodes,
+            peer_count_distribution,
+        } = connectivity;
+        a.ids(isolated_nodes);
+        a.keys(peer_count_distribution);
+        let CentralizationMetrics {
+            first_seen_gini: _,
+            dominant_observers,
+            miner_first_seen_ratio: _,
+        } = centralization;
+        a.ids(dominant_observers);
+        let PartitionRiskMetrics {
+            bridge_nodes,
+            connected_components: _,
+        } = partition_risk;
+        a.ids(bridge_nodes);
+        for event in partition_events {
+            a.time_in_place(&mut event.partition_at);
+            if let Some(t) = &mut event.heal_at {
+                a.time_in_place(t);
+            }
+            a.timing(&mut event.drop_latency_secs);
+            a.timing(&mut event.first_reconnect_secs);
+            a.timing(&mut event.reconverge_secs);
+        }
+    }
+}
+
+// --- tx relay v2 ---
+
+impl Anonymize for TxRelayV2Report {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            report_schema_version: _,
+            protocol_usage: _,
+            delivery_analysis,
+            connection_stability,
+            request_response: _,
+            assessment,
+        } = self;
+        let TxDeliveryAnalysis {
+            total_txs_created: _,
+            txs_fully_propagated: _,
+            txs_potentially_lost: _,
+            per_node_delivery_rate,
+            average_propagation_coverage: _,
+        } = delivery_analysis;
+        a.keys(per_node_delivery_rate);
+        let ConnectionStabilityMetrics {
+            total_drops: _,
+            drops_tx_verification: _,
+            drops_duplicate_tx: _,
+            drops_other: _,
+            drops_by_node,
+            average_connection_duration_sec,
+        } = connection_stability;
+        a.timing(average_connection_duration_sec);
+        a.keys(drops_by_node);
+        let TxRelayAssessment {
+            health_score: _,
+            v2_active: _,
+            has_lost_txs: _,
+            has_stability_issues: _,
+            findings,
+            recommendations,
+        } = assessment;
+        a.texts(findings);
+        a.texts(recommendations);
+    }
+}
+
+// --- dandelion ---
+
+impl Anonymize for DandelionReport {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            report_schema_version: _,
+            total_transactions: _,
+            paths_reconstructed: _,
+            originator_confirmed_count: _,
+            fluff_detection: _,
+            avg_path_confidence: _,
+            avg_stem_length: _,
+            min_stem_length: _,
+            max_stem_length: _,
+            stem_length_distribution: _,
+            avg_stem_duration_ms,
+            avg_hop_delay_ms,
+            node_stats,
+            frequent_fluff_nodes,
+            paths,
+            privacy_assessment,
+        } = self;
+        a.timing(avg_stem_duration_ms);
+        a.timing(avg_hop_delay_ms);
+        node_stats.anonymize(a);
+        for (node_id, _) in frequent_fluff_nodes.iter_mut() {
+            a.id_in_place(node_id);
+        }
+        paths.anonymize(a);
+        let DandelionPrivacyAssessment {
+            privacy_score: _,
+            effective_anonymity: _,
+            trivially_deanonymizable_pct: _,
+            findings,
+            recommendations,
+        } = privacy_assessment;
+        a.texts(findings);
+        a.texts(recommendations);
+    }
+}
+
+impl Anonymize for NodeDandelionStats {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            node_id,
+            stem_relay_count: _,
+            fluff_point_count: _,
+            originator_count: _,
+            avg_stem_position: _,
+        } = self;
+        a.id_in_place(node_id);
+    }
+}
+
+impl Anonymize for DandelionPath {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            tx_hash: _,
+            originator,
+            originator_ip,
+            stem_path,
+            fluff_node,
+            stem_length: _,
+            stem_duration_ms,
+            fluff_recipients: _,
+            originator_confirmed: _,
+            confidence: _,
+        } = self;
+        a.timing(stem_duration_ms);
+        a.id_in_place(originator);
+        *originator_ip = None;
+        stem_path.anonymize(a);
+        if let Some(node) = fluff_node {
+            a.id_in_place(node);
+        }
+    }
+}
+
+impl Anonymize for StemHop {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            node_id,
+            from_node_id,
+            from_ip,
+            timestamp,
+            delta_ms,
+        } = self;
+        a.timing(delta_ms);
+        a.id_in_place(node_id);
+        if let Some(from) = from_node_id {
+            a.id_in_place(from);
+        }
+        from_ip.clear();
+        a.time_in_place(timestamp);
+    }
+}
+
+// --- bandwidth ---
+
+impl Anonymize for BandwidthReport {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            report_schema_version: _,
+            total_bytes: _,
+            total_bytes_sent: _,
+            total_bytes_received: _,
+            total_messages: _,
+            avg_bytes_per_node: _,
+            median_bytes_per_node: _,
+            max_bytes_node,
+            min_bytes_node,
+            bytes_by_category: _,
+            per_node_stats,
+            bandwidth_over_time,
+        } = self;
+        a.id_in_place(&mut max_bytes_node.0);
+        a.id_in_place(&mut min_bytes_node.0);
+        per_node_stats.anonymize(a);
+        bandwidth_over_time.anonymize(a);
+    }
+}
+
+impl Anonymize for NodeBandwidthStats {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            node_id,
+            total_bytes_sent: _,
+            total_bytes_received: _,
+            total_bytes: _,
+            bytes_by_category: _,
+            top_peers,
+            message_count_sent: _,
+            message_count_received: _,
+        } = self;
+        a.id_in_place(node_id);
+        top_peers.anonymize(a);
+    }
+}
+
+impl Anonymize for PeerBandwidth {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            peer_ip,
+            bytes_sent: _,
+            bytes_received: _,
+            message_count: _,
+        } = self;
+        *peer_ip = a.peer(peer_ip);
+    }
+}
+
+impl Anonymize for BandwidthMatrix {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            report_schema_version: _,
+            total_bytes: _,
+            total_messages: _,
+            pairs,
+        } = self;
+        pairs.anonymize(a);
+    }
+}
+
+impl Anonymize for PairBandwidth {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            sender,
+            receiver,
+            bytes: _,
+            message_count: _,
+        } = self;
+        // Unresolved peers (raw IPs) get a pseudonym too
+        a.id_in_place(sender);
+        a.id_in_place(receiver);
+    }
+}
+
+impl Anonymize for BandwidthWindow {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            start,
+            end,
+            bytes_sent: _,
+            bytes_received: _,
+            message_count: _,
+        } = self;
+        a.time_in_place(start);
+        a.time_in_place(end);
+    }
+}
+
+// --- block propagation ---
+
+impl Anonymize for BlockPropagationReport {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            report_schema_version: _,
+            total_blocks: _,
+            daemon_count: _,
+            average_time_to_50_ms,
+            median_time_to_50_ms,
+            p95_time_to_50_ms,
+            average_time_to_90_ms,
+            median_time_to_90_ms,
+            p95_time_to_90_ms,
+            average_time_to_100_ms,
+            fully_propagated_blocks: _,
+            block_interval_secs: _,
+            observed_block_interval_secs,
+            median_time_to_90_interval_share: _,
+            alternative_blocks,
+            miner_attribution,
+            per_block,
+        } = self;
+        a.timing(average_time_to_50_ms);
+        a.timing(median_time_to_50_ms);
+        a.timing(p95_time_to_50_ms);
+        a.timing(average_time_to_90_ms);
+        a.timing(median_time_to_90_ms);
+        a.timing(p95_time_to_90_ms);
+        a.timing(average_time_to_100_ms);
+        a.timing(observed_block_interval_secs);
+        alternative_blocks.anonymize(a);
+        miner_attribution.anonymize(a);
+        per_block.anonymize(a);
+    }
+}
+
+impl Anonymize for BlockPropagation {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            height: _,
+            block_hash: _,
+            miner,
+            first_seen_node,
+            first_seen_time,
+            nodes_reached: _,
+            time_to_50_ms,
+            time_to_90_ms,
+            time_to_100_ms,
+            time_to_90_interval_share: _,
+            tx_count: _,
+        } = self;
+        a.timing(time_to_50_ms);
+        a.timing(time_to_90_ms);
+        a.timing(time_to_100_ms);
+        if let Some(miner) = miner {
+            a.id_in_place(miner);
+        }
+        a.id_in_place(first_seen_node);
+        a.time_in_place(first_seen_time);
+    }
+}
+
+impl Anonymize for AlternativeBlockEvent {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            height: _,
+            block_hashes: _,
+            miners,
+        } = self;
+        a.ids(miners);
+    }
+}
+
+impl Anonymize for MinerAttribution {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            attributed_blocks: _,
+            unattributed_blocks: _,
+            shares,
+            chi_square: _,
+            degrees_of_freedom: _,
+            p_value: _,
+            significant: _,
+            note,
+        } = self;
+        shares.anonymize(a);
+        *note = a.text(note);
+    }
+}
+
+impl Anonymize for MinerShare {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            miner_id,
+            weight: _,
+            expected_share: _,
+            expected_blocks: _,
+            actual_blocks: _,
+            actual_share: _,
+        } = self;
+        a.id_in_place(miner_id);
+    }
+}
+
+// --- mempool ---
+
+impl Anonymize for MempoolReport {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            report_schema_version: _,
+            window_size_sec: _,
+            daemon_count: _,
+            observed_transactions: _,
+            confirmed_transactions: _,
+            max_divergence: _,
+            max_divergence_node,
+            max_divergence_time,
+            windows,
+            per_node,
+            never_seen,
+            never_seen_by_count: _,
+        } = self;
+        if let Some(node) = max_divergence_node {
+            a.id_in_place(node);
+        }
+        if let Some(t) = max_divergence_time {
+            a.time_in_place(t);
+        }
+        windows.anonymize(a);
+        per_node.anonymize(a);
+        never_seen.anonymize(a);
+    }
+}
+
+impl Anonymize for ConflictReport {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            report_schema_version: _,
+            conflict_sets: _,
+            resolved_sets: _,
+            avg_resolution_secs,
+            max_resolution_secs,
+            split_sets: _,
+            double_relayers,
+            sets,
+        } = self;
+        a.timing(avg_resolution_secs);
+        a.timing(max_resolution_secs);
+        a.ids(double_relayers);
+        double_relayers.sort();
+        sets.anonymize(a);
+    }
+}
+
+impl Anonymize for ConflictSetAnalysis {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            conflict_id,
+            agent_id,
+            tx_hashes: _,
+            first_seen,
+            nodes_reached: _,
+            preferred: _,
+            split_by_continent: _,
+            split_by_as: _,
+            contested_links: _,
+            included_tx: _,
+            included_height: _,
+            resolution_secs,
+            double_relayers,
+            per_node,
+        } = self;
+        a.timing(resolution_secs);
+        *conflict_id = a.text(conflict_id);
+        if let Some(agent) = agent_id {
+            a.id_in_place(agent);
+        }
+        if let Some(t) = first_seen {
+            a.time_in_place(t);
+        }
+        a.ids(double_relayers);
+        double_relayers.sort();
+        per_node.anonymize(a);
+    }
+}
+
+impl Anonymize for NodeConflictView {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            node_id,
+            first_seen_order: _,
+            first_seen_times,
+            preferred_tx: _,
+            relayed: _,
+        } = self;
+        a.id_in_place(node_id);
+        for t in first_seen_times {
+            a.time_in_place(t);
+        }
+    }
+}
+
+impl Anonymize for ReadinessReport {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            report_schema_version: _,
+            targets,
+            not_ready,
+        } = self;
+        targets.anonymize(a);
+        not_ready.anonymize(a);
+    }
+}
+
+impl Anonymize for TargetReadiness {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            target: _,
+            checks: _,
+            ready: _,
+            timeouts: _,
+            refused: _,
+            bad_responses: _,
+            mean_sec,
+            median_sec,
+            max_sec,
+            slowest_agent,
+        } = self;
+        a.timing(mean_sec);
+        a.timing(median_sec);
+        a.timing(max_sec);
+        if let Some(agent) = slowest_agent {
+            a.id_in_place(agent);
+        }
+    }
+}
+
+impl Anonymize for NotReadyCheck {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            agent,
+            target: _,
+            status: _,
+            attempts: _,
+            error,
+        } = self;
+        a.id_in_place(agent);
+        if let Some(error) = error {
+            *error = a.text(error);
+        }
+    }
+}
+
+impl Anonymize for TxSuccessReport {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            report_schema_version: _,
+            source: _,
+            attempted: _,
+            succeeded: _,
+            failed: _,
+            success_rate: _,
+            failure_reasons: _,
+            median_time_to_first_success,
+            agents_without_success,
+            per_agent,
+        } = self;
+        a.timing(median_time_to_first_success);
+        a.ids(agents_without_success);
+        agents_without_success.sort();
+        per_agent.anonymize(a);
+        per_agent.sort_by(|x, y| x.agent_id.cmp(&y.agent_id));
+    }
+}
+
+impl Anonymize for AgentTxSuccess {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            agent_id,
+            attempted: _,
+            succeeded: _,
+            failed: _,
+            success_rate: _,
+            first_attempt,
+            first_success,
+            time_to_first_success: _,
+            failure_reasons: _,
+        } = self;
+        a.id_in_place(agent_id);
+        a.time_in_place(first_attempt);
+        if let Some(t) = first_success {
+            a.time_in_place(t);
+        }
+    }
+}
+
+impl Anonymize for MonitorReport {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            report_schema_version: _,
+            source: _,
+            window_size_sec: _,
+            sample_count: _,
+            poll_count: _,
+            first_sample,
+            last_sample,
+            windows,
+            per_node,
+        } = self;
+        for t in [first_sample, last_sample].into_iter().flatten() {
+            a.time_in_place(t);
+        }
+        windows.anonymize(a);
+        per_node.anonymize(a);
+    }
+}
+
+impl Anonymize for MonitorWindow {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            start,
+            end,
+            samples: _,
+            avg_height_lag: _,
+            max_height_lag: _,
+            avg_mempool_txs: _,
+            avg_peers: _,
+        } = self;
+        a.time_in_place(start);
+        a.time_in_place(end);
+    }
+}
+
+impl Anonymize for MonitorNodeSummary {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            node_id,
+            samples: _,
+            final_height: _,
+            avg_height_lag: _,
+            max_height_lag: _,
+            avg_mempool_txs: _,
+            avg_peers: _,
+            bytes_in: _,
+            bytes_out: _,
+        } = self;
+        a.id_in_place(node_id);
+    }
+}
+
+impl Anonymize for MempoolWindow {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            start,
+            end,
+            median_mempool_size: _,
+            reference_size: _,
+            avg_divergence: _,
+            max_divergence: _,
+            max_divergence_node,
+            diverged_nodes: _,
+        } = self;
+        a.time_in_place(start);
+        a.time_in_place(end);
+        if let Some(node) = max_divergence_node {
+            a.id_in_place(node);
+        }
+    }
+}
+
+impl Anonymize for NodeMempoolDivergence {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            node_id,
+            avg_divergence: _,
+            max_divergence: _,
+            diverged_windows: _,
+            longest_divergence_sec,
+        } = self;
+        a.timing(longest_divergence_sec);
+        a.id_in_place(node_id);
+    }
+}
+
+impl Anonymize for UnseenTransaction {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            tx_hash: _,
+            block_height: _,
+            missing_nodes,
+        } = self;
+        a.ids(missing_nodes);
+    }
+}
+
+// --- trace ---
+
+impl Anonymize for TxTrace {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            report_schema_version: _,
+            tx_hash: _,
+            sender,
+            recipient,
+            created_at,
+            daemon_count: _,
+            nodes_reached: _,
+            stem_path,
+            block_height: _,
+            block_time,
+            events,
+        } = self;
+        for id in [sender, recipient].into_iter().flatten() {
+            a.id_in_place(id);
+        }
+        for t in [created_at, block_time].into_iter().flatten() {
+            a.time_in_place(t);
+        }
+        if let Some(path) = stem_path {
+            path.anonymize(a);
+        }
+        events.anonymize(a);
+    }
+}
+
+impl Anonymize for TraceEvent {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            timestamp,
+            offset_ms,
+            kind: _,
+            node_id,
+            peer,
+            detail,
+        } = self;
+        a.timing(offset_ms);
+        a.time_in_place(timestamp);
+        a.id_in_place(node_id);
+        // Unresolved peers are raw IPs `text` would leave as-is, so they
+        // get a pseudonym like any other id
+        if let Some(peer) = peer {
+            a.id_in_place(peer);
+        }
+        if let Some(detail) = detail {
+            *detail = a.text(detail);
+        }
+    }
+}
+
+// --- network graph ---
+
+impl Anonymize for NetworkGraphReport {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            report_schema_version: _,
+            total_daemon_nodes: _,
+            total_unique_connections: _,
+            analysis_duration_sec: _,
+            snapshots,
+            final_state,
+            degree_distribution: _,
+            churn_stats,
+            validation,
+            intended_comparison,
+            reachability,
+        } = self;
+        snapshots.anonymize(a);
+        final_state.anonymize(a);
+        a.timing(churn_stats);
+        intended_comparison.anonymize(a);
+        if let Some(r) = reachability {
+            a.ids(&mut r.unreachable_with_inbound);
+        }
+        let NetworkValidation {
+            expected_max_outbound: _,
+            actual_max_outbound: _,
+            outbound_valid: _,
+            nodes_exceeding_outbound,
+            findings,
+        } = validation;
+        a.ids(nodes_exceeding_outbound);
+        a.texts(findings);
+    }
+}
+
+impl Anonymize for IntendedComparison {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            intended_edges: _,
+            observed_edges: _,
+            matched_edges: _,
+            missing_edges,
+            extra_edges,
+            per_node,
+        } = self;
+        for (x, y) in missing_edges.iter_mut().chain(extra_edges.iter_mut()) {
+            a.id_in_place(x);
+            a.id_in_place(y);
+        }
+        *per_node = std::mem::take(per_node)
+            .into_iter()
+            .map(|(id, mut diff)| {
+                let NodeEdgeDiff { missing, extra } = &mut diff;
+                a.ids(missing);
+                a.ids(extra);
+                (a.id(&id), diff)
+            })
+            .collect();
+    }
+}
+
+impl Anonymize for NetworkSnapshot {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            timestamp,
+            time_label: _,
+            total_connections: _,
+            node_degrees,
+            avg_outbound: _,
+            avg_inbound: _,
+            isolated_nodes,
+        } = self;
+        a.time_in_place(timestamp);
+        a.keys(node_degrees);
+        for degree in node_degrees.values_mut() {
+            let NodeDegree {
+                node_id,
+                outbound: _,
+                inbound: _,
+                total: _,
+            } = degree;
+            a.id_in_place(node_id);
+        }
+        a.ids(isolated_nodes);
+    }
+}
+
+// --- upgrade analysis ---
+
+impl Anonymize for UpgradeAnalysisReport {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            report_schema_version: _,
+            metadata,
+            upgrade_info,
+            time_series,
+            pre_upgrade_summary,
+            transition_summary,
+            post_upgrade_summary,
+            changes,
+            assessment,
+        } = self;
+        let UpgradeAnalysisMetadata {
+            analysis_timestamp: _,
+            simulation_data_dir,
+            simulation_start,
+            simulation_end,
+            window_size_sec: _,
+            total_windows: _,
+            total_nodes: _,
+            total_transactions: _,
+            spy_visibility_levels: _,
+            spy_trials_per_level: _,
+            fluff_gap_thresholds_ms: _,
+            coverage_percentiles: _,
+            anonymization,
+        } = metadata;
+        *simulation_data_dir = "(anonymized)".to_string();
+        a.time_in_place(simulation_start);
+        a.time_in_place(simulation_end);
+        *anonymization = Some(a.info().clone());
+
+        upgrade_info.anonymize(a);
+        time_series.anonymize(a);
+        pre_upgrade_summary.anonymize(a);
+        transition_summary.anonymize(a);
+        post_upgrade_summary.anonymize(a);
+        changes.anonymize(a);
+        let UpgradeAssessment {
+            verdict: _,
+            metrics_improved: _,
+            metrics_degraded: _,
+            metrics_unchanged: _,
+            findings,
+            concerns,
+            recommendations,
+        } = assessment;
+        a.texts(findings);
+        a.texts(concerns);
+        a.texts(recommendations);
+    }
+}
+
+impl Anonymize for UpgradeManifest {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            pre_upgrade_version: _,
+            post_upgrade_version: _,
+            node_upgrades,
+            upgrade_start,
+            upgrade_end,
+        } = self;
+        for event in node_upgrades.iter_mut() {
+            let NodeUpgradeEvent {
+                node_id,
+                timestamp,
+                version: _,
+            } = event;
+            a.id_in_place(node_id);
+            a.time_in_place(timestamp);
+        }
+        if let Some(t) = upgrade_start {
+            a.time_in_place(t);
+        }
+        if let Some(t) = upgrade_end {
+            a.time_in_place(t);
+        }
+    }
+}
+
+impl Anonymize for AggregatedMetrics {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            period_label: _,
+            start,
+            end,
+            window_count: _,
+            total_txs: _,
+            mean_spy_accuracy_by_visibility: _,
+            mean_propagation_ms,
+            mean_coverage_ms_by_percentile,
+            mean_peer_count: _,
+            mean_gini: _,
+            mean_stem_length: _,
+            mean_stem_length_by_gap_threshold: _,
+            std_spy_accuracy_by_visibility: _,
+            std_propagation_ms,
+            std_coverage_ms_by_percentile,
+            std_peer_count: _,
+            std_gini: _,
+            std_stem_length: _,
+            std_stem_length_by_gap_threshold: _,
+            total_bytes_sent: _,
+            total_bytes_received: _,
+            total_bandwidth: _,
+            mean_bandwidth_per_window: _,
+            std_bandwidth_per_window: _,
+            windows,
+        } = self;
+        a.timing(mean_propagation_ms);
+        a.timing(mean_coverage_ms_by_percentile);
+        a.timing(std_propagation_ms);
+        a.timing(std_coverage_ms_by_percentile);
+        a.time_in_place(start);
+        a.time_in_place(end);
+        windows.anonymize(a);
+    }
+}
+
+impl Anonymize for WindowedMetrics {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            window,
+            tx_count: _,
+            observation_count: _,
+            spy_accuracy_by_visibility: _,
+            spy_analyzable_txs: _,
+            avg_propagation_ms,
+            median_propagation_ms,
+            p95_propagation_ms,
+            coverage_ms_by_percentile,
+            avg_peer_count: _,
+            gini_coefficient: _,
+            avg_stem_length: _,
+            stem_length_by_gap_threshold: _,
+            paths_reconstructed: _,
+            bytes_sent: _,
+            bytes_received: _,
+            total_bandwidth: _,
+            bandwidth_message_count: _,
+            blocks_seen: _,
+            avg_block_propagation_ms,
+            block_rate_vs_target: _,
+            conflict_count: _,
+            tx_success_rate: _,
+            avg_height_lag: _,
+            avg_mempool_txs: _,
+        } = self;
+        a.timing(avg_propagation_ms);
+        a.timing(median_propagation_ms);
+        a.timing(p95_propagation_ms);
+        a.timing(coverage_ms_by_percentile);
+        a.timing(avg_block_propagation_ms);
+        let TimeWindow { start, end, label } = window;
+        a.time_in_place(start);
+        a.time_in_place(end);
+        if let Some(label) = label {
+            *label = a.text(label);
+        }
+    }
+}
+
+impl Anonymize for MetricChange {
+    fn anonymize(&mut self, a: &Anonymizer) {
+        let Self {
+            metric_name: _,
+            pre_value: _,
+            post_value: _,
+            absolute_change: _,
+            percent_change: _,
+            p_value: _,
+            statistically_significant: _,
+            interpretation,
+            impact: _,
+            confidence_interval: _,
+        } = self;
+        *interpretation = a.text(interpretation);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::REPORT_SCHEMA_VERSION;
+    use crate::utils::duration::SimDuration;
+
+    fn agents() -> Vec<AnalysisAgentInfo> {
+        ["user-1", "user-10", "miner-001"]
+            .iter()
+            .enumerate()
+            .map(|(i, id)| AnalysisAgentInfo {
+                id: id.to_string(),
+                ip_addr: format!("72.0.{}.10", i),
+                rpc_port: 18081,
+                script_type: String::new(),
+                wallet_address: None,
+                network_node_id: None,
+                continent: None,
+                as_number: None,
+                role: None,
+                reachable: None,
+                out_peers: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pseudonyms_are_stable_and_seeded() {
+        let a = Anonymizer::new(7, 1.0, 0.0, &agents());
+        let b = Anonymizer::new(7, 1.0, 0.0, &agents());
+        let c = Anonymizer::new(8, 1.0, 0.0, &agents());
+        assert_eq!(a.id("user-1"), b.id("user-1"));
+        assert_ne!(a.id("user-1"), a.id("user-10"));
+        assert_ne!(a.id("user-1"), c.id("user-1"));
+        assert!(!a.id("user-1").contains("user"));
+    }
+
+    #[test]
+    fn text_scrubs_longest_ids_first() {
+        let a = Anonymizer::new(0, 1.0, 0.0, &agents());
+        let scrubbed = a.text("user-10 relayed via 72.0.0.10 before user-1");
+        assert_eq!(
+            scrubbed,
+            format!(
+                "{} relayed via [ip] before {}",
+                a.id("user-10"),
+                a.id("user-1")
+            )
+        );
+    }
+
+    #[test]
+    fn trace_pseudonymizes_unresolved_peers() {
+        let a = Anonymizer::new(0, 1.0, 0.0, &agents());
+        let mut event = TraceEvent {
+            timestamp: 1.0,
+            offset_ms: 0.0,
+            kind: TraceEventKind::FirstSeen,
+            node_id: "user-1".to_string(),
+            peer: Some("203.0.113.7".to_string()),
+            detail: Some("Inbound connection".to_string()),
+        };
+        event.anonymize(&a);
+        assert_eq!(event.peer, Some(a.id("203.0.113.7")));
+        assert_eq!(event.detail.as_deref(), Some("Inbound connection"));
+    }
+
+    #[test]
+    fn time_is_floored_to_granularity() {
+        let a = Anonymizer::new(0, 10.0, 0.0, &[]);
+        assert_eq!(a.time(946_684_807.3), 946_684_800.0);
+        let exact = Anonymizer::new(0, 0.0, 0.0, &[]);
+        assert_eq!(exact.time(12.5), 12.5);
+    }
+
+    #[test]
+    fn timing_noise_is_seeded_and_bounded() {
+        let draws = |seed| {
+            let a = Anonymizer::new(seed, 1.0, 0.05, &[]);
+            (0..1000).map(|_| a.noise(200.0)).collect::<Vec<_>>()
+        };
+        let noisy = draws(7);
+        assert_eq!(noisy, draws(7));
+        assert_ne!(noisy, draws(8));
+        assert!(noisy.iter().all(|v| (190.0..=210.0).contains(v)));
+        assert!(noisy.iter().any(|v| *v < 195.0) && noisy.iter().any(|v| *v > 205.0));
+
+        let exact = Anonymizer::new(7, 1.0, 0.0, &[]);
+        assert_eq!(exact.noise(200.0), 200.0);
+        let mut metrics = vec![Some(1.5), None];
+        exact.timing(&mut metrics);
+        assert_eq!(metrics, [Some(1.5), None]);
+    }
+
+    #[test]
+    fn full_report_drops_ips_and_records_parameters() {
+        let a = Anonymizer::new(3, 5.0, 0.1, &agents());
+        let mut report = FullAnalysisReport {
+            metadata: AnalysisMetadata {
+                report_schema_version: REPORT_SCHEMA_VERSION,
+                analysis_timestamp: String::new(),
+                simulation_data_dir: "/home/me/run".to_string(),
+                total_nodes: 3,
+                total_transactions: 1,
+                total_blocks: 0,
+                anonymization: None,
+                manifest: Some(SimulationManifest {
+                    generator_version: "0.1.0".to_string(),
+                    schema_version: 2,
+                    generated_at: String::new(),
+                    config: Some(crate::manifest::HashedFile {
+                        path: "/home/me/run.yaml".to_string(),
+                        sha256: "ab12".to_string(),
+                    }),
+                    gml: None,
+                    simulation_seed: 1,
+                    stop_time: SimDuration::from_secs(3600),
+                    chain: Default::default(),
+                    block_interval: 120,
+                    binary_versions: vec![crate::manifest::BinaryVersion {
+                        path: "/home/me/bin/monerod".to_string(),
+                        version: Some("Monero 'Fluorine Fermi' (v0.18.3.4-release)".to_string()),
+                    }],
+                    resources: None,
+                    miner_placement: Default::default(),
+                    agent_distribution: None,
+                    network_events: Vec::new(),
+                    pcap_capture: Some(crate::manifest::PcapCapture {
+                        agents: vec!["user-1".to_string()],
+                        capture_dir: Some("/home/me/pcaps".to_string()),
+                    }),
+                    resolved_config: serde_json::json!({ "agents": ["user-1"] }),
+                }),
+            },
+            spy_node_analysis: Some(SpyNodeReport {
+                total_transactions: 1,
+                analyzable_transactions: 1,
+                inference_accuracy: 1.0,
+                timing_spread_distribution: TimingDistribution {
+                    high_vulnerability_count: 1,
+                    moderate_vulnerability_count: 0,
+                    low_vulnerability_count: 0,
+                },
+                vulnerable_senders: Vec::new(),
+                per_tx_analysis: vec![SpyNodeTxAnalysis {
+                    tx_hash: "ab".to_string(),
+                    true_sender: "user-1".to_string(),
+                    true_sender_ip: Some("72.0.0.10".to_string()),
+                    first_seen_by: vec![FirstSeenEntry {
+                        node_id: "miner-001".to_string(),
+                        timestamp: 103.0,
+                        delta_from_first_ms: 0.0,
+                        source_ip: "72.0.0.10".to_string(),
+                    }],
+                    correlation_confidence: 0.9,
+                    timing_spread_ms: 1.0,
+                    inferred_originator_ip: Some("72.0.0.10".to_string()),
+                    inference_correct: true,
+                    anonymity_set_size: None,
+                }],
+                observers: Vec::new(),
+                per_spy: Vec::new(),
+                avg_anonymity_set_size: None,
+            }),
+            propagation_analysis: None,
+            resilience_analysis: None,
+        };
+
+        report.anonymize(&a);
+
+        let json = serde_json::to_string(&report).unwrap();
+        for leaked in ["user-1", "miner-001", "72.0.0.10", "/home/me"] {
+            assert!(!json.contains(leaked), "{} leaked into {}", leaked, json);
+        }
+        let info = report.metadata.anonymization.as_ref().unwrap();
+        assert_eq!(info.pseudonym_seed, 3);
+        assert_eq!(info.timing_noise, 0.1);
+        let manifest = report.metadata.manifest.as_ref().unwrap();
+        assert_eq!(manifest.config.as_ref().unwrap().sha256, "ab12");
+        let entry = &report.spy_node_analysis.as_ref().unwrap().per_tx_analysis[0];
+        assert_eq!(entry.first_seen_by[0].timestamp, 100.0);
+    }
+}
@@ -3,8 +3,10 @@
 //! Provides functions to analyze network bandwidth usage from parsed log data,
 //! including per-node statistics, category breakdowns, and time series.
 
-use std::collections::HashMap;
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet};
 
+use super::schema::REPORT_SCHEMA_VERSION;
 use super::types::*;
 
 /// Map command IDs to human-readable names
@@ -40,10 +42,10 @@ fn calculate_node_stats(
     for event in events {
         if event.is_sent {
             total_bytes_sent += event.bytes;
-            message_count_sent += 1;
+            message_count_sent += event.messages;
         } else {
             total_bytes_received += event.bytes;
-            message_count_received += 1;
+            message_count_received += event.messages;
         }
 
         // Aggregate by category
@@ -61,7 +63,7 @@ fn calculate_node_stats(
         } else {
             cat.bytes_received += event.bytes;
         }
-        cat.message_count += 1;
+        cat.message_count += event.messages;
 
         // Aggregate by peer
         let peer = by_peer
@@ -77,7 +79,7 @@ fn calculate_node_stats(
         } else {
             peer.bytes_received += event.bytes;
         }
-        peer.message_count += 1;
+        peer.message_count += event.messages;
     }
 
     // Get top peers by total bytes
@@ -105,17 +107,32 @@ fn calculate_node_stats(
 pub fn analyze_bandwidth(
     log_data: &HashMap<String, NodeLogData>,
     top_peers_per_node: usize,
+) -> BandwidthReport {
+    analyze_bandwidth_iter(log_data.values(), top_peers_per_node)
+}
+
+/// [`analyze_bandwidth`] over any sequence of nodes, such as
+/// `LogIndex::nodes`. Each node is reduced to its statistics before the
+/// next one is read.
+pub fn analyze_bandwidth_iter<N: Borrow<NodeLogData>>(
+    nodes: impl IntoIterator<Item = N>,
+    top_peers_per_node: usize,
 ) -> BandwidthReport {
     let mut per_node_stats: Vec<NodeBandwidthStats> = Vec::new();
     let mut network_by_category: HashMap<String, CategoryBandwidth> = HashMap::new();
 
     // Calculate per-node stats
-    for (node_id, node_data) in log_data {
+    for node_data in nodes {
+        let node_data = node_data.borrow();
         if node_data.bandwidth_events.is_empty() {
             continue;
         }
 
-        let stats = calculate_node_stats(node_id, &node_data.bandwidth_events, top_peers_per_node);
+        let stats = calculate_node_stats(
+            &node_data.node_id,
+            &node_data.bandwidth_events,
+            top_peers_per_node,
+        );
 
         // Aggregate categories into network-wide totals
         for (cat_id, cat_stats) in &stats.bytes_by_category {
@@ -181,6 +198,7 @@ pub fn analyze_bandwidth(
         .unwrap_or_else(|| ("".to_string(), 0));
 
     BandwidthReport {
+        report_schema_version: REPORT_SCHEMA_VERSION,
         total_bytes,
         total_bytes_sent,
         total_bytes_received,
@@ -195,34 +213,119 @@ pub fn analyze_bandwidth(
     }
 }
 
-/// Calculate bandwidth over time windows
-pub fn bandwidth_time_series(
+/// Node-to-node traffic, with peer IPs resolved to agent ids
+pub fn bandwidth_matrix(
     log_data: &HashMap<String, NodeLogData>,
-    window_size_sec: f64,
-) -> Vec<BandwidthWindow> {
-    // Collect all bandwidth events with timestamps
-    let mut all_events: Vec<&BandwidthEvent> = Vec::new();
-    for node_data in log_data.values() {
+    agents: &[AnalysisAgentInfo],
+) -> BandwidthMatrix {
+    bandwidth_matrix_iter(log_data.values(), agents)
+}
+
+/// [`bandwidth_matrix`] over any sequence of nodes, such as
+/// `LogIndex::nodes`.
+///
+/// Both ends log every message, so each transfer is counted once, from the
+/// sender's log. A receiver's log only fills in for senders without
+/// bandwidth logs of their own (unlogged agents or unknown IPs).
+pub fn bandwidth_matrix_iter<N: Borrow<NodeLogData>>(
+    nodes: impl IntoIterator<Item = N>,
+    agents: &[AnalysisAgentInfo],
+) -> BandwidthMatrix {
+    let ip_to_agent: HashMap<&str, &str> = agents
+        .iter()
+        .map(|a| (a.ip_addr.as_str(), a.id.as_str()))
+        .collect();
+    let resolve = |ip: &str| {
+        ip_to_agent
+            .get(ip)
+            .map_or_else(|| ip.to_string(), |id| id.to_string())
+    };
+
+    // (sender, receiver) -> (bytes, messages)
+    let mut sent: HashMap<(String, String), (u64, u64)> = HashMap::new();
+    let mut received: HashMap<(String, String), (u64, u64)> = HashMap::new();
+    let mut logged: HashSet<String> = HashSet::new();
+    for node_data in nodes {
+        let node_data = node_data.borrow();
+        if node_data.bandwidth_events.is_empty() {
+            continue;
+        }
+        logged.insert(node_data.node_id.clone());
         for event in &node_data.bandwidth_events {
-            all_events.push(event);
+            let peer = resolve(&event.peer_ip);
+            let (map, key) = if event.is_sent {
+                (&mut sent, (node_data.node_id.clone(), peer))
+            } else {
+                (&mut received, (peer, node_data.node_id.clone()))
+            };
+            let entry = map.entry(key).or_default();
+            entry.0 += event.bytes;
+            entry.1 += event.messages;
+        }
+    }
+    for (key, (bytes, messages)) in received {
+        if !logged.contains(&key.0) {
+            let entry = sent.entry(key).or_default();
+            entry.0 += bytes;
+            entry.1 += messages;
         }
     }
 
-    if all_events.is_empty() {
-        return Vec::new();
+    let mut pairs: Vec<PairBandwidth> = sent
+        .into_iter()
+        .map(
+            |((sender, receiver), (bytes, message_count))| PairBandwidth {
+                sender,
+                receiver,
+                bytes,
+                message_count,
+            },
+        )
+        .collect();
+    pairs.sort_by(|a, b| {
+        b.bytes
+            .cmp(&a.bytes)
+            .then_with(|| a.sender.cmp(&b.sender))
+            .then_with(|| a.receiver.cmp(&b.receiver))
+    });
+
+    BandwidthMatrix {
+        report_schema_version: REPORT_SCHEMA_VERSION,
+        total_bytes: pairs.iter().map(|p| p.bytes).sum(),
+        total_messages: pairs.iter().map(|p| p.message_count).sum(),
+        pairs,
     }
+}
+
+/// Calculate bandwidth over time windows
+pub fn bandwidth_time_series(
+    log_data: &HashMap<String, NodeLogData>,
+    window_size_sec: f64,
+) -> Vec<BandwidthWindow> {
+    bandwidth_time_series_iter(log_data.values(), window_size_sec)
+}
 
+/// [`bandwidth_time_series`] over any sequence of nodes. The sequence is
+/// walked twice, once for the time range and once to fill the windows, so
+/// a `LogIndex::nodes` pass parses every node twice.
+pub fn bandwidth_time_series_iter<I>(nodes: I, window_size_sec: f64) -> Vec<BandwidthWindow>
+where
+    I: IntoIterator + Clone,
+    I::Item: Borrow<NodeLogData>,
+{
     // Find time range
-    let min_time = all_events
-        .iter()
-        .map(|e| e.timestamp)
-        .fold(f64::MAX, f64::min);
-    let max_time = all_events
-        .iter()
-        .map(|e| e.timestamp)
-        .fold(f64::MIN, f64::max);
+    let mut min_time = f64::MAX;
+    let mut max_time = f64::MIN;
+    let mut any_events = false;
+    for node_data in nodes.clone() {
+        for event in &node_data.borrow().bandwidth_events {
+            min_time = min_time.min(event.timestamp);
+            max_time = max_time.max(event.timestamp);
+            any_events = true;
+        }
+    }
 
-    if min_time >= max_time {
+    if !any_events || min_time >= max_time {
         return Vec::new();
     }
 
@@ -243,17 +346,19 @@ pub fn bandwidth_time_series(
     }
 
     // Aggregate events into windows
-    for event in all_events {
-        // Find which window this event belongs to
-        let window_idx = ((event.timestamp - min_time) / window_size_sec) as usize;
-        if window_idx < windows.len() {
-            let window = &mut windows[window_idx];
-            if event.is_sent {
-                window.bytes_sent += event.bytes;
-            } else {
-                window.bytes_received += event.bytes;
+    for node_data in nodes {
+        for event in &node_data.borrow().bandwidth_events {
+            // Find which window this event belongs to
+            let window_idx = ((event.timestamp - min_time) / window_size_sec) as usize;
+            if window_idx < windows.len() {
+                let window = &mut windows[window_idx];
+                if event.is_sent {
+                    window.bytes_sent += event.bytes;
+                } else {
+                    window.bytes_received += event.bytes;
+                }
+                window.message_count += event.messages;
             }
-            window.message_count += 1;
         }
     }
 
@@ -304,6 +409,7 @@ mod tests {
                 is_sent: true,
                 command_category: "command-1001".to_string(),
                 initiated_by_us: true,
+                messages: 1,
             },
             BandwidthEvent {
                 timestamp: 101.0,
@@ -314,6 +420,7 @@ mod tests {
                 is_sent: false,
                 command_category: "command-1001".to_string(),
                 initiated_by_us: false,
+                messages: 1,
             },
         ];
 
@@ -324,4 +431,62 @@ mod tests {
         assert_eq!(stats.message_count_sent, 1);
         assert_eq!(stats.message_count_received, 1);
     }
+
+    #[test]
+    fn matrix_counts_each_transfer_once_by_agent() {
+        use crate::analysis::testutil::SyntheticRun;
+
+        let mut run = SyntheticRun::new();
+        for id in ["a", "b", "c"] {
+            run.agent(id, "agents.regular_user");
+        }
+        run.traffic("a", "b", 1.0, 1000, "command-2008")
+            .traffic("a", "b", 2.0, 200, "command-2008")
+            .traffic("b", "a", 3.0, 300, "command-1003");
+        // c has no logs; only b's receipt shows its traffic, and an unknown
+        // IP stays an IP
+        for (peer_ip, bytes) in [(run.ip("c"), 70), ("203.0.113.9".to_string(), 5)] {
+            let event = BandwidthEvent {
+                timestamp: 4.0,
+                peer_ip,
+                peer_port: 18080,
+                direction: ConnectionDirection::Inbound,
+                bytes,
+                is_sent: false,
+                command_category: "command-2008".to_string(),
+                initiated_by_us: false,
+                messages: 1,
+            };
+            run.log_data
+                .get_mut("b")
+                .expect("b has logs")
+                .bandwidth_events
+                .push(event);
+        }
+
+        let matrix = bandwidth_matrix(&run.log_map(), &run.agents);
+        let pairs: Vec<(&str, &str, u64, u64)> = matrix
+            .pairs
+            .iter()
+            .map(|p| {
+                (
+                    p.sender.as_str(),
+                    p.receiver.as_str(),
+                    p.bytes,
+                    p.message_count,
+                )
+            })
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("a", "b", 1200, 2),
+                ("b", "a", 300, 1),
+                ("c", "b", 70, 1),
+                ("203.0.113.9", "b", 5, 1),
+            ]
+        );
+        assert_eq!(matrix.total_bytes, 1575);
+        assert_eq!(matrix.total_messages, 5);
+    }
 }
@@ -0,0 +1,384 @@
+//! Block propagation analysis.
+//!
+//! Follows each block height from the first daemon that logged it until
+//! every daemon has it, flags heights where daemons saw competing blocks,
+//! and checks which miners found the blocks against their `miners.json`
+//! hashrate weights. Propagation delays are also given as a share of the
+//! target block interval (the manifest's `block_interval`), so runs with
+//! different intervals compare.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use super::schema::REPORT_SCHEMA_VERSION;
+use super::stats::{chi_square_p_value, mean, median, percentile};
+use super::types::*;
+
+/// Expected block counts below this make the chi-square approximation rough
+const MIN_EXPECTED_BLOCKS: f64 = 5.0;
+
+/// Everything the daemons logged about one height
+#[derive(Default)]
+struct HeightSightings<'a> {
+    /// Each daemon's earliest sighting
+    first_by_node: HashMap<&'a str, SimTime>,
+    /// Daemons that received each (non-empty) hash
+    nodes_by_hash: BTreeMap<&'a str, BTreeSet<&'a str>>,
+    /// (time, daemon) of every locally mined block
+    mined: Vec<(SimTime, &'a str)>,
+}
+
+/// Analyze how blocks spread through the daemons. `miner_weights` maps
+/// miner agent ids to their `miners.json` weight; when empty, no miner
+/// attribution check is made. `block_interval_secs` is the target interval
+/// the simulation ran with.
+pub fn analyze_block_propagation(
+    blocks: &[BlockInfo],
+    log_data: &HashMap<String, NodeLogData>,
+    miner_weights: &HashMap<String, f64>,
+    block_interval_secs: f64,
+) -> BlockPropagationReport {
+    let daemon_count = log_data.len();
+
+    let mut heights: BTreeMap<u64, HeightSightings> = BTreeMap::new();
+    for node_data in log_data.values() {
+        for obs in &node_data.block_observations {
+            let sightings = heights.entry(obs.height).or_default();
+            sightings
+                .first_by_node
+                .entry(&obs.node_id)
+                .and_modify(|t| {
+                    if obs.timestamp < *t {
+                        *t = obs.timestamp;
+                    }
+                })
+                .or_insert(obs.timestamp);
+            if !obs.block_hash.is_empty() {
+                sightings
+                    .nodes_by_hash
+                    .entry(&obs.block_hash)
+                    .or_default()
+                    .insert(&obs.node_id);
+            }
+            if obs.is_local {
+                sightings.mined.push((obs.timestamp, &obs.node_id));
+            }
+        }
+    }
+
+    let tx_counts: HashMap<u64, usize> = blocks.iter().map(|b| (b.height, b.tx_count)).collect();
+
+    let mut per_block = Vec::with_capacity(heights.len());
+    let mut alternative_blocks = Vec::new();
+    for (height, sightings) in &heights {
+        per_block.push(block_propagation(
+            *height,
+            sightings,
+            daemon_count,
+            tx_counts.get(height).copied(),
+            block_interval_secs,
+        ));
+
+        let miners: BTreeSet<&str> = sightings.mined.iter().map(|(_, node)| *node).collect();
+        if sightings.nodes_by_hash.len() > 1 || miners.len() > 1 {
+            alternative_blocks.push(AlternativeBlockEvent {
+                height: *height,
+                block_hashes: sightings
+                    .nodes_by_hash
+                    .keys()
+                    .map(|h| h.to_string())
+                    .collect(),
+                miners: miners.into_iter().map(String::from).collect(),
+            });
+        }
+    }
+
+    let to_50: Vec<f64> = per_block.iter().filter_map(|b| b.time_to_50_ms).collect();
+    let to_90: Vec<f64> = per_block.iter().filter_map(|b| b.time_to_90_ms).collect();
+    let to_100: Vec<f64> = per_block.iter().filter_map(|b| b.time_to_100_ms).collect();
+
+    let gaps: Vec<f64> = per_block
+        .windows(2)
+        .filter(|pair| pair[1].height == pair[0].height + 1)
+        .map(|pair| pair[1].first_seen_time - pair[0].first_seen_time)
+        .collect();
+    let median_to_90 = median(&to_90);
+
+    let miner_attribution = if miner_weights.is_empty() {
+        None
+    } else {
+        Some(attribute_miners(&per_block, miner_weights))
+    };
+
+    BlockPropagationReport {
+        report_schema_version: REPORT_SCHEMA_VERSION,
+        total_blocks: per_block.len(),
+        daemon_count,
+        average_time_to_50_ms: mean(&to_50),
+        median_time_to_50_ms: median(&to_50),
+        p95_time_to_50_ms: percentile(&to_50, 95.0),
+        average_time_to_90_ms: mean(&to_90),
+        median_time_to_90_ms: median_to_90,
+        p95_time_to_90_ms: percentile(&to_90, 95.0),
+        average_time_to_100_ms: mean(&to_100),
+        fully_propagated_blocks: to_100.len(),
+        block_interval_secs,
+        observed_block_interval_secs: (!gaps.is_empty()).then(|| mean(&gaps)),
+        median_time_to_90_interval_share: (!to_90.is_empty())
+            .then(|| median_to_90 / (block_interval_secs * 1000.0)),
+        alternative_blocks,
+        miner_attribution,
+        per_block,
+    }
+}
+
+fn block_propagation(
+    height: u64,
+    sightings: &HeightSightings,
+    daemon_count: usize,
+    tx_count: Option<usize>,
+    block_interval_secs: f64,
+) -> BlockPropagation {
+    let mut arrivals: Vec<(SimTime, &str)> = sightings
+        .first_by_node
+        .iter()
+        .map(|(node, t)| (*t, *node))
+        .collect();
+    arrivals.sort_by(|a, b| {
+        a.0.partial_cmp(&b.0)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.1.cmp(b.1))
+    });
+    let (first_seen_time, first_seen_node) = arrivals[0];
+
+    // Delay until `fraction` of all daemons (rounded up) have the block
+    let time_to = |fraction: f64| {
+        let needed = ((fraction * daemon_count as f64).ceil() as usize).max(1);
+        arrivals
+            .get(needed - 1)
+            .map(|(t, _)| (t - first_seen_time) * 1000.0)
+    };
+
+    // The hash most daemons received; ties go to the smaller hash
+    let block_hash = sightings
+        .nodes_by_hash
+        .iter()
+        .max_by(|a, b| a.1.len().cmp(&b.1.len()).then_with(|| b.0.cmp(a.0)))
+        .map(|(hash, _)| hash.to_string());
+
+    let miner = sightings
+        .mined
+        .iter()
+        .min_by(|a, b| {
+            a.0.partial_cmp(&b.0)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.1.cmp(b.1))
+        })
+        .map(|(_, node)| node.to_string());
+
+    BlockPropagation {
+        height,
+        block_hash,
+        miner,
+        first_seen_node: first_seen_node.to_string(),
+        first_seen_time,
+        nodes_reached: arrivals.len(),
+        time_to_50_ms: time_to(0.5),
+        time_to_90_ms: time_to(0.9),
+        time_to_100_ms: time_to(1.0),
+        time_to_90_interval_share: time_to(0.9).map(|ms| ms / (block_interval_secs * 1000.0)),
+        tx_count,
+    }
+}
+
+/// Compare each weighted miner's share of the attributed blocks with its
+/// share of the total weight (Pearson chi-square goodness of fit)
+fn attribute_miners(
+    per_block: &[BlockPropagation],
+    miner_weights: &HashMap<String, f64>,
+) -> MinerAttribution {
+    let mut actual: HashMap<&str, usize> = HashMap::new();
+    for block in per_block {
+        if let Some(miner) = block.miner.as_deref() {
+            if miner_weights.contains_key(miner) {
+                *actual.entry(miner).or_default() += 1;
+            }
+        }
+    }
+    let attributed_blocks: usize = actual.values().sum();
+    let total_weight: f64 = miner_weights.values().sum();
+
+    let mut miners: Vec<(&String, &f64)> = miner_weights.iter().collect();
+    miners.sort_by_key(|(miner_id, _)| *miner_id);
+    let shares: Vec<MinerShare> = miners
+        .into_iter()
+        .map(|(miner_id, &weight)| {
+            let expected_share = if total_weight > 0.0 {
+                weight / total_weight
+            } else {
+                0.0
+            };
+            let actual_blocks = actual.get(miner_id.as_str()).copied().unwrap_or(0);
+            MinerShare {
+                miner_id: miner_id.clone(),
+                weight,
+                expected_share,
+                expected_blocks: expected_share * attributed_blocks as f64,
+                actual_blocks,
+                actual_share: if attributed_blocks > 0 {
+                    actual_blocks as f64 / attributed_blocks as f64
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect();
+
+    let tested: Vec<&MinerShare> = shares.iter().filter(|s| s.expected_blocks > 0.0).collect();
+    let degrees_of_freedom = tested.len().saturating_sub(1);
+    let chi_square = (degrees_of_freedom > 0).then(|| {
+        tested
+            .iter()
+            .map(|s| (s.actual_blocks as f64 - s.expected_blocks).powi(2) / s.expected_blocks)
+            .sum::<f64>()
+    });
+    let p_value = chi_square.and_then(|x| chi_square_p_value(x, degrees_of_freedom));
+    let significant = p_value.is_some_and(|p| p < 0.05);
+
+    let note = match (chi_square, p_value) {
+        (Some(x), Some(p)) => {
+            let mut note = format!(
+                "chi-square {:.2} with {} df, p = {:.3}: block shares {} the miners.json weights",
+                x,
+                degrees_of_freedom,
+                p,
+                if significant {
+                    "deviate from"
+                } else {
+                    "are consistent with"
+                }
+            );
+            if tested
+                .iter()
+                .any(|s| s.expected_blocks < MIN_EXPECTED_BLOCKS)
+            {
+                note.push_str(" (some expected counts are below 5; treat p as rough)");
+            }
+            note
+        }
+        _ => "fewer than two weighted miners with attributed blocks; no test made".to_string(),
+    };
+
+    MinerAttribution {
+        attributed_blocks,
+        unattributed_blocks: per_block.len() - attributed_blocks,
+        shares,
+        chi_square,
+        degrees_of_freedom,
+        p_value,
+        significant,
+        note,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::testutil::SyntheticRun;
+
+    fn run() -> SyntheticRun {
+        let mut run = SyntheticRun::new();
+        run.agent("miner-1", "agents.autonomous_miner")
+            .agent("miner-2", "agents.autonomous_miner");
+        for n in 1..=8 {
+            run.agent(&format!("user-{}", n), "agents.regular_user");
+        }
+        run
+    }
+
+    #[test]
+    fn thresholds_follow_arrival_order() {
+        let mut run = run();
+        run.mined(1, &["tx1"]);
+        let ids: Vec<String> = run.agents.iter().map(|a| a.id.clone()).collect();
+        // miner-1 at 100.0, then one daemon every 100ms
+        run.block_seen("", 1, "miner-1", None, 100.0);
+        for (i, id) in ids.iter().enumerate().skip(1) {
+            run.block_seen("aa", 1, id, Some("miner-1"), 100.0 + i as f64 * 0.1);
+        }
+
+        let report = analyze_block_propagation(&run.blocks, &run.log_map(), &HashMap::new(), 120.0);
+        assert_eq!(report.daemon_count, 10);
+        let block = &report.per_block[0];
+        assert_eq!(block.first_seen_node, "miner-1");
+        assert_eq!(block.miner.as_deref(), Some("miner-1"));
+        assert_eq!(block.block_hash.as_deref(), Some("aa"));
+        assert_eq!(block.tx_count, Some(1));
+        assert!((block.time_to_50_ms.unwrap() - 400.0).abs() < 1e-6);
+        assert!((block.time_to_90_ms.unwrap() - 800.0).abs() < 1e-6);
+        assert!((block.time_to_100_ms.unwrap() - 900.0).abs() < 1e-6);
+        assert_eq!(report.fully_propagated_blocks, 1);
+        assert!(report.alternative_blocks.is_empty());
+        assert!(report.miner_attribution.is_none());
+    }
+
+    #[test]
+    fn competing_blocks_are_reported_and_partial_spread_has_no_100() {
+        let mut run = run();
+        run.block_seen("", 5, "miner-1", None, 10.0)
+            .block_seen("", 5, "miner-2", None, 10.2)
+            .block_seen("aa", 5, "user-1", Some("miner-1"), 10.1)
+            .block_seen("bb", 5, "user-2", Some("miner-2"), 10.3);
+
+        let report = analyze_block_propagation(&[], &run.log_map(), &HashMap::new(), 120.0);
+        let block = &report.per_block[0];
+        assert_eq!(block.nodes_reached, 4);
+        assert_eq!(block.time_to_50_ms, None);
+        assert_eq!(block.time_to_100_ms, None);
+        assert_eq!(block.miner.as_deref(), Some("miner-1"));
+        assert_eq!(report.alternative_blocks.len(), 1);
+        assert_eq!(report.alternative_blocks[0].block_hashes, ["aa", "bb"]);
+        assert_eq!(report.alternative_blocks[0].miners, ["miner-1", "miner-2"]);
+    }
+
+    #[test]
+    fn miner_shares_are_tested_against_weights() {
+        let mut run = run();
+        // miner-1 finds all 30 blocks although both have equal weight
+        for height in 1..=30 {
+            run.block(height, "miner-1", &[], height as f64 * 120.0, 0.5);
+        }
+        let weights: HashMap<String, f64> =
+            [("miner-1".to_string(), 50.0), ("miner-2".to_string(), 50.0)]
+                .into_iter()
+                .collect();
+
+        let report = analyze_block_propagation(&run.blocks, &run.log_map(), &weights, 120.0);
+        let attribution = report.miner_attribution.unwrap();
+        assert_eq!(attribution.attributed_blocks, 30);
+        assert_eq!(attribution.unattributed_blocks, 0);
+        assert_eq!(attribution.degrees_of_freedom, 1);
+        assert!((attribution.chi_square.unwrap() - 30.0).abs() < 1e-9);
+        assert!(attribution.significant);
+        assert_eq!(attribution.shares[1].miner_id, "miner-2");
+        assert_eq!(attribution.shares[1].actual_blocks, 0);
+        assert!((attribution.shares[1].expected_blocks - 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn delays_are_normalized_by_the_target_interval() {
+        let mut run = run();
+        // Blocks every 30s, reaching everyone 1.5s after the miner
+        for height in 1..=4 {
+            run.block(height, "miner-1", &[], height as f64 * 30.0, 1.5);
+        }
+        let report = analyze_block_propagation(&run.blocks, &run.log_map(), &HashMap::new(), 30.0);
+        assert_eq!(report.block_interval_secs, 30.0);
+        assert_eq!(report.observed_block_interval_secs, Some(30.0));
+        assert!((report.median_time_to_90_interval_share.unwrap() - 0.05).abs() < 1e-9);
+        assert!((report.per_block[0].time_to_90_interval_share.unwrap() - 0.05).abs() < 1e-9);
+
+        // The same delays take a smaller share of Monero's 120s
+        let slow = analyze_block_propagation(&run.blocks, &run.log_map(), &HashMap::new(), 120.0);
+        assert!((slow.median_time_to_90_interval_share.unwrap() - 0.0125).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,304 @@
+//! Multi-run comparison.
+//!
+//! Each run is reduced to a few headline metrics per analysis, keeping the
+//! samples behind them (per transaction, per node or per stem path). Every
+//! run is then compared with the first one, the baseline, using the same
+//! Welch's t-test as the upgrade analysis.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use super::propagation::DEFAULT_COVERAGE_PERCENTILES;
+use super::schema::REPORT_SCHEMA_VERSION;
+use super::stats::{compare_samples, mean};
+use super::types::*;
+use super::{analyze_bandwidth, analyze_dandelion, analyze_propagation, analyze_spy_vulnerability};
+
+/// An analysis `Compare` can run on every run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareAnalysis {
+    Propagation,
+    Bandwidth,
+    Spy,
+    Dandelion,
+}
+
+impl CompareAnalysis {
+    pub const ALL: [CompareAnalysis; 4] = [
+        CompareAnalysis::Propagation,
+        CompareAnalysis::Bandwidth,
+        CompareAnalysis::Spy,
+        CompareAnalysis::Dandelion,
+    ];
+}
+
+/// `propagation`, `bandwidth`, `spy` or `dandelion`
+impl FromStr for CompareAnalysis {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CompareAnalysis::ALL
+            .into_iter()
+            .find(|a| a.to_string() == s)
+            .ok_or_else(|| {
+                format!(
+                    "invalid analysis '{}' (expected propagation, bandwidth, spy or dandelion)",
+                    s
+                )
+            })
+    }
+}
+
+impl fmt::Display for CompareAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CompareAnalysis::Propagation => "propagation",
+            CompareAnalysis::Bandwidth => "bandwidth",
+            CompareAnalysis::Spy => "spy",
+            CompareAnalysis::Dandelion => "dandelion",
+        })
+    }
+}
+
+/// Run `analyses` on one run and collect their headline metrics
+pub fn summarize_run(
+    label: &str,
+    transactions: &[Transaction],
+    blocks: &[BlockInfo],
+    log_data: &HashMap<String, NodeLogData>,
+    agents: &[AnalysisAgentInfo],
+    analyses: &[CompareAnalysis],
+) -> RunSummary {
+    let mut metrics = Vec::new();
+    for &analysis in analyses {
+        let mut metric = |name: &str, value: f64, samples: Vec<f64>, higher_is_better: bool| {
+            metrics.push(RunMetric {
+                metric_name: name.to_string(),
+                analysis: analysis.to_string(),
+                value,
+                sample_count: samples.len(),
+                samples,
+                higher_is_better,
+            })
+        };
+        match analysis {
+            CompareAnalysis::Propagation => {
+                let report = analyze_propagation(
+                    transactions,
+                    blocks,
+                    log_data,
+                    agents,
+                    false,
+                    &DEFAULT_COVERAGE_PERCENTILES,
+                    None,
+                );
+                let per_tx = &report.per_tx_analysis;
+                metric(
+                    "Avg Propagation (ms)",
+                    report.average_propagation_ms,
+                    per_tx
+                        .iter()
+                        .map(|a| a.network_propagation_time_ms)
+                        .collect(),
+                    false,
+                );
+                let coverage: Vec<f64> = per_tx.iter().map(|a| a.propagation_coverage).collect();
+                metric("Propagation Coverage", mean(&coverage), coverage, true);
+                metric(
+                    "Confirmation Delay (s)",
+                    report.average_confirmation_delay_sec,
+                    per_tx
+                        .iter()
+                        .filter_map(|a| a.confirmation_delay_sec)
+                        .collect(),
+                    false,
+                );
+            }
+            CompareAnalysis::Bandwidth => {
+                let report = analyze_bandwidth(log_data, 0);
+                let nodes = &report.per_node_stats;
+                metric(
+                    "Bytes per Node",
+                    report.avg_bytes_per_node,
+                    nodes.iter().map(|n| n.total_bytes as f64).collect(),
+                    false,
+                );
+                let messages: Vec<f64> = nodes
+                    .iter()
+                    .map(|n| (n.message_count_sent + n.message_count_received) as f64)
+                    .collect();
+                metric("Messages per Node", mean(&messages), messages, false);
+            }
+            CompareAnalysis::Spy => {
+                let report = analyze_spy_vulnerability(transactions, log_data, agents, None);
+                let per_tx = &report.per_tx_analysis;
+                metric(
+                    "Spy Accuracy",
+                    report.inference_accuracy,
+                    per_tx
+                        .iter()
+                        .map(|a| if a.inference_correct { 1.0 } else { 0.0 })
+                        .collect(),
+                    false,
+                );
+                let spread: Vec<f64> = per_tx.iter().map(|a| a.timing_spread_ms).collect();
+                metric("Timing Spread (ms)", mean(&spread), spread, true);
+            }
+            CompareAnalysis::Dandelion => {
+                let report =
+                    analyze_dandelion(transactions, log_data, agents, FluffDetection::default());
+                metric(
+                    "Avg Stem Length",
+                    report.avg_stem_length,
+                    report.paths.iter().map(|p| p.stem_length as f64).collect(),
+                    true,
+                );
+                let privacy = &report.privacy_assessment;
+                metric(
+                    "Privacy Score",
+                    privacy.privacy_score as f64,
+                    Vec::new(),
+                    true,
+                );
+                metric(
+                    "Trivially Deanonymizable (%)",
+                    privacy.trivially_deanonymizable_pct,
+                    Vec::new(),
+                    false,
+                );
+            }
+        }
+    }
+
+    RunSummary {
+        label: label.to_string(),
+        agents: agents.len(),
+        transactions: transactions.len(),
+        daemons: log_data.len(),
+        metrics,
+    }
+}
+
+/// Compare every run with the first. Metrics missing from the baseline are
+/// left out.
+pub fn compare_runs(runs: Vec<RunSummary>, analyses: &[CompareAnalysis]) -> ComparisonReport {
+    let baseline = runs.first();
+    let comparisons = runs
+        .iter()
+        .skip(1)
+        .map(|run| {
+            let changes: Vec<MetricComparison> = run
+                .metrics
+                .iter()
+                .filter_map(|metric| {
+                    let base = baseline?
+                        .metrics
+                        .iter()
+                        .find(|m| m.metric_name == metric.metric_name)?;
+                    let c = compare_samples(
+                        base.value,
+                        metric.value,
+                        &base.samples,
+                        &metric.samples,
+                        metric.higher_is_better,
+                    );
+                    Some(MetricComparison {
+                        metric_name: metric.metric_name.clone(),
+                        baseline_value: base.value,
+                        value: metric.value,
+                        absolute_change: c.absolute_change,
+                        percent_change: c.percent_change,
+                        p_value: c.p_value,
+                        statistically_significant: c.significant,
+                        impact: c.impact,
+                        confidence_interval: c.confidence_interval,
+                    })
+                })
+                .collect();
+            RunComparison {
+                label: run.label.clone(),
+                significant_changes: changes
+                    .iter()
+                    .filter(|c| c.statistically_significant)
+                    .count(),
+                changes,
+            }
+        })
+        .collect();
+
+    ComparisonReport {
+        report_schema_version: REPORT_SCHEMA_VERSION,
+        baseline: baseline.map(|r| r.label.clone()).unwrap_or_default(),
+        analyses: analyses.iter().map(|a| a.to_string()).collect(),
+        runs,
+        comparisons,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::testutil::SyntheticRun;
+
+    /// Three agents; every transaction reaches both other daemons after
+    /// `delay` seconds
+    fn run(delay: f64) -> SyntheticRun {
+        let mut run = SyntheticRun::new();
+        for id in ["a", "b", "c"] {
+            run.agent(id, "agents.regular_user");
+        }
+        for i in 0..6 {
+            let hash = format!("tx{}", i);
+            let at = i as f64 * 10.0;
+            // A little jitter so the samples have variance
+            let jitter = (i % 3) as f64 * 0.01;
+            run.transaction(&hash, "a", "b", at)
+                .observe(&hash, "b", "a", at + delay + jitter)
+                .observe(&hash, "c", "b", at + 2.0 * delay + jitter);
+        }
+        run
+    }
+
+    fn summary(label: &str, run: &SyntheticRun) -> RunSummary {
+        summarize_run(
+            label,
+            &run.transactions,
+            &run.blocks,
+            &run.log_map(),
+            &run.agents,
+            &[CompareAnalysis::Propagation],
+        )
+    }
+
+    #[test]
+    fn slower_run_is_a_significant_regression() {
+        let baseline = summary("baseline", &run(0.1));
+        let slow = summary("slow", &run(0.5));
+        let report = compare_runs(vec![baseline, slow], &[CompareAnalysis::Propagation]);
+
+        assert_eq!(report.baseline, "baseline");
+        assert_eq!(report.analyses, ["propagation"]);
+        assert_eq!(report.comparisons.len(), 1);
+        let comparison = &report.comparisons[0];
+        assert_eq!(comparison.label, "slow");
+
+        let propagation = comparison
+            .changes
+            .iter()
+            .find(|c| c.metric_name == "Avg Propagation (ms)")
+            .expect("propagation compared");
+        assert!(propagation.value > propagation.baseline_value);
+        assert!(propagation.statistically_significant);
+        assert_eq!(propagation.impact, ChangeImpact::Negative);
+        assert!(comparison.significant_changes >= 1);
+    }
+
+    #[test]
+    fn analyses_parse_by_name() {
+        for analysis in CompareAnalysis::ALL {
+            assert_eq!(analysis.to_string().parse(), Ok(analysis));
+        }
+        assert!("resilience".parse::<CompareAnalysis>().is_err());
+    }
+}
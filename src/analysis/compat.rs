@@ -0,0 +1,381 @@
+//! Compatibility check between generated artifacts and this analyzer.
+//!
+//! The generator stamps its crate version and `ARTIFACT_SCHEMA_VERSION` into
+//! `agent_registry.json`, `miners.json` and `simulation_metadata.json`. Before
+//! parsing anything, the analyzer compares those stamps (and probes for the
+//! fields it relies on) so a shadow.data from an older or newer monerosim
+//! fails loudly or degrades in a way the user is told about, instead of
+//! silently producing empty sections.
+
+use std::fmt;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::ARTIFACT_SCHEMA_VERSION;
+
+/// Oldest stamped schema this analyzer reads without caveats. Unstamped
+/// (pre-versioning) artifacts are still accepted best-effort.
+pub const OLDEST_SUPPORTED_SCHEMA: u32 = 1;
+
+/// Registry fields added after the oldest supported schema: the schema that
+/// first writes each one and what the analyzer loses without it. Most are
+/// only written for some agents, so absence alone proves nothing and older
+/// registries are recognized by their stamp instead.
+const REGISTRY_FIELDS: &[(u32, &str)] = &[
+    (
+        2,
+        "no network_node_id in registry: intra-node propagation pairs cannot be detected",
+    ),
+    (
+        3,
+        "no role in registry: spy daemons cannot be told apart, so spy analysis observes from every node",
+    ),
+    (
+        4,
+        "no reachable in registry: unreachable daemons cannot be told apart, so network graph degrees are not split by reachability",
+    ),
+    (
+        5,
+        "no continent or as_number in registry: propagation and conflict breakdowns by region and AS are empty",
+    ),
+    (
+        6,
+        "no out_peers in registry: every node's outbound count is checked against --expected-outbound",
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Compatibility {
+    Supported,
+    BestEffort,
+    Unsupported,
+}
+
+impl fmt::Display for Compatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Compatibility::Supported => write!(f, "supported"),
+            Compatibility::BestEffort => write!(f, "best-effort"),
+            Compatibility::Unsupported => write!(f, "unsupported"),
+        }
+    }
+}
+
+/// Outcome of [`check_artifacts`]: overall level plus each specific
+/// degradation that led to it.
+#[derive(Debug, Clone)]
+pub struct CompatibilityReport {
+    pub level: Compatibility,
+    /// Generator crate version, if any artifact recorded it
+    pub generator_version: Option<String>,
+    /// Artifact schema version; None for pre-versioning artifacts
+    pub schema_version: Option<u32>,
+    pub degradations: Vec<String>,
+}
+
+impl CompatibilityReport {
+    fn degrade(&mut self, level: Compatibility, msg: impl Into<String>) {
+        self.level = self.level.max(level);
+        self.degradations.push(msg.into());
+    }
+}
+
+impl fmt::Display for CompatibilityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Artifact compatibility: {} (generated by monerosim {}, schema {}; analyzer {}, schema {}..={})",
+            self.level,
+            self.generator_version.as_deref().unwrap_or("unknown"),
+            self.schema_version
+                .map_or_else(|| "unversioned".to_string(), |v| v.to_string()),
+            env!("CARGO_PKG_VERSION"),
+            OLDEST_SUPPORTED_SCHEMA,
+            ARTIFACT_SCHEMA_VERSION,
+        )?;
+        for d in &self.degradations {
+            write!(f, "\n  - {}", d)?;
+        }
+        Ok(())
+    }
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn stamp(json: &Value) -> (Option<String>, Option<u32>) {
+    let version = json
+        .get("generator_version")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let schema = json
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .map(|v| v as u32);
+    (version, schema)
+}
+
+/// Inspect the artifacts in `shared_dir` and classify how well this
+/// analyzer can read them. Never fails: problems become degradations.
+pub fn check_artifacts(shared_dir: &Path) -> CompatibilityReport {
+    let mut report = CompatibilityReport {
+        level: Compatibility::Supported,
+        generator_version: None,
+        schema_version: None,
+        degradations: Vec::new(),
+    };
+
+    let metadata = read_json(&shared_dir.join("simulation_metadata.json"));
+    let registry = read_json(&shared_dir.join("agent_registry.json"));
+    let miners = read_json(&shared_dir.join("miners.json"));
+
+    // Every stamped artifact must agree; a mix means files from different
+    // generator runs were copied into one directory.
+    let stamped = [
+        ("simulation_metadata.json", metadata.as_ref()),
+        ("agent_registry.json", registry.as_ref()),
+        ("miners.json", miners.as_ref()),
+    ];
+    for (name, json) in stamped {
+        let Some(json) = json else { continue };
+        let (version, schema) = stamp(json);
+        if report.generator_version.is_none() {
+            report.generator_version = version;
+        }
+        match (report.schema_version, schema) {
+            (None, s) => report.schema_version = s,
+            (Some(a), Some(b)) if a != b => report.degrade(
+                Compatibility::BestEffort,
+                format!(
+                    "{} has schema {} but other artifacts have {}: files come from different generator runs",
+                    name, b, a
+                ),
+            ),
+            _ => {}
+        }
+    }
+
+    match report.schema_version {
+        Some(v) if v > ARTIFACT_SCHEMA_VERSION => report.degrade(
+            Compatibility::Unsupported,
+            format!(
+                "artifacts use schema {} from a newer monerosim; upgrade tx-analyzer",
+                v
+            ),
+        ),
+        Some(v) if v < OLDEST_SUPPORTED_SCHEMA => report.degrade(
+            Compatibility::Unsupported,
+            format!("artifacts use retired schema {}", v),
+        ),
+        Some(_) => {}
+        None => report.degrade(
+            Compatibility::BestEffort,
+            "artifacts predate schema versioning: registry fields are probed individually",
+        ),
+    }
+
+    if metadata.is_none() {
+        report.degrade(
+            Compatibility::BestEffort,
+            "no simulation_metadata.json: generator version and binary versions unknown",
+        );
+    }
+
+    let Some(registry) = registry else {
+        report.degrade(
+            Compatibility::Unsupported,
+            "agent_registry.json missing or not valid JSON: no agents to attribute logs to",
+        );
+        return report;
+    };
+
+    let agents: Vec<&Value> = match registry.get("agents").and_then(|v| v.as_array()) {
+        Some(list) => list.iter().collect(),
+        None => match registry.as_object() {
+            Some(map) => {
+                report.degrade(
+                    Compatibility::BestEffort,
+                    "agent_registry.json uses the legacy id->agent map layout",
+                );
+                map.values().filter(|v| v.is_object()).collect()
+            }
+            None => Vec::new(),
+        },
+    };
+
+    let has = |agent: &Value, keys: &[&str]| keys.iter().any(|k| agent.get(k).is_some());
+    if !agents.is_empty()
+        && !agents
+            .iter()
+            .any(|a| has(a, &["user_script", "script_type"]))
+    {
+        report.degrade(
+            Compatibility::BestEffort,
+            "no user_script in registry: miner/user role filters disabled (mining centralization and per-role tx relay stats will be empty)",
+        );
+    }
+    for &(since, msg) in REGISTRY_FIELDS {
+        if !matches!(report.schema_version, Some(v) if v >= since) {
+            report.degrade(Compatibility::BestEffort, msg);
+        }
+    }
+    // Since schema 1 daemon-less agents legitimately omit the port, so only
+    // unversioned registries are probed for it.
+    if report.schema_version.is_none() {
+        let missing = agents
+            .iter()
+            .filter(|a| !has(a, &["daemon_rpc_port", "rpc_port"]))
+            .count();
+        if missing > 0 {
+            report.degrade(
+                Compatibility::BestEffort,
+                format!(
+                    "no daemon_rpc_port for {} agent(s): assuming {}",
+                    missing,
+                    crate::MONERO_RPC_PORT
+                ),
+            );
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, name: &str, json: Value) {
+        std::fs::write(dir.join(name), json.to_string()).unwrap();
+    }
+
+    fn current(dir: &Path) {
+        let stamp = |extra: Value| {
+            let mut v = serde_json::json!({
+                "generator_version": env!("CARGO_PKG_VERSION"),
+                "schema_version": ARTIFACT_SCHEMA_VERSION,
+            });
+            v.as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            v
+        };
+        write(
+            dir,
+            "simulation_metadata.json",
+            stamp(serde_json::json!({})),
+        );
+        write(
+            dir,
+            "agent_registry.json",
+            stamp(serde_json::json!({"agents": [
+                {"id": "miner-001", "ip_addr": "72.0.0.10", "daemon_rpc_port": 18081,
+                 "user_script": "agents.autonomous_miner"},
+                {"id": "script-001", "ip_addr": "45.0.0.10", "user_script": "agents.x"}
+            ]})),
+        );
+        write(dir, "miners.json", stamp(serde_json::json!({"miners": []})));
+    }
+
+    #[test]
+    fn current_artifacts_are_supported() {
+        let tmp = TempDir::new().unwrap();
+        current(tmp.path());
+        let report = check_artifacts(tmp.path());
+        assert_eq!(report.level, Compatibility::Supported, "{}", report);
+        assert!(report.degradations.is_empty());
+        assert_eq!(report.schema_version, Some(ARTIFACT_SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn unversioned_legacy_artifacts_degrade_with_specific_reasons() {
+        let tmp = TempDir::new().unwrap();
+        // Pre-versioning layout: map registry, no metadata, no role scripts,
+        // one agent without a port.
+        write(
+            tmp.path(),
+            "agent_registry.json",
+            serde_json::json!({
+                "user-001": {"ip_addr": "72.0.0.10", "rpc_port": 18081},
+                "user-002": {"ip_addr": "72.0.1.10"}
+            }),
+        );
+        let report = check_artifacts(tmp.path());
+        assert_eq!(report.level, Compatibility::BestEffort, "{}", report);
+        assert_eq!(report.schema_version, None);
+        let text = report.to_string();
+        for expected in [
+            "predate schema versioning",
+            "no simulation_metadata.json",
+            "legacy id->agent map",
+            "no user_script in registry",
+            "no daemon_rpc_port for 1 agent(s)",
+            "no network_node_id in registry",
+            "no role in registry",
+            "no reachable in registry",
+            "no continent or as_number in registry",
+            "no out_peers in registry",
+        ] {
+            assert!(
+                text.contains(expected),
+                "missing {:?} in:\n{}",
+                expected,
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn older_schemas_degrade_for_each_later_registry_field() {
+        let tmp = TempDir::new().unwrap();
+        current(tmp.path());
+        for &(since, msg) in REGISTRY_FIELDS {
+            for (schema, lacks) in [(since - 1, true), (since, false)] {
+                if schema < OLDEST_SUPPORTED_SCHEMA {
+                    continue;
+                }
+                for name in [
+                    "simulation_metadata.json",
+                    "agent_registry.json",
+                    "miners.json",
+                ] {
+                    let path = tmp.path().join(name);
+                    let mut json: Value =
+                        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+                    json["schema_version"] = schema.into();
+                    write(tmp.path(), name, json);
+                }
+                let report = check_artifacts(tmp.path());
+                assert_eq!(
+                    report.degradations.iter().any(|d| d == msg),
+                    lacks,
+                    "schema {}: {}",
+                    schema,
+                    report
+                );
+                if lacks {
+                    assert_eq!(report.level, Compatibility::BestEffort, "{}", report);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn newer_schema_is_unsupported() {
+        let tmp = TempDir::new().unwrap();
+        current(tmp.path());
+        write(
+            tmp.path(),
+            "simulation_metadata.json",
+            serde_json::json!({"generator_version": "99.0.0", "schema_version": ARTIFACT_SCHEMA_VERSION + 1}),
+        );
+        let report = check_artifacts(tmp.path());
+        assert_eq!(report.level, Compatibility::Unsupported, "{}", report);
+        assert!(report.to_string().contains("from a newer monerosim"));
+        assert!(report.to_string().contains("different generator runs"));
+    }
+}
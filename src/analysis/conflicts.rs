@@ -0,0 +1,245 @@
+//! Conflicting-transaction (double-spend) analysis.
+//!
+//! An agent attempting a double spend broadcasts two or more transactions
+//! spending the same inputs, usually to different parts of the network, and
+//! appends them as one set to `conflicts.json` in the shared directory
+//! (`BaseAgent.record_conflict_set` does this):
+//!
+//! ```json
+//! [{"conflict_id": "ds-1", "agent_id": "attacker-001",
+//!   "tx_hashes": ["<hash a>", "<hash b>"], "timestamp": 946688400.0}]
+//! ```
+//!
+//! A daemon keeps whichever transaction of a set reaches it first and
+//! rejects the others, so the first-seen order at each daemon shows how the
+//! network split. The split is broken down by continent and AS, and the
+//! relays between daemons on different sides are counted. A set is resolved
+//! once one of its transactions is mined.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+use color_eyre::eyre::{Context, Result};
+
+use super::schema::REPORT_SCHEMA_VERSION;
+use super::stats::mean;
+use super::types::*;
+
+/// Conflict sets file in the shared directory
+pub const CONFLICTS_FILE: &str = "conflicts.json";
+
+/// Load the conflict sets at `path`; empty if the file is missing
+pub fn load_conflict_sets(path: &Path) -> Result<Vec<ConflictSet>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read conflict sets: {}", path.display()))?;
+    serde_json::from_str(&text)
+        .with_context(|| format!("Failed to parse conflict sets: {}", path.display()))
+}
+
+/// Analyze how the network split over each conflict set and how it resolved
+pub fn analyze_conflicts(
+    sets: &[ConflictSet],
+    transactions: &[Transaction],
+    blocks: &[BlockInfo],
+    log_data: &HashMap<String, NodeLogData>,
+    agents: &[AnalysisAgentInfo],
+) -> ConflictReport {
+    let mut tx_height: HashMap<&str, u64> = HashMap::new();
+    for block in blocks {
+        for tx in &block.transactions {
+            let height = tx_height.entry(tx).or_insert(block.height);
+            *height = (*height).min(block.height);
+        }
+    }
+    let mut block_time: HashMap<u64, SimTime> = HashMap::new();
+    for data in log_data.values() {
+        for obs in &data.block_observations {
+            let t = block_time.entry(obs.height).or_insert(obs.timestamp);
+            *t = t.min(obs.timestamp);
+        }
+    }
+    // Co-located daemons share an IP, and a relay from it is credited to each
+    let mut ip_owners: HashMap<&str, Vec<&str>> = HashMap::new();
+    for agent in agents {
+        ip_owners
+            .entry(agent.ip_addr.as_str())
+            .or_default()
+            .push(agent.id.as_str());
+    }
+    let agent_info: HashMap<&str, &AnalysisAgentInfo> =
+        agents.iter().map(|a| (a.id.as_str(), a)).collect();
+
+    let analyses: Vec<ConflictSetAnalysis> = sets
+        .iter()
+        .enumerate()
+        .map(|(i, set)| {
+            analyze_set(
+                i,
+                set,
+                transactions,
+                log_data,
+                &ip_owners,
+                &agent_info,
+                &tx_height,
+                &block_time,
+            )
+        })
+        .collect();
+
+    let resolution: Vec<f64> = analyses.iter().filter_map(|s| s.resolution_secs).collect();
+    let double_relayers: BTreeSet<&str> = analyses
+        .iter()
+        .flat_map(|s| s.double_relayers.iter().map(String::as_str))
+        .collect();
+
+    ConflictReport {
+        report_schema_version: REPORT_SCHEMA_VERSION,
+        conflict_sets: analyses.len(),
+        resolved_sets: analyses.iter().filter(|s| s.included_tx.is_some()).count(),
+        avg_resolution_secs: (!resolution.is_empty()).then(|| mean(&resolution)),
+        max_resolution_secs: resolution.iter().copied().reduce(f64::max),
+        split_sets: analyses.iter().filter(|s| s.preferred.len() > 1).count(),
+        double_relayers: double_relayers.into_iter().map(str::to_string).collect(),
+        sets: analyses,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn analyze_set(
+    index: usize,
+    set: &ConflictSet,
+    transactions: &[Transaction],
+    log_data: &HashMap<String, NodeLogData>,
+    ip_owners: &HashMap<&str, Vec<&str>>,
+    agent_info: &HashMap<&str, &AnalysisAgentInfo>,
+    tx_height: &HashMap<&str, u64>,
+    block_time: &HashMap<u64, SimTime>,
+) -> ConflictSetAnalysis {
+    let members: BTreeSet<&str> = set.tx_hashes.iter().map(String::as_str).collect();
+
+    // Daemon -> transaction -> first sighting
+    let mut seen: BTreeMap<&str, BTreeMap<&str, SimTime>> = BTreeMap::new();
+    let mut sighting = |node: &'_ str, tx, t: SimTime| {
+        let Some((node, _)) = log_data.get_key_value(node) else {
+            return;
+        };
+        let first = seen.entry(node).or_default().entry(tx).or_insert(t);
+        *first = first.min(t);
+    };
+    // The sender's daemon never logs receiving its own transaction
+    for tx in transactions {
+        if let Some(&member) = members.get(tx.tx_hash.as_str()) {
+            sighting(&tx.sender_id, member, tx.timestamp);
+        }
+    }
+    // Daemon -> the set's transactions it sent to another daemon
+    let mut relayed: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    let mut links: BTreeSet<(&str, &str)> = BTreeSet::new();
+    for (node_id, data) in log_data {
+        for obs in &data.tx_observations {
+            let Some(&member) = members.get(obs.tx_hash.as_str()) else {
+                continue;
+            };
+            sighting(node_id, member, obs.timestamp);
+            for &source in ip_owners.get(obs.source_ip.as_str()).into_iter().flatten() {
+                if source == node_id {
+                    continue;
+                }
+                relayed.entry(source).or_default().insert(member);
+                links.insert((source.min(node_id), source.max(node_id)));
+            }
+        }
+    }
+
+    let per_node: Vec<NodeConflictView> = seen
+        .iter()
+        .map(|(&node_id, times)| {
+            let mut order: Vec<(&str, SimTime)> = times.iter().map(|(&tx, &t)| (tx, t)).collect();
+            order.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(b.0)));
+            NodeConflictView {
+                node_id: node_id.to_string(),
+                first_seen_order: order.iter().map(|(tx, _)| tx.to_string()).collect(),
+                first_seen_times: order.iter().map(|(_, t)| *t).collect(),
+                preferred_tx: order[0].0.to_string(),
+                relayed: relayed
+                    .get(node_id)
+                    .map(|txs| txs.iter().map(|tx| tx.to_string()).collect())
+                    .unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    let preference: HashMap<&str, &str> = per_node
+        .iter()
+        .map(|v| (v.node_id.as_str(), v.preferred_tx.as_str()))
+        .collect();
+    let mut preferred: BTreeMap<String, usize> = BTreeMap::new();
+    for tx in preference.values() {
+        *preferred.entry(tx.to_string()).or_default() += 1;
+    }
+    let split_by = |region: &dyn Fn(&AnalysisAgentInfo) -> Option<String>| {
+        let mut split: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+        for (node_id, tx) in &preference {
+            let region = agent_info
+                .get(node_id)
+                .and_then(|a| region(a))
+                .unwrap_or_else(|| "unknown".to_string());
+            *split
+                .entry(region)
+                .or_default()
+                .entry(tx.to_string())
+                .or_default() += 1;
+        }
+        split
+            .into_iter()
+            .map(|(region, preferred)| ConflictRegionSplit { region, preferred })
+            .collect()
+    };
+    let contested_links = links
+        .iter()
+        .filter(
+            |(a, b)| matches!((preference.get(a), preference.get(b)), (Some(x), Some(y)) if x != y),
+        )
+        .count();
+
+    let first_seen = seen
+        .values()
+        .flat_map(|times| times.values().copied())
+        .chain(set.timestamp)
+        .reduce(f64::min);
+    let included = members
+        .iter()
+        .filter_map(|tx| Some((*tx, *tx_height.get(tx)?)))
+        .min_by_key(|(_, height)| *height);
+    let resolution_secs = included
+        .and_then(|(_, height)| block_time.get(&height))
+        .zip(first_seen)
+        .map(|(mined, first)| mined - first);
+
+    ConflictSetAnalysis {
+        conflict_id: set
+            .conflict_id
+            .clone()
+            .unwrap_or_else(|| format!("conflict-{}", index)),
+        agent_id: set.agent_id.clone(),
+        tx_hashes: set.tx_hashes.clone(),
+        first_seen,
+        nodes_reached: per_node.len(),
+        preferred,
+        split_by_continent: split_by(&|a| a.continent.clone()),
+        split_by_as: split_by(&|a| a.as_number.map(|n| format!("AS{}", n))),
+        contested_links,
+        included_tx: included.map(|(tx, _)| tx.to_string()),
+        included_height: included.map(|(_, height)| height),
+        resolution_secs,
+        double_relayers: relayed
+            .iter()
+            .filter(|(_, txs)| txs.len() > 1)
+            .map(|(node, _)| node.to_string())
+            .collect(),
+        per_node,
+    }
+}
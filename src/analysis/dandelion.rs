@@ -2,45 +2,40 @@
 //!
 //! Reconstructs the propagation path of transactions through the network,
 //! identifying stem phase (linear relay) vs fluff phase (broadcast).
+//! [`reconstruct_path`] is the one stem tracer; the upgrade analysis sweeps
+//! it over several fluff gap thresholds.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
+use super::schema::REPORT_SCHEMA_VERSION;
+use super::stats::mean;
 use super::types::*;
 
-/// Minimum recipients to consider it a fluff event
-const FLUFF_MIN_RECIPIENTS: usize = 3;
-
-/// Gap threshold (ms) for fluff detection: if the first 3 observations from
-/// a sender are within this gap, it's a fluff broadcast. If the gap is larger,
-/// the first observation is a stem relay and the rest are later gossip re-relays.
-const FLUFF_GAP_THRESHOLD_MS: f64 = 2000.0;
+/// Deliveries this close together (ms) cannot be ordered at the logs'
+/// millisecond resolution
+const TIE_WINDOW_MS: f64 = 1.0;
 
 /// Analyze Dandelion++ stem paths for all transactions
 pub fn analyze_dandelion(
     transactions: &[Transaction],
     log_data: &HashMap<String, NodeLogData>,
     agents: &[AnalysisAgentInfo],
+    detection: FluffDetection,
 ) -> DandelionReport {
-    // Build IP -> node_id mapping
-    let ip_to_node: HashMap<String, String> = agents
+    let ip_to_node: HashMap<&str, &str> = agents
         .iter()
-        .map(|a| (a.ip_addr.clone(), a.id.clone()))
+        .map(|a| (a.ip_addr.as_str(), a.id.as_str()))
         .collect();
-
-    // Build node_id -> IP mapping
-    let node_to_ip: HashMap<String, String> = agents
+    let node_to_ip: HashMap<&str, &str> = agents
         .iter()
-        .map(|a| (a.id.clone(), a.ip_addr.clone()))
+        .map(|a| (a.id.as_str(), a.ip_addr.as_str()))
         .collect();
 
     // Collect all TX observations grouped by tx_hash
-    let mut tx_observations: HashMap<String, Vec<TxObservation>> = HashMap::new();
-    for (_, node_data) in log_data {
+    let mut tx_observations: HashMap<&str, Vec<&TxObservation>> = HashMap::new();
+    for node_data in log_data.values() {
         for obs in &node_data.tx_observations {
-            tx_observations
-                .entry(obs.tx_hash.clone())
-                .or_default()
-                .push(obs.clone());
+            tx_observations.entry(&obs.tx_hash).or_default().push(obs);
         }
     }
 
@@ -52,8 +47,10 @@ pub fn analyze_dandelion(
     let mut node_stem_positions: HashMap<String, Vec<usize>> = HashMap::new();
 
     for tx in transactions {
-        if let Some(observations) = tx_observations.get(&tx.tx_hash) {
-            if let Some(path) = reconstruct_path(tx, observations, &ip_to_node, &node_to_ip) {
+        if let Some(observations) = tx_observations.get(tx.tx_hash.as_str()) {
+            if let Some(path) =
+                reconstruct_path(tx, observations, &ip_to_node, &node_to_ip, detection)
+            {
                 // Update node statistics
                 *node_originator_counts
                     .entry(path.originator.clone())
@@ -152,14 +149,18 @@ pub fn analyze_dandelion(
 
     // Count originator confirmations
     let originator_confirmed_count = paths.iter().filter(|p| p.originator_confirmed).count();
+    let confidences: Vec<f64> = paths.iter().map(|p| p.confidence).collect();
 
     // Generate privacy assessment
     let privacy_assessment = assess_privacy(&paths, transactions.len());
 
     DandelionReport {
+        report_schema_version: REPORT_SCHEMA_VERSION,
         total_transactions: transactions.len(),
         paths_reconstructed: paths.len(),
         originator_confirmed_count,
+        fluff_detection: detection,
+        avg_path_confidence: mean(&confidences),
         avg_stem_length,
         min_stem_length,
         max_stem_length,
@@ -177,19 +178,27 @@ pub fn analyze_dandelion(
 ///
 /// The stem path is a chain: originator -> A -> B -> C -> fluff
 /// Each node in the chain receives from the previous node, then relays to exactly one next node.
-/// The fluff point is where a node broadcasts to multiple peers simultaneously.
-fn reconstruct_path(
+/// The fluff point is where a node broadcasts to multiple peers simultaneously,
+/// as decided by `detection`.
+///
+/// HEURISTIC — `confidence` multiplies, per hop, one over the number of
+/// equally plausible choices: the current sender's deliveries tied with its
+/// earliest one, times the distinct peers the chosen node heard the
+/// transaction from at that same moment. Ties are [`TIE_WINDOW_MS`] apart
+/// at most.
+pub(super) fn reconstruct_path(
     tx: &Transaction,
-    observations: &[TxObservation],
-    ip_to_node: &HashMap<String, String>,
-    node_to_ip: &HashMap<String, String>,
+    observations: &[&TxObservation],
+    ip_to_node: &HashMap<&str, &str>,
+    node_to_ip: &HashMap<&str, &str>,
+    detection: FluffDetection,
 ) -> Option<DandelionPath> {
     if observations.is_empty() {
         return None;
     }
 
     // Sort observations by timestamp
-    let mut sorted_obs = observations.to_vec();
+    let mut sorted_obs: Vec<&TxObservation> = observations.to_vec();
     sorted_obs.sort_by(|a, b| {
         a.timestamp
             .partial_cmp(&b.timestamp)
@@ -198,72 +207,80 @@ fn reconstruct_path(
 
     // Get originator info
     let originator = tx.sender_id.clone();
-    let originator_ip = node_to_ip.get(&originator).cloned();
+    let originator_ip = node_to_ip.get(originator.as_str()).copied();
 
     // Build the stem path by following the chain of relays
     // Key insight: in stem phase, each receiver becomes the sender to exactly one next node
     // In fluff phase, one sender broadcasts to many nodes simultaneously
 
     let mut stem_path: Vec<StemHop> = Vec::new();
-    let mut used_observations: std::collections::HashSet<usize> = std::collections::HashSet::new();
+    let mut used_observations: HashSet<usize> = HashSet::new();
     let mut fluff_node: Option<String> = None;
     let mut fluff_recipients = 0usize;
+    let mut confidence = 1.0;
 
-    // First, find the first observation that came from the originator
-    let first_hop_idx = sorted_obs.iter().position(|obs| {
-        originator_ip
-            .as_ref()
-            .map(|ip| &obs.source_ip == ip)
-            .unwrap_or(false)
-    });
-
-    // Seed the stem path with the first hop and set the sender for the chain walk.
-    let mut current_sender_ip = if let Some(idx) = first_hop_idx {
-        // Start building chain from first hop
-        let first_obs = &sorted_obs[idx];
-        stem_path.push(StemHop {
-            node_id: first_obs.node_id.clone(),
-            from_node_id: Some(originator.clone()),
-            from_ip: first_obs.source_ip.clone(),
-            timestamp: first_obs.timestamp,
-            delta_ms: 0.0,
-        });
-        used_observations.insert(idx);
-        node_to_ip.get(&first_obs.node_id).cloned()
-    } else {
-        // Originator not found in observations, start from first observation
-        let first_obs = &sorted_obs[0];
-        let from_node = ip_to_node.get(&first_obs.source_ip).cloned();
-        stem_path.push(StemHop {
-            node_id: first_obs.node_id.clone(),
-            from_node_id: from_node,
-            from_ip: first_obs.source_ip.clone(),
-            timestamp: first_obs.timestamp,
-            delta_ms: 0.0,
-        });
-        used_observations.insert(0);
-        node_to_ip.get(&first_obs.node_id).cloned()
+    // Unused deliveries from `sender_ip` (from anyone if None), earliest first
+    let deliveries = |used: &HashSet<usize>, sender_ip: Option<&str>| {
+        sorted_obs
+            .iter()
+            .enumerate()
+            .filter(|(i, obs)| {
+                !used.contains(i) && sender_ip.map_or(true, |ip| obs.source_ip == ip)
+            })
+            .map(|(i, obs)| (i, *obs))
+            .collect::<Vec<(usize, &TxObservation)>>()
+    };
+    let hop_weight = |candidates: &[(usize, &TxObservation)], chosen: &TxObservation| {
+        let tied = |a: SimTime, b: SimTime| (a - b).abs() * 1000.0 <= TIE_WINDOW_MS;
+        let tied_recipients = candidates
+            .iter()
+            .filter(|(_, obs)| tied(obs.timestamp, chosen.timestamp))
+            .count();
+        let senders: HashSet<&str> = sorted_obs
+            .iter()
+            .filter(|obs| obs.node_id == chosen.node_id && tied(obs.timestamp, chosen.timestamp))
+            .map(|obs| obs.source_ip.as_str())
+            .collect();
+        1.0 / (tied_recipients.max(1) * senders.len().max(1)) as f64
     };
 
+    // Seed the stem path with the first delivery from the originator, or
+    // the earliest delivery at all if the originator never shows up
+    let from_originator = originator_ip
+        .map(|ip| deliveries(&used_observations, Some(ip)))
+        .filter(|d| !d.is_empty());
+    let originator_found = from_originator.is_some();
+    let first_candidates = from_originator.unwrap_or_else(|| deliveries(&used_observations, None));
+    let (first_idx, first_obs) = first_candidates[0];
+    stem_path.push(StemHop {
+        node_id: first_obs.node_id.clone(),
+        from_node_id: if originator_found {
+            Some(originator.clone())
+        } else {
+            ip_to_node
+                .get(first_obs.source_ip.as_str())
+                .map(|s| s.to_string())
+        },
+        from_ip: first_obs.source_ip.clone(),
+        timestamp: first_obs.timestamp,
+        delta_ms: 0.0,
+    });
+    confidence *= hop_weight(&first_candidates, first_obs);
+    used_observations.insert(first_idx);
+    let mut current_sender_ip = node_to_ip.get(first_obs.node_id.as_str()).copied();
+
     // Follow the chain: find next observation where source_ip matches current node's IP
+    let min_recipients = detection.min_recipients.max(1);
     let mut max_iterations = 100; // Prevent infinite loops
     while max_iterations > 0 {
         max_iterations -= 1;
 
-        if current_sender_ip.is_none() {
+        let Some(sender_ip) = current_sender_ip else {
             break;
-        }
-
-        let sender_ip = current_sender_ip
-            .as_ref()
-            .expect("invariant: current_sender_ip.is_none() handled by break above");
+        };
 
         // Find all observations from the current sender that we haven't used
-        let from_current: Vec<(usize, &TxObservation)> = sorted_obs
-            .iter()
-            .enumerate()
-            .filter(|(i, obs)| !used_observations.contains(i) && &obs.source_ip == sender_ip)
-            .collect();
+        let from_current = deliveries(&used_observations, Some(sender_ip));
 
         if from_current.is_empty() {
             // No more observations from this sender - end of traceable path
@@ -272,16 +289,16 @@ fn reconstruct_path(
 
         // Check if this is a fluff point: multiple nodes received from same sender
         // from_current is already scoped to this specific TX hash and source IP,
-        // so 3+ recipients means the sender *may* have broadcast (fluffed) this TX.
+        // so enough recipients means the sender *may* have broadcast (fluffed) this TX.
         // However, we must check the time gap: genuine fluff broadcasts cluster
-        // tightly (within ~2s), while gossip re-relays happen seconds later after
+        // tightly, while gossip re-relays happen seconds later after
         // the TX round-trips through stem + fluff + gossip.
-        if from_current.len() >= FLUFF_MIN_RECIPIENTS {
+        if from_current.len() >= min_recipients {
             let first_time = from_current[0].1.timestamp;
-            let third_time = from_current[2].1.timestamp;
-            let gap_ms = (third_time - first_time) * 1000.0;
+            let last_time = from_current[min_recipients - 1].1.timestamp;
+            let gap_ms = (last_time - first_time) * 1000.0;
 
-            if gap_ms <= FLUFF_GAP_THRESHOLD_MS {
+            if gap_ms <= detection.gap_ms {
                 // Observations are clustered -> genuine fluff broadcast
                 fluff_node = stem_path.last().map(|h| h.node_id.clone());
                 fluff_recipients = from_current.len();
@@ -300,14 +317,15 @@ fn reconstruct_path(
 
         stem_path.push(StemHop {
             node_id: next_obs.node_id.clone(),
-            from_node_id: ip_to_node.get(sender_ip).cloned(),
+            from_node_id: ip_to_node.get(sender_ip).map(|s| s.to_string()),
             from_ip: next_obs.source_ip.clone(),
             timestamp: next_obs.timestamp,
             delta_ms: (next_obs.timestamp - prev_timestamp) * 1000.0,
         });
+        confidence *= hop_weight(&from_current, next_obs);
 
         used_observations.insert(next_idx);
-        current_sender_ip = node_to_ip.get(&next_obs.node_id).cloned();
+        current_sender_ip = node_to_ip.get(next_obs.node_id.as_str()).copied();
     }
 
     // If no fluff detected, the last node in stem is likely the fluff point
@@ -340,13 +358,14 @@ fn reconstruct_path(
     Some(DandelionPath {
         tx_hash: tx.tx_hash.clone(),
         originator,
-        originator_ip,
+        originator_ip: originator_ip.map(|s| s.to_string()),
         stem_path,
         fluff_node,
         stem_length,
         stem_duration_ms,
         fluff_recipients,
         originator_confirmed,
+        confidence,
     })
 }
 
@@ -502,3 +521,75 @@ pub fn format_stem_path(path: &DandelionPath) -> String {
 
     parts.join(" → ")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::testutil::SyntheticRun;
+
+    /// a -> b -> c stem, then c delivers to d, e and f 200ms apart
+    fn run() -> SyntheticRun {
+        let mut run = SyntheticRun::new();
+        for id in ["a", "b", "c", "d", "e", "f"] {
+            run.agent(id, "agents.regular_user");
+        }
+        run.transaction("t1", "a", "f", 0.0);
+        let at = run.stem("t1", &["a", "b", "c"], 0.0, 0.1);
+        run.fluff("t1", "c", &["d", "e", "f"], at + 0.1, 0.2);
+        run
+    }
+
+    fn path(run: &SyntheticRun, detection: FluffDetection) -> DandelionPath {
+        let report = analyze_dandelion(&run.transactions, &run.log_map(), &run.agents, detection);
+        assert_eq!(report.fluff_detection, detection);
+        report.paths.into_iter().next().expect("path reconstructed")
+    }
+
+    fn hops(path: &DandelionPath) -> Vec<&str> {
+        path.stem_path.iter().map(|h| h.node_id.as_str()).collect()
+    }
+
+    #[test]
+    fn fluff_detection_parameters_move_the_fluff_point() {
+        let run = run();
+
+        let default = path(&run, FluffDetection::default());
+        assert_eq!(hops(&default), ["b", "c"]);
+        assert_eq!(default.fluff_node.as_deref(), Some("c"));
+        assert_eq!(default.fluff_recipients, 3);
+        assert!(default.originator_confirmed);
+
+        // d, e and f span 400ms: too slow for a 100ms broadcast
+        let tight = path(
+            &run,
+            FluffDetection {
+                gap_ms: 100.0,
+                ..FluffDetection::default()
+            },
+        );
+        assert_eq!(hops(&tight), ["b", "c", "d"]);
+        assert_eq!(tight.fluff_node.as_deref(), Some("d"));
+
+        let four = path(
+            &run,
+            FluffDetection {
+                min_recipients: 4,
+                ..FluffDetection::default()
+            },
+        );
+        assert_eq!(hops(&four), ["b", "c", "d"]);
+    }
+
+    #[test]
+    fn tied_senders_lower_the_confidence() {
+        let mut run = run();
+        assert_eq!(path(&run, FluffDetection::default()).confidence, 1.0);
+
+        // c also hears the transaction from e at the same millisecond as
+        // from b, so the b -> c hop is a coin flip
+        run.observe("t1", "c", "e", 0.2);
+        let tied = path(&run, FluffDetection::default());
+        assert_eq!(hops(&tied), ["b", "c"]);
+        assert_eq!(tied.confidence, 0.5);
+    }
+}
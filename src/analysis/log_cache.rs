@@ -0,0 +1,344 @@
+//! Per-host cache of parsed daemon logs.
+//!
+//! Each host's parsed [`NodeLogData`] is kept as zstd-compressed bincode in
+//! `<cache_dir>/<agent_id>.bincode`, behind a fingerprint of the host's log
+//! files (path, size, mtime), the analyzer version and the parse-time
+//! [`LogReduction`]. A host is re-parsed
+//! only when its fingerprint no longer matches, so re-running or touching
+//! one host costs one host's parse.
+//!
+//! Every entry starts with `CACHE_MAGIC` and the `CACHE_SCHEMA_VERSION` it
+//! was written with. Entries from another schema, or from before the header
+//! existed, are re-parsed and rewritten rather than decoded on a guess.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use color_eyre::eyre::{Context, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::log_parser::{node_log_files, parse_node_logs, LogReduction, NodeLogFiles};
+use super::schema::CACHE_SCHEMA_VERSION;
+use super::types::*;
+
+/// First bytes of every cache entry
+const CACHE_MAGIC: [u8; 4] = *b"MSLC";
+
+/// What a cache entry was parsed from
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Fingerprint {
+    analyzer_version: String,
+    /// Path, size, and mtime (seconds, nanoseconds) of each log file
+    files: Vec<(PathBuf, u64, u64, u32)>,
+    reduction: LogReduction,
+}
+
+impl Fingerprint {
+    /// `None` if a log file's metadata cannot be read
+    fn of(logs: &NodeLogFiles, reduction: &LogReduction) -> Option<Self> {
+        let files = logs
+            .files
+            .iter()
+            .map(|path| {
+                let meta = fs::metadata(path).ok()?;
+                let mtime = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+                Some((
+                    path.clone(),
+                    meta.len(),
+                    mtime.as_secs(),
+                    mtime.subsec_nanos(),
+                ))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            analyzer_version: env!("CARGO_PKG_VERSION").to_string(),
+            files,
+            reduction: reduction.clone(),
+        })
+    }
+}
+
+fn entry_path(cache_dir: &Path, agent_id: &str) -> PathBuf {
+    cache_dir.join(format!("{}.bincode", agent_id))
+}
+
+/// Why an entry's header rules it out, if it does
+fn check_header(reader: &mut impl Read) -> std::result::Result<(), String> {
+    let mut header = [0u8; 8];
+    reader
+        .read_exact(&mut header)
+        .map_err(|e| format!("no header: {}", e))?;
+    if header[..4] != CACHE_MAGIC {
+        return Err("written before cache entries were versioned".to_string());
+    }
+    let version = u32::from_le_bytes(header[4..].try_into().unwrap());
+    if version != CACHE_SCHEMA_VERSION {
+        return Err(format!(
+            "cache schema version {}, this analyzer reads version {}",
+            version, CACHE_SCHEMA_VERSION
+        ));
+    }
+    Ok(())
+}
+
+/// The cached data, if the entry exists, has the current schema and was
+/// parsed from `fingerprint`
+fn load_entry(path: &Path, fingerprint: &Fingerprint) -> Option<NodeLogData> {
+    let file = File::open(path).ok()?;
+    let mut reader = BufReader::new(zstd::Decoder::new(file).ok()?);
+    if let Err(reason) = check_header(&mut reader) {
+        log::warn!(
+            "Cache entry {} not loaded ({}); re-parsing",
+            path.display(),
+            reason
+        );
+        return None;
+    }
+    let stored: Fingerprint = bincode::deserialize_from(&mut reader).ok()?;
+    if &stored != fingerprint {
+        return None;
+    }
+    match bincode::deserialize_from(reader) {
+        Ok(data) => Some(data),
+        Err(e) => {
+            log::warn!(
+                "Cache entry {} unreadable although its schema version matches \
+                 (bump CACHE_SCHEMA_VERSION when NodeLogData changes): {}",
+                path.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Write one entry (atomic write via tmp+rename)
+fn save_entry(path: &Path, fingerprint: &Fingerprint, data: &NodeLogData) -> Result<()> {
+    let tmp_path = path.with_extension("bincode.tmp");
+    let file = File::create(&tmp_path)
+        .with_context(|| format!("Failed to create cache tmp file: {}", tmp_path.display()))?;
+    // zstd level 3 is a good balance of speed and compression
+    let mut encoder = zstd::Encoder::new(file, 3).context("Failed to create zstd encoder")?;
+    encoder
+        .write_all(&CACHE_MAGIC)
+        .and_then(|()| encoder.write_all(&CACHE_SCHEMA_VERSION.to_le_bytes()))
+        .context("Failed to write cache header")?;
+    bincode::serialize_into(&mut encoder, fingerprint)
+        .and_then(|()| bincode::serialize_into(&mut encoder, data))
+        .context("Failed to serialize log data to bincode+zstd")?;
+    encoder
+        .finish()
+        .context("Failed to finish zstd compression")?;
+    fs::rename(&tmp_path, path)
+        .with_context(|| format!("Failed to rename cache file to {}", path.display()))
+}
+
+/// [`parse_all_logs_reduced`](super::parse_all_logs_reduced) with a
+/// per-host cache in `cache_dir`: unchanged hosts are loaded, the others
+/// parsed in parallel and their entries rewritten. An entry parsed with
+/// another reduction counts as changed. A cache that cannot be written only
+/// costs the speedup.
+pub fn parse_all_logs_cached(
+    log_dir: &Path,
+    agents: &[AnalysisAgentInfo],
+    cache_dir: &Path,
+    reduction: &LogReduction,
+) -> Result<HashMap<String, NodeLogData>> {
+    Ok(load_or_parse(log_dir, agents, cache_dir, reduction)?.0)
+}
+
+/// The parsed logs, and the ids of the hosts that were parsed rather than
+/// loaded
+fn load_or_parse(
+    log_dir: &Path,
+    agents: &[AnalysisAgentInfo],
+    cache_dir: &Path,
+    reduction: &LogReduction,
+) -> Result<(HashMap<String, NodeLogData>, Vec<String>)> {
+    if let Err(e) = fs::create_dir_all(cache_dir) {
+        log::warn!(
+            "Failed to create cache dir {}, parsing without cache: {}",
+            cache_dir.display(),
+            e
+        );
+        let log_data = super::parse_all_logs_reduced(log_dir, agents, reduction)?;
+        let mut parsed: Vec<String> = log_data.keys().cloned().collect();
+        parsed.sort();
+        return Ok((log_data, parsed));
+    }
+    log::info!(
+        "Loading logs for {} agents from {} (cache: {})...",
+        agents.len(),
+        log_dir.display(),
+        cache_dir.display()
+    );
+
+    let results: Vec<(String, NodeLogData, bool)> = agents
+        .par_iter()
+        .filter_map(|agent| {
+            let logs = node_log_files(log_dir, &agent.id)?;
+            let fingerprint = Fingerprint::of(&logs, reduction);
+            let path = entry_path(cache_dir, &agent.id);
+
+            if let Some(data) = fingerprint.as_ref().and_then(|f| load_entry(&path, f)) {
+                log::debug!("{}: cache hit", agent.id);
+                return Some((agent.id.clone(), data, false));
+            }
+            log::info!("{}: cache miss, parsing", agent.id);
+            let data = parse_node_logs(&agent.id, &logs, reduction)?;
+            if let Some(ref fingerprint) = fingerprint {
+                if let Err(e) = save_entry(&path, fingerprint, &data) {
+                    log::warn!("Failed to write cache entry for {}: {}", agent.id, e);
+                }
+            }
+            Some((agent.id.clone(), data, true))
+        })
+        .collect();
+
+    let mut parsed: Vec<String> = results
+        .iter()
+        .filter(|(_, _, parsed)| *parsed)
+        .map(|(id, _, _)| id.clone())
+        .collect();
+    parsed.sort();
+    log::info!(
+        "Loaded {} nodes: {} from cache, {} parsed",
+        results.len(),
+        results.len() - parsed.len(),
+        parsed.len()
+    );
+
+    let log_data = results
+        .into_iter()
+        .map(|(id, data, _)| (id, data))
+        .collect();
+    Ok((log_data, parsed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::synth::{generate, write_dataset, SynthOptions};
+
+    #[test]
+    fn touching_one_host_reparses_only_that_host() {
+        let run = generate(&SynthOptions {
+            agents: 5,
+            txs: 10,
+            ..SynthOptions::default()
+        });
+        let tmp = tempfile::TempDir::new().unwrap();
+        write_dataset(&run, 0, tmp.path()).unwrap();
+        let log_dir = tmp.path().join("daemon_logs");
+        let cache_dir = tmp.path().join("parsed_logs");
+        let exact = LogReduction::default();
+
+        let (first, parsed) = load_or_parse(&log_dir, &run.agents, &cache_dir, &exact).unwrap();
+        assert_eq!(parsed.len(), run.agents.len());
+
+        let (second, parsed) = load_or_parse(&log_dir, &run.agents, &cache_dir, &exact).unwrap();
+        assert!(parsed.is_empty(), "{:?}", parsed);
+        assert_eq!(
+            serde_json::to_value(&second).unwrap(),
+            serde_json::to_value(&first).unwrap()
+        );
+
+        let touched = &run.agents[2].id;
+        let log = log_dir
+            .join(format!("monero-{}", touched))
+            .join("bitmonero.log");
+        File::options()
+            .append(true)
+            .open(&log)
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(5))
+            .unwrap();
+        let (_, parsed) = load_or_parse(&log_dir, &run.agents, &cache_dir, &exact).unwrap();
+        assert_eq!(parsed, std::slice::from_ref(touched));
+    }
+
+    #[test]
+    fn changing_the_reduction_reparses() {
+        let run = generate(&SynthOptions {
+            agents: 3,
+            txs: 5,
+            ..SynthOptions::default()
+        });
+        let tmp = tempfile::TempDir::new().unwrap();
+        write_dataset(&run, 0, tmp.path()).unwrap();
+        let log_dir = tmp.path().join("daemon_logs");
+        let cache_dir = tmp.path().join("parsed_logs");
+        let sampled = LogReduction {
+            sample_bandwidth: 4,
+            ..LogReduction::default()
+        };
+
+        load_or_parse(&log_dir, &run.agents, &cache_dir, &LogReduction::default()).unwrap();
+        let (data, parsed) = load_or_parse(&log_dir, &run.agents, &cache_dir, &sampled).unwrap();
+        assert_eq!(parsed.len(), run.agents.len());
+        assert!(data
+            .values()
+            .flat_map(|d| &d.bandwidth_events)
+            .any(|e| e.messages == 4));
+        let (_, parsed) = load_or_parse(&log_dir, &run.agents, &cache_dir, &sampled).unwrap();
+        assert!(parsed.is_empty(), "{:?}", parsed);
+    }
+
+    #[test]
+    fn entries_from_other_schemas_are_reparsed() {
+        let run = generate(&SynthOptions {
+            agents: 3,
+            txs: 5,
+            ..SynthOptions::default()
+        });
+        let tmp = tempfile::TempDir::new().unwrap();
+        write_dataset(&run, 0, tmp.path()).unwrap();
+        let log_dir = tmp.path().join("daemon_logs");
+        let cache_dir = tmp.path().join("parsed_logs");
+        let exact = LogReduction::default();
+        let (first, _) = load_or_parse(&log_dir, &run.agents, &cache_dir, &exact).unwrap();
+
+        let id = &run.agents[0].id;
+        let path = entry_path(&cache_dir, id);
+        let fingerprint = Fingerprint::of(&node_log_files(&log_dir, id).unwrap(), &exact).unwrap();
+        let rewrite = |header: &[u8]| {
+            let mut encoder = zstd::Encoder::new(File::create(&path).unwrap(), 3).unwrap();
+            encoder.write_all(header).unwrap();
+            bincode::serialize_into(&mut encoder, &fingerprint).unwrap();
+            bincode::serialize_into(&mut encoder, &first[id]).unwrap();
+            encoder.finish().unwrap();
+        };
+
+        // An entry from before the header, and one from a newer schema
+        let newer = [&CACHE_MAGIC[..], &(CACHE_SCHEMA_VERSION + 1).to_le_bytes()].concat();
+        for header in [&[][..], &newer[..]] {
+            rewrite(header);
+            let reader =
+                &mut BufReader::new(zstd::Decoder::new(File::open(&path).unwrap()).unwrap());
+            assert!(check_header(reader).is_err());
+            assert!(load_entry(&path, &fingerprint).is_none());
+
+            let (again, parsed) = load_or_parse(&log_dir, &run.agents, &cache_dir, &exact).unwrap();
+            assert_eq!(parsed, std::slice::from_ref(id));
+            assert_eq!(
+                serde_json::to_value(&again[id]).unwrap(),
+                serde_json::to_value(&first[id]).unwrap()
+            );
+            // Rewritten with the current header
+            assert!(load_entry(&path, &fingerprint).is_some());
+        }
+
+        let err = check_header(&mut &newer[..]).unwrap_err();
+        assert!(err.contains("this analyzer reads version"), "{}", err);
+        let err = check_header(&mut &b"\x05\0\0\0\0\0\0\0"[..]).unwrap_err();
+        assert!(
+            err.contains("before cache entries were versioned"),
+            "{}",
+            err
+        );
+    }
+}
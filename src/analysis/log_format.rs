@@ -0,0 +1,261 @@
+//! Monerod log line formats.
+//!
+//! Single source of truth for the lines `log_parser` reads: the parser
+//! builds its regexes from the literals here, and the writers here
+//! (used by the synthetic data generator) produce exactly those lines. A
+//! format change therefore lands on both sides at once, and the round-trip
+//! test below fails if they ever disagree.
+
+use super::types::*;
+
+/// Timestamp at the start of every line, e.g. "2000-01-01 04:00:05.464"
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+pub const INBOUND_TAG: &str = "INC";
+pub const OUTBOUND_TAG: &str = "OUT";
+
+pub const NOTIFY_NEW_TRANSACTIONS: &str = "Received NOTIFY_NEW_TRANSACTIONS";
+pub const INCLUDING_TRANSACTION: &str = "Including transaction";
+pub const TX_ADDED_TO_POOL: &str = "Transaction added to pool: txid";
+pub const NEW_CONNECTION: &str = "NEW CONNECTION";
+pub const CLOSE_CONNECTION: &str = "CLOSE CONNECTION";
+pub const NOTIFY_NEW_FLUFFY_BLOCK: &str = "Received NOTIFY_NEW_FLUFFY_BLOCK";
+pub const BLOCK_ADDED: &str = "+++++ BLOCK SUCCESSFULLY ADDED";
+/// Follows [`BLOCK_ADDED`]: "HEIGHT N, difficulty: D"
+pub const BLOCK_HEIGHT: &str = "HEIGHT";
+pub const NOTIFY_TX_POOL_HASH: &str = "Received NOTIFY_TX_POOL_HASH";
+pub const NOTIFY_REQUEST_TX_POOL_TXS: &str = "NOTIFY_REQUEST_TX_POOL_TXS";
+pub const TX_VERIFICATION_DROP: &str = "Tx verification failed, dropping connection";
+pub const DROPPING_CONNECTION: &str = "dropping connection";
+
+/// Render a simulation timestamp the way monerod prints it
+pub fn format_timestamp(t: SimTime) -> String {
+    let millis = (t * 1000.0).round() as i64;
+    chrono::DateTime::from_timestamp_millis(millis)
+        .map(|dt| dt.naive_utc().format(TIMESTAMP_FORMAT).to_string())
+        .unwrap_or_default()
+}
+
+pub fn direction_tag(direction: ConnectionDirection) -> &'static str {
+    match direction {
+        ConnectionDirection::Inbound => INBOUND_TAG,
+        ConnectionDirection::Outbound => OUTBOUND_TAG,
+    }
+}
+
+/// One full log line: timestamp, level, message
+pub fn line(t: SimTime, message: &str) -> String {
+    format!("{}\tI {}", format_timestamp(t), message)
+}
+
+/// "[IP:PORT DIR]"
+fn peer(ip: &str, port: u16, direction: ConnectionDirection) -> String {
+    format!("[{}:{} {}]", ip, port, direction_tag(direction))
+}
+
+pub fn tx_notification(
+    ip: &str,
+    port: u16,
+    direction: ConnectionDirection,
+    count: usize,
+) -> String {
+    format!(
+        "{} {} ({} txes)",
+        peer(ip, port, direction),
+        NOTIFY_NEW_TRANSACTIONS,
+        count
+    )
+}
+
+pub fn including_transaction(tx_hash: &str) -> String {
+    format!("{} <{}>", INCLUDING_TRANSACTION, tx_hash)
+}
+
+pub fn connection(event: &ConnectionEvent) -> String {
+    format!(
+        "[{}:{} {} {}] {}",
+        event.peer_ip,
+        event.peer_port,
+        event.connection_id,
+        direction_tag(event.direction),
+        if event.is_open {
+            NEW_CONNECTION
+        } else {
+            CLOSE_CONNECTION
+        }
+    )
+}
+
+pub fn fluffy_block(ip: &str, port: u16, block_hash: &str, height: u64) -> String {
+    format!(
+        "{} {} <{}> (height {}, 1 txes)",
+        peer(ip, port, ConnectionDirection::Inbound),
+        NOTIFY_NEW_FLUFFY_BLOCK,
+        block_hash,
+        height
+    )
+}
+
+pub fn block_height(height: u64, difficulty: u64) -> String {
+    format!("{} {}, difficulty: {}", BLOCK_HEIGHT, height, difficulty)
+}
+
+pub fn bandwidth(event: &BandwidthEvent) -> String {
+    format!(
+        "{} {} bytes {} for category {} initiated by {}",
+        peer(&event.peer_ip, event.peer_port, event.direction),
+        event.bytes,
+        if event.is_sent { "sent" } else { "received" },
+        event.command_category,
+        if event.initiated_by_us { "us" } else { "peer" }
+    )
+}
+
+/// Render the events of one node as a monerod log, in time order.
+///
+/// Covers what the generator produces (tx observations, connections, block
+/// observations, bandwidth); tx relay v2 and drop events are not written.
+/// Parsing the result yields the same data, except that locally mined
+/// blocks lose their hash (monerod does not log it on that path).
+pub fn render_log(data: &NodeLogData) -> String {
+    let mut entries: Vec<(SimTime, Vec<String>)> = Vec::new();
+    for obs in &data.tx_observations {
+        entries.push((
+            obs.timestamp,
+            vec![
+                tx_notification(&obs.source_ip, obs.source_port, obs.direction, 1),
+                including_transaction(&obs.tx_hash),
+            ],
+        ));
+    }
+    for event in &data.connection_events {
+        entries.push((event.timestamp, vec![connection(event)]));
+    }
+    for obs in &data.block_observations {
+        let lines = match (&obs.source_ip, obs.is_local) {
+            (Some(ip), false) => vec![fluffy_block(
+                ip,
+                crate::MONERO_P2P_PORT,
+                &obs.block_hash,
+                obs.height,
+            )],
+            _ => vec![BLOCK_ADDED.to_string(), block_height(obs.height, 1)],
+        };
+        entries.push((obs.timestamp, lines));
+    }
+    for event in &data.bandwidth_events {
+        entries.push((event.timestamp, vec![bandwidth(event)]));
+    }
+    entries.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut out = String::new();
+    for (t, lines) in entries {
+        for message in lines {
+            out.push_str(&line(t, &message));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::log_parser::parse_log_file;
+    use crate::analysis::synth::{generate, SynthOptions};
+
+    fn ms(t: SimTime) -> i64 {
+        (t * 1000.0).round() as i64
+    }
+
+    /// Order-insensitive comparison on the fields the log line carries
+    fn assert_same<T, K: Ord + std::fmt::Debug>(
+        what: &str,
+        want: &[T],
+        got: &[T],
+        key: impl Fn(&T) -> K,
+    ) {
+        let mut want: Vec<K> = want.iter().map(&key).collect();
+        let mut got: Vec<K> = got.iter().map(&key).collect();
+        want.sort();
+        got.sort();
+        assert_eq!(got, want, "{}", what);
+    }
+
+    #[test]
+    fn rendered_logs_parse_back_to_the_same_events() {
+        let run = generate(&SynthOptions {
+            agents: 6,
+            txs: 10,
+            ..SynthOptions::default()
+        });
+        let tmp = tempfile::TempDir::new().unwrap();
+
+        for (id, expected) in &run.log_data {
+            let path = tmp.path().join(format!("{}.log", id));
+            std::fs::write(&path, render_log(expected)).unwrap();
+            let parsed = parse_log_file(&path, id).unwrap();
+
+            assert_same(
+                id,
+                &expected.tx_observations,
+                &parsed.tx_observations,
+                |o| {
+                    (
+                        ms(o.timestamp),
+                        o.tx_hash.clone(),
+                        o.source_ip.clone(),
+                        o.source_port,
+                    )
+                },
+            );
+            assert_same(
+                id,
+                &expected.connection_events,
+                &parsed.connection_events,
+                |e| {
+                    (
+                        ms(e.timestamp),
+                        e.peer_ip.clone(),
+                        e.connection_id.clone(),
+                        direction_tag(e.direction),
+                        e.is_open,
+                    )
+                },
+            );
+            assert_same(
+                id,
+                &expected.block_observations,
+                &parsed.block_observations,
+                |o| {
+                    let hash = if o.is_local {
+                        ""
+                    } else {
+                        o.block_hash.as_str()
+                    };
+                    (
+                        ms(o.timestamp),
+                        o.height,
+                        hash.to_string(),
+                        o.source_ip.clone(),
+                        o.is_local,
+                    )
+                },
+            );
+            assert_same(
+                id,
+                &expected.bandwidth_events,
+                &parsed.bandwidth_events,
+                |e| {
+                    (
+                        ms(e.timestamp),
+                        e.peer_ip.clone(),
+                        e.bytes,
+                        e.is_sent,
+                        e.command_category.clone(),
+                        e.initiated_by_us,
+                    )
+                },
+            );
+        }
+    }
+}
@@ -3,16 +3,19 @@
 //! Parses transaction observations, connection events, and block observations
 //! from monerod log files using streaming and parallel processing.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 
 use color_eyre::eyre::{Context, Result};
 use rayon::prelude::*;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 
+use super::log_format::*;
 use super::types::*;
 
 /// Compiled regex patterns for log parsing
@@ -54,56 +57,106 @@ pub struct LogPatterns {
 
 impl LogPatterns {
     pub fn new() -> Self {
+        // Message literals come from `log_format` so the synthetic generator
+        // writes exactly what is matched here.
+        let e = regex::escape;
+        let ip_port_dir = format!(
+            r"\[(\d+\.\d+\.\d+\.\d+):(\d+)\s+({}|{})\]",
+            INBOUND_TAG, OUTBOUND_TAG
+        );
+        let ip_port_id_dir = format!(
+            r"\[(\d+\.\d+\.\d+\.\d+):(\d+)\s+([a-f0-9-]+)\s+({}|{})\]",
+            INBOUND_TAG, OUTBOUND_TAG
+        );
+        let re = |pattern: String, name: &str| {
+            Regex::new(&pattern).unwrap_or_else(|_| panic!("Invalid {} regex", name))
+        };
         Self {
-            tx_notification: Regex::new(
-                r"\[(\d+\.\d+\.\d+\.\d+):(\d+)\s+(INC|OUT)\]\s+Received NOTIFY_NEW_TRANSACTIONS \((\d+) txes\)"
-            ).expect("Invalid tx_notification regex"),
-            tx_hash: Regex::new(
-                r"Including transaction <([a-f0-9]{64})>"
-            ).expect("Invalid tx_hash regex"),
-            tx_added_to_pool: Regex::new(
-                r"Transaction added to pool: txid <([a-f0-9]{64})>"
-            ).expect("Invalid tx_added_to_pool regex"),
-            connection_open: Regex::new(
-                r"\[(\d+\.\d+\.\d+\.\d+):(\d+)\s+([a-f0-9-]+)\s+(INC|OUT)\]\s+NEW CONNECTION"
-            ).expect("Invalid connection_open regex"),
-            connection_close: Regex::new(
-                r"\[(\d+\.\d+\.\d+\.\d+):(\d+)\s+([a-f0-9-]+)\s+(INC|OUT)\]\s+CLOSE CONNECTION"
-            ).expect("Invalid connection_close regex"),
-            block_received: Regex::new(
-                r"\[(\d+\.\d+\.\d+\.\d+):\d+\s+(INC|OUT)\].*Received NOTIFY_NEW_FLUFFY_BLOCK <([a-f0-9]{64})> \(height (\d+)"
-            ).expect("Invalid block_received regex"),
-            block_mined: Regex::new(
-                r"\+\+\+\+\+ BLOCK SUCCESSFULLY ADDED"
-            ).expect("Invalid block_mined regex"),
-            block_height_line: Regex::new(
-                r"HEIGHT (\d+), difficulty:"
-            ).expect("Invalid block_height_line regex"),
-            timestamp: Regex::new(
-                r"^(\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}\.\d+)"
-            ).expect("Invalid timestamp regex"),
+            tx_notification: re(
+                format!(
+                    r"{}\s+{} \((\d+) txes\)",
+                    ip_port_dir,
+                    e(NOTIFY_NEW_TRANSACTIONS)
+                ),
+                "tx_notification",
+            ),
+            tx_hash: re(
+                format!(r"{} <([a-f0-9]{{64}})>", e(INCLUDING_TRANSACTION)),
+                "tx_hash",
+            ),
+            tx_added_to_pool: re(
+                format!(r"{} <([a-f0-9]{{64}})>", e(TX_ADDED_TO_POOL)),
+                "tx_added_to_pool",
+            ),
+            connection_open: re(
+                format!(r"{}\s+{}", ip_port_id_dir, e(NEW_CONNECTION)),
+                "connection_open",
+            ),
+            connection_close: re(
+                format!(r"{}\s+{}", ip_port_id_dir, e(CLOSE_CONNECTION)),
+                "connection_close",
+            ),
+            block_received: re(
+                format!(
+                    r"\[(\d+\.\d+\.\d+\.\d+):\d+\s+({}|{})\].*{} <([a-f0-9]{{64}})> \(height (\d+)",
+                    INBOUND_TAG,
+                    OUTBOUND_TAG,
+                    e(NOTIFY_NEW_FLUFFY_BLOCK)
+                ),
+                "block_received",
+            ),
+            block_mined: re(e(BLOCK_ADDED), "block_mined"),
+            block_height_line: re(
+                format!(r"{} (\d+), difficulty:", e(BLOCK_HEIGHT)),
+                "block_height_line",
+            ),
+            timestamp: re(
+                r"^(\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}\.\d+)".to_string(),
+                "timestamp",
+            ),
             // TX Relay V2 patterns
-            tx_pool_hash: Regex::new(
-                r"\[(\d+\.\d+\.\d+\.\d+):(\d+)\s+(INC|OUT)\]\s+Received NOTIFY_TX_POOL_HASH \((\d+) txes\)"
-            ).expect("Invalid tx_pool_hash regex"),
-            tx_pool_request_received: Regex::new(
-                r"\[(\d+\.\d+\.\d+\.\d+):(\d+)\s+(INC|OUT)\]\s+Received NOTIFY_REQUEST_TX_POOL_TXS \((\d+) txes\)"
-            ).expect("Invalid tx_pool_request_received regex"),
-            tx_pool_request_sent: Regex::new(
-                r"Requesting (\d+) transactions via NOTIFY_REQUEST_TX_POOL_TXS"
-            ).expect("Invalid tx_pool_request_sent regex"),
-            drop_tx_verification: Regex::new(
-                r"Tx verification failed, dropping connection"
-            ).expect("Invalid drop_tx_verification regex"),
-            drop_duplicate_tx: Regex::new(
-                r"Duplicate transaction.*dropping connection"
-            ).expect("Invalid drop_duplicate_tx regex"),
-            drop_connection: Regex::new(
-                r"\[(\d+\.\d+\.\d+\.\d+):\d+.*\].*dropping connection"
-            ).expect("Invalid drop_connection regex"),
-            bandwidth: Regex::new(
-                r"\[(\d+\.\d+\.\d+\.\d+):(\d+)\s+(INC|OUT)\]\s+(\d+)\s+bytes\s+(sent|received)\s+for\s+category\s+(command-\d+)\s+initiated\s+by\s+(us|peer)"
-            ).expect("Invalid bandwidth regex"),
+            tx_pool_hash: re(
+                format!(
+                    r"{}\s+{} \((\d+) txes\)",
+                    ip_port_dir,
+                    e(NOTIFY_TX_POOL_HASH)
+                ),
+                "tx_pool_hash",
+            ),
+            tx_pool_request_received: re(
+                format!(
+                    r"{}\s+Received {} \((\d+) txes\)",
+                    ip_port_dir,
+                    e(NOTIFY_REQUEST_TX_POOL_TXS)
+                ),
+                "tx_pool_request_received",
+            ),
+            tx_pool_request_sent: re(
+                format!(
+                    r"Requesting (\d+) transactions via {}",
+                    e(NOTIFY_REQUEST_TX_POOL_TXS)
+                ),
+                "tx_pool_request_sent",
+            ),
+            drop_tx_verification: re(e(TX_VERIFICATION_DROP), "drop_tx_verification"),
+            drop_duplicate_tx: re(
+                format!(r"Duplicate transaction.*{}", e(DROPPING_CONNECTION)),
+                "drop_duplicate_tx",
+            ),
+            drop_connection: re(
+                format!(
+                    r"\[(\d+\.\d+\.\d+\.\d+):\d+.*\].*{}",
+                    e(DROPPING_CONNECTION)
+                ),
+                "drop_connection",
+            ),
+            bandwidth: re(
+                format!(
+                    r"{}\s+(\d+)\s+bytes\s+(sent|received)\s+for\s+category\s+(command-\d+)\s+initiated\s+by\s+(us|peer)",
+                    ip_port_dir
+                ),
+                "bandwidth",
+            ),
         }
     }
 }
@@ -118,20 +171,99 @@ pub fn parse_timestamp(s: &str) -> Option<SimTime> {
     let ts_str = caps.get(1)?.as_str();
 
     // Parse using chrono
-    let dt = chrono::NaiveDateTime::parse_from_str(ts_str, "%Y-%m-%d %H:%M:%S%.3f").ok()?;
+    let dt = chrono::NaiveDateTime::parse_from_str(ts_str, TIMESTAMP_FORMAT).ok()?;
     Some(dt.and_utc().timestamp() as f64 + dt.and_utc().timestamp_subsec_millis() as f64 / 1000.0)
 }
 
 /// Parse connection direction from string
 fn parse_direction(s: &str) -> ConnectionDirection {
     match s {
-        "INC" => ConnectionDirection::Inbound,
-        "OUT" => ConnectionDirection::Outbound,
+        INBOUND_TAG => ConnectionDirection::Inbound,
+        OUTBOUND_TAG => ConnectionDirection::Outbound,
         _ => ConnectionDirection::Inbound, // Default
     }
 }
 
+/// Event types the parser can be told to leave out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum EventKind {
+    /// `bandwidth_events`
+    Bandwidth,
+    /// `connection_events` and `connection_drops`
+    Connections,
+}
+
+/// `bandwidth` or `connections`
+impl std::str::FromStr for EventKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bandwidth" => Ok(EventKind::Bandwidth),
+            "connections" => Ok(EventKind::Connections),
+            _ => Err(format!(
+                "invalid event type '{}' (expected bandwidth or connections)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EventKind::Bandwidth => "bandwidth",
+            EventKind::Connections => "connections",
+        })
+    }
+}
+
+/// What the parser keeps of the high-volume event types. The default keeps
+/// every event as logged.
+///
+/// Bandwidth events that stand for several log entries carry the entry
+/// count in `BandwidthEvent::messages` and the summed bytes, so the
+/// bandwidth analyses report (approximately, when sampled) the same totals.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LogReduction {
+    /// Keep the first of every N bandwidth entries per command category and
+    /// direction, scaled by N (0 or 1 keeps all). Message counts stay exact;
+    /// bytes are estimated from the kept entries.
+    pub sample_bandwidth: u64,
+    /// Sum bandwidth entries into windows of this many seconds, one event
+    /// per window, peer, direction and category, stamped with the window
+    /// start
+    pub aggregate_bandwidth_sec: Option<f64>,
+    /// Event types not recorded at all
+    pub skip: BTreeSet<EventKind>,
+}
+
+impl LogReduction {
+    /// True if this keeps every event as logged
+    pub fn is_exact(&self) -> bool {
+        self.sample_bandwidth <= 1 && self.aggregate_bandwidth_sec.is_none() && self.skip.is_empty()
+    }
+
+    pub fn skips(&self, kind: EventKind) -> bool {
+        self.skip.contains(&kind)
+    }
+
+    /// Only the skipped types: which lines carry events does not depend on
+    /// sampling or binning, so a [`LogIndex`] is built with this
+    fn skips_only(&self) -> Self {
+        Self {
+            skip: self.skip.clone(),
+            ..Self::default()
+        }
+    }
+}
+
+/// A pre-binned bandwidth event: window index, peer ip and port, direction,
+/// sent, category and initiator
+type BandwidthBin = (u64, String, u16, ConnectionDirection, bool, String, bool);
+
 /// State for multi-line parsing
+#[derive(Default)]
 struct ParseState {
     /// Pending TX notification context (source_ip, source_port, direction, timestamp)
     pending_tx_notification: Option<(String, u16, ConnectionDirection, SimTime)>,
@@ -139,104 +271,218 @@ struct ParseState {
     pending_block_mined: bool,
     /// Last seen timestamp
     last_timestamp: SimTime,
+    reduction: LogReduction,
+    /// Sampling progress per (category, sent)
+    bandwidth_samples: HashMap<(String, bool), SampleGroup>,
+    /// Index in `bandwidth_events` of each bin of the current window
+    bandwidth_bins: HashMap<BandwidthBin, usize>,
+    bandwidth_window: u64,
 }
 
-impl Default for ParseState {
-    fn default() -> Self {
+/// Bandwidth entries seen of one category and direction, and where the last
+/// kept one went
+#[derive(Default)]
+struct SampleGroup {
+    seen: u64,
+    /// Index in `bandwidth_events` and logged bytes of the last kept entry
+    last_kept: Option<(usize, u64)>,
+}
+
+impl ParseState {
+    fn new(reduction: &LogReduction) -> Self {
         Self {
-            pending_tx_notification: None,
-            pending_block_mined: false,
-            last_timestamp: 0.0,
+            reduction: reduction.clone(),
+            ..Self::default()
+        }
+    }
+
+    /// Record a logged bandwidth entry as the reduction asks
+    fn record_bandwidth(&mut self, mut event: BandwidthEvent, data: &mut NodeLogData) {
+        let n = self.reduction.sample_bandwidth;
+        let mut sample = None;
+        if n > 1 {
+            let group = self
+                .bandwidth_samples
+                .entry((event.command_category.clone(), event.is_sent))
+                .or_default();
+            let keep = group.seen % n == 0;
+            group.seen += 1;
+            if !keep {
+                return;
+            }
+            sample = Some((event.command_category.clone(), event.is_sent, event.bytes));
+            event.bytes *= n;
+            event.messages *= n;
+        }
+
+        let index = self.place_bandwidth(event, data);
+        if let Some((category, is_sent, bytes)) = sample {
+            if let Some(group) = self.bandwidth_samples.get_mut(&(category, is_sent)) {
+                group.last_kept = Some((index, bytes));
+            }
         }
     }
+
+    /// Push `event`, or add it to its window's bin; where it went
+    fn place_bandwidth(&mut self, mut event: BandwidthEvent, data: &mut NodeLogData) -> usize {
+        let Some(window_sec) = self.reduction.aggregate_bandwidth_sec else {
+            data.bandwidth_events.push(event);
+            return data.bandwidth_events.len() - 1;
+        };
+        let window = (event.timestamp / window_sec).floor().max(0.0) as u64;
+        if window != self.bandwidth_window {
+            // Timestamps only grow within a file, so earlier bins are final
+            self.bandwidth_bins.clear();
+            self.bandwidth_window = window;
+        }
+        let bin = (
+            window,
+            event.peer_ip.clone(),
+            event.peer_port,
+            event.direction,
+            event.is_sent,
+            event.command_category.clone(),
+            event.initiated_by_us,
+        );
+        match self.bandwidth_bins.get(&bin) {
+            Some(&i) => {
+                let binned = &mut data.bandwidth_events[i];
+                binned.bytes += event.bytes;
+                binned.messages += event.messages;
+                i
+            }
+            None => {
+                let i = data.bandwidth_events.len();
+                self.bandwidth_bins.insert(bin, i);
+                event.timestamp = window as f64 * window_sec;
+                data.bandwidth_events.push(event);
+                i
+            }
+        }
+    }
+
+    /// At the end of a file, scale the last kept sample of each group by
+    /// the entries actually left after it rather than N, so message counts
+    /// come out exact
+    fn finish(&mut self, data: &mut NodeLogData) {
+        let n = self.reduction.sample_bandwidth;
+        for group in self.bandwidth_samples.values() {
+            let left = group.seen % n;
+            if let (Some((i, bytes)), true) = (group.last_kept, left > 0) {
+                let event = &mut data.bandwidth_events[i];
+                event.messages -= n - left;
+                event.bytes -= (n - left) * bytes;
+            }
+        }
+        self.bandwidth_samples.clear();
+    }
 }
 
 /// Parse a single log file
 pub fn parse_log_file(path: &Path, node_id: &str) -> Result<NodeLogData> {
+    parse_log_file_reduced(path, node_id, &LogReduction::default())
+}
+
+/// [`parse_log_file`], keeping what `reduction` asks for
+pub fn parse_log_file_reduced(
+    path: &Path,
+    node_id: &str,
+    reduction: &LogReduction,
+) -> Result<NodeLogData> {
     let file =
         File::open(path).with_context(|| format!("Failed to open log file: {}", path.display()))?;
     let reader = BufReader::with_capacity(64 * 1024, file);
 
     let mut data = NodeLogData::new(node_id.to_string());
-    let mut state = ParseState::default();
+    let mut state = ParseState::new(reduction);
 
     for line_result in reader.lines() {
         let line = match line_result {
             Ok(l) => l,
             Err(_) => continue, // Skip malformed lines
         };
+        parse_line(&line, node_id, &mut state, &mut data);
+    }
+    state.finish(&mut data);
 
-        // Try to parse timestamp
-        if let Some(ts) = parse_timestamp(&line) {
-            state.last_timestamp = ts;
-        }
-
-        // Check for TX notification (sets up context for following TX hash lines)
-        if let Some(caps) = PATTERNS.tx_notification.captures(&line) {
-            let source_ip = caps
-                .get(1)
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default();
-            let source_port: u16 = caps
-                .get(2)
-                .and_then(|m| m.as_str().parse().ok())
-                .unwrap_or(0);
-            let direction = parse_direction(caps.get(3).map(|m| m.as_str()).unwrap_or(""));
-            let tx_count: u32 = caps
-                .get(4)
-                .and_then(|m| m.as_str().parse().ok())
-                .unwrap_or(0);
+    Ok(data)
+}
 
-            if tx_count > 0 {
-                state.pending_tx_notification =
-                    Some((source_ip, source_port, direction, state.last_timestamp));
-            }
-            continue;
-        }
+/// Feed one log line to the parser, recording any event it carries in
+/// `data`
+fn parse_line(line: &str, node_id: &str, state: &mut ParseState, data: &mut NodeLogData) {
+    // Try to parse timestamp
+    if let Some(ts) = parse_timestamp(line) {
+        state.last_timestamp = ts;
+    }
 
-        // Check for TX hash (immediately follows notification)
-        if let Some(caps) = PATTERNS.tx_hash.captures(&line) {
-            if let Some((ref source_ip, source_port, direction, timestamp)) =
-                state.pending_tx_notification
-            {
-                let tx_hash = caps
-                    .get(1)
-                    .map(|m| m.as_str().to_string())
-                    .unwrap_or_default();
-                data.tx_observations.push(TxObservation {
-                    tx_hash,
-                    node_id: node_id.to_string(),
-                    timestamp,
-                    source_ip: source_ip.clone(),
-                    source_port,
-                    direction,
-                });
-            }
-            // Don't clear pending_tx_notification - there may be multiple TXs in one notification
-            continue;
-        }
+    // Check for TX notification (sets up context for following TX hash lines)
+    if let Some(caps) = PATTERNS.tx_notification.captures(line) {
+        let source_ip = caps
+            .get(1)
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default();
+        let source_port: u16 = caps
+            .get(2)
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(0);
+        let direction = parse_direction(caps.get(3).map(|m| m.as_str()).unwrap_or(""));
+        let tx_count: u32 = caps
+            .get(4)
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(0);
 
-        // If we hit a non-TX-hash line, clear the pending notification
-        if state.pending_tx_notification.is_some() && !PATTERNS.tx_hash.is_match(&line) {
-            state.pending_tx_notification = None;
+        if tx_count > 0 {
+            state.pending_tx_notification =
+                Some((source_ip, source_port, direction, state.last_timestamp));
         }
+        return;
+    }
 
-        // Check for connection open
-        if let Some(caps) = PATTERNS.connection_open.captures(&line) {
-            let peer_ip = caps
+    // Check for TX hash (immediately follows notification)
+    if let Some(caps) = PATTERNS.tx_hash.captures(line) {
+        if let Some((ref source_ip, source_port, direction, timestamp)) =
+            state.pending_tx_notification
+        {
+            let tx_hash = caps
                 .get(1)
                 .map(|m| m.as_str().to_string())
                 .unwrap_or_default();
-            let peer_port: u16 = caps
-                .get(2)
-                .and_then(|m| m.as_str().parse().ok())
-                .unwrap_or(0);
-            let connection_id = caps
-                .get(3)
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default();
-            let direction = parse_direction(caps.get(4).map(|m| m.as_str()).unwrap_or(""));
+            data.tx_observations.push(TxObservation {
+                tx_hash,
+                node_id: node_id.to_string(),
+                timestamp,
+                source_ip: source_ip.clone(),
+                source_port,
+                direction,
+            });
+        }
+        // Don't clear pending_tx_notification - there may be multiple TXs in one notification
+        return;
+    }
+
+    // If we hit a non-TX-hash line, clear the pending notification
+    if state.pending_tx_notification.is_some() && !PATTERNS.tx_hash.is_match(line) {
+        state.pending_tx_notification = None;
+    }
 
+    // Check for connection open
+    if let Some(caps) = PATTERNS.connection_open.captures(line) {
+        let peer_ip = caps
+            .get(1)
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default();
+        let peer_port: u16 = caps
+            .get(2)
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(0);
+        let connection_id = caps
+            .get(3)
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default();
+        let direction = parse_direction(caps.get(4).map(|m| m.as_str()).unwrap_or(""));
+
+        if !state.reduction.skips(EventKind::Connections) {
             data.connection_events.push(ConnectionEvent {
                 timestamp: state.last_timestamp,
                 peer_ip,
@@ -245,25 +491,27 @@ pub fn parse_log_file(path: &Path, node_id: &str) -> Result<NodeLogData> {
                 direction,
                 is_open: true,
             });
-            continue;
         }
+        return;
+    }
 
-        // Check for connection close
-        if let Some(caps) = PATTERNS.connection_close.captures(&line) {
-            let peer_ip = caps
-                .get(1)
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default();
-            let peer_port: u16 = caps
-                .get(2)
-                .and_then(|m| m.as_str().parse().ok())
-                .unwrap_or(0);
-            let connection_id = caps
-                .get(3)
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default();
-            let direction = parse_direction(caps.get(4).map(|m| m.as_str()).unwrap_or(""));
+    // Check for connection close
+    if let Some(caps) = PATTERNS.connection_close.captures(line) {
+        let peer_ip = caps
+            .get(1)
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default();
+        let peer_port: u16 = caps
+            .get(2)
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(0);
+        let connection_id = caps
+            .get(3)
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default();
+        let direction = parse_direction(caps.get(4).map(|m| m.as_str()).unwrap_or(""));
 
+        if !state.reduction.skips(EventKind::Connections) {
             data.connection_events.push(ConnectionEvent {
                 timestamp: state.last_timestamp,
                 peer_ip,
@@ -272,130 +520,133 @@ pub fn parse_log_file(path: &Path, node_id: &str) -> Result<NodeLogData> {
                 direction,
                 is_open: false,
             });
-            continue;
         }
+        return;
+    }
 
-        // Check for block received
-        if let Some(caps) = PATTERNS.block_received.captures(&line) {
-            let source_ip = caps.get(1).map(|m| m.as_str().to_string());
-            let block_hash = caps
-                .get(3)
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default();
+    // Check for block received
+    if let Some(caps) = PATTERNS.block_received.captures(line) {
+        let source_ip = caps.get(1).map(|m| m.as_str().to_string());
+        let block_hash = caps
+            .get(3)
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default();
+        let height: u64 = caps
+            .get(4)
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(0);
+
+        data.block_observations.push(BlockObservation {
+            block_hash,
+            height,
+            node_id: node_id.to_string(),
+            timestamp: state.last_timestamp,
+            source_ip,
+            is_local: false,
+        });
+        return;
+    }
+
+    // Check for block mined locally
+    if PATTERNS.block_mined.is_match(line) {
+        state.pending_block_mined = true;
+        return;
+    }
+
+    // Check for block height (follows block mined)
+    if state.pending_block_mined {
+        if let Some(caps) = PATTERNS.block_height_line.captures(line) {
             let height: u64 = caps
-                .get(4)
+                .get(1)
                 .and_then(|m| m.as_str().parse().ok())
                 .unwrap_or(0);
 
             data.block_observations.push(BlockObservation {
-                block_hash,
+                block_hash: String::new(), // We don't have the hash from this line
                 height,
                 node_id: node_id.to_string(),
                 timestamp: state.last_timestamp,
-                source_ip,
-                is_local: false,
+                source_ip: None,
+                is_local: true,
             });
-            continue;
+            state.pending_block_mined = false;
         }
+    }
 
-        // Check for block mined locally
-        if PATTERNS.block_mined.is_match(&line) {
-            state.pending_block_mined = true;
-            continue;
-        }
+    // ================================================================
+    // TX Relay V2 Protocol Parsing
+    // ================================================================
 
-        // Check for block height (follows block mined)
-        if state.pending_block_mined {
-            if let Some(caps) = PATTERNS.block_height_line.captures(&line) {
-                let height: u64 = caps
-                    .get(1)
-                    .and_then(|m| m.as_str().parse().ok())
-                    .unwrap_or(0);
-
-                data.block_observations.push(BlockObservation {
-                    block_hash: String::new(), // We don't have the hash from this line
-                    height,
-                    node_id: node_id.to_string(),
-                    timestamp: state.last_timestamp,
-                    source_ip: None,
-                    is_local: true,
-                });
-                state.pending_block_mined = false;
-            }
-        }
+    // Check for TX pool hash announcement (v2)
+    if let Some(caps) = PATTERNS.tx_pool_hash.captures(line) {
+        let source_ip = caps
+            .get(1)
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default();
+        let direction = parse_direction(caps.get(3).map(|m| m.as_str()).unwrap_or(""));
+        let tx_count: usize = caps
+            .get(4)
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(0);
 
-        // ================================================================
-        // TX Relay V2 Protocol Parsing
-        // ================================================================
+        data.tx_hash_announcements.push(TxHashAnnouncement {
+            timestamp: state.last_timestamp,
+            node_id: node_id.to_string(),
+            source_ip,
+            direction,
+            tx_count,
+            tx_hashes: Vec::new(), // Not logged individually at this level
+        });
+        return;
+    }
 
-        // Check for TX pool hash announcement (v2)
-        if let Some(caps) = PATTERNS.tx_pool_hash.captures(&line) {
-            let source_ip = caps
-                .get(1)
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default();
-            let direction = parse_direction(caps.get(3).map(|m| m.as_str()).unwrap_or(""));
-            let tx_count: usize = caps
-                .get(4)
-                .and_then(|m| m.as_str().parse().ok())
-                .unwrap_or(0);
+    // Check for TX pool request received (v2)
+    if let Some(caps) = PATTERNS.tx_pool_request_received.captures(line) {
+        let source_ip = caps
+            .get(1)
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default();
+        let tx_count: usize = caps
+            .get(4)
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(0);
 
-            data.tx_hash_announcements.push(TxHashAnnouncement {
-                timestamp: state.last_timestamp,
-                node_id: node_id.to_string(),
-                source_ip,
-                direction,
-                tx_count,
-                tx_hashes: Vec::new(), // Not logged individually at this level
-            });
-            continue;
-        }
+        data.tx_requests.push(TxRequest {
+            timestamp: state.last_timestamp,
+            node_id: node_id.to_string(),
+            target_ip: source_ip,
+            tx_count,
+            is_outgoing: false,
+        });
+        return;
+    }
 
-        // Check for TX pool request received (v2)
-        if let Some(caps) = PATTERNS.tx_pool_request_received.captures(&line) {
-            let source_ip = caps
-                .get(1)
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default();
-            let tx_count: usize = caps
-                .get(4)
-                .and_then(|m| m.as_str().parse().ok())
-                .unwrap_or(0);
+    // Check for TX pool request sent (v2)
+    if let Some(caps) = PATTERNS.tx_pool_request_sent.captures(line) {
+        let tx_count: usize = caps
+            .get(1)
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(0);
 
-            data.tx_requests.push(TxRequest {
-                timestamp: state.last_timestamp,
-                node_id: node_id.to_string(),
-                target_ip: source_ip,
-                tx_count,
-                is_outgoing: false,
-            });
-            continue;
-        }
+        data.tx_requests.push(TxRequest {
+            timestamp: state.last_timestamp,
+            node_id: node_id.to_string(),
+            target_ip: String::new(), // Not captured in this log line
+            tx_count,
+            is_outgoing: true,
+        });
+        return;
+    }
 
-        // Check for TX pool request sent (v2)
-        if let Some(caps) = PATTERNS.tx_pool_request_sent.captures(&line) {
-            let tx_count: usize = caps
+    // Check for connection drops with reasons
+    let keep_drops = !state.reduction.skips(EventKind::Connections);
+    if PATTERNS.drop_tx_verification.is_match(line) {
+        if let Some(caps) = PATTERNS.drop_connection.captures(line) {
+            let peer_ip = caps
                 .get(1)
-                .and_then(|m| m.as_str().parse().ok())
-                .unwrap_or(0);
-
-            data.tx_requests.push(TxRequest {
-                timestamp: state.last_timestamp,
-                node_id: node_id.to_string(),
-                target_ip: String::new(), // Not captured in this log line
-                tx_count,
-                is_outgoing: true,
-            });
-            continue;
-        }
-
-        // Check for connection drops with reasons
-        if PATTERNS.drop_tx_verification.is_match(&line) {
-            if let Some(caps) = PATTERNS.drop_connection.captures(&line) {
-                let peer_ip = caps
-                    .get(1)
-                    .map(|m| m.as_str().to_string())
-                    .unwrap_or_default();
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default();
+            if keep_drops {
                 data.connection_drops.push(ConnectionDrop {
                     timestamp: state.last_timestamp,
                     node_id: node_id.to_string(),
@@ -403,15 +654,17 @@ pub fn parse_log_file(path: &Path, node_id: &str) -> Result<NodeLogData> {
                     reason: "tx_verification_failed".to_string(),
                 });
             }
-            continue;
         }
+        return;
+    }
 
-        if PATTERNS.drop_duplicate_tx.is_match(&line) {
-            if let Some(caps) = PATTERNS.drop_connection.captures(&line) {
-                let peer_ip = caps
-                    .get(1)
-                    .map(|m| m.as_str().to_string())
-                    .unwrap_or_default();
+    if PATTERNS.drop_duplicate_tx.is_match(line) {
+        if let Some(caps) = PATTERNS.drop_connection.captures(line) {
+            let peer_ip = caps
+                .get(1)
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default();
+            if keep_drops {
                 data.connection_drops.push(ConnectionDrop {
                     timestamp: state.last_timestamp,
                     node_id: node_id.to_string(),
@@ -419,60 +672,65 @@ pub fn parse_log_file(path: &Path, node_id: &str) -> Result<NodeLogData> {
                     reason: "duplicate_tx".to_string(),
                 });
             }
-            continue;
         }
+        return;
+    }
 
-        // Generic dropping connection
-        if let Some(caps) = PATTERNS.drop_connection.captures(&line) {
-            let peer_ip = caps
-                .get(1)
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default();
+    // Generic dropping connection
+    if let Some(caps) = PATTERNS.drop_connection.captures(line) {
+        let peer_ip = caps
+            .get(1)
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default();
+        if keep_drops {
             data.connection_drops.push(ConnectionDrop {
                 timestamp: state.last_timestamp,
                 node_id: node_id.to_string(),
                 peer_ip,
                 reason: "other".to_string(),
             });
-            continue;
         }
+        return;
+    }
 
-        // Check for bandwidth log entry
-        if let Some(caps) = PATTERNS.bandwidth.captures(&line) {
-            let peer_ip = caps
-                .get(1)
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default();
-            let peer_port: u16 = caps
-                .get(2)
-                .and_then(|m| m.as_str().parse().ok())
-                .unwrap_or(0);
-            let direction = parse_direction(caps.get(3).map(|m| m.as_str()).unwrap_or(""));
-            let bytes: u64 = caps
-                .get(4)
-                .and_then(|m| m.as_str().parse().ok())
-                .unwrap_or(0);
-            let is_sent = caps.get(5).map(|m| m.as_str() == "sent").unwrap_or(false);
-            let command_category = caps
-                .get(6)
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default();
-            let initiated_by_us = caps.get(7).map(|m| m.as_str() == "us").unwrap_or(false);
-
-            data.bandwidth_events.push(BandwidthEvent {
-                timestamp: state.last_timestamp,
-                peer_ip,
-                peer_port,
-                direction,
-                bytes,
-                is_sent,
-                command_category,
-                initiated_by_us,
-            });
-        }
+    // Check for bandwidth log entry
+    if state.reduction.skips(EventKind::Bandwidth) {
+        return;
     }
+    if let Some(caps) = PATTERNS.bandwidth.captures(line) {
+        let peer_ip = caps
+            .get(1)
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default();
+        let peer_port: u16 = caps
+            .get(2)
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(0);
+        let direction = parse_direction(caps.get(3).map(|m| m.as_str()).unwrap_or(""));
+        let bytes: u64 = caps
+            .get(4)
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(0);
+        let is_sent = caps.get(5).map(|m| m.as_str() == "sent").unwrap_or(false);
+        let command_category = caps
+            .get(6)
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default();
+        let initiated_by_us = caps.get(7).map(|m| m.as_str() == "us").unwrap_or(false);
 
-    Ok(data)
+        let event = BandwidthEvent {
+            timestamp: state.last_timestamp,
+            peer_ip,
+            peer_port,
+            direction,
+            bytes,
+            is_sent,
+            command_category,
+            initiated_by_us,
+            messages: 1,
+        };
+        state.record_bandwidth(event, data);
+    }
 }
 
 /// Find the daemon log file for a node.
@@ -534,6 +792,119 @@ fn find_daemon_log_files(node_dir: &Path) -> Vec<std::path::PathBuf> {
     daemon_logs
 }
 
+/// The daemon log files of one node
+pub(super) struct NodeLogFiles {
+    pub(super) files: Vec<PathBuf>,
+    /// The legacy `bash.1000.stdout` fallback: a single file whose events
+    /// are kept in log order
+    legacy: bool,
+}
+
+/// Locate an agent's daemon logs under `log_dir`, trying the directory
+/// naming conventions in turn:
+/// 1. monero-<agent_id> (new: /tmp/monero-miner-001/ or daemon_logs/monero-miner-001/)
+/// 2. <agent_id> (legacy shadow.data: hosts/miner-001/)
+pub(super) fn node_log_files(log_dir: &Path, agent_id: &str) -> Option<NodeLogFiles> {
+    let node_dir = log_dir.join(format!("monero-{}", agent_id));
+    let node_dir = if node_dir.exists() {
+        node_dir
+    } else {
+        log_dir.join(agent_id)
+    };
+
+    let files = find_daemon_log_files(&node_dir);
+    if !files.is_empty() {
+        return Some(NodeLogFiles {
+            files,
+            legacy: false,
+        });
+    }
+
+    // Last resort: try bash.1000.stdout in the agent dir
+    let log_path = node_dir.join("bash.1000.stdout");
+    if !log_path.exists() {
+        log::debug!("No log file found for {}", agent_id);
+        return None;
+    }
+    Some(NodeLogFiles {
+        files: vec![log_path],
+        legacy: true,
+    })
+}
+
+/// Merge the data parsed from each of a node's log files, sorting the
+/// timed events
+fn merge_node_parts(node_id: &str, parts: impl IntoIterator<Item = NodeLogData>) -> NodeLogData {
+    let mut merged_data = NodeLogData::new(node_id.to_string());
+    for data in parts {
+        merged_data.tx_observations.extend(data.tx_observations);
+        merged_data
+            .tx_hash_announcements
+            .extend(data.tx_hash_announcements);
+        merged_data.tx_requests.extend(data.tx_requests);
+        merged_data.connection_events.extend(data.connection_events);
+        merged_data
+            .block_observations
+            .extend(data.block_observations);
+        merged_data.connection_drops.extend(data.connection_drops);
+        merged_data.bandwidth_events.extend(data.bandwidth_events);
+    }
+
+    // Sort by timestamp after merging
+    merged_data.tx_observations.sort_by(|a, b| {
+        a.timestamp
+            .partial_cmp(&b.timestamp)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    merged_data.connection_events.sort_by(|a, b| {
+        a.timestamp
+            .partial_cmp(&b.timestamp)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    merged_data.bandwidth_events.sort_by(|a, b| {
+        a.timestamp
+            .partial_cmp(&b.timestamp)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    merged_data
+}
+
+/// Parse one node's log files. `None` if the legacy single file fails to
+/// parse; a daemon log that fails to parse is skipped.
+pub(super) fn parse_node_logs(
+    agent_id: &str,
+    logs: &NodeLogFiles,
+    reduction: &LogReduction,
+) -> Option<NodeLogData> {
+    if logs.legacy {
+        let log_path = &logs.files[0];
+        return match parse_log_file_reduced(log_path, agent_id, reduction) {
+            Ok(data) => Some(data),
+            Err(e) => {
+                log::warn!("Failed to parse {}: {}", log_path.display(), e);
+                None
+            }
+        };
+    }
+
+    // Parse all daemon log files and merge results
+    let parts = logs.files.iter().filter_map(|log_path| {
+        parse_log_file_reduced(log_path, agent_id, reduction)
+            .map_err(|e| log::debug!("Failed to parse {}: {}", log_path.display(), e))
+            .ok()
+    });
+    let merged_data = merge_node_parts(agent_id, parts);
+
+    log::debug!(
+        "Parsed {} ({} log files): {} TX observations, {} connection events",
+        agent_id,
+        logs.files.len(),
+        merged_data.tx_observations.len(),
+        merged_data.connection_events.len()
+    );
+    Some(merged_data)
+}
+
 /// Parse all log files in parallel.
 ///
 /// `log_dir` is the directory containing node data directories. This can be:
@@ -543,6 +914,15 @@ fn find_daemon_log_files(node_dir: &Path) -> Vec<std::path::PathBuf> {
 pub fn parse_all_logs(
     log_dir: &Path,
     agents: &[AnalysisAgentInfo],
+) -> Result<HashMap<String, NodeLogData>> {
+    parse_all_logs_reduced(log_dir, agents, &LogReduction::default())
+}
+
+/// [`parse_all_logs`], keeping what `reduction` asks for
+pub fn parse_all_logs_reduced(
+    log_dir: &Path,
+    agents: &[AnalysisAgentInfo],
+    reduction: &LogReduction,
 ) -> Result<HashMap<String, NodeLogData>> {
     log::info!(
         "Parsing logs for {} agents from {}...",
@@ -553,97 +933,379 @@ pub fn parse_all_logs(
     let results: Vec<(String, NodeLogData)> = agents
         .par_iter()
         .filter_map(|agent| {
-            // Try multiple directory naming conventions:
-            // 1. monero-<agent_id> (new: /tmp/monero-miner-001/ or daemon_logs/monero-miner-001/)
-            // 2. <agent_id> (legacy shadow.data: hosts/miner-001/)
-            let node_dir = log_dir.join(format!("monero-{}", agent.id));
-            let node_dir = if node_dir.exists() {
-                node_dir
-            } else {
-                log_dir.join(&agent.id)
-            };
+            let logs = node_log_files(log_dir, &agent.id)?;
+            Some((
+                agent.id.clone(),
+                parse_node_logs(&agent.id, &logs, reduction)?,
+            ))
+        })
+        .collect();
+
+    let node_count = results.len();
+    let total_tx_obs: usize = results.iter().map(|(_, d)| d.tx_observations.len()).sum();
+    log::info!(
+        "Parsed {} nodes, {} total TX observations",
+        node_count,
+        total_tx_obs
+    );
 
-            let log_files = find_daemon_log_files(&node_dir);
+    Ok(results.into_iter().collect())
+}
 
-            if log_files.is_empty() {
-                // Last resort: try bash.1000.stdout in the agent dir
-                let log_path = node_dir.join("bash.1000.stdout");
-                if !log_path.exists() {
-                    log::debug!("No log file found for {}", agent.id);
-                    return None;
-                }
+// ============================================================================
+// Low-memory (two-pass) parsing
+// ============================================================================
 
-                match parse_log_file(&log_path, &agent.id) {
-                    Ok(data) => Some((agent.id.clone(), data)),
-                    Err(e) => {
-                        log::warn!("Failed to parse {}: {}", log_path.display(), e);
-                        None
-                    }
-                }
-            } else {
-                // Parse all daemon log files and merge results
-                let mut merged_data = NodeLogData::new(agent.id.clone());
-
-                for log_path in &log_files {
-                    match parse_log_file(log_path, &agent.id) {
-                        Ok(data) => {
-                            merged_data.tx_observations.extend(data.tx_observations);
-                            merged_data
-                                .tx_hash_announcements
-                                .extend(data.tx_hash_announcements);
-                            merged_data.tx_requests.extend(data.tx_requests);
-                            merged_data.connection_events.extend(data.connection_events);
-                            merged_data
-                                .block_observations
-                                .extend(data.block_observations);
-                            merged_data.connection_drops.extend(data.connection_drops);
-                            merged_data.bandwidth_events.extend(data.bandwidth_events);
-                        }
-                        Err(e) => {
-                            log::debug!("Failed to parse {}: {}", log_path.display(), e);
+/// A log line that carries an event or changes the parser's multi-line
+/// state, with the timestamp in effect just before it
+#[derive(Debug, Clone, Copy)]
+struct IndexedLine {
+    offset: u64,
+    timestamp: SimTime,
+}
+
+#[derive(Debug)]
+struct FileIndex {
+    path: PathBuf,
+    lines: Vec<IndexedLine>,
+}
+
+#[derive(Debug)]
+struct NodeLogIndex {
+    node_id: String,
+    files: Vec<FileIndex>,
+    legacy: bool,
+}
+
+/// Per-node indexes of the log lines that matter, built by a first pass
+/// over the logs that keeps no events.
+///
+/// [`LogIndex::nodes`] then parses one node at a time from the indexed
+/// lines, so an analysis that folds the nodes into its own aggregates holds
+/// at most one node's events. Re-reading only the indexed lines yields
+/// exactly what [`parse_all_logs_reduced`] returns for that node.
+#[derive(Debug, Default)]
+pub struct LogIndex {
+    nodes: Vec<NodeLogIndex>,
+    reduction: LogReduction,
+}
+
+impl LogIndex {
+    /// Index the logs of `agents` under `log_dir` (same layouts as
+    /// [`parse_all_logs`]). Skipped event types are left out of the index;
+    /// sampling and binning apply as the nodes are parsed.
+    pub fn build(
+        log_dir: &Path,
+        agents: &[AnalysisAgentInfo],
+        reduction: &LogReduction,
+    ) -> Result<Self> {
+        log::info!(
+            "Indexing logs for {} agents from {}...",
+            agents.len(),
+            log_dir.display()
+        );
+
+        let skips = reduction.skips_only();
+        let nodes: Vec<NodeLogIndex> = agents
+            .par_iter()
+            .filter_map(|agent| {
+                let logs = node_log_files(log_dir, &agent.id)?;
+                let mut files = Vec::new();
+                for log_path in &logs.files {
+                    match index_log_file(log_path, &agent.id, &skips) {
+                        Ok(index) => files.push(index),
+                        Err(e) if logs.legacy => {
+                            log::warn!("Failed to index {}: {}", log_path.display(), e);
+                            return None;
                         }
+                        Err(e) => log::debug!("Failed to index {}: {}", log_path.display(), e),
                     }
                 }
+                Some(NodeLogIndex {
+                    node_id: agent.id.clone(),
+                    files,
+                    legacy: logs.legacy,
+                })
+            })
+            .collect();
 
-                // Sort by timestamp after merging
-                merged_data.tx_observations.sort_by(|a, b| {
-                    a.timestamp
-                        .partial_cmp(&b.timestamp)
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                });
-                merged_data.connection_events.sort_by(|a, b| {
-                    a.timestamp
-                        .partial_cmp(&b.timestamp)
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                });
-                merged_data.bandwidth_events.sort_by(|a, b| {
-                    a.timestamp
-                        .partial_cmp(&b.timestamp)
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                });
+        let index = Self {
+            nodes,
+            reduction: reduction.clone(),
+        };
+        log::info!(
+            "Indexed {} nodes, {} relevant log lines",
+            index.node_count(),
+            index.line_count()
+        );
+        Ok(index)
+    }
 
-                log::debug!(
-                    "Parsed {} ({} log files): {} TX observations, {} connection events",
-                    agent.id,
-                    log_files.len(),
-                    merged_data.tx_observations.len(),
-                    merged_data.connection_events.len()
-                );
+    /// Number of nodes with logs
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Number of indexed lines over all nodes
+    pub fn line_count(&self) -> usize {
+        self.nodes
+            .iter()
+            .flat_map(|n| &n.files)
+            .map(|f| f.lines.len())
+            .sum()
+    }
 
-                Some((agent.id.clone(), merged_data))
+    /// Parse the nodes one at a time, in registry order. Each call starts a
+    /// new pass over the logs; a node whose logs can no longer be read is
+    /// skipped with a warning.
+    pub fn nodes(&self) -> IndexedNodes<'_> {
+        IndexedNodes {
+            nodes: self.nodes.iter(),
+            reduction: &self.reduction,
+        }
+    }
+}
+
+/// Iterator over the parsed nodes of a [`LogIndex`]
+#[derive(Debug, Clone)]
+pub struct IndexedNodes<'a> {
+    nodes: std::slice::Iter<'a, NodeLogIndex>,
+    reduction: &'a LogReduction,
+}
+
+impl Iterator for IndexedNodes<'_> {
+    type Item = NodeLogData;
+
+    fn next(&mut self) -> Option<NodeLogData> {
+        for node in self.nodes.by_ref() {
+            match node.parse(self.reduction) {
+                Ok(data) => return Some(data),
+                Err(e) => log::warn!("Failed to re-read logs of {}: {}", node.node_id, e),
             }
-        })
+        }
+        None
+    }
+}
+
+impl NodeLogIndex {
+    fn parse(&self, reduction: &LogReduction) -> Result<NodeLogData> {
+        if self.legacy {
+            return replay_log_file(&self.files[0], &self.node_id, reduction);
+        }
+        let parts = self.files.iter().filter_map(|file| {
+            replay_log_file(file, &self.node_id, reduction)
+                .map_err(|e| log::debug!("Failed to parse {}: {}", file.path.display(), e))
+                .ok()
+        });
+        Ok(merge_node_parts(&self.node_id, parts))
+    }
+}
+
+/// A raw line as `BufRead::lines` yields it: `None` if it is not UTF-8,
+/// otherwise without the line ending
+fn line_text(raw: &[u8]) -> Option<&str> {
+    let line = std::str::from_utf8(raw).ok()?;
+    Some(match line.strip_suffix('\n') {
+        Some(line) => line.strip_suffix('\r').unwrap_or(line),
+        None => line,
+    })
+}
+
+fn event_count(data: &NodeLogData) -> usize {
+    data.tx_observations.len()
+        + data.connection_events.len()
+        + data.block_observations.len()
+        + data.tx_hash_announcements.len()
+        + data.tx_requests.len()
+        + data.connection_drops.len()
+        + data.bandwidth_events.len()
+}
+
+/// First pass over one file: run the parser, recording the lines that
+/// produce events or change the pending multi-line state, and discard the
+/// events
+fn index_log_file(path: &Path, node_id: &str, skips: &LogReduction) -> Result<FileIndex> {
+    let file =
+        File::open(path).with_context(|| format!("Failed to open log file: {}", path.display()))?;
+    let mut reader = BufReader::with_capacity(64 * 1024, file);
+
+    let mut state = ParseState::new(skips);
+    let mut scratch = NodeLogData::new(node_id.to_string());
+    let mut lines = Vec::new();
+    let mut raw = Vec::new();
+    let mut offset = 0u64;
+    loop {
+        raw.clear();
+        let read = reader
+            .read_until(b'\n', &mut raw)
+            .with_context(|| format!("Failed to read log file: {}", path.display()))?;
+        if read == 0 {
+            break;
+        }
+        let start = offset;
+        offset += read as u64;
+        let Some(line) = line_text(&raw) else {
+            continue; // Skip malformed lines
+        };
+
+        let timestamp = state.last_timestamp;
+        let pending_before = state.pending_tx_notification.clone();
+        let mined_before = state.pending_block_mined;
+        parse_line(line, node_id, &mut state, &mut scratch);
+
+        let emitted = event_count(&scratch) > 0;
+        if emitted
+            || state.pending_tx_notification != pending_before
+            || state.pending_block_mined != mined_before
+        {
+            lines.push(IndexedLine {
+                offset: start,
+                timestamp,
+            });
+        }
+        if emitted {
+            scratch = NodeLogData::new(node_id.to_string());
+        }
+    }
+
+    lines.shrink_to_fit();
+    Ok(FileIndex {
+        path: path.to_path_buf(),
+        lines,
+    })
+}
+
+/// Second pass over one file: parse only its indexed lines. Each line sees
+/// the same parser state as in a full pass, so yields the same events.
+fn replay_log_file(
+    file: &FileIndex,
+    node_id: &str,
+    reduction: &LogReduction,
+) -> Result<NodeLogData> {
+    let path = &file.path;
+    let handle =
+        File::open(path).with_context(|| format!("Failed to open log file: {}", path.display()))?;
+    let mut reader = BufReader::with_capacity(64 * 1024, handle);
+
+    let mut data = NodeLogData::new(node_id.to_string());
+    let mut state = ParseState::new(reduction);
+    let mut raw = Vec::new();
+    let mut position = 0u64;
+    for entry in &file.lines {
+        raw.clear();
+        let read = reader
+            .seek_relative((entry.offset - position) as i64)
+            .and_then(|()| reader.read_until(b'\n', &mut raw))
+            .with_context(|| format!("Failed to read log file: {}", path.display()))?;
+        position = entry.offset + read as u64;
+        if let Some(line) = line_text(&raw) {
+            state.last_timestamp = entry.timestamp;
+            parse_line(line, node_id, &mut state, &mut data);
+        }
+    }
+    state.finish(&mut data);
+    Ok(data)
+}
+
+// ============================================================================
+// Agent transaction attempts
+// ============================================================================
+
+/// A Python agent log line, "2000-01-01 01:00:05,123 - user-001 -
+/// RegularUserAgent[user-001] - INFO - message": time, millis, agent, message
+static AGENT_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}),(\d{3}) - (\S+) - \S+ - [A-Z]+ - (.*)$")
+        .expect("Invalid agent line regex")
+});
+
+/// "Sent transaction: <hash> ..." (regular users) or "Batch transaction
+/// sent successfully: <hash> ..." (miner distributor)
+static AGENT_TX_SENT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:Sent transaction|Batch transaction sent successfully): ([0-9a-fA-F]{64})")
+        .expect("Invalid agent send regex")
+});
+
+/// "Failed to send transaction: <error>" or "Failed to send batch
+/// transaction after N attempts: <error>"
+static AGENT_TX_FAILED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^Failed to send (?:batch )?transaction(?: after \d+ attempts)?: (.*)$")
+        .expect("Invalid agent failure regex")
+});
+
+/// The send attempt an agent log line reports, if it reports one
+pub fn parse_agent_tx_line(line: &str) -> Option<TxAttempt> {
+    let caps = AGENT_LINE.captures(line)?;
+    let time = chrono::NaiveDateTime::parse_from_str(&caps[1], "%Y-%m-%d %H:%M:%S").ok()?;
+    let millis: f64 = caps[2].parse().ok()?;
+    let message = &caps[4];
+    let (outcome, reason, tx_hash) = if let Some(sent) = AGENT_TX_SENT.captures(message) {
+        (TxOutcome::Success, None, Some(sent[1].to_string()))
+    } else if let Some(failed) = AGENT_TX_FAILED.captures(message) {
+        (TxOutcome::Failure, Some(failed[1].to_string()), None)
+    } else if message.starts_with("Transaction response missing tx_hash") {
+        (
+            TxOutcome::Failure,
+            Some("missing tx_hash".to_string()),
+            None,
+        )
+    } else {
+        return None;
+    };
+    Some(TxAttempt {
+        agent_id: caps[3].to_string(),
+        timestamp: time.and_utc().timestamp() as f64 + millis / 1000.0,
+        outcome,
+        reason,
+        tx_hash,
+    })
+}
+
+/// Send attempts from the agents' stdout under `hosts_dir`
+/// (`shadow.data/hosts/<host>/*.stdout`), for runs without
+/// `tx_attempts.jsonl`. Sorted by time.
+pub fn parse_agent_tx_logs(hosts_dir: &Path) -> Result<Vec<TxAttempt>> {
+    let host_dirs: Vec<PathBuf> = std::fs::read_dir(hosts_dir)
+        .with_context(|| format!("Failed to read hosts directory: {}", hosts_dir.display()))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_dir())
         .collect();
 
-    let node_count = results.len();
-    let total_tx_obs: usize = results.iter().map(|(_, d)| d.tx_observations.len()).sum();
+    let mut attempts: Vec<TxAttempt> = host_dirs
+        .par_iter()
+        .flat_map_iter(|dir| {
+            let mut found = Vec::new();
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                return found;
+            };
+            for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+                if !path.extension().is_some_and(|ext| ext == "stdout") {
+                    continue;
+                }
+                let Ok(file) = File::open(&path) else {
+                    continue;
+                };
+                found.extend(
+                    BufReader::new(file)
+                        .lines()
+                        .map_while(|line| line.ok())
+                        .filter(|line| line.contains("ransaction"))
+                        .filter_map(|line| parse_agent_tx_line(&line)),
+                );
+            }
+            found
+        })
+        .collect();
+    attempts.sort_by(|a, b| {
+        a.timestamp
+            .total_cmp(&b.timestamp)
+            .then_with(|| a.agent_id.cmp(&b.agent_id))
+    });
     log::info!(
-        "Parsed {} nodes, {} total TX observations",
-        node_count,
-        total_tx_obs
+        "Found {} transaction attempts in agent logs under {}",
+        attempts.len(),
+        hosts_dir.display()
     );
-
-    Ok(results.into_iter().collect())
+    Ok(attempts)
 }
 
 #[cfg(test)]
@@ -682,4 +1344,102 @@ mod tests {
             "9effc6a5a5fa0f07e1f5b540ed604804471f4fb7d7e7d7e57f0c0010ed67c8b7"
         );
     }
+
+    #[test]
+    fn replaying_indexed_lines_matches_a_full_parse() {
+        let t = crate::SHADOW_EPOCH + 100.0;
+        let hash = |n: u32| format!("{:064x}", n);
+        let log = [
+            line(t, "unrelated startup chatter"),
+            line(
+                t + 1.0,
+                &tx_notification("10.0.0.2", 18080, ConnectionDirection::Inbound, 2),
+            ),
+            including_transaction(&hash(1)),
+            including_transaction(&hash(2)),
+            // Ends the notification: the next hash belongs to nobody
+            line(t + 2.0, "something else entirely"),
+            including_transaction(&hash(3)),
+            line(t + 3.0, BLOCK_ADDED),
+            line(t + 4.0, "more chatter"),
+            block_height(7, 1),
+            line(t + 5.0, "timestamp carried to the next event"),
+            "[10.0.0.3:18080 OUT] 512 bytes sent for category command-2008 initiated by us"
+                .to_string(),
+        ]
+        .join("\n");
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("bitmonero.log");
+        std::fs::write(&path, log).unwrap();
+
+        let full = parse_log_file(&path, "node").unwrap();
+        let exact = LogReduction::default();
+        let index = index_log_file(&path, "node", &exact).unwrap();
+        let replayed = replay_log_file(&index, "node", &exact).unwrap();
+
+        assert_eq!(full.tx_observations.len(), 2);
+        assert_eq!(full.block_observations.len(), 1);
+        assert_eq!(full.bandwidth_events.len(), 1);
+        assert_eq!(full.bandwidth_events[0].timestamp, t + 5.0);
+        assert_eq!(index.lines.len(), 7);
+        assert_eq!(
+            serde_json::to_value(&replayed).unwrap(),
+            serde_json::to_value(&full).unwrap()
+        );
+    }
+
+    #[test]
+    fn log_index_yields_what_parse_all_logs_returns() {
+        let run = crate::analysis::synth::generate(&crate::analysis::synth::SynthOptions {
+            agents: 8,
+            txs: 20,
+            ..Default::default()
+        });
+        let tmp = tempfile::TempDir::new().unwrap();
+        crate::analysis::synth::write_dataset(&run, 0, tmp.path()).unwrap();
+        let log_dir = tmp.path().join("daemon_logs");
+
+        let mut full = parse_all_logs(&log_dir, &run.agents).unwrap();
+        let index = LogIndex::build(&log_dir, &run.agents, &LogReduction::default()).unwrap();
+        assert_eq!(index.node_count(), run.agents.len());
+        for node in index.nodes() {
+            let expected = full.remove(&node.node_id).unwrap();
+            assert_eq!(
+                serde_json::to_value(&node).unwrap(),
+                serde_json::to_value(&expected).unwrap()
+            );
+        }
+        assert!(full.is_empty());
+    }
+
+    #[test]
+    fn agent_tx_lines_report_attempts() {
+        let hash = "9effc6a5a5fa0f07e1f5b540ed604804471f4fb7d7e7d7e57f0c0010ed67c8b7";
+        let sent = parse_agent_tx_line(&format!(
+            "2000-01-01 01:00:05,250 - user-001 - RegularUserAgent[user-001] - INFO - \
+             Sent transaction: {} to user-002 for 0.5 XMR",
+            hash
+        ))
+        .unwrap();
+        assert_eq!(sent.agent_id, "user-001");
+        assert_eq!(sent.timestamp, crate::SHADOW_EPOCH + 3605.25);
+        assert_eq!(sent.outcome, TxOutcome::Success);
+        assert_eq!(sent.tx_hash.as_deref(), Some(hash));
+
+        let failed = parse_agent_tx_line(
+            "2000-01-01 01:00:09,000 - md - MinerDistributorAgent[md] - ERROR - \
+             Failed to send batch transaction after 3 attempts: not enough unlocked money",
+        )
+        .unwrap();
+        assert_eq!(failed.agent_id, "md");
+        assert_eq!(failed.outcome, TxOutcome::Failure);
+        assert_eq!(failed.reason.as_deref(), Some("not enough unlocked money"));
+
+        assert!(parse_agent_tx_line(
+            "2000-01-01 01:00:09,000 - md - MinerDistributorAgent[md] - INFO - \
+             Preparing batch transaction: 3 recipients"
+        )
+        .is_none());
+        assert!(parse_agent_tx_line(&including_transaction(hash)).is_none());
+    }
 }
@@ -0,0 +1,326 @@
+//! Mempool divergence analysis.
+//!
+//! A daemon holds a transaction from its first sighting (or, for the
+//! sender's own daemon, the creation time) until it receives the block
+//! including it. Sampling every daemon's holdings at the end of
+//! each time window shows whether the mempools converge: each daemon is
+//! compared with the reference set (transactions at least half the daemons
+//! hold), and confirmed transactions a daemon never saw beforehand are
+//! listed, since relay bugs show up as exactly these persistent gaps.
+
+use std::collections::{BTreeMap, HashMap};
+
+use rayon::prelude::*;
+
+use super::schema::REPORT_SCHEMA_VERSION;
+use super::stats::median;
+use super::time_window::{create_time_windows, find_simulation_time_range};
+use super::types::*;
+
+/// One daemon's mempool over time
+struct NodeMempool<'a> {
+    node_id: &'a str,
+    /// (tx, first sighting, confirmation); confirmation is infinite for
+    /// transactions never seen in a block
+    held: Vec<(&'a str, SimTime, SimTime)>,
+}
+
+impl<'a> NodeMempool<'a> {
+    /// Transactions held at `at`
+    fn holding(&self, at: SimTime) -> impl Iterator<Item = &'a str> + '_ {
+        self.held
+            .iter()
+            .filter(move |(_, seen, confirmed)| *seen <= at && at < *confirmed)
+            .map(|(tx, _, _)| *tx)
+    }
+}
+
+/// Analyze mempool convergence over windows of `window_size_sec`
+pub fn analyze_mempool(
+    transactions: &[Transaction],
+    blocks: &[BlockInfo],
+    log_data: &HashMap<String, NodeLogData>,
+    window_size_sec: f64,
+) -> MempoolReport {
+    let tx_height: HashMap<&str, u64> = blocks
+        .iter()
+        .flat_map(|b| b.transactions.iter().map(move |tx| (tx.as_str(), b.height)))
+        .collect();
+
+    // Earliest sighting of each height, network-wide and per daemon
+    let mut network_block_time: HashMap<u64, SimTime> = HashMap::new();
+    let mut node_block_times: HashMap<&str, HashMap<u64, SimTime>> = HashMap::new();
+    for (node_id, data) in log_data {
+        let times = node_block_times.entry(node_id).or_default();
+        for obs in &data.block_observations {
+            for map in [&mut network_block_time, &mut *times] {
+                let t = map.entry(obs.height).or_insert(obs.timestamp);
+                if obs.timestamp < *t {
+                    *t = obs.timestamp;
+                }
+            }
+        }
+    }
+
+    // When a daemon considers a transaction confirmed: its own receipt of
+    // the block, or the network's first sighting if it never logged one
+    let confirmed_at = |node_id: &str, tx: &str| {
+        let height = tx_height.get(tx)?;
+        node_block_times[node_id]
+            .get(height)
+            .or_else(|| network_block_time.get(height))
+            .copied()
+    };
+
+    let mut node_ids: Vec<&str> = log_data.keys().map(|s| s.as_str()).collect();
+    node_ids.sort();
+    let mut first_seen: HashMap<&str, HashMap<&str, SimTime>> = HashMap::new();
+    for &node_id in &node_ids {
+        first_seen.entry(node_id).or_default();
+    }
+    // The sender's daemon never logs receiving its own transaction
+    for tx in transactions {
+        if let Some(seen) = first_seen.get_mut(tx.sender_id.as_str()) {
+            seen.insert(&tx.tx_hash, tx.timestamp);
+        }
+    }
+    for &node_id in &node_ids {
+        let seen = first_seen
+            .get_mut(node_id)
+            .expect("every daemon has an entry");
+        for obs in &log_data[node_id].tx_observations {
+            let t = seen.entry(&obs.tx_hash).or_insert(obs.timestamp);
+            if obs.timestamp < *t {
+                *t = obs.timestamp;
+            }
+        }
+    }
+
+    let mempools: Vec<NodeMempool> = node_ids
+        .iter()
+        .map(|&node_id| {
+            let mut held: Vec<(&str, SimTime, SimTime)> = first_seen[node_id]
+                .iter()
+                .map(|(&tx, &seen)| {
+                    let confirmed = confirmed_at(node_id, tx).unwrap_or(f64::INFINITY);
+                    (tx, seen, confirmed)
+                })
+                .filter(|(_, seen, confirmed)| seen < confirmed)
+                .collect();
+            held.sort_by(|a, b| a.0.cmp(b.0));
+            NodeMempool { node_id, held }
+        })
+        .collect();
+
+    // Confirmed transactions some daemons never held
+    let mut observed: Vec<&str> = first_seen
+        .values()
+        .flat_map(|seen| seen.keys().copied())
+        .collect();
+    observed.sort();
+    observed.dedup();
+    let mut confirmed_transactions = 0;
+    let mut never_seen: Vec<UnseenTransaction> = Vec::new();
+    for block in blocks {
+        if !network_block_time.contains_key(&block.height) {
+            continue;
+        }
+        for tx in &block.transactions {
+            if observed.binary_search(&tx.as_str()).is_ok() {
+                confirmed_transactions += 1;
+            }
+            let missing_nodes: Vec<String> = node_ids
+                .iter()
+                .filter(|&&node_id| {
+                    let confirmed = confirmed_at(node_id, tx).unwrap_or(f64::INFINITY);
+                    !first_seen[node_id]
+                        .get(tx.as_str())
+                        .is_some_and(|&seen| seen < confirmed)
+                })
+                .map(|node_id| node_id.to_string())
+                .collect();
+            if !missing_nodes.is_empty() {
+                never_seen.push(UnseenTransaction {
+                    tx_hash: tx.clone(),
+                    block_height: block.height,
+                    missing_nodes,
+                });
+            }
+        }
+    }
+    never_seen.sort_by(|a, b| {
+        b.missing_nodes
+            .len()
+            .cmp(&a.missing_nodes.len())
+            .then_with(|| a.block_height.cmp(&b.block_height))
+            .then_with(|| a.tx_hash.cmp(&b.tx_hash))
+    });
+    let mut never_seen_by_count: BTreeMap<usize, usize> = BTreeMap::new();
+    for tx in &never_seen {
+        *never_seen_by_count
+            .entry(tx.missing_nodes.len())
+            .or_default() += 1;
+    }
+
+    let (sim_start, sim_end) = find_simulation_time_range(log_data);
+    let time_windows = create_time_windows(sim_start, sim_end, window_size_sec);
+    let sampled: Vec<(MempoolWindow, Vec<usize>)> = time_windows
+        .par_iter()
+        .map(|window| sample_window(window, &mempools))
+        .collect();
+
+    let per_node = node_divergence(&mempools, &sampled, window_size_sec);
+    // The first window reaching the largest divergence
+    let max_divergence = sampled
+        .iter()
+        .map(|(w, _)| w.max_divergence)
+        .max()
+        .unwrap_or(0);
+    let worst = sampled
+        .iter()
+        .map(|(w, _)| w)
+        .find(|w| max_divergence > 0 && w.max_divergence == max_divergence);
+
+    MempoolReport {
+        report_schema_version: REPORT_SCHEMA_VERSION,
+        window_size_sec,
+        daemon_count: node_ids.len(),
+        observed_transactions: observed.len(),
+        confirmed_transactions,
+        max_divergence,
+        max_divergence_node: worst.and_then(|w| w.max_divergence_node.clone()),
+        max_divergence_time: worst.map(|w| w.end),
+        windows: sampled.into_iter().map(|(w, _)| w).collect(),
+        per_node,
+        never_seen,
+        never_seen_by_count,
+    }
+}
+
+/// Every daemon's holdings at the window end, compared with the reference
+/// set. Also returns each daemon's divergence, in `mempools` order.
+fn sample_window(window: &TimeWindow, mempools: &[NodeMempool]) -> (MempoolWindow, Vec<usize>) {
+    let at = window.end;
+    let mut holders: HashMap<&str, usize> = HashMap::new();
+    for m in mempools {
+        for tx in m.holding(at) {
+            *holders.entry(tx).or_default() += 1;
+        }
+    }
+    let in_reference = |tx: &str| holders.get(tx).is_some_and(|&n| n * 2 >= mempools.len());
+    let reference_size = holders.keys().filter(|tx| in_reference(tx)).count();
+
+    let mut sizes: Vec<f64> = Vec::with_capacity(mempools.len());
+    let divergences: Vec<usize> = mempools
+        .iter()
+        .map(|m| {
+            let (size, shared) = m.holding(at).fold((0, 0), |(size, shared), tx| {
+                (size + 1, shared + in_reference(tx) as usize)
+            });
+            sizes.push(size as f64);
+            size + reference_size - 2 * shared
+        })
+        .collect();
+
+    let worst = divergences
+        .iter()
+        .enumerate()
+        .filter(|(_, &d)| d > 0)
+        .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(&a.0)));
+    let window = MempoolWindow {
+        start: window.start,
+        end: window.end,
+        median_mempool_size: median(&sizes),
+        reference_size,
+        avg_divergence: if divergences.is_empty() {
+            0.0
+        } else {
+            divergences.iter().sum::<usize>() as f64 / divergences.len() as f64
+        },
+        max_divergence: worst.map_or(0, |(_, &d)| d),
+        max_divergence_node: worst.map(|(i, _)| mempools[i].node_id.to_string()),
+        diverged_nodes: divergences.iter().filter(|&&d| d > 0).count(),
+    };
+    (window, divergences)
+}
+
+fn node_divergence(
+    mempools: &[NodeMempool],
+    sampled: &[(MempoolWindow, Vec<usize>)],
+    window_size_sec: f64,
+) -> Vec<NodeMempoolDivergence> {
+    let mut per_node: Vec<NodeMempoolDivergence> = mempools
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let series: Vec<usize> = sampled.iter().map(|(_, d)| d[i]).collect();
+            let mut longest = 0;
+            let mut run = 0;
+            for &d in &series {
+                run = if d > 0 { run + 1 } else { 0 };
+                longest = longest.max(run);
+            }
+            NodeMempoolDivergence {
+                node_id: m.node_id.to_string(),
+                avg_divergence: if series.is_empty() {
+                    0.0
+                } else {
+                    series.iter().sum::<usize>() as f64 / series.len() as f64
+                },
+                max_divergence: series.iter().copied().max().unwrap_or(0),
+                diverged_windows: series.iter().filter(|&&d| d > 0).count(),
+                longest_divergence_sec: longest as f64 * window_size_sec,
+            }
+        })
+        .collect();
+    per_node.sort_by(|a, b| {
+        b.max_divergence
+            .cmp(&a.max_divergence)
+            .then_with(|| a.node_id.cmp(&b.node_id))
+    });
+    per_node
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::testutil::SyntheticRun;
+
+    #[test]
+    fn a_node_missing_a_transaction_diverges_until_the_block() {
+        let mut run = SyntheticRun::new();
+        for id in ["a", "b", "c"] {
+            run.agent(id, "agents.regular_user");
+        }
+        // a sends both; its own daemon never logs them
+        run.transaction("tx1", "a", "b", 0.0)
+            .transaction("tx2", "a", "b", 3.0)
+            .observe("tx1", "b", "a", 1.0)
+            .observe("tx1", "c", "a", 2.0)
+            .observe("tx2", "b", "a", 4.0)
+            .block(1, "a", &["tx1", "tx2"], 25.0, 0.0);
+
+        let report = analyze_mempool(&run.transactions, &run.blocks, &run.log_map(), 10.0);
+        assert_eq!(report.daemon_count, 3);
+        assert_eq!(report.observed_transactions, 2);
+        assert_eq!(report.confirmed_transactions, 2);
+
+        // Windows start at the first logged event, b's sighting at 1.0
+        let divergence: Vec<usize> = report.windows.iter().map(|w| w.max_divergence).collect();
+        assert_eq!(divergence, [1, 1, 0]);
+        assert_eq!(report.windows[0].reference_size, 2);
+        assert_eq!(report.max_divergence, 1);
+        assert_eq!(report.max_divergence_node.as_deref(), Some("c"));
+        assert_eq!(report.max_divergence_time, Some(11.0));
+
+        assert_eq!(report.per_node[0].node_id, "c");
+        assert_eq!(report.per_node[0].diverged_windows, 2);
+        assert_eq!(report.per_node[0].longest_divergence_sec, 20.0);
+        assert_eq!(report.per_node[1].max_divergence, 0);
+
+        assert_eq!(report.never_seen.len(), 1);
+        assert_eq!(report.never_seen[0].tx_hash, "tx2");
+        assert_eq!(report.never_seen[0].missing_nodes, ["c"]);
+        assert_eq!(report.never_seen_by_count, BTreeMap::from([(1, 1)]));
+    }
+}
@@ -3,29 +3,77 @@
 //! This module provides tools for analyzing transaction propagation patterns,
 //! spy node vulnerabilities, and network resilience metrics.
 
+pub mod anonymize;
 pub mod bandwidth;
+pub mod block_propagation;
+pub mod compare;
+pub mod compat;
+pub mod conflicts;
 pub mod dandelion;
+pub mod log_cache;
+pub mod log_format;
 pub mod log_parser;
+pub mod mempool;
+pub mod monitor_ingest;
 pub mod network_graph;
 pub mod network_resilience;
+pub mod pcap;
 pub mod propagation;
+pub mod readiness;
 pub mod report;
+pub mod schema;
 pub mod spy_node;
-pub(crate) mod stats;
+pub mod stats;
+pub mod sweep;
+pub mod synth;
+pub mod testutil;
 pub mod time_window;
+pub mod trace;
 pub mod tx_relay;
+pub mod tx_success;
 pub mod types;
 pub mod upgrade_analysis;
 
-pub use bandwidth::{analyze_bandwidth, bandwidth_time_series, format_bytes};
+pub use anonymize::{AnonymizationInfo, Anonymize, Anonymizer};
+pub use bandwidth::{
+    analyze_bandwidth, analyze_bandwidth_iter, bandwidth_matrix, bandwidth_matrix_iter,
+    bandwidth_time_series, bandwidth_time_series_iter, format_bytes,
+};
+pub use block_propagation::analyze_block_propagation;
+pub use compare::{compare_runs, summarize_run, CompareAnalysis};
+pub use compat::{check_artifacts, Compatibility, CompatibilityReport};
+pub use conflicts::{analyze_conflicts, load_conflict_sets, CONFLICTS_FILE};
 pub use dandelion::analyze_dandelion;
-pub use log_parser::parse_all_logs;
+pub use log_cache::parse_all_logs_cached;
+pub use log_parser::{
+    parse_agent_tx_logs, parse_all_logs, parse_all_logs_reduced, EventKind, LogIndex, LogReduction,
+};
+pub use mempool::analyze_mempool;
+pub use monitor_ingest::{
+    find_metrics_file, load_monitor_samples, merge_into_windows, summarize_monitor,
+};
 pub use network_graph::{analyze_network_graph, NetworkGraphReport};
 pub use network_resilience::analyze_resilience;
-pub use propagation::analyze_propagation;
+pub use pcap::{find_pcaps, index_pcaps, resolve_capture_dir, PCAP_INDEX_FILE};
+pub use propagation::{
+    analyze_propagation, analyze_propagation_streamed, DEFAULT_COVERAGE_PERCENTILES,
+};
+pub use readiness::analyze_readiness;
 pub use report::{generate_json_report, generate_text_report};
-pub use spy_node::analyze_spy_vulnerability;
+pub use schema::{CACHE_SCHEMA_VERSION, REPORT_SCHEMA_VERSION};
+pub use spy_node::{analyze_spy_vulnerability, registry_spies};
+pub use stats::{
+    bootstrap_mean_difference_ci, calculate_stats, chi_square_p_value, compare_samples,
+    is_significant, welch, welch_t_test, SampleComparison, WelchTest,
+};
+pub use sweep::{
+    discover_sweep_runs, locate_run, summarize_sweep, sweep_run_metrics, RunLayout, SweepAnalysis,
+    SweepRunSpec, SWEEP_SUMMARY_FILE,
+};
+pub use synth::{generate as generate_synthetic, write_dataset, DelayDistribution, SynthOptions};
 pub use time_window::*;
+pub use trace::{match_tx_hash, trace_transaction, TxHashMatch};
 pub use tx_relay::analyze_tx_relay_v2;
+pub use tx_success::{analyze_tx_success, classify_failure, load_tx_attempts, TX_ATTEMPTS_FILE};
 pub use types::*;
 pub use upgrade_analysis::analyze_upgrade_impact;
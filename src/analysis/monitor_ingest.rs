@@ -0,0 +1,316 @@
+//! Simulation monitor time-series ingestion.
+//!
+//! With `metrics` or `output_format` set on its agent, the simulation
+//! monitor appends one sample per node per poll to `monitor_metrics.jsonl`
+//! (one JSON object per line) or `monitor_metrics.csv` (a header naming the
+//! recorded columns, then one row per sample) in the shared directory.
+//! Both carry `time`, `poll` and `node`, then whichever of `height`,
+//! `mempool`, `peers`, `bytes_in` and `bytes_out` were selected; a value
+//! the node did not report is null or empty.
+//!
+//! Height lag is measured within a poll: the highest height any node
+//! reported in it minus the node's own.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Context, Result};
+
+use super::schema::REPORT_SCHEMA_VERSION;
+use super::stats::mean;
+use super::time_window::create_time_windows;
+use super::types::*;
+
+/// File names the monitor writes in the shared directory, by format
+pub const MONITOR_METRICS_FILES: [&str; 2] = ["monitor_metrics.jsonl", "monitor_metrics.csv"];
+
+/// The monitor's time-series file in `shared_dir`, if it wrote one
+pub fn find_metrics_file(shared_dir: &Path) -> Option<PathBuf> {
+    MONITOR_METRICS_FILES
+        .iter()
+        .map(|name| shared_dir.join(name))
+        .find(|path| path.exists())
+}
+
+/// Load a time-series file, as CSV for a `.csv` extension and JSON lines
+/// otherwise
+pub fn load_monitor_samples(path: &Path) -> Result<Vec<MonitorSample>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read monitor metrics: {}", path.display()))?;
+    let samples = if path.extension().is_some_and(|ext| ext == "csv") {
+        parse_csv(&text)
+    } else {
+        parse_jsonl(&text)
+    };
+    samples.with_context(|| format!("Failed to parse monitor metrics: {}", path.display()))
+}
+
+/// Parse JSON lines; blank lines are skipped
+pub fn parse_jsonl(text: &str) -> Result<Vec<MonitorSample>> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| serde_json::from_str(line).map_err(|e| eyre!("line {}: {}", i + 1, e)))
+        .collect()
+}
+
+/// Parse CSV with a header row. `time` and `node` are required; metric
+/// columns the monitor did not record are simply absent.
+pub fn parse_csv(text: &str) -> Result<Vec<MonitorSample>> {
+    let mut reader = csv::Reader::from_reader(text.as_bytes());
+    let headers = reader.headers()?.clone();
+    let column = |name: &str| headers.iter().position(|h| h == name);
+    let (Some(time), Some(node)) = (column("time"), column("node")) else {
+        return Err(eyre!(
+            "header must name 'time' and 'node' columns, got: {}",
+            headers.iter().collect::<Vec<_>>().join(",")
+        ));
+    };
+    let optional = [
+        "poll",
+        "height",
+        "mempool",
+        "peers",
+        "bytes_in",
+        "bytes_out",
+    ]
+    .map(column);
+
+    let mut samples = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        let line = i + 2;
+        let record = record.map_err(|e| eyre!("line {}: {}", line, e))?;
+        let field = |index: Option<usize>| index.and_then(|i| record.get(i)).unwrap_or("");
+        let number = |index: Option<usize>| -> Result<Option<u64>> {
+            match field(index).trim() {
+                "" => Ok(None),
+                value => value
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| eyre!("line {}: '{}' is not a count", line, value)),
+            }
+        };
+        let [poll, height, mempool, peers, bytes_in, bytes_out] = optional;
+        samples.push(MonitorSample {
+            time: field(Some(time))
+                .trim()
+                .parse()
+                .map_err(|_| eyre!("line {}: '{}' is not a time", line, field(Some(time))))?,
+            poll: number(poll)?,
+            node: field(Some(node)).to_string(),
+            height: number(height)?,
+            mempool: number(mempool)?,
+            peers: number(peers)?,
+            bytes_in: number(bytes_in)?,
+            bytes_out: number(bytes_out)?,
+        });
+    }
+    Ok(samples)
+}
+
+/// Each sample's height lag behind the highest node of its poll, parallel
+/// to `samples`; `None` without a height. Samples without a poll number
+/// are grouped by time.
+pub fn height_lags(samples: &[MonitorSample]) -> Vec<Option<u64>> {
+    let poll_key = |s: &MonitorSample| s.poll.ok_or(s.time.to_bits());
+    let mut tips: HashMap<Result<u64, u64>, u64> = HashMap::new();
+    for sample in samples {
+        if let Some(height) = sample.height {
+            let tip = tips.entry(poll_key(sample)).or_insert(height);
+            *tip = (*tip).max(height);
+        }
+    }
+    samples
+        .iter()
+        .map(|s| Some(tips[&poll_key(s)] - s.height?))
+        .collect()
+}
+
+fn mean_of(values: impl Iterator<Item = u64>) -> Option<f64> {
+    let values: Vec<f64> = values.map(|v| v as f64).collect();
+    (!values.is_empty()).then(|| mean(&values))
+}
+
+/// Fill each window's monitor fields from the samples taken in it.
+/// Windows without samples keep `None`.
+pub fn merge_into_windows(windows: &mut [WindowedMetrics], samples: &[MonitorSample]) {
+    let lags = height_lags(samples);
+    for metrics in windows {
+        let in_window: Vec<usize> = (0..samples.len())
+            .filter(|&i| metrics.window.contains(samples[i].time))
+            .collect();
+        metrics.avg_height_lag = mean_of(in_window.iter().filter_map(|&i| lags[i]));
+        metrics.avg_mempool_txs = mean_of(in_window.iter().filter_map(|&i| samples[i].mempool));
+    }
+}
+
+/// Summarize a time series over windows of `window_size_sec`, starting at
+/// the first sample
+pub fn summarize_monitor(
+    samples: &[MonitorSample],
+    window_size_sec: f64,
+    source: &str,
+) -> MonitorReport {
+    let lags = height_lags(samples);
+    let first = samples.iter().map(|s| s.time).reduce(f64::min);
+    let last = samples.iter().map(|s| s.time).reduce(f64::max);
+
+    // Whole windows, so the last sample falls inside one
+    let windows = match (first, last) {
+        (Some(first), Some(last)) => {
+            let count = ((last - first) / window_size_sec).floor() + 1.0;
+            create_time_windows(first, first + count * window_size_sec, window_size_sec)
+        }
+        _ => Vec::new(),
+    };
+    let windows = windows
+        .into_iter()
+        .map(|window| {
+            let in_window: Vec<usize> = (0..samples.len())
+                .filter(|&i| window.contains(samples[i].time))
+                .collect();
+            MonitorWindow {
+                start: window.start,
+                end: window.end,
+                samples: in_window.len(),
+                avg_height_lag: mean_of(in_window.iter().filter_map(|&i| lags[i])),
+                max_height_lag: in_window.iter().filter_map(|&i| lags[i]).max(),
+                avg_mempool_txs: mean_of(in_window.iter().filter_map(|&i| samples[i].mempool)),
+                avg_peers: mean_of(in_window.iter().filter_map(|&i| samples[i].peers)),
+            }
+        })
+        .collect();
+
+    let mut by_node: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (i, sample) in samples.iter().enumerate() {
+        by_node.entry(sample.node.as_str()).or_default().push(i);
+    }
+    let mut per_node: Vec<MonitorNodeSummary> = by_node
+        .into_iter()
+        .map(|(node, mut indices)| {
+            indices.sort_by(|&a, &b| samples[a].time.total_cmp(&samples[b].time));
+            let last_of = |value: fn(&MonitorSample) -> Option<u64>| {
+                indices.iter().rev().find_map(|&i| value(&samples[i]))
+            };
+            MonitorNodeSummary {
+                node_id: node.to_string(),
+                samples: indices.len(),
+                final_height: last_of(|s| s.height),
+                avg_height_lag: mean_of(indices.iter().filter_map(|&i| lags[i])),
+                max_height_lag: indices.iter().filter_map(|&i| lags[i]).max(),
+                avg_mempool_txs: mean_of(indices.iter().filter_map(|&i| samples[i].mempool)),
+                avg_peers: mean_of(indices.iter().filter_map(|&i| samples[i].peers)),
+                bytes_in: last_of(|s| s.bytes_in),
+                bytes_out: last_of(|s| s.bytes_out),
+            }
+        })
+        .collect();
+    per_node.sort_by(|a, b| {
+        b.max_height_lag
+            .cmp(&a.max_height_lag)
+            .then_with(|| a.node_id.cmp(&b.node_id))
+    });
+
+    let mut polls: Vec<u64> = samples.iter().filter_map(|s| s.poll).collect();
+    polls.sort_unstable();
+    polls.dedup();
+
+    MonitorReport {
+        report_schema_version: REPORT_SCHEMA_VERSION,
+        source: source.to_string(),
+        window_size_sec,
+        sample_count: samples.len(),
+        poll_count: polls.len(),
+        first_sample: first,
+        last_sample: last,
+        windows,
+        per_node,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSONL: &str = r#"{"time": 946688400.0, "poll": 1, "node": "miner-001", "height": 12, "mempool": 3, "peers": 8, "bytes_in": 1000, "bytes_out": 2000}
+{"time": 946688400.5, "poll": 1, "node": "user-001", "height": 10, "mempool": 1, "peers": 4, "bytes_in": null, "bytes_out": null}
+
+{"time": 946688460.0, "poll": 2, "node": "miner-001", "height": 13, "mempool": 0, "peers": 8, "bytes_in": 1500, "bytes_out": 2600}
+{"time": 946688460.5, "poll": 2, "node": "user-001", "height": 13, "mempool": 2, "peers": 5}
+"#;
+
+    const CSV: &str = "time,poll,node,height,mempool\n\
+                       946688400.0,1,miner-001,12,3\n\
+                       946688400.5,1,user-001,10,1\n\
+                       946688460.0,2,miner-001,13,0\n\
+                       946688460.5,2,user-001,13,\n";
+
+    #[test]
+    fn jsonl_and_csv_parse_to_the_same_samples() {
+        let jsonl = parse_jsonl(JSONL).unwrap();
+        assert_eq!(jsonl.len(), 4);
+        assert_eq!(jsonl[0].bytes_out, Some(2000));
+        assert_eq!(jsonl[1].bytes_in, None);
+        assert_eq!(jsonl[3].peers, Some(5));
+
+        let csv = parse_csv(CSV).unwrap();
+        assert_eq!(csv.len(), 4);
+        assert_eq!(csv[3].mempool, None, "empty cells are unreported values");
+        for (c, j) in csv.iter().zip(&jsonl) {
+            assert_eq!((c.time, c.poll, &c.node), (j.time, j.poll, &j.node));
+            assert_eq!(c.height, j.height);
+            assert_eq!(c.peers, None, "peers was not recorded");
+            assert_eq!(c.bytes_in, None);
+        }
+    }
+
+    #[test]
+    fn malformed_rows_name_their_line() {
+        let err = parse_jsonl("{\"time\": 1.0, \"node\": \"a\"}\n{\"node\": \"b\"}\n").unwrap_err();
+        assert!(err.to_string().starts_with("line 2:"), "{}", err);
+
+        let err = parse_csv("time,node,height\n1.0,a,5\n2.0,b,tall\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 3: 'tall' is not a count");
+
+        let err = parse_csv("node,height\na,5\n").unwrap_err();
+        assert!(err.to_string().contains("'time' and 'node'"), "{}", err);
+    }
+
+    #[test]
+    fn height_lag_is_measured_within_a_poll() {
+        let samples = parse_jsonl(JSONL).unwrap();
+        assert_eq!(
+            height_lags(&samples),
+            vec![Some(0), Some(2), Some(0), Some(0)]
+        );
+
+        let report = summarize_monitor(&samples, 60.0, "monitor_metrics.jsonl");
+        assert_eq!(report.poll_count, 2);
+        assert_eq!(report.windows.len(), 2);
+        assert_eq!(report.windows[0].avg_height_lag, Some(1.0));
+        assert_eq!(report.windows[0].max_height_lag, Some(2));
+        assert_eq!(report.windows[1].avg_mempool_txs, Some(1.0));
+        assert_eq!(report.per_node[0].node_id, "user-001");
+        assert_eq!(report.per_node[1].final_height, Some(13));
+        assert_eq!(report.per_node[1].bytes_in, Some(1500));
+    }
+
+    #[test]
+    fn samples_merge_into_the_windows_they_fall_in() {
+        let samples = parse_csv(CSV).unwrap();
+        let mut windows: Vec<WindowedMetrics> = create_time_windows(946688400.0, 946688580.0, 60.0)
+            .into_iter()
+            .map(|window| WindowedMetrics {
+                window,
+                ..Default::default()
+            })
+            .collect();
+        merge_into_windows(&mut windows, &samples);
+
+        assert_eq!(windows[0].avg_height_lag, Some(1.0));
+        assert_eq!(windows[0].avg_mempool_txs, Some(2.0));
+        assert_eq!(windows[1].avg_height_lag, Some(0.0));
+        assert_eq!(windows[1].avg_mempool_txs, Some(0.0));
+        assert_eq!(windows[2].avg_height_lag, None);
+    }
+}
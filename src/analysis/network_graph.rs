@@ -5,11 +5,14 @@
 //! - Degree distribution (inbound/outbound)
 //! - Time-based topology snapshots
 //! - GraphViz DOT output for visualization
+//! - Intended vs observed edges (`intended_topology.json`)
 
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
+use super::schema::REPORT_SCHEMA_VERSION;
 use super::types::*;
+use crate::topology::intended::IntendedTopology;
 
 /// A snapshot of the network graph at a specific point in time
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +60,9 @@ struct ActiveConnection {
 /// Full network graph analysis report
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkGraphReport {
+    /// `REPORT_SCHEMA_VERSION` at analysis time
+    #[serde(default)]
+    pub report_schema_version: u32,
     /// Analysis metadata
     pub total_daemon_nodes: usize,
     pub total_unique_connections: usize,
@@ -76,6 +82,47 @@ pub struct NetworkGraphReport {
 
     /// Validation against expected Monero defaults
     pub validation: NetworkValidation,
+
+    /// Diff against the generator's intended topology, when one was given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intended_comparison: Option<IntendedComparison>,
+
+    /// Degrees split by reachability, when the registry marks any daemon
+    /// unreachable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reachability: Option<ReachabilityBreakdown>,
+}
+
+/// Final-state degree distributions of reachable and unreachable daemons
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReachabilityBreakdown {
+    pub reachable: DegreeDistribution,
+    pub unreachable: DegreeDistribution,
+    /// Unreachable nodes that logged an inbound connection at any point;
+    /// empty when NAT held
+    pub unreachable_with_inbound: Vec<String>,
+}
+
+/// Intended vs observed agent-to-agent edges, both taken as undirected
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntendedComparison {
+    pub intended_edges: usize,
+    /// Edges open at any point in the logs
+    pub observed_edges: usize,
+    pub matched_edges: usize,
+    /// Intended but never observed, as `[a, b]` with `a < b`
+    pub missing_edges: Vec<(String, String)>,
+    /// Observed but never intended
+    pub extra_edges: Vec<(String, String)>,
+    /// Nodes with at least one missing or extra edge
+    pub per_node: BTreeMap<String, NodeEdgeDiff>,
+}
+
+/// One node's side of the intended/observed diff
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodeEdgeDiff {
+    pub missing: Vec<String>,
+    pub extra: Vec<String>,
 }
 
 /// Degree distribution statistics
@@ -122,7 +169,8 @@ pub struct ConnectionChurnStats {
 /// Validation against expected network properties
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkValidation {
-    /// Expected max outbound (Monero default: 8)
+    /// Expected max outbound for nodes whose registry entry has no
+    /// `out_peers` (the `--expected-outbound` CLI value)
     pub expected_max_outbound: usize,
     /// Actual max outbound observed
     pub actual_max_outbound: usize,
@@ -134,11 +182,14 @@ pub struct NetworkValidation {
     pub findings: Vec<String>,
 }
 
-/// Analyze the network graph from connection events
+/// Analyze the network graph from connection events. Each node's outbound
+/// count is checked against its registry `out_peers`, falling back to
+/// `expected_outbound`.
 pub fn analyze_network_graph(
     log_data: &HashMap<String, NodeLogData>,
     agents: &[AnalysisAgentInfo],
     snapshot_times: Option<Vec<SimTime>>,
+    expected_outbound: usize,
 ) -> NetworkGraphReport {
     // Build IP to node mapping (only for daemon nodes)
     let daemon_agents: Vec<&AnalysisAgentInfo> = agents
@@ -250,7 +301,7 @@ pub fn analyze_network_graph(
     let final_state = create_snapshot(end_time, &connection_state, &ip_to_node, &daemon_node_ids);
 
     // Calculate degree distribution from final state
-    let degree_distribution = calculate_degree_distribution(&final_state);
+    let degree_distribution = calculate_degree_distribution(final_state.node_degrees.values());
 
     // Calculate churn statistics
     let churn_stats = calculate_churn_stats(
@@ -261,22 +312,30 @@ pub fn analyze_network_graph(
         duration,
     );
 
-    // Validate against expected Monero defaults
-    let validation = validate_network(&final_state, 8); // Monero default is 8 outbound
+    // Validate against the limits the daemons ran with
+    let outbound_limits: HashMap<&str, usize> = daemon_agents
+        .iter()
+        .filter_map(|a| Some((a.id.as_str(), a.out_peers?)))
+        .collect();
+    let mut validation = validate_network(&final_state, expected_outbound, &outbound_limits);
+
+    let reachability = reachability_breakdown(&daemon_agents, &all_events, &final_state);
+    if let Some(r) = reachability
+        .as_ref()
+        .filter(|r| !r.unreachable_with_inbound.is_empty())
+    {
+        validation.findings.push(format!(
+            "{} unreachable node(s) accepted inbound connections: {}",
+            r.unreachable_with_inbound.len(),
+            r.unreachable_with_inbound.join(", ")
+        ));
+    }
 
     // Count unique connections ever observed
-    let mut unique_edges: HashSet<(String, String)> = HashSet::new();
-    for (node_id, node_data) in log_data {
-        for event in &node_data.connection_events {
-            if event.is_open {
-                if let Some(&peer_node) = ip_to_node.get(event.peer_ip.as_str()) {
-                    unique_edges.insert((node_id.clone(), peer_node.to_string()));
-                }
-            }
-        }
-    }
+    let unique_edges = observed_edges(log_data, &ip_to_node);
 
     NetworkGraphReport {
+        report_schema_version: REPORT_SCHEMA_VERSION,
         total_daemon_nodes: daemon_agents.len(),
         total_unique_connections: unique_edges.len(),
         analysis_duration_sec: duration,
@@ -285,6 +344,136 @@ pub fn analyze_network_graph(
         degree_distribution,
         churn_stats,
         validation,
+        intended_comparison: None,
+        reachability,
+    }
+}
+
+/// Split final-state degrees by registry reachability and list unreachable
+/// nodes with any inbound connection. None when no daemon is unreachable.
+fn reachability_breakdown(
+    daemon_agents: &[&AnalysisAgentInfo],
+    events: &[(SimTime, &str, &ConnectionEvent)],
+    final_state: &NetworkSnapshot,
+) -> Option<ReachabilityBreakdown> {
+    let unreachable: HashSet<&str> = daemon_agents
+        .iter()
+        .filter(|a| a.reachable == Some(false))
+        .map(|a| a.id.as_str())
+        .collect();
+    if unreachable.is_empty() {
+        return None;
+    }
+    let class = |want: bool| {
+        calculate_degree_distribution(
+            final_state
+                .node_degrees
+                .values()
+                .filter(|d| unreachable.contains(d.node_id.as_str()) == want),
+        )
+    };
+    let mut unreachable_with_inbound: Vec<String> = events
+        .iter()
+        .filter(|(_, node, event)| {
+            event.is_open
+                && event.direction == ConnectionDirection::Inbound
+                && unreachable.contains(node)
+        })
+        .map(|(_, node, _)| node.to_string())
+        .collect();
+    unreachable_with_inbound.sort();
+    unreachable_with_inbound.dedup();
+    Some(ReachabilityBreakdown {
+        reachable: class(false),
+        unreachable: class(true),
+        unreachable_with_inbound,
+    })
+}
+
+/// Directed (logging node, peer node) pairs of every connection opened
+fn observed_edges(
+    log_data: &HashMap<String, NodeLogData>,
+    ip_to_node: &HashMap<&str, &str>,
+) -> HashSet<(String, String)> {
+    let mut edges = HashSet::new();
+    for (node_id, node_data) in log_data {
+        for event in &node_data.connection_events {
+            if event.is_open {
+                if let Some(&peer_node) = ip_to_node.get(event.peer_ip.as_str()) {
+                    edges.insert((node_id.clone(), peer_node.to_string()));
+                }
+            }
+        }
+    }
+    edges
+}
+
+fn undirected(a: &str, b: &str) -> (String, String) {
+    if a <= b {
+        (a.to_string(), b.to_string())
+    } else {
+        (b.to_string(), a.to_string())
+    }
+}
+
+/// Diff the intended topology against the connections seen in the logs.
+/// Connections to addresses no agent owns are left out of both sides.
+pub fn compare_intended(
+    log_data: &HashMap<String, NodeLogData>,
+    agents: &[AnalysisAgentInfo],
+    intended: &IntendedTopology,
+) -> IntendedComparison {
+    let ip_to_node: HashMap<&str, &str> = agents
+        .iter()
+        .map(|a| (a.ip_addr.as_str(), a.id.as_str()))
+        .collect();
+    let observed: BTreeSet<(String, String)> = observed_edges(log_data, &ip_to_node)
+        .iter()
+        .filter(|(a, b)| a != b)
+        .map(|(a, b)| undirected(a, b))
+        .collect();
+    let wanted: BTreeSet<(String, String)> = intended
+        .iter()
+        .flat_map(|(id, node)| {
+            node.connections
+                .iter()
+                .filter_map(|c| c.target.as_deref())
+                .filter(move |target| target != id)
+                .map(move |target| undirected(id, target))
+        })
+        .collect();
+
+    let missing_edges: Vec<(String, String)> = wanted.difference(&observed).cloned().collect();
+    let extra_edges: Vec<(String, String)> = observed.difference(&wanted).cloned().collect();
+    let mut per_node: BTreeMap<String, NodeEdgeDiff> = BTreeMap::new();
+    for (a, b) in &missing_edges {
+        per_node
+            .entry(a.clone())
+            .or_default()
+            .missing
+            .push(b.clone());
+        per_node
+            .entry(b.clone())
+            .or_default()
+            .missing
+            .push(a.clone());
+    }
+    for (a, b) in &extra_edges {
+        per_node.entry(a.clone()).or_default().extra.push(b.clone());
+        per_node.entry(b.clone()).or_default().extra.push(a.clone());
+    }
+    for diff in per_node.values_mut() {
+        diff.missing.sort();
+        diff.extra.sort();
+    }
+
+    IntendedComparison {
+        intended_edges: wanted.len(),
+        observed_edges: observed.len(),
+        matched_edges: wanted.intersection(&observed).count(),
+        missing_edges,
+        extra_edges,
+        per_node,
     }
 }
 
@@ -381,7 +570,9 @@ fn create_snapshot(
 }
 
 /// Calculate degree distribution statistics
-fn calculate_degree_distribution(snapshot: &NetworkSnapshot) -> DegreeDistribution {
+fn calculate_degree_distribution<'a>(
+    degrees: impl IntoIterator<Item = &'a NodeDegree>,
+) -> DegreeDistribution {
     let mut outbound_histogram: HashMap<usize, usize> = HashMap::new();
     let mut inbound_histogram: HashMap<usize, usize> = HashMap::new();
     let mut total_histogram: HashMap<usize, usize> = HashMap::new();
@@ -389,7 +580,7 @@ fn calculate_degree_distribution(snapshot: &NetworkSnapshot) -> DegreeDistributi
     let mut outbound_values: Vec<usize> = Vec::new();
     let mut inbound_values: Vec<usize> = Vec::new();
 
-    for degree in snapshot.node_degrees.values() {
+    for degree in degrees {
         *outbound_histogram.entry(degree.outbound).or_insert(0) += 1;
         *inbound_histogram.entry(degree.inbound).or_insert(0) += 1;
         *total_histogram.entry(degree.total).or_insert(0) += 1;
@@ -481,8 +672,13 @@ fn calculate_churn_stats(
     }
 }
 
-/// Validate network against expected properties
-fn validate_network(snapshot: &NetworkSnapshot, expected_max_outbound: usize) -> NetworkValidation {
+/// Validate network against expected properties; `outbound_limits`
+/// overrides `expected_max_outbound` per node
+fn validate_network(
+    snapshot: &NetworkSnapshot,
+    expected_max_outbound: usize,
+    outbound_limits: &HashMap<&str, usize>,
+) -> NetworkValidation {
     let actual_max_outbound = snapshot
         .node_degrees
         .values()
@@ -493,7 +689,13 @@ fn validate_network(snapshot: &NetworkSnapshot, expected_max_outbound: usize) ->
     let nodes_exceeding: Vec<String> = snapshot
         .node_degrees
         .iter()
-        .filter(|(_, d)| d.outbound > expected_max_outbound)
+        .filter(|(id, d)| {
+            let limit = outbound_limits
+                .get(id.as_str())
+                .copied()
+                .unwrap_or(expected_max_outbound);
+            d.outbound > limit
+        })
         .map(|(id, _)| id.clone())
         .collect();
 
@@ -501,12 +703,17 @@ fn validate_network(snapshot: &NetworkSnapshot, expected_max_outbound: usize) ->
 
     let mut findings = Vec::new();
 
-    if !outbound_valid {
+    if !outbound_valid && outbound_limits.is_empty() {
         findings.push(format!(
             "{} nodes exceed expected outbound limit of {}",
             nodes_exceeding.len(),
             expected_max_outbound
         ));
+    } else if !outbound_valid {
+        findings.push(format!(
+            "{} nodes exceed the outbound limit they were configured with",
+            nodes_exceeding.len()
+        ));
     }
 
     if snapshot.isolated_nodes.len() > 0 {
@@ -573,3 +780,124 @@ pub fn generate_dot(snapshot: &NetworkSnapshot, _agents: &[AnalysisAgentInfo]) -
     dot.push_str("}\n");
     dot
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::testutil::SyntheticRun;
+    use crate::topology::intended::{ConnectionType, IntendedConnection, IntendedNode};
+
+    fn intended(run: &SyntheticRun, edges: &[(&str, &str)]) -> IntendedTopology {
+        let mut topology = IntendedTopology::new();
+        for agent in &run.agents {
+            topology.insert(
+                agent.id.clone(),
+                IntendedNode {
+                    ip: agent.ip_addr.clone(),
+                    connections: Vec::new(),
+                },
+            );
+        }
+        for (from, to) in edges {
+            topology
+                .get_mut(*from)
+                .unwrap()
+                .connections
+                .push(IntendedConnection {
+                    target: Some(to.to_string()),
+                    address: format!("{}:18080", run.ip(to)),
+                    connection_type: ConnectionType::Priority,
+                });
+        }
+        topology
+    }
+
+    #[test]
+    fn reports_missing_and_extra_edges_per_node() {
+        let mut run = SyntheticRun::new();
+        run.agent("a", "")
+            .agent("b", "")
+            .agent("c", "")
+            .agent("d", "")
+            // a-b intended and seen from the other end; c-d seen, not intended
+            .connect("b", "a", 10.0)
+            .connect("c", "d", 20.0);
+        let topology = intended(&run, &[("a", "b"), ("a", "c"), ("c", "a")]);
+
+        let diff = compare_intended(&run.log_map(), &run.agents, &topology);
+        assert_eq!(diff.intended_edges, 2);
+        assert_eq!(diff.observed_edges, 2);
+        assert_eq!(diff.matched_edges, 1);
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+        assert_eq!(diff.missing_edges, [pair("a", "c")]);
+        assert_eq!(diff.extra_edges, [pair("c", "d")]);
+        assert_eq!(diff.per_node.len(), 3, "b has no diff");
+        assert_eq!(diff.per_node["a"].missing, ["c"]);
+        assert_eq!(diff.per_node["c"].missing, ["a"]);
+        assert_eq!(diff.per_node["c"].extra, ["d"]);
+        assert!(diff.per_node["d"].missing.is_empty());
+    }
+
+    #[test]
+    fn splits_degrees_by_reachability_and_flags_inbound_to_unreachable() {
+        let mut run = SyntheticRun::new();
+        run.agent("a", "")
+            .agent("b", "")
+            .agent("nat1", "")
+            .agent("nat2", "")
+            .unreachable("nat1")
+            .unreachable("nat2")
+            .connect("nat1", "a", 10.0)
+            .connect("nat2", "a", 11.0)
+            .connect("nat2", "b", 12.0)
+            .connect("a", "b", 13.0);
+
+        let report = analyze_network_graph(&run.log_map(), &run.agents, None, 8);
+        let r = report.reachability.expect("unreachable agents registered");
+        assert_eq!(r.unreachable.outbound_stats.mean, 1.5);
+        assert_eq!(r.unreachable.inbound_stats.max, 0);
+        assert_eq!(r.reachable.inbound_stats.mean, 2.0);
+        assert!(r.unreachable_with_inbound.is_empty());
+
+        // b dialing nat1 means the NAT leaked
+        run.connect("b", "nat1", 14.0);
+        let report = analyze_network_graph(&run.log_map(), &run.agents, None, 8);
+        assert_eq!(
+            report.reachability.unwrap().unreachable_with_inbound,
+            ["nat1"]
+        );
+        assert!(report
+            .validation
+            .findings
+            .iter()
+            .any(|f| f.contains("accepted inbound connections: nat1")));
+
+        run.agents.iter_mut().for_each(|a| a.reachable = None);
+        let report = analyze_network_graph(&run.log_map(), &run.agents, None, 8);
+        assert!(report.reachability.is_none());
+    }
+
+    #[test]
+    fn outbound_is_checked_against_each_nodes_registry_limit() {
+        let mut run = SyntheticRun::new();
+        run.agent("a", "")
+            .agent("b", "")
+            .agent("c", "")
+            .connect("a", "b", 10.0)
+            .connect("a", "c", 11.0)
+            .connect("b", "c", 12.0);
+
+        let report = analyze_network_graph(&run.log_map(), &run.agents, None, 1);
+        assert_eq!(report.validation.nodes_exceeding_outbound, ["a"]);
+
+        // a ran with room for both peers, b with none
+        run.out_peers("a", 2).out_peers("b", 0);
+        let report = analyze_network_graph(&run.log_map(), &run.agents, None, 8);
+        assert_eq!(report.validation.nodes_exceeding_outbound, ["b"]);
+        assert!(report
+            .validation
+            .findings
+            .iter()
+            .any(|f| f.contains("exceed the outbound limit they were configured with")));
+    }
+}
@@ -1,17 +1,27 @@
 //! Network resilience analysis.
 //!
 //! Analyzes network connectivity, centralization metrics, and partition risk
-//! based on connection patterns observed in simulation logs.
+//! based on connection patterns observed in simulation logs. With a
+//! `network.events` schedule it also reports what each scheduled partition
+//! did: the connections it cut, how long the network took to re-converge
+//! after the heal, and the transactions stuck on one side.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use super::stats::gini;
 use super::types::*;
+use crate::topology::{partition_windows, PartitionWindow, ScheduledNetworkEvent};
 
-/// Analyze network resilience based on connection topology
+/// Seconds after a partition within which closed cross-group connections
+/// are attributed to it
+pub const PARTITION_DROP_WINDOW_SECS: f64 = 60.0;
+
+/// Analyze network resilience based on connection topology, and each
+/// partition in `events` (the manifest's `network_events`; empty for none)
 pub fn analyze_resilience(
     log_data: &HashMap<String, NodeLogData>,
     agents: &[AnalysisAgentInfo],
+    events: &[ScheduledNetworkEvent],
 ) -> ResilienceMetrics {
     // Build adjacency graph from connection events
     let graph = build_connection_graph(log_data, agents);
@@ -25,11 +35,174 @@ pub fn analyze_resilience(
     // Partition risk
     let partition_risk = analyze_partition_risk(&graph);
 
+    let partition_events = partition_windows(events)
+        .iter()
+        .map(|window| analyze_partition_event(log_data, agents, window))
+        .collect();
+
     ResilienceMetrics {
         connectivity,
         centralization,
         partition_risk,
+        partition_events,
+    }
+}
+
+/// Connections cut by one scheduled partition and the recovery after its
+/// heal. Connections are counted once per pair of nodes, whichever end
+/// logged them.
+fn analyze_partition_event(
+    log_data: &HashMap<String, NodeLogData>,
+    agents: &[AnalysisAgentInfo],
+    window: &PartitionWindow<'_>,
+) -> PartitionEventMetrics {
+    let groups = &window.partition.groups;
+    let at = window.partition.at as SimTime;
+    let heal_at = window.heal_at.map(|h| h as SimTime);
+    let heal_or_end = heal_at.unwrap_or(SimTime::INFINITY);
+
+    let group_of: HashMap<&str, usize> = groups
+        .iter()
+        .enumerate()
+        .flat_map(|(g, ids)| ids.iter().map(move |id| (id.as_str(), g)))
+        .collect();
+    let ip_to_node: HashMap<&str, &str> = agents
+        .iter()
+        .map(|a| (a.ip_addr.as_str(), a.id.as_str()))
+        .collect();
+    // The unordered pair behind a connection between two groups
+    let cross_pair = |node: &str, peer_ip: &str| -> Option<(String, String)> {
+        let peer = *ip_to_node.get(peer_ip)?;
+        let (a, b) = (group_of.get(node)?, group_of.get(peer)?);
+        (a != b).then(|| {
+            let (x, y) = if node < peer {
+                (node, peer)
+            } else {
+                (peer, node)
+            };
+            (x.to_string(), y.to_string())
+        })
+    };
+    let grouped_nodes = || {
+        log_data
+            .iter()
+            .filter(|(id, _)| group_of.contains_key(id.as_str()))
+    };
+
+    // Cross-group pairs with a connection open just before `t`
+    let open_before = |t: SimTime| -> usize {
+        let mut pairs = BTreeSet::new();
+        for (node_id, data) in grouped_nodes() {
+            let mut active: HashMap<&str, (String, String)> = HashMap::new();
+            for event in data.connection_events.iter().filter(|e| e.timestamp < t) {
+                if !event.is_open {
+                    active.remove(event.connection_id.as_str());
+                } else if let Some(pair) = cross_pair(node_id, &event.peer_ip) {
+                    active.insert(event.connection_id.as_str(), pair);
+                }
+            }
+            pairs.extend(active.into_values());
+        }
+        pairs.len()
+    };
+
+    let mut dropped = BTreeSet::new();
+    let mut last_drop: Option<SimTime> = None;
+    let mut first_reconnect: Option<SimTime> = None;
+    for (node_id, data) in grouped_nodes() {
+        for event in &data.connection_events {
+            let Some(pair) = cross_pair(node_id, &event.peer_ip) else {
+                continue;
+            };
+            if !event.is_open
+                && event.timestamp >= at
+                && event.timestamp <= at + PARTITION_DROP_WINDOW_SECS
+            {
+                dropped.insert(pair);
+                last_drop = Some(last_drop.map_or(event.timestamp, |t| t.max(event.timestamp)));
+            } else if event.is_open && heal_at.is_some_and(|h| event.timestamp >= h) {
+                first_reconnect =
+                    Some(first_reconnect.map_or(event.timestamp, |t| t.min(event.timestamp)));
+            }
+        }
     }
+
+    let cross_group_connections_during = open_before(heal_or_end);
+    PartitionEventMetrics {
+        partition_at: at,
+        heal_at,
+        group_sizes: groups.iter().map(Vec::len).collect(),
+        cross_group_connections_before: open_before(at),
+        cross_group_drops: dropped.len(),
+        drop_latency_secs: last_drop.map(|t| t - at),
+        cross_group_connections_during,
+        detected: !dropped.is_empty() && cross_group_connections_during == 0,
+        first_reconnect_secs: heal_at.zip(first_reconnect).map(|(h, t)| t - h),
+        reconverge_secs: heal_at.and_then(|h| reconverge_secs(log_data, &group_of, h)),
+        stuck_transactions: stuck_transactions(log_data, &group_of, groups.len(), at, heal_or_end),
+    }
+}
+
+/// Seconds from `heal_at` until every grouped node has seen the first block
+/// any of them saw after it
+fn reconverge_secs(
+    log_data: &HashMap<String, NodeLogData>,
+    group_of: &HashMap<&str, usize>,
+    heal_at: SimTime,
+) -> Option<f64> {
+    let grouped: Vec<&NodeLogData> = group_of
+        .keys()
+        .map(|id| log_data.get(*id))
+        .collect::<Option<_>>()?;
+    let first_after = grouped
+        .iter()
+        .flat_map(|data| &data.block_observations)
+        .filter(|obs| obs.timestamp >= heal_at)
+        .min_by(|a, b| a.timestamp.total_cmp(&b.timestamp))?;
+    grouped
+        .iter()
+        .map(|data| {
+            data.block_observations
+                .iter()
+                .filter(|obs| obs.block_hash == first_after.block_hash)
+                .map(|obs| obs.timestamp)
+                .min_by(SimTime::total_cmp)
+        })
+        .try_fold(heal_at, |latest, seen| Some(latest.max(seen?)))
+        .map(|latest| latest - heal_at)
+}
+
+/// Transactions first seen in `[start, end)` that some group of `group_of`
+/// had not seen by `end`, sorted
+fn stuck_transactions(
+    log_data: &HashMap<String, NodeLogData>,
+    group_of: &HashMap<&str, usize>,
+    group_count: usize,
+    start: SimTime,
+    end: SimTime,
+) -> Vec<String> {
+    let mut first_seen: HashMap<&str, SimTime> = HashMap::new();
+    let mut reached: HashMap<&str, BTreeSet<usize>> = HashMap::new();
+    for (node_id, data) in log_data {
+        let group = group_of.get(node_id.as_str());
+        for obs in &data.tx_observations {
+            let first = first_seen
+                .entry(obs.tx_hash.as_str())
+                .or_insert(obs.timestamp);
+            *first = first.min(obs.timestamp);
+            if let Some(&g) = group.filter(|_| obs.timestamp < end) {
+                reached.entry(obs.tx_hash.as_str()).or_default().insert(g);
+            }
+        }
+    }
+    let mut stuck: Vec<String> = first_seen
+        .into_iter()
+        .filter(|(_, t)| (start..end).contains(t))
+        .filter(|(hash, _)| reached.get(hash).map_or(0, BTreeSet::len) < group_count)
+        .map(|(hash, _)| hash.to_string())
+        .collect();
+    stuck.sort();
+    stuck
 }
 
 /// Build a graph of active connections (node_id -> set of connected peer IPs)
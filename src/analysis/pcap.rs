@@ -0,0 +1,159 @@
+//! Pcap traces of agents with `capture_pcap`.
+//!
+//! Shadow writes one trace per network interface into each captured host's
+//! directory, `<data_dir>/hosts/<agent>/eth0.pcap` (and `lo.pcap`).
+//! `run_sim.sh` moves them into `general.pcap_capture_dir/<agent>/` when the
+//! config sets one. Nothing here parses the traces; `index_pcaps` only finds
+//! them so Wireshark, tshark or scripts can be pointed at the right files.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Result};
+
+use super::schema::REPORT_SCHEMA_VERSION;
+use super::types::{AgentPcaps, PcapFile, PcapIndex};
+use crate::manifest::PcapCapture;
+
+/// File name of the index in the output directory
+pub const PCAP_INDEX_FILE: &str = "pcap_index.json";
+
+/// `general.pcap_capture_dir` as `run_sim.sh` used it: relative paths are
+/// under the archive directory, which holds `data_dir` (`shadow.data`)
+pub fn resolve_capture_dir(data_dir: &Path, capture_dir: &str) -> PathBuf {
+    let dir = Path::new(capture_dir);
+    if dir.is_absolute() {
+        return dir.to_path_buf();
+    }
+    data_dir.parent().unwrap_or(Path::new(".")).join(dir)
+}
+
+/// `*.pcap` files one directory below `root`, keyed by that directory's
+/// name (the agent id); empty when `root` does not exist
+pub fn find_pcaps(root: &Path) -> Result<BTreeMap<String, Vec<PathBuf>>> {
+    let mut found = BTreeMap::new();
+    if !root.is_dir() {
+        return Ok(found);
+    }
+    let entries =
+        std::fs::read_dir(root).map_err(|e| eyre!("Failed to read {}: {}", root.display(), e))?;
+    for host_dir in entries.flatten().map(|e| e.path()) {
+        let Ok(files) = std::fs::read_dir(&host_dir) else {
+            continue;
+        };
+        let mut pcaps: Vec<PathBuf> = files
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "pcap"))
+            .collect();
+        if pcaps.is_empty() {
+            continue;
+        }
+        pcaps.sort();
+        let agent = host_dir
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        found.insert(agent, pcaps);
+    }
+    Ok(found)
+}
+
+/// Every agent's traces, from Shadow's host directories under `data_dir`
+/// and from `capture_dir` (where a trace of the same name wins). `capture`
+/// is the manifest's section: agents it lists without traces are `missing`.
+pub fn index_pcaps(
+    data_dir: &Path,
+    capture_dir: Option<&Path>,
+    capture: Option<&PcapCapture>,
+) -> Result<PcapIndex> {
+    let mut found = find_pcaps(&data_dir.join("hosts"))?;
+    if let Some(dir) = capture_dir {
+        for (agent, pcaps) in find_pcaps(dir)? {
+            let files = found.entry(agent).or_default();
+            files.retain(|f| !pcaps.iter().any(|p| p.file_name() == f.file_name()));
+            files.extend(pcaps);
+            files.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+        }
+    }
+
+    let expected: Option<Vec<&String>> = capture.map(|c| c.agents.iter().collect());
+    let agents = found
+        .into_iter()
+        .map(|(agent_id, paths)| AgentPcaps {
+            expected: expected.as_ref().map(|e| e.contains(&&agent_id)),
+            files: paths
+                .iter()
+                .map(|path| PcapFile {
+                    path: path.display().to_string(),
+                    interface: path
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                    size_bytes: std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+                })
+                .collect(),
+            agent_id,
+        })
+        .collect::<Vec<_>>();
+    let missing = expected
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|id| !agents.iter().any(|a| &a.agent_id == *id))
+        .cloned()
+        .collect();
+    Ok(PcapIndex {
+        report_schema_version: REPORT_SCHEMA_VERSION,
+        data_dir: data_dir.display().to_string(),
+        capture_dir: capture_dir.map(|d| d.display().to_string()),
+        agents,
+        missing,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn touch(path: &Path, bytes: usize) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, vec![0u8; bytes]).unwrap();
+    }
+
+    #[test]
+    fn traces_are_found_in_host_and_capture_dirs() {
+        let tmp = TempDir::new().unwrap();
+        let data_dir = tmp.path().join("shadow.data");
+        touch(&data_dir.join("hosts/user-001/eth0.pcap"), 10);
+        touch(&data_dir.join("hosts/user-001/lo.pcap"), 1);
+        touch(&data_dir.join("hosts/user-001/monerod.1000.stdout"), 5);
+        touch(&data_dir.join("hosts/user-002/monerod.1000.stdout"), 5);
+        let capture_dir = resolve_capture_dir(&data_dir, "pcap");
+        assert_eq!(capture_dir, tmp.path().join("pcap"));
+        touch(&capture_dir.join("miner-001/eth0.pcap"), 20);
+
+        let capture = PcapCapture {
+            agents: ["miner-001", "user-001", "user-003"]
+                .map(String::from)
+                .to_vec(),
+            capture_dir: Some("pcap".to_string()),
+        };
+        let index = index_pcaps(&data_dir, Some(&capture_dir), Some(&capture)).unwrap();
+
+        let ids: Vec<&str> = index.agents.iter().map(|a| a.agent_id.as_str()).collect();
+        assert_eq!(ids, ["miner-001", "user-001"]);
+        assert!(index.agents.iter().all(|a| a.expected == Some(true)));
+        let user = &index.agents[1];
+        let interfaces: Vec<&str> = user.files.iter().map(|f| f.interface.as_str()).collect();
+        assert_eq!(interfaces, ["eth0", "lo"]);
+        assert_eq!(user.files[0].size_bytes, 10);
+        assert_eq!(index.missing, ["user-003"]);
+
+        // Without the manifest nothing is known to be missing
+        let index = index_pcaps(&data_dir, None, None).unwrap();
+        assert_eq!(index.agents.len(), 1);
+        assert_eq!(index.agents[0].expected, None);
+        assert!(index.missing.is_empty());
+    }
+}
@@ -1,40 +1,140 @@
 //! Transaction propagation timing analysis.
 //!
 //! Analyzes how quickly transactions propagate through the network and
-//! identifies bottleneck nodes.
+//! identifies bottleneck nodes. Coverage curves give, per transaction, the
+//! time from creation until a percentile of the daemons had seen it, and
+//! network-wide the median of those times. Given the generated
+//! `latency_matrix.json`, relay delays are also set against the network's
+//! lower bound. With registry geography, relay hops are also broken down by
+//! continent and AS (see [`RegionBreakdown`]).
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use super::stats::{mean, median, percentile};
 use super::types::*;
+use crate::topology::latency::AgentLatencyMatrix;
 
-/// Analyze propagation timing for all transactions
+/// Coverage percentiles reported unless others are requested
+pub const DEFAULT_COVERAGE_PERCENTILES: [f64; 6] = [10.0, 25.0, 50.0, 75.0, 90.0, 99.0];
+
+/// Share of a transaction's propagation time its slowest hop must take to
+/// count as an AS-boundary bottleneck
+pub const AS_BOTTLENECK_SHARE: f64 = 0.5;
+
+/// Analyze propagation timing for all transactions. With
+/// `exclude_intra_node`, hop pairs between agents on the same GML node are
+/// left out of the hop latency aggregates (they are always counted).
+/// `percentiles` (0-100] select the coverage curve points. With `latency`
+/// the report carries each hop's [`LatencyOverhead`].
 pub fn analyze_propagation(
     transactions: &[Transaction],
     blocks: &[BlockInfo],
     log_data: &HashMap<String, NodeLogData>,
-    total_nodes: usize,
+    agents: &[AnalysisAgentInfo],
+    exclude_intra_node: bool,
+    percentiles: &[f64],
+    latency: Option<&AgentLatencyMatrix>,
 ) -> PropagationReport {
-    // Build TX hash to block inclusion time mapping
-    let mut tx_to_block: HashMap<String, (u64, SimTime)> = HashMap::new();
-
     // We need to estimate block times from log data
     // Collect all block observations and find the earliest time for each height
     let mut block_times: HashMap<u64, SimTime> = HashMap::new();
-    for (_, node_data) in log_data {
-        for obs in &node_data.block_observations {
-            block_times
-                .entry(obs.height)
-                .and_modify(|t| {
-                    if obs.timestamp < *t {
-                        *t = obs.timestamp;
-                    }
-                })
-                .or_insert(obs.timestamp);
-        }
+    for node_data in log_data.values() {
+        record_block_times(&mut block_times, &node_data.block_observations);
+    }
+
+    let observations = log_data.values().flat_map(|d| &d.tx_observations);
+    let tx_observations = group_by_tx(observations);
+    let mut report = propagation_report(
+        transactions,
+        blocks,
+        &block_times,
+        &tx_observations,
+        agents,
+        exclude_intra_node,
+        percentiles,
+    );
+    report.latency_overhead = latency.map(|matrix| {
+        latency_overhead(&relay_hops(transactions, &tx_observations, agents), matrix)
+    });
+    report
+}
+
+/// [`analyze_propagation`] over nodes parsed one at a time (see
+/// `LogIndex::nodes`). Only the nodes' tx observations are kept; every
+/// other event is dropped as soon as its node has been read.
+pub fn analyze_propagation_streamed(
+    transactions: &[Transaction],
+    blocks: &[BlockInfo],
+    nodes: impl IntoIterator<Item = NodeLogData>,
+    agents: &[AnalysisAgentInfo],
+    exclude_intra_node: bool,
+    percentiles: &[f64],
+    latency: Option<&AgentLatencyMatrix>,
+) -> PropagationReport {
+    let mut block_times: HashMap<u64, SimTime> = HashMap::new();
+    let mut observations: Vec<TxObservation> = Vec::new();
+    for node_data in nodes {
+        record_block_times(&mut block_times, &node_data.block_observations);
+        observations.extend(node_data.tx_observations);
+    }
+
+    let tx_observations = group_by_tx(&observations);
+    let mut report = propagation_report(
+        transactions,
+        blocks,
+        &block_times,
+        &tx_observations,
+        agents,
+        exclude_intra_node,
+        percentiles,
+    );
+    report.latency_overhead = latency.map(|matrix| {
+        latency_overhead(&relay_hops(transactions, &tx_observations, agents), matrix)
+    });
+    report
+}
+
+/// Keep the earliest sighting of each block height
+fn record_block_times(block_times: &mut HashMap<u64, SimTime>, observations: &[BlockObservation]) {
+    for obs in observations {
+        block_times
+            .entry(obs.height)
+            .and_modify(|t| {
+                if obs.timestamp < *t {
+                    *t = obs.timestamp;
+                }
+            })
+            .or_insert(obs.timestamp);
+    }
+}
+
+/// Build TX hash to observations mapping
+fn group_by_tx<'a>(
+    observations: impl IntoIterator<Item = &'a TxObservation>,
+) -> HashMap<String, Vec<&'a TxObservation>> {
+    let mut tx_observations: HashMap<String, Vec<&TxObservation>> = HashMap::new();
+    for obs in observations {
+        tx_observations
+            .entry(obs.tx_hash.clone())
+            .or_default()
+            .push(obs);
     }
+    tx_observations
+}
+
+fn propagation_report(
+    transactions: &[Transaction],
+    blocks: &[BlockInfo],
+    block_times: &HashMap<u64, SimTime>,
+    tx_observations: &HashMap<String, Vec<&TxObservation>>,
+    agents: &[AnalysisAgentInfo],
+    exclude_intra_node: bool,
+    percentiles: &[f64],
+) -> PropagationReport {
+    let total_nodes = agents.len();
 
     // Map TX hashes to their block inclusion time
+    let mut tx_to_block: HashMap<String, (u64, SimTime)> = HashMap::new();
     for block in blocks {
         if let Some(&block_time) = block_times.get(&block.height) {
             for tx_hash in &block.transactions {
@@ -43,17 +143,6 @@ pub fn analyze_propagation(
         }
     }
 
-    // Build TX hash to observations mapping
-    let mut tx_observations: HashMap<String, Vec<&TxObservation>> = HashMap::new();
-    for (_, node_data) in log_data {
-        for obs in &node_data.tx_observations {
-            tx_observations
-                .entry(obs.tx_hash.clone())
-                .or_default()
-                .push(obs);
-        }
-    }
-
     // Analyze each transaction
     let analyses: Vec<PropagationAnalysis> = transactions
         .iter()
@@ -64,6 +153,7 @@ pub fn analyze_propagation(
                 observations,
                 tx_to_block.get(&tx.tx_hash).cloned(),
                 total_nodes,
+                percentiles,
             ))
         })
         .collect();
@@ -80,7 +170,12 @@ pub fn analyze_propagation(
         .collect();
 
     // Find bottleneck nodes
-    let bottleneck_nodes = identify_bottlenecks(&analyses, &tx_observations);
+    let bottleneck_nodes = identify_bottlenecks(&analyses, tx_observations);
+
+    let hops = relay_hops(transactions, tx_observations, agents);
+    let hop_latency = hop_latency_stats(&hops, agents, exclude_intra_node);
+    let by_continent = continent_delays(tx_observations, agents);
+    let by_region = region_breakdown(&hops, &analyses, agents);
 
     PropagationReport {
         total_transactions: transactions.len(),
@@ -89,8 +184,13 @@ pub fn analyze_propagation(
         median_propagation_ms: median(&propagation_times),
         p95_propagation_ms: percentile(&propagation_times, 95.0),
         average_confirmation_delay_sec: mean(&confirmation_delays),
+        coverage_curve: coverage_curve(&analyses, percentiles),
+        hop_latency: Some(hop_latency),
+        by_continent,
+        by_region,
         bottleneck_nodes,
         per_tx_analysis: analyses,
+        latency_overhead: None,
     }
 }
 
@@ -100,6 +200,7 @@ fn analyze_single_tx_propagation(
     observations: &[&TxObservation],
     block_info: Option<(u64, SimTime)>,
     total_nodes: usize,
+    percentiles: &[f64],
 ) -> PropagationAnalysis {
     // Sort observations by timestamp
     let mut sorted_obs: Vec<&TxObservation> = observations.to_vec();
@@ -154,9 +255,384 @@ fn analyze_single_tx_propagation(
         nodes_observed,
         total_nodes,
         propagation_coverage,
+        coverage: coverage_times(tx, &sorted_obs, total_nodes, percentiles),
     }
 }
 
+/// Time from `tx`'s creation until each of `percentiles` of `total_nodes`
+/// daemons (rounded up, at least one) had seen it. The sender's daemon
+/// holds it from creation; every other daemon counts from its first
+/// sighting.
+pub(super) fn coverage_times(
+    tx: &Transaction,
+    observations: &[&TxObservation],
+    total_nodes: usize,
+    percentiles: &[f64],
+) -> Vec<CoveragePoint> {
+    let mut first_seen: HashMap<&str, SimTime> = HashMap::new();
+    first_seen.insert(&tx.sender_id, tx.timestamp);
+    for obs in observations {
+        first_seen
+            .entry(obs.node_id.as_str())
+            .and_modify(|t| *t = t.min(obs.timestamp))
+            .or_insert(obs.timestamp);
+    }
+    let mut arrivals: Vec<SimTime> = first_seen.into_values().collect();
+    arrivals.sort_by(f64::total_cmp);
+
+    percentiles
+        .iter()
+        .map(|&percentile| {
+            let needed = ((percentile / 100.0 * total_nodes as f64).ceil() as usize).max(1);
+            CoveragePoint {
+                percentile,
+                time_ms: arrivals
+                    .get(needed - 1)
+                    .map(|t| (t - tx.timestamp) * 1000.0),
+            }
+        })
+        .collect()
+}
+
+/// Median time to each percentile over the transactions that reached it
+fn coverage_curve(
+    analyses: &[PropagationAnalysis],
+    percentiles: &[f64],
+) -> Vec<CoverageCurvePoint> {
+    percentiles
+        .iter()
+        .enumerate()
+        .map(|(i, &percentile)| {
+            let times: Vec<f64> = analyses
+                .iter()
+                .filter_map(|a| a.coverage.get(i)?.time_ms)
+                .collect();
+            CoverageCurvePoint {
+                percentile,
+                median_ms: (!times.is_empty()).then(|| median(&times)),
+                transactions: times.len(),
+            }
+        })
+        .collect()
+}
+
+/// A node's first sighting of a tx, heard from `peer` `delay_ms` after the
+/// peer's own first sighting (the creation time when the peer is the
+/// sender)
+struct RelayHop<'a> {
+    tx_hash: &'a str,
+    node: &'a str,
+    peer: &'a str,
+    delay_ms: f64,
+}
+
+/// Every relay hop, in transaction order and by receiving node within a
+/// transaction
+fn relay_hops<'a>(
+    transactions: &'a [Transaction],
+    tx_observations: &'a HashMap<String, Vec<&'a TxObservation>>,
+    agents: &'a [AnalysisAgentInfo],
+) -> Vec<RelayHop<'a>> {
+    let ip_to_agent: HashMap<&str, &str> = agents
+        .iter()
+        .map(|a| (a.ip_addr.as_str(), a.id.as_str()))
+        .collect();
+
+    let mut hops = Vec::new();
+    for tx in transactions {
+        let Some(observations) = tx_observations.get(&tx.tx_hash) else {
+            continue;
+        };
+        // First sighting per node; the sender "sees" it at creation.
+        let mut first_seen: BTreeMap<&str, &TxObservation> = BTreeMap::new();
+        for obs in observations {
+            first_seen
+                .entry(obs.node_id.as_str())
+                .and_modify(|o| {
+                    if obs.timestamp < o.timestamp {
+                        *o = obs;
+                    }
+                })
+                .or_insert(obs);
+        }
+        for (node, obs) in &first_seen {
+            let Some(&peer) = ip_to_agent.get(obs.source_ip.as_str()) else {
+                continue;
+            };
+            let peer_time = if peer == tx.sender_id {
+                Some(tx.timestamp)
+            } else {
+                first_seen.get(peer).map(|o| o.timestamp)
+            };
+            let Some(peer_time) = peer_time.filter(|t| *t <= obs.timestamp) else {
+                continue;
+            };
+            hops.push(RelayHop {
+                tx_hash: &tx.tx_hash,
+                node,
+                peer,
+                delay_ms: (obs.timestamp - peer_time) * 1000.0,
+            });
+        }
+    }
+    hops
+}
+
+/// Aggregate the relay hops' delays, classifying pairs by GML node
+/// placement.
+fn hop_latency_stats(
+    hops: &[RelayHop],
+    agents: &[AnalysisAgentInfo],
+    exclude_intra_node: bool,
+) -> HopLatencyStats {
+    let placement: HashMap<&str, u32> = agents
+        .iter()
+        .filter_map(|a| a.network_node_id.map(|n| (a.id.as_str(), n)))
+        .collect();
+
+    let mut inter = Vec::new();
+    let mut intra = Vec::new();
+    for hop in hops {
+        match (placement.get(hop.node), placement.get(hop.peer)) {
+            (Some(a), Some(b)) if a == b => intra.push(hop.delay_ms),
+            _ => inter.push(hop.delay_ms),
+        }
+    }
+
+    let pairs = inter.len() + intra.len();
+    let aggregate: Vec<f64> = if exclude_intra_node {
+        inter
+    } else {
+        inter.into_iter().chain(intra.iter().copied()).collect()
+    };
+    HopLatencyStats {
+        pairs,
+        intra_node_pairs: intra.len(),
+        intra_node_fraction: if pairs > 0 {
+            intra.len() as f64 / pairs as f64
+        } else {
+            0.0
+        },
+        placement_known: !placement.is_empty(),
+        intra_node_excluded: exclude_intra_node,
+        average_ms: mean(&aggregate),
+        median_ms: median(&aggregate),
+        p95_ms: percentile(&aggregate, 95.0),
+        intra_node_average_ms: mean(&intra),
+    }
+}
+
+/// Each hop's delay less the matrix latency from its relaying agent to its
+/// receiving one, with the pairs whose median overhead is an outlier
+fn latency_overhead(hops: &[RelayHop], matrix: &AgentLatencyMatrix) -> LatencyOverhead {
+    let mut unmatched_hops = 0;
+    let mut matched: Vec<(&RelayHop, f64)> = Vec::new();
+    for hop in hops {
+        match matrix.get(hop.peer).and_then(|row| row.get(hop.node)) {
+            Some(&theoretical) => matched.push((hop, theoretical)),
+            None => unmatched_hops += 1,
+        }
+    }
+    let overheads: Vec<f64> = matched.iter().map(|(h, t)| h.delay_ms - t).collect();
+    let (q1, q3) = (percentile(&overheads, 25.0), percentile(&overheads, 75.0));
+    let anomaly_threshold_ms = q3 + 3.0 * (q3 - q1);
+
+    // (from, to) -> theoretical, observed delays
+    let mut pairs: BTreeMap<(&str, &str), (f64, Vec<f64>)> = BTreeMap::new();
+    let mut per_tx: Vec<TxHopOverhead> = Vec::new();
+    for (hop, theoretical) in &matched {
+        pairs
+            .entry((hop.peer, hop.node))
+            .or_insert_with(|| (*theoretical, Vec::new()))
+            .1
+            .push(hop.delay_ms);
+        if per_tx.last().map(|t| t.tx_hash.as_str()) != Some(hop.tx_hash) {
+            per_tx.push(TxHopOverhead {
+                tx_hash: hop.tx_hash.to_string(),
+                hops: Vec::new(),
+            });
+        }
+        if let Some(tx) = per_tx.last_mut() {
+            tx.hops.push(HopOverhead {
+                from: hop.peer.to_string(),
+                to: hop.node.to_string(),
+                observed_ms: hop.delay_ms,
+                theoretical_ms: *theoretical,
+                overhead_ms: hop.delay_ms - theoretical,
+            });
+        }
+    }
+
+    let mut anomalous_pairs: Vec<PairOverhead> = pairs
+        .into_iter()
+        .map(|((from, to), (theoretical_ms, observed))| {
+            let median_observed_ms = median(&observed);
+            PairOverhead {
+                from: from.to_string(),
+                to: to.to_string(),
+                hops: observed.len(),
+                theoretical_ms,
+                median_observed_ms,
+                median_overhead_ms: median_observed_ms - theoretical_ms,
+            }
+        })
+        .filter(|p| p.median_overhead_ms > anomaly_threshold_ms)
+        .collect();
+    anomalous_pairs.sort_by(|a, b| b.median_overhead_ms.total_cmp(&a.median_overhead_ms));
+
+    LatencyOverhead {
+        hops: matched.len(),
+        unmatched_hops,
+        average_overhead_ms: mean(&overheads),
+        median_overhead_ms: median(&overheads),
+        p95_overhead_ms: percentile(&overheads, 95.0),
+        anomaly_threshold_ms,
+        anomalous_pairs,
+        per_tx,
+    }
+}
+
+/// Each node's first sighting of a tx, less the tx's first sighting
+/// anywhere, grouped by the node's registry continent
+fn continent_delays(
+    tx_observations: &HashMap<String, Vec<&TxObservation>>,
+    agents: &[AnalysisAgentInfo],
+) -> Vec<ContinentPropagation> {
+    let continent: HashMap<&str, &str> = agents
+        .iter()
+        .filter_map(|a| a.continent.as_deref().map(|c| (a.id.as_str(), c)))
+        .collect();
+    if continent.is_empty() {
+        return Vec::new();
+    }
+
+    let mut delays: BTreeMap<&str, (BTreeSet<&str>, Vec<f64>)> = BTreeMap::new();
+    for observations in tx_observations.values() {
+        let mut first_seen: HashMap<&str, SimTime> = HashMap::new();
+        for obs in observations {
+            first_seen
+                .entry(obs.node_id.as_str())
+                .and_modify(|t| *t = t.min(obs.timestamp))
+                .or_insert(obs.timestamp);
+        }
+        let Some(first) = first_seen.values().copied().reduce(f64::min) else {
+            continue;
+        };
+        for (node, seen) in first_seen {
+            if let Some(c) = continent.get(node) {
+                let (nodes, samples) = delays.entry(c).or_default();
+                nodes.insert(node);
+                samples.push((seen - first) * 1000.0);
+            }
+        }
+    }
+
+    delays
+        .into_iter()
+        .map(|(continent, (nodes, samples))| ContinentPropagation {
+            continent: continent.to_string(),
+            nodes: nodes.len(),
+            observations: samples.len(),
+            average_delay_ms: mean(&samples),
+            median_delay_ms: median(&samples),
+            p95_delay_ms: percentile(&samples, 95.0),
+        })
+        .collect()
+}
+
+/// Relay hops grouped by the continent and AS of both ends, and the
+/// transactions whose slowest hop crossed an AS boundary and took at least
+/// [`AS_BOTTLENECK_SHARE`] of the time from creation to the last first
+/// sighting. None when no agent has a `continent` or `as_number`.
+fn region_breakdown(
+    hops: &[RelayHop],
+    analyses: &[PropagationAnalysis],
+    agents: &[AnalysisAgentInfo],
+) -> Option<RegionBreakdown> {
+    let continent: HashMap<&str, &str> = agents
+        .iter()
+        .filter_map(|a| a.continent.as_deref().map(|c| (a.id.as_str(), c)))
+        .collect();
+    let as_number: HashMap<&str, u32> = agents
+        .iter()
+        .filter_map(|a| a.as_number.map(|n| (a.id.as_str(), n)))
+        .collect();
+    if continent.is_empty() && as_number.is_empty() {
+        log::debug!("Registry has no continent or as_number; no region breakdown");
+        return None;
+    }
+
+    let (mut intra, mut inter) = (Vec::new(), Vec::new());
+    let mut regions: BTreeMap<(&str, &str), Vec<f64>> = BTreeMap::new();
+    let mut ases: BTreeMap<(u32, u32), Vec<f64>> = BTreeMap::new();
+    for hop in hops {
+        if let (Some(&from), Some(&to)) = (continent.get(hop.peer), continent.get(hop.node)) {
+            if from == to { &mut intra } else { &mut inter }.push(hop.delay_ms);
+            regions.entry((from, to)).or_default().push(hop.delay_ms);
+        }
+        if let (Some(&from), Some(&to)) = (as_number.get(hop.peer), as_number.get(hop.node)) {
+            ases.entry((from, to)).or_default().push(hop.delay_ms);
+        }
+    }
+    let cell = |from: String, to: String, delays: &[f64]| GeoPairDelay {
+        from,
+        to,
+        hops: delays.len(),
+        median_ms: median(delays),
+    };
+
+    // Each transaction's slowest hop, set against its propagation time
+    let spans: HashMap<&str, f64> = analyses
+        .iter()
+        .filter_map(|a| {
+            let last = a.first_seen_time? + a.network_propagation_time_ms / 1000.0;
+            Some((a.tx_hash.as_str(), (last - a.creation_time) * 1000.0))
+        })
+        .collect();
+    let mut slowest: BTreeMap<&str, &RelayHop> = BTreeMap::new();
+    for hop in hops {
+        let entry = slowest.entry(hop.tx_hash).or_insert(hop);
+        if hop.delay_ms > entry.delay_ms {
+            *entry = hop;
+        }
+    }
+    let mut as_bottlenecks: Vec<AsBoundaryBottleneck> = slowest
+        .into_values()
+        .filter_map(|hop| {
+            let (&from_as, &to_as) = (as_number.get(hop.peer)?, as_number.get(hop.node)?);
+            let span = *spans.get(hop.tx_hash)?;
+            let share = if span > 0.0 { hop.delay_ms / span } else { 0.0 };
+            (from_as != to_as && share >= AS_BOTTLENECK_SHARE).then(|| AsBoundaryBottleneck {
+                tx_hash: hop.tx_hash.to_string(),
+                from: hop.peer.to_string(),
+                to: hop.node.to_string(),
+                from_as,
+                to_as,
+                delay_ms: hop.delay_ms,
+                share,
+            })
+        })
+        .collect();
+    as_bottlenecks.sort_by(|a, b| b.delay_ms.total_cmp(&a.delay_ms));
+
+    Some(RegionBreakdown {
+        region_hops: intra.len() + inter.len(),
+        intra_region_hops: intra.len(),
+        intra_region_median_ms: (!intra.is_empty()).then(|| median(&intra)),
+        inter_region_hops: inter.len(),
+        inter_region_median_ms: (!inter.is_empty()).then(|| median(&inter)),
+        region_matrix: regions
+            .iter()
+            .map(|((from, to), d)| cell(from.to_string(), to.to_string(), d))
+            .collect(),
+        as_matrix: ases
+            .iter()
+            .map(|((from, to), d)| cell(from.to_string(), to.to_string(), d))
+            .collect(),
+        as_bottlenecks,
+    })
+}
+
 /// Identify nodes that are consistently slow to receive transactions
 fn identify_bottlenecks(
     _analyses: &[PropagationAnalysis],
@@ -211,3 +687,235 @@ fn identify_bottlenecks(
 
     bottlenecks
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// user-a and user-b share GML node 0; user-c and user-d each have
+    /// their own node. user-a creates the tx, user-b hears it from user-a
+    /// (intra-node), user-c from user-a, user-d from user-c.
+    fn fixture() -> (
+        Vec<Transaction>,
+        HashMap<String, NodeLogData>,
+        Vec<AnalysisAgentInfo>,
+    ) {
+        let mut run = crate::analysis::testutil::SyntheticRun::new();
+        for (id, node) in [("user-a", 0), ("user-b", 0), ("user-c", 1), ("user-d", 2)] {
+            run.agent(id, "").place(id, node);
+        }
+        run.transaction("aa", "user-a", "user-d", 100.0)
+            .observe("aa", "user-b", "user-a", 100.001)
+            .observe("aa", "user-c", "user-a", 100.100)
+            .observe("aa", "user-d", "user-c", 100.250);
+        (run.transactions.clone(), run.log_map(), run.agents)
+    }
+
+    #[test]
+    fn intra_node_pairs_are_counted_and_reported() {
+        let (txs, log_data, agents) = fixture();
+        let report = analyze_propagation(&txs, &[], &log_data, &agents, false, &[], None);
+        let hop = report.hop_latency.unwrap();
+        assert_eq!(hop.pairs, 3);
+        assert_eq!(hop.intra_node_pairs, 1);
+        assert!((hop.intra_node_fraction - 1.0 / 3.0).abs() < 1e-9);
+        assert!(hop.placement_known);
+        assert!((hop.intra_node_average_ms - 1.0).abs() < 1e-6);
+        assert!((hop.average_ms - (1.0 + 100.0 + 150.0) / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn excluding_intra_node_pairs_raises_the_average() {
+        let (txs, log_data, agents) = fixture();
+        let report = analyze_propagation(&txs, &[], &log_data, &agents, true, &[], None);
+        let hop = report.hop_latency.unwrap();
+        assert!(hop.intra_node_excluded);
+        assert_eq!(hop.intra_node_pairs, 1);
+        assert!((hop.average_ms - 125.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn without_placement_no_pair_is_intra_node() {
+        let (txs, log_data, mut agents) = fixture();
+        for a in &mut agents {
+            a.network_node_id = None;
+        }
+        let hop = analyze_propagation(&txs, &[], &log_data, &agents, true, &[], None)
+            .hop_latency
+            .unwrap();
+        assert!(!hop.placement_known);
+        assert_eq!(hop.intra_node_pairs, 0);
+        assert_eq!(hop.pairs, 3);
+    }
+
+    #[test]
+    fn first_sightings_are_grouped_by_continent() {
+        let (txs, log_data, mut agents) = fixture();
+        for a in &mut agents {
+            if a.id != "user-d" {
+                a.continent = Some("europe".to_string());
+            }
+        }
+        let report = analyze_propagation(&txs, &[], &log_data, &agents, false, &[], None);
+        let by: Vec<_> = report
+            .by_continent
+            .iter()
+            .map(|c| (c.continent.as_str(), c.nodes, c.observations))
+            .collect();
+        // user-a never observes its own tx, so Europe counts user-b and user-c
+        assert_eq!(by, [("europe", 2, 2)]);
+        assert!((report.by_continent[0].average_delay_ms - 49.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn relay_hops_are_broken_down_by_region_and_as() {
+        let (txs, log_data, mut agents) = fixture();
+        let report = analyze_propagation(&txs, &[], &log_data, &agents, false, &[], None);
+        assert!(report.by_region.is_none());
+
+        for a in &mut agents {
+            let (continent, as_number) = match a.id.as_str() {
+                "user-d" => ("asia", 500),
+                _ => ("europe", 200),
+            };
+            a.continent = Some(continent.to_string());
+            a.as_number = Some(as_number);
+        }
+        let report = analyze_propagation(&txs, &[], &log_data, &agents, false, &[], None);
+        let region = report.by_region.unwrap();
+        assert_eq!((region.intra_region_hops, region.inter_region_hops), (2, 1));
+        assert!((region.intra_region_median_ms.unwrap() - 50.5).abs() < 1e-6);
+        assert!((region.inter_region_median_ms.unwrap() - 150.0).abs() < 1e-6);
+        let cells: Vec<_> = region
+            .region_matrix
+            .iter()
+            .map(|c| (c.from.as_str(), c.to.as_str(), c.hops))
+            .collect();
+        assert_eq!(cells, [("europe", "asia", 1), ("europe", "europe", 2)]);
+        assert_eq!(region.as_matrix.len(), 2);
+
+        // user-c -> user-d takes 150 of the 250 ms, from AS 200 into AS 500
+        let [bottleneck] = region.as_bottlenecks.as_slice() else {
+            panic!("{:?}", region.as_bottlenecks);
+        };
+        assert_eq!((bottleneck.from_as, bottleneck.to_as), (200, 500));
+        assert_eq!(bottleneck.to, "user-d");
+        assert!((bottleneck.share - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn coverage_counts_the_sender_from_creation() {
+        let (txs, log_data, agents) = fixture();
+        let report = analyze_propagation(
+            &txs,
+            &[],
+            &log_data,
+            &agents,
+            false,
+            &[25.0, 50.0, 75.0, 100.0],
+            None,
+        );
+        // Four daemons: user-a at creation, then b, c and d
+        let times: Vec<Option<f64>> = report.per_tx_analysis[0]
+            .coverage
+            .iter()
+            .map(|c| c.time_ms.map(|t| t.round()))
+            .collect();
+        assert_eq!(times, [Some(0.0), Some(1.0), Some(100.0), Some(250.0)]);
+
+        let curve = &report.coverage_curve;
+        assert_eq!(curve.len(), 4);
+        assert_eq!(curve[3].percentile, 100.0);
+        assert_eq!(curve[3].transactions, 1);
+        assert!((curve[3].median_ms.unwrap() - 250.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn unreached_percentiles_have_no_time() {
+        let (txs, mut log_data, agents) = fixture();
+        log_data.get_mut("user-d").unwrap().tx_observations.clear();
+        let report = analyze_propagation(&txs, &[], &log_data, &agents, false, &[90.0], None);
+        assert_eq!(
+            report.per_tx_analysis[0].coverage,
+            [CoveragePoint {
+                percentile: 90.0,
+                time_ms: None
+            }]
+        );
+        assert_eq!(report.coverage_curve[0].median_ms, None);
+        assert_eq!(report.coverage_curve[0].transactions, 0);
+    }
+
+    #[test]
+    fn overhead_is_measured_against_the_latency_matrix() {
+        let mut run = crate::analysis::testutil::SyntheticRun::new();
+        for (id, node) in [("user-a", 0), ("user-b", 0), ("user-c", 1), ("user-d", 2)] {
+            run.agent(id, "").place(id, node);
+        }
+        // user-c -> user-d takes 150 ms over a 30 ms path; every other hop
+        // is within a millisecond of its minimum
+        run.transaction("aa", "user-a", "user-d", 100.0)
+            .observe("aa", "user-b", "user-a", 100.001)
+            .observe("aa", "user-c", "user-a", 100.100)
+            .observe("aa", "user-d", "user-c", 100.250);
+        for (tx, t) in [("bb", 200.0), ("cc", 300.0), ("dd", 400.0)] {
+            run.transaction(tx, "user-a", "user-d", t)
+                .observe(tx, "user-b", "user-a", t + 0.001)
+                .observe(tx, "user-c", "user-a", t + 0.100)
+                .observe(tx, "user-d", "user-b", t + 0.007);
+        }
+        let mut matrix = AgentLatencyMatrix::new();
+        for (from, to, ms) in [
+            ("user-a", "user-b", 1.0),
+            ("user-a", "user-c", 99.0),
+            ("user-b", "user-d", 5.0),
+            ("user-c", "user-d", 30.0),
+        ] {
+            matrix
+                .entry(from.to_string())
+                .or_default()
+                .insert(to.to_string(), ms);
+        }
+        let log_data = run.log_map();
+
+        let overhead = analyze_propagation(
+            &run.transactions,
+            &[],
+            &log_data,
+            &run.agents,
+            false,
+            &[],
+            Some(&matrix),
+        )
+        .latency_overhead
+        .unwrap();
+        assert_eq!(overhead.hops, 12);
+        assert_eq!(overhead.unmatched_hops, 0);
+        assert!((overhead.anomaly_threshold_ms - 4.0).abs() < 1e-6);
+        assert_eq!(overhead.anomalous_pairs.len(), 1);
+        let pair = &overhead.anomalous_pairs[0];
+        assert_eq!((pair.from.as_str(), pair.to.as_str()), ("user-c", "user-d"));
+        assert!((pair.median_overhead_ms - 120.0).abs() < 1e-6);
+
+        assert_eq!(overhead.per_tx.len(), 4);
+        assert_eq!(overhead.per_tx[0].tx_hash, "aa");
+        let hop = &overhead.per_tx[0].hops[2];
+        assert_eq!((hop.from.as_str(), hop.to.as_str()), ("user-c", "user-d"));
+        assert!((hop.theoretical_ms - 30.0).abs() < 1e-6);
+
+        matrix.remove("user-c");
+        let overhead = analyze_propagation(
+            &run.transactions,
+            &[],
+            &log_data,
+            &run.agents,
+            false,
+            &[],
+            Some(&matrix),
+        )
+        .latency_overhead
+        .unwrap();
+        assert_eq!(overhead.unmatched_hops, 1);
+        assert!(overhead.anomalous_pairs.is_empty());
+    }
+}
@@ -0,0 +1,105 @@
+//! Daemon and wallet readiness latencies.
+//!
+//! Agent wrappers run `monerosim-healthcheck` against the agent's daemon
+//! and wallet RPC before starting the agent, each writing one record to
+//! `<shared_dir>/readiness/`. This groups the records by target and
+//! reports how long the RPCs took to answer and which never did.
+
+use std::collections::BTreeMap;
+
+use super::schema::REPORT_SCHEMA_VERSION;
+use super::stats::{mean, median};
+use super::types::*;
+use crate::healthcheck::ReadinessRecord;
+
+/// Summarize readiness records; targets in name order
+pub fn analyze_readiness(records: &[ReadinessRecord]) -> ReadinessReport {
+    let mut by_target: BTreeMap<&str, Vec<&ReadinessRecord>> = BTreeMap::new();
+    for record in records {
+        by_target.entry(&record.target).or_default().push(record);
+    }
+
+    let targets = by_target
+        .into_iter()
+        .map(|(target, records)| {
+            let ready: Vec<&ReadinessRecord> =
+                records.iter().copied().filter(|r| r.is_ready()).collect();
+            let elapsed: Vec<f64> = ready.iter().map(|r| r.elapsed_sec).collect();
+            let count = |status: &str| records.iter().filter(|r| r.status == status).count();
+            let slowest = ready
+                .iter()
+                .max_by(|a, b| a.elapsed_sec.total_cmp(&b.elapsed_sec));
+            TargetReadiness {
+                target: target.to_string(),
+                checks: records.len(),
+                ready: ready.len(),
+                timeouts: count("timeout"),
+                refused: count("refused"),
+                bad_responses: count("bad_response"),
+                mean_sec: (!elapsed.is_empty()).then(|| mean(&elapsed)),
+                median_sec: (!elapsed.is_empty()).then(|| median(&elapsed)),
+                max_sec: slowest.map(|r| r.elapsed_sec),
+                slowest_agent: slowest.map(|r| r.agent.clone()),
+            }
+        })
+        .collect();
+
+    let not_ready = records
+        .iter()
+        .filter(|r| !r.is_ready())
+        .map(|r| NotReadyCheck {
+            agent: r.agent.clone(),
+            target: r.target.clone(),
+            status: r.status.clone(),
+            attempts: r.attempts,
+            error: r.error.clone(),
+        })
+        .collect();
+
+    ReadinessReport {
+        report_schema_version: REPORT_SCHEMA_VERSION,
+        targets,
+        not_ready,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(agent: &str, target: &str, status: &str, elapsed_sec: f64) -> ReadinessRecord {
+        ReadinessRecord {
+            agent: agent.to_string(),
+            target: target.to_string(),
+            url: "http://11.0.0.1:18081/json_rpc".to_string(),
+            method: "get_info".to_string(),
+            status: status.to_string(),
+            attempts: 3,
+            started_at: 946684900.0,
+            elapsed_sec,
+            error: (status != "ready").then(|| "connection refused".to_string()),
+        }
+    }
+
+    #[test]
+    fn latencies_cover_ready_checks_per_target() {
+        let report = analyze_readiness(&[
+            record("user-001", "wallet", "ready", 4.0),
+            record("user-001", "daemon", "ready", 1.0),
+            record("user-002", "daemon", "ready", 3.0),
+            record("user-003", "daemon", "refused", 120.0),
+        ]);
+
+        let daemon = &report.targets[0];
+        assert_eq!(daemon.target, "daemon");
+        assert_eq!((daemon.checks, daemon.ready, daemon.refused), (3, 2, 1));
+        assert_eq!(daemon.mean_sec, Some(2.0));
+        assert_eq!(daemon.max_sec, Some(3.0));
+        assert_eq!(daemon.slowest_agent.as_deref(), Some("user-002"));
+        assert_eq!(report.targets[1].median_sec, Some(4.0));
+
+        assert_eq!(report.not_ready.len(), 1);
+        assert_eq!(report.not_ready[0].agent, "user-003");
+        assert_eq!(report.not_ready[0].status, "refused");
+    }
+}
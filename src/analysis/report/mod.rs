@@ -0,0 +1,582 @@
+//! Report generation for transaction routing analysis.
+//!
+//! Generates both JSON and human-readable text reports, plus flat CSV or
+//! Parquet tables of the per-transaction, per-node and per-window datasets
+//! (see [`tables`]).
+
+pub mod tables;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use color_eyre::eyre::{Context, Result};
+
+use super::types::*;
+
+pub use tables::{
+    bandwidth_matrix_table, bandwidth_node_table, full_report_tables, propagation_tx_table,
+    spy_tx_table, sweep_summary_table, upgrade_window_table, write_tables, ReportFormat, Table,
+};
+
+/// Generate JSON report
+pub fn generate_json_report(report: &FullAnalysisReport, output_path: &Path) -> Result<()> {
+    let json =
+        serde_json::to_string_pretty(report).context("Failed to serialize report to JSON")?;
+
+    fs::write(output_path, json)
+        .with_context(|| format!("Failed to write JSON report to {}", output_path.display()))?;
+
+    log::info!("JSON report written to {}", output_path.display());
+    Ok(())
+}
+
+/// Generate human-readable text report
+pub fn generate_text_report(report: &FullAnalysisReport, output_path: &Path) -> Result<()> {
+    let mut lines: Vec<String> = Vec::new();
+
+    // Header
+    lines.push("=".repeat(80));
+    lines.push("                   MONEROSIM TRANSACTION ROUTING ANALYSIS".to_string());
+    lines.push("=".repeat(80));
+    lines.push(String::new());
+
+    // Metadata
+    lines.push(format!(
+        "Analysis Date: {}",
+        report.metadata.analysis_timestamp
+    ));
+    lines.push(format!(
+        "Data Directory: {}",
+        report.metadata.simulation_data_dir
+    ));
+    lines.push(format!("Nodes Analyzed: {}", report.metadata.total_nodes));
+    lines.push(format!(
+        "Transactions: {}",
+        report.metadata.total_transactions
+    ));
+    lines.push(format!("Blocks: {}", report.metadata.total_blocks));
+    if let Some(ref anon) = report.metadata.anonymization {
+        lines.push(format!(
+            "Anonymized: pseudonym seed {}, timestamps floored to {}s, timing noise ±{}%, IPs dropped",
+            anon.pseudonym_seed,
+            anon.time_granularity_sec,
+            anon.timing_noise * 100.0
+        ));
+    }
+    lines.push(String::new());
+
+    // Spy Node Analysis
+    if let Some(ref spy) = report.spy_node_analysis {
+        lines.push("=".repeat(80));
+        lines.push("                          SPY NODE VULNERABILITY".to_string());
+        lines.push("=".repeat(80));
+        lines.push(String::new());
+
+        lines.push(format!(
+            "Overall Inference Accuracy: {:.1}%",
+            spy.inference_accuracy * 100.0
+        ));
+        lines.push(
+            "  A spy node observing first-seen timing could correctly identify the sender"
+                .to_string(),
+        );
+        lines.push(format!(
+            "  for {} out of {} transactions.",
+            (spy.inference_accuracy * spy.analyzable_transactions as f64).round() as usize,
+            spy.analyzable_transactions
+        ));
+        if !spy.observers.is_empty() {
+            lines.push(format!(
+                "  Observed from spy node(s): {}",
+                spy.observers.join(", ")
+            ));
+        }
+        if let Some(avg) = spy.avg_anonymity_set_size {
+            lines.push(format!(
+                "  Average anonymity set seen by the spies: {:.2} candidate originator(s)",
+                avg
+            ));
+        }
+        lines.push(String::new());
+
+        if !spy.per_spy.is_empty() {
+            lines.push("Per-Spy Contribution:".to_string());
+            for stats in &spy.per_spy {
+                lines.push(format!(
+                    "  {}: saw {} TXs, first for {} ({:.1}%), {:.1}% accurate alone",
+                    stats.node_id,
+                    stats.transactions_observed,
+                    stats.first_observations,
+                    stats.first_observation_share * 100.0,
+                    stats.accuracy * 100.0
+                ));
+            }
+            lines.push(String::new());
+        }
+
+        lines.push("Timing Distribution:".to_string());
+        lines.push(format!(
+            "  < 100ms spread:  {} transactions (high vulnerability)",
+            spy.timing_spread_distribution.high_vulnerability_count
+        ));
+        lines.push(format!(
+            "  100-500ms:       {} transactions (moderate vulnerability)",
+            spy.timing_spread_distribution.moderate_vulnerability_count
+        ));
+        lines.push(format!(
+            "  > 500ms:         {} transactions (low vulnerability)",
+            spy.timing_spread_distribution.low_vulnerability_count
+        ));
+        lines.push(String::new());
+
+        if !spy.vulnerable_senders.is_empty() {
+            lines.push(
+                "Most Observable Senders (heuristic confidence; unvalidated weights):".to_string(),
+            );
+            for (i, sender) in spy.vulnerable_senders.iter().take(5).enumerate() {
+                lines.push(format!(
+                    "  {}. {}: {} high-confidence inferences ({:.0}% accurate)",
+                    i + 1,
+                    sender.sender_id,
+                    sender.high_confidence_inferences,
+                    sender.accuracy * 100.0
+                ));
+            }
+            lines.push(String::new());
+        }
+
+        lines.push(
+            "RECOMMENDATION: Transaction timing correlation is viable in this topology."
+                .to_string(),
+        );
+        lines.push(
+            "Consider implementing Dandelion++ or similar origin-hiding protocols.".to_string(),
+        );
+        lines.push(String::new());
+    }
+
+    // Propagation Analysis
+    if let Some(ref prop) = report.propagation_analysis {
+        lines.push("=".repeat(80));
+        lines.push("                         PROPAGATION TIMING".to_string());
+        lines.push("=".repeat(80));
+        lines.push(String::new());
+
+        lines.push("Transaction Propagation:".to_string());
+        lines.push(format!(
+            "  Average time to reach all nodes: {:.1}ms",
+            prop.average_propagation_ms
+        ));
+        lines.push(format!("  Median: {:.1}ms", prop.median_propagation_ms));
+        lines.push(format!(
+            "  95th percentile: {:.1}ms",
+            prop.p95_propagation_ms
+        ));
+        lines.push(String::new());
+
+        if !prop.coverage_curve.is_empty() {
+            lines.push("Coverage (median time from creation to % of daemons):".to_string());
+            for point in &prop.coverage_curve {
+                lines.push(format!(
+                    "  {:>5}%: {}  ({} of {} txs reached)",
+                    point.percentile,
+                    point
+                        .median_ms
+                        .map_or("n/a".to_string(), |ms| format!("{:.1}ms", ms)),
+                    point.transactions,
+                    prop.analyzed_transactions
+                ));
+            }
+            lines.push(String::new());
+        }
+
+        if let Some(ref hop) = prop.hop_latency {
+            lines.push(format!(
+                "Per-Hop Relay Latency ({} pairs{}):",
+                hop.pairs,
+                if hop.intra_node_excluded {
+                    ", intra-node excluded"
+                } else {
+                    ""
+                }
+            ));
+            lines.push(format!(
+                "  Average: {:.1}ms  Median: {:.1}ms  P95: {:.1}ms",
+                hop.average_ms, hop.median_ms, hop.p95_ms
+            ));
+            if hop.placement_known {
+                lines.push(format!(
+                    "  Intra-node pairs: {} ({:.1}%), avg {:.1}ms",
+                    hop.intra_node_pairs,
+                    hop.intra_node_fraction * 100.0,
+                    hop.intra_node_average_ms
+                ));
+            } else {
+                lines.push(
+                    "  Intra-node pairs: unknown (no network_node_id in registry)".to_string(),
+                );
+            }
+            lines.push(String::new());
+        }
+
+        if let Some(ref overhead) = prop.latency_overhead {
+            lines.push(format!(
+                "Relay Overhead over Topology Latency ({} hops, {} not in matrix):",
+                overhead.hops, overhead.unmatched_hops
+            ));
+            lines.push(format!(
+                "  Average: {:.1}ms  Median: {:.1}ms  P95: {:.1}ms",
+                overhead.average_overhead_ms, overhead.median_overhead_ms, overhead.p95_overhead_ms
+            ));
+            if !overhead.anomalous_pairs.is_empty() {
+                lines.push(format!(
+                    "  Anomalous pairs (median overhead > {:.1}ms):",
+                    overhead.anomaly_threshold_ms
+                ));
+                for pair in overhead.anomalous_pairs.iter().take(10) {
+                    lines.push(format!(
+                        "    {} -> {}: {:.1}ms over {:.1}ms minimum ({} hops)",
+                        pair.from, pair.to, pair.median_overhead_ms, pair.theoretical_ms, pair.hops
+                    ));
+                }
+            }
+            lines.push(String::new());
+        }
+
+        if !prop.by_continent.is_empty() {
+            lines.push("First-Sighting Delay by Continent:".to_string());
+            for c in &prop.by_continent {
+                lines.push(format!(
+                    "  {:<14} {:>4} nodes  Average: {:.1}ms  Median: {:.1}ms  P95: {:.1}ms",
+                    c.continent, c.nodes, c.average_delay_ms, c.median_delay_ms, c.p95_delay_ms
+                ));
+            }
+            lines.push(String::new());
+        }
+
+        if let Some(ref region) = prop.by_region {
+            lines.extend(region_breakdown_lines(region));
+            lines.push(String::new());
+        }
+
+        lines.push("Block Confirmation Delays:".to_string());
+        lines.push(format!(
+            "  Average time from TX creation to block inclusion: {:.1} seconds",
+            prop.average_confirmation_delay_sec
+        ));
+        lines.push(String::new());
+
+        if !prop.bottleneck_nodes.is_empty() {
+            lines.push("Bottleneck Nodes (consistently slow to receive):".to_string());
+            for (i, node) in prop.bottleneck_nodes.iter().take(5).enumerate() {
+                lines.push(format!(
+                    "  {}. {} (avg delay: {:.0}ms) - {} observations",
+                    i + 1,
+                    node.node_id,
+                    node.average_delay_ms,
+                    node.observations
+                ));
+            }
+            lines.push(String::new());
+        }
+    }
+
+    // Network Resilience
+    if let Some(ref res) = report.resilience_analysis {
+        lines.push("=".repeat(80));
+        lines.push("                        NETWORK RESILIENCE".to_string());
+        lines.push("=".repeat(80));
+        lines.push(String::new());
+
+        lines.push("Connectivity:".to_string());
+        lines.push(format!("  Total nodes: {}", res.connectivity.total_nodes));
+        lines.push(format!(
+            "  Average peer count: {:.1}",
+            res.connectivity.average_peer_count
+        ));
+        lines.push(format!(
+            "  Min: {}, Max: {}",
+            res.connectivity.min_peer_count, res.connectivity.max_peer_count
+        ));
+        if !res.connectivity.isolated_nodes.is_empty() {
+            lines.push(format!(
+                "  Isolated nodes: {}",
+                res.connectivity.isolated_nodes.join(", ")
+            ));
+        }
+        lines.push(String::new());
+
+        lines.push("Centralization Metrics:".to_string());
+        lines.push(format!(
+            "  First-seen Gini coefficient: {:.2} (0=equal, 1=centralized)",
+            res.centralization.first_seen_gini
+        ));
+        if !res.centralization.dominant_observers.is_empty() {
+            lines.push(format!(
+                "  Dominant observers (>15% of first-sees): {}",
+                res.centralization.dominant_observers.join(", ")
+            ));
+        }
+        lines.push(format!(
+            "  Miner first-seen ratio: {:.1}%",
+            res.centralization.miner_first_seen_ratio * 100.0
+        ));
+        lines.push(String::new());
+
+        lines.push("Partition Risk:".to_string());
+        lines.push(format!(
+            "  Connected components: {}",
+            res.partition_risk.connected_components
+        ));
+        if !res.partition_risk.bridge_nodes.is_empty() {
+            lines.push(format!(
+                "  Bridge nodes (removal may partition): {}",
+                res.partition_risk.bridge_nodes.join(", ")
+            ));
+        }
+        lines.push(String::new());
+
+        for event in &res.partition_events {
+            lines.extend(partition_event_lines(event));
+            lines.push(String::new());
+        }
+
+        let gini = res.centralization.first_seen_gini;
+        if gini > 0.4 {
+            lines.push("RECOMMENDATION: Network shows significant centralization.".to_string());
+            lines.push("This creates potential surveillance points.".to_string());
+        } else if gini > 0.2 {
+            lines.push(
+                "RECOMMENDATION: Network has moderate centralization around some nodes."
+                    .to_string(),
+            );
+        } else {
+            lines.push("RECOMMENDATION: Network appears well-distributed.".to_string());
+        }
+        lines.push(String::new());
+    }
+
+    // Footer
+    lines.push("=".repeat(80));
+
+    let content = lines.join("\n");
+    fs::write(output_path, content)
+        .with_context(|| format!("Failed to write text report to {}", output_path.display()))?;
+
+    log::info!("Text report written to {}", output_path.display());
+    Ok(())
+}
+
+/// Log a summary of the report at info level
+pub fn log_summary(report: &FullAnalysisReport) {
+    let mut out = String::new();
+    let _ = writeln!(out, "=== TRANSACTION ROUTING ANALYSIS SUMMARY ===\n");
+    let _ = writeln!(out, "Nodes: {}", report.metadata.total_nodes);
+    let _ = writeln!(out, "Transactions: {}", report.metadata.total_transactions);
+    let _ = writeln!(out, "Blocks: {}", report.metadata.total_blocks);
+
+    if let Some(ref spy) = report.spy_node_analysis {
+        let _ = writeln!(out, "\nSpy Node Vulnerability:");
+        let _ = writeln!(
+            out,
+            "  Inference accuracy: {:.1}%",
+            spy.inference_accuracy * 100.0
+        );
+        let _ = writeln!(
+            out,
+            "  High vulnerability TXs: {}",
+            spy.timing_spread_distribution.high_vulnerability_count
+        );
+        if let Some(avg) = spy.avg_anonymity_set_size {
+            let _ = writeln!(
+                out,
+                "  Spies: {} (avg anonymity set {:.2})",
+                spy.observers.len(),
+                avg
+            );
+        }
+    }
+
+    if let Some(ref prop) = report.propagation_analysis {
+        let _ = writeln!(out, "\nPropagation Timing:");
+        let _ = writeln!(out, "  Average: {:.1}ms", prop.average_propagation_ms);
+        let _ = writeln!(out, "  Median: {:.1}ms", prop.median_propagation_ms);
+        let _ = writeln!(out, "  P95: {:.1}ms", prop.p95_propagation_ms);
+        if !prop.coverage_curve.is_empty() {
+            let points: Vec<String> = prop
+                .coverage_curve
+                .iter()
+                .map(|p| {
+                    format!(
+                        "{}% {}",
+                        p.percentile,
+                        p.median_ms
+                            .map_or("n/a".to_string(), |ms| format!("{:.0}ms", ms))
+                    )
+                })
+                .collect();
+            let _ = writeln!(out, "  Coverage (median): {}", points.join(", "));
+        }
+        if let Some(ref hop) = prop.hop_latency {
+            let _ = writeln!(
+                out,
+                "  Per-hop avg: {:.1}ms ({:.1}% intra-node pairs{})",
+                hop.average_ms,
+                hop.intra_node_fraction * 100.0,
+                if hop.intra_node_excluded {
+                    ", excluded"
+                } else {
+                    ""
+                }
+            );
+        }
+        if let Some(ref overhead) = prop.latency_overhead {
+            let _ = writeln!(
+                out,
+                "  Overhead over topology latency: median {:.1}ms ({} anomalous pairs)",
+                overhead.median_overhead_ms,
+                overhead.anomalous_pairs.len()
+            );
+        }
+    }
+
+    if let Some(ref res) = report.resilience_analysis {
+        let _ = writeln!(out, "\nNetwork Resilience:");
+        let _ = writeln!(
+            out,
+            "  Avg peers: {:.1}",
+            res.connectivity.average_peer_count
+        );
+        let _ = writeln!(
+            out,
+            "  Gini coefficient: {:.2}",
+            res.centralization.first_seen_gini
+        );
+        let _ = writeln!(
+            out,
+            "  Components: {}",
+            res.partition_risk.connected_components
+        );
+        for event in &res.partition_events {
+            let _ = writeln!(
+                out,
+                "  Partition at {:.0}s: {} ({} cross-group drops, {} stuck txs)",
+                event.partition_at,
+                if event.detected {
+                    "detected"
+                } else {
+                    "NOT detected"
+                },
+                event.cross_group_drops,
+                event.stuck_transactions.len()
+            );
+        }
+    }
+
+    log::info!("{}", out.trim_end());
+}
+
+/// One scheduled partition: what it cut, and the recovery after its heal
+fn partition_event_lines(event: &PartitionEventMetrics) -> Vec<String> {
+    let secs = |v: Option<f64>| v.map_or("n/a".to_string(), |s| format!("{:.1}s", s));
+    let sizes: Vec<String> = event.group_sizes.iter().map(usize::to_string).collect();
+    let mut lines = vec![
+        format!(
+            "Partition at {:.0}s ({} daemons per group), {}:",
+            event.partition_at,
+            sizes.join(" / "),
+            event
+                .heal_at
+                .map_or("never healed".to_string(), |h| format!(
+                    "healed at {:.0}s",
+                    h
+                ))
+        ),
+        format!(
+            "  Cross-group connections: {} before, {} dropped (last after {}), {} during",
+            event.cross_group_connections_before,
+            event.cross_group_drops,
+            secs(event.drop_latency_secs),
+            event.cross_group_connections_during
+        ),
+        format!("  Detected: {}", if event.detected { "yes" } else { "no" }),
+    ];
+    if event.heal_at.is_some() {
+        lines.push(format!(
+            "  After heal: first reconnect {}, re-converged {}",
+            secs(event.first_reconnect_secs),
+            secs(event.reconverge_secs)
+        ));
+    }
+    lines.push(format!(
+        "  Stuck transactions: {}",
+        event.stuck_transactions.len()
+    ));
+    lines
+}
+
+/// The `by_region` section: intra vs inter-region medians, the region
+/// matrix as a table (relaying continent per row, receiving per column) and
+/// the AS boundaries transactions were held up at
+fn region_breakdown_lines(region: &RegionBreakdown) -> Vec<String> {
+    let ms = |v: Option<f64>| v.map_or("n/a".to_string(), |ms| format!("{:.1}ms", ms));
+    let mut lines = vec![
+        format!(
+            "Propagation by Region ({} hops between known continents):",
+            region.region_hops
+        ),
+        format!(
+            "  Intra-region median: {} ({} hops)  Inter-region median: {} ({} hops)",
+            ms(region.intra_region_median_ms),
+            region.intra_region_hops,
+            ms(region.inter_region_median_ms),
+            region.inter_region_hops
+        ),
+    ];
+    let names: BTreeSet<&str> = region
+        .region_matrix
+        .iter()
+        .flat_map(|c| [c.from.as_str(), c.to.as_str()])
+        .collect();
+    if !names.is_empty() {
+        lines.push("  Median hop delay (ms), relaying row -> receiving column:".to_string());
+        let mut header = format!("    {:<14}", "");
+        for name in &names {
+            header.push_str(&format!(" {:>13}", name));
+        }
+        lines.push(header);
+        for from in &names {
+            let mut row = format!("    {:<14}", from);
+            for to in &names {
+                let cell = region
+                    .region_matrix
+                    .iter()
+                    .find(|c| c.from == *from && c.to == *to)
+                    .map_or("-".to_string(), |c| format!("{:.1}", c.median_ms));
+                row.push_str(&format!(" {:>13}", cell));
+            }
+            lines.push(row);
+        }
+    }
+    if !region.as_bottlenecks.is_empty() {
+        let mut boundaries: BTreeMap<(u32, u32), (usize, f64)> = BTreeMap::new();
+        for b in &region.as_bottlenecks {
+            let entry = boundaries.entry((b.from_as, b.to_as)).or_default();
+            entry.0 += 1;
+            entry.1 = entry.1.max(b.delay_ms);
+        }
+        lines.push(format!(
+            "  AS-boundary bottlenecks ({} txs held up by one crossing):",
+            region.as_bottlenecks.len()
+        ));
+        for ((from, to), (txs, slowest)) in boundaries {
+            lines.push(format!(
+                "    AS {} -> AS {}: {} txs, slowest hop {:.1}ms",
+                from, to, txs, slowest
+            ));
+        }
+    }
+    lines
+}
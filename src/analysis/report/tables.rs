@@ -0,0 +1,724 @@
+//! Flat table export of report datasets, for pandas/R and other tooling
+//! that wants one row per transaction, node or window instead of nested
+//! JSON.
+//!
+//! Each table is written as `<name>.csv`, or `<name>.parquet` when built
+//! with the `parquet` feature, from the same (possibly anonymized) report
+//! the JSON comes from, so row counts always match the JSON arrays. Empty
+//! cells (CSV) and nulls (Parquet) mark absent optional values.
+//!
+//! Column names are stable; new columns are only ever appended.
+//!
+//! `spy_per_tx` (one row per `spy_node_analysis.per_tx_analysis` entry):
+//! `tx_hash`, `true_sender`, `true_sender_ip`, `inferred_originator_ip`,
+//! `inference_correct`, `correlation_confidence`, `timing_spread_ms`,
+//! `first_seen_node`, `observer_count`, `anonymity_set_size`.
+//!
+//! `propagation_per_tx` (one row per `propagation_analysis.per_tx_analysis`
+//! entry): `tx_hash`, `creation_time`, `first_seen_time`,
+//! `block_inclusion_time`, `confirmation_delay_sec`,
+//! `network_propagation_time_ms`, `median_propagation_ms`,
+//! `p95_propagation_ms`, `nodes_observed`, `total_nodes`,
+//! `propagation_coverage`, then one `time_to_<P>pct_ms` per
+//! `coverage_curve` entry (`P` = its percentile).
+//!
+//! `bandwidth_per_node` (one row per `per_node_stats` entry): `node_id`,
+//! `total_bytes_sent`, `total_bytes_received`, `total_bytes`,
+//! `message_count_sent`, `message_count_received`.
+//!
+//! `bandwidth_matrix` (one row per `pairs` entry of `bandwidth_matrix.json`):
+//! `sender`, `receiver`, `bytes`, `message_count`.
+//!
+//! `upgrade_windows` (one row per `time_series` entry): `window_start`,
+//! `window_end`, `label`, `tx_count`, `observation_count`,
+//! `spy_analyzable_txs`, `avg_propagation_ms`, `median_propagation_ms`,
+//! `p95_propagation_ms`, `avg_peer_count`, `gini_coefficient`,
+//! `avg_stem_length`, `paths_reconstructed`, `bytes_sent`,
+//! `bytes_received`, `total_bandwidth`, `bandwidth_message_count`,
+//! `blocks_seen`, `avg_block_propagation_ms`, `block_rate_vs_target`,
+//! `conflict_count`, `tx_success_rate`,
+//! `avg_height_lag`, `avg_mempool_txs`, then one
+//! `spy_accuracy_at_<P>pct` per `metadata.spy_visibility_levels` entry
+//! (`P` = level x 100) and one `stem_length_gap_<MS>ms` per
+//! `metadata.fluff_gap_thresholds_ms` entry and one `time_to_<P>pct_ms`
+//! per `metadata.coverage_percentiles` entry.
+//!
+//! `sweep_summary` (one row per `runs` entry of `sweep_summary.json`):
+//! `run`, `status`, one column per `parameters` entry (scalars as text,
+//! anything else as JSON), `agents`, `transactions`, `daemons`, one column
+//! per `metrics` entry, `error`.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use color_eyre::eyre::{Context, Result};
+
+use super::super::types::*;
+
+/// Output format of the report datasets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    /// JSON reports only
+    #[default]
+    Json,
+    /// JSON reports plus CSV tables
+    Csv,
+    /// JSON reports plus Parquet tables (`parquet` feature)
+    Parquet,
+}
+
+/// `json`, `csv` or `parquet`
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(ReportFormat::Json),
+            "csv" => Ok(ReportFormat::Csv),
+            "parquet" if cfg!(feature = "parquet") => Ok(ReportFormat::Parquet),
+            "parquet" => Err("parquet output needs a build with `--features parquet`".to_string()),
+            _ => Err(format!(
+                "invalid report format '{}' (expected json, csv or parquet)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ReportFormat::Json => "json",
+            ReportFormat::Csv => "csv",
+            ReportFormat::Parquet => "parquet",
+        })
+    }
+}
+
+/// The cells of one column, `None` for absent values
+#[derive(Debug, Clone, PartialEq)]
+pub enum Values {
+    Str(Vec<Option<String>>),
+    Int(Vec<Option<i64>>),
+    Float(Vec<Option<f64>>),
+    Bool(Vec<Option<bool>>),
+}
+
+impl Values {
+    fn len(&self) -> usize {
+        match self {
+            Values::Str(v) => v.len(),
+            Values::Int(v) => v.len(),
+            Values::Float(v) => v.len(),
+            Values::Bool(v) => v.len(),
+        }
+    }
+
+    /// CSV text of one cell; empty when absent
+    fn cell(&self, row: usize) -> String {
+        match self {
+            Values::Str(v) => v[row].clone().unwrap_or_default(),
+            Values::Int(v) => v[row].map(|x| x.to_string()).unwrap_or_default(),
+            Values::Float(v) => v[row].map(|x| x.to_string()).unwrap_or_default(),
+            Values::Bool(v) => v[row].map(|x| x.to_string()).unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub name: String,
+    pub values: Values,
+}
+
+/// A flat dataset, stored by column
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    /// File stem the table is written under
+    pub name: &'static str,
+    pub columns: Vec<Column>,
+}
+
+impl Table {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            columns: Vec::new(),
+        }
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.columns.first().map_or(0, |c| c.values.len())
+    }
+
+    pub fn column_names(&self) -> Vec<&str> {
+        self.columns.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    fn push(mut self, name: impl Into<String>, values: Values) -> Self {
+        self.columns.push(Column {
+            name: name.into(),
+            values,
+        });
+        self
+    }
+
+    fn str<V: Into<Option<String>>>(self, name: &str, values: impl IntoIterator<Item = V>) -> Self {
+        self.push(
+            name,
+            Values::Str(values.into_iter().map(Into::into).collect()),
+        )
+    }
+
+    fn int<V: Into<Option<i64>>>(self, name: &str, values: impl IntoIterator<Item = V>) -> Self {
+        self.push(
+            name,
+            Values::Int(values.into_iter().map(Into::into).collect()),
+        )
+    }
+
+    fn float<V: Into<Option<f64>>>(self, name: &str, values: impl IntoIterator<Item = V>) -> Self {
+        self.push(
+            name,
+            Values::Float(values.into_iter().map(Into::into).collect()),
+        )
+    }
+
+    fn bool<V: Into<Option<bool>>>(self, name: &str, values: impl IntoIterator<Item = V>) -> Self {
+        self.push(
+            name,
+            Values::Bool(values.into_iter().map(Into::into).collect()),
+        )
+    }
+}
+
+/// `spy_per_tx`
+pub fn spy_tx_table(report: &SpyNodeReport) -> Table {
+    let rows = &report.per_tx_analysis;
+    Table::new("spy_per_tx")
+        .str("tx_hash", rows.iter().map(|r| r.tx_hash.clone()))
+        .str("true_sender", rows.iter().map(|r| r.true_sender.clone()))
+        .str(
+            "true_sender_ip",
+            rows.iter().map(|r| r.true_sender_ip.clone()),
+        )
+        .str(
+            "inferred_originator_ip",
+            rows.iter().map(|r| r.inferred_originator_ip.clone()),
+        )
+        .bool(
+            "inference_correct",
+            rows.iter().map(|r| r.inference_correct),
+        )
+        .float(
+            "correlation_confidence",
+            rows.iter().map(|r| r.correlation_confidence),
+        )
+        .float("timing_spread_ms", rows.iter().map(|r| r.timing_spread_ms))
+        .str(
+            "first_seen_node",
+            rows.iter()
+                .map(|r| r.first_seen_by.first().map(|e| e.node_id.clone())),
+        )
+        .int(
+            "observer_count",
+            rows.iter().map(|r| r.first_seen_by.len() as i64),
+        )
+        .int(
+            "anonymity_set_size",
+            rows.iter().map(|r| r.anonymity_set_size.map(|n| n as i64)),
+        )
+}
+
+/// `propagation_per_tx`
+pub fn propagation_tx_table(report: &PropagationReport) -> Table {
+    let rows = &report.per_tx_analysis;
+    let mut table = Table::new("propagation_per_tx")
+        .str("tx_hash", rows.iter().map(|r| r.tx_hash.clone()))
+        .float("creation_time", rows.iter().map(|r| r.creation_time))
+        .float("first_seen_time", rows.iter().map(|r| r.first_seen_time))
+        .float(
+            "block_inclusion_time",
+            rows.iter().map(|r| r.block_inclusion_time),
+        )
+        .float(
+            "confirmation_delay_sec",
+            rows.iter().map(|r| r.confirmation_delay_sec),
+        )
+        .float(
+            "network_propagation_time_ms",
+            rows.iter().map(|r| r.network_propagation_time_ms),
+        )
+        .float(
+            "median_propagation_ms",
+            rows.iter().map(|r| r.median_propagation_ms),
+        )
+        .float(
+            "p95_propagation_ms",
+            rows.iter().map(|r| r.p95_propagation_ms),
+        )
+        .int(
+            "nodes_observed",
+            rows.iter().map(|r| r.nodes_observed as i64),
+        )
+        .int("total_nodes", rows.iter().map(|r| r.total_nodes as i64))
+        .float(
+            "propagation_coverage",
+            rows.iter().map(|r| r.propagation_coverage),
+        );
+
+    // Parallel to the curve's percentiles
+    for (i, point) in report.coverage_curve.iter().enumerate() {
+        table = table.float(
+            &format!("time_to_{}pct_ms", point.percentile),
+            rows.iter()
+                .map(|r| r.coverage.get(i).and_then(|c| c.time_ms)),
+        );
+    }
+    table
+}
+
+/// `bandwidth_per_node`
+pub fn bandwidth_node_table(report: &BandwidthReport) -> Table {
+    let rows = &report.per_node_stats;
+    Table::new("bandwidth_per_node")
+        .str("node_id", rows.iter().map(|r| r.node_id.clone()))
+        .int(
+            "total_bytes_sent",
+            rows.iter().map(|r| r.total_bytes_sent as i64),
+        )
+        .int(
+            "total_bytes_received",
+            rows.iter().map(|r| r.total_bytes_received as i64),
+        )
+        .int("total_bytes", rows.iter().map(|r| r.total_bytes as i64))
+        .int(
+            "message_count_sent",
+            rows.iter().map(|r| r.message_count_sent as i64),
+        )
+        .int(
+            "message_count_received",
+            rows.iter().map(|r| r.message_count_received as i64),
+        )
+}
+
+/// `bandwidth_matrix`
+pub fn bandwidth_matrix_table(matrix: &BandwidthMatrix) -> Table {
+    let rows = &matrix.pairs;
+    Table::new("bandwidth_matrix")
+        .str("sender", rows.iter().map(|r| r.sender.clone()))
+        .str("receiver", rows.iter().map(|r| r.receiver.clone()))
+        .int("bytes", rows.iter().map(|r| r.bytes as i64))
+        .int("message_count", rows.iter().map(|r| r.message_count as i64))
+}
+
+/// `upgrade_windows`
+pub fn upgrade_window_table(report: &UpgradeAnalysisReport) -> Table {
+    let rows = &report.time_series;
+    let opt_int = |v: Option<u64>| v.map(|x| x as i64);
+    let mut table = Table::new("upgrade_windows")
+        .float("window_start", rows.iter().map(|r| r.window.start))
+        .float("window_end", rows.iter().map(|r| r.window.end))
+        .str("label", rows.iter().map(|r| r.window.label.clone()))
+        .int("tx_count", rows.iter().map(|r| r.tx_count as i64))
+        .int(
+            "observation_count",
+            rows.iter().map(|r| r.observation_count as i64),
+        )
+        .int(
+            "spy_analyzable_txs",
+            rows.iter().map(|r| r.spy_analyzable_txs as i64),
+        )
+        .float(
+            "avg_propagation_ms",
+            rows.iter().map(|r| r.avg_propagation_ms),
+        )
+        .float(
+            "median_propagation_ms",
+            rows.iter().map(|r| r.median_propagation_ms),
+        )
+        .float(
+            "p95_propagation_ms",
+            rows.iter().map(|r| r.p95_propagation_ms),
+        )
+        .float("avg_peer_count", rows.iter().map(|r| r.avg_peer_count))
+        .float("gini_coefficient", rows.iter().map(|r| r.gini_coefficient))
+        .float("avg_stem_length", rows.iter().map(|r| r.avg_stem_length))
+        .int(
+            "paths_reconstructed",
+            rows.iter().map(|r| r.paths_reconstructed as i64),
+        )
+        .int("bytes_sent", rows.iter().map(|r| opt_int(r.bytes_sent)))
+        .int(
+            "bytes_received",
+            rows.iter().map(|r| opt_int(r.bytes_received)),
+        )
+        .int(
+            "total_bandwidth",
+            rows.iter().map(|r| opt_int(r.total_bandwidth)),
+        )
+        .int(
+            "bandwidth_message_count",
+            rows.iter().map(|r| opt_int(r.bandwidth_message_count)),
+        )
+        .int(
+            "blocks_seen",
+            rows.iter().map(|r| r.blocks_seen.map(|x| x as i64)),
+        )
+        .float(
+            "avg_block_propagation_ms",
+            rows.iter().map(|r| r.avg_block_propagation_ms),
+        )
+        .float(
+            "block_rate_vs_target",
+            rows.iter().map(|r| r.block_rate_vs_target),
+        )
+        .int(
+            "conflict_count",
+            rows.iter().map(|r| r.conflict_count.map(|x| x as i64)),
+        )
+        .float("tx_success_rate", rows.iter().map(|r| r.tx_success_rate))
+        .float("avg_height_lag", rows.iter().map(|r| r.avg_height_lag))
+        .float("avg_mempool_txs", rows.iter().map(|r| r.avg_mempool_txs));
+
+    // Parallel to the metadata's level and threshold lists
+    let nth = |values: &Option<Vec<f64>>, i: usize| values.as_ref().and_then(|v| v.get(i).copied());
+    for (i, level) in report.metadata.spy_visibility_levels.iter().enumerate() {
+        table = table.float(
+            &format!("spy_accuracy_at_{}pct", (level * 100.0).round() as u32),
+            rows.iter().map(|r| nth(&r.spy_accuracy_by_visibility, i)),
+        );
+    }
+    for (i, gap) in report.metadata.fluff_gap_thresholds_ms.iter().enumerate() {
+        table = table.float(
+            &format!("stem_length_gap_{}ms", gap.round() as u64),
+            rows.iter().map(|r| nth(&r.stem_length_by_gap_threshold, i)),
+        );
+    }
+    for (i, percentile) in report.metadata.coverage_percentiles.iter().enumerate() {
+        table = table.float(
+            &format!("time_to_{}pct_ms", percentile),
+            rows.iter().map(|r| {
+                r.coverage_ms_by_percentile
+                    .as_ref()
+                    .and_then(|v| v.get(i).copied().flatten())
+            }),
+        );
+    }
+    table
+}
+
+/// `sweep_summary`
+pub fn sweep_summary_table(summary: &SweepSummary) -> Table {
+    let rows = &summary.runs;
+    let mut table = Table::new("sweep_summary")
+        .str("run", rows.iter().map(|r| r.name.clone()))
+        .str(
+            "status",
+            rows.iter().map(|r| match r.status {
+                SweepRunStatus::Ok => "ok".to_string(),
+                SweepRunStatus::Failed => "failed".to_string(),
+            }),
+        );
+    for name in &summary.parameters {
+        table = table.str(
+            name,
+            rows.iter().map(|r| {
+                r.parameters.get(name).map(|v| match v {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+            }),
+        );
+    }
+    table = table
+        .int("agents", rows.iter().map(|r| r.agents as i64))
+        .int("transactions", rows.iter().map(|r| r.transactions as i64))
+        .int("daemons", rows.iter().map(|r| r.daemons as i64));
+    for name in &summary.metrics {
+        table = table.float(
+            name,
+            rows.iter().map(|r| r.metrics.get(name).copied().flatten()),
+        );
+    }
+    table.str("error", rows.iter().map(|r| r.error.clone()))
+}
+
+/// The tables of whichever analyses a full report contains
+pub fn full_report_tables(report: &FullAnalysisReport) -> Vec<Table> {
+    let mut tables = Vec::new();
+    if let Some(ref spy) = report.spy_node_analysis {
+        tables.push(spy_tx_table(spy));
+    }
+    if let Some(ref propagation) = report.propagation_analysis {
+        tables.push(propagation_tx_table(propagation));
+    }
+    tables
+}
+
+/// Write each table into `dir` in `format`, returning the files written
+/// (none for [`ReportFormat::Json`])
+pub fn write_tables(tables: &[Table], dir: &Path, format: ReportFormat) -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    for table in tables {
+        let path = match format {
+            ReportFormat::Json => continue,
+            ReportFormat::Csv => {
+                let path = dir.join(format!("{}.csv", table.name));
+                write_csv(table, &path)?;
+                path
+            }
+            ReportFormat::Parquet => {
+                let path = dir.join(format!("{}.parquet", table.name));
+                write_parquet(table, &path)?;
+                path
+            }
+        };
+        log::info!(
+            "{} table ({} rows) written to {}",
+            table.name,
+            table.row_count(),
+            path.display()
+        );
+        written.push(path);
+    }
+    Ok(written)
+}
+
+fn write_csv(table: &Table, path: &Path) -> Result<()> {
+    let mut writer = csv::Writer::from_path(path)
+        .with_context(|| format!("Failed to create CSV file {}", path.display()))?;
+    writer.write_record(table.column_names())?;
+    for row in 0..table.row_count() {
+        writer.write_record(table.columns.iter().map(|c| c.values.cell(row)))?;
+    }
+    writer
+        .flush()
+        .with_context(|| format!("Failed to write CSV file {}", path.display()))
+}
+
+#[cfg(not(feature = "parquet"))]
+fn write_parquet(_table: &Table, path: &Path) -> Result<()> {
+    Err(color_eyre::eyre::eyre!(
+        "Cannot write {}: parquet output needs a build with `--features parquet`",
+        path.display()
+    ))
+}
+
+#[cfg(feature = "parquet")]
+fn write_parquet(table: &Table, path: &Path) -> Result<()> {
+    use std::fs::File;
+    use std::sync::Arc;
+
+    use parquet::basic::{LogicalType, Repetition, Type as PhysicalType};
+    use parquet::data_type::{BoolType, ByteArray, ByteArrayType, DoubleType, Int64Type};
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::types::Type;
+
+    /// Present values, and the definition level of every row (1 = present)
+    fn split<T: Clone, U>(values: &[Option<T>], map: impl Fn(T) -> U) -> (Vec<U>, Vec<i16>) {
+        let present = values.iter().flatten().cloned().map(map).collect();
+        let levels = values.iter().map(|v| v.is_some() as i16).collect();
+        (present, levels)
+    }
+
+    let fields = table
+        .columns
+        .iter()
+        .map(|column| {
+            let (physical, logical) = match column.values {
+                Values::Str(_) => (PhysicalType::BYTE_ARRAY, Some(LogicalType::String)),
+                Values::Int(_) => (PhysicalType::INT64, None),
+                Values::Float(_) => (PhysicalType::DOUBLE, None),
+                Values::Bool(_) => (PhysicalType::BOOLEAN, None),
+            };
+            Type::primitive_type_builder(&column.name, physical)
+                .with_repetition(Repetition::OPTIONAL)
+                .with_logical_type(logical)
+                .build()
+                .map(Arc::new)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let schema = Type::group_type_builder(table.name)
+        .with_fields(fields)
+        .build()?;
+
+    let file = File::create(path)
+        .with_context(|| format!("Failed to create Parquet file {}", path.display()))?;
+    let mut writer = SerializedFileWriter::new(
+        file,
+        Arc::new(schema),
+        Arc::new(WriterProperties::builder().build()),
+    )?;
+    let mut row_group = writer.next_row_group()?;
+    for column in &table.columns {
+        let mut column_writer = row_group
+            .next_column()?
+            .expect("one column writer per schema field");
+        match &column.values {
+            Values::Str(v) => {
+                let (values, levels) = split(v, |s| ByteArray::from(s.as_str()));
+                column_writer
+                    .typed::<ByteArrayType>()
+                    .write_batch(&values, Some(&levels), None)?;
+            }
+            Values::Int(v) => {
+                let (values, levels) = split(v, |x| x);
+                column_writer
+                    .typed::<Int64Type>()
+                    .write_batch(&values, Some(&levels), None)?;
+            }
+            Values::Float(v) => {
+                let (values, levels) = split(v, |x| x);
+                column_writer
+                    .typed::<DoubleType>()
+                    .write_batch(&values, Some(&levels), None)?;
+            }
+            Values::Bool(v) => {
+                let (values, levels) = split(v, |x| x);
+                column_writer
+                    .typed::<BoolType>()
+                    .write_batch(&values, Some(&levels), None)?;
+            }
+        }
+        column_writer.close()?;
+    }
+    row_group.close()?;
+    writer
+        .close()
+        .with_context(|| format!("Failed to write Parquet file {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::testutil::regression_run;
+    use crate::analysis::upgrade_analysis::UpgradeAnalysisConfig;
+    use crate::analysis::{
+        analyze_bandwidth, analyze_propagation, analyze_spy_vulnerability, analyze_upgrade_impact,
+        DEFAULT_COVERAGE_PERCENTILES,
+    };
+
+    /// Every dataset of the regression run, with the JSON array each table
+    /// flattens
+    fn datasets() -> Vec<(Table, serde_json::Value)> {
+        let run = regression_run();
+        let log_data = run.log_map();
+        let spy = analyze_spy_vulnerability(&run.transactions, &log_data, &run.agents, None);
+        let propagation = analyze_propagation(
+            &run.transactions,
+            &run.blocks,
+            &log_data,
+            &run.agents,
+            false,
+            &DEFAULT_COVERAGE_PERCENTILES,
+            None,
+        );
+        let bandwidth = analyze_bandwidth(&log_data, 10);
+        let upgrade = analyze_upgrade_impact(
+            &run.transactions,
+            &log_data,
+            &run.agents,
+            &run.blocks,
+            &UpgradeAnalysisConfig {
+                window_size_sec: 10.0,
+                ..UpgradeAnalysisConfig::default()
+            },
+            "",
+        )
+        .unwrap();
+        let json = |value: serde_json::Value, key: &str| value[key].clone();
+        vec![
+            (
+                spy_tx_table(&spy),
+                json(serde_json::to_value(&spy).unwrap(), "per_tx_analysis"),
+            ),
+            (
+                propagation_tx_table(&propagation),
+                json(
+                    serde_json::to_value(&propagation).unwrap(),
+                    "per_tx_analysis",
+                ),
+            ),
+            (
+                bandwidth_node_table(&bandwidth),
+                json(serde_json::to_value(&bandwidth).unwrap(), "per_node_stats"),
+            ),
+            (
+                upgrade_window_table(&upgrade),
+                json(serde_json::to_value(&upgrade).unwrap(), "time_series"),
+            ),
+        ]
+    }
+
+    #[test]
+    fn csv_rows_match_json_reports() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        for (table, json) in datasets() {
+            let json_rows = json.as_array().unwrap().len();
+            assert!(json_rows > 0, "{} is empty", table.name);
+
+            let paths =
+                write_tables(std::slice::from_ref(&table), tmp.path(), ReportFormat::Csv).unwrap();
+            let mut reader = csv::Reader::from_path(&paths[0]).unwrap();
+            assert_eq!(
+                reader.headers().unwrap().iter().collect::<Vec<_>>(),
+                table.column_names()
+            );
+            let rows = reader.records().collect::<Result<Vec<_>, _>>().unwrap();
+            assert_eq!(rows.len(), json_rows, "{}", table.name);
+            assert!(rows.iter().all(|r| r.len() == table.columns.len()));
+        }
+    }
+
+    #[test]
+    fn upgrade_columns_are_named_after_metadata_levels() {
+        let (table, _) = datasets().pop().unwrap();
+        let names = table.column_names();
+        assert!(names.contains(&"spy_accuracy_at_5pct"));
+        assert!(names.contains(&"spy_accuracy_at_50pct"));
+        assert!(names.contains(&"stem_length_gap_2000ms"));
+        assert_eq!(names[..3], ["window_start", "window_end", "label"]);
+    }
+
+    #[test]
+    fn format_parses_known_names_only() {
+        assert_eq!("csv".parse::<ReportFormat>(), Ok(ReportFormat::Csv));
+        assert_eq!("json".parse::<ReportFormat>(), Ok(ReportFormat::Json));
+        assert!("xlsx".parse::<ReportFormat>().is_err());
+        assert_eq!(
+            "parquet".parse::<ReportFormat>().is_ok(),
+            cfg!(feature = "parquet")
+        );
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn parquet_rows_match_json_reports() {
+        use std::fs::File;
+
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        for (table, json) in datasets() {
+            let paths = write_tables(
+                std::slice::from_ref(&table),
+                tmp.path(),
+                ReportFormat::Parquet,
+            )
+            .unwrap();
+            let reader = SerializedFileReader::new(File::open(&paths[0]).unwrap()).unwrap();
+            let metadata = reader.metadata().file_metadata();
+            assert_eq!(
+                metadata.num_rows() as usize,
+                json.as_array().unwrap().len(),
+                "{}",
+                table.name
+            );
+            assert_eq!(metadata.schema_descr().num_columns(), table.columns.len());
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Layout versions of what the analyzer writes: the JSON reports and the
+//! parsed-log cache.
+//!
+//! Every report carries `report_schema_version` so scripts reading it can
+//! tell when a field was added, renamed or changed meaning. Reports written
+//! before versioning read back as version 0. Cache entries start with a
+//! header holding `CACHE_SCHEMA_VERSION`; entries with another version are
+//! re-parsed instead of decoded. Bump a version in the same change that
+//! alters the layout and add a line to its history.
+
+/// Layout version of the JSON reports `tx-analyzer` writes
+pub const REPORT_SCHEMA_VERSION: u32 = 6;
+
+/// Layout version of the `NodeLogData` cache entries
+pub const CACHE_SCHEMA_VERSION: u32 = 2;
+
+/// What each `REPORT_SCHEMA_VERSION` changed, oldest first
+pub const REPORT_SCHEMA_HISTORY: &[(u32, &str)] = &[
+    (0, "Reports without report_schema_version"),
+    (1, "report_schema_version added to every report"),
+    (
+        2,
+        "ResilienceMetrics.partition_events, one per scheduled partition",
+    ),
+    (3, "conflicts report; WindowedMetrics.conflict_count"),
+    (
+        4,
+        "Block interval fields in BlockPropagationReport and WindowedMetrics",
+    ),
+    (5, "tx_success report; WindowedMetrics.tx_success_rate"),
+    (6, "AnonymizationInfo.timing_noise"),
+];
+
+/// What each `CACHE_SCHEMA_VERSION` changed, oldest first
+pub const CACHE_SCHEMA_HISTORY: &[(u32, &str)] = &[
+    (0, "Fingerprint and NodeLogData without a header"),
+    (1, "Header with magic bytes and the schema version"),
+    (
+        2,
+        "BandwidthEvent.messages; the fingerprint records the LogReduction",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histories_end_at_the_current_versions() {
+        for (history, current) in [
+            (REPORT_SCHEMA_HISTORY, REPORT_SCHEMA_VERSION),
+            (CACHE_SCHEMA_HISTORY, CACHE_SCHEMA_VERSION),
+        ] {
+            let versions: Vec<u32> = history.iter().map(|(v, _)| *v).collect();
+            assert_eq!(versions, (0..=current).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn reports_carry_the_version_and_old_ones_read_as_zero() {
+        let report = crate::analysis::analyze_readiness(&[]);
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["report_schema_version"], REPORT_SCHEMA_VERSION);
+
+        let old: crate::analysis::ReadinessReport =
+            serde_json::from_str(r#"{"targets": [], "not_ready": []}"#).unwrap();
+        assert_eq!(old.report_schema_version, 0);
+    }
+}
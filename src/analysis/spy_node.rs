@@ -3,23 +3,57 @@
 //! Analyzes how effectively a spy node could deanonymize transaction origins
 //! by observing first-seen timing patterns.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
+use super::stats::mean;
 use super::types::*;
 
-/// Analyze spy node vulnerability for all transactions
+/// Agents the registry marks with role `spy`, sorted
+pub fn registry_spies(agents: &[AnalysisAgentInfo]) -> Vec<String> {
+    let mut spies: Vec<String> = agents
+        .iter()
+        .filter(|a| a.role.as_deref() == Some("spy"))
+        .map(|a| a.id.clone())
+        .collect();
+    spies.sort();
+    spies
+}
+
+/// Analyze spy node vulnerability for all transactions.
+///
+/// `monitors` restricts the observations to a chosen spy coalition; without
+/// it the registry's spies are used, or every node when there are none.
 pub fn analyze_spy_vulnerability(
     transactions: &[Transaction],
     log_data: &HashMap<String, NodeLogData>,
     agents: &[AnalysisAgentInfo],
+    monitors: Option<&[String]>,
 ) -> SpyNodeReport {
     // Build IP-to-agent mapping
     let ip_to_agent: HashMap<&str, &AnalysisAgentInfo> =
         agents.iter().map(|a| (a.ip_addr.as_str(), a)).collect();
 
+    // Without an explicit monitor set, agents the registry marks as spies
+    // are the observers; without any, every node's logs stand in for a
+    // global passive adversary
+    let mut observers: Vec<String> = match monitors {
+        Some(ids) => ids.to_vec(),
+        None => registry_spies(agents),
+    };
+    observers.sort();
+    observers.dedup();
+    let spy_ips: HashSet<&str> = agents
+        .iter()
+        .filter(|a| observers.contains(&a.id))
+        .map(|a| a.ip_addr.as_str())
+        .collect();
+
     // Build TX hash to observations mapping
     let mut tx_observations: HashMap<String, Vec<&TxObservation>> = HashMap::new();
-    for (_, node_data) in log_data {
+    for (node_id, node_data) in log_data {
+        if !observers.is_empty() && !observers.contains(node_id) {
+            continue;
+        }
         for obs in &node_data.tx_observations {
             tx_observations
                 .entry(obs.tx_hash.clone())
@@ -30,13 +64,27 @@ pub fn analyze_spy_vulnerability(
 
     let mut analyses = Vec::new();
     let mut correct_inferences = 0;
+    // Observer -> (transactions seen, first sightings, correct inferences)
+    let mut spy_tallies: HashMap<&str, (usize, usize, usize)> = HashMap::new();
 
     for tx in transactions {
         if let Some(observations) = tx_observations.get(&tx.tx_hash) {
-            let analysis = analyze_single_tx(tx, observations, &ip_to_agent);
+            let mut analysis = analyze_single_tx(tx, observations, &ip_to_agent);
             if analysis.inference_correct {
                 correct_inferences += 1;
             }
+            if !observers.is_empty() {
+                let (anonymity_set, per_spy) =
+                    coalition_view(observations, analysis.true_sender_ip.as_deref(), &spy_ips);
+                analysis.anonymity_set_size = Some(anonymity_set);
+                let first = analysis.first_seen_by.first().map(|e| e.node_id.as_str());
+                for (node_id, correct) in per_spy {
+                    let tally = spy_tallies.entry(node_id).or_default();
+                    tally.0 += 1;
+                    tally.1 += usize::from(first == Some(node_id));
+                    tally.2 += usize::from(correct);
+                }
+            }
             analyses.push(analysis);
         }
     }
@@ -48,6 +96,35 @@ pub fn analyze_spy_vulnerability(
         0.0
     };
 
+    let per_spy = observers
+        .iter()
+        .map(|id| {
+            let (observed, first, correct) =
+                spy_tallies.get(id.as_str()).copied().unwrap_or_default();
+            SpyObserverStats {
+                node_id: id.clone(),
+                transactions_observed: observed,
+                first_observations: first,
+                first_observation_share: if total_txs > 0 {
+                    first as f64 / total_txs as f64
+                } else {
+                    0.0
+                },
+                accuracy: if observed > 0 {
+                    correct as f64 / observed as f64
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect();
+    let set_sizes: Vec<f64> = analyses
+        .iter()
+        .filter_map(|a| a.anonymity_set_size)
+        .map(|n| n as f64)
+        .collect();
+    let avg_anonymity_set_size = (!set_sizes.is_empty()).then(|| mean(&set_sizes));
+
     // Calculate timing distribution
     let timing_distribution = calculate_timing_distribution(&analyses);
 
@@ -61,7 +138,45 @@ pub fn analyze_spy_vulnerability(
         timing_spread_distribution: timing_distribution,
         vulnerable_senders,
         per_tx_analysis: analyses,
+        observers,
+        per_spy,
+        avg_anonymity_set_size,
+    }
+}
+
+/// The coalition's view of one transaction: the size of its anonymity set
+/// and, per spy that saw it, whether that spy's observations alone name the
+/// sender.
+///
+/// HEURISTIC — the anonymity set is the distinct non-spy peers the spies
+/// first received the transaction from. Under a first-spy estimator each of
+/// them is an equally plausible originator; peers seen only on later
+/// sightings, and other spies relaying to each other, are not counted.
+fn coalition_view<'a>(
+    observations: &[&'a TxObservation],
+    true_sender_ip: Option<&str>,
+    spy_ips: &HashSet<&str>,
+) -> (usize, Vec<(&'a str, bool)>) {
+    let mut by_spy: HashMap<&str, Vec<&TxObservation>> = HashMap::new();
+    for &obs in observations {
+        by_spy.entry(&obs.node_id).or_default().push(obs);
     }
+
+    let mut candidates: HashSet<&str> = HashSet::new();
+    let per_spy = by_spy
+        .into_iter()
+        .map(|(node_id, mut seen)| {
+            seen.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+            let first_source = seen[0].source_ip.as_str();
+            if !spy_ips.contains(first_source) {
+                candidates.insert(first_source);
+            }
+            let correct =
+                true_sender_ip.is_some() && infer_originator(&seen).as_deref() == true_sender_ip;
+            (node_id, correct)
+        })
+        .collect();
+    (candidates.len(), per_spy)
 }
 
 /// Analyze a single transaction for spy node vulnerability
@@ -124,6 +239,7 @@ fn analyze_single_tx(
         timing_spread_ms,
         inferred_originator_ip,
         inference_correct,
+        anonymity_set_size: None,
     }
 }
 
@@ -272,3 +388,78 @@ fn find_vulnerable_senders(analyses: &[SpyNodeTxAnalysis]) -> Vec<VulnerableSend
 
     vulnerable
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::testutil::SyntheticRun;
+
+    #[test]
+    fn registry_spies_are_the_only_observers() {
+        let mut run = SyntheticRun::new();
+        run.agent("user-1", "agents.regular_user")
+            .agent("relay-1", "")
+            .agent("relay-2", "")
+            .agent("spy-1", "")
+            .transaction("aa", "user-1", "relay-1", 100.0)
+            // relay-1 hears it straight from the sender, the spy one hop later
+            .observe("aa", "relay-1", "user-1", 100.1)
+            .observe("aa", "spy-1", "relay-2", 100.3);
+
+        let everyone =
+            analyze_spy_vulnerability(&run.transactions, &run.log_map(), &run.agents, None);
+        assert!(everyone.observers.is_empty());
+        assert_eq!(everyone.inference_accuracy, 1.0);
+
+        run.role("spy-1", "spy");
+        let spy = analyze_spy_vulnerability(&run.transactions, &run.log_map(), &run.agents, None);
+        assert_eq!(spy.observers, ["spy-1"]);
+        assert_eq!(spy.per_tx_analysis[0].first_seen_by.len(), 1);
+        assert_eq!(spy.per_tx_analysis[0].first_seen_by[0].node_id, "spy-1");
+        assert_eq!(spy.inference_accuracy, 0.0);
+    }
+
+    #[test]
+    fn chosen_monitors_report_per_spy_stats_and_anonymity_sets() {
+        let mut run = SyntheticRun::new();
+        for id in ["user-1", "relay-1", "relay-2", "spy-1", "spy-2"] {
+            run.agent(id, "");
+        }
+        run.transaction("aa", "user-1", "relay-1", 100.0)
+            .observe("aa", "relay-1", "user-1", 100.05)
+            .observe("aa", "spy-1", "user-1", 100.1)
+            .observe("aa", "spy-2", "relay-2", 100.3)
+            // Spies relaying to each other add no candidate
+            .observe("aa", "spy-2", "spy-1", 100.4);
+
+        let everyone =
+            analyze_spy_vulnerability(&run.transactions, &run.log_map(), &run.agents, None);
+        assert!(everyone.per_spy.is_empty());
+        assert_eq!(everyone.avg_anonymity_set_size, None);
+        assert_eq!(everyone.per_tx_analysis[0].anonymity_set_size, None);
+
+        let monitors = ["spy-2".to_string(), "spy-1".to_string()];
+        let report = analyze_spy_vulnerability(
+            &run.transactions,
+            &run.log_map(),
+            &run.agents,
+            Some(&monitors),
+        );
+        assert_eq!(report.observers, ["spy-1", "spy-2"]);
+        assert_eq!(report.inference_accuracy, 1.0);
+        assert_eq!(report.per_tx_analysis[0].first_seen_by[0].node_id, "spy-1");
+        assert_eq!(report.per_tx_analysis[0].anonymity_set_size, Some(2));
+        assert_eq!(report.avg_anonymity_set_size, Some(2.0));
+
+        let spy_1 = &report.per_spy[0];
+        assert_eq!(spy_1.node_id, "spy-1");
+        assert_eq!(spy_1.first_observations, 1);
+        assert_eq!(spy_1.first_observation_share, 1.0);
+        assert_eq!(spy_1.accuracy, 1.0);
+        // On its own spy-2 blames relay-2
+        let spy_2 = &report.per_spy[1];
+        assert_eq!(spy_2.transactions_observed, 1);
+        assert_eq!(spy_2.first_observations, 0);
+        assert_eq!(spy_2.accuracy, 0.0);
+    }
+}
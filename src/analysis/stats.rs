@@ -3,19 +3,60 @@
 //! These consolidate mean/median/percentile/gini implementations that were
 //! previously duplicated (and, in a couple of places, subtly wrong — bare
 //! `[len / 2]` medians that ignored even-length inputs) across the analysis
-//! modules.
+//! modules, plus the significance tests and bootstrap confidence intervals
+//! behind every before/after comparison (upgrade periods, multi-run
+//! comparisons).
+//!
+//! Test reference values match `statistics.stdev` and
+//! `scipy.stats.ttest_ind(a, b, equal_var=False)`.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use super::types::{ChangeImpact, ConfidenceInterval};
+
+/// Resamples drawn for each bootstrap confidence interval
+pub const BOOTSTRAP_RESAMPLES: usize = 1000;
+
+/// Confidence level of the intervals reported with each comparison
+pub const CONFIDENCE_LEVEL: f64 = 0.95;
+
+/// Fixed so that the same inputs always give the same interval
+const BOOTSTRAP_SEED: u64 = 0;
 
 /// Arithmetic mean of a slice. Returns `0.0` for an empty slice.
-pub(crate) fn mean(values: &[f64]) -> f64 {
+pub fn mean(values: &[f64]) -> f64 {
     if values.is_empty() {
         return 0.0;
     }
     values.iter().sum::<f64>() / values.len() as f64
 }
 
+/// Sample standard deviation (`n - 1` denominator). `None` with fewer than
+/// two values.
+pub fn std_dev(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let mean = mean(values);
+    let variance =
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    Some(variance.sqrt())
+}
+
+/// Mean and sample standard deviation of the present values; the standard
+/// deviation needs at least two.
+pub fn calculate_stats(values: &[Option<f64>]) -> (Option<f64>, Option<f64>) {
+    let valid: Vec<f64> = values.iter().filter_map(|v| *v).collect();
+    if valid.is_empty() {
+        return (None, None);
+    }
+    (Some(mean(&valid)), std_dev(&valid))
+}
+
 /// Median of a slice. Even-length inputs return the average of the two middle
 /// elements (the standard convention). Returns `0.0` for an empty slice.
-pub(crate) fn median(values: &[f64]) -> f64 {
+pub fn median(values: &[f64]) -> f64 {
     if values.is_empty() {
         return 0.0;
     }
@@ -31,7 +72,7 @@ pub(crate) fn median(values: &[f64]) -> f64 {
 
 /// The `p`th percentile (`p` in `0..=100`) via nearest-rank on the sorted
 /// values. Returns `0.0` for an empty slice.
-pub(crate) fn percentile(values: &[f64], p: f64) -> f64 {
+pub fn percentile(values: &[f64], p: f64) -> f64 {
     if values.is_empty() {
         return 0.0;
     }
@@ -46,7 +87,7 @@ pub(crate) fn percentile(values: &[f64], p: f64) -> f64 {
 /// Returns `0.0` for empty input or when all values are zero (perfect
 /// equality). The result ranges from `0.0` (perfect equality) toward `1.0`
 /// (maximum concentration).
-pub(crate) fn gini(values: &[f64]) -> f64 {
+pub fn gini(values: &[f64]) -> f64 {
     if values.is_empty() {
         return 0.0;
     }
@@ -68,6 +109,333 @@ pub(crate) fn gini(values: &[f64]) -> f64 {
     gini_sum / (n * sum)
 }
 
+/// Result of Welch's unequal-variances t-test
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WelchTest {
+    /// `(mean1 - mean2) / standard error`
+    pub t: f64,
+    /// Welch-Satterthwaite degrees of freedom
+    pub df: f64,
+    /// Two-tailed p-value for equal means
+    pub p_value: f64,
+}
+
+/// Welch's t-test of two samples. `None` with fewer than two values in
+/// either sample, or when both have zero variance.
+pub fn welch(sample1: &[f64], sample2: &[f64]) -> Option<WelchTest> {
+    if sample1.len() < 2 || sample2.len() < 2 {
+        return None;
+    }
+
+    let n1 = sample1.len() as f64;
+    let n2 = sample2.len() as f64;
+
+    let mean1 = mean(sample1);
+    let mean2 = mean(sample2);
+
+    let var1 = sample1.iter().map(|x| (x - mean1).powi(2)).sum::<f64>() / (n1 - 1.0);
+    let var2 = sample2.iter().map(|x| (x - mean2).powi(2)).sum::<f64>() / (n2 - 1.0);
+
+    let se = (var1 / n1 + var2 / n2).sqrt();
+    if se == 0.0 {
+        return None;
+    }
+
+    let t = (mean1 - mean2) / se;
+
+    // Welch-Satterthwaite degrees of freedom
+    let df_num = (var1 / n1 + var2 / n2).powi(2);
+    let df_denom = (var1 / n1).powi(2) / (n1 - 1.0) + (var2 / n2).powi(2) / (n2 - 1.0);
+    let df = df_num / df_denom;
+
+    // Two-tailed p-value from the Student's t distribution, computed exactly via
+    // the regularized incomplete beta function.
+    Some(WelchTest {
+        t,
+        df,
+        p_value: student_t_two_tailed_p(t, df),
+    })
+}
+
+/// Perform a simple two-sample t-test (Welch's t-test).
+///
+/// Returns the p-value for the null hypothesis that the two samples have equal means.
+pub fn welch_t_test(sample1: &[f64], sample2: &[f64]) -> Option<f64> {
+    welch(sample1, sample2).map(|w| w.p_value)
+}
+
+/// Percentile bootstrap confidence interval for `mean(samples) -
+/// mean(baseline)`, at `level` (e.g. 0.95) over `resamples` resamples.
+/// `None` with fewer than two values on either side.
+pub fn bootstrap_mean_difference_ci(
+    baseline: &[f64],
+    samples: &[f64],
+    level: f64,
+    resamples: usize,
+    seed: u64,
+) -> Option<ConfidenceInterval> {
+    if baseline.len() < 2 || samples.len() < 2 || resamples == 0 {
+        return None;
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut resample_mean = |values: &[f64]| {
+        let sum: f64 = (0..values.len())
+            .map(|_| values[rng.gen_range(0..values.len())])
+            .sum();
+        sum / values.len() as f64
+    };
+    let mut differences: Vec<f64> = (0..resamples)
+        .map(|_| resample_mean(samples) - resample_mean(baseline))
+        .collect();
+    differences.sort_by(|a, b| a.total_cmp(b));
+
+    let tail = (1.0 - level) / 2.0;
+    let at = |q: f64| {
+        let idx = (q * (resamples - 1) as f64).round() as usize;
+        differences[idx.min(resamples - 1)]
+    };
+    Some(ConfidenceInterval {
+        lower: at(tail),
+        upper: at(1.0 - tail),
+        level,
+    })
+}
+
+/// Two-tailed p-value for a Student's t statistic with `df` degrees of freedom.
+///
+/// Uses the identity P(|T| > t) = I_x(df/2, 1/2) with x = df / (df + t^2),
+/// where I_x is the regularized incomplete beta function.
+fn student_t_two_tailed_p(t: f64, df: f64) -> f64 {
+    if df <= 0.0 {
+        return f64::NAN;
+    }
+    let t = t.abs();
+    let x = df / (df + t * t);
+    regularized_incomplete_beta(df / 2.0, 0.5, x)
+}
+
+/// Upper-tail p-value of a chi-square statistic with `df` degrees of
+/// freedom: Q(df/2, statistic/2), the regularized upper incomplete gamma
+/// function. `None` for `df == 0`.
+pub fn chi_square_p_value(statistic: f64, df: usize) -> Option<f64> {
+    if df == 0 {
+        return None;
+    }
+    let a = df as f64 / 2.0;
+    let x = statistic.max(0.0) / 2.0;
+    if x == 0.0 {
+        return Some(1.0);
+    }
+    let ln_prefactor = a * x.ln() - x - ln_gamma(a);
+
+    // Numerical Recipes `gammq`: series below a + 1, continued fraction above
+    const MAXIT: usize = 200;
+    const EPS: f64 = 3.0e-12;
+    if x < a + 1.0 {
+        let mut ap = a;
+        let mut del = 1.0 / a;
+        let mut sum = del;
+        for _ in 0..MAXIT {
+            ap += 1.0;
+            del *= x / ap;
+            sum += del;
+            if del.abs() < sum.abs() * EPS {
+                break;
+            }
+        }
+        Some((1.0 - sum * ln_prefactor.exp()).clamp(0.0, 1.0))
+    } else {
+        const FPMIN: f64 = 1.0e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / FPMIN;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..=MAXIT {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < FPMIN {
+                d = FPMIN;
+            }
+            c = b + an / c;
+            if c.abs() < FPMIN {
+                c = FPMIN;
+            }
+            d = 1.0 / d;
+            let del = d * c;
+            h *= del;
+            if (del - 1.0).abs() < EPS {
+                break;
+            }
+        }
+        Some((ln_prefactor.exp() * h).clamp(0.0, 1.0))
+    }
+}
+
+/// Regularized incomplete beta function I_x(a, b).
+///
+/// Numerical Recipes `betai`: uses the continued-fraction expansion (`betacf`)
+/// together with the symmetry relation I_x(a, b) = 1 - I_{1-x}(b, a) for fast
+/// convergence.
+fn regularized_incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    // Beta prefactor bt = x^a * (1-x)^b / B(a, b), computed in log space.
+    let ln_bt = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    let bt = ln_bt.exp();
+
+    if x < (a + 1.0) / (a + b + 2.0) {
+        bt * betacf(a, b, x) / a
+    } else {
+        1.0 - bt * betacf(b, a, 1.0 - x) / b
+    }
+}
+
+/// Continued-fraction evaluation for the incomplete beta function
+/// (Numerical Recipes `betacf`, Lentz's algorithm).
+fn betacf(a: f64, b: f64, x: f64) -> f64 {
+    const MAXIT: usize = 200;
+    const EPS: f64 = 3.0e-12;
+    const FPMIN: f64 = 1.0e-300;
+
+    let qab = a + b;
+    let qap = a + 1.0;
+    let qam = a - 1.0;
+    let mut c = 1.0;
+    let mut d = 1.0 - qab * x / qap;
+    if d.abs() < FPMIN {
+        d = FPMIN;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    for m in 1..=MAXIT {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+
+        // Even step of the recurrence.
+        let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+        d = 1.0 + aa * d;
+        if d.abs() < FPMIN {
+            d = FPMIN;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < FPMIN {
+            c = FPMIN;
+        }
+        d = 1.0 / d;
+        h *= d * c;
+
+        // Odd step of the recurrence.
+        let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+        d = 1.0 + aa * d;
+        if d.abs() < FPMIN {
+            d = FPMIN;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < FPMIN {
+            c = FPMIN;
+        }
+        d = 1.0 / d;
+        let del = d * c;
+        h *= del;
+        if (del - 1.0).abs() < EPS {
+            break;
+        }
+    }
+
+    h
+}
+
+/// Natural log of the gamma function (Numerical Recipes `gammln`, Lanczos).
+fn ln_gamma(xx: f64) -> f64 {
+    const COF: [f64; 6] = [
+        76.18009172947146,
+        -86.50532032941677,
+        24.01409824083091,
+        -1.231739572450155,
+        0.1208650973866179e-2,
+        -0.5395239384953e-5,
+    ];
+
+    let x = xx;
+    let mut y = xx;
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let mut ser = 1.000000000190015;
+    for c in COF.iter() {
+        y += 1.0;
+        ser += c / y;
+    }
+    -tmp + (2.5066282746310005 * ser / x).ln()
+}
+
+/// Determine if a change is statistically significant at p < 0.05.
+pub fn is_significant(p_value: Option<f64>) -> bool {
+    p_value.map(|p| p < 0.05).unwrap_or(false)
+}
+
+/// A metric's change from a baseline, tested with Welch's t-test
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleComparison {
+    pub absolute_change: f64,
+    /// 0 when the baseline value is 0
+    pub percent_change: f64,
+    pub p_value: Option<f64>,
+    pub significant: bool,
+    /// Neutral unless significant
+    pub impact: ChangeImpact,
+    /// Bootstrap interval for the difference in sample means
+    pub confidence_interval: Option<ConfidenceInterval>,
+}
+
+/// Compare a metric's `value` against its `baseline`, each summarizing the
+/// matching samples (per window, per transaction, ...).
+pub fn compare_samples(
+    baseline: f64,
+    value: f64,
+    baseline_samples: &[f64],
+    samples: &[f64],
+    higher_is_better: bool,
+) -> SampleComparison {
+    let absolute_change = value - baseline;
+    let percent_change = if baseline != 0.0 {
+        (absolute_change / baseline) * 100.0
+    } else {
+        0.0
+    };
+
+    let p_value = welch_t_test(baseline_samples, samples);
+    let significant = is_significant(p_value);
+
+    let impact = if !significant || absolute_change == 0.0 {
+        ChangeImpact::Neutral
+    } else if (absolute_change > 0.0) == higher_is_better {
+        ChangeImpact::Positive
+    } else {
+        ChangeImpact::Negative
+    };
+
+    SampleComparison {
+        absolute_change,
+        percent_change,
+        p_value,
+        significant,
+        impact,
+        confidence_interval: bootstrap_mean_difference_ci(
+            baseline_samples,
+            samples,
+            CONFIDENCE_LEVEL,
+            BOOTSTRAP_RESAMPLES,
+            BOOTSTRAP_SEED,
+        ),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,4 +482,141 @@ mod tests {
         let g = gini(&v);
         assert!(g > 0.9, "expected concentrated gini near 1, got {}", g);
     }
+
+    #[test]
+    fn test_student_t_two_tailed_p() {
+        // Reference two-tailed p-values from the Student's t distribution.
+        assert!((student_t_two_tailed_p(2.228, 10.0) - 0.050).abs() < 1e-3);
+        assert!((student_t_two_tailed_p(2.0, 10.0) - 0.0734).abs() < 1e-3);
+        assert!((student_t_two_tailed_p(1.0, 5.0) - 0.363).abs() < 1e-3);
+        assert!((student_t_two_tailed_p(12.706, 1.0) - 0.050).abs() < 1e-3);
+        // Large df converges to the normal distribution.
+        assert!((student_t_two_tailed_p(1.96, 1000.0) - 0.0501).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_chi_square_p_value() {
+        // 5% critical values
+        for (statistic, df) in [(3.841, 1), (9.488, 4), (18.307, 10)] {
+            let p = chi_square_p_value(statistic, df).unwrap();
+            assert!((p - 0.05).abs() < 1e-3, "df {}: {}", df, p);
+        }
+        assert_eq!(chi_square_p_value(0.0, 3), Some(1.0));
+        assert_eq!(chi_square_p_value(1.0, 0), None);
+    }
+
+    #[test]
+    fn test_compare_samples() {
+        let slow = [100.0, 110.0, 90.0, 105.0, 95.0];
+        let fast = [50.0, 55.0, 45.0, 52.0, 48.0];
+        let c = compare_samples(100.0, 50.0, &slow, &fast, false);
+        assert_eq!(c.absolute_change, -50.0);
+        assert_eq!(c.percent_change, -50.0);
+        assert!(c.significant);
+        assert_eq!(c.impact, ChangeImpact::Positive);
+        assert_eq!(
+            compare_samples(100.0, 50.0, &slow, &fast, true).impact,
+            ChangeImpact::Negative
+        );
+
+        // Too few samples to test: never significant
+        let c = compare_samples(80.0, 20.0, &[80.0], &[20.0], true);
+        assert_eq!(c.p_value, None);
+        assert_eq!(c.impact, ChangeImpact::Neutral);
+    }
+
+    #[test]
+    fn test_calculate_stats() {
+        let values = vec![Some(1.0), Some(2.0), Some(3.0), Some(4.0), Some(5.0)];
+        let (mean, std) = calculate_stats(&values);
+        assert!((mean.unwrap() - 3.0).abs() < 0.001);
+        assert!((std.unwrap() - 1.5811).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_std_dev() {
+        assert_eq!(std_dev(&[]), None);
+        assert_eq!(std_dev(&[3.0]), None);
+        // statistics.stdev([2, 4, 4, 4, 5, 5, 7, 9])
+        let s = std_dev(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]).unwrap();
+        assert!((s - 2.138089935299395).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_gini_known_value() {
+        // Mean absolute difference / (2 * mean) = 0.8 / 3
+        assert!((gini(&[1.0, 2.0, 3.0, 4.0, 5.0]) - 0.8 / 3.0).abs() < 1e-12);
+    }
+
+    /// Samples a and b with the expected t, df and p
+    type WelchCase = (&'static [f64], &'static [f64], f64, f64, f64);
+
+    #[test]
+    fn test_welch_matches_scipy() {
+        // (a, b, t, df, p) from scipy.stats.ttest_ind(a, b, equal_var=False)
+        let cases: [WelchCase; 3] = [
+            (
+                &[1.0, 2.0, 3.0, 4.0, 5.0],
+                &[2.0, 4.0, 6.0, 8.0, 10.0],
+                -1.8973665961010275,
+                5.882352941176471,
+                0.10753119493072329,
+            ),
+            (
+                &[10.1, 9.8, 10.3, 10.0, 9.9, 10.2],
+                &[10.9, 11.2, 10.7, 11.0, 11.4],
+                -6.9257304489290865,
+                6.947620456310311,
+                0.0002339208177732996,
+            ),
+            (
+                &[19.1, 21.3, 20.5, 18.7, 22.0, 20.9, 19.8],
+                &[24.2, 19.9, 26.1, 23.4, 25.0],
+                -2.9579192251161968,
+                5.474353904731801,
+                0.02829686915963072,
+            ),
+        ];
+        for (a, b, t, df, p) in cases {
+            let w = welch(a, b).unwrap();
+            assert!((w.t - t).abs() < 1e-9, "t {} vs {}", w.t, t);
+            assert!((w.df - df).abs() < 1e-9, "df {} vs {}", w.df, df);
+            assert!((w.p_value - p).abs() < 1e-6, "p {} vs {}", w.p_value, p);
+            assert_eq!(welch_t_test(a, b), Some(w.p_value));
+        }
+
+        // Swapping the samples flips t only
+        let w = welch(&[2.0, 4.0, 6.0, 8.0, 10.0], &[1.0, 2.0, 3.0, 4.0, 5.0]).unwrap();
+        assert!((w.t - 1.8973665961010275).abs() < 1e-9);
+        assert!((w.p_value - 0.10753119493072329).abs() < 1e-6);
+
+        assert_eq!(welch(&[1.0], &[1.0, 2.0]), None);
+        assert_eq!(welch(&[1.0, 1.0], &[1.0, 1.0]), None);
+    }
+
+    #[test]
+    fn test_bootstrap_mean_difference_ci() {
+        let slow = [100.0, 110.0, 90.0, 105.0, 95.0, 102.0];
+        let fast = [50.0, 55.0, 45.0, 52.0, 48.0, 51.0];
+        let ci = bootstrap_mean_difference_ci(&slow, &fast, 0.95, 1000, 7).unwrap();
+        // The observed difference is -49.17; zero is far outside
+        assert!(ci.lower < -49.17 && -49.17 < ci.upper, "{:?}", ci);
+        assert!(ci.upper < 0.0);
+        assert_eq!(ci.level, 0.95);
+
+        // Seeded: identical inputs give identical intervals
+        assert_eq!(
+            bootstrap_mean_difference_ci(&slow, &fast, 0.95, 1000, 7),
+            Some(ci)
+        );
+
+        // Identical samples straddle zero
+        let same = bootstrap_mean_difference_ci(&slow, &slow, 0.95, 1000, 7).unwrap();
+        assert!(same.lower <= 0.0 && 0.0 <= same.upper);
+
+        assert_eq!(
+            bootstrap_mean_difference_ci(&[1.0], &fast, 0.95, 1000, 7),
+            None
+        );
+    }
 }
@@ -0,0 +1,403 @@
+//! Batch analysis of a parameter sweep.
+//!
+//! The runs come from the `sweep_index.json` `monerosim sweep` writes, each
+//! variant's directory holding its run's outputs, or else from every run
+//! directory under a root (`parse_run_name` recovers `name=value`
+//! parameters from directory names). Each run is reduced to a few headline
+//! metrics per `SweepAnalysis`, one row per run, so runs can be plotted
+//! against the swept parameters.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use color_eyre::eyre::{eyre, Result};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use super::propagation::DEFAULT_COVERAGE_PERCENTILES;
+use super::schema::REPORT_SCHEMA_VERSION;
+use super::stats::mean;
+use super::types::*;
+use super::{
+    analyze_bandwidth, analyze_propagation, analyze_spy_vulnerability, analyze_tx_relay_v2,
+};
+use crate::sweep::{SweepIndex, SWEEP_INDEX_FILE};
+
+/// File stem of the summary (`.csv` and `.json`)
+pub const SWEEP_SUMMARY_FILE: &str = "sweep_summary";
+
+/// Where a run directory may keep its daemon logs, by preference
+const LOG_DIRS: [&str; 3] = ["daemon_logs", "shadow.data/hosts", "hosts"];
+
+/// Random spy coalitions averaged for `spy_accuracy_at_<P>pct`
+const SPY_TRIALS: u64 = 3;
+
+/// An analysis `Sweep` can run on every run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SweepAnalysis {
+    Propagation,
+    Spy,
+    Bandwidth,
+    Health,
+}
+
+impl SweepAnalysis {
+    pub const ALL: [SweepAnalysis; 4] = [
+        SweepAnalysis::Propagation,
+        SweepAnalysis::Spy,
+        SweepAnalysis::Bandwidth,
+        SweepAnalysis::Health,
+    ];
+
+    /// Columns the analysis fills, in order. `spy_visibility` is the
+    /// fraction of daemons in the random spy coalitions.
+    pub fn metric_names(&self, spy_visibility: f64) -> Vec<String> {
+        let names: &[&str] = match self {
+            SweepAnalysis::Propagation => &[
+                "median_propagation_ms",
+                "avg_propagation_ms",
+                "propagation_coverage",
+                "avg_confirmation_delay_sec",
+            ],
+            SweepAnalysis::Spy => {
+                return vec![
+                    "spy_accuracy".to_string(),
+                    spy_visibility_metric(spy_visibility),
+                ]
+            }
+            SweepAnalysis::Bandwidth => &["total_bytes", "avg_bytes_per_node", "total_messages"],
+            SweepAnalysis::Health => &["health_score"],
+        };
+        names.iter().map(|n| n.to_string()).collect()
+    }
+}
+
+/// `propagation`, `spy`, `bandwidth` or `health`
+impl FromStr for SweepAnalysis {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SweepAnalysis::ALL
+            .into_iter()
+            .find(|a| a.to_string() == s)
+            .ok_or_else(|| {
+                format!(
+                    "invalid analysis '{}' (expected propagation, spy, bandwidth or health)",
+                    s
+                )
+            })
+    }
+}
+
+impl fmt::Display for SweepAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SweepAnalysis::Propagation => "propagation",
+            SweepAnalysis::Spy => "spy",
+            SweepAnalysis::Bandwidth => "bandwidth",
+            SweepAnalysis::Health => "health",
+        })
+    }
+}
+
+/// `spy_accuracy_at_<P>pct`, as in the upgrade window tables
+fn spy_visibility_metric(spy_visibility: f64) -> String {
+    format!("spy_accuracy_at_{}pct", (spy_visibility * 100.0).round())
+}
+
+/// A run to analyze
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepRunSpec {
+    pub name: String,
+    pub dir: PathBuf,
+    /// Swept parameter -> value
+    pub parameters: BTreeMap<String, serde_json::Value>,
+}
+
+/// Where a run directory keeps what the analyses read
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunLayout {
+    /// Daemon logs: `daemon_logs/`, `shadow.data/hosts/` or `hosts/`
+    pub log_dir: PathBuf,
+    /// Registries and `transactions.json`: `shared/`,
+    /// `transaction_registry/` (archived runs) or the directory itself
+    pub shared_dir: PathBuf,
+    /// Parsed log cache, `parsed_logs/` in the Shadow data directory
+    pub cache_dir: PathBuf,
+}
+
+/// Find the logs and registries of the run in `dir`
+pub fn locate_run(dir: &Path) -> Result<RunLayout> {
+    let log_dir = LOG_DIRS
+        .iter()
+        .map(|sub| dir.join(sub))
+        .find(|path| path.is_dir())
+        .ok_or_else(|| {
+            eyre!(
+                "no daemon_logs/, shadow.data/hosts/ or hosts/ in {}; has the run finished?",
+                dir.display()
+            )
+        })?;
+    let candidates = [
+        dir.join("shared"),
+        dir.join("transaction_registry"),
+        dir.to_path_buf(),
+    ];
+    let shared_dir = ["transactions.json", "agent_registry.json"]
+        .iter()
+        .find_map(|file| candidates.iter().find(|c| c.join(file).is_file()))
+        .cloned()
+        .ok_or_else(|| {
+            eyre!(
+                "no agent_registry.json in {0}/shared/, {0}/transaction_registry/ or {0}",
+                dir.display()
+            )
+        })?;
+    let data_dir = dir.join("shadow.data");
+    let data_dir = if data_dir.is_dir() {
+        data_dir
+    } else {
+        dir.to_path_buf()
+    };
+    Ok(RunLayout {
+        log_dir,
+        shared_dir,
+        cache_dir: data_dir.join("parsed_logs"),
+    })
+}
+
+/// Parameters from a directory named like a sweep variant,
+/// `out_peers=8,topology=Mesh`; empty for any other name. Values that
+/// parse as JSON (numbers, booleans) are kept as such.
+pub fn parse_run_name(name: &str) -> BTreeMap<String, serde_json::Value> {
+    let pairs: Option<Vec<(&str, &str)>> = name
+        .split(',')
+        .map(|part| part.split_once('=').filter(|(k, _)| !k.is_empty()))
+        .collect();
+    pairs
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(key, value)| {
+            let value = serde_json::from_str(value)
+                .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+            (key.to_string(), value)
+        })
+        .collect()
+}
+
+/// The runs under `root`: the variants of its `sweep_index.json`, or else
+/// every subdirectory holding daemon logs, by name
+pub fn discover_sweep_runs(root: &Path) -> Result<Vec<SweepRunSpec>> {
+    let index_path = root.join(SWEEP_INDEX_FILE);
+    if index_path.exists() {
+        let index = SweepIndex::load(&index_path)?;
+        return Ok(index
+            .variants
+            .into_iter()
+            .map(|v| SweepRunSpec {
+                dir: root.join(&v.dir),
+                name: v.name,
+                parameters: v.parameters,
+            })
+            .collect());
+    }
+
+    let entries = std::fs::read_dir(root)
+        .map_err(|e| eyre!("Failed to read runs root {}: {}", root.display(), e))?;
+    let mut runs: Vec<SweepRunSpec> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| LOG_DIRS.iter().any(|sub| path.join(sub).is_dir()))
+        .map(|dir| {
+            let name = dir
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            SweepRunSpec {
+                parameters: parse_run_name(&name),
+                name,
+                dir,
+            }
+        })
+        .collect();
+    if runs.is_empty() {
+        return Err(eyre!(
+            "No {} and no run directories in {}",
+            SWEEP_INDEX_FILE,
+            root.display()
+        ));
+    }
+    runs.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(runs)
+}
+
+/// Run `analyses` on one run's data and collect their metrics
+pub fn sweep_run_metrics(
+    transactions: &[Transaction],
+    blocks: &[BlockInfo],
+    log_data: &HashMap<String, NodeLogData>,
+    agents: &[AnalysisAgentInfo],
+    analyses: &[SweepAnalysis],
+    spy_visibility: f64,
+) -> BTreeMap<String, Option<f64>> {
+    let mut metrics = BTreeMap::new();
+    for &analysis in analyses {
+        let mut metric = |name: &str, value: Option<f64>| {
+            metrics.insert(name.to_string(), value);
+        };
+        match analysis {
+            SweepAnalysis::Propagation => {
+                let report = analyze_propagation(
+                    transactions,
+                    blocks,
+                    log_data,
+                    agents,
+                    false,
+                    &DEFAULT_COVERAGE_PERCENTILES,
+                    None,
+                );
+                let analyzed = report.analyzed_transactions > 0;
+                let coverage: Vec<f64> = report
+                    .per_tx_analysis
+                    .iter()
+                    .map(|a| a.propagation_coverage)
+                    .collect();
+                metric(
+                    "median_propagation_ms",
+                    analyzed.then_some(report.median_propagation_ms),
+                );
+                metric(
+                    "avg_propagation_ms",
+                    analyzed.then_some(report.average_propagation_ms),
+                );
+                metric("propagation_coverage", analyzed.then(|| mean(&coverage)));
+                let confirmed = report
+                    .per_tx_analysis
+                    .iter()
+                    .any(|a| a.confirmation_delay_sec.is_some());
+                metric(
+                    "avg_confirmation_delay_sec",
+                    confirmed.then_some(report.average_confirmation_delay_sec),
+                );
+            }
+            SweepAnalysis::Spy => {
+                let report = analyze_spy_vulnerability(transactions, log_data, agents, None);
+                metric(
+                    "spy_accuracy",
+                    (!report.per_tx_analysis.is_empty()).then_some(report.inference_accuracy),
+                );
+                metric(
+                    &spy_visibility_metric(spy_visibility),
+                    coalition_accuracy(transactions, log_data, agents, spy_visibility),
+                );
+            }
+            SweepAnalysis::Bandwidth => {
+                let report = analyze_bandwidth(log_data, 0);
+                let measured = !report.per_node_stats.is_empty();
+                metric("total_bytes", measured.then_some(report.total_bytes as f64));
+                metric(
+                    "avg_bytes_per_node",
+                    measured.then_some(report.avg_bytes_per_node),
+                );
+                metric(
+                    "total_messages",
+                    measured.then_some(report.total_messages as f64),
+                );
+            }
+            SweepAnalysis::Health => {
+                let report = analyze_tx_relay_v2(transactions, log_data, agents);
+                metric("health_score", Some(report.assessment.health_score as f64));
+            }
+        }
+    }
+    metrics
+}
+
+/// Mean spy accuracy of `SPY_TRIALS` random coalitions of `visibility` of
+/// the daemons (at least one), seeded so reruns agree
+fn coalition_accuracy(
+    transactions: &[Transaction],
+    log_data: &HashMap<String, NodeLogData>,
+    agents: &[AnalysisAgentInfo],
+    visibility: f64,
+) -> Option<f64> {
+    let mut nodes: Vec<String> = log_data.keys().cloned().collect();
+    if nodes.is_empty() {
+        return None;
+    }
+    nodes.sort();
+    let size = ((nodes.len() as f64 * visibility).round() as usize).clamp(1, nodes.len());
+    let accuracies: Vec<f64> = (0..SPY_TRIALS)
+        .filter_map(|seed| {
+            let mut coalition = nodes.clone();
+            coalition.shuffle(&mut StdRng::seed_from_u64(seed));
+            coalition.truncate(size);
+            let report =
+                analyze_spy_vulnerability(transactions, log_data, agents, Some(&coalition));
+            (!report.per_tx_analysis.is_empty()).then_some(report.inference_accuracy)
+        })
+        .collect();
+    (!accuracies.is_empty()).then(|| mean(&accuracies))
+}
+
+/// Put the runs' rows together; parameters in first-seen order
+pub fn summarize_sweep(
+    runs_root: &Path,
+    analyses: &[SweepAnalysis],
+    spy_visibility: f64,
+    runs: Vec<SweepRunResult>,
+) -> SweepSummary {
+    let mut parameters: Vec<String> = Vec::new();
+    for run in &runs {
+        for name in run.parameters.keys() {
+            if !parameters.contains(name) {
+                parameters.push(name.clone());
+            }
+        }
+    }
+    let failed = runs
+        .iter()
+        .filter(|r| r.status == SweepRunStatus::Failed)
+        .count();
+    SweepSummary {
+        report_schema_version: REPORT_SCHEMA_VERSION,
+        runs_root: runs_root.display().to_string(),
+        analyses: analyses.iter().map(|a| a.to_string()).collect(),
+        parameters,
+        metrics: analyses
+            .iter()
+            .flat_map(|a| a.metric_names(spy_visibility))
+            .collect(),
+        succeeded: runs.len() - failed,
+        failed,
+        runs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variant_names_give_parameters() {
+        let params = parse_run_name("out_peers=8,topology=Mesh,mining=true");
+        assert_eq!(params["out_peers"], serde_json::json!(8));
+        assert_eq!(params["topology"], serde_json::json!("Mesh"));
+        assert_eq!(params["mining"], serde_json::json!(true));
+        assert!(parse_run_name("20240101_120000_baseline").is_empty());
+        assert!(parse_run_name("out_peers=8,extra").is_empty());
+    }
+
+    #[test]
+    fn spy_visibility_names_its_column() {
+        assert_eq!(
+            SweepAnalysis::Spy.metric_names(0.2),
+            ["spy_accuracy", "spy_accuracy_at_20pct"]
+        );
+        assert_eq!("health".parse(), Ok(SweepAnalysis::Health));
+        assert!("dandelion".parse::<SweepAnalysis>().is_err());
+    }
+}
@@ -0,0 +1,432 @@
+//! Synthetic datasets for trying the analysis tooling without Shadow.
+//!
+//! [`generate`] fabricates a self-consistent run (random P2P graph, Dandelion
+//! stems followed by a latency-weighted flood, periodic blocks) on top of
+//! [`SyntheticRun`], and [`write_dataset`] lays it out the way a real run
+//! does: registries and transactions in `shared/`, one `bitmonero.log` per
+//! agent in `daemon_logs/`. Logs are rendered with `log_format`, the same
+//! definitions the parser is built from. Everything is derived from the
+//! seed, so the same options always produce the same files.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use color_eyre::eyre::{Context, Result};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use super::log_format::render_log;
+use super::testutil::SyntheticRun;
+use super::types::SimTime;
+use crate::shadow::{AgentInfo, AgentRegistry, SimulationMetadata};
+
+/// First transaction, in simulation seconds (leaves room for connections)
+const START_SECS: f64 = 600.0;
+const TX_INTERVAL_SECS: f64 = 2.0;
+const BLOCK_INTERVAL_SECS: f64 = 120.0;
+/// Stems are 0..=MAX_STEM_HOPS relays long
+const MAX_STEM_HOPS: usize = 3;
+
+/// Per-hop relay delay, in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DelayDistribution {
+    Fixed(f64),
+    Uniform(f64, f64),
+    Exponential(f64),
+}
+
+impl DelayDistribution {
+    fn sample_secs(&self, rng: &mut StdRng) -> f64 {
+        let ms = match *self {
+            DelayDistribution::Fixed(ms) => ms,
+            DelayDistribution::Uniform(min, max) => rng.gen_range(min..=max),
+            DelayDistribution::Exponential(mean) => -mean * (1.0 - rng.gen::<f64>()).ln(),
+        };
+        ms / 1000.0
+    }
+}
+
+/// `fixed:MS`, `uniform:MIN:MAX` or `exp:MEAN`
+impl FromStr for DelayDistribution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let kind = parts.next().unwrap_or_default();
+        let numbers: Vec<f64> = parts
+            .map(|p| {
+                p.parse::<f64>()
+                    .ok()
+                    .filter(|v| v.is_finite() && *v >= 0.0)
+                    .ok_or_else(|| format!("'{}' is not a non-negative number of ms", p))
+            })
+            .collect::<Result<_, _>>()?;
+        match (kind, numbers.as_slice()) {
+            ("fixed", [ms]) => Ok(DelayDistribution::Fixed(*ms)),
+            ("uniform", [min, max]) if min <= max => Ok(DelayDistribution::Uniform(*min, *max)),
+            ("exp", [mean]) => Ok(DelayDistribution::Exponential(*mean)),
+            _ => Err(format!(
+                "invalid delay distribution '{}' (expected fixed:MS, uniform:MIN:MAX or exp:MEAN)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for DelayDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DelayDistribution::Fixed(ms) => write!(f, "fixed:{}", ms),
+            DelayDistribution::Uniform(min, max) => write!(f, "uniform:{}:{}", min, max),
+            DelayDistribution::Exponential(mean) => write!(f, "exp:{}", mean),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SynthOptions {
+    /// Total agents, miners included
+    pub agents: usize,
+    pub txs: usize,
+    pub seed: u64,
+    /// Defaults to a tenth of the agents (at least one)
+    pub miners: Option<usize>,
+    /// Outbound connections each agent opens
+    pub out_peers: usize,
+    pub hop_delay: DelayDistribution,
+    /// Skip the Dandelion stem so every transaction is broadcast by its
+    /// sender: first-seen timing then points straight at the origin
+    pub spy_friendly: bool,
+}
+
+impl Default for SynthOptions {
+    fn default() -> Self {
+        Self {
+            agents: 20,
+            txs: 500,
+            seed: 0,
+            miners: None,
+            out_peers: 4,
+            hop_delay: DelayDistribution::Uniform(50.0, 250.0),
+            spy_friendly: false,
+        }
+    }
+}
+
+fn hash64(rng: &mut StdRng) -> String {
+    (0..4)
+        .map(|_| format!("{:016x}", rng.gen::<u64>()))
+        .collect()
+}
+
+/// Absolute time of `secs` into the simulation, at log (ms) resolution
+fn at(secs: f64) -> SimTime {
+    ((crate::SHADOW_EPOCH + secs) * 1000.0).round() / 1000.0
+}
+
+/// Earliest arrival at every node reachable from `origin`, with the
+/// neighbour it arrived from. Each edge traversal draws a fresh delay.
+fn flood(
+    peers: &[BTreeSet<usize>],
+    origin: usize,
+    start: SimTime,
+    delay: DelayDistribution,
+    rng: &mut StdRng,
+) -> Vec<(usize, usize, SimTime)> {
+    let to_ms = |t: SimTime| (t * 1000.0).round() as i64;
+    let mut reached = vec![false; peers.len()];
+    reached[origin] = true;
+    let mut heap = BinaryHeap::new();
+    for &n in &peers[origin] {
+        heap.push(Reverse((to_ms(start + delay.sample_secs(rng)), n, origin)));
+    }
+    let mut arrivals = Vec::new();
+    while let Some(Reverse((t_ms, node, from))) = heap.pop() {
+        if reached[node] {
+            continue;
+        }
+        reached[node] = true;
+        let t = t_ms as f64 / 1000.0;
+        arrivals.push((node, from, t));
+        for &n in &peers[node] {
+            if !reached[n] {
+                heap.push(Reverse((to_ms(t + delay.sample_secs(rng)), n, node)));
+            }
+        }
+    }
+    arrivals
+}
+
+/// Fabricate a run from `options`; see the module docs for its shape.
+pub fn generate(options: &SynthOptions) -> SyntheticRun {
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let total = options.agents.max(2);
+    let miners = options.miners.unwrap_or(total / 10).clamp(1, total - 1);
+
+    let mut run = SyntheticRun::new();
+    let mut ids = Vec::with_capacity(total);
+    for i in 0..total {
+        let (id, script) = if i < miners {
+            (format!("miner-{:03}", i + 1), "agents.autonomous_miner")
+        } else {
+            (format!("user-{:03}", i - miners + 1), "agents.regular_user")
+        };
+        run.agent(&id, script);
+        ids.push(id);
+    }
+
+    // Random graph: a spanning tree keeps it connected, then each agent
+    // tops up to `out_peers` outbound connections.
+    let mut peers: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); total];
+    let open = |run: &mut SyntheticRun, peers: &mut [BTreeSet<usize>], a: usize, b: usize| {
+        peers[a].insert(b);
+        peers[b].insert(a);
+        let t = at(START_SECS / 2.0 + a as f64);
+        run.connect(&ids[a], &ids[b], t);
+        run.traffic(&ids[a], &ids[b], t, 250, "command-1001");
+        run.traffic(&ids[b], &ids[a], t, 250, "command-1001");
+    };
+    for a in 1..total {
+        let b = rng.gen_range(0..a);
+        open(&mut run, &mut peers, a, b);
+    }
+    for a in 0..total {
+        let mut candidates: Vec<usize> = (0..total)
+            .filter(|&b| b != a && !peers[a].contains(&b))
+            .collect();
+        candidates.shuffle(&mut rng);
+        let missing = options.out_peers.saturating_sub(peers[a].len());
+        for b in candidates.into_iter().take(missing) {
+            open(&mut run, &mut peers, a, b);
+        }
+    }
+
+    let users: Vec<usize> = (miners..total).collect();
+    let mut pending: Vec<(String, SimTime)> = Vec::new();
+    for k in 0..options.txs {
+        let hash = hash64(&mut rng);
+        let sender = users[rng.gen_range(0..users.len())];
+        let recipient = *users
+            .iter()
+            .filter(|&&u| u != sender)
+            .collect::<Vec<_>>()
+            .choose(&mut rng)
+            .copied()
+            .unwrap_or(&sender);
+        let t0 = at(START_SECS + k as f64 * TX_INTERVAL_SECS + rng.gen::<f64>());
+        run.transaction(&hash, &ids[sender], &ids[recipient], t0);
+        if let Some(tx) = run.transactions.last_mut() {
+            tx.amount = (rng.gen_range(0.1..5.0) * 1000.0f64).round() / 1000.0;
+        }
+
+        // Stem: a random walk that avoids revisiting nodes
+        let hops = if options.spy_friendly {
+            0
+        } else {
+            rng.gen_range(0..=MAX_STEM_HOPS)
+        };
+        let mut path = vec![sender];
+        let mut t = t0;
+        for _ in 0..hops {
+            let current = *path.last().expect("path holds the sender");
+            let next: Vec<usize> = peers[current]
+                .iter()
+                .copied()
+                .filter(|n| !path.contains(n))
+                .collect();
+            let Some(&next) = next.choose(&mut rng) else {
+                break;
+            };
+            t = at(t - crate::SHADOW_EPOCH + options.hop_delay.sample_secs(&mut rng));
+            run.observe(&hash, &ids[next], &ids[current], t);
+            run.traffic(&ids[current], &ids[next], t, 1_500, "command-2008");
+            path.push(next);
+        }
+
+        // Fluff from the end of the stem; stem nodes already have it
+        let fluffer = *path.last().expect("path holds the sender");
+        for (node, from, t) in flood(&peers, fluffer, t, options.hop_delay, &mut rng) {
+            if !path.contains(&node) {
+                run.observe(&hash, &ids[node], &ids[from], t);
+                run.traffic(&ids[from], &ids[node], t, 1_500, "command-2008");
+            }
+        }
+        pending.push((hash, t0));
+    }
+
+    // A block every BLOCK_INTERVAL_SECS takes whatever is in the pool
+    let mut height = 1;
+    let mut block_secs = START_SECS + BLOCK_INTERVAL_SECS;
+    while !pending.is_empty() {
+        let block_at = at(block_secs);
+        let (included, rest): (Vec<_>, Vec<_>) =
+            pending.into_iter().partition(|(_, t)| *t < block_at);
+        pending = rest;
+        let txs: Vec<&str> = included.iter().map(|(h, _)| h.as_str()).collect();
+        let miner = rng.gen_range(0..miners);
+        let block_hash = hash64(&mut rng);
+        run.mined(height, &txs);
+        run.block_seen(&block_hash, height, &ids[miner], None, block_at);
+        for (node, from, t) in flood(&peers, miner, block_at, options.hop_delay, &mut rng) {
+            run.block_seen(&block_hash, height, &ids[node], Some(&ids[from]), t);
+            run.traffic(&ids[from], &ids[node], t, 4_000, "command-2004");
+        }
+        height += 1;
+        block_secs += BLOCK_INTERVAL_SECS;
+    }
+    run
+}
+
+/// Write `run` in the layout of a real run:
+/// `<out>/shared/{agent_registry,transactions,blocks_with_transactions,simulation_metadata}.json`
+/// and `<out>/daemon_logs/monero-<id>/bitmonero.log`.
+pub fn write_dataset(run: &SyntheticRun, seed: u64, out: &Path) -> Result<()> {
+    let shared = out.join("shared");
+    let logs = out.join("daemon_logs");
+    std::fs::create_dir_all(&shared)
+        .with_context(|| format!("Failed to create {}", shared.display()))?;
+
+    let registry = AgentRegistry {
+        generator_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: crate::ARTIFACT_SCHEMA_VERSION,
+        chain: crate::config::Chain::Regtest,
+        agents: run
+            .agents
+            .iter()
+            .map(|a| AgentInfo {
+                id: a.id.clone(),
+                ip_addr: a.ip_addr.clone(),
+                network_node_id: a.network_node_id,
+                continent: a.continent.clone(),
+                as_number: a.as_number,
+                pinned: None,
+                daemon: true,
+                wallet: true,
+                user_script: Some(a.script_type.clone()),
+                attributes: BTreeMap::new(),
+                wallet_address: None,
+                wallet_rpc_port: Some(crate::MONERO_WALLET_RPC_PORT),
+                daemon_rpc_port: Some(a.rpc_port),
+                p2p_port: Some(crate::MONERO_P2P_PORT),
+                reachable: a.reachable,
+                out_peers: a.out_peers.map(|n| n as u32),
+                in_peers: None,
+                is_public_node: None,
+                is_seed: None,
+                remote_daemon: None,
+                daemon_selection_strategy: None,
+                wallet_args: None,
+                daemon_binary: None,
+                wallet_binary: None,
+                role: a.role.clone(),
+                schedule: None,
+            })
+            .collect(),
+    };
+    let metadata = SimulationMetadata {
+        generator_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: crate::ARTIFACT_SCHEMA_VERSION,
+        simulation_seed: seed,
+        binaries: BTreeMap::new(),
+    };
+    let files = [
+        (
+            "agent_registry.json",
+            serde_json::to_string_pretty(&registry)?,
+        ),
+        (
+            "transactions.json",
+            serde_json::to_string_pretty(&run.transactions)?,
+        ),
+        (
+            "blocks_with_transactions.json",
+            serde_json::to_string_pretty(&run.blocks)?,
+        ),
+        (
+            "simulation_metadata.json",
+            serde_json::to_string_pretty(&metadata)?,
+        ),
+    ];
+    for (name, content) in files {
+        let path = shared.join(name);
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    for (id, data) in &run.log_data {
+        let dir = logs.join(format!("monero-{}", id));
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        std::fs::write(dir.join("bitmonero.log"), render_log(data))
+            .with_context(|| format!("Failed to write log for {}", id))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{analyze_spy_vulnerability, parse_all_logs};
+
+    fn small(seed: u64, spy_friendly: bool) -> SynthOptions {
+        SynthOptions {
+            agents: 12,
+            txs: 40,
+            seed,
+            spy_friendly,
+            ..SynthOptions::default()
+        }
+    }
+
+    #[test]
+    fn written_dataset_parses_and_covers_every_transaction() {
+        let run = generate(&small(7, false));
+        let tmp = tempfile::TempDir::new().unwrap();
+        write_dataset(&run, 7, tmp.path()).unwrap();
+
+        let logs = parse_all_logs(&tmp.path().join("daemon_logs"), &run.agents).unwrap();
+        assert_eq!(logs.len(), 12);
+        for tx in &run.transactions {
+            let seen_by = logs
+                .values()
+                .filter(|d| d.tx_observations.iter().any(|o| o.tx_hash == tx.tx_hash))
+                .count();
+            // Everyone but the sender logs receiving it
+            assert_eq!(seen_by, 11, "{}", tx.tx_hash);
+        }
+        let mined: usize = run.blocks.iter().map(|b| b.tx_count).sum();
+        assert_eq!(mined, 40);
+
+        let again = serde_json::to_string(&generate(&small(7, false))).unwrap();
+        assert_eq!(serde_json::to_string(&run).unwrap(), again);
+    }
+
+    #[test]
+    fn spy_friendly_runs_give_the_origin_away() {
+        let accuracy = |spy_friendly| {
+            let run = generate(&small(3, spy_friendly));
+            analyze_spy_vulnerability(&run.transactions, &run.log_map(), &run.agents, None)
+                .inference_accuracy
+        };
+        let (normal, friendly) = (accuracy(false), accuracy(true));
+        assert!(friendly > 0.9, "spy-friendly accuracy {}", friendly);
+        assert!(normal < friendly, "{} vs {}", normal, friendly);
+    }
+
+    #[test]
+    fn delay_distributions_parse() {
+        assert_eq!("fixed:100".parse(), Ok(DelayDistribution::Fixed(100.0)));
+        assert_eq!(
+            "uniform:50:250".parse(),
+            Ok(DelayDistribution::Uniform(50.0, 250.0))
+        );
+        assert_eq!("exp:80".parse(), Ok(DelayDistribution::Exponential(80.0)));
+        for bad in ["uniform:300:100", "exp", "gauss:1", "fixed:-1"] {
+            assert!(bad.parse::<DelayDistribution>().is_err(), "{}", bad);
+        }
+    }
+}
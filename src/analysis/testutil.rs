@@ -0,0 +1,375 @@
+//! Synthetic observation builders for analysis tests.
+//!
+//! Hand-assembling `NodeLogData` means keeping IPs, node ids and timestamps
+//! consistent across a dozen structs per transaction. [`SyntheticRun`] does
+//! that bookkeeping: agents get sequential IPs, and relays are described in
+//! terms of agent ids ("b heard it from a"). Everything is deterministic, so
+//! a built run can be serialized and committed as a fixture.
+//!
+//! Public (not `cfg(test)`) so integration tests under `tests/` can use it.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use super::types::{
+    AnalysisAgentInfo, BandwidthEvent, BlockInfo, BlockObservation, ConnectionDirection,
+    ConnectionEvent, NodeLogData, SimTime, Transaction, TxObservation,
+};
+
+/// P2P port stamped on every synthetic observation
+const P2P_PORT: u16 = crate::MONERO_P2P_PORT;
+
+/// A complete synthetic analyzer input: registry, transactions.json,
+/// blocks_with_transactions.json and per-node parsed logs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyntheticRun {
+    pub agents: Vec<AnalysisAgentInfo>,
+    pub transactions: Vec<Transaction>,
+    pub blocks: Vec<BlockInfo>,
+    /// Ordered so serialized fixtures are byte-stable
+    pub log_data: BTreeMap<String, NodeLogData>,
+}
+
+impl SyntheticRun {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an agent; the n-th agent gets IP `10.0.0.n` (rolling over
+    /// into `10.0.1.x` after 250).
+    pub fn agent(&mut self, id: &str, script_type: &str) -> &mut Self {
+        let n = self.agents.len();
+        self.agents.push(AnalysisAgentInfo {
+            id: id.to_string(),
+            ip_addr: format!("10.0.{}.{}", n / 250, n % 250 + 1),
+            rpc_port: crate::MONERO_RPC_PORT,
+            script_type: script_type.to_string(),
+            wallet_address: None,
+            network_node_id: None,
+            continent: None,
+            as_number: None,
+            role: None,
+            reachable: None,
+            out_peers: None,
+        });
+        self.log_data
+            .insert(id.to_string(), NodeLogData::new(id.to_string()));
+        self
+    }
+
+    /// Place an already registered agent on a GML node
+    pub fn place(&mut self, id: &str, network_node_id: u32) -> &mut Self {
+        self.agent_mut(id).network_node_id = Some(network_node_id);
+        self
+    }
+
+    /// Give an already registered agent a registry role (e.g. `spy`)
+    pub fn role(&mut self, id: &str, role: &str) -> &mut Self {
+        self.agent_mut(id).role = Some(role.to_string());
+        self
+    }
+
+    /// Mark an already registered agent unreachable, as the registry does
+    /// for hosts with blocked inbound P2P
+    pub fn unreachable(&mut self, id: &str) -> &mut Self {
+        self.agent_mut(id).reachable = Some(false);
+        self
+    }
+
+    /// Record the outbound limit an agent's daemon ran with
+    pub fn out_peers(&mut self, id: &str, limit: usize) -> &mut Self {
+        self.agent_mut(id).out_peers = Some(limit);
+        self
+    }
+
+    pub fn ip(&self, id: &str) -> String {
+        self.agents
+            .iter()
+            .find(|a| a.id == id)
+            .unwrap_or_else(|| panic!("unknown synthetic agent {}", id))
+            .ip_addr
+            .clone()
+    }
+
+    fn index(&self, id: &str) -> usize {
+        self.agents
+            .iter()
+            .position(|a| a.id == id)
+            .unwrap_or_else(|| panic!("unknown synthetic agent {}", id))
+    }
+
+    fn agent_mut(&mut self, id: &str) -> &mut AnalysisAgentInfo {
+        self.agents
+            .iter_mut()
+            .find(|a| a.id == id)
+            .unwrap_or_else(|| panic!("unknown synthetic agent {}", id))
+    }
+
+    fn node(&mut self, id: &str) -> &mut NodeLogData {
+        self.log_data
+            .get_mut(id)
+            .unwrap_or_else(|| panic!("unknown synthetic agent {}", id))
+    }
+
+    /// Record a transaction as the sender's wallet created it
+    pub fn transaction(
+        &mut self,
+        hash: &str,
+        sender: &str,
+        recipient: &str,
+        at: SimTime,
+    ) -> &mut Self {
+        self.transactions.push(Transaction {
+            tx_hash: hash.to_string(),
+            sender_id: sender.to_string(),
+            recipient_id: recipient.to_string(),
+            amount: 1.0,
+            timestamp: at,
+        });
+        self
+    }
+
+    /// `node` logs receiving `hash` from `from` at `at`
+    pub fn observe(&mut self, hash: &str, node: &str, from: &str, at: SimTime) -> &mut Self {
+        let source_ip = self.ip(from);
+        self.node(node).tx_observations.push(TxObservation {
+            tx_hash: hash.to_string(),
+            node_id: node.to_string(),
+            timestamp: at,
+            source_ip,
+            source_port: P2P_PORT,
+            direction: ConnectionDirection::Inbound,
+        });
+        self
+    }
+
+    /// Dandelion++ stem: `path[0]` originates, each later node hears it from
+    /// its predecessor `hop_secs` after the previous hop. Returns the time
+    /// of the last hop.
+    pub fn stem(&mut self, hash: &str, path: &[&str], start: SimTime, hop_secs: f64) -> SimTime {
+        let mut at = start;
+        for pair in path.windows(2) {
+            at += hop_secs;
+            self.observe(hash, pair[1], pair[0], at);
+        }
+        at
+    }
+
+    /// Fluff broadcast: every node in `to` hears `hash` from `from`,
+    /// `spread_secs` apart starting at `at`.
+    pub fn fluff(
+        &mut self,
+        hash: &str,
+        from: &str,
+        to: &[&str],
+        at: SimTime,
+        spread_secs: f64,
+    ) -> &mut Self {
+        for (k, node) in to.iter().enumerate() {
+            self.observe(hash, node, from, at + k as f64 * spread_secs);
+        }
+        self
+    }
+
+    /// Open a connection from `a` (outbound) to `b` (inbound) at `at`
+    pub fn connect(&mut self, a: &str, b: &str, at: SimTime) -> &mut Self {
+        let (a_ip, b_ip) = (self.ip(a), self.ip(b));
+        // Hex, like monerod's connection UUIDs, so rendered logs parse
+        let connection_id = format!("{:08x}-{:04x}", self.index(a), self.index(b));
+        self.node(a).connection_events.push(ConnectionEvent {
+            timestamp: at,
+            peer_ip: b_ip,
+            peer_port: P2P_PORT,
+            connection_id: connection_id.clone(),
+            direction: ConnectionDirection::Outbound,
+            is_open: true,
+        });
+        self.node(b).connection_events.push(ConnectionEvent {
+            timestamp: at,
+            peer_ip: a_ip,
+            peer_port: P2P_PORT,
+            connection_id,
+            direction: ConnectionDirection::Inbound,
+            is_open: true,
+        });
+        self
+    }
+
+    /// Close the connection `connect(a, b, _)` opened, logged on both ends
+    pub fn disconnect(&mut self, a: &str, b: &str, at: SimTime) -> &mut Self {
+        let (a_ip, b_ip) = (self.ip(a), self.ip(b));
+        let connection_id = format!("{:08x}-{:04x}", self.index(a), self.index(b));
+        for (node, peer_ip, direction) in [
+            (a, b_ip, ConnectionDirection::Outbound),
+            (b, a_ip, ConnectionDirection::Inbound),
+        ] {
+            self.node(node).connection_events.push(ConnectionEvent {
+                timestamp: at,
+                peer_ip,
+                peer_port: P2P_PORT,
+                connection_id: connection_id.clone(),
+                direction,
+                is_open: false,
+            });
+        }
+        self
+    }
+
+    /// `bytes` of `category` traffic from `from` to `to`, logged on both ends
+    pub fn traffic(
+        &mut self,
+        from: &str,
+        to: &str,
+        at: SimTime,
+        bytes: u64,
+        category: &str,
+    ) -> &mut Self {
+        let (from_ip, to_ip) = (self.ip(from), self.ip(to));
+        for (node, peer_ip, is_sent, direction) in [
+            (from, to_ip, true, ConnectionDirection::Outbound),
+            (to, from_ip, false, ConnectionDirection::Inbound),
+        ] {
+            self.node(node).bandwidth_events.push(BandwidthEvent {
+                timestamp: at,
+                peer_ip,
+                peer_port: P2P_PORT,
+                direction,
+                bytes,
+                is_sent,
+                command_category: category.to_string(),
+                initiated_by_us: is_sent,
+                messages: 1,
+            });
+        }
+        self
+    }
+
+    /// `miner` finds block `height` with `txs` at `at`; every other agent
+    /// logs it `relay_secs` later.
+    pub fn block(
+        &mut self,
+        height: u64,
+        miner: &str,
+        txs: &[&str],
+        at: SimTime,
+        relay_secs: f64,
+    ) -> &mut Self {
+        let block_hash = format!("block{:04}", height);
+        self.mined(height, txs);
+        let ids: Vec<String> = self.agents.iter().map(|a| a.id.clone()).collect();
+        for id in ids {
+            if id == miner {
+                self.block_seen(&block_hash, height, &id, None, at);
+            } else {
+                self.block_seen(&block_hash, height, &id, Some(miner), at + relay_secs);
+            }
+        }
+        self
+    }
+
+    /// Record block `height` with `txs` in blocks_with_transactions.json
+    pub fn mined(&mut self, height: u64, txs: &[&str]) -> &mut Self {
+        self.blocks.push(BlockInfo {
+            height,
+            transactions: txs.iter().map(|t| t.to_string()).collect(),
+            tx_count: txs.len(),
+        });
+        self
+    }
+
+    /// `node` logs block `height`: mined locally when `from` is None,
+    /// otherwise received from `from`
+    pub fn block_seen(
+        &mut self,
+        block_hash: &str,
+        height: u64,
+        node: &str,
+        from: Option<&str>,
+        at: SimTime,
+    ) -> &mut Self {
+        let source_ip = from.map(|f| self.ip(f));
+        self.node(node).block_observations.push(BlockObservation {
+            block_hash: block_hash.to_string(),
+            height,
+            node_id: node.to_string(),
+            timestamp: at,
+            is_local: source_ip.is_none(),
+            source_ip,
+        });
+        self
+    }
+
+    /// Logs in the shape the analyzers take
+    pub fn log_map(&self) -> HashMap<String, NodeLogData> {
+        self.log_data
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+}
+
+/// The dataset behind `tests/analysis_regression.rs` (committed as
+/// `tests/fixtures/analysis_run.json`): two miners, eight users, a dozen
+/// transactions with stems of 0-3 relays, four blocks, a ring-plus-chords
+/// connection graph and per-link traffic. Changing it changes the baseline.
+pub fn regression_run() -> SyntheticRun {
+    let miners = ["miner-001", "miner-002"];
+    let users: Vec<String> = (1..=8).map(|n| format!("user-{:03}", n)).collect();
+    let mut run = SyntheticRun::new();
+    for m in miners {
+        run.agent(m, "agents.autonomous_miner");
+    }
+    for u in &users {
+        run.agent(u, "agents.regular_user");
+    }
+    let all: Vec<String> = run.agents.iter().map(|a| a.id.clone()).collect();
+
+    // Ring plus +3 chords, with traffic on every link
+    for i in 0..all.len() {
+        for step in [1, 3] {
+            let (a, b) = (&all[i], &all[(i + step) % all.len()]);
+            run.connect(a, b, 900.0 + i as f64);
+            run.traffic(a, b, 950.0, 1_500 + 37 * i as u64, "command-2008");
+            run.traffic(b, a, 951.0, 220 + 11 * step as u64, "command-1003");
+        }
+    }
+
+    let mut hashes = Vec::new();
+    for k in 0..12 {
+        let hash = format!("tx{:02}", k);
+        let sender = &users[k % users.len()];
+        let t0 = 1_000.0 + 60.0 * k as f64;
+        run.transaction(&hash, sender, &users[(k + 4) % users.len()], t0);
+
+        let mut path: Vec<&str> = vec![sender];
+        path.extend((0..k % 4).map(|j| users[(k + 1 + j) % users.len()].as_str()));
+        let last = run.stem(&hash, &path, t0, 0.15 + 0.01 * k as f64);
+
+        // The last stem node fluffs to four peers, the rest hear it by gossip
+        let rest: Vec<&str> = all
+            .iter()
+            .map(String::as_str)
+            .filter(|id| !path.contains(id))
+            .collect();
+        let (fluffed, gossiped) = rest.split_at(4);
+        let fluffer = *path.last().expect("path holds the sender");
+        run.fluff(&hash, fluffer, fluffed, last + 0.05, 0.1);
+        for (g, node) in gossiped.iter().enumerate() {
+            run.observe(
+                &hash,
+                node,
+                fluffed[g % fluffed.len()],
+                last + 1.5 + 0.2 * g as f64,
+            );
+        }
+        hashes.push(hash);
+    }
+
+    for (b, chunk) in hashes.chunks(3).enumerate() {
+        let txs: Vec<&str> = chunk.iter().map(String::as_str).collect();
+        let at = 1_000.0 + 60.0 * (3 * b + 2) as f64 + 120.0;
+        run.block(b as u64 + 1, miners[b % miners.len()], &txs, at, 0.4);
+    }
+    run
+}
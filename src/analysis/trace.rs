@@ -0,0 +1,364 @@
+//! Single-transaction trace.
+//!
+//! Gathers everything the logs say about one transaction into a timeline:
+//! its creation, every daemon's sightings (source IPs resolved to agents),
+//! the v2 announcements and requests leading up to each first sighting, the
+//! reconstructed Dandelion++ stem and the block that included it.
+//!
+//! HEURISTIC — the daemons log v2 announcements and requests with a count
+//! but no hashes, so those events are linked to the transaction by peer and
+//! time only: an announcement from (or request to) the peer a daemon later
+//! received the transaction from, between creation and that first sighting.
+//! Busy peers can contribute unrelated events.
+
+use std::collections::{HashMap, HashSet};
+
+use super::dandelion::reconstruct_path;
+use super::schema::REPORT_SCHEMA_VERSION;
+use super::types::*;
+
+/// Near-misses listed when a hash is not found
+const MAX_NEAR_MISSES: usize = 5;
+
+/// Outcome of looking up a (possibly abbreviated) transaction hash
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxHashMatch {
+    Found(String),
+    /// Every known hash starting with the prefix, sorted
+    Ambiguous(Vec<String>),
+    /// The closest known hashes, nearest first
+    NotFound {
+        near_misses: Vec<String>,
+    },
+}
+
+/// Resolve `prefix` (case-insensitive) against every hash in
+/// `transactions.json` and the logs. A full hash always matches itself.
+pub fn match_tx_hash(
+    prefix: &str,
+    transactions: &[Transaction],
+    log_data: &HashMap<String, NodeLogData>,
+) -> TxHashMatch {
+    let prefix = prefix.trim().to_ascii_lowercase();
+    let mut known: Vec<&str> = transactions
+        .iter()
+        .map(|tx| tx.tx_hash.as_str())
+        .chain(
+            log_data
+                .values()
+                .flat_map(|data| data.tx_observations.iter().map(|o| o.tx_hash.as_str())),
+        )
+        .collect();
+    known.sort_unstable();
+    known.dedup();
+
+    if let Some(exact) = known.iter().find(|h| h.eq_ignore_ascii_case(&prefix)) {
+        return TxHashMatch::Found(exact.to_string());
+    }
+    let matches: Vec<String> = known
+        .iter()
+        .filter(|h| h.to_ascii_lowercase().starts_with(&prefix))
+        .map(|h| h.to_string())
+        .collect();
+    match matches.len() {
+        0 => {}
+        1 => return TxHashMatch::Found(matches.into_iter().next().expect("one match")),
+        _ => return TxHashMatch::Ambiguous(matches),
+    }
+
+    // Nearest by mismatched characters over the prefix length, so a typo
+    // anywhere in the prefix still finds the intended hash
+    let distance = |hash: &str| {
+        let hash = hash.to_ascii_lowercase();
+        let mismatched = hash
+            .bytes()
+            .zip(prefix.bytes())
+            .filter(|(a, b)| a != b)
+            .count();
+        mismatched + prefix.len().saturating_sub(hash.len())
+    };
+    let mut ranked: Vec<(usize, &str)> = known.iter().map(|h| (distance(h), *h)).collect();
+    ranked.sort_unstable();
+    TxHashMatch::NotFound {
+        near_misses: ranked
+            .into_iter()
+            .take(MAX_NEAR_MISSES)
+            .map(|(_, h)| h.to_string())
+            .collect(),
+    }
+}
+
+/// Assemble the timeline of `tx_hash` (a full hash, see [`match_tx_hash`])
+pub fn trace_transaction(
+    tx_hash: &str,
+    transactions: &[Transaction],
+    blocks: &[BlockInfo],
+    log_data: &HashMap<String, NodeLogData>,
+    agents: &[AnalysisAgentInfo],
+) -> TxTrace {
+    let ip_to_node: HashMap<&str, &str> = agents
+        .iter()
+        .map(|a| (a.ip_addr.as_str(), a.id.as_str()))
+        .collect();
+    let node_to_ip: HashMap<&str, &str> = agents
+        .iter()
+        .map(|a| (a.id.as_str(), a.ip_addr.as_str()))
+        .collect();
+    let resolve = |ip: &str| ip_to_node.get(ip).copied().unwrap_or(ip).to_string();
+
+    let tx = transactions.iter().find(|tx| tx.tx_hash == tx_hash);
+    let mut observations: Vec<&TxObservation> = log_data
+        .values()
+        .flat_map(|data| data.tx_observations.iter())
+        .filter(|obs| obs.tx_hash == tx_hash)
+        .collect();
+    observations.sort_by(|a, b| {
+        a.timestamp
+            .total_cmp(&b.timestamp)
+            .then_with(|| a.node_id.cmp(&b.node_id))
+    });
+
+    let mut events: Vec<TraceEvent> = Vec::new();
+    let mut event = |timestamp, kind, node_id: &str, peer: Option<String>, detail| {
+        events.push(TraceEvent {
+            timestamp,
+            offset_ms: 0.0,
+            kind,
+            node_id: node_id.to_string(),
+            peer,
+            detail,
+        })
+    };
+
+    if let Some(tx) = tx {
+        event(
+            tx.timestamp,
+            TraceEventKind::Created,
+            &tx.sender_id,
+            Some(tx.recipient_id.clone()),
+            Some(format!("{} XMR to the recipient", tx.amount)),
+        );
+    }
+
+    // First sightings, and the peer each daemon got the transaction from
+    let mut first_seen: HashMap<&str, &TxObservation> = HashMap::new();
+    for &obs in &observations {
+        let first = !first_seen.contains_key(obs.node_id.as_str());
+        if first {
+            first_seen.insert(&obs.node_id, obs);
+        }
+        event(
+            obs.timestamp,
+            if first {
+                TraceEventKind::FirstSeen
+            } else {
+                TraceEventKind::SeenAgain
+            },
+            &obs.node_id,
+            Some(resolve(&obs.source_ip)),
+            Some(format!("{:?} connection", obs.direction)),
+        );
+    }
+
+    let window_start = tx
+        .map(|tx| tx.timestamp)
+        .or_else(|| observations.first().map(|o| o.timestamp));
+    if let Some(window_start) = window_start {
+        for (node_id, first) in &first_seen {
+            let Some(data) = log_data.get(*node_id) else {
+                continue;
+            };
+            let in_window = |t: SimTime| window_start <= t && t <= first.timestamp;
+            for ann in &data.tx_hash_announcements {
+                let linked = if ann.tx_hashes.is_empty() {
+                    ann.source_ip == first.source_ip && in_window(ann.timestamp)
+                } else {
+                    ann.tx_hashes.iter().any(|h| h == tx_hash)
+                };
+                if linked {
+                    event(
+                        ann.timestamp,
+                        TraceEventKind::HashAnnounced,
+                        node_id,
+                        Some(resolve(&ann.source_ip)),
+                        Some(format!("{} hash(es) announced", ann.tx_count)),
+                    );
+                }
+            }
+            for req in &data.tx_requests {
+                if req.is_outgoing && req.target_ip == first.source_ip && in_window(req.timestamp) {
+                    event(
+                        req.timestamp,
+                        TraceEventKind::TxRequested,
+                        node_id,
+                        Some(resolve(&req.target_ip)),
+                        Some(format!("{} tx(s) requested", req.tx_count)),
+                    );
+                }
+            }
+        }
+    }
+
+    // Block inclusion: the earliest sighting of the including height
+    let block_height = blocks
+        .iter()
+        .find(|b| b.transactions.iter().any(|h| h == tx_hash))
+        .map(|b| b.height);
+    let block_sighting = block_height.and_then(|height| {
+        log_data
+            .values()
+            .flat_map(|data| data.block_observations.iter())
+            .filter(|obs| obs.height == height)
+            .min_by(|a, b| {
+                a.timestamp
+                    .total_cmp(&b.timestamp)
+                    .then_with(|| a.node_id.cmp(&b.node_id))
+            })
+    });
+    if let Some(obs) = block_sighting {
+        event(
+            obs.timestamp,
+            TraceEventKind::BlockIncluded,
+            &obs.node_id,
+            obs.source_ip.as_deref().map(resolve),
+            Some(if obs.is_local {
+                format!("block {} mined locally", obs.height)
+            } else {
+                format!("block {}", obs.height)
+            }),
+        );
+    }
+
+    events.sort_by(|a, b| {
+        a.timestamp
+            .total_cmp(&b.timestamp)
+            .then_with(|| a.kind.cmp(&b.kind))
+            .then_with(|| a.node_id.cmp(&b.node_id))
+    });
+    if let Some(origin) = window_start {
+        for e in &mut events {
+            e.offset_ms = (e.timestamp - origin) * 1000.0;
+        }
+    }
+
+    let reached: HashSet<&str> = first_seen.keys().copied().collect();
+    TxTrace {
+        report_schema_version: REPORT_SCHEMA_VERSION,
+        tx_hash: tx_hash.to_string(),
+        sender: tx.map(|tx| tx.sender_id.clone()),
+        recipient: tx.map(|tx| tx.recipient_id.clone()),
+        created_at: tx.map(|tx| tx.timestamp),
+        daemon_count: log_data.len(),
+        nodes_reached: reached.len(),
+        stem_path: tx.and_then(|tx| {
+            reconstruct_path(
+                tx,
+                &observations,
+                &ip_to_node,
+                &node_to_ip,
+                FluffDetection::default(),
+            )
+        }),
+        block_height,
+        block_time: block_sighting.map(|obs| obs.timestamp),
+        events,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::testutil::SyntheticRun;
+
+    fn run() -> SyntheticRun {
+        let mut run = SyntheticRun::new();
+        for id in ["a", "b", "c"] {
+            run.agent(id, "agents.regular_user");
+        }
+        run.transaction("ab12", "a", "c", 0.0)
+            .transaction("ab34", "a", "c", 1.0)
+            .transaction("cd56", "b", "c", 2.0)
+            .observe("ab12", "b", "a", 0.5)
+            .observe("ab12", "c", "b", 0.8)
+            .observe("ab12", "c", "a", 1.5)
+            .block(1, "a", &["ab12"], 10.0, 0.5);
+        run
+    }
+
+    #[test]
+    fn prefixes_resolve_or_list_candidates() {
+        let run = run();
+        let log_map = run.log_map();
+        let lookup = |p: &str| match_tx_hash(p, &run.transactions, &log_map);
+
+        assert_eq!(lookup("AB1"), TxHashMatch::Found("ab12".into()));
+        assert_eq!(lookup("cd56"), TxHashMatch::Found("cd56".into()));
+        assert_eq!(
+            lookup("ab"),
+            TxHashMatch::Ambiguous(vec!["ab12".into(), "ab34".into()])
+        );
+        let TxHashMatch::NotFound { near_misses } = lookup("ab3x") else {
+            panic!("ab3x should not match");
+        };
+        assert_eq!(near_misses[0], "ab34");
+        assert_eq!(near_misses.len(), 3);
+    }
+
+    #[test]
+    fn timeline_is_chronological_from_creation_to_block() {
+        let mut run = run();
+        // c hears hashes from b (linked) and from a (c got ab12 from b first)
+        for (from, at) in [("b", 0.7), ("a", 0.6)] {
+            let source_ip = run.ip(from);
+            let announcement = TxHashAnnouncement {
+                timestamp: at,
+                node_id: "c".into(),
+                source_ip,
+                direction: ConnectionDirection::Inbound,
+                tx_count: 1,
+                tx_hashes: Vec::new(),
+            };
+            run.log_data
+                .get_mut("c")
+                .expect("c has logs")
+                .tx_hash_announcements
+                .push(announcement);
+        }
+        let trace = trace_transaction(
+            "ab12",
+            &run.transactions,
+            &run.blocks,
+            &run.log_map(),
+            &run.agents,
+        );
+        assert_eq!(trace.sender.as_deref(), Some("a"));
+        assert_eq!(trace.nodes_reached, 2);
+        assert_eq!(trace.block_height, Some(1));
+
+        let kinds: Vec<TraceEventKind> = trace.events.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                TraceEventKind::Created,
+                TraceEventKind::FirstSeen,
+                TraceEventKind::HashAnnounced,
+                TraceEventKind::FirstSeen,
+                TraceEventKind::SeenAgain,
+                TraceEventKind::BlockIncluded,
+            ]
+        );
+        // Source IPs come back as agent ids
+        assert_eq!(trace.events[2].peer.as_deref(), Some("b"));
+        assert_eq!(trace.events[3].node_id, "c");
+        assert_eq!(trace.events[3].peer.as_deref(), Some("b"));
+        assert_eq!(trace.events[3].offset_ms, 800.0);
+        assert!(trace
+            .events
+            .windows(2)
+            .all(|w| w[0].timestamp <= w[1].timestamp));
+
+        let stem = trace.stem_path.expect("stem reconstructed");
+        let hops: Vec<&str> = stem.stem_path.iter().map(|h| h.node_id.as_str()).collect();
+        assert_eq!(hops, ["b", "c"]);
+    }
+}
@@ -5,6 +5,7 @@
 
 use std::collections::{HashMap, HashSet};
 
+use super::schema::REPORT_SCHEMA_VERSION;
 use super::types::*;
 
 /// Analyze TX relay v2 protocol usage and compare with v1
@@ -21,6 +22,7 @@ pub fn analyze_tx_relay_v2(
         generate_assessment(&protocol_usage, &delivery_analysis, &connection_stability);
 
     TxRelayV2Report {
+        report_schema_version: REPORT_SCHEMA_VERSION,
         protocol_usage,
         delivery_analysis,
         connection_stability,
@@ -0,0 +1,141 @@
+//! Transaction success-rate analysis.
+//!
+//! Agents append one line per send attempt to `tx_attempts.jsonl` in the
+//! shared directory (`BaseAgent.record_tx_attempt` does this):
+//!
+//! ```json
+//! {"agent_id": "user-001", "timestamp": 946688400.0, "outcome": "failure",
+//!  "reason": "not enough unlocked money"}
+//! ```
+//!
+//! Runs from before the file existed are read from the agents' own log lines
+//! instead (`log_parser::parse_agent_tx_logs`). Failures are grouped by the
+//! wallet error behind them, and the time from an agent's first attempt to
+//! its first success shows how long its wallet sat without spendable funds.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use color_eyre::eyre::{eyre, Context, Result};
+
+use super::schema::REPORT_SCHEMA_VERSION;
+use super::stats::median;
+use super::types::*;
+
+/// Attempts file in the shared directory
+pub const TX_ATTEMPTS_FILE: &str = "tx_attempts.jsonl";
+
+/// Load the attempts at `path`; blank lines are skipped
+pub fn load_tx_attempts(path: &Path) -> Result<Vec<TxAttempt>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read transaction attempts: {}", path.display()))?;
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| serde_json::from_str(line).map_err(|e| eyre!("line {}: {}", i + 1, e)))
+        .collect::<Result<_>>()
+        .with_context(|| format!("Failed to parse transaction attempts: {}", path.display()))
+}
+
+/// Failure category for a wallet error message
+pub fn classify_failure(reason: Option<&str>) -> &'static str {
+    let Some(reason) = reason.map(str::to_lowercase) else {
+        return "unknown";
+    };
+    let has = |needles: &[&str]| needles.iter().any(|n| reason.contains(n));
+    if has(&["not enough unlocked money"]) {
+        "not_enough_unlocked_money"
+    } else if has(&["not enough money", "insufficient funds"]) {
+        "not_enough_money"
+    } else if has(&["busy", "no connection to daemon", "not synchronized"]) {
+        "daemon_busy"
+    } else if has(&["wallet not ready", "wallet is not ready", "no wallet"]) {
+        "wallet_not_ready"
+    } else if has(&["timed out", "timeout"]) {
+        "timeout"
+    } else if has(&[
+        "connection refused",
+        "failed to connect",
+        "connection error",
+    ]) {
+        "rpc_unreachable"
+    } else if has(&["missing tx_hash"]) {
+        "no_tx_hash"
+    } else {
+        "other"
+    }
+}
+
+/// Summarize the attempts per agent and overall
+pub fn analyze_tx_success(attempts: &[TxAttempt], source: &str) -> TxSuccessReport {
+    let mut by_agent: BTreeMap<&str, Vec<&TxAttempt>> = BTreeMap::new();
+    for attempt in attempts {
+        by_agent
+            .entry(attempt.agent_id.as_str())
+            .or_default()
+            .push(attempt);
+    }
+
+    let per_agent: Vec<AgentTxSuccess> = by_agent
+        .into_iter()
+        .map(|(agent_id, mut attempts)| {
+            attempts.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+            let succeeded = attempts
+                .iter()
+                .filter(|a| a.outcome == TxOutcome::Success)
+                .count();
+            let first_attempt = attempts[0].timestamp;
+            let first_success = attempts
+                .iter()
+                .find(|a| a.outcome == TxOutcome::Success)
+                .map(|a| a.timestamp);
+            let mut failure_reasons: BTreeMap<String, usize> = BTreeMap::new();
+            for attempt in attempts.iter().filter(|a| a.outcome == TxOutcome::Failure) {
+                *failure_reasons
+                    .entry(classify_failure(attempt.reason.as_deref()).to_string())
+                    .or_default() += 1;
+            }
+            AgentTxSuccess {
+                agent_id: agent_id.to_string(),
+                attempted: attempts.len(),
+                succeeded,
+                failed: attempts.len() - succeeded,
+                success_rate: succeeded as f64 / attempts.len() as f64,
+                first_attempt,
+                first_success,
+                time_to_first_success: first_success.map(|t| t - first_attempt),
+                failure_reasons,
+            }
+        })
+        .collect();
+
+    let attempted = attempts.len();
+    let succeeded: usize = per_agent.iter().map(|a| a.succeeded).sum();
+    let mut failure_reasons: BTreeMap<String, usize> = BTreeMap::new();
+    for agent in &per_agent {
+        for (reason, count) in &agent.failure_reasons {
+            *failure_reasons.entry(reason.clone()).or_default() += count;
+        }
+    }
+    let waits: Vec<f64> = per_agent
+        .iter()
+        .filter_map(|a| a.time_to_first_success)
+        .collect();
+
+    TxSuccessReport {
+        report_schema_version: REPORT_SCHEMA_VERSION,
+        source: source.to_string(),
+        attempted,
+        succeeded,
+        failed: attempted - succeeded,
+        success_rate: (attempted > 0).then(|| succeeded as f64 / attempted as f64),
+        failure_reasons,
+        median_time_to_first_success: (!waits.is_empty()).then(|| median(&waits)),
+        agents_without_success: per_agent
+            .iter()
+            .filter(|a| a.succeeded == 0)
+            .map(|a| a.agent_id.clone())
+            .collect(),
+        per_agent,
+    }
+}
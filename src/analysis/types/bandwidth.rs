@@ -6,7 +6,8 @@ use serde::{Deserialize, Serialize};
 
 use super::core::{ConnectionDirection, SimTime};
 
-/// Single bandwidth log entry
+/// Single bandwidth log entry, or several entries merged at parse time
+/// (see `LogReduction`)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BandwidthEvent {
     /// When the transfer occurred
@@ -17,7 +18,7 @@ pub struct BandwidthEvent {
     pub peer_port: u16,
     /// Connection direction (INC or OUT)
     pub direction: ConnectionDirection,
-    /// Bytes transferred
+    /// Bytes transferred, summed over `messages`
     pub bytes: u64,
     /// True if sent, false if received
     pub is_sent: bool,
@@ -25,6 +26,14 @@ pub struct BandwidthEvent {
     pub command_category: String,
     /// Whether we initiated the message
     pub initiated_by_us: bool,
+    /// Log entries this event stands for: 1 as logged, N for a kept 1-in-N
+    /// sample, the entry count for a pre-binned window
+    #[serde(default = "one_message")]
+    pub messages: u64,
+}
+
+fn one_message() -> u64 {
+    1
 }
 
 /// Bandwidth statistics per command category
@@ -55,6 +64,28 @@ pub struct PeerBandwidth {
     pub message_count: u64,
 }
 
+/// Traffic from one node to another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairBandwidth {
+    /// Agent id, or the raw IP when no agent has it
+    pub sender: String,
+    pub receiver: String,
+    pub bytes: u64,
+    pub message_count: u64,
+}
+
+/// Directed node-to-node traffic matrix
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BandwidthMatrix {
+    /// `REPORT_SCHEMA_VERSION` at analysis time
+    #[serde(default)]
+    pub report_schema_version: u32,
+    pub total_bytes: u64,
+    pub total_messages: u64,
+    /// Sorted by bytes, largest first
+    pub pairs: Vec<PairBandwidth>,
+}
+
 /// Per-node bandwidth summary
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeBandwidthStats {
@@ -94,6 +125,9 @@ pub struct BandwidthWindow {
 /// Network-wide bandwidth report
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BandwidthReport {
+    /// `REPORT_SCHEMA_VERSION` at analysis time
+    #[serde(default)]
+    pub report_schema_version: u32,
     /// Total bytes transferred across network
     pub total_bytes: u64,
     /// Total bytes sent
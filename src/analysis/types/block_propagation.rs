@@ -0,0 +1,106 @@
+//! Block propagation analysis result types.
+
+use serde::{Deserialize, Serialize};
+
+use super::core::SimTime;
+
+/// How one block height spread through the daemons
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockPropagation {
+    pub height: u64,
+    /// Hash most daemons received; `None` when only the miner saw it
+    /// (locally mined blocks are logged without a hash)
+    pub block_hash: Option<String>,
+    /// Earliest daemon that logged the block as mined locally
+    pub miner: Option<String>,
+    pub first_seen_node: String,
+    pub first_seen_time: SimTime,
+    pub nodes_reached: usize,
+    /// Delay after the first sighting until 50%/90%/100% of daemons had the
+    /// block; `None` when it never got that far
+    pub time_to_50_ms: Option<f64>,
+    pub time_to_90_ms: Option<f64>,
+    pub time_to_100_ms: Option<f64>,
+    /// `time_to_90_ms` as a share of the target block interval: the part of
+    /// the interval in which a competing block could still be found
+    #[serde(default)]
+    pub time_to_90_interval_share: Option<f64>,
+    /// From `blocks_with_transactions.json`, when the height is listed there
+    pub tx_count: Option<usize>,
+}
+
+/// A height at which daemons saw competing blocks (orphans or a short
+/// alternative chain)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlternativeBlockEvent {
+    pub height: u64,
+    pub block_hashes: Vec<String>,
+    /// Every daemon that mined a block at this height
+    pub miners: Vec<String>,
+}
+
+/// One miner's expected and observed share of the attributed blocks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinerShare {
+    pub miner_id: String,
+    /// Hashrate weight from `miners.json`
+    pub weight: f64,
+    pub expected_share: f64,
+    pub expected_blocks: f64,
+    pub actual_blocks: usize,
+    pub actual_share: f64,
+}
+
+/// Block attribution checked against the `miners.json` weights
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinerAttribution {
+    pub attributed_blocks: usize,
+    /// Blocks with no locally-mined sighting, or mined by an agent missing
+    /// from `miners.json`
+    pub unattributed_blocks: usize,
+    pub shares: Vec<MinerShare>,
+    /// Pearson chi-square of actual vs expected block counts; `None` with
+    /// fewer than two weighted miners or no attributed blocks
+    pub chi_square: Option<f64>,
+    pub degrees_of_freedom: usize,
+    pub p_value: Option<f64>,
+    /// p < 0.05: the observed shares are unlikely under the weights
+    pub significant: bool,
+    pub note: String,
+}
+
+/// Aggregated block propagation report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockPropagationReport {
+    /// `REPORT_SCHEMA_VERSION` at analysis time
+    #[serde(default)]
+    pub report_schema_version: u32,
+    pub total_blocks: usize,
+    pub daemon_count: usize,
+    pub average_time_to_50_ms: f64,
+    pub median_time_to_50_ms: f64,
+    pub p95_time_to_50_ms: f64,
+    pub average_time_to_90_ms: f64,
+    pub median_time_to_90_ms: f64,
+    pub p95_time_to_90_ms: f64,
+    pub average_time_to_100_ms: f64,
+    /// Blocks every daemon received
+    pub fully_propagated_blocks: usize,
+    /// Target seconds between blocks, from the manifest's `block_interval`
+    #[serde(default = "default_block_interval_secs")]
+    pub block_interval_secs: f64,
+    /// Mean gap between the first sightings of consecutive heights
+    #[serde(default)]
+    pub observed_block_interval_secs: Option<f64>,
+    /// `median_time_to_90_ms` as a share of the target interval
+    #[serde(default)]
+    pub median_time_to_90_interval_share: Option<f64>,
+    pub alternative_blocks: Vec<AlternativeBlockEvent>,
+    /// `None` when `miners.json` was not available
+    pub miner_attribution: Option<MinerAttribution>,
+    pub per_block: Vec<BlockPropagation>,
+}
+
+fn default_block_interval_secs() -> f64 {
+    crate::TARGET_BLOCK_INTERVAL_SECS as f64
+}
@@ -0,0 +1,68 @@
+//! Multi-run comparison types.
+
+use serde::{Deserialize, Serialize};
+
+use super::upgrade::{ChangeImpact, ConfidenceInterval};
+
+/// One metric of one run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunMetric {
+    pub metric_name: String,
+    /// Analysis the metric comes from (`propagation`, `bandwidth`, ...)
+    pub analysis: String,
+    pub value: f64,
+    /// Samples behind the value (per transaction, per node, ...); empty for
+    /// scores with nothing to test
+    #[serde(skip)]
+    pub samples: Vec<f64>,
+    pub sample_count: usize,
+    pub higher_is_better: bool,
+}
+
+/// Every metric collected from one run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub label: String,
+    pub agents: usize,
+    pub transactions: usize,
+    pub daemons: usize,
+    pub metrics: Vec<RunMetric>,
+}
+
+/// One metric of a run against the baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricComparison {
+    pub metric_name: String,
+    pub baseline_value: f64,
+    pub value: f64,
+    pub absolute_change: f64,
+    pub percent_change: f64,
+    /// Welch's t-test over the samples; `None` with fewer than two samples
+    /// on either side
+    pub p_value: Option<f64>,
+    pub statistically_significant: bool,
+    pub impact: ChangeImpact,
+    /// Bootstrap interval for the difference in sample means
+    pub confidence_interval: Option<ConfidenceInterval>,
+}
+
+/// One run against the baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunComparison {
+    pub label: String,
+    pub changes: Vec<MetricComparison>,
+    pub significant_changes: usize,
+}
+
+/// Side-by-side comparison of two or more runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparisonReport {
+    /// `REPORT_SCHEMA_VERSION` at analysis time
+    #[serde(default)]
+    pub report_schema_version: u32,
+    /// Label of the first run, which every other run is compared with
+    pub baseline: String,
+    pub analyses: Vec<String>,
+    pub runs: Vec<RunSummary>,
+    pub comparisons: Vec<RunComparison>,
+}
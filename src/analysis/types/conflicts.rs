@@ -0,0 +1,95 @@
+//! Conflicting-transaction (double-spend) analysis result types.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::core::SimTime;
+
+/// One entry of `conflicts.json`: transactions spending the same inputs,
+/// recorded by the agent that broadcast them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictSet {
+    /// Defaults to the set's position in the file
+    #[serde(default)]
+    pub conflict_id: Option<String>,
+    /// Agent that created the transactions
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    pub tx_hashes: Vec<String>,
+    /// When the agent broadcast the set
+    #[serde(default)]
+    pub timestamp: Option<SimTime>,
+}
+
+/// How one daemon saw a conflict set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeConflictView {
+    pub node_id: String,
+    /// The set's transactions in the order this daemon first saw them
+    pub first_seen_order: Vec<String>,
+    /// First sighting of each transaction in `first_seen_order`
+    pub first_seen_times: Vec<SimTime>,
+    /// The transaction seen first, which the daemon keeps in its pool
+    pub preferred_tx: String,
+    /// The set's transactions this daemon sent to another daemon
+    pub relayed: Vec<String>,
+}
+
+/// Daemons preferring each transaction within one region
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictRegionSplit {
+    /// Continent, or `AS<n>`; `unknown` when the registry has neither
+    pub region: String,
+    /// Transaction -> daemons in the region preferring it
+    pub preferred: BTreeMap<String, usize>,
+}
+
+/// Everything observed about one conflict set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictSetAnalysis {
+    pub conflict_id: String,
+    pub agent_id: Option<String>,
+    pub tx_hashes: Vec<String>,
+    /// Earliest of the broadcast time and any daemon's first sighting
+    pub first_seen: Option<SimTime>,
+    /// Daemons that saw at least one of the transactions
+    pub nodes_reached: usize,
+    /// Transaction -> daemons preferring it
+    pub preferred: BTreeMap<String, usize>,
+    /// Sorted by region
+    pub split_by_continent: Vec<ConflictRegionSplit>,
+    /// Sorted by region
+    pub split_by_as: Vec<ConflictRegionSplit>,
+    /// Connections between daemons preferring different transactions,
+    /// counted from the relays of the set's transactions
+    pub contested_links: usize,
+    /// The transaction that made it into a block, if any
+    pub included_tx: Option<String>,
+    pub included_height: Option<u64>,
+    /// From `first_seen` to the network's first sighting of that block
+    pub resolution_secs: Option<f64>,
+    /// Daemons that relayed more than one of the set's transactions
+    pub double_relayers: Vec<String>,
+    pub per_node: Vec<NodeConflictView>,
+}
+
+/// Aggregated conflicting-transaction report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictReport {
+    /// `REPORT_SCHEMA_VERSION` at analysis time
+    #[serde(default)]
+    pub report_schema_version: u32,
+    pub conflict_sets: usize,
+    /// Sets with a transaction included in a block
+    pub resolved_sets: usize,
+    pub avg_resolution_secs: Option<f64>,
+    pub max_resolution_secs: Option<f64>,
+    /// Sets where the daemons that saw them disagreed on the preferred
+    /// transaction
+    pub split_sets: usize,
+    /// Distinct daemons that relayed both sides of some set
+    pub double_relayers: Vec<String>,
+    /// In `conflicts.json` order
+    pub sets: Vec<ConflictSetAnalysis>,
+}
@@ -32,10 +32,30 @@ pub struct AnalysisAgentInfo {
     pub script_type: String,
     #[serde(default)]
     pub wallet_address: Option<String>,
+    /// GML node the agent's host sits on (registry schema >= 2, GML only)
+    #[serde(default)]
+    pub network_node_id: Option<u32>,
+    /// Registry `continent` (`north_america`, `europe`, ...); absent in
+    /// registries from older generators
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub continent: Option<String>,
+    /// Registry `as_number` of the agent's GML node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_number: Option<u32>,
+    /// Registry `role` (`"spy"` for spy daemons); None for ordinary agents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    /// Registry `reachable`: `Some(false)` when inbound P2P was blocked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reachable: Option<bool>,
+    /// Registry `out_peers`: the outbound limit the daemon ran with;
+    /// absent in registries from older generators
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub out_peers: Option<usize>,
 }
 
 /// Connection direction from log entries
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ConnectionDirection {
     /// INC - peer connected to us
     Inbound,
@@ -42,6 +42,29 @@ pub struct DandelionPath {
     pub fluff_recipients: usize,
     /// Did the first hop match the originator? (sanity check)
     pub originator_confirmed: bool,
+    /// How unambiguous the hop choices were, from 1 (every hop had a single
+    /// candidate) towards 0; absent (0) in reports from older analyzers
+    #[serde(default)]
+    pub confidence: f64,
+}
+
+/// How a fluff broadcast is told apart from a stem relay: a sender whose
+/// first `min_recipients` deliveries of a transaction fall within `gap_ms`
+/// is fluffing it; otherwise its earliest delivery is the next stem hop and
+/// the rest are later gossip re-relays.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FluffDetection {
+    pub gap_ms: f64,
+    pub min_recipients: usize,
+}
+
+impl Default for FluffDetection {
+    fn default() -> Self {
+        Self {
+            gap_ms: 2000.0,
+            min_recipients: 3,
+        }
+    }
 }
 
 /// Statistics about a node's role in Dandelion++
@@ -61,12 +84,21 @@ pub struct NodeDandelionStats {
 /// Dandelion++ analysis report
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DandelionReport {
+    /// `REPORT_SCHEMA_VERSION` at analysis time
+    #[serde(default)]
+    pub report_schema_version: u32,
     /// Total transactions analyzed
     pub total_transactions: usize,
     /// Transactions with reconstructable paths
     pub paths_reconstructed: usize,
     /// Transactions where originator was confirmed in path
     pub originator_confirmed_count: usize,
+    /// Fluff detection parameters the paths were traced with
+    #[serde(default)]
+    pub fluff_detection: FluffDetection,
+    /// Mean of the paths' `confidence`
+    #[serde(default)]
+    pub avg_path_confidence: f64,
 
     /// Stem length statistics
     pub avg_stem_length: f64,
@@ -0,0 +1,68 @@
+//! Mempool divergence analysis result types.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::core::SimTime;
+
+/// The daemons' mempools sampled at the end of one window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MempoolWindow {
+    pub start: SimTime,
+    pub end: SimTime,
+    pub median_mempool_size: f64,
+    /// Transactions at least half the daemons hold: the median node's view
+    pub reference_size: usize,
+    /// Mean over daemons of the symmetric difference with the reference
+    pub avg_divergence: f64,
+    pub max_divergence: usize,
+    pub max_divergence_node: Option<String>,
+    /// Daemons whose mempool differs from the reference at all
+    pub diverged_nodes: usize,
+}
+
+/// How far, and for how long, one daemon's mempool strayed from the
+/// reference
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeMempoolDivergence {
+    pub node_id: String,
+    pub avg_divergence: f64,
+    pub max_divergence: usize,
+    pub diverged_windows: usize,
+    /// Longest run of consecutive diverged windows, in seconds
+    pub longest_divergence_sec: f64,
+}
+
+/// A confirmed transaction some daemons never had in their mempool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnseenTransaction {
+    pub tx_hash: String,
+    pub block_height: u64,
+    /// Daemons with no sighting before they received the block
+    pub missing_nodes: Vec<String>,
+}
+
+/// Aggregated mempool divergence report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MempoolReport {
+    /// `REPORT_SCHEMA_VERSION` at analysis time
+    #[serde(default)]
+    pub report_schema_version: u32,
+    pub window_size_sec: f64,
+    pub daemon_count: usize,
+    /// Transactions seen by at least one daemon
+    pub observed_transactions: usize,
+    /// Observed transactions included in a block some daemon logged
+    pub confirmed_transactions: usize,
+    pub max_divergence: usize,
+    pub max_divergence_node: Option<String>,
+    pub max_divergence_time: Option<SimTime>,
+    pub windows: Vec<MempoolWindow>,
+    /// Sorted by max divergence, largest first
+    pub per_node: Vec<NodeMempoolDivergence>,
+    /// Sorted by number of missing daemons, most first
+    pub never_seen: Vec<UnseenTransaction>,
+    /// Number of missing daemons -> confirmed transactions missed by that many
+    pub never_seen_by_count: BTreeMap<usize, usize>,
+}
@@ -14,23 +14,50 @@
 //! - `dandelion`: Dandelion++ stem-path analysis types.
 //! - `upgrade`: time-windowed types used by the upgrade-impact pipeline.
 //! - `bandwidth`: bandwidth analysis types.
+//! - `block_propagation`: block propagation analysis result types.
+//! - `mempool`: mempool divergence analysis result types.
+//! - `conflicts`: conflicting-transaction (double-spend) analysis types.
+//! - `monitor`: simulation monitor time-series types.
+//! - `pcap`: packet capture index types.
+//! - `compare`: multi-run comparison types.
+//! - `sweep`: sweep batch analysis types.
+//! - `readiness`: daemon and wallet RPC readiness types.
+//! - `trace`: single-transaction trace types.
+//! - `tx_success`: agent transaction success-rate types.
 //!
 //! All previously-public items are re-exported below so callers can keep
 //! using `use crate::analysis::types::*;` (or the direct paths
 //! `analysis::types::TypeName` from outside) unchanged.
 
 mod bandwidth;
+mod block_propagation;
+mod compare;
+mod conflicts;
 mod core;
 mod dandelion;
+mod mempool;
+mod monitor;
+mod pcap;
 mod propagation;
+mod readiness;
 mod resilience;
 mod spy;
+mod sweep;
+mod trace;
 mod tx_relay;
+mod tx_success;
 mod upgrade;
 
 pub use bandwidth::{
-    BandwidthEvent, BandwidthReport, BandwidthWindow, CategoryBandwidth, NodeBandwidthStats,
-    PeerBandwidth,
+    BandwidthEvent, BandwidthMatrix, BandwidthReport, BandwidthWindow, CategoryBandwidth,
+    NodeBandwidthStats, PairBandwidth, PeerBandwidth,
+};
+pub use block_propagation::{
+    AlternativeBlockEvent, BlockPropagation, BlockPropagationReport, MinerAttribution, MinerShare,
+};
+pub use compare::{ComparisonReport, MetricComparison, RunComparison, RunMetric, RunSummary};
+pub use conflicts::{
+    ConflictRegionSplit, ConflictReport, ConflictSet, ConflictSetAnalysis, NodeConflictView,
 };
 pub use core::{
     AnalysisAgentInfo, BlockInfo, BlockObservation, ConnectionDirection, ConnectionDrop,
@@ -38,22 +65,35 @@ pub use core::{
     TxRelayProtocol, TxRequest,
 };
 pub use dandelion::{
-    DandelionPath, DandelionPrivacyAssessment, DandelionReport, NodeDandelionStats, StemHop,
+    DandelionPath, DandelionPrivacyAssessment, DandelionReport, FluffDetection, NodeDandelionStats,
+    StemHop,
+};
+pub use mempool::{MempoolReport, MempoolWindow, NodeMempoolDivergence, UnseenTransaction};
+pub use monitor::{MonitorNodeSummary, MonitorReport, MonitorSample, MonitorWindow};
+pub use pcap::{AgentPcaps, PcapFile, PcapIndex};
+pub use propagation::{
+    AsBoundaryBottleneck, BottleneckNode, ContinentPropagation, CoverageCurvePoint, CoveragePoint,
+    GeoPairDelay, HopLatencyStats, HopOverhead, LatencyOverhead, PairOverhead, PropagationAnalysis,
+    PropagationReport, RegionBreakdown, TxHopOverhead,
 };
-pub use propagation::{BottleneckNode, PropagationAnalysis, PropagationReport};
+pub use readiness::{NotReadyCheck, ReadinessReport, TargetReadiness};
 pub use resilience::{
     AnalysisMetadata, CentralizationMetrics, ConnectivityMetrics, FullAnalysisReport,
-    PartitionRiskMetrics, ResilienceMetrics,
+    PartitionEventMetrics, PartitionRiskMetrics, ResilienceMetrics,
 };
 pub use spy::{
-    FirstSeenEntry, SpyNodeReport, SpyNodeTxAnalysis, TimingDistribution, VulnerableSender,
+    FirstSeenEntry, SpyNodeReport, SpyNodeTxAnalysis, SpyObserverStats, TimingDistribution,
+    VulnerableSender,
 };
+pub use sweep::{SweepRunResult, SweepRunStatus, SweepSummary};
+pub use trace::{TraceEvent, TraceEventKind, TxTrace};
 pub use tx_relay::{
     ConnectionStabilityMetrics, ProtocolUsageStats, RequestResponseMetrics, TxDeliveryAnalysis,
     TxRelayAssessment, TxRelayV2Report,
 };
+pub use tx_success::{AgentTxSuccess, TxAttempt, TxOutcome, TxSuccessReport};
 pub use upgrade::{
-    AggregatedMetrics, ChangeImpact, MetricChange, NodeUpgradeEvent, TimeWindow,
-    UpgradeAnalysisMetadata, UpgradeAnalysisReport, UpgradeAssessment, UpgradeManifest,
+    AggregatedMetrics, ChangeImpact, ConfidenceInterval, MetricChange, NodeUpgradeEvent,
+    TimeWindow, UpgradeAnalysisMetadata, UpgradeAnalysisReport, UpgradeAssessment, UpgradeManifest,
     UpgradeVerdict, WindowedMetrics,
 };
@@ -0,0 +1,74 @@
+//! Simulation monitor time-series types.
+
+use serde::{Deserialize, Serialize};
+
+use super::core::SimTime;
+
+/// One node's values at one monitor poll. Columns the monitor was not
+/// asked to record, or the node did not report, are `None`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorSample {
+    pub time: SimTime,
+    /// Monitor cycle number; samples of one poll share it
+    #[serde(default)]
+    pub poll: Option<u64>,
+    pub node: String,
+    #[serde(default)]
+    pub height: Option<u64>,
+    /// Transactions in the node's pool
+    #[serde(default)]
+    pub mempool: Option<u64>,
+    #[serde(default)]
+    pub peers: Option<u64>,
+    /// Cumulative bytes received since the daemon started
+    #[serde(default)]
+    pub bytes_in: Option<u64>,
+    /// Cumulative bytes sent since the daemon started
+    #[serde(default)]
+    pub bytes_out: Option<u64>,
+}
+
+/// Monitor samples aggregated over one window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorWindow {
+    pub start: SimTime,
+    pub end: SimTime,
+    pub samples: usize,
+    /// Mean over samples of the poll's highest height minus the node's
+    pub avg_height_lag: Option<f64>,
+    pub max_height_lag: Option<u64>,
+    pub avg_mempool_txs: Option<f64>,
+    pub avg_peers: Option<f64>,
+}
+
+/// One node over the whole time series
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorNodeSummary {
+    pub node_id: String,
+    pub samples: usize,
+    pub final_height: Option<u64>,
+    pub avg_height_lag: Option<f64>,
+    pub max_height_lag: Option<u64>,
+    pub avg_mempool_txs: Option<f64>,
+    pub avg_peers: Option<f64>,
+    /// Last cumulative counters the node reported
+    pub bytes_in: Option<u64>,
+    pub bytes_out: Option<u64>,
+}
+
+/// Summary of a simulation monitor time-series file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorReport {
+    /// `REPORT_SCHEMA_VERSION` at analysis time
+    #[serde(default)]
+    pub report_schema_version: u32,
+    pub source: String,
+    pub window_size_sec: f64,
+    pub sample_count: usize,
+    pub poll_count: usize,
+    pub first_sample: Option<SimTime>,
+    pub last_sample: Option<SimTime>,
+    pub windows: Vec<MonitorWindow>,
+    /// Sorted by max height lag, largest first
+    pub per_node: Vec<MonitorNodeSummary>,
+}
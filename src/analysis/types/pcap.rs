@@ -0,0 +1,41 @@
+//! Packet capture index types.
+
+use serde::{Deserialize, Serialize};
+
+/// One pcap trace Shadow wrote
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PcapFile {
+    pub path: String,
+    /// Network interface the trace is of, from the file name (`eth0`, `lo`)
+    pub interface: String,
+    pub size_bytes: u64,
+}
+
+/// The pcap traces of one agent's host
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentPcaps {
+    pub agent_id: String,
+    /// Whether the manifest lists the agent under `pcap_capture`; absent
+    /// without a manifest
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<bool>,
+    /// By interface
+    pub files: Vec<PcapFile>,
+}
+
+/// Where a run's pcap traces are, per agent, for tools that read them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PcapIndex {
+    /// `REPORT_SCHEMA_VERSION` at analysis time
+    #[serde(default)]
+    pub report_schema_version: u32,
+    pub data_dir: String,
+    /// `general.pcap_capture_dir`, resolved, when the traces were collected
+    /// there
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capture_dir: Option<String>,
+    /// Agents with traces, by id
+    pub agents: Vec<AgentPcaps>,
+    /// Agents the manifest expects traces of that have none
+    pub missing: Vec<String>,
+}
@@ -18,6 +18,30 @@ pub struct PropagationAnalysis {
     pub nodes_observed: usize,
     pub total_nodes: usize,
     pub propagation_coverage: f64,
+    /// Time to reach each requested percentile of the daemons; absent in
+    /// reports from older analyzers
+    #[serde(default)]
+    pub coverage: Vec<CoveragePoint>,
+}
+
+/// Time from creation until a percentile of the daemons had seen a
+/// transaction. The sender's daemon counts from the creation time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoveragePoint {
+    /// e.g. 90.0 for 90% of the daemons
+    pub percentile: f64,
+    /// None if the transaction never reached that many daemons
+    pub time_ms: Option<f64>,
+}
+
+/// One point of the network-wide coverage curve
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoverageCurvePoint {
+    pub percentile: f64,
+    /// Median time over the transactions that reached the percentile
+    pub median_ms: Option<f64>,
+    /// Transactions that reached it
+    pub transactions: usize,
 }
 
 /// Aggregated propagation report
@@ -29,8 +53,163 @@ pub struct PropagationReport {
     pub median_propagation_ms: f64,
     pub p95_propagation_ms: f64,
     pub average_confirmation_delay_sec: f64,
+    /// Median time to each coverage percentile; absent in reports from
+    /// older analyzers
+    #[serde(default)]
+    pub coverage_curve: Vec<CoverageCurvePoint>,
+    /// Per-hop relay latency; absent in reports from older analyzers
+    #[serde(default)]
+    pub hop_latency: Option<HopLatencyStats>,
+    /// Delays by registry `continent`; empty when the registry has none
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub by_continent: Vec<ContinentPropagation>,
+    /// Relay delays within and across regions and ASes; None when the
+    /// registry has neither `continent` nor `as_number`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub by_region: Option<RegionBreakdown>,
     pub bottleneck_nodes: Vec<BottleneckNode>,
     pub per_tx_analysis: Vec<PropagationAnalysis>,
+    /// Relay delay beyond the network's lower bound; only with
+    /// `--latency-matrix`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_overhead: Option<LatencyOverhead>,
+}
+
+/// Each relay hop's observed delay (as in [`HopLatencyStats`]) less the
+/// shortest-path latency between the two agents' GML nodes from
+/// `latency_matrix.json`: time spent in the daemons rather than on the wire.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyOverhead {
+    /// Hops with both agents in the matrix
+    pub hops: usize,
+    /// Hops left out because an agent has no matrix entry
+    pub unmatched_hops: usize,
+    pub average_overhead_ms: f64,
+    pub median_overhead_ms: f64,
+    pub p95_overhead_ms: f64,
+    /// A pair is flagged when its median overhead exceeds this: the upper
+    /// quartile of all hop overheads plus three interquartile ranges
+    pub anomaly_threshold_ms: f64,
+    /// Flagged pairs, highest overhead first
+    pub anomalous_pairs: Vec<PairOverhead>,
+    /// Every hop, per transaction; cleared without `--detailed`
+    pub per_tx: Vec<TxHopOverhead>,
+}
+
+/// Overhead of all hops from one agent to another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairOverhead {
+    /// Relaying agent
+    pub from: String,
+    /// Receiving agent
+    pub to: String,
+    pub hops: usize,
+    pub theoretical_ms: f64,
+    pub median_observed_ms: f64,
+    pub median_overhead_ms: f64,
+}
+
+/// The relay hops of one transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxHopOverhead {
+    pub tx_hash: String,
+    pub hops: Vec<HopOverhead>,
+}
+
+/// One relay: `to`'s first sighting, heard from `from`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HopOverhead {
+    pub from: String,
+    pub to: String,
+    pub observed_ms: f64,
+    pub theoretical_ms: f64,
+    /// `observed_ms - theoretical_ms`
+    pub overhead_ms: f64,
+}
+
+/// Relay latency per observation pair: each node's first sighting of a tx
+/// paired with the relaying peer's own first sighting (or the creation time
+/// when the peer is the sender).
+///
+/// Agents sharing a GML node see each other with near-zero latency, which
+/// flatters network-wide averages; such intra-node pairs are counted and,
+/// with `--exclude-intra-node`, left out of the aggregates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HopLatencyStats {
+    pub pairs: usize,
+    pub intra_node_pairs: usize,
+    /// `intra_node_pairs / pairs`
+    pub intra_node_fraction: f64,
+    /// False when the registry has no `network_node_id` (switch network or
+    /// pre-schema-2 artifacts); intra-node counts are then zero
+    pub placement_known: bool,
+    /// Whether intra-node pairs were excluded from the aggregates below
+    pub intra_node_excluded: bool,
+    pub average_ms: f64,
+    pub median_ms: f64,
+    pub p95_ms: f64,
+    /// Mean over intra-node pairs alone, reported separately
+    pub intra_node_average_ms: f64,
+}
+
+/// How late the nodes on one continent see transactions, relative to each
+/// transaction's first sighting anywhere
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContinentPropagation {
+    pub continent: String,
+    pub nodes: usize,
+    pub observations: usize,
+    pub average_delay_ms: f64,
+    pub median_delay_ms: f64,
+    pub p95_delay_ms: f64,
+}
+
+/// Relay hops (as in [`HopLatencyStats`]) grouped by the registry geography
+/// of the relaying and the receiving agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionBreakdown {
+    /// Hops with both agents' `continent` known
+    pub region_hops: usize,
+    pub intra_region_hops: usize,
+    /// None without intra-region hops
+    pub intra_region_median_ms: Option<f64>,
+    pub inter_region_hops: usize,
+    /// None without inter-region hops
+    pub inter_region_median_ms: Option<f64>,
+    /// Median hop delay per (relaying, receiving) continent
+    pub region_matrix: Vec<GeoPairDelay>,
+    /// Median hop delay per (relaying, receiving) AS; empty without
+    /// registry `as_number`
+    pub as_matrix: Vec<GeoPairDelay>,
+    /// Transactions whose slowest hop crossed an AS boundary and took at
+    /// least `AS_BOTTLENECK_SHARE` of their propagation time, slowest first
+    pub as_bottlenecks: Vec<AsBoundaryBottleneck>,
+}
+
+/// One cell of a region or AS delay matrix
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoPairDelay {
+    /// Relaying agent's continent or AS
+    pub from: String,
+    /// Receiving agent's continent or AS
+    pub to: String,
+    pub hops: usize,
+    pub median_ms: f64,
+}
+
+/// A transaction held up by one hop from one AS into another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AsBoundaryBottleneck {
+    pub tx_hash: String,
+    /// Relaying agent
+    pub from: String,
+    /// Receiving agent
+    pub to: String,
+    pub from_as: u32,
+    pub to_as: u32,
+    pub delay_ms: f64,
+    /// `delay_ms` over the time from creation to the last first sighting
+    pub share: f64,
 }
 
 /// A node that is consistently slow to receive transactions
@@ -0,0 +1,39 @@
+//! RPC readiness types, from the healthcheck records in the shared dir.
+
+use serde::{Deserialize, Serialize};
+
+/// How long one kind of RPC (`daemon` or `wallet`) took to come up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetReadiness {
+    pub target: String,
+    pub checks: usize,
+    pub ready: usize,
+    pub timeouts: usize,
+    pub refused: usize,
+    pub bad_responses: usize,
+    /// Over the ready checks, seconds from the check starting until ready
+    pub mean_sec: Option<f64>,
+    pub median_sec: Option<f64>,
+    pub max_sec: Option<f64>,
+    pub slowest_agent: Option<String>,
+}
+
+/// A check that gave up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotReadyCheck {
+    pub agent: String,
+    pub target: String,
+    pub status: String,
+    pub attempts: u32,
+    pub error: Option<String>,
+}
+
+/// Daemon and wallet readiness latencies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadinessReport {
+    /// `REPORT_SCHEMA_VERSION` at analysis time
+    #[serde(default)]
+    pub report_schema_version: u32,
+    pub targets: Vec<TargetReadiness>,
+    pub not_ready: Vec<NotReadyCheck>,
+}
@@ -4,8 +4,11 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+use super::core::SimTime;
 use super::propagation::PropagationReport;
 use super::spy::SpyNodeReport;
+use crate::analysis::anonymize::AnonymizationInfo;
+use crate::manifest::SimulationManifest;
 
 /// Network resilience metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +16,9 @@ pub struct ResilienceMetrics {
     pub connectivity: ConnectivityMetrics,
     pub centralization: CentralizationMetrics,
     pub partition_risk: PartitionRiskMetrics,
+    /// One entry per scheduled partition (`network.events` in the manifest)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub partition_events: Vec<PartitionEventMetrics>,
 }
 
 /// Connectivity analysis
@@ -46,6 +52,40 @@ pub struct PartitionRiskMetrics {
     pub connected_components: usize,
 }
 
+/// What the logs show of one scheduled partition and its heal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartitionEventMetrics {
+    pub partition_at: SimTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heal_at: Option<SimTime>,
+    /// Daemons per side of the partition
+    pub group_sizes: Vec<usize>,
+    /// Connections between groups open just before the partition
+    pub cross_group_connections_before: usize,
+    /// Connections between groups closed within `PARTITION_DROP_WINDOW_SECS`
+    /// of the partition, as logged by either end
+    pub cross_group_drops: usize,
+    /// Seconds from the partition to the last of those closes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drop_latency_secs: Option<f64>,
+    /// Connections between groups still open just before the heal (or at
+    /// the end of the logs)
+    pub cross_group_connections_during: usize,
+    /// Cross-group connections went down and none were open during the
+    /// partition
+    pub detected: bool,
+    /// Seconds from the heal to the first connection between groups
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_reconnect_secs: Option<f64>,
+    /// Seconds from the heal until every grouped daemon has seen the first
+    /// block found after it; None when some never did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reconverge_secs: Option<f64>,
+    /// Transactions first seen during the partition that had not reached
+    /// every group by the heal
+    pub stuck_transactions: Vec<String>,
+}
+
 /// Complete analysis report
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FullAnalysisReport {
@@ -58,9 +98,18 @@ pub struct FullAnalysisReport {
 /// Report metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalysisMetadata {
+    /// `REPORT_SCHEMA_VERSION` at analysis time
+    #[serde(default)]
+    pub report_schema_version: u32,
     pub analysis_timestamp: String,
     pub simulation_data_dir: String,
     pub total_nodes: usize,
     pub total_transactions: usize,
     pub total_blocks: usize,
+    /// Set when the report was passed through `--anonymize`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anonymization: Option<AnonymizationInfo>,
+    /// `manifest.json` of the generated simulation, when available
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<SimulationManifest>,
 }
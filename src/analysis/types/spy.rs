@@ -24,6 +24,10 @@ pub struct SpyNodeTxAnalysis {
     pub timing_spread_ms: f64,
     pub inferred_originator_ip: Option<String>,
     pub inference_correct: bool,
+    /// Candidate originators the spy coalition cannot tell apart; absent
+    /// when every node's observations count
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anonymity_set_size: Option<usize>,
 }
 
 /// Aggregated spy node analysis report
@@ -35,6 +39,31 @@ pub struct SpyNodeReport {
     pub timing_spread_distribution: TimingDistribution,
     pub vulnerable_senders: Vec<VulnerableSender>,
     pub per_tx_analysis: Vec<SpyNodeTxAnalysis>,
+    /// Spy nodes whose logs were used (the chosen monitor set, or agents
+    /// with registry role `spy`); empty when every node's observations count
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub observers: Vec<String>,
+    /// One entry per observer, in `observers` order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub per_spy: Vec<SpyObserverStats>,
+    /// Mean `anonymity_set_size` over transactions the coalition saw
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_anonymity_set_size: Option<f64>,
+}
+
+/// How much one spy of the coalition contributes on its own
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpyObserverStats {
+    pub node_id: String,
+    /// Analyzable transactions this spy saw at all
+    pub transactions_observed: usize,
+    /// Analyzable transactions this spy saw before any other spy
+    pub first_observations: usize,
+    /// `first_observations` over the coalition's analyzable transactions
+    pub first_observation_share: f64,
+    /// Inference accuracy from this spy's observations alone, over the
+    /// transactions it saw
+    pub accuracy: f64,
 }
 
 /// Distribution of timing spreads
@@ -0,0 +1,50 @@
+//! Sweep batch analysis types.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Whether a run of the sweep could be analyzed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SweepRunStatus {
+    Ok,
+    Failed,
+}
+
+/// One run's row of the sweep summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepRunResult {
+    /// Variant name, or the run directory's name
+    pub name: String,
+    pub dir: String,
+    /// Swept parameter -> this run's value
+    pub parameters: BTreeMap<String, serde_json::Value>,
+    pub status: SweepRunStatus,
+    /// Why the run failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub agents: usize,
+    pub transactions: usize,
+    pub daemons: usize,
+    /// Metric -> value; `None` when the run has nothing to measure it on
+    pub metrics: BTreeMap<String, Option<f64>>,
+}
+
+/// Headline metrics of every run of a sweep, keyed by the swept parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepSummary {
+    /// `REPORT_SCHEMA_VERSION` at analysis time
+    #[serde(default)]
+    pub report_schema_version: u32,
+    pub runs_root: String,
+    pub analyses: Vec<String>,
+    /// Swept parameter names, in column order
+    pub parameters: Vec<String>,
+    /// Metric names, in column order
+    pub metrics: Vec<String>,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// In sweep order
+    pub runs: Vec<SweepRunResult>,
+}
@@ -0,0 +1,73 @@
+//! Single-transaction trace types.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::core::SimTime;
+use super::dandelion::DandelionPath;
+
+/// What happened at one point of a transaction's trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceEventKind {
+    /// The sender's wallet submitted the transaction (`transactions.json`)
+    Created,
+    /// A v2 hash announcement a daemon received before it first saw the
+    /// transaction
+    HashAnnounced,
+    /// A v2 request a daemon sent before it first saw the transaction
+    TxRequested,
+    FirstSeen,
+    /// A later copy of a transaction the daemon already had
+    SeenAgain,
+    /// The earliest sighting of the block including the transaction
+    BlockIncluded,
+}
+
+impl fmt::Display for TraceEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TraceEventKind::Created => "created",
+            TraceEventKind::HashAnnounced => "hash announced",
+            TraceEventKind::TxRequested => "tx requested",
+            TraceEventKind::FirstSeen => "first seen",
+            TraceEventKind::SeenAgain => "seen again",
+            TraceEventKind::BlockIncluded => "in block",
+        })
+    }
+}
+
+/// One entry of the timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceEvent {
+    pub timestamp: SimTime,
+    /// Milliseconds after creation, or after the first sighting when the
+    /// transaction is missing from `transactions.json`
+    pub offset_ms: f64,
+    pub kind: TraceEventKind,
+    pub node_id: String,
+    /// Agent the daemon heard from (or asked); the raw IP when no agent has it
+    pub peer: Option<String>,
+    pub detail: Option<String>,
+}
+
+/// Chronological timeline of one transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxTrace {
+    /// `REPORT_SCHEMA_VERSION` at analysis time
+    #[serde(default)]
+    pub report_schema_version: u32,
+    pub tx_hash: String,
+    /// `None` when the transaction is missing from `transactions.json`
+    pub sender: Option<String>,
+    pub recipient: Option<String>,
+    pub created_at: Option<SimTime>,
+    pub daemon_count: usize,
+    /// Daemons that logged receiving the transaction
+    pub nodes_reached: usize,
+    pub stem_path: Option<DandelionPath>,
+    pub block_height: Option<u64>,
+    pub block_time: Option<SimTime>,
+    pub events: Vec<TraceEvent>,
+}
@@ -61,6 +61,9 @@ pub struct RequestResponseMetrics {
 /// Full TX relay v2 comparison report
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TxRelayV2Report {
+    /// `REPORT_SCHEMA_VERSION` at analysis time
+    #[serde(default)]
+    pub report_schema_version: u32,
     /// Protocol usage statistics
     pub protocol_usage: ProtocolUsageStats,
     /// TX delivery analysis
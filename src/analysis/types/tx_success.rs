@@ -0,0 +1,69 @@
+//! Agent transaction success-rate result types.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::core::SimTime;
+
+/// Whether an agent's send went through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TxOutcome {
+    Success,
+    Failure,
+}
+
+/// One line of `tx_attempts.jsonl`: an agent's attempt to send a transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxAttempt {
+    pub agent_id: String,
+    pub timestamp: SimTime,
+    pub outcome: TxOutcome,
+    /// The wallet's error message, for failures
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// For successes
+    #[serde(default)]
+    pub tx_hash: Option<String>,
+}
+
+/// One agent's attempts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentTxSuccess {
+    pub agent_id: String,
+    pub attempted: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// `succeeded / attempted`
+    pub success_rate: f64,
+    pub first_attempt: SimTime,
+    pub first_success: Option<SimTime>,
+    /// From the first attempt to the first success; long waits usually mean
+    /// the wallet had no unlocked funds yet
+    pub time_to_first_success: Option<f64>,
+    /// Failure category -> count
+    pub failure_reasons: BTreeMap<String, usize>,
+}
+
+/// Aggregated transaction success report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxSuccessReport {
+    /// `REPORT_SCHEMA_VERSION` at analysis time
+    #[serde(default)]
+    pub report_schema_version: u32,
+    /// `tx_attempts.jsonl`, or the log directory the attempts were read from
+    pub source: String,
+    pub attempted: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub success_rate: Option<f64>,
+    /// Failure category -> count over all agents
+    pub failure_reasons: BTreeMap<String, usize>,
+    /// Median over the agents that succeeded at least once
+    pub median_time_to_first_success: Option<f64>,
+    /// Agents whose every attempt failed
+    pub agents_without_success: Vec<String>,
+    /// Sorted by agent id
+    pub per_agent: Vec<AgentTxSuccess>,
+}
@@ -3,6 +3,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::core::SimTime;
+use crate::analysis::anonymize::AnonymizationInfo;
 
 /// A time window for segmented analysis
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,6 +53,10 @@ pub struct WindowedMetrics {
     pub median_propagation_ms: Option<f64>,
     /// 95th percentile propagation (ms)
     pub p95_propagation_ms: Option<f64>,
+    /// Median time from creation to each coverage percentile (parallel to
+    /// coverage_percentiles in metadata); None where no transaction got there
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage_ms_by_percentile: Option<Vec<Option<f64>>>,
 
     // Network metrics (snapshot at window end)
     /// Average peer count
@@ -76,6 +81,37 @@ pub struct WindowedMetrics {
     pub total_bandwidth: Option<u64>,
     /// Total message count
     pub bandwidth_message_count: Option<u64>,
+
+    // Block propagation metrics
+    /// Blocks first seen in this window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocks_seen: Option<usize>,
+    /// Mean time for those blocks to reach 90% of daemons (ms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_block_propagation_ms: Option<f64>,
+    /// Blocks seen per block expected at the target interval over the
+    /// window (1.0 = on target)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_rate_vs_target: Option<f64>,
+
+    // Conflicting-transaction metrics
+    /// Conflict sets (`conflicts.json`) first seen in this window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conflict_count: Option<usize>,
+
+    // Agent send metrics
+    /// Share of agent send attempts (`tx_attempts.jsonl`) in this window
+    /// that succeeded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_success_rate: Option<f64>,
+
+    // Simulation monitor metrics, merged from its time-series file
+    /// Mean of each poll's highest height minus a node's height
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_height_lag: Option<f64>,
+    /// Mean transactions in a node's pool at a poll
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_mempool_txs: Option<f64>,
 }
 
 impl Default for WindowedMetrics {
@@ -89,6 +125,7 @@ impl Default for WindowedMetrics {
             avg_propagation_ms: None,
             median_propagation_ms: None,
             p95_propagation_ms: None,
+            coverage_ms_by_percentile: None,
             avg_peer_count: None,
             gini_coefficient: None,
             avg_stem_length: None,
@@ -98,6 +135,13 @@ impl Default for WindowedMetrics {
             bytes_received: None,
             total_bandwidth: None,
             bandwidth_message_count: None,
+            blocks_seen: None,
+            avg_block_propagation_ms: None,
+            block_rate_vs_target: None,
+            conflict_count: None,
+            tx_success_rate: None,
+            avg_height_lag: None,
+            avg_mempool_txs: None,
         }
     }
 }
@@ -120,6 +164,9 @@ pub struct AggregatedMetrics {
     /// Mean spy accuracy at each visibility level
     pub mean_spy_accuracy_by_visibility: Option<Vec<f64>>,
     pub mean_propagation_ms: Option<f64>,
+    /// Mean time to each coverage percentile
+    #[serde(default)]
+    pub mean_coverage_ms_by_percentile: Option<Vec<f64>>,
     pub mean_peer_count: Option<f64>,
     pub mean_gini: Option<f64>,
     pub mean_stem_length: Option<f64>,
@@ -130,6 +177,9 @@ pub struct AggregatedMetrics {
     /// Std dev of spy accuracy at each visibility level
     pub std_spy_accuracy_by_visibility: Option<Vec<f64>>,
     pub std_propagation_ms: Option<f64>,
+    /// Std dev of time to each coverage percentile
+    #[serde(default)]
+    pub std_coverage_ms_by_percentile: Option<Vec<f64>>,
     pub std_peer_count: Option<f64>,
     pub std_gini: Option<f64>,
     pub std_stem_length: Option<f64>,
@@ -173,6 +223,19 @@ pub struct MetricChange {
     pub interpretation: String,
     /// Direction of impact (positive, negative, neutral)
     pub impact: ChangeImpact,
+    /// Bootstrap interval for the post - pre difference in window means;
+    /// absent in reports from older analyzers
+    #[serde(default)]
+    pub confidence_interval: Option<ConfidenceInterval>,
+}
+
+/// Confidence interval for a difference in means
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceInterval {
+    pub lower: f64,
+    pub upper: f64,
+    /// e.g. 0.95
+    pub level: f64,
 }
 
 /// Direction and nature of a metric change
@@ -212,6 +275,9 @@ pub struct NodeUpgradeEvent {
 /// Complete upgrade impact analysis report
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpgradeAnalysisReport {
+    /// `REPORT_SCHEMA_VERSION` at analysis time
+    #[serde(default)]
+    pub report_schema_version: u32,
     /// Analysis metadata
     pub metadata: UpgradeAnalysisMetadata,
     /// Information about the upgrade
@@ -247,6 +313,12 @@ pub struct UpgradeAnalysisMetadata {
     pub spy_trials_per_level: usize,
     /// Gap thresholds (ms) used for multi-threshold stem length analysis
     pub fluff_gap_thresholds_ms: Vec<f64>,
+    /// Coverage percentiles timed per window
+    #[serde(default)]
+    pub coverage_percentiles: Vec<f64>,
+    /// Set when the report was passed through `--anonymize`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anonymization: Option<AnonymizationInfo>,
 }
 
 /// Overall assessment of upgrade impact
@@ -3,7 +3,8 @@
 
 use std::collections::HashMap;
 
-use super::super::time_window::*;
+use super::super::propagation::DEFAULT_COVERAGE_PERCENTILES;
+use super::super::stats::{calculate_stats, compare_samples, SampleComparison};
 use super::super::types::*;
 use super::metrics::FLUFF_GAP_THRESHOLDS_MS;
 
@@ -29,38 +30,18 @@ pub(super) fn create_period_summary(
     let total_txs: usize = windows.iter().map(|w| w.tx_count).sum();
 
     // Calculate per-visibility-level spy accuracy stats
-    // Determine number of visibility levels from the first window that has data
-    let num_levels = windows
-        .iter()
-        .filter_map(|w| w.spy_accuracy_by_visibility.as_ref())
-        .map(|v| v.len())
-        .next()
-        .unwrap_or(0);
-
-    let (mean_spy_by_vis, std_spy_by_vis) = if num_levels > 0 {
-        let mut means = Vec::with_capacity(num_levels);
-        let mut stds = Vec::with_capacity(num_levels);
-        for level_idx in 0..num_levels {
-            let values: Vec<Option<f64>> = windows
-                .iter()
-                .map(|w| {
-                    w.spy_accuracy_by_visibility
-                        .as_ref()
-                        .and_then(|v| v.get(level_idx).copied())
-                })
-                .collect();
-            let (m, s) = calculate_stats(&values);
-            means.push(m.unwrap_or(0.0));
-            stds.push(s.unwrap_or(0.0));
-        }
-        (Some(means), Some(stds))
-    } else {
-        (None, None)
-    };
+    let (mean_spy_by_vis, std_spy_by_vis) = per_index_stats(windows, |w| {
+        w.spy_accuracy_by_visibility
+            .as_ref()
+            .map(|v| v.iter().copied().map(Some).collect())
+    });
 
     let prop_values: Vec<Option<f64>> = windows.iter().map(|w| w.avg_propagation_ms).collect();
     let (mean_prop, std_prop) = calculate_stats(&prop_values);
 
+    let (mean_coverage, std_coverage) =
+        per_index_stats(windows, |w| w.coverage_ms_by_percentile.clone());
+
     let peer_values: Vec<Option<f64>> = windows.iter().map(|w| w.avg_peer_count).collect();
     let (mean_peer, std_peer) = calculate_stats(&peer_values);
 
@@ -71,33 +52,11 @@ pub(super) fn create_period_summary(
     let (mean_stem, std_stem) = calculate_stats(&stem_values);
 
     // Per-threshold stem length aggregation (mirrors spy accuracy pattern)
-    let num_thresholds = windows
-        .iter()
-        .filter_map(|w| w.stem_length_by_gap_threshold.as_ref())
-        .map(|v| v.len())
-        .next()
-        .unwrap_or(0);
-
-    let (mean_stem_by_threshold, std_stem_by_threshold) = if num_thresholds > 0 {
-        let mut means = Vec::with_capacity(num_thresholds);
-        let mut stds = Vec::with_capacity(num_thresholds);
-        for t_idx in 0..num_thresholds {
-            let values: Vec<Option<f64>> = windows
-                .iter()
-                .map(|w| {
-                    w.stem_length_by_gap_threshold
-                        .as_ref()
-                        .and_then(|v| v.get(t_idx).copied())
-                })
-                .collect();
-            let (m, s) = calculate_stats(&values);
-            means.push(m.unwrap_or(0.0));
-            stds.push(s.unwrap_or(0.0));
-        }
-        (Some(means), Some(stds))
-    } else {
-        (None, None)
-    };
+    let (mean_stem_by_threshold, std_stem_by_threshold) = per_index_stats(windows, |w| {
+        w.stem_length_by_gap_threshold
+            .as_ref()
+            .map(|v| v.iter().copied().map(Some).collect())
+    });
 
     // Bandwidth aggregation
     let total_bytes_sent: u64 = windows.iter().filter_map(|w| w.bytes_sent).sum();
@@ -118,12 +77,14 @@ pub(super) fn create_period_summary(
         total_txs,
         mean_spy_accuracy_by_visibility: mean_spy_by_vis,
         mean_propagation_ms: mean_prop,
+        mean_coverage_ms_by_percentile: mean_coverage,
         mean_peer_count: mean_peer,
         mean_gini: mean_gini,
         mean_stem_length: mean_stem,
         mean_stem_length_by_gap_threshold: mean_stem_by_threshold,
         std_spy_accuracy_by_visibility: std_spy_by_vis,
         std_propagation_ms: std_prop,
+        std_coverage_ms_by_percentile: std_coverage,
         std_peer_count: std_peer,
         std_gini: std_gini,
         std_stem_length: std_stem,
@@ -149,6 +110,38 @@ pub(super) fn create_period_summary(
     })
 }
 
+/// Mean and std dev of each entry of a per-window vector metric. The
+/// length comes from the first window that has the metric; windows
+/// without it, or without a given entry, are skipped for that entry.
+fn per_index_stats(
+    windows: &[&WindowedMetrics],
+    values: impl Fn(&WindowedMetrics) -> Option<Vec<Option<f64>>>,
+) -> (Option<Vec<f64>>, Option<Vec<f64>>) {
+    let per_window: Vec<Option<Vec<Option<f64>>>> = windows.iter().map(|w| values(w)).collect();
+    let len = per_window
+        .iter()
+        .flatten()
+        .map(|v| v.len())
+        .next()
+        .unwrap_or(0);
+    if len == 0 {
+        return (None, None);
+    }
+
+    let mut means = Vec::with_capacity(len);
+    let mut stds = Vec::with_capacity(len);
+    for idx in 0..len {
+        let column: Vec<Option<f64>> = per_window
+            .iter()
+            .map(|v| v.as_ref().and_then(|v| v.get(idx).copied().flatten()))
+            .collect();
+        let (m, s) = calculate_stats(&column);
+        means.push(m.unwrap_or(0.0));
+        stds.push(s.unwrap_or(0.0));
+    }
+    (Some(means), Some(stds))
+}
+
 /// Compare pre and post upgrade periods.
 pub(super) fn compare_periods(
     pre: &AggregatedMetrics,
@@ -164,31 +157,14 @@ pub(super) fn compare_periods(
                         post_samples: Vec<f64>,
                         higher_is_better: bool|
      -> MetricChange {
-        let absolute_change = post_v - pre_v;
-        let percent_change = if pre_v != 0.0 {
-            (absolute_change / pre_v) * 100.0
-        } else {
-            0.0
-        };
-
-        let p_value = welch_t_test(&pre_samples, &post_samples);
-        let significant = is_significant(p_value);
-
-        let impact = if !significant {
-            ChangeImpact::Neutral
-        } else if higher_is_better {
-            if absolute_change > 0.0 {
-                ChangeImpact::Positive
-            } else {
-                ChangeImpact::Negative
-            }
-        } else {
-            if absolute_change < 0.0 {
-                ChangeImpact::Positive
-            } else {
-                ChangeImpact::Negative
-            }
-        };
+        let SampleComparison {
+            absolute_change,
+            percent_change,
+            p_value,
+            significant,
+            impact,
+            confidence_interval,
+        } = compare_samples(pre_v, post_v, &pre_samples, &post_samples, higher_is_better);
 
         let interpretation = generate_interpretation(name, percent_change, significant, impact);
 
@@ -202,6 +178,7 @@ pub(super) fn compare_periods(
             statistically_significant: significant,
             interpretation,
             impact,
+            confidence_interval,
         }
     };
 
@@ -277,6 +254,25 @@ pub(super) fn compare_periods(
         changes.push(change);
     }
 
+    // Time to 90% coverage: Lower is better (faster network)
+    if let Some(idx) = DEFAULT_COVERAGE_PERCENTILES.iter().position(|&p| p == 90.0) {
+        let nth = |w: &WindowedMetrics| {
+            w.coverage_ms_by_percentile
+                .as_ref()
+                .and_then(|v| v.get(idx).copied().flatten())
+        };
+        let mean_at = |m: &AggregatedMetrics| {
+            m.mean_coverage_ms_by_percentile
+                .as_ref()
+                .and_then(|v| v.get(idx).copied())
+        };
+        if let Some(change) =
+            add_change("Time to 90% (ms)", mean_at(pre), mean_at(post), &nth, false)
+        {
+            changes.push(change);
+        }
+    }
+
     // Peer count: Higher is better (more connectivity)
     if let Some(change) = add_change(
         "Avg Peer Count",
@@ -399,6 +395,13 @@ fn generate_interpretation(
             direction,
             percent_change.abs()
         ),
+        name if name.starts_with("Time to ") => format!(
+            "Network speed {} - {} {} by {:.1}%",
+            impact_word,
+            name,
+            direction,
+            percent_change.abs()
+        ),
         "Avg Peer Count" => format!(
             "Connectivity {} - average peer count {} by {:.1}%",
             impact_word,
@@ -1,8 +1,11 @@
-//! Per-window metric computation: TX propagation, bandwidth, peer counts,
-//! synthetic spy accuracy, Gini coefficient, and Dandelion stem length.
+//! Per-window metric computation: TX propagation and coverage, bandwidth,
+//! peer counts, synthetic spy accuracy, Gini coefficient, Dandelion stem
+//! length, and block propagation.
 
 use std::collections::{HashMap, HashSet};
 
+use super::super::dandelion::reconstruct_path;
+use super::super::propagation::{coverage_times, DEFAULT_COVERAGE_PERCENTILES};
 use super::super::types::*;
 use super::windows::{BwRef, SpyTrialSets};
 
@@ -69,6 +72,8 @@ pub(super) fn calculate_window_metrics_fast(
     metrics.avg_propagation_ms = avg_prop;
     metrics.median_propagation_ms = median_prop;
     metrics.p95_propagation_ms = p95_prop;
+    metrics.coverage_ms_by_percentile =
+        calculate_coverage_for_window(window_txs, &tx_observations, ip_to_agent.len());
 
     // Pre-computed connection state
     metrics.avg_peer_count = avg_peer_count;
@@ -99,6 +104,61 @@ pub(super) fn calculate_window_metrics_fast(
     metrics
 }
 
+/// Fill each window's block propagation fields from the blocks first seen
+/// in it. Windows without blocks keep `None`.
+pub(super) fn add_block_metrics(
+    windows: &mut [WindowedMetrics],
+    per_block: &[BlockPropagation],
+    block_interval_secs: f64,
+) {
+    for metrics in windows {
+        let in_window: Vec<&BlockPropagation> = per_block
+            .iter()
+            .filter(|b| metrics.window.contains(b.first_seen_time))
+            .collect();
+        if in_window.is_empty() {
+            continue;
+        }
+        let to_90: Vec<f64> = in_window.iter().filter_map(|b| b.time_to_90_ms).collect();
+        metrics.blocks_seen = Some(in_window.len());
+        let expected = (metrics.window.end - metrics.window.start) / block_interval_secs;
+        metrics.block_rate_vs_target = (expected > 0.0).then(|| in_window.len() as f64 / expected);
+        metrics.avg_block_propagation_ms =
+            (!to_90.is_empty()).then(|| crate::analysis::stats::mean(&to_90));
+    }
+}
+
+/// Count the conflict sets first seen in each window. Windows without any
+/// keep `None`.
+pub(super) fn add_conflict_metrics(windows: &mut [WindowedMetrics], sets: &[ConflictSetAnalysis]) {
+    for metrics in windows {
+        let count = sets
+            .iter()
+            .filter_map(|s| s.first_seen)
+            .filter(|t| metrics.window.contains(*t))
+            .count();
+        if count > 0 {
+            metrics.conflict_count = Some(count);
+        }
+    }
+}
+
+/// Share of the send attempts in each window that succeeded. Windows
+/// without attempts keep `None`.
+pub(super) fn add_tx_success_metrics(windows: &mut [WindowedMetrics], attempts: &[TxAttempt]) {
+    for metrics in windows {
+        let (attempted, succeeded) = attempts
+            .iter()
+            .filter(|a| metrics.window.contains(a.timestamp))
+            .fold((0usize, 0usize), |(n, ok), a| {
+                (n + 1, ok + usize::from(a.outcome == TxOutcome::Success))
+            });
+        if attempted > 0 {
+            metrics.tx_success_rate = Some(succeeded as f64 / attempted as f64);
+        }
+    }
+}
+
 /// Calculate bandwidth metrics from a pre-windowed slice of bandwidth events.
 fn calculate_bandwidth_from_slice(bw_slice: &[BwRef]) -> (u64, u64, u64) {
     let mut bytes_sent: u64 = 0;
@@ -111,7 +171,7 @@ fn calculate_bandwidth_from_slice(bw_slice: &[BwRef]) -> (u64, u64, u64) {
         } else {
             bytes_received += bw.event.bytes;
         }
-        message_count += 1;
+        message_count += bw.event.messages;
     }
 
     (bytes_sent, bytes_received, message_count)
@@ -239,6 +299,38 @@ fn calculate_propagation_for_window(
     (Some(avg), Some(median), Some(p95))
 }
 
+/// Median time to each coverage percentile over the window's transactions
+/// (see [`DEFAULT_COVERAGE_PERCENTILES`]).
+fn calculate_coverage_for_window(
+    transactions: &[&Transaction],
+    tx_observations: &HashMap<String, Vec<&TxObservation>>,
+    total_nodes: usize,
+) -> Option<Vec<Option<f64>>> {
+    let per_tx: Vec<Vec<CoveragePoint>> = transactions
+        .iter()
+        .filter_map(|tx| {
+            let observations = tx_observations.get(&tx.tx_hash)?;
+            Some(coverage_times(
+                tx,
+                observations,
+                total_nodes,
+                &DEFAULT_COVERAGE_PERCENTILES,
+            ))
+        })
+        .collect();
+    if per_tx.is_empty() {
+        return None;
+    }
+
+    let medians = (0..DEFAULT_COVERAGE_PERCENTILES.len())
+        .map(|i| {
+            let times: Vec<f64> = per_tx.iter().filter_map(|c| c[i].time_ms).collect();
+            (!times.is_empty()).then(|| crate::analysis::stats::median(&times))
+        })
+        .collect();
+    Some(medians)
+}
+
 /// Calculate Gini coefficient for first-seen distribution.
 fn calculate_gini_for_window(
     tx_observations: &HashMap<String, Vec<&TxObservation>>,
@@ -272,79 +364,6 @@ fn calculate_gini_for_window(
     Some(crate::analysis::stats::gini(&values))
 }
 
-/// Trace stem length for a single TX with a given fluff gap threshold.
-///
-/// Walks the chain of relays starting from the originator. At each step, if 3+
-/// recipients are found from the current sender, checks whether the time gap
-/// between the 1st and 3rd observation is within `threshold_ms`. If so, it's
-/// a genuine fluff broadcast and the chain stops. Otherwise, the first
-/// observation is a stem relay (the rest are later gossip re-relays).
-fn trace_stem_with_threshold(
-    sorted_obs: &[&TxObservation],
-    originator_ip: &str,
-    node_to_ip: &HashMap<&str, &str>,
-    threshold_ms: f64,
-) -> usize {
-    let mut stem_length = 0usize;
-    let mut used: HashSet<usize> = HashSet::new();
-
-    // Find first observation from originator
-    let first_hop_idx = sorted_obs
-        .iter()
-        .enumerate()
-        .find(|(_, obs)| obs.source_ip == originator_ip)
-        .map(|(i, _)| i);
-
-    if let Some(idx) = first_hop_idx {
-        used.insert(idx);
-        stem_length = 1;
-
-        let mut current_sender_ip = node_to_ip.get(sorted_obs[idx].node_id.as_str()).copied();
-
-        for _ in 0..50 {
-            if current_sender_ip.is_none() {
-                break;
-            }
-
-            let sender_ip = current_sender_ip
-                .expect("invariant: current_sender_ip.is_none() handled by break above");
-
-            let from_current: Vec<(usize, &TxObservation)> = sorted_obs
-                .iter()
-                .enumerate()
-                .filter(|(i, obs)| !used.contains(i) && obs.source_ip == sender_ip)
-                .map(|(i, obs)| (i, *obs))
-                .collect();
-
-            if from_current.is_empty() {
-                break;
-            }
-
-            // Check for fluff (3+ recipients with clustered timing)
-            if from_current.len() >= 3 {
-                let first_time = from_current[0].1.timestamp;
-                let third_time = from_current[2].1.timestamp;
-                let gap_ms = (third_time - first_time) * 1000.0;
-
-                if gap_ms <= threshold_ms {
-                    // Genuine fluff broadcast -> stop
-                    break;
-                }
-                // Large gap -> stem relay + later gossip, fall through
-            }
-
-            // Take first recipient as next stem hop
-            let (next_idx, next_obs) = from_current[0];
-            used.insert(next_idx);
-            stem_length += 1;
-
-            current_sender_ip = node_to_ip.get(next_obs.node_id.as_str()).copied();
-        }
-    }
-
-    stem_length
-}
-
 /// Calculate simplified Dandelion metrics for a window with multi-threshold gap detection.
 fn calculate_dandelion_for_window(
     transactions: &[&Transaction],
@@ -352,7 +371,11 @@ fn calculate_dandelion_for_window(
     ip_to_agent: &HashMap<&str, &AnalysisAgentInfo>,
     gap_thresholds_ms: &[f64],
 ) -> (Option<f64>, usize, Option<Vec<f64>>) {
-    // Build node_to_ip map once
+    // Build the address maps once
+    let ip_to_node: HashMap<&str, &str> = ip_to_agent
+        .iter()
+        .map(|(ip, agent)| (*ip, agent.id.as_str()))
+        .collect();
     let node_to_ip: HashMap<&str, &str> = ip_to_agent
         .iter()
         .map(|(ip, agent)| (agent.id.as_str(), *ip))
@@ -362,48 +385,27 @@ fn calculate_dandelion_for_window(
     let mut paths_count = 0usize;
 
     for tx in transactions {
-        if let Some(observations) = tx_observations.get(&tx.tx_hash) {
-            if observations.is_empty() {
-                continue;
-            }
-
-            let originator_ip = ip_to_agent
-                .iter()
-                .find(|(_, agent)| agent.id == tx.sender_id)
-                .map(|(ip, _)| *ip);
-
-            if originator_ip.is_none() {
-                continue;
-            }
-
-            let originator_ip = originator_ip
-                .expect("invariant: originator_ip.is_none() handled by continue above");
-
-            // Sort observations once
-            let mut sorted_obs: Vec<&TxObservation> = observations.iter().copied().collect();
-            sorted_obs.sort_by(|a, b| {
-                a.timestamp
-                    .partial_cmp(&b.timestamp)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            });
-
-            let mut any_positive = false;
-            for (t_idx, &threshold_ms) in gap_thresholds_ms.iter().enumerate() {
-                let stem_len = trace_stem_with_threshold(
-                    &sorted_obs,
-                    originator_ip,
-                    &node_to_ip,
-                    threshold_ms,
-                );
-                if stem_len > 0 {
-                    per_threshold_lengths[t_idx].push(stem_len as f64);
-                    any_positive = true;
-                }
+        let Some(observations) = tx_observations.get(&tx.tx_hash) else {
+            continue;
+        };
+
+        let mut any_positive = false;
+        for (t_idx, &threshold_ms) in gap_thresholds_ms.iter().enumerate() {
+            let detection = FluffDetection {
+                gap_ms: threshold_ms,
+                ..FluffDetection::default()
+            };
+            // Only paths that start at the originator count
+            let path = reconstruct_path(tx, observations, &ip_to_node, &node_to_ip, detection)
+                .filter(|p| p.originator_confirmed);
+            if let Some(path) = path {
+                per_threshold_lengths[t_idx].push(path.stem_length as f64);
+                any_positive = true;
             }
+        }
 
-            if any_positive {
-                paths_count += 1;
-            }
+        if any_positive {
+            paths_count += 1;
         }
     }
 
@@ -8,9 +8,11 @@
 //! - `windows`: pre-partitioning of TX observations, bandwidth events, and
 //!   connection state into per-window slices, plus the synthetic-spy trial
 //!   sets shared across the parallel window pipeline.
-//! - `metrics`: per-window metric computation (TX propagation, bandwidth,
-//!   peer counts, synthetic spy accuracy, Gini coefficient, Dandelion stem
-//!   length).
+//! - `metrics`: per-window metric computation (TX propagation and
+//!   coverage, bandwidth, peer counts, synthetic spy accuracy, Gini
+//!   coefficient, Dandelion stem length, block propagation, conflict
+//!   sets, agent send success rate). Monitor
+//!   height lag and pool size are merged in by `monitor_ingest`.
 //! - `assembly`: per-period aggregation, pre-vs-post comparison, and
 //!   overall-assessment generation.
 //!
@@ -29,11 +31,15 @@ use chrono::Utc;
 use color_eyre::eyre::Result;
 use rayon::prelude::*;
 
+use super::schema::REPORT_SCHEMA_VERSION;
 use super::time_window::*;
 use super::types::*;
 
 use assembly::{compare_periods, create_period_summary, generate_assessment};
-use metrics::{calculate_window_metrics_fast, FLUFF_GAP_THRESHOLDS_MS};
+use metrics::{
+    add_block_metrics, add_conflict_metrics, add_tx_success_metrics, calculate_window_metrics_fast,
+    FLUFF_GAP_THRESHOLDS_MS,
+};
 use windows::{build_spy_trial_sets, prepartition_data};
 
 /// Configuration for upgrade analysis
@@ -47,6 +53,15 @@ pub struct UpgradeAnalysisConfig {
     pub pre_upgrade_end: Option<SimTime>,
     /// Manual override: start of post-upgrade period
     pub post_upgrade_start: Option<SimTime>,
+    /// Optional simulation monitor time-series file to merge into the windows
+    pub monitor_path: Option<String>,
+    /// Optional `conflicts.json` whose sets are counted per window
+    pub conflicts_path: Option<String>,
+    /// Optional `tx_attempts.jsonl` whose success rate is taken per window
+    pub tx_attempts_path: Option<String>,
+    /// Target seconds between blocks (the manifest's `block_interval`),
+    /// against which each window's block rate is measured
+    pub block_interval_secs: f64,
 }
 
 impl Default for UpgradeAnalysisConfig {
@@ -56,6 +71,10 @@ impl Default for UpgradeAnalysisConfig {
             manifest_path: None,
             pre_upgrade_end: None,
             post_upgrade_start: None,
+            monitor_path: None,
+            conflicts_path: None,
+            tx_attempts_path: None,
+            block_interval_secs: crate::TARGET_BLOCK_INTERVAL_SECS as f64,
         }
     }
 }
@@ -65,7 +84,7 @@ pub fn analyze_upgrade_impact(
     transactions: &[Transaction],
     log_data: &HashMap<String, NodeLogData>,
     agents: &[AnalysisAgentInfo],
-    _blocks: &[BlockInfo],
+    blocks: &[BlockInfo],
     config: &UpgradeAnalysisConfig,
     data_dir: &str,
 ) -> Result<UpgradeAnalysisReport> {
@@ -143,7 +162,7 @@ pub fn analyze_upgrade_impact(
         build_spy_trial_sets(&node_ids, SPY_VISIBILITY_LEVELS, SPY_TRIALS_PER_LEVEL, 42);
 
     // Process all windows in parallel using rayon
-    let windowed_metrics: Vec<WindowedMetrics> = windows
+    let mut windowed_metrics: Vec<WindowedMetrics> = windows
         .par_iter()
         .enumerate()
         .map(|(i, window)| {
@@ -173,6 +192,41 @@ pub fn analyze_upgrade_impact(
         })
         .collect();
 
+    // Block propagation, attributed to the window each block was first seen in
+    let block_report = super::analyze_block_propagation(
+        blocks,
+        log_data,
+        &HashMap::new(),
+        config.block_interval_secs,
+    );
+    add_block_metrics(
+        &mut windowed_metrics,
+        &block_report.per_block,
+        config.block_interval_secs,
+    );
+
+    // Conflict sets, attributed to the window each was first seen in
+    if let Some(ref path) = config.conflicts_path {
+        let sets = super::load_conflict_sets(Path::new(path))?;
+        log::info!("Counting {} conflict sets from {}", sets.len(), path);
+        let conflicts = super::analyze_conflicts(&sets, transactions, blocks, log_data, agents);
+        add_conflict_metrics(&mut windowed_metrics, &conflicts.sets);
+    }
+
+    // Agent send attempts, by the window each was made in
+    if let Some(ref path) = config.tx_attempts_path {
+        let attempts = super::load_tx_attempts(Path::new(path))?;
+        log::info!("Counting {} send attempts from {}", attempts.len(), path);
+        add_tx_success_metrics(&mut windowed_metrics, &attempts);
+    }
+
+    // Height lag and pool size from the simulation monitor, if it recorded them
+    if let Some(ref path) = config.monitor_path {
+        let samples = super::load_monitor_samples(Path::new(path))?;
+        log::info!("Merging {} monitor samples from {}", samples.len(), path);
+        super::merge_into_windows(&mut windowed_metrics, &samples);
+    }
+
     // Aggregate by period label
     let by_label = aggregate_windows_by_label(&windowed_metrics);
 
@@ -203,9 +257,12 @@ pub fn analyze_upgrade_impact(
         spy_visibility_levels: SPY_VISIBILITY_LEVELS.to_vec(),
         spy_trials_per_level: SPY_TRIALS_PER_LEVEL,
         fluff_gap_thresholds_ms: FLUFF_GAP_THRESHOLDS_MS.to_vec(),
+        coverage_percentiles: super::DEFAULT_COVERAGE_PERCENTILES.to_vec(),
+        anonymization: None,
     };
 
     Ok(UpgradeAnalysisReport {
+        report_schema_version: REPORT_SCHEMA_VERSION,
         metadata,
         upgrade_info: manifest,
         time_series: windowed_metrics,
@@ -0,0 +1,105 @@
+//! `monerosim-healthcheck`: wait for a daemon or wallet RPC to answer.
+//!
+//! Run from agent wrapper scripts inside Shadow. Exits 0 once the RPC
+//! answers `--method` with `--expect-json-field` present, or with
+//! 3 (timeout), 4 (connection refused) or 5 (bad response) after the last
+//! retry. `--report` writes the outcome as JSON for `tx-analyzer summary`.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::Parser;
+use monerosim::healthcheck::{wait_until_ready, HealthcheckOptions};
+
+#[derive(Parser)]
+#[command(name = "monerosim-healthcheck")]
+#[command(about = "Wait for a Monero daemon or wallet RPC to become ready")]
+struct Cli {
+    /// RPC endpoint, e.g. http://11.0.0.1:18081/json_rpc
+    #[arg(long)]
+    url: String,
+
+    /// JSON-RPC method to call (get_info for a daemon, get_version for a wallet)
+    #[arg(long, default_value = "get_info")]
+    method: String,
+
+    /// Per-attempt timeout in seconds
+    #[arg(long, default_value = "1", value_parser = parse_seconds)]
+    timeout: Duration,
+
+    /// Attempts after the first
+    #[arg(long, default_value = "60")]
+    retries: u32,
+
+    /// Seconds between attempts
+    #[arg(long, default_value = "1", value_parser = parse_seconds)]
+    interval: Duration,
+
+    /// Dotted path that must be present in the reply, e.g. result.height
+    #[arg(long)]
+    expect_json_field: Option<String>,
+
+    /// Agent id recorded in the report
+    #[arg(long, default_value = "")]
+    agent: String,
+
+    /// What is checked, recorded in the report (daemon or wallet)
+    #[arg(long, default_value = "daemon")]
+    target: String,
+
+    /// Write the outcome as JSON to this file
+    #[arg(long)]
+    report: Option<PathBuf>,
+}
+
+/// Parse a non-negative, finite number of seconds
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    s.trim()
+        .parse::<f64>()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| format!("invalid seconds '{}' (expected a non-negative number)", s))
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let options = HealthcheckOptions {
+        url: cli.url,
+        method: cli.method,
+        timeout: cli.timeout,
+        retries: cli.retries,
+        interval: cli.interval,
+        expect_json_field: cli.expect_json_field,
+    };
+    let record = wait_until_ready(&options, &cli.agent, &cli.target);
+
+    if let Some(path) = &cli.report {
+        let written = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| Ok(serde_json::to_string_pretty(&record)?))
+            .and_then(|json| std::fs::write(path, json));
+        if let Err(e) = written {
+            eprintln!("failed to write {}: {}", path.display(), e);
+        }
+    }
+
+    match &record.error {
+        None => println!(
+            "{} {} ready after {:.1}s ({} attempt(s))",
+            record.target, record.url, record.elapsed_sec, record.attempts
+        ),
+        Some(error) => {
+            eprintln!(
+                "{} {} not ready after {:.1}s ({} attempt(s)): {}: {}",
+                record.target,
+                record.url,
+                record.elapsed_sec,
+                record.attempts,
+                record.status,
+                error
+            );
+            std::process::exit(record.exit_code());
+        }
+    }
+}
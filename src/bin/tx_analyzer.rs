@@ -8,15 +8,19 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use clap::{Parser, Subcommand};
-use color_eyre::eyre::{Context, Result};
+use color_eyre::eyre::{eyre, Context, Result};
+use rayon::prelude::*;
 
 use monerosim::analysis::{
     self,
-    types::{
-        AnalysisAgentInfo, AnalysisMetadata, BlockInfo, FullAnalysisReport, NodeLogData,
-        Transaction,
-    },
+    anonymize::{Anonymize, Anonymizer},
+    types::{AnalysisAgentInfo, AnalysisMetadata, BlockInfo, FullAnalysisReport, Transaction},
 };
+use monerosim::manifest::SimulationManifest;
+use monerosim::topology::intended::load_intended_topology;
+use monerosim::topology::latency::load_latency_matrix;
+use monerosim::topology::ScheduledNetworkEvent;
+use monerosim::utils::duration::SimDuration;
 
 #[derive(Parser)]
 #[command(name = "tx-analyzer")]
@@ -38,6 +42,7 @@ struct Cli {
 
     /// Path to shared data directory.
     /// Defaults to `MONEROSIM_SHARED_DIR` env var (or `/tmp/monerosim_shared` if unset).
+    /// Registries missing here are read from the manifest's directory.
     #[arg(short, long, default_value_os_t = PathBuf::from(monerosim::shared_dir()))]
     shared_dir: PathBuf,
 
@@ -53,9 +58,118 @@ struct Cli {
     #[arg(short = 'j', long, default_value = "0")]
     threads: usize,
 
-    /// Disable parsed log cache (force re-parse from raw logs)
+    /// Disable the per-host parsed log cache in <data-dir>/parsed_logs/
+    /// (force re-parse from raw logs)
     #[arg(long)]
     no_cache: bool,
+
+    /// Index the logs, then parse one node at a time instead of holding
+    /// every node's events (propagation and bandwidth only; skips the cache)
+    #[arg(long)]
+    low_memory: bool,
+
+    /// Keep one in N bandwidth log entries per category and direction; the
+    /// bandwidth reports scale what is kept by N (1 = keep all)
+    #[arg(long, default_value = "1")]
+    sample_bandwidth: u64,
+
+    /// Sum bandwidth log entries into windows of this length while parsing
+    /// (e.g. 10s, 1m); totals stay exact, time series get this resolution
+    #[arg(long)]
+    aggregate_bandwidth: Option<SimDuration>,
+
+    /// Event types not to parse, comma-separated (bandwidth, connections);
+    /// analyses of them then find none
+    #[arg(long, value_delimiter = ',')]
+    skip_events: Vec<analysis::EventKind>,
+
+    /// Scrub reports for publication: pseudonymize agent ids, drop IPs,
+    /// coarsen timestamps and add noise to timing metrics (applied to JSON,
+    /// text and stdout output alike)
+    #[arg(long)]
+    anonymize: bool,
+
+    /// Seed for --anonymize pseudonyms (same seed => same pseudonyms)
+    #[arg(long, default_value = "0")]
+    anonymize_seed: u64,
+
+    /// Timestamp granularity in seconds for --anonymize (0 = keep exact)
+    #[arg(long, default_value = "1.0")]
+    anonymize_granularity: f64,
+
+    /// Largest relative noise --anonymize adds to durations and latencies,
+    /// in [0, 1) (0 = keep exact)
+    #[arg(long, default_value = "0.05")]
+    anonymize_noise: f64,
+
+    /// Also write the per-transaction, per-node and per-window datasets as
+    /// flat tables: json (no tables), csv, or parquet (needs a build with
+    /// `--features parquet`)
+    #[arg(long, default_value = "json")]
+    format: analysis::report::ReportFormat,
+
+    /// Analyze artifacts even if they come from an incompatible monerosim
+    #[arg(long)]
+    ignore_compat: bool,
+
+    /// Generation manifest to embed in report metadata (skipped if missing)
+    #[arg(long, default_value = "shadow_output/manifest.json")]
+    manifest: PathBuf,
+}
+
+impl Cli {
+    /// What the parser keeps, from --sample-bandwidth, --aggregate-bandwidth
+    /// and --skip-events
+    fn log_reduction(&self) -> Result<analysis::LogReduction> {
+        let aggregate_bandwidth_sec = match self.aggregate_bandwidth {
+            Some(window) => {
+                let seconds = window.as_secs();
+                if seconds == 0 {
+                    return Err(eyre!("--aggregate-bandwidth must be at least 1s"));
+                }
+                Some(seconds as f64)
+            }
+            None => None,
+        };
+        Ok(analysis::LogReduction {
+            sample_bandwidth: self.sample_bandwidth,
+            aggregate_bandwidth_sec,
+            skip: self.skip_events.iter().copied().collect(),
+        })
+    }
+}
+
+/// One run for `compare`: `<data_dir>:<shared_dir>:<label>`
+#[derive(Debug, Clone)]
+struct RunSpec {
+    data_dir: PathBuf,
+    shared_dir: PathBuf,
+    label: String,
+}
+
+impl std::str::FromStr for RunSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Split from the right so only the label and shared dir must be
+        // colon-free
+        let mut parts = s.rsplitn(3, ':');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(label), Some(shared_dir), Some(data_dir))
+                if !label.is_empty() && !shared_dir.is_empty() && !data_dir.is_empty() =>
+            {
+                Ok(RunSpec {
+                    data_dir: PathBuf::from(data_dir),
+                    shared_dir: PathBuf::from(shared_dir),
+                    label: label.to_string(),
+                })
+            }
+            _ => Err(format!(
+                "invalid run '{}' (expected <data_dir>:<shared_dir>:<label>)",
+                s
+            )),
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -73,6 +187,11 @@ enum Commands {
         /// Skip resilience analysis
         #[arg(long)]
         no_resilience: bool,
+
+        /// Leave hop pairs between agents on the same GML node out of the
+        /// propagation hop latency aggregates
+        #[arg(long)]
+        exclude_intra_node: bool,
     },
 
     /// Analyze spy node vulnerability only
@@ -80,6 +199,15 @@ enum Commands {
         /// Minimum confidence threshold for reporting
         #[arg(long, default_value = "0.5")]
         min_confidence: f64,
+
+        /// Only use these agents' logs as the spy coalition (comma-separated
+        /// agent ids)
+        #[arg(long, value_delimiter = ',', conflicts_with = "spy_role")]
+        spy_nodes: Vec<String>,
+
+        /// Only use the logs of agents the registry marks with role `spy`
+        #[arg(long)]
+        spy_role: bool,
     },
 
     /// Analyze propagation timing only
@@ -87,6 +215,27 @@ enum Commands {
         /// Include per-transaction details in output
         #[arg(long)]
         detailed: bool,
+
+        /// Leave hop pairs between agents on the same GML node out of the
+        /// hop latency aggregates
+        #[arg(long)]
+        exclude_intra_node: bool,
+
+        /// Coverage percentiles to time: how long until that share of the
+        /// daemons had seen each transaction
+        #[arg(long, value_delimiter = ',', default_value = "10,25,50,75,90,99")]
+        percentiles: Vec<f64>,
+
+        /// Report each hop's delay over the topology's minimum from the
+        /// generator's latency_matrix.json (in the output directory)
+        #[arg(long, value_name = "PATH")]
+        latency_matrix: Option<PathBuf>,
+
+        /// Break relay delays down by the registry's continent and AS:
+        /// intra vs inter-region medians, delay matrices and AS-boundary
+        /// bottlenecks
+        #[arg(long)]
+        by_region: bool,
     },
 
     /// Analyze network resilience only
@@ -119,6 +268,15 @@ enum Commands {
         /// Only show transactions with stem length <= N (privacy concerns)
         #[arg(long)]
         short_stems: Option<usize>,
+
+        /// A sender's deliveries closer together than this are a fluff
+        /// broadcast rather than a stem relay followed by gossip
+        #[arg(long, default_value = "2000")]
+        fluff_gap_ms: f64,
+
+        /// Deliveries from one sender needed to call it a fluff broadcast
+        #[arg(long, default_value = "3")]
+        min_fluff_recipients: usize,
     },
 
     /// Analyze network P2P topology and connection patterns
@@ -127,9 +285,15 @@ enum Commands {
         #[arg(long)]
         dot: bool,
 
-        /// Expected max outbound connections (default: 8 for Monero)
+        /// Expected max outbound connections for nodes whose registry
+        /// entry has no `out_peers` (older generators)
         #[arg(long, default_value = "8")]
         expected_outbound: usize,
+
+        /// Diff observed edges against the generator's intended_topology.json
+        /// (in the shared directory)
+        #[arg(long, value_name = "PATH")]
+        compare_intended: Option<PathBuf>,
     },
 
     /// Analyze upgrade impact by comparing metrics across time windows
@@ -149,6 +313,11 @@ enum Commands {
         /// Manual override: start of post-upgrade period (simulation time in seconds)
         #[arg(long)]
         post_upgrade_start: Option<f64>,
+
+        /// Simulation monitor time-series file to merge into the windows
+        /// (default: monitor_metrics.jsonl or .csv in the shared directory)
+        #[arg(long)]
+        monitor: Option<PathBuf>,
     },
 
     /// Analyze bandwidth and data usage
@@ -165,9 +334,159 @@ enum Commands {
         #[arg(long)]
         time_series: Option<u64>,
 
-        /// Show top N nodes by bandwidth
+        /// Show top N nodes (and node pairs, with --matrix) by bandwidth
         #[arg(long, default_value = "10")]
         top: usize,
+
+        /// Also write the node-to-node traffic matrix
+        /// (bandwidth_matrix.json and .csv)
+        #[arg(long)]
+        matrix: bool,
+    },
+
+    /// Analyze block propagation, competing blocks and miner attribution
+    Blocks {
+        /// Show per-block timing
+        #[arg(long)]
+        detailed: bool,
+    },
+
+    /// Analyze whether the daemons' mempools converge over time
+    Mempool {
+        /// Size of each time window in seconds
+        #[arg(long, default_value = "60")]
+        window_size: u64,
+    },
+
+    /// Analyze conflicting transactions (double spends) from conflicts.json
+    Conflicts {
+        /// Conflict sets file (default: conflicts.json in the shared directory)
+        #[arg(long)]
+        file: Option<PathBuf>,
+
+        /// Show every daemon's first-seen order
+        #[arg(long)]
+        detailed: bool,
+    },
+
+    /// Analyze how often agents' sends succeed, from tx_attempts.jsonl or
+    /// the agents' logs
+    TxSuccess {
+        /// Attempts file (default: tx_attempts.jsonl in the shared
+        /// directory, else the agent logs under --log-dir or
+        /// shadow.data/hosts)
+        #[arg(long)]
+        file: Option<PathBuf>,
+
+        /// Show every agent
+        #[arg(long)]
+        detailed: bool,
+    },
+
+    /// Summarize the simulation monitor's time-series file
+    Monitor {
+        /// Time-series file (default: monitor_metrics.jsonl or .csv in the
+        /// shared directory)
+        #[arg(long)]
+        file: Option<PathBuf>,
+
+        /// Size of each time window in seconds
+        #[arg(long, default_value = "60")]
+        window_size: u64,
+    },
+
+    /// Follow one transaction hop by hop through the logs
+    Trace {
+        /// Transaction hash, or an unambiguous prefix of one
+        #[arg(long)]
+        tx_hash: String,
+    },
+
+    /// List the pcap traces of agents with capture_pcap, per agent
+    Pcaps {
+        /// Where the traces were collected (default: the manifest's
+        /// general.pcap_capture_dir, under the data directory's parent
+        /// when relative)
+        #[arg(long)]
+        capture_dir: Option<PathBuf>,
+    },
+
+    /// Compare two or more runs side by side, each against the first
+    Compare {
+        /// A run as <data_dir>:<shared_dir>:<label>; repeat for each run,
+        /// baseline first
+        #[arg(long = "run", required = true)]
+        runs: Vec<RunSpec>,
+
+        /// Analyses to run on each: propagation, bandwidth, spy, dandelion
+        #[arg(
+            long,
+            value_delimiter = ',',
+            default_value = "propagation,bandwidth,spy,dandelion"
+        )]
+        analyses: Vec<analysis::CompareAnalysis>,
+    },
+
+    /// Analyze every run of a parameter sweep and tabulate their headline
+    /// metrics against the swept parameters
+    Sweep {
+        /// Sweep root holding sweep_index.json (each variant directory
+        /// holding its run), or a directory of run directories
+        #[arg(long)]
+        runs_root: PathBuf,
+
+        /// Analyses to run on each: propagation, spy, bandwidth, health
+        #[arg(
+            long,
+            value_delimiter = ',',
+            default_value = "propagation,spy,bandwidth,health"
+        )]
+        analyses: Vec<analysis::SweepAnalysis>,
+
+        /// Fraction of the daemons in the random spy coalitions behind
+        /// spy_accuracy_at_<P>pct
+        #[arg(long, default_value = "0.2")]
+        spy_visibility: f64,
+
+        /// Runs analyzed at once; each holds its parsed logs in memory
+        #[arg(long, default_value = "2")]
+        parallel_runs: usize,
+    },
+
+    /// Generate a fake but consistent dataset (registry, transactions,
+    /// blocks, daemon logs) to try the other commands without Shadow
+    Synth {
+        /// Number of agents, miners included
+        #[arg(long, default_value = "20")]
+        agents: usize,
+
+        /// Number of transactions
+        #[arg(long, default_value = "500")]
+        txs: usize,
+
+        /// RNG seed (same seed => identical dataset)
+        #[arg(long, default_value = "0")]
+        seed: u64,
+
+        /// Number of miners (default: a tenth of the agents)
+        #[arg(long)]
+        miners: Option<usize>,
+
+        /// Outbound connections per agent
+        #[arg(long, default_value = "4")]
+        out_peers: usize,
+
+        /// Per-hop relay delay: fixed:MS, uniform:MIN:MAX or exp:MEAN
+        #[arg(long, default_value = "uniform:50:250")]
+        hop_delay: analysis::DelayDistribution,
+
+        /// Skip Dandelion stems so first-seen timing exposes every sender
+        #[arg(long)]
+        spy_friendly: bool,
+
+        /// Directory to write shared/ and daemon_logs/ into
+        #[arg(long)]
+        out: PathBuf,
     },
 }
 
@@ -187,11 +506,305 @@ fn main() -> Result<()> {
             .context("Failed to configure thread pool")?;
     }
 
+    // Synth produces the inputs everything below reads, so it runs alone
+    if let Commands::Synth {
+        agents,
+        txs,
+        seed,
+        miners,
+        out_peers,
+        hop_delay,
+        spy_friendly,
+        ref out,
+    } = cli.command
+    {
+        let options = analysis::SynthOptions {
+            agents,
+            txs,
+            seed,
+            miners,
+            out_peers,
+            hop_delay,
+            spy_friendly,
+        };
+        let run = analysis::generate_synthetic(&options);
+        analysis::write_dataset(&run, seed, out)?;
+        println!(
+            "Wrote {} agents, {} transactions, {} blocks to {}",
+            run.agents.len(),
+            run.transactions.len(),
+            run.blocks.len(),
+            out.display()
+        );
+        println!(
+            "Analyze with: tx-analyzer -d {0} -s {0}/shared -l {0}/daemon_logs -o {0}/analysis full",
+            out.display()
+        );
+        return Ok(());
+    }
+
+    // Compare loads its own runs instead of the global data directories
+    if let Commands::Compare {
+        ref runs,
+        ref analyses,
+    } = cli.command
+    {
+        if runs.len() < 2 {
+            return Err(eyre!("compare needs at least two --run specs"));
+        }
+        let mut labels: Vec<&str> = runs.iter().map(|r| r.label.as_str()).collect();
+        labels.sort_unstable();
+        if let Some(w) = labels.windows(2).find(|w| w[0] == w[1]) {
+            return Err(eyre!("run label '{}' is used more than once", w[0]));
+        }
+
+        let mut summaries = Vec::new();
+        for run in runs {
+            summaries.push(summarize_compare_run(run, analyses, &cli)?);
+        }
+        let report = analysis::compare_runs(summaries, analyses);
+
+        let text = format_comparison_report(&report);
+        print!("{}", text);
+
+        fs::create_dir_all(&cli.output).with_context(|| {
+            format!(
+                "Failed to create output directory: {}",
+                cli.output.display()
+            )
+        })?;
+        let json = serde_json::to_string_pretty(&report)?;
+        fs::write(cli.output.join("comparison_report.json"), &json)?;
+        fs::write(cli.output.join("comparison_report.txt"), &text)?;
+        log::info!(
+            "Comparison report written to {}",
+            cli.output.join("comparison_report.json").display()
+        );
+        return Ok(());
+    }
+
+    // Sweep walks its own runs, like Compare
+    if let Commands::Sweep {
+        ref runs_root,
+        ref analyses,
+        spy_visibility,
+        parallel_runs,
+    } = cli.command
+    {
+        if !(spy_visibility > 0.0 && spy_visibility <= 1.0) {
+            return Err(eyre!(
+                "--spy-visibility must be in (0, 1], got {}",
+                spy_visibility
+            ));
+        }
+        let runs = analysis::discover_sweep_runs(runs_root)?;
+        let reduction = cli.log_reduction()?;
+        log::info!(
+            "Analyzing {} runs from {}, {} at a time",
+            runs.len(),
+            runs_root.display(),
+            parallel_runs.max(1)
+        );
+        // Chunks bound how many runs hold their parsed logs at once
+        let results: Vec<analysis::types::SweepRunResult> = runs
+            .chunks(parallel_runs.max(1))
+            .flat_map(|chunk| {
+                chunk
+                    .par_iter()
+                    .map(|run| analyze_sweep_run(run, analyses, spy_visibility, &reduction, &cli))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let summary = analysis::summarize_sweep(runs_root, analyses, spy_visibility, results);
+
+        fs::create_dir_all(&cli.output).with_context(|| {
+            format!(
+                "Failed to create output directory: {}",
+                cli.output.display()
+            )
+        })?;
+        let json_path = cli
+            .output
+            .join(format!("{}.json", analysis::SWEEP_SUMMARY_FILE));
+        fs::write(&json_path, serde_json::to_string_pretty(&summary)?)?;
+        analysis::report::write_tables(
+            &[analysis::report::sweep_summary_table(&summary)],
+            &cli.output,
+            analysis::report::ReportFormat::Csv,
+        )?;
+        println!(
+            "Analyzed {} of {} runs ({} failed); summary in {}",
+            summary.succeeded,
+            summary.runs.len(),
+            summary.failed,
+            json_path.display()
+        );
+        for run in &summary.runs {
+            if let Some(error) = &run.error {
+                println!("  {}: {}", run.name, error);
+            }
+        }
+        if summary.succeeded == 0 {
+            return Err(eyre!(
+                "No run under {} could be analyzed",
+                runs_root.display()
+            ));
+        }
+        return Ok(());
+    }
+
+    // Pcaps only looks for files, so it needs neither registries nor logs
+    if let Commands::Pcaps { ref capture_dir } = cli.command {
+        let manifest = load_manifest(&cli.manifest);
+        let capture = manifest.as_ref().and_then(|m| m.pcap_capture.as_ref());
+        let capture_dir = capture_dir.clone().or_else(|| {
+            capture
+                .and_then(|c| c.capture_dir.as_deref())
+                .map(|dir| analysis::resolve_capture_dir(&cli.data_dir, dir))
+        });
+        let index = analysis::index_pcaps(&cli.data_dir, capture_dir.as_deref(), capture)?;
+        print_pcap_index(&index);
+
+        fs::create_dir_all(&cli.output).with_context(|| {
+            format!(
+                "Failed to create output directory: {}",
+                cli.output.display()
+            )
+        })?;
+        let path = cli.output.join(analysis::PCAP_INDEX_FILE);
+        fs::write(&path, serde_json::to_string_pretty(&index)?)?;
+        log::info!("Pcap index written to {}", path.display());
+        return Ok(());
+    }
+
+    let compat = analysis::check_artifacts(&cli.shared_dir);
+    match compat.level {
+        analysis::Compatibility::Supported => log::info!("{}", compat),
+        analysis::Compatibility::BestEffort => log::warn!("{}", compat),
+        analysis::Compatibility::Unsupported if cli.ignore_compat => log::warn!("{}", compat),
+        analysis::Compatibility::Unsupported => {
+            return Err(eyre!(
+                "{}\n(pass --ignore-compat to analyze anyway)",
+                compat
+            ));
+        }
+    }
+
     // Load data sources
     log::info!("Loading data from {}...", cli.shared_dir.display());
-    let agents = load_agent_registry(&cli.shared_dir)?;
+    let registry_dir = registry_dir(&cli.shared_dir, &cli.manifest);
+    let agents = load_agent_registry(&registry_dir)?;
     let transactions = load_transactions(&cli.shared_dir)?;
     let blocks = load_blocks(&cli.shared_dir)?;
+    let manifest = load_manifest(&cli.manifest);
+    let manifest = manifest.as_ref();
+    // Target block interval the simulation ran with
+    let block_interval_secs =
+        manifest.map_or(monerosim::TARGET_BLOCK_INTERVAL_SECS, |m| m.block_interval) as f64;
+    if !(0.0..1.0).contains(&cli.anonymize_noise) {
+        return Err(eyre!(
+            "--anonymize-noise must be in [0, 1), got {}",
+            cli.anonymize_noise
+        ));
+    }
+    let anonymizer = cli.anonymize.then(|| {
+        Anonymizer::new(
+            cli.anonymize_seed,
+            cli.anonymize_granularity,
+            cli.anonymize_noise,
+            &agents,
+        )
+    });
+    let anonymizer = anonymizer.as_ref();
+
+    // Monitor reads only its time-series file, so it skips the logs
+    if let Commands::Monitor {
+        ref file,
+        window_size,
+    } = cli.command
+    {
+        let path = file
+            .clone()
+            .or_else(|| analysis::find_metrics_file(&cli.shared_dir))
+            .ok_or_else(|| {
+                eyre!(
+                    "No monitor_metrics.jsonl or .csv in {}; set metrics or output_format \
+                     on the simulation monitor agent, or pass --file",
+                    cli.shared_dir.display()
+                )
+            })?;
+        let samples = analysis::load_monitor_samples(&path)?;
+        let mut report =
+            analysis::summarize_monitor(&samples, window_size as f64, &path.to_string_lossy());
+        scrub(&mut report, anonymizer);
+
+        print_monitor_report(&report);
+
+        fs::create_dir_all(&cli.output).with_context(|| {
+            format!(
+                "Failed to create output directory: {}",
+                cli.output.display()
+            )
+        })?;
+        let json = serde_json::to_string_pretty(&report)?;
+        fs::write(cli.output.join("monitor_report.json"), &json)?;
+        log::info!(
+            "Monitor report written to {}",
+            cli.output.join("monitor_report.json").display()
+        );
+        return Ok(());
+    }
+
+    // TxSuccess reads the agents' attempts, not the daemon logs
+    if let Commands::TxSuccess { ref file, detailed } = cli.command {
+        let path = file
+            .clone()
+            .unwrap_or_else(|| cli.shared_dir.join(analysis::TX_ATTEMPTS_FILE));
+        let (attempts, source) = if path.exists() {
+            (analysis::load_tx_attempts(&path)?, path)
+        } else if file.is_some() {
+            return Err(eyre!("No transaction attempts at {}", path.display()));
+        } else {
+            let hosts_dir = cli
+                .log_dir
+                .clone()
+                .unwrap_or_else(|| cli.data_dir.join("hosts"));
+            log::info!(
+                "No {}; reading attempts from the agent logs",
+                path.display()
+            );
+            (analysis::parse_agent_tx_logs(&hosts_dir)?, hosts_dir)
+        };
+        if attempts.is_empty() {
+            return Err(eyre!(
+                "No transaction attempts in {}; agents record them in {}",
+                source.display(),
+                analysis::TX_ATTEMPTS_FILE
+            ));
+        }
+        log::info!("Analyzing {} transaction attempts...", attempts.len());
+        let mut report = analysis::analyze_tx_success(&attempts, &source.to_string_lossy());
+        scrub(&mut report, anonymizer);
+
+        print_tx_success_report(&report, detailed);
+
+        fs::create_dir_all(&cli.output).with_context(|| {
+            format!(
+                "Failed to create output directory: {}",
+                cli.output.display()
+            )
+        })?;
+        let txt_path = cli.output.join("tx_success_report.txt");
+        fs::write(&txt_path, format_tx_success_report(&report, detailed))?;
+        log::info!("TX success text report written to {}", txt_path.display());
+        let json = serde_json::to_string_pretty(&report)?;
+        fs::write(cli.output.join("tx_success_report.json"), &json)?;
+        log::info!(
+            "TX success report written to {}",
+            cli.output.join("tx_success_report.json").display()
+        );
+        return Ok(());
+    }
 
     log::info!(
         "Loaded {} agents, {} transactions, {} blocks",
@@ -227,32 +840,42 @@ fn main() -> Result<()> {
         }
     };
 
-    // Parse logs (with caching)
-    let cache_path = cli.data_dir.join("parsed_logs.bincode");
+    // Parse logs (with caching), or only index them for --low-memory
+    let cache_dir = cli.data_dir.join("parsed_logs");
+    let reduction = cli.log_reduction()?;
+    if !reduction.is_exact() {
+        log::info!("Reducing parsed logs: {:?}", reduction);
+    }
     let start = std::time::Instant::now();
 
-    let log_data = if !cli.no_cache {
-        if let Some(cached) = try_load_cache(&cache_path, &log_dir) {
-            log::info!(
-                "Loaded parsed logs from cache in {:.1}s",
-                start.elapsed().as_secs_f64()
-            );
-            cached
-        } else {
-            log::info!("Parsing logs from {}...", log_dir.display());
-            let data = analysis::parse_all_logs(&log_dir, &agents)?;
-            log::info!("Parsed logs in {:.1}s", start.elapsed().as_secs_f64());
-            if let Err(e) = save_cache(&cache_path, &data) {
-                log::warn!("Failed to write cache: {}", e);
-            }
-            data
+    let log_index = if cli.low_memory {
+        if !matches!(
+            cli.command,
+            Commands::Propagation { .. } | Commands::Bandwidth { .. }
+        ) {
+            return Err(eyre!(
+                "--low-memory is only supported by the propagation and bandwidth commands"
+            ));
         }
+        let index = analysis::LogIndex::build(&log_dir, &agents, &reduction)?;
+        log::info!("Indexed logs in {:.1}s", start.elapsed().as_secs_f64());
+        Some(index)
+    } else {
+        None
+    };
+
+    let log_data = if log_index.is_some() {
+        HashMap::new()
+    } else if !cli.no_cache {
+        let data = analysis::parse_all_logs_cached(&log_dir, &agents, &cache_dir, &reduction)?;
+        log::info!("Loaded logs in {:.1}s", start.elapsed().as_secs_f64());
+        data
     } else {
         log::info!(
             "Parsing logs from {} (cache disabled)...",
             log_dir.display()
         );
-        let data = analysis::parse_all_logs(&log_dir, &agents)?;
+        let data = analysis::parse_all_logs_reduced(&log_dir, &agents, &reduction)?;
         log::info!(
             "Parsed logs in {:.1}s (cache disabled)",
             start.elapsed().as_secs_f64()
@@ -274,21 +897,55 @@ fn main() -> Result<()> {
             no_spy,
             no_propagation,
             no_resilience,
+            exclude_intra_node,
         } => {
-            run_full_analysis(
-                &cli.output,
-                &cli.data_dir,
+            let report = run_full_analysis(
+                FullAnalysisOptions {
+                    output_dir: &cli.output,
+                    data_dir: &cli.data_dir,
+                    manifest,
+                    run_spy: !no_spy,
+                    run_propagation: !no_propagation,
+                    run_resilience: !no_resilience,
+                    exclude_intra_node,
+                    anonymizer,
+                },
                 &transactions,
                 &blocks,
                 &log_data,
                 &agents,
-                !no_spy,
-                !no_propagation,
-                !no_resilience,
+            )?;
+            analysis::report::write_tables(
+                &analysis::report::full_report_tables(&report),
+                &cli.output,
+                cli.format,
             )?;
         }
-        Commands::SpyNode { min_confidence } => {
-            let spy_report = analysis::analyze_spy_vulnerability(&transactions, &log_data, &agents);
+        Commands::SpyNode {
+            min_confidence,
+            spy_nodes,
+            spy_role,
+        } => {
+            let monitors = if spy_role {
+                let spies = analysis::registry_spies(&agents);
+                if spies.is_empty() {
+                    return Err(eyre!("--spy-role: no agent in the registry has role `spy`"));
+                }
+                Some(spies)
+            } else if !spy_nodes.is_empty() {
+                if let Some(id) = spy_nodes.iter().find(|id| !log_data.contains_key(*id)) {
+                    return Err(eyre!("--spy-nodes: no daemon logs for agent '{}'", id));
+                }
+                Some(spy_nodes)
+            } else {
+                None
+            };
+            let spy_report = analysis::analyze_spy_vulnerability(
+                &transactions,
+                &log_data,
+                &agents,
+                monitors.as_deref(),
+            );
 
             // Filter by confidence if requested
             let filtered_report = if min_confidence > 0.0 {
@@ -301,38 +958,92 @@ fn main() -> Result<()> {
                 spy_report
             };
 
-            let report = FullAnalysisReport {
-                metadata: create_metadata(&cli.data_dir, &agents, &transactions, &blocks),
+            let mut report = FullAnalysisReport {
+                metadata: create_metadata(&cli.data_dir, manifest, &agents, &transactions, &blocks),
                 spy_node_analysis: Some(filtered_report),
                 propagation_analysis: None,
                 resilience_analysis: None,
             };
+            scrub(&mut report, anonymizer);
 
             analysis::generate_json_report(&report, &cli.output.join("spy_node_report.json"))?;
             analysis::generate_text_report(&report, &cli.output.join("spy_node_report.txt"))?;
-            analysis::report::print_summary(&report);
+            analysis::report::write_tables(
+                &analysis::report::full_report_tables(&report),
+                &cli.output,
+                cli.format,
+            )?;
+            analysis::report::log_summary(&report);
         }
-        Commands::Propagation { detailed } => {
-            let mut prop_report =
-                analysis::analyze_propagation(&transactions, &blocks, &log_data, agents.len());
+        Commands::Propagation {
+            detailed,
+            exclude_intra_node,
+            percentiles,
+            latency_matrix,
+            by_region,
+        } => {
+            if let Some(p) = percentiles.iter().find(|p| !(**p > 0.0 && **p <= 100.0)) {
+                return Err(eyre!("coverage percentile {} is not in (0, 100]", p));
+            }
+            let latency_matrix = latency_matrix
+                .map(|path| load_latency_matrix(&path))
+                .transpose()?;
+            let mut prop_report = match log_index {
+                Some(ref index) => analysis::analyze_propagation_streamed(
+                    &transactions,
+                    &blocks,
+                    index.nodes(),
+                    &agents,
+                    exclude_intra_node,
+                    &percentiles,
+                    latency_matrix.as_ref(),
+                ),
+                None => analysis::analyze_propagation(
+                    &transactions,
+                    &blocks,
+                    &log_data,
+                    &agents,
+                    exclude_intra_node,
+                    &percentiles,
+                    latency_matrix.as_ref(),
+                ),
+            };
 
             if !detailed {
                 prop_report.per_tx_analysis.clear();
+                if let Some(overhead) = prop_report.latency_overhead.as_mut() {
+                    overhead.per_tx.clear();
+                }
+            }
+            if !by_region {
+                prop_report.by_region = None;
+            } else if prop_report.by_region.is_none() {
+                log::warn!(
+                    "--by-region: the agent registry has no continent or as_number fields \
+                     (generated by an older monerosim?); skipping the region breakdown"
+                );
             }
 
-            let report = FullAnalysisReport {
-                metadata: create_metadata(&cli.data_dir, &agents, &transactions, &blocks),
+            let mut report = FullAnalysisReport {
+                metadata: create_metadata(&cli.data_dir, manifest, &agents, &transactions, &blocks),
                 spy_node_analysis: None,
                 propagation_analysis: Some(prop_report),
                 resilience_analysis: None,
             };
+            scrub(&mut report, anonymizer);
 
             analysis::generate_json_report(&report, &cli.output.join("propagation_report.json"))?;
             analysis::generate_text_report(&report, &cli.output.join("propagation_report.txt"))?;
-            analysis::report::print_summary(&report);
+            analysis::report::write_tables(
+                &analysis::report::full_report_tables(&report),
+                &cli.output,
+                cli.format,
+            )?;
+            analysis::report::log_summary(&report);
         }
         Commands::Resilience { export_graph } => {
-            let resilience_report = analysis::analyze_resilience(&log_data, &agents);
+            let resilience_report =
+                analysis::analyze_resilience(&log_data, &agents, network_events(manifest));
 
             if export_graph {
                 // Export connection graph
@@ -344,9 +1055,16 @@ fn main() -> Result<()> {
                             .connection_events
                             .iter()
                             .filter(|e| e.is_open)
-                            .map(|e| e.peer_ip.clone())
+                            .map(|e| match anonymizer {
+                                Some(a) => a.peer(&e.peer_ip),
+                                None => e.peer_ip.clone(),
+                            })
                             .collect();
-                        (node_id.clone(), peers)
+                        let node_id = match anonymizer {
+                            Some(a) => a.id(node_id),
+                            None => node_id.clone(),
+                        };
+                        (node_id, peers)
                     })
                     .collect();
 
@@ -355,16 +1073,17 @@ fn main() -> Result<()> {
                 log::info!("Network graph exported to {}", graph_path.display());
             }
 
-            let report = FullAnalysisReport {
-                metadata: create_metadata(&cli.data_dir, &agents, &transactions, &blocks),
+            let mut report = FullAnalysisReport {
+                metadata: create_metadata(&cli.data_dir, manifest, &agents, &transactions, &blocks),
                 spy_node_analysis: None,
                 propagation_analysis: None,
                 resilience_analysis: Some(resilience_report),
             };
+            scrub(&mut report, anonymizer);
 
             analysis::generate_json_report(&report, &cli.output.join("resilience_report.json"))?;
             analysis::generate_text_report(&report, &cli.output.join("resilience_report.txt"))?;
-            analysis::report::print_summary(&report);
+            analysis::report::log_summary(&report);
         }
         Commands::Summary => {
             // Quick summary without full analysis
@@ -407,6 +1126,20 @@ fn main() -> Result<()> {
             println!("  Connection events: {}", total_conn_events);
             println!("  Connection drops: {}", total_drops);
             println!();
+
+            let records = monerosim::healthcheck::load_readiness_records(&cli.shared_dir);
+            if !records.is_empty() {
+                let mut report = analysis::analyze_readiness(&records);
+                scrub(&mut report, anonymizer);
+                print_readiness_report(&report);
+
+                let json = serde_json::to_string_pretty(&report)?;
+                fs::write(cli.output.join("readiness_report.json"), &json)?;
+                log::info!(
+                    "Readiness report written to {}",
+                    cli.output.join("readiness_report.json").display()
+                );
+            }
         }
         Commands::TxRelayV2 {
             compare_with,
@@ -415,7 +1148,8 @@ fn main() -> Result<()> {
             log::info!("Analyzing TX relay v2 protocol behavior...");
 
             // Run v2 analysis on primary data
-            let v2_report = analysis::analyze_tx_relay_v2(&transactions, &log_data, &agents);
+            let mut v2_report = analysis::analyze_tx_relay_v2(&transactions, &log_data, &agents);
+            scrub(&mut v2_report, anonymizer);
 
             // Print primary report
             print_v2_report(&v2_report);
@@ -447,13 +1181,18 @@ fn main() -> Result<()> {
                         compare_dir.join("hosts")
                     }
                 };
-                let compare_log_data = analysis::parse_all_logs(&compare_log_dir, &compare_agents)?;
+                let compare_log_data = analysis::parse_all_logs_reduced(
+                    &compare_log_dir,
+                    &compare_agents,
+                    &reduction,
+                )?;
 
-                let compare_report = analysis::analyze_tx_relay_v2(
+                let mut compare_report = analysis::analyze_tx_relay_v2(
                     &compare_transactions,
                     &compare_log_data,
                     &compare_agents,
                 );
+                scrub(&mut compare_report, anonymizer);
 
                 // Print comparison
                 println!("\n");
@@ -481,10 +1220,21 @@ fn main() -> Result<()> {
         Commands::Dandelion {
             detailed,
             short_stems,
+            fluff_gap_ms,
+            min_fluff_recipients,
         } => {
+            if min_fluff_recipients == 0 {
+                return Err(eyre!("--min-fluff-recipients must be at least 1"));
+            }
             log::info!("Analyzing Dandelion++ stem paths...");
 
-            let dandelion_report = analysis::analyze_dandelion(&transactions, &log_data, &agents);
+            let detection = analysis::types::FluffDetection {
+                gap_ms: fluff_gap_ms,
+                min_recipients: min_fluff_recipients,
+            };
+            let mut dandelion_report =
+                analysis::analyze_dandelion(&transactions, &log_data, &agents, detection);
+            scrub(&mut dandelion_report, anonymizer);
 
             // Print report
             print_dandelion_report(&dandelion_report, detailed, short_stems);
@@ -499,11 +1249,20 @@ fn main() -> Result<()> {
         }
         Commands::NetworkGraph {
             dot,
-            expected_outbound: _,
+            expected_outbound,
+            compare_intended,
         } => {
             log::info!("Analyzing network P2P topology...");
 
-            let graph_report = analysis::analyze_network_graph(&log_data, &agents, None);
+            let mut graph_report =
+                analysis::analyze_network_graph(&log_data, &agents, None, expected_outbound);
+            if let Some(path) = compare_intended {
+                let intended = load_intended_topology(&path)?;
+                graph_report.intended_comparison = Some(analysis::network_graph::compare_intended(
+                    &log_data, &agents, &intended,
+                ));
+            }
+            scrub(&mut graph_report, anonymizer);
 
             // Print report
             print_network_graph_report(&graph_report);
@@ -533,6 +1292,7 @@ fn main() -> Result<()> {
             manifest,
             pre_upgrade_end,
             post_upgrade_start,
+            monitor,
         } => {
             log::info!(
                 "Analyzing upgrade impact with {}s time windows...",
@@ -544,9 +1304,19 @@ fn main() -> Result<()> {
                 manifest_path: manifest.map(|p| p.to_string_lossy().to_string()),
                 pre_upgrade_end,
                 post_upgrade_start,
+                monitor_path: monitor
+                    .or_else(|| analysis::find_metrics_file(&cli.shared_dir))
+                    .map(|p| p.to_string_lossy().to_string()),
+                conflicts_path: Some(cli.shared_dir.join(analysis::CONFLICTS_FILE))
+                    .filter(|p| p.exists())
+                    .map(|p| p.to_string_lossy().to_string()),
+                tx_attempts_path: Some(cli.shared_dir.join(analysis::TX_ATTEMPTS_FILE))
+                    .filter(|p| p.exists())
+                    .map(|p| p.to_string_lossy().to_string()),
+                block_interval_secs,
             };
 
-            let upgrade_report = analysis::analyze_upgrade_impact(
+            let mut upgrade_report = analysis::analyze_upgrade_impact(
                 &transactions,
                 &log_data,
                 &agents,
@@ -554,6 +1324,7 @@ fn main() -> Result<()> {
                 &config,
                 &cli.data_dir.to_string_lossy(),
             )?;
+            scrub(&mut upgrade_report, anonymizer);
 
             // Generate text report
             let text_report = format_upgrade_report(&upgrade_report);
@@ -574,6 +1345,11 @@ fn main() -> Result<()> {
                 "Upgrade analysis written to {}",
                 cli.output.join("upgrade_analysis.json").display()
             );
+            analysis::report::write_tables(
+                &[analysis::report::upgrade_window_table(&upgrade_report)],
+                &cli.output,
+                cli.format,
+            )?;
         }
 
         Commands::Bandwidth {
@@ -581,20 +1357,38 @@ fn main() -> Result<()> {
             by_category,
             time_series,
             top,
+            matrix,
         } => {
             log::info!("Analyzing bandwidth usage...");
 
             // Analyze bandwidth
-            let mut report = analysis::analyze_bandwidth(&log_data, 10);
+            let mut report = match log_index {
+                Some(ref index) => analysis::analyze_bandwidth_iter(index.nodes(), 10),
+                None => analysis::analyze_bandwidth(&log_data, 10),
+            };
 
             // Calculate time series if requested
             if let Some(window_size) = time_series {
-                report.bandwidth_over_time =
-                    analysis::bandwidth_time_series(&log_data, window_size as f64);
+                report.bandwidth_over_time = match log_index {
+                    Some(ref index) => {
+                        analysis::bandwidth_time_series_iter(index.nodes(), window_size as f64)
+                    }
+                    None => analysis::bandwidth_time_series(&log_data, window_size as f64),
+                };
+            }
+
+            scrub(&mut report, anonymizer);
+
+            let mut traffic_matrix = matrix.then(|| match log_index {
+                Some(ref index) => analysis::bandwidth_matrix_iter(index.nodes(), &agents),
+                None => analysis::bandwidth_matrix(&log_data, &agents),
+            });
+            if let Some(m) = traffic_matrix.as_mut() {
+                scrub(m, anonymizer);
             }
 
             // Print report
-            print_bandwidth_report(&report, per_node, by_category, top);
+            print_bandwidth_report(&report, traffic_matrix.as_ref(), per_node, by_category, top);
 
             // Save JSON report
             let json = serde_json::to_string_pretty(&report)?;
@@ -603,36 +1397,461 @@ fn main() -> Result<()> {
                 "Bandwidth report written to {}",
                 cli.output.join("bandwidth_report.json").display()
             );
+            analysis::report::write_tables(
+                &[analysis::report::bandwidth_node_table(&report)],
+                &cli.output,
+                cli.format,
+            )?;
+
+            if let Some(m) = &traffic_matrix {
+                let json = serde_json::to_string_pretty(m)?;
+                fs::write(cli.output.join("bandwidth_matrix.json"), &json)?;
+                // The matrix is a table at heart: CSV always, Parquet on request
+                let table = [analysis::report::bandwidth_matrix_table(m)];
+                analysis::report::write_tables(
+                    &table,
+                    &cli.output,
+                    analysis::report::ReportFormat::Csv,
+                )?;
+                if cli.format == analysis::report::ReportFormat::Parquet {
+                    analysis::report::write_tables(&table, &cli.output, cli.format)?;
+                }
+                log::info!(
+                    "Bandwidth matrix written to {}",
+                    cli.output.join("bandwidth_matrix.json").display()
+                );
+            }
         }
-    }
+        Commands::Blocks { detailed } => {
+            log::info!("Analyzing block propagation...");
 
-    Ok(())
-}
+            let miner_weights = load_miner_weights(&registry_dir)?;
+            let mut block_report = analysis::analyze_block_propagation(
+                &blocks,
+                &log_data,
+                &miner_weights,
+                block_interval_secs,
+            );
+            scrub(&mut block_report, anonymizer);
 
-/// Print TX relay v2 report to stdout
-fn print_v2_report(report: &analysis::types::TxRelayV2Report) {
-    println!("\n================================================================================");
-    println!("                      TX RELAY V2 PROTOCOL ANALYSIS");
-    println!("================================================================================\n");
+            // Print report
+            print_block_report(&block_report, detailed);
 
-    println!("Protocol Usage:");
-    println!(
-        "  V1 broadcasts (NOTIFY_NEW_TRANSACTIONS): {}",
-        report.protocol_usage.v1_tx_broadcasts
-    );
-    println!(
-        "  V2 hash announcements (NOTIFY_TX_POOL_HASH): {}",
-        report.protocol_usage.v2_hash_announcements
-    );
-    println!(
-        "  V2 requests (NOTIFY_REQUEST_TX_POOL_TXS): {}",
-        report.protocol_usage.v2_tx_requests
-    );
-    println!(
-        "  V2 usage ratio: {:.1}%",
-        report.protocol_usage.v2_usage_ratio * 100.0
-    );
-    println!();
+            // Save text report
+            let txt_path = cli.output.join("block_propagation_report.txt");
+            fs::write(&txt_path, format_block_report(&block_report, detailed))?;
+            log::info!(
+                "Block propagation text report written to {}",
+                txt_path.display()
+            );
+
+            // Save JSON report
+            let json = serde_json::to_string_pretty(&block_report)?;
+            fs::write(cli.output.join("block_propagation_report.json"), &json)?;
+            log::info!(
+                "Block propagation report written to {}",
+                cli.output.join("block_propagation_report.json").display()
+            );
+        }
+        Commands::Mempool { window_size } => {
+            log::info!(
+                "Analyzing mempool divergence with {}s time windows...",
+                window_size
+            );
+
+            let mut mempool_report =
+                analysis::analyze_mempool(&transactions, &blocks, &log_data, window_size as f64);
+            scrub(&mut mempool_report, anonymizer);
+
+            // Print report
+            print_mempool_report(&mempool_report);
+
+            // Save JSON report
+            let json = serde_json::to_string_pretty(&mempool_report)?;
+            fs::write(cli.output.join("mempool_report.json"), &json)?;
+            log::info!(
+                "Mempool report written to {}",
+                cli.output.join("mempool_report.json").display()
+            );
+        }
+        Commands::Conflicts { file, detailed } => {
+            let path = file.unwrap_or_else(|| cli.shared_dir.join(analysis::CONFLICTS_FILE));
+            if !path.exists() {
+                return Err(eyre!(
+                    "No conflict sets at {}; the double-spending agent records them there",
+                    path.display()
+                ));
+            }
+            let sets = analysis::load_conflict_sets(&path)?;
+            log::info!("Analyzing {} conflict sets...", sets.len());
+
+            let mut conflict_report =
+                analysis::analyze_conflicts(&sets, &transactions, &blocks, &log_data, &agents);
+            scrub(&mut conflict_report, anonymizer);
+
+            // Print report
+            print_conflict_report(&conflict_report, detailed);
+
+            // Save text report
+            let txt_path = cli.output.join("conflicts_report.txt");
+            fs::write(
+                &txt_path,
+                format_conflict_report(&conflict_report, detailed),
+            )?;
+            log::info!("Conflicts text report written to {}", txt_path.display());
+
+            // Save JSON report
+            let json = serde_json::to_string_pretty(&conflict_report)?;
+            fs::write(cli.output.join("conflicts_report.json"), &json)?;
+            log::info!(
+                "Conflicts report written to {}",
+                cli.output.join("conflicts_report.json").display()
+            );
+        }
+        Commands::Trace { tx_hash } => {
+            let tx_hash = match analysis::match_tx_hash(&tx_hash, &transactions, &log_data) {
+                analysis::TxHashMatch::Found(hash) => hash,
+                analysis::TxHashMatch::Ambiguous(matches) => {
+                    let more = match matches.len().saturating_sub(10) {
+                        0 => String::new(),
+                        n => format!("\n  ... and {} more", n),
+                    };
+                    return Err(eyre!(
+                        "'{}' matches {} transactions, use a longer prefix:\n  {}{}",
+                        tx_hash,
+                        matches.len(),
+                        matches[..matches.len().min(10)].join("\n  "),
+                        more
+                    ));
+                }
+                analysis::TxHashMatch::NotFound { near_misses } if near_misses.is_empty() => {
+                    return Err(eyre!(
+                        "Transaction '{}' not found: no transactions in the data",
+                        tx_hash
+                    ));
+                }
+                analysis::TxHashMatch::NotFound { near_misses } => {
+                    return Err(eyre!(
+                        "Transaction '{}' not found. Closest hashes:\n  {}",
+                        tx_hash,
+                        near_misses.join("\n  ")
+                    ));
+                }
+            };
+            log::info!("Tracing transaction {}...", tx_hash);
+
+            let mut trace =
+                analysis::trace_transaction(&tx_hash, &transactions, &blocks, &log_data, &agents);
+            scrub(&mut trace, anonymizer);
+
+            // Print timeline
+            print_tx_trace(&trace);
+
+            // Save JSON trace
+            let file = format!("tx_trace_{}.json", &tx_hash[..tx_hash.len().min(16)]);
+            let json = serde_json::to_string_pretty(&trace)?;
+            fs::write(cli.output.join(&file), &json)?;
+            log::info!("Trace written to {}", cli.output.join(&file).display());
+        }
+        Commands::Synth { .. }
+        | Commands::Compare { .. }
+        | Commands::Sweep { .. }
+        | Commands::Pcaps { .. }
+        | Commands::Monitor { .. }
+        | Commands::TxSuccess { .. } => {
+            unreachable!("handled before loading data")
+        }
+    }
+
+    Ok(())
+}
+
+/// Load one `compare` run and collect its metrics. The logs are dropped
+/// before the next run is loaded.
+fn summarize_compare_run(
+    run: &RunSpec,
+    analyses: &[analysis::CompareAnalysis],
+    cli: &Cli,
+) -> Result<analysis::types::RunSummary> {
+    let compat = analysis::check_artifacts(&run.shared_dir);
+    match compat.level {
+        analysis::Compatibility::Supported => log::info!("{}: {}", run.label, compat),
+        analysis::Compatibility::BestEffort => log::warn!("{}: {}", run.label, compat),
+        analysis::Compatibility::Unsupported if cli.ignore_compat => {
+            log::warn!("{}: {}", run.label, compat)
+        }
+        analysis::Compatibility::Unsupported => {
+            return Err(eyre!(
+                "{}: {}\n(pass --ignore-compat to analyze anyway)",
+                run.label,
+                compat
+            ));
+        }
+    }
+
+    log::info!(
+        "Loading run '{}' from {}...",
+        run.label,
+        run.shared_dir.display()
+    );
+    let agents = load_agent_registry(&run.shared_dir)?;
+    let transactions = load_transactions(&run.shared_dir)?;
+    let blocks = load_blocks(&run.shared_dir)?;
+
+    // Same layout as --compare-with: daemon_logs/ first, then hosts/
+    let log_dir = {
+        let daemon_logs = run.data_dir.join("daemon_logs");
+        if daemon_logs.exists() {
+            daemon_logs
+        } else {
+            run.data_dir.join("hosts")
+        }
+    };
+    let reduction = cli.log_reduction()?;
+    let log_data = if cli.no_cache {
+        analysis::parse_all_logs_reduced(&log_dir, &agents, &reduction)?
+    } else {
+        analysis::parse_all_logs_cached(
+            &log_dir,
+            &agents,
+            &run.data_dir.join("parsed_logs"),
+            &reduction,
+        )?
+    };
+
+    Ok(analysis::summarize_run(
+        &run.label,
+        &transactions,
+        &blocks,
+        &log_data,
+        &agents,
+        analyses,
+    ))
+}
+
+/// Analyze one run of a sweep; an error or panic becomes a failed row
+/// instead of ending the batch
+fn analyze_sweep_run(
+    run: &analysis::SweepRunSpec,
+    analyses: &[analysis::SweepAnalysis],
+    spy_visibility: f64,
+    reduction: &analysis::LogReduction,
+    cli: &Cli,
+) -> analysis::types::SweepRunResult {
+    let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        sweep_run_result(run, analyses, spy_visibility, reduction, cli)
+    }));
+    let error = match outcome {
+        Ok(Ok(result)) => return result,
+        Ok(Err(e)) => format!("{:#}", e),
+        Err(panic) => format!(
+            "analysis panicked: {}",
+            panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_default()
+        ),
+    };
+    log::warn!("Run {} failed: {}", run.name, error);
+    analysis::types::SweepRunResult {
+        name: run.name.clone(),
+        dir: run.dir.display().to_string(),
+        parameters: run.parameters.clone(),
+        status: analysis::types::SweepRunStatus::Failed,
+        error: Some(error),
+        agents: 0,
+        transactions: 0,
+        daemons: 0,
+        metrics: Default::default(),
+    }
+}
+
+/// Load one sweep run, reusing its parsed log cache, and collect its
+/// metrics. The logs are dropped before returning.
+fn sweep_run_result(
+    run: &analysis::SweepRunSpec,
+    analyses: &[analysis::SweepAnalysis],
+    spy_visibility: f64,
+    reduction: &analysis::LogReduction,
+    cli: &Cli,
+) -> Result<analysis::types::SweepRunResult> {
+    let layout = analysis::locate_run(&run.dir)?;
+    let compat = analysis::check_artifacts(&layout.shared_dir);
+    match compat.level {
+        analysis::Compatibility::Supported => log::info!("{}: {}", run.name, compat),
+        analysis::Compatibility::BestEffort => log::warn!("{}: {}", run.name, compat),
+        analysis::Compatibility::Unsupported if cli.ignore_compat => {
+            log::warn!("{}: {}", run.name, compat)
+        }
+        analysis::Compatibility::Unsupported => {
+            return Err(eyre!("{} (pass --ignore-compat to analyze anyway)", compat));
+        }
+    }
+
+    log::info!("Loading run '{}' from {}...", run.name, run.dir.display());
+    let agents = load_agent_registry(&layout.shared_dir)?;
+    let transactions = load_transactions(&layout.shared_dir)?;
+    let blocks = load_blocks(&layout.shared_dir)?;
+    let log_data = if cli.no_cache {
+        analysis::parse_all_logs_reduced(&layout.log_dir, &agents, reduction)?
+    } else {
+        analysis::parse_all_logs_cached(&layout.log_dir, &agents, &layout.cache_dir, reduction)?
+    };
+
+    Ok(analysis::types::SweepRunResult {
+        name: run.name.clone(),
+        dir: run.dir.display().to_string(),
+        parameters: run.parameters.clone(),
+        status: analysis::types::SweepRunStatus::Ok,
+        error: None,
+        agents: agents.len(),
+        transactions: transactions.len(),
+        daemons: log_data.len(),
+        metrics: analysis::sweep_run_metrics(
+            &transactions,
+            &blocks,
+            &log_data,
+            &agents,
+            analyses,
+            spy_visibility,
+        ),
+    })
+}
+
+/// Format a multi-run comparison as text
+fn format_comparison_report(report: &analysis::types::ComparisonReport) -> String {
+    use std::fmt::Write;
+    let mut out = String::new();
+
+    writeln!(
+        out,
+        "\n================================================================================"
+    )
+    .expect("write to String is infallible");
+    writeln!(out, "                          RUN COMPARISON")
+        .expect("write to String is infallible");
+    writeln!(
+        out,
+        "================================================================================\n"
+    )
+    .expect("write to String is infallible");
+
+    writeln!(out, "Runs (baseline: {}):", report.baseline).expect("write to String is infallible");
+    for run in &report.runs {
+        writeln!(
+            out,
+            "  {:<16} {} agents, {} transactions, {} daemons",
+            run.label, run.agents, run.transactions, run.daemons
+        )
+        .expect("write to String is infallible");
+    }
+    writeln!(out).expect("write to String is infallible");
+
+    // Every run's value side by side
+    write!(out, "{:<30}", "Metric").expect("write to String is infallible");
+    for run in &report.runs {
+        write!(out, " | {:>14}", run.label).expect("write to String is infallible");
+    }
+    writeln!(out).expect("write to String is infallible");
+    write!(out, "{:-<30}", "").expect("write to String is infallible");
+    for _ in &report.runs {
+        write!(out, "-+-{:-<14}", "").expect("write to String is infallible");
+    }
+    writeln!(out).expect("write to String is infallible");
+    if let Some(baseline) = report.runs.first() {
+        for metric in &baseline.metrics {
+            write!(out, "{:<30}", metric.metric_name).expect("write to String is infallible");
+            for run in &report.runs {
+                let value = run
+                    .metrics
+                    .iter()
+                    .find(|m| m.metric_name == metric.metric_name)
+                    .map_or_else(|| "-".to_string(), |m| format!("{:.3}", m.value));
+                write!(out, " | {:>14}", value).expect("write to String is infallible");
+            }
+            writeln!(out).expect("write to String is infallible");
+        }
+    }
+    writeln!(out).expect("write to String is infallible");
+
+    for comparison in &report.comparisons {
+        writeln!(
+            out,
+            "{} vs {} ({} significant change(s)):",
+            comparison.label, report.baseline, comparison.significant_changes
+        )
+        .expect("write to String is infallible");
+        writeln!(
+            out,
+            "  {:<30} | {:>10} | {:>9} | {:>11} | 95% CI of difference",
+            "Metric", "Change", "p-value", "Significant"
+        )
+        .expect("write to String is infallible");
+        writeln!(
+            out,
+            "  {:-<30}-+-{:-<10}-+-{:-<9}-+-{:-<11}-+-{:-<20}",
+            "", "", "", "", ""
+        )
+        .expect("write to String is infallible");
+        for change in &comparison.changes {
+            let significant = match (change.statistically_significant, change.impact) {
+                (false, _) => "NO",
+                (true, analysis::types::ChangeImpact::Positive) => "YES (+)",
+                (true, analysis::types::ChangeImpact::Negative) => "YES (-)",
+                (true, analysis::types::ChangeImpact::Neutral) => "YES",
+            };
+            writeln!(
+                out,
+                "  {:<30} | {:>+9.1}% | {:>9} | {:>11} | {}",
+                change.metric_name,
+                change.percent_change,
+                change
+                    .p_value
+                    .map_or_else(|| "-".to_string(), |p| format!("{:.4}", p)),
+                significant,
+                change.confidence_interval.map_or_else(
+                    || "-".to_string(),
+                    |ci| format!("[{:.3}, {:.3}]", ci.lower, ci.upper)
+                )
+            )
+            .expect("write to String is infallible");
+        }
+        writeln!(out).expect("write to String is infallible");
+    }
+    writeln!(
+        out,
+        "(+) improvement, (-) regression; significance is Welch's t-test at p < 0.05"
+    )
+    .expect("write to String is infallible");
+
+    out
+}
+
+/// Print TX relay v2 report to stdout
+fn print_v2_report(report: &analysis::types::TxRelayV2Report) {
+    println!("\n================================================================================");
+    println!("                      TX RELAY V2 PROTOCOL ANALYSIS");
+    println!("================================================================================\n");
+
+    println!("Protocol Usage:");
+    println!(
+        "  V1 broadcasts (NOTIFY_NEW_TRANSACTIONS): {}",
+        report.protocol_usage.v1_tx_broadcasts
+    );
+    println!(
+        "  V2 hash announcements (NOTIFY_TX_POOL_HASH): {}",
+        report.protocol_usage.v2_hash_announcements
+    );
+    println!(
+        "  V2 requests (NOTIFY_REQUEST_TX_POOL_TXS): {}",
+        report.protocol_usage.v2_tx_requests
+    );
+    println!(
+        "  V2 usage ratio: {:.1}%",
+        report.protocol_usage.v2_usage_ratio * 100.0
+    );
+    println!();
 
     println!("TX Delivery:");
     println!(
@@ -754,6 +1973,10 @@ fn print_dandelion_report(
 
     println!("Overview:");
     println!("  Total transactions: {}", report.total_transactions);
+    println!(
+        "  Fluff detection: {}+ recipients within {:.0}ms",
+        report.fluff_detection.min_recipients, report.fluff_detection.gap_ms
+    );
     println!("  Paths reconstructed: {}", report.paths_reconstructed);
     println!(
         "  Originator confirmed: {} ({:.1}%)",
@@ -764,6 +1987,10 @@ fn print_dandelion_report(
             0.0
         }
     );
+    println!(
+        "  Avg path confidence (heuristic): {:.2}",
+        report.avg_path_confidence
+    );
     println!();
 
     println!("Stem Length Statistics:");
@@ -857,6 +2084,7 @@ fn print_dandelion_report(
                     path.fluff_node.as_deref().unwrap_or("unknown")
                 );
                 println!("  Fluff recipients: {}", path.fluff_recipients);
+                println!("  Confidence: {:.2}", path.confidence);
                 println!("  Path: {}", analysis::dandelion::format_stem_path(path));
                 println!();
             }
@@ -918,6 +2146,29 @@ fn print_network_graph_report(report: &analysis::NetworkGraphReport) {
     );
     println!();
 
+    if let Some(r) = &report.reachability {
+        println!("By Reachability (final state):");
+        for (class, dist) in [("Reachable", &r.reachable), ("Unreachable", &r.unreachable)] {
+            println!(
+                "  {}: outbound mean={:.1} max={}, inbound mean={:.1} max={}",
+                class,
+                dist.outbound_stats.mean,
+                dist.outbound_stats.max,
+                dist.inbound_stats.mean,
+                dist.inbound_stats.max
+            );
+        }
+        if r.unreachable_with_inbound.is_empty() {
+            println!("  No unreachable node accepted an inbound connection");
+        } else {
+            println!(
+                "  Unreachable nodes with inbound: {}",
+                r.unreachable_with_inbound.join(", ")
+            );
+        }
+        println!();
+    }
+
     println!("Connection Churn:");
     println!("  Total opens: {}", report.churn_stats.total_opens);
     println!("  Total closes: {}", report.churn_stats.total_closes);
@@ -969,6 +2220,28 @@ fn print_network_graph_report(report: &analysis::NetworkGraphReport) {
     }
     println!();
 
+    if let Some(cmp) = &report.intended_comparison {
+        println!("Intended vs Observed:");
+        println!(
+            "  Intended edges: {}, observed: {}, matched: {}",
+            cmp.intended_edges, cmp.observed_edges, cmp.matched_edges
+        );
+        println!(
+            "  Missing: {}, extra: {}",
+            cmp.missing_edges.len(),
+            cmp.extra_edges.len()
+        );
+        for (node, diff) in cmp
+            .per_node
+            .iter()
+            .filter(|(_, d)| !d.missing.is_empty())
+            .take(10)
+        {
+            println!("  {} never reached: {}", node, diff.missing.join(", "));
+        }
+        println!();
+    }
+
     // Show snapshots over time
     if report.snapshots.len() > 1 {
         println!("Network Evolution:");
@@ -1037,6 +2310,16 @@ fn format_upgrade_report(report: &analysis::types::UpgradeAnalysisReport) -> Str
         report.metadata.window_size_sec as u64, report.metadata.total_windows
     )
     .expect("write to String is infallible");
+    if let Some(ref anon) = report.metadata.anonymization {
+        writeln!(
+            out,
+            "Anonymized: pseudonym seed {}, timestamps floored to {}s, timing noise ±{}%, IPs dropped",
+            anon.pseudonym_seed,
+            anon.time_granularity_sec,
+            anon.timing_noise * 100.0
+        )
+        .expect("write to String is infallible");
+    }
     writeln!(out).expect("write to String is infallible");
 
     // Upgrade info
@@ -1534,62 +2817,697 @@ fn format_upgrade_report(report: &analysis::types::UpgradeAnalysisReport) -> Str
     out
 }
 
-/// Print bandwidth analysis report to stdout
-fn print_bandwidth_report(
-    report: &analysis::types::BandwidthReport,
-    show_per_node: bool,
-    show_by_category: bool,
-    top_n: usize,
-) {
-    println!("\n================================================================================");
-    println!("                      BANDWIDTH ANALYSIS");
-    println!("================================================================================\n");
+/// Print block propagation report to stdout
+fn print_block_report(report: &analysis::types::BlockPropagationReport, detailed: bool) {
+    print!("{}", format_block_report(report, detailed));
+}
 
-    // Network totals
-    println!("Network Totals:");
-    println!(
-        "  Total Data:     {} ({} sent, {} received)",
-        analysis::format_bytes(report.total_bytes),
-        analysis::format_bytes(report.total_bytes_sent),
-        analysis::format_bytes(report.total_bytes_received)
-    );
-    println!("  Total Messages: {}", report.total_messages);
-    println!("  Nodes:          {}", report.per_node_stats.len());
-    println!();
+/// Format block propagation report as text
+fn format_block_report(report: &analysis::types::BlockPropagationReport, detailed: bool) -> String {
+    use std::fmt::Write;
+    let mut out = String::new();
+    let ms = |v: Option<f64>| v.map_or_else(|| "-".to_string(), |v| format!("{:.1}ms", v));
 
-    // Per-node summary
-    println!("Per-Node Statistics:");
-    println!(
-        "  Average:  {}/node",
-        analysis::format_bytes(report.avg_bytes_per_node as u64)
-    );
-    println!(
-        "  Median:   {}/node",
-        analysis::format_bytes(report.median_bytes_per_node as u64)
-    );
-    println!(
-        "  Max:      {} ({})",
-        analysis::format_bytes(report.max_bytes_node.1),
-        report.max_bytes_node.0
-    );
-    println!(
-        "  Min:      {} ({})",
-        analysis::format_bytes(report.min_bytes_node.1),
-        report.min_bytes_node.0
-    );
-    println!();
+    writeln!(
+        out,
+        "\n================================================================================"
+    )
+    .expect("write to String is infallible");
+    writeln!(out, "                       BLOCK PROPAGATION ANALYSIS")
+        .expect("write to String is infallible");
+    writeln!(
+        out,
+        "================================================================================\n"
+    )
+    .expect("write to String is infallible");
 
-    // By category table
-    if show_by_category && !report.bytes_by_category.is_empty() {
-        println!("Bandwidth by Message Type:");
-        println!(
-            "{:<20} | {:>12} | {:>12} | {:>12} | {:>10}",
-            "Category", "Sent", "Received", "Total", "Messages"
-        );
-        println!(
-            "{:-<20}-+-{:-^12}-+-{:-^12}-+-{:-^12}-+-{:-^10}",
-            "", "", "", "", ""
-        );
+    writeln!(out, "Overview:").expect("write to String is infallible");
+    writeln!(out, "  Blocks observed: {}", report.total_blocks)
+        .expect("write to String is infallible");
+    writeln!(out, "  Daemons: {}", report.daemon_count).expect("write to String is infallible");
+    writeln!(
+        out,
+        "  Reached every daemon: {}/{}",
+        report.fully_propagated_blocks, report.total_blocks
+    )
+    .expect("write to String is infallible");
+    writeln!(
+        out,
+        "  Block interval: target {:.0}s, observed {}",
+        report.block_interval_secs,
+        report
+            .observed_block_interval_secs
+            .map_or_else(|| "-".to_string(), |s| format!("{:.1}s", s))
+    )
+    .expect("write to String is infallible");
+    writeln!(out).expect("write to String is infallible");
+
+    writeln!(out, "Time to Reach Daemons:").expect("write to String is infallible");
+    writeln!(
+        out,
+        "  50%: avg {:.1}ms, median {:.1}ms, p95 {:.1}ms",
+        report.average_time_to_50_ms, report.median_time_to_50_ms, report.p95_time_to_50_ms
+    )
+    .expect("write to String is infallible");
+    writeln!(
+        out,
+        "  90%: avg {:.1}ms, median {:.1}ms, p95 {:.1}ms",
+        report.average_time_to_90_ms, report.median_time_to_90_ms, report.p95_time_to_90_ms
+    )
+    .expect("write to String is infallible");
+    writeln!(out, "  100%: avg {:.1}ms", report.average_time_to_100_ms)
+        .expect("write to String is infallible");
+    if let Some(share) = report.median_time_to_90_interval_share {
+        writeln!(
+            out,
+            "  Median 90% delay: {:.2}% of the block interval",
+            share * 100.0
+        )
+        .expect("write to String is infallible");
+    }
+    writeln!(out).expect("write to String is infallible");
+
+    writeln!(
+        out,
+        "Competing Blocks: {} height(s)",
+        report.alternative_blocks.len()
+    )
+    .expect("write to String is infallible");
+    for event in &report.alternative_blocks {
+        writeln!(
+            out,
+            "  Height {}: {} hash(es), mined by {}",
+            event.height,
+            event.block_hashes.len(),
+            if event.miners.is_empty() {
+                "unknown".to_string()
+            } else {
+                event.miners.join(", ")
+            }
+        )
+        .expect("write to String is infallible");
+    }
+    writeln!(out).expect("write to String is infallible");
+
+    if let Some(ref attribution) = report.miner_attribution {
+        writeln!(
+            out,
+            "Miner Attribution ({} attributed, {} unattributed):",
+            attribution.attributed_blocks, attribution.unattributed_blocks
+        )
+        .expect("write to String is infallible");
+        for share in &attribution.shares {
+            writeln!(
+                out,
+                "  {}: {} blocks ({:.1}%), expected {:.1} ({:.1}%)",
+                share.miner_id,
+                share.actual_blocks,
+                share.actual_share * 100.0,
+                share.expected_blocks,
+                share.expected_share * 100.0
+            )
+            .expect("write to String is infallible");
+        }
+        writeln!(out, "  Note: {}", attribution.note).expect("write to String is infallible");
+        writeln!(out).expect("write to String is infallible");
+    }
+
+    if detailed {
+        writeln!(out, "Per-Block Timing:").expect("write to String is infallible");
+        for block in &report.per_block {
+            writeln!(
+                out,
+                "  Height {}: first {} at {:.3}s, miner {}, {}/{} daemons, 50% {}, 90% {}, 100% {}",
+                block.height,
+                block.first_seen_node,
+                block.first_seen_time,
+                block.miner.as_deref().unwrap_or("unknown"),
+                block.nodes_reached,
+                report.daemon_count,
+                ms(block.time_to_50_ms),
+                ms(block.time_to_90_ms),
+                ms(block.time_to_100_ms)
+            )
+            .expect("write to String is infallible");
+        }
+        writeln!(out).expect("write to String is infallible");
+    }
+
+    out
+}
+
+/// Print mempool divergence report to stdout
+/// Print a single-transaction timeline to stdout
+fn print_tx_trace(trace: &analysis::types::TxTrace) {
+    println!("\n================================================================================");
+    println!("                      TRANSACTION TRACE");
+    println!("================================================================================\n");
+
+    println!("Transaction: {}", trace.tx_hash);
+    match (&trace.sender, &trace.recipient) {
+        (Some(sender), Some(recipient)) => println!("  {} -> {}", sender, recipient),
+        _ => println!("  Not in transactions.json (sender unknown)"),
+    }
+    println!(
+        "  Reached {}/{} daemons",
+        trace.nodes_reached, trace.daemon_count
+    );
+    match (trace.block_height, trace.block_time) {
+        (Some(height), Some(at)) => println!("  Included in block {} at {:.3}s", height, at),
+        (Some(height), None) => println!("  Included in block {} (never logged)", height),
+        _ => println!("  Not included in any block"),
+    }
+    println!();
+
+    if let Some(path) = &trace.stem_path {
+        println!("Dandelion++ Stem:");
+        let hops: Vec<&str> = path.stem_path.iter().map(|h| h.node_id.as_str()).collect();
+        println!("  {} -> {}", path.originator, hops.join(" -> "));
+        if let Some(fluff) = &path.fluff_node {
+            println!(
+                "  Fluffed by {} to {} peer(s) after {:.0}ms",
+                fluff, path.fluff_recipients, path.stem_duration_ms
+            );
+        }
+        println!();
+    }
+
+    println!("Timeline:");
+    println!(
+        "  {:>14} | {:>10} | {:<14} | {:<16} | {:<16} | Detail",
+        "Time (s)", "+ms", "Event", "Node", "Peer"
+    );
+    for e in &trace.events {
+        println!(
+            "  {:>14.3} | {:>10.1} | {:<14} | {:<16} | {:<16} | {}",
+            e.timestamp,
+            e.offset_ms,
+            e.kind.to_string(),
+            e.node_id,
+            e.peer.as_deref().unwrap_or("-"),
+            e.detail.as_deref().unwrap_or("")
+        );
+    }
+    println!();
+    println!("  Announcements and requests are matched by peer and time, not by hash.");
+}
+
+fn print_readiness_report(report: &analysis::types::ReadinessReport) {
+    let secs = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{:.1}", v));
+    println!("RPC readiness (monerosim-healthcheck):");
+    for target in &report.targets {
+        println!(
+            "  {}: {}/{} ready, mean {}s, median {}s, max {}s{}",
+            target.target,
+            target.ready,
+            target.checks,
+            secs(target.mean_sec),
+            secs(target.median_sec),
+            secs(target.max_sec),
+            target
+                .slowest_agent
+                .as_ref()
+                .map_or(String::new(), |a| format!(" ({})", a))
+        );
+    }
+    for check in report.not_ready.iter().take(10) {
+        println!(
+            "  not ready: {} {} ({} after {} attempt(s))",
+            check.agent, check.target, check.status, check.attempts
+        );
+    }
+    if report.not_ready.len() > 10 {
+        println!("  ... and {} more", report.not_ready.len() - 10);
+    }
+    println!();
+}
+
+fn print_monitor_report(report: &analysis::types::MonitorReport) {
+    println!("\n================================================================================");
+    println!("                        SIMULATION MONITOR TIME SERIES");
+    println!("================================================================================\n");
+
+    println!("Overview:");
+    println!("  Source: {}", report.source);
+    println!(
+        "  Samples: {} over {} poll(s) of {} node(s)",
+        report.sample_count,
+        report.poll_count,
+        report.per_node.len()
+    );
+    if let (Some(first), Some(last)) = (report.first_sample, report.last_sample) {
+        println!("  Span: {:.1}s - {:.1}s", first, last);
+    }
+    println!();
+
+    let opt = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{:.2}", v));
+    if !report.windows.is_empty() {
+        println!("Windows ({}s):", report.window_size_sec);
+        println!(
+            "  {:>14} | {:>8} | {:>10} | {:>8} | {:>10} | {:>8}",
+            "Start", "Samples", "Avg lag", "Max lag", "Avg pool", "Avg peers"
+        );
+        for window in &report.windows {
+            println!(
+                "  {:>14.1} | {:>8} | {:>10} | {:>8} | {:>10} | {:>8}",
+                window.start,
+                window.samples,
+                opt(window.avg_height_lag),
+                window
+                    .max_height_lag
+                    .map_or("-".to_string(), |v| v.to_string()),
+                opt(window.avg_mempool_txs),
+                opt(window.avg_peers)
+            );
+        }
+        println!();
+    }
+
+    let lagging: Vec<_> = report
+        .per_node
+        .iter()
+        .filter(|n| n.max_height_lag.unwrap_or(0) > 0)
+        .collect();
+    if !lagging.is_empty() {
+        println!("Most Lagging Nodes:");
+        println!(
+            "  {:<16} | {:>8} | {:>10} | {:>12}",
+            "Node", "Max lag", "Avg lag", "Final height"
+        );
+        for node in lagging.iter().take(10) {
+            println!(
+                "  {:<16} | {:>8} | {:>10} | {:>12}",
+                node.node_id,
+                node.max_height_lag.unwrap_or(0),
+                opt(node.avg_height_lag),
+                node.final_height.map_or("-".to_string(), |v| v.to_string())
+            );
+        }
+        if lagging.len() > 10 {
+            println!("  ... and {} more nodes", lagging.len() - 10);
+        }
+        println!();
+    }
+}
+
+fn print_pcap_index(index: &analysis::types::PcapIndex) {
+    if index.agents.is_empty() {
+        println!(
+            "No pcap traces under {}/hosts{}",
+            index.data_dir,
+            index
+                .capture_dir
+                .as_ref()
+                .map_or(String::new(), |dir| format!(" or {}", dir))
+        );
+    }
+    for agent in &index.agents {
+        println!(
+            "{}{}",
+            agent.agent_id,
+            if agent.expected == Some(false) {
+                " (not in the manifest's pcap_capture)"
+            } else {
+                ""
+            }
+        );
+        for file in &agent.files {
+            println!(
+                "  {:<6} {:>10}  {}",
+                file.interface,
+                analysis::format_bytes(file.size_bytes),
+                file.path
+            );
+        }
+    }
+    if !index.missing.is_empty() {
+        println!(
+            "No traces for {} of the captured agents: {}",
+            index.missing.len(),
+            index.missing.join(", ")
+        );
+    }
+}
+
+fn print_conflict_report(report: &analysis::types::ConflictReport, detailed: bool) {
+    print!("{}", format_conflict_report(report, detailed));
+}
+
+/// Format conflicting-transaction report as text
+fn format_conflict_report(report: &analysis::types::ConflictReport, detailed: bool) -> String {
+    use std::fmt::Write;
+    let mut out = String::new();
+    let short = |tx: &str| format!("{}...", &tx[..16.min(tx.len())]);
+    let secs = |v: Option<f64>| v.map_or_else(|| "-".to_string(), |v| format!("{:.1}s", v));
+    let counts = |preferred: &std::collections::BTreeMap<String, usize>| {
+        preferred
+            .iter()
+            .map(|(tx, n)| format!("{} {}", short(tx), n))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    writeln!(
+        out,
+        "\n================================================================================"
+    )
+    .expect("write to String is infallible");
+    writeln!(out, "                   CONFLICTING TRANSACTION ANALYSIS")
+        .expect("write to String is infallible");
+    writeln!(
+        out,
+        "================================================================================\n"
+    )
+    .expect("write to String is infallible");
+
+    writeln!(out, "Overview:").expect("write to String is infallible");
+    writeln!(out, "  Conflict sets: {}", report.conflict_sets)
+        .expect("write to String is infallible");
+    writeln!(
+        out,
+        "  Split the network: {}/{}",
+        report.split_sets, report.conflict_sets
+    )
+    .expect("write to String is infallible");
+    writeln!(
+        out,
+        "  Resolved by a block: {}/{} (avg {}, max {})",
+        report.resolved_sets,
+        report.conflict_sets,
+        secs(report.avg_resolution_secs),
+        secs(report.max_resolution_secs)
+    )
+    .expect("write to String is infallible");
+    writeln!(
+        out,
+        "  Nodes relaying both sides: {}",
+        if report.double_relayers.is_empty() {
+            "none".to_string()
+        } else {
+            report.double_relayers.join(", ")
+        }
+    )
+    .expect("write to String is infallible");
+    writeln!(out).expect("write to String is infallible");
+
+    for set in &report.sets {
+        writeln!(
+            out,
+            "Conflict {} ({} transactions{}):",
+            set.conflict_id,
+            set.tx_hashes.len(),
+            set.agent_id
+                .as_ref()
+                .map_or_else(String::new, |a| format!(", from {}", a))
+        )
+        .expect("write to String is infallible");
+        writeln!(
+            out,
+            "  Reached {} node(s), preferred: {}",
+            set.nodes_reached,
+            counts(&set.preferred)
+        )
+        .expect("write to String is infallible");
+        for (label, split) in [
+            ("continent", &set.split_by_continent),
+            ("AS", &set.split_by_as),
+        ] {
+            if split.len() > 1 {
+                writeln!(out, "  By {}:", label).expect("write to String is infallible");
+                for region in split {
+                    writeln!(out, "    {}: {}", region.region, counts(&region.preferred))
+                        .expect("write to String is infallible");
+                }
+            }
+        }
+        writeln!(out, "  Links across the split: {}", set.contested_links)
+            .expect("write to String is infallible");
+        match (&set.included_tx, set.included_height) {
+            (Some(tx), Some(height)) => writeln!(
+                out,
+                "  Resolved: {} at height {} after {}",
+                short(tx),
+                height,
+                secs(set.resolution_secs)
+            ),
+            _ => writeln!(out, "  Unresolved: no transaction of the set was mined"),
+        }
+        .expect("write to String is infallible");
+        if !set.double_relayers.is_empty() {
+            writeln!(
+                out,
+                "  Relayed both sides: {}",
+                set.double_relayers.join(", ")
+            )
+            .expect("write to String is infallible");
+        }
+        if detailed {
+            for node in &set.per_node {
+                let order: Vec<String> = node
+                    .first_seen_order
+                    .iter()
+                    .zip(&node.first_seen_times)
+                    .map(|(tx, t)| format!("{} at {:.1}s", short(tx), t))
+                    .collect();
+                writeln!(out, "    {:<16} {}", node.node_id, order.join(" < "))
+                    .expect("write to String is infallible");
+            }
+        }
+        writeln!(out).expect("write to String is infallible");
+    }
+
+    out
+}
+
+fn print_tx_success_report(report: &analysis::types::TxSuccessReport, detailed: bool) {
+    print!("{}", format_tx_success_report(report, detailed));
+}
+
+/// Format transaction success report as text
+fn format_tx_success_report(report: &analysis::types::TxSuccessReport, detailed: bool) -> String {
+    use std::fmt::Write;
+    let mut out = String::new();
+    let pct = |v: Option<f64>| v.map_or_else(|| "-".to_string(), |v| format!("{:.1}%", v * 100.0));
+    let secs = |v: Option<f64>| v.map_or_else(|| "-".to_string(), |v| format!("{:.1}s", v));
+
+    writeln!(
+        out,
+        "\n================================================================================"
+    )
+    .expect("write to String is infallible");
+    writeln!(out, "                      TRANSACTION SUCCESS ANALYSIS")
+        .expect("write to String is infallible");
+    writeln!(
+        out,
+        "================================================================================\n"
+    )
+    .expect("write to String is infallible");
+
+    writeln!(out, "Overview:").expect("write to String is infallible");
+    writeln!(out, "  Source: {}", report.source).expect("write to String is infallible");
+    writeln!(
+        out,
+        "  Attempts: {} by {} agent(s)",
+        report.attempted,
+        report.per_agent.len()
+    )
+    .expect("write to String is infallible");
+    writeln!(
+        out,
+        "  Succeeded: {} ({}), failed: {}",
+        report.succeeded,
+        pct(report.success_rate),
+        report.failed
+    )
+    .expect("write to String is infallible");
+    writeln!(
+        out,
+        "  Median time to first success: {}",
+        secs(report.median_time_to_first_success)
+    )
+    .expect("write to String is infallible");
+    writeln!(
+        out,
+        "  Agents without a success: {}",
+        if report.agents_without_success.is_empty() {
+            "none".to_string()
+        } else {
+            report.agents_without_success.join(", ")
+        }
+    )
+    .expect("write to String is infallible");
+
+    if !report.failure_reasons.is_empty() {
+        writeln!(out, "\nFailure reasons:").expect("write to String is infallible");
+        let mut reasons: Vec<(&String, &usize)> = report.failure_reasons.iter().collect();
+        reasons.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        for (reason, count) in reasons {
+            writeln!(out, "  {:<28} {}", reason, count).expect("write to String is infallible");
+        }
+    }
+
+    if detailed {
+        writeln!(out, "\nPer agent:").expect("write to String is infallible");
+        writeln!(
+            out,
+            "  {:<20} {:>8} {:>8} {:>8} {:>14}",
+            "Agent", "Attempts", "OK", "Rate", "First success"
+        )
+        .expect("write to String is infallible");
+        for agent in &report.per_agent {
+            writeln!(
+                out,
+                "  {:<20} {:>8} {:>8} {:>8} {:>14}",
+                agent.agent_id,
+                agent.attempted,
+                agent.succeeded,
+                pct(Some(agent.success_rate)),
+                secs(agent.time_to_first_success)
+            )
+            .expect("write to String is infallible");
+        }
+    }
+    writeln!(out).expect("write to String is infallible");
+
+    out
+}
+
+fn print_mempool_report(report: &analysis::types::MempoolReport) {
+    println!("\n================================================================================");
+    println!("                      MEMPOOL DIVERGENCE ANALYSIS");
+    println!("================================================================================\n");
+
+    println!("Overview:");
+    println!("  Daemons: {}", report.daemon_count);
+    println!(
+        "  Transactions observed: {} ({} confirmed)",
+        report.observed_transactions, report.confirmed_transactions
+    );
+    println!(
+        "  Windows: {} of {}s",
+        report.windows.len(),
+        report.window_size_sec
+    );
+    println!();
+
+    println!("Maximum Divergence:");
+    match (&report.max_divergence_node, report.max_divergence_time) {
+        (Some(node), Some(at)) => println!(
+            "  {} transaction(s) off the reference at {:.1}s ({})",
+            report.max_divergence, at, node
+        ),
+        _ => println!("  None: every daemon matched the reference in every window"),
+    }
+    println!();
+
+    let diverging: Vec<_> = report
+        .per_node
+        .iter()
+        .filter(|n| n.max_divergence > 0)
+        .collect();
+    if !diverging.is_empty() {
+        println!("Most Divergent Nodes:");
+        println!(
+            "  {:<16} | {:>8} | {:>8} | {:>8} | {:>12}",
+            "Node", "Max", "Avg", "Windows", "Longest (s)"
+        );
+        for node in diverging.iter().take(10) {
+            println!(
+                "  {:<16} | {:>8} | {:>8.2} | {:>8} | {:>12.0}",
+                node.node_id,
+                node.max_divergence,
+                node.avg_divergence,
+                node.diverged_windows,
+                node.longest_divergence_sec
+            );
+        }
+        if diverging.len() > 10 {
+            println!("  ... and {} more nodes", diverging.len() - 10);
+        }
+        println!();
+    }
+
+    println!(
+        "Confirmed Before Reaching Every Daemon: {} transaction(s)",
+        report.never_seen.len()
+    );
+    for (missing, count) in report.never_seen_by_count.iter().rev() {
+        println!("  Never seen by {} node(s): {}", missing, count);
+    }
+    for tx in report.never_seen.iter().take(10) {
+        println!(
+            "  {}... (height {}): {}",
+            &tx.tx_hash[..16.min(tx.tx_hash.len())],
+            tx.block_height,
+            tx.missing_nodes.join(", ")
+        );
+    }
+    if report.never_seen.len() > 10 {
+        println!(
+            "  ... and {} more (see JSON report for full details)",
+            report.never_seen.len() - 10
+        );
+    }
+    println!();
+}
+
+/// Print bandwidth analysis report to stdout
+fn print_bandwidth_report(
+    report: &analysis::types::BandwidthReport,
+    matrix: Option<&analysis::types::BandwidthMatrix>,
+    show_per_node: bool,
+    show_by_category: bool,
+    top_n: usize,
+) {
+    println!("\n================================================================================");
+    println!("                      BANDWIDTH ANALYSIS");
+    println!("================================================================================\n");
+
+    // Network totals
+    println!("Network Totals:");
+    println!(
+        "  Total Data:     {} ({} sent, {} received)",
+        analysis::format_bytes(report.total_bytes),
+        analysis::format_bytes(report.total_bytes_sent),
+        analysis::format_bytes(report.total_bytes_received)
+    );
+    println!("  Total Messages: {}", report.total_messages);
+    println!("  Nodes:          {}", report.per_node_stats.len());
+    println!();
+
+    // Per-node summary
+    println!("Per-Node Statistics:");
+    println!(
+        "  Average:  {}/node",
+        analysis::format_bytes(report.avg_bytes_per_node as u64)
+    );
+    println!(
+        "  Median:   {}/node",
+        analysis::format_bytes(report.median_bytes_per_node as u64)
+    );
+    println!(
+        "  Max:      {} ({})",
+        analysis::format_bytes(report.max_bytes_node.1),
+        report.max_bytes_node.0
+    );
+    println!(
+        "  Min:      {} ({})",
+        analysis::format_bytes(report.min_bytes_node.1),
+        report.min_bytes_node.0
+    );
+    println!();
+
+    // By category table
+    if show_by_category && !report.bytes_by_category.is_empty() {
+        println!("Bandwidth by Message Type:");
+        println!(
+            "{:<20} | {:>12} | {:>12} | {:>12} | {:>10}",
+            "Category", "Sent", "Received", "Total", "Messages"
+        );
+        println!(
+            "{:-<20}-+-{:-^12}-+-{:-^12}-+-{:-^12}-+-{:-^10}",
+            "", "", "", "", ""
+        );
 
         // Sort categories by total bytes
         let mut categories: Vec<_> = report.bytes_by_category.values().collect();
@@ -1641,6 +3559,60 @@ fn print_bandwidth_report(
         println!();
     }
 
+    // Top node pairs, both directions combined
+    if let Some(matrix) = matrix {
+        let mut links: HashMap<(&str, &str), (u64, u64, u64)> = HashMap::new();
+        for pair in &matrix.pairs {
+            let forward = pair.sender <= pair.receiver;
+            let key = if forward {
+                (pair.sender.as_str(), pair.receiver.as_str())
+            } else {
+                (pair.receiver.as_str(), pair.sender.as_str())
+            };
+            let link = links.entry(key).or_default();
+            if forward {
+                link.0 += pair.bytes;
+            } else {
+                link.1 += pair.bytes;
+            }
+            link.2 += pair.message_count;
+        }
+        let mut links: Vec<_> = links.into_iter().collect();
+        links.sort_by(|a, b| {
+            (b.1 .0 + b.1 .1)
+                .cmp(&(a.1 .0 + a.1 .1))
+                .then_with(|| a.0.cmp(&b.0))
+        });
+
+        let pairs_to_show = top_n.min(links.len());
+        if pairs_to_show > 0 {
+            println!("Top {} Node Pairs by Bandwidth:", pairs_to_show);
+            println!(
+                "{:>4} | {:<15} | {:<15} | {:>12} | {:>12} | {:>12} | {:>10}",
+                "Rank", "Node A", "Node B", "Total", "A -> B", "B -> A", "Messages"
+            );
+            println!(
+                "{:-^4}-+-{:-^15}-+-{:-^15}-+-{:-^12}-+-{:-^12}-+-{:-^12}-+-{:-^10}",
+                "", "", "", "", "", "", ""
+            );
+            for (i, ((a, b), (a_to_b, b_to_a, messages))) in
+                links.iter().take(pairs_to_show).enumerate()
+            {
+                println!(
+                    "{:>4} | {:<15} | {:<15} | {:>12} | {:>12} | {:>12} | {:>10}",
+                    i + 1,
+                    &a[..a.len().min(15)],
+                    &b[..b.len().min(15)],
+                    analysis::format_bytes(a_to_b + b_to_a),
+                    analysis::format_bytes(*a_to_b),
+                    analysis::format_bytes(*b_to_a),
+                    messages
+                );
+            }
+            println!();
+        }
+    }
+
     // Per-node detailed breakdown
     if show_per_node && !report.per_node_stats.is_empty() {
         println!("All Nodes:");
@@ -1689,20 +3661,42 @@ fn print_bandwidth_report(
     }
 
     println!("(See bandwidth_report.json for full data)");
+    if matrix.is_some() {
+        println!("(See bandwidth_matrix.json for every node pair)");
+    }
     println!();
 }
 
+/// Where `full` writes, what it records as provenance, and which of its
+/// sections run
+struct FullAnalysisOptions<'a> {
+    output_dir: &'a PathBuf,
+    data_dir: &'a PathBuf,
+    manifest: Option<&'a SimulationManifest>,
+    run_spy: bool,
+    run_propagation: bool,
+    run_resilience: bool,
+    exclude_intra_node: bool,
+    anonymizer: Option<&'a Anonymizer>,
+}
+
 fn run_full_analysis(
-    output_dir: &PathBuf,
-    data_dir: &PathBuf,
+    options: FullAnalysisOptions<'_>,
     transactions: &[Transaction],
     blocks: &[BlockInfo],
     log_data: &std::collections::HashMap<String, analysis::types::NodeLogData>,
     agents: &[AnalysisAgentInfo],
-    run_spy: bool,
-    run_propagation: bool,
-    run_resilience: bool,
-) -> Result<()> {
+) -> Result<FullAnalysisReport> {
+    let FullAnalysisOptions {
+        output_dir,
+        data_dir,
+        manifest,
+        run_spy,
+        run_propagation,
+        run_resilience,
+        exclude_intra_node,
+        anonymizer,
+    } = options;
     log::info!("Running full analysis...");
 
     let spy_report = if run_spy {
@@ -1711,6 +3705,7 @@ fn run_full_analysis(
             transactions,
             log_data,
             agents,
+            None,
         ))
     } else {
         None
@@ -1722,7 +3717,10 @@ fn run_full_analysis(
             transactions,
             blocks,
             log_data,
-            agents.len(),
+            agents,
+            exclude_intra_node,
+            &analysis::DEFAULT_COVERAGE_PERCENTILES,
+            None,
         ))
     } else {
         None
@@ -1730,45 +3728,102 @@ fn run_full_analysis(
 
     let resilience_report = if run_resilience {
         log::info!("Analyzing network resilience...");
-        Some(analysis::analyze_resilience(log_data, agents))
+        Some(analysis::analyze_resilience(
+            log_data,
+            agents,
+            network_events(manifest),
+        ))
     } else {
         None
     };
 
-    let report = FullAnalysisReport {
-        metadata: create_metadata(data_dir, agents, transactions, blocks),
+    let mut report = FullAnalysisReport {
+        metadata: create_metadata(data_dir, manifest, agents, transactions, blocks),
         spy_node_analysis: spy_report,
         propagation_analysis: prop_report,
         resilience_analysis: resilience_report,
     };
+    scrub(&mut report, anonymizer);
 
     // Generate reports
     analysis::generate_json_report(&report, &output_dir.join("full_report.json"))?;
     analysis::generate_text_report(&report, &output_dir.join("report.txt"))?;
 
     // Print summary
-    analysis::report::print_summary(&report);
+    analysis::report::log_summary(&report);
 
     log::info!(
         "Analysis complete. Reports written to {}",
         output_dir.display()
     );
 
-    Ok(())
+    Ok(report)
 }
 
 fn create_metadata(
     data_dir: &PathBuf,
+    manifest: Option<&SimulationManifest>,
     agents: &[AnalysisAgentInfo],
     transactions: &[Transaction],
     blocks: &[BlockInfo],
 ) -> AnalysisMetadata {
     AnalysisMetadata {
+        report_schema_version: analysis::REPORT_SCHEMA_VERSION,
         analysis_timestamp: chrono::Utc::now().to_rfc3339(),
         simulation_data_dir: data_dir.display().to_string(),
         total_nodes: agents.len(),
         total_transactions: transactions.len(),
         total_blocks: blocks.len(),
+        anonymization: None,
+        manifest: manifest.cloned(),
+    }
+}
+
+/// Scheduled partitions from the manifest, for the resilience analysis
+fn network_events(manifest: Option<&SimulationManifest>) -> &[ScheduledNetworkEvent] {
+    manifest.map_or(&[], |m| m.network_events.as_slice())
+}
+
+/// Read the generation manifest; reports are still produced without one
+/// (older runs, or a non-default output directory without `--manifest`).
+fn load_manifest(path: &Path) -> Option<SimulationManifest> {
+    if !path.exists() {
+        log::info!(
+            "No manifest at {}; reports will not record generation provenance",
+            path.display()
+        );
+        return None;
+    }
+    SimulationManifest::load(path)
+        .map_err(|e| log::warn!("Ignoring manifest: {:#}", e))
+        .ok()
+}
+
+/// Apply `--anonymize` before a report is printed or written anywhere, so
+/// every output format sees the same scrubbed data.
+fn scrub<T: Anonymize>(report: &mut T, anonymizer: Option<&Anonymizer>) {
+    if let Some(a) = anonymizer {
+        report.anonymize(a);
+    }
+}
+
+/// Directory holding the registries: the shared dir when the simulation
+/// copied them there, otherwise the generator's output directory (next to
+/// the manifest), which always has them.
+fn registry_dir(shared_dir: &Path, manifest: &Path) -> PathBuf {
+    if shared_dir.join("agent_registry.json").exists() {
+        return shared_dir.to_path_buf();
+    }
+    match manifest.parent() {
+        Some(output_dir) if output_dir.join("agent_registry.json").exists() => {
+            log::info!(
+                "No agent_registry.json in {}; using the registries in {}",
+                shared_dir.display(),
+                output_dir.display()
+            );
+            output_dir.to_path_buf()
+        }
+        _ => shared_dir.to_path_buf(),
     }
 }
 
@@ -1811,6 +3866,31 @@ fn load_agent_registry(shared_dir: &PathBuf) -> Result<Vec<AnalysisAgentInfo>> {
                 .get("wallet_address")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string());
+            let network_node_id = value
+                .get("network_node_id")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as u32);
+            let continent = value
+                .get("continent")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let as_number = value
+                .get("as_number")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as u32);
+            let role = value
+                .get("role")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            // The DNS server runs no daemon and logs no P2P traffic
+            if role.as_deref() == Some("infrastructure") {
+                continue;
+            }
+            let reachable = value.get("reachable").and_then(|v| v.as_bool());
+            let out_peers = value
+                .get("out_peers")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as usize);
 
             agents.push(AnalysisAgentInfo {
                 id,
@@ -1818,6 +3898,12 @@ fn load_agent_registry(shared_dir: &PathBuf) -> Result<Vec<AnalysisAgentInfo>> {
                 rpc_port,
                 script_type,
                 wallet_address,
+                network_node_id,
+                continent,
+                as_number,
+                role,
+                reachable,
+                out_peers,
             });
         }
     }
@@ -1844,6 +3930,27 @@ fn load_agent_registry(shared_dir: &PathBuf) -> Result<Vec<AnalysisAgentInfo>> {
                 .get("wallet_address")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string());
+            let network_node_id = value
+                .get("network_node_id")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as u32);
+            let continent = value
+                .get("continent")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let as_number = value
+                .get("as_number")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as u32);
+            let role = value
+                .get("role")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let reachable = value.get("reachable").and_then(|v| v.as_bool());
+            let out_peers = value
+                .get("out_peers")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as usize);
 
             agents.push(AnalysisAgentInfo {
                 id: id.clone(),
@@ -1851,6 +3958,12 @@ fn load_agent_registry(shared_dir: &PathBuf) -> Result<Vec<AnalysisAgentInfo>> {
                 rpc_port,
                 script_type,
                 wallet_address,
+                network_node_id,
+                continent,
+                as_number,
+                role,
+                reachable,
+                out_peers,
             });
         }
     }
@@ -1914,77 +4027,39 @@ fn load_transactions(shared_dir: &PathBuf) -> Result<Vec<Transaction>> {
     Ok(transactions)
 }
 
-/// Try to load parsed log data from a bincode cache file.
-/// Returns None if the cache doesn't exist, is stale, or fails to deserialize.
-fn try_load_cache(cache_path: &Path, hosts_dir: &Path) -> Option<HashMap<String, NodeLogData>> {
-    let cache_meta = fs::metadata(cache_path).ok()?;
-    let cache_mtime = cache_meta.modified().ok()?;
-
-    // Walk hosts_dir to find the newest log file; if any is newer than cache, invalidate
-    if let Ok(entries) = fs::read_dir(hosts_dir) {
-        for entry in entries.flatten() {
-            let host_dir = entry.path();
-            if !host_dir.is_dir() {
-                continue;
-            }
-            if let Ok(files) = fs::read_dir(&host_dir) {
-                for file in files.flatten() {
-                    if let Ok(meta) = file.metadata() {
-                        if let Ok(mtime) = meta.modified() {
-                            if mtime > cache_mtime {
-                                log::info!(
-                                    "Cache stale: {} is newer than cache",
-                                    file.path().display()
-                                );
-                                return None;
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
+/// Miner weights from miners.json, keyed by agent id; empty if the file is
+/// missing
+fn load_miner_weights(shared_dir: &Path) -> Result<HashMap<String, f64>> {
+    let path = shared_dir.join("miners.json");
 
-    let file = fs::File::open(cache_path).ok()?;
-    let decoder = match zstd::Decoder::new(file) {
-        Ok(d) => d,
-        Err(e) => {
-            log::warn!("Cache decompression failed: {}", e);
-            return None;
-        }
-    };
-    let reader = std::io::BufReader::new(decoder);
-    match bincode::deserialize_from(reader) {
-        Ok(data) => Some(data),
-        Err(e) => {
-            log::warn!("Cache deserialization failed (schema change?): {}", e);
-            None
-        }
+    if !path.exists() {
+        log::warn!(
+            "No miners.json found at {}; skipping miner attribution",
+            path.display()
+        );
+        return Ok(HashMap::new());
     }
-}
 
-/// Save parsed log data to a zstd-compressed bincode cache file (atomic write via tmp+rename).
-/// Uses streaming compression to avoid materializing the full uncompressed buffer in memory.
-fn save_cache(cache_path: &Path, data: &HashMap<String, NodeLogData>) -> Result<()> {
-    let tmp_path = cache_path.with_extension("bincode.tmp");
-    let file = fs::File::create(&tmp_path)
-        .with_context(|| format!("Failed to create cache tmp file: {}", tmp_path.display()))?;
-    // zstd level 3 is a good balance of speed and compression
-    let mut encoder = zstd::Encoder::new(file, 3).context("Failed to create zstd encoder")?;
-    bincode::serialize_into(&mut encoder, data)
-        .context("Failed to serialize log data to bincode+zstd")?;
-    encoder
-        .finish()
-        .context("Failed to finish zstd compression")?;
-    let compressed_size = fs::metadata(&tmp_path).map(|m| m.len()).unwrap_or(0);
-    fs::rename(&tmp_path, cache_path)
-        .with_context(|| format!("Failed to rename cache file to {}", cache_path.display()))?;
-    log::info!(
-        "Wrote cache: {} ({:.1} MB)",
-        cache_path.display(),
-        compressed_size as f64 / 1_048_576.0
-    );
-    Ok(())
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read miners from {}", path.display()))?;
+    let json: serde_json::Value =
+        serde_json::from_str(&content).context("Failed to parse miners JSON")?;
+
+    let weights = json["miners"]
+        .as_array()
+        .map(|miners| {
+            miners
+                .iter()
+                .filter_map(|m| {
+                    let id = m["agent_id"].as_str()?;
+                    let weight = m["weight"].as_f64()?;
+                    Some((id.to_string(), weight))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(weights)
 }
 
 fn load_blocks(shared_dir: &PathBuf) -> Result<Vec<BlockInfo>> {
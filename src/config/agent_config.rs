@@ -7,34 +7,38 @@ use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::BTreeMap;
 use std::sync::LazyLock;
 
-use crate::utils::duration::parse_duration_to_seconds;
-
-use super::phases::{DaemonPhase, WalletPhase};
-use super::types::{DaemonConfig, DaemonSelectionStrategy};
-
-/// Deserialize an optional duration field that accepts either a u32 (seconds)
-/// or a duration string like "4h", "30m", "120s".
-fn deserialize_duration_option<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    use serde::de;
-
-    let value: Option<serde_yaml::Value> = Option::deserialize(deserializer)?;
-    match value {
-        None => Ok(None),
-        Some(serde_yaml::Value::Number(n)) => n
-            .as_u64()
-            .and_then(|v| u32::try_from(v).ok())
-            .map(Some)
-            .ok_or_else(|| de::Error::custom(format!("invalid u32 value: {n}"))),
-        Some(serde_yaml::Value::String(s)) => parse_duration_to_seconds(&s)
-            .map(|v| Some(v as u32))
-            .map_err(|e| de::Error::custom(e)),
-        Some(other) => Err(de::Error::custom(format!(
-            "expected number or duration string, got: {other:?}"
-        ))),
-    }
+use crate::utils::duration::SimDuration;
+
+use super::attributes::{LegacyAttributes, UserAgentAttributes};
+use super::hashrate::Hashrate;
+use super::phases::{DaemonPhase, DaemonRestart, WalletPhase};
+use super::types::{
+    AgentPlacement, DaemonConfig, DaemonSelectionStrategy, IpVersion, MonitorMetric,
+    MonitorOutputFormat, PeerDiscovery,
+};
+use super::workload::WorkloadProfile;
+
+/// A key of an agent entry, parsed here rather than by serde: the
+/// flattened `extra` map makes serde buffer the entry and drop the key
+/// from its errors.
+fn agent_field<T: serde::de::DeserializeOwned>(
+    key: &str,
+    value: Option<serde_yaml::Value>,
+) -> Result<Option<T>, String> {
+    value
+        .filter(|v| !v.is_null())
+        .map(|v| serde_yaml::from_value(v).map_err(|e| format!("{}: {}", key, e)))
+        .transpose()
+}
+
+/// A duration key of an agent entry in whole seconds, given as a number
+/// or a duration string like "4h", "30m", "120s"
+fn agent_seconds(key: &str, value: Option<serde_yaml::Value>) -> Result<Option<u32>, String> {
+    agent_field::<SimDuration>(key, value)?
+        .map(|d| {
+            u32::try_from(d.as_secs()).map_err(|_| format!("{}: duration {} is too long", key, d))
+        })
+        .transpose()
 }
 
 // Static regex patterns for parsing phase fields (compiled once).
@@ -93,11 +97,13 @@ pub enum OptionValue {
 /// `daemon_0: "monerod"`, `daemon_0_start: "0s"`, `daemon_0_stop: "30m"`
 #[derive(Debug, Clone, Serialize)]
 pub struct AgentConfig {
-    /// Daemon binary (e.g., "monerod") or remote daemon config
+    /// Daemon binary (e.g., "monerod") or remote daemon config. Also
+    /// accepted as `daemon_binary`.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub daemon: Option<DaemonConfig>,
 
-    /// Wallet binary (e.g., "monero-wallet-rpc")
+    /// Wallet binary (e.g., "monero-wallet-rpc"). Also accepted as
+    /// `wallet_binary`.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wallet: Option<String>,
 
@@ -105,7 +111,8 @@ pub struct AgentConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub script: Option<String>,
 
-    /// Per-agent daemon options (override global defaults)
+    /// Per-agent daemon options (override global defaults). Also accepted
+    /// as `daemon_arg_overrides`.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub daemon_options: Option<BTreeMap<String, OptionValue>>,
 
@@ -115,12 +122,24 @@ pub struct AgentConfig {
 
     /// Start time for this agent (e.g., "0s", "30m", "2h")
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub start_time: Option<String>,
+    pub start_time: Option<SimDuration>,
+
+    /// Time at which this agent's daemon, wallet and script are stopped
+    /// (e.g., "1h"); unset runs them to the end of the simulation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shutdown_time: Option<SimDuration>,
+
+    /// Time at which this agent's script stops acting and exits (e.g.,
+    /// "90m"), at most `general.stop_time`; its daemon and wallet keep
+    /// running. Unset uses `shutdown_time` or the simulation end.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lifetime: Option<SimDuration>,
 
     // === Miner-specific fields ===
-    /// Hashrate for autonomous miners
+    /// Hashrate for autonomous miners: a weight, a percentage ("25%") or
+    /// `auto` (see `general.hashrate_distribution`)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub hashrate: Option<u32>,
+    pub hashrate: Option<Hashrate>,
 
     // === User-specific fields ===
     /// Transaction interval in seconds for regular users
@@ -131,6 +150,10 @@ pub struct AgentConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub activity_start_time: Option<u32>,
 
+    /// Transaction profile replacing `transaction_interval`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workload: Option<WorkloadProfile>,
+
     /// Whether this agent can receive distributions from miner_distributor
     #[serde(skip_serializing_if = "Option::is_none")]
     pub can_receive_distributions: Option<bool>,
@@ -181,6 +204,15 @@ pub struct AgentConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub detailed_logging: Option<bool>,
 
+    /// Columns of the monitor's time-series file; setting this or
+    /// `output_format` turns the file on, with every metric by default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<Vec<MonitorMetric>>,
+
+    /// Format of the monitor's time-series file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_format: Option<MonitorOutputFormat>,
+
     // === Phase support (for upgrade scenarios) ===
     // Daemon phases are parsed from flat fields (daemon_0, daemon_0_start, daemon_0_stop, etc.)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -190,8 +222,14 @@ pub struct AgentConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wallet_phases: Option<BTreeMap<u32, WalletPhase>>,
 
+    /// Daemon stops and restarts, in order; see `restart_phases`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restarts: Option<Vec<DaemonRestart>>,
+
     // === Legacy support ===
-    /// Additional daemon arguments (legacy, prefer daemon_options)
+    /// Extra daemon arguments (`--flag[=value]`); a flag monerosim already
+    /// emits is replaced rather than repeated, and `daemon_options` wins
+    /// over both
     #[serde(skip_serializing_if = "Option::is_none")]
     pub daemon_args: Option<Vec<String>>,
 
@@ -207,15 +245,80 @@ pub struct AgentConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wallet_env: Option<BTreeMap<String, String>>,
 
-    /// Generic attributes (for custom script parameters)
+    /// Typed attributes; unrecognized keys are passed to the agent script
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub attributes: Option<BTreeMap<String, String>>,
+    pub attributes: Option<UserAgentAttributes>,
 
     /// Subnet group for IP clustering
     /// Agents with the same subnet_group will be assigned IPs in the same /24 subnet.
     /// Useful for simulating Sybil attacks where an attacker's nodes share infrastructure.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subnet_group: Option<String>,
+
+    /// Host download bandwidth, e.g. "50 Mbit" (default 1 Gbit/s)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bandwidth_down: Option<String>,
+
+    /// Host upload bandwidth, e.g. "10 Mbit" (default 1 Gbit/s)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bandwidth_up: Option<String>,
+
+    /// Address family (default v4); only v4 can be simulated, see
+    /// `validate_ip_versions`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_version: Option<IpVersion>,
+
+    /// monerod P2P port (default: first free triple on the host, see
+    /// `PortAllocator`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p2p_port: Option<u16>,
+
+    /// monerod RPC port
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rpc_port: Option<u16>,
+
+    /// monero-wallet-rpc port
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wallet_rpc_port: Option<u16>,
+
+    /// monerod `--out-peers` (default: `network.out_peers`, else monerod's
+    /// own default)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub out_peers: Option<u32>,
+
+    /// monerod `--in-peers` (default: `network.in_peers`, else unlimited)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_peers: Option<u32>,
+
+    /// DNS server, seed links or both (default) for finding peers; only
+    /// with `general.enable_dns_server`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discovery: Option<PeerDiscovery>,
+
+    /// GML node, AS or region this agent must be placed on; see
+    /// `AgentPlacement`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placement: Option<AgentPlacement>,
+
+    /// Run on this other agent's Shadow host, sharing its IP; see
+    /// `validate_colocation`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub colocate_with: Option<String>,
+
+    /// Data-segment limit of each of the agent's processes, e.g. "2 GiB"
+    /// (default: `general.memory_limit`); see `utils::limits`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_limit: Option<String>,
+
+    /// Relative CPU share of each of the agent's processes, 1-100 (default:
+    /// `general.cpu_weight`, else 100)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_weight: Option<u32>,
+
+    /// Have Shadow write pcap traces of the agent's host; see
+    /// `general.pcap_capture_dir`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capture_pcap: Option<bool>,
 }
 
 impl AgentConfig {
@@ -257,6 +360,72 @@ impl AgentConfig {
         self.wallet_phases.as_ref().is_some_and(|p| !p.is_empty())
     }
 
+    /// The daemon phases that run: `daemon_N` phases, else `restarts` (see
+    /// `restart_phases`)
+    pub fn effective_daemon_phases(&self) -> Option<BTreeMap<u32, DaemonPhase>> {
+        self.daemon_phases
+            .clone()
+            .filter(|p| !p.is_empty())
+            .or_else(|| self.restart_phases())
+    }
+
+    /// Binary spec the daemon ends up running: the last phase's, else the
+    /// local `daemon`
+    pub fn final_daemon_binary(&self) -> Option<String> {
+        match self.effective_daemon_phases() {
+            Some(phases) => phases.into_values().last().map(|p| p.path),
+            None => match &self.daemon {
+                Some(DaemonConfig::Local(path)) => Some(path.clone()),
+                _ => None,
+            },
+        }
+    }
+
+    /// Binary spec the wallet ends up running: the last phase's, else `wallet`
+    pub fn final_wallet_binary(&self) -> Option<&str> {
+        match self.wallet_phases.as_ref().filter(|p| !p.is_empty()) {
+            Some(phases) => phases.values().last().map(|p| p.path.as_str()),
+            None => self.wallet.as_deref(),
+        }
+    }
+
+    /// `restarts` as daemon phases: phase 0 is the configured daemon
+    /// (starting at the agent's start time) and each restart opens the
+    /// next phase. None without restarts or a local `daemon` binary.
+    pub fn restart_phases(&self) -> Option<BTreeMap<u32, DaemonPhase>> {
+        let restarts = self.restarts.as_ref().filter(|r| !r.is_empty())?;
+        let Some(DaemonConfig::Local(path)) = &self.daemon else {
+            return None;
+        };
+        let mut current = DaemonPhase {
+            path: path.clone(),
+            args: self.daemon_args.clone(),
+            env: self.daemon_env.clone(),
+            start: None,
+            stop: None,
+        };
+        let mut phases = BTreeMap::new();
+        for (i, restart) in restarts.iter().enumerate() {
+            let mut next = DaemonPhase {
+                start: Some(restart.start_at),
+                ..current.clone()
+            };
+            if let Some(path) = &restart.binary_path {
+                next.path = path.clone();
+            }
+            if let Some(extra) = &restart.extra_args {
+                next.args
+                    .get_or_insert_with(Vec::new)
+                    .extend(extra.iter().cloned());
+            }
+            current.stop = Some(restart.stop_at);
+            phases.insert(i as u32, current);
+            current = next;
+        }
+        phases.insert(restarts.len() as u32, current);
+        Some(phases)
+    }
+
     /// Check if this is a miner based on hashrate
     /// Note: Miners are identified by having a hashrate value, not by script name
     /// (e.g., "miner_distributor" is NOT a miner - it distributes rewards)
@@ -285,12 +454,63 @@ impl AgentConfig {
         }
     }
 
+    /// Id of the Shadow host this agent runs on: its own, or the
+    /// `colocate_with` agent's
+    pub fn host_id<'a>(&'a self, agent_id: &'a str) -> &'a str {
+        self.colocate_with.as_deref().unwrap_or(agent_id)
+    }
+
     /// Check if this agent is configured as a public node
     pub fn is_public_node(&self) -> bool {
+        self.attributes.as_ref().is_some_and(|a| a.is_public_node)
+    }
+
+    /// Check if this agent is a seed node (`is_seed_node: true`); miners are
+    /// seeds regardless
+    pub fn is_seed_node(&self) -> bool {
+        self.is_miner() || self.attributes.as_ref().is_some_and(|a| a.is_seed_node)
+    }
+
+    /// Whether the config itself sets `is_seed_node: true`, which mining
+    /// alone does not
+    pub fn is_marked_seed_node(&self) -> bool {
+        self.attributes.as_ref().is_some_and(|a| a.is_seed_node)
+    }
+
+    /// Check if this agent is behind NAT (`is_unreachable: true`): it
+    /// dials out but is never dialed
+    pub fn is_unreachable(&self) -> bool {
+        self.attributes.as_ref().is_some_and(|a| a.is_unreachable)
+    }
+
+    /// Whether Shadow captures this agent's packets (`capture_pcap: true`)
+    pub fn captures_pcap(&self) -> bool {
+        self.capture_pcap == Some(true)
+    }
+
+    /// Check if this agent is a spy (monitoring) daemon
+    pub fn is_spy(&self) -> bool {
+        self.attributes.as_ref().is_some_and(|a| a.is_spy)
+    }
+
+    /// The raw `spy_targets` attribute, if set
+    pub fn spy_targets(&self) -> Option<&str> {
+        self.attributes.as_ref()?.spy_targets.as_deref()
+    }
+
+    /// The `--attributes` pairs for this agent's script
+    pub fn attribute_map(&self) -> BTreeMap<String, String> {
         self.attributes
             .as_ref()
-            .and_then(|attrs| attrs.get("is_public_node"))
-            .map_or(false, |v| v.to_lowercase() == "true")
+            .map(UserAgentAttributes::to_map)
+            .unwrap_or_default()
+    }
+
+    /// Second at which this agent's script stops (its `--stop-time`):
+    /// `lifetime`, capped at `end` (the agent's shutdown or the simulation
+    /// end).
+    pub fn script_stop_secs(&self, end: u64) -> u64 {
+        self.lifetime.map_or(end, |l| l.as_secs().min(end))
     }
 
     /// Check if this is a daemon-only (relay) agent: has daemon but no wallet or script
@@ -304,28 +524,37 @@ impl AgentConfig {
 /// Raw struct for deserializing AgentConfig with flat phase fields support
 #[derive(Debug, Clone, Deserialize)]
 struct AgentConfigRaw {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "daemon_binary", skip_serializing_if = "Option::is_none")]
     pub daemon: Option<DaemonConfig>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "wallet_binary", skip_serializing_if = "Option::is_none")]
     pub wallet: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub script: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        alias = "daemon_arg_overrides",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub daemon_options: Option<BTreeMap<String, OptionValue>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wallet_options: Option<BTreeMap<String, OptionValue>>,
+    #[serde(default)]
+    pub start_time: Option<serde_yaml::Value>,
+    #[serde(default)]
+    pub shutdown_time: Option<serde_yaml::Value>,
+    #[serde(default)]
+    pub lifetime: Option<serde_yaml::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub start_time: Option<String>,
+    pub hashrate: Option<Hashrate>,
+    #[serde(default)]
+    pub transaction_interval: Option<serde_yaml::Value>,
+    #[serde(default)]
+    pub activity_start_time: Option<serde_yaml::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub hashrate: Option<u32>,
-    #[serde(default, deserialize_with = "deserialize_duration_option")]
-    pub transaction_interval: Option<u32>,
-    #[serde(default, deserialize_with = "deserialize_duration_option")]
-    pub activity_start_time: Option<u32>,
+    pub workload: Option<WorkloadProfile>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub can_receive_distributions: Option<bool>,
-    #[serde(default, deserialize_with = "deserialize_duration_option")]
-    pub wait_time: Option<u32>,
+    #[serde(default)]
+    pub wait_time: Option<serde_yaml::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub initial_fund_amount: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -338,14 +567,18 @@ struct AgentConfigRaw {
     pub md_out_per_tx: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub md_output_amount: Option<f64>,
-    #[serde(default, deserialize_with = "deserialize_duration_option")]
-    pub poll_interval: Option<u32>,
+    #[serde(default)]
+    pub poll_interval: Option<serde_yaml::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status_file: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub enable_alerts: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub detailed_logging: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<Vec<MonitorMetric>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_format: Option<MonitorOutputFormat>,
     // Note: daemon_phases and wallet_phases are NOT parsed from YAML directly
     // They are populated from flat fields (daemon_0, daemon_0_start, etc.)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -357,9 +590,39 @@ struct AgentConfigRaw {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wallet_env: Option<BTreeMap<String, String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub attributes: Option<BTreeMap<String, String>>,
+    pub attributes: Option<BTreeMap<String, serde_yaml::Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subnet_group: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bandwidth_down: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bandwidth_up: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_version: Option<IpVersion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p2p_port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rpc_port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wallet_rpc_port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub out_peers: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_peers: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discovery: Option<PeerDiscovery>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placement: Option<AgentPlacement>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub colocate_with: Option<String>,
+    #[serde(default)]
+    pub restarts: Option<serde_yaml::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_limit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_weight: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capture_pcap: Option<bool>,
     /// Capture any extra fields for flat phase parsing
     #[serde(flatten)]
     pub extra: BTreeMap<String, serde_yaml::Value>,
@@ -371,9 +634,90 @@ impl<'de> Deserialize<'de> for AgentConfig {
         D: Deserializer<'de>,
     {
         let raw = AgentConfigRaw::deserialize(deserializer)?;
+        let timing = (|| {
+            Ok::<_, String>((
+                agent_field::<SimDuration>("start_time", raw.start_time.clone())?,
+                agent_field::<SimDuration>("shutdown_time", raw.shutdown_time.clone())?,
+                agent_field::<SimDuration>("lifetime", raw.lifetime.clone())?,
+                agent_field::<Vec<DaemonRestart>>("restarts", raw.restarts.clone())?,
+                agent_seconds("transaction_interval", raw.transaction_interval.clone())?,
+                agent_seconds("activity_start_time", raw.activity_start_time.clone())?,
+                agent_seconds("wait_time", raw.wait_time.clone())?,
+                agent_seconds("poll_interval", raw.poll_interval.clone())?,
+            ))
+        })();
+        let (
+            start_time,
+            shutdown_time,
+            lifetime,
+            restarts,
+            transaction_interval,
+            activity_start_time,
+            wait_time,
+            poll_interval,
+        ) = timing.map_err(serde::de::Error::custom)?;
+
+        let (attributes, legacy) = match raw.attributes {
+            Some(map) => {
+                let (attributes, legacy) =
+                    UserAgentAttributes::from_yaml(map).map_err(serde::de::Error::custom)?;
+                (Some(attributes), legacy)
+            }
+            None => (None, LegacyAttributes::default()),
+        };
+        let hashrate = merge_legacy("hashrate", raw.hashrate, legacy.hashrate);
+        let migrated = (|| {
+            let hashrate = hashrate?;
+            match (legacy.is_miner, hashrate) {
+                (Some(true), None) => {
+                    return Err("attribute is_miner: true requires a hashrate".to_string())
+                }
+                (Some(false), Some(_)) => {
+                    return Err("attribute is_miner: false conflicts with hashrate".to_string())
+                }
+                _ => {}
+            }
+            Ok((
+                hashrate,
+                merge_legacy(
+                    "transaction_interval",
+                    transaction_interval,
+                    legacy.transaction_interval,
+                )?,
+                merge_legacy(
+                    "activity_start_time",
+                    activity_start_time,
+                    legacy.activity_start_time,
+                )?,
+                merge_legacy(
+                    "can_receive_distributions",
+                    raw.can_receive_distributions,
+                    legacy.can_receive_distributions,
+                )?,
+                merge_legacy(
+                    "min_transaction_amount",
+                    raw.min_transaction_amount,
+                    legacy.min_transaction_amount,
+                )?,
+                merge_legacy(
+                    "max_transaction_amount",
+                    raw.max_transaction_amount,
+                    legacy.max_transaction_amount,
+                )?,
+            ))
+        })();
+        let (
+            hashrate,
+            transaction_interval,
+            activity_start_time,
+            can_receive_distributions,
+            min_transaction_amount,
+            max_transaction_amount,
+        ) = migrated.map_err(serde::de::Error::custom)?;
 
         // Parse flat phase fields from extra (e.g., daemon_0, daemon_0_args, daemon_0_start)
-        let (parsed_daemon_phases, parsed_wallet_phases) = parse_phase_fields(&raw.extra);
+        let (parsed_daemon_phases, parsed_wallet_phases) =
+            parse_phase_fields(&raw.extra).map_err(serde::de::Error::custom)?;
 
         // Convert parsed phases to Option (None if empty)
         let daemon_phases = if !parsed_daemon_phases.is_empty() {
@@ -394,34 +738,70 @@ impl<'de> Deserialize<'de> for AgentConfig {
             script: raw.script,
             daemon_options: raw.daemon_options,
             wallet_options: raw.wallet_options,
-            start_time: raw.start_time,
-            hashrate: raw.hashrate,
-            transaction_interval: raw.transaction_interval,
-            activity_start_time: raw.activity_start_time,
-            can_receive_distributions: raw.can_receive_distributions,
-            wait_time: raw.wait_time,
+            start_time,
+            shutdown_time,
+            lifetime,
+            hashrate,
+            transaction_interval,
+            activity_start_time,
+            workload: raw.workload,
+            can_receive_distributions,
+            wait_time,
             initial_fund_amount: raw.initial_fund_amount,
-            max_transaction_amount: raw.max_transaction_amount,
-            min_transaction_amount: raw.min_transaction_amount,
+            max_transaction_amount,
+            min_transaction_amount,
             md_n_recipients: raw.md_n_recipients,
             md_out_per_tx: raw.md_out_per_tx,
             md_output_amount: raw.md_output_amount,
-            poll_interval: raw.poll_interval,
+            poll_interval,
             status_file: raw.status_file,
             enable_alerts: raw.enable_alerts,
             detailed_logging: raw.detailed_logging,
+            metrics: raw.metrics,
+            output_format: raw.output_format,
             daemon_phases,
             wallet_phases,
             daemon_args: raw.daemon_args,
             wallet_args: raw.wallet_args,
             daemon_env: raw.daemon_env,
             wallet_env: raw.wallet_env,
-            attributes: raw.attributes,
+            attributes,
             subnet_group: raw.subnet_group,
+            bandwidth_down: raw.bandwidth_down,
+            bandwidth_up: raw.bandwidth_up,
+            ip_version: raw.ip_version,
+            p2p_port: raw.p2p_port,
+            rpc_port: raw.rpc_port,
+            wallet_rpc_port: raw.wallet_rpc_port,
+            out_peers: raw.out_peers,
+            in_peers: raw.in_peers,
+            discovery: raw.discovery,
+            placement: raw.placement,
+            colocate_with: raw.colocate_with,
+            restarts,
+            memory_limit: raw.memory_limit,
+            cpu_weight: raw.cpu_weight,
+            capture_pcap: raw.capture_pcap,
         })
     }
 }
 
+/// Merge a typed field with the same key set inside `attributes` by an
+/// older config; setting both to different values is an error.
+fn merge_legacy<T: PartialEq + std::fmt::Debug>(
+    key: &str,
+    field: Option<T>,
+    attribute: Option<T>,
+) -> Result<Option<T>, String> {
+    match (field, attribute) {
+        (Some(f), Some(a)) if f != a => Err(format!(
+            "{} is set both as a field ({:?}) and in attributes ({:?})",
+            key, f, a
+        )),
+        (field, attribute) => Ok(field.or(attribute)),
+    }
+}
+
 /// Parse phase fields for a single phase type (daemon or wallet) from flat YAML keys.
 ///
 /// Matches keys like `{prefix}_{N}`, `{prefix}_{N}_args`, etc. against the
@@ -433,8 +813,11 @@ fn parse_typed_phases<P: Phase>(
     re_env: &Regex,
     re_start: &Regex,
     re_stop: &Regex,
-) -> BTreeMap<u32, P> {
+) -> Result<BTreeMap<u32, P>, String> {
     let mut phases: BTreeMap<u32, P> = BTreeMap::new();
+    let time = |key: &str, value: &serde_yaml::Value| {
+        serde_yaml::from_value::<SimDuration>(value.clone()).map_err(|e| format!("{}: {}", key, e))
+    };
 
     // Helper that parses the phase number capture group. The regex only
     // matches `\d+`, so the only realistic failure is u32 overflow on a
@@ -492,7 +875,7 @@ fn parse_typed_phases<P: Phase>(
             phases
                 .entry(phase_num)
                 .or_default()
-                .set_start(value.as_str().unwrap_or_default().to_string());
+                .set_start(time(key, value)?);
         } else if let Some(caps) = re_stop.captures(key) {
             let Some(phase_num) = parse_phase_num(&caps, key) else {
                 continue;
@@ -500,17 +883,49 @@ fn parse_typed_phases<P: Phase>(
             phases
                 .entry(phase_num)
                 .or_default()
-                .set_stop(value.as_str().unwrap_or_default().to_string());
+                .set_stop(time(key, value)?);
         }
     }
 
-    phases
+    Ok(phases)
 }
 
 /// Parse flat phase fields (daemon_0, daemon_0_args, etc.) into structured phases
-fn parse_phase_fields(
-    extra: &BTreeMap<String, serde_yaml::Value>,
-) -> (BTreeMap<u32, DaemonPhase>, BTreeMap<u32, WalletPhase>) {
+/// Keys of an agent entry that no field, alias or phase pattern accepts.
+/// Values are ignored; `count` and `name` are left to replication and
+/// `group` to `agent_groups`.
+pub(crate) fn unknown_agent_keys(entry: &serde_yaml::Mapping) -> Vec<String> {
+    let blanked: serde_yaml::Mapping = entry
+        .keys()
+        .filter(|k| !matches!(k.as_str(), Some("count" | "name" | "group")))
+        .map(|k| (k.clone(), serde_yaml::Value::Null))
+        .collect();
+    let Ok(raw) = serde_yaml::from_value::<AgentConfigRaw>(serde_yaml::Value::Mapping(blanked))
+    else {
+        return Vec::new();
+    };
+    let phase_patterns = [
+        &*DAEMON_RE,
+        &*DAEMON_ARGS_RE,
+        &*DAEMON_ENV_RE,
+        &*DAEMON_START_RE,
+        &*DAEMON_STOP_RE,
+        &*WALLET_RE,
+        &*WALLET_ARGS_RE,
+        &*WALLET_ENV_RE,
+        &*WALLET_START_RE,
+        &*WALLET_STOP_RE,
+    ];
+    raw.extra
+        .into_keys()
+        .filter(|key| !phase_patterns.iter().any(|re| re.is_match(key)))
+        .collect()
+}
+
+/// Daemon and wallet phases parsed from the flat fields, keyed by phase
+type ParsedPhases = (BTreeMap<u32, DaemonPhase>, BTreeMap<u32, WalletPhase>);
+
+fn parse_phase_fields(extra: &BTreeMap<String, serde_yaml::Value>) -> Result<ParsedPhases, String> {
     let daemon_phases = parse_typed_phases(
         extra,
         &DAEMON_RE,
@@ -518,7 +933,7 @@ fn parse_phase_fields(
         &DAEMON_ENV_RE,
         &DAEMON_START_RE,
         &DAEMON_STOP_RE,
-    );
+    )?;
     let wallet_phases = parse_typed_phases(
         extra,
         &WALLET_RE,
@@ -526,8 +941,8 @@ fn parse_phase_fields(
         &WALLET_ENV_RE,
         &WALLET_START_RE,
         &WALLET_STOP_RE,
-    );
-    (daemon_phases, wallet_phases)
+    )?;
+    Ok((daemon_phases, wallet_phases))
 }
 
 /// Common interface for phase types (DaemonPhase and WalletPhase share identical fields)
@@ -535,8 +950,8 @@ trait Phase: Default {
     fn set_path(&mut self, path: String);
     fn set_args(&mut self, args: Vec<String>);
     fn set_env(&mut self, env: BTreeMap<String, String>);
-    fn set_start(&mut self, start: String);
-    fn set_stop(&mut self, stop: String);
+    fn set_start(&mut self, start: SimDuration);
+    fn set_stop(&mut self, stop: SimDuration);
 }
 
 macro_rules! impl_phase {
@@ -551,10 +966,10 @@ macro_rules! impl_phase {
             fn set_env(&mut self, env: BTreeMap<String, String>) {
                 self.env = Some(env);
             }
-            fn set_start(&mut self, start: String) {
+            fn set_start(&mut self, start: SimDuration) {
                 self.start = Some(start);
             }
-            fn set_stop(&mut self, stop: String) {
+            fn set_stop(&mut self, stop: SimDuration) {
                 self.stop = Some(stop);
             }
         }
@@ -563,3 +978,30 @@ macro_rules! impl_phase {
 
 impl_phase!(DaemonPhase);
 impl_phase!(WalletPhase);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bad_durations_name_their_key() {
+        for (yaml, expected) in [
+            ("start_time: soon", "start_time: Invalid duration"),
+            ("wait_time: 3x", "wait_time: Invalid duration"),
+            ("lifetime: -5", "lifetime: Invalid duration -5"),
+            ("restarts: [{stop_at: 5m30s, start_at: 10m}]", "restarts: "),
+            ("daemon_0_start: later", "daemon_0_start: Invalid duration"),
+        ] {
+            let yaml = format!("daemon: monerod\n{}\n", yaml);
+            let err = serde_yaml::from_str::<AgentConfig>(&yaml)
+                .unwrap_err()
+                .to_string();
+            assert!(err.starts_with(expected), "{}", err);
+        }
+
+        let agent: AgentConfig =
+            serde_yaml::from_str("daemon: monerod\nstart_time: 90m\nwait_time: 120\n").unwrap();
+        assert_eq!(agent.start_time, Some(SimDuration::from_secs(5400)));
+        assert_eq!(agent.wait_time, Some(120));
+    }
+}
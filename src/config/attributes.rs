@@ -0,0 +1,301 @@
+//! Typed agent `attributes`.
+//!
+//! Keys monerosim itself interprets get typed fields; everything else is kept
+//! in `extra` and handed to the agent script verbatim. Older configs also set
+//! typed `AgentConfig` fields (`hashrate`, `transaction_interval`, ...) inside
+//! `attributes`; those are split out by `from_yaml` and migrated by the
+//! `AgentConfig` deserializer.
+
+use serde::{Serialize, Serializer};
+use serde_yaml::Value;
+use std::collections::BTreeMap;
+
+use crate::utils::duration::SimDuration;
+
+use super::hashrate::Hashrate;
+
+/// Attribute keys read by the bundled agent scripts, kept in `extra`
+const SCRIPT_ATTRIBUTES: &[&str] = &["tx_send_probability"];
+
+/// `AgentConfig` fields older configs set inside `attributes`
+pub(super) const LEGACY_ATTRIBUTES: &[&str] = &[
+    "is_miner",
+    "hashrate",
+    "transaction_interval",
+    "activity_start_time",
+    "can_receive_distributions",
+    "min_transaction_amount",
+    "max_transaction_amount",
+];
+
+/// Attribute keys with a typed field on `UserAgentAttributes`
+const TYPED_ATTRIBUTES: &[&str] = &[
+    "is_public_node",
+    "is_seed_node",
+    "is_spy",
+    "spy_targets",
+    "is_unreachable",
+    "location",
+];
+
+/// An agent's `attributes`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UserAgentAttributes {
+    /// Advertised to wallet-only agents as a public RPC node
+    pub is_public_node: bool,
+    /// Always-on bootstrap node, exempt from churn and NAT
+    pub is_seed_node: bool,
+    /// Monitoring daemon; see `spy_targets`
+    pub is_spy: bool,
+    /// Raw `spy_targets` value, parsed by `SpyTargets`
+    pub spy_targets: Option<String>,
+    /// Behind NAT: dials out but is never dialed
+    pub is_unreachable: bool,
+    /// Free-form location label passed to the agent script
+    pub location: Option<String>,
+    /// Keys monerosim does not interpret, passed to the agent script as-is
+    pub extra: BTreeMap<String, String>,
+}
+
+/// Typed `AgentConfig` fields found in an old-style `attributes` map
+#[derive(Debug, Default)]
+pub(super) struct LegacyAttributes {
+    pub is_miner: Option<bool>,
+    pub hashrate: Option<Hashrate>,
+    pub transaction_interval: Option<u32>,
+    pub activity_start_time: Option<u32>,
+    pub can_receive_distributions: Option<bool>,
+    pub min_transaction_amount: Option<String>,
+    pub max_transaction_amount: Option<String>,
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!(
+            "attribute {}: expected true or false, got {:?}",
+            key, value
+        )),
+    }
+}
+
+fn parse_seconds(key: &str, value: &str) -> Result<u32, String> {
+    let duration: SimDuration = value
+        .parse()
+        .map_err(|e| format!("attribute {}: {}", key, e))?;
+    u32::try_from(duration.as_secs())
+        .map_err(|_| format!("attribute {}: duration {} is too long", key, duration))
+}
+
+fn parse_amount(key: &str, value: &str) -> Result<String, String> {
+    match value.trim().parse::<f64>() {
+        Ok(amount) if amount >= 0.0 => Ok(value.trim().to_string()),
+        _ => Err(format!(
+            "attribute {}: expected a non-negative XMR amount, got {:?}",
+            key, value
+        )),
+    }
+}
+
+impl UserAgentAttributes {
+    /// Build from string values. Boolean keys must be `true` or `false`.
+    pub fn from_map(map: BTreeMap<String, String>) -> Result<Self, String> {
+        let mut attrs = UserAgentAttributes::default();
+        for (key, value) in map {
+            match key.as_str() {
+                "is_public_node" => attrs.is_public_node = parse_bool(&key, &value)?,
+                "is_seed_node" => attrs.is_seed_node = parse_bool(&key, &value)?,
+                "is_spy" => attrs.is_spy = parse_bool(&key, &value)?,
+                "is_unreachable" => attrs.is_unreachable = parse_bool(&key, &value)?,
+                "spy_targets" => attrs.spy_targets = Some(value),
+                "location" => attrs.location = Some(value),
+                _ => {
+                    attrs.extra.insert(key, value);
+                }
+            }
+        }
+        Ok(attrs)
+    }
+
+    /// Build from a YAML mapping of scalars, splitting out legacy typed
+    /// fields. Fails on values that do not fit their key's type, e.g.
+    /// `hashrate: "fifty"`.
+    pub(super) fn from_yaml(
+        map: BTreeMap<String, Value>,
+    ) -> Result<(Self, LegacyAttributes), String> {
+        let mut legacy = LegacyAttributes::default();
+        let mut rest = BTreeMap::new();
+        for (key, value) in map {
+            let value = match value {
+                Value::String(s) => s,
+                Value::Bool(b) => b.to_string(),
+                Value::Number(n) => n.to_string(),
+                other => {
+                    return Err(format!(
+                        "attribute {}: expected a string, number or boolean, got {:?}",
+                        key, other
+                    ))
+                }
+            };
+            match key.as_str() {
+                "is_miner" => legacy.is_miner = Some(parse_bool(&key, &value)?),
+                "hashrate" => {
+                    legacy.hashrate = Some(
+                        Hashrate::parse(&value)
+                            .map_err(|e| format!("attribute hashrate: {}", e))?,
+                    )
+                }
+                "transaction_interval" => {
+                    legacy.transaction_interval = Some(parse_seconds(&key, &value)?)
+                }
+                "activity_start_time" => {
+                    legacy.activity_start_time = Some(parse_seconds(&key, &value)?)
+                }
+                "can_receive_distributions" => {
+                    legacy.can_receive_distributions = Some(parse_bool(&key, &value)?)
+                }
+                "min_transaction_amount" => {
+                    legacy.min_transaction_amount = Some(parse_amount(&key, &value)?)
+                }
+                "max_transaction_amount" => {
+                    legacy.max_transaction_amount = Some(parse_amount(&key, &value)?)
+                }
+                _ => {
+                    rest.insert(key, value);
+                }
+            }
+        }
+        Ok((Self::from_map(rest)?, legacy))
+    }
+
+    /// The `--attributes key value` pairs handed to agent scripts. False
+    /// flags are omitted.
+    pub fn to_map(&self) -> BTreeMap<String, String> {
+        let mut map = self.extra.clone();
+        for (key, set) in [
+            ("is_public_node", self.is_public_node),
+            ("is_seed_node", self.is_seed_node),
+            ("is_spy", self.is_spy),
+            ("is_unreachable", self.is_unreachable),
+        ] {
+            if set {
+                map.insert(key.to_string(), "true".to_string());
+            }
+        }
+        if let Some(targets) = &self.spy_targets {
+            map.insert("spy_targets".to_string(), targets.clone());
+        }
+        if let Some(location) = &self.location {
+            map.insert("location".to_string(), location.clone());
+        }
+        map
+    }
+
+    /// `extra` keys neither monerosim nor the bundled scripts read, each
+    /// with the closest known key when it looks like a typo
+    pub fn unrecognized(&self) -> Vec<(&str, Option<&'static str>)> {
+        let known = || {
+            TYPED_ATTRIBUTES
+                .iter()
+                .chain(LEGACY_ATTRIBUTES)
+                .chain(SCRIPT_ATTRIBUTES)
+        };
+        self.extra
+            .keys()
+            .filter(|key| !SCRIPT_ATTRIBUTES.contains(&key.as_str()))
+            .map(|key| {
+                let suggestion = known()
+                    .map(|k| (edit_distance(key, k), *k))
+                    .filter(|(d, _)| *d <= 2)
+                    .min()
+                    .map(|(_, k)| k);
+                (key.as_str(), suggestion)
+            })
+            .collect()
+    }
+}
+
+impl Serialize for UserAgentAttributes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_map().serialize(serializer)
+    }
+}
+
+/// Levenshtein distance, for typo suggestions
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitute.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(s: &str) -> BTreeMap<String, Value> {
+        serde_yaml::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn legacy_keys_are_split_out() {
+        let (attrs, legacy) = UserAgentAttributes::from_yaml(yaml(
+            "{is_miner: true, hashrate: \"10\", transaction_interval: 5m, \
+             can_receive_distributions: True, is_spy: true, location: Lisbon, motto: hi}",
+        ))
+        .unwrap();
+        assert_eq!(legacy.is_miner, Some(true));
+        assert_eq!(legacy.hashrate, Some(Hashrate::Weight(10.0)));
+        assert_eq!(legacy.transaction_interval, Some(300));
+        assert_eq!(legacy.can_receive_distributions, Some(true));
+        assert!(attrs.is_spy);
+        assert_eq!(attrs.location.as_deref(), Some("Lisbon"));
+        assert_eq!(
+            attrs.to_map(),
+            BTreeMap::from([
+                ("is_spy".to_string(), "true".to_string()),
+                ("location".to_string(), "Lisbon".to_string()),
+                ("motto".to_string(), "hi".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn mistyped_values_are_rejected() {
+        for bad in [
+            "{hashrate: fifty}",
+            "{is_spy: yes}",
+            "{is_miner: 1}",
+            "{min_transaction_amount: lots}",
+            "{transaction_interval: soon}",
+            "{motto: [a, b]}",
+        ] {
+            assert!(
+                UserAgentAttributes::from_yaml(yaml(bad)).is_err(),
+                "{}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn unrecognized_keys_suggest_close_matches() {
+        let (attrs, _) = UserAgentAttributes::from_yaml(yaml(
+            "{is_minner: true, tx_send_probability: 0.5, colour: red}",
+        ))
+        .unwrap();
+        assert_eq!(
+            attrs.unrecognized(),
+            [("colour", None), ("is_minner", Some("is_miner"))]
+        );
+    }
+}
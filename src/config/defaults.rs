@@ -16,6 +16,11 @@ pub(super) fn default_shadow_log_level() -> String {
     "info".to_string() // Reduced from "trace" to lower I/O overhead
 }
 
+/// Wallet-only agents discover a public node at runtime unless told otherwise
+pub(super) fn default_remote_daemon_address() -> String {
+    "auto".to_string()
+}
+
 pub(super) fn default_shared_dir() -> String {
     crate::shared_dir()
 }
@@ -27,3 +32,37 @@ pub(super) fn default_daemon_data_dir() -> String {
 pub(super) fn default_model_unblocked_syscall_latency() -> bool {
     true
 }
+
+pub(super) fn default_propagation_factor() -> f64 {
+    0.0075 // ms/km: light in fibre (~0.005) with ~1.5x route inflation
+}
+
+pub(super) fn default_processing_delay_ms() -> f64 {
+    1.0
+}
+
+pub(super) fn default_link_latency_ms() -> f64 {
+    50.0
+}
+
+pub(super) fn default_local_latency_ms() -> f64 {
+    1.0
+}
+
+pub(super) fn default_hybrid_seed_connections() -> usize {
+    2
+}
+
+pub(super) fn default_hybrid_topology_connections() -> usize {
+    6
+}
+
+/// monerod's P2P_DEFAULT_CONNECTIONS_COUNT, used when out-peers is not set
+pub(super) fn default_out_peers() -> i64 {
+    crate::MONERO_DEFAULT_OUT_PEERS as i64
+}
+
+/// Cap on `out_peers` / `in_peers` when `network.max_peers` is unset
+pub(super) fn default_max_peers() -> u32 {
+    256
+}
@@ -1,5 +1,7 @@
 //! Configuration validation error types.
 
+use crate::utils::duration::SimDuration;
+
 /// Errors from phase validation
 #[derive(Debug, thiserror::Error)]
 pub enum PhaseValidationError {
@@ -28,17 +30,10 @@ pub enum PhaseValidationError {
         phase_type: String,
         phase_num: u32,
         next_phase_num: u32,
-        stop_time: String,
-        start_time: String,
+        stop_time: SimDuration,
+        start_time: SimDuration,
         min_gap: u64,
     },
-
-    #[error("Invalid duration format for {phase_type} phase {phase_num}: {detail}")]
-    InvalidDuration {
-        phase_type: String,
-        phase_num: u32,
-        detail: String,
-    },
 }
 
 /// Configuration validation errors
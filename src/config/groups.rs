@@ -0,0 +1,231 @@
+//! `agent_groups`: shared defaults for agent entries.
+//!
+//! A group is a named set of agent fields. An entry with `group: <name>`
+//! takes every field of the group it does not set itself, so precedence is
+//! the agent's own value, then its group's, then the global
+//! `general.daemon_defaults` / `wallet_defaults` that generation applies
+//! under both. Mappings (`attributes`, `daemon_options`, `wallet_options`,
+//! the env maps) merge key by key with the same precedence, at any depth;
+//! any other value, lists included, is replaced whole.
+//!
+//! Merging runs on the raw YAML before `count:` replication and before
+//! `AgentConfig` is deserialized, so a replicated entry's instances all
+//! share its group and validation and generation only ever see the merged
+//! agents.
+
+use serde_yaml::{Mapping, Value};
+
+use super::migrate::RENAMED_AGENT_KEYS;
+
+/// Top-level section holding the groups
+pub const AGENT_GROUPS_KEY: &str = "agent_groups";
+/// Agent key naming the entry's group
+pub const GROUP_KEY: &str = "group";
+
+/// Agent keys a group cannot set: they name or count entries, or nest groups
+const ENTRY_ONLY_KEYS: &[&str] = &["count", "name", GROUP_KEY];
+
+/// Merge each agent entry's group into it and drop its `group` key.
+/// Returns whether any entry had a group. Errors name the entry or group.
+pub fn apply_agent_groups(doc: &mut Value) -> Result<bool, String> {
+    let groups = match doc.get(AGENT_GROUPS_KEY) {
+        None | Some(Value::Null) => Mapping::new(),
+        Some(Value::Mapping(groups)) => groups.clone(),
+        Some(other) => {
+            return Err(format!(
+                "{} must be a mapping of group name to agent fields, got {:?}",
+                AGENT_GROUPS_KEY, other
+            ))
+        }
+    };
+    for (name, group) in &groups {
+        let name = name.as_str().unwrap_or_default();
+        let group = group
+            .as_mapping()
+            .ok_or_else(|| format!("group '{}' must be a mapping of agent fields", name))?;
+        if let Some(key) = ENTRY_ONLY_KEYS.iter().find(|k| group.contains_key(**k)) {
+            return Err(format!(
+                "group '{}' cannot set '{}'; set it on the agent entries",
+                name, key
+            ));
+        }
+    }
+
+    let Some(agents) = doc.get_mut("agents").and_then(Value::as_mapping_mut) else {
+        return Ok(false);
+    };
+    let mut grouped = false;
+    for (id, entry) in agents.iter_mut() {
+        let Some(entry) = entry.as_mapping_mut() else {
+            continue;
+        };
+        let Some(group) = entry.remove(GROUP_KEY) else {
+            continue;
+        };
+        let id = id.as_str().unwrap_or_default();
+        let name = group
+            .as_str()
+            .ok_or_else(|| format!("agent '{}': group must be a string, got {:?}", id, group))?;
+        let defaults = groups
+            .get(name)
+            .and_then(Value::as_mapping)
+            .ok_or_else(|| {
+                let names: Vec<&str> = groups.keys().filter_map(Value::as_str).collect();
+                format!(
+                    "agent '{}': unknown group '{}' (defined in {}: {})",
+                    id,
+                    name,
+                    AGENT_GROUPS_KEY,
+                    if names.is_empty() {
+                        "none".to_string()
+                    } else {
+                        names.join(", ")
+                    }
+                )
+            })?;
+        merge_group(entry, defaults);
+        grouped = true;
+    }
+    Ok(grouped)
+}
+
+/// The current spelling of an agent key, so a group's `daemon` and an
+/// agent's `daemon_binary` count as the same field
+fn canonical(key: &Value) -> Option<&str> {
+    let key = key.as_str()?;
+    Some(
+        RENAMED_AGENT_KEYS
+            .iter()
+            .find(|(old, _)| *old == key)
+            .map_or(key, |(_, new)| new),
+    )
+}
+
+/// Fill `entry` from `defaults` where it does not set a field itself
+fn merge_group(entry: &mut Mapping, defaults: &Mapping) {
+    for (key, default) in defaults {
+        let own = entry
+            .keys()
+            .find(|k| canonical(k) == canonical(key))
+            .cloned();
+        match own {
+            None => {
+                entry.insert(key.clone(), default.clone());
+            }
+            Some(own) => {
+                if let (Some(Value::Mapping(own)), Value::Mapping(default)) =
+                    (entry.get_mut(&own), default)
+                {
+                    merge_mapping(own, default);
+                }
+            }
+        }
+    }
+}
+
+/// `merge_group` for nested mappings, where keys are plain data
+fn merge_mapping(own: &mut Mapping, defaults: &Mapping) {
+    for (key, default) in defaults {
+        match (own.get_mut(key), default) {
+            (None, _) => {
+                own.insert(key.clone(), default.clone());
+            }
+            (Some(Value::Mapping(own)), Value::Mapping(default)) => merge_mapping(own, default),
+            (Some(_), _) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(yaml: &str) -> Value {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn agent<'a>(doc: &'a Value, id: &str) -> &'a Value {
+        &doc["agents"][id]
+    }
+
+    #[test]
+    fn agents_override_their_group_field_by_field() {
+        let mut config = doc(r#"
+agent_groups:
+  heavy_users:
+    daemon: monerod
+    wallet: monero-wallet-rpc
+    transaction_interval: 30
+    daemon_args: ["--log-level=1"]
+    attributes:
+      tier: heavy
+      spend:
+        min: "0.1"
+        max: "1"
+    daemon_options:
+      out-peers: 16
+agents:
+  user-a:
+    group: heavy_users
+    daemon_binary: ./monerod-b
+    transaction_interval: 10
+    daemon_args: ["--log-level=2"]
+    attributes:
+      region: eu
+      spend:
+        max: "5"
+    daemon_options:
+      in-peers: 8
+  user-b:
+    group: heavy_users
+  miner:
+    daemon: monerod
+    hashrate: 10
+"#);
+        assert_eq!(apply_agent_groups(&mut config), Ok(true));
+
+        let a = agent(&config, "user-a");
+        assert!(a.get("group").is_none());
+        assert_eq!(a["daemon_binary"], "./monerod-b");
+        assert!(a.get("daemon").is_none(), "the alias counts as set");
+        assert_eq!(a["wallet"], "monero-wallet-rpc");
+        assert_eq!(a["transaction_interval"], 10);
+        assert_eq!(a["daemon_args"], doc(r#"["--log-level=2"]"#));
+        assert_eq!(
+            a["attributes"],
+            doc("{region: eu, spend: {max: \"5\", min: \"0.1\"}, tier: heavy}")
+        );
+        assert_eq!(a["daemon_options"], doc("{in-peers: 8, out-peers: 16}"));
+
+        let b = agent(&config, "user-b");
+        assert_eq!(b["daemon"], "monerod");
+        assert_eq!(b["attributes"]["spend"]["max"], "1");
+        assert_eq!(
+            agent(&config, "miner"),
+            &doc("{daemon: monerod, hashrate: 10}")
+        );
+    }
+
+    #[test]
+    fn unknown_groups_and_entry_keys_are_rejected() {
+        let mut config = doc("agent_groups: {light: {wallet: w}}\nagents: {u: {group: heavy}}");
+        let err = apply_agent_groups(&mut config).unwrap_err();
+        assert_eq!(
+            err,
+            "agent 'u': unknown group 'heavy' (defined in agent_groups: light)"
+        );
+
+        let mut config = doc("agents: {u: {group: heavy}}");
+        assert!(apply_agent_groups(&mut config)
+            .unwrap_err()
+            .ends_with("agent_groups: none)"));
+
+        let mut config = doc("agent_groups: {light: {count: 3}}\nagents: {}");
+        assert!(apply_agent_groups(&mut config)
+            .unwrap_err()
+            .contains("cannot set 'count'"));
+
+        let mut config = doc("agents: {u: {daemon: monerod}}");
+        assert_eq!(apply_agent_groups(&mut config), Ok(false));
+    }
+}
@@ -0,0 +1,348 @@
+//! Miner hashrates (`hashrate:`) and `general.hashrate_distribution`.
+//!
+//! A miner's `hashrate` is a plain weight (`25`, `12.5`), a share of the
+//! network (`"25%"`), or `auto`, assigned by the distribution. All of them
+//! resolve to absolute weights, which go to `miners.json` and the mining
+//! agents.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::utils::seed::seeded_hash;
+
+/// How far percentages may stray from summing to 100
+const PERCENT_TOLERANCE: f64 = 0.5;
+
+/// Pareto shape giving the classic 80/20 split
+const DEFAULT_PARETO_ALPHA: f64 = 1.16;
+
+/// One miner's configured hashrate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hashrate {
+    /// Relative weight, 0 < w <= 100
+    Weight(f64),
+    /// Percentage of the network; all percentages sum to about 100
+    Percent(f64),
+    /// Assigned by `general.hashrate_distribution`
+    Auto,
+}
+
+impl Hashrate {
+    /// Parse `25`, `12.5`, `25%` or `auto`
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        if s == "auto" {
+            return Ok(Hashrate::Auto);
+        }
+        let (number, percent) = match s.strip_suffix('%') {
+            Some(number) => (number.trim(), true),
+            None => (s, false),
+        };
+        let value: f64 = number.parse().map_err(|_| {
+            format!(
+                "invalid hashrate {:?}: expected a number, a percentage like \"25%\" or auto",
+                s
+            )
+        })?;
+        Ok(if percent {
+            Hashrate::Percent(value)
+        } else {
+            Hashrate::Weight(value)
+        })
+    }
+
+    /// The configured weight or percentage; None for `auto`
+    pub fn value(&self) -> Option<f64> {
+        match self {
+            Hashrate::Weight(v) | Hashrate::Percent(v) => Some(*v),
+            Hashrate::Auto => None,
+        }
+    }
+}
+
+impl From<u32> for Hashrate {
+    fn from(weight: u32) -> Self {
+        Hashrate::Weight(weight as f64)
+    }
+}
+
+impl fmt::Display for Hashrate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Hashrate::Weight(w) => write!(f, "{}", w),
+            Hashrate::Percent(p) => write!(f, "{}%", p),
+            Hashrate::Auto => f.write_str("auto"),
+        }
+    }
+}
+
+impl Serialize for Hashrate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Hashrate::Weight(w) => serialize_weight(w, serializer),
+            _ => serializer.collect_str(self),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Hashrate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        match serde_yaml::Value::deserialize(deserializer)? {
+            serde_yaml::Value::Number(n) => n
+                .as_f64()
+                .map(Hashrate::Weight)
+                .ok_or_else(|| D::Error::custom(format!("invalid hashrate: {}", n))),
+            serde_yaml::Value::String(s) => Hashrate::parse(&s).map_err(D::Error::custom),
+            other => Err(D::Error::custom(format!(
+                "invalid hashrate {:?}: expected a number, a percentage like \"25%\" or auto",
+                other
+            ))),
+        }
+    }
+}
+
+/// Whole weights as integers, so readers expecting the older integer
+/// weights keep working
+pub fn serialize_weight<S: Serializer>(weight: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    if weight.fract() == 0.0 && *weight >= 0.0 && *weight <= u64::MAX as f64 {
+        serializer.serialize_u64(*weight as u64)
+    } else {
+        serializer.serialize_f64(*weight)
+    }
+}
+
+/// `general.hashrate_distribution`: how `hashrate: auto` miners get their
+/// weights. `equal`, `pareto` / `pareto(alpha)`, or `explicit` (the
+/// default: every miner states its hashrate).
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum HashrateDistribution {
+    #[default]
+    Explicit,
+    Equal,
+    Pareto {
+        alpha: f64,
+    },
+}
+
+impl TryFrom<String> for HashrateDistribution {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, String> {
+        let s = s.trim();
+        match s {
+            "explicit" => return Ok(HashrateDistribution::Explicit),
+            "equal" => return Ok(HashrateDistribution::Equal),
+            "pareto" => {
+                return Ok(HashrateDistribution::Pareto {
+                    alpha: DEFAULT_PARETO_ALPHA,
+                })
+            }
+            _ => {}
+        }
+        let alpha = s
+            .strip_prefix("pareto(")
+            .and_then(|rest| rest.strip_suffix(')'))
+            .ok_or_else(|| {
+                format!(
+                    "invalid hashrate_distribution {:?}: expected equal, pareto, pareto(alpha) or explicit",
+                    s
+                )
+            })?;
+        let alpha: f64 = alpha
+            .trim()
+            .parse()
+            .ok()
+            .filter(|a: &f64| a.is_finite() && *a > 0.0)
+            .ok_or_else(|| format!("pareto alpha must be a positive number, got {:?}", alpha))?;
+        Ok(HashrateDistribution::Pareto { alpha })
+    }
+}
+
+impl From<HashrateDistribution> for String {
+    fn from(d: HashrateDistribution) -> String {
+        match d {
+            HashrateDistribution::Explicit => "explicit".to_string(),
+            HashrateDistribution::Equal => "equal".to_string(),
+            HashrateDistribution::Pareto { alpha } => format!("pareto({})", alpha),
+        }
+    }
+}
+
+/// Absolute weights for `miners` (agent id, hashrate). Percentages keep
+/// their value; `auto` miners share what the others leave of 100 according
+/// to `distribution`, a Pareto draw ordered by `seed`. Weights are rounded
+/// to thousandths.
+pub fn resolve_miner_weights<'a>(
+    miners: impl IntoIterator<Item = (&'a str, Hashrate)>,
+    distribution: HashrateDistribution,
+    seed: u64,
+) -> Result<BTreeMap<String, f64>, String> {
+    let mut weights = BTreeMap::new();
+    let mut auto = Vec::new();
+    let (mut percent_miner, mut weight_miner) = (None, None);
+    for (agent_id, hashrate) in miners {
+        let value = match hashrate {
+            Hashrate::Auto => {
+                auto.push(agent_id);
+                continue;
+            }
+            Hashrate::Percent(p) => {
+                percent_miner = Some(agent_id);
+                p
+            }
+            Hashrate::Weight(w) => {
+                weight_miner = Some(agent_id);
+                w
+            }
+        };
+        if !(value.is_finite() && value > 0.0 && value <= 100.0) {
+            return Err(format!(
+                "Mining agent '{}': hashrate {} out of valid range (must be above 0 and at most 100)",
+                agent_id, hashrate
+            ));
+        }
+        weights.insert(agent_id.to_string(), value);
+    }
+    if let (Some(p), Some(w)) = (percent_miner, weight_miner) {
+        return Err(format!(
+            "Mining agents mix percentages ('{}') and plain weights ('{}'); use one form",
+            p, w
+        ));
+    }
+
+    let configured: f64 = weights.values().sum();
+    if auto.is_empty() {
+        if percent_miner.is_some() && (configured - 100.0).abs() > PERCENT_TOLERANCE {
+            return Err(format!(
+                "Miner hashrate percentages sum to {}%, expected 100%",
+                configured
+            ));
+        }
+        return Ok(weights);
+    }
+
+    if distribution == HashrateDistribution::Explicit {
+        return Err(format!(
+            "Mining agent '{}' has hashrate auto but general.hashrate_distribution is explicit",
+            auto[0]
+        ));
+    }
+    let remaining = 100.0 - configured;
+    if remaining <= 0.0 {
+        return Err(format!(
+            "Configured hashrates already sum to {}, leaving nothing for the {} auto miner(s)",
+            configured,
+            auto.len()
+        ));
+    }
+    let n = auto.len();
+    let raw: Vec<f64> = match distribution {
+        HashrateDistribution::Pareto { alpha } => (0..n)
+            .map(|i| (1.0 - (i as f64 + 0.5) / n as f64).powf(-1.0 / alpha))
+            .collect(),
+        _ => vec![1.0; n],
+    };
+    let total: f64 = raw.iter().sum();
+    auto.sort_by_cached_key(|id| seeded_hash(seed, &format!("hashrate:{}", id)));
+    for (agent_id, r) in auto.into_iter().zip(raw) {
+        let weight = (r / total * remaining * 1000.0).round() / 1000.0;
+        weights.insert(agent_id.to_string(), weight);
+    }
+    Ok(weights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(
+        miners: &[(&str, &str)],
+        distribution: HashrateDistribution,
+    ) -> Result<BTreeMap<String, f64>, String> {
+        resolve_miner_weights(
+            miners
+                .iter()
+                .map(|(id, h)| (*id, Hashrate::parse(h).unwrap())),
+            distribution,
+            42,
+        )
+    }
+
+    #[test]
+    fn hashrates_parse_and_round_trip() {
+        for (input, expected) in [
+            ("25", Hashrate::Weight(25.0)),
+            ("12.5", Hashrate::Weight(12.5)),
+            ("25%", Hashrate::Percent(25.0)),
+            (" 33.3 % ", Hashrate::Percent(33.3)),
+            ("auto", Hashrate::Auto),
+        ] {
+            assert_eq!(Hashrate::parse(input), Ok(expected), "{}", input);
+        }
+        assert!(Hashrate::parse("25 percent").is_err());
+        let parsed: Vec<Hashrate> = serde_yaml::from_str("[25, 12.5, \"25%\", auto]").unwrap();
+        assert_eq!(
+            serde_yaml::to_string(&parsed).unwrap(),
+            "- 25\n- 12.5\n- 25%\n- auto\n"
+        );
+
+        let pareto: HashrateDistribution = serde_yaml::from_str("pareto(2)").unwrap();
+        assert_eq!(pareto, HashrateDistribution::Pareto { alpha: 2.0 });
+        assert!(serde_yaml::from_str::<HashrateDistribution>("pareto(-1)").is_err());
+        assert!(serde_yaml::from_str::<HashrateDistribution>("zipf").is_err());
+    }
+
+    #[test]
+    fn percentages_must_sum_to_100() {
+        let weights = resolve(
+            &[("a", "60%"), ("b", "39.8%")],
+            HashrateDistribution::Explicit,
+        )
+        .unwrap();
+        assert_eq!(weights["b"], 39.8);
+        let err = resolve(
+            &[("a", "60%"), ("b", "30%")],
+            HashrateDistribution::Explicit,
+        );
+        assert!(err.unwrap_err().contains("sum to 90%"));
+        let err = resolve(&[("a", "60%"), ("b", "40")], HashrateDistribution::Explicit);
+        assert!(err.unwrap_err().contains("mix percentages"));
+        // Plain weights keep their value whatever they sum to
+        let weights = resolve(&[("a", "5"), ("b", "2.5")], HashrateDistribution::Explicit).unwrap();
+        assert_eq!(weights["b"], 2.5);
+    }
+
+    #[test]
+    fn auto_miners_share_the_remainder() {
+        let weights = resolve(
+            &[("a", "40%"), ("b", "auto"), ("c", "auto"), ("d", "auto")],
+            HashrateDistribution::Equal,
+        )
+        .unwrap();
+        assert_eq!(weights["b"], 20.0);
+        assert_eq!(weights["d"], 20.0);
+
+        let ids: Vec<String> = (0..10).map(|i| format!("m{}", i)).collect();
+        let miners: Vec<(&str, &str)> = ids.iter().map(|id| (id.as_str(), "auto")).collect();
+        let weights = resolve(&miners, HashrateDistribution::Pareto { alpha: 1.16 }).unwrap();
+        let mut sorted: Vec<f64> = weights.values().copied().collect();
+        sorted.sort_by(f64::total_cmp);
+        assert!((sorted.iter().sum::<f64>() - 100.0).abs() < 0.01);
+        // Heavy-tailed: the largest miner dwarfs the smallest
+        assert!(sorted[9] > 5.0 * sorted[0], "{:?}", sorted);
+        assert_eq!(
+            resolve(&miners, HashrateDistribution::Pareto { alpha: 1.16 }).unwrap(),
+            weights
+        );
+
+        let err = resolve(&[("a", "auto")], HashrateDistribution::Explicit);
+        assert!(err.unwrap_err().contains("is explicit"));
+        let err = resolve(&[("a", "100"), ("b", "auto")], HashrateDistribution::Equal);
+        assert!(err.unwrap_err().contains("leaving nothing"));
+    }
+}
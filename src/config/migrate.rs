@@ -0,0 +1,372 @@
+//! `config_version` and the rewrites between versions.
+//!
+//! A config declares the schema it was written for with a top-level
+//! `config_version`; configs without one are version 1. Each [`Migration`]
+//! rewrites the raw YAML from one version to the next, and [`migrate`]
+//! chains them up to [`CONFIG_VERSION`]. Steps work on the document rather
+//! than on `Config`, so they can move keys the current structs no longer
+//! know about.
+
+use serde_yaml::{Mapping, Value};
+
+use super::attributes::LEGACY_ATTRIBUTES;
+
+/// The `config_version` this build writes and reads natively
+pub const CONFIG_VERSION: u32 = 2;
+
+/// Agent keys renamed in version 2
+pub(super) const RENAMED_AGENT_KEYS: &[(&str, &str)] = &[
+    ("daemon_binary", "daemon"),
+    ("wallet_binary", "wallet"),
+    ("daemon_arg_overrides", "daemon_options"),
+];
+
+/// First version whose unknown `general.shadow_experimental` keys are
+/// passed through to Shadow; older configs predate pass-through, so an
+/// unknown key there is rejected as a typo
+pub(super) const SHADOW_PASSTHROUGH_VERSION: u32 = 2;
+
+/// `shadow:` keys renamed in version 2
+const RENAMED_SHADOW_KEYS: &[(&str, &str)] = &[("workers", "parallelism")];
+
+/// One version-to-version rewrite of a raw config
+pub struct Migration {
+    /// Version the step reads; it writes `from + 1`
+    pub from: u32,
+    pub summary: &'static str,
+    /// Rewrites the document in place, returning one note per change
+    apply: fn(&mut Mapping) -> Result<Vec<String>, String>,
+}
+
+/// Every step, oldest first
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    from: 1,
+    summary: "renames daemon_binary, wallet_binary, daemon_arg_overrides and shadow.workers, \
+              and moves typed agent fields out of attributes",
+    apply: v1_to_v2,
+}];
+
+/// A spelling an older version used, with what replaced it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeprecatedField {
+    /// Dotted path, e.g. `agents.miner-001.daemon_binary`
+    pub path: String,
+    /// Path of the field that replaced it, or why it is gone
+    pub replacement: String,
+}
+
+/// The document's `config_version`: 1 when absent, an error when it is not
+/// a positive integer
+pub fn declared_version(doc: &Value) -> Result<u32, String> {
+    match doc.get("config_version") {
+        None => Ok(1),
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v > 0)
+            .ok_or_else(|| format!("config_version must be a positive integer, got {:?}", v)),
+    }
+}
+
+/// Spellings in `doc` that version 2 replaced, in document order
+pub fn deprecated_fields(doc: &Value) -> Vec<DeprecatedField> {
+    let mut found = Vec::new();
+    if let Some(shadow) = doc.get("shadow").and_then(Value::as_mapping) {
+        for (old, new) in RENAMED_SHADOW_KEYS {
+            if shadow.contains_key(*old) {
+                found.push(DeprecatedField {
+                    path: format!("shadow.{}", old),
+                    replacement: format!("shadow.{}", new),
+                });
+            }
+        }
+    }
+    let Some(agents) = doc.get("agents").and_then(Value::as_mapping) else {
+        return found;
+    };
+    for (id, entry) in agents {
+        let (Some(id), Some(entry)) = (id.as_str(), entry.as_mapping()) else {
+            continue;
+        };
+        for (old, new) in RENAMED_AGENT_KEYS {
+            if entry.contains_key(*old) {
+                found.push(DeprecatedField {
+                    path: format!("agents.{}.{}", id, old),
+                    replacement: format!("agents.{}.{}", id, new),
+                });
+            }
+        }
+        let Some(attributes) = entry.get("attributes").and_then(Value::as_mapping) else {
+            continue;
+        };
+        for key in LEGACY_ATTRIBUTES {
+            if attributes.contains_key(*key) {
+                found.push(DeprecatedField {
+                    path: format!("agents.{}.attributes.{}", id, key),
+                    replacement: match *key {
+                        "is_miner" => "implied by agents.{id}.hashrate".replace("{id}", id),
+                        key => format!("agents.{}.{}", id, key),
+                    },
+                });
+            }
+        }
+    }
+    found
+}
+
+/// Rewrite `doc` to [`CONFIG_VERSION`], returning what changed; an empty
+/// list when it already is. Fails on documents newer than this build.
+pub fn migrate(doc: &mut Value) -> Result<Vec<String>, String> {
+    let mut version = declared_version(doc)?;
+    if version > CONFIG_VERSION {
+        return Err(format!(
+            "config_version {} is newer than this monerosim supports ({}); \
+             a config cannot be migrated to an older version",
+            version, CONFIG_VERSION
+        ));
+    }
+    let root = doc
+        .as_mapping_mut()
+        .ok_or("the config must be a YAML mapping")?;
+    let mut notes = Vec::new();
+    while version < CONFIG_VERSION {
+        let step = MIGRATIONS
+            .iter()
+            .find(|m| m.from == version)
+            .ok_or_else(|| format!("no migration from config_version {}", version))?;
+        notes.extend(
+            (step.apply)(root)
+                .map_err(|e| format!("config_version {} -> {}: {}", version, version + 1, e))?,
+        );
+        version += 1;
+        set_version(root, version);
+        notes.push(format!("set config_version: {}", version));
+    }
+    Ok(notes)
+}
+
+/// Write `config_version` as the first key
+fn set_version(root: &mut Mapping, version: u32) {
+    root.remove("config_version");
+    let rest = std::mem::take(root);
+    root.insert("config_version".into(), version.into());
+    root.extend(rest);
+}
+
+/// Rename `old` to `new` in `map`, keeping its position
+fn rename_key(map: &mut Mapping, old: &str, new: &str) -> Result<bool, String> {
+    if !map.contains_key(old) {
+        return Ok(false);
+    }
+    if map.contains_key(new) {
+        return Err(format!("both {} and {} are set", old, new));
+    }
+    *map = std::mem::take(map)
+        .into_iter()
+        .map(|(k, v)| match k.as_str() {
+            Some(key) if key == old => (Value::from(new), v),
+            _ => (k, v),
+        })
+        .collect();
+    Ok(true)
+}
+
+/// Version 2 spells agent binaries and option maps without the old
+/// aliases, names `shadow.workers` `parallelism`, and sets typed agent
+/// fields directly instead of inside `attributes`
+fn v1_to_v2(root: &mut Mapping) -> Result<Vec<String>, String> {
+    let mut notes = Vec::new();
+    if let Some(shadow) = root.get_mut("shadow").and_then(Value::as_mapping_mut) {
+        for (old, new) in RENAMED_SHADOW_KEYS {
+            if rename_key(shadow, old, new).map_err(|e| format!("shadow: {}", e))? {
+                notes.push(format!("shadow.{} -> shadow.{}", old, new));
+            }
+        }
+    }
+    let Some(agents) = root.get_mut("agents").and_then(Value::as_mapping_mut) else {
+        return Ok(notes);
+    };
+    for (id, entry) in agents.iter_mut() {
+        let id = id.as_str().unwrap_or_default().to_string();
+        let Some(entry) = entry.as_mapping_mut() else {
+            continue;
+        };
+        for (old, new) in RENAMED_AGENT_KEYS {
+            if rename_key(entry, old, new).map_err(|e| format!("agent '{}': {}", id, e))? {
+                notes.push(format!("agents.{}.{} -> {}", id, old, new));
+            }
+        }
+        notes.extend(lift_legacy_attributes(&id, entry)?);
+    }
+    Ok(notes)
+}
+
+/// Move the typed fields older configs set inside `attributes` onto the
+/// agent, converting the string values the old form allowed
+fn lift_legacy_attributes(id: &str, entry: &mut Mapping) -> Result<Vec<String>, String> {
+    let Some(attributes) = entry.get_mut("attributes").and_then(Value::as_mapping_mut) else {
+        return Ok(Vec::new());
+    };
+    let mut lifted = Vec::new();
+    for key in LEGACY_ATTRIBUTES {
+        if let Some(value) = attributes.remove(*key) {
+            lifted.push((*key, value));
+        }
+    }
+    if attributes.is_empty() {
+        entry.remove("attributes");
+    }
+    // is_miner is checked against the hashrate, which may be lifted too
+    lifted.sort_by_key(|(key, _)| *key == "is_miner");
+
+    let mut notes = Vec::new();
+    for (key, value) in lifted {
+        let path = format!("agents.{}.attributes.{}", id, key);
+        if key == "is_miner" {
+            let is_miner = matches!(&value, Value::Bool(true))
+                || value
+                    .as_str()
+                    .is_some_and(|s| s.eq_ignore_ascii_case("true"));
+            if is_miner && !entry.contains_key("hashrate") {
+                return Err(format!("{}: is_miner: true requires a hashrate", path));
+            }
+            notes.push(format!("{} removed (implied by hashrate)", path));
+            continue;
+        }
+        let value = match (key, value) {
+            ("can_receive_distributions", Value::String(s)) => match s.to_lowercase().as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                _ => return Err(format!("{}: expected true or false, got {:?}", path, s)),
+            },
+            ("min_transaction_amount" | "max_transaction_amount", Value::Number(n)) => {
+                Value::String(n.to_string())
+            }
+            (_, value) => value,
+        };
+        match entry.get(key) {
+            Some(existing) if *existing != value => {
+                return Err(format!(
+                    "{} ({:?}) conflicts with agents.{}.{} ({:?})",
+                    path, value, id, key, existing
+                ))
+            }
+            Some(_) => {}
+            None => {
+                entry.insert(key.into(), value);
+            }
+        }
+        notes.push(format!("{} -> agents.{}.{}", path, id, key));
+    }
+    Ok(notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(yaml: &str) -> Value {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn migrations_chain_up_to_the_current_version() {
+        for (i, step) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(step.from, i as u32 + 1, "steps start at 1 without gaps");
+        }
+        assert_eq!(MIGRATIONS.len() as u32 + 1, CONFIG_VERSION);
+    }
+
+    #[test]
+    fn declared_version_defaults_to_one() {
+        assert_eq!(declared_version(&doc("general: {}")), Ok(1));
+        assert_eq!(declared_version(&doc("config_version: 2")), Ok(2));
+        assert!(declared_version(&doc("config_version: 0")).is_err());
+        assert!(declared_version(&doc("config_version: two")).is_err());
+    }
+
+    #[test]
+    fn v1_to_v2_renames_and_lifts_legacy_fields() {
+        let mut config = doc(r#"
+general:
+  stop_time: 1h
+shadow:
+  workers: 4
+agents:
+  miner:
+    daemon_binary: monerod
+    daemon_arg_overrides:
+      log-level: 1
+    attributes:
+      is_miner: "true"
+      hashrate: "25"
+  user:
+    wallet_binary: monero-wallet-rpc
+    attributes:
+      transaction_interval: "5m"
+      can_receive_distributions: "true"
+      min_transaction_amount: 0.5
+      location: eu
+"#);
+        assert_eq!(deprecated_fields(&config).len(), 9);
+        let notes = migrate(&mut config).unwrap();
+        assert_eq!(notes.last().unwrap(), "set config_version: 2");
+        assert_eq!(
+            config,
+            doc(r#"
+config_version: 2
+general:
+  stop_time: 1h
+shadow:
+  parallelism: 4
+agents:
+  miner:
+    daemon: monerod
+    daemon_options:
+      log-level: 1
+    hashrate: "25"
+  user:
+    wallet: monero-wallet-rpc
+    attributes:
+      location: eu
+    transaction_interval: "5m"
+    can_receive_distributions: true
+    min_transaction_amount: "0.5"
+"#)
+        );
+        assert!(deprecated_fields(&config).is_empty());
+        assert_eq!(migrate(&mut config), Ok(Vec::new()), "already current");
+    }
+
+    #[test]
+    fn v1_to_v2_refuses_to_guess_between_conflicting_fields() {
+        let renamed = migrate(&mut doc(
+            "agents:\n  a:\n    daemon: x\n    daemon_binary: y\n",
+        ));
+        assert!(renamed
+            .unwrap_err()
+            .contains("both daemon_binary and daemon"));
+
+        let lifted = migrate(&mut doc(
+            "agents:\n  a:\n    hashrate: 10\n    attributes:\n      hashrate: 20\n",
+        ));
+        assert!(lifted
+            .unwrap_err()
+            .contains("conflicts with agents.a.hashrate"));
+
+        let miner = migrate(&mut doc(
+            "agents:\n  a:\n    attributes:\n      is_miner: true\n",
+        ));
+        assert!(miner.unwrap_err().contains("requires a hashrate"));
+    }
+
+    #[test]
+    fn newer_configs_are_not_downgraded() {
+        let err = migrate(&mut doc("config_version: 99")).unwrap_err();
+        assert!(
+            err.contains("newer than this monerosim supports"),
+            "{}",
+            err
+        );
+    }
+}
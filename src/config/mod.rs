@@ -7,7 +7,22 @@
 //!   `DaemonConfig`, `AgentDefinitions`, etc.)
 //! - `agent_config`: per-agent configuration (`AgentConfig`, `OptionValue`)
 //!   plus its custom `Deserialize` impl and the flat-phase-field parser.
-//! - `phases`: `DaemonPhase`, `WalletPhase`, and `MIN_PHASE_GAP_SECONDS`.
+//! - `attributes`: typed agent `attributes` (`UserAgentAttributes`) and the
+//!   migration of legacy typed keys out of them.
+//! - `phases`: `DaemonPhase`, `WalletPhase`, `DaemonRestart`, and
+//!   `MIN_PHASE_GAP_SECONDS`.
+//! - `hashrate`: miner `Hashrate`s, `HashrateDistribution` and weight
+//!   resolution.
+//! - `replication`: `count:` expansion of agent entries.
+//! - `groups`: `agent_groups` defaults merged into agent entries.
+//! - `sweep`: the `sweep` section's parameter axes and the dot-path
+//!   overrides `monerosim sweep` applies per combination.
+//! - `migrate`: `config_version` and the version-to-version rewrites behind
+//!   `--migrate`.
+//! - `fields`: known field names of the derived structs.
+//! - `resolved`: `NetworkSettings` and the ignored-field report behind
+//!   `--print-resolved-config`.
+//! - `workload`: per-agent transaction `WorkloadProfile`s.
 //! - `defaults`: serde `default = "..."` value functions.
 //! - `validation`: phase-validation logic (`validate_daemon_phases`).
 //! - `errors`: `PhaseValidationError` and `ValidationError`.
@@ -16,18 +31,46 @@
 //! using `use crate::config::SomeType;` unchanged.
 
 mod agent_config;
+mod attributes;
 mod defaults;
 mod errors;
+pub(crate) mod fields;
+mod groups;
+mod hashrate;
+mod migrate;
 mod phases;
+mod replication;
+mod resolved;
+mod sweep;
 mod types;
 mod validation;
+mod workload;
 
+pub(crate) use agent_config::unknown_agent_keys;
 pub use agent_config::{AgentConfig, OptionValue};
+pub use attributes::UserAgentAttributes;
 pub use errors::{PhaseValidationError, ValidationError};
-pub use phases::{DaemonPhase, WalletPhase, MIN_PHASE_GAP_SECONDS};
+pub use groups::{apply_agent_groups, AGENT_GROUPS_KEY, GROUP_KEY};
+pub use hashrate::{resolve_miner_weights, serialize_weight, Hashrate, HashrateDistribution};
+pub use migrate::{
+    declared_version, deprecated_fields, migrate, DeprecatedField, Migration, CONFIG_VERSION,
+    MIGRATIONS,
+};
+pub use phases::{DaemonPhase, DaemonRestart, WalletPhase, MIN_PHASE_GAP_SECONDS};
+pub use resolved::{resolve, IgnoredField, IgnoredKind, NetworkSettings, ResolvedConfig};
+pub use sweep::{
+    check_overrides, expand_sweep, get_path, set_path, sweep_section, SweepAxis, SweepConfig,
+    SweepVariant, SWEEP_KEY,
+};
 pub use types::{
-    AgentDefinitions, Config, DaemonConfig, DaemonSelectionStrategy, Distribution,
-    DistributionStrategy, FallbackSeedsMode, GeneralConfig, Network, PeerMode, PerformanceConfig,
-    RegionWeights, Topology, TurnoverConfig,
+    AgentDefinitions, AgentPlacement, BinariesConfig, BlockControllerConfig, Chain, Config,
+    ConnectionEnforcement, DaemonConfig, DaemonSelectionStrategy, Distribution, DistributionPolicy,
+    DistributionStrategy, FallbackSeedsMode, GeneralConfig, HybridOptions, IpVersion,
+    LatencySynthesis, LinkProfile, MinerDistributorConfig, MinerPlacement, MinerSelector,
+    MiningPoolConfig, MonitorMetric, MonitorOutputFormat, Network, NetworkEvent,
+    NetworkEventAction, PeerDiscovery, PeerMode, PerformanceConfig, RecipientSelector,
+    RegionWeights, ResourceModelConfig, SeedSelection, ShadowExperimentalConfig, ShadowOptions,
+    ShadowScheduler, StartupConfig, Topology, TurnoverConfig,
 };
 pub use validation::validate_daemon_phases;
+pub use workload::{RateWindow, WorkloadProfile};
@@ -3,6 +3,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
+use crate::utils::duration::SimDuration;
+
 /// Configuration for a single daemon phase in an upgrade scenario
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct DaemonPhase {
@@ -16,10 +18,10 @@ pub struct DaemonPhase {
     pub env: Option<BTreeMap<String, String>>,
     /// Start time for this phase (default: "0s" for phase 0)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub start: Option<String>,
+    pub start: Option<SimDuration>,
     /// Stop time for this phase (when to send SIGTERM)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub stop: Option<String>,
+    pub stop: Option<SimDuration>,
 }
 
 /// Configuration for a single wallet phase in an upgrade scenario
@@ -35,10 +37,26 @@ pub struct WalletPhase {
     pub env: Option<BTreeMap<String, String>>,
     /// Start time for this phase
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub start: Option<String>,
+    pub start: Option<SimDuration>,
     /// Stop time for this phase (when to send SIGTERM)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub stop: Option<String>,
+    pub stop: Option<SimDuration>,
+}
+
+/// One daemon restart (crash or upgrade): monerod is stopped at `stop_at`
+/// and started again at `start_at` on the same data directory and IP
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DaemonRestart {
+    /// When to send SIGTERM to the running daemon
+    pub stop_at: SimDuration,
+    /// When the daemon comes back up
+    pub start_at: SimDuration,
+    /// Binary to restart with (default: the one that was running)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binary_path: Option<String>,
+    /// Arguments added to `daemon_args` from this restart on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_args: Option<Vec<String>>,
 }
 
 /// Minimum gap between phase stop and next phase start (in seconds)
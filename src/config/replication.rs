@@ -0,0 +1,347 @@
+//! `count:` replication for agent entries.
+//!
+//! An entry with `count: N` stands for N otherwise identical agents named
+//! `{key}-001`..`{key}-NNN` (declaration order). Per-instance differences are
+//! limited on purpose: `hashrate` may be a list (one value per instance) or a
+//! `{min, max}` range spread linearly across the instances, and string
+//! `attributes` may use `{i}` (1-based instance number) and `{id}`.
+//!
+//! Any entry may carry a `name`, which replaces the key (or, as a list with
+//! one name per instance, the positional `{key}-NNN` ids) as the agent id.
+//! Names are host names, so they must be RFC 1123 labels.
+//!
+//! Expansion runs on the raw YAML before `AgentConfig` is deserialized, so
+//! everything downstream (IP allocation, miner registry, validation) only
+//! ever sees plain agents, and configs without `count` parse exactly as before.
+
+use serde_yaml::{Mapping, Value};
+use std::collections::HashMap;
+
+/// Expand every `count:` entry, keeping declaration order. Errors name the
+/// offending entry.
+pub(super) fn expand_agent_entries(entries: Mapping) -> Result<Vec<(String, Value)>, String> {
+    let mut out: Vec<(String, Value)> = Vec::new();
+    // id -> entry that produced it, to name both sides of a collision
+    let mut seen: HashMap<String, String> = HashMap::new();
+    let mut push = |id: String, value: Value, origin: &str| {
+        if let Some(first) = seen.insert(id.clone(), origin.to_string()) {
+            return Err(format!(
+                "duplicate agent id '{}' (produced by both '{}' and '{}')",
+                id, first, origin
+            ));
+        }
+        out.push((id, value));
+        Ok(())
+    };
+
+    for (key, mut value) in entries {
+        let key = key
+            .as_str()
+            .ok_or_else(|| format!("agent ids must be strings, got {:?}", key))?
+            .to_string();
+        let name = value.as_mapping_mut().and_then(|m| m.remove("name"));
+        let count = match value.as_mapping().and_then(|m| m.get("count")) {
+            None => {
+                let id = match name {
+                    None => key.clone(),
+                    Some(Value::String(name)) => {
+                        check_host_name(&name).map_err(|e| format!("agent '{}': {}", key, e))?;
+                        name
+                    }
+                    Some(other) => {
+                        return Err(format!(
+                            "agent '{}': name must be a string, got {:?}",
+                            key, other
+                        ))
+                    }
+                };
+                push(id, value, &key)?;
+                continue;
+            }
+            Some(c) => c
+                .as_u64()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| format!("agent '{}': count must be a positive integer", key))?
+                as usize,
+        };
+        let mut template = value.as_mapping().cloned().unwrap_or_default();
+        template.remove("count");
+        let hashrates = per_instance_hashrates(template.get("hashrate"), count)
+            .map_err(|e| format!("agent '{}': {}", key, e))?;
+        let names = per_instance_names(name.as_ref(), count)
+            .map_err(|e| format!("agent '{}': {}", key, e))?;
+
+        let width = count.to_string().len().max(3);
+        for i in 0..count {
+            let id = match &names {
+                Some(names) => names[i].clone(),
+                None => format!("{}-{:0width$}", key, i + 1, width = width),
+            };
+            let mut instance = template.clone();
+            if let Some(h) = &hashrates {
+                instance.insert("hashrate".into(), h[i].clone());
+            }
+            if let Some(Value::Mapping(attrs)) = instance.get_mut("attributes") {
+                for v in attrs.values_mut() {
+                    if let Value::String(s) = v {
+                        *s = s.replace("{i}", &(i + 1).to_string()).replace("{id}", &id);
+                    }
+                }
+            }
+            push(id, Value::Mapping(instance), &key)?;
+        }
+    }
+    Ok(out)
+}
+
+/// None when `name` is absent (instances keep positional ids)
+fn per_instance_names(spec: Option<&Value>, count: usize) -> Result<Option<Vec<String>>, String> {
+    let list = match spec {
+        None => return Ok(None),
+        Some(Value::Sequence(list)) => list,
+        Some(other) => {
+            return Err(format!(
+                "name on a count entry must be a list of {} names, got {:?}",
+                count, other
+            ))
+        }
+    };
+    if list.len() != count {
+        return Err(format!(
+            "name list has {} values but count is {}",
+            list.len(),
+            count
+        ));
+    }
+    list.iter()
+        .map(|v| {
+            let name = v
+                .as_str()
+                .ok_or_else(|| format!("name list entry {:?} is not a string", v))?;
+            check_host_name(name)?;
+            Ok(name.to_string())
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+/// Agent names become Shadow host names: one RFC 1123 label of ASCII
+/// letters, digits and hyphens, not starting or ending with a hyphen
+fn check_host_name(name: &str) -> Result<(), String> {
+    let valid = (1..=63).contains(&name.len())
+        && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        && !name.starts_with('-')
+        && !name.ends_with('-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "name '{}' is not a valid host name (1-63 letters, digits or hyphens, \
+             not starting or ending with a hyphen)",
+            name
+        ))
+    }
+}
+
+/// None when `hashrate` is absent or a single value (every instance keeps it)
+fn per_instance_hashrates(
+    spec: Option<&Value>,
+    count: usize,
+) -> Result<Option<Vec<Value>>, String> {
+    match spec {
+        None | Some(Value::Number(_)) | Some(Value::String(_)) => Ok(None),
+        Some(Value::Sequence(list)) => {
+            if list.len() != count {
+                return Err(format!(
+                    "hashrate list has {} values but count is {}",
+                    list.len(),
+                    count
+                ));
+            }
+            list.iter()
+                .map(|v| match v {
+                    Value::Number(_) | Value::String(_) => Ok(v.clone()),
+                    _ => Err(format!(
+                        "hashrate list entry {:?} is not a number or percentage",
+                        v
+                    )),
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(Some)
+        }
+        Some(Value::Mapping(range)) => {
+            let bound = |name: &str| {
+                range
+                    .get(name)
+                    .and_then(Value::as_u64)
+                    .ok_or_else(|| format!("hashrate range needs integer '{}'", name))
+            };
+            let (min, max) = (bound("min")?, bound("max")?);
+            if min > max {
+                return Err(format!("hashrate range min {} exceeds max {}", min, max));
+            }
+            if count == 1 {
+                return Ok(Some(vec![Value::from(min)]));
+            }
+            let step = (max - min) as f64 / (count - 1) as f64;
+            Ok(Some(
+                (0..count)
+                    .map(|i| Value::from(min + (step * i as f64).round() as u64))
+                    .collect(),
+            ))
+        }
+        Some(other) => Err(format!(
+            "hashrate must be a number, a percentage, a list or {{min, max}}, got {:?}",
+            other
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::AgentDefinitions;
+
+    fn parse(yaml: &str) -> Result<AgentDefinitions, serde_yaml::Error> {
+        serde_yaml::from_str(yaml)
+    }
+
+    #[test]
+    fn mixed_explicit_and_counted_entries_expand() {
+        let agents = parse(
+            r#"
+pool:
+  daemon: monerod
+  hashrate: 40
+miner:
+  count: 3
+  daemon: monerod
+  wallet: monero-wallet-rpc
+  hashrate: [10, 20, 30]
+user:
+  count: 4
+  daemon: monerod
+  script: agents.regular_user
+  attributes:
+    label: "shop {i}"
+    owner: "{id}"
+monitor:
+  script: agents.simulation_monitor
+"#,
+        )
+        .unwrap()
+        .agents;
+
+        let ids: Vec<&str> = agents.keys().map(String::as_str).collect();
+        assert_eq!(
+            ids,
+            [
+                "miner-001",
+                "miner-002",
+                "miner-003",
+                "monitor",
+                "pool",
+                "user-001",
+                "user-002",
+                "user-003",
+                "user-004"
+            ]
+        );
+        let hashrates: Vec<Option<String>> = ["pool", "miner-001", "miner-002", "miner-003"]
+            .iter()
+            .map(|id| agents[*id].hashrate.map(|h| h.to_string()))
+            .collect();
+        assert_eq!(
+            hashrates,
+            ["40", "10", "20", "30"].map(|h| Some(h.to_string()))
+        );
+        assert!(agents["user-003"].hashrate.is_none());
+
+        let attrs = agents["user-003"].attributes.as_ref().unwrap();
+        assert_eq!(attrs.extra["label"], "shop 3");
+        assert_eq!(attrs.extra["owner"], "user-003");
+        assert!(agents["user-001"].has_local_daemon());
+        assert!(!agents["user-001"].is_miner());
+    }
+
+    #[test]
+    fn hashrate_range_is_spread_across_instances() {
+        let agents =
+            parse("miner:\n  count: 5\n  daemon: monerod\n  hashrate: {min: 10, max: 50}\n")
+                .unwrap()
+                .agents;
+        let hashrates: Vec<Option<f64>> = agents
+            .values()
+            .map(|a| a.hashrate.and_then(|h| h.value()))
+            .collect();
+        assert_eq!(hashrates, [10.0, 20.0, 30.0, 40.0, 50.0].map(Some));
+    }
+
+    #[test]
+    fn bad_counts_and_hashrate_lists_are_rejected() {
+        for (yaml, expected) in [
+            (
+                "u:\n  count: 0\n  daemon: monerod\n",
+                "count must be a positive integer",
+            ),
+            (
+                "m:\n  count: 3\n  daemon: monerod\n  hashrate: [1, 2]\n",
+                "hashrate list has 2 values but count is 3",
+            ),
+        ] {
+            let err = parse(yaml).unwrap_err().to_string();
+            assert!(err.contains(expected), "{}", err);
+        }
+    }
+
+    #[test]
+    fn expansion_colliding_with_an_explicit_id_is_rejected() {
+        let err = parse(
+            r#"
+miner-001:
+  daemon: monerod
+  hashrate: 40
+miner:
+  count: 2
+  daemon: monerod
+  hashrate: 10
+"#,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("duplicate agent id 'miner-001'"), "{}", err);
+    }
+
+    #[test]
+    fn names_replace_keys_and_positional_ids() {
+        let agents = parse(
+            r#"
+shop:
+  name: coffee-shop
+  daemon: monerod
+relay:
+  count: 2
+  name: [relay-north, relay-south]
+  daemon: monerod
+  attributes:
+    owner: "{id}"
+"#,
+        )
+        .unwrap()
+        .agents;
+        let ids: Vec<&str> = agents.keys().map(String::as_str).collect();
+        assert_eq!(ids, ["coffee-shop", "relay-north", "relay-south"]);
+        let attrs = agents["relay-south"].attributes.as_ref().unwrap();
+        assert_eq!(attrs.extra["owner"], "relay-south");
+    }
+
+    #[test]
+    fn names_must_be_host_names() {
+        for name in ["-edge", "edge-", "has_underscore", "dotted.name", "\"\""] {
+            let err = parse(&format!("a:\n  name: {}\n  daemon: monerod\n", name))
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("is not a valid host name"), "{}", err);
+        }
+        assert!(parse("a:\n  name: Node-7\n  daemon: monerod\n").is_ok());
+    }
+}
@@ -0,0 +1,467 @@
+//! The configuration as generation actually applies it.
+//!
+//! Some fields are accepted but have no effect, either never (not
+//! implemented) or only in combination with other settings (e.g. `topology`
+//! outside Hybrid peer mode). [`resolve`] fills in the network defaults the
+//! orchestrator uses and lists every such field with the reason, warning
+//! about the unimplemented ones. `--print-resolved-config` dumps the result.
+//!
+//! Every `general:` field is classified in [`GENERAL_FIELDS`]; a test checks
+//! the table against the struct, so a new field cannot be added without
+//! deciding how it is applied.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use super::defaults::default_max_peers;
+use super::types::{
+    Config, ConnectionEnforcement, Distribution, DistributionStrategy, HybridOptions,
+    MinerPlacement, Network, PeerMode, RegionWeights, SeedSelection, Topology,
+};
+
+/// How a `general:` field reaches the generated simulation
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Handling {
+    Applied,
+    /// Accepted for compatibility, has no effect; the reason says what
+    /// happens instead
+    Unimplemented(&'static str),
+}
+
+const GENERAL_FIELDS: &[(&str, Handling)] = &[
+    ("stop_time", Handling::Applied),
+    ("fresh_blockchain", Handling::Applied),
+    (
+        "python_venv",
+        Handling::Unimplemented("agents use the venv detected in the working directory"),
+    ),
+    ("log_level", Handling::Applied),
+    ("simulation_seed", Handling::Applied),
+    ("chain", Handling::Applied),
+    ("fixed_difficulty", Handling::Applied),
+    ("parallelism", Handling::Applied),
+    ("enable_dns_server", Handling::Applied),
+    ("difficulty_cache_ttl", Handling::Applied),
+    ("shadow_log_level", Handling::Applied),
+    ("runahead", Handling::Applied),
+    ("shadow_experimental", Handling::Applied),
+    ("bootstrap_end_time", Handling::Applied),
+    ("progress", Handling::Applied),
+    ("process_threads", Handling::Applied),
+    ("native_preemption", Handling::Applied),
+    ("daemon_defaults", Handling::Applied),
+    ("wallet_defaults", Handling::Applied),
+    ("shared_dir", Handling::Applied),
+    ("daemon_data_dir", Handling::Applied),
+    ("blockchain_source_dir", Handling::Applied),
+    ("fallback_seeds", Handling::Applied),
+    ("reachable_fraction", Handling::Applied),
+    ("reachable_by_role", Handling::Applied),
+    ("hidden_fraction", Handling::Applied),
+    ("turnover", Handling::Applied),
+    ("binaries", Handling::Applied),
+    ("strict_validation", Handling::Applied),
+    ("agents_path", Handling::Applied),
+    ("startup", Handling::Applied),
+    ("resource_model", Handling::Applied),
+    ("memory_limit", Handling::Applied),
+    ("cpu_weight", Handling::Applied),
+    ("pcap_capture_dir", Handling::Applied),
+    ("hashrate_distribution", Handling::Applied),
+];
+
+static LINK_PROFILE_DISTRIBUTION: Distribution = Distribution {
+    strategy: DistributionStrategy::Sequential,
+    weights: None,
+};
+
+/// Peer discovery and placement settings with the defaults applied
+/// (Dynamic peer mode, no seeds, DAG topology, Global distribution).
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkSettings {
+    pub peer_mode: PeerMode,
+    pub seed_nodes: Vec<String>,
+    pub topology: Option<Topology>,
+    pub hybrid_options: Option<HybridOptions>,
+    pub connection_enforcement: ConnectionEnforcement,
+    pub seed_selection: SeedSelection,
+    /// None leaves the policy's default (5 for `auto`, required count for
+    /// `first_n`)
+    pub seed_count: Option<usize>,
+    /// None defaults to Global in `distribute_agents_across_topology`
+    pub distribution_strategy: Option<DistributionStrategy>,
+    pub distribution_weights: Option<RegionWeights>,
+    /// GML networks only
+    pub miner_placement: Option<MinerPlacement>,
+    /// Share of the user agents per region; switch networks only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geographic_distribution: Option<BTreeMap<String, f64>>,
+    /// Share of the agents per AS; GML networks only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub as_weights: Option<BTreeMap<String, f64>>,
+    /// Shadow's topology keeps only the nodes needed between hosts; GML
+    /// networks only
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub prune_unused_nodes: bool,
+    /// Network-wide `--out-peers` / `--in-peers`; None leaves monerod's
+    /// default unless an agent sets its own
+    pub out_peers: Option<u32>,
+    pub in_peers: Option<u32>,
+    /// Largest peer limit an agent may ask for
+    pub max_peers: u32,
+}
+
+impl NetworkSettings {
+    pub fn from_config(config: &Config) -> Self {
+        let (
+            peer_mode,
+            seed_nodes,
+            seed_selection,
+            seed_count,
+            topology,
+            hybrid_options,
+            enforcement,
+            distribution,
+            miner_placement,
+        ) = match &config.network {
+            Some(Network::Gml {
+                peer_mode,
+                seed_nodes,
+                seed_selection,
+                seed_count,
+                topology,
+                hybrid_options,
+                connection_enforcement,
+                distribution,
+                miner_placement,
+                ..
+            }) => (
+                peer_mode,
+                seed_nodes,
+                seed_selection,
+                seed_count,
+                topology,
+                hybrid_options,
+                connection_enforcement,
+                distribution.as_ref(),
+                miner_placement.as_ref(),
+            ),
+            // Switch topology doesn't use distribution config; the
+            // link-profile graph has one node per continent, so
+            // sequential placement cycles agents over the continents
+            Some(Network::Switch {
+                peer_mode,
+                seed_nodes,
+                seed_selection,
+                seed_count,
+                topology,
+                hybrid_options,
+                connection_enforcement,
+                link_profile,
+                ..
+            }) => (
+                peer_mode,
+                seed_nodes,
+                seed_selection,
+                seed_count,
+                topology,
+                hybrid_options,
+                connection_enforcement,
+                link_profile.as_ref().map(|_| &LINK_PROFILE_DISTRIBUTION),
+                None,
+            ),
+            None => (&None, &None, &None, &None, &None, &None, &None, None, None),
+        };
+        let (out_peers, in_peers, max_peers) = match &config.network {
+            Some(
+                Network::Gml {
+                    out_peers,
+                    in_peers,
+                    max_peers,
+                    ..
+                }
+                | Network::Switch {
+                    out_peers,
+                    in_peers,
+                    max_peers,
+                    ..
+                },
+            ) => (*out_peers, *in_peers, *max_peers),
+            None => (None, None, None),
+        };
+        let (geographic_distribution, as_weights, prune_unused_nodes) = match &config.network {
+            Some(Network::Switch {
+                geographic_distribution,
+                ..
+            }) => (geographic_distribution.clone(), None, false),
+            Some(Network::Gml {
+                as_weights,
+                prune_unused_nodes,
+                ..
+            }) => (
+                None,
+                as_weights.clone(),
+                prune_unused_nodes.unwrap_or(false),
+            ),
+            None => (None, None, false),
+        };
+        NetworkSettings {
+            peer_mode: peer_mode.clone().unwrap_or(PeerMode::Dynamic),
+            seed_nodes: seed_nodes.clone().unwrap_or_default(),
+            seed_selection: seed_selection.unwrap_or_default(),
+            seed_count: *seed_count,
+            topology: Some(topology.clone().unwrap_or(Topology::Dag)),
+            hybrid_options: hybrid_options.clone(),
+            connection_enforcement: enforcement.unwrap_or_default(),
+            distribution_strategy: distribution.map(|d| d.strategy.clone()),
+            distribution_weights: distribution.and_then(|d| d.weights.clone()),
+            miner_placement: miner_placement.cloned(),
+            geographic_distribution,
+            as_weights,
+            prune_unused_nodes,
+            out_peers,
+            in_peers,
+            max_peers: max_peers.unwrap_or_else(default_max_peers),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IgnoredKind {
+    /// Never has an effect
+    Unimplemented,
+    /// Has no effect with the rest of this configuration
+    Inapplicable,
+}
+
+/// A configured field generation does not use
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IgnoredField {
+    /// Dotted path, e.g. `general.fresh_blockchain`
+    pub field: String,
+    pub kind: IgnoredKind,
+    pub reason: String,
+}
+
+/// `--print-resolved-config` output
+#[derive(Debug, Serialize)]
+pub struct ResolvedConfig<'a> {
+    pub config: &'a Config,
+    pub network: NetworkSettings,
+    pub ignored: Vec<IgnoredField>,
+}
+
+/// Resolve a loaded, validated config, warning once per unimplemented field
+/// it sets.
+pub fn resolve(config: &Config) -> ResolvedConfig<'_> {
+    let network = NetworkSettings::from_config(config);
+    let mut ignored = Vec::new();
+    let mut push = |field: String, kind: IgnoredKind, reason: String| {
+        ignored.push(IgnoredField {
+            field,
+            kind,
+            reason,
+        })
+    };
+
+    // Unset optional fields are skipped when serializing, so the keys
+    // present are the ones the config sets (or that have serde defaults)
+    let general = serde_yaml::to_value(&config.general).unwrap_or_default();
+    for (name, handling) in GENERAL_FIELDS {
+        if let Handling::Unimplemented(reason) = handling {
+            if general.get(*name).is_some() {
+                push(
+                    format!("general.{}", name),
+                    IgnoredKind::Unimplemented,
+                    reason.to_string(),
+                );
+            }
+        }
+    }
+
+    match &config.network {
+        Some(Network::Switch {
+            bandwidth, latency, ..
+        }) => {
+            for (name, value) in [("bandwidth", bandwidth), ("latency", latency)] {
+                if value.is_some() {
+                    push(
+                        format!("network.{}", name),
+                        IgnoredKind::Unimplemented,
+                        "switch networks use the built-in Shadow graph for `type`; \
+                         set `link_profile` for latency and loss"
+                            .to_string(),
+                    );
+                }
+            }
+        }
+        Some(Network::Gml { .. }) | None => {}
+    }
+
+    let explicit_topology = matches!(
+        &config.network,
+        Some(Network::Gml {
+            topology: Some(_),
+            ..
+        }) | Some(Network::Switch {
+            topology: Some(_),
+            ..
+        })
+    );
+    let wired = match network.peer_mode {
+        PeerMode::Hybrid => true,
+        PeerMode::Hardcoded => matches!(
+            network.topology,
+            Some(Topology::Tree { .. } | Topology::SmallWorld { .. })
+        ),
+        PeerMode::Dynamic => false,
+    };
+    if explicit_topology && !wired {
+        push(
+            "network.topology".to_string(),
+            IgnoredKind::Inapplicable,
+            format!(
+                "topology connections are only wired in Hybrid peer_mode, or Hardcoded for \
+                 Tree and SmallWorld (peer_mode is {:?})",
+                network.peer_mode
+            ),
+        );
+    }
+
+    let counted = match network.seed_selection {
+        SeedSelection::Auto => network.peer_mode != PeerMode::Dynamic,
+        SeedSelection::FirstN => true,
+        SeedSelection::Miners | SeedSelection::Explicit | SeedSelection::Attribute => false,
+    };
+    if network.seed_count.is_some() && !counted {
+        push(
+            "network.seed_count".to_string(),
+            IgnoredKind::Inapplicable,
+            format!(
+                "seed_count only sizes seed_selection first_n, or auto outside Dynamic peer_mode \
+                 (seed_selection is {:?}, peer_mode is {:?})",
+                network.seed_selection, network.peer_mode
+            ),
+        );
+    }
+
+    for (id, agent) in &config.agents.agents {
+        let mines = agent
+            .script
+            .as_deref()
+            .is_some_and(|s| s.contains("autonomous_miner"));
+        if agent.hashrate.is_some() && !mines {
+            push(
+                format!("agents.{}.hashrate", id),
+                IgnoredKind::Inapplicable,
+                "only agents running agents.autonomous_miner mine; the agent is still \
+                 started and registered as a miner"
+                    .to_string(),
+            );
+        }
+    }
+
+    if config.sweep.is_some() {
+        push(
+            "sweep".to_string(),
+            IgnoredKind::Inapplicable,
+            "only `monerosim sweep` expands the axes; this run generates the base config"
+                .to_string(),
+        );
+    }
+
+    ResolvedConfig {
+        config,
+        network,
+        ignored,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::fields::struct_fields;
+    use crate::config::GeneralConfig;
+
+    #[test]
+    fn every_general_field_is_classified() {
+        let mut fields = struct_fields::<GeneralConfig>().to_vec();
+        let mut table: Vec<&str> = GENERAL_FIELDS.iter().map(|(name, _)| *name).collect();
+        assert!(!fields.is_empty());
+        fields.sort();
+        table.sort();
+        assert_eq!(
+            fields, table,
+            "GENERAL_FIELDS must list every GeneralConfig field"
+        );
+    }
+
+    #[test]
+    fn ignored_fields_are_reported() {
+        let config: Config = serde_yaml::from_str(
+            r#"
+general:
+  stop_time: 1h
+  python_venv: venv
+network:
+  type: 1_gbit_switch
+  latency: 10ms
+  peer_mode: Dynamic
+  topology: Mesh
+agents:
+  miner:
+    daemon: monerod
+    wallet: monero-wallet-rpc
+    script: agents.autonomous_miner
+    hashrate: 50
+  relay:
+    daemon: monerod
+    wallet: monero-wallet-rpc
+    hashrate: 50
+"#,
+        )
+        .unwrap();
+        let resolved = resolve(&config);
+        let ignored: Vec<(&str, IgnoredKind)> = resolved
+            .ignored
+            .iter()
+            .map(|f| (f.field.as_str(), f.kind))
+            .collect();
+        assert_eq!(
+            ignored,
+            [
+                ("general.python_venv", IgnoredKind::Unimplemented),
+                ("network.latency", IgnoredKind::Unimplemented),
+                ("network.topology", IgnoredKind::Inapplicable),
+                ("agents.relay.hashrate", IgnoredKind::Inapplicable),
+            ]
+        );
+        assert_eq!(resolved.network.peer_mode, PeerMode::Dynamic);
+        assert_eq!(resolved.network.topology, Some(Topology::Mesh));
+    }
+
+    #[test]
+    fn defaults_leave_nothing_ignored() {
+        let config: Config =
+            serde_yaml::from_str("general:\n  stop_time: 1h\nagents: {}\n").unwrap();
+        let resolved = resolve(&config);
+        assert!(resolved.ignored.is_empty(), "{:?}", resolved.ignored);
+        assert_eq!(resolved.network.topology, Some(Topology::Dag));
+    }
+
+    #[test]
+    fn hardcoded_tree_is_wired() {
+        let config: Config = serde_yaml::from_str(
+            "general:\n  stop_time: 1h\nnetwork:\n  type: 1_gbit_switch\n  \
+             peer_mode: Hardcoded\n  topology:\n    Tree:\n      fanout: 3\nagents: {}\n",
+        )
+        .unwrap();
+        let resolved = resolve(&config);
+        assert_eq!(
+            resolved.network.topology,
+            Some(Topology::Tree { fanout: 3 })
+        );
+        assert!(resolved.ignored.is_empty(), "{:?}", resolved.ignored);
+    }
+}
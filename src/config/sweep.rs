@@ -0,0 +1,562 @@
+//! `sweep:` parameter sweeps.
+//!
+//! A sweep lists axes, each a dotted path into the config and the values
+//! to put there:
+//!
+//! ```yaml
+//! sweep:
+//!   axes:
+//!     - path: network.out_peers
+//!       values: [8, 12, 16]
+//!     - path: network.topology
+//!       values: [Mesh, Ring]
+//! ```
+//!
+//! `monerosim sweep` generates one simulation per combination (the first
+//! axis varies slowest). Overrides are applied to the raw YAML before
+//! anything is deserialized, so a path reaches into an untagged enum such
+//! as `network` the same way whichever variant the base config uses, and
+//! `agent_groups` and `count:` see the overridden values. Path segments are
+//! mapping keys, or indices into lists; missing mappings along the way are
+//! created. An untagged enum silently drops fields its variant does not
+//! have, so each combination's parsed config is checked to still hold its
+//! overrides (`check_overrides`).
+
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+
+use crate::utils::duration::SimDuration;
+
+/// Top-level section holding the sweep
+pub const SWEEP_KEY: &str = "sweep";
+
+/// `sweep:`: the axes whose combinations `monerosim sweep` generates
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SweepConfig {
+    pub axes: Vec<SweepAxis>,
+}
+
+/// One swept parameter
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SweepAxis {
+    /// Dotted path into the config, e.g. `network.out_peers` or
+    /// `agents.user.transaction_interval`
+    pub path: String,
+    /// Values put at `path`, any YAML the field accepts
+    pub values: Vec<Value>,
+    /// Name in the variant directory names; the path's last segment when
+    /// unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// One per value, in the directory names; required for values that are
+    /// mappings or lists, which have no short form of their own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<String>>,
+}
+
+impl SweepAxis {
+    /// `name`, else the path's last segment
+    pub fn display_name(&self) -> &str {
+        self.name
+            .as_deref()
+            .unwrap_or_else(|| self.path.rsplit('.').next().unwrap_or(&self.path))
+    }
+
+    /// The directory-name form of each value
+    fn value_labels(&self) -> Result<Vec<String>, String> {
+        if let Some(labels) = &self.labels {
+            return Ok(labels.iter().map(|l| sanitize(l)).collect());
+        }
+        self.values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                scalar_label(value).map(|l| sanitize(&l)).ok_or_else(|| {
+                    format!(
+                        "sweep axis '{}': value {} is a {}; give the axis `labels`",
+                        self.display_name(),
+                        i + 1,
+                        kind(value)
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+impl SweepConfig {
+    pub(super) fn validate(&self) -> Result<(), String> {
+        if self.axes.is_empty() {
+            return Err("sweep needs at least one axis".to_string());
+        }
+        let mut names: Vec<&str> = Vec::new();
+        for axis in &self.axes {
+            if axis.path.split('.').any(str::is_empty) {
+                return Err(format!(
+                    "sweep axis path '{}' must be dot-separated field names",
+                    axis.path
+                ));
+            }
+            if axis.path == SWEEP_KEY || axis.path.starts_with("sweep.") {
+                return Err(format!(
+                    "sweep axis path '{}' cannot sweep the sweep",
+                    axis.path
+                ));
+            }
+            let name = axis.display_name();
+            if names.contains(&name) {
+                return Err(format!(
+                    "two sweep axes are named '{}'; set `name` on one of them",
+                    name
+                ));
+            }
+            names.push(name);
+            if axis.values.is_empty() {
+                return Err(format!("sweep axis '{}' has no values", name));
+            }
+            if let Some(labels) = &axis.labels {
+                if labels.len() != axis.values.len() {
+                    return Err(format!(
+                        "sweep axis '{}' has {} labels for {} values",
+                        name,
+                        labels.len(),
+                        axis.values.len()
+                    ));
+                }
+            }
+            let labels = axis.value_labels()?;
+            for (i, label) in labels.iter().enumerate() {
+                if labels[..i].contains(label) {
+                    return Err(format!(
+                        "sweep axis '{}' has two values labelled '{}'",
+                        name, label
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One combination of axis values
+#[derive(Debug, Clone)]
+pub struct SweepVariant {
+    /// `name=label` per axis, comma-separated; used as its directory name
+    pub name: String,
+    /// (axis name, path, value) in axis order
+    pub overrides: Vec<(String, String, Value)>,
+    /// The base config with the overrides applied and `sweep` removed
+    pub doc: Value,
+}
+
+/// The `sweep` section of a raw config, if it has one
+pub fn sweep_section(doc: &Value) -> Result<Option<SweepConfig>, String> {
+    match doc.get(SWEEP_KEY) {
+        None | Some(Value::Null) => Ok(None),
+        Some(section) => {
+            let sweep: SweepConfig = serde_yaml::from_value(section.clone())
+                .map_err(|e| format!("invalid sweep section: {}", e))?;
+            sweep.validate()?;
+            Ok(Some(sweep))
+        }
+    }
+}
+
+/// Every combination of the sweep's axis values applied to `doc`, first
+/// axis slowest
+pub fn expand_sweep(doc: &Value) -> Result<Vec<SweepVariant>, String> {
+    let sweep =
+        sweep_section(doc)?.ok_or_else(|| format!("the config has no {} section", SWEEP_KEY))?;
+    let labels: Vec<Vec<String>> = sweep
+        .axes
+        .iter()
+        .map(SweepAxis::value_labels)
+        .collect::<Result<_, _>>()?;
+    let mut base = doc.clone();
+    if let Some(root) = base.as_mapping_mut() {
+        root.remove(SWEEP_KEY);
+    }
+
+    let total: usize = sweep.axes.iter().map(|a| a.values.len()).product();
+    let mut variants = Vec::with_capacity(total);
+    for n in 0..total {
+        // Mixed-radix digits of n, last axis fastest
+        let mut rest = n;
+        let mut picks = vec![0; sweep.axes.len()];
+        for (pick, axis) in picks.iter_mut().zip(&sweep.axes).rev() {
+            *pick = rest % axis.values.len();
+            rest /= axis.values.len();
+        }
+
+        let mut doc = base.clone();
+        let mut name = Vec::new();
+        let mut overrides = Vec::new();
+        for ((axis, labels), &pick) in sweep.axes.iter().zip(&labels).zip(&picks) {
+            let value = axis.values[pick].clone();
+            set_path(&mut doc, &axis.path, value.clone())
+                .map_err(|e| format!("sweep axis '{}': {}", axis.display_name(), e))?;
+            name.push(format!(
+                "{}={}",
+                sanitize(axis.display_name()),
+                labels[pick]
+            ));
+            overrides.push((axis.display_name().to_string(), axis.path.clone(), value));
+        }
+        variants.push(SweepVariant {
+            name: name.join(","),
+            overrides,
+            doc,
+        });
+    }
+    Ok(variants)
+}
+
+/// Put `value` at the dotted `path` in `doc`, creating missing mappings on
+/// the way. A segment indexes a list when the value there is one.
+pub fn set_path(doc: &mut Value, path: &str, value: Value) -> Result<(), String> {
+    let segments: Vec<&str> = path.split('.').collect();
+    let (last, parents) = segments
+        .split_last()
+        .ok_or_else(|| "empty path".to_string())?;
+    let mut current = doc;
+    for (i, segment) in segments.iter().enumerate() {
+        if current.is_null() {
+            *current = Value::Mapping(Mapping::new());
+        }
+        let at = segments[..i].join(".");
+        let is_last = i == parents.len();
+        current = match current {
+            Value::Mapping(map) => {
+                let key = Value::String(segment.to_string());
+                if is_last {
+                    map.insert(key, value);
+                    return Ok(());
+                }
+                map.entry(key).or_insert(Value::Null)
+            }
+            Value::Sequence(items) => {
+                let len = items.len();
+                let index = segment
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&i| i < len)
+                    .ok_or_else(|| {
+                        format!(
+                            "'{}' is a list of {}; '{}' is not an index into it",
+                            at, len, segment
+                        )
+                    })?;
+                if is_last {
+                    items[index] = value;
+                    return Ok(());
+                }
+                &mut items[index]
+            }
+            other => {
+                return Err(format!(
+                    "'{}' is a {}, so '{}' cannot be set inside it",
+                    at,
+                    kind(other),
+                    path
+                ))
+            }
+        };
+    }
+    unreachable!("the last segment ({}) returns above", last)
+}
+
+/// The value at the dotted `path`, if there is one
+pub fn get_path<'a>(doc: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(doc, |current, segment| match current {
+            Value::Mapping(map) => map.get(segment),
+            Value::Sequence(items) => items.get(segment.parse::<usize>().ok()?),
+            Value::Tagged(tagged) if tagged.tag == segment => Some(&tagged.value),
+            _ => None,
+        })
+}
+
+/// Check that the config parsed from `variant` still holds each override.
+/// `parsed` is that config serialized back to YAML. Paths under `agents`
+/// and `agent_groups` are skipped: groups and `count:` reshape those
+/// entries, and the loader already rejects unknown agent keys.
+pub fn check_overrides(parsed: &Value, variant: &SweepVariant) -> Result<(), String> {
+    for (name, path, value) in &variant.overrides {
+        let top = path.split('.').next().unwrap_or_default();
+        if top == "agents" || top == super::AGENT_GROUPS_KEY || value.is_null() {
+            continue;
+        }
+        let kept = get_path(parsed, path).is_some_and(|actual| contains(actual, value));
+        if !kept {
+            return Err(format!(
+                "sweep axis '{}': {} = {} did not survive parsing the config; the \
+                 field does not exist there (for `network`, check it belongs to the \
+                 base config's network type)",
+                name,
+                path,
+                serde_json::to_string(value).unwrap_or_default()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Whether `actual` holds everything in `expected`: mappings key by key
+/// (defaults may add keys), lists element by element, numbers and
+/// durations by value.
+/// Enum variants with data serialize as `!Variant` tagged values, which
+/// match the `{Variant: ...}` mapping form configs use.
+fn contains(actual: &Value, expected: &Value) -> bool {
+    match (untag(actual), untag(expected)) {
+        (Some(actual), Some(expected)) => contains(&actual, &expected),
+        (Some(actual), None) => contains(&actual, expected),
+        (None, Some(expected)) => contains(actual, &expected),
+        (None, None) => contains_untagged(actual, expected),
+    }
+}
+
+fn contains_untagged(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Mapping(actual), Value::Mapping(expected)) => expected
+            .iter()
+            .all(|(k, v)| v.is_null() || actual.get(k).is_some_and(|actual| contains(actual, v))),
+        (Value::Sequence(actual), Value::Sequence(expected)) => {
+            actual.len() == expected.len()
+                && actual.iter().zip(expected).all(|(a, e)| contains(a, e))
+        }
+        (Value::Number(a), Value::Number(e)) => a.as_f64() == e.as_f64(),
+        (Value::String(a), e) if same_duration(a, e) => true,
+        _ => actual == expected,
+    }
+}
+
+/// Durations re-serialize in their largest unit, so `120m` or `7200` comes
+/// back as `2h`
+fn same_duration(actual: &str, expected: &Value) -> bool {
+    let expected = match expected {
+        Value::String(s) => s.parse::<SimDuration>().ok(),
+        Value::Number(n) => n.as_u64().map(SimDuration::from_secs),
+        _ => None,
+    };
+    expected.is_some_and(|e| actual.parse() == Ok(e))
+}
+
+/// `!Variant value` as the mapping `{Variant: value}`
+fn untag(value: &Value) -> Option<Value> {
+    let Value::Tagged(tagged) = value else {
+        return None;
+    };
+    let variant = tagged.tag.to_string().trim_start_matches('!').to_string();
+    let mut map = Mapping::new();
+    map.insert(Value::String(variant), tagged.value.clone());
+    Some(Value::Mapping(map))
+}
+
+/// A scalar's own text, for directory names
+fn scalar_label(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Null => Some("null".to_string()),
+        Value::Sequence(_) | Value::Mapping(_) | Value::Tagged(_) => None,
+    }
+}
+
+/// Keep a label safe as (part of) a directory name
+fn sanitize(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Sequence(_) => "list",
+        Value::Mapping(_) => "mapping",
+        Value::Tagged(_) => "tagged value",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, Network, Topology};
+
+    const BASE: &str = r#"
+general:
+  stop_time: 1h
+network:
+  type: 1_gbit_switch
+  peer_mode: Hybrid
+agents:
+  user:
+    daemon: monerod
+    wallet: monero-wallet-rpc
+    count: 2
+sweep:
+  axes:
+    - path: network.out_peers
+      values: [8, 12]
+    - path: network.topology
+      values: [Mesh, {Tree: {fanout: 3}}]
+      labels: [Mesh, tree3]
+"#;
+
+    fn variants(yaml: &str) -> Result<Vec<SweepVariant>, String> {
+        expand_sweep(&serde_yaml::from_str(yaml).unwrap())
+    }
+
+    #[test]
+    fn combinations_vary_the_last_axis_fastest() {
+        let variants = variants(BASE).unwrap();
+        let names: Vec<&str> = variants.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "out_peers=8,topology=Mesh",
+                "out_peers=8,topology=tree3",
+                "out_peers=12,topology=Mesh",
+                "out_peers=12,topology=tree3"
+            ]
+        );
+        let last = &variants[3];
+        assert!(last.doc.get(SWEEP_KEY).is_none());
+        assert_eq!(
+            get_path(&last.doc, "network.out_peers"),
+            Some(&Value::from(12))
+        );
+        assert_eq!(
+            get_path(&last.doc, "network.type"),
+            Some(&Value::from("1_gbit_switch"))
+        );
+    }
+
+    #[test]
+    fn overrides_reach_into_the_network_enum() {
+        for variant in variants(BASE).unwrap() {
+            let config: Config = serde_yaml::from_value(variant.doc.clone()).unwrap();
+            let Some(Network::Switch {
+                out_peers,
+                topology,
+                ..
+            }) = &config.network
+            else {
+                panic!("{}: not a switch network", variant.name);
+            };
+            assert!(matches!(out_peers, Some(8 | 12)));
+            assert!(matches!(
+                topology,
+                Some(Topology::Mesh | Topology::Tree { fanout: 3 })
+            ));
+            check_overrides(&serde_yaml::to_value(&config).unwrap(), &variant).unwrap();
+        }
+    }
+
+    #[test]
+    fn whole_sections_and_variants_can_be_swapped() {
+        let yaml = BASE.replace(
+            "  axes:\n",
+            "  axes:\n    - path: network\n      name: net\n      values:\n        \
+             - {type: 1_gbit_switch, peer_mode: Dynamic}\n        \
+             - {path: topo.gml, peer_mode: Dynamic}\n      labels: [switch, gml]\n",
+        );
+        let variants = variants(&yaml).unwrap();
+        assert_eq!(variants.len(), 8);
+        assert!(variants[4].name.starts_with("net=gml,"));
+        // The axes after it still apply to the swapped-in network
+        let config: Config = serde_yaml::from_value(variants[7].doc.clone()).unwrap();
+        let Some(Network::Gml {
+            path, out_peers, ..
+        }) = &config.network
+        else {
+            panic!("not a GML network");
+        };
+        assert_eq!((path.as_str(), *out_peers), ("topo.gml", Some(12)));
+    }
+
+    #[test]
+    fn fields_the_network_variant_lacks_are_caught() {
+        // `path` belongs to GML networks; the switch network drops it
+        let yaml = BASE.replace("path: network.out_peers", "path: network.path");
+        let variant = &variants(&yaml).unwrap()[0];
+        let config: Config = serde_yaml::from_value(variant.doc.clone()).unwrap();
+        let err = check_overrides(&serde_yaml::to_value(&config).unwrap(), variant).unwrap_err();
+        assert!(err.contains("network.path = 8 did not survive"), "{}", err);
+    }
+
+    #[test]
+    fn durations_match_in_any_unit() {
+        let yaml = BASE.replace(
+            "  axes:\n",
+            "  axes:\n    - path: general.stop_time\n      values: [120m, 7200, 90m]\n",
+        );
+        for variant in variants(&yaml).unwrap() {
+            let config: Config = serde_yaml::from_value(variant.doc.clone()).unwrap();
+            check_overrides(&serde_yaml::to_value(&config).unwrap(), &variant).unwrap();
+        }
+    }
+
+    #[test]
+    fn paths_index_lists_and_create_mappings() {
+        let mut doc: Value =
+            serde_yaml::from_str("general: {stop_time: 1h}\nlist: [a, b]\n").unwrap();
+        set_path(&mut doc, "list.1", Value::from("c")).unwrap();
+        set_path(
+            &mut doc,
+            "block_controller.block_interval",
+            Value::from("30s"),
+        )
+        .unwrap();
+        set_path(&mut doc, "general.stop_time", Value::from("2h")).unwrap();
+        assert_eq!(get_path(&doc, "list.1"), Some(&Value::from("c")));
+        assert_eq!(
+            get_path(&doc, "block_controller.block_interval"),
+            Some(&Value::from("30s"))
+        );
+        assert_eq!(
+            get_path(&doc, "general.stop_time"),
+            Some(&Value::from("2h"))
+        );
+
+        let err = set_path(&mut doc, "list.2", Value::from("d")).unwrap_err();
+        assert!(err.contains("not an index"), "{}", err);
+        let err = set_path(&mut doc, "general.stop_time.unit", Value::from("h")).unwrap_err();
+        assert!(err.contains("'general.stop_time' is a string"), "{}", err);
+    }
+
+    #[test]
+    fn invalid_axes_are_rejected() {
+        let axes = |axes: &str| variants(&format!("general: {{}}\nsweep:\n  axes: {}\n", axes));
+        assert!(axes("[]").unwrap_err().contains("at least one axis"));
+        assert!(axes("[{path: a.x, values: []}]")
+            .unwrap_err()
+            .contains("has no values"));
+        assert!(axes("[{path: a.x, values: [1]}, {path: b.x, values: [2]}]")
+            .unwrap_err()
+            .contains("two sweep axes are named 'x'"));
+        assert!(axes("[{path: a.x, values: [{k: 1}]}]")
+            .unwrap_err()
+            .contains("give the axis `labels`"));
+        assert!(axes("[{path: a.x, values: [1, 2], labels: [one]}]")
+            .unwrap_err()
+            .contains("1 labels for 2 values"));
+        assert!(axes("[{path: a..x, values: [1]}]")
+            .unwrap_err()
+            .contains("dot-separated"));
+        assert!(axes("[{path: a.x, values: [a b, a/b]}]")
+            .unwrap_err()
+            .contains("two values labelled 'a_b'"));
+    }
+}
@@ -5,12 +5,27 @@
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
+use crate::ip::as_manager::{parse_subnet_size, AsRegion};
+use crate::topology::{schedule_network_events, ScheduledNetworkEvent};
+use crate::utils::duration::SimDuration;
+
 use super::agent_config::{AgentConfig, OptionValue};
 use super::defaults::{
-    default_daemon_data_dir, default_difficulty_cache_ttl, default_model_unblocked_syscall_latency,
-    default_parallelism, default_shadow_log_level, default_shared_dir, default_simulation_seed,
+    default_daemon_data_dir, default_difficulty_cache_ttl, default_hybrid_seed_connections,
+    default_hybrid_topology_connections, default_link_latency_ms, default_local_latency_ms,
+    default_model_unblocked_syscall_latency, default_out_peers, default_parallelism,
+    default_processing_delay_ms, default_propagation_factor, default_remote_daemon_address,
+    default_shadow_log_level, default_shared_dir, default_simulation_seed,
 };
 use super::errors::ValidationError;
+use super::hashrate::HashrateDistribution;
+use super::migrate::SHADOW_PASSTHROUGH_VERSION;
+use super::replication::expand_agent_entries;
+use super::sweep::SweepConfig;
+
+/// How far `network.geographic_distribution` and `as_weights` may sum from
+/// 1.0
+pub const SHARE_SUM_TOLERANCE: f64 = 1e-3;
 
 /// Peer mode options for network configuration
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -20,6 +35,77 @@ pub enum PeerMode {
     Hybrid,
 }
 
+/// Monero network the daemons and wallets run on (`general.chain`).
+///
+/// - `Regtest` (default): miners add blocks with the `generateblocks` RPC.
+/// - `Testnet` / `Stagenet`: those networks' consensus rules and default
+///   ports (28080.. / 38080..); blocks need real mining.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Chain {
+    #[default]
+    Regtest,
+    Testnet,
+    Stagenet,
+}
+
+impl Chain {
+    pub const ALL: [Chain; 3] = [Chain::Regtest, Chain::Testnet, Chain::Stagenet];
+
+    /// Inverse of [`Chain::name`]
+    pub fn from_name(name: &str) -> Option<Chain> {
+        Chain::ALL.into_iter().find(|c| c.name() == name)
+    }
+
+    /// Config spelling, also passed to agent scripts as `--network`
+    pub fn name(self) -> &'static str {
+        match self {
+            Chain::Regtest => "regtest",
+            Chain::Testnet => "testnet",
+            Chain::Stagenet => "stagenet",
+        }
+    }
+
+    /// monerod flags selecting the chain
+    pub fn daemon_flags(self) -> &'static [&'static str] {
+        match self {
+            Chain::Regtest => &["--regtest", "--keep-fakechain"],
+            Chain::Testnet => &["--testnet"],
+            Chain::Stagenet => &["--stagenet"],
+        }
+    }
+
+    /// monero-wallet-rpc flag selecting the chain; regtest wallets use
+    /// mainnet addresses and take none
+    pub fn wallet_flag(self) -> Option<&'static str> {
+        match self {
+            Chain::Regtest => None,
+            Chain::Testnet => Some("--testnet"),
+            Chain::Stagenet => Some("--stagenet"),
+        }
+    }
+
+    /// Network byte of the chain's standard addresses; regtest wallets use
+    /// mainnet's
+    pub fn address_prefix(self) -> u64 {
+        match self {
+            Chain::Regtest => 18,
+            Chain::Testnet => 53,
+            Chain::Stagenet => 24,
+        }
+    }
+
+    /// Distance of the chain's default ports from mainnet's
+    /// 18080 / 18081 / 18082
+    pub fn port_offset(self) -> u16 {
+        match self {
+            Chain::Regtest => 0,
+            Chain::Testnet => 10_000,
+            Chain::Stagenet => 20_000,
+        }
+    }
+}
+
 /// How to populate the in-sim hosts at Monero's hardcoded fallback seed IPs
 /// (the IPs baked into monerod at src/p2p/net_node.inl).
 ///
@@ -54,6 +140,20 @@ pub enum Topology {
     Mesh,
     Ring,
     Dag,
+    /// Balanced `fanout`-ary tree in agent order: agent 0 is the root and
+    /// each agent links to its parent and children. Also wired in Hardcoded
+    /// peer mode. Written `topology: {Tree: {fanout: 3}}`.
+    Tree {
+        fanout: usize,
+    },
+    /// Watts–Strogatz small world: a ring lattice of degree `k` whose edges
+    /// are rewired with probability `rewire_prob`, drawn from the simulation
+    /// seed. Wired like Tree. Written
+    /// `topology: {SmallWorld: {k: 4, rewire_prob: 0.1}}`.
+    SmallWorld {
+        k: usize,
+        rewire_prob: f64,
+    },
 }
 
 /// Strategy for distributing agents across network topology nodes.
@@ -112,6 +212,37 @@ pub struct Distribution {
     pub weights: Option<RegionWeights>,
 }
 
+/// Where miners go on a GML topology (`network.miner_placement`). Miners
+/// are numbered from 0 in agent-id order; everyone else follows
+/// `distribution`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum MinerPlacement {
+    /// At most one miner per AS until every AS has one, with the ASes
+    /// spread over the topology
+    SpreadAs,
+    /// Every miner in the AS `as_number`
+    Colocated { as_number: String },
+    /// Miner index -> GML node id, for every miner
+    Explicit { nodes: BTreeMap<usize, u32> },
+}
+
+/// GML node an agent must sit on (an agent's `placement`). Pinned agents
+/// are placed before `distribution` and `network.miner_placement` handle
+/// the rest.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentPlacement {
+    /// This GML node id
+    NodeId(u32),
+    /// A node with this `AS` attribute
+    #[serde(rename = "as")]
+    As(String),
+    /// A node in this region (`north_america`, `europe`, ... as in
+    /// `distribution.weights`; the display names work too)
+    Region(String),
+}
+
 impl Default for Distribution {
     fn default() -> Self {
         Self {
@@ -121,19 +252,198 @@ impl Default for Distribution {
     }
 }
 
+/// How edge latencies are synthesized for GML edges that have none, from the
+/// great-circle distance between the endpoints' `Latitude`/`Longitude`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LatencySynthesis {
+    /// Milliseconds of one-way latency per kilometre of great-circle distance
+    #[serde(default = "default_propagation_factor")]
+    pub propagation_factor: f64,
+    /// Fixed per-edge delay added on top of the distance term
+    #[serde(default = "default_processing_delay_ms")]
+    pub processing_delay_ms: f64,
+}
+
+impl Default for LatencySynthesis {
+    fn default() -> Self {
+        Self {
+            propagation_factor: default_propagation_factor(),
+            processing_delay_ms: default_processing_delay_ms(),
+        }
+    }
+}
+
+/// Latency and loss for a switch network. Generation replaces the switch with
+/// a small graph of one node per continent whose edges carry these values.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LinkProfile {
+    /// Mean one-way latency between continents, in milliseconds
+    #[serde(default = "default_link_latency_ms")]
+    pub latency_ms: f64,
+    /// Each inter-continent edge is offset from `latency_ms` by a seeded
+    /// uniform draw in `[-jitter_ms, +jitter_ms]`
+    #[serde(default)]
+    pub jitter_ms: f64,
+    /// One-way latency between hosts on the same continent, in milliseconds
+    #[serde(default = "default_local_latency_ms")]
+    pub local_latency_ms: f64,
+    /// Packet loss on every edge, in percent (0-100)
+    #[serde(default)]
+    pub packet_loss: f64,
+}
+
+impl Default for LinkProfile {
+    fn default() -> Self {
+        Self {
+            latency_ms: default_link_latency_ms(),
+            jitter_ms: 0.0,
+            local_latency_ms: default_local_latency_ms(),
+            packet_loss: 0.0,
+        }
+    }
+}
+
+/// What a `network.events` entry does
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkEventAction {
+    /// Split the daemons into `groups` that stop talking to each other
+    Partition,
+    /// Lift the partition in force
+    Heal,
+}
+
+impl NetworkEventAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NetworkEventAction::Partition => "partition",
+            NetworkEventAction::Heal => "heal",
+        }
+    }
+}
+
+/// A scheduled change to the network (`network.events`), e.g.
+/// `{at: 30m, action: partition, groups: [[user000..user009], [user010..]]}`
+/// then `{at: 50m, action: heal}`. See `topology::partition`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct NetworkEvent {
+    /// Simulated time the event fires ("30m", "1800s")
+    pub at: SimDuration,
+    pub action: NetworkEventAction,
+    /// Sides of a partition: agent ids or inclusive ranges such as
+    /// `user000..user009` (`user010..` is open-ended). Empty for a heal
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<Vec<String>>,
+}
+
+/// Per-agent connection mix for `peer_mode: Hybrid`. Without it every
+/// regular agent gets all seeds plus the whole topology template.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HybridOptions {
+    /// Seeds per regular agent, rotated by agent index so load spreads evenly
+    #[serde(default = "default_hybrid_seed_connections")]
+    pub seed_connections: usize,
+    /// Topology-template peers per regular agent, taken in template order
+    #[serde(default = "default_hybrid_topology_connections")]
+    pub topology_connections: usize,
+}
+
+/// Flag fixed peers are handed to monerod with
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionEnforcement {
+    /// `--add-priority-node`: fixed peers are kept, others still allowed
+    #[default]
+    Priority,
+    /// `--add-exclusive-node`: fixed peers only, and no `--seed-node`
+    Exclusive,
+}
+
+impl ConnectionEnforcement {
+    /// monerod flag for a fixed peer, without the `=IP:PORT`
+    pub fn flag(self) -> &'static str {
+        match self {
+            ConnectionEnforcement::Priority => "--add-priority-node",
+            ConnectionEnforcement::Exclusive => "--add-exclusive-node",
+        }
+    }
+
+    /// monerod flag for a bootstrap peer; exclusive mode injects no
+    /// `--seed-node`, so those peers are pinned as well
+    pub fn seed_flag(self) -> &'static str {
+        match self {
+            ConnectionEnforcement::Priority => "--seed-node",
+            ConnectionEnforcement::Exclusive => self.flag(),
+        }
+    }
+}
+
+/// Which agents the others bootstrap from (`network.seed_selection`)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SeedSelection {
+    /// Miners in Dynamic mode. Otherwise `is_seed_node` agents, topped up
+    /// to `seed_count` (default 5) by promoting other agents, each with a
+    /// validation warning
+    #[default]
+    Auto,
+    /// Every miner
+    Miners,
+    /// The agents `network.seed_nodes` names, by id or `IP:PORT`
+    Explicit,
+    /// The first `seed_count` reachable, non-spy daemons in agent order
+    FirstN,
+    /// Exactly the agents with `is_seed_node: true`
+    Attribute,
+}
+
+impl Default for HybridOptions {
+    fn default() -> Self {
+        Self {
+            seed_connections: default_hybrid_seed_connections(),
+            topology_connections: default_hybrid_topology_connections(),
+        }
+    }
+}
+
 /// Unified configuration that supports only agent mode
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
+    /// Schema version the file was written for (1 when absent); see
+    /// `config::migrate`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_version: Option<u32>,
     pub general: GeneralConfig,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub network: Option<Network>,
     pub agents: AgentDefinitions,
+    /// Named agent defaults, merged into each entry with a matching
+    /// `group:` when the config is loaded (see `config::groups`); kept for
+    /// the manifest
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub agent_groups: BTreeMap<String, serde_yaml::Mapping>,
     /// Optional performance-tuning knobs that don't fit neatly into
     /// `general:`. Existing perf fields (runahead, parallelism, process_threads,
     /// shadow_log_level) stay in `general:` for backward compat — this
     /// stanza is for additions, currently just one Shadow-level toggle.
     #[serde(default)]
     pub performance: PerformanceConfig,
+    /// Shadow `general:` / `experimental:` settings; each one set here wins
+    /// over its older home in `general:` or `performance:`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shadow: Option<ShadowOptions>,
+    /// Block production shared by the mining agents, see
+    /// `BlockControllerConfig`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_controller: Option<BlockControllerConfig>,
+    /// Who the miner distributor pays, how much and how often, see
+    /// `MinerDistributorConfig`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub miner_distributor: Option<MinerDistributorConfig>,
+    /// Parameter axes `monerosim sweep` expands into one simulation per
+    /// combination; a plain run ignores it, see `SweepConfig`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sweep: Option<SweepConfig>,
 }
 
 /// Shadow / sim-engine performance knobs. All fields default to the
@@ -159,16 +469,126 @@ impl Default for PerformanceConfig {
     }
 }
 
+/// `shadow:`: Shadow settings monerosim otherwise fills in itself. Unset
+/// fields keep today's values: dynamic runahead on, syscall latency
+/// modelled, `general.parallelism` workers, `general.simulation_seed`, and
+/// no runahead or data directory (Shadow's defaults).
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ShadowOptions {
+    /// Fixed runahead, e.g. "1ms"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runahead: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub use_dynamic_runahead: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_unblocked_syscall_latency: Option<bool>,
+    /// Worker threads (0 = one per core)
+    #[serde(default, skip_serializing_if = "Option::is_none", alias = "workers")]
+    pub parallelism: Option<u32>,
+    /// Shadow's own seed, independent of the agents' `simulation_seed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Shadow's `data_directory`; `{seed}` is replaced by Shadow's seed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_directory: Option<String>,
+}
+
+impl ShadowOptions {
+    /// `data_directory` with `{seed}` filled in
+    pub fn data_directory_for(&self, seed: u64) -> Option<String> {
+        self.data_directory
+            .as_ref()
+            .map(|template| template.replace("{seed}", &seed.to_string()))
+    }
+}
+
+/// `general.shadow_experimental`: Shadow's `experimental:` section.
+///
+/// The keys monerosim knows are typed and validated; from config_version 2
+/// anything else is copied into the generated config verbatim, so options
+/// added by newer Shadow releases can be used without a monerosim release.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ShadowExperimentalConfig {
+    /// Fixed runahead, e.g. "1ms". Replaces the older `general.runahead`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runahead: Option<String>,
+    /// Defaults to true (what monerosim has always emitted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub use_dynamic_runahead: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduler: Option<ShadowScheduler>,
+    /// e.g. "1 microsecond"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_unapplied_cpu_latency: Option<String>,
+    /// Unknown keys, emitted as-is
+    #[serde(flatten)]
+    pub passthrough: BTreeMap<String, serde_yaml::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ShadowScheduler {
+    ThreadPerCore,
+    ThreadPerHost,
+}
+
+impl ShadowScheduler {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShadowScheduler::ThreadPerCore => "thread-per-core",
+            ShadowScheduler::ThreadPerHost => "thread-per-host",
+        }
+    }
+}
+
 impl Config {
     /// Validate the configuration
     pub fn validate(&self) -> Result<(), ValidationError> {
         // Validate general settings
-        if self.general.stop_time.is_empty() {
+        if self.general.stop_time == SimDuration::ZERO {
+            return Err(ValidationError::InvalidGeneral(
+                "stop_time must be at least 1s".to_string(),
+            ));
+        }
+
+        if self.general.blockchain_source_dir.is_some()
+            && self.general.fresh_blockchain != Some(false)
+        {
             return Err(ValidationError::InvalidGeneral(
-                "stop_time cannot be empty".to_string(),
+                "blockchain_source_dir requires fresh_blockchain: false".to_string(),
             ));
         }
 
+        if self.general.fixed_difficulty == Some(0) {
+            return Err(ValidationError::InvalidGeneral(
+                "fixed_difficulty must be positive".to_string(),
+            ));
+        }
+
+        if let Some(shadow) = &self.shadow {
+            self.validate_shadow_options(shadow)?;
+        }
+        if let Some(experimental) = &self.general.shadow_experimental {
+            self.validate_shadow_experimental(experimental)?;
+        }
+
+        let block_interval = BlockControllerConfig::interval_secs(self.block_controller.as_ref())
+            .map_err(ValidationError::InvalidGeneral)?;
+        BlockControllerConfig::assign_pools(self.block_controller.as_ref(), &self.agents)
+            .map_err(ValidationError::InvalidGeneral)?;
+        if let Some(startup) = &self.general.startup {
+            crate::process::StartupSettings::from_config(Some(startup), block_interval)
+                .map_err(ValidationError::InvalidGeneral)?;
+        }
+        if let Some(distributor) = &self.miner_distributor {
+            distributor
+                .validate()
+                .map_err(ValidationError::InvalidGeneral)?;
+        }
+        if let Some(sweep) = &self.sweep {
+            sweep.validate().map_err(ValidationError::InvalidGeneral)?;
+        }
+
         // Validate network settings
         if let Some(network) = &self.network {
             match network {
@@ -176,6 +596,14 @@ impl Config {
                     path,
                     peer_mode,
                     seed_nodes,
+                    latency_synthesis,
+                    hybrid_options,
+                    connection_enforcement,
+                    seed_selection,
+                    seed_count,
+                    miner_placement,
+                    subnet_size,
+                    as_weights,
                     ..
                 } => {
                     if path.is_empty() {
@@ -183,12 +611,37 @@ impl Config {
                             "GML path cannot be empty".to_string(),
                         ));
                     }
+                    if let Some(ls) = latency_synthesis {
+                        if !(ls.propagation_factor >= 0.0 && ls.processing_delay_ms >= 0.0) {
+                            return Err(ValidationError::InvalidNetwork(
+                                "latency_synthesis factors must be non-negative".to_string(),
+                            ));
+                        }
+                    }
                     Self::validate_peer_config(peer_mode, seed_nodes)?;
+                    Self::validate_seed_selection(seed_selection, *seed_count, seed_nodes)?;
+                    self.validate_hybrid_options(peer_mode, hybrid_options)?;
+                    Self::validate_connection_enforcement(peer_mode, connection_enforcement)?;
+                    if let Some(placement) = miner_placement {
+                        self.validate_miner_placement(placement)?;
+                    }
+                    if let Some(size) = subnet_size {
+                        parse_subnet_size(size).map_err(ValidationError::InvalidNetwork)?;
+                    }
+                    if let Some(weights) = as_weights {
+                        Self::validate_shares("as_weights", weights)?;
+                    }
                 }
                 Network::Switch {
                     network_type,
                     peer_mode,
                     seed_nodes,
+                    hybrid_options,
+                    connection_enforcement,
+                    seed_selection,
+                    seed_count,
+                    link_profile,
+                    geographic_distribution,
                     ..
                 } => {
                     if network_type.is_empty() {
@@ -196,11 +649,479 @@ impl Config {
                             "Network type cannot be empty for Switch".to_string(),
                         ));
                     }
+                    if let Some(lp) = link_profile {
+                        Self::validate_link_profile(lp)?;
+                    }
+                    if let Some(shares) = geographic_distribution {
+                        if let Some(name) = shares
+                            .keys()
+                            .find(|name| AsRegion::from_key(name).is_none())
+                        {
+                            return Err(ValidationError::InvalidNetwork(format!(
+                                "geographic_distribution: unknown region '{}' (expected {})",
+                                name,
+                                AsRegion::all()
+                                    .iter()
+                                    .filter_map(AsRegion::key)
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            )));
+                        }
+                        Self::validate_shares("geographic_distribution", shares)?;
+                    }
                     Self::validate_peer_config(peer_mode, seed_nodes)?;
+                    Self::validate_seed_selection(seed_selection, *seed_count, seed_nodes)?;
+                    self.validate_hybrid_options(peer_mode, hybrid_options)?;
+                    Self::validate_connection_enforcement(peer_mode, connection_enforcement)?;
+                }
+            }
+        }
+        self.validate_peer_limits()?;
+        self.network_event_schedule()
+            .map_err(ValidationError::InvalidNetwork)?;
+        self.validate_discovery()?;
+        self.validate_placements()?;
+        self.validate_chain()?;
+
+        Ok(())
+    }
+
+    /// Autonomous miners add blocks with the `generateblocks` RPC, which
+    /// monerod only serves on regtest
+    fn validate_chain(&self) -> Result<(), ValidationError> {
+        if self.general.chain == Chain::Regtest {
+            return Ok(());
+        }
+        let miners: Vec<&str> = self
+            .agents
+            .agents
+            .iter()
+            .filter(|(_, agent)| {
+                agent.is_miner()
+                    && agent
+                        .script
+                        .as_deref()
+                        .is_some_and(|s| s.contains("autonomous_miner"))
+            })
+            .map(|(id, _)| id.as_str())
+            .collect();
+        if miners.is_empty() {
+            return Ok(());
+        }
+        Err(ValidationError::InvalidGeneral(format!(
+            "chain: {} cannot run autonomous miners ({}): they add blocks with the \
+             generateblocks RPC, which monerod only serves on regtest. Use chain: regtest, \
+             or give the miners a script that mines with start_mining",
+            self.general.chain.name(),
+            miners.join(", ")
+        )))
+    }
+
+    /// `discovery` chooses between the DNS server and seed links, so it
+    /// needs both a daemon and `enable_dns_server`
+    fn validate_discovery(&self) -> Result<(), ValidationError> {
+        let dns = self.general.enable_dns_server.unwrap_or(false);
+        for (id, agent) in &self.agents.agents {
+            let Some(discovery) = agent.discovery else {
+                continue;
+            };
+            if !agent.has_local_daemon() {
+                return Err(ValidationError::InvalidAgent(format!(
+                    "Agent '{}': discovery needs a local daemon",
+                    id
+                )));
+            }
+            if !dns && discovery != PeerDiscovery::Seeds {
+                return Err(ValidationError::InvalidAgent(format!(
+                    "Agent '{}': discovery {:?} requires general.enable_dns_server",
+                    id, discovery
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// `placement` needs a GML network and an agent with a host of its own
+    /// in the user-agent set; whether the node, AS or region exists is
+    /// checked against the graph during generation
+    fn validate_placements(&self) -> Result<(), ValidationError> {
+        let gml = matches!(self.network, Some(Network::Gml { .. }));
+        for (id, agent) in &self.agents.agents {
+            let Some(placement) = &agent.placement else {
+                continue;
+            };
+            let problem = if !gml {
+                Some("placement requires a GML network".to_string())
+            } else if agent.colocate_with.is_some() {
+                Some("placement conflicts with colocate_with (the host decides)".to_string())
+            } else if !(agent.has_local_daemon() || agent.has_remote_daemon() || agent.has_wallet())
+            {
+                Some("placement needs a daemon or wallet".to_string())
+            } else {
+                match placement {
+                    AgentPlacement::As(asn) if asn.trim().is_empty() => {
+                        Some("placement as needs an AS number".to_string())
+                    }
+                    AgentPlacement::Region(region) if AsRegion::from_key(region).is_none() => {
+                        Some(format!(
+                            "unknown placement region '{}' (expected one of {})",
+                            region,
+                            AsRegion::all()
+                                .iter()
+                                .filter_map(AsRegion::key)
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ))
+                    }
+                    _ => None,
+                }
+            };
+            if let Some(problem) = problem {
+                return Err(ValidationError::InvalidAgent(format!(
+                    "Agent '{}': {}",
+                    id, problem
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// `network.events` in seconds, with partition groups resolved against
+    /// the daemon agents; empty without events
+    pub fn network_event_schedule(&self) -> Result<Vec<ScheduledNetworkEvent>, String> {
+        let events = self.network.as_ref().map_or(&[][..], Network::events);
+        if events.is_empty() {
+            return Ok(Vec::new());
+        }
+        let stop_secs = self.general.stop_time.as_secs();
+        let daemon_ids: Vec<&str> = self
+            .agents
+            .agents
+            .iter()
+            .filter(|(_, agent)| agent.has_local_daemon())
+            .map(|(id, _)| id.as_str())
+            .collect();
+        schedule_network_events(events, &daemon_ids, stop_secs)
+            .map_err(|e| format!("network.{}", e))
+    }
+
+    /// `out_peers` / `in_peers` must fit under `network.max_peers`, be set
+    /// in one place per level, and only an isolated daemon (`in_peers: 0`
+    /// too) may make no outbound connections.
+    fn validate_peer_limits(&self) -> Result<(), ValidationError> {
+        let network = super::resolved::NetworkSettings::from_config(self);
+        let daemon_defaults = self.general.daemon_defaults.as_ref();
+        for (field, flag, limit) in [
+            ("out_peers", "out-peers", network.out_peers),
+            ("in_peers", "in-peers", network.in_peers),
+        ] {
+            let Some(limit) = limit else { continue };
+            if limit > network.max_peers {
+                return Err(ValidationError::InvalidNetwork(format!(
+                    "{} {} is over max_peers {}",
+                    field, limit, network.max_peers
+                )));
+            }
+            if daemon_defaults.is_some_and(|d| d.contains_key(flag)) {
+                return Err(ValidationError::InvalidNetwork(format!(
+                    "{} conflicts with general.daemon_defaults.{}; set one",
+                    field, flag
+                )));
+            }
+        }
+
+        for (id, agent) in &self.agents.agents {
+            for (field, flag, limit) in [
+                ("out_peers", "out-peers", agent.out_peers),
+                ("in_peers", "in-peers", agent.in_peers),
+            ] {
+                let Some(limit) = limit else { continue };
+                if limit > network.max_peers {
+                    return Err(ValidationError::InvalidAgent(format!(
+                        "Agent '{}': {} {} is over network.max_peers {}",
+                        id, field, limit, network.max_peers
+                    )));
+                }
+                if agent
+                    .daemon_options
+                    .as_ref()
+                    .is_some_and(|opts| opts.contains_key(flag))
+                {
+                    return Err(ValidationError::InvalidAgent(format!(
+                        "Agent '{}': {} conflicts with daemon_options.{}; set one",
+                        id, field, flag
+                    )));
+                }
+            }
+            let out_peers = agent.out_peers.or(network.out_peers);
+            let in_peers = agent.in_peers.or(network.in_peers);
+            if agent.has_local_daemon() && out_peers == Some(0) && in_peers != Some(0) {
+                return Err(ValidationError::InvalidAgent(format!(
+                    "Agent '{}': out_peers 0 leaves the daemon only inbound peers; \
+                     set in_peers: 0 as well for a deliberately isolated node",
+                    id
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Latencies must be non-negative and jitter cannot push an edge below
+    /// zero; packet loss is a percentage.
+    fn validate_link_profile(lp: &LinkProfile) -> Result<(), ValidationError> {
+        let invalid = |msg: String| Err(ValidationError::InvalidNetwork(msg));
+        if !(lp.latency_ms >= 0.0 && lp.jitter_ms >= 0.0 && lp.local_latency_ms > 0.0) {
+            return invalid(
+                "link_profile latencies must be non-negative (local_latency_ms above zero)"
+                    .to_string(),
+            );
+        }
+        if lp.jitter_ms > lp.latency_ms {
+            return invalid(format!(
+                "link_profile jitter_ms ({}) must not exceed latency_ms ({})",
+                lp.jitter_ms, lp.latency_ms
+            ));
+        }
+        if !(0.0..=100.0).contains(&lp.packet_loss) {
+            return invalid(format!(
+                "link_profile packet_loss must be a percentage in 0-100, got {}",
+                lp.packet_loss
+            ));
+        }
+        Ok(())
+    }
+
+    /// Shares must be non-negative and sum to 1.0 within
+    /// `SHARE_SUM_TOLERANCE`
+    fn validate_shares(field: &str, shares: &BTreeMap<String, f64>) -> Result<(), ValidationError> {
+        let invalid = |msg: String| Err(ValidationError::InvalidNetwork(msg));
+        if shares.is_empty() {
+            return invalid(format!("{} must not be empty", field));
+        }
+        if let Some((key, share)) = shares.iter().find(|(_, s)| !(s.is_finite() && **s >= 0.0)) {
+            return invalid(format!(
+                "{}: share of '{}' must be non-negative, got {}",
+                field, key, share
+            ));
+        }
+        let total: f64 = shares.values().sum();
+        if (total - 1.0).abs() > SHARE_SUM_TOLERANCE {
+            return invalid(format!("{} must sum to 1.0, got {}", field, total));
+        }
+        Ok(())
+    }
+
+    /// `shadow.runahead` must parse as a positive Shadow time, and the
+    /// runahead settings may not also be set in their older homes.
+    fn validate_shadow_options(&self, shadow: &ShadowOptions) -> Result<(), ValidationError> {
+        let invalid = |msg: String| ValidationError::InvalidGeneral(msg);
+        let experimental = self.general.shadow_experimental.as_ref();
+        if let Some(runahead) = &shadow.runahead {
+            let ns = crate::utils::parse_shadow_time_ns(runahead)
+                .map_err(|e| invalid(format!("shadow.runahead: {}", e)))?;
+            if ns == 0 {
+                return Err(invalid("shadow.runahead must be positive".to_string()));
+            }
+            if self.general.runahead.is_some() || experimental.is_some_and(|e| e.runahead.is_some())
+            {
+                return Err(invalid(
+                    "runahead is set in both shadow and general; keep only shadow.runahead"
+                        .to_string(),
+                ));
+            }
+        }
+        if shadow.use_dynamic_runahead.is_some()
+            && experimental.is_some_and(|e| e.use_dynamic_runahead.is_some())
+        {
+            return Err(invalid(
+                "use_dynamic_runahead is set in both shadow and general.shadow_experimental; \
+                 keep only shadow.use_dynamic_runahead"
+                    .to_string(),
+            ));
+        }
+        if shadow
+            .data_directory
+            .as_ref()
+            .is_some_and(|d| d.trim().is_empty())
+        {
+            return Err(invalid("shadow.data_directory cannot be empty".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Typed keys must parse as Shadow times, neither they nor
+    /// pass-through keys may restate a setting monerosim emits from
+    /// elsewhere in the config, and pass-through needs a config_version
+    /// that allows it.
+    fn validate_shadow_experimental(
+        &self,
+        experimental: &ShadowExperimentalConfig,
+    ) -> Result<(), ValidationError> {
+        let invalid = |msg: String| ValidationError::InvalidGeneral(msg);
+        if let Some(runahead) = &experimental.runahead {
+            let ns = crate::utils::parse_shadow_time_ns(runahead)
+                .map_err(|e| invalid(format!("shadow_experimental.runahead: {}", e)))?;
+            if ns == 0 {
+                return Err(invalid(
+                    "shadow_experimental.runahead must be positive".to_string(),
+                ));
+            }
+            if self.general.runahead.is_some() {
+                return Err(invalid(
+                    "runahead is set in both general and general.shadow_experimental; keep only the latter".to_string(),
+                ));
+            }
+        }
+        if let Some(latency) = &experimental.max_unapplied_cpu_latency {
+            crate::utils::parse_shadow_time_ns(latency).map_err(|e| {
+                invalid(format!(
+                    "shadow_experimental.max_unapplied_cpu_latency: {}",
+                    e
+                ))
+            })?;
+        }
+        if experimental
+            .passthrough
+            .contains_key("native_preemption_enabled")
+        {
+            return Err(invalid(
+                "shadow_experimental.native_preemption_enabled: use general.native_preemption"
+                    .to_string(),
+            ));
+        }
+        let version = self.config_version.unwrap_or(1);
+        if version < SHADOW_PASSTHROUGH_VERSION && !experimental.passthrough.is_empty() {
+            let keys: Vec<&str> = experimental
+                .passthrough
+                .keys()
+                .map(String::as_str)
+                .collect();
+            return Err(invalid(format!(
+                "shadow_experimental: unknown key(s) {}; config_version {} configs do not pass \
+                 unknown keys through to Shadow (check the spelling, or --migrate to \
+                 config_version {})",
+                keys.join(", "),
+                version,
+                SHADOW_PASSTHROUGH_VERSION
+            )));
+        }
+        Ok(())
+    }
+
+    /// `hybrid_options` only applies to Hybrid mode, and the fixed
+    /// connections it hands out must fit in the daemon's out-peers budget,
+    /// otherwise monerod silently drops some of them.
+    fn validate_hybrid_options(
+        &self,
+        peer_mode: &Option<PeerMode>,
+        hybrid_options: &Option<HybridOptions>,
+    ) -> Result<(), ValidationError> {
+        let Some(opts) = hybrid_options else {
+            return Ok(());
+        };
+        if !matches!(peer_mode, Some(PeerMode::Hybrid)) {
+            return Err(ValidationError::InvalidNetwork(format!(
+                "hybrid_options requires peer_mode Hybrid (got {:?})",
+                peer_mode
+            )));
+        }
+        let network_out_peers = super::resolved::NetworkSettings::from_config(self).out_peers;
+        let daemon_default = self
+            .general
+            .daemon_defaults
+            .as_ref()
+            .and_then(|d| d.get("out-peers"));
+        let out_peers = match (network_out_peers, daemon_default) {
+            (Some(n), _) => i64::from(n),
+            (None, Some(OptionValue::Number(n))) => *n,
+            (None, Some(OptionValue::String(s))) => s.parse().map_err(|_| {
+                ValidationError::InvalidGeneral(format!(
+                    "daemon_defaults.out-peers '{}' is not a number",
+                    s
+                ))
+            })?,
+            _ => default_out_peers(),
+        };
+        let requested = opts.seed_connections + opts.topology_connections;
+        if requested as i64 > out_peers {
+            return Err(ValidationError::InvalidNetwork(format!(
+                "hybrid_options asks for {} seed + {} topology connections but out-peers is {}",
+                opts.seed_connections, opts.topology_connections, out_peers
+            )));
+        }
+        Ok(())
+    }
+
+    /// Exclusive peers pin the whole peer set, which contradicts Dynamic
+    /// discovery
+    fn validate_connection_enforcement(
+        peer_mode: &Option<PeerMode>,
+        connection_enforcement: &Option<ConnectionEnforcement>,
+    ) -> Result<(), ValidationError> {
+        if connection_enforcement == &Some(ConnectionEnforcement::Exclusive)
+            && !matches!(peer_mode, Some(PeerMode::Hardcoded | PeerMode::Hybrid))
+        {
+            return Err(ValidationError::InvalidNetwork(format!(
+                "connection_enforcement exclusive requires peer_mode Hardcoded or Hybrid (got {:?})",
+                peer_mode
+            )));
+        }
+        Ok(())
+    }
+
+    /// `colocated` needs an AS and `explicit` a node for exactly the
+    /// configured miners, not counting those with their own `placement`
+    fn validate_miner_placement(&self, placement: &MinerPlacement) -> Result<(), ValidationError> {
+        let miners = self
+            .agents
+            .agents
+            .values()
+            .filter(|a| a.is_miner() && a.placement.is_none())
+            .count();
+        match placement {
+            MinerPlacement::SpreadAs => Ok(()),
+            MinerPlacement::Colocated { as_number } if as_number.trim().is_empty() => {
+                Err(ValidationError::InvalidNetwork(
+                    "miner_placement colocated needs an as_number".to_string(),
+                ))
+            }
+            MinerPlacement::Colocated { .. } => Ok(()),
+            MinerPlacement::Explicit { nodes } => {
+                if let Some(index) = nodes.keys().find(|&&i| i >= miners) {
+                    return Err(ValidationError::InvalidNetwork(format!(
+                        "miner_placement explicit maps miner {} but there are {} miners",
+                        index, miners
+                    )));
                 }
+                if nodes.len() < miners {
+                    return Err(ValidationError::InvalidNetwork(format!(
+                        "miner_placement explicit maps {} of {} miners",
+                        nodes.len(),
+                        miners
+                    )));
+                }
+                Ok(())
             }
         }
+    }
 
+    /// `explicit` seeds come from `seed_nodes`; a seed count of zero would
+    /// leave nobody to bootstrap from
+    fn validate_seed_selection(
+        seed_selection: &Option<SeedSelection>,
+        seed_count: Option<usize>,
+        seed_nodes: &Option<Vec<String>>,
+    ) -> Result<(), ValidationError> {
+        if seed_count == Some(0) {
+            return Err(ValidationError::InvalidNetwork(
+                "seed_count must be at least 1".to_string(),
+            ));
+        }
+        if matches!(seed_selection, Some(SeedSelection::Explicit)) && seed_nodes.is_none() {
+            return Err(ValidationError::InvalidNetwork(
+                "seed_selection explicit requires seed_nodes".to_string(),
+            ));
+        }
         Ok(())
     }
 
@@ -241,15 +1162,29 @@ impl Config {
 /// Shared general configuration
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GeneralConfig {
-    pub stop_time: String,
+    pub stop_time: SimDuration,
+    /// `false` keeps the daemons' data directories from the previous run
+    /// (or seeds them from `blockchain_source_dir`) instead of starting
+    /// from genesis. Unset or `true` removes them before generation
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fresh_blockchain: Option<bool>,
+    /// Not implemented (agents use the venv detected in the working
+    /// directory); accepted so older configs still load
     #[serde(skip_serializing_if = "Option::is_none")]
     pub python_venv: Option<String>,
+    /// `--log-level` passed to every agent script (DEBUG when unset)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub log_level: Option<String>,
     #[serde(default = "default_simulation_seed")]
     pub simulation_seed: u64,
+    /// Monero network the daemons and wallets run on, see `Chain`
+    #[serde(default)]
+    pub chain: Chain,
+    /// `--fixed-difficulty` for every daemon, so block production no
+    /// longer depends on the difficulty ramp-up. Unset keeps monerod's
+    /// adjusting difficulty; `daemon_defaults` / `daemon_options` win
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_difficulty: Option<u64>,
     /// Shadow parallelism: number of worker threads
     /// - 0 = auto-detect CPU cores (default, fastest)
     /// - 1 = single-threaded (required for deterministic runs)
@@ -269,14 +1204,19 @@ pub struct GeneralConfig {
     #[serde(default = "default_shadow_log_level")]
     pub shadow_log_level: String,
     /// Shadow runahead duration (e.g., "1ms", "10ms")
-    /// Experimental: may improve simulation speed at cost of accuracy
+    /// Experimental: may improve simulation speed at cost of accuracy.
+    /// Kept for existing configs; `shadow_experimental.runahead` is validated.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub runahead: Option<String>,
+    /// Shadow `experimental:` options (runahead, scheduler, ...), see
+    /// `ShadowExperimentalConfig`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shadow_experimental: Option<ShadowExperimentalConfig>,
     /// Bootstrap end time - Shadow provides high bandwidth/no packet loss until this time
     /// Useful for allowing network to settle before applying realistic constraints
     /// Format: e.g., "7200s" or "2h"
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub bootstrap_end_time: Option<String>,
+    pub bootstrap_end_time: Option<SimDuration>,
     /// Show simulation progress on stderr (default: true for visibility)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub progress: Option<bool>,
@@ -312,6 +1252,12 @@ pub struct GeneralConfig {
     #[serde(default = "default_daemon_data_dir")]
     pub daemon_data_dir: String,
 
+    /// Chain data copied into the daemons' data directories when
+    /// `fresh_blockchain` is false: `monero-{agent_id}/` per daemon, or a
+    /// template data directory holding `lmdb/` for all of them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blockchain_source_dir: Option<String>,
+
     /// How to populate hosts at Monero's hardcoded fallback seed IPs.
     /// See `FallbackSeedsMode` for semantics.
     ///
@@ -372,6 +1318,551 @@ pub struct GeneralConfig {
     /// `--turnover-session`. See docs/20260618_mainnet_topology_targets.md.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub turnover: Option<TurnoverConfig>,
+
+    /// Default monerod / monero-wallet-rpc binaries for agents that don't
+    /// name their own. Unset entries fall back to `~/.monerosim/bin`, then PATH.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binaries: Option<BinariesConfig>,
+
+    /// Fail generation on any warning in the validation report instead of
+    /// carrying on. Also enabled by `--strict` on the CLI.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strict_validation: bool,
+
+    /// Directory agent scripts are checked against at generation time:
+    /// module scripts (`agents.regular_user`) must exist as `.py` files or
+    /// packages below it, and relative script paths resolve against it.
+    /// Defaults to the current working directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agents_path: Option<String>,
+
+    /// Pacing of the startup schedule (stagger, startup rate limit, block
+    /// maturity), see `StartupConfig`. Written out as `startup_schedule.json`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub startup: Option<StartupConfig>,
+
+    /// Per-process memory and file descriptor assumptions behind the
+    /// generation-time resource estimate, see `ResourceModelConfig`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_model: Option<ResourceModelConfig>,
+
+    /// Default `memory_limit` of every agent's processes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_limit: Option<String>,
+
+    /// Default `cpu_weight` of every agent's processes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_weight: Option<u32>,
+
+    /// Where `run_sim.sh` collects the pcap traces of agents with
+    /// `capture_pcap` when archiving, one subdirectory per agent; relative
+    /// paths are under the archive directory. Unset, they stay in Shadow's
+    /// host directories.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pcap_capture_dir: Option<String>,
+
+    /// Weights for miners with `hashrate: auto`: `equal`, `pareto` /
+    /// `pareto(alpha)`, or `explicit` (every miner states its hashrate)
+    #[serde(default, skip_serializing_if = "is_explicit")]
+    pub hashrate_distribution: HashrateDistribution,
+}
+
+fn is_explicit(distribution: &HashrateDistribution) -> bool {
+    *distribution == HashrateDistribution::Explicit
+}
+
+/// Per-process costs the resource estimate assumes (see
+/// `GeneralConfig::resource_model`). Unset fields keep the defaults in
+/// `resources`; calibrate them against the binaries actually launched.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ResourceModelConfig {
+    /// Resident memory of one monerod, in MB
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monerod_mb: Option<u64>,
+    /// Resident memory of one monero-wallet-rpc, in MB
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet_rpc_mb: Option<u64>,
+    /// Resident memory of one Python agent script, in MB
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_mb: Option<u64>,
+    /// Open file descriptors of one monerod (sockets, database files)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monerod_fds: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet_rpc_fds: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_fds: Option<u64>,
+}
+
+/// Startup pacing (see `GeneralConfig::startup`). Every daemon, wallet and
+/// script start is derived from these and the agent's dependencies: a
+/// wallet follows its daemon, a script its wallet, users and the miner
+/// distributor wait for block maturity.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct StartupConfig {
+    /// Gap between consecutive miners' (and users') daemons, e.g. "1s"
+    /// (the default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stagger: Option<SimDuration>,
+    /// Most processes started in one simulated second; computed start
+    /// times move to the next free second. Explicit `start_time`s are
+    /// taken as given. Unset = no limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_per_second: Option<u32>,
+    /// How long after the first miner users start and coinbase outputs are
+    /// spendable, e.g. "2h". Defaults to 60 blocks at
+    /// `block_controller.block_interval` (120s)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_maturity: Option<SimDuration>,
+}
+
+/// Parameters shared by the mining agents (see `Config::block_controller`).
+/// There is no controller process: the autonomous miners aim at
+/// `block_interval` together, and generation derives block maturity from
+/// it.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BlockControllerConfig {
+    /// Target time between blocks, e.g. "30s". Defaults to Monero's 120s
+    /// (`DIFFICULTY_TARGET_V2`); other values need `fixed_difficulty`,
+    /// since monerod retargets difficulty towards 120s
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_interval: Option<SimDuration>,
+    /// Mining pools by name. Each miner belongs to exactly one pool, and
+    /// each pool gets its own `miners_<name>.json`. Empty = no pools
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub pools: BTreeMap<String, MiningPoolConfig>,
+}
+
+/// One mining pool (see `BlockControllerConfig::pools`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MiningPoolConfig {
+    /// Which miners the pool claims
+    pub miners: MinerSelector,
+}
+
+/// Miners a pool claims. Written as a list of agent ids,
+/// `{attribute: {key: value, ...}}` or `{fraction: 0.3}`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MinerSelector {
+    /// These miners, by agent id
+    Agents(Vec<String>),
+    /// Miners whose attributes have all of these values
+    Attribute(BTreeMap<String, serde_yaml::Value>),
+    /// This share of all miners, taken from those no other pool claims
+    Fraction(f64),
+}
+
+impl Serialize for MinerSelector {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        match self {
+            MinerSelector::Agents(ids) => ids.serialize(serializer),
+            MinerSelector::Attribute(filter) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("attribute", filter)?;
+                map.end()
+            }
+            MinerSelector::Fraction(fraction) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("fraction", fraction)?;
+                map.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MinerSelector {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Agents(Vec<String>),
+            Filter {
+                attribute: BTreeMap<String, serde_yaml::Value>,
+            },
+            Fraction {
+                fraction: f64,
+            },
+        }
+        match Raw::deserialize(deserializer).map_err(|_| {
+            serde::de::Error::custom(
+                "expected a list of agent ids, {attribute: {key: value, ...}} or \
+                 {fraction: <0..1>}",
+            )
+        })? {
+            Raw::Agents(ids) => Ok(MinerSelector::Agents(ids)),
+            Raw::Filter { attribute } => Ok(MinerSelector::Attribute(attribute)),
+            Raw::Fraction { fraction } => Ok(MinerSelector::Fraction(fraction)),
+        }
+    }
+}
+
+impl BlockControllerConfig {
+    /// `block_interval` in seconds; the target interval when unset
+    pub fn interval_secs(config: Option<&Self>) -> Result<u64, String> {
+        let Some(interval) = config.and_then(|c| c.block_interval) else {
+            return Ok(crate::TARGET_BLOCK_INTERVAL_SECS);
+        };
+        match interval.as_secs() {
+            0 => Err("block_controller.block_interval must be at least 1s".to_string()),
+            secs => Ok(secs),
+        }
+    }
+
+    /// Seconds until coinbase outputs unlock: 60 blocks at the interval
+    pub fn maturity_secs(block_interval: u64) -> u64 {
+        crate::COINBASE_MATURITY_BLOCKS * block_interval
+    }
+
+    /// Each pool's miners, in agent id order; empty without `pools`.
+    /// Listed and attribute pools claim first, then `fraction` pools take
+    /// `round(fraction * miners)` of the unclaimed miners in pool name
+    /// order. Every miner must end up in exactly one pool.
+    pub fn assign_pools(
+        config: Option<&Self>,
+        agents: &AgentDefinitions,
+    ) -> Result<BTreeMap<String, Vec<String>>, String> {
+        let Some(pools) = config.map(|c| &c.pools).filter(|p| !p.is_empty()) else {
+            return Ok(BTreeMap::new());
+        };
+        let miners: Vec<&String> = agents
+            .agents
+            .iter()
+            .filter(|(_, agent)| agent.is_miner())
+            .map(|(id, _)| id)
+            .collect();
+        // Miner -> the pool that claimed it
+        let mut claimed: BTreeMap<String, String> = BTreeMap::new();
+        let mut assigned: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        fn claim(
+            claimed: &mut BTreeMap<String, String>,
+            pool: &str,
+            miners: &[String],
+        ) -> Result<(), String> {
+            for miner in miners {
+                if let Some(other) = claimed.insert(miner.clone(), pool.to_string()) {
+                    return Err(format!(
+                        "block_controller.pools: miner '{}' is claimed by both '{}' and '{}'",
+                        miner, other, pool
+                    ));
+                }
+            }
+            Ok(())
+        }
+
+        for (name, pool) in pools {
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                return Err(format!(
+                    "block_controller.pools: pool name '{}' must be letters, digits, '_' or '-'",
+                    name
+                ));
+            }
+            let members = match &pool.miners {
+                MinerSelector::Agents(ids) => {
+                    for id in ids {
+                        match agents.agents.get(id) {
+                            Some(agent) if agent.is_miner() => {}
+                            Some(_) => {
+                                return Err(format!(
+                                    "block_controller.pools.{}: agent '{}' is not a miner (no \
+                                     hashrate)",
+                                    name, id
+                                ))
+                            }
+                            None => {
+                                return Err(format!(
+                                    "block_controller.pools.{}: unknown agent '{}'",
+                                    name, id
+                                ))
+                            }
+                        }
+                    }
+                    ids.clone()
+                }
+                MinerSelector::Attribute(filter) => {
+                    if filter.is_empty() {
+                        return Err(format!(
+                            "block_controller.pools.{}.miners.attribute needs at least one \
+                             attribute",
+                            name
+                        ));
+                    }
+                    let mut values = BTreeMap::new();
+                    for (key, value) in filter {
+                        let value = RecipientSelector::scalar(value).ok_or_else(|| {
+                            format!(
+                                "block_controller.pools.{}.miners.attribute {}: expected a \
+                                 string, number or boolean",
+                                name, key
+                            )
+                        })?;
+                        values.insert(key, value);
+                    }
+                    miners
+                        .iter()
+                        .filter(|id| {
+                            let attributes = agents.agents[id.as_str()].attribute_map();
+                            values
+                                .iter()
+                                .all(|(key, value)| attributes.get(*key) == Some(value))
+                        })
+                        .map(|id| id.to_string())
+                        .collect()
+                }
+                MinerSelector::Fraction(fraction) => {
+                    if !(*fraction > 0.0 && *fraction <= 1.0) {
+                        return Err(format!(
+                            "block_controller.pools.{}.miners.fraction must be in (0, 1], got {}",
+                            name, fraction
+                        ));
+                    }
+                    continue;
+                }
+            };
+            claim(&mut claimed, name, &members)?;
+            assigned.insert(name.clone(), members);
+        }
+
+        for (name, pool) in pools {
+            let MinerSelector::Fraction(fraction) = pool.miners else {
+                continue;
+            };
+            let wanted = (fraction * miners.len() as f64).round() as usize;
+            let members: Vec<String> = miners
+                .iter()
+                .filter(|id| !claimed.contains_key(id.as_str()))
+                .take(wanted)
+                .map(|id| id.to_string())
+                .collect();
+            if members.len() < wanted {
+                return Err(format!(
+                    "block_controller.pools.{}: fraction {} asks for {} miners but only {} are \
+                     left unclaimed",
+                    name,
+                    fraction,
+                    wanted,
+                    members.len()
+                ));
+            }
+            claim(&mut claimed, name, &members)?;
+            assigned.insert(name.clone(), members);
+        }
+
+        let unclaimed: Vec<&str> = miners
+            .iter()
+            .map(|id| id.as_str())
+            .filter(|id| !claimed.contains_key(*id))
+            .collect();
+        if !unclaimed.is_empty() {
+            return Err(format!(
+                "block_controller.pools: miners {} are in no pool; every miner must belong to \
+                 exactly one",
+                unclaimed.join(", ")
+            ));
+        }
+        for members in assigned.values_mut() {
+            members.sort();
+        }
+        Ok(assigned)
+    }
+}
+
+/// Payout policy of the miner distributor (see `Config::miner_distributor`).
+/// Generation resolves it into `distribution_plan.json` in the shared
+/// directory, and each agent's `can_receive_distributions` follows
+/// `recipients` instead of being set per agent.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MinerDistributorConfig {
+    #[serde(default)]
+    pub policy: DistributionPolicy,
+    /// Time between payout rounds, e.g. "5m" (the default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payout_interval: Option<SimDuration>,
+    /// Smallest payout in XMR; smaller ones are raised to it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_payout: Option<f64>,
+    /// Python module computing the payouts, for `policy: custom_script`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+    #[serde(default)]
+    pub recipients: RecipientSelector,
+}
+
+/// How a payout round is split between the recipients. The base payout is
+/// the distributor's `md_out_per_tx` outputs of `md_output_amount` XMR.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DistributionPolicy {
+    /// Every recipient gets the base payout
+    #[default]
+    Equal,
+    /// Scaled by the recipient's expected sending rate, so the round's
+    /// total stays that of `equal`
+    Proportional,
+    /// `script` decides
+    CustomScript,
+}
+
+/// Agents the miner distributor pays. Only agents with a wallet qualify.
+/// Written `all_users`, `non_miners` or `{attribute: {key: value, ...}}`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum RecipientSelector {
+    /// Every agent with a wallet, miners included
+    AllUsers,
+    /// Agents with a wallet and no hashrate
+    #[default]
+    NonMiners,
+    /// Agents whose attributes have all of these values
+    Attribute(BTreeMap<String, serde_yaml::Value>),
+}
+
+impl Serialize for RecipientSelector {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        match self {
+            RecipientSelector::AllUsers => serializer.serialize_str("all_users"),
+            RecipientSelector::NonMiners => serializer.serialize_str("non_miners"),
+            RecipientSelector::Attribute(filter) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("attribute", filter)?;
+                map.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RecipientSelector {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Named(String),
+            Filter {
+                attribute: BTreeMap<String, serde_yaml::Value>,
+            },
+        }
+        match Raw::deserialize(deserializer).map_err(|_| {
+            serde::de::Error::custom(
+                "expected all_users, non_miners or {attribute: {key: value, ...}}",
+            )
+        })? {
+            Raw::Named(name) => match name.as_str() {
+                "all_users" => Ok(RecipientSelector::AllUsers),
+                "non_miners" => Ok(RecipientSelector::NonMiners),
+                other => Err(serde::de::Error::custom(format!(
+                    "unknown recipients '{}': expected all_users, non_miners or \
+                     {{attribute: {{key: value, ...}}}}",
+                    other
+                ))),
+            },
+            Raw::Filter { attribute } => Ok(RecipientSelector::Attribute(attribute)),
+        }
+    }
+}
+
+impl MinerDistributorConfig {
+    /// `payout_interval` in seconds
+    pub fn payout_interval_secs(&self) -> Result<u64, String> {
+        let Some(interval) = self.payout_interval else {
+            return Ok(DEFAULT_PAYOUT_INTERVAL_SECS);
+        };
+        match interval.as_secs() {
+            0 => Err("miner_distributor.payout_interval must be at least 1s".to_string()),
+            secs => Ok(secs),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        self.payout_interval_secs()?;
+        if let Some(min) = self.min_payout {
+            if !(min.is_finite() && min >= 0.0) {
+                return Err(format!(
+                    "miner_distributor.min_payout must be a non-negative amount, got {}",
+                    min
+                ));
+            }
+        }
+        match (self.policy, &self.script) {
+            (DistributionPolicy::CustomScript, None) => {
+                return Err("miner_distributor.policy custom_script requires a script".to_string())
+            }
+            (DistributionPolicy::CustomScript, Some(script)) if script.trim().is_empty() => {
+                return Err("miner_distributor.script cannot be empty".to_string())
+            }
+            (DistributionPolicy::Equal | DistributionPolicy::Proportional, Some(_)) => {
+                return Err(
+                    "miner_distributor.script is only used with policy: custom_script".to_string(),
+                )
+            }
+            _ => {}
+        }
+        if let RecipientSelector::Attribute(filter) = &self.recipients {
+            if filter.is_empty() {
+                return Err(
+                    "miner_distributor.recipients.attribute needs at least one attribute"
+                        .to_string(),
+                );
+            }
+            for (key, value) in filter {
+                RecipientSelector::scalar(value).ok_or_else(|| {
+                    format!(
+                        "miner_distributor.recipients.attribute {}: expected a string, number \
+                         or boolean",
+                        key
+                    )
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `MinerDistributorConfig::payout_interval` default, the distributor's
+/// funding cycle
+pub const DEFAULT_PAYOUT_INTERVAL_SECS: u64 = 300;
+
+impl RecipientSelector {
+    /// Whether `agent` is paid
+    pub fn selects(&self, agent: &AgentConfig) -> bool {
+        if !agent.has_wallet() {
+            return false;
+        }
+        match self {
+            RecipientSelector::AllUsers => true,
+            RecipientSelector::NonMiners => !agent.is_miner(),
+            RecipientSelector::Attribute(filter) => {
+                let attributes = agent.attribute_map();
+                filter.iter().all(|(key, value)| {
+                    attributes.get(key).map(String::as_str) == Self::scalar(value).as_deref()
+                })
+            }
+        }
+    }
+
+    /// An attribute value as the registry writes it
+    fn scalar(value: &serde_yaml::Value) -> Option<String> {
+        match value {
+            serde_yaml::Value::String(s) => Some(s.clone()),
+            serde_yaml::Value::Bool(b) => Some(b.to_string()),
+            serde_yaml::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Default binary locations (see `GeneralConfig::binaries`). Values accept the
+/// same forms as an agent's `daemon:` / `wallet:` field: a shorthand name,
+/// `~/path`, or an absolute path.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BinariesConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monerod: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet_rpc: Option<String>,
 }
 
 /// Default reachable fraction: 1.0 = all nodes reachable (perfect network).
@@ -390,9 +1881,9 @@ fn default_hidden_fraction() -> f64 {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TurnoverConfig {
     /// Mean ONLINE session length, e.g. "2h". Exponentially distributed.
-    pub mean_session: String,
+    pub mean_session: SimDuration,
     /// Mean OFFLINE gap between sessions, e.g. "30m". Exponentially distributed.
-    pub mean_downtime: String,
+    pub mean_downtime: SimDuration,
     /// Fraction of eligible nodes that cycle (default 1.0 = all). The
     /// complement stays always-on. Selected deterministically by seed.
     #[serde(default = "default_turnover_fraction")]
@@ -400,17 +1891,17 @@ pub struct TurnoverConfig {
     /// Optional floor on any single session (default 300s) so a restarting
     /// node has time to sync and serve before it leaves again.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub min_session: Option<String>,
+    pub min_session: Option<SimDuration>,
     /// Optional ceiling on any single session (e.g. "6h"). Omit to let the
     /// exponential tail run free (the >6h share then emerges from the mean
     /// rather than being clamped); set it to hard-bound the tail.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub max_session: Option<String>,
+    pub max_session: Option<SimDuration>,
     /// Optional floor on offline gaps (default 30s) — long enough for the
     /// stopped daemon to release its P2P/RPC ports before the next start
     /// (matches the proven phase-gap minimum).
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub min_downtime: Option<String>,
+    pub min_downtime: Option<SimDuration>,
 }
 
 /// Default turnover participation fraction: 1.0 = every eligible node cycles.
@@ -419,19 +1910,116 @@ fn default_turnover_fraction() -> f64 {
 }
 
 /// Agent definitions - named map of agents
-/// Each key is the agent ID (e.g., "miner_001", "user_001")
-#[derive(Debug, Serialize, Deserialize)]
+/// Each key is the agent ID (e.g., "miner_001", "user_001"); an entry with
+/// `count: N` expands into N agents (see `replication`).
+#[derive(Debug, Clone, Serialize)]
 pub struct AgentDefinitions {
     /// Named agents map - agent_id -> AgentConfig
     #[serde(flatten)]
     pub agents: BTreeMap<String, AgentConfig>,
 }
 
+impl<'de> Deserialize<'de> for AgentDefinitions {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let entries = serde_yaml::Mapping::deserialize(deserializer)?;
+        let mut agents = BTreeMap::new();
+        for (id, value) in expand_agent_entries(entries).map_err(D::Error::custom)? {
+            let agent: AgentConfig = serde_yaml::from_value(value)
+                .map_err(|e| D::Error::custom(format!("agent '{}': {}", id, e)))?;
+            agents.insert(id, agent);
+        }
+        Ok(AgentDefinitions { agents })
+    }
+}
+
+/// Address family an agent's host uses
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum IpVersion {
+    #[default]
+    V4,
+    V6,
+    Dual,
+}
+
+/// Where a daemon looks for its first peers when `enable_dns_server` is on
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PeerDiscovery {
+    /// The simulated DNS server only (`DNS_PUBLIC`); no generated seed links
+    Dns,
+    /// The generated seed links only, as if there were no DNS server
+    Seeds,
+    /// Both
+    #[default]
+    Both,
+}
+
+/// Column the simulation monitor records per node on each poll
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MonitorMetric {
+    /// Chain height
+    Height,
+    /// Transactions in the node's pool
+    Mempool,
+    /// Incoming plus outgoing connections
+    Peers,
+    /// Cumulative bytes received and sent
+    Bandwidth,
+}
+
+impl MonitorMetric {
+    pub const ALL: [MonitorMetric; 4] = [
+        MonitorMetric::Height,
+        MonitorMetric::Mempool,
+        MonitorMetric::Peers,
+        MonitorMetric::Bandwidth,
+    ];
+
+    /// Spelling in config and on the monitor's command line
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MonitorMetric::Height => "height",
+            MonitorMetric::Mempool => "mempool",
+            MonitorMetric::Peers => "peers",
+            MonitorMetric::Bandwidth => "bandwidth",
+        }
+    }
+}
+
+/// Format of the simulation monitor's time-series file
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MonitorOutputFormat {
+    /// One JSON object per line
+    #[default]
+    Jsonl,
+    /// A header row naming the selected columns, then one row per sample
+    Csv,
+}
+
+impl MonitorOutputFormat {
+    /// File extension, also the spelling on the monitor's command line
+    pub fn extension(self) -> &'static str {
+        match self {
+            MonitorOutputFormat::Jsonl => "jsonl",
+            MonitorOutputFormat::Csv => "csv",
+        }
+    }
+}
+
 /// Daemon selection strategy for wallet-only agents connecting to remote public nodes
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum DaemonSelectionStrategy {
-    /// Randomly select from available public nodes
+    /// Randomly select from available public nodes (also `random_public`)
+    #[serde(alias = "random_public")]
     Random,
     /// Use the first available public node
     First,
@@ -464,7 +2052,10 @@ pub enum DaemonConfig {
     Local(String),
     /// Remote daemon - connects to another daemon for wallet-only agents
     Remote {
-        /// "auto" for automatic discovery from public nodes, or specific "ip:port"
+        /// "auto" (the default) for discovery from public nodes at runtime,
+        /// the id of a public-node agent (resolved to its RPC address at
+        /// generation time), or a specific "ip:port"
+        #[serde(default = "default_remote_daemon_address")]
         address: String,
         /// Selection strategy when address is "auto"
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -489,6 +2080,34 @@ pub enum Network {
         seed_nodes: Option<Vec<String>>,
         #[serde(skip_serializing_if = "Option::is_none")]
         topology: Option<Topology>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        hybrid_options: Option<HybridOptions>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        connection_enforcement: Option<ConnectionEnforcement>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        seed_selection: Option<SeedSelection>,
+        /// Seeds wanted by `seed_selection` auto (non-Dynamic) and first_n
+        #[serde(skip_serializing_if = "Option::is_none")]
+        seed_count: Option<usize>,
+        /// `--out-peers` for daemon agents that don't set `out_peers`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        out_peers: Option<u32>,
+        /// `--in-peers` for daemon agents that don't set `in_peers`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        in_peers: Option<u32>,
+        /// Largest `out_peers` / `in_peers` validation accepts
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max_peers: Option<u32>,
+        /// Latency/loss injection; unset keeps the built-in switch graph
+        #[serde(skip_serializing_if = "Option::is_none")]
+        link_profile: Option<LinkProfile>,
+        /// Share of the user agents per region (`europe`, `NorthAmerica`,
+        /// ...), summing to 1.0; unset cycles agents over all six regions
+        #[serde(skip_serializing_if = "Option::is_none")]
+        geographic_distribution: Option<BTreeMap<String, f64>>,
+        /// Scheduled partitions and heals, in time order
+        #[serde(skip_serializing_if = "Option::is_none")]
+        events: Option<Vec<NetworkEvent>>,
     },
     Gml {
         path: String,
@@ -498,27 +2117,81 @@ pub enum Network {
         seed_nodes: Option<Vec<String>>,
         #[serde(skip_serializing_if = "Option::is_none")]
         topology: Option<Topology>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        hybrid_options: Option<HybridOptions>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        connection_enforcement: Option<ConnectionEnforcement>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        seed_selection: Option<SeedSelection>,
+        /// Seeds wanted by `seed_selection` auto (non-Dynamic) and first_n
+        #[serde(skip_serializing_if = "Option::is_none")]
+        seed_count: Option<usize>,
+        /// `--out-peers` for daemon agents that don't set `out_peers`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        out_peers: Option<u32>,
+        /// `--in-peers` for daemon agents that don't set `in_peers`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        in_peers: Option<u32>,
+        /// Largest `out_peers` / `in_peers` validation accepts
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max_peers: Option<u32>,
         /// Agent distribution strategy across the GML topology.
         /// Defaults to Global (distribute across all regions).
         #[serde(skip_serializing_if = "Option::is_none")]
         distribution: Option<Distribution>,
+        /// Constrains where miners land, overriding `distribution` for them
+        #[serde(skip_serializing_if = "Option::is_none")]
+        miner_placement: Option<MinerPlacement>,
+        /// Latency synthesis for edges without a `latency` attribute.
+        /// Defaults apply when unset.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        latency_synthesis: Option<LatencySynthesis>,
+        /// Size of each AS's subnet as a prefix length, "/24" (245 agents
+        /// per AS, the default) up to "/16"
+        #[serde(skip_serializing_if = "Option::is_none")]
+        subnet_size: Option<String>,
+        /// Share of the agents per AS number, summing to 1.0; replaces
+        /// `distribution` for agents without a placement
+        #[serde(skip_serializing_if = "Option::is_none")]
+        as_weights: Option<BTreeMap<String, f64>>,
+        /// Write Shadow only the nodes on shortest paths between nodes
+        /// that carry hosts; off by default
+        #[serde(skip_serializing_if = "Option::is_none")]
+        prune_unused_nodes: Option<bool>,
+        /// Scheduled partitions and heals, in time order
+        #[serde(skip_serializing_if = "Option::is_none")]
+        events: Option<Vec<NetworkEvent>>,
     },
 }
 
+impl Network {
+    /// `events`, empty when unset
+    pub fn events(&self) -> &[NetworkEvent] {
+        match self {
+            Network::Switch { events, .. } | Network::Gml { events, .. } => {
+                events.as_deref().unwrap_or_default()
+            }
+        }
+    }
+}
+
 /// Default implementations
 impl Default for GeneralConfig {
     fn default() -> Self {
         Self {
-            stop_time: "1h".to_string(),
+            stop_time: SimDuration::from_secs(3600),
             fresh_blockchain: Some(true),
             python_venv: None,
             log_level: Some("info".to_string()),
             simulation_seed: default_simulation_seed(),
+            chain: Chain::default(),
+            fixed_difficulty: None,
             parallelism: default_parallelism(),
             enable_dns_server: None,
             difficulty_cache_ttl: default_difficulty_cache_ttl(),
             shadow_log_level: default_shadow_log_level(),
             runahead: None,
+            shadow_experimental: None,
             bootstrap_end_time: None,
             progress: Some(true),     // Default to showing progress
             process_threads: Some(1), // Default to single-threaded for determinism
@@ -527,11 +2200,21 @@ impl Default for GeneralConfig {
             wallet_defaults: None,    // No wallet defaults by default
             shared_dir: default_shared_dir(),
             daemon_data_dir: default_daemon_data_dir(),
+            blockchain_source_dir: None,
             fallback_seeds: FallbackSeedsMode::default(),
             reachable_fraction: default_reachable_fraction(),
             reachable_by_role: None,
             hidden_fraction: default_hidden_fraction(),
             turnover: None,
+            binaries: None,
+            strict_validation: false,
+            agents_path: None,
+            startup: None,
+            resource_model: None,
+            memory_limit: None,
+            cpu_weight: None,
+            pcap_capture_dir: None,
+            hashrate_distribution: HashrateDistribution::Explicit,
         }
     }
 }
@@ -545,6 +2228,16 @@ impl Default for Network {
             peer_mode: Some(PeerMode::Dynamic),
             seed_nodes: None,
             topology: Some(Topology::Dag), // Default to DAG for backward compatibility
+            hybrid_options: None,
+            connection_enforcement: None,
+            seed_selection: None,
+            seed_count: None,
+            out_peers: None,
+            in_peers: None,
+            max_peers: None,
+            link_profile: None,
+            geographic_distribution: None,
+            events: None,
         }
     }
 }
@@ -2,8 +2,6 @@
 
 use std::collections::BTreeMap;
 
-use crate::utils::duration::parse_duration_to_seconds;
-
 use super::errors::PhaseValidationError;
 use super::phases::{DaemonPhase, MIN_PHASE_GAP_SECONDS};
 
@@ -51,7 +49,7 @@ pub fn validate_daemon_phases(
             let next_phase = &phases[&next_phase_num];
 
             // Current phase needs stop time
-            let stop_time = match &phase.stop {
+            let stop_time = match phase.stop {
                 Some(t) => t,
                 None => {
                     return Err(PhaseValidationError::MissingTiming {
@@ -63,7 +61,7 @@ pub fn validate_daemon_phases(
             };
 
             // Next phase needs start time
-            let next_start_time = match &next_phase.start {
+            let next_start_time = match next_phase.start {
                 Some(t) => t,
                 None => {
                     return Err(PhaseValidationError::MissingTiming {
@@ -74,31 +72,14 @@ pub fn validate_daemon_phases(
                 }
             };
 
-            // Parse and compare times
-            let stop_seconds = parse_duration_to_seconds(stop_time).map_err(|e| {
-                PhaseValidationError::InvalidDuration {
-                    phase_type: format!("daemon (agent {})", agent_id),
-                    phase_num,
-                    detail: format!("stop time: {}", e),
-                }
-            })?;
-
-            let start_seconds = parse_duration_to_seconds(next_start_time).map_err(|e| {
-                PhaseValidationError::InvalidDuration {
-                    phase_type: format!("daemon (agent {})", agent_id),
-                    phase_num: next_phase_num,
-                    detail: format!("start time: {}", e),
-                }
-            })?;
-
             // Check gap is sufficient
-            if start_seconds < stop_seconds + MIN_PHASE_GAP_SECONDS {
+            if next_start_time.as_secs() < stop_time.as_secs() + MIN_PHASE_GAP_SECONDS {
                 return Err(PhaseValidationError::GapTooSmall {
                     phase_type: format!("daemon (agent {})", agent_id),
                     phase_num,
                     next_phase_num,
-                    stop_time: stop_time.clone(),
-                    start_time: next_start_time.clone(),
+                    stop_time,
+                    start_time: next_start_time,
                     min_gap: MIN_PHASE_GAP_SECONDS,
                 });
             }
@@ -0,0 +1,291 @@
+//! Per-agent transaction workload profiles (`workload:`).
+//!
+//! A profile turns into a list of send times, in simulation seconds, over
+//! the window an agent is active. The generator writes them to the
+//! agent's workload file so the Python agent follows the profile instead
+//! of `transaction_interval`.
+
+use rand::Rng;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::utils::duration::SimDuration;
+
+/// How often a user agent sends transactions. Rates are transactions per
+/// hour; durations take seconds or a duration string ("30s", "5m", "2h").
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "profile", rename_all = "snake_case")]
+pub enum WorkloadProfile {
+    /// One transaction every `interval`
+    Constant {
+        #[serde(deserialize_with = "seconds")]
+        interval: u64,
+    },
+    /// Poisson arrivals at `rate` per hour
+    Poisson { rate: f64 },
+    /// `burst_size` transactions `burst_interval` apart, then `idle` before
+    /// the next burst
+    Bursty {
+        burst_size: u32,
+        #[serde(deserialize_with = "seconds")]
+        burst_interval: u64,
+        #[serde(deserialize_with = "seconds")]
+        idle: u64,
+    },
+    /// Poisson arrivals at each window's rate; nothing outside the windows
+    Schedule { windows: Vec<RateWindow> },
+}
+
+/// `rate` transactions per hour from `from` until `to` (simulation time)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateWindow {
+    #[serde(deserialize_with = "seconds")]
+    pub from: u64,
+    #[serde(deserialize_with = "seconds")]
+    pub to: u64,
+    pub rate: f64,
+}
+
+fn seconds<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    SimDuration::deserialize(deserializer).map(SimDuration::as_secs)
+}
+
+fn check_rate(rate: f64, what: &str) -> Result<(), String> {
+    if rate.is_finite() && rate > 0.0 {
+        Ok(())
+    } else {
+        Err(format!("{} must be a positive number, got {}", what, rate))
+    }
+}
+
+impl WorkloadProfile {
+    /// The profile name as written in the config
+    pub fn name(&self) -> &'static str {
+        match self {
+            WorkloadProfile::Constant { .. } => "constant",
+            WorkloadProfile::Poisson { .. } => "poisson",
+            WorkloadProfile::Bursty { .. } => "bursty",
+            WorkloadProfile::Schedule { .. } => "schedule",
+        }
+    }
+
+    /// Reject parameters that would send nothing or never advance
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            WorkloadProfile::Constant { interval } => {
+                if *interval == 0 {
+                    Err("constant interval must be positive".to_string())
+                } else {
+                    Ok(())
+                }
+            }
+            WorkloadProfile::Poisson { rate } => check_rate(*rate, "poisson rate"),
+            WorkloadProfile::Bursty {
+                burst_size,
+                burst_interval,
+                idle,
+            } => {
+                if *burst_size == 0 {
+                    Err("bursty burst_size must be at least 1".to_string())
+                } else if *burst_interval == 0 || *idle == 0 {
+                    Err("bursty burst_interval and idle must be positive".to_string())
+                } else {
+                    Ok(())
+                }
+            }
+            WorkloadProfile::Schedule { windows } => {
+                if windows.is_empty() {
+                    return Err("schedule needs at least one window".to_string());
+                }
+                let mut sorted: Vec<&RateWindow> = windows.iter().collect();
+                sorted.sort_by_key(|w| w.from);
+                for (i, w) in sorted.iter().enumerate() {
+                    if w.from >= w.to {
+                        return Err(format!(
+                            "schedule window {}s-{}s does not end after it starts",
+                            w.from, w.to
+                        ));
+                    }
+                    check_rate(
+                        w.rate,
+                        &format!("schedule window {}s-{}s rate", w.from, w.to),
+                    )?;
+                    if let Some(next) = sorted.get(i + 1).filter(|n| n.from < w.to) {
+                        return Err(format!(
+                            "schedule windows {}s-{}s and {}s-{}s overlap",
+                            w.from, w.to, next.from, next.to
+                        ));
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Send times in `[from, until)`, in simulation seconds (millisecond
+    /// resolution). A constant profile first sends one interval after
+    /// `from` and a bursty one bursts at `from`; the Poisson-based
+    /// profiles draw their gaps from `rng`.
+    pub fn send_times<R: Rng>(&self, from: u64, until: u64, rng: &mut R) -> Vec<f64> {
+        let (from, until) = (from as f64, until as f64);
+        let mut times = Vec::new();
+        match self {
+            WorkloadProfile::Constant { interval } => {
+                let mut t = from + *interval as f64;
+                while t < until {
+                    times.push(t);
+                    t += *interval as f64;
+                }
+            }
+            WorkloadProfile::Poisson { rate } => poisson(&mut times, *rate, from, until, rng),
+            WorkloadProfile::Bursty {
+                burst_size,
+                burst_interval,
+                idle,
+            } => {
+                let mut t = from;
+                'bursts: loop {
+                    for i in 0..*burst_size {
+                        if i > 0 {
+                            t += *burst_interval as f64;
+                        }
+                        if t >= until {
+                            break 'bursts;
+                        }
+                        times.push(t);
+                    }
+                    t += *idle as f64;
+                }
+            }
+            WorkloadProfile::Schedule { windows } => {
+                let mut sorted: Vec<&RateWindow> = windows.iter().collect();
+                sorted.sort_by_key(|w| w.from);
+                for w in sorted {
+                    let start = from.max(w.from as f64);
+                    let end = until.min(w.to as f64);
+                    if start < end {
+                        poisson(&mut times, w.rate, start, end, rng);
+                    }
+                }
+            }
+        }
+        times
+    }
+
+    /// Expected number of transactions in `[from, until)`
+    pub fn expected_count(&self, from: u64, until: u64) -> f64 {
+        let hours = |a: u64, b: u64| b.saturating_sub(a) as f64 / 3600.0;
+        match self {
+            WorkloadProfile::Poisson { rate } => rate * hours(from, until),
+            WorkloadProfile::Schedule { windows } => windows
+                .iter()
+                .map(|w| w.rate * hours(from.max(w.from), until.min(w.to)))
+                .sum(),
+            // Deterministic: the send times are the expectation
+            WorkloadProfile::Constant { .. } | WorkloadProfile::Bursty { .. } => {
+                self.send_times(from, until, &mut rand::rngs::mock::StepRng::new(0, 0))
+                    .len() as f64
+            }
+        }
+    }
+}
+
+/// Poisson arrivals at `rate` per hour in `[from, until)`
+fn poisson<R: Rng>(times: &mut Vec<f64>, rate: f64, from: f64, until: f64, rng: &mut R) {
+    let mean_gap = 3600.0 / rate;
+    let mut t = from;
+    loop {
+        let u: f64 = rng.gen();
+        t += -(1.0 - u).ln() * mean_gap;
+        if t >= until {
+            break;
+        }
+        times.push((t * 1000.0).round() / 1000.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn parse(yaml: &str) -> WorkloadProfile {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn profiles_parse_durations_and_rates() {
+        assert_eq!(
+            parse("profile: constant\ninterval: 2m"),
+            WorkloadProfile::Constant { interval: 120 }
+        );
+        assert_eq!(
+            parse("profile: bursty\nburst_size: 3\nburst_interval: 10\nidle: 1h"),
+            WorkloadProfile::Bursty {
+                burst_size: 3,
+                burst_interval: 10,
+                idle: 3600
+            }
+        );
+        let schedule = parse(
+            "profile: schedule\nwindows:\n  - {from: 1h, to: 2h, rate: 30}\n  - {from: 0, to: 1h, rate: 6}",
+        );
+        assert!(schedule.validate().is_ok());
+        assert!(serde_yaml::from_str::<WorkloadProfile>("profile: uniform").is_err());
+    }
+
+    #[test]
+    fn invalid_parameters_are_rejected() {
+        let invalid = [
+            "profile: constant\ninterval: 0",
+            "profile: poisson\nrate: 0",
+            "profile: poisson\nrate: -3",
+            "profile: bursty\nburst_size: 0\nburst_interval: 1\nidle: 60",
+            "profile: bursty\nburst_size: 2\nburst_interval: 1\nidle: 0",
+            "profile: schedule\nwindows: []",
+            "profile: schedule\nwindows:\n  - {from: 2h, to: 1h, rate: 6}",
+            "profile: schedule\nwindows:\n  - {from: 0, to: 2h, rate: 6}\n  - {from: 1h, to: 3h, rate: 6}",
+        ];
+        for yaml in invalid {
+            assert!(parse(yaml).validate().is_err(), "{}", yaml);
+        }
+    }
+
+    #[test]
+    fn send_times_follow_the_profile() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let constant = WorkloadProfile::Constant { interval: 60 };
+        assert_eq!(
+            constant.send_times(100, 300, &mut rng),
+            vec![160.0, 220.0, 280.0]
+        );
+        assert_eq!(constant.expected_count(100, 300), 3.0);
+
+        let bursty = WorkloadProfile::Bursty {
+            burst_size: 2,
+            burst_interval: 5,
+            idle: 100,
+        };
+        assert_eq!(
+            bursty.send_times(0, 210, &mut rng),
+            vec![0.0, 5.0, 105.0, 110.0]
+        );
+
+        let schedule = WorkloadProfile::Schedule {
+            windows: vec![RateWindow {
+                from: 3600,
+                to: 7200,
+                rate: 600.0,
+            }],
+        };
+        let times = schedule.send_times(0, 10_800, &mut rng);
+        assert!(times.iter().all(|&t| (3600.0..7200.0).contains(&t)));
+        // 600 expected; well within a few standard deviations
+        assert!((500..700).contains(&times.len()), "{}", times.len());
+        assert_eq!(schedule.expected_count(0, 10_800), 600.0);
+        assert_eq!(schedule.expected_count(5400, 10_800), 300.0);
+    }
+}
@@ -1,22 +1,295 @@
-use crate::config::{validate_daemon_phases, Config};
-use crate::utils::validation::{validate_agent_daemon_config, validate_mining_config};
-use color_eyre::eyre::{eyre, WrapErr};
+use crate::config::fields::struct_fields;
+use crate::config::{
+    apply_agent_groups, declared_version, deprecated_fields, migrate, unknown_agent_keys,
+    validate_daemon_phases, Config, DeprecatedField, GeneralConfig, PerformanceConfig,
+    AGENT_GROUPS_KEY, CONFIG_VERSION,
+};
+use crate::process::assign_agent_ports;
+use crate::utils::validation::{
+    validate_agent_attributes, validate_agent_bandwidth, validate_agent_daemon_config,
+    validate_agent_schedule, validate_colocation, validate_daemon_args, validate_daemon_restarts,
+    validate_ip_versions, validate_mining_config, validate_process_limits, validate_spy_agents,
+    validate_subnet_groups, validate_unreachable_agents, validate_wallet_args, validate_workloads,
+};
+use color_eyre::eyre::{bail, eyre, WrapErr};
 use color_eyre::Result;
-use log::info;
-use std::fs::File;
+use log::{info, warn};
+use serde_yaml::Value;
+use std::fmt;
+use std::fs;
 use std::path::Path;
 
+/// How this build treats a config's `config_version`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionSupport {
+    /// Read natively
+    Current,
+    /// Read as is, deprecated spellings included; `--migrate` rewrites it
+    /// to the current version
+    Migratable,
+    /// Written for a newer monerosim; upgrade to read it
+    TooNew,
+}
+
+/// Every `config_version` this build knows, oldest first, with what
+/// changed in it
+pub const COMPATIBILITY: &[(u32, VersionSupport, &str)] = &[
+    (
+        1,
+        VersionSupport::Migratable,
+        "no config_version; accepts daemon_binary, wallet_binary, daemon_arg_overrides, \
+         shadow.workers and typed agent fields inside attributes",
+    ),
+    (
+        2,
+        VersionSupport::Current,
+        "config_version: 2; the older spellings above are rejected, and unknown \
+         general.shadow_experimental keys are passed through to Shadow",
+    ),
+];
+
+/// How this build treats `version`
+pub fn version_support(version: u32) -> VersionSupport {
+    COMPATIBILITY
+        .iter()
+        .find(|(v, _, _)| *v == version)
+        .map_or(VersionSupport::TooNew, |(_, support, _)| *support)
+}
+
+/// What a raw config uses that this build does not read natively
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompatibilityReport {
+    /// Declared `config_version` (1 when absent)
+    pub version: u32,
+    pub support: VersionSupport,
+    /// Dotted paths of fields no known version accepts
+    pub unknown: Vec<String>,
+    /// Spellings of an older version
+    pub deprecated: Vec<DeprecatedField>,
+}
+
+impl CompatibilityReport {
+    /// Whether the config loads as is (deprecated spellings only warn
+    /// below the current version)
+    pub fn is_loadable(&self) -> bool {
+        self.support != VersionSupport::TooNew
+            && self.unknown.is_empty()
+            && (self.support == VersionSupport::Migratable || self.deprecated.is_empty())
+    }
+
+    /// Whether `--migrate` turns it into a config that loads natively
+    pub fn migratable(&self) -> bool {
+        self.support == VersionSupport::Migratable && self.unknown.is_empty()
+    }
+}
+
+/// A config this build cannot read, with everything that is wrong with it
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigCompatibilityError {
+    /// `config_version` is not a positive integer
+    InvalidVersion(String),
+    Incompatible(CompatibilityReport),
+}
+
+impl std::error::Error for ConfigCompatibilityError {}
+
+impl fmt::Display for ConfigCompatibilityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let report = match self {
+            ConfigCompatibilityError::InvalidVersion(e) => return write!(f, "{}", e),
+            ConfigCompatibilityError::Incompatible(report) => report,
+        };
+        if report.support == VersionSupport::TooNew {
+            write!(
+                f,
+                "config_version {} is newer than this monerosim supports ({}); upgrade \
+                 monerosim (--migrate cannot downgrade a config)",
+                report.version, CONFIG_VERSION
+            )?;
+        } else {
+            write!(
+                f,
+                "config_version {} config is not compatible with this monerosim",
+                report.version
+            )?;
+        }
+        if !report.unknown.is_empty() {
+            write!(
+                f,
+                "\nunknown fields (--migrate cannot fix these; check their spelling against \
+                 docs/CONFIGURATION.md):"
+            )?;
+            for path in &report.unknown {
+                write!(f, "\n  - {}", path)?;
+            }
+        }
+        if !report.deprecated.is_empty() {
+            if report.migratable() {
+                write!(f, "\ndeprecated fields (--migrate rewrites these):")?;
+            } else if report.support == VersionSupport::Current {
+                write!(
+                    f,
+                    "\nfields renamed in config_version {} (rename them, or remove \
+                     config_version and run --migrate):",
+                    CONFIG_VERSION
+                )?;
+            } else {
+                write!(f, "\ndeprecated fields:")?;
+            }
+            for field in &report.deprecated {
+                write!(f, "\n  - {} -> {}", field.path, field.replacement)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compare a raw config against the schema this build reads: its
+/// `config_version`, top-level, `general:`, `performance:` and agent keys
+/// nothing accepts, and spellings of older versions. Ok when it loads,
+/// possibly with deprecation warnings.
+pub fn check_config_compatibility(
+    doc: &Value,
+) -> std::result::Result<CompatibilityReport, ConfigCompatibilityError> {
+    let version = declared_version(doc).map_err(ConfigCompatibilityError::InvalidVersion)?;
+    let mut unknown = Vec::new();
+    let mut check = |section: Option<&str>, map: &serde_yaml::Mapping, known: &[&str]| {
+        for key in map.keys() {
+            let key = key
+                .as_str()
+                .map_or_else(|| format!("{:?}", key), str::to_string);
+            if !known.contains(&key.as_str()) {
+                unknown.push(match section {
+                    Some(section) => format!("{}.{}", section, key),
+                    None => key,
+                });
+            }
+        }
+    };
+    if let Some(root) = doc.as_mapping() {
+        check(None, root, struct_fields::<Config>());
+    }
+    for (section, known) in [
+        ("general", struct_fields::<GeneralConfig>()),
+        ("performance", struct_fields::<PerformanceConfig>()),
+    ] {
+        if let Some(map) = doc.get(section).and_then(Value::as_mapping) {
+            check(Some(section), map, known);
+        }
+    }
+    for section in ["agents", AGENT_GROUPS_KEY] {
+        let Some(entries) = doc.get(section).and_then(Value::as_mapping) else {
+            continue;
+        };
+        for (id, entry) in entries {
+            if let (Some(id), Some(entry)) = (id.as_str(), entry.as_mapping()) {
+                unknown.extend(
+                    unknown_agent_keys(entry)
+                        .into_iter()
+                        .map(|key| format!("{}.{}.{}", section, id, key)),
+                );
+            }
+        }
+    }
+
+    let report = CompatibilityReport {
+        version,
+        support: version_support(version),
+        unknown,
+        deprecated: deprecated_fields(doc),
+    };
+    if report.is_loadable() {
+        Ok(report)
+    } else {
+        Err(ConfigCompatibilityError::Incompatible(report))
+    }
+}
+
+/// Rewrite the config at `input` to the current `config_version` and
+/// return it as YAML, checking that the result loads. Written to `output`
+/// when given; the input file is only overwritten when `output` names it.
+/// Comments are not carried over.
+pub fn migrate_config(input: &Path, output: Option<&Path>) -> Result<String> {
+    let content = fs::read_to_string(input)
+        .wrap_err_with(|| format!("Failed to open config file: {}", input.display()))?;
+    let mut doc: Value = serde_yaml::from_str(&content)
+        .wrap_err_with(|| format!("Failed to parse YAML config: {}", input.display()))?;
+    if let Err(ConfigCompatibilityError::Incompatible(report)) = check_config_compatibility(&doc) {
+        if !report.unknown.is_empty() {
+            bail!(
+                "Cannot migrate {}: {}",
+                input.display(),
+                ConfigCompatibilityError::Incompatible(report)
+            );
+        }
+    }
+    let notes =
+        migrate(&mut doc).map_err(|e| eyre!("Cannot migrate {}: {}", input.display(), e))?;
+    for note in &notes {
+        info!("Migrated {}", note);
+    }
+    check_config_compatibility(&doc)
+        .map_err(|e| eyre!("Migrated config is not loadable: {}", e))?;
+    serde_yaml::from_value::<Config>(doc.clone()).wrap_err("Migrated config does not parse")?;
+    let yaml = serde_yaml::to_string(&doc).wrap_err("Failed to serialize migrated config")?;
+
+    if let Some(output) = output {
+        fs::write(output, &yaml)
+            .wrap_err_with(|| format!("Failed to write '{}'", output.display()))?;
+        info!(
+            "Wrote config_version {} config to {}",
+            CONFIG_VERSION,
+            output.display()
+        );
+    }
+    Ok(yaml)
+}
+
 /// Load and parse configuration from a YAML file
 pub fn load_config(config_path: &Path) -> Result<Config> {
     info!("Loading configuration from: {:?}", config_path);
 
-    // Open the configuration file
-    let file = File::open(config_path)
+    let content = fs::read_to_string(config_path)
         .wrap_err_with(|| format!("Failed to open config file: {}", config_path.display()))?;
 
-    // Parse the YAML content
-    let config: Config = serde_yaml::from_reader(file)
+    // Check the schema on the raw document first, so unknown fields are
+    // reported instead of silently dropped
+    let mut doc: Value = serde_yaml::from_str(&content)
         .wrap_err_with(|| format!("Failed to parse YAML config: {}", config_path.display()))?;
+    let compatibility = check_config_compatibility(&doc)
+        .wrap_err_with(|| format!("Incompatible configuration in {}", config_path.display()))?;
+    if !compatibility.deprecated.is_empty() {
+        let fields: Vec<String> = compatibility
+            .deprecated
+            .iter()
+            .map(|f| format!("{} -> {}", f.path, f.replacement))
+            .collect();
+        warn!(
+            "{} uses config_version {} spellings ({}); `monerosim --config {} --migrate \
+             --migrate-output <path>` rewrites it to config_version {}",
+            config_path.display(),
+            compatibility.version,
+            fields.join(", "),
+            config_path.display(),
+            CONFIG_VERSION
+        );
+    }
+
+    // Parse the YAML content, with agent groups merged into their agents
+    // first so everything below sees the resolved entries
+    let grouped = apply_agent_groups(&mut doc).map_err(|e| {
+        eyre!(
+            "Agent group configuration error in {}: {}",
+            config_path.display(),
+            e
+        )
+    })?;
+    let config: Config = if grouped {
+        serde_yaml::from_value(doc)
+    } else {
+        serde_yaml::from_str(&content)
+    }
+    .wrap_err_with(|| format!("Failed to parse YAML config: {}", config_path.display()))?;
 
     // Log that we're using agent mode
     info!("Detected agent-based configuration");
@@ -30,9 +303,54 @@ pub fn load_config(config_path: &Path) -> Result<Config> {
     validate_agent_daemon_config(&config.agents.agents)
         .map_err(|e| eyre!("Agent configuration error: {}", e))?;
 
-    validate_mining_config(&config.agents.agents)
+    validate_mining_config(&config.agents.agents, config.general.hashrate_distribution)
         .map_err(|e| eyre!("Mining configuration error: {}", e))?;
 
+    validate_agent_bandwidth(&config.agents.agents)
+        .map_err(|e| eyre!("Bandwidth configuration error: {}", e))?;
+
+    validate_agent_attributes(&config.agents.agents)
+        .map_err(|e| eyre!("Attribute configuration error: {}", e))?;
+
+    validate_ip_versions(&config.agents.agents)
+        .map_err(|e| eyre!("IP configuration error: {}", e))?;
+
+    validate_spy_agents(&config.agents.agents)
+        .map_err(|e| eyre!("Spy configuration error: {}", e))?;
+
+    validate_unreachable_agents(&config.agents.agents)
+        .map_err(|e| eyre!("Reachability configuration error: {}", e))?;
+
+    validate_daemon_restarts(&config.agents.agents)
+        .map_err(|e| eyre!("Restart configuration error: {}", e))?;
+
+    validate_agent_schedule(&config.agents.agents, config.general.stop_time)
+        .map_err(|e| eyre!("Schedule configuration error: {}", e))?;
+
+    validate_workloads(&config.agents.agents)
+        .map_err(|e| eyre!("Workload configuration error: {}", e))?;
+
+    validate_colocation(&config.agents.agents)
+        .map_err(|e| eyre!("Host configuration error: {}", e))?;
+    validate_subnet_groups(&config.agents.agents)
+        .map_err(|e| eyre!("IP configuration error: {}", e))?;
+    validate_process_limits(&config.agents.agents, &config.general)
+        .map_err(|e| eyre!("Resource limit configuration error: {}", e))?;
+    assign_agent_ports(&config.agents.agents, config.general.chain)
+        .map_err(|e| eyre!("Port configuration error: {}", e))?;
+
+    validate_daemon_args(
+        config.general.daemon_defaults.as_ref(),
+        &config.agents.agents,
+    )
+    .map_err(|e| eyre!("Daemon argument error: {}", e))?;
+
+    validate_wallet_args(
+        config.general.wallet_defaults.as_ref(),
+        &config.agents.agents,
+    )
+    .map_err(|e| eyre!("Wallet argument error: {}", e))?;
+
     // Validate daemon phase timing for agents with phases
     for (agent_id, agent_config) in &config.agents.agents {
         if let Some(phases) = &agent_config.daemon_phases {
@@ -48,3 +366,163 @@ pub fn load_config(config_path: &Path) -> Result<Config> {
 
     Ok(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DaemonConfig;
+    use tempfile::TempDir;
+
+    const MINIMAL: &str = r#"
+general:
+  stop_time: 1h
+network:
+  type: 1_gbit_switch
+agents:
+  miner:
+    daemon: monerod
+    wallet: monero-wallet-rpc
+    hashrate: 100
+"#;
+
+    fn check(yaml: &str) -> std::result::Result<CompatibilityReport, ConfigCompatibilityError> {
+        check_config_compatibility(&serde_yaml::from_str(yaml).unwrap())
+    }
+
+    #[test]
+    fn unknown_fields_are_listed_at_every_level() {
+        let yaml = format!(
+            "{}    hashrat: 5\n    daemon_0_start: 10m\nmetadata: x\nperformance:\n  fast: true\n",
+            MINIMAL.replace("  stop_time: 1h\n", "  stop_time: 1h\n  stoptime: 2h\n")
+        );
+        let Err(ConfigCompatibilityError::Incompatible(report)) = check(&yaml) else {
+            panic!("unknown fields must be rejected");
+        };
+        assert_eq!(
+            report.unknown,
+            [
+                "metadata",
+                "general.stoptime",
+                "performance.fast",
+                "agents.miner.hashrat"
+            ]
+        );
+        assert!(!report.migratable());
+        let message = ConfigCompatibilityError::Incompatible(report).to_string();
+        assert!(
+            message.contains("--migrate cannot fix these"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn newer_versions_ask_for_an_upgrade() {
+        let err = check(&format!("config_version: 3\n{}", MINIMAL)).unwrap_err();
+        assert!(
+            err.to_string().starts_with(
+                "config_version 3 is newer than this monerosim supports (2); upgrade monerosim"
+            ),
+            "{}",
+            err
+        );
+        assert!(matches!(
+            check("config_version: -1\ngeneral: {}"),
+            Err(ConfigCompatibilityError::InvalidVersion(_))
+        ));
+    }
+
+    #[test]
+    fn old_spellings_load_in_version_1_only() {
+        let v1 = MINIMAL.replace("daemon: monerod", "daemon_binary: monerod");
+        let report = check(&v1).unwrap();
+        assert_eq!(report.version, 1);
+        assert_eq!(report.support, VersionSupport::Migratable);
+        assert_eq!(report.deprecated[0].path, "agents.miner.daemon_binary");
+        assert!(report.migratable());
+
+        let err = check(&format!("config_version: 2\n{}", v1)).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("fields renamed in config_version 2"),
+            "{}",
+            err
+        );
+        assert!(check(&format!("config_version: 2\n{}", MINIMAL))
+            .unwrap()
+            .deprecated
+            .is_empty());
+    }
+
+    #[test]
+    fn migrate_writes_only_where_asked() {
+        let tmp = TempDir::new().unwrap();
+        let input = tmp.path().join("old.yaml");
+        let old = MINIMAL.replace("daemon: monerod", "daemon_binary: monerod");
+        fs::write(&input, &old).unwrap();
+
+        let printed = migrate_config(&input, None).unwrap();
+        assert!(printed.starts_with("config_version: 2\n"), "{}", printed);
+        assert_eq!(fs::read_to_string(&input).unwrap(), old, "input untouched");
+
+        let output = tmp.path().join("new.yaml");
+        migrate_config(&input, Some(&output)).unwrap();
+        assert_eq!(fs::read_to_string(&input).unwrap(), old);
+        let migrated = load_config(&output).unwrap();
+        assert_eq!(migrated.config_version, Some(CONFIG_VERSION));
+        assert!(matches!(
+            &migrated.agents.agents["miner"].daemon,
+            Some(DaemonConfig::Local(binary)) if binary == "monerod"
+        ));
+
+        migrate_config(&input, Some(&input)).unwrap();
+        assert!(fs::read_to_string(&input)
+            .unwrap()
+            .starts_with("config_version: 2"));
+    }
+
+    #[test]
+    fn agent_groups_are_merged_before_validation() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("groups.yaml");
+        let grouped = format!(
+            "{}{}",
+            MINIMAL,
+            r#"  user:
+    group: heavy
+    count: 2
+    attributes: {location: eu}
+agent_groups:
+  heavy:
+    daemon: monerod
+    wallet: monero-wallet-rpc
+    bandwidth_down: 10 Mbit
+    attributes: {location: us, tier: heavy}
+"#
+        );
+        fs::write(&path, &grouped).unwrap();
+        let config = load_config(&path).unwrap();
+        for id in ["user-001", "user-002"] {
+            let user = &config.agents.agents[id];
+            assert_eq!(user.wallet.as_deref(), Some("monero-wallet-rpc"));
+            assert_eq!(user.bandwidth_down.as_deref(), Some("10 Mbit"));
+            let attributes = user.attributes.as_ref().unwrap();
+            assert_eq!(attributes.location.as_deref(), Some("eu"));
+            assert_eq!(attributes.extra["tier"], "heavy");
+        }
+        assert!(config.agent_groups.contains_key("heavy"));
+
+        // The merged agents are what the validators check
+        fs::write(&path, grouped.replace("10 Mbit", "fast")).unwrap();
+        let err = format!("{:?}", load_config(&path).unwrap_err());
+        assert!(err.contains("user-001"), "{}", err);
+
+        fs::write(&path, grouped.replace("group: heavy", "group: light")).unwrap();
+        let err = format!("{:?}", load_config(&path).unwrap_err());
+        assert!(err.contains("unknown group 'light'"), "{}", err);
+
+        fs::write(&path, grouped.replace("bandwidth_down:", "bandwith_down:")).unwrap();
+        let err = format!("{:?}", load_config(&path).unwrap_err());
+        assert!(err.contains("agent_groups.heavy.bandwith_down"), "{}", err);
+    }
+}
@@ -0,0 +1,123 @@
+//! `generation_trace.json`: the decisions generation made and why.
+//!
+//! Planning records each choice it makes on the user's behalf (the GML node
+//! an agent lands on, where its IP comes from, which agents become seeds,
+//! when each process starts, a topology warning it carried on past) into one
+//! `GenerationTrace`, written next to `shadow_agents.yaml` and logged stage
+//! by stage with `--verbose`.
+
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+/// File name of the trace in the output directory
+pub const GENERATION_TRACE_FILE: &str = "generation_trace.json";
+
+/// Network loading and topology checks
+pub const STAGE_TOPOLOGY: &str = "topology";
+/// GML node of each agent
+pub const STAGE_PLACEMENT: &str = "placement";
+/// Source of each host's IP
+pub const STAGE_IP_ALLOCATION: &str = "ip_allocation";
+/// Seed nodes regular agents bootstrap from
+pub const STAGE_SEEDS: &str = "seeds";
+/// Start time of each process
+pub const STAGE_SCHEDULING: &str = "scheduling";
+
+/// One decision; `subject` is an agent id, or the config field decided on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Decision {
+    pub stage: String,
+    pub subject: String,
+    pub decision: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenerationTrace {
+    pub decisions: Vec<Decision>,
+}
+
+impl GenerationTrace {
+    pub fn record(
+        &mut self,
+        stage: &str,
+        subject: impl Into<String>,
+        decision: impl Into<String>,
+        reason: impl Into<String>,
+    ) {
+        self.decisions.push(Decision {
+            stage: stage.to_string(),
+            subject: subject.into(),
+            decision: decision.into(),
+            reason: reason.into(),
+        });
+    }
+
+    /// Decisions of one stage, in the order they were made
+    pub fn stage<'a>(&'a self, stage: &'a str) -> impl Iterator<Item = &'a Decision> {
+        self.decisions.iter().filter(move |d| d.stage == stage)
+    }
+
+    /// Stages in the order they first decided something, then each subject
+    /// with its decisions indented below it
+    pub fn render(&self) -> String {
+        let mut stages: Vec<&str> = Vec::new();
+        for decision in &self.decisions {
+            if !stages.contains(&decision.stage.as_str()) {
+                stages.push(&decision.stage);
+            }
+        }
+        let mut out = format!("Generation trace: {} decision(s)", self.decisions.len());
+        for stage in stages {
+            let mut subject: Option<&str> = None;
+            let _ = write!(out, "\n  {}:", stage);
+            for decision in self.stage(stage) {
+                if subject != Some(decision.subject.as_str()) {
+                    let _ = write!(out, "\n    {}", decision.subject);
+                    subject = Some(&decision.subject);
+                }
+                let _ = write!(out, "\n      {} ({})", decision.decision, decision.reason);
+            }
+        }
+        out
+    }
+
+    /// Log `render()` at info level
+    pub fn log(&self) {
+        if !self.decisions.is_empty() {
+            log::info!("{}", self.render());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rendering_groups_by_stage_then_subject() {
+        let mut trace = GenerationTrace::default();
+        trace.record(STAGE_SEEDS, "miner-001", "seed node", "miner");
+        trace.record(STAGE_SCHEDULING, "miner-001", "daemon at 0s", "stagger");
+        trace.record(
+            STAGE_SCHEDULING,
+            "miner-001",
+            "wallet at 2s",
+            "after daemon",
+        );
+        trace.record(STAGE_SEEDS, "miner-002", "seed node", "miner");
+
+        assert_eq!(
+            trace.render(),
+            "Generation trace: 4 decision(s)\n  seeds:\n    miner-001\n      seed node (miner)\
+             \n    miner-002\n      seed node (miner)\n  scheduling:\n    miner-001\
+             \n      daemon at 0s (stagger)\n      wallet at 2s (after daemon)"
+        );
+        assert_eq!(trace.stage(STAGE_SEEDS).count(), 2);
+
+        let json = serde_json::to_value(&trace).unwrap();
+        assert_eq!(json["decisions"][1]["stage"], "scheduling");
+        assert_eq!(json["decisions"][1]["reason"], "stagger");
+    }
+}
@@ -1,5 +1,5 @@
 use color_eyre::eyre::{eyre, Result};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 
 /// Represents a node in a GML graph
@@ -32,6 +32,73 @@ impl GmlNode {
     pub fn has_ip(&self) -> bool {
         self.ip.is_some()
     }
+
+    /// `(latitude, longitude)` in degrees from the `Latitude`/`Longitude`
+    /// attributes (any case). `Ok(None)` when either is absent; `Err` when
+    /// present but unparseable or out of range.
+    pub fn coordinates(&self) -> Result<Option<(f64, f64)>, String> {
+        let get = |name: &str| {
+            self.attributes
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.trim_matches('"'))
+        };
+        let (Some(lat), Some(lon)) = (get("latitude"), get("longitude")) else {
+            return Ok(None);
+        };
+        let parse = |v: &str, what: &str, limit: f64| match v.parse::<f64>() {
+            Ok(x) if x.abs() <= limit => Ok(x),
+            _ => Err(format!(
+                "Node {} has invalid {} '{}' (expected -{}..={})",
+                self.id, what, v, limit, limit
+            )),
+        };
+        Ok(Some((
+            parse(lat, "Latitude", 90.0)?,
+            parse(lon, "Longitude", 180.0)?,
+        )))
+    }
+}
+
+/// Great-circle (haversine) distance in km between two `(lat, lon)` points.
+pub fn great_circle_km(a: (f64, f64), b: (f64, f64)) -> f64 {
+    const EARTH_RADIUS_KM: f64 = 6371.0;
+    let (lat1, lon1) = (a.0.to_radians(), a.1.to_radians());
+    let (lat2, lon2) = (b.0.to_radians(), b.1.to_radians());
+    let h = ((lat2 - lat1) / 2.0).sin().powi(2)
+        + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * h.sqrt().asin()
+}
+
+/// Fill in a `latency` attribute for every edge that lacks one and whose
+/// endpoints both carry coordinates: `distance_km * propagation_factor +
+/// processing_delay_ms`. Written in whole microseconds since Shadow's time
+/// units are integral. Returns the number of edges synthesized.
+pub fn synthesize_edge_latencies(
+    graph: &mut GmlGraph,
+    propagation_factor: f64,
+    processing_delay_ms: f64,
+) -> usize {
+    let coords: HashMap<u32, (f64, f64)> = graph
+        .nodes
+        .iter()
+        .filter_map(|n| n.coordinates().ok().flatten().map(|c| (n.id, c)))
+        .collect();
+    let mut synthesized = 0;
+    for edge in &mut graph.edges {
+        if edge.attributes.contains_key("latency") {
+            continue;
+        }
+        let (Some(&a), Some(&b)) = (coords.get(&edge.source), coords.get(&edge.target)) else {
+            continue;
+        };
+        let ms = great_circle_km(a, b) * propagation_factor + processing_delay_ms;
+        let us = (ms * 1000.0).round().max(1.0) as u64;
+        edge.attributes
+            .insert("latency".to_string(), format!("{}us", us));
+        synthesized += 1;
+    }
+    synthesized
 }
 
 /// Parse IP address from GML node attributes
@@ -96,6 +163,10 @@ pub struct GmlGraph {
     pub attributes: HashMap<String, String>,
 }
 
+/// Joins the keys of a nested attribute block to its parent's key:
+/// `graphics [ x 1.0 ]` becomes the attribute `graphics.x`.
+pub const NESTED_KEY_SEPARATOR: char = '.';
+
 /// Token types for GML parsing
 #[derive(Debug, Clone, PartialEq)]
 enum Token {
@@ -112,20 +183,29 @@ struct Lexer {
     input: Vec<char>,
     position: usize,
     current_char: Option<char>,
+    /// Line of `current_char`, from 1
+    line: usize,
+    /// Line on which the last token returned by `next_token` started
+    token_line: usize,
 }
 
 impl Lexer {
     fn new(input: &str) -> Self {
         let chars: Vec<char> = input.chars().collect();
-        let current_char = chars.get(0).copied();
+        let current_char = chars.first().copied();
         Self {
             input: chars,
             position: 0,
             current_char,
+            line: 1,
+            token_line: 1,
         }
     }
 
     fn advance(&mut self) {
+        if self.current_char == Some('\n') {
+            self.line += 1;
+        }
         self.position += 1;
         self.current_char = self.input.get(self.position).copied();
     }
@@ -152,7 +232,11 @@ impl Lexer {
         }
     }
 
+    /// A quoted string. Backslash escapes and the GML spec's character
+    /// entities (`&quot;`, `&amp;`, `&#34;`, `&#x22;`, ...) are decoded;
+    /// strings may span lines.
     fn read_string(&mut self) -> Result<String> {
+        let start_line = self.line;
         let mut result = String::new();
         self.advance(); // Skip opening quote
 
@@ -177,13 +261,37 @@ impl Lexer {
                     }
                     self.advance();
                 }
+            } else if ch == '&' {
+                result.push_str(&self.read_entity());
             } else {
                 result.push(ch);
                 self.advance();
             }
         }
 
-        Err(eyre!("Unterminated string literal"))
+        Err(eyre!(
+            "line {}: unterminated string literal starting here",
+            start_line
+        ))
+    }
+
+    /// A character entity at `&`, decoded; anything that is not a complete
+    /// known entity is kept as written.
+    fn read_entity(&mut self) -> String {
+        let mut raw = String::from("&");
+        self.advance();
+        while let Some(ch) = self.current_char {
+            if ch == ';' {
+                self.advance();
+                return decode_entity(&raw[1..]).map_or_else(|| format!("{};", raw), String::from);
+            }
+            if !(ch.is_ascii_alphanumeric() || ch == '#') || raw.len() > 8 {
+                break;
+            }
+            raw.push(ch);
+            self.advance();
+        }
+        raw
     }
 
     fn read_identifier_or_number(&mut self) -> String {
@@ -204,6 +312,7 @@ impl Lexer {
     fn next_token(&mut self) -> Result<Token> {
         loop {
             self.skip_whitespace();
+            self.token_line = self.line;
 
             match self.current_char {
                 None => return Ok(Token::Eof),
@@ -227,49 +336,94 @@ impl Lexer {
                     let identifier = self.read_identifier_or_number();
                     return Ok(Token::Identifier(identifier));
                 }
-                Some(ch) if ch.is_numeric() || ch == '-' || ch == '+' => {
+                Some(ch) if ch.is_numeric() || ch == '-' || ch == '+' || ch == '.' => {
                     let number = self.read_identifier_or_number();
                     return Ok(Token::Number(number));
                 }
                 Some(ch) => {
-                    return Err(eyre!("Unexpected character: '{}'", ch));
+                    return Err(eyre!("line {}: unexpected character '{}'", self.line, ch));
                 }
             }
         }
     }
 }
 
+/// The character for an entity name between `&` and `;`
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "quot" => Some('"'),
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "apos" => Some('\''),
+        _ => {
+            let code = name.strip_prefix('#')?;
+            let code = match code.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => code.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+/// Quote `value` as a GML string, escaping what `read_string` decodes
+pub fn quote_gml_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => out.push_str("&quot;"),
+            '&' => out.push_str("&amp;"),
+            '\\' => out.push_str("\\\\"),
+            _ => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
+
 /// Parser for GML format
 struct Parser {
     lexer: Lexer,
     current_token: Token,
+    /// Line on which `current_token` starts
+    line: usize,
 }
 
 impl Parser {
     fn new(mut lexer: Lexer) -> Result<Self> {
         let current_token = lexer.next_token()?;
+        let line = lexer.token_line;
         Ok(Self {
             lexer,
             current_token,
+            line,
         })
     }
 
     fn advance(&mut self) -> Result<()> {
         self.current_token = self.lexer.next_token()?;
+        self.line = self.lexer.token_line;
         Ok(())
     }
 
+    /// An error at the current token's line
+    fn error(&self, message: String) -> color_eyre::eyre::Report {
+        eyre!("line {}: {}", self.line, message)
+    }
+
     fn expect_identifier(&mut self, expected: &str) -> Result<()> {
         match &self.current_token {
             Token::Identifier(id) if id == expected => {
                 self.advance()?;
                 Ok(())
             }
-            _ => Err(eyre!(
-                "Expected identifier '{}', found {:?}",
+            _ => Err(self.error(format!(
+                "expected '{}', found {}",
                 expected,
-                self.current_token
-            )),
+                describe(&self.current_token)
+            ))),
         }
     }
 
@@ -279,7 +433,10 @@ impl Parser {
                 self.advance()?;
                 Ok(())
             }
-            _ => Err(eyre!("Expected '[', found {:?}", self.current_token)),
+            _ => Err(self.error(format!(
+                "expected '[', found {}",
+                describe(&self.current_token)
+            ))),
         }
     }
 
@@ -289,7 +446,10 @@ impl Parser {
                 self.advance()?;
                 Ok(())
             }
-            _ => Err(eyre!("Expected ']', found {:?}", self.current_token)),
+            _ => Err(self.error(format!(
+                "expected ']', found {}",
+                describe(&self.current_token)
+            ))),
         }
     }
 
@@ -300,11 +460,79 @@ impl Parser {
                 self.advance()?;
                 Ok(result)
             }
-            _ => Err(eyre!("Expected value, found {:?}", self.current_token)),
+            _ => Err(self.error(format!(
+                "expected a value, found {}",
+                describe(&self.current_token)
+            ))),
+        }
+    }
+
+    /// The value of `key` (already consumed) into `attributes`. A nested
+    /// `[ ... ]` block is flattened into `key.sub` attributes, a `comment`
+    /// is dropped, and a repeated key keeps its last value.
+    fn parse_attribute(
+        &mut self,
+        key: String,
+        attributes: &mut HashMap<String, String>,
+    ) -> Result<()> {
+        if self.current_token == Token::LeftBracket {
+            let open_line = self.line;
+            self.advance()?;
+            loop {
+                match &self.current_token {
+                    Token::RightBracket => break,
+                    Token::Identifier(sub) => {
+                        let sub = format!("{}{}{}", key, NESTED_KEY_SEPARATOR, sub);
+                        self.advance()?;
+                        self.parse_attribute(sub, attributes)?;
+                    }
+                    Token::Eof => {
+                        return Err(self.error(format!(
+                            "'{}' block opened on line {} is never closed",
+                            key, open_line
+                        )))
+                    }
+                    other => {
+                        return Err(self.error(format!(
+                            "expected an attribute name in '{}', found {}",
+                            key,
+                            describe(other)
+                        )))
+                    }
+                }
+            }
+            return self.expect_right_bracket();
+        }
+        let value = self.parse_value()?;
+        if key != "comment" && !key.ends_with(&format!("{}comment", NESTED_KEY_SEPARATOR)) {
+            attributes.insert(key, value);
+        }
+        Ok(())
+    }
+
+    /// The key at the current token in a `what` block, consumed. Reports an
+    /// unclosed block (opened on `open_line`) at end of input.
+    fn attribute_name(&mut self, what: &str, open_line: usize) -> Result<String> {
+        match &self.current_token {
+            Token::Identifier(key) => {
+                let key = key.clone();
+                self.advance()?;
+                Ok(key)
+            }
+            Token::Eof => Err(self.error(format!(
+                "{} opened on line {} is never closed",
+                what, open_line
+            ))),
+            other => Err(self.error(format!(
+                "expected an attribute name in {}, found {}",
+                what,
+                describe(other)
+            ))),
         }
     }
 
     fn parse_node(&mut self) -> Result<GmlNode> {
+        let open_line = self.line;
         self.expect_identifier("node")?;
         self.expect_left_bracket()?;
 
@@ -313,40 +541,28 @@ impl Parser {
         let mut attributes = HashMap::new();
 
         while self.current_token != Token::RightBracket {
-            match &self.current_token {
-                Token::Identifier(key) => {
-                    let key = key.clone();
-                    self.advance()?;
+            let key = self.attribute_name("node", open_line)?;
+            match key.as_str() {
+                "id" => {
+                    let line = self.line;
                     let value = self.parse_value()?;
-
-                    match key.as_str() {
-                        "id" => {
-                            id = Some(
-                                value
-                                    .parse::<u32>()
-                                    .map_err(|_| eyre!("Invalid node id: {}", value))?,
-                            );
-                        }
-                        "label" => {
-                            label = Some(value);
-                        }
-                        _ => {
-                            attributes.insert(key, value);
-                        }
-                    }
+                    id = Some(
+                        value
+                            .parse::<u32>()
+                            .map_err(|_| eyre!("line {}: invalid node id '{}'", line, value))?,
+                    );
                 }
-                _ => {
-                    return Err(eyre!(
-                        "Expected attribute name in node, found {:?}",
-                        self.current_token
-                    ))
+                "label" => {
+                    label = Some(self.parse_value()?);
                 }
+                _ => self.parse_attribute(key, &mut attributes)?,
             }
         }
 
         self.expect_right_bracket()?;
 
-        let id = id.ok_or_else(|| eyre!("Node missing required 'id' attribute"))?;
+        let id =
+            id.ok_or_else(|| eyre!("line {}: node missing required 'id' attribute", open_line))?;
 
         // Parse IP and region from attributes
         let ip = parse_ip(&attributes);
@@ -362,6 +578,7 @@ impl Parser {
     }
 
     fn parse_edge(&mut self) -> Result<GmlEdge> {
+        let open_line = self.line;
         self.expect_identifier("edge")?;
         self.expect_left_bracket()?;
 
@@ -370,56 +587,53 @@ impl Parser {
         let mut attributes = HashMap::new();
 
         while self.current_token != Token::RightBracket {
-            match &self.current_token {
-                Token::Identifier(key) => {
-                    let key = key.clone();
-                    self.advance()?;
+            let key = self.attribute_name("edge", open_line)?;
+            match key.as_str() {
+                "source" | "target" => {
+                    let line = self.line;
                     let value = self.parse_value()?;
-
-                    match key.as_str() {
-                        "source" => {
-                            source = Some(
-                                value
-                                    .parse::<u32>()
-                                    .map_err(|_| eyre!("Invalid edge source: {}", value))?,
-                            );
-                        }
-                        "target" => {
-                            target = Some(
-                                value
-                                    .parse::<u32>()
-                                    .map_err(|_| eyre!("Invalid edge target: {}", value))?,
-                            );
-                        }
-                        _ => {
-                            // Special handling for packet_loss: convert percentage strings to floats
-                            let processed_value = if key == "packet_loss" && value.ends_with('%') {
-                                // Remove '%' and parse as float, then divide by 100
-                                if let Ok(percentage) = value.trim_end_matches('%').parse::<f64>() {
-                                    format!("{}", percentage / 100.0)
-                                } else {
-                                    value // Keep original if parsing fails
-                                }
-                            } else {
-                                value
-                            };
-                            attributes.insert(key, processed_value);
-                        }
+                    let endpoint = value
+                        .parse::<u32>()
+                        .map_err(|_| eyre!("line {}: invalid edge {} '{}'", line, key, value))?;
+                    if key == "source" {
+                        source = Some(endpoint);
+                    } else {
+                        target = Some(endpoint);
                     }
                 }
-                _ => {
-                    return Err(eyre!(
-                        "Expected attribute name in edge, found {:?}",
-                        self.current_token
-                    ))
+                "packet_loss" if self.current_token != Token::LeftBracket => {
+                    // Special handling for packet_loss: convert percentage strings to floats
+                    let value = self.parse_value()?;
+                    let processed_value = if value.ends_with('%') {
+                        // Remove '%' and parse as float, then divide by 100
+                        if let Ok(percentage) = value.trim_end_matches('%').parse::<f64>() {
+                            format!("{}", percentage / 100.0)
+                        } else {
+                            value // Keep original if parsing fails
+                        }
+                    } else {
+                        value
+                    };
+                    attributes.insert(key, processed_value);
                 }
+                _ => self.parse_attribute(key, &mut attributes)?,
             }
         }
 
         self.expect_right_bracket()?;
 
-        let source = source.ok_or_else(|| eyre!("Edge missing required 'source' attribute"))?;
-        let target = target.ok_or_else(|| eyre!("Edge missing required 'target' attribute"))?;
+        let source = source.ok_or_else(|| {
+            eyre!(
+                "line {}: edge missing required 'source' attribute",
+                open_line
+            )
+        })?;
+        let target = target.ok_or_else(|| {
+            eyre!(
+                "line {}: edge missing required 'target' attribute",
+                open_line
+            )
+        })?;
 
         Ok(GmlEdge {
             source,
@@ -428,7 +642,34 @@ impl Parser {
         })
     }
 
+    /// The file: optional top-level attributes (`Creator`, `Version`, ...)
+    /// around a single `graph [ ... ]`.
     fn parse_graph(&mut self) -> Result<GmlGraph> {
+        let mut graph = None;
+        loop {
+            match &self.current_token {
+                Token::Eof => break,
+                Token::Identifier(keyword) if keyword == "graph" => {
+                    if graph.is_some() {
+                        return Err(self.error("a second 'graph' block".to_string()));
+                    }
+                    graph = Some(self.parse_graph_block()?);
+                }
+                Token::Identifier(keyword) => {
+                    let key = keyword.clone();
+                    self.advance()?;
+                    self.parse_attribute(key, &mut HashMap::new())?;
+                }
+                other => {
+                    return Err(self.error(format!("expected 'graph', found {}", describe(other))))
+                }
+            }
+        }
+        graph.ok_or_else(|| eyre!("no 'graph [ ... ]' block found"))
+    }
+
+    fn parse_graph_block(&mut self) -> Result<GmlGraph> {
+        let open_line = self.line;
         self.expect_identifier("graph")?;
         self.expect_left_bracket()?;
 
@@ -438,28 +679,16 @@ impl Parser {
 
         while self.current_token != Token::RightBracket {
             match &self.current_token {
-                Token::Identifier(keyword) => {
-                    match keyword.as_str() {
-                        "node" => {
-                            nodes.push(self.parse_node()?);
-                        }
-                        "edge" => {
-                            edges.push(self.parse_edge()?);
-                        }
-                        _ => {
-                            // Parse as graph attribute
-                            let key = keyword.clone();
-                            self.advance()?;
-                            let value = self.parse_value()?;
-                            attributes.insert(key, value);
-                        }
-                    }
+                Token::Identifier(keyword) if keyword == "node" => {
+                    nodes.push(self.parse_node()?);
+                }
+                Token::Identifier(keyword) if keyword == "edge" => {
+                    edges.push(self.parse_edge()?);
                 }
                 _ => {
-                    return Err(eyre!(
-                        "Expected keyword in graph, found {:?}",
-                        self.current_token
-                    ))
+                    // Parse as graph attribute
+                    let key = self.attribute_name("graph", open_line)?;
+                    self.parse_attribute(key, &mut attributes)?;
                 }
             }
         }
@@ -474,17 +703,84 @@ impl Parser {
     }
 }
 
+/// A token as quoted in error messages
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Identifier(s) => format!("'{}'", s),
+        Token::Number(n) => format!("number {}", n),
+        Token::String(s) => format!("string \"{}\"", s),
+        Token::LeftBracket => "'['".to_string(),
+        Token::RightBracket => "']'".to_string(),
+        Token::Eof => "end of file".to_string(),
+    }
+}
+
 /// Parse a GML file and return a GmlGraph object
 pub fn parse_gml_file(path: &str) -> Result<GmlGraph> {
     let content =
         fs::read_to_string(path).map_err(|e| eyre!("Failed to read GML file '{}': {}", path, e))?;
 
-    let lexer = Lexer::new(&content);
+    parse_gml(&content).map_err(|e| eyre!("GML file '{}': {}", path, e))
+}
+
+/// Parse GML text
+pub fn parse_gml(content: &str) -> Result<GmlGraph> {
+    let lexer = Lexer::new(content);
     let mut parser = Parser::new(lexer)?;
 
     parser.parse_graph()
 }
 
+/// Serialize `graph` as GML that `parse_gml_file` reads back. Attributes are
+/// sorted by key so the output is stable; numeric values are written bare,
+/// everything else quoted. Flattened `a.b` keys are nested back into
+/// `a [ b ... ]` blocks.
+pub fn write_gml(graph: &GmlGraph) -> String {
+    fn push_attributes(out: &mut String, indent: &str, attributes: &HashMap<String, String>) {
+        let mut sorted: Vec<_> = attributes.iter().collect();
+        sorted.sort();
+        let mut blocks: BTreeMap<&str, HashMap<String, String>> = BTreeMap::new();
+        for (key, value) in sorted {
+            if let Some((block, sub)) = key.split_once(NESTED_KEY_SEPARATOR) {
+                blocks
+                    .entry(block)
+                    .or_default()
+                    .insert(sub.to_string(), value.clone());
+            } else if value.parse::<f64>().is_ok() {
+                out.push_str(&format!("{}{} {}\n", indent, key, value));
+            } else {
+                out.push_str(&format!("{}{} {}\n", indent, key, quote_gml_string(value)));
+            }
+        }
+        for (block, attributes) in blocks {
+            out.push_str(&format!("{}{} [\n", indent, block));
+            push_attributes(out, &format!("{}  ", indent), &attributes);
+            out.push_str(&format!("{}]\n", indent));
+        }
+    }
+
+    let mut out = String::from("graph [\n");
+    push_attributes(&mut out, "  ", &graph.attributes);
+    for node in &graph.nodes {
+        out.push_str(&format!("  node [\n    id {}\n", node.id));
+        if let Some(label) = &node.label {
+            out.push_str(&format!("    label {}\n", quote_gml_string(label)));
+        }
+        push_attributes(&mut out, "    ", &node.attributes);
+        out.push_str("  ]\n");
+    }
+    for edge in &graph.edges {
+        out.push_str(&format!(
+            "  edge [\n    source {}\n    target {}\n",
+            edge.source, edge.target
+        ));
+        push_attributes(&mut out, "    ", &edge.attributes);
+        out.push_str("  ]\n");
+    }
+    out.push_str("]\n");
+    out
+}
+
 /// Group nodes by autonomous system if AS attributes exist
 pub fn get_autonomous_systems(graph: &GmlGraph) -> Vec<Vec<u32>> {
     let mut as_groups: HashMap<String, Vec<u32>> = HashMap::new();
@@ -525,6 +821,10 @@ pub fn validate_topology(graph: &GmlGraph) -> Result<(), String> {
         }
     }
 
+    for node in &graph.nodes {
+        node.coordinates()?;
+    }
+
     // Check that all edges reference valid nodes
     for edge in &graph.edges {
         if !node_ids.contains(&edge.source) {
@@ -986,4 +1286,106 @@ mod tests {
         assert_eq!(node.get_ip(), None);
         assert_eq!(node.get_region(), None);
     }
+
+    #[test]
+    fn test_synthesize_latency_from_coordinates() {
+        // New York <-> London is ~5570 km great-circle.
+        let gml_content = r#"
+            graph [
+                node [ id 0 label "New York" Latitude 40.7128 Longitude -74.0060 ]
+                node [ id 1 label "London" Latitude 51.5074 Longitude -0.1278 ]
+                node [ id 2 ]
+                edge [ source 0 target 1 ]
+                edge [ source 1 target 2 ]
+                edge [ source 0 target 0 latency "5ms" ]
+            ]
+        "#;
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(temp_file, "{}", gml_content).unwrap();
+        let mut graph = parse_gml_file(temp_file.path().to_str().unwrap()).unwrap();
+        assert!(validate_topology(&graph).is_ok());
+
+        let km = great_circle_km((40.7128, -74.0060), (51.5074, -0.1278));
+        assert!((5560.0..5580.0).contains(&km), "NY-London {} km", km);
+
+        assert_eq!(synthesize_edge_latencies(&mut graph, 0.0075, 1.0), 1);
+        let latency = graph.edges[0].attributes.get("latency").unwrap();
+        let us: u64 = latency.trim_end_matches("us").parse().unwrap();
+        // 5570 km * 0.0075 ms/km + 1 ms ~= 42.8 ms
+        assert!((42_700..42_850).contains(&us), "latency {}", latency);
+        // No coordinates on node 2, explicit latency kept on the self-loop.
+        assert!(!graph.edges[1].attributes.contains_key("latency"));
+        assert_eq!(
+            graph.edges[2].attributes.get("latency"),
+            Some(&"5ms".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_out_of_range_coordinates() {
+        let mut attributes = HashMap::new();
+        attributes.insert("Latitude".to_string(), "91.0".to_string());
+        attributes.insert("Longitude".to_string(), "0".to_string());
+        let graph = GmlGraph {
+            nodes: vec![GmlNode {
+                id: 0,
+                label: None,
+                ip: None,
+                region: None,
+                attributes,
+            }],
+            edges: vec![],
+            attributes: HashMap::new(),
+        };
+        let err = validate_topology(&graph).unwrap_err();
+        assert!(err.contains("Latitude"), "{}", err);
+    }
+
+    #[test]
+    fn test_malformed_gml_reports_the_line() {
+        for (gml, expected) in [
+            (
+                "graph [\n  node [ id 0 ]\n  node [ id x ]\n]\n",
+                "line 3: invalid node id 'x'",
+            ),
+            (
+                "graph [\n  node [\n    id 0\n    graphics [ x 1\n  ]\n",
+                "line 6: node opened on line 2 is never closed",
+            ),
+            (
+                "graph [\n  node [ id 0 label \"open\n]\n",
+                "line 2: unterminated string literal",
+            ),
+            (
+                "graph [\n  edge [ source 0 ]\n]\n",
+                "line 2: edge missing required 'target'",
+            ),
+            (
+                "graph [\n  node [ id 0 ] ;\n]\n",
+                "line 2: unexpected character ';'",
+            ),
+            (
+                "graph [\n  node [ id [ 1 ] ]\n]\n",
+                "line 2: expected a value, found '['",
+            ),
+            ("Creator \"x\"\n", "no 'graph [ ... ]' block found"),
+        ] {
+            let err = parse_gml(gml).unwrap_err().to_string();
+            assert!(err.contains(expected), "{:?}: {}", gml, err);
+        }
+    }
+
+    #[test]
+    fn test_strings_decode_entities_and_write_gml_escapes_them() {
+        let graph = parse_gml(
+            "graph [ node [ id 0 label \"a &quot;b&quot; &amp; &#99;&#x64; \\\"e\\\" &bogus; &\" ] ]",
+        )
+        .unwrap();
+        let label = graph.nodes[0].label.clone().unwrap();
+        assert_eq!(label, "a \"b\" & cd \"e\" &bogus; &");
+        let written = write_gml(&graph);
+        assert!(written.contains("&quot;b&quot; &amp; cd"), "{}", written);
+        assert_eq!(parse_gml(&written).unwrap().nodes[0].label, Some(label));
+    }
 }
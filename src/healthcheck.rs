@@ -0,0 +1,375 @@
+//! RPC readiness checks run by `monerosim-healthcheck` inside the simulation.
+//!
+//! Agent wrapper scripts call the binary for the agent's daemon (`get_info`)
+//! and wallet (`get_version`) RPC before starting the agent. Each check
+//! POSTs a JSON-RPC request over plain HTTP, retrying until the reply is a
+//! JSON-RPC result carrying the expected field, and writes a
+//! `ReadinessRecord` to `<shared_dir>/readiness/` so `tx-analyzer summary`
+//! can report how long daemons and wallets took to come up. The exit code
+//! tells a timeout, a refused connection and a bad reply apart.
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Name of the healthcheck binary on the simulation's `PATH`
+pub const HEALTHCHECK_BINARY: &str = "monerosim-healthcheck";
+/// Directory in the shared dir holding one `ReadinessRecord` per check
+pub const READINESS_DIR: &str = "readiness";
+
+/// Exit code when the check never connected in time
+pub const EXIT_TIMEOUT: i32 = 3;
+/// Exit code when the last attempt's connection was refused
+pub const EXIT_REFUSED: i32 = 4;
+/// Exit code when the RPC answered, but not with the expected result
+pub const EXIT_BAD_RESPONSE: i32 = 5;
+
+/// What to check and how persistently
+#[derive(Debug, Clone)]
+pub struct HealthcheckOptions {
+    /// `http://host:port/path`
+    pub url: String,
+    /// JSON-RPC method, `get_info` for a daemon, `get_version` for a wallet
+    pub method: String,
+    /// Per-attempt connect and read timeout
+    pub timeout: Duration,
+    /// Attempts after the first
+    pub retries: u32,
+    /// Pause between attempts
+    pub interval: Duration,
+    /// Dotted path into the reply that must be present and non-null,
+    /// e.g. `result.height`
+    pub expect_json_field: Option<String>,
+}
+
+/// Why one attempt failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum CheckError {
+    Timeout(String),
+    Refused(String),
+    BadResponse(String),
+}
+
+impl CheckError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CheckError::Timeout(_) => EXIT_TIMEOUT,
+            CheckError::Refused(_) => EXIT_REFUSED,
+            CheckError::BadResponse(_) => EXIT_BAD_RESPONSE,
+        }
+    }
+
+    /// `status` of a `ReadinessRecord` that ended in this error
+    pub fn status(&self) -> &'static str {
+        match self {
+            CheckError::Timeout(_) => "timeout",
+            CheckError::Refused(_) => "refused",
+            CheckError::BadResponse(_) => "bad_response",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            CheckError::Timeout(m) | CheckError::Refused(m) | CheckError::BadResponse(m) => m,
+        }
+    }
+}
+
+/// Outcome of one check, as written to the readiness directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadinessRecord {
+    pub agent: String,
+    /// What was checked, `daemon` or `wallet`
+    pub target: String,
+    pub url: String,
+    pub method: String,
+    /// `ready`, or the last attempt's error: `timeout`, `refused`,
+    /// `bad_response`
+    pub status: String,
+    pub attempts: u32,
+    /// Clock time when the check started (simulated epoch seconds in Shadow)
+    pub started_at: f64,
+    /// Seconds from the start until ready, or until giving up
+    pub elapsed_sec: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ReadinessRecord {
+    pub fn is_ready(&self) -> bool {
+        self.status == "ready"
+    }
+
+    /// Exit code of the binary for this outcome, 0 when ready
+    pub fn exit_code(&self) -> i32 {
+        match self.status.as_str() {
+            "ready" => 0,
+            "refused" => EXIT_REFUSED,
+            "bad_response" => EXIT_BAD_RESPONSE,
+            _ => EXIT_TIMEOUT,
+        }
+    }
+}
+
+/// Split `http://host:port/path` into the address and the path
+fn parse_url(url: &str) -> Result<(String, String), CheckError> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| CheckError::BadResponse(format!("'{}' is not an http:// URL", url)))?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    if !authority.contains(':') {
+        return Err(CheckError::BadResponse(format!("'{}' has no port", url)));
+    }
+    Ok((authority.to_string(), path.to_string()))
+}
+
+fn io_error(e: std::io::Error) -> CheckError {
+    match e.kind() {
+        ErrorKind::TimedOut | ErrorKind::WouldBlock => CheckError::Timeout(e.to_string()),
+        _ => CheckError::Refused(e.to_string()),
+    }
+}
+
+/// Follow a dotted path into `value`
+fn field<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(value, |v, key| v.get(key))
+        .filter(|v| !v.is_null())
+}
+
+/// One JSON-RPC request; the parsed reply on success
+pub fn check_once(options: &HealthcheckOptions) -> Result<Value, CheckError> {
+    let (authority, path) = parse_url(&options.url)?;
+    let addr = authority
+        .to_socket_addrs()
+        .map_err(io_error)?
+        .next()
+        .ok_or_else(|| CheckError::Refused(format!("'{}' resolves to nothing", authority)))?;
+    let mut stream = TcpStream::connect_timeout(&addr, options.timeout).map_err(io_error)?;
+    stream
+        .set_read_timeout(Some(options.timeout))
+        .and_then(|_| stream.set_write_timeout(Some(options.timeout)))
+        .map_err(io_error)?;
+
+    let body = serde_json::json!({"jsonrpc": "2.0", "id": "0", "method": options.method});
+    let body = body.to_string();
+    // One write, so the server never sees a partial request
+    let request = format!(
+        "POST {} HTTP/1.0\r\nHost: {}\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        path,
+        authority,
+        body.len(),
+        body
+    );
+    stream.write_all(request.as_bytes()).map_err(io_error)?;
+    let mut reply = Vec::new();
+    stream.read_to_end(&mut reply).map_err(io_error)?;
+
+    let reply = String::from_utf8_lossy(&reply);
+    let (head, body) = reply
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| CheckError::BadResponse("reply is not HTTP".to_string()))?;
+    let status_line = head.lines().next().unwrap_or_default();
+    if status_line.split_whitespace().nth(1) != Some("200") {
+        return Err(CheckError::BadResponse(format!(
+            "HTTP status '{}'",
+            status_line
+        )));
+    }
+    let json: Value = serde_json::from_str(body)
+        .map_err(|e| CheckError::BadResponse(format!("reply is not JSON: {}", e)))?;
+    if let Some(error) = json.get("error") {
+        return Err(CheckError::BadResponse(format!("RPC error: {}", error)));
+    }
+    if let Some(path) = &options.expect_json_field {
+        if field(&json, path).is_none() {
+            return Err(CheckError::BadResponse(format!("reply has no '{}'", path)));
+        }
+    }
+    Ok(json)
+}
+
+/// Retry `check_once` until it succeeds or the retries run out
+pub fn wait_until_ready(
+    options: &HealthcheckOptions,
+    agent: &str,
+    target: &str,
+) -> ReadinessRecord {
+    let started_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |d| d.as_secs_f64());
+    let start = Instant::now();
+    let mut attempts = 0;
+    let result = loop {
+        attempts += 1;
+        match check_once(options) {
+            Ok(_) => break Ok(()),
+            Err(e) if attempts > options.retries => break Err(e),
+            Err(_) => std::thread::sleep(options.interval),
+        }
+    };
+    ReadinessRecord {
+        agent: agent.to_string(),
+        target: target.to_string(),
+        url: options.url.clone(),
+        method: options.method.clone(),
+        status: match &result {
+            Ok(()) => "ready".to_string(),
+            Err(e) => e.status().to_string(),
+        },
+        attempts,
+        started_at,
+        elapsed_sec: start.elapsed().as_secs_f64(),
+        error: result.err().map(|e| e.message().to_string()),
+    }
+}
+
+/// Every readiness record in `shared_dir`, sorted by agent then target.
+/// Files that fail to parse are skipped with a warning.
+pub fn load_readiness_records(shared_dir: &Path) -> Vec<ReadinessRecord> {
+    let Ok(entries) = std::fs::read_dir(shared_dir.join(READINESS_DIR)) else {
+        return Vec::new();
+    };
+    let mut records: Vec<ReadinessRecord> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            let text = std::fs::read_to_string(&path).ok()?;
+            match serde_json::from_str(&text) {
+                Ok(record) => Some(record),
+                Err(e) => {
+                    log::warn!("Skipping readiness record {}: {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect();
+    records.sort_by(|a, b| (&a.agent, &a.target).cmp(&(&b.agent, &b.target)));
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    /// Serve `reply` to one connection; the URL to reach it
+    fn serve(reply: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/json_rpc", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            // Read the whole request (its JSON body ends in '}') before
+            // replying, or closing would reset the connection
+            let mut request = Vec::new();
+            let mut chunk = [0u8; 1024];
+            while !request.ends_with(b"}") {
+                match stream.read(&mut chunk) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&chunk[..n]),
+                }
+            }
+            stream.write_all(reply.as_bytes()).unwrap();
+        });
+        url
+    }
+
+    fn options(url: String, expect: Option<&str>) -> HealthcheckOptions {
+        HealthcheckOptions {
+            url,
+            method: "get_info".to_string(),
+            timeout: Duration::from_secs(2),
+            retries: 0,
+            interval: Duration::from_millis(10),
+            expect_json_field: expect.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn a_result_with_the_expected_field_is_ready() {
+        let url = serve(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n\
+             {\"id\":\"0\",\"jsonrpc\":\"2.0\",\"result\":{\"height\":1,\"status\":\"OK\"}}",
+        );
+        let record = wait_until_ready(&options(url, Some("result.height")), "user-001", "daemon");
+        assert!(record.is_ready(), "{:?}", record);
+        assert_eq!(record.exit_code(), 0);
+        assert_eq!(record.attempts, 1);
+        assert_eq!(record.error, None);
+    }
+
+    #[test]
+    fn failures_map_to_distinct_exit_codes() {
+        let url = serve("HTTP/1.1 200 OK\r\n\r\n{\"result\":{\"status\":\"BUSY\"}}");
+        let err = check_once(&options(url, Some("result.height"))).unwrap_err();
+        assert_eq!(err.exit_code(), EXIT_BAD_RESPONSE);
+        assert_eq!(err.message(), "reply has no 'result.height'");
+
+        let url = serve("HTTP/1.1 200 OK\r\n\r\n{\"error\":{\"code\":-32601}}");
+        assert_eq!(
+            check_once(&options(url, None)).unwrap_err().status(),
+            "bad_response"
+        );
+
+        let url = serve("HTTP/1.1 500 Internal Server Error\r\n\r\n");
+        assert_eq!(
+            check_once(&options(url, None)).unwrap_err().message(),
+            "HTTP status 'HTTP/1.1 500 Internal Server Error'"
+        );
+
+        // Nothing listens on a port just released
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut refused = options(format!("http://127.0.0.1:{}/json_rpc", port), None);
+        refused.retries = 2;
+        let record = wait_until_ready(&refused, "user-001", "wallet");
+        assert_eq!(record.status, "refused");
+        assert_eq!(record.attempts, 3);
+        assert_eq!(record.exit_code(), EXIT_REFUSED);
+        assert_eq!(CheckError::Timeout(String::new()).exit_code(), EXIT_TIMEOUT);
+    }
+
+    #[test]
+    fn records_load_from_the_readiness_directory() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let dir = tmp.path().join(READINESS_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        for (agent, target) in [("user-002", "daemon"), ("user-001", "wallet")] {
+            let record = ReadinessRecord {
+                agent: agent.to_string(),
+                target: target.to_string(),
+                url: "http://10.0.0.1:18081/json_rpc".to_string(),
+                method: "get_info".to_string(),
+                status: "ready".to_string(),
+                attempts: 2,
+                started_at: 946684900.0,
+                elapsed_sec: 1.5,
+                error: None,
+            };
+            std::fs::write(
+                dir.join(format!("{}_{}.json", agent, target)),
+                serde_json::to_string(&record).unwrap(),
+            )
+            .unwrap();
+        }
+        std::fs::write(dir.join("broken.json"), "{").unwrap();
+
+        let records = load_readiness_records(tmp.path());
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].agent, "user-001");
+        assert!(load_readiness_records(&tmp.path().join("missing")).is_empty());
+    }
+}
@@ -0,0 +1,485 @@
+//! `monerosim inspect`: a summary of an already generated `shadow_agents.yaml`.
+//!
+//! The Shadow config is read back into `ShadowConfig`. When the
+//! `agent_registry.json` written next to it is available, each host's
+//! daemon and wallet binaries and role come from there; otherwise
+//! processes are told apart by the name of the program they run.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{Result, WrapErr};
+use serde::Serialize;
+
+use crate::registry::AGENT_REGISTRY_FILE;
+use crate::shadow::{AgentInfo, AgentRegistry, ProcessArgs, ShadowConfig, ShadowProcess};
+use crate::utils::options::flag_name;
+
+/// Buckets of the start-time histogram, spread evenly over the simulation
+pub const START_TIME_BUCKETS: usize = 10;
+/// Host names listed per line of the text summary before eliding the rest
+const LISTED_HOSTS: usize = 5;
+
+/// What a process runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessKind {
+    Daemon,
+    Wallet,
+    Script,
+}
+
+/// One host of the config
+#[derive(Debug, Clone, Serialize)]
+pub struct HostSummary {
+    pub network_node_id: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_addr: Option<String>,
+    /// `daemon_wallet`, `daemon`, `wallet`, `script` or `empty`, from the
+    /// processes the host runs
+    pub kind: &'static str,
+    /// Processes of each kind
+    pub processes: BTreeMap<ProcessKind, usize>,
+    /// Earliest process start, in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_start: Option<u64>,
+    /// Registry `role` of the agent (`spy`, `infrastructure`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    /// Hosts (or, if no host has the address, `ip:port`) the daemon is told
+    /// to dial with `--seed-node`, `--add-priority-node` or
+    /// `--add-exclusive-node`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub peers: Vec<String>,
+}
+
+/// Processes starting in `[from, to)` seconds
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StartBucket {
+    pub from: u64,
+    pub to: u64,
+    pub processes: usize,
+}
+
+/// How the config and `agent_registry.json` line up
+#[derive(Debug, Clone, Serialize)]
+pub struct RegistryCheck {
+    pub path: PathBuf,
+    pub agents: usize,
+    /// Registry agents without a host in the config
+    pub missing_hosts: Vec<String>,
+    /// Hosts without an agent in the registry
+    pub unregistered_hosts: Vec<String>,
+}
+
+/// Everything `monerosim inspect` reports, also its `--json` output
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigSummary {
+    pub stop_time: u64,
+    pub graph_type: String,
+    pub hosts: usize,
+    pub processes: usize,
+    pub processes_by_kind: BTreeMap<ProcessKind, usize>,
+    /// Number of processes -> hosts running that many
+    pub processes_per_host: BTreeMap<usize, usize>,
+    /// Host kind (see `HostSummary::kind`) -> hosts of that kind
+    pub hosts_by_kind: BTreeMap<&'static str, Vec<String>>,
+    pub start_times: Vec<StartBucket>,
+    /// Dialed host -> hosts whose daemon dials it
+    pub seed_nodes: BTreeMap<String, Vec<String>>,
+    /// Hosts without any process
+    pub empty_hosts: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry: Option<RegistryCheck>,
+    pub host_details: BTreeMap<String, HostSummary>,
+}
+
+/// Parse a generated Shadow config
+pub fn load_shadow_config(path: &Path) -> Result<ShadowConfig> {
+    let content =
+        fs::read_to_string(path).wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+    serde_yaml::from_str(&content)
+        .wrap_err_with(|| format!("Failed to parse {} as a Shadow config", path.display()))
+}
+
+/// Parse an `agent_registry.json`
+pub fn load_agent_registry(path: &Path) -> Result<AgentRegistry> {
+    let content =
+        fs::read_to_string(path).wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content)
+        .wrap_err_with(|| format!("Failed to parse {} as an agent registry", path.display()))
+}
+
+/// `registry`, else the `agent_registry.json` next to `shadow_yaml` if
+/// there is one
+pub fn find_agent_registry(shadow_yaml: &Path, registry: Option<&Path>) -> Option<PathBuf> {
+    if let Some(path) = registry {
+        return Some(path.to_path_buf());
+    }
+    let beside = shadow_yaml
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(AGENT_REGISTRY_FILE);
+    beside.is_file().then_some(beside)
+}
+
+/// The arguments a process passes to the program it runs, skipping a
+/// `bash -c` launcher's own (see `ShadowProcess::executable`)
+fn program_args(process: &ShadowProcess) -> Vec<&str> {
+    match &process.args {
+        ProcessArgs::List(args) if process.executable() != process.path => {
+            args[3..].iter().map(String::as_str).collect()
+        }
+        ProcessArgs::List(args) => args.iter().map(String::as_str).collect(),
+        ProcessArgs::Str(args) => args.split_whitespace().collect(),
+    }
+}
+
+fn process_kind(process: &ShadowProcess, agent: Option<&AgentInfo>) -> ProcessKind {
+    let executable = process.executable();
+    if let Some(agent) = agent {
+        if agent.daemon_binary.as_deref() == Some(executable) {
+            return ProcessKind::Daemon;
+        }
+        if agent.wallet_binary.as_deref() == Some(executable) {
+            return ProcessKind::Wallet;
+        }
+    }
+    let name = Path::new(executable)
+        .file_name()
+        .map_or(executable.into(), |name| name.to_string_lossy());
+    if name.contains("wallet-rpc") {
+        ProcessKind::Wallet
+    } else if name.contains("monerod") {
+        ProcessKind::Daemon
+    } else {
+        ProcessKind::Script
+    }
+}
+
+fn host_kind(processes: &BTreeMap<ProcessKind, usize>) -> &'static str {
+    let has = |kind| processes.contains_key(&kind);
+    match (has(ProcessKind::Daemon), has(ProcessKind::Wallet)) {
+        (true, true) => "daemon_wallet",
+        (true, false) => "daemon",
+        (false, true) => "wallet",
+        (false, false) if has(ProcessKind::Script) => "script",
+        (false, false) => "empty",
+    }
+}
+
+/// Summarize `config`, cross-referenced with an agent registry and the
+/// path it was read from when given
+pub fn summarize(
+    config: &ShadowConfig,
+    registry: Option<(&AgentRegistry, &Path)>,
+) -> Result<ConfigSummary> {
+    let agents: BTreeMap<&str, &AgentInfo> = registry
+        .map(|(registry, _)| {
+            registry
+                .agents
+                .iter()
+                .map(|agent| (agent.id.as_str(), agent))
+                .collect()
+        })
+        .unwrap_or_default();
+    let host_by_ip: BTreeMap<&str, &str> = config
+        .hosts
+        .iter()
+        .filter_map(|(name, host)| Some((host.ip_addr.as_deref()?, name.as_str())))
+        .collect();
+
+    let mut host_details = BTreeMap::new();
+    let mut starts = Vec::new();
+    for (name, host) in &config.hosts {
+        let agent = agents.get(name.as_str()).copied();
+        let mut processes = BTreeMap::new();
+        let mut peers = BTreeSet::new();
+        let mut first_start: Option<u64> = None;
+        for process in &host.processes {
+            let kind = process_kind(process, agent);
+            *processes.entry(kind).or_default() += 1;
+            let start = process.start_time.as_secs();
+            starts.push(start);
+            first_start = Some(first_start.map_or(start, |first| first.min(start)));
+            if kind != ProcessKind::Daemon {
+                continue;
+            }
+            for arg in program_args(process) {
+                if !matches!(
+                    flag_name(arg),
+                    Some("seed-node" | "add-priority-node" | "add-exclusive-node")
+                ) {
+                    continue;
+                }
+                if let Some((_, addr)) = arg.split_once('=') {
+                    let ip = addr.rsplit_once(':').map_or(addr, |(ip, _)| ip);
+                    peers.insert(host_by_ip.get(ip).map_or(addr, |host| host).to_string());
+                }
+            }
+        }
+        host_details.insert(
+            name.clone(),
+            HostSummary {
+                network_node_id: host.network_node_id,
+                ip_addr: host.ip_addr.clone(),
+                kind: host_kind(&processes),
+                processes,
+                first_start,
+                role: agent.and_then(|agent| agent.role.clone()),
+                peers: peers.into_iter().collect(),
+            },
+        );
+    }
+
+    let mut processes_by_kind = BTreeMap::new();
+    let mut processes_per_host = BTreeMap::new();
+    let mut hosts_by_kind: BTreeMap<&'static str, Vec<String>> = BTreeMap::new();
+    let mut seed_nodes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (name, host) in &host_details {
+        for (kind, count) in &host.processes {
+            *processes_by_kind.entry(*kind).or_default() += count;
+        }
+        *processes_per_host
+            .entry(host.processes.values().sum())
+            .or_default() += 1;
+        hosts_by_kind
+            .entry(host.kind)
+            .or_default()
+            .push(name.clone());
+        for peer in &host.peers {
+            seed_nodes
+                .entry(peer.clone())
+                .or_default()
+                .push(name.clone());
+        }
+    }
+
+    let registry = registry.map(|(registry, path)| RegistryCheck {
+        path: path.to_path_buf(),
+        agents: registry.agents.len(),
+        missing_hosts: registry
+            .agents
+            .iter()
+            .filter(|agent| !config.hosts.contains_key(&agent.id))
+            .map(|agent| agent.id.clone())
+            .collect(),
+        unregistered_hosts: config
+            .hosts
+            .keys()
+            .filter(|name| !agents.contains_key(name.as_str()))
+            .cloned()
+            .collect(),
+    });
+
+    Ok(ConfigSummary {
+        stop_time: config.general.stop_time.as_secs(),
+        graph_type: config.network.graph.graph_type.clone(),
+        hosts: config.hosts.len(),
+        processes: starts.len(),
+        processes_by_kind,
+        processes_per_host,
+        empty_hosts: hosts_by_kind.get("empty").cloned().unwrap_or_default(),
+        hosts_by_kind,
+        start_times: start_histogram(&starts, config.general.stop_time.as_secs()),
+        seed_nodes,
+        registry,
+        host_details,
+    })
+}
+
+/// `START_TIME_BUCKETS` equal buckets covering the simulation and every
+/// start, even one past `stop_time`
+fn start_histogram(starts: &[u64], stop_time: u64) -> Vec<StartBucket> {
+    let span = starts
+        .iter()
+        .map(|s| s + 1)
+        .max()
+        .unwrap_or(0)
+        .max(stop_time);
+    let width = span.div_ceil(START_TIME_BUCKETS as u64).max(1);
+    let mut buckets: Vec<StartBucket> = (0..START_TIME_BUCKETS as u64)
+        .map(|i| StartBucket {
+            from: i * width,
+            to: (i + 1) * width,
+            processes: 0,
+        })
+        .collect();
+    for start in starts {
+        buckets[(start / width) as usize].processes += 1;
+    }
+    buckets
+}
+
+/// `a, b, c, d, e (+N more)`
+fn list_hosts(hosts: &[String]) -> String {
+    let mut listed = hosts
+        .iter()
+        .take(LISTED_HOSTS)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if hosts.len() > LISTED_HOSTS {
+        listed.push_str(&format!(" (+{} more)", hosts.len() - LISTED_HOSTS));
+    }
+    listed
+}
+
+impl fmt::Display for ConfigSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} hosts, {} processes on a {} network, stop_time {}s",
+            self.hosts, self.processes, self.graph_type, self.stop_time
+        )?;
+        let kinds: Vec<String> = self
+            .processes_by_kind
+            .iter()
+            .map(|(kind, n)| format!("{} {:?}", n, kind).to_lowercase())
+            .collect();
+        writeln!(f, "Processes: {}", kinds.join(", "))?;
+        let per_host: Vec<String> = self
+            .processes_per_host
+            .iter()
+            .map(|(processes, hosts)| format!("{} x {}", hosts, processes))
+            .collect();
+        writeln!(
+            f,
+            "Processes per host (hosts x processes): {}",
+            per_host.join(", ")
+        )?;
+
+        writeln!(f, "\nHosts by kind:")?;
+        for (kind, hosts) in &self.hosts_by_kind {
+            writeln!(
+                f,
+                "  {:<14} {:>6}  {}",
+                kind,
+                hosts.len(),
+                list_hosts(hosts)
+            )?;
+        }
+
+        writeln!(f, "\nProcess start times:")?;
+        let widest = self
+            .start_times
+            .iter()
+            .map(|b| b.processes)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        for bucket in &self.start_times {
+            let line = format!(
+                "  {:>7}s - {:>7}s {:>6}  {}",
+                bucket.from,
+                bucket.to,
+                bucket.processes,
+                "#".repeat(bucket.processes * 40 / widest)
+            );
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        if self.seed_nodes.is_empty() {
+            writeln!(f, "\nNo daemon is given --seed-node or fixed peers")?;
+        } else {
+            writeln!(f, "\nSeed nodes (dialed by):")?;
+            for (seed, dialers) in &self.seed_nodes {
+                writeln!(
+                    f,
+                    "  {:<24} {:>6}  {}",
+                    seed,
+                    dialers.len(),
+                    list_hosts(dialers)
+                )?;
+            }
+        }
+
+        if !self.empty_hosts.is_empty() {
+            writeln!(
+                f,
+                "\nHosts without processes: {}",
+                list_hosts(&self.empty_hosts)
+            )?;
+        }
+        match &self.registry {
+            Some(registry) => {
+                writeln!(
+                    f,
+                    "\nRegistry {}: {} agents",
+                    registry.path.display(),
+                    registry.agents
+                )?;
+                if !registry.missing_hosts.is_empty() {
+                    writeln!(
+                        f,
+                        "  Agents without a host: {}",
+                        list_hosts(&registry.missing_hosts)
+                    )?;
+                }
+                if !registry.unregistered_hosts.is_empty() {
+                    writeln!(
+                        f,
+                        "  Hosts not in the registry: {}",
+                        list_hosts(&registry.unregistered_hosts)
+                    )?;
+                }
+            }
+            None => writeln!(
+                f,
+                "\nNo {} found; process kinds are guessed from program names",
+                AGENT_REGISTRY_FILE
+            )?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::duration::SimDuration;
+
+    #[test]
+    fn histogram_covers_starts_past_stop_time() {
+        let buckets = start_histogram(&[0, 5, 99, 150], 100);
+        assert_eq!(buckets.len(), START_TIME_BUCKETS);
+        assert_eq!(buckets[0].to, 16);
+        assert_eq!(buckets[0].processes, 2);
+        assert_eq!(buckets.iter().map(|b| b.processes).sum::<usize>(), 4);
+        assert!(buckets.last().unwrap().to > 150);
+
+        let empty = start_histogram(&[], 0);
+        assert_eq!(empty[0].to, 1);
+    }
+
+    #[test]
+    fn bash_launched_daemons_are_daemons() {
+        let process = ShadowProcess {
+            path: "/bin/bash".to_string(),
+            args: ProcessArgs::List(
+                [
+                    "-c",
+                    "ulimit -n 4096 && exec \"$0\" \"$@\"",
+                    "/opt/monerod-v2",
+                ]
+                .iter()
+                .chain(&["--seed-node=10.0.0.1:18080"])
+                .map(|s| s.to_string())
+                .collect(),
+            ),
+            start_time: SimDuration::from_secs(5),
+            ..Default::default()
+        };
+        assert_eq!(process_kind(&process, None), ProcessKind::Daemon);
+        assert_eq!(program_args(&process), vec!["--seed-node=10.0.0.1:18080"]);
+
+        let wrapper = ShadowProcess {
+            path: "/bin/bash".to_string(),
+            args: ProcessArgs::List(vec!["scripts/agent_user-001_wrapper.sh".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(process_kind(&wrapper, None), ProcessKind::Script);
+    }
+}
@@ -4,9 +4,13 @@
 //! geographic distribution across continents and subnet management
 //! for different network configurations.
 
-use super::as_manager::AsSubnetManager;
+use std::collections::BTreeMap;
+
+use super::as_manager::{AsRegion, AsSubnetManager};
 use super::registry::{AgentType, GlobalIpRegistry};
 use crate::gml_parser::{GmlGraph, GmlNode};
+use crate::topology::distribution::largest_remainder;
+use crate::utils::seed::seeded_hash;
 
 /// Get AS number from a GML node
 fn get_node_as_number(gml_node: &GmlNode) -> Option<String> {
@@ -17,6 +21,39 @@ fn get_node_as_number(gml_node: &GmlNode) -> Option<String> {
         .cloned()
 }
 
+/// Region of each of `agent_ids` under a switch network's
+/// `geographic_distribution` (region key -> share). Each region gets its
+/// `largest_remainder` count of agents, handed out in an order drawn from
+/// `seed`, so the counts follow the shares exactly and the agents picked
+/// for each region are stable for a seed. Unknown region keys get no agents.
+pub fn assign_geographic_regions(
+    agent_ids: &[&str],
+    shares: &BTreeMap<String, f64>,
+    seed: u64,
+) -> Vec<AsRegion> {
+    let mut region_shares = [0.0; 6];
+    for (name, share) in shares {
+        if let Some(region) = AsRegion::from_key(name) {
+            region_shares[region.index()] += share;
+        }
+    }
+    let counts = largest_remainder(agent_ids.len(), &region_shares);
+    let mut slots = AsRegion::all()
+        .into_iter()
+        .zip(counts)
+        .flat_map(|(region, count)| std::iter::repeat(region).take(count));
+
+    let mut order: Vec<usize> = (0..agent_ids.len()).collect();
+    order.sort_by_key(|&i| (seeded_hash(seed, &format!("region:{}", agent_ids[i])), i));
+    let mut regions = vec![AsRegion::NorthAmerica; agent_ids.len()];
+    for i in order {
+        if let Some(region) = slots.next() {
+            regions[i] = region;
+        }
+    }
+    regions
+}
+
 /// Get IP address for an agent using the centralized Global IP Registry
 /// Priority order:
 /// 0) Pre-registered IP (e.g., Monero fallback seed pinning) — honored unconditionally
@@ -24,10 +61,13 @@ fn get_node_as_number(gml_node: &GmlNode) -> Option<String> {
 /// 2) Pre-allocated GML IP
 /// 3) AS-aware IP
 /// 4) Dynamic IP assignment
+///
+/// Each `AgentType` draws from its own pool at steps 3 and 4 (see
+/// `AsSubnetManager::assign_as_aware_ip` and `GlobalIpRegistry::assign_ip`),
+/// so adding or removing agents of one type never moves another type's IPs.
 pub fn get_agent_ip(
     agent_type: AgentType,
     agent_id: &str,
-    agent_index: usize,
     network_node_id: u32,
     gml_graph: Option<&GmlGraph>,
     using_gml_topology: bool,
@@ -43,6 +83,7 @@ pub fn get_agent_ip(
             pre_registered,
             agent_id
         );
+        ip_registry.note_allocation(agent_id, "pinned before allocation (fallback seed IP)");
         return Ok(pre_registered);
     }
 
@@ -56,6 +97,7 @@ pub fn get_agent_ip(
                     agent_id,
                     group
                 );
+                ip_registry.note_allocation(agent_id, format!("subnet_group '{}'", group));
                 return Ok(ip);
             }
             Err(e) => {
@@ -94,6 +136,10 @@ pub fn get_agent_ip(
                                     agent_id,
                                     network_node_id
                                 );
+                                ip_registry.note_allocation(
+                                    agent_id,
+                                    format!("ip of GML node {}", network_node_id),
+                                );
                                 return Ok(pre_allocated_ip.to_string());
                             }
                         } else {
@@ -115,6 +161,10 @@ pub fn get_agent_ip(
                                     agent_id,
                                     network_node_id
                                 );
+                                ip_registry.note_allocation(
+                                    agent_id,
+                                    format!("ip of GML node {}", network_node_id),
+                                );
                                 return Ok(pre_allocated_ip.to_string());
                             }
                         }
@@ -123,7 +173,7 @@ pub fn get_agent_ip(
 
                 // Priority 2: Try AS-aware assignment using the legacy AS subnet manager
                 if let Some(as_number) = get_node_as_number(gml_node) {
-                    if let Some(as_ip) = subnet_manager.assign_as_aware_ip(&as_number) {
+                    if let Some(as_ip) = subnet_manager.assign_as_aware_ip(&as_number, agent_type) {
                         // Check if this AS IP conflicts with our registry
                         if let Some(conflicting_agent) = ip_registry.get_agent_for_ip(&as_ip) {
                             if conflicting_agent != agent_id {
@@ -136,6 +186,13 @@ pub fn get_agent_ip(
                                     as_number,
                                     network_node_id
                                 );
+                                ip_registry.note_allocation(
+                                    agent_id,
+                                    format!(
+                                        "AS {} subnet of GML node {}",
+                                        as_number, network_node_id
+                                    ),
+                                );
                                 return Ok(as_ip);
                             }
                         } else {
@@ -158,6 +215,13 @@ pub fn get_agent_ip(
                                     as_number,
                                     network_node_id
                                 );
+                                ip_registry.note_allocation(
+                                    agent_id,
+                                    format!(
+                                        "AS {} subnet of GML node {}",
+                                        as_number, network_node_id
+                                    ),
+                                );
                                 return Ok(as_ip);
                             }
                         }
@@ -181,43 +245,40 @@ pub fn get_agent_ip(
                 ip,
                 agent_id
             );
+            let pool = if agent_type == AgentType::UserAgent {
+                "the regional pool".to_string()
+            } else {
+                format!("the {:?} pool", agent_type)
+            };
+            ip_registry.note_allocation(agent_id, format!("next free address in {}", pool));
             Ok(ip)
         }
         Err(error) => {
             // Fallback to legacy assignment if centralized registry fails
             log::warn!(
-                "IP registry assignment failed for {}: {}. Using geographic fallback.",
+                "IP registry assignment failed for {}: {}. Using the {:?} fallback pool.",
                 agent_id,
-                error
+                error,
+                agent_type
             );
 
-            // Fallback range: 198.18.0.0/15 (RFC 2544 benchmarking).
-            // Public per epee::is_ip_local, so monerod accepts it
-            // without --allow-local-ip. Easy to recognize as "test".
-            let fallback_ip = match agent_type {
-                AgentType::UserAgent => format!("198.18.10.{}", 10 + (agent_index % 245)),
-                AgentType::MinerDistributor => format!("198.18.20.{}", 10 + (agent_index % 245)),
-                AgentType::PureScriptAgent => format!("198.18.30.{}", 10 + (agent_index % 245)),
-                AgentType::Infrastructure => format!("198.18.40.{}", 10 + (agent_index % 245)),
-            };
-
-            // Try to register the fallback IP. If it's already owned by a
-            // *different* agent, returning it would assign one IP to two
-            // hosts — that is a hard error rather than a silent duplicate.
-            if let Some(conflicting_agent) = ip_registry.get_agent_for_ip(&fallback_ip) {
-                if conflicting_agent != agent_id {
-                    return Err(color_eyre::eyre::eyre!(
-                        "Cannot assign a unique IP to agent '{}': geographic fallback {} is already assigned to agent '{}' (registry error: {})",
-                        agent_id, fallback_ip, conflicting_agent, error
-                    ));
-                }
-                // Same agent already owns it — safe to reuse.
-            } else {
-                // Newly-free IP: register it (cannot conflict, just checked).
-                let _ = ip_registry.register_pre_allocated_ip(&fallback_ip, agent_id);
-            }
+            // Fallback: the agent type's own pool in 198.18.0.0/15, which
+            // no other type draws from
+            let fallback_ip =
+                ip_registry
+                    .assign_pool_ip(agent_type, agent_id)
+                    .map_err(|pool_error| {
+                        color_eyre::eyre::eyre!(
+                            "Cannot assign a unique IP to agent '{}': {} (registry error: {})",
+                            agent_id,
+                            pool_error,
+                            error
+                        )
+                    })?;
 
             log::info!("Assigned fallback IP {} to agent {}", fallback_ip, agent_id);
+            ip_registry
+                .note_allocation(agent_id, format!("fallback range, pool failed: {}", error));
             Ok(fallback_ip)
         }
     }
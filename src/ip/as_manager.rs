@@ -19,7 +19,12 @@
 //!
 //! ## IP Allocation Scheme
 //!
-//! - Each AS gets its own /24 subnet with up to 254 hosts
+//! - Each AS gets its own /24 subnet with 245 hosts (.10 through .254), or
+//!   a larger subnet with `network.subnet_size`; `check_capacity` fails
+//!   generation before any AS would run out
+//! - User agents count up from .10; every other agent type counts down from
+//!   the top of the subnet in its own stripe, so no user agent's address
+//!   depends on how many DNS servers, distributors or scripts there are
 //! - Subnets are drawn from real RIR (Regional Internet Registry) IP ranges
 //!   so that IPs look geographically realistic (e.g., ARIN ranges for North America)
 //! - Each region cycles through its allocated first-octets for diversity
@@ -39,7 +44,9 @@
 //! | Africa         | 8.33%      | AS 1000-1099         |
 //! | Oceania        | 8.33%      | AS 1100-1199         |
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+use super::registry::{AgentType, REGISTRY_REGION_OCTETS};
 
 /// Region classification for AS numbers (for logging/debugging)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -79,6 +86,57 @@ impl AsRegion {
         }
     }
 
+    /// Snake-case key, as in `distribution.weights` and the agent registry's
+    /// `continent`; None for Unknown
+    pub fn key(&self) -> Option<&'static str> {
+        match self {
+            AsRegion::NorthAmerica => Some("north_america"),
+            AsRegion::Europe => Some("europe"),
+            AsRegion::Asia => Some("asia"),
+            AsRegion::SouthAmerica => Some("south_america"),
+            AsRegion::Africa => Some("africa"),
+            AsRegion::Oceania => Some("oceania"),
+            AsRegion::Unknown => None,
+        }
+    }
+
+    /// Region named by its key or display name, ignoring case and
+    /// separators (`europe`, `Europe`, `North America`, `NorthAmerica`);
+    /// None for anything else
+    pub fn from_key(name: &str) -> Option<Self> {
+        let squash = |s: &str| s.to_lowercase().replace([' ', '_'], "");
+        let key = squash(name.trim());
+        AsRegion::all()
+            .into_iter()
+            .find(|region| region.key().map(squash).as_deref() == Some(key.as_str()))
+    }
+
+    /// Region of an IP the allocator handed out, from its first octet: the
+    /// AS-aware tables below or `REGISTRY_REGION_OCTETS`. Subnet-group and
+    /// fallback addresses are Unknown.
+    pub fn from_ip(ip: &str) -> Self {
+        let Some(octet) = ip.split('.').next().and_then(|o| o.parse::<u8>().ok()) else {
+            return AsRegion::Unknown;
+        };
+        let tables: [(AsRegion, &[u8]); 6] = [
+            (AsRegion::NorthAmerica, &NA_OCTETS),
+            (AsRegion::Europe, &EU_OCTETS),
+            (AsRegion::Asia, &ASIA_OCTETS),
+            (AsRegion::SouthAmerica, &SA_OCTETS),
+            (AsRegion::Africa, &AF_OCTETS),
+            (AsRegion::Oceania, &OC_OCTETS),
+        ];
+        for (region, octets) in tables {
+            if octets.contains(&octet) {
+                return region;
+            }
+        }
+        REGISTRY_REGION_OCTETS
+            .iter()
+            .position(|&o| o == octet)
+            .map_or(AsRegion::Unknown, |i| AsRegion::all()[i])
+    }
+
     /// Get the index of this region (0-5) for array indexing
     pub fn index(&self) -> usize {
         match self {
@@ -161,6 +219,47 @@ const AF_OCTETS: [u8; 6] = [41, 102, 105, 154, 196, 197];
 /// (both are APNIC) and were replaced with 120/123 to keep the tables disjoint.
 const OC_OCTETS: [u8; 8] = [120, 123, 121, 122, 139, 144, 202, 203];
 
+/// Prefix length of each AS's subnet unless `network.subnet_size` says
+/// otherwise
+pub const DEFAULT_AS_PREFIX: u8 = 24;
+
+/// Largest AS subnet `network.subnet_size` may ask for
+pub const MIN_AS_PREFIX: u8 = 16;
+
+/// Addresses at the start of every AS subnet that are never handed out
+const RESERVED_HOSTS: u32 = 10;
+
+/// Agent types that count down from the top of a subnet, one address in
+/// every `SERVICE_TYPES` each
+const SERVICE_TYPES: u32 = 3;
+
+/// Stripe of a non-user agent type at the top of a subnet; None for user
+/// agents, which count up from the bottom
+fn service_stripe(agent_type: AgentType) -> Option<u32> {
+    match agent_type {
+        AgentType::UserAgent => None,
+        AgentType::Infrastructure => Some(0),
+        AgentType::MinerDistributor => Some(1),
+        AgentType::PureScriptAgent => Some(2),
+    }
+}
+
+/// Parse `network.subnet_size` ("/16", "16") into a prefix length
+pub fn parse_subnet_size(size: &str) -> Result<u8, String> {
+    let prefix: u8 = size
+        .trim()
+        .trim_start_matches('/')
+        .parse()
+        .map_err(|_| format!("subnet_size '{}' is not a prefix length like /16", size))?;
+    if !(MIN_AS_PREFIX..=DEFAULT_AS_PREFIX).contains(&prefix) {
+        return Err(format!(
+            "subnet_size /{} is out of range: use /{} (254 addresses per AS) up to /{}",
+            prefix, DEFAULT_AS_PREFIX, MIN_AS_PREFIX
+        ));
+    }
+    Ok(prefix)
+}
+
 /// Calculate region boundaries proportionally for any topology size.
 ///
 /// This function divides the node range 0..total_nodes into 6 geographic
@@ -227,13 +326,16 @@ pub fn get_region_for_node(node_id: usize, total_nodes: usize) -> AsRegion {
 
 /// Dynamic AS-aware subnet manager for GML topologies.
 ///
-/// Handles any AS number by mapping it to a unique /24 subnet in the 10.0.0.0/8 range.
+/// Handles any AS number by mapping it to a unique subnet in its region's
+/// ranges: a /24 by default, or a larger prefix from `network.subnet_size`.
 #[derive(Debug)]
 pub struct AsSubnetManager {
-    /// Maps AS number string to next available host number (1-254)
-    host_counters: HashMap<String, u8>,
+    /// Hosts handed out so far per AS number string and agent type
+    host_counters: HashMap<(String, AgentType), u32>,
     /// Statistics for logging
     assignments_per_region: HashMap<AsRegion, u32>,
+    /// Prefix length of every AS subnet, `MIN_AS_PREFIX..=DEFAULT_AS_PREFIX`
+    prefix: u8,
 }
 
 impl AsSubnetManager {
@@ -241,21 +343,76 @@ impl AsSubnetManager {
         AsSubnetManager {
             host_counters: HashMap::new(),
             assignments_per_region: HashMap::new(),
+            prefix: DEFAULT_AS_PREFIX,
+        }
+    }
+
+    /// A manager giving every AS a /`prefix` subnet (see `parse_subnet_size`)
+    pub fn with_prefix(prefix: u8) -> Self {
+        AsSubnetManager {
+            prefix: prefix.clamp(MIN_AS_PREFIX, DEFAULT_AS_PREFIX),
+            ..Self::new()
         }
     }
 
+    /// Addresses `assign_as_aware_ip` hands out per AS: all but the first
+    /// ten and the broadcast address
+    pub fn hosts_per_subnet(&self) -> usize {
+        (self.subnet_len() - RESERVED_HOSTS - 1) as usize
+    }
+
+    /// Addresses in every AS subnet, network and broadcast included
+    fn subnet_len(&self) -> u32 {
+        1 << (32 - self.prefix)
+    }
+
+    fn used(&self, as_number: &str, agent_type: AgentType) -> u32 {
+        self.host_counters
+            .get(&(as_number.to_string(), agent_type))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Offset of the `n`th address of a non-user type's stripe, counting
+    /// down from the last host
+    fn service_offset(&self, stripe: u32, n: u32) -> Option<u32> {
+        (self.subnet_len() - 2).checked_sub(n * SERVICE_TYPES + stripe)
+    }
+
+    /// Offset of the lowest address non-user types hold in the AS's subnet,
+    /// the broadcast address when they hold none; user agents must stay
+    /// below it
+    fn service_floor(&self, as_number: &str) -> u32 {
+        [
+            AgentType::Infrastructure,
+            AgentType::MinerDistributor,
+            AgentType::PureScriptAgent,
+        ]
+        .into_iter()
+        .filter_map(|agent_type| {
+            let used = self.used(as_number, agent_type);
+            let stripe = service_stripe(agent_type)?;
+            (used > 0).then(|| self.service_offset(stripe, used - 1))?
+        })
+        .min()
+        .unwrap_or(self.subnet_len() - 1)
+    }
+
+    /// Addresses still free for user agents in the AS's subnet
+    fn user_hosts_free(&self, as_number: &str) -> u32 {
+        self.service_floor(as_number)
+            .saturating_sub(RESERVED_HOSTS + self.used(as_number, AgentType::UserAgent))
+    }
+
     /// Parse AS number string to u32
     fn parse_as_number(as_str: &str) -> Option<u32> {
         as_str.parse::<u32>().ok()
     }
 
-    /// Get the /24 subnet base for an AS number.
-    ///
-    /// Maps AS numbers to region-appropriate IP ranges (see the `*_OCTETS`
-    /// tables above) to simulate a realistic global Internet with diverse IP
-    /// addresses. Each region cycles through its RIR-derived first octets;
-    /// each AS gets its own /24 subnet within its region's IP range.
-    pub fn get_subnet_base(as_number: &str) -> Option<String> {
+    /// First octet and subnet slot of an AS: regions cycle through their
+    /// RIR-derived first octets (see the `*_OCTETS` tables above), and the
+    /// slot counts the AS's subnets within that first octet
+    fn region_slot(as_number: &str) -> Option<(u8, usize)> {
         let as_num = Self::parse_as_number(as_number)?;
         let region = AsRegion::from_as_number(as_num);
 
@@ -269,88 +426,115 @@ impl AsSubnetManager {
             AsRegion::Oceania => as_num.saturating_sub(1100),     // 0-99
             AsRegion::Unknown => as_num,
         };
-
-        // Map to region-appropriate IP ranges based on real RIR allocations
-        // Each region cycles through multiple first octets for diversity
-        let offset = region_offset as usize; // Convert to usize for array indexing
-                                             // Cycle the region's first octets; second/third octets extend capacity
-                                             // beyond one /24 per region. Tables are the module-level `*_OCTETS`
-                                             // consts (kept disjoint across regions — see the invariant note above).
-        let subnet = match region {
-            AsRegion::NorthAmerica => {
-                let first = NA_OCTETS[offset % NA_OCTETS.len()];
-                let second = (offset / NA_OCTETS.len()) % 256;
-                let third = (offset / (NA_OCTETS.len() * 256)) % 256;
-                format!("{}.{}.{}", first, second, third)
-            }
-            AsRegion::Europe => {
-                let first = EU_OCTETS[offset % EU_OCTETS.len()];
-                let second = (offset / EU_OCTETS.len()) % 256;
-                let third = (offset / (EU_OCTETS.len() * 256)) % 256;
-                format!("{}.{}.{}", first, second, third)
-            }
-            AsRegion::Asia => {
-                let first = ASIA_OCTETS[offset % ASIA_OCTETS.len()];
-                let second = (offset / ASIA_OCTETS.len()) % 256;
-                let third = (offset / (ASIA_OCTETS.len() * 256)) % 256;
-                format!("{}.{}.{}", first, second, third)
-            }
-            AsRegion::SouthAmerica => {
-                let first = SA_OCTETS[offset % SA_OCTETS.len()];
-                let second = (offset / SA_OCTETS.len()) % 256;
-                let third = (offset / (SA_OCTETS.len() * 256)) % 256;
-                format!("{}.{}.{}", first, second, third)
-            }
-            AsRegion::Africa => {
-                let first = AF_OCTETS[offset % AF_OCTETS.len()];
-                let second = (offset / AF_OCTETS.len()) % 256;
-                let third = (offset / (AF_OCTETS.len() * 256)) % 256;
-                format!("{}.{}.{}", first, second, third)
-            }
-            AsRegion::Oceania => {
-                let first = OC_OCTETS[offset % OC_OCTETS.len()];
-                let second = (offset / OC_OCTETS.len()) % 256;
-                let third = (offset / (OC_OCTETS.len() * 256)) % 256;
-                format!("{}.{}.{}", first, second, third)
-            }
-            AsRegion::Unknown => {
-                // Fallback to diverse range
-                let as_usize = as_num as usize;
-                format!(
-                    "{}.{}.{}",
-                    100 + (as_usize % 50),
-                    (as_usize / 50) % 256,
-                    (as_usize / (50 * 256)) % 256
-                )
-            }
+        let offset = region_offset as usize;
+        // Tables are the module-level `*_OCTETS` consts (kept disjoint
+        // across regions — see the invariant note above); Unknown ASes get
+        // a diverse fallback range
+        let octets: &[u8] = match region {
+            AsRegion::NorthAmerica => &NA_OCTETS,
+            AsRegion::Europe => &EU_OCTETS,
+            AsRegion::Asia => &ASIA_OCTETS,
+            AsRegion::SouthAmerica => &SA_OCTETS,
+            AsRegion::Africa => &AF_OCTETS,
+            AsRegion::Oceania => &OC_OCTETS,
+            AsRegion::Unknown => return Some((100 + (offset % 50) as u8, offset / 50)),
         };
-
-        Some(subnet)
+        Some((octets[offset % octets.len()], offset / octets.len()))
     }
 
-    /// Assign an IP address based on AS number.
+    /// Get the /24 subnet base for an AS number.
     ///
-    /// Returns a unique IP within the AS's /24 subnet.
-    /// Host addresses start at 10 to avoid reserved addresses (0, 1 for gateway, etc.)
-    pub fn assign_as_aware_ip(&mut self, as_number: &str) -> Option<String> {
-        let subnet_base = Self::get_subnet_base(as_number)?;
+    /// Maps AS numbers to region-appropriate IP ranges to simulate a
+    /// realistic global Internet with diverse IP addresses. Each AS gets
+    /// its own /24 within its region's IP range; the second and third
+    /// octets extend capacity beyond one /24 per first octet.
+    pub fn get_subnet_base(as_number: &str) -> Option<String> {
+        let (first, slot) = Self::region_slot(as_number)?;
+        Some(format!("{}.{}.{}", first, slot % 256, (slot / 256) % 256))
+    }
 
-        // Get or initialize the host counter for this AS (start at 10)
-        let counter = self
-            .host_counters
-            .entry(as_number.to_string())
-            .or_insert(10);
-
-        // Check if we've exhausted the subnet (max 254 for last octet).
-        // Counter is u8 and increments via saturating_add(1), so 255 is
-        // both the maximum value and the exhausted-sentinel.
-        if *counter == 255 {
-            log::warn!("AS {} subnet exhausted (254 hosts assigned)", as_number);
-            return None;
+    /// First address of the AS's subnet. /24s keep `get_subnet_base`'s
+    /// layout; larger subnets are laid out back to back from the first
+    /// octet's start. None for an AS that is not a number, or whose slot
+    /// does not fit under its first octet.
+    fn subnet_start(&self, as_number: &str) -> Option<u32> {
+        let (first, slot) = Self::region_slot(as_number)?;
+        let first = (first as u32) << 24;
+        if self.prefix == DEFAULT_AS_PREFIX {
+            return Some(
+                first | (((slot % 256) as u32) << 16) | ((((slot / 256) % 256) as u32) << 8),
+            );
         }
+        let subnets_per_octet = 1usize << (self.prefix - 8);
+        (slot < subnets_per_octet).then(|| first | ((slot as u32) << (32 - self.prefix)))
+    }
 
-        let ip = format!("{}.{}", subnet_base, counter);
-        *counter = counter.saturating_add(1);
+    /// The AS's subnet in CIDR notation, e.g. "3.0.0.0/24"
+    pub fn subnet_of(&self, as_number: &str) -> Option<String> {
+        self.subnet_start(as_number)
+            .map(|start| format!("{}/{}", std::net::Ipv4Addr::from(start), self.prefix))
+    }
+
+    /// Fail when placing `demand` (AS number -> agents that take an address
+    /// from the AS's subnet) would run any subnet out of addresses, naming
+    /// each such AS and how many addresses it is short
+    pub fn check_capacity(&self, demand: &BTreeMap<String, usize>) -> Result<(), String> {
+        let mut overflows = Vec::new();
+        for (as_number, &needed) in demand {
+            let Some(subnet) = self.subnet_of(as_number) else {
+                continue;
+            };
+            let free = self.user_hosts_free(as_number) as usize;
+            if needed > free {
+                overflows.push(format!(
+                    "AS {} ({}) needs {} addresses but has {} free, {} short",
+                    as_number,
+                    subnet,
+                    needed,
+                    free,
+                    needed - free
+                ));
+            }
+        }
+        if overflows.is_empty() {
+            return Ok(());
+        }
+        Err(format!(
+            "{}; set network.subnet_size to a larger subnet (e.g. /{}) or spread the agents \
+             over more ASes",
+            overflows.join("; "),
+            if self.prefix > 20 { 20 } else { MIN_AS_PREFIX }
+        ))
+    }
+
+    /// Assign an IP address based on AS number.
+    ///
+    /// Returns a unique IP within the AS's subnet, None once it is full.
+    /// User agents take host addresses upward from 10 to avoid reserved
+    /// addresses (0, 1 for gateway, etc.); other agent types take theirs
+    /// from their stripe at the top of the subnet.
+    pub fn assign_as_aware_ip(&mut self, as_number: &str, agent_type: AgentType) -> Option<String> {
+        let start = self.subnet_start(as_number)?;
+        let users = self.used(as_number, AgentType::UserAgent);
+        let offset = match service_stripe(agent_type) {
+            None => (self.user_hosts_free(as_number) > 0).then_some(RESERVED_HOSTS + users),
+            Some(stripe) => self
+                .service_offset(stripe, self.used(as_number, agent_type))
+                .filter(|offset| *offset >= RESERVED_HOSTS + users),
+        };
+        let Some(offset) = offset else {
+            log::warn!(
+                "AS {} subnet has no address left for {:?} agents",
+                as_number,
+                agent_type
+            );
+            return None;
+        };
+        let ip = std::net::Ipv4Addr::from(start + offset).to_string();
+        *self
+            .host_counters
+            .entry((as_number.to_string(), agent_type))
+            .or_insert(0) += 1;
 
         // Track statistics
         if let Some(as_num) = Self::parse_as_number(as_number) {
@@ -389,16 +573,17 @@ impl AsSubnetManager {
             }
         }
 
-        stats.push_str(&format!(
-            "  Unique ASes used: {}\n",
-            self.host_counters.len()
-        ));
+        stats.push_str(&format!("  Unique ASes used: {}\n", self.unique_as_count()));
         stats
     }
 
     /// Get the number of unique ASes that have been assigned IPs
     pub fn unique_as_count(&self) -> usize {
-        self.host_counters.len()
+        self.host_counters
+            .keys()
+            .map(|(as_number, _)| as_number)
+            .collect::<std::collections::HashSet<_>>()
+            .len()
     }
 }
 
@@ -456,22 +641,129 @@ mod tests {
         let mut manager = AsSubnetManager::new();
 
         // First agent in AS 0 (North America) -> ARIN octet 3
-        let ip1 = manager.assign_as_aware_ip("0").unwrap();
+        let ip1 = manager
+            .assign_as_aware_ip("0", AgentType::UserAgent)
+            .unwrap();
         assert_eq!(ip1, "3.0.0.10");
 
         // Second agent in AS 0 -> same subnet, next host
-        let ip2 = manager.assign_as_aware_ip("0").unwrap();
+        let ip2 = manager
+            .assign_as_aware_ip("0", AgentType::UserAgent)
+            .unwrap();
         assert_eq!(ip2, "3.0.0.11");
 
         // First agent in AS 1 (North America) -> ARIN octet 4
-        let ip3 = manager.assign_as_aware_ip("1").unwrap();
+        let ip3 = manager
+            .assign_as_aware_ip("1", AgentType::UserAgent)
+            .unwrap();
         assert_eq!(ip3, "4.0.0.10");
 
         // First agent in AS 500 (Asia, offset 0) -> APNIC octet 1
-        let ip4 = manager.assign_as_aware_ip("500").unwrap();
+        let ip4 = manager
+            .assign_as_aware_ip("500", AgentType::UserAgent)
+            .unwrap();
         assert_eq!(ip4, "1.0.0.10");
     }
 
+    #[test]
+    fn subnet_size_sets_capacity_and_layout() {
+        assert_eq!(parse_subnet_size("/16"), Ok(16));
+        assert_eq!(parse_subnet_size("20"), Ok(20));
+        assert!(parse_subnet_size("/8")
+            .unwrap_err()
+            .contains("out of range"));
+        assert!(parse_subnet_size("large").is_err());
+
+        let mut manager = AsSubnetManager::new();
+        assert_eq!(manager.hosts_per_subnet(), 245);
+        assert_eq!(manager.subnet_of("0"), Some("3.0.0.0/24".to_string()));
+        for _ in 0..245 {
+            assert!(manager
+                .assign_as_aware_ip("0", AgentType::UserAgent)
+                .is_some());
+        }
+        assert_eq!(manager.assign_as_aware_ip("0", AgentType::UserAgent), None);
+
+        let mut manager = AsSubnetManager::with_prefix(16);
+        assert_eq!(manager.hosts_per_subnet(), 65_525);
+        assert_eq!(manager.subnet_of("0"), Some("3.0.0.0/16".to_string()));
+        assert_eq!(manager.subnet_of("1"), Some("4.0.0.0/16".to_string()));
+        let ips: Vec<String> = (0..300)
+            .map(|_| {
+                manager
+                    .assign_as_aware_ip("0", AgentType::UserAgent)
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(ips[0], "3.0.0.10");
+        assert_eq!(ips[299], "3.0.1.53");
+    }
+
+    #[test]
+    fn other_agent_types_count_down_in_their_own_stripes() {
+        let mut manager = AsSubnetManager::new();
+        let dns = manager.assign_as_aware_ip("0", AgentType::Infrastructure);
+        let script = manager.assign_as_aware_ip("0", AgentType::PureScriptAgent);
+        let script2 = manager.assign_as_aware_ip("0", AgentType::PureScriptAgent);
+        let distributor = manager.assign_as_aware_ip("0", AgentType::MinerDistributor);
+        assert_eq!(dns.as_deref(), Some("3.0.0.254"));
+        assert_eq!(distributor.as_deref(), Some("3.0.0.253"));
+        assert_eq!(script.as_deref(), Some("3.0.0.252"));
+        assert_eq!(script2.as_deref(), Some("3.0.0.249"));
+        assert_eq!(
+            manager
+                .assign_as_aware_ip("0", AgentType::UserAgent)
+                .as_deref(),
+            Some("3.0.0.10")
+        );
+
+        // Users stop below the lowest address the other types hold
+        for _ in 1..239 {
+            assert!(manager
+                .assign_as_aware_ip("0", AgentType::UserAgent)
+                .is_some());
+        }
+        assert_eq!(manager.assign_as_aware_ip("0", AgentType::UserAgent), None);
+        assert_eq!(
+            manager.assign_as_aware_ip("0", AgentType::PureScriptAgent),
+            None
+        );
+        assert_eq!(
+            manager
+                .assign_as_aware_ip("0", AgentType::Infrastructure)
+                .as_deref(),
+            Some("3.0.0.251")
+        );
+        assert_eq!(manager.unique_as_count(), 1);
+    }
+
+    #[test]
+    fn capacity_check_names_each_overflowing_as() {
+        let mut manager = AsSubnetManager::new();
+        manager.assign_as_aware_ip("0", AgentType::UserAgent);
+        let demand: BTreeMap<String, usize> = [
+            ("0".to_string(), 245),
+            ("1".to_string(), 245),
+            ("200".to_string(), 300),
+        ]
+        .into_iter()
+        .collect();
+        let err = manager.check_capacity(&demand).unwrap_err();
+        assert!(
+            err.contains("AS 0 (3.0.0.0/24) needs 245 addresses but has 244 free, 1 short"),
+            "{}",
+            err
+        );
+        assert!(err.contains("AS 200"), "{}", err);
+        assert!(!err.contains("AS 1 "), "{}", err);
+        assert!(err.contains("network.subnet_size"), "{}", err);
+
+        assert_eq!(
+            AsSubnetManager::with_prefix(16).check_capacity(&demand),
+            Ok(())
+        );
+    }
+
     #[test]
     fn region_octet_tables_are_pairwise_disjoint() {
         // Invariant guard: the six AS-aware region tables must not share a
@@ -538,15 +830,39 @@ mod tests {
         assert_eq!(AsRegion::from_as_number(5000), AsRegion::Unknown);
     }
 
+    #[test]
+    fn allocated_ips_map_back_to_their_region() {
+        let mut manager = AsSubnetManager::new();
+        for as_number in ["0", "250", "600", "900", "1050", "1150"] {
+            let ip = manager
+                .assign_as_aware_ip(as_number, AgentType::UserAgent)
+                .unwrap();
+            assert_eq!(
+                AsRegion::from_ip(&ip),
+                manager.get_region(as_number),
+                "{}",
+                ip
+            );
+        }
+        let mut registry = crate::ip::GlobalIpRegistry::new();
+        let ip = registry
+            .assign_ip(crate::ip::AgentType::UserAgent, "user-002")
+            .unwrap();
+        assert_eq!(AsRegion::from_ip(&ip), AsRegion::Asia);
+        assert_eq!(AsRegion::from_ip("100.64.0.10"), AsRegion::Unknown);
+        assert_eq!(AsRegion::from_ip("not an ip"), AsRegion::Unknown);
+        assert_eq!(AsRegion::Asia.key(), Some("asia"));
+    }
+
     #[test]
     fn test_stats() {
         let mut manager = AsSubnetManager::new();
 
         // Assign IPs across different regions
-        manager.assign_as_aware_ip("0"); // North America
-        manager.assign_as_aware_ip("50"); // North America
-        manager.assign_as_aware_ip("200"); // Europe
-        manager.assign_as_aware_ip("500"); // Asia
+        manager.assign_as_aware_ip("0", AgentType::UserAgent); // North America
+        manager.assign_as_aware_ip("50", AgentType::UserAgent); // North America
+        manager.assign_as_aware_ip("200", AgentType::UserAgent); // Europe
+        manager.assign_as_aware_ip("500", AgentType::UserAgent); // Asia
 
         assert_eq!(manager.unique_as_count(), 4);
 
@@ -1,11 +1,12 @@
 //! IP address allocation with geographic distribution across continents.
 //!
-//! Handles switch-based (round-robin) and GML-based (AS-aware) allocation.
+//! Handles switch-based (round-robin, or by `geographic_distribution` share)
+//! and GML-based (AS-aware) allocation.
 
 pub mod allocator;
 pub mod as_manager;
 pub mod registry;
 
-pub use allocator::get_agent_ip;
+pub use allocator::{assign_geographic_regions, get_agent_ip};
 pub use as_manager::AsSubnetManager;
 pub use registry::{AgentType, GlobalIpRegistry};
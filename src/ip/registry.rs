@@ -4,11 +4,13 @@
 //! uniqueness and track which addresses are assigned to which agents
 //! in the simulation.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::as_manager::AsRegion;
 
 /// First octet per geographic region for the dynamic/fallback IP path in
-/// `assign_ip`, indexed by `agent_number % 6`
-/// (0=NA, 1=Europe, 2=Asia, 3=South America, 4=Africa, 5=Oceania).
+/// `assign_ip`, indexed by `agent_number % 6` or the region `set_region`
+/// gave the agent (0=NA, 1=Europe, 2=Asia, 3=South America, 4=Africa, 5=Oceania).
 ///
 /// INVARIANT: every octet here MUST be absent from all of `as_manager.rs`'s
 /// per-region octet tables (NA/EU/ASIA/SA/AF/OC_OCTETS) and distinct from each
@@ -23,6 +25,30 @@ use std::collections::{HashMap, HashSet};
 /// dedicated seed hosts never collide with these geographic assignments.
 pub(crate) const REGISTRY_REGION_OCTETS: [u8; 6] = [72, 91, 116, 45, 156, 210];
 
+/// Subnet groups `assign_subnet_group_ip` can create, one 100.64.x.0/24 each
+pub const MAX_SUBNET_GROUPS: usize = 256;
+
+/// Hosts per subnet group (.10 through .254)
+pub const SUBNET_GROUP_HOSTS: usize = 245;
+
+/// /24s in each agent type's pool (see `pool_third_octet`)
+const POOL_SUBNETS: u32 = 10;
+
+/// Hosts per pool /24 (.10 through .254)
+const POOL_SUBNET_HOSTS: u32 = 245;
+
+/// Third octet of the first /24 of an agent type's pool in 198.18.0.0/15
+/// (RFC 2544 benchmarking). Public per epee::is_ip_local, so monerod
+/// accepts it without --allow-local-ip, and easy to recognize as "test".
+fn pool_third_octet(agent_type: AgentType) -> u32 {
+    match agent_type {
+        AgentType::UserAgent => 10,
+        AgentType::MinerDistributor => 20,
+        AgentType::PureScriptAgent => 30,
+        AgentType::Infrastructure => 40,
+    }
+}
+
 /// Agent type classification for IP allocation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum AgentType {
@@ -47,7 +73,14 @@ pub struct GlobalIpRegistry {
     /// Each subnet group gets a unique /24 subnet in the 10.100.x.0 range
     subnet_groups: HashMap<String, (String, u8)>,
     /// Next available subnet ID for new groups
-    next_subnet_group_id: u8,
+    next_subnet_group_id: u16,
+    /// Addresses each agent type has drawn from its pool so far
+    pool_counters: HashMap<AgentType, u32>,
+    /// Agent id -> how `get_agent_ip()` chose its IP
+    allocation_sources: BTreeMap<String, String>,
+    /// Agent id -> region `assign_ip` uses instead of the one its number
+    /// picks (`network.geographic_distribution`)
+    regions: HashMap<String, AsRegion>,
 }
 
 impl GlobalIpRegistry {
@@ -58,9 +91,18 @@ impl GlobalIpRegistry {
             used_ips: HashSet::new(),
             subnet_groups: HashMap::new(),
             next_subnet_group_id: 0,
+            pool_counters: HashMap::new(),
+            allocation_sources: BTreeMap::new(),
+            regions: HashMap::new(),
         }
     }
 
+    /// Place `agent_id`'s dynamic IP in `region` rather than the one its
+    /// agent number picks
+    pub fn set_region(&mut self, agent_id: &str, region: AsRegion) {
+        self.regions.insert(agent_id.to_string(), region);
+    }
+
     /// Look up the IP previously assigned to `agent_id`, if any.
     /// Used by `get_agent_ip()` Priority 0 to honor pre-registered pinnings.
     pub fn get_ip_for_agent(&self, agent_id: &str) -> Option<&String> {
@@ -68,22 +110,24 @@ impl GlobalIpRegistry {
     }
 
     /// Assign a unique IP address for the given agent type and ID.
-    /// Distributes agents across different IP ranges to simulate global
-    /// internet distribution.
+    /// User agents are distributed across different IP ranges to simulate
+    /// global internet distribution; every other type draws from its own
+    /// pool (`assign_pool_ip`), so they never shift a user agent's address.
     ///
     /// agent_number assignment by ID prefix (collision-free ranges so
     /// agents in different categories don't fight for the same IP):
     ///   user-NNN          -> 0..999       (index = NNN)
     ///   miner-NNN         -> 1000..1999   (1000 + NNN)
     ///   relay-NNN         -> 2000..2999   (2000 + NNN)
-    ///   script-NNN        -> 3000..3999   (3000 + NNN)
-    ///   miner-distributor -> 50           (singleton)
-    ///   simulation-monitor -> 51          (singleton)
     ///   everything else   -> 0            (will collide → fallback path)
     ///
     /// The trailing digit run is parsed regardless of separator so both
     /// `user-001` and `user001` work.
-    pub fn assign_ip(&mut self, _agent_type: AgentType, agent_id: &str) -> Result<String, String> {
+    pub fn assign_ip(&mut self, agent_type: AgentType, agent_id: &str) -> Result<String, String> {
+        if agent_type != AgentType::UserAgent {
+            return self.assign_pool_ip(agent_type, agent_id);
+        }
+
         fn trailing_num(s: &str) -> u32 {
             let digits: String = s.chars().rev().take_while(|c| c.is_ascii_digit()).collect();
             digits
@@ -94,18 +138,12 @@ impl GlobalIpRegistry {
                 .unwrap_or(0)
         }
 
-        let agent_number = if agent_id == "miner-distributor" {
-            50
-        } else if agent_id == "simulation-monitor" {
-            51
-        } else if agent_id.starts_with("user") {
+        let agent_number = if agent_id.starts_with("user") {
             trailing_num(agent_id)
         } else if agent_id.starts_with("miner-") {
             1000 + trailing_num(agent_id)
         } else if agent_id.starts_with("relay-") {
             2000 + trailing_num(agent_id)
-        } else if agent_id.starts_with("script") {
-            3000 + trailing_num(agent_id)
         } else {
             0
         };
@@ -115,7 +153,10 @@ impl GlobalIpRegistry {
         // REGISTRY_REGION_OCTETS (see that const for the public-range and
         // disjoint-with-as_manager rationale):
         //   0 NA=72  1 EU=91  2 Asia=116  3 SA=45  4 Africa=156  5 Oceania=210
-        let region = (agent_number % 6) as usize; // always 0..=5
+        let region = match self.regions.get(agent_id) {
+            Some(region) => region.index(),
+            None => (agent_number % 6) as usize, // always 0..=5
+        };
         let subnet_offset = agent_number / 6;
         let octet1 = REGISTRY_REGION_OCTETS[region];
         let octet2 = subnet_offset % 256;
@@ -137,32 +178,62 @@ impl GlobalIpRegistry {
             if self.assigned_ips.get(&ip) == Some(&agent_id.to_string()) {
                 Ok(ip)
             } else {
-                // Fallback: try a different host IP in the same /24. host_octet4
-                // is at most 254, so +100 can push the octet past 255 (an
-                // invalid IPv4 octet). Reject rather than emit a malformed IP.
-                let fallback_octet = host_octet4 + 100;
-                if fallback_octet > 254 {
-                    return Err(format!(
-                        "Could not assign unique IP for agent {}: fallback host octet {} exceeds 254",
-                        agent_id, fallback_octet
-                    ));
-                }
-                let fallback_ip =
-                    format!("{}.{}.{}.{}", octet1, octet2, subnet_octet3, fallback_octet);
-                if !self.used_ips.contains(&fallback_ip) {
-                    self.used_ips.insert(fallback_ip.clone());
-                    self.assigned_ips
-                        .insert(fallback_ip.clone(), agent_id.to_string());
-                    self.agent_to_ip
-                        .insert(agent_id.to_string(), fallback_ip.clone());
-                    Ok(fallback_ip)
-                } else {
-                    Err(format!("Could not assign unique IP for agent {}", agent_id))
-                }
+                // Another agent maps to the same address: take the next
+                // free host of the same /24
+                let fallback_ip = (host_octet4 + 1..=254)
+                    .map(|host| format!("{}.{}.{}.{}", octet1, octet2, subnet_octet3, host))
+                    .find(|candidate| !self.used_ips.contains(candidate))
+                    .ok_or_else(|| {
+                        format!(
+                            "Could not assign unique IP for agent {}: {}.{}.{}.0/24 has no free \
+                             host above .{}",
+                            agent_id, octet1, octet2, subnet_octet3, host_octet4
+                        )
+                    })?;
+                self.used_ips.insert(fallback_ip.clone());
+                self.assigned_ips
+                    .insert(fallback_ip.clone(), agent_id.to_string());
+                self.agent_to_ip
+                    .insert(agent_id.to_string(), fallback_ip.clone());
+                Ok(fallback_ip)
             }
         }
     }
 
+    /// Assign the next free address of `agent_type`'s pool: 198.18.x.10
+    /// through .254 over `POOL_SUBNETS` /24s, with a counter per type so an
+    /// agent's address depends only on the agents of its own type before it
+    pub fn assign_pool_ip(
+        &mut self,
+        agent_type: AgentType,
+        agent_id: &str,
+    ) -> Result<String, String> {
+        if let Some(ip) = self.agent_to_ip.get(agent_id) {
+            return Ok(ip.clone());
+        }
+        let counter = self.pool_counters.entry(agent_type).or_insert(0);
+        while *counter < POOL_SUBNETS * POOL_SUBNET_HOSTS {
+            let ip = format!(
+                "198.18.{}.{}",
+                pool_third_octet(agent_type) + *counter / POOL_SUBNET_HOSTS,
+                10 + *counter % POOL_SUBNET_HOSTS
+            );
+            *counter += 1;
+            if !self.used_ips.contains(&ip) {
+                self.used_ips.insert(ip.clone());
+                self.assigned_ips.insert(ip.clone(), agent_id.to_string());
+                self.agent_to_ip.insert(agent_id.to_string(), ip.clone());
+                return Ok(ip);
+            }
+        }
+        Err(format!(
+            "Could not assign unique IP for agent {}: the {:?} pool's {} addresses are taken",
+            agent_id,
+            agent_type,
+            POOL_SUBNETS * POOL_SUBNET_HOSTS
+        ))
+    }
+
     /// Check if an IP is already assigned (fast HashSet lookup)
     pub fn is_ip_assigned(&self, ip: &str) -> bool {
         self.used_ips.contains(ip)
@@ -196,6 +267,17 @@ impl GlobalIpRegistry {
         self.assigned_ips.get(ip)
     }
 
+    /// Record how `agent_id`'s IP was chosen, for the generation trace
+    pub fn note_allocation(&mut self, agent_id: &str, source: impl Into<String>) {
+        self.allocation_sources
+            .insert(agent_id.to_string(), source.into());
+    }
+
+    /// Agent id -> how its IP was chosen, for every `get_agent_ip()` call
+    pub fn allocation_sources(&self) -> &BTreeMap<String, String> {
+        &self.allocation_sources
+    }
+
     /// Get all assigned IPs for debugging
     pub fn get_all_assigned_ips(&self) -> &HashMap<String, String> {
         &self.assigned_ips
@@ -224,13 +306,22 @@ impl GlobalIpRegistry {
         subnet_group: &str,
         agent_id: &str,
     ) -> Result<String, String> {
-        // Get or create subnet allocation for this group
+        // Get or create subnet allocation for this group; 100.64.0.0/16
+        // holds one /24 per group
+        if !self.subnet_groups.contains_key(subnet_group)
+            && self.next_subnet_group_id as usize >= MAX_SUBNET_GROUPS
+        {
+            return Err(format!(
+                "Cannot create subnet group '{}': 100.64.0.0/16 holds {} groups",
+                subnet_group, MAX_SUBNET_GROUPS
+            ));
+        }
         let (subnet_prefix, next_host) = self
             .subnet_groups
             .entry(subnet_group.to_string())
             .or_insert_with(|| {
                 let subnet_id = self.next_subnet_group_id;
-                self.next_subnet_group_id = self.next_subnet_group_id.wrapping_add(1);
+                self.next_subnet_group_id += 1;
                 // RFC 6598 (CGNAT) — public per epee, plenty of /24 capacity.
                 let prefix = format!("100.64.{}", subnet_id);
                 log::info!(
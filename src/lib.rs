@@ -7,11 +7,23 @@
 //!
 //! - `config` / `config_loader`: YAML config parsing and loading
 //! - `orchestrator`: High-level config generation coordination
+//! - `presets`: Scenario templates written by `monerosim init`
+//! - `manifest`: `manifest.json` provenance (config/GML hashes, version)
+//! - `validation_report`: `validation_report.json`, non-fatal findings
+//! - `generation_trace`: `generation_trace.json`, the decisions made and why
+//! - `resources`: Memory and file descriptor estimate of a planned simulation
 //! - `shadow`: Shadow YAML data structures
 //! - `ip`: IP address allocation with geographic distribution
 //! - `topology`: Network topology (switch, GML) and peer connections
 //! - `agent`: Agent config generation (miners, users, scripts)
 //! - `process`: Process/wrapper script generation
+//! - `registry`: Agent, public-node and miner registries in the shared dir
+//! - `healthcheck`: RPC readiness checks run by `monerosim-healthcheck`
+//! - `inspect`: Summary of a generated Shadow config (`monerosim inspect`)
+//! - `seeds`: Monero's fallback seed IPs, from the embedded snapshot or a
+//!   source tree, and checks of a config against them (`monerosim seeds`)
+//! - `sweep`: One generated simulation per combination of a config's
+//!   `sweep` axes, plus `sweep_index.json` (`monerosim sweep`)
 //! - `analysis`: Post-simulation log analysis
 //! - `utils`: Duration parsing, validation, seed extraction
 
@@ -36,12 +48,20 @@ pub fn default_daemon_data_dir() -> String {
 /// `time.time()` (Python) or log timestamps to get simulation-relative seconds.
 pub const SHADOW_EPOCH: f64 = 946_684_800.0;
 
+/// Layout version of the generated registries and `simulation_metadata.json`.
+/// Bump when a field the analyzer relies on is added, renamed or changes
+/// meaning, and teach `analysis::compat` what older versions lack.
+pub const ARTIFACT_SCHEMA_VERSION: u32 = 6;
+
 /// Monero P2P port (mainnet/regtest default).
 pub const MONERO_P2P_PORT: u16 = 18080;
 /// Monero daemon RPC port (mainnet/regtest default).
 pub const MONERO_RPC_PORT: u16 = 18081;
 /// Monero wallet RPC port (mainnet/regtest default).
 pub const MONERO_WALLET_RPC_PORT: u16 = 18082;
+/// monerod's P2P_DEFAULT_CONNECTIONS_COUNT, the outbound peers a daemon
+/// keeps when `--out-peers` is not set.
+pub const MONERO_DEFAULT_OUT_PEERS: u32 = 12;
 
 /// Default host bandwidth in bits/sec (1 Gbit/s).
 pub const DEFAULT_BANDWIDTH_BPS: &str = "1000000000";
@@ -52,18 +72,29 @@ pub const MALLOC_THRESHOLD: &str = "131072";
 // "override" was a no-op. We run at monerod's CLI default (1) instead.
 // See orchestrator.rs near monero_environment population for the
 // re-enable recipe (use daemon_defaults instead of an env var).
-/// IP offset for miner-distributor agents to avoid collision with user agents.
-pub const DISTRIBUTOR_IP_OFFSET: usize = 100;
-/// IP offset for pure-script agents.
-pub const SCRIPT_IP_OFFSET: usize = 200;
+/// Host name and registry id of the DNS server (`enable_dns_server`); no
+/// underscore, Shadow requires RFC-compliant host names.
+pub const DNS_SERVER_ID: &str = "dnsserver";
+/// Seconds after the DNS server starts that its watchdog checks port 53.
+pub const DNS_WATCHDOG_DELAY_SECS: u64 = 10;
 /// Delay (seconds) between daemon start and wallet start.
 pub const WALLET_STARTUP_DELAY_SECS: u64 = 2;
 /// Delay (seconds) between wallet start and agent script start.
 pub const AGENT_STARTUP_DELAY_SECS: u64 = 3;
+/// Delay (seconds) between a miner's wallet-registration script and its
+/// mining script.
+pub const MINING_SCRIPT_DELAY_SECS: u64 = 10;
 /// Max chars to preview when logging registry JSON.
 pub const REGISTRY_PREVIEW_CHARS: usize = 500;
+/// Agents with `capture_pcap` beyond which generation warns about the
+/// traces' disk usage.
+pub const PCAP_WARN_AGENTS: usize = 4;
+/// Blocks before a coinbase output can be spent (`CRYPTONOTE_MINED_MONEY_UNLOCK_WINDOW`).
+pub const COINBASE_MATURITY_BLOCKS: u64 = 60;
+/// Monero's target block interval (`DIFFICULTY_TARGET_V2`), in seconds.
+pub const TARGET_BLOCK_INTERVAL_SECS: u64 = 120;
 /// Monero coinbase maturity: 60 blocks at 120s each.
-pub const BLOCK_MATURITY_SECONDS: u64 = 7200;
+pub const BLOCK_MATURITY_SECONDS: u64 = COINBASE_MATURITY_BLOCKS * TARGET_BLOCK_INTERVAL_SECS;
 
 /// Mainnet fallback seed IPs hardcoded in monerod at
 /// `monero-shadow/src/p2p/net_node.inl:752-758`. These are the IPs monerod
@@ -76,6 +107,9 @@ pub const BLOCK_MATURITY_SECONDS: u64 = 7200;
 /// Monero source tree (`<repo>/sibling_repos/monero-shadow/src/p2p/net_node.inl`
 /// or sibling layouts; override with `MONERO_SRC_DIR`). This baked-in
 /// list is only used if the source isn't reachable on disk.
+///
+/// `monerosim seeds` prints it; `monerosim seeds --from-source` extracts a
+/// fresh list to compare and paste in when `monero.pin` moves.
 pub const MONERO_FALLBACK_SEED_IPS: [&str; 6] = [
     "176.9.0.187",
     "88.198.163.90",
@@ -85,6 +119,10 @@ pub const MONERO_FALLBACK_SEED_IPS: [&str; 6] = [
     "5.104.84.64",
 ];
 
+/// Monero release `MONERO_FALLBACK_SEED_IPS` was taken from; kept equal to
+/// `monero.pin`
+pub const MONERO_FALLBACK_SEED_VERSION: &str = "v0.18.5.1";
+
 /// Generate the agent ID for the Nth fallback seed (1-indexed).
 /// `seed_index = 1` → `"monero-seed-001"`.
 pub fn fallback_seed_agent_id(seed_index: usize) -> String {
@@ -95,10 +133,20 @@ pub mod agent;
 pub mod analysis;
 pub mod config;
 pub mod config_loader;
+pub mod generation_trace;
 pub mod gml_parser;
+pub mod healthcheck;
+pub mod inspect;
 pub mod ip;
+pub mod manifest;
 pub mod orchestrator;
+pub mod presets;
 pub mod process;
+pub mod registry;
+pub mod resources;
+pub mod seeds;
 pub mod shadow;
+pub mod sweep;
 pub mod topology;
 pub mod utils;
+pub mod validation_report;
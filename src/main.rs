@@ -1,44 +1,45 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use color_eyre::eyre::WrapErr;
 use color_eyre::Result;
 use env_logger::Env;
 use log::{info, warn};
 use std::fs;
-use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 
 // Use modules from the library instead of redeclaring them
+use monerosim::config::Config;
 use monerosim::config_loader;
-use monerosim::orchestrator::generate_agent_shadow_config;
-
-/// Recursively fix permissions on a directory tree to allow deletion.
-/// This handles cases where monero-wallet-rpc creates directories with
-/// restrictive permissions (d---------) that prevent normal rm -rf.
-fn fix_permissions_recursive(path: &Path) -> std::io::Result<()> {
-    if path.is_dir() {
-        // First, ensure we can read and traverse this directory
-        let mut perms = fs::metadata(path)?.permissions();
-        perms.set_mode(0o755);
-        fs::set_permissions(path, perms)?;
-
-        // Then recursively fix children
-        for entry in fs::read_dir(path)? {
-            let entry = entry?;
-            fix_permissions_recursive(&entry.path())?;
-        }
-    }
-    Ok(())
-}
+use monerosim::gml_parser::{validate_topology, write_gml};
+use monerosim::inspect::{find_agent_registry, load_agent_registry, load_shadow_config, summarize};
+use monerosim::orchestrator::{
+    collect_binary_paths, enforce_validation_report, generate_and_write, log_generation_summary,
+    plan_agent_shadow_config, regenerate_registries,
+};
+use monerosim::presets::{find_preset, write_preset, PresetParams, PRESETS};
+use monerosim::seeds::{embedded_snapshot, snapshot_from_source, verify_config};
+use monerosim::sweep::{generate_sweep, SWEEP_INDEX_FILE};
+use monerosim::topology::{generate_topology, TopologyParams};
+use monerosim::utils::validation::{validate_agent_scripts, validate_gml_ip_consistency};
+use monerosim::utils::{
+    prepare_directory, remove_dir_with_permissions, verify_binaries, CleanupOutcome, CleanupPolicy,
+    SimDuration,
+};
 
-/// Remove a directory tree, first fixing permissions if needed.
-fn remove_dir_with_permissions(path: &Path) -> std::io::Result<()> {
-    if path.exists() {
-        // Try normal removal first
-        if fs::remove_dir_all(path).is_err() {
-            // If it fails, fix permissions and try again
-            fix_permissions_recursive(path)?;
-            fs::remove_dir_all(path)?;
-        }
+/// Clear a previous run's directory, logging what was kept and why.
+fn clean_directory(path: &Path, policy: CleanupPolicy, what: &str) -> Result<()> {
+    let outcome = prepare_directory(path, policy)
+        .wrap_err_with(|| format!("Failed to clean {} '{}'", what, path.display()))?;
+    match outcome {
+        CleanupOutcome::Absent => {}
+        CleanupOutcome::Removed => info!("Removed previous {} '{}'", what, path.display()),
+        CleanupOutcome::Kept => info!("Keeping existing {} '{}'", what, path.display()),
+        CleanupOutcome::Preserved { foreign } => warn!(
+            "Not deleting {} '{}': it holds files monerosim did not generate ({}, ...). \
+             Generated files are written alongside them; pass --force to wipe it.",
+            what,
+            path.display(),
+            foreign.join(", ")
+        ),
     }
     Ok(())
 }
@@ -46,10 +47,14 @@ fn remove_dir_with_permissions(path: &Path) -> std::io::Result<()> {
 /// Configuration utility for Monero network simulations in Shadow
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Path to the simulation configuration YAML file
-    #[arg(short, long)]
-    config: PathBuf,
+    #[arg(short, long, required = true)]
+    config: Option<PathBuf>,
 
     /// Output directory for Shadow configuration and simulation files
     #[arg(short, long, default_value = "shadow_output")]
@@ -71,16 +76,409 @@ struct Args {
     /// Overrides
     /// `general.turnover.mean_session`. See --turnover-downtime / --turnover-max-session.
     #[arg(long)]
-    turnover_session: Option<String>,
+    turnover_session: Option<SimDuration>,
 
     /// Mean OFFLINE gap between turnover sessions (e.g. "30m"). See --turnover-session.
     #[arg(long)]
-    turnover_downtime: Option<String>,
+    turnover_downtime: Option<SimDuration>,
 
     /// Hard ceiling on any single turnover session (e.g. "6h"); omit to let the
     /// exponential tail run free. See --turnover-session.
     #[arg(long)]
-    turnover_max_session: Option<String>,
+    turnover_max_session: Option<SimDuration>,
+
+    /// Don't require the monerod / wallet binaries to exist on this machine
+    /// (for generating configs that will run elsewhere).
+    #[arg(long, alias = "allow-missing-binaries")]
+    skip_binary_check: bool,
+
+    /// Don't require agent scripts to exist under `general.agents_path`
+    /// (for containerized runs where scripts are only present at runtime).
+    #[arg(long)]
+    skip_script_checks: bool,
+
+    /// Validate the config and plan the simulation (GML, topology, mining,
+    /// IP allocation) without removing, creating or writing anything.
+    /// Prints what would be generated; exits non-zero on any failure.
+    #[arg(long, alias = "dry-run")]
+    check: bool,
+
+    /// Print the configuration as generation applies it (defaults filled
+    /// in, agent groups merged, CLI overrides applied) as JSON, including
+    /// an `ignored` list of configured fields that have no effect and why,
+    /// then exit. With --check, plan the simulation after printing.
+    #[arg(long)]
+    print_resolved_config: bool,
+
+    /// Don't delete the output or shared directory; generated files are
+    /// overwritten in place and everything else is left alone.
+    #[arg(long, conflicts_with = "force")]
+    keep_existing: bool,
+
+    /// Delete the output and shared directories even if they hold files
+    /// monerosim did not generate. By default such directories are kept.
+    #[arg(long)]
+    force: bool,
+
+    /// Write the registries and other runtime files to the output directory
+    /// only, without cleaning or populating the shared directory (for
+    /// people who stage it themselves)
+    #[arg(long)]
+    no_populate_shared: bool,
+
+    /// Treat every warning in the validation report as an error. Same as
+    /// `general.strict_validation: true`.
+    #[arg(long)]
+    strict: bool,
+
+    /// Log every decision generation made and why (GML placement, IP
+    /// source, seeds, start times), grouped by stage. The same decisions
+    /// are always written to `generation_trace.json`.
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Rewrite the config to the current `config_version` and exit,
+    /// printing the result unless --migrate-output is given. Comments are
+    /// not carried over.
+    #[arg(long)]
+    migrate: bool,
+
+    /// Where --migrate writes the migrated config. The input file is only
+    /// overwritten when this names it.
+    #[arg(long, requires = "migrate")]
+    migrate_output: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Write a ready-to-run configuration from a bundled preset into the
+    /// current directory
+    Init(InitArgs),
+    /// Write a synthetic GML topology, usable as `network.path`
+    GenTopology(GenTopologyArgs),
+    /// Rebuild the registries in the shared directory from an already
+    /// generated Shadow config, without rewriting it or cleaning anything
+    RegenRegistries(RegenRegistriesArgs),
+    /// Summarize a generated Shadow config: hosts, processes, start times
+    /// and seed nodes
+    Inspect(InspectArgs),
+    /// Print Monero's hardcoded mainnet fallback seed IPs, extract them
+    /// from a Monero source tree, or check a config's seeds against them
+    Seeds(SeedsArgs),
+    /// Generate one simulation per combination of the config's `sweep`
+    /// axes, each in its own directory, plus sweep_index.json
+    Sweep(SweepArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct InitArgs {
+    /// Preset to write: small-dev, research-50 or upgrade-ab
+    #[arg(long)]
+    preset: String,
+
+    /// Daemon-running agents, split between the preset's roles (default:
+    /// the preset's own size)
+    #[arg(long)]
+    nodes: Option<usize>,
+
+    /// Simulation duration, e.g. "6h" (default: the preset's own)
+    #[arg(long)]
+    stop_time: Option<SimDuration>,
+
+    /// Overwrite existing files
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct RegenRegistriesArgs {
+    /// The simulation configuration the Shadow config was generated from
+    #[arg(short, long)]
+    config: PathBuf,
+
+    /// The generated Shadow config whose hosts the registries follow
+    #[arg(long, default_value = "shadow_output/shadow_agents.yaml")]
+    hosts: PathBuf,
+}
+
+#[derive(clap::Args, Debug)]
+struct InspectArgs {
+    /// The generated Shadow config, e.g. shadow_output/shadow_agents.yaml
+    shadow_config: PathBuf,
+
+    /// Agent registry to cross-reference (default: the agent_registry.json
+    /// next to the Shadow config, if there is one)
+    #[arg(long)]
+    registry: Option<PathBuf>,
+
+    /// Print the summary as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct SeedsArgs {
+    /// Monero source tree (or its src/p2p/net_node.inl) to extract the
+    /// list from instead of using the embedded snapshot
+    #[arg(long)]
+    from_source: Option<PathBuf>,
+
+    /// Simulation config whose fallback-seed hosts and IP:PORT seed nodes
+    /// are checked against the list; exits non-zero on stale entries
+    #[arg(long)]
+    verify: Option<PathBuf>,
+
+    /// Print as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct SweepArgs {
+    /// Simulation config with a `sweep` section
+    #[arg(short, long)]
+    config: PathBuf,
+
+    /// Directory the variant directories and sweep_index.json go in
+    #[arg(long, default_value = "sweeps")]
+    output_root: PathBuf,
+
+    /// Don't require the monerod / wallet binaries to exist on this machine
+    #[arg(long, alias = "allow-missing-binaries")]
+    skip_binary_check: bool,
+
+    /// Don't require agent scripts to exist under `general.agents_path`
+    #[arg(long)]
+    skip_script_checks: bool,
+
+    /// Replace variant directories even if they hold files monerosim did
+    /// not generate
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct GenTopologyArgs {
+    /// Number of autonomous systems
+    #[arg(long, default_value_t = 10)]
+    as_groups: usize,
+
+    /// Network nodes in each autonomous system
+    #[arg(long, default_value_t = 5)]
+    nodes_per_as: usize,
+
+    /// Latency range of links inside an AS, in ms, as MIN-MAX
+    #[arg(long, default_value = "1-5", value_parser = parse_latency_range)]
+    intra_latency: (f64, f64),
+
+    /// Latency range of links between ASes, in ms, as MIN-MAX
+    #[arg(long, default_value = "20-150", value_parser = parse_latency_range)]
+    inter_latency: (f64, f64),
+
+    /// Comma-separated node bandwidth classes; each node draws one
+    #[arg(long, value_delimiter = ',', default_value = "100Mbit,1Gbit")]
+    bandwidth: Vec<String>,
+
+    /// Seed for link placement, latencies and bandwidths
+    #[arg(long, default_value_t = 12345)]
+    seed: u64,
+
+    /// Path of the GML file to write
+    #[arg(short, long)]
+    output: PathBuf,
+}
+
+/// Parse "MIN-MAX" (or a single value) in milliseconds
+fn parse_latency_range(s: &str) -> std::result::Result<(f64, f64), String> {
+    let parse = |v: &str| {
+        v.trim()
+            .parse::<f64>()
+            .map_err(|_| format!("invalid latency '{}' (expected MIN-MAX in ms)", v))
+    };
+    match s.split_once('-') {
+        Some((lo, hi)) => Ok((parse(lo)?, parse(hi)?)),
+        None => parse(s).map(|v| (v, v)),
+    }
+}
+
+/// `gen-topology`: generate, check with the same validators as a loaded
+/// GML file, and write.
+fn gen_topology(args: GenTopologyArgs) -> Result<()> {
+    let params = TopologyParams {
+        as_groups: args.as_groups,
+        nodes_per_as: args.nodes_per_as,
+        intra_latency_ms: args.intra_latency,
+        inter_latency_ms: args.inter_latency,
+        bandwidth_classes: args.bandwidth,
+        seed: args.seed,
+    };
+    let graph = generate_topology(&params).map_err(|e| color_eyre::eyre::eyre!("{}", e))?;
+    validate_topology(&graph).map_err(|e| color_eyre::eyre::eyre!("{}", e))?;
+    validate_gml_ip_consistency(&graph).map_err(|e| color_eyre::eyre::eyre!("{}", e))?;
+    fs::write(&args.output, write_gml(&graph))
+        .wrap_err_with(|| format!("Failed to write '{}'", args.output.display()))?;
+    println!(
+        "Wrote {} nodes and {} edges to {:?}; use it as `network.path`",
+        graph.nodes.len(),
+        graph.edges.len(),
+        args.output
+    );
+    Ok(())
+}
+
+/// `init`: write the preset and print how to run it
+fn init(args: InitArgs) -> Result<()> {
+    let preset = find_preset(&args.preset).map_err(|e| color_eyre::eyre::eyre!("{}", e))?;
+    let params = PresetParams {
+        nodes: args.nodes,
+        stop_time: args.stop_time,
+    };
+    let written = write_preset(preset, &params, Path::new("."), args.force)?;
+    for path in &written {
+        println!("Wrote {:?}", path);
+    }
+    let config = preset.config_file();
+    println!("\nNext steps:");
+    println!(
+        "  monerosim --config {} --check   # validate and estimate resources",
+        config
+    );
+    println!(
+        "  ./run_sim.sh --config {}        # generate and run in Shadow",
+        config
+    );
+    for note in preset.notes {
+        println!("  {}", note);
+    }
+    println!(
+        "Other presets: {}",
+        PRESETS
+            .iter()
+            .filter(|p| p.name != preset.name)
+            .map(|p| format!("{} ({})", p.name, p.summary))
+            .collect::<Vec<_>>()
+            .join("; ")
+    );
+    Ok(())
+}
+
+/// `regen-registries`: registries only, from the hosts of `--hosts`
+fn regen_registries(args: RegenRegistriesArgs) -> Result<()> {
+    let config = config_loader::load_config(&args.config)?;
+    let written = regenerate_registries(&config, &args.hosts)?;
+    for path in written {
+        println!("Wrote {:?}", path);
+    }
+    Ok(())
+}
+
+/// `inspect`: summarize a generated config without regenerating anything
+fn inspect(args: InspectArgs) -> Result<()> {
+    let config = load_shadow_config(&args.shadow_config)?;
+    let registry_path = find_agent_registry(&args.shadow_config, args.registry.as_deref());
+    let registry = registry_path
+        .as_deref()
+        .map(load_agent_registry)
+        .transpose()?;
+    let summary = summarize(&config, registry.as_ref().zip(registry_path.as_deref()))?;
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&summary).wrap_err("Failed to serialize summary")?
+        );
+    } else {
+        print!("{}", summary);
+    }
+    Ok(())
+}
+
+/// `seeds`: the snapshot, a fresh extraction, or a config checked against
+/// either
+fn seeds(args: SeedsArgs) -> Result<()> {
+    let snapshot = match &args.from_source {
+        Some(path) => snapshot_from_source(path)?,
+        None => embedded_snapshot(),
+    };
+    let Some(config_path) = &args.verify else {
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&snapshot)?);
+        } else {
+            print!("{}", snapshot);
+            let embedded = embedded_snapshot();
+            if args.from_source.is_some() && snapshot.seeds != embedded.seeds {
+                println!(
+                    "Differs from the embedded snapshot (monero {}); update \
+                     MONERO_FALLBACK_SEED_IPS and MONERO_FALLBACK_SEED_VERSION in src/lib.rs",
+                    monerosim::MONERO_FALLBACK_SEED_VERSION
+                );
+            }
+        }
+        return Ok(());
+    };
+
+    let config = config_loader::load_config(config_path)?;
+    let check = verify_config(&config, &snapshot)?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&check)?);
+    } else {
+        print!("{}", check);
+    }
+    let stale = check.stale().count();
+    if stale > 0 {
+        color_eyre::eyre::bail!(
+            "{} stale seed address(es) in {}",
+            stale,
+            config_path.display()
+        );
+    }
+    Ok(())
+}
+
+/// `sweep`: every variant generated, then the index
+fn sweep(args: SweepArgs) -> Result<()> {
+    let policy = if args.force {
+        CleanupPolicy::Force
+    } else {
+        CleanupPolicy::Auto
+    };
+    let index = generate_sweep(&args.config, &args.output_root, policy, |config| {
+        check_inputs(config, args.skip_binary_check, args.skip_script_checks)
+    })?;
+    for variant in &index.variants {
+        println!("{}", args.output_root.join(&variant.dir).display());
+    }
+    println!(
+        "Generated {} variants; index in {}",
+        index.variants.len(),
+        args.output_root.join(SWEEP_INDEX_FILE).display()
+    );
+    Ok(())
+}
+
+/// Fail on missing binaries or agent scripts before touching anything on
+/// disk; otherwise every Shadow process just exits instantly at simulation
+/// start, and a mistyped agent script only shows up as a Python import
+/// error deep in the Shadow logs.
+fn check_inputs(config: &Config, skip_binary_check: bool, skip_script_checks: bool) -> Result<()> {
+    if skip_binary_check {
+        warn!("Skipping binary check (--skip-binary-check)");
+    } else {
+        let binaries = collect_binary_paths(config)?;
+        verify_binaries(&binaries).map_err(|e| color_eyre::eyre::eyre!("{}", e))?;
+        info!("Verified {} binaries", binaries.len());
+    }
+
+    if skip_script_checks {
+        warn!("Skipping agent script check (--skip-script-checks)");
+    } else {
+        let agents_path = match &config.general.agents_path {
+            Some(path) => PathBuf::from(path),
+            None => std::env::current_dir().wrap_err("Failed to get current directory")?,
+        };
+        validate_agent_scripts(&config.agents.agents, &agents_path)
+            .map_err(|e| color_eyre::eyre::eyre!("Agent script error: {}", e))?;
+    }
+    Ok(())
 }
 
 fn main() -> Result<()> {
@@ -88,12 +486,33 @@ fn main() -> Result<()> {
     let args = Args::parse();
     env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
 
+    match args.command {
+        Some(Command::Init(init_args)) => return init(init_args),
+        Some(Command::GenTopology(gen_args)) => return gen_topology(gen_args),
+        Some(Command::RegenRegistries(regen_args)) => return regen_registries(regen_args),
+        Some(Command::Inspect(inspect_args)) => return inspect(inspect_args),
+        Some(Command::Seeds(seeds_args)) => return seeds(seeds_args),
+        Some(Command::Sweep(sweep_args)) => return sweep(sweep_args),
+        None => {}
+    }
+    let config_path = args
+        .config
+        .expect("clap requires --config without a subcommand");
+
+    if args.migrate {
+        let yaml = config_loader::migrate_config(&config_path, args.migrate_output.as_deref())?;
+        if args.migrate_output.is_none() {
+            print!("{}", yaml);
+        }
+        return Ok(());
+    }
+
     info!("Starting MoneroSim configuration parser v2");
-    info!("Configuration file: {:?}", args.config);
+    info!("Configuration file: {:?}", config_path);
     info!("Output directory: {:?}", args.output);
 
     // Load configuration using new system
-    let mut new_config = config_loader::load_config(&args.config)?;
+    let mut new_config = config_loader::load_config(&config_path)?;
 
     // CLI override: --reachable sets the global reachable fraction, beating
     // general.reachable_fraction from the config file.
@@ -108,6 +527,10 @@ fn main() -> Result<()> {
         new_config.general.reachable_fraction = r;
     }
 
+    if args.strict {
+        new_config.general.strict_validation = true;
+    }
+
     // CLI: --turnover-* enable or override peer turnover. Any of these flags
     // switches turnover on (with defaults) when the config has no [general.turnover].
     if args.turnover_session.is_some()
@@ -119,8 +542,8 @@ fn main() -> Result<()> {
                 .general
                 .turnover
                 .get_or_insert_with(|| monerosim::config::TurnoverConfig {
-                    mean_session: "2h".to_string(),
-                    mean_downtime: "30m".to_string(),
+                    mean_session: SimDuration::from_secs(2 * 3600),
+                    mean_downtime: SimDuration::from_secs(30 * 60),
                     fraction: 1.0,
                     min_session: None,
                     max_session: None,
@@ -136,11 +559,25 @@ fn main() -> Result<()> {
             c.max_session = Some(m);
         }
         info!(
-            "CLI turnover: mean_session={} mean_downtime={} max_session={:?} fraction={}",
-            c.mean_session, c.mean_downtime, c.max_session, c.fraction
+            "CLI turnover: mean_session={} mean_downtime={} max_session={} fraction={}",
+            c.mean_session,
+            c.mean_downtime,
+            c.max_session.map_or("none".to_string(), |m| m.to_string()),
+            c.fraction
         );
     }
 
+    if args.print_resolved_config {
+        let resolved = monerosim::config::resolve(&new_config);
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&resolved).wrap_err("Failed to serialize config")?
+        );
+        if !args.check {
+            return Ok(());
+        }
+    }
+
     // Determine output directory and final config path
     let (output_dir, shadow_config_path) =
         if args.output.extension().map_or(false, |ext| ext == "yaml") {
@@ -155,27 +592,55 @@ fn main() -> Result<()> {
             (args.output.clone(), args.output.join("shadow_agents.yaml"))
         };
 
-    // Clean up previous simulation state
-    info!("Cleaning up previous simulation state");
-    if output_dir.exists() {
-        // Only clean if it's not the current directory
-        if output_dir != Path::new(".") {
-            remove_dir_with_permissions(&output_dir).wrap_err_with(|| {
-                format!(
-                    "Failed to remove output directory '{}'",
-                    output_dir.display()
-                )
-            })?;
+    check_inputs(&new_config, args.skip_binary_check, args.skip_script_checks)?;
+
+    if args.check {
+        let plan = plan_agent_shadow_config(&new_config, &shadow_config_path)?;
+        enforce_validation_report(&plan)?;
+        if args.verbose {
+            plan.trace.log();
         }
+        log_generation_summary(&new_config, &plan, false);
+        return Ok(());
+    }
+
+    // Clean up previous simulation state. Only directories holding a
+    // previous run's generated files are wiped unless --force is given.
+    let policy = if args.force {
+        CleanupPolicy::Force
+    } else if args.keep_existing {
+        CleanupPolicy::KeepExisting
+    } else {
+        CleanupPolicy::Auto
+    };
+    info!("Cleaning up previous simulation state");
+    // Never remove the current directory
+    if output_dir != Path::new(".") {
+        clean_directory(&output_dir, policy, "output directory")?;
     }
     let shared_dir = Path::new(&new_config.general.shared_dir);
-    remove_dir_with_permissions(shared_dir).wrap_err("Failed to remove shared directory")?;
+    if args.no_populate_shared {
+        info!(
+            "Leaving shared directory '{}' alone (--no-populate-shared)",
+            shared_dir.display()
+        );
+    } else {
+        clean_directory(shared_dir, policy, "shared directory")?;
+    }
 
     // Clean up per-agent data directories from previous runs ({daemon_data_dir}/monero-*)
     // This replaces the per-agent `rm -rf {daemon_data_dir}/monero-{id}` that was previously
     // done inside each daemon's bash wrapper at simulation startup.
+    // `fresh_blockchain: false` keeps them: generation then reuses or
+    // replaces them with the chain data from `blockchain_source_dir`.
     let daemon_data_dir = Path::new(&new_config.general.daemon_data_dir);
-    if let Ok(entries) = fs::read_dir(daemon_data_dir) {
+    let warm_start = new_config.general.fresh_blockchain == Some(false);
+    if warm_start {
+        info!(
+            "Keeping daemon data directories in {} (fresh_blockchain: false)",
+            daemon_data_dir.display()
+        );
+    } else if let Ok(entries) = fs::read_dir(daemon_data_dir) {
         for entry in entries.flatten() {
             let name = entry.file_name();
             let name_str = name.to_string_lossy();
@@ -204,20 +669,32 @@ fn main() -> Result<()> {
             output_dir.display()
         )
     })?;
-    fs::create_dir_all(shared_dir).wrap_err("Failed to create shared directory")?;
+    if !args.no_populate_shared {
+        fs::create_dir_all(shared_dir).wrap_err("Failed to create shared directory")?;
+    }
 
     // Generate agent-based Shadow configuration
     info!("Running in agent-based simulation mode");
-    generate_agent_shadow_config(&new_config, &shadow_config_path)?;
+    let result = generate_and_write(
+        &new_config,
+        Some(&config_path),
+        &shadow_config_path,
+        !args.no_populate_shared,
+    )?;
+    if args.verbose {
+        result.plan.trace.log();
+    }
 
     info!(
         "Generated Agent-based Shadow configuration: {:?}",
         shadow_config_path
     );
 
+    // Shadow resolves the relative topology.gml against its working directory
     info!(
-        "Ready to run Shadow simulation with: shadow {:?}",
-        shadow_config_path
+        "Ready to run Shadow simulation with: cd {:?} && shadow {:?}",
+        output_dir,
+        shadow_config_path.file_name().unwrap_or_default()
     );
 
     info!("Configuration parsing completed successfully");
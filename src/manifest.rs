@@ -0,0 +1,203 @@
+//! `manifest.json`: provenance of a generated simulation.
+//!
+//! Written next to `shadow_agents.yaml` so an output directory can be traced
+//! back to the config file, GML topology and monerosim version that produced
+//! it. `tx-analyzer` reads it back (`--manifest`) and embeds it in report
+//! metadata.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use color_eyre::eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{resolve, BlockControllerConfig, Chain, Config, Network};
+use crate::resources::ResourceEstimate;
+use crate::topology::{MinerLocation, RealizedDistribution, ScheduledNetworkEvent};
+use crate::utils::duration::SimDuration;
+use crate::utils::hash::sha256_hex;
+
+/// File name of the manifest in the output directory
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// An input file and the SHA-256 of its contents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HashedFile {
+    pub path: String,
+    pub sha256: String,
+}
+
+impl HashedFile {
+    pub fn read(path: &Path) -> Result<Self> {
+        let bytes =
+            fs::read(path).wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+        Ok(HashedFile {
+            path: path.display().to_string(),
+            sha256: sha256_hex(&bytes),
+        })
+    }
+}
+
+/// A binary the simulation launches and the first line of its `--version`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BinaryVersion {
+    pub path: String,
+    /// Absent when the binary couldn't be run (e.g. `--skip-binary-check`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationManifest {
+    /// monerosim crate version that generated the simulation
+    pub generator_version: String,
+    /// `ARTIFACT_SCHEMA_VERSION` at generation time
+    pub schema_version: u32,
+    /// RFC 3339 generation time
+    pub generated_at: String,
+    /// The YAML config; absent when the config was built in memory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<HashedFile>,
+    /// The GML topology, for GML networks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gml: Option<HashedFile>,
+    pub simulation_seed: u64,
+    pub stop_time: SimDuration,
+    /// `general.chain`; regtest for manifests from before it existed
+    #[serde(default)]
+    pub chain: Chain,
+    /// `block_controller.block_interval` in seconds; 120 for manifests from
+    /// before it existed
+    #[serde(default = "default_block_interval")]
+    pub block_interval: u64,
+    /// Every monerod / monero-wallet-rpc the simulation launches
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub binary_versions: Vec<BinaryVersion>,
+    /// Peak memory and file descriptors the simulation is expected to need,
+    /// and what the generating machine offered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resources: Option<ResourceEstimate>,
+    /// Each miner's GML node and AS, on GML topologies
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub miner_placement: BTreeMap<String, MinerLocation>,
+    /// Agents per region or AS, when `network.geographic_distribution` or
+    /// `as_weights` set their shares
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_distribution: Option<RealizedDistribution>,
+    /// Scheduled partitions and heals (`network.events`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub network_events: Vec<ScheduledNetworkEvent>,
+    /// Agents Shadow writes pcap traces for, when any set `capture_pcap`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pcap_capture: Option<PcapCapture>,
+    /// `--print-resolved-config` output: the config after defaults, plus the
+    /// fields generation ignored
+    pub resolved_config: serde_json::Value,
+}
+
+/// Packet capture of a simulation: who is captured and where the traces
+/// end up
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PcapCapture {
+    /// Agents with `capture_pcap: true`, each on its own Shadow host
+    pub agents: Vec<String>,
+    /// `general.pcap_capture_dir`; absent when the traces stay in Shadow's
+    /// host directories
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capture_dir: Option<String>,
+}
+
+fn default_block_interval() -> u64 {
+    crate::TARGET_BLOCK_INTERVAL_SECS
+}
+
+impl SimulationManifest {
+    /// Describe `config`, hashing `config_path` (the file it was loaded from)
+    /// and the GML file it references. `binary_versions`, `resources`,
+    /// `miner_placement`, `agent_distribution`, `network_events` and
+    /// `pcap_capture` are left for the caller, which has already planned the
+    /// simulation.
+    pub fn new(config: &Config, config_path: Option<&Path>) -> Result<Self> {
+        let gml = match &config.network {
+            Some(Network::Gml { path, .. }) => Some(HashedFile::read(Path::new(path))?),
+            _ => None,
+        };
+        let block_interval = BlockControllerConfig::interval_secs(config.block_controller.as_ref())
+            .map_err(|e| color_eyre::eyre::eyre!(e))?;
+        Ok(SimulationManifest {
+            generator_version: env!("CARGO_PKG_VERSION").to_string(),
+            schema_version: crate::ARTIFACT_SCHEMA_VERSION,
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            config: config_path.map(HashedFile::read).transpose()?,
+            gml,
+            simulation_seed: config.general.simulation_seed,
+            stop_time: config.general.stop_time,
+            chain: config.general.chain,
+            block_interval,
+            binary_versions: Vec::new(),
+            resources: None,
+            miner_placement: BTreeMap::new(),
+            agent_distribution: None,
+            network_events: Vec::new(),
+            pcap_capture: None,
+            resolved_config: serde_json::to_value(resolve(config))
+                .wrap_err("Failed to serialize resolved config")?,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read manifest {}", path.display()))?;
+        serde_json::from_str(&content)
+            .wrap_err_with(|| format!("Failed to parse manifest {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn manifest_for(dir: &TempDir, name: &str, yaml: &str) -> SimulationManifest {
+        let path = dir.path().join(name);
+        fs::write(&path, yaml).unwrap();
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        SimulationManifest::new(&config, Some(&path)).unwrap()
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let dir = TempDir::new().unwrap();
+        let manifest = manifest_for(&dir, "a.yaml", "general:\n  stop_time: 1h\nagents: {}\n");
+        let path = dir.path().join(MANIFEST_FILE);
+        fs::write(&path, serde_json::to_string_pretty(&manifest).unwrap()).unwrap();
+        assert_eq!(SimulationManifest::load(&path).unwrap(), manifest);
+        assert_eq!(manifest.stop_time.to_string(), "1h");
+        assert_eq!(
+            manifest.resolved_config["config"]["general"]["stop_time"],
+            "1h"
+        );
+        assert!(manifest.gml.is_none());
+        assert_eq!(manifest.block_interval, crate::TARGET_BLOCK_INTERVAL_SECS);
+
+        let fast = manifest_for(
+            &dir,
+            "fast.yaml",
+            "general:\n  stop_time: 1h\nblock_controller:\n  block_interval: 30s\nagents: {}\n",
+        );
+        assert_eq!(fast.block_interval, 30);
+    }
+
+    #[test]
+    fn hashes_follow_the_config_contents() {
+        let dir = TempDir::new().unwrap();
+        let a = manifest_for(&dir, "a.yaml", "general:\n  stop_time: 1h\nagents: {}\n");
+        let same = manifest_for(&dir, "b.yaml", "general:\n  stop_time: 1h\nagents: {}\n");
+        let changed = manifest_for(&dir, "c.yaml", "general:\n  stop_time: 2h\nagents: {}\n");
+        let hash = |m: &SimulationManifest| m.config.as_ref().unwrap().sha256.clone();
+        assert_eq!(hash(&a), hash(&same));
+        assert_ne!(hash(&a), hash(&changed));
+        assert_eq!(hash(&a).len(), 64);
+    }
+}
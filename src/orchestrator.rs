@@ -3,38 +3,169 @@
 //! This module coordinates the overall configuration generation process,
 //! managing the flow from configuration parsing through Shadow YAML generation.
 
+use crate::agent::distribution_plan::{
+    apply_recipient_selector, build_distribution_plan, DISTRIBUTION_PLAN_FILE,
+};
+use crate::agent::wallet_keys::{build_wallet_bootstraps, wallet_keys_file};
+use crate::agent::workload::{
+    build_workloads, summarize_workloads, workload_file, WorkloadSummary,
+};
 use crate::agent::{
-    prepare_fallback_seeds, process_miner_distributor, process_pure_script_agents,
-    process_simulation_monitor, process_user_agents, UserAgentProcessContext,
+    find_miner_distributor, prepare_fallback_seeds, process_miner_distributor,
+    process_pure_script_agents, process_simulation_monitor, process_user_agents,
+    UserAgentProcessContext,
+};
+use crate::config::{
+    resolve, resolve_miner_weights, AgentDefinitions, BlockControllerConfig, Chain, Config,
+    FallbackSeedsMode, GeneralConfig, IgnoredKind, Network, NetworkSettings,
+};
+use crate::generation_trace::{
+    GenerationTrace, GENERATION_TRACE_FILE, STAGE_IP_ALLOCATION, STAGE_SCHEDULING, STAGE_SEEDS,
+    STAGE_TOPOLOGY,
 };
-use crate::config::{Config, DistributionStrategy, Network, PeerMode, RegionWeights};
-use crate::gml_parser::{self, get_autonomous_systems, validate_topology, GmlGraph};
+use crate::gml_parser::{
+    self, get_autonomous_systems, quote_gml_string, validate_topology, GmlGraph,
+};
+use crate::ip::as_manager::{parse_subnet_size, DEFAULT_AS_PREFIX};
 use crate::ip::{get_agent_ip, AgentType, AsSubnetManager, GlobalIpRegistry};
+use crate::manifest::{BinaryVersion, PcapCapture, SimulationManifest, MANIFEST_FILE};
+use crate::process::{
+    assign_agent_ports, log_level_arg, partition_processes, PartitionDaemon, StartupScheduler,
+    StartupSettings, STARTUP_SCHEDULE_FILE,
+};
+use crate::registry::{
+    build_registries, load_shadow_hosts, Registries, RegistryContext, AGENT_REGISTRY_FILE,
+    MINER_REGISTRY_FILE,
+};
+use crate::resources::{
+    estimate_resources, MachineResources, ProcessKind, ResourceEstimate, ResourceModel,
+};
 use crate::shadow::{
-    AgentInfo, AgentRegistry, MinerInfo, MinerRegistry, PublicNodeInfo, PublicNodeRegistry,
+    AgentRegistry, BinaryMetadata, MinerRegistry, NodeRestart, PublicNodeRegistry, RestartManifest,
     ShadowConfig, ShadowExperimental, ShadowFileSource, ShadowGeneral, ShadowGraph, ShadowHost,
-    ShadowNetwork,
+    ShadowHostOptions, ShadowNetwork, SimulationMetadata,
+};
+use crate::topology::intended::{build_intended_topology, INTENDED_TOPOLOGY_FILE};
+use crate::topology::latency::{agent_latency_matrix, LATENCY_MATRIX_FILE};
+use crate::topology::{
+    link_profile_graph, prune_to_nodes, MinerLocation, RealizedDistribution, ScheduledNetworkEvent,
+    SeedReason, TopologyConnections, DEFAULT_SEED_COUNT,
 };
-use crate::topology::Topology;
-use crate::utils::duration::parse_duration_to_seconds;
-use crate::utils::validation::{validate_gml_ip_consistency, validate_topology_config};
+use crate::utils::binary::{capture_version, BinaryResolver};
+use crate::utils::duration::SimDuration;
+use crate::utils::limits::ProcessLimits;
+use crate::utils::script::ScriptSet;
+use crate::utils::validation::{
+    canonicalize_gml_units, unrecognized_attribute_warnings, validate_gml_ip_consistency,
+    validate_topology_config,
+};
+use crate::utils::warm_start::{plan_warm_start, seed_data_dirs, WarmStartPlan};
+use crate::validation_report::{Severity, ValidationReport, VALIDATION_REPORT_FILE};
+use rayon::prelude::*;
 use serde_json;
 use serde_yaml;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write as _;
 use std::fs;
-use std::path::Path;
-
-/// Convert a bandwidth string like "10Gbit" or "500Mbit" to a numeric Mbit string.
-/// Passes through values that are already plain numbers.
-fn convert_bandwidth_value(value: &str) -> String {
-    if value.ends_with("Gbit") {
-        if let Ok(gbit) = value.trim_end_matches("Gbit").parse::<f64>() {
-            return format!("{}", gbit * 1000.0);
+use std::path::{Path, PathBuf};
+
+/// Every binary the generated config will launch, as resolved path -> the
+/// agents using it. Mirrors the per-agent resolution in `process_user_agents`.
+pub fn collect_binary_paths(
+    config: &Config,
+) -> color_eyre::eyre::Result<BTreeMap<String, Vec<String>>> {
+    let resolver = BinaryResolver::new(config.general.binaries.as_ref());
+    let mut binaries: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut add = |spec: &str, agent_id: &str| -> color_eyre::eyre::Result<()> {
+        let path = resolver.resolve(spec).map_err(|e| {
+            color_eyre::eyre::eyre!("Agent '{}': binary '{}': {}", agent_id, spec, e)
+        })?;
+        binaries.entry(path).or_default().push(agent_id.to_string());
+        Ok(())
+    };
+
+    for (agent_id, agent) in &config.agents.agents {
+        if let Some(phases) = agent.effective_daemon_phases() {
+            for phase in phases.values() {
+                add(&phase.path, agent_id)?;
+            }
+        } else if let Some(crate::config::DaemonConfig::Local(spec)) = &agent.daemon {
+            add(spec, agent_id)?;
+        }
+        if let Some(phases) = agent.wallet_phases.as_ref().filter(|p| !p.is_empty()) {
+            for phase in phases.values() {
+                add(&phase.path, agent_id)?;
+            }
+        } else if let Some(spec) = &agent.wallet {
+            add(spec, agent_id)?;
         }
-    } else if value.ends_with("Mbit") {
-        return value.trim_end_matches("Mbit").to_string();
     }
-    value.to_string()
+    // Auto-injected fallback seed hosts run the default monerod.
+    if matches!(config.general.fallback_seeds, FallbackSeedsMode::Auto) {
+        add("monerod", "fallback seeds")?;
+    }
+    for users in binaries.values_mut() {
+        users.dedup();
+    }
+    Ok(binaries)
+}
+
+/// `upgrade_manifest.json` for agents with `restarts` (None if no daemon
+/// restarts). Binaries are resolved as in `collect_binary_paths`.
+pub fn build_restart_manifest(
+    config: &Config,
+) -> color_eyre::eyre::Result<Option<RestartManifest>> {
+    let resolver = BinaryResolver::new(config.general.binaries.as_ref());
+    let mut first_binaries = BTreeSet::new();
+    let mut last_binaries = BTreeSet::new();
+    let mut upgrades = Vec::new();
+    for (agent_id, agent) in &config.agents.agents {
+        let (Some(restarts), Some(phases)) = (&agent.restarts, agent.restart_phases()) else {
+            continue;
+        };
+        let binaries = phases
+            .values()
+            .map(|phase| {
+                resolver.resolve(&phase.path).map_err(|e| {
+                    color_eyre::eyre::eyre!("Agent '{}': binary '{}': {}", agent_id, phase.path, e)
+                })
+            })
+            .collect::<color_eyre::eyre::Result<Vec<_>>>()?;
+        let at = |time: SimDuration| crate::SHADOW_EPOCH + time.as_secs() as f64;
+        for (i, restart) in restarts.iter().enumerate() {
+            let upgrade_end = at(restart.start_at);
+            upgrades.push(NodeRestart {
+                node_id: agent_id.clone(),
+                timestamp: upgrade_end,
+                version: binaries[i + 1].clone(),
+                previous_version: binaries[i].clone(),
+                upgrade_start: at(restart.stop_at),
+                upgrade_end,
+            });
+        }
+        first_binaries.extend(binaries.first().cloned());
+        last_binaries.extend(binaries.last().cloned());
+    }
+    if upgrades.is_empty() {
+        return Ok(None);
+    }
+    let shared = |binaries: BTreeSet<String>| match binaries.len() {
+        1 => binaries.into_iter().next(),
+        _ => None,
+    };
+    Ok(Some(RestartManifest {
+        pre_upgrade_version: shared(first_binaries),
+        post_upgrade_version: shared(last_binaries),
+        upgrade_start: upgrades
+            .iter()
+            .map(|u| u.upgrade_start)
+            .fold(f64::MAX, f64::min),
+        upgrade_end: upgrades
+            .iter()
+            .map(|u| u.upgrade_end)
+            .fold(f64::MIN, f64::max),
+        upgrades,
+    }))
 }
 
 /// Detect the Python site-packages path in the virtual environment.
@@ -56,11 +187,21 @@ fn detect_venv_site_packages(base_dir: &str) -> Option<String> {
     None
 }
 
-/// Generate Shadow network configuration from GML graph
+/// File name of the converted GML, in the output directory
+pub const GML_FILE_NAME: &str = "topology.gml";
+
+/// Generate Shadow network configuration from GML graph. The converted
+/// `topology.gml` is appended to `files` rather than written directly.
+///
+/// It lives next to the Shadow config and is referenced by its bare file
+/// name. Shadow resolves `network.graph.file.path` against its working
+/// directory, not the config file, so Shadow must be started from the
+/// output directory (as `run_sim.sh` does).
 pub fn generate_gml_network_config(
     gml_graph: &GmlGraph,
     _gml_path: &str,
     output_dir: &Path,
+    files: &mut Vec<(PathBuf, String)>,
 ) -> color_eyre::eyre::Result<ShadowGraph> {
     // Validate the topology first
     validate_topology(gml_graph)
@@ -70,76 +211,94 @@ pub fn generate_gml_network_config(
     validate_gml_ip_consistency(gml_graph)
         .map_err(|e| color_eyre::eyre::eyre!("GML IP validation failed: {}", e))?;
 
-    // Create a GML file with converted attributes (e.g., packet_loss percentages to floats)
+    // Create a GML file from the loaded graph, whose units are already canonical
     // Place in output directory alongside the Shadow config for locality and cleanup
-    let temp_gml_path = output_dir
-        .join("topology.gml")
-        .to_string_lossy()
-        .to_string();
-
-    let mut gml_content = String::new();
-    gml_content.push_str("graph [\n");
-
-    // Add graph attributes
-    for (key, value) in &gml_graph.attributes {
-        gml_content.push_str(&format!("  {} {}\n", key, value));
-    }
-
-    // Add nodes
-    for node in &gml_graph.nodes {
-        gml_content.push_str("  node [\n");
-        gml_content.push_str(&format!("    id {}\n", node.id));
-        if let Some(label) = &node.label {
-            gml_content.push_str(&format!("    label \"{}\"\n", label));
-        }
-        for (key, value) in &node.attributes {
-            let (processed_value, needs_quotes) = if key == "bandwidth" {
-                (convert_bandwidth_value(value), false)
+    let gml_path = output_dir.join(GML_FILE_NAME);
+
+    // Units were canonicalized on load; packet loss is a bare fraction.
+    // Attributes from nested blocks (`graphics.x`) mean nothing to Shadow,
+    // whose GML keys cannot contain the separator, so they are left out.
+    // Keys are sorted so the file is stable.
+    fn push_attributes(content: &mut String, attributes: &HashMap<String, String>) {
+        let mut sorted: Vec<_> = attributes
+            .iter()
+            .filter(|(key, _)| !key.contains(gml_parser::NESTED_KEY_SEPARATOR))
+            .collect();
+        sorted.sort_unstable();
+        for (key, value) in sorted {
+            let _ = if key == "packet_loss" {
+                writeln!(content, "    {} {}", key, value)
             } else {
-                (value.clone(), true)
+                writeln!(content, "    {} {}", key, quote_gml_string(value))
             };
-            if needs_quotes {
-                gml_content.push_str(&format!("    {} \"{}\"\n", key, processed_value));
-            } else {
-                gml_content.push_str(&format!("    {} {}\n", key, processed_value));
-            }
         }
-        gml_content.push_str("  ]\n");
     }
 
-    // Add edges with converted attributes
-    for edge in &gml_graph.edges {
-        gml_content.push_str("  edge [\n");
-        gml_content.push_str(&format!("    source {}\n", edge.source));
-        gml_content.push_str(&format!("    target {}\n", edge.target));
-        for (key, value) in &edge.attributes {
-            let (processed_value, needs_quotes) = if key == "packet_loss" {
-                (value.clone(), false)
-            } else if key == "bandwidth" {
-                (convert_bandwidth_value(value), false)
-            } else {
-                (value.clone(), true)
-            };
-            if needs_quotes {
-                gml_content.push_str(&format!("    {} \"{}\"\n", key, processed_value));
-            } else {
-                gml_content.push_str(&format!("    {} {}\n", key, processed_value));
+    // Nodes and edges are rendered in parallel chunks, then joined into a
+    // buffer sized for the whole file: large CAIDA graphs run to tens of
+    // thousands of nodes
+    const CHUNK: usize = 1024;
+    let nodes: Vec<String> = gml_graph
+        .nodes
+        .par_chunks(CHUNK)
+        .map(|chunk| {
+            let mut content = String::new();
+            for node in chunk {
+                let _ = writeln!(content, "  node [\n    id {}", node.id);
+                if let Some(label) = &node.label {
+                    let _ = writeln!(content, "    label {}", quote_gml_string(label));
+                }
+                push_attributes(&mut content, &node.attributes);
+                content.push_str("  ]\n");
             }
-        }
-        gml_content.push_str("  ]\n");
-    }
+            content
+        })
+        .collect();
+    let edges: Vec<String> = gml_graph
+        .edges
+        .par_chunks(CHUNK)
+        .map(|chunk| {
+            let mut content = String::new();
+            for edge in chunk {
+                let _ = writeln!(
+                    content,
+                    "  edge [\n    source {}\n    target {}",
+                    edge.source, edge.target
+                );
+                push_attributes(&mut content, &edge.attributes);
+                content.push_str("  ]\n");
+            }
+            content
+        })
+        .collect();
 
+    let mut graph_attributes: Vec<_> = gml_graph
+        .attributes
+        .iter()
+        .filter(|(key, _)| !key.contains(gml_parser::NESTED_KEY_SEPARATOR))
+        .collect();
+    graph_attributes.sort_unstable();
+    let size = nodes.iter().chain(&edges).map(String::len).sum::<usize>() + 1024;
+    let mut gml_content = String::with_capacity(size);
+    gml_content.push_str("graph [\n");
+    for (key, value) in graph_attributes {
+        let _ = if value.parse::<f64>().is_ok() {
+            writeln!(gml_content, "  {} {}", key, value)
+        } else {
+            writeln!(gml_content, "  {} {}", key, quote_gml_string(value))
+        };
+    }
+    for chunk in nodes.into_iter().chain(edges) {
+        gml_content.push_str(&chunk);
+    }
     gml_content.push_str("]\n");
 
-    // Write the temporary GML file
-    std::fs::write(&temp_gml_path, &gml_content)
-        .map_err(|e| color_eyre::eyre::eyre!("Failed to write temporary GML file: {}", e))?;
+    let path = GML_FILE_NAME.to_string();
+    files.push((gml_path, gml_content));
 
     Ok(ShadowGraph {
         graph_type: "gml".to_string(),
-        file: Some(ShadowFileSource {
-            path: temp_gml_path,
-        }),
+        file: Some(ShadowFileSource { path }),
         nodes: None,
         edges: None,
     })
@@ -207,6 +366,11 @@ fn compose_base_environment(
             "MONEROSIM_DAEMON_DATA_DIR".to_string(),
             config.general.daemon_data_dir.clone(),
         ),
+        // `general.chain`, for wallets and agent scripts' `--network`
+        (
+            "MONEROSIM_CHAIN".to_string(),
+            config.general.chain.name().to_string(),
+        ),
     ]
     .iter()
     .cloned()
@@ -243,8 +407,7 @@ fn compose_base_environment(
         // This ensures the DNS server is reachable from all other nodes via the GML topology
         let dns_ip = get_agent_ip(
             AgentType::Infrastructure,
-            "dnsserver",
-            0, // agent index
+            crate::DNS_SERVER_ID,
             0, // network_node_id 0
             gml_graph,
             gml_graph.is_some(), // using_gml_topology
@@ -274,68 +437,14 @@ fn compose_base_environment(
     ))
 }
 
-/// Extract peer mode, configured seed-node list, topology, and GML
-/// distribution config from the optional `network` block. The defaults match
-/// the legacy fall-throughs (Dynamic peer mode, no seeds, DAG topology, no
-/// distribution overrides).
-fn extract_network_topology_config(
-    config: &Config,
-) -> (
-    PeerMode,
-    Vec<String>,
-    Option<Topology>,
-    Option<DistributionStrategy>,
-    Option<RegionWeights>,
-) {
-    match &config.network {
-        Some(Network::Gml {
-            peer_mode,
-            seed_nodes,
-            topology,
-            distribution,
-            ..
-        }) => {
-            let mode = peer_mode.as_ref().unwrap_or(&PeerMode::Dynamic).clone();
-            let seeds = seed_nodes.as_ref().unwrap_or(&Vec::new()).clone();
-            let topo = topology.as_ref().unwrap_or(&Topology::Dag).clone();
-            // Extract distribution config (defaults to Global strategy)
-            let (strategy, weights) = match distribution {
-                Some(dist) => (Some(dist.strategy.clone()), dist.weights.clone()),
-                None => (None, None), // Will default to Global in distribution.rs
-            };
-            (mode, seeds, Some(topo), strategy, weights)
-        }
-        Some(Network::Switch {
-            peer_mode,
-            seed_nodes,
-            topology,
-            ..
-        }) => {
-            let mode = peer_mode.as_ref().unwrap_or(&PeerMode::Dynamic).clone();
-            let seeds = seed_nodes.as_ref().unwrap_or(&Vec::new()).clone();
-            let topo = topology.as_ref().unwrap_or(&Topology::Dag).clone();
-            // Switch topology doesn't use distribution config
-            (mode, seeds, Some(topo), None, None)
-        }
-        None => {
-            // Default to Dynamic mode with no seed nodes and DAG topology
-            (
-                PeerMode::Dynamic,
-                Vec::new(),
-                Some(Topology::Dag),
-                None,
-                None,
-            )
-        }
-    }
-}
-
 /// Build the DNS server wrapper script and ShadowHost, inserting the host
-/// into `hosts` under the agent id `dnsserver`. Pinned to network node 0 so
-/// it's reachable from every node in the GML topology.
+/// into `hosts` under [`crate::DNS_SERVER_ID`]. Pinned to network node 0 so
+/// it's reachable from every node in the GML topology. A watchdog process on
+/// the same host checks that port 53 answers shortly after the server
+/// starts, since daemons that only use DNS discovery find no peers without it.
 fn emit_dns_server_host(
     dns_ip: &str,
-    scripts_dir: &Path,
+    scripts: &ScriptSet,
     shared_dir_path: &Path,
     current_dir: &str,
     home_dir: &str,
@@ -343,15 +452,16 @@ fn emit_dns_server_host(
     environment: &BTreeMap<String, String>,
     hosts: &mut BTreeMap<String, ShadowHost>,
 ) -> color_eyre::eyre::Result<()> {
-    let dns_agent_id = "dnsserver"; // No underscore - Shadow requires RFC-compliant hostnames
+    let dns_agent_id = crate::DNS_SERVER_ID;
 
     // Create DNS server process
     let dns_script = "agents.dns_server";
     let dns_args = format!(
-        "--id {} --bind-ip {} --port 53 --shared-dir {} --log-level DEBUG",
+        "--id {} --bind-ip {} --port 53 --shared-dir {} {}",
         dns_agent_id,
         dns_ip,
-        shared_dir_path.to_string_lossy()
+        shared_dir_path.to_string_lossy(),
+        log_level_arg(environment)
     );
 
     // `exec` so bash is replaced by python3 — see add_user_agent_process.
@@ -370,15 +480,40 @@ export PATH="$PATH:{}/.monerosim/bin"
     );
 
     let dns_process = crate::utils::script::write_wrapper_script(
-        scripts_dir,
+        scripts,
         "dns_server_wrapper.sh",
         &dns_wrapper_script,
         environment,
-        "1s".to_string(),
+        SimDuration::from_secs(1),
         None,
         Some(crate::shadow::ExpectedFinalState::Running),
     )?;
-    let dns_processes = vec![dns_process];
+
+    // A failed check exits 1, which Shadow reports against the expected 0
+    let watchdog_script = format!(
+        r#"#!/bin/bash
+for attempt in 1 2 3 4 5; do
+    if python3 -c "import socket; socket.create_connection(('{ip}', 53), timeout=2).close()" 2>/dev/null; then
+        echo "DNS watchdog: {ip}:53 is answering"
+        exit 0
+    fi
+    sleep 2
+done
+echo "DNS WATCHDOG: NOTHING ANSWERS ON {ip}:53; daemons using discovery dns will find no peers" >&2
+exit 1
+"#,
+        ip = dns_ip
+    );
+    let watchdog_process = crate::utils::script::write_wrapper_script(
+        scripts,
+        "dns_watchdog.sh",
+        &watchdog_script,
+        environment,
+        SimDuration::from_secs(1 + crate::DNS_WATCHDOG_DELAY_SECS),
+        None,
+        Some(crate::shadow::ExpectedFinalState::Exited(0)),
+    )?;
+    let dns_processes = vec![dns_process, watchdog_process];
 
     hosts.insert(
         dns_agent_id.to_string(),
@@ -389,6 +524,7 @@ export PATH="$PATH:{}/.monerosim/bin"
             processes: dns_processes,
             bandwidth_down: Some(crate::DEFAULT_BANDWIDTH_BPS.to_string()),
             bandwidth_up: Some(crate::DEFAULT_BANDWIDTH_BPS.to_string()),
+            host_options: None,
         },
     );
 
@@ -396,211 +532,23 @@ export PATH="$PATH:{}/.monerosim/bin"
     Ok(())
 }
 
-/// Build the agent registry by joining the (already populated) `hosts` map
-/// with the effective agent definitions. Reads each agent's IP from the host
-/// entry rather than re-allocating, so the registry agrees with what Shadow
-/// will run.
-fn build_agent_registry(
-    effective_agents: &crate::config::AgentDefinitions,
-    hosts: &BTreeMap<String, ShadowHost>,
-) -> AgentRegistry {
-    let mut agent_registry = AgentRegistry { agents: Vec::new() };
-
-    // Populate agent registry from all agent types
-    // Extract IPs from the already created hosts instead of generating new ones
-
-    // Add all agents to registry from the effective agents map (so
-    // auto-injected fallback-seed hosts appear here too — DNS server
-    // and other consumers read this file).
-    for (agent_id, agent_config) in effective_agents.agents.iter() {
-        // Get IP from the corresponding host that was already created
-        let agent_ip = hosts
-            .get(agent_id)
-            .and_then(|host| host.ip_addr.clone())
-            .unwrap_or_else(|| {
-                log::warn!(
-                    "Agent '{}' has no host entry with an IP address; using placeholder 0.0.0.0",
-                    agent_id
-                );
-                "0.0.0.0".to_string()
-            });
-
-        let mut attributes = agent_config.attributes.clone().unwrap_or_default();
-
-        // Add computed is_miner attribute to the agent registry
-        let is_miner = agent_config.is_miner();
-        attributes.insert("is_miner".to_string(), is_miner.to_string());
-
-        // Add hashrate if present
-        if let Some(hashrate) = agent_config.hashrate {
-            attributes.insert("hashrate".to_string(), hashrate.to_string());
-        }
-
-        // Add can_receive_distributions if true
-        if agent_config.can_receive_distributions() {
-            attributes.insert("can_receive_distributions".to_string(), "true".to_string());
-        }
-
-        // Determine agent type characteristics
-        let has_local_daemon = agent_config.has_local_daemon();
-        let has_wallet = agent_config.has_wallet();
-        let is_public_node = attributes
-            .get("is_public_node")
-            .map(|v| v.to_lowercase() == "true")
-            .unwrap_or(false);
-
-        // Get remote daemon info for wallet-only agents
-        let remote_daemon = agent_config.remote_daemon_address().map(|s| s.to_string());
-        let daemon_selection_strategy = agent_config
-            .daemon_selection_strategy()
-            .map(|s| format!("{:?}", s).to_lowercase());
-
-        let agent_info = AgentInfo {
-            id: agent_id.clone(),
-            ip_addr: agent_ip,
-            daemon: has_local_daemon,
-            wallet: has_wallet,
-            user_script: agent_config.script.clone(),
-            attributes,
-            wallet_rpc_port: if has_wallet {
-                Some(crate::MONERO_WALLET_RPC_PORT)
-            } else {
-                None
-            },
-            daemon_rpc_port: if has_local_daemon {
-                Some(crate::MONERO_RPC_PORT)
-            } else {
-                None
-            },
-            is_public_node: if is_public_node { Some(true) } else { None },
-            remote_daemon,
-            daemon_selection_strategy,
-        };
-        agent_registry.agents.push(agent_info);
-    }
-
-    agent_registry
-}
-
-/// Build the public-node registry from agents flagged as `is_public_node`
-/// that also run a local daemon. Wallet-only agents in the registry are
-/// excluded because they have no daemon to advertise.
-fn build_public_node_registry(agent_registry: &AgentRegistry) -> PublicNodeRegistry {
-    let mut public_node_registry = PublicNodeRegistry {
-        nodes: Vec::new(),
-        version: 1,
-    };
-
-    // Populate public node registry from agents with is_public_node attribute
-    for agent in &agent_registry.agents {
-        if agent.is_public_node == Some(true) && agent.daemon {
-            let public_node = PublicNodeInfo {
-                agent_id: agent.id.clone(),
-                ip_addr: agent.ip_addr.clone(),
-                rpc_port: agent.daemon_rpc_port.unwrap_or(crate::MONERO_RPC_PORT),
-                p2p_port: Some(crate::MONERO_P2P_PORT),
-                status: "available".to_string(),
-                registered_at: 0.0, // Will be updated at runtime
-                attributes: Some(agent.attributes.clone()),
-            };
-            public_node_registry.nodes.push(public_node);
-        }
-    }
-
-    public_node_registry
-}
-
-/// Build and validate the miner registry. Reads each miner's IP from the
-/// already-populated `agent_registry` so it matches what Shadow will run, and
-/// upgrades a zero-total-weight registry to default per-miner weights of 10
-/// (preserving the legacy stdout warning text).
-fn build_miner_registry(
-    config_agents: &crate::config::AgentDefinitions,
-    agent_registry: &AgentRegistry,
-) -> MinerRegistry {
-    let mut miner_registry = MinerRegistry { miners: Vec::new() };
-
-    // Populate miner registry from agents that are miners
-    for (agent_id, agent_config) in config_agents.agents.iter() {
-        if agent_config.is_miner() {
-            // Find the IP address from the already populated agent_registry
-            let agent_ip = agent_registry
-                .agents
-                .iter()
-                .find(|a| a.id == *agent_id)
-                .map(|a| a.ip_addr.clone())
-                .unwrap_or_else(|| {
-                    log::warn!(
-                        "Miner '{}' not found in agent registry; using placeholder 0.0.0.0",
-                        agent_id
-                    );
-                    "0.0.0.0".to_string()
-                });
-
-            // Determine miner weight (hashrate)
-            // Use hashrate field if available, otherwise check attributes, default to 10
-            let weight = agent_config
-                .hashrate
-                .or_else(|| {
-                    agent_config
-                        .attributes
-                        .as_ref()
-                        .and_then(|attrs| attrs.get("hashrate"))
-                        .and_then(|h| h.parse::<u32>().ok())
-                })
-                .unwrap_or(10); // Default to 10 for better distribution
-
-            let miner_info = MinerInfo {
-                agent_id: agent_id.clone(),
-                ip_addr: agent_ip,
-                wallet_address: None, // Will be populated by the block controller
-                weight,
-            };
-            miner_registry.miners.push(miner_info);
-        }
-    }
-
-    // Validate the miner registry before writing
-    if miner_registry.miners.is_empty() {
-        println!(
-            "Warning: No miners were found in the configuration. Mining will not work correctly."
-        );
-    } else {
-        // Calculate total weight to ensure it's positive
-        let total_weight: u32 = miner_registry.miners.iter().map(|m| m.weight).sum();
-        if total_weight == 0 {
-            println!("Warning: Total mining hashrate weight is zero. Setting default weights of 10 for each miner.");
-            // Set default weights if total is zero
-            for miner in miner_registry.miners.iter_mut() {
-                miner.weight = 10;
-            }
-        } else {
-            println!(
-                "Mining weight distribution: {} miners with total weight {}",
-                miner_registry.miners.len(),
-                total_weight
-            );
-        }
-    }
-
-    miner_registry
-}
-
 /// Choose the Shadow network graph type based on the configured network
 /// block. GML configurations defer to `generate_gml_network_config` for the
-/// emitted topology file; switch / unset configurations build a synthetic
-/// switch graph inline.
+/// emitted topology file, as does a switch with a `link_profile` (its
+/// generated continent graph); other switch / unset configurations build a
+/// synthetic switch graph inline.
 fn build_shadow_network_graph(
     network: &Option<Network>,
     gml_graph: Option<&GmlGraph>,
     output_dir: &Path,
+    files: &mut Vec<(PathBuf, String)>,
 ) -> color_eyre::eyre::Result<ShadowGraph> {
     let graph = match network {
         Some(Network::Gml { path, .. }) => {
             // Use the loaded and validated GML graph to generate network config
             if let Some(gml) = gml_graph {
                 // Pass both the GML graph and the output dir for topology.gml
-                generate_gml_network_config(gml, path, output_dir)?
+                generate_gml_network_config(gml, path, output_dir, files)?
             } else {
                 // Fallback to switch if GML loading failed
                 ShadowGraph {
@@ -611,11 +559,15 @@ fn build_shadow_network_graph(
                 }
             }
         }
-        Some(Network::Switch { network_type, .. }) => ShadowGraph {
-            graph_type: network_type.clone(),
-            file: None,
-            nodes: None,
-            edges: None,
+        Some(Network::Switch { network_type, .. }) => match gml_graph {
+            // link_profile: emit the generated continent graph
+            Some(gml) => generate_gml_network_config(gml, "", output_dir, files)?,
+            None => ShadowGraph {
+                graph_type: network_type.clone(),
+                file: None,
+                nodes: None,
+                edges: None,
+            },
         },
         None => ShadowGraph {
             graph_type: "1_gbit_switch".to_string(),
@@ -627,31 +579,134 @@ fn build_shadow_network_graph(
     Ok(graph)
 }
 
-/// Emit the post-generation summary to stdout: simulation time, host count,
-/// network topology summary, registry paths, and per-subnet IP allocation
-/// counts. Also runs the IP-subnet diversity validation, which can fail and
-/// abort the run.
-fn log_generation_summary(
-    config: &Config,
-    output_path: &Path,
-    shadow_config: &ShadowConfig,
-    gml_graph: Option<&GmlGraph>,
-    ip_registry: &GlobalIpRegistry,
-    agent_registry_path: &Path,
-    miner_registry_path: &Path,
-) -> color_eyre::eyre::Result<()> {
-    println!(
-        "Generated Agent-based Shadow configuration at {:?}",
-        output_path
+/// Everything `generate_agent_shadow_config` produces, computed without
+/// touching the filesystem. `write_generation_plan` persists it; `--check`
+/// only prints its summary.
+pub struct GenerationPlan {
+    pub output_path: PathBuf,
+    pub shadow_config: ShadowConfig,
+    pub gml_graph: Option<GmlGraph>,
+    pub ip_registry: GlobalIpRegistry,
+    /// Seed node addresses wired into the daemons
+    pub seed_nodes: Vec<String>,
+    pub miner_count: usize,
+    pub agent_registry_path: PathBuf,
+    pub miner_registry_path: PathBuf,
+    /// Registries, metadata, topology.gml and the Shadow YAML, in write
+    /// order. All of them go to the output directory.
+    pub files: Vec<(PathBuf, String)>,
+    /// The shared dir, and the files the agents read from it at runtime,
+    /// relative to both it and the output directory
+    pub shared_dir: PathBuf,
+    pub shared_files: Vec<PathBuf>,
+    pub scripts: ScriptSet,
+    /// Wallet directories pre-created under the shared dir
+    pub wallet_dirs: Vec<PathBuf>,
+    /// Launched binaries and their versions, as in `simulation_metadata.json`
+    pub binary_versions: Vec<BinaryVersion>,
+    /// Non-fatal findings, also in `files` as `validation_report.json`
+    pub report: ValidationReport,
+    /// Decisions made while planning, also in `files` as
+    /// `generation_trace.json`
+    pub trace: GenerationTrace,
+    /// Peak memory and file descriptors, checked against this machine
+    pub resources: ResourceEstimate,
+    /// Everything besides the hosts the registries were built from
+    pub registry_context: RegistryContext,
+    /// Expected transactions from agents' `workload` profiles
+    pub workload: WorkloadSummary,
+    /// Each miner's GML node and AS (GML topologies only)
+    pub miner_locations: BTreeMap<String, MinerLocation>,
+    /// GML node and AS of each agent with a `placement`
+    pub pinned_locations: BTreeMap<String, MinerLocation>,
+    /// Agents per region or AS under `network.geographic_distribution` /
+    /// `as_weights`
+    pub agent_distribution: Option<RealizedDistribution>,
+    /// `network.events` resolved to seconds and agent ids
+    pub network_events: Vec<ScheduledNetworkEvent>,
+    /// Agents whose hosts capture packets (`capture_pcap`)
+    pub pcap_agents: Vec<String>,
+    /// Chain data seeded into the daemons' data directories when
+    /// `fresh_blockchain` is false
+    pub warm_start: Option<WarmStartPlan>,
+    /// The agent, public-node and miner registries, also in `files`
+    pub registries: Registries,
+}
+
+/// Log the generation summary: simulation time, host and miner
+/// counts, network topology summary, registry paths, seed nodes and
+/// per-subnet IP allocation counts. `written` selects "created" vs
+/// "would be written" wording for `--check`.
+pub fn log_generation_summary(config: &Config, plan: &GenerationPlan, written: bool) {
+    if written {
+        log::info!(
+            "Generated Agent-based Shadow configuration at {:?}",
+            plan.output_path
+        );
+    } else {
+        log::info!(
+            "Check passed: would generate Agent-based Shadow configuration at {:?}",
+            plan.output_path
+        );
+    }
+    log::info!("  - Simulation time: {}", config.general.stop_time);
+    log::info!("  - Chain: {}", config.general.chain.name());
+    log::info!("  - Total hosts: {}", plan.shadow_config.hosts.len());
+    log::info!("  - Miners: {}", plan.miner_count);
+    log::info!("  - Wrapper scripts: {}", plan.scripts.len());
+    let r = &plan.resources;
+    log::info!(
+        "  - Processes: {} ({} at peak: {} monerod, {} wallet-rpc, {} agent scripts)",
+        r.total_processes,
+        r.peak_processes,
+        r.peak_monerod,
+        r.peak_wallet_rpc,
+        r.peak_agents
+    );
+    log::info!(
+        "  - Estimated memory: {} MB{}",
+        r.memory_mb,
+        r.machine
+            .available_memory_mb
+            .map_or(String::new(), |mb| format!(" ({} MB available)", mb))
+    );
+    log::info!(
+        "  - Estimated file descriptors: {}{}",
+        r.file_descriptors,
+        r.machine
+            .fd_limit
+            .map_or(String::new(), |n| format!(" (ulimit -n {})", n))
+    );
+    if let Some(warm) = &plan.warm_start {
+        log::info!(
+            "  - Blockchain: warm start from {:?} ({} data director{} {})",
+            warm.source,
+            warm.copies.len(),
+            if warm.copies.len() == 1 { "y" } else { "ies" },
+            if written { "copied" } else { "to copy" }
+        );
+    }
+    let w = &plan.workload;
+    if w.agents > 0 {
+        log::info!(
+            "  - Expected transactions: {:.0} from {} workload agent(s) ({:.1} tx/hour over {})",
+            w.expected_transactions,
+            w.agents,
+            w.expected_per_hour,
+            config.general.stop_time
+        );
+    }
+    log::info!(
+        "  - Validation report: {} warning(s), {} note(s)",
+        plan.report.count(Severity::Warning),
+        plan.report.count(Severity::Info)
     );
-    println!("  - Simulation time: {}", config.general.stop_time);
-    println!("  - Total hosts: {}", shadow_config.hosts.len());
 
     // Show network topology information
     match &config.network {
         Some(Network::Gml { path, .. }) => {
-            if let Some(gml) = gml_graph {
-                println!(
+            if let Some(gml) = &plan.gml_graph {
+                log::info!(
                     "  - Network topology: GML from '{}' ({} nodes, {} edges)",
                     path,
                     gml.nodes.len(),
@@ -661,46 +716,482 @@ fn log_generation_summary(
                 // Show autonomous systems if available
                 let as_groups = get_autonomous_systems(gml);
                 if as_groups.len() > 1 {
-                    println!("  - Autonomous systems: {} groups", as_groups.len());
+                    log::info!("  - Autonomous systems: {} groups", as_groups.len());
+                }
+                if !plan.miner_locations.is_empty() {
+                    log::info!("  - Miner placement:");
+                    for (agent_id, location) in &plan.miner_locations {
+                        log::info!(
+                            "      {} -> node {} (AS {})",
+                            agent_id,
+                            location.node,
+                            location.as_number.as_deref().unwrap_or("-")
+                        );
+                    }
+                }
+                if !plan.pinned_locations.is_empty() {
+                    log::info!("  - Pinned agents:");
+                    for (agent_id, location) in &plan.pinned_locations {
+                        let placement = config
+                            .agents
+                            .agents
+                            .get(agent_id)
+                            .and_then(|a| a.placement.as_ref());
+                        log::info!(
+                            "      {} -> node {} (AS {}, pinned {:?})",
+                            agent_id,
+                            location.node,
+                            location.as_number.as_deref().unwrap_or("-"),
+                            placement
+                        );
+                    }
                 }
             }
         }
-        Some(Network::Switch { network_type, .. }) => {
-            println!("  - Network topology: Switch ({})", network_type);
-        }
+        Some(Network::Switch {
+            network_type,
+            link_profile,
+            ..
+        }) => match link_profile {
+            Some(lp) => log::info!(
+                "  - Network topology: Switch with link profile ({} ms ± {} ms, {}% loss)",
+                lp.latency_ms,
+                lp.jitter_ms,
+                lp.packet_loss
+            ),
+            None => log::info!("  - Network topology: Switch ({})", network_type),
+        },
         None => {
-            println!("  - Network topology: Default switch (1_gbit_switch)");
+            log::info!("  - Network topology: Default switch (1_gbit_switch)");
         }
     }
 
-    println!("  - Agent registry created at {:?}", agent_registry_path);
-    println!("  - Miner registry created at {:?}", miner_registry_path);
+    if let Some(distribution) = &plan.agent_distribution {
+        let total: usize = distribution.counts.values().sum();
+        log::info!("  - Agent distribution (by {:?}):", distribution.by);
+        for (key, count) in &distribution.counts {
+            let share = distribution
+                .shares
+                .get(key)
+                .map_or("-".to_string(), |s| format!("{:.1}%", s * 100.0));
+            log::info!(
+                "      {}: {} agents ({:.1}%, configured {})",
+                key,
+                count,
+                *count as f64 * 100.0 / total.max(1) as f64,
+                share
+            );
+        }
+    }
+
+    let verb = if written {
+        "created at"
+    } else {
+        "would be written to"
+    };
+    log::info!("  - Agent registry {} {:?}", verb, plan.agent_registry_path);
+    log::info!("  - Miner registry {} {:?}", verb, plan.miner_registry_path);
+    log::info!("  - Seed nodes: {:?}", plan.seed_nodes);
 
     // Log IP allocation statistics
-    let ip_stats = ip_registry.get_allocation_stats();
-    println!("  - IP Allocation Summary:");
+    let ip_stats = plan.ip_registry.get_allocation_stats();
+    log::info!("  - IP Allocation Summary:");
     let mut sorted_stats: Vec<_> = ip_stats.iter().collect();
     sorted_stats.sort_by_key(|(subnet, _)| (*subnet).clone());
     for (subnet, count) in sorted_stats {
-        println!("    - {}: {} IPs assigned", subnet, count);
+        log::info!("    - {}: {} IPs assigned", subnet, count);
     }
-    let all_ips_map = ip_registry.get_all_assigned_ips();
-    let all_ips: Vec<String> = all_ips_map.keys().cloned().collect();
-    println!("  - Total IPs assigned: {}", all_ips.len());
+    log::info!(
+        "  - Total IPs assigned: {}",
+        plan.ip_registry.get_all_assigned_ips().len()
+    );
+}
 
-    // Validate IP subnet diversity for Monero P2P compatibility
-    crate::utils::validate_ip_subnet_diversity(&all_ips, shadow_config.hosts.len())
-        .map_err(|e| color_eyre::eyre::eyre!("IP diversity validation failed: {}", e))?;
+/// What `generate` produced, entirely in memory: the plan (Shadow config,
+/// registries, wrapper scripts and every file's contents) and the
+/// manifest. `write_outputs` persists it.
+pub struct GenerationResult {
+    pub plan: GenerationPlan,
+    pub manifest: SimulationManifest,
+}
 
-    Ok(())
+impl GenerationResult {
+    pub fn shadow_config(&self) -> &ShadowConfig {
+        &self.plan.shadow_config
+    }
+
+    pub fn agent_registry(&self) -> &AgentRegistry {
+        &self.plan.registries.agents
+    }
+
+    pub fn public_nodes(&self) -> &PublicNodeRegistry {
+        &self.plan.registries.public_nodes
+    }
+
+    pub fn miner_registry(&self) -> &MinerRegistry {
+        &self.plan.registries.miners
+    }
+
+    /// IPs assigned per subnet
+    pub fn ip_allocation(&self) -> BTreeMap<String, usize> {
+        self.plan
+            .ip_registry
+            .get_allocation_stats()
+            .into_iter()
+            .collect()
+    }
+
+    /// Seed node addresses wired into the daemons
+    pub fn seed_nodes(&self) -> &[String] {
+        &self.plan.seed_nodes
+    }
+
+    /// Warning messages of the validation report
+    pub fn warnings(&self) -> Vec<&str> {
+        self.plan
+            .report
+            .issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Warning)
+            .map(|issue| issue.message.as_str())
+            .collect()
+    }
 }
 
-/// Generate a Shadow configuration with agent support
+/// Plan the simulation for the Shadow YAML at `output_path` and attach its
+/// manifest, without writing anything. `config_path` is the YAML the config
+/// was loaded from, if any. Fails on validation errors, which with
+/// `strict_validation` includes every warning.
+pub fn generate(
+    config: &Config,
+    config_path: Option<&Path>,
+    output_path: &Path,
+) -> color_eyre::eyre::Result<GenerationResult> {
+    let plan = plan_agent_shadow_config(config, output_path)?;
+    check_validation_report(&plan.report)?;
+    attach_manifest(config, config_path, plan)
+}
+
+fn attach_manifest(
+    config: &Config,
+    config_path: Option<&Path>,
+    mut plan: GenerationPlan,
+) -> color_eyre::eyre::Result<GenerationResult> {
+    let mut manifest = SimulationManifest::new(config, config_path)?;
+    manifest.binary_versions = plan.binary_versions.clone();
+    manifest.resources = Some(plan.resources.clone());
+    manifest.miner_placement = plan.miner_locations.clone();
+    manifest.agent_distribution = plan.agent_distribution.clone();
+    manifest.network_events = plan.network_events.clone();
+    manifest.pcap_capture = (!plan.pcap_agents.is_empty()).then(|| PcapCapture {
+        agents: plan.pcap_agents.clone(),
+        capture_dir: config.general.pcap_capture_dir.clone(),
+    });
+    plan.files.push((
+        plan.output_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(MANIFEST_FILE),
+        serde_json::to_string_pretty(&manifest)?,
+    ));
+    Ok(GenerationResult { plan, manifest })
+}
+
+/// Write everything in `result` to the output directory (see
+/// `write_generation_plan`); `populate_shared_dir` then stages the shared
+/// dir. The Shadow config refers to its scripts and topology by path, so
+/// `output_dir` must be the directory `result` was generated for.
+pub fn write_outputs(result: &GenerationResult, output_dir: &Path) -> color_eyre::eyre::Result<()> {
+    let generated_for = std::path::absolute(
+        result
+            .plan
+            .output_path
+            .parent()
+            .unwrap_or_else(|| Path::new(".")),
+    )?;
+    if std::path::absolute(output_dir)? != generated_for {
+        return Err(color_eyre::eyre::eyre!(
+            "Generation result is for {:?}, not {:?}; generate again for that directory",
+            generated_for,
+            output_dir
+        ));
+    }
+    write_generation_plan(&result.plan)
+}
+
+/// Generate a Shadow configuration with agent support, plus `manifest.json`
+/// next to it, write it all and populate the shared dir. `config_path` is
+/// the YAML the config was loaded from, if any. `generate`, `write_outputs`
+/// and `populate_shared_dir` do the same in steps.
 pub fn generate_agent_shadow_config(
     config: &Config,
+    config_path: Option<&Path>,
+    output_path: &Path,
+) -> color_eyre::eyre::Result<()> {
+    generate_and_write(config, config_path, output_path, true).map(|_| ())
+}
+
+/// `generate_agent_shadow_config`, leaving the shared dir alone unless
+/// `populate_shared` is set. Returns what was written.
+pub fn generate_and_write(
+    config: &Config,
+    config_path: Option<&Path>,
     output_path: &Path,
+    populate_shared: bool,
+) -> color_eyre::eyre::Result<GenerationResult> {
+    let plan = plan_agent_shadow_config(config, output_path)?;
+    if let Err(e) = enforce_validation_report(&plan) {
+        // Leave the report behind for CI even though nothing else is written
+        if let Some((path, content)) = plan
+            .files
+            .iter()
+            .find(|(p, _)| p.ends_with(VALIDATION_REPORT_FILE))
+        {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, content)
+                .map_err(|e| color_eyre::eyre::eyre!("Failed to write {:?}: {}", path, e))?;
+        }
+        return Err(e);
+    }
+    let result = attach_manifest(config, config_path, plan)?;
+    write_generation_plan(&result.plan)?;
+    if populate_shared {
+        populate_shared_dir(&result.plan)?;
+    } else {
+        log::info!(
+            "Not populating shared dir {:?}; stage {} file(s) from the output directory yourself",
+            result.plan.shared_dir,
+            result.plan.shared_files.len()
+        );
+    }
+    log_generation_summary(config, &result.plan, true);
+    Ok(result)
+}
+
+/// Rebuild `agent_registry.json`, `public_nodes.json`, `miners.json` and
+/// the pool registries next to the already generated Shadow config at
+/// `shadow_yaml` and in the shared dir, from the hosts of that config. The
+/// YAML is left untouched, as is everything else. The rest of the registry
+/// inputs are planned from `config` again. Returns the written paths.
+pub fn regenerate_registries(
+    config: &Config,
+    shadow_yaml: &Path,
+) -> color_eyre::eyre::Result<Vec<PathBuf>> {
+    let hosts = load_shadow_hosts(shadow_yaml)?;
+    let plan = plan_agent_shadow_config(config, shadow_yaml)?;
+    let mut report = ValidationReport::default();
+    let registries = build_registries(
+        &config.agents,
+        &hosts,
+        &plan.registry_context,
+        plan.gml_graph.as_ref(),
+        &mut report,
+    );
+    report.log();
+    let output_dir = shadow_yaml.parent().unwrap_or_else(|| Path::new("."));
+    let mut files = registries.files(output_dir)?;
+    files.extend(registries.files(Path::new(&config.general.shared_dir))?);
+    for (path, content) in &files {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)
+            .map_err(|e| color_eyre::eyre::eyre!("Failed to write {:?}: {}", path, e))?;
+    }
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+/// Log the plan's validation report and fail if it holds errors, which
+/// with `strict_validation` includes every warning.
+pub fn enforce_validation_report(plan: &GenerationPlan) -> color_eyre::eyre::Result<()> {
+    plan.report.log();
+    check_validation_report(&plan.report)
+}
+
+/// Launch every process of an agent with a `memory_limit` or `cpu_weight`
+/// (its own or `general`'s) under those limits. A memory limit below what
+/// `general.resource_model` charges one of the processes is a warning:
+/// the process would likely fail its allocations.
+fn apply_process_limits(
+    hosts: &mut BTreeMap<String, ShadowHost>,
+    agents: &AgentDefinitions,
+    general: &GeneralConfig,
+    report: &mut ValidationReport,
 ) -> color_eyre::eyre::Result<()> {
+    let model = ResourceModel::from_config(general.resource_model.as_ref());
+    for (id, host) in hosts.iter_mut() {
+        let Some(agent) = agents.agents.get(id) else {
+            continue;
+        };
+        let Some(limits) = ProcessLimits::resolve(agent, general)
+            .map_err(|e| color_eyre::eyre::eyre!("Agent '{}': {}", id, e))?
+        else {
+            continue;
+        };
+        let mut short = BTreeSet::new();
+        for process in &mut host.processes {
+            let kind = ProcessKind::of_path(process.executable());
+            if let Some(mb) = limits.memory_mb {
+                let (model_mb, _) = model.cost(kind);
+                if mb < model_mb && short.insert(kind.name()) {
+                    report.warn(
+                        "resources",
+                        format!(
+                            "Agent '{}': memory_limit of {} MiB is below the {} MB \
+                             general.resource_model assumes for a {}; raise the limit or \
+                             calibrate the model",
+                            id,
+                            mb,
+                            model_mb,
+                            kind.name()
+                        ),
+                    );
+                }
+            }
+            limits.apply(process);
+        }
+    }
+    Ok(())
+}
+
+/// Turn on Shadow's packet capture on the host of every agent with
+/// `capture_pcap`, returning those agents. Each trace holds all of its
+/// host's traffic, so more than `PCAP_WARN_AGENTS` of them is a warning.
+fn apply_pcap_capture(
+    hosts: &mut BTreeMap<String, ShadowHost>,
+    agents: &AgentDefinitions,
+    general: &GeneralConfig,
+    report: &mut ValidationReport,
+) -> Vec<String> {
+    let mut captured = Vec::new();
+    for (id, host) in hosts.iter_mut() {
+        if agents.agents.get(id).is_some_and(|a| a.captures_pcap()) {
+            host.host_options
+                .get_or_insert_with(ShadowHostOptions::default)
+                .pcap_enabled = true;
+            captured.push(id.clone());
+        }
+    }
+    if captured.len() > crate::PCAP_WARN_AGENTS {
+        report.warn(
+            "pcap",
+            format!(
+                "{} agents set capture_pcap; each trace records every packet of its host for \
+                 the whole run, which adds up to gigabytes on long or busy simulations. \
+                 Capture only the nodes under study",
+                captured.len()
+            ),
+        );
+    }
+    if captured.is_empty() && general.pcap_capture_dir.is_some() {
+        report.warn(
+            "pcap",
+            "general.pcap_capture_dir is set but no agent sets capture_pcap: true",
+        );
+    }
+    captured
+}
+
+fn check_validation_report(report: &ValidationReport) -> color_eyre::eyre::Result<()> {
+    if report.has_errors() {
+        return Err(color_eyre::eyre::eyre!(
+            "Validation failed with {} error(s){}",
+            report.count(Severity::Error),
+            if report.strict {
+                " (strict_validation turns warnings into errors)"
+            } else {
+                ""
+            }
+        ));
+    }
+    Ok(())
+}
+
+/// Persist a plan into the output directory: wrapper scripts, registries,
+/// topology, the Shadow YAML, and any warm-start chain data. Parent
+/// directories are created as needed. The shared dir is left to
+/// `populate_shared_dir`.
+pub fn write_generation_plan(plan: &GenerationPlan) -> color_eyre::eyre::Result<()> {
+    plan.scripts.write_all()?;
+    for (path, content) in &plan.files {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                color_eyre::eyre::eyre!("Failed to create directory {:?}: {}", parent, e)
+            })?;
+        }
+        fs::write(path, content)
+            .map_err(|e| color_eyre::eyre::eyre!("Failed to write {:?}: {}", path, e))?;
+        log::info!("Wrote {:?} ({} bytes)", path, content.len());
+    }
+
+    if let Some(warm) = &plan.warm_start {
+        seed_data_dirs(warm).map_err(|e| {
+            color_eyre::eyre::eyre!("Failed to copy chain data from {:?}: {}", warm.source, e)
+        })?;
+    }
+    Ok(())
+}
+
+/// Stage the shared dir for the run: copy the registries and other files
+/// the agents read from the written output directory, and pre-create the
+/// wallet directories. Copies rather than symlinks, since agents update
+/// some of them (e.g. miner wallet addresses) during the run and the output
+/// directory should keep what was generated. Returns the copied files.
+pub fn populate_shared_dir(plan: &GenerationPlan) -> color_eyre::eyre::Result<Vec<PathBuf>> {
+    let output_dir = plan.output_path.parent().unwrap_or_else(|| Path::new("."));
+    let mut copied = Vec::new();
+    for relative in &plan.shared_files {
+        let (from, to) = (output_dir.join(relative), plan.shared_dir.join(relative));
+        if std::path::absolute(&from)? == std::path::absolute(&to)? {
+            continue;
+        }
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                color_eyre::eyre::eyre!("Failed to create directory {:?}: {}", parent, e)
+            })?;
+        }
+        fs::copy(&from, &to)
+            .map_err(|e| color_eyre::eyre::eyre!("Failed to copy {:?} to {:?}: {}", from, to, e))?;
+        copied.push(to);
+    }
+
+    // Pre-create wallet directories for all agents that have wallets.
+    // This replaces the per-agent bash cleanup processes that previously ran
+    // inside the simulation to `rm -rf && mkdir -p && chmod 755` wallet dirs.
+    // Since main.rs already cleans /tmp/monerosim_shared/ before generation,
+    // we just need to create fresh directories with correct permissions.
+    for wallet_dir in &plan.wallet_dirs {
+        fs::create_dir_all(wallet_dir).map_err(|e| {
+            color_eyre::eyre::eyre!("Failed to create wallet dir {:?}: {}", wallet_dir, e)
+        })?;
+        // Set permissions explicitly (monero-wallet-rpc can create files with restrictive perms)
+        let mut perms = fs::metadata(wallet_dir)?.permissions();
+        use std::os::unix::fs::PermissionsExt;
+        perms.set_mode(0o755);
+        fs::set_permissions(wallet_dir, perms)?;
+    }
+    log::info!(
+        "Populated shared dir {:?}: copied {} file(s) from {:?}, created {} wallet dir(s)",
+        plan.shared_dir,
+        copied.len(),
+        output_dir,
+        plan.wallet_dirs.len()
+    );
+    Ok(copied)
+}
+
+/// Run every parsing, validation and allocation step of generation and
+/// return the result in memory. Reads the config, GML and binaries but never
+/// writes, creates or removes anything.
+pub fn plan_agent_shadow_config(
+    config: &Config,
+    output_path: &Path,
+) -> color_eyre::eyre::Result<GenerationPlan> {
     let shared_dir_path = Path::new(&config.general.shared_dir);
+    let mut report = ValidationReport::default();
+    let mut trace = GenerationTrace::default();
 
     // Mining and agent configuration validation is handled by AgentConfig methods
 
@@ -710,16 +1201,85 @@ pub fn generate_agent_shadow_config(
         .to_string();
 
     // Load and validate GML graph if specified
-    let gml_graph = if let Some(Network::Gml { path, .. }) = &config.network {
-        let graph = gml_parser::parse_gml_file(path)?;
+    let gml_graph = if let Some(Network::Gml {
+        path,
+        latency_synthesis,
+        ..
+    }) = &config.network
+    {
+        let mut graph = gml_parser::parse_gml_file(path)?;
+        canonicalize_gml_units(&mut graph)
+            .map_err(|e| color_eyre::eyre::eyre!("GML '{}': {}", path, e))?;
         validate_topology(&graph)
             .map_err(|e| color_eyre::eyre::eyre!("GML validation failed: {}", e))?;
-        println!(
+        let synthesis = latency_synthesis.clone().unwrap_or_default();
+        let synthesized = gml_parser::synthesize_edge_latencies(
+            &mut graph,
+            synthesis.propagation_factor,
+            synthesis.processing_delay_ms,
+        );
+        if synthesized > 0 {
+            log::info!(
+                "Synthesized latency for {} of {} GML edges from node coordinates",
+                synthesized,
+                graph.edges.len()
+            );
+            trace.record(
+                STAGE_TOPOLOGY,
+                "network.latency_synthesis",
+                format!("synthesized latency for {} edge(s)", synthesized),
+                "the edges have no latency and both endpoints have coordinates",
+            );
+        }
+        let missing = graph
+            .edges
+            .iter()
+            .filter(|e| !e.attributes.contains_key("latency"))
+            .count();
+        if missing > 0 {
+            report.warn(
+                "topology",
+                format!(
+                    "{} GML edges have no latency and no coordinates on both endpoints to \
+                     synthesize one",
+                    missing
+                ),
+            );
+        }
+        log::info!(
             "Loaded GML topology from '{}' with {} nodes and {} edges",
             path,
             graph.nodes.len(),
             graph.edges.len()
         );
+        trace.record(
+            STAGE_TOPOLOGY,
+            "network.path",
+            format!(
+                "GML graph of {} nodes and {} edges",
+                graph.nodes.len(),
+                graph.edges.len()
+            ),
+            format!("loaded from '{}'", path),
+        );
+        Some(graph)
+    } else if let Some(Network::Switch {
+        link_profile: Some(profile),
+        ..
+    }) = &config.network
+    {
+        let graph = link_profile_graph(profile, config.general.simulation_seed);
+        log::info!(
+            "Generated link-profile topology with {} nodes and {} edges",
+            graph.nodes.len(),
+            graph.edges.len()
+        );
+        trace.record(
+            STAGE_TOPOLOGY,
+            "network.link_profile",
+            format!("switch graph of {} nodes", graph.nodes.len()),
+            "a link profile gives each host its own node",
+        );
         Some(graph)
     } else {
         None
@@ -733,8 +1293,17 @@ pub fn generate_agent_shadow_config(
     // Create centralized IP registry for robust IP management
     let mut ip_registry = GlobalIpRegistry::new();
 
-    // Create AS-aware subnet manager for GML topology compatibility
-    let mut subnet_manager = AsSubnetManager::new();
+    // Create AS-aware subnet manager for GML topology compatibility, with
+    // the subnet size the config asks for (validated at load)
+    let subnet_prefix = match &config.network {
+        Some(Network::Gml {
+            subnet_size: Some(size),
+            ..
+        }) => parse_subnet_size(size)
+            .map_err(|e| color_eyre::eyre::eyre!("Network configuration error: {}", e))?,
+        _ => DEFAULT_AS_PREFIX,
+    };
+    let mut subnet_manager = AsSubnetManager::with_prefix(subnet_prefix);
 
     // Compose base + Monero-specific environment maps and (optionally)
     // allocate the DNS server IP from node 0's subnet.
@@ -749,23 +1318,41 @@ pub fn generate_agent_shadow_config(
         )?;
     let enable_dns_server = config.general.enable_dns_server.unwrap_or(false);
 
-    // Fully-resolved binary paths (installed to ~/.monerosim/bin by setup.sh)
-    let monerod_path = format!("{}/.monerosim/bin/monerod", home_dir);
-    let wallet_path = format!("{}/.monerosim/bin/monero-wallet-rpc", home_dir);
+    // Fully-resolved binary paths (installed to ~/.monerosim/bin by setup.sh,
+    // overridable via general.binaries, else found on PATH)
+    let binary_resolver = BinaryResolver::new(config.general.binaries.as_ref());
+    let monerod_path = binary_resolver.resolve("monerod")?;
+    let wallet_path = binary_resolver.resolve("monero-wallet-rpc")?;
 
     // Store seed nodes for P2P connections
     let mut seed_nodes: Vec<String> = Vec::new();
 
-    // Determine if we're actually using GML topology based on network configuration
-    let using_gml_topology = if let Some(Network::Gml { path: _, .. }) = &config.network {
-        true // We're using GML topology
-    } else {
-        false // We're using switch topology
-    };
-
-    // Extract peer mode, seed nodes, topology, and distribution config from configuration
-    let (peer_mode, seed_node_list, topology, distribution_strategy, distribution_weights) =
-        extract_network_topology_config(config);
+    // GML files and link-profile switches both place hosts on a graph
+    let using_gml_topology = gml_graph.is_some();
+
+    // Peer mode, seed nodes, topology and distribution with defaults applied,
+    // and the configured fields that have no effect
+    let resolved = resolve(config);
+    for field in &resolved.ignored {
+        let message = format!("'{}' has no effect: {}", field.field, field.reason);
+        match field.kind {
+            IgnoredKind::Unimplemented => report.warn("config", message),
+            IgnoredKind::Inapplicable => report.info("config", message),
+        }
+    }
+    for warning in unrecognized_attribute_warnings(&config.agents.agents) {
+        report.warn("attributes", warning);
+    }
+    let network = resolved.network;
+    let NetworkSettings {
+        peer_mode,
+        seed_nodes: seed_node_list,
+        seed_selection,
+        seed_count,
+        topology,
+        prune_unused_nodes,
+        ..
+    } = &network;
 
     // Validate topology configuration
     // Count user agents (agents with daemon or wallet)
@@ -778,49 +1365,34 @@ pub fn generate_agent_shadow_config(
 
     if let Some(topo) = &topology {
         if let Err(e) = validate_topology_config(topo, user_agent_count) {
-            println!("Warning: Topology validation failed: {}", e);
-            // Continue with default DAG topology
+            report.warn("topology", format!("Topology validation failed: {}", e));
+            trace.record(
+                STAGE_TOPOLOGY,
+                "network.topology",
+                format!("kept the {:?} template", topo),
+                format!(
+                    "a template that does not fit {} agents is a warning, not an error: {}",
+                    user_agent_count, e
+                ),
+            );
         }
     }
 
-    // Informative print only: the real seed wiring (--add-priority-node) is
-    // built in topology::peer_connections from the IPs assigned at host
-    // emission. Do NOT resolve miner IPs here — calling get_agent_ip before
-    // process_user_agents allocates (and caches) every miner's IP against
-    // network node 0, pinning all miners into node 0's subnet regardless of
-    // their GML placement (docs/20260711_code_quality_review.md, P0 #1).
-    if seed_node_list.is_empty() {
-        let miner_ids: Vec<&String> = config
-            .agents
-            .agents
-            .iter()
-            .filter(|(_, cfg)| cfg.is_miner())
-            .map(|(agent_id, _)| agent_id)
-            .collect();
-        println!(
-            "Using {} miners as seed sources (IPs assigned at host emission): {:?}",
-            miner_ids.len(),
-            miner_ids
-        );
-    } else {
-        println!("Using configured seed nodes: {:?}", seed_node_list);
-    }
-
-    // Create scripts directory for wrapper scripts (used by all agent types)
-    let scripts_dir = output_path
+    // Scripts directory for wrapper scripts (used by all agent types). The
+    // scripts embed this path, so make it absolute; canonicalize only when it
+    // already exists since planning must not create it.
+    let output_parent = output_path
         .parent()
-        .ok_or_else(|| color_eyre::eyre::eyre!("Output path has no parent directory"))?
-        .join("scripts");
-    fs::create_dir_all(&scripts_dir)
-        .map_err(|e| color_eyre::eyre::eyre!("Failed to create scripts directory: {}", e))?;
-    let scripts_dir = fs::canonicalize(&scripts_dir)
-        .map_err(|e| color_eyre::eyre::eyre!("Failed to canonicalize scripts directory: {}", e))?;
+        .ok_or_else(|| color_eyre::eyre::eyre!("Output path has no parent directory"))?;
+    let output_parent = fs::canonicalize(output_parent)
+        .unwrap_or_else(|_| Path::new(&current_dir).join(output_parent));
+    let scripts = ScriptSet::new(output_parent.join("scripts"));
 
     // Create DNS server host if enabled
     if let Some(ref dns_ip) = dns_server_ip {
         emit_dns_server_host(
             dns_ip,
-            &scripts_dir,
+            &scripts,
             shared_dir_path,
             &current_dir,
             &home_dir,
@@ -828,6 +1400,18 @@ pub fn generate_agent_shadow_config(
             &environment,
             &mut hosts,
         )?;
+        trace.record(
+            STAGE_SCHEDULING,
+            crate::DNS_SERVER_ID,
+            "dns server at 1s",
+            "started ahead of every daemon so discovery has an answer",
+        );
+        trace.record(
+            STAGE_SCHEDULING,
+            crate::DNS_SERVER_ID,
+            format!("dns watchdog at {}s", 1 + crate::DNS_WATCHDOG_DELAY_SECS),
+            "checks port 53 once the server has had time to bind",
+        );
     }
 
     // Reserve Monero fallback-seed IPs (and inject synthesized seed
@@ -837,14 +1421,122 @@ pub fn generate_agent_shadow_config(
     // `<repo>/sibling_repos/monero` (or sibling layouts), with the
     // hardcoded constant as a fallback.
     let repo_dir = std::path::Path::new(&current_dir);
-    let (effective_agents, _seed_count) = prepare_fallback_seeds(
+    let chain = config.general.chain;
+    if chain != Chain::Regtest {
+        if config.general.fallback_seeds != FallbackSeedsMode::Off {
+            report.warn(
+                "config",
+                format!(
+                    "fallback_seeds pins Monero's mainnet fallback seed IPs, which a {} \
+                     daemon never dials; set general.fallback_seeds: off",
+                    chain.name()
+                ),
+            );
+        }
+        if config.general.fixed_difficulty.is_some() {
+            report.warn(
+                "config",
+                format!(
+                    "fixed_difficulty replaces the {} difficulty adjustment; unset it, or use \
+                     chain: regtest",
+                    chain.name()
+                ),
+            );
+        }
+    }
+    let (mut effective_agents, _seed_count) = prepare_fallback_seeds(
         config.general.fallback_seeds,
         &config.agents,
         &mut ip_registry,
         repo_dir,
     );
+    // The distributor's recipients follow its `recipients` selector
+    if let Some(distributor) = &config.miner_distributor {
+        apply_recipient_selector(&mut effective_agents, distributor, &mut report);
+        if find_miner_distributor(&config.agents).is_none() {
+            report.warn(
+                "distribution",
+                "miner_distributor is configured, but no agent runs agents.miner_distributor",
+            );
+        }
+    }
 
-    // Process all agent types from the configuration
+    // fresh_blockchain: false keeps (or seeds) the daemons' chain data
+    let warm_start = if config.general.fresh_blockchain == Some(false) {
+        let daemons: Vec<&str> = effective_agents
+            .agents
+            .iter()
+            .filter(|(_, agent)| agent.has_local_daemon())
+            .map(|(id, _)| id.as_str())
+            .collect();
+        Some(
+            plan_warm_start(
+                config
+                    .general
+                    .blockchain_source_dir
+                    .as_deref()
+                    .map(Path::new),
+                Path::new(&config.general.daemon_data_dir),
+                &daemons,
+                &mut report,
+            )
+            .map_err(|e| color_eyre::eyre::eyre!("Warm start configuration error: {}", e))?,
+        )
+    } else {
+        None
+    };
+
+    // Absolute hashrate weights, `auto` miners included
+    let miner_weights = resolve_miner_weights(
+        effective_agents
+            .agents
+            .iter()
+            .filter_map(|(id, agent)| Some((id.as_str(), agent.hashrate?))),
+        config.general.hashrate_distribution,
+        config.general.simulation_seed,
+    )
+    .map_err(|e| color_eyre::eyre::eyre!("Mining configuration error: {}", e))?;
+    let mining_pools =
+        BlockControllerConfig::assign_pools(config.block_controller.as_ref(), &effective_agents)
+            .map_err(|e| color_eyre::eyre::eyre!("Mining pool configuration error: {}", e))?;
+    for (pool, miners) in &mining_pools {
+        log::info!("Mining pool '{}': {}", pool, miners.join(", "));
+    }
+
+    let mut peer_sources = BTreeMap::new();
+    let mut topology_connections = BTreeMap::new();
+    let mut wallet_args = BTreeMap::new();
+    let mut daemon_args = BTreeMap::new();
+    let mut schedules = BTreeMap::new();
+    let mut miner_locations = BTreeMap::new();
+    let mut agent_distribution = None;
+    let mut pinned_locations = BTreeMap::new();
+    let block_interval = BlockControllerConfig::interval_secs(config.block_controller.as_ref())
+        .map_err(|e| color_eyre::eyre::eyre!("Block controller configuration error: {}", e))?;
+    let startup_settings =
+        StartupSettings::from_config(config.general.startup.as_ref(), block_interval)
+            .map_err(|e| color_eyre::eyre::eyre!("Startup configuration error: {}", e))?;
+    if block_interval != crate::TARGET_BLOCK_INTERVAL_SECS
+        && config.general.fixed_difficulty.is_none()
+    {
+        report.warn(
+            "mining",
+            format!(
+                "block_controller.block_interval is {}s, but without fixed_difficulty monerod \
+                 retargets difficulty towards {}s and the miners slow down to match",
+                block_interval,
+                crate::TARGET_BLOCK_INTERVAL_SECS
+            ),
+        );
+    }
+    let mut startup = StartupScheduler::new(startup_settings);
+    let mut seeds = Vec::new();
+    let agent_ports = assign_agent_ports(&effective_agents.agents, config.general.chain)
+        .map_err(|e| color_eyre::eyre::eyre!("Port configuration error: {}", e))?;
+
+    // Process all agent types from the configuration. stop_time is handed
+    // to every process builder in seconds.
+    let stop_secs = config.general.stop_time.as_secs();
     process_user_agents(UserAgentProcessContext {
         agents: &effective_agents,
         hosts: &mut hosts,
@@ -853,44 +1545,75 @@ pub fn generate_agent_shadow_config(
         ip_registry: &mut ip_registry,
         monerod_path: &monerod_path,
         wallet_path: &wallet_path,
+        binaries: &binary_resolver,
         environment: &environment,
         monero_environment: &monero_environment,
         shared_dir: shared_dir_path,
         current_dir: &current_dir,
         gml_graph: gml_graph.as_ref(),
         using_gml_topology,
-        peer_mode: &peer_mode,
-        topology: topology.as_ref(),
+        network: &network,
+        seeds: &mut seeds,
+        peer_sources: &mut peer_sources,
+        topology_connections: &mut topology_connections,
+        wallet_args: &mut wallet_args,
+        daemon_args: &mut daemon_args,
+        schedules: &mut schedules,
+        miner_locations: &mut miner_locations,
+        pinned_locations: &mut pinned_locations,
+        startup: &mut startup,
+        agent_ports: &agent_ports,
         enable_dns_server,
         daemon_defaults: config.general.daemon_defaults.as_ref(),
         wallet_defaults: config.general.wallet_defaults.as_ref(),
-        distribution_strategy: distribution_strategy.as_ref(),
-        distribution_weights: distribution_weights.as_ref(),
-        scripts_dir: &scripts_dir,
+        agent_distribution: &mut agent_distribution,
+        scripts: &scripts,
         daemon_data_dir: &config.general.daemon_data_dir,
         simulation_seed: config.general.simulation_seed,
         reachable_fraction: config.general.reachable_fraction,
         reachable_by_role: config.general.reachable_by_role.as_ref(),
         hidden_fraction: config.general.hidden_fraction,
-        simulation_stop_secs: parse_duration_to_seconds(&config.general.stop_time).map_err(
-            |e| {
-                color_eyre::eyre::eyre!(
-                    "Failed to parse stop_time '{}': {}",
-                    config.general.stop_time,
-                    e
-                )
-            },
-        )?,
+        chain: config.general.chain,
+        fixed_difficulty: config.general.fixed_difficulty,
+        block_interval,
+        simulation_stop_secs: stop_secs,
         turnover: config.general.turnover.as_ref(),
+        warm_start: warm_start.is_some(),
+        miner_weights: &miner_weights,
+        mining_pools: &mining_pools,
+        trace: &mut trace,
     })?;
 
-    // Calculate offset for script agents to avoid IP collisions
-    // Use a larger offset to ensure clear separation between agent types
-    // Count all agents in the map for offset calculation (use the
-    // effective set so injected seeds are accounted for).
-    let total_agent_count = effective_agents.agents.len();
-    let distributor_offset = total_agent_count + crate::DISTRIBUTOR_IP_OFFSET;
-    let script_offset = total_agent_count + crate::SCRIPT_IP_OFFSET;
+    // Which agents became seeds and why; an agent promoted against its
+    // config is a warning (an error under strict_validation)
+    log::info!(
+        "Seeds ({:?} selection): {}",
+        seed_selection,
+        seeds
+            .iter()
+            .map(|s| format!("{} ({})", s.id, s.reason))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    for seed in &seeds {
+        trace.record(
+            STAGE_SEEDS,
+            seed.id.as_str(),
+            "seed node",
+            format!("{} ({:?} selection)", seed.reason, seed_selection),
+        );
+    }
+    for seed in seeds.iter().filter(|s| s.reason == SeedReason::Promoted) {
+        report.warn(
+            "seeds",
+            format!(
+                "Agent '{}' was promoted to seed node to reach {} seeds; set is_seed_node \
+                 or choose a seed_selection policy",
+                seed.id,
+                seed_count.unwrap_or(DEFAULT_SEED_COUNT)
+            ),
+        );
+    }
 
     process_miner_distributor(
         &config.agents,
@@ -900,12 +1623,12 @@ pub fn generate_agent_shadow_config(
         &environment,
         shared_dir_path,
         &current_dir,
-        &config.general.stop_time,
+        stop_secs,
         gml_graph.as_ref(),
         using_gml_topology,
-        distributor_offset,
-        &peer_mode,
-        &scripts_dir,
+        peer_mode,
+        &scripts,
+        &mut startup,
     )?;
 
     process_pure_script_agents(
@@ -916,11 +1639,11 @@ pub fn generate_agent_shadow_config(
         &environment,
         shared_dir_path,
         &current_dir,
-        &config.general.stop_time,
+        stop_secs,
         gml_graph.as_ref(),
         using_gml_topology,
-        script_offset,
-        &scripts_dir,
+        &scripts,
+        &mut startup,
     )?;
 
     // Get output directory from output_path (parent of output file)
@@ -943,85 +1666,248 @@ pub fn generate_agent_shadow_config(
         shared_dir_path,
         &current_dir,
         &output_dir,
-        &config.general.stop_time,
+        stop_secs,
         gml_graph.as_ref(),
         using_gml_topology,
-        script_offset + 50, // Offset from other script agents
-        &scripts_dir,
+        &scripts,
+        &mut startup,
     )?;
 
-    // Build agent registry from the effective agents and the (already
-    // populated) hosts map.
-    let agent_registry = build_agent_registry(&effective_agents, &hosts);
-
-    // Note: miner_distributor, simulation_monitor, and pure_script agents are now
-    // part of the unified agents map and are handled above
-
-    // Write agent registry to file
-    let agent_registry_path = shared_dir_path.join("agent_registry.json");
-    let agent_registry_json = serde_json::to_string_pretty(&agent_registry)?;
+    // `network.events`: set_bans helpers on every grouped daemon's host
+    let network_events = config
+        .network_event_schedule()
+        .map_err(|e| color_eyre::eyre::eyre!(e))?;
+    let mut partition_daemons: BTreeMap<String, PartitionDaemon> = BTreeMap::new();
+    for id in network_events
+        .iter()
+        .flat_map(|e| e.groups.iter().flatten())
+    {
+        let Some(agent) = effective_agents.agents.get(id) else {
+            continue;
+        };
+        let ip = hosts
+            .get(agent.host_id(id))
+            .and_then(|h| h.ip_addr.clone())
+            .ok_or_else(|| color_eyre::eyre::eyre!("network.events: '{}' has no host IP", id))?;
+        let rpc_port = agent_ports.get(id).copied().unwrap_or_default().rpc;
+        partition_daemons.insert(id.clone(), PartitionDaemon { ip, rpc_port });
+    }
+    for (agent_id, process) in partition_processes(
+        &network_events,
+        &partition_daemons,
+        stop_secs,
+        &environment,
+        &scripts,
+    )? {
+        trace.record(
+            STAGE_SCHEDULING,
+            agent_id.as_str(),
+            format!("set_bans at {}", process.start_time),
+            "network.events",
+        );
+        let host_id = effective_agents.agents[&agent_id].host_id(&agent_id);
+        if let Some(host) = hosts.get_mut(host_id) {
+            host.processes.push(process);
+        }
+    }
 
-    // DEBUG: Log registry structure before writing
+    apply_process_limits(&mut hosts, &effective_agents, &config.general, &mut report)?;
+    let pcap_agents =
+        apply_pcap_capture(&mut hosts, &effective_agents, &config.general, &mut report);
+
+    // Registries from the effective agents and the (already populated)
+    // hosts map; `regen-registries` rebuilds them the same way from a
+    // generated YAML
+    let registry_context = RegistryContext {
+        effective_agents,
+        wallet_args,
+        daemon_args,
+        schedules,
+        agent_ports,
+        binaries: binary_resolver,
+        seeds: seeds.iter().map(|s| s.id.clone()).collect(),
+        miner_weights,
+        mining_pools,
+        chain: config.general.chain,
+        simulation_seed: config.general.simulation_seed,
+    };
+    let registries = build_registries(
+        &config.agents,
+        &hosts,
+        &registry_context,
+        gml_graph.as_ref(),
+        &mut report,
+    );
+    let agent_registry = &registries.agents;
     log::info!("Agent registry has {} agents", agent_registry.agents.len());
+    log::info!(
+        "Public node registry has {} nodes",
+        registries.public_nodes.nodes.len()
+    );
+    let agent_registry_path = output_dir.join(AGENT_REGISTRY_FILE);
+    let miner_registry_path = output_dir.join(MINER_REGISTRY_FILE);
+    let mut files: Vec<(PathBuf, String)> = registries.files(shared_dir_path)?;
     log::info!(
         "Agent registry JSON preview (first {} chars): {}",
         crate::REGISTRY_PREVIEW_CHARS,
-        &agent_registry_json
+        files[0]
+            .1
             .chars()
             .take(crate::REGISTRY_PREVIEW_CHARS)
             .collect::<String>()
     );
 
-    std::fs::write(&agent_registry_path, &agent_registry_json)?;
+    // Per-agent Hybrid peers with their source (seed vs topology template),
+    // only present when network.hybrid_options limits the mix.
+    if !peer_sources.is_empty() {
+        files.push((
+            shared_dir_path.join("peer_sources.json"),
+            serde_json::to_string_pretty(&peer_sources)?,
+        ));
+    }
 
-    // DEBUG: Verify file was written
-    let written_size = std::fs::metadata(&agent_registry_path)?.len();
-    log::info!(
-        "Wrote agent registry to {:?}, size: {} bytes",
-        agent_registry_path,
-        written_size
-    );
+    // Intended template graph when Hardcoded mode wires one (Tree,
+    // SmallWorld), for comparison with the observed peer graph
+    if let Some(topology) = topology
+        .as_ref()
+        .filter(|_| !topology_connections.is_empty())
+    {
+        files.push((
+            shared_dir_path.join("topology_connections.json"),
+            serde_json::to_string_pretty(&TopologyConnections {
+                topology: topology.clone(),
+                simulation_seed: config.general.simulation_seed,
+                adjacency: topology_connections,
+            })?,
+        ));
+    }
 
-    // Build public-node registry from the agent registry (wallet-only agents
-    // are excluded — they have no daemon to advertise).
-    let public_node_registry = build_public_node_registry(&agent_registry);
+    // Every daemon's injected seed / priority / exclusive peers, resolved to
+    // agent ids
+    let agent_ips: BTreeMap<String, String> = agent_registry
+        .agents
+        .iter()
+        .map(|a| (a.id.clone(), a.ip_addr.clone()))
+        .collect();
+    for (agent_id, source) in ip_registry.allocation_sources() {
+        let ip = ip_registry
+            .get_ip_for_agent(agent_id)
+            .or_else(|| agent_ips.get(agent_id))
+            .map_or("no IP", String::as_str);
+        trace.record(STAGE_IP_ALLOCATION, agent_id.as_str(), ip, source.as_str());
+    }
+    files.push((
+        shared_dir_path.join(INTENDED_TOPOLOGY_FILE),
+        serde_json::to_string_pretty(&build_intended_topology(
+            &registry_context.daemon_args,
+            &agent_ips,
+        ))?,
+    ));
+
+    // Shortest-path latency between every two agents on a GML topology,
+    // for `tx-analyzer propagation --latency-matrix`. Compact: it grows
+    // with the square of the agent count.
+    if let Some(gml) = &gml_graph {
+        let placements: BTreeMap<String, u32> = agent_registry
+            .agents
+            .iter()
+            .filter_map(|a| Some((a.id.clone(), a.network_node_id?)))
+            .collect();
+        match agent_latency_matrix(gml, &placements) {
+            Ok(matrix) => files.push((
+                output_dir.join(LATENCY_MATRIX_FILE),
+                serde_json::to_string(&matrix)?,
+            )),
+            Err(e) => report.warn(
+                "topology",
+                format!("No {} written: {}", LATENCY_MATRIX_FILE, e),
+            ),
+        }
+    }
 
-    // Write public node registry to file
-    let public_nodes_path = shared_dir_path.join("public_nodes.json");
-    let public_nodes_json = serde_json::to_string_pretty(&public_node_registry)?;
-    std::fs::write(&public_nodes_path, &public_nodes_json)?;
-    log::info!(
-        "Wrote public node registry to {:?} with {} nodes",
-        public_nodes_path,
-        public_node_registry.nodes.len()
+    // Daemon restarts, for `tx-analyzer upgrade-analysis --manifest`
+    if let Some(manifest) = build_restart_manifest(config)? {
+        files.push((
+            shared_dir_path.join("upgrade_manifest.json"),
+            serde_json::to_string_pretty(&manifest)?,
+        ));
+    }
+
+    // Send times for agents with a `workload`, read through --workload-file
+    let workloads = build_workloads(
+        &config.agents,
+        &registry_context.schedules,
+        config.general.simulation_seed,
+        stop_secs,
+        &mut report,
     );
+    for (agent_id, workload) in &workloads {
+        files.push((
+            workload_file(shared_dir_path, agent_id),
+            serde_json::to_string_pretty(workload)?,
+        ));
+    }
+    let workload = summarize_workloads(&workloads, stop_secs);
+
+    // Who the distributor pays and how much, read from the shared directory
+    if let Some(distributor) = &config.miner_distributor {
+        let plan = build_distribution_plan(
+            distributor,
+            &registry_context.effective_agents,
+            &workloads,
+            &mut report,
+        )
+        .map_err(|e| color_eyre::eyre::eyre!("Miner distributor configuration error: {}", e))?;
+        files.push((
+            shared_dir_path.join(DISTRIBUTION_PLAN_FILE),
+            serde_json::to_string_pretty(&plan)?,
+        ));
+    }
 
-    // Build + validate the miner registry from agents flagged as miners.
-    let miner_registry = build_miner_registry(&config.agents, &agent_registry);
+    // Deterministic wallets, restored through --wallet-keys-file
+    for (agent_id, bootstrap) in build_wallet_bootstraps(
+        &config.agents,
+        config.general.simulation_seed,
+        config.general.chain,
+    ) {
+        files.push((
+            wallet_keys_file(shared_dir_path, &agent_id),
+            serde_json::to_string_pretty(&bootstrap)?,
+        ));
+    }
 
-    // Write miner registry to file
-    let miner_registry_path = shared_dir_path.join("miners.json");
-    let miner_registry_json = serde_json::to_string_pretty(&miner_registry)?;
-    std::fs::write(&miner_registry_path, &miner_registry_json)?;
+    let simulation_metadata = SimulationMetadata {
+        generator_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: crate::ARTIFACT_SCHEMA_VERSION,
+        simulation_seed: config.general.simulation_seed,
+        binaries: collect_binary_paths(config)?
+            .into_iter()
+            .map(|(path, used_by)| {
+                let version = capture_version(&path);
+                (path, BinaryMetadata { used_by, version })
+            })
+            .collect(),
+    };
+    files.push((
+        shared_dir_path.join("simulation_metadata.json"),
+        serde_json::to_string_pretty(&simulation_metadata)?,
+    ));
+    let binary_versions = simulation_metadata
+        .binaries
+        .iter()
+        .map(|(path, metadata)| BinaryVersion {
+            path: path.clone(),
+            version: metadata.version.clone(),
+        })
+        .collect();
 
-    // Pre-create wallet directories for all agents that have wallets.
-    // This replaces the per-agent bash cleanup processes that previously ran
-    // inside the simulation to `rm -rf && mkdir -p && chmod 755` wallet dirs.
-    // Since main.rs already cleans /tmp/monerosim_shared/ before generation,
-    // we just need to create fresh directories with correct permissions.
-    for (agent_id, agent_config) in config.agents.agents.iter() {
-        if agent_config.has_wallet() || agent_config.has_wallet_phases() {
-            let wallet_dir = shared_dir_path.join(format!("{}_wallet", agent_id));
-            fs::create_dir_all(&wallet_dir).map_err(|e| {
-                color_eyre::eyre::eyre!("Failed to create wallet dir {:?}: {}", wallet_dir, e)
-            })?;
-            // Set permissions explicitly (monero-wallet-rpc can create files with restrictive perms)
-            let mut perms = fs::metadata(&wallet_dir)?.permissions();
-            use std::os::unix::fs::PermissionsExt;
-            perms.set_mode(0o755);
-            fs::set_permissions(&wallet_dir, perms)?;
-        }
-    }
+    let wallet_dirs: Vec<PathBuf> = config
+        .agents
+        .agents
+        .iter()
+        .filter(|(_, agent_config)| agent_config.has_wallet() || agent_config.has_wallet_phases())
+        .map(|(agent_id, _)| shared_dir_path.join(format!("{}_wallet", agent_id)))
+        .collect();
 
     // Note: GML topologies do NOT require a 1:1 mapping between nodes and Shadow hosts.
     // Shadow only requires that each host's network_node_id references a valid GML node.
@@ -1031,34 +1917,88 @@ pub fn generate_agent_shadow_config(
 
     // BTreeMap is already sorted by key, ensuring consistent ordering in output
 
-    // Parse stop_time to seconds
-    let stop_time_seconds = parse_duration_to_seconds(&config.general.stop_time).map_err(|e| {
-        color_eyre::eyre::eyre!(
-            "Failed to parse stop_time '{}': {}",
-            config.general.stop_time,
-            e
-        )
-    })?;
+    // `prune_unused_nodes`: Shadow only needs the nodes carrying hosts and
+    // the shortest paths between them
+    let pruned_graph = match gml_graph.as_ref().filter(|_| *prune_unused_nodes) {
+        Some(gml) => {
+            let used: BTreeSet<u32> = hosts.values().map(|h| h.network_node_id).collect();
+            let pruned = prune_to_nodes(gml, &used)
+                .map_err(|e| color_eyre::eyre::eyre!("prune_unused_nodes: {}", e))?;
+            report.info(
+                "topology",
+                format!(
+                    "Pruned the GML topology for Shadow to {} of {} nodes and {} of {} edges",
+                    pruned.nodes.len(),
+                    gml.nodes.len(),
+                    pruned.edges.len(),
+                    gml.edges.len()
+                ),
+            );
+            Some(pruned)
+        }
+        None => None,
+    };
 
     // Build Shadow's network graph from the configured network block.
-    let shadow_graph =
-        build_shadow_network_graph(&config.network, gml_graph.as_ref(), &output_dir)?;
+    let shadow_graph = build_shadow_network_graph(
+        &config.network,
+        pruned_graph.as_ref().or(gml_graph.as_ref()),
+        &output_dir,
+        &mut files,
+    )?;
+
+    let experimental = config
+        .general
+        .shadow_experimental
+        .clone()
+        .unwrap_or_default();
+    if !experimental.passthrough.is_empty() {
+        let keys: Vec<&str> = experimental
+            .passthrough
+            .keys()
+            .map(String::as_str)
+            .collect();
+        report.info(
+            "shadow",
+            format!(
+                "Passing Shadow experimental option(s) through unchecked: {}",
+                keys.join(", ")
+            ),
+        );
+    }
+
+    // `shadow:` wins over the older general / performance fields
+    let shadow = config.shadow.clone().unwrap_or_default();
+    let shadow_seed = shadow.seed.unwrap_or(config.general.simulation_seed);
 
     // Create final Shadow configuration
     let shadow_config = ShadowConfig {
         general: ShadowGeneral {
-            stop_time: stop_time_seconds,
-            seed: config.general.simulation_seed, // Shadow uses this to seed all RNGs for determinism
-            parallelism: config.general.parallelism, // 0=auto, 1=deterministic, N=N threads
-            model_unblocked_syscall_latency: config.performance.model_unblocked_syscall_latency,
+            stop_time: config.general.stop_time,
+            seed: shadow_seed, // Shadow uses this to seed all RNGs for determinism
+            parallelism: shadow.parallelism.unwrap_or(config.general.parallelism), // 0=auto, 1=deterministic, N=N threads
+            model_unblocked_syscall_latency: shadow
+                .model_unblocked_syscall_latency
+                .unwrap_or(config.performance.model_unblocked_syscall_latency),
             log_level: config.general.shadow_log_level.clone(), // Use shadow_log_level (default: "info")
-            bootstrap_end_time: config.general.bootstrap_end_time.clone(), // High bandwidth period for network settling
+            bootstrap_end_time: config.general.bootstrap_end_time, // High bandwidth period for network settling
             progress: config.general.progress.unwrap_or(true), // Show simulation progress on stderr (default: true)
+            data_directory: shadow.data_directory_for(shadow_seed),
         },
         experimental: ShadowExperimental {
-            runahead: config.general.runahead.clone(), // Optional runahead for performance tuning
-            use_dynamic_runahead: true,
+            // Optional runahead for performance tuning (validation rejects setting several)
+            runahead: shadow
+                .runahead
+                .or(experimental.runahead)
+                .or_else(|| config.general.runahead.clone()),
+            use_dynamic_runahead: shadow
+                .use_dynamic_runahead
+                .or(experimental.use_dynamic_runahead)
+                .unwrap_or(true),
             native_preemption_enabled: config.general.native_preemption, // Pass through config (Shadow default false when unset)
+            scheduler: experimental.scheduler.map(|s| s.as_str().to_string()),
+            max_unapplied_cpu_latency: experimental.max_unapplied_cpu_latency,
+            passthrough: experimental.passthrough,
         },
         network: ShadowNetwork {
             graph: shadow_graph,
@@ -1068,19 +2008,107 @@ pub fn generate_agent_shadow_config(
         hosts,
     };
 
-    // Write configuration
     let config_yaml = serde_yaml::to_string(&shadow_config)?;
-    std::fs::write(output_path, config_yaml)?;
+    files.push((output_path.to_path_buf(), config_yaml));
 
-    log_generation_summary(
-        config,
-        output_path,
-        &shadow_config,
-        gml_graph.as_ref(),
-        &ip_registry,
-        &agent_registry_path,
-        &miner_registry_path,
-    )?;
+    // Validate IP subnet diversity for Monero P2P compatibility
+    let all_ips: Vec<String> = ip_registry.get_all_assigned_ips().keys().cloned().collect();
+    crate::utils::validate_ip_subnet_diversity(&all_ips, shadow_config.hosts.len())
+        .map_err(|e| color_eyre::eyre::eyre!("IP diversity validation failed: {}", e))?;
 
-    Ok(())
+    // A configured seed node no simulated host answers on is dialed in vain
+    // (agent ids name a seed for `seed_selection: explicit`)
+    for seed in seed_node_list {
+        if registry_context.effective_agents.agents.contains_key(seed) {
+            continue;
+        }
+        let ip = seed.rsplit_once(':').map_or(seed.as_str(), |(ip, _)| ip);
+        if !ip_registry.get_all_assigned_ips().contains_key(ip) {
+            report.warn(
+                "seed_nodes",
+                format!(
+                    "Seed node {} is not the address of any simulated host",
+                    seed
+                ),
+            );
+        }
+    }
+
+    // Every computed start time and what it waits for, for debugging
+    for entry in startup.entries() {
+        let reason = match entry.after {
+            _ if entry.explicit => "start_time in the config".to_string(),
+            Some(step) => format!("after its {}", step.name()),
+            None => "startup stagger".to_string(),
+        };
+        trace.record(
+            STAGE_SCHEDULING,
+            entry.agent_id.as_str(),
+            format!("{} at {}s", entry.step.name(), entry.start_time),
+            reason,
+        );
+    }
+    files.push((
+        output_dir.join(STARTUP_SCHEDULE_FILE),
+        serde_json::to_string_pretty(&startup)?,
+    ));
+
+    // Peak demand against this machine's memory and open-file limit
+    let resources = estimate_resources(
+        shadow_config.hosts.values().flat_map(|h| &h.processes),
+        ResourceModel::from_config(config.general.resource_model.as_ref()),
+        MachineResources::detect(),
+    );
+    resources.check(&mut report);
+
+    report.apply_strictness(config.general.strict_validation);
+    files.push((
+        output_dir.join(VALIDATION_REPORT_FILE),
+        serde_json::to_string_pretty(&report)?,
+    ));
+    files.push((
+        output_dir.join(GENERATION_TRACE_FILE),
+        serde_json::to_string_pretty(&trace)?,
+    ));
+
+    // What the agents read from the shared dir is generated into the output
+    // directory like everything else, so a run is archived by copying that
+    // one directory; `populate_shared_dir` copies it over
+    let mut shared_files = Vec::new();
+    for (path, _) in files.iter_mut() {
+        if let Ok(relative) = path.strip_prefix(shared_dir_path) {
+            let relative = relative.to_path_buf();
+            *path = output_dir.join(&relative);
+            shared_files.push(relative);
+        }
+    }
+
+    Ok(GenerationPlan {
+        output_path: output_path.to_path_buf(),
+        shadow_config,
+        gml_graph,
+        ip_registry,
+        seed_nodes,
+        miner_count: registries.miners.miners.len(),
+        agent_registry_path,
+        miner_registry_path,
+        files,
+        shared_dir: shared_dir_path.to_path_buf(),
+        shared_files,
+        scripts,
+        wallet_dirs,
+        binary_versions,
+        report,
+        trace,
+        resources,
+        registry_context,
+        workload,
+        miner_locations,
+        pinned_locations,
+        agent_distribution,
+        network_events,
+        pcap_agents,
+        warm_start,
+        registries,
+    })
 }
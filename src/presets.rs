@@ -0,0 +1,314 @@
+//! Scenario presets for `monerosim init`.
+//!
+//! Each preset is a YAML template embedded at build time, with `{{name}}`
+//! placeholders for the role counts derived from the node count and the
+//! times derived from `stop_time`. Users are funded by the miner
+//! distributor once coinbase outputs mature, so every preset starts user
+//! activity two thirds into the run and needs at least `MIN_STOP_SECS`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{bail, eyre, Result, WrapErr};
+
+use crate::gml_parser::write_gml;
+use crate::topology::{generate_topology, TopologyParams};
+use crate::utils::duration::SimDuration;
+
+/// Shortest `stop_time` a preset accepts: coinbase maturity plus time for
+/// the distributor to fund the users and for them to transact
+pub const MIN_STOP_SECS: u64 = 3 * 3600;
+
+/// Network nodes per AS of a preset's generated topology
+const NODES_PER_AS: usize = 5;
+
+/// A bundled scenario
+#[derive(Debug)]
+pub struct Preset {
+    pub name: &'static str,
+    pub summary: &'static str,
+    template: &'static str,
+    /// Daemon-running agents when `--nodes` is not given
+    pub default_nodes: usize,
+    pub min_nodes: usize,
+    pub default_stop_time: SimDuration,
+    /// Role name -> agent count for a node count
+    roles: fn(usize) -> Vec<(&'static str, usize)>,
+    /// Whether the preset runs on a generated GML topology
+    topology: bool,
+    /// Preset-specific setup, printed after the written files
+    pub notes: &'static [&'static str],
+}
+
+/// Every bundled preset, in `init --help` order
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        name: "small-dev",
+        summary: "5 nodes on a switch: miners, users, distributor and monitor",
+        template: include_str!("presets/small-dev.yaml"),
+        default_nodes: 5,
+        min_nodes: 2,
+        default_stop_time: SimDuration::from_secs(4 * 3600),
+        roles: |nodes| {
+            let miners = (nodes * 2 / 5).max(1);
+            vec![("miners", miners), ("users", nodes - miners)]
+        },
+        topology: false,
+        notes: &[],
+    },
+    Preset {
+        name: "research-50",
+        summary: "50 nodes on a generated GML topology with pareto miners and spy nodes",
+        template: include_str!("presets/research-50.yaml"),
+        default_nodes: 50,
+        min_nodes: 10,
+        default_stop_time: SimDuration::from_secs(6 * 3600),
+        roles: |nodes| {
+            let miners = nodes / 10;
+            let spies = (nodes / 25).max(1);
+            vec![
+                ("miners", miners),
+                ("spies", spies),
+                ("users", nodes - miners - spies),
+            ]
+        },
+        topology: true,
+        notes: &[],
+    },
+    Preset {
+        name: "upgrade-ab",
+        summary: "10 nodes on a switch, half the users restarting onto a second monerod build",
+        template: include_str!("presets/upgrade-ab.yaml"),
+        default_nodes: 10,
+        min_nodes: 4,
+        default_stop_time: SimDuration::from_secs(4 * 3600),
+        roles: |nodes| {
+            let miners = (nodes / 5).max(2);
+            let users_b = (nodes - miners) / 2;
+            vec![
+                ("miners", miners),
+                ("users_a", nodes - miners - users_b),
+                ("users_b", users_b),
+            ]
+        },
+        topology: false,
+        notes: &[
+            "Install the B build as ~/.monerosim/bin/monerod-b (or put it on PATH); \
+             user-b-* restart onto it, see upgrade_manifest.json after the run",
+        ],
+    },
+];
+
+/// The preset called `name`
+pub fn find_preset(name: &str) -> Result<&'static Preset, String> {
+    PRESETS.iter().find(|p| p.name == name).ok_or_else(|| {
+        let names: Vec<&str> = PRESETS.iter().map(|p| p.name).collect();
+        format!(
+            "Unknown preset '{}' (available: {})",
+            name,
+            names.join(", ")
+        )
+    })
+}
+
+/// What `init` may override; unset values take the preset's defaults
+#[derive(Debug, Clone, Default)]
+pub struct PresetParams {
+    pub nodes: Option<usize>,
+    pub stop_time: Option<SimDuration>,
+}
+
+impl Preset {
+    /// File name of the written config
+    pub fn config_file(&self) -> String {
+        format!("{}.yaml", self.name)
+    }
+
+    /// File name of the written topology, for presets that have one
+    pub fn topology_file(&self) -> Option<String> {
+        self.topology.then(|| format!("{}.gml", self.name))
+    }
+
+    /// The config with every placeholder filled in; `topology` is the
+    /// `network.path` of presets with a generated topology.
+    pub fn render(&self, params: &PresetParams, topology: &str) -> Result<String, String> {
+        let nodes = params.nodes.unwrap_or(self.default_nodes);
+        if nodes < self.min_nodes {
+            return Err(format!(
+                "Preset '{}' needs at least {} nodes, got {}",
+                self.name, self.min_nodes, nodes
+            ));
+        }
+        let stop_time = params.stop_time.unwrap_or(self.default_stop_time);
+        let stop = stop_time.as_secs();
+        if stop < MIN_STOP_SECS {
+            return Err(format!(
+                "Preset '{}' needs a stop time of at least {}s so users are funded \
+                 before they transact, got {}",
+                self.name, MIN_STOP_SECS, stop_time
+            ));
+        }
+        let activity_start = stop * 2 / 3;
+        let upgrade_at = activity_start + (stop - activity_start) / 2;
+
+        let mut values: BTreeMap<&str, String> = (self.roles)(nodes)
+            .into_iter()
+            .map(|(role, count)| (role, count.to_string()))
+            .collect();
+        values.insert("stop_time", stop.to_string());
+        values.insert("activity_start", activity_start.to_string());
+        values.insert("upgrade_at", upgrade_at.to_string());
+        values.insert("upgraded_at", (upgrade_at + 120).to_string());
+        values.insert("topology", topology.to_string());
+        let topology_params = self.topology_params(nodes);
+        values.insert(
+            "topology_nodes",
+            (topology_params.as_groups * topology_params.nodes_per_as).to_string(),
+        );
+
+        let mut config = self.template.to_string();
+        for (name, value) in &values {
+            config = config.replace(&format!("{{{{{}}}}}", name), value);
+        }
+        if let Some(start) = config.find("{{") {
+            let end = config[start..]
+                .find("}}")
+                .map_or(config.len(), |e| start + e + 2);
+            return Err(format!(
+                "Preset '{}' has no value for {}",
+                self.name,
+                &config[start..end]
+            ));
+        }
+        Ok(config)
+    }
+
+    /// One AS per `NODES_PER_AS` agents, with `gen-topology`'s defaults
+    fn topology_params(&self, nodes: usize) -> TopologyParams {
+        TopologyParams {
+            as_groups: nodes.div_ceil(NODES_PER_AS).max(2),
+            nodes_per_as: NODES_PER_AS,
+            intra_latency_ms: (1.0, 5.0),
+            inter_latency_ms: (20.0, 150.0),
+            bandwidth_classes: vec!["100Mbit".to_string(), "1Gbit".to_string()],
+            seed: 12345,
+        }
+    }
+}
+
+/// Write `preset`'s config, and its topology if it has one, into `dir`.
+/// Existing files are only replaced with `force`. Returns the written paths,
+/// config first.
+pub fn write_preset(
+    preset: &Preset,
+    params: &PresetParams,
+    dir: &Path,
+    force: bool,
+) -> Result<Vec<PathBuf>> {
+    let config_path = dir.join(preset.config_file());
+    let topology_path = preset.topology_file().map(|file| dir.join(file));
+    for path in std::iter::once(&config_path).chain(topology_path.as_ref()) {
+        if path.exists() && !force {
+            bail!(
+                "'{}' already exists; pass --force to overwrite it",
+                path.display()
+            );
+        }
+    }
+
+    // Relative to where monerosim runs, so a preset written into the
+    // current directory keeps a bare file name
+    let network_path = match preset.topology_file() {
+        Some(file) if dir == Path::new(".") => file,
+        Some(file) => dir.join(file).to_string_lossy().to_string(),
+        None => String::new(),
+    };
+    let config = preset
+        .render(params, &network_path)
+        .map_err(|e| eyre!("{}", e))?;
+
+    let mut written = Vec::new();
+    if let Some(path) = &topology_path {
+        let nodes = params.nodes.unwrap_or(preset.default_nodes);
+        let graph =
+            generate_topology(&preset.topology_params(nodes)).map_err(|e| eyre!("{}", e))?;
+        fs::write(path, write_gml(&graph))
+            .wrap_err_with(|| format!("Failed to write '{}'", path.display()))?;
+        written.push(path.clone());
+    }
+    fs::write(&config_path, config)
+        .wrap_err_with(|| format!("Failed to write '{}'", config_path.display()))?;
+    written.insert(0, config_path);
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roles_add_up_to_the_node_count() {
+        for preset in PRESETS {
+            for nodes in [preset.min_nodes, preset.default_nodes, 200] {
+                let roles = (preset.roles)(nodes);
+                assert_eq!(
+                    roles.iter().map(|(_, n)| n).sum::<usize>(),
+                    nodes,
+                    "{} with {} nodes",
+                    preset.name,
+                    nodes
+                );
+                assert!(
+                    roles.iter().all(|(_, n)| *n > 0),
+                    "{}: {:?}",
+                    preset.name,
+                    roles
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rendering_fills_every_placeholder() {
+        let preset = find_preset("upgrade-ab").unwrap();
+        let config = preset
+            .render(
+                &PresetParams {
+                    nodes: Some(11),
+                    stop_time: Some(SimDuration::from_secs(6 * 3600)),
+                },
+                "",
+            )
+            .unwrap();
+        assert!(config.contains("stop_time: 21600s"), "{}", config);
+        assert!(config.contains("activity_start_time: 14400"));
+        assert!(config.contains("{stop_at: 18000s, start_at: 18120s, binary_path: monerod-b}"));
+        assert!(config.contains("count: 5\n"), "users_a");
+        assert!(config.contains("count: 4\n"), "users_b");
+
+        let err = preset
+            .render(
+                &PresetParams {
+                    nodes: Some(3),
+                    stop_time: None,
+                },
+                "",
+            )
+            .unwrap_err();
+        assert!(err.contains("at least 4 nodes"), "{}", err);
+        let err = preset
+            .render(
+                &PresetParams {
+                    nodes: None,
+                    stop_time: Some(SimDuration::from_secs(3600)),
+                },
+                "",
+            )
+            .unwrap_err();
+        assert!(err.contains("stop time of at least"), "{}", err);
+        assert!(find_preset("huge")
+            .unwrap_err()
+            .contains("small-dev, research-50"));
+    }
+}
@@ -3,12 +3,124 @@
 //! This file handles generation of Shadow process configurations
 //! for Python agent scripts.
 
-use crate::shadow::ShadowProcess;
-use crate::utils::duration::parse_duration_to_seconds;
-use crate::utils::script::write_wrapper_script;
+use crate::healthcheck::{HEALTHCHECK_BINARY, READINESS_DIR};
+use crate::shadow::{ExpectedFinalState, ShadowProcess};
+use crate::utils::duration::SimDuration;
+use crate::utils::options::shell_quote;
+use crate::utils::script::{write_wrapper_script, ScriptSet};
+use crate::utils::seed::agent_seed;
 use std::collections::BTreeMap;
 use std::path::Path;
 
+/// `--log-level` for an agent script: `general.log_level` (exported to the
+/// environment as `MONEROSIM_LOG_LEVEL`), DEBUG when unset.
+pub fn log_level_arg(environment: &BTreeMap<String, String>) -> String {
+    format!(
+        "--log-level {}",
+        environment
+            .get("MONEROSIM_LOG_LEVEL")
+            .map_or("DEBUG", String::as_str)
+    )
+}
+
+/// `--network` for an agent script: `general.chain` (exported to the
+/// environment as `MONEROSIM_CHAIN`), regtest when unset.
+pub fn network_arg(environment: &BTreeMap<String, String>) -> String {
+    format!(
+        "--network {}",
+        environment
+            .get("MONEROSIM_CHAIN")
+            .map_or("regtest", String::as_str)
+    )
+}
+
+/// `--random-seed` for an agent script, derived from the agent id and the
+/// `SIMULATION_SEED` the generator exports; None without one.
+pub fn random_seed_arg(environment: &BTreeMap<String, String>, agent_id: &str) -> Option<String> {
+    let seed: u64 = environment.get("SIMULATION_SEED")?.parse().ok()?;
+    Some(format!("--random-seed {}", agent_seed(seed, agent_id)))
+}
+
+/// `--stop-time` for an agent script: the simulated second at which it
+/// leaves its run loop and exits.
+pub fn stop_time_arg(stop_secs: u64) -> String {
+    format!("--stop-time {}", stop_secs)
+}
+
+/// Expected final state of an agent script stopping at `stop_secs` on a
+/// host whose processes run until `end_secs`: one stopping earlier has
+/// exited by itself, otherwise it is still running at the end.
+pub fn script_final_state(stop_secs: u64, end_secs: u64) -> ExpectedFinalState {
+    if stop_secs < end_secs {
+        ExpectedFinalState::Exited(0)
+    } else {
+        ExpectedFinalState::Running
+    }
+}
+
+/// Attempts after the first for the daemon check, matching the agents' own
+/// `wait_until_ready(max_wait=120)`
+const DAEMON_READY_RETRIES: u32 = 120;
+/// Attempts after the first for the wallet check (`max_wait=180`)
+const WALLET_READY_RETRIES: u32 = 180;
+
+/// Wrapper script lines that wait for the agent's local daemon and wallet
+/// RPC with `monerosim-healthcheck`, both checks running at once, and
+/// record each outcome in `<shared_dir>/readiness/`. Skipped when the
+/// binary is not installed; the agent then only relies on its own retries.
+pub fn readiness_checks(
+    agent_id: &str,
+    ip: &str,
+    daemon_rpc_port: Option<u16>,
+    wallet_rpc_port: Option<u16>,
+    shared_dir: &Path,
+) -> String {
+    let checks: Vec<String> = [
+        (
+            "daemon",
+            daemon_rpc_port,
+            "get_info",
+            "result.height",
+            DAEMON_READY_RETRIES,
+        ),
+        (
+            "wallet",
+            wallet_rpc_port,
+            "get_version",
+            "result.version",
+            WALLET_READY_RETRIES,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(target, port, method, field, retries)| {
+        Some(format!(
+            "  {} --url http://{}:{}/json_rpc --method {} --expect-json-field {} \
+             --timeout 1 --retries {} --agent {} --target {} --report {}/{}/{}_{}.json &",
+            HEALTHCHECK_BINARY,
+            ip,
+            port?,
+            method,
+            field,
+            retries,
+            agent_id,
+            target,
+            shared_dir.to_string_lossy(),
+            READINESS_DIR,
+            agent_id,
+            target
+        ))
+    })
+    .collect();
+    if checks.is_empty() {
+        return String::new();
+    }
+    format!(
+        "if command -v {} >/dev/null 2>&1; then\n{}\n  wait\nfi\n",
+        HEALTHCHECK_BINARY,
+        checks.join("\n")
+    )
+}
+
 /// Arguments for `add_user_agent_process`.
 pub struct UserAgentProcessArgs<'a> {
     pub processes: &'a mut Vec<ShadowProcess>,
@@ -22,13 +134,21 @@ pub struct UserAgentProcessArgs<'a> {
     pub environment: &'a BTreeMap<String, String>,
     pub shared_dir: &'a Path,
     pub current_dir: &'a str,
-    pub index: usize,
-    pub stop_time: &'a str,
-    pub custom_start_time: Option<&'a str>,
+    /// Seconds, from the `StartupScheduler`
+    pub start_time: u64,
+    /// Second the script stops, passed as `--stop-time`
+    pub stop_time: u64,
+    /// Second the agent's processes end: its `shutdown_time` or the
+    /// simulation end
+    pub end_time: u64,
     pub remote_daemon: Option<&'a str>,
     pub daemon_selection_strategy: Option<&'a str>,
-    pub scripts_dir: &'a Path,
+    pub scripts: &'a ScriptSet,
     pub wallet_rpc_cmd: Option<&'a str>,
+    /// The agent's `{agent_id}_workload.json`, if it has a `workload`
+    pub workload_file: Option<&'a Path>,
+    /// The agent's `{agent_id}_wallet_keys.json`, if it has a wallet
+    pub wallet_keys_file: Option<&'a Path>,
 }
 
 /// Add a user agent process to the processes list
@@ -40,9 +160,11 @@ pub fn add_user_agent_process(args: UserAgentProcessArgs<'_>) {
         format!("--id {}", args.agent_id),
         format!("--shared-dir {}", args.shared_dir.to_string_lossy()),
         format!("--rpc-host {}", args.agent_ip),
-        format!("--log-level DEBUG"),
-        format!("--stop-time {}", args.stop_time),
+        log_level_arg(args.environment),
+        network_arg(args.environment),
+        stop_time_arg(args.stop_time),
     ];
+    agent_args.extend(random_seed_arg(args.environment, args.agent_id));
 
     // Add local daemon RPC port if available
     if let Some(port) = args.daemon_rpc_port {
@@ -69,23 +191,35 @@ pub fn add_user_agent_process(args: UserAgentProcessArgs<'_>) {
         agent_args.push(format!("--daemon-selection-strategy {}", strategy));
     }
 
+    // Send times from the agent's workload profile
+    if let Some(path) = args.workload_file {
+        agent_args.push(format!("--workload-file {}", path.to_string_lossy()));
+    }
+
+    // Keys the agent restores its wallet from
+    if let Some(path) = args.wallet_keys_file {
+        agent_args.push(format!("--wallet-keys-file {}", path.to_string_lossy()));
+    }
+
     // Add attributes from config as command-line arguments
     // This ensures attributes are available inside Shadow's isolated filesystem
     if let Some(attrs) = args.attributes {
         for (key, value) in attrs {
             // Map transaction_interval to --tx-frequency for backward compatibility
             if key == "transaction_interval" {
-                agent_args.push(format!("--tx-frequency {}", value));
+                agent_args.push(format!("--tx-frequency {}", shell_quote(value)));
             }
             // Pass ALL attributes as --attributes key value pairs
-            // This bypasses Shadow filesystem isolation issues
-            agent_args.push(format!("--attributes {} {}", key, value));
+            // This bypasses Shadow filesystem isolation issues. Values are
+            // free-form config text, so quote them for the wrapper's shell.
+            agent_args.push(format!(
+                "--attributes {} {}",
+                shell_quote(key),
+                shell_quote(value)
+            ));
         }
     }
 
-    // Remove stop-time from agent args since agents handle their own lifecycle
-    agent_args.retain(|arg| !arg.starts_with("--stop-time"));
-
     // `exec` so bash is replaced by python3 — Shadow's SIGTERM at shutdown
     // then goes directly to the agent (which has its own SIGTERM handler in
     // base_agent.py) instead of being absorbed by an idle bash parent.
@@ -105,8 +239,9 @@ pub fn add_user_agent_process(args: UserAgentProcessArgs<'_>) {
 
     // Create wrapper script with fully-resolved paths.
     // No shell variable expansion needed - all paths are absolute.
-    // Python agents handle their own RPC readiness retries via
-    // wait_until_ready() with exponential backoff in base_agent.py.
+    // monerosim-healthcheck records when the RPCs came up; the Python
+    // agents still retry on their own via wait_until_ready() in
+    // base_agent.py.
     let wallet_export = match args.wallet_rpc_cmd {
         // Outer double-quotes make the assignment a single word; the inner
         // single-quoted segments produced by shell_quote_args are then
@@ -123,36 +258,33 @@ pub fn add_user_agent_process(args: UserAgentProcessArgs<'_>) {
         .cloned()
         .unwrap_or_default();
 
+    let readiness = readiness_checks(
+        args.agent_id,
+        args.agent_ip,
+        args.daemon_rpc_port,
+        args.wallet_rpc_port,
+        args.shared_dir,
+    );
+
     let wrapper_content = format!(
         r#"#!/bin/bash
 cd {}
 export PYTHONPATH={}:{}
 export PATH="$PATH:{}/.monerosim/bin"
-{}
+{}{}
 {} 2>&1
 "#,
-        args.current_dir, args.current_dir, venv_sp, home_dir, wallet_export, python_cmd
+        args.current_dir, args.current_dir, venv_sp, home_dir, readiness, wallet_export, python_cmd
     );
 
-    // Determine start time
-    let start_time = if let Some(custom_time) = args.custom_start_time {
-        if parse_duration_to_seconds(custom_time).is_ok() {
-            custom_time.to_string()
-        } else {
-            format!("{}s", 65 + args.index * 2)
-        }
-    } else {
-        format!("{}s", 65 + args.index * 2)
-    };
-
     match write_wrapper_script(
-        args.scripts_dir,
+        args.scripts,
         &format!("agent_{}_wrapper.sh", args.agent_id),
         &wrapper_content,
         args.environment,
-        start_time,
+        SimDuration::from_secs(args.start_time),
         None,
-        Some(crate::shadow::ExpectedFinalState::Running),
+        Some(script_final_state(args.stop_time, args.end_time)),
     ) {
         Ok(process) => args.processes.push(process),
         Err(e) => log::error!(
@@ -174,10 +306,21 @@ pub struct MiningAgentProcessArgs<'a> {
     pub environment: &'a BTreeMap<String, String>,
     pub shared_dir: &'a Path,
     pub current_dir: &'a str,
-    pub index: usize,
-    pub custom_start_time: Option<&'a str>,
-    pub scripts_dir: &'a Path,
+    /// Seconds, from the `StartupScheduler`
+    pub start_time: u64,
+    /// Second the script stops, passed as `--stop-time`
+    pub stop_time: u64,
+    /// Second the agent's processes end: its `shutdown_time` or the
+    /// simulation end
+    pub end_time: u64,
+    pub scripts: &'a ScriptSet,
     pub wallet_rpc_cmd: Option<&'a str>,
+    /// The daemon starts on an existing chain rather than at genesis
+    pub warm_start: bool,
+    /// Target seconds between blocks across all miners
+    pub block_interval: u64,
+    /// Mining pool of the miner, whose `miners_<pool>.json` it reads
+    pub pool: Option<&'a str>,
 }
 
 /// Create mining agent processes
@@ -192,18 +335,39 @@ pub fn create_mining_agent_process(args: MiningAgentProcessArgs<'_>) -> Vec<Shad
         format!("--rpc-host {}", args.ip_addr),
         format!("--daemon-rpc-port {}", args.daemon_rpc_port),
         format!("--shared-dir {}", args.shared_dir.to_string_lossy()),
-        format!("--log-level DEBUG"),
+        log_level_arg(args.environment),
+        network_arg(args.environment),
+        stop_time_arg(args.stop_time),
     ];
+    script_args.extend(random_seed_arg(args.environment, args.agent_id));
 
     // Add wallet RPC port if provided
     if let Some(wallet_port) = args.wallet_rpc_port {
         script_args.push(format!("--wallet-rpc-port {}", wallet_port));
     }
 
+    if args.warm_start {
+        script_args.push("--warm-start".to_string());
+    }
+    script_args.push(format!("--block-interval {}", args.block_interval));
+    if let Some(pool) = args.pool {
+        script_args.push(format!("--pool {}", pool));
+        script_args.push(format!(
+            "--pool-registry {}",
+            args.shared_dir
+                .join(crate::registry::pool_registry_file(pool))
+                .to_string_lossy()
+        ));
+    }
+
     // Add attributes as key-value pairs
     if let Some(attrs) = args.attributes {
         for (key, value) in attrs {
-            script_args.push(format!("--attributes {} {}", key, value));
+            script_args.push(format!(
+                "--attributes {} {}",
+                shell_quote(key),
+                shell_quote(value)
+            ));
         }
     }
 
@@ -249,36 +413,33 @@ pub fn create_mining_agent_process(args: MiningAgentProcessArgs<'_>) -> Vec<Shad
         .cloned()
         .unwrap_or_default();
 
+    let readiness = readiness_checks(
+        args.agent_id,
+        args.ip_addr,
+        Some(args.daemon_rpc_port),
+        args.wallet_rpc_port,
+        args.shared_dir,
+    );
+
     let wrapper_content = format!(
         r#"#!/bin/bash
 cd {}
 export PYTHONPATH={}:{}
 export PATH="$PATH:{}/.monerosim/bin"
-{}
+{}{}
 {} 2>&1
 "#,
-        args.current_dir, args.current_dir, venv_sp, home_dir, wallet_export, python_cmd
+        args.current_dir, args.current_dir, venv_sp, home_dir, readiness, wallet_export, python_cmd
     );
 
-    // Determine start time
-    let start_time = if let Some(custom_time) = args.custom_start_time {
-        if parse_duration_to_seconds(custom_time).is_ok() {
-            custom_time.to_string()
-        } else {
-            format!("{}s", 65 + args.index * 2)
-        }
-    } else {
-        format!("{}s", 65 + args.index * 2)
-    };
-
     match write_wrapper_script(
-        args.scripts_dir,
+        args.scripts,
         &format!("mining_agent_{}_wrapper.sh", args.agent_id),
         &wrapper_content,
         args.environment,
-        start_time,
+        SimDuration::from_secs(args.start_time),
         None,
-        Some(crate::shadow::ExpectedFinalState::Running),
+        Some(script_final_state(args.stop_time, args.end_time)),
     ) {
         Ok(process) => vec![process],
         Err(e) => {
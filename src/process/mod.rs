@@ -1,10 +1,18 @@
 //! Shadow process configuration for daemons, wallets, and agent scripts.
 
 pub mod agent_scripts;
+pub mod partition;
+pub mod types;
 pub mod wallet;
 
 pub use agent_scripts::{
-    add_user_agent_process, create_mining_agent_process, MiningAgentProcessArgs,
+    add_user_agent_process, create_mining_agent_process, log_level_arg, network_arg,
+    random_seed_arg, readiness_checks, script_final_state, stop_time_arg, MiningAgentProcessArgs,
     UserAgentProcessArgs,
 };
+pub use partition::{partition_processes, PartitionDaemon};
+pub use types::{
+    assign_agent_ports, AgentPorts, AgentStartup, PortAllocator, StartupEntry, StartupRole,
+    StartupScheduler, StartupSettings, StartupStep, STARTUP_SCHEDULE_FILE,
+};
 pub use wallet::{add_wallet_process, build_wallet_args, DaemonAddress, WalletProcessArgs};
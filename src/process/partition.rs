@@ -0,0 +1,132 @@
+//! Helper processes enforcing `network.events` partitions.
+//!
+//! Each grouped daemon gets one short-lived process per event: at a
+//! partition it bans every other group's IP with monerod's `set_bans` RPC
+//! (dropping the open connections too), at the heal it lifts those bans.
+//! See `topology::partition` for the schedule itself.
+
+use std::collections::BTreeMap;
+
+use crate::config::NetworkEventAction;
+use crate::shadow::{ExpectedFinalState, ShadowProcess};
+use crate::topology::{partition_windows, ScheduledNetworkEvent};
+use crate::utils::duration::SimDuration;
+use crate::utils::script::{write_wrapper_script, ScriptSet};
+
+/// Attempts, a second apart, before a helper gives up on its daemon's RPC
+const SET_BANS_ATTEMPTS: u32 = 30;
+
+/// A daemon's address as the helpers see it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionDaemon {
+    pub ip: String,
+    pub rpc_port: u16,
+}
+
+/// Plan the `set_bans` helpers for `schedule`, returned per agent id.
+/// `daemons` holds every grouped daemon; bans last until the heal, or the
+/// simulation end at `stop_secs` when there is none.
+pub fn partition_processes(
+    schedule: &[ScheduledNetworkEvent],
+    daemons: &BTreeMap<String, PartitionDaemon>,
+    stop_secs: u64,
+    environment: &BTreeMap<String, String>,
+    scripts: &ScriptSet,
+) -> color_eyre::eyre::Result<Vec<(String, ShadowProcess)>> {
+    let mut processes = Vec::new();
+    for (n, window) in partition_windows(schedule).into_iter().enumerate() {
+        let groups = &window.partition.groups;
+        let ip_of = |id: &str| {
+            daemons.get(id).ok_or_else(|| {
+                color_eyre::eyre::eyre!("network.events: no daemon host for '{}'", id)
+            })
+        };
+        for (g, group) in groups.iter().enumerate() {
+            let mut others: Vec<&str> = Vec::new();
+            for id in groups
+                .iter()
+                .enumerate()
+                .filter(|(o, _)| *o != g)
+                .flat_map(|(_, o)| o)
+            {
+                others.push(&ip_of(id)?.ip);
+            }
+            // Co-located agents share an IP
+            others.sort_unstable();
+            others.dedup();
+
+            for id in group {
+                let daemon = ip_of(id)?;
+                let until = window.heal_at.unwrap_or(stop_secs);
+                let mut steps = vec![(
+                    NetworkEventAction::Partition,
+                    window.partition.at,
+                    set_bans_body(&others, true, until - window.partition.at),
+                )];
+                if let Some(heal_at) = window.heal_at {
+                    steps.push((
+                        NetworkEventAction::Heal,
+                        heal_at,
+                        set_bans_body(&others, false, 0),
+                    ));
+                }
+                for (action, at, body) in steps {
+                    let content = set_bans_script(id, daemon, action, &body);
+                    let process = write_wrapper_script(
+                        scripts,
+                        &format!("network_event_{}_{}_{}.sh", n, action.as_str(), id),
+                        &content,
+                        environment,
+                        SimDuration::from_secs(at),
+                        None,
+                        Some(ExpectedFinalState::Exited(0)),
+                    )?;
+                    processes.push((id.clone(), process));
+                }
+            }
+        }
+    }
+    Ok(processes)
+}
+
+/// `set_bans` request banning (or unbanning) `ips` for `seconds`
+fn set_bans_body(ips: &[&str], ban: bool, seconds: u64) -> String {
+    let bans: Vec<String> = ips
+        .iter()
+        .map(|ip| format!(r#"{{"host":"{}","ban":{},"seconds":{}}}"#, ip, ban, seconds))
+        .collect();
+    format!(
+        r#"{{"jsonrpc":"2.0","id":"0","method":"set_bans","params":{{"bans":[{}]}}}}"#,
+        bans.join(",")
+    )
+}
+
+/// Retry `body` against the daemon until it answers OK; exit 1 if it never
+/// does, so Shadow flags the failed event
+fn set_bans_script(
+    agent_id: &str,
+    daemon: &PartitionDaemon,
+    action: NetworkEventAction,
+    body: &str,
+) -> String {
+    format!(
+        r#"#!/bin/bash
+# network.events {action} for {agent_id}
+for attempt in $(seq 1 {attempts}); do
+  if curl -s --max-time 5 -H 'Content-Type: application/json' \
+    -d '{body}' http://{ip}:{port}/json_rpc | grep -q '"status": *"OK"'; then
+    exit 0
+  fi
+  sleep 1
+done
+echo "set_bans ({action}) failed on {agent_id}" >&2
+exit 1
+"#,
+        action = action.as_str(),
+        agent_id = agent_id,
+        attempts = SET_BANS_ATTEMPTS,
+        body = body,
+        ip = daemon.ip,
+        port = daemon.rpc_port,
+    )
+}
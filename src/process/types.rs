@@ -0,0 +1,575 @@
+//! Per-agent port assignment and startup scheduling.
+//!
+//! Every agent normally has a Shadow host of its own and listens on the
+//! standard Monero ports of `general.chain`. Agents that share a host (`colocate_with`) need
+//! distinct ports, which `PortAllocator` hands out per host.
+//!
+//! `StartupScheduler` derives every process start time from what it waits
+//! for (a wallet its daemon, a script its wallet, users and the miner
+//! distributor block maturity) and the `general.startup` pacing knobs.
+//! Block maturity defaults to 60 blocks at `block_controller.block_interval`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::config::{AgentConfig, BlockControllerConfig, Chain, StartupConfig};
+
+/// Startup schedule written next to the Shadow config for debugging
+pub const STARTUP_SCHEDULE_FILE: &str = "startup_schedule.json";
+
+/// Gap between successive default port triples on one host
+/// (18080/18081/18082, then 18090/18091/18092, ...)
+pub const PORT_STRIDE: u16 = 10;
+
+/// Ports an agent's daemon and wallet listen on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgentPorts {
+    pub p2p: u16,
+    pub rpc: u16,
+    pub wallet_rpc: u16,
+}
+
+impl Default for AgentPorts {
+    fn default() -> Self {
+        AgentPorts {
+            p2p: crate::MONERO_P2P_PORT,
+            rpc: crate::MONERO_RPC_PORT,
+            wallet_rpc: crate::MONERO_WALLET_RPC_PORT,
+        }
+    }
+}
+
+impl AgentPorts {
+    /// monerod's and monero-wallet-rpc's default ports on `chain`
+    pub fn for_chain(chain: Chain) -> Self {
+        let base = AgentPorts::default();
+        let offset = chain.port_offset();
+        AgentPorts {
+            p2p: base.p2p + offset,
+            rpc: base.rpc + offset,
+            wallet_rpc: base.wallet_rpc + offset,
+        }
+    }
+}
+
+/// Hands out non-conflicting port triples per Shadow host
+#[derive(Debug, Default)]
+pub struct PortAllocator {
+    /// First triple tried on every host
+    base: AgentPorts,
+    used: BTreeMap<String, BTreeSet<u16>>,
+}
+
+impl PortAllocator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allocator whose triples start at `chain`'s default ports
+    pub fn for_chain(chain: Chain) -> Self {
+        PortAllocator {
+            base: AgentPorts::for_chain(chain),
+            used: BTreeMap::new(),
+        }
+    }
+
+    /// Claim `port` on `host`; Err if something on the host already has it
+    pub fn reserve(&mut self, host: &str, port: u16) -> Result<(), String> {
+        if self.used.entry(host.to_string()).or_default().insert(port) {
+            Ok(())
+        } else {
+            Err(format!("port {} is already used on host '{}'", port, host))
+        }
+    }
+
+    /// Ports for an agent on `host`. Requested ports are taken as given
+    /// (reserve them first); the rest come from the first default triple
+    /// whose matching ports are all free.
+    pub fn allocate(
+        &mut self,
+        host: &str,
+        p2p: Option<u16>,
+        rpc: Option<u16>,
+        wallet_rpc: Option<u16>,
+    ) -> Result<AgentPorts, String> {
+        let used = self.used.entry(host.to_string()).or_default();
+        let base = self.base;
+        let shifted = |port: u16, k: u16| k.checked_mul(PORT_STRIDE)?.checked_add(port);
+        for k in 0.. {
+            let candidate = match (
+                shifted(base.p2p, k),
+                shifted(base.rpc, k),
+                shifted(base.wallet_rpc, k),
+            ) {
+                (Some(p2p), Some(rpc), Some(wallet_rpc)) => AgentPorts {
+                    p2p,
+                    rpc,
+                    wallet_rpc,
+                },
+                _ => break,
+            };
+            let ports = AgentPorts {
+                p2p: p2p.unwrap_or(candidate.p2p),
+                rpc: rpc.unwrap_or(candidate.rpc),
+                wallet_rpc: wallet_rpc.unwrap_or(candidate.wallet_rpc),
+            };
+            let picked = [
+                (p2p, ports.p2p),
+                (rpc, ports.rpc),
+                (wallet_rpc, ports.wallet_rpc),
+            ]
+            .into_iter()
+            .filter(|(requested, _)| requested.is_none())
+            .map(|(_, port)| port);
+            if picked.clone().all(|port| !used.contains(&port)) {
+                used.extend(picked);
+                return Ok(ports);
+            }
+        }
+        Err(format!("no free port triple left on host '{}'", host))
+    }
+}
+
+/// Ports for every agent, keyed by agent id. Explicit `p2p_port`,
+/// `rpc_port` and `wallet_rpc_port` are reserved first, so a default
+/// triple (from `chain`'s ports) never takes a port another agent on the
+/// host asked for.
+pub fn assign_agent_ports(
+    agents: &BTreeMap<String, AgentConfig>,
+    chain: Chain,
+) -> Result<BTreeMap<String, AgentPorts>, String> {
+    let mut allocator = PortAllocator::for_chain(chain);
+    for (agent_id, agent) in agents {
+        let host = agent.host_id(agent_id);
+        for (field, port) in [
+            ("p2p_port", agent.p2p_port),
+            ("rpc_port", agent.rpc_port),
+            ("wallet_rpc_port", agent.wallet_rpc_port),
+        ] {
+            if let Some(port) = port {
+                allocator
+                    .reserve(host, port)
+                    .map_err(|e| format!("Agent '{}' {}: {}", agent_id, field, e))?;
+            }
+        }
+    }
+    agents
+        .iter()
+        .map(|(agent_id, agent)| {
+            allocator
+                .allocate(
+                    agent.host_id(agent_id),
+                    agent.p2p_port,
+                    agent.rpc_port,
+                    agent.wallet_rpc_port,
+                )
+                .map(|ports| (agent_id.clone(), ports))
+                .map_err(|e| format!("Agent '{}': {}", agent_id, e))
+        })
+        .collect()
+}
+
+/// `general.startup` with defaults applied, in seconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct StartupSettings {
+    pub stagger: u64,
+    pub max_per_second: Option<u32>,
+    pub block_maturity: u64,
+}
+
+impl Default for StartupSettings {
+    fn default() -> Self {
+        StartupSettings {
+            stagger: 1,
+            max_per_second: None,
+            block_maturity: crate::BLOCK_MATURITY_SECONDS,
+        }
+    }
+}
+
+impl StartupSettings {
+    /// `block_interval` (seconds) sets the default block maturity
+    pub fn from_config(
+        config: Option<&StartupConfig>,
+        block_interval: u64,
+    ) -> Result<Self, String> {
+        let mut settings = StartupSettings {
+            block_maturity: BlockControllerConfig::maturity_secs(block_interval),
+            ..StartupSettings::default()
+        };
+        let Some(config) = config else {
+            return Ok(settings);
+        };
+        if let Some(stagger) = config.stagger {
+            settings.stagger = stagger.as_secs();
+        }
+        if let Some(maturity) = config.block_maturity {
+            settings.block_maturity = maturity.as_secs();
+        }
+        if config.max_per_second == Some(0) {
+            return Err("startup.max_per_second must be at least 1".to_string());
+        }
+        settings.max_per_second = config.max_per_second;
+        Ok(settings)
+    }
+}
+
+/// Which start an agent's daemon gets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartupRole {
+    /// Miners come up first, one stagger apart
+    Miner,
+    /// Hardcoded-mode seed nodes all start once blocks have matured
+    Seed,
+    /// Everyone else starts after block maturity, one stagger apart
+    User,
+}
+
+/// A scheduled process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupStep {
+    Daemon,
+    Wallet,
+    Script,
+    MiningScript,
+    Distributor,
+    Monitor,
+    ScriptAgent,
+}
+
+impl StartupStep {
+    /// The name `startup_schedule.json` uses
+    pub fn name(&self) -> &'static str {
+        match self {
+            StartupStep::Daemon => "daemon",
+            StartupStep::Wallet => "wallet",
+            StartupStep::Script => "script",
+            StartupStep::MiningScript => "mining_script",
+            StartupStep::Distributor => "distributor",
+            StartupStep::Monitor => "monitor",
+            StartupStep::ScriptAgent => "script_agent",
+        }
+    }
+}
+
+/// One entry of `startup_schedule.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StartupEntry {
+    pub agent_id: String,
+    pub step: StartupStep,
+    pub start_time: u64,
+    /// Step of the same agent this one waits for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<StartupStep>,
+    /// Start time set in the config and taken as given
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub explicit: bool,
+}
+
+/// Start times of an agent's daemon, wallet and script
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgentStartup {
+    pub daemon: u64,
+    pub wallet: u64,
+    pub script: u64,
+}
+
+/// Hands out start times in scheduling order. Computed times are pushed to
+/// the next second with room under `max_per_second`; explicit ones are
+/// kept but still count towards the limit.
+#[derive(Debug, Default, Serialize)]
+pub struct StartupScheduler {
+    settings: StartupSettings,
+    entries: Vec<StartupEntry>,
+    #[serde(skip)]
+    starts_per_second: BTreeMap<u64, u32>,
+    #[serde(skip)]
+    miners: u64,
+    #[serde(skip)]
+    users: u64,
+    #[serde(skip)]
+    script_agents: u64,
+}
+
+impl StartupScheduler {
+    pub fn new(settings: StartupSettings) -> Self {
+        StartupScheduler {
+            settings,
+            ..Default::default()
+        }
+    }
+
+    pub fn settings(&self) -> &StartupSettings {
+        &self.settings
+    }
+
+    pub fn entries(&self) -> &[StartupEntry] {
+        &self.entries
+    }
+
+    /// First second at or after `earliest` with room for another start
+    fn claim(&mut self, earliest: u64, explicit: bool) -> u64 {
+        let mut second = earliest;
+        if let (Some(limit), false) = (self.settings.max_per_second, explicit) {
+            while self.starts_per_second.get(&second).copied().unwrap_or(0) >= limit {
+                second += 1;
+            }
+        }
+        *self.starts_per_second.entry(second).or_default() += 1;
+        second
+    }
+
+    fn push(
+        &mut self,
+        agent_id: &str,
+        step: StartupStep,
+        earliest: u64,
+        after: Option<StartupStep>,
+        explicit: bool,
+    ) -> u64 {
+        let start_time = self.claim(earliest, explicit);
+        self.entries.push(StartupEntry {
+            agent_id: agent_id.to_string(),
+            step,
+            start_time,
+            after,
+            explicit,
+        });
+        start_time
+    }
+
+    /// Schedule an agent's daemon (at `explicit` if set, else by `role`),
+    /// its wallet after the daemon and its script after the wallet. Every
+    /// agent takes a place in its role's stagger, explicit start or not.
+    pub fn agent(
+        &mut self,
+        agent_id: &str,
+        role: StartupRole,
+        explicit: Option<u64>,
+    ) -> AgentStartup {
+        let stagger = self.settings.stagger;
+        let computed = match role {
+            StartupRole::Miner => {
+                self.miners += 1;
+                (self.miners - 1) * stagger
+            }
+            StartupRole::Seed => self.settings.block_maturity,
+            StartupRole::User => {
+                self.users += 1;
+                self.settings.block_maturity + (self.users - 1) * stagger
+            }
+        };
+        let daemon = self.push(
+            agent_id,
+            StartupStep::Daemon,
+            explicit.unwrap_or(computed),
+            None,
+            explicit.is_some(),
+        );
+        let wallet = self.push(
+            agent_id,
+            StartupStep::Wallet,
+            daemon + crate::WALLET_STARTUP_DELAY_SECS,
+            Some(StartupStep::Daemon),
+            false,
+        );
+        let script = self.push(
+            agent_id,
+            StartupStep::Script,
+            wallet + crate::AGENT_STARTUP_DELAY_SECS,
+            Some(StartupStep::Wallet),
+            false,
+        );
+        AgentStartup {
+            daemon,
+            wallet,
+            script,
+        }
+    }
+
+    /// A miner's mining script, after its wallet-registration script
+    pub fn mining_script(&mut self, agent_id: &str, script: u64) -> u64 {
+        self.push(
+            agent_id,
+            StartupStep::MiningScript,
+            script + crate::MINING_SCRIPT_DELAY_SECS,
+            Some(StartupStep::Script),
+            false,
+        )
+    }
+
+    /// The miner distributor, at `wait_time` if set, else two maturity
+    /// windows in: one for the first coinbase outputs to unlock, one more so
+    /// there are enough outputs on chain for ring signatures.
+    pub fn distributor(&mut self, agent_id: &str, wait_time: Option<u64>) -> u64 {
+        let earliest = wait_time.unwrap_or(2 * self.settings.block_maturity);
+        self.push(
+            agent_id,
+            StartupStep::Distributor,
+            earliest,
+            None,
+            wait_time.is_some(),
+        )
+    }
+
+    /// When the first agent script is up (or would be, for a miner
+    /// starting at 0)
+    fn first_script(&self) -> u64 {
+        self.entries
+            .iter()
+            .filter(|e| e.step == StartupStep::Script)
+            .map(|e| e.start_time)
+            .min()
+            .unwrap_or(crate::WALLET_STARTUP_DELAY_SECS + crate::AGENT_STARTUP_DELAY_SECS)
+    }
+
+    /// The simulation monitor, alongside the first agent script. Schedule
+    /// user agents first.
+    pub fn monitor(&mut self, agent_id: &str) -> u64 {
+        let earliest = self.first_script();
+        self.push(agent_id, StartupStep::Monitor, earliest, None, false)
+    }
+
+    /// A script-only agent, one stagger apart after the first agent
+    /// script. Schedule user agents first.
+    pub fn script_agent(&mut self, agent_id: &str) -> u64 {
+        self.script_agents += 1;
+        let earliest = self.first_script() + self.script_agents * self.settings.stagger;
+        self.push(agent_id, StartupStep::ScriptAgent, earliest, None, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::duration::SimDuration;
+
+    #[test]
+    fn hosts_get_the_standard_ports_then_shifted_triples() {
+        let mut allocator = PortAllocator::new();
+        let first = allocator.allocate("a", None, None, None).unwrap();
+        assert_eq!(first, AgentPorts::default());
+        assert_eq!(
+            allocator.allocate("b", None, None, None).unwrap(),
+            AgentPorts::default(),
+            "another host starts over"
+        );
+        assert_eq!(
+            allocator.allocate("a", None, None, None).unwrap(),
+            AgentPorts {
+                p2p: 18090,
+                rpc: 18091,
+                wallet_rpc: 18092,
+            }
+        );
+    }
+
+    #[test]
+    fn reserved_ports_are_skipped_and_clashes_rejected() {
+        let mut allocator = PortAllocator::new();
+        allocator.reserve("a", 18081).unwrap();
+        assert!(allocator.reserve("a", 18081).unwrap_err().contains("18081"));
+
+        // The reserving agent keeps its port; the rest avoid it
+        let own = allocator.allocate("a", None, Some(18081), None).unwrap();
+        assert_eq!(own, AgentPorts::default());
+        let next = allocator.allocate("a", None, None, None).unwrap();
+        assert_eq!(next.p2p, 18090);
+    }
+
+    #[test]
+    fn testnet_and_stagenet_use_their_own_ports() {
+        let mut allocator = PortAllocator::for_chain(Chain::Testnet);
+        assert_eq!(
+            allocator.allocate("a", None, None, None).unwrap(),
+            AgentPorts {
+                p2p: 28080,
+                rpc: 28081,
+                wallet_rpc: 28082,
+            }
+        );
+        assert_eq!(
+            allocator.allocate("a", None, None, None).unwrap().p2p,
+            28090
+        );
+        assert_eq!(AgentPorts::for_chain(Chain::Stagenet).rpc, 38081);
+        assert_eq!(AgentPorts::for_chain(Chain::Regtest), AgentPorts::default());
+    }
+
+    #[test]
+    fn dependencies_follow_their_daemon() {
+        let mut scheduler = StartupScheduler::new(StartupSettings::default());
+        let first = scheduler.agent("miner-001", StartupRole::Miner, None);
+        let second = scheduler.agent("miner-002", StartupRole::Miner, None);
+        assert_eq!(
+            (first.daemon, first.wallet, first.script),
+            (0, 2, 5),
+            "wallet after daemon, script after wallet"
+        );
+        assert_eq!(second.daemon, 1);
+        assert_eq!(scheduler.mining_script("miner-001", first.script), 15);
+
+        let user = scheduler.agent("user-001", StartupRole::User, None);
+        assert_eq!(user.daemon, crate::BLOCK_MATURITY_SECONDS);
+        let late = scheduler.agent("user-002", StartupRole::User, Some(30));
+        assert_eq!((late.daemon, late.wallet), (30, 32), "explicit start wins");
+        assert_eq!(
+            scheduler.agent("user-003", StartupRole::User, None).daemon,
+            crate::BLOCK_MATURITY_SECONDS + 2,
+            "explicit starts keep their place in the stagger"
+        );
+        assert_eq!(
+            scheduler.agent("seed-001", StartupRole::Seed, None).daemon,
+            crate::BLOCK_MATURITY_SECONDS
+        );
+
+        assert_eq!(scheduler.monitor("monitor"), 5);
+        assert_eq!(scheduler.script_agent("script-001"), 6);
+        assert_eq!(scheduler.script_agent("script-002"), 7);
+        assert_eq!(
+            scheduler.distributor("distributor", None),
+            2 * crate::BLOCK_MATURITY_SECONDS
+        );
+        assert_eq!(scheduler.distributor("distributor-2", Some(600)), 600);
+    }
+
+    #[test]
+    fn startup_knobs_pace_the_schedule() {
+        let config = StartupConfig {
+            stagger: Some(SimDuration::from_secs(5)),
+            max_per_second: Some(1),
+            block_maturity: Some(SimDuration::from_secs(600)),
+        };
+        let settings =
+            StartupSettings::from_config(Some(&config), crate::TARGET_BLOCK_INTERVAL_SECS).unwrap();
+        let mut scheduler = StartupScheduler::new(settings);
+
+        // Explicit starts are kept even over the limit
+        scheduler.agent("a", StartupRole::Miner, Some(0));
+        scheduler.agent("b", StartupRole::Miner, Some(0));
+        let c = scheduler.agent("c", StartupRole::Miner, None);
+        assert_eq!(c.daemon, 10);
+        // a and b both want their wallet at 2s
+        let wallets: Vec<u64> = scheduler
+            .entries()
+            .iter()
+            .filter(|e| e.step == StartupStep::Wallet && e.agent_id != "c")
+            .map(|e| e.start_time)
+            .collect();
+        assert_eq!(wallets, vec![2, 3]);
+        assert_eq!(scheduler.agent("u", StartupRole::User, None).daemon, 600);
+        assert_eq!(scheduler.distributor("d", None), 1200);
+
+        let zero = StartupConfig {
+            max_per_second: Some(0),
+            ..Default::default()
+        };
+        assert!(
+            StartupSettings::from_config(Some(&zero), crate::TARGET_BLOCK_INTERVAL_SECS).is_err()
+        );
+        let err = serde_yaml::from_str::<StartupConfig>("stagger: soon").unwrap_err();
+        assert!(err.to_string().starts_with("stagger: "), "{}", err);
+    }
+}
@@ -3,10 +3,12 @@
 //! This file handles generation of Shadow process configurations
 //! for monero-wallet-rpc instances.
 
-use crate::config::OptionValue;
+use crate::config::{Chain, OptionValue};
 use crate::shadow::{ProcessArgs, ShadowProcess};
+use crate::utils::duration::SimDuration;
 use crate::utils::options::{
-    merge_options, options_to_args, shell_quote_args, translate_wallet_log_level,
+    apply_option_overrides, merge_args, merge_options, options_to_args, shell_quote_args,
+    translate_wallet_log_level,
 };
 use std::collections::BTreeMap;
 
@@ -19,6 +21,10 @@ use std::collections::BTreeMap;
 /// dir (derived from `shared_dir` + `agent_id`), and the phase-specific
 /// `custom_args`.
 ///
+/// `custom_args` (`wallet_args` or phase args) replace generated flags of
+/// the same name, and the agent's own `wallet_options` win over both (see
+/// `merge_args`).
+///
 /// Returns argv-style strings (one element per arg); join later if a shell
 /// string is needed (see `shell_quote_args`).
 pub fn build_wallet_args(
@@ -26,7 +32,7 @@ pub fn build_wallet_args(
     agent_ip: &str,
     daemon_address: &str,
     wallet_rpc_port: u16,
-    _environment: &BTreeMap<String, String>,
+    environment: &BTreeMap<String, String>,
     custom_args: Option<&Vec<String>>,
     wallet_defaults: Option<&BTreeMap<String, OptionValue>>,
     wallet_options: Option<&BTreeMap<String, OptionValue>>,
@@ -46,6 +52,14 @@ pub fn build_wallet_args(
         "--confirm-external-bind".to_string(),
         "--allow-mismatched-daemon-version".to_string(),
     ];
+    // The chain the generator exported as MONEROSIM_CHAIN
+    if let Some(flag) = environment
+        .get("MONEROSIM_CHAIN")
+        .and_then(|name| Chain::from_name(name))
+        .and_then(Chain::wallet_flag)
+    {
+        args.push(flag.to_string());
+    }
 
     // Note: we intentionally do NOT set --max-concurrency on wallet-rpc.
     // With limited threads (e.g., 2), wallet-rpc's background refresh can
@@ -59,11 +73,13 @@ pub fn build_wallet_args(
     args.extend(options_to_args(&merged_wallet_options));
     args.push("--daemon-ssl-allow-any-cert".to_string());
 
-    if let Some(custom) = custom_args {
-        args.extend(custom.iter().cloned());
-    }
-
-    args
+    let args = merge_args(args, custom_args.map_or(&[], |a| a.as_slice()));
+    let agent_overrides: BTreeMap<String, OptionValue> = wallet_options
+        .iter()
+        .flat_map(|opts| opts.keys())
+        .filter_map(|k| Some((k.clone(), merged_wallet_options.get(k)?.clone())))
+        .collect();
+    apply_option_overrides(args, &agent_overrides)
 }
 
 /// Format a daemon URL for a wallet's `--daemon-address` flag.
@@ -109,7 +125,7 @@ pub struct WalletProcessArgs<'a> {
     pub wallet_rpc_port: u16,
     pub wallet_binary_path: &'a str,
     pub environment: &'a BTreeMap<String, String>,
-    pub wallet_start_time: &'a str,
+    pub wallet_start_time: SimDuration,
     pub custom_args: Option<&'a Vec<String>>,
     pub custom_env: Option<&'a BTreeMap<String, String>>,
     pub wallet_defaults: Option<&'a BTreeMap<String, OptionValue>>,
@@ -117,8 +133,9 @@ pub struct WalletProcessArgs<'a> {
     pub shared_dir: &'a str,
 }
 
-/// Add a wallet process pointing at the given daemon address.
-pub fn add_wallet_process(args: WalletProcessArgs<'_>) -> String {
+/// Add a wallet process pointing at the given daemon address. Returns the
+/// shell-quoted command (for `WALLET_RPC_CMD`) and the argv it runs with.
+pub fn add_wallet_process(args: WalletProcessArgs<'_>) -> (String, Vec<String>) {
     let daemon_address = args.daemon.format();
     let wallet_args = build_wallet_args(
         args.agent_id,
@@ -151,13 +168,13 @@ pub fn add_wallet_process(args: WalletProcessArgs<'_>) -> String {
 
     args.processes.push(ShadowProcess {
         path: args.wallet_binary_path.to_string(),
-        args: ProcessArgs::List(wallet_args),
+        args: ProcessArgs::List(wallet_args.clone()),
         environment: wallet_env,
-        start_time: args.wallet_start_time.to_string(),
+        start_time: args.wallet_start_time,
         shutdown_time: None,
         shutdown_signal: None,
         expected_final_state: Some(crate::shadow::ExpectedFinalState::Running),
     });
 
-    wallet_cmd
+    (wallet_cmd, wallet_args)
 }
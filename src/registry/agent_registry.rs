@@ -0,0 +1,257 @@
+//! `agent_registry.json` and `public_nodes.json`.
+//!
+//! Python agents discover each other through these files, so they must
+//! match the hosts Shadow runs: addresses and placement are read from the
+//! host entries, never re-allocated.
+
+use std::collections::BTreeMap;
+
+use crate::agent::wallet_keys::wallet_address;
+use crate::config::AgentDefinitions;
+use crate::gml_parser::GmlGraph;
+use crate::ip::as_manager::AsRegion;
+use crate::shadow::{AgentInfo, AgentRegistry, PublicNodeInfo, PublicNodeRegistry, ShadowHost};
+use crate::utils::options::flag_value;
+
+use super::RegistryContext;
+
+/// Build the agent registry by joining `hosts` (planned, or read back from
+/// a generated `shadow_agents.yaml`) with the effective agent definitions.
+/// Each agent's IP, network node and firewall come from its host entry
+/// rather than being re-allocated, so the registry agrees with what Shadow
+/// will run; everything else comes from `context`.
+pub fn build_agent_registry(
+    effective_agents: &AgentDefinitions,
+    hosts: &BTreeMap<String, ShadowHost>,
+    context: &RegistryContext,
+    gml_graph: Option<&GmlGraph>,
+) -> AgentRegistry {
+    let mut agent_registry = AgentRegistry {
+        generator_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: crate::ARTIFACT_SCHEMA_VERSION,
+        chain: context.chain,
+        agents: Vec::new(),
+    };
+
+    // Populate agent registry from all agent types
+    // Extract IPs from the already created hosts instead of generating new ones
+
+    // Add all agents to registry from the effective agents map (so
+    // auto-injected fallback-seed hosts appear here too — DNS server
+    // and other consumers read this file).
+    for (agent_id, agent_config) in effective_agents.agents.iter() {
+        // Get IP from the corresponding host that was already created
+        // (the host agent's, for co-located agents)
+        let host_id = agent_config.host_id(agent_id);
+        let ports = context
+            .agent_ports
+            .get(agent_id)
+            .copied()
+            .unwrap_or_default();
+        let agent_ip = hosts
+            .get(host_id)
+            .and_then(|host| host.ip_addr.clone())
+            .unwrap_or_else(|| {
+                log::warn!(
+                    "Agent '{}' has no host entry with an IP address; using placeholder 0.0.0.0",
+                    agent_id
+                );
+                "0.0.0.0".to_string()
+            });
+
+        let mut attributes = agent_config.attribute_map();
+
+        // Add computed is_miner attribute to the agent registry
+        let is_miner = agent_config.is_miner();
+        attributes.insert("is_miner".to_string(), is_miner.to_string());
+
+        // Add the resolved hashrate weight if present
+        if let Some(weight) = context.miner_weights.get(agent_id) {
+            attributes.insert("hashrate".to_string(), weight.to_string());
+        }
+
+        // Add can_receive_distributions if true
+        if agent_config.can_receive_distributions() {
+            attributes.insert("can_receive_distributions".to_string(), "true".to_string());
+        }
+
+        // Determine agent type characteristics
+        let has_local_daemon = agent_config.has_local_daemon();
+        let has_wallet = agent_config.has_wallet();
+        let is_public_node = agent_config.is_public_node();
+
+        // Get remote daemon info for wallet-only agents
+        let remote_daemon = agent_config.remote_daemon_address().map(|s| s.to_string());
+        let daemon_selection_strategy = agent_config
+            .daemon_selection_strategy()
+            .map(|s| format!("{:?}", s).to_lowercase());
+
+        let unreachable = hosts
+            .get(host_id)
+            .and_then(|host| host.blocked_inbound_ports.as_ref())
+            .is_some_and(|blocked| blocked.contains(&ports.p2p));
+
+        let network_node_id = hosts
+            .get(host_id)
+            .filter(|_| gml_graph.is_some())
+            .map(|host| host.network_node_id);
+        // Placement: the GML node's AS decides the continent; switch
+        // networks only have the allocated IP's range to go by
+        let as_number = gml_graph
+            .zip(network_node_id)
+            .and_then(|(gml, node_id)| gml.nodes.iter().find(|n| n.id == node_id))
+            .and_then(|node| {
+                node.attributes
+                    .get("AS")
+                    .or_else(|| node.attributes.get("as"))
+            })
+            .and_then(|as_number| as_number.parse::<u32>().ok());
+        let region = match as_number {
+            Some(as_number) => AsRegion::from_as_number(as_number),
+            None => AsRegion::from_ip(&agent_ip),
+        };
+
+        // monerod treats a negative limit as "use the default"
+        let daemon_args = context.daemon_args.get(agent_id);
+        let peer_limit = |flag: &str| {
+            daemon_args
+                .and_then(|args| flag_value(args, flag))
+                .and_then(|value| value.parse::<u32>().ok())
+        };
+        let out_peers = has_local_daemon
+            .then(|| peer_limit("out-peers").unwrap_or(crate::MONERO_DEFAULT_OUT_PEERS));
+        let in_peers = peer_limit("in-peers").filter(|_| has_local_daemon);
+
+        let agent_info = AgentInfo {
+            id: agent_id.clone(),
+            ip_addr: agent_ip,
+            network_node_id,
+            continent: region.key().map(str::to_string),
+            as_number,
+            pinned: (network_node_id.is_some() && agent_config.placement.is_some()).then_some(true),
+            daemon: has_local_daemon,
+            wallet: has_wallet,
+            user_script: agent_config.script.clone(),
+            attributes,
+            wallet_address: has_wallet
+                .then(|| wallet_address(context.simulation_seed, agent_id, context.chain)),
+            wallet_rpc_port: if has_wallet {
+                Some(ports.wallet_rpc)
+            } else {
+                None
+            },
+            daemon_rpc_port: if has_local_daemon {
+                Some(ports.rpc)
+            } else {
+                None
+            },
+            p2p_port: if has_local_daemon {
+                Some(ports.p2p)
+            } else {
+                None
+            },
+            reachable: (has_local_daemon && unreachable).then_some(false),
+            out_peers,
+            in_peers,
+            is_public_node: if is_public_node { Some(true) } else { None },
+            is_seed: context.seeds.contains(agent_id).then_some(true),
+            remote_daemon,
+            daemon_selection_strategy,
+            wallet_args: context.wallet_args.get(agent_id).cloned(),
+            daemon_binary: agent_config
+                .final_daemon_binary()
+                .and_then(|spec| context.binaries.resolve(&spec).ok()),
+            wallet_binary: agent_config
+                .final_wallet_binary()
+                .and_then(|spec| context.binaries.resolve(spec).ok()),
+            role: agent_config.is_spy().then(|| "spy".to_string()),
+            schedule: context.schedules.get(agent_id).copied(),
+        };
+        agent_registry.agents.push(agent_info);
+    }
+
+    // The DNS server is infrastructure, not an agent, but agents and the
+    // analyzer need to tell its address apart from theirs
+    if let Some(dns_host) = hosts.get(crate::DNS_SERVER_ID) {
+        agent_registry.agents.push(AgentInfo {
+            id: crate::DNS_SERVER_ID.to_string(),
+            ip_addr: dns_host.ip_addr.clone().unwrap_or_default(),
+            network_node_id: gml_graph.map(|_| dns_host.network_node_id),
+            continent: None,
+            as_number: None,
+            pinned: None,
+            daemon: false,
+            wallet: false,
+            user_script: Some("agents.dns_server".to_string()),
+            attributes: BTreeMap::new(),
+            wallet_address: None,
+            wallet_rpc_port: None,
+            daemon_rpc_port: None,
+            p2p_port: None,
+            reachable: None,
+            out_peers: None,
+            in_peers: None,
+            is_public_node: None,
+            is_seed: None,
+            remote_daemon: None,
+            daemon_selection_strategy: None,
+            wallet_args: None,
+            daemon_binary: None,
+            wallet_binary: None,
+            role: Some("infrastructure".to_string()),
+            schedule: None,
+        });
+    }
+
+    // Wallet-only agents naming their daemon by agent id record the
+    // address it resolved to, as their wallet uses
+    let rpc_addresses: BTreeMap<String, String> = agent_registry
+        .agents
+        .iter()
+        .filter_map(|a| {
+            Some((
+                a.id.clone(),
+                format!("{}:{}", a.ip_addr, a.daemon_rpc_port?),
+            ))
+        })
+        .collect();
+    for agent in &mut agent_registry.agents {
+        if let Some(resolved) = agent
+            .remote_daemon
+            .as_ref()
+            .and_then(|addr| rpc_addresses.get(addr))
+        {
+            agent.remote_daemon = Some(resolved.clone());
+        }
+    }
+
+    agent_registry
+}
+
+/// Build the public-node registry from agents flagged as `is_public_node`
+/// that also run a local daemon. Wallet-only agents in the registry are
+/// excluded because they have no daemon to advertise.
+pub fn build_public_node_registry(agent_registry: &AgentRegistry) -> PublicNodeRegistry {
+    let mut public_node_registry = PublicNodeRegistry {
+        nodes: Vec::new(),
+        version: 1,
+    };
+
+    // Populate public node registry from agents with is_public_node attribute
+    for agent in &agent_registry.agents {
+        if agent.is_public_node == Some(true) && agent.daemon {
+            let public_node = PublicNodeInfo {
+                agent_id: agent.id.clone(),
+                ip_addr: agent.ip_addr.clone(),
+                rpc_port: agent.daemon_rpc_port.unwrap_or(crate::MONERO_RPC_PORT),
+                p2p_port: agent.p2p_port.or(Some(crate::MONERO_P2P_PORT)),
+                status: "available".to_string(),
+                registered_at: 0.0, // Will be updated at runtime
+                attributes: Some(agent.attributes.clone()),
+            };
+            public_node_registry.nodes.push(public_node);
+        }
+    }
+
+    public_node_registry
+}
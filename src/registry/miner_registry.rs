@@ -0,0 +1,117 @@
+//! `miners.json`: the miners, their hashrate weights and network shares,
+//! read by the mining agents and the analysis. With mining pools each pool
+//! also gets `miners_<pool>.json`, the same entries for its miners only.
+
+use std::collections::BTreeMap;
+
+use crate::config::AgentDefinitions;
+use crate::shadow::{AgentRegistry, MinerInfo, MinerRegistry};
+use crate::validation_report::ValidationReport;
+
+/// Build and validate the miner registry. Reads each miner's IP from the
+/// already-populated `agent_registry` so it matches what Shadow will run and
+/// its weight from `miner_weights` (see `resolve_miner_weights`), then
+/// normalizes the weights into shares. A miner without a resolved weight
+/// gets the default of 10. Missing miners are reported as warnings.
+/// `mining_pools` (pool -> miners) sets each miner's `pool`.
+pub fn build_miner_registry(
+    config_agents: &AgentDefinitions,
+    agent_registry: &AgentRegistry,
+    miner_weights: &BTreeMap<String, f64>,
+    mining_pools: &BTreeMap<String, Vec<String>>,
+    report: &mut ValidationReport,
+) -> MinerRegistry {
+    let mut miner_registry = MinerRegistry {
+        generator_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: crate::ARTIFACT_SCHEMA_VERSION,
+        miners: Vec::new(),
+    };
+
+    // Populate miner registry from agents that are miners
+    for (agent_id, agent_config) in config_agents.agents.iter() {
+        if agent_config.is_miner() {
+            // Find the IP address from the already populated agent_registry
+            let agent_info = agent_registry.agents.iter().find(|a| a.id == *agent_id);
+            let agent_ip = agent_info.map(|a| a.ip_addr.clone()).unwrap_or_else(|| {
+                log::warn!(
+                    "Miner '{}' not found in agent registry; using placeholder 0.0.0.0",
+                    agent_id
+                );
+                "0.0.0.0".to_string()
+            });
+
+            let weight = miner_weights.get(agent_id).copied().unwrap_or(10.0);
+
+            let miner_info = MinerInfo {
+                agent_id: agent_id.clone(),
+                ip_addr: agent_ip,
+                // The deterministic wallet's; None for miners without one
+                wallet_address: agent_info.and_then(|a| a.wallet_address.clone()),
+                weight,
+                share: 0.0, // Set once the total is known
+                pool: mining_pools
+                    .iter()
+                    .find(|(_, members)| members.contains(agent_id))
+                    .map(|(name, _)| name.clone()),
+            };
+            miner_registry.miners.push(miner_info);
+        }
+    }
+
+    // Validate the miner registry before writing
+    if miner_registry.miners.is_empty() {
+        report.warn(
+            "mining",
+            "No miners were found in the configuration; no blocks will be mined",
+        );
+    } else {
+        let total_weight: f64 = miner_registry.miners.iter().map(|m| m.weight).sum();
+        for miner in miner_registry.miners.iter_mut() {
+            miner.share = (miner.weight / total_weight * 1e6).round() / 1e6;
+        }
+        log::info!(
+            "Mining weight distribution: {} miners with total weight {}",
+            miner_registry.miners.len(),
+            total_weight
+        );
+    }
+
+    miner_registry
+}
+
+/// Each pool's `miners_<pool>.json` contents: the entries of `registry` in
+/// that pool, network-wide shares unchanged. A pool without miners is
+/// reported as a warning and still gets an (empty) registry.
+pub fn build_pool_registries(
+    registry: &MinerRegistry,
+    mining_pools: &BTreeMap<String, Vec<String>>,
+    report: &mut ValidationReport,
+) -> BTreeMap<String, MinerRegistry> {
+    mining_pools
+        .keys()
+        .map(|name| {
+            let miners: Vec<MinerInfo> = registry
+                .miners
+                .iter()
+                .filter(|m| m.pool.as_deref() == Some(name.as_str()))
+                .cloned()
+                .collect();
+            if miners.is_empty() {
+                report.warn(
+                    "mining",
+                    format!(
+                        "Mining pool '{}' has no miners; its registry is empty and it mines no \
+                         blocks",
+                        name
+                    ),
+                );
+            }
+            let pool = MinerRegistry {
+                generator_version: registry.generator_version.clone(),
+                schema_version: registry.schema_version,
+                miners,
+            };
+            (name.clone(), pool)
+        })
+        .collect()
+}
@@ -0,0 +1,137 @@
+//! Registry files in the shared directory.
+//!
+//! `agent_registry.json`, `public_nodes.json`, `miners.json` and the
+//! per-pool `miners_<pool>.json` are built from the config and the Shadow
+//! hosts only, so they can be regenerated from an existing (possibly
+//! hand-edited) `shadow_agents.yaml` with `monerosim regen-registries`
+//! without rewriting the YAML or cleaning any directory.
+
+pub mod agent_registry;
+pub mod miner_registry;
+
+pub use agent_registry::{build_agent_registry, build_public_node_registry};
+pub use miner_registry::{build_miner_registry, build_pool_registries};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{Result, WrapErr};
+use serde::Deserialize;
+
+use crate::config::{AgentDefinitions, Chain};
+use crate::gml_parser::GmlGraph;
+use crate::process::AgentPorts;
+use crate::shadow::{AgentRegistry, AgentSchedule, MinerRegistry, PublicNodeRegistry, ShadowHost};
+use crate::utils::binary::BinaryResolver;
+use crate::validation_report::ValidationReport;
+
+pub const AGENT_REGISTRY_FILE: &str = "agent_registry.json";
+pub const PUBLIC_NODES_FILE: &str = "public_nodes.json";
+pub const MINER_REGISTRY_FILE: &str = "miners.json";
+
+/// A mining pool's registry file, `miners_<pool>.json`
+pub fn pool_registry_file(pool: &str) -> String {
+    format!("miners_{}.json", pool)
+}
+
+/// What the registries take from planning besides the hosts. All of it
+/// follows from the config alone.
+#[derive(Debug, Clone)]
+pub struct RegistryContext {
+    /// The configured agents plus injected fallback-seed hosts
+    pub effective_agents: AgentDefinitions,
+    /// Each wallet's effective argv
+    pub wallet_args: BTreeMap<String, Vec<String>>,
+    /// Each daemon's effective argv
+    pub daemon_args: BTreeMap<String, Vec<String>>,
+    /// Each user agent's start and shutdown time
+    pub schedules: BTreeMap<String, AgentSchedule>,
+    pub agent_ports: BTreeMap<String, AgentPorts>,
+    /// Resolves agents' daemon / wallet binaries as generation did
+    pub binaries: BinaryResolver,
+    /// Agents selected as seed nodes
+    pub seeds: BTreeSet<String>,
+    /// Each miner's resolved hashrate weight
+    pub miner_weights: BTreeMap<String, f64>,
+    /// Each mining pool's miners (see `BlockControllerConfig::assign_pools`)
+    pub mining_pools: BTreeMap<String, Vec<String>>,
+    pub chain: Chain,
+    /// Derives the agents' deterministic wallet addresses
+    pub simulation_seed: u64,
+}
+
+pub struct Registries {
+    pub agents: AgentRegistry,
+    pub public_nodes: PublicNodeRegistry,
+    pub miners: MinerRegistry,
+    /// `miners_<pool>.json` by pool name
+    pub pools: BTreeMap<String, MinerRegistry>,
+}
+
+/// Build all the registries. Missing miners and empty pools go to
+/// `report`.
+pub fn build_registries(
+    config_agents: &AgentDefinitions,
+    hosts: &BTreeMap<String, ShadowHost>,
+    context: &RegistryContext,
+    gml_graph: Option<&GmlGraph>,
+    report: &mut ValidationReport,
+) -> Registries {
+    let agents = build_agent_registry(&context.effective_agents, hosts, context, gml_graph);
+    let public_nodes = build_public_node_registry(&agents);
+    let miners = build_miner_registry(
+        config_agents,
+        &agents,
+        &context.miner_weights,
+        &context.mining_pools,
+        report,
+    );
+    let pools = build_pool_registries(&miners, &context.mining_pools, report);
+    Registries {
+        agents,
+        public_nodes,
+        miners,
+        pools,
+    }
+}
+
+impl Registries {
+    /// The registry files under `shared_dir`, as (path, JSON) in write order
+    pub fn files(&self, shared_dir: &Path) -> Result<Vec<(PathBuf, String)>> {
+        let mut files = vec![
+            (
+                shared_dir.join(AGENT_REGISTRY_FILE),
+                serde_json::to_string_pretty(&self.agents)?,
+            ),
+            (
+                shared_dir.join(PUBLIC_NODES_FILE),
+                serde_json::to_string_pretty(&self.public_nodes)?,
+            ),
+            (
+                shared_dir.join(MINER_REGISTRY_FILE),
+                serde_json::to_string_pretty(&self.miners)?,
+            ),
+        ];
+        for (pool, registry) in &self.pools {
+            files.push((
+                shared_dir.join(pool_registry_file(pool)),
+                serde_json::to_string_pretty(registry)?,
+            ));
+        }
+        Ok(files)
+    }
+}
+
+/// The `hosts:` section of a generated `shadow_agents.yaml`
+pub fn load_shadow_hosts(path: &Path) -> Result<BTreeMap<String, ShadowHost>> {
+    #[derive(Deserialize)]
+    struct HostsOnly {
+        hosts: BTreeMap<String, ShadowHost>,
+    }
+    let content =
+        fs::read_to_string(path).wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+    let parsed: HostsOnly = serde_yaml::from_str(&content)
+        .wrap_err_with(|| format!("Failed to parse the hosts of {}", path.display()))?;
+    Ok(parsed.hosts)
+}
@@ -0,0 +1,361 @@
+//! Resource estimate of a planned simulation.
+//!
+//! Shadow runs every simulated process natively on one machine, so a large
+//! config can exhaust its memory or open-file limit hours into a run.
+//! Planning classifies every process of the Shadow config (monerod,
+//! monero-wallet-rpc, or an agent script behind a bash wrapper), charges it
+//! the per-process costs of `general.resource_model`, and takes the peak
+//! over the processes' start and shutdown times, so daemon restarts under
+//! turnover and sequential wallet phases are not counted twice. The peak
+//! is compared with `MemAvailable` from `/proc/meminfo` and the soft
+//! `ulimit -n`; going over either is a warning, an error under
+//! `strict_validation`.
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::ResourceModelConfig;
+use crate::shadow::ShadowProcess;
+use crate::validation_report::ValidationReport;
+
+/// Resident MB of one monerod when `resource_model` does not say
+pub const DEFAULT_MONEROD_MB: u64 = 250;
+pub const DEFAULT_WALLET_RPC_MB: u64 = 80;
+pub const DEFAULT_AGENT_MB: u64 = 40;
+/// Open file descriptors of one monerod (P2P and RPC sockets, LMDB files)
+pub const DEFAULT_MONEROD_FDS: u64 = 128;
+pub const DEFAULT_WALLET_RPC_FDS: u64 = 32;
+pub const DEFAULT_AGENT_FDS: u64 = 16;
+
+/// What a Shadow process runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessKind {
+    Monerod,
+    WalletRpc,
+    /// A bash wrapper around a Python agent script
+    Agent,
+}
+
+impl ProcessKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProcessKind::Monerod => "monerod",
+            ProcessKind::WalletRpc => "monero-wallet-rpc",
+            ProcessKind::Agent => "agent script",
+        }
+    }
+
+    /// Classify by the executable: the Monero binaries by file name,
+    /// anything else is an agent script
+    pub fn of_path(path: &str) -> Self {
+        let name = Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy())
+            .unwrap_or_default();
+        if name.starts_with("monero-wallet-rpc") {
+            ProcessKind::WalletRpc
+        } else if name.starts_with("monerod") {
+            ProcessKind::Monerod
+        } else {
+            ProcessKind::Agent
+        }
+    }
+}
+
+/// `ResourceModelConfig` with the defaults filled in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceModel {
+    pub monerod_mb: u64,
+    pub wallet_rpc_mb: u64,
+    pub agent_mb: u64,
+    pub monerod_fds: u64,
+    pub wallet_rpc_fds: u64,
+    pub agent_fds: u64,
+}
+
+impl ResourceModel {
+    pub fn from_config(config: Option<&ResourceModelConfig>) -> Self {
+        let c = config.cloned().unwrap_or_default();
+        ResourceModel {
+            monerod_mb: c.monerod_mb.unwrap_or(DEFAULT_MONEROD_MB),
+            wallet_rpc_mb: c.wallet_rpc_mb.unwrap_or(DEFAULT_WALLET_RPC_MB),
+            agent_mb: c.agent_mb.unwrap_or(DEFAULT_AGENT_MB),
+            monerod_fds: c.monerod_fds.unwrap_or(DEFAULT_MONEROD_FDS),
+            wallet_rpc_fds: c.wallet_rpc_fds.unwrap_or(DEFAULT_WALLET_RPC_FDS),
+            agent_fds: c.agent_fds.unwrap_or(DEFAULT_AGENT_FDS),
+        }
+    }
+
+    /// (MB, file descriptors) of one process
+    pub fn cost(&self, kind: ProcessKind) -> (u64, u64) {
+        match kind {
+            ProcessKind::Monerod => (self.monerod_mb, self.monerod_fds),
+            ProcessKind::WalletRpc => (self.wallet_rpc_mb, self.wallet_rpc_fds),
+            ProcessKind::Agent => (self.agent_mb, self.agent_fds),
+        }
+    }
+}
+
+/// What the machine generating the simulation offers; `None` where it
+/// could not be read (e.g. not Linux)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MachineResources {
+    /// `MemAvailable` in MB
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub available_memory_mb: Option<u64>,
+    /// Soft open-file limit (`ulimit -n`); `None` also when unlimited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fd_limit: Option<u64>,
+}
+
+impl MachineResources {
+    pub fn detect() -> Self {
+        let read = |path: &str| fs::read_to_string(path).ok();
+        MachineResources {
+            available_memory_mb: read("/proc/meminfo").and_then(|s| parse_mem_available_mb(&s)),
+            fd_limit: read("/proc/self/limits").and_then(|s| parse_open_files_limit(&s)),
+        }
+    }
+}
+
+/// `MemAvailable` of a `/proc/meminfo` dump, in MB
+pub fn parse_mem_available_mb(meminfo: &str) -> Option<u64> {
+    meminfo.lines().find_map(|line| {
+        let kb = line
+            .strip_prefix("MemAvailable:")?
+            .split_whitespace()
+            .next()?;
+        kb.parse::<u64>().ok().map(|kb| kb / 1024)
+    })
+}
+
+/// Soft limit of the `Max open files` row of `/proc/self/limits`
+pub fn parse_open_files_limit(limits: &str) -> Option<u64> {
+    limits.lines().find_map(|line| {
+        let soft = line
+            .strip_prefix("Max open files")?
+            .split_whitespace()
+            .next()?;
+        soft.parse().ok()
+    })
+}
+
+/// Peak demand of a planned simulation, in the dry-run summary and
+/// `manifest.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceEstimate {
+    /// Processes in the Shadow config, including restarts and phases
+    pub total_processes: usize,
+    /// Most processes running at once, and of each kind at that moment
+    pub peak_processes: usize,
+    pub peak_monerod: usize,
+    pub peak_wallet_rpc: usize,
+    pub peak_agents: usize,
+    pub memory_mb: u64,
+    pub file_descriptors: u64,
+    pub model: ResourceModel,
+    pub machine: MachineResources,
+}
+
+impl ResourceEstimate {
+    /// Warn about every limit the estimate exceeds
+    pub fn check(&self, report: &mut ValidationReport) {
+        if let Some(available) = self.machine.available_memory_mb {
+            if self.memory_mb > available {
+                report.warn(
+                    "resources",
+                    format!(
+                        "Estimated peak memory {} MB exceeds the {} MB available \
+                         ({} processes at once; calibrate general.resource_model \
+                         for your binaries)",
+                        self.memory_mb, available, self.peak_processes
+                    ),
+                );
+            }
+        }
+        if let Some(limit) = self.machine.fd_limit {
+            if self.file_descriptors > limit {
+                report.warn(
+                    "resources",
+                    format!(
+                        "Estimated {} open file descriptors exceed the open-file \
+                         limit of {} (raise it with `ulimit -n`)",
+                        self.file_descriptors, limit
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Estimate the peak demand of a Shadow config's `processes` (every host's)
+/// under `model` and record what `machine` offers
+pub fn estimate_resources<'a>(
+    processes: impl IntoIterator<Item = &'a ShadowProcess>,
+    model: ResourceModel,
+    machine: MachineResources,
+) -> ResourceEstimate {
+    // (time, starts?, kind): stops sort before starts at the same second,
+    // as Shadow stops a phase before the next one binds its ports
+    let mut events: Vec<(u64, bool, ProcessKind)> = Vec::new();
+    let mut total_processes = 0;
+    for process in processes {
+        total_processes += 1;
+        let kind = ProcessKind::of_path(process.executable());
+        let start = process.start_time.as_secs();
+        events.push((start, true, kind));
+        if let Some(stop) = process.shutdown_time {
+            events.push((stop.as_secs().max(start), false, kind));
+        }
+    }
+    events.sort_by_key(|&(at, starts, _)| (at, starts));
+
+    let mut running = [0usize; 3];
+    let mut peak = (0u64, [0usize; 3]);
+    let index = |kind| match kind {
+        ProcessKind::Monerod => 0,
+        ProcessKind::WalletRpc => 1,
+        ProcessKind::Agent => 2,
+    };
+    let kinds = [
+        ProcessKind::Monerod,
+        ProcessKind::WalletRpc,
+        ProcessKind::Agent,
+    ];
+    let memory = |running: &[usize; 3]| {
+        kinds
+            .iter()
+            .zip(running)
+            .map(|(&k, &n)| model.cost(k).0 * n as u64)
+            .sum::<u64>()
+    };
+    for (_, starts, kind) in events {
+        let slot = &mut running[index(kind)];
+        if starts {
+            *slot += 1;
+        } else {
+            *slot = slot.saturating_sub(1);
+        }
+        let mb = memory(&running);
+        if mb > peak.0 || (mb == peak.0 && running.iter().sum::<usize>() > peak.1.iter().sum()) {
+            peak = (mb, running);
+        }
+    }
+
+    let (memory_mb, counts) = peak;
+    ResourceEstimate {
+        total_processes,
+        peak_processes: counts.iter().sum(),
+        peak_monerod: counts[0],
+        peak_wallet_rpc: counts[1],
+        peak_agents: counts[2],
+        memory_mb,
+        file_descriptors: kinds
+            .iter()
+            .zip(counts)
+            .map(|(&k, n)| model.cost(k).1 * n as u64)
+            .sum(),
+        model,
+        machine,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shadow::ProcessArgs;
+    use crate::validation_report::Severity;
+    use std::collections::BTreeMap;
+
+    fn process(path: &str, start: &str, stop: Option<&str>) -> ShadowProcess {
+        ShadowProcess {
+            path: path.to_string(),
+            args: ProcessArgs::default(),
+            environment: BTreeMap::new(),
+            start_time: start.parse().unwrap(),
+            shutdown_time: stop.map(|stop| stop.parse().unwrap()),
+            shutdown_signal: None,
+            expected_final_state: None,
+        }
+    }
+
+    #[test]
+    fn restarts_and_phases_count_once_at_the_peak() {
+        // A daemon restarted at 100s, its wallet and agent, and a second
+        // host's daemon
+        let processes = [
+            process("/bin/monerod", "0s", Some("100s")),
+            process("/bin/monerod", "100s", None),
+            process("/bin/monero-wallet-rpc", "2s", None),
+            process("/bin/bash", "5s", None),
+            process("/opt/monerod", "1s", None),
+        ];
+        let model = ResourceModel::from_config(Some(&ResourceModelConfig {
+            monerod_mb: Some(100),
+            ..ResourceModelConfig::default()
+        }));
+        let estimate = estimate_resources(&processes, model, MachineResources::default());
+        assert_eq!(estimate.total_processes, 5);
+        assert_eq!(estimate.peak_processes, 4);
+        assert_eq!(
+            (
+                estimate.peak_monerod,
+                estimate.peak_wallet_rpc,
+                estimate.peak_agents
+            ),
+            (2, 1, 1)
+        );
+        assert_eq!(
+            estimate.memory_mb,
+            2 * 100 + DEFAULT_WALLET_RPC_MB + DEFAULT_AGENT_MB
+        );
+        assert_eq!(
+            estimate.file_descriptors,
+            2 * DEFAULT_MONEROD_FDS + DEFAULT_WALLET_RPC_FDS + DEFAULT_AGENT_FDS
+        );
+    }
+
+    #[test]
+    fn exceeding_the_machine_is_a_warning() {
+        let processes = vec![process("/bin/monerod", "0s", None); 10];
+        let model = ResourceModel::from_config(None);
+        let check = |machine| {
+            let mut report = ValidationReport::default();
+            estimate_resources(&processes, model, machine).check(&mut report);
+            report.count(Severity::Warning)
+        };
+        assert_eq!(check(MachineResources::default()), 0);
+        assert_eq!(
+            check(MachineResources {
+                available_memory_mb: Some(1024),
+                fd_limit: Some(1024),
+            }),
+            2
+        );
+        assert_eq!(
+            check(MachineResources {
+                available_memory_mb: Some(64 * 1024),
+                fd_limit: Some(65536),
+            }),
+            0
+        );
+    }
+
+    #[test]
+    fn reads_proc_files() {
+        let meminfo = "MemTotal:       16318412 kB\nMemAvailable:    8159206 kB\n";
+        assert_eq!(parse_mem_available_mb(meminfo), Some(7967));
+        let limits = "Limit                     Soft Limit           Hard Limit           Units\n\
+                      Max processes             63455                63455                processes\n\
+                      Max open files            1024                 1048576              files\n";
+        assert_eq!(parse_open_files_limit(limits), Some(1024));
+        assert_eq!(
+            parse_open_files_limit(
+                "Max open files            unlimited            unlimited            files\n"
+            ),
+            None
+        );
+    }
+}
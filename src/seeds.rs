@@ -0,0 +1,265 @@
+//! `monerosim seeds`: Monero's hardcoded mainnet fallback seed IPs.
+//!
+//! monerosim embeds a snapshot of the list (`MONERO_FALLBACK_SEED_IPS`,
+//! taken from `MONERO_FALLBACK_SEED_VERSION`). A Monero source tree yields
+//! a fresh one, and a simulation config can be checked against either: its
+//! `monero-seed-NNN` hosts and its `IP:PORT` seed nodes should not point at
+//! addresses Monero no longer ships.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use color_eyre::eyre::{eyre, Result};
+use serde::Serialize;
+
+use crate::config::{Config, FallbackSeedsMode, NetworkSettings};
+use crate::orchestrator::plan_agent_shadow_config;
+use crate::utils::seed_extractor::{
+    locate_net_node, monero_source_version, parse_ip_seed_nodes, SeedNode,
+};
+use crate::{MONERO_FALLBACK_SEED_IPS, MONERO_FALLBACK_SEED_VERSION, MONERO_P2P_PORT};
+
+/// Agent id prefix of the hosts pinned to fallback seed IPs
+const FALLBACK_SEED_PREFIX: &str = "monero-seed-";
+
+/// A mainnet fallback seed list and where it came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SeedSnapshot {
+    /// Monero release, e.g. `v0.18.5.1`; `None` if a bare `net_node.inl`
+    /// gave no hint
+    pub monero_version: Option<String>,
+    /// `embedded` or the path of the parsed `net_node.inl`
+    pub source: String,
+    pub seeds: Vec<SeedNode>,
+}
+
+impl SeedSnapshot {
+    /// True if `ip` is one of the listed seeds
+    pub fn contains(&self, ip: &str) -> bool {
+        self.seeds.iter().any(|s| s.ip == ip)
+    }
+}
+
+/// The list compiled into monerosim
+pub fn embedded_snapshot() -> SeedSnapshot {
+    SeedSnapshot {
+        monero_version: Some(MONERO_FALLBACK_SEED_VERSION.to_string()),
+        source: "embedded".to_string(),
+        seeds: MONERO_FALLBACK_SEED_IPS
+            .iter()
+            .map(|ip| SeedNode {
+                ip: ip.to_string(),
+                port: MONERO_P2P_PORT,
+            })
+            .collect(),
+    }
+}
+
+/// The mainnet list of a Monero source tree, or of a `net_node.inl` file
+pub fn snapshot_from_source(path: &Path) -> Result<SeedSnapshot> {
+    let (file, root) = locate_net_node(path).map_err(|e| eyre!("{}", e))?;
+    let content = std::fs::read_to_string(&file)
+        .map_err(|e| eyre!("Failed to read {}: {}", file.display(), e))?;
+    let seeds = parse_ip_seed_nodes(&content).map_err(|e| eyre!("{}: {}", file.display(), e))?;
+    if seeds.mainnet.is_empty() {
+        return Err(eyre!(
+            "{}: get_ip_seed_nodes() lists no mainnet seeds",
+            file.display()
+        ));
+    }
+    Ok(SeedSnapshot {
+        monero_version: root.as_deref().and_then(monero_source_version),
+        source: file.display().to_string(),
+        seeds: seeds.mainnet,
+    })
+}
+
+impl fmt::Display for SeedSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} mainnet fallback seeds from {} (monero {})",
+            self.seeds.len(),
+            self.source,
+            self.monero_version.as_deref().unwrap_or("version unknown")
+        )?;
+        for seed in &self.seeds {
+            writeln!(f, "  {}", seed)?;
+        }
+        Ok(())
+    }
+}
+
+/// What a seed address of the config turned out to be
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SeedStatus {
+    /// In the snapshot
+    Current,
+    /// The address of this simulated host
+    InSim(String),
+    /// Neither in the snapshot nor a simulated host's address
+    Stale,
+}
+
+/// One seed address the config hardcodes
+#[derive(Debug, Clone, Serialize)]
+pub struct SeedEntry {
+    /// `network.seed_nodes`, or the `monero-seed-NNN` host pinned to it
+    pub origin: String,
+    pub address: String,
+    pub status: SeedStatus,
+}
+
+/// A config's seed addresses against a snapshot
+#[derive(Debug, Clone, Serialize)]
+pub struct SeedCheck {
+    pub snapshot: SeedSnapshot,
+    pub fallback_seeds: FallbackSeedsMode,
+    pub entries: Vec<SeedEntry>,
+    /// Snapshot seeds no simulated host answers on (`auto` hosts them all)
+    pub unhosted: Vec<String>,
+}
+
+impl SeedCheck {
+    /// Entries pointing at neither a current seed nor a simulated host
+    pub fn stale(&self) -> impl Iterator<Item = &SeedEntry> {
+        self.entries
+            .iter()
+            .filter(|e| e.status == SeedStatus::Stale)
+    }
+}
+
+/// Plan `config` in memory and check its fallback-seed hosts and `IP:PORT`
+/// seed nodes against `snapshot`
+pub fn verify_config(config: &Config, snapshot: &SeedSnapshot) -> Result<SeedCheck> {
+    let plan = plan_agent_shadow_config(config, Path::new("shadow_output/shadow_agents.yaml"))?;
+    let hosts: BTreeMap<&str, &str> = plan
+        .registries
+        .agents
+        .agents
+        .iter()
+        .map(|a| (a.ip_addr.as_str(), a.id.as_str()))
+        .collect();
+
+    let mut entries = Vec::new();
+    for agent in &plan.registries.agents.agents {
+        if agent.id.starts_with(FALLBACK_SEED_PREFIX) {
+            entries.push(SeedEntry {
+                origin: agent.id.clone(),
+                address: agent.ip_addr.clone(),
+                status: if snapshot.contains(&agent.ip_addr) {
+                    SeedStatus::Current
+                } else {
+                    SeedStatus::Stale
+                },
+            });
+        }
+    }
+    for seed in NetworkSettings::from_config(config).seed_nodes {
+        // Agent ids name simulated hosts by definition
+        let Some((ip, _)) = seed.rsplit_once(':') else {
+            continue;
+        };
+        let status = if snapshot.contains(ip) {
+            SeedStatus::Current
+        } else if let Some(id) = hosts.get(ip) {
+            SeedStatus::InSim(id.to_string())
+        } else {
+            SeedStatus::Stale
+        };
+        entries.push(SeedEntry {
+            origin: "network.seed_nodes".to_string(),
+            address: seed.clone(),
+            status,
+        });
+    }
+
+    let mode = config.general.fallback_seeds;
+    let unhosted = if mode == FallbackSeedsMode::Off {
+        Vec::new()
+    } else {
+        snapshot
+            .seeds
+            .iter()
+            .filter(|s| !hosts.contains_key(s.ip.as_str()))
+            .map(|s| s.ip.clone())
+            .collect()
+    };
+    Ok(SeedCheck {
+        snapshot: snapshot.clone(),
+        fallback_seeds: mode,
+        entries,
+        unhosted,
+    })
+}
+
+impl fmt::Display for SeedCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Checked against {} seeds from {} (monero {}); fallback_seeds: {:?}",
+            self.snapshot.seeds.len(),
+            self.snapshot.source,
+            self.snapshot
+                .monero_version
+                .as_deref()
+                .unwrap_or("version unknown"),
+            self.fallback_seeds
+        )?;
+        if self.entries.is_empty() {
+            writeln!(f, "The config hardcodes no seed addresses")?;
+        }
+        for entry in &self.entries {
+            let status = match &entry.status {
+                SeedStatus::Current => "current".to_string(),
+                SeedStatus::InSim(id) => format!("simulated host {}", id),
+                SeedStatus::Stale => "STALE: not a current seed or a simulated host".to_string(),
+            };
+            writeln!(f, "  {:<20} {:<22} {}", entry.origin, entry.address, status)?;
+        }
+        if !self.unhosted.is_empty() {
+            writeln!(
+                f,
+                "Seeds without a simulated host (monerod's fallback dials fail): {}",
+                self.unhosted.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_snapshot_follows_the_monero_pin() {
+        let pin = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/monero.pin"))
+            .expect("monero.pin at the repo root");
+        assert_eq!(
+            embedded_snapshot().monero_version.as_deref(),
+            Some(pin.trim()),
+            "monero.pin moved: refresh MONERO_FALLBACK_SEED_IPS with \
+             `monerosim seeds --from-source <monero>` and bump MONERO_FALLBACK_SEED_VERSION"
+        );
+    }
+
+    #[test]
+    fn vendored_source_matches_the_embedded_snapshot() {
+        let tree = Path::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/monero"
+        ));
+        let from_tree = snapshot_from_source(tree).unwrap();
+        assert_eq!(from_tree.monero_version.as_deref(), Some("v0.18.5.1"));
+        assert_eq!(from_tree.seeds, embedded_snapshot().seeds);
+
+        // The file alone still finds the tree it sits in
+        let from_file = snapshot_from_source(&tree.join("src/p2p/net_node.inl")).unwrap();
+        assert_eq!(from_file.monero_version, from_tree.monero_version);
+
+        assert!(snapshot_from_source(&tree.join("src")).is_err());
+    }
+}
@@ -5,8 +5,9 @@
 pub mod types;
 
 pub use types::{
-    AgentInfo, AgentRegistry, ExpectedFinalState, MinerInfo, MinerRegistry, ProcessArgs,
-    PublicNodeInfo, PublicNodeRegistry, ShadowConfig, ShadowExperimental, ShadowFileSource,
-    ShadowGeneral, ShadowGraph, ShadowHost, ShadowNetwork, ShadowNetworkEdge, ShadowNetworkNode,
-    ShadowProcess,
+    AgentInfo, AgentRegistry, AgentSchedule, BinaryMetadata, ExpectedFinalState, MinerInfo,
+    MinerRegistry, NodeRestart, ProcessArgs, PublicNodeInfo, PublicNodeRegistry, RestartManifest,
+    ShadowConfig, ShadowExperimental, ShadowFileSource, ShadowGeneral, ShadowGraph, ShadowHost,
+    ShadowHostOptions, ShadowNetwork, ShadowNetworkEdge, ShadowNetworkNode, ShadowProcess,
+    SimulationMetadata,
 };
@@ -4,36 +4,52 @@
 //! configurations, including host definitions, process configurations,
 //! network topology structures, and agent/miner registry types.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
+use crate::config::Chain;
+use crate::utils::duration::SimDuration;
+
 // ============================================================================
 // Registry Types
 // ============================================================================
 
 /// Information about a miner agent in the simulation.
 ///
-/// This structure contains details about miners that are used by the block
-/// controller to manage mining operations and reward distribution.
-#[derive(Serialize, Debug)]
+/// This structure contains details about miners that are used by the mining
+/// agents, the miner distributor and the analysis.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MinerInfo {
     /// Unique identifier for the miner agent
     pub agent_id: String,
     /// IP address of the miner
     pub ip_addr: String,
-    /// Wallet address for receiving mining rewards (populated at runtime)
+    /// Wallet address for receiving mining rewards: the agent's
+    /// deterministic wallet, derived at generation
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wallet_address: Option<String>,
-    /// Mining weight/hashrate percentage (should sum to 100 across all miners)
-    pub weight: u32,
+    /// Resolved hashrate weight: the configured weight or percentage, or
+    /// the one `hashrate_distribution` assigned
+    #[serde(serialize_with = "crate::config::serialize_weight")]
+    pub weight: f64,
+    /// `weight` over the total of all miners' weights
+    pub share: f64,
+    /// Mining pool the miner belongs to (`block_controller.pools`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool: Option<String>,
 }
 
 /// Registry of all miners in the simulation.
 ///
 /// This is written to `/tmp/monerosim_shared/miners.json` for use by
-/// the block controller and mining coordination agents.
-#[derive(Serialize, Debug)]
+/// the mining agents and the analysis, and per pool to
+/// `miners_<pool>.json` with just that pool's miners.
+#[derive(Serialize, Deserialize, Debug)]
 pub struct MinerRegistry {
+    /// monerosim crate version that wrote this file
+    pub generator_version: String,
+    /// `ARTIFACT_SCHEMA_VERSION` at generation time
+    pub schema_version: u32,
     /// List of all miner agents
     pub miners: Vec<MinerInfo>,
 }
@@ -48,12 +64,27 @@ pub struct MinerRegistry {
 /// - Daemon-only: daemon=true, wallet=false
 /// - Wallet-only: daemon=false, wallet=true, remote_daemon=Some(...)
 /// - Script-only: daemon=false, wallet=false
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct AgentInfo {
     /// Unique identifier for the agent
     pub id: String,
     /// IP address assigned to the agent
     pub ip_addr: String,
+    /// GML node the host is placed on (None for switch networks, where
+    /// every host shares one node)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_node_id: Option<u32>,
+    /// Continent the host was placed on (`north_america`, `europe`, ...):
+    /// from the GML node's AS, else from the allocated IP's range. None for
+    /// subnet-group and fallback addresses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continent: Option<String>,
+    /// `AS` attribute of the GML node the host sits on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub as_number: Option<u32>,
+    /// `true` when the agent's `placement` chose its GML node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<bool>,
     /// Whether this agent runs a local Monero daemon
     pub daemon: bool,
     /// Whether this agent has a wallet
@@ -62,38 +93,128 @@ pub struct AgentInfo {
     pub user_script: Option<String>,
     /// Custom attributes for agent configuration
     pub attributes: BTreeMap<String, String>,
+    /// Primary address of the agent's deterministic wallet (see
+    /// `agent::wallet_keys`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wallet_address: Option<String>,
     /// RPC port for wallet service
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wallet_rpc_port: Option<u16>,
     /// RPC port for daemon service (None for wallet-only and script-only agents)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub daemon_rpc_port: Option<u16>,
+    /// P2P port of the daemon (None for wallet-only and script-only agents)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p2p_port: Option<u16>,
+    /// `false` when the host blocks inbound P2P (`is_unreachable` or
+    /// `reachable_fraction`); absent for reachable daemons
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reachable: Option<bool>,
+    /// Outbound peers the daemon keeps (`--out-peers`, else monerod's
+    /// default); None for agents without a daemon
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub out_peers: Option<u32>,
+    /// Inbound peer limit (`--in-peers`); absent when unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_peers: Option<u32>,
     /// Whether this agent's daemon is available as a public node
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_public_node: Option<bool>,
+    /// `true` when regular agents bootstrap from this agent (see
+    /// `network.seed_selection`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_seed: Option<bool>,
     /// Remote daemon address for wallet-only agents (e.g., "auto" or "192.168.1.10:18081")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub remote_daemon: Option<String>,
     /// Daemon selection strategy for wallet-only agents using "auto" (e.g., "random", "first", "round_robin")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub daemon_selection_strategy: Option<String>,
+    /// Arguments monero-wallet-rpc runs with, after `wallet_defaults`,
+    /// `wallet_options` and `wallet_args` are merged
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wallet_args: Option<Vec<String>>,
+    /// Resolved monerod the agent runs (the last phase's for phased daemons)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub daemon_binary: Option<String>,
+    /// Resolved monero-wallet-rpc (the last phase's for phased wallets)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wallet_binary: Option<String>,
+    /// Special role the generator gave this agent (`"spy"` for spy daemons,
+    /// `"infrastructure"` for the DNS server)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    /// When the agent starts and its processes are stopped (None for
+    /// script-only agents)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<AgentSchedule>,
+}
+
+/// An agent's lifetime in simulated seconds
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgentSchedule {
+    /// Daemon start; wallet and script follow after their startup delays
+    pub start_time: u64,
+    /// When `shutdown_time` stops every process; absent if they run to
+    /// the end of the simulation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shutdown_time: Option<u64>,
 }
 
 /// Registry of all agents in the simulation.
 ///
 /// This is written to `/tmp/monerosim_shared/agent_registry.json` for use by
 /// all agents to discover each other and coordinate activities.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct AgentRegistry {
+    /// monerosim crate version that wrote this file
+    pub generator_version: String,
+    /// `ARTIFACT_SCHEMA_VERSION` at generation time
+    pub schema_version: u32,
+    /// `general.chain` every daemon and wallet runs on
+    pub chain: Chain,
     /// List of all agents in the simulation
     pub agents: Vec<AgentInfo>,
 }
 
+/// `upgrade_manifest.json`: every configured daemon restart, in the format
+/// `tx-analyzer upgrade-analysis --manifest` reads. Times are absolute
+/// simulated timestamps (`SHADOW_EPOCH` + seconds), like the parsed logs.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RestartManifest {
+    /// Binary every restarting daemon started with, if they share one
+    pub pre_upgrade_version: Option<String>,
+    /// Binary every restarting daemon ends up on, if they share one
+    pub post_upgrade_version: Option<String>,
+    /// First `stop_at` across all restarts
+    pub upgrade_start: f64,
+    /// Last `start_at` across all restarts
+    pub upgrade_end: f64,
+    /// One entry per restart, in agent order
+    pub upgrades: Vec<NodeRestart>,
+}
+
+/// One daemon restart in `upgrade_manifest.json`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct NodeRestart {
+    pub node_id: String,
+    /// When the restarted daemon comes up (same as `upgrade_end`)
+    pub timestamp: f64,
+    /// Binary after the restart
+    pub version: String,
+    /// Binary before the restart
+    pub previous_version: String,
+    /// When the daemon was stopped
+    pub upgrade_start: f64,
+    /// When it was started again
+    pub upgrade_end: f64,
+}
+
 /// Information about a public node available for wallet-only agents.
 ///
 /// Public nodes are daemon agents that have `is_public_node: true` attribute
 /// and can accept connections from wallet-only agents.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct PublicNodeInfo {
     /// Agent ID of the public node
     pub agent_id: String,
@@ -117,7 +238,7 @@ pub struct PublicNodeInfo {
 ///
 /// This is written to `/tmp/monerosim_shared/public_nodes.json` for use by
 /// wallet-only agents to discover daemons they can connect to.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct PublicNodeRegistry {
     /// List of all public nodes
     pub nodes: Vec<PublicNodeInfo>,
@@ -125,6 +246,31 @@ pub struct PublicNodeRegistry {
     pub version: u32,
 }
 
+/// Generation-time facts about a simulation, written next to the registries
+/// as `simulation_metadata.json` so later analysis (and upgrade manifests)
+/// can tell exactly what was run.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SimulationMetadata {
+    /// monerosim crate version that generated the config
+    pub generator_version: String,
+    /// `ARTIFACT_SCHEMA_VERSION` at generation time
+    pub schema_version: u32,
+    pub simulation_seed: u64,
+    /// Resolved binary path -> info
+    pub binaries: BTreeMap<String, BinaryMetadata>,
+}
+
+/// One binary referenced by the generated config.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BinaryMetadata {
+    /// Agents whose processes launch this binary
+    pub used_by: Vec<String>,
+    /// First line of `--version`; absent when the binary couldn't be run
+    /// (e.g. generated with --skip-binary-check on another machine)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
 // ============================================================================
 // Shadow Configuration Types
 // ============================================================================
@@ -132,58 +278,118 @@ pub struct PublicNodeRegistry {
 /// Main Shadow simulator configuration.
 ///
 /// This is the root structure that gets serialized to YAML and consumed
-/// by the Shadow network simulator.
-#[derive(Serialize, Debug, Clone)]
+/// by the Shadow network simulator, and read back by `monerosim inspect`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ShadowConfig {
     /// General simulation settings
     pub general: ShadowGeneral,
     /// Network topology configuration
     pub network: ShadowNetwork,
     /// Experimental Shadow features
+    #[serde(default)]
     pub experimental: ShadowExperimental,
     /// Map of hostname to host configuration
     pub hosts: BTreeMap<String, ShadowHost>,
 }
 
 /// General Shadow simulation settings.
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ShadowGeneral {
-    /// Simulation stop time in seconds
-    pub stop_time: u64,
+    /// Simulation stop time, written in seconds; read back from either
+    /// seconds or a duration such as "10s"
+    #[serde(serialize_with = "serialize_seconds")]
+    pub stop_time: SimDuration,
     /// Random seed for deterministic simulation
     /// Shadow uses this to seed all random number generators
+    #[serde(default = "default_seed")]
     pub seed: u64,
     /// Number of parallel worker threads (1 = single-threaded for determinism)
+    #[serde(default)]
     pub parallelism: u32,
     /// Whether to model unblocked syscall latency
+    #[serde(default)]
     pub model_unblocked_syscall_latency: bool,
     /// Log level for Shadow (trace, debug, info, warn, error)
+    #[serde(default = "default_log_level")]
     pub log_level: String,
     /// Bootstrap end time - during bootstrap period, Shadow enables high bandwidth and no packet loss
     /// This helps networks settle before applying realistic constraints
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub bootstrap_end_time: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_shadow_time_option"
+    )]
+    pub bootstrap_end_time: Option<SimDuration>,
     /// Show simulation progress on stderr
+    #[serde(default)]
     pub progress: bool,
+    /// Where Shadow writes host output (Shadow defaults to "shadow.data")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_directory: Option<String>,
+}
+
+/// Shadow's `general.seed` default
+fn default_seed() -> u64 {
+    1
+}
+
+/// Shadow's `general.log_level` default
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+/// `general.stop_time` as a bare number of seconds
+fn serialize_seconds<S: serde::Serializer>(
+    time: &SimDuration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(time.as_secs())
+}
+
+/// A time in Shadow's own format ("300s"; Shadow does not accept "5m")
+fn serialize_shadow_time<S: serde::Serializer>(
+    time: &SimDuration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&time.to_shadow_string())
+}
+
+fn serialize_shadow_time_option<S: serde::Serializer>(
+    time: &Option<SimDuration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match time {
+        Some(time) => serialize_shadow_time(time, serializer),
+        None => serializer.serialize_none(),
+    }
 }
 
 /// Experimental Shadow features configuration.
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 pub struct ShadowExperimental {
     /// Runahead duration (optional, e.g., "1ms")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub runahead: Option<String>,
     /// Whether to use dynamic runahead
+    #[serde(default)]
     pub use_dynamic_runahead: bool,
     /// Enable native preemption so CPU-bound threads don't starve other threads.
     /// Without this, monero-wallet-rpc's ring signature computation can monopolize
     /// the host and prevent the RPC handler thread from responding.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub native_preemption_enabled: Option<bool>,
+    /// "thread-per-core" or "thread-per-host"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheduler: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_unapplied_cpu_latency: Option<String>,
+    /// `general.shadow_experimental` keys monerosim doesn't know, verbatim
+    #[serde(flatten)]
+    pub passthrough: BTreeMap<String, serde_yaml::Value>,
 }
 
 /// Shadow network configuration.
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ShadowNetwork {
     /// Network graph/topology definition
     pub graph: ShadowGraph,
@@ -196,7 +402,7 @@ pub struct ShadowNetwork {
 /// Shadow network graph definition.
 ///
 /// Can represent either a simple switch network or a complex GML-based topology.
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ShadowGraph {
     /// Type of network graph (e.g., "1_gbit_switch" or "gml")
     #[serde(rename = "type")]
@@ -213,14 +419,14 @@ pub struct ShadowGraph {
 }
 
 /// Reference to an external GML topology file.
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ShadowFileSource {
     /// Path to the GML file
     pub path: String,
 }
 
 /// Network node definition for inline topologies.
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ShadowNetworkNode {
     /// Unique node ID
     pub id: u32,
@@ -236,7 +442,7 @@ pub struct ShadowNetworkNode {
 }
 
 /// Network edge (link) definition for inline topologies.
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ShadowNetworkEdge {
     /// Source node ID
     pub source: u32,
@@ -256,8 +462,9 @@ pub struct ShadowNetworkEdge {
 /// Shadow host definition.
 ///
 /// Represents a simulated host in the Shadow network, which can run multiple
-/// processes (e.g., monerod, monero-wallet-rpc, agent scripts).
-#[derive(Serialize, Debug, Clone)]
+/// processes (e.g., monerod, monero-wallet-rpc, agent scripts). Also read
+/// back from a generated config by `registry::load_shadow_hosts`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ShadowHost {
     /// ID of the network node this host is attached to
     pub network_node_id: u32,
@@ -277,6 +484,21 @@ pub struct ShadowHost {
     /// Upload bandwidth for this host
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bandwidth_up: Option<String>,
+    /// Per-host options (packet capture); `None` = Shadow's defaults
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_options: Option<ShadowHostOptions>,
+}
+
+/// Shadow's per-host `host_options`.
+///
+/// With `pcap_enabled`, Shadow writes one pcap file per network interface
+/// (`eth0.pcap`, `lo.pcap`) into the host's directory under
+/// `<data_directory>/hosts/<hostname>/`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ShadowHostOptions {
+    /// Capture the host's packets
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pcap_enabled: bool,
 }
 
 /// Expected final state for a Shadow process.
@@ -289,7 +511,7 @@ pub struct ShadowHost {
 /// expected_final_state:
 ///   signaled: SIGTERM
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ExpectedFinalState {
     /// Process exited with the given exit code
     Exited(i32),
@@ -321,6 +543,27 @@ impl serde::Serialize for ExpectedFinalState {
     }
 }
 
+impl<'de> serde::Deserialize<'de> for ExpectedFinalState {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Name(String),
+            Exited { exited: i32 },
+            Signaled { signaled: String },
+        }
+        match Repr::deserialize(deserializer)? {
+            Repr::Name(name) if name == "running" => Ok(ExpectedFinalState::Running),
+            Repr::Name(name) => Err(serde::de::Error::custom(format!(
+                "unknown expected_final_state '{}'",
+                name
+            ))),
+            Repr::Exited { exited } => Ok(ExpectedFinalState::Exited(exited)),
+            Repr::Signaled { signaled } => Ok(ExpectedFinalState::Signaled(signaled)),
+        }
+    }
+}
+
 /// Process arguments for a Shadow process.
 ///
 /// Mirrors Shadow's own `ProcessArgs` enum: arguments can be either a
@@ -331,7 +574,7 @@ impl serde::Serialize for ExpectedFinalState {
 /// disagreement between bash/Shadow tokenizers on quoting and globbing.
 /// Use `Str` for the wrapper-script case where the single arg is just
 /// the script path.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ProcessArgs {
     /// Single string; Shadow's parser tokenizes it into argv.
     Str(String),
@@ -354,6 +597,21 @@ impl serde::Serialize for ProcessArgs {
     }
 }
 
+impl<'de> serde::Deserialize<'de> for ProcessArgs {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Str(String),
+            List(Vec<String>),
+        }
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Str(s) => ProcessArgs::Str(s),
+            Repr::List(v) => ProcessArgs::List(v),
+        })
+    }
+}
+
 impl From<String> for ProcessArgs {
     fn from(s: String) -> Self {
         ProcessArgs::Str(s)
@@ -375,19 +633,27 @@ impl From<Vec<String>> for ProcessArgs {
 /// Shadow process definition.
 ///
 /// Represents a single process to be executed within a Shadow host.
-#[derive(Serialize, Debug, Default, Clone)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct ShadowProcess {
     /// Path to the executable
     pub path: String,
     /// Command-line arguments
+    #[serde(default)]
     pub args: ProcessArgs,
     /// Environment variables for the process
+    #[serde(default)]
     pub environment: BTreeMap<String, String>,
-    /// Start time for the process (e.g., "0s", "10s", "1m")
-    pub start_time: String,
+    /// Start time for the process; Shadow starts it at the beginning
+    /// when unset
+    #[serde(default, serialize_with = "serialize_shadow_time")]
+    pub start_time: SimDuration,
     /// Shutdown time - when to send the `shutdown_signal` to the process
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub shutdown_time: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_shadow_time_option"
+    )]
+    pub shutdown_time: Option<SimDuration>,
     /// Signal to send at `shutdown_time`. Shadow defaults to SIGTERM
     /// when this field is omitted; we only emit it when overriding
     /// (e.g. SIGKILL for non-final wallet phases that may deadlock and
@@ -398,3 +664,18 @@ pub struct ShadowProcess {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expected_final_state: Option<ExpectedFinalState>,
 }
+
+impl ShadowProcess {
+    /// The program the process runs: `path`, or the `$0` a `bash -c`
+    /// launcher execs (see `utils::limits`)
+    pub fn executable(&self) -> &str {
+        match &self.args {
+            ProcessArgs::List(args)
+                if self.path.ends_with("/bash") && args.len() > 2 && args[0] == "-c" =>
+            {
+                &args[2]
+            }
+            _ => &self.path,
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! `monerosim sweep`: one generated simulation per combination of a
+//! config's `sweep` axes.
+//!
+//! Each combination gets its own directory under the output root, named
+//! after its axis values (`out_peers=8,topology=Mesh`). It holds the fully
+//! resolved config snapshot (`config.yaml`, without the `sweep` section),
+//! `shadow_agents.yaml`, `manifest.json` and the rest of a normal output
+//! directory, including the shared-dir files, which are not staged.
+//! `sweep_index.json` at the root lists every variant with its axis values,
+//! for `tx-analyzer sweep` and scripts.
+//!
+//! Variants keep the base config's `shared_dir` and `daemon_data_dir`;
+//! sweep those too to run variants side by side.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use color_eyre::eyre::{bail, eyre, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{check_overrides, expand_sweep, sweep_section, Config, SweepAxis};
+use crate::config_loader::load_config;
+use crate::manifest::{HashedFile, MANIFEST_FILE};
+use crate::orchestrator::generate_and_write;
+use crate::utils::cleanup::{prepare_directory, CleanupOutcome, CleanupPolicy};
+
+/// Index of the variants, at the root of the sweep
+pub const SWEEP_INDEX_FILE: &str = "sweep_index.json";
+/// Config snapshot in each variant directory
+pub const VARIANT_CONFIG_FILE: &str = "config.yaml";
+/// Shadow config in each variant directory
+pub const VARIANT_SHADOW_FILE: &str = "shadow_agents.yaml";
+
+/// `sweep_index.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SweepIndex {
+    /// monerosim crate version that generated the sweep
+    pub generator_version: String,
+    /// RFC 3339 generation time
+    pub generated_at: String,
+    /// The config holding the `sweep` section
+    pub base_config: HashedFile,
+    pub axes: Vec<SweepAxis>,
+    /// In generation order, first axis slowest
+    pub variants: Vec<SweepIndexEntry>,
+}
+
+/// One variant of a sweep; paths are relative to the sweep root
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SweepIndexEntry {
+    /// Also the directory name
+    pub name: String,
+    /// Axis name -> the value this variant gives it
+    pub parameters: BTreeMap<String, serde_json::Value>,
+    pub dir: String,
+    pub config: String,
+    pub shadow_config: String,
+    pub manifest: String,
+}
+
+impl SweepIndex {
+    /// Read `sweep_index.json` from `path`
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read sweep index {}", path.display()))?;
+        serde_json::from_str(&text)
+            .wrap_err_with(|| format!("Failed to parse sweep index {}", path.display()))
+    }
+}
+
+/// Generate every variant of the sweep in `config_path` under
+/// `output_root` and write `sweep_index.json`. `check` runs on each
+/// variant's loaded config before anything of it is generated (binary and
+/// script checks). Variant directories are cleaned with `policy`; one that
+/// holds files monerosim did not generate is an error rather than being
+/// written into.
+pub fn generate_sweep(
+    config_path: &Path,
+    output_root: &Path,
+    policy: CleanupPolicy,
+    check: impl Fn(&Config) -> Result<()>,
+) -> Result<SweepIndex> {
+    let text = fs::read_to_string(config_path)
+        .wrap_err_with(|| format!("Failed to read {}", config_path.display()))?;
+    let doc: serde_yaml::Value = serde_yaml::from_str(&text)
+        .wrap_err_with(|| format!("Failed to parse {}", config_path.display()))?;
+    let axes = sweep_section(&doc)
+        .map_err(|e| eyre!("{}: {}", config_path.display(), e))?
+        .ok_or_else(|| eyre!("{} has no sweep section", config_path.display()))?
+        .axes;
+    let variants = expand_sweep(&doc).map_err(|e| eyre!("{}: {}", config_path.display(), e))?;
+    log::info!(
+        "Sweep of {} axes: {} variants under {}",
+        axes.len(),
+        variants.len(),
+        output_root.display()
+    );
+
+    let mut entries = Vec::with_capacity(variants.len());
+    for variant in &variants {
+        let dir = output_root.join(&variant.name);
+        match prepare_directory(&dir, policy)
+            .wrap_err_with(|| format!("Failed to clean {}", dir.display()))?
+        {
+            CleanupOutcome::Preserved { foreign } => bail!(
+                "{} holds files monerosim did not generate ({}, ...); pass --force to replace it",
+                dir.display(),
+                foreign.join(", ")
+            ),
+            CleanupOutcome::Absent | CleanupOutcome::Removed | CleanupOutcome::Kept => {}
+        }
+        fs::create_dir_all(&dir).wrap_err_with(|| format!("Failed to create {}", dir.display()))?;
+
+        let snapshot = dir.join(VARIANT_CONFIG_FILE);
+        let yaml = serde_yaml::to_string(&variant.doc)
+            .wrap_err_with(|| format!("Failed to serialize variant {}", variant.name))?;
+        fs::write(&snapshot, yaml)
+            .wrap_err_with(|| format!("Failed to write {}", snapshot.display()))?;
+        let config =
+            load_config(&snapshot).wrap_err_with(|| format!("Sweep variant {}", variant.name))?;
+        let parsed = serde_yaml::to_value(&config)
+            .wrap_err_with(|| format!("Failed to serialize variant {}", variant.name))?;
+        check_overrides(&parsed, variant).map_err(|e| eyre!("{}: {}", variant.name, e))?;
+        check(&config).wrap_err_with(|| format!("Sweep variant {}", variant.name))?;
+
+        log::info!("Generating sweep variant {}", variant.name);
+        generate_and_write(
+            &config,
+            Some(&snapshot),
+            &dir.join(VARIANT_SHADOW_FILE),
+            false,
+        )
+        .wrap_err_with(|| format!("Sweep variant {}", variant.name))?;
+
+        let parameters = variant
+            .overrides
+            .iter()
+            .map(|(name, _, value)| Ok((name.clone(), serde_json::to_value(value)?)))
+            .collect::<std::result::Result<_, serde_json::Error>>()
+            .wrap_err_with(|| format!("Sweep variant {}", variant.name))?;
+        let relative = |file: &str| format!("{}/{}", variant.name, file);
+        entries.push(SweepIndexEntry {
+            name: variant.name.clone(),
+            parameters,
+            dir: variant.name.clone(),
+            config: relative(VARIANT_CONFIG_FILE),
+            shadow_config: relative(VARIANT_SHADOW_FILE),
+            manifest: relative(MANIFEST_FILE),
+        });
+    }
+
+    let index = SweepIndex {
+        generator_version: env!("CARGO_PKG_VERSION").to_string(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        base_config: HashedFile::read(config_path)?,
+        axes,
+        variants: entries,
+    };
+    let path = output_root.join(SWEEP_INDEX_FILE);
+    fs::write(&path, serde_json::to_string_pretty(&index)?)
+        .wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+    Ok(index)
+}
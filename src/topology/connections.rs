@@ -3,24 +3,64 @@
 //! This file handles generation of peer connections based on the selected
 //! topology pattern and peer discovery mode (Dynamic, Hardcoded, Hybrid).
 
+use std::collections::{BTreeMap, BTreeSet};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+
+use crate::config::{ConnectionEnforcement, HybridOptions};
 use crate::topology::types::Topology;
 
+/// Which rule produced a fixed peer connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerSource {
+    Seed,
+    Topology,
+}
+
+/// One fixed peer handed to an agent, as recorded in `peer_sources.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PeerAssignment {
+    /// `IP:PORT` of the peer
+    pub peer: String,
+    pub source: PeerSource,
+}
+
+/// Template graph wired in Hardcoded peer mode, as recorded in
+/// `topology_connections.json`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TopologyConnections {
+    pub topology: Topology,
+    pub simulation_seed: u64,
+    /// Agent id -> ids of the agents it was given as priority peers
+    pub adjacency: BTreeMap<String, Vec<String>>,
+}
+
 /// Generate peer connections based on topology template
 ///
 /// # Arguments
-/// * `topology` - The network topology template (Star, Mesh, Ring, DAG)
+/// * `topology` - The network topology template (Star, Mesh, Ring, DAG, Tree, SmallWorld)
 /// * `agent_index` - The index of the current agent
 /// * `seed_agents` - List of all available seed nodes
 /// * `agent_ip` - The IP address of the current agent
+/// * `seed` - Simulation seed; only SmallWorld rewiring draws from it
+/// * `enforcement` - Priority or exclusive fixed peers
 ///
 /// # Returns
 /// A vector of connection strings in the format `--seed-node=IP:PORT`
+/// (the `enforcement` flag for Tree and SmallWorld, whose edges are
+/// persistent, and for every template in exclusive mode)
 pub fn generate_topology_connections(
     topology: &Topology,
     agent_index: usize,
     seed_agents: &[String],
     agent_ip: &str,
+    seed: u64,
+    enforcement: ConnectionEnforcement,
 ) -> Vec<String> {
+    let seed_flag = enforcement.seed_flag();
     match topology {
         Topology::Star => {
             // Star topology: all nodes connect to the first seed node (hub)
@@ -31,7 +71,7 @@ pub fn generate_topology_connections(
                 if seed_agents[0].starts_with(&format!("{}:", agent_ip)) {
                     vec![]
                 } else {
-                    vec![format!("--seed-node={}", seed_agents[0])]
+                    vec![format!("{}={}", seed_flag, seed_agents[0])]
                 }
             }
         }
@@ -41,7 +81,7 @@ pub fn generate_topology_connections(
             for seed in seed_agents.iter() {
                 // Don't connect to self
                 if !seed.starts_with(&format!("{}:", agent_ip)) {
-                    connections.push(format!("--seed-node={}", seed));
+                    connections.push(format!("{}={}", seed_flag, seed));
                 }
             }
             connections
@@ -65,14 +105,14 @@ pub fn generate_topology_connections(
                     let prev_seed = &seed_agents[prev_index];
                     // Don't connect to self
                     if !prev_seed.starts_with(&format!("{}:", agent_ip)) {
-                        connections.push(format!("--seed-node={}", prev_seed));
+                        connections.push(format!("{}={}", seed_flag, prev_seed));
                     }
                 }
                 if next_index < seed_agents.len() {
                     let next_seed = &seed_agents[next_index];
                     // Don't connect to self
                     if !next_seed.starts_with(&format!("{}:", agent_ip)) {
-                        connections.push(format!("--seed-node={}", next_seed));
+                        connections.push(format!("{}={}", seed_flag, next_seed));
                     }
                 }
             }
@@ -85,11 +125,342 @@ pub fn generate_topology_connections(
                 if j < agent_index {
                     // Don't connect to self
                     if !seed.starts_with(&format!("{}:", agent_ip)) {
-                        connections.push(format!("--seed-node={}", seed));
+                        connections.push(format!("{}={}", seed_flag, seed));
                     }
                 }
             }
             connections
         }
+        Topology::Tree { fanout } => tree_neighbors(agent_index, seed_agents.len(), *fanout)
+            .into_iter()
+            .map(|j| &seed_agents[j])
+            .filter(|peer| !peer.starts_with(&format!("{}:", agent_ip)))
+            .map(|peer| format!("{}={}", enforcement.flag(), peer))
+            .collect(),
+        Topology::SmallWorld { k, rewire_prob } => {
+            small_world_adjacency(seed_agents.len(), *k, *rewire_prob, seed)
+                .get(agent_index)
+                .into_iter()
+                .flatten()
+                .map(|&j| &seed_agents[j])
+                .filter(|peer| !peer.starts_with(&format!("{}:", agent_ip)))
+                .map(|peer| format!("{}={}", enforcement.flag(), peer))
+                .collect()
+        }
+    }
+}
+
+/// Watts–Strogatz graph over `count` nodes: a ring lattice linking each node
+/// to its `k / 2` nearest neighbours on either side, then each lattice edge
+/// rewired with probability `rewire_prob` to a uniformly chosen new endpoint.
+/// Rewiring draws from `seed` in a fixed order, so the graph reproduces.
+/// Returns sorted neighbour lists; the edge count is that of the lattice.
+pub fn small_world_adjacency(
+    count: usize,
+    k: usize,
+    rewire_prob: f64,
+    seed: u64,
+) -> Vec<Vec<usize>> {
+    let mut adjacency = vec![BTreeSet::new(); count];
+    if count < 2 {
+        return vec![Vec::new(); count];
+    }
+    // Validation requires an even k below count; clamp so anything else
+    // still yields a simple graph
+    let half = (k / 2).clamp(1, count - 1);
+    for i in 0..count {
+        for j in 1..=half {
+            let t = (i + j) % count;
+            if t != i {
+                adjacency[i].insert(t);
+                adjacency[t].insert(i);
+            }
+        }
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    for j in 1..=half {
+        for i in 0..count {
+            let t = (i + j) % count;
+            if !adjacency[i].contains(&t) || rng.gen::<f64>() >= rewire_prob {
+                continue;
+            }
+            let free: Vec<usize> = (0..count)
+                .filter(|&m| m != i && !adjacency[i].contains(&m))
+                .collect();
+            if free.is_empty() {
+                continue;
+            }
+            let m = free[rng.gen_range(0..free.len())];
+            adjacency[i].remove(&t);
+            adjacency[t].remove(&i);
+            adjacency[i].insert(m);
+            adjacency[m].insert(i);
+        }
+    }
+    adjacency
+        .into_iter()
+        .map(|peers| peers.into_iter().collect())
+        .collect()
+}
+
+/// Parent (first) and children of `index` in a balanced `fanout`-ary tree
+/// over `count` nodes laid out breadth-first; a fanout of 0 is treated as 1.
+fn tree_neighbors(index: usize, count: usize, fanout: usize) -> Vec<usize> {
+    let fanout = fanout.max(1);
+    let mut neighbors = Vec::new();
+    if index >= count {
+        return neighbors;
+    }
+    if index > 0 {
+        neighbors.push((index - 1) / fanout);
+    }
+    let first_child = index.saturating_mul(fanout).saturating_add(1);
+    neighbors.extend((first_child..first_child.saturating_add(fanout)).take_while(|&c| c < count));
+    neighbors
+}
+
+/// Pick a Hybrid agent's fixed peers under `hybrid_options`.
+///
+/// Seeds are rotated by `agent_index` so consecutive agents start on
+/// different seeds and the load spreads evenly; the choice is a pure
+/// function of the inputs, so runs reproduce. Topology peers follow
+/// [`generate_topology_connections`] order, skipping any already picked as
+/// seeds. Fewer than requested are returned when the pools run short.
+pub fn select_hybrid_peers(
+    options: &HybridOptions,
+    topology: Option<&Topology>,
+    agent_index: usize,
+    seed_agents: &[String],
+    all_agent_ips: &[String],
+    agent_ip: &str,
+    seed: u64,
+) -> Vec<PeerAssignment> {
+    let self_prefix = format!("{}:", agent_ip);
+    let seeds: Vec<&String> = seed_agents
+        .iter()
+        .filter(|s| !s.starts_with(&self_prefix))
+        .collect();
+
+    let mut picked: Vec<PeerAssignment> = Vec::new();
+    if !seeds.is_empty() {
+        let start = agent_index % seeds.len();
+        picked.extend(
+            (0..options.seed_connections.min(seeds.len())).map(|k| PeerAssignment {
+                peer: seeds[(start + k) % seeds.len()].clone(),
+                source: PeerSource::Seed,
+            }),
+        );
+    }
+
+    if let Some(topo) = topology {
+        let mut taken = 0;
+        for conn in generate_topology_connections(
+            topo,
+            agent_index,
+            all_agent_ips,
+            agent_ip,
+            seed,
+            // Only the peers are kept; the caller picks the flags
+            ConnectionEnforcement::Priority,
+        ) {
+            if taken == options.topology_connections {
+                break;
+            }
+            let peer = conn
+                .split_once('=')
+                .map_or(conn.as_str(), |(_, p)| p)
+                .to_string();
+            if picked.iter().any(|p| p.peer == peer) {
+                continue;
+            }
+            picked.push(PeerAssignment {
+                peer,
+                source: PeerSource::Topology,
+            });
+            taken += 1;
+        }
+    }
+    picked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hybrid_mix_is_honoured_for_twenty_agents() {
+        let ips: Vec<String> = (0..20).map(|n| format!("10.0.0.{}:18080", n + 1)).collect();
+        let seeds: Vec<String> = ips[..5].to_vec();
+        let options = HybridOptions {
+            seed_connections: 2,
+            topology_connections: 6,
+        };
+
+        let mut first_seeds = std::collections::BTreeSet::new();
+        for (i, entry) in ips.iter().enumerate().skip(5) {
+            let ip = entry.split(':').next().unwrap();
+            let peers =
+                select_hybrid_peers(&options, Some(&Topology::Mesh), i, &seeds, &ips, ip, 0);
+            let count = |src| peers.iter().filter(|p| p.source == src).count();
+            assert_eq!(count(PeerSource::Seed), 2, "agent {}", i);
+            assert_eq!(count(PeerSource::Topology), 6, "agent {}", i);
+            assert!(peers
+                .iter()
+                .all(|p| !p.peer.starts_with(&format!("{}:", ip))));
+            let unique: std::collections::HashSet<_> = peers.iter().map(|p| &p.peer).collect();
+            assert_eq!(unique.len(), peers.len(), "duplicate peer for agent {}", i);
+            first_seeds.insert(peers[0].peer.clone());
+
+            // Deterministic: same inputs, same selection
+            assert_eq!(
+                peers,
+                select_hybrid_peers(&options, Some(&Topology::Mesh), i, &seeds, &ips, ip, 0)
+            );
+        }
+        // Rotation spreads regular agents over every seed
+        assert_eq!(first_seeds.len(), seeds.len());
+    }
+
+    #[test]
+    fn hybrid_selection_skips_self_and_runs_short_gracefully() {
+        let ips: Vec<String> = (0..3).map(|n| format!("10.0.0.{}:18080", n + 1)).collect();
+        let options = HybridOptions {
+            seed_connections: 4,
+            topology_connections: 6,
+        };
+        let peers = select_hybrid_peers(
+            &options,
+            Some(&Topology::Mesh),
+            0,
+            &ips,
+            &ips,
+            "10.0.0.1",
+            0,
+        );
+        // Two other seeds; the mesh has nothing left that isn't already a seed
+        assert_eq!(peers.len(), 2);
+        assert!(peers.iter().all(|p| p.source == PeerSource::Seed));
+    }
+
+    #[test]
+    fn tree_edges_form_a_tree() {
+        for fanout in [2, 3, 5] {
+            for n in [1, 2, 3, 7, 10, 31, 100] {
+                let ips: Vec<String> = (0..n).map(|k| format!("10.0.{}.1:18080", k)).collect();
+                let topology = Topology::Tree { fanout };
+                let mut edges = std::collections::BTreeSet::new();
+                for (i, entry) in ips.iter().enumerate() {
+                    let ip = entry.split(':').next().unwrap();
+                    for conn in generate_topology_connections(
+                        &topology,
+                        i,
+                        &ips,
+                        ip,
+                        0,
+                        ConnectionEnforcement::Priority,
+                    ) {
+                        let peer = conn.strip_prefix("--add-priority-node=").unwrap();
+                        let j = ips.iter().position(|e| e == peer).unwrap();
+                        edges.insert((i.min(j), i.max(j)));
+                    }
+                    // Only the root has no parent; nobody has more than
+                    // `fanout` children
+                    let degree = tree_neighbors(i, n, fanout).len();
+                    assert!(degree <= fanout + usize::from(i > 0));
+                }
+                // n - 1 edges plus connectivity means connected and acyclic
+                assert_eq!(
+                    edges.len(),
+                    n.saturating_sub(1),
+                    "fanout {} n {}",
+                    fanout,
+                    n
+                );
+                let mut reached = vec![false; n];
+                let mut stack = vec![0];
+                while let Some(v) = stack.pop() {
+                    if v < n && !std::mem::replace(&mut reached[v], true) {
+                        stack.extend(
+                            edges.iter().filter_map(|&(a, b)| {
+                                (a == v).then_some(b).or((b == v).then_some(a))
+                            }),
+                        );
+                    }
+                }
+                assert!(reached.iter().all(|&r| r), "fanout {} n {}", fanout, n);
+            }
+        }
+    }
+
+    #[test]
+    fn tree_neighbors_are_parent_then_children() {
+        assert_eq!(tree_neighbors(0, 10, 3), [1, 2, 3]);
+        assert_eq!(tree_neighbors(1, 10, 3), [0, 4, 5, 6]);
+        assert_eq!(tree_neighbors(3, 10, 3), [0]);
+        assert_eq!(tree_neighbors(2, 10, 3), [0, 7, 8, 9]);
+        assert!(tree_neighbors(10, 10, 3).is_empty());
+        // Fanout 0 degrades to a chain instead of dividing by zero
+        assert_eq!(tree_neighbors(1, 3, 0), [0, 2]);
+    }
+
+    fn edge_set(adjacency: &[Vec<usize>]) -> BTreeSet<(usize, usize)> {
+        adjacency
+            .iter()
+            .enumerate()
+            .flat_map(|(i, peers)| peers.iter().map(move |&j| (i.min(j), i.max(j))))
+            .collect()
+    }
+
+    #[test]
+    fn small_world_keeps_the_lattice_edge_count_and_reproduces() {
+        for rewire_prob in [0.0, 0.1, 0.5, 1.0] {
+            let graph = small_world_adjacency(50, 4, rewire_prob, 42);
+            assert_eq!(graph, small_world_adjacency(50, 4, rewire_prob, 42));
+            assert_eq!(edge_set(&graph).len(), 100, "p {}", rewire_prob);
+            for (i, peers) in graph.iter().enumerate() {
+                assert!(!peers.contains(&i), "self-loop at {}", i);
+                // Undirected: every edge is listed at both ends
+                assert!(peers.iter().all(|&j| graph[j].contains(&i)));
+            }
+        }
+        // No rewiring leaves the ring lattice
+        let lattice = small_world_adjacency(10, 4, 0.0, 7);
+        assert_eq!(lattice[0], [1, 2, 8, 9]);
+        assert!(lattice.iter().all(|peers| peers.len() == 4));
+        // The seed picks the rewiring
+        assert_ne!(
+            small_world_adjacency(50, 4, 0.5, 1),
+            small_world_adjacency(50, 4, 0.5, 2)
+        );
+    }
+
+    #[test]
+    fn small_world_connections_skip_self_and_follow_the_seed() {
+        let ips: Vec<String> = (0..20).map(|n| format!("10.0.0.{}:18080", n + 1)).collect();
+        let topology = Topology::SmallWorld {
+            k: 4,
+            rewire_prob: 0.3,
+        };
+        let adjacency = small_world_adjacency(20, 4, 0.3, 9);
+        for (i, entry) in ips.iter().enumerate() {
+            let ip = entry.split(':').next().unwrap();
+            let conns = generate_topology_connections(
+                &topology,
+                i,
+                &ips,
+                ip,
+                9,
+                ConnectionEnforcement::Priority,
+            );
+            let expected: Vec<String> = adjacency[i]
+                .iter()
+                .map(|&j| format!("--add-priority-node={}", ips[j]))
+                .collect();
+            assert_eq!(conns, expected);
+        }
+        // Degenerate sizes stay simple graphs instead of panicking
+        assert!(small_world_adjacency(1, 4, 0.5, 0)[0].is_empty());
+        assert_eq!(small_world_adjacency(3, 8, 1.0, 0).len(), 3);
     }
 }
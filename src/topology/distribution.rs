@@ -7,14 +7,102 @@
 //! - **Sequential**: Assign agents to nodes 0, 1, 2, ... (legacy behavior)
 //! - **Weighted**: Custom weights per region
 //!
+//! `as_weights` replaces the strategy with a share of the agents per AS, and
+//! `largest_remainder` turns such shares into whole agent counts (also for a
+//! switch network's `geographic_distribution`).
+//!
 //! The distribution ensures agents are spread across the simulated Internet
-//! rather than clustering in a single region.
+//! rather than clustering in a single region. A `MinerPlacement` takes the
+//! miners out of the strategy and places them by AS or node instead, and
+//! agents with a `placement` of their own are pinned before anyone else.
 
 use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 
-use crate::config::{DistributionStrategy, RegionWeights};
-use crate::ip::as_manager::calculate_region_boundaries;
+use crate::config::{AgentPlacement, DistributionStrategy, MinerPlacement, RegionWeights};
+use crate::ip::as_manager::{calculate_region_boundaries, AsRegion};
+
+/// Miners and the placement they follow, for
+/// `distribute_agents_across_topology`
+pub struct MinerConstraint<'a> {
+    pub placement: &'a MinerPlacement,
+    /// Per agent, whether it mines
+    pub is_miner: &'a [bool],
+    /// GML node ids by node index
+    pub node_ids: &'a [u32],
+}
+
+/// Custom weights for `distribute_agents_across_topology`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DistributionWeights<'a> {
+    /// Per region, for the Weighted strategy
+    pub regions: Option<&'a RegionWeights>,
+    /// Share per AS number (`as_weights`); replaces the strategy
+    pub as_shares: Option<&'a BTreeMap<String, f64>>,
+}
+
+/// Agents with a `placement`, for `distribute_agents_across_topology`
+pub struct PinConstraint<'a> {
+    /// Per agent, its id and placement if it has one
+    pub placements: &'a [Option<(&'a str, &'a AgentPlacement)>],
+    /// GML node ids by node index
+    pub node_ids: &'a [u32],
+}
+
+/// What the keys of a `RealizedDistribution` name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DistributionKey {
+    /// Region keys of a switch network's `geographic_distribution`
+    Region,
+    /// AS numbers of a GML network's `as_weights`
+    As,
+}
+
+/// Agents per region or AS under `geographic_distribution` / `as_weights`,
+/// as reported in the generation summary and the manifest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RealizedDistribution {
+    pub by: DistributionKey,
+    /// Configured share per key
+    pub shares: BTreeMap<String, f64>,
+    /// Agents each key got
+    pub counts: BTreeMap<String, usize>,
+}
+
+/// Split `total` agents over `shares` by the largest-remainder method: each
+/// share gets the whole part of its quota, and the agents left over go to
+/// the largest fractional parts, ties to the earlier share. The counts sum
+/// to `total` unless every share is zero.
+pub fn largest_remainder(total: usize, shares: &[f64]) -> Vec<usize> {
+    let sum: f64 = shares.iter().sum();
+    if sum <= 0.0 {
+        return vec![0; shares.len()];
+    }
+    let quotas: Vec<f64> = shares.iter().map(|s| total as f64 * s / sum).collect();
+    let mut counts: Vec<usize> = quotas.iter().map(|q| q.floor() as usize).collect();
+    let left = total.saturating_sub(counts.iter().sum());
+    let mut order: Vec<usize> = (0..shares.len()).collect();
+    order.sort_by(|&a, &b| {
+        (quotas[b] - quotas[b].floor()).total_cmp(&(quotas[a] - quotas[a].floor()))
+    });
+    for &i in order.iter().take(left) {
+        counts[i] += 1;
+    }
+    counts
+}
+
+/// Where a miner ended up, as reported in the generation summary and the
+/// manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MinerLocation {
+    /// GML node id
+    pub node: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_number: Option<String>,
+}
 
 /// Distributes agents across network topology nodes.
 ///
@@ -24,37 +112,269 @@ use crate::ip::as_manager::calculate_region_boundaries;
 /// * `agent_count` - Number of agents to distribute
 /// * `as_numbers` - Vector of AS numbers from GML (if available)
 /// * `strategy` - Distribution strategy to use (defaults to Global)
-/// * `weights` - Optional custom region weights (for Weighted strategy)
+/// * `weights` - Optional custom region weights (for Weighted strategy) and
+///   AS shares (replacing `strategy`)
+/// * `miners` - Miner placement; the other agents follow `strategy`
+/// * `pins` - Per-agent placements, satisfied first; miner placement and
+///   `strategy` then handle the remaining agents as if the pinned ones
+///   did not exist
 ///
 /// # Returns
 ///
-/// * Vector of node assignments (indices for GML, empty for switch)
+/// * Vector of node assignments (indices for GML, empty for switch), or an
+///   error when a miner placement or pin names an AS or node the topology
+///   lacks, or pins more agents to a node than its AS subnet holds
 pub fn distribute_agents_across_topology(
     topology_path: Option<&Path>,
     agent_count: usize,
     as_numbers: &[Option<String>],
     strategy: Option<&DistributionStrategy>,
-    weights: Option<&RegionWeights>,
-) -> Vec<Option<usize>> {
+    weights: DistributionWeights<'_>,
+    miners: Option<MinerConstraint<'_>>,
+    pins: Option<PinConstraint<'_>>,
+) -> Result<Vec<Option<usize>>, String> {
     let strategy = strategy.unwrap_or(&DistributionStrategy::Global);
     let total_nodes = as_numbers.len();
 
-    match topology_path {
-        Some(path) => {
-            info!(
-                "Distributing {} agents across GML topology '{}' using {:?} strategy",
-                agent_count,
-                path.display(),
-                strategy
-            );
-            distribute_agents_gml(agent_count, total_nodes, strategy, weights)
+    let Some(path) = topology_path else {
+        info!("Using switch-based topology with {} agents", agent_count);
+        // For switch-based topology, there's no explicit distribution needed
+        return Ok(vec![None; agent_count]);
+    };
+    info!(
+        "Distributing {} agents across GML topology '{}' using {:?} strategy",
+        agent_count,
+        path.display(),
+        strategy
+    );
+    let pinned = match pins.filter(|_| total_nodes > 0) {
+        Some(pins) => place_pinned(pins.placements, as_numbers, pins.node_ids)?,
+        None => Vec::new(),
+    };
+    let pinned_at = |i: usize| pinned.get(i).copied().flatten();
+    let free: Vec<usize> = (0..agent_count)
+        .filter(|&i| pinned_at(i).is_none())
+        .collect();
+    let free_miners: Vec<bool> = match &miners {
+        Some(m) => free
+            .iter()
+            .map(|&i| m.is_miner.get(i).copied().unwrap_or(false))
+            .collect(),
+        None => Vec::new(),
+    };
+    let mut rest = distribute_unpinned(
+        free.len(),
+        as_numbers,
+        strategy,
+        weights,
+        miners.map(|m| MinerConstraint {
+            placement: m.placement,
+            is_miner: &free_miners,
+            node_ids: m.node_ids,
+        }),
+    )?
+    .into_iter();
+    Ok((0..agent_count)
+        .map(|i| match pinned_at(i) {
+            Some(node) => Some(node),
+            None => rest.next().flatten(),
+        })
+        .collect())
+}
+
+/// Node indices of the agents that have no pin
+fn distribute_unpinned(
+    agent_count: usize,
+    as_numbers: &[Option<String>],
+    strategy: &DistributionStrategy,
+    weights: DistributionWeights<'_>,
+    miners: Option<MinerConstraint<'_>>,
+) -> Result<Vec<Option<usize>>, String> {
+    let total_nodes = as_numbers.len();
+    let Some(miners) = miners.filter(|_| total_nodes > 0) else {
+        return distribute_free(agent_count, as_numbers, strategy, weights);
+    };
+
+    let miner_count = miners.is_miner.iter().filter(|&&m| m).count();
+    let mut placed =
+        place_miners(miners.placement, miner_count, as_numbers, miners.node_ids)?.into_iter();
+    let mut others =
+        distribute_free(agent_count - miner_count, as_numbers, strategy, weights)?.into_iter();
+    Ok((0..agent_count)
+        .map(|i| {
+            if miners.is_miner.get(i).copied().unwrap_or(false) {
+                placed.next()
+            } else {
+                others.next().flatten()
+            }
+        })
+        .collect())
+}
+
+/// Node indices of the agents that are neither pinned nor placed as miners:
+/// by AS share when `as_weights` is set, else by `strategy`
+fn distribute_free(
+    agent_count: usize,
+    as_numbers: &[Option<String>],
+    strategy: &DistributionStrategy,
+    weights: DistributionWeights<'_>,
+) -> Result<Vec<Option<usize>>, String> {
+    match weights.as_shares.filter(|_| !as_numbers.is_empty()) {
+        Some(shares) => distribute_by_as(agent_count, as_numbers, shares),
+        None => Ok(distribute_agents_gml(
+            agent_count,
+            as_numbers.len(),
+            strategy,
+            weights.regions,
+        )),
+    }
+}
+
+/// AS-share distribution: `largest_remainder` counts per AS, in AS order,
+/// each AS's agents taking turns over its nodes
+fn distribute_by_as(
+    agent_count: usize,
+    as_numbers: &[Option<String>],
+    shares: &BTreeMap<String, f64>,
+) -> Result<Vec<Option<usize>>, String> {
+    let counts = largest_remainder(agent_count, &shares.values().copied().collect::<Vec<_>>());
+    let mut assignments = Vec::with_capacity(agent_count);
+    for (as_number, count) in shares.keys().zip(counts) {
+        let nodes: Vec<usize> = (0..as_numbers.len())
+            .filter(|&n| as_numbers[n].as_deref() == Some(as_number.as_str()))
+            .collect();
+        if nodes.is_empty() {
+            return Err(format!("as_weights: no GML node is in AS {}", as_number));
+        }
+        info!("  AS {}: {} agents", as_number, count);
+        assignments.extend((0..count).map(|k| Some(nodes[k % nodes.len()])));
+    }
+    Ok(assignments)
+}
+
+/// Node index per pinned agent (None for the others). Pins sharing an AS
+/// or region take turns over its nodes.
+fn place_pinned(
+    placements: &[Option<(&str, &AgentPlacement)>],
+    as_numbers: &[Option<String>],
+    node_ids: &[u32],
+) -> Result<Vec<Option<usize>>, String> {
+    let boundaries = calculate_region_boundaries(as_numbers.len());
+    let mut turns: BTreeMap<String, usize> = BTreeMap::new();
+    let mut per_node: BTreeMap<usize, Vec<&str>> = BTreeMap::new();
+    let mut placed = Vec::with_capacity(placements.len());
+    for pin in placements {
+        let Some((agent_id, placement)) = pin else {
+            placed.push(None);
+            continue;
+        };
+        let candidates: Vec<usize> = match placement {
+            AgentPlacement::NodeId(id) => {
+                node_ids.iter().position(|n| n == id).into_iter().collect()
+            }
+            AgentPlacement::As(asn) => (0..as_numbers.len())
+                .filter(|&n| as_numbers[n].as_deref() == Some(asn.as_str()))
+                .collect(),
+            AgentPlacement::Region(name) => AsRegion::from_key(name)
+                .map(|region| {
+                    let (_, start, end) = boundaries[region.index()];
+                    (start..=end).collect()
+                })
+                .unwrap_or_default(),
+        };
+        if candidates.is_empty() {
+            return Err(format!(
+                "Agent '{}': placement {:?} matches no GML node",
+                agent_id, placement
+            ));
         }
-        None => {
-            info!("Using switch-based topology with {} agents", agent_count);
-            // For switch-based topology, there's no explicit distribution needed
-            vec![None; agent_count]
+        let turn = turns.entry(format!("{:?}", placement)).or_insert(0);
+        let node = candidates[*turn % candidates.len()];
+        *turn += 1;
+        debug!("Pinned {} -> node {} ({:?})", agent_id, node, placement);
+        per_node.entry(node).or_default().push(agent_id);
+        placed.push(Some(node));
+    }
+
+    if !per_node.is_empty() {
+        info!(
+            "Pinned {} agents to {} GML nodes",
+            per_node.values().map(Vec::len).sum::<usize>(),
+            per_node.len()
+        );
+    }
+    Ok(placed)
+}
+
+/// Node indices for `miner_count` miners under `placement`. Nodes without
+/// an AS attribute count as an AS of their own.
+fn place_miners(
+    placement: &MinerPlacement,
+    miner_count: usize,
+    as_numbers: &[Option<String>],
+    node_ids: &[u32],
+) -> Result<Vec<usize>, String> {
+    // Node indices per AS, ASes in order of their first node
+    let mut groups: Vec<(Option<&str>, Vec<usize>)> = Vec::new();
+    let mut group_of: BTreeMap<&str, usize> = BTreeMap::new();
+    for (node, as_number) in as_numbers.iter().enumerate() {
+        match as_number.as_deref() {
+            Some(asn) => match group_of.get(asn) {
+                Some(&g) => groups[g].1.push(node),
+                None => {
+                    group_of.insert(asn, groups.len());
+                    groups.push((Some(asn), vec![node]));
+                }
+            },
+            None => groups.push((None, vec![node])),
         }
     }
+
+    let placed = match placement {
+        MinerPlacement::SpreadAs => {
+            let n = groups.len();
+            (0..miner_count)
+                .map(|k| {
+                    // Fewer miners than ASes: evenly spaced ASes, which spreads
+                    // them over the regions too. Otherwise cycle the ASes and
+                    // their nodes.
+                    let (group, round) = if miner_count <= n {
+                        (k * n / miner_count, 0)
+                    } else {
+                        (k % n, k / n)
+                    };
+                    let nodes = &groups[group].1;
+                    nodes[round % nodes.len()]
+                })
+                .collect()
+        }
+        MinerPlacement::Colocated { as_number } => {
+            let nodes = groups
+                .iter()
+                .find(|(asn, _)| *asn == Some(as_number.as_str()))
+                .map(|(_, nodes)| nodes)
+                .ok_or_else(|| {
+                    format!(
+                        "miner_placement colocated: no GML node is in AS {}",
+                        as_number
+                    )
+                })?;
+            (0..miner_count).map(|k| nodes[k % nodes.len()]).collect()
+        }
+        MinerPlacement::Explicit { nodes } => (0..miner_count)
+            .map(|k| {
+                let id = nodes.get(&k).ok_or_else(|| {
+                    format!("miner_placement explicit: miner {} is not mapped", k)
+                })?;
+                node_ids
+                    .iter()
+                    .position(|n| n == id)
+                    .ok_or_else(|| format!("miner_placement explicit: no GML node {}", id))
+            })
+            .collect::<Result<_, _>>()?,
+    };
+    info!("Placed {} miners ({:?})", miner_count, placement);
+    Ok(placed)
 }
 
 /// Distributes agents across GML topology using the specified strategy.
@@ -117,7 +437,7 @@ fn distribute_global(agent_count: usize, total_nodes: usize) -> Vec<Option<usize
         // Cycle through regions round-robin
         let region_idx = i % 6;
         let (region, start, end) = boundaries[region_idx];
-        let region_size = end.saturating_sub(start) + 1;
+        let region_size = region_size(start, end);
 
         if region_size == 0 {
             // Region has no nodes, skip to next
@@ -147,6 +467,12 @@ fn distribute_global(agent_count: usize, total_nodes: usize) -> Vec<Option<usize
     assignments
 }
 
+/// Nodes in a region from `calculate_region_boundaries`. Small topologies
+/// leave the last regions empty, as `start` past `end`.
+fn region_size(start: usize, end: usize) -> usize {
+    (end + 1).saturating_sub(start)
+}
+
 /// Weighted distribution: use custom weights to determine how many agents
 /// go to each region.
 fn distribute_weighted(
@@ -216,11 +542,19 @@ fn distribute_weighted(
 
     for (region_idx, &count) in agents_per_region.iter().enumerate() {
         let (region, start, end) = boundaries[region_idx];
-        let region_size = end.saturating_sub(start) + 1;
+        let region_size = region_size(start, end);
+        if region_size == 0 && count > 0 {
+            warn!(
+                "Region {:?} has no nodes, assigning its agents to node 0",
+                region
+            );
+        }
 
         for j in 0..count {
-            let offset = j % region_size;
-            let node = start + offset;
+            let node = match region_size {
+                0 => 0,
+                size => start + j % size,
+            };
             assignments.push(Some(node));
             debug!("Agent -> node {} ({:?})", node, region);
         }
@@ -294,6 +628,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_small_topologies_stay_in_range() {
+        // 10 nodes leave Africa and Oceania without nodes
+        let boundaries = calculate_region_boundaries(10);
+        assert_eq!(region_size(boundaries[5].1, boundaries[5].2), 0);
+        for result in [distribute_global(12, 10), distribute_weighted(12, 10, None)] {
+            assert_eq!(result.len(), 12);
+            assert!(
+                result.iter().all(|n| n.is_some_and(|n| n < 10)),
+                "{:?}",
+                result
+            );
+        }
+    }
+
     #[test]
     fn test_weighted_distribution() {
         let weights = RegionWeights {
@@ -318,4 +667,198 @@ mod tests {
             }
         }
     }
+
+    /// `nodes` nodes in ASes of `as_size` consecutive nodes
+    fn as_numbers(nodes: usize, as_size: usize) -> Vec<Option<String>> {
+        (0..nodes)
+            .map(|n| Some((n / as_size).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn spread_as_uses_every_as_before_doubling_up() {
+        for (nodes, as_size) in [(60, 5), (30, 1), (100, 7), (6, 2)] {
+            let asns = as_numbers(nodes, as_size);
+            let as_count = nodes.div_ceil(as_size);
+            for miners in [1, 2, 3, as_count, as_count + 1, 2 * as_count + 3] {
+                let placed = place_miners(&MinerPlacement::SpreadAs, miners, &asns, &[]).unwrap();
+                assert_eq!(placed.len(), miners);
+                let mut per_as = vec![0; as_count];
+                for node in &placed {
+                    per_as[node / as_size] += 1;
+                }
+                let (min, max) = (per_as.iter().min().unwrap(), per_as.iter().max().unwrap());
+                if miners <= as_count {
+                    assert_eq!(*max, 1, "{} miners on {:?}", miners, per_as);
+                } else {
+                    assert!(max - min <= 1, "{} miners on {:?}", miners, per_as);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn colocated_and_explicit_placements() {
+        let asns = as_numbers(40, 4);
+        let colocated = MinerPlacement::Colocated {
+            as_number: "3".to_string(),
+        };
+        for miners in [1, 4, 9] {
+            let placed = place_miners(&colocated, miners, &asns, &[]).unwrap();
+            assert!(placed.iter().all(|&n| asns[n].as_deref() == Some("3")));
+        }
+        let missing = MinerPlacement::Colocated {
+            as_number: "99".to_string(),
+        };
+        assert!(place_miners(&missing, 2, &asns, &[]).is_err());
+
+        // GML ids need not match the node indices
+        let node_ids: Vec<u32> = (0..40).map(|i| 100 + i).collect();
+        let explicit = MinerPlacement::Explicit {
+            nodes: BTreeMap::from([(0, 139), (1, 100)]),
+        };
+        assert_eq!(
+            place_miners(&explicit, 2, &asns, &node_ids).unwrap(),
+            [39, 0]
+        );
+        assert!(place_miners(&explicit, 3, &asns, &node_ids).is_err());
+        let unknown = MinerPlacement::Explicit {
+            nodes: BTreeMap::from([(0, 7)]),
+        };
+        assert!(place_miners(&unknown, 1, &asns, &node_ids).is_err());
+    }
+
+    #[test]
+    fn miners_are_placed_and_others_distributed_normally() {
+        let asns = as_numbers(1200, 100);
+        let is_miner = [true, false, true, false, false, true, false];
+        let others = is_miner.iter().filter(|&&m| !m).count();
+        let placement = MinerPlacement::Colocated {
+            as_number: "5".to_string(),
+        };
+        let result = distribute_agents_across_topology(
+            Some(Path::new("topology.gml")),
+            is_miner.len(),
+            &asns,
+            None,
+            DistributionWeights::default(),
+            Some(MinerConstraint {
+                placement: &placement,
+                is_miner: &is_miner,
+                node_ids: &[],
+            }),
+            None,
+        )
+        .unwrap();
+        let (miners, regular): (Vec<_>, Vec<_>) =
+            result.iter().zip(is_miner).partition(|(_, m)| *m);
+        assert!(miners
+            .iter()
+            .all(|(n, _)| asns[n.unwrap()].as_deref() == Some("5")));
+        let regular: Vec<Option<usize>> = regular.into_iter().map(|(n, _)| *n).collect();
+        assert_eq!(regular, distribute_global(others, 1200));
+    }
+
+    #[test]
+    fn pinned_agents_are_placed_first_and_others_distributed_normally() {
+        let asns = as_numbers(1200, 1);
+        let node_ids: Vec<u32> = (0..1200).collect();
+        let (by_node, by_as, by_region) = (
+            AgentPlacement::NodeId(42),
+            AgentPlacement::As("7".to_string()),
+            AgentPlacement::Region("oceania".to_string()),
+        );
+        let placements = [
+            None,
+            Some(("spy", &by_as)),
+            Some(("victim", &by_node)),
+            None,
+            Some(("kiwi", &by_region)),
+            None,
+        ];
+        let result = distribute_agents_across_topology(
+            Some(Path::new("topology.gml")),
+            placements.len(),
+            &asns,
+            None,
+            DistributionWeights::default(),
+            None,
+            Some(PinConstraint {
+                placements: &placements,
+                node_ids: &node_ids,
+            }),
+        )
+        .unwrap();
+        assert_eq!(result[1], Some(7));
+        assert_eq!(result[2], Some(42));
+        let (_, start, end) = calculate_region_boundaries(1200)[AsRegion::Oceania.index()];
+        assert!((start..=end).contains(&result[4].unwrap()));
+        assert_eq!(
+            [result[0], result[3], result[5]].to_vec(),
+            distribute_global(3, 1200)
+        );
+    }
+
+    #[test]
+    fn pins_to_missing_nodes_are_rejected() {
+        let asns = as_numbers(10, 1);
+        let node_ids: Vec<u32> = (0..10).collect();
+        let missing = AgentPlacement::NodeId(99);
+        let err = place_pinned(&[Some(("a", &missing))], &asns, &node_ids).unwrap_err();
+        assert!(err.contains("Agent 'a'"), "{}", err);
+    }
+
+    #[test]
+    fn largest_remainder_matches_proportions() {
+        let shares = [0.45, 0.3, 0.15, 0.1];
+        for (total, expected) in [
+            (0, [0, 0, 0, 0]),
+            (1, [1, 0, 0, 0]),
+            (7, [3, 2, 1, 1]),
+            (10, [5, 3, 1, 1]),
+            (20, [9, 6, 3, 2]),
+            (33, [15, 10, 5, 3]),
+            (100, [45, 30, 15, 10]),
+        ] {
+            let counts = largest_remainder(total, &shares);
+            assert_eq!(counts, expected, "{} agents", total);
+            assert_eq!(counts.iter().sum::<usize>(), total);
+        }
+        // Equal remainders go to the earlier share
+        assert_eq!(largest_remainder(2, &[1.0, 1.0, 1.0]), [1, 1, 0]);
+        assert_eq!(largest_remainder(5, &[0.0, 0.0]), [0, 0]);
+    }
+
+    #[test]
+    fn as_weights_replace_the_strategy() {
+        let asns = as_numbers(30, 5);
+        let shares = BTreeMap::from([("1".to_string(), 0.75), ("4".to_string(), 0.25)]);
+        let weights = DistributionWeights {
+            regions: None,
+            as_shares: Some(&shares),
+        };
+        for (agents, in_as1) in [(4, 3), (10, 8), (21, 16)] {
+            let result = distribute_agents_across_topology(
+                Some(Path::new("topology.gml")),
+                agents,
+                &asns,
+                None,
+                weights,
+                None,
+                None,
+            )
+            .unwrap();
+            let count = |asn: &str| {
+                result
+                    .iter()
+                    .filter(|n| asns[n.unwrap()].as_deref() == Some(asn))
+                    .count()
+            };
+            assert_eq!((count("1"), count("4")), (in_as1, agents - in_as1));
+        }
+
+        let missing = BTreeMap::from([("99".to_string(), 1.0)]);
+        let err = distribute_by_as(3, &asns, &missing).unwrap_err();
+        assert!(err.contains("AS 99"), "{}", err);
+    }
 }
@@ -0,0 +1,188 @@
+//! Synthetic GML topologies for `monerosim gen-topology`.
+//!
+//! Nodes are grouped into autonomous systems (a shared `AS` attribute). Each
+//! AS is a random tree of intra-AS links, and each AS links to a random
+//! earlier AS so the whole graph is connected, plus one more random AS for
+//! redundancy. AS numbers are spread over the synthetic 0-1199 range so
+//! AS-aware IP allocation covers every region.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::gml_parser::{GmlEdge, GmlGraph, GmlNode};
+use crate::utils::bandwidth::{format_bandwidth, parse_bandwidth_bps};
+use crate::utils::duration::format_shadow_time;
+
+/// Size of the synthetic AS number space classified by `AsRegion`
+const AS_NUMBER_SPACE: usize = 1200;
+
+/// Parameters for `generate_topology`
+#[derive(Debug, Clone)]
+pub struct TopologyParams {
+    pub as_groups: usize,
+    pub nodes_per_as: usize,
+    /// One-way latency range (ms) of links inside an AS; self-loops use the minimum
+    pub intra_latency_ms: (f64, f64),
+    /// One-way latency range (ms) of links between ASes
+    pub inter_latency_ms: (f64, f64),
+    /// Node bandwidths, e.g. "100Mbit"; each node draws one
+    pub bandwidth_classes: Vec<String>,
+    pub seed: u64,
+}
+
+impl TopologyParams {
+    /// Sizes must be positive, latency ranges ordered and non-negative, and
+    /// bandwidth classes parseable.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.as_groups == 0 || self.nodes_per_as == 0 {
+            return Err("as_groups and nodes_per_as must be at least 1".to_string());
+        }
+        if self.as_groups > AS_NUMBER_SPACE {
+            return Err(format!(
+                "as_groups must be at most {} (the synthetic AS number space)",
+                AS_NUMBER_SPACE
+            ));
+        }
+        for (name, (lo, hi)) in [
+            ("intra-AS", self.intra_latency_ms),
+            ("inter-AS", self.inter_latency_ms),
+        ] {
+            if !(lo >= 0.0 && lo <= hi && hi.is_finite()) {
+                return Err(format!(
+                    "{} latency range {}-{} ms must be non-negative with min <= max",
+                    name, lo, hi
+                ));
+            }
+        }
+        if self.bandwidth_classes.is_empty() {
+            return Err("at least one bandwidth class is required".to_string());
+        }
+        for class in &self.bandwidth_classes {
+            parse_bandwidth_bps(class)?;
+        }
+        Ok(())
+    }
+}
+
+/// Generate a connected topology from `params`. Deterministic for a seed.
+pub fn generate_topology(params: &TopologyParams) -> Result<GmlGraph, String> {
+    params.validate()?;
+    let mut rng = StdRng::seed_from_u64(params.seed);
+    let classes = params
+        .bandwidth_classes
+        .iter()
+        .map(|c| parse_bandwidth_bps(c).map(format_bandwidth))
+        .collect::<Result<Vec<_>, _>>()?;
+    let latency = |ms: f64| format_shadow_time((ms * 1000.0).round().max(1.0) as u64 * 1000);
+    let node_id = |group: usize, k: usize| (group * params.nodes_per_as + k) as u32;
+
+    let mut nodes = Vec::with_capacity(params.as_groups * params.nodes_per_as);
+    for group in 0..params.as_groups {
+        let as_number = group * AS_NUMBER_SPACE / params.as_groups;
+        for k in 0..params.nodes_per_as {
+            let bandwidth = classes[rng.gen_range(0..classes.len())].clone();
+            nodes.push(GmlNode {
+                id: node_id(group, k),
+                label: None,
+                ip: None,
+                region: None,
+                attributes: HashMap::from([
+                    ("AS".to_string(), as_number.to_string()),
+                    ("bandwidth".to_string(), bandwidth),
+                ]),
+            });
+        }
+    }
+
+    let mut edges = Vec::new();
+    let mut link = |source: u32, target: u32, ms: f64| {
+        edges.push(GmlEdge {
+            source,
+            target,
+            attributes: HashMap::from([("latency".to_string(), latency(ms))]),
+        });
+    };
+    let (intra_lo, intra_hi) = params.intra_latency_ms;
+    let (inter_lo, inter_hi) = params.inter_latency_ms;
+
+    for node in &nodes {
+        link(node.id, node.id, intra_lo);
+    }
+    for group in 0..params.as_groups {
+        for k in 1..params.nodes_per_as {
+            let parent = rng.gen_range(0..k);
+            let ms = rng.gen_range(intra_lo..=intra_hi);
+            link(node_id(group, parent), node_id(group, k), ms);
+        }
+    }
+
+    let mut linked = BTreeSet::new();
+    for group in 1..params.as_groups {
+        let mut peers = vec![rng.gen_range(0..group)];
+        if params.as_groups > 2 {
+            let extra = (group + rng.gen_range(1..params.as_groups)) % params.as_groups;
+            peers.push(extra);
+        }
+        for peer in peers {
+            if !linked.insert((group.min(peer), group.max(peer))) {
+                continue;
+            }
+            let a = node_id(group, rng.gen_range(0..params.nodes_per_as));
+            let b = node_id(peer, rng.gen_range(0..params.nodes_per_as));
+            let ms = rng.gen_range(inter_lo..=inter_hi);
+            link(a, b, ms);
+        }
+    }
+
+    Ok(GmlGraph {
+        nodes,
+        edges,
+        attributes: HashMap::from([("directed".to_string(), "0".to_string())]),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> TopologyParams {
+        TopologyParams {
+            as_groups: 4,
+            nodes_per_as: 3,
+            intra_latency_ms: (1.0, 5.0),
+            inter_latency_ms: (20.0, 150.0),
+            bandwidth_classes: vec!["100Mbit".to_string(), "1 Gbit".to_string()],
+            seed: 1,
+        }
+    }
+
+    #[test]
+    fn nodes_share_as_numbers_within_a_group() {
+        let graph = generate_topology(&params()).unwrap();
+        assert_eq!(graph.nodes.len(), 12);
+        let as_of = |id: usize| graph.nodes[id].attributes["AS"].as_str();
+        assert_eq!(
+            [as_of(0), as_of(2), as_of(3), as_of(11)],
+            ["0", "0", "300", "900"]
+        );
+        assert!(graph
+            .nodes
+            .iter()
+            .all(|n| ["100 Mbit", "1 Gbit"].contains(&n.attributes["bandwidth"].as_str())));
+    }
+
+    #[test]
+    fn invalid_params_are_rejected() {
+        let with = |f: fn(&mut TopologyParams)| {
+            let mut p = params();
+            f(&mut p);
+            generate_topology(&p)
+        };
+        assert!(with(|p| p.as_groups = 0).is_err());
+        assert!(with(|p| p.intra_latency_ms = (5.0, 1.0)).is_err());
+        assert!(with(|p| p.inter_latency_ms = (-1.0, 10.0)).is_err());
+        assert!(with(|p| p.bandwidth_classes.clear()).is_err());
+        assert!(with(|p| p.bandwidth_classes = vec!["1G".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+//! `intended_topology.json`: the peer connections injected into each daemon.
+//!
+//! Built from every daemon's final argv, so `daemon_args` and phase args
+//! are reflected as well as the generated wiring. `tx-analyzer network-graph
+//! --compare-intended` diffs it against the connections seen in the logs.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use color_eyre::eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::options::flag_name;
+
+/// File name of the intended topology in the shared directory
+pub const INTENDED_TOPOLOGY_FILE: &str = "intended_topology.json";
+
+/// monerod flag a connection was injected with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionType {
+    /// `--seed-node`: bootstrap only
+    Seed,
+    /// `--add-priority-node`: kept connected
+    Priority,
+    /// `--add-exclusive-node`: kept connected, and nothing else
+    Exclusive,
+}
+
+impl ConnectionType {
+    fn from_flag(name: &str) -> Option<Self> {
+        match name {
+            "seed-node" => Some(ConnectionType::Seed),
+            "add-priority-node" => Some(ConnectionType::Priority),
+            "add-exclusive-node" => Some(ConnectionType::Exclusive),
+            _ => None,
+        }
+    }
+}
+
+/// One peer a daemon was told to connect to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntendedConnection {
+    /// Agent at `address`; absent for addresses no agent owns (external
+    /// `seed_nodes`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// `IP:PORT` as passed to monerod
+    pub address: String,
+    #[serde(rename = "type")]
+    pub connection_type: ConnectionType,
+}
+
+/// A daemon and the connections injected into it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntendedNode {
+    pub ip: String,
+    pub connections: Vec<IntendedConnection>,
+}
+
+/// Agent id -> its intended connections
+pub type IntendedTopology = BTreeMap<String, IntendedNode>;
+
+/// Peer connections in a daemon argv, as `--flag=IP:PORT` or `--flag IP:PORT`
+pub fn peer_connections(args: &[String]) -> Vec<(ConnectionType, String)> {
+    let mut connections = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let Some(connection_type) = flag_name(arg).and_then(ConnectionType::from_flag) else {
+            continue;
+        };
+        let address = match arg.split_once('=') {
+            Some((_, address)) => Some(address.to_string()),
+            None => iter.next().cloned(),
+        };
+        connections.extend(address.map(|a| (connection_type, a)));
+    }
+    connections
+}
+
+/// Build the intended topology from each daemon's final argv.
+/// `agent_ips` maps agent id to IP and resolves connection targets.
+pub fn build_intended_topology(
+    daemon_args: &BTreeMap<String, Vec<String>>,
+    agent_ips: &BTreeMap<String, String>,
+) -> IntendedTopology {
+    let owner: BTreeMap<&str, &str> = agent_ips
+        .iter()
+        .map(|(id, ip)| (ip.as_str(), id.as_str()))
+        .collect();
+    daemon_args
+        .iter()
+        .filter_map(|(id, args)| {
+            let ip = agent_ips.get(id)?;
+            let connections = peer_connections(args)
+                .into_iter()
+                .map(|(connection_type, address)| IntendedConnection {
+                    target: address
+                        .rsplit_once(':')
+                        .and_then(|(host, _)| owner.get(host))
+                        .map(|id| id.to_string()),
+                    address,
+                    connection_type,
+                })
+                .collect();
+            Some((
+                id.clone(),
+                IntendedNode {
+                    ip: ip.clone(),
+                    connections,
+                },
+            ))
+        })
+        .collect()
+}
+
+pub fn load_intended_topology(path: &Path) -> Result<IntendedTopology> {
+    let content = fs::read_to_string(path)
+        .wrap_err_with(|| format!("Failed to read intended topology {}", path.display()))?;
+    serde_json::from_str(&content)
+        .wrap_err_with(|| format!("Failed to parse intended topology {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn reads_every_connection_flag_form() {
+        let args = strings(&[
+            "--p2p-bind-ip=10.0.0.1",
+            "--seed-node=10.0.0.2:18080",
+            "--add-priority-node",
+            "10.0.0.3:18080",
+            "--add-exclusive-node=192.168.9.9:18080",
+            "--out-peers=8",
+        ]);
+        assert_eq!(
+            peer_connections(&args),
+            [
+                (ConnectionType::Seed, "10.0.0.2:18080".to_string()),
+                (ConnectionType::Priority, "10.0.0.3:18080".to_string()),
+                (ConnectionType::Exclusive, "192.168.9.9:18080".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_targets_and_skips_wallet_only_agents() {
+        let agent_ips: BTreeMap<String, String> =
+            [("a", "10.0.0.1"), ("b", "10.0.0.2"), ("w", "10.0.0.3")]
+                .iter()
+                .map(|(id, ip)| (id.to_string(), ip.to_string()))
+                .collect();
+        let daemon_args: BTreeMap<String, Vec<String>> = [
+            (
+                "a",
+                strings(&[
+                    "--add-priority-node=10.0.0.2:18080",
+                    "--seed-node=1.2.3.4:18080",
+                ]),
+            ),
+            ("b", strings(&["--add-priority-node=10.0.0.1:18080"])),
+        ]
+        .into_iter()
+        .map(|(id, args)| (id.to_string(), args))
+        .collect();
+
+        let topology = build_intended_topology(&daemon_args, &agent_ips);
+        assert_eq!(topology.len(), 2, "w runs no daemon");
+        assert_eq!(topology["a"].ip, "10.0.0.1");
+        assert_eq!(
+            topology["a"].connections,
+            [
+                IntendedConnection {
+                    target: Some("b".to_string()),
+                    address: "10.0.0.2:18080".to_string(),
+                    connection_type: ConnectionType::Priority,
+                },
+                IntendedConnection {
+                    target: None,
+                    address: "1.2.3.4:18080".to_string(),
+                    connection_type: ConnectionType::Seed,
+                },
+            ]
+        );
+
+        let json = serde_json::to_string(&topology).unwrap();
+        assert!(json.contains(r#""type":"priority""#), "{}", json);
+        assert_eq!(
+            serde_json::from_str::<IntendedTopology>(&json).unwrap(),
+            topology
+        );
+    }
+}
@@ -0,0 +1,295 @@
+//! `latency_matrix.json`: the least time a message between two agents can
+//! take.
+//!
+//! Shadow routes every packet along the lowest-latency path through the GML
+//! graph, so the shortest-path latency between two agents' nodes is a lower
+//! bound on any relay between them (hosts on one node talk over its
+//! self-loop). `tx-analyzer propagation --latency-matrix` subtracts it from
+//! the observed relay delays.
+//!
+//! Distances are kept as one flat `f32` row per source node, so a few
+//! thousand nodes stay in the tens of megabytes.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use color_eyre::eyre::{Result, WrapErr};
+
+use crate::gml_parser::GmlGraph;
+use crate::utils::duration::parse_shadow_time_ns;
+
+/// File name of the agent latency matrix in the output directory
+pub const LATENCY_MATRIX_FILE: &str = "latency_matrix.json";
+
+/// Agent id -> agent id -> shortest-path latency in ms
+pub type AgentLatencyMatrix = BTreeMap<String, BTreeMap<String, f64>>;
+
+/// Shortest-path latency from a set of source nodes to every GML node
+#[derive(Debug, Clone)]
+pub struct NodeLatencies {
+    /// Node index by GML node id
+    index: HashMap<u32, usize>,
+    /// Row of `ms` by source node index
+    rows: HashMap<usize, usize>,
+    /// `rows.len()` rows of one column per node; infinite when unreachable
+    ms: Vec<f32>,
+    /// Self-loop latency by node index: what hosts sharing the node see
+    self_loop: Vec<Option<f32>>,
+}
+
+/// The GML graph as adjacency lists of `(node index, latency ns)`, with
+/// self-loops kept apart
+pub(crate) struct LatencyAdjacency {
+    /// Node index by GML node id
+    pub index: HashMap<u32, usize>,
+    pub adjacency: Vec<Vec<(usize, u64)>>,
+    /// Lowest self-loop latency by node index
+    pub self_loop: Vec<Option<f32>>,
+}
+
+impl LatencyAdjacency {
+    /// Edges run both ways unless the graph is `directed 1`; every edge
+    /// needs a `latency`
+    pub fn new(graph: &GmlGraph) -> Result<Self, String> {
+        let n = graph.nodes.len();
+        let index: HashMap<u32, usize> = graph
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.id, i))
+            .collect();
+        let directed = graph
+            .attributes
+            .get("directed")
+            .is_some_and(|d| d.trim() == "1");
+
+        let mut adjacency: Vec<Vec<(usize, u64)>> = vec![Vec::new(); n];
+        let mut self_loop: Vec<Option<f32>> = vec![None; n];
+        for edge in &graph.edges {
+            let latency = edge
+                .attributes
+                .get("latency")
+                .ok_or_else(|| format!("edge {} -> {} has no latency", edge.source, edge.target))?;
+            let ns = parse_shadow_time_ns(latency)
+                .map_err(|e| format!("edge {} -> {}: {}", edge.source, edge.target, e))?;
+            let node = |id: u32| {
+                index
+                    .get(&id)
+                    .copied()
+                    .ok_or_else(|| format!("edge references unknown node {}", id))
+            };
+            let (a, b) = (node(edge.source)?, node(edge.target)?);
+            if a == b {
+                let ms = (ns as f64 / 1e6) as f32;
+                self_loop[a] = Some(self_loop[a].map_or(ms, |m| m.min(ms)));
+                continue;
+            }
+            adjacency[a].push((b, ns));
+            if !directed {
+                adjacency[b].push((a, ns));
+            }
+        }
+        Ok(Self {
+            index,
+            adjacency,
+            self_loop,
+        })
+    }
+}
+
+impl NodeLatencies {
+    /// Every node to every node
+    pub fn all_pairs(graph: &GmlGraph) -> Result<Self, String> {
+        let ids: Vec<u32> = graph.nodes.iter().map(|n| n.id).collect();
+        Self::from_sources(graph, &ids)
+    }
+
+    /// One Dijkstra run per distinct node in `sources` (GML node ids) over
+    /// the edges' `latency`; edges run both ways unless the graph is
+    /// `directed 1`
+    pub fn from_sources(graph: &GmlGraph, sources: &[u32]) -> Result<Self, String> {
+        let n = graph.nodes.len();
+        let LatencyAdjacency {
+            index,
+            adjacency,
+            self_loop,
+        } = LatencyAdjacency::new(graph)?;
+
+        let distinct: BTreeSet<usize> = sources
+            .iter()
+            .map(|id| {
+                index
+                    .get(id)
+                    .copied()
+                    .ok_or_else(|| format!("no GML node {}", id))
+            })
+            .collect::<Result<_, _>>()?;
+        let mut rows = HashMap::with_capacity(distinct.len());
+        let mut ms = Vec::with_capacity(distinct.len() * n);
+        let mut dist = vec![u64::MAX; n];
+        for source in distinct {
+            dist.fill(u64::MAX);
+            dist[source] = 0;
+            let mut heap = BinaryHeap::from([Reverse((0u64, source))]);
+            while let Some(Reverse((d, node))) = heap.pop() {
+                if d > dist[node] {
+                    continue;
+                }
+                for &(next, w) in &adjacency[node] {
+                    let nd = d.saturating_add(w);
+                    if nd < dist[next] {
+                        dist[next] = nd;
+                        heap.push(Reverse((nd, next)));
+                    }
+                }
+            }
+            rows.insert(source, rows.len());
+            ms.extend(dist.iter().map(|&d| match d {
+                u64::MAX => f32::INFINITY,
+                d => (d as f64 / 1e6) as f32,
+            }));
+        }
+
+        Ok(Self {
+            index,
+            rows,
+            ms,
+            self_loop,
+        })
+    }
+
+    /// Latency in ms from node `from` (one of the sources) to node `to`,
+    /// by GML node id; None when either is unknown or `to` is unreachable.
+    /// Within one node this is the self-loop latency, else zero.
+    pub fn get(&self, from: u32, to: u32) -> Option<f32> {
+        let (a, b) = (*self.index.get(&from)?, *self.index.get(&to)?);
+        if a == b {
+            return Some(self.self_loop[a].unwrap_or(0.0));
+        }
+        let row = *self.rows.get(&a)?;
+        let ms = self.ms[row * self.self_loop.len() + b];
+        ms.is_finite().then_some(ms)
+    }
+}
+
+/// Pairwise latency between agents placed on GML nodes (agent id -> node
+/// id), rounded to the microsecond. Pairs with no path are left out.
+pub fn agent_latency_matrix(
+    graph: &GmlGraph,
+    placements: &BTreeMap<String, u32>,
+) -> Result<AgentLatencyMatrix, String> {
+    let nodes: Vec<u32> = placements.values().copied().collect();
+    let latencies = NodeLatencies::from_sources(graph, &nodes)?;
+    Ok(placements
+        .iter()
+        .map(|(from, &from_node)| {
+            let row = placements
+                .iter()
+                .filter(|(to, _)| *to != from)
+                .filter_map(|(to, &to_node)| {
+                    let ms = latencies.get(from_node, to_node)?;
+                    Some((to.clone(), (ms as f64 * 1000.0).round() / 1000.0))
+                })
+                .collect();
+            (from.clone(), row)
+        })
+        .collect())
+}
+
+/// Read a `latency_matrix.json` written during generation
+pub fn load_latency_matrix(path: &Path) -> Result<AgentLatencyMatrix> {
+    let content = fs::read_to_string(path)
+        .wrap_err_with(|| format!("Failed to read latency matrix {}", path.display()))?;
+    serde_json::from_str(&content)
+        .wrap_err_with(|| format!("Failed to parse latency matrix {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gml_parser::{GmlEdge, GmlNode};
+
+    fn graph(edges: &[(u32, u32, &str)], directed: bool) -> GmlGraph {
+        let mut ids: Vec<u32> = edges.iter().flat_map(|(a, b, _)| [*a, *b]).collect();
+        ids.sort();
+        ids.dedup();
+        GmlGraph {
+            nodes: ids
+                .into_iter()
+                .map(|id| GmlNode {
+                    id,
+                    label: None,
+                    ip: None,
+                    region: None,
+                    attributes: HashMap::new(),
+                })
+                .collect(),
+            edges: edges
+                .iter()
+                .map(|(source, target, latency)| GmlEdge {
+                    source: *source,
+                    target: *target,
+                    attributes: HashMap::from([("latency".to_string(), latency.to_string())]),
+                })
+                .collect(),
+            attributes: HashMap::from([(
+                "directed".to_string(),
+                if directed { "1" } else { "0" }.to_string(),
+            )]),
+        }
+    }
+
+    #[test]
+    fn shortest_paths_follow_the_cheapest_route() {
+        // 1 -> 3 directly costs 50 ms, via 2 only 30 ms
+        let g = graph(
+            &[
+                (1, 2, "10 ms"),
+                (2, 3, "20 ms"),
+                (1, 3, "50 ms"),
+                (3, 3, "1500 us"),
+                (4, 4, "1 ms"),
+            ],
+            false,
+        );
+        let all = NodeLatencies::all_pairs(&g).unwrap();
+        assert_eq!(all.get(1, 3), Some(30.0));
+        assert_eq!(all.get(3, 1), Some(30.0));
+        assert_eq!(all.get(3, 3), Some(1.5));
+        assert_eq!(all.get(2, 2), Some(0.0));
+        assert_eq!(all.get(1, 4), None, "4 is only connected to itself");
+        assert_eq!(all.get(1, 99), None);
+
+        let one = NodeLatencies::from_sources(&g, &[2, 2]).unwrap();
+        assert_eq!(one.get(2, 1), Some(10.0));
+        assert_eq!(one.get(1, 2), None, "1 is not a source");
+    }
+
+    #[test]
+    fn directed_graphs_route_one_way() {
+        let g = graph(&[(1, 2, "10 ms"), (2, 1, "40 ms")], true);
+        let all = NodeLatencies::all_pairs(&g).unwrap();
+        assert_eq!(all.get(1, 2), Some(10.0));
+        assert_eq!(all.get(2, 1), Some(40.0));
+    }
+
+    #[test]
+    fn agents_get_their_nodes_latencies() {
+        let g = graph(&[(0, 1, "25 ms"), (1, 1, "2 ms")], false);
+        let placements: BTreeMap<String, u32> = [("a", 0), ("b", 1), ("c", 1)]
+            .iter()
+            .map(|(id, node)| (id.to_string(), *node))
+            .collect();
+        let matrix = agent_latency_matrix(&g, &placements).unwrap();
+        assert_eq!(matrix["a"]["b"], 25.0);
+        assert_eq!(matrix["c"]["a"], 25.0);
+        assert_eq!(matrix["b"]["c"], 2.0);
+        assert!(!matrix["a"].contains_key("a"));
+
+        let mut no_latency = g.clone();
+        no_latency.edges[0].attributes.clear();
+        assert!(agent_latency_matrix(&no_latency, &placements).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+//! Generated graph for a switch network with a `link_profile`.
+//!
+//! Shadow's built-in switch graph has no latency or loss knobs, so a switch
+//! with a link profile is replaced by a complete graph of one node per
+//! continent (in `AsRegion` order). Each node's `AS` is the first AS number of
+//! its region, so AS-aware IP allocation still gives region-appropriate
+//! addresses.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+
+use crate::config::LinkProfile;
+use crate::gml_parser::{GmlEdge, GmlGraph, GmlNode};
+use crate::ip::as_manager::AsRegion;
+use crate::utils::duration::format_shadow_time;
+
+/// First AS number of each region, as classified by `AsRegion::from_as_number`
+const REGION_AS_NUMBERS: [u32; 6] = [0, 200, 500, 800, 1000, 1100];
+
+/// Build the continent graph for `profile`. Self-loops carry
+/// `local_latency_ms`; inter-continent edges carry `latency_ms` offset by a
+/// jitter drawn from `seed`. Every edge carries the packet loss as a 0-1
+/// fraction. Latencies are whole microseconds.
+pub fn link_profile_graph(profile: &LinkProfile, seed: u64) -> GmlGraph {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let loss = format!("{}", profile.packet_loss / 100.0);
+    let latency = |ms: f64| format_shadow_time((ms * 1000.0).round().max(1.0) as u64 * 1000);
+
+    let nodes = AsRegion::all()
+        .iter()
+        .zip(REGION_AS_NUMBERS)
+        .enumerate()
+        .map(|(id, (region, as_number))| GmlNode {
+            id: id as u32,
+            label: Some(region.name().to_string()),
+            ip: None,
+            region: Some(region.name().to_string()),
+            attributes: HashMap::from([("AS".to_string(), as_number.to_string())]),
+        })
+        .collect::<Vec<_>>();
+
+    let mut edges = Vec::new();
+    for source in 0..nodes.len() as u32 {
+        for target in source..nodes.len() as u32 {
+            let ms = if source == target {
+                profile.local_latency_ms
+            } else if profile.jitter_ms > 0.0 {
+                profile.latency_ms + rng.gen_range(-profile.jitter_ms..=profile.jitter_ms)
+            } else {
+                profile.latency_ms
+            };
+            edges.push(GmlEdge {
+                source,
+                target,
+                attributes: HashMap::from([
+                    ("latency".to_string(), latency(ms)),
+                    ("packet_loss".to_string(), loss.clone()),
+                ]),
+            });
+        }
+    }
+
+    GmlGraph {
+        nodes,
+        edges,
+        attributes: HashMap::from([("directed".to_string(), "0".to_string())]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn profile(latency_ms: f64, jitter_ms: f64, packet_loss: f64) -> LinkProfile {
+        LinkProfile {
+            latency_ms,
+            jitter_ms,
+            packet_loss,
+            ..LinkProfile::default()
+        }
+    }
+
+    #[test]
+    fn edges_carry_the_profile() {
+        let graph = link_profile_graph(&profile(80.0, 0.0, 2.5), 7);
+        assert_eq!(graph.nodes.len(), 6);
+        // Complete graph with self-loops
+        assert_eq!(graph.edges.len(), 21);
+        for edge in &graph.edges {
+            let expected = if edge.source == edge.target {
+                "1 ms"
+            } else {
+                "80 ms"
+            };
+            assert_eq!(edge.attributes["latency"], expected);
+            assert_eq!(edge.attributes["packet_loss"], "0.025");
+        }
+        assert_eq!(graph.nodes[1].attributes["AS"], "200");
+        assert_eq!(graph.nodes[5].label.as_deref(), Some("Oceania"));
+    }
+
+    #[test]
+    fn jitter_is_bounded_and_seeded() {
+        let latencies = |seed| {
+            link_profile_graph(&profile(50.0, 10.0, 0.0), seed)
+                .edges
+                .iter()
+                .filter(|e| e.source != e.target)
+                .map(|e| {
+                    crate::utils::parse_shadow_time_ns(&e.attributes["latency"]).unwrap() / 1000
+                })
+                .collect::<Vec<_>>()
+        };
+        let first = latencies(1);
+        assert!(first.iter().all(|us| (40_000..=60_000).contains(us)));
+        assert!(first.iter().any(|&us| us != 50_000));
+        assert_eq!(first, latencies(1));
+        assert_ne!(first, latencies(2));
+    }
+
+    #[test]
+    fn invalid_profiles_are_rejected() {
+        let config = |link_profile: &str| {
+            let yaml = format!(
+                "general:\n  stop_time: 1h\nnetwork:\n  type: 1_gbit_switch\n  \
+                 link_profile: {}\nagents: {{}}\n",
+                link_profile
+            );
+            serde_yaml::from_str::<Config>(&yaml).unwrap().validate()
+        };
+        assert!(config("{latency_ms: 30, jitter_ms: 5, packet_loss: 1.5}").is_ok());
+        assert!(config("{packet_loss: 101}").is_err());
+        assert!(config("{packet_loss: -1}").is_err());
+        assert!(config("{latency_ms: -5}").is_err());
+        assert!(config("{latency_ms: 10, jitter_ms: 20}").is_err());
+    }
+}
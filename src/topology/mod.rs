@@ -5,10 +5,32 @@
 
 pub mod connections;
 pub mod distribution;
+pub mod generator;
+pub mod intended;
+pub mod latency;
+pub mod link_profile;
+pub mod partition;
 pub mod peer_connections;
+pub mod prune;
+pub mod seeds;
+pub mod spy;
 pub mod types;
 
-pub use connections::generate_topology_connections;
-pub use distribution::distribute_agents_across_topology;
-pub use peer_connections::{build_peer_topology, AgentEntry, PeerTopology};
+pub use connections::{
+    generate_topology_connections, select_hybrid_peers, small_world_adjacency, PeerAssignment,
+    PeerSource, TopologyConnections,
+};
+pub use distribution::{
+    distribute_agents_across_topology, largest_remainder, DistributionKey, DistributionWeights,
+    MinerConstraint, MinerLocation, PinConstraint, RealizedDistribution,
+};
+pub use generator::{generate_topology, TopologyParams};
+pub use link_profile::link_profile_graph;
+pub use partition::{
+    partition_windows, schedule_network_events, PartitionWindow, ScheduledNetworkEvent,
+};
+pub use peer_connections::{build_peer_topology, AgentEntry, PeerTopology, PeerTopologyContext};
+pub use prune::prune_to_nodes;
+pub use seeds::{select_seeds, SeedCandidate, SeedChoice, SeedReason, DEFAULT_SEED_COUNT};
+pub use spy::SpyTargets;
 pub use types::Topology;
@@ -0,0 +1,287 @@
+//! Scheduled network partitions (`network.events`).
+//!
+//! A partition splits the daemons into groups that stop talking to each
+//! other until the next heal. Shadow cannot cut links mid-run, so the split
+//! is enforced by the daemons themselves: at the partition every grouped
+//! daemon bans the other groups' IPs through monerod's `set_bans` RPC (which
+//! also drops the open connections), and lifts the bans at the heal (see
+//! `process::partition`). Agents outside every group are left alone and keep
+//! bridging the groups, so a clean split names every daemon.
+//!
+//! The resolved schedule is recorded in the manifest, where
+//! `tx-analyzer resilience` reads it back.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{NetworkEvent, NetworkEventAction};
+
+/// A `network.events` entry with its time in seconds and its groups
+/// resolved to agent ids
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledNetworkEvent {
+    /// Simulated second the event fires
+    pub at: u64,
+    pub action: NetworkEventAction,
+    /// Daemon agent ids per side of a partition, sorted; empty for a heal
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<Vec<String>>,
+}
+
+/// A partition and the heal that ends it, if any
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartitionWindow<'a> {
+    pub partition: &'a ScheduledNetworkEvent,
+    pub heal_at: Option<u64>,
+}
+
+/// Pair every partition in `schedule` with the heal that follows it
+pub fn partition_windows(schedule: &[ScheduledNetworkEvent]) -> Vec<PartitionWindow<'_>> {
+    schedule
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| e.action == NetworkEventAction::Partition)
+        .map(|(i, partition)| PartitionWindow {
+            partition,
+            heal_at: schedule
+                .get(i + 1)
+                .filter(|e| e.action == NetworkEventAction::Heal)
+                .map(|e| e.at),
+        })
+        .collect()
+}
+
+/// Resolve `events` against the daemon agents `daemon_ids`. Events must be
+/// in strictly increasing time order before `stop_secs`, alternate between
+/// partition and heal starting with a partition, and split the daemons into
+/// at least two non-empty, disjoint groups.
+pub fn schedule_network_events(
+    events: &[NetworkEvent],
+    daemon_ids: &[&str],
+    stop_secs: u64,
+) -> Result<Vec<ScheduledNetworkEvent>, String> {
+    let mut schedule: Vec<ScheduledNetworkEvent> = Vec::with_capacity(events.len());
+    for (i, event) in events.iter().enumerate() {
+        let context = format!("events[{}]", i);
+        let at = event.at.as_secs();
+        if at >= stop_secs {
+            return Err(format!(
+                "{}: at {}s is not before stop_time ({}s)",
+                context, at, stop_secs
+            ));
+        }
+        if let Some(previous) = schedule.last() {
+            if at <= previous.at {
+                return Err(format!(
+                    "{}: at {}s must come after the previous event ({}s)",
+                    context, at, previous.at
+                ));
+            }
+        }
+        let partitioned = schedule
+            .last()
+            .is_some_and(|e| e.action == NetworkEventAction::Partition);
+        let groups = match event.action {
+            NetworkEventAction::Heal => {
+                if !partitioned {
+                    return Err(format!("{}: heal without a partition in force", context));
+                }
+                if !event.groups.is_empty() {
+                    return Err(format!("{}: a heal takes no groups", context));
+                }
+                Vec::new()
+            }
+            NetworkEventAction::Partition => {
+                if partitioned {
+                    return Err(format!(
+                        "{}: partition while another is in force; heal it first",
+                        context
+                    ));
+                }
+                resolve_groups(&event.groups, daemon_ids)
+                    .map_err(|e| format!("{}: {}", context, e))?
+            }
+        };
+        schedule.push(ScheduledNetworkEvent {
+            at,
+            action: event.action,
+            groups,
+        });
+    }
+    Ok(schedule)
+}
+
+/// Resolve each group's selectors to sorted agent ids
+fn resolve_groups(groups: &[Vec<String>], daemon_ids: &[&str]) -> Result<Vec<Vec<String>>, String> {
+    if groups.len() < 2 {
+        return Err(format!(
+            "a partition needs at least two groups, got {}",
+            groups.len()
+        ));
+    }
+    let mut owner: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut resolved = Vec::with_capacity(groups.len());
+    for (g, selectors) in groups.iter().enumerate() {
+        let mut members: BTreeSet<&str> = BTreeSet::new();
+        for selector in selectors {
+            members.extend(select(selector, daemon_ids)?);
+        }
+        if members.is_empty() {
+            return Err(format!("group {} matches no daemon agent", g));
+        }
+        for id in &members {
+            if let Some(other) = owner.insert(id, g) {
+                return Err(format!("agent '{}' is in groups {} and {}", id, other, g));
+            }
+        }
+        resolved.push(members.into_iter().map(str::to_string).collect());
+    }
+    Ok(resolved)
+}
+
+/// Agents one selector names: an id, or an inclusive range `a..b` over ids
+/// sharing the bounds' prefix, compared by their numeric suffix. Either
+/// bound may be left out.
+fn select<'a>(selector: &str, daemon_ids: &[&'a str]) -> Result<Vec<&'a str>, String> {
+    let Some((lo, hi)) = selector.split_once("..") else {
+        return daemon_ids
+            .iter()
+            .find(|id| **id == selector)
+            .map(|id| vec![*id])
+            .ok_or_else(|| format!("'{}' is not a daemon agent", selector));
+    };
+    let (lo, hi) = (bound(lo, selector)?, bound(hi, selector)?);
+    let prefix = match (lo, hi) {
+        (Some((a, _)), Some((b, _))) if a != b => {
+            return Err(format!(
+                "range '{}' mixes prefixes '{}' and '{}'",
+                selector, a, b
+            ))
+        }
+        (Some((p, _)), _) | (None, Some((p, _))) => p,
+        (None, None) => return Err(format!("range '{}' needs at least one bound", selector)),
+    };
+    let (min, max) = (lo.map_or(0, |(_, n)| n), hi.map_or(u64::MAX, |(_, n)| n));
+    Ok(daemon_ids
+        .iter()
+        .copied()
+        .filter(|id| {
+            split_numeric(id).is_some_and(|(p, n)| p == prefix && (min..=max).contains(&n))
+        })
+        .collect())
+}
+
+/// A range bound split by `split_numeric`; None when left out
+fn bound<'s>(b: &'s str, selector: &str) -> Result<Option<(&'s str, u64)>, String> {
+    if b.is_empty() {
+        return Ok(None);
+    }
+    split_numeric(b)
+        .map(Some)
+        .ok_or_else(|| format!("range bound '{}' in '{}' must end in a number", b, selector))
+}
+
+/// `user010` -> (`user`, 10); None without a numeric suffix
+fn split_numeric(id: &str) -> Option<(&str, u64)> {
+    let digits = id.len() - id.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return None;
+    }
+    let (prefix, number) = id.split_at(id.len() - digits);
+    Some((prefix, number.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(at: &str, action: NetworkEventAction, groups: &[&[&str]]) -> NetworkEvent {
+        NetworkEvent {
+            at: at.parse().unwrap(),
+            action,
+            groups: groups
+                .iter()
+                .map(|g| g.iter().map(|s| s.to_string()).collect())
+                .collect(),
+        }
+    }
+
+    const IDS: [&str; 6] = [
+        "miner001", "user000", "user001", "user009", "user010", "user011",
+    ];
+
+    #[test]
+    fn ranges_select_by_prefix_and_number() {
+        let schedule = schedule_network_events(
+            &[
+                event(
+                    "30m",
+                    NetworkEventAction::Partition,
+                    &[&["miner001", "user000..user009"], &["user010.."]],
+                ),
+                event("50m", NetworkEventAction::Heal, &[]),
+            ],
+            &IDS,
+            3600,
+        )
+        .unwrap();
+        assert_eq!(schedule[0].at, 1800);
+        assert_eq!(
+            schedule[0].groups,
+            vec![
+                vec!["miner001", "user000", "user001", "user009"],
+                vec!["user010", "user011"]
+            ]
+        );
+        assert!(schedule[1].groups.is_empty());
+
+        let windows = partition_windows(&schedule);
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].heal_at, Some(3000));
+    }
+
+    #[test]
+    fn rejects_bad_schedules() {
+        let split: &[&[&str]] = &[&["user000..user001"], &["user010.."]];
+        let err =
+            |events: &[NetworkEvent]| schedule_network_events(events, &IDS, 3600).unwrap_err();
+
+        assert!(err(&[event("10m", NetworkEventAction::Heal, &[])]).contains("without a partition"));
+        assert!(err(&[
+            event("10m", NetworkEventAction::Partition, split),
+            event("5m", NetworkEventAction::Heal, &[]),
+        ])
+        .contains("after the previous event"));
+        assert!(err(&[
+            event("10m", NetworkEventAction::Partition, split),
+            event("20m", NetworkEventAction::Partition, split),
+        ])
+        .contains("heal it first"));
+        assert!(err(&[event("2h", NetworkEventAction::Partition, split)]).contains("stop_time"));
+        assert!(err(&[event(
+            "10m",
+            NetworkEventAction::Partition,
+            &[&["user000.."], &["user010.."]]
+        )])
+        .contains("is in groups 0 and 1"));
+        assert!(err(&[event(
+            "10m",
+            NetworkEventAction::Partition,
+            &[&["user000.."]]
+        )])
+        .contains("at least two groups"));
+        assert!(err(&[event(
+            "10m",
+            NetworkEventAction::Partition,
+            &[&["user000"], &["wallet007"]]
+        )])
+        .contains("not a daemon agent"));
+        assert!(err(&[event(
+            "10m",
+            NetworkEventAction::Partition,
+            &[&["user000"], &["miner001..user002"]]
+        )])
+        .contains("mixes prefixes"));
+    }
+}
@@ -11,10 +11,12 @@
 //! user-agent set to produce per-agent classification + connection maps
 //! consumed by `process_user_agents`.
 
-use crate::config::{AgentConfig, PeerMode};
+use super::seeds::{select_seeds, SeedCandidate, SeedChoice, SeedReason};
+use crate::config::{AgentConfig, NetworkSettings, PeerMode};
 use crate::gml_parser::GmlGraph;
 use crate::ip::{get_agent_ip, AgentType, AsSubnetManager, GlobalIpRegistry};
-use std::collections::HashMap;
+use crate::process::AgentPorts;
+use std::collections::{BTreeMap, HashMap};
 
 /// Classification entry for a single user agent during peer-topology
 /// construction. Carries enough state to drive ring/cross-link
@@ -38,10 +40,13 @@ pub struct PeerTopology {
     pub agent_info: Vec<AgentEntry>,
     /// Subset of `agent_info`: agents flagged `is_miner`.
     pub miners: Vec<AgentEntry>,
-    /// Subset: explicit seed nodes (post-promotion in non-Dynamic modes).
+    /// Subset: agents acting as seed nodes: `is_seed_node` agents, seeds
+    /// that are not miners, and miners promoted to fill the seed set.
     pub seed_nodes: Vec<AgentEntry>,
-    /// Subset: everything else (post-promotion in non-Dynamic modes).
+    /// Subset: everything else except spies.
     pub regular_agents: Vec<AgentEntry>,
+    /// The agents regular agents bootstrap from, and why each was chosen.
+    pub seeds: Vec<SeedChoice>,
     /// `IP:PORT` strings for every user agent — fed to topology
     /// generators downstream.
     pub all_agent_ips: Vec<String>,
@@ -71,8 +76,23 @@ fn build_ring_connections(group: &[AgentEntry], flag: &str) -> HashMap<String, V
     connections
 }
 
-/// Classify user agents, allocate IPs, promote seed nodes (in
-/// Hardcoded/Hybrid modes), and build the initial peer-connection maps.
+/// IP allocation state and per-agent inputs for [`build_peer_topology`],
+/// threaded through from `UserAgentProcessContext`.
+pub struct PeerTopologyContext<'a> {
+    /// GML topology, if any (used by `get_agent_ip`)
+    pub gml_graph: Option<&'a GmlGraph>,
+    /// Whether the GML topology is actually in use
+    pub using_gml_topology: bool,
+    pub subnet_manager: &'a mut AsSubnetManager,
+    pub ip_registry: &'a mut GlobalIpRegistry,
+    /// Receives `IP:PORT` of each seed regular agents bootstrap from
+    pub seed_agents: &'a mut Vec<String>,
+    /// Per-agent ports from `assign_agent_ports`
+    pub agent_ports: &'a BTreeMap<String, AgentPorts>,
+}
+
+/// Classify user agents, allocate IPs, select the seeds per
+/// `network.seed_selection`, and build the initial peer-connection maps.
 ///
 /// Mutates `subnet_manager` / `ip_registry` via `get_agent_ip` and
 /// pushes the seed-source IP list onto `seed_agents`.
@@ -80,58 +100,79 @@ fn build_ring_connections(group: &[AgentEntry], flag: &str) -> HashMap<String, V
 /// # Arguments
 /// * `user_agents` - Input agent slice (id, config) in registry order.
 /// * `agent_node_assignments` - Pre-computed per-agent network-node IDs.
-/// * `peer_mode` - Drives seed-promotion + which group seeds the others.
-/// * `gml_graph` - GML topology, if any (used by `get_agent_ip`).
-/// * `using_gml_topology` - Whether the GML topology is actually in use.
-/// * `subnet_manager` / `ip_registry` - IP allocation state (mutated).
-/// * `seed_agents` - Out-parameter receiving `IP:PORT` of seed source.
+/// * `network` - Peer mode (the `auto` seed policy and the seed-node
+///   ring), seed policy (see [`select_seeds`]), `seed_nodes` for
+///   `explicit` selection, and the flag the miner / seed rings are
+///   emitted with.
+/// * `ctx` - IP allocation state (mutated), seed out-parameter and ports.
 pub fn build_peer_topology(
     user_agents: &[(&String, &AgentConfig)],
     agent_node_assignments: &[u32],
-    peer_mode: &PeerMode,
-    gml_graph: Option<&GmlGraph>,
-    using_gml_topology: bool,
-    subnet_manager: &mut AsSubnetManager,
-    ip_registry: &mut GlobalIpRegistry,
-    seed_agents: &mut Vec<String>,
+    network: &NetworkSettings,
+    ctx: PeerTopologyContext<'_>,
 ) -> color_eyre::eyre::Result<PeerTopology> {
+    let PeerTopologyContext {
+        gml_graph,
+        using_gml_topology,
+        subnet_manager,
+        ip_registry,
+        seed_agents,
+        agent_ports,
+    } = ctx;
+    let peer_mode = &network.peer_mode;
+    let enforcement = network.connection_enforcement;
     let mut agent_info: Vec<AgentEntry> = Vec::new();
     let mut all_agent_ips = Vec::new();
     let mut miners: Vec<AgentEntry> = Vec::new();
     let mut seed_nodes: Vec<AgentEntry> = Vec::new();
     let mut regular_agents: Vec<AgentEntry> = Vec::new();
 
+    // Host IPs first: a co-located agent takes its host's IP, and the host
+    // may come later in the list
+    let mut host_ips: HashMap<&str, String> = HashMap::new();
     for (i, (agent_id, agent_config)) in user_agents.iter().enumerate() {
-        let is_miner = agent_config.is_miner();
-        let is_seed_node = is_miner
-            || agent_config
-                .attributes
-                .as_ref()
-                .map(|attrs| attrs.get("is_seed_node").map_or(false, |v| v == "true"))
-                .unwrap_or(false);
-
+        if agent_config.colocate_with.is_some() {
+            continue;
+        }
         let network_node_id = if i < agent_node_assignments.len() {
             agent_node_assignments[i]
         } else {
             0
         };
 
-        let subnet_group = user_agents
-            .iter()
-            .find(|(id, _)| id.as_str() == *agent_id)
-            .and_then(|(_, config)| config.subnet_group.as_deref());
         let agent_ip = get_agent_ip(
             AgentType::UserAgent,
             agent_id,
-            i,
             network_node_id,
             gml_graph,
             using_gml_topology,
             subnet_manager,
             ip_registry,
-            subnet_group,
+            agent_config.subnet_group.as_deref(),
         )?;
-        let agent_port = crate::MONERO_P2P_PORT;
+        host_ips.insert(agent_id.as_str(), agent_ip);
+    }
+
+    for (i, (agent_id, agent_config)) in user_agents.iter().enumerate() {
+        let is_miner = agent_config.is_miner();
+        let is_seed_node = agent_config.is_seed_node();
+
+        let host_id = agent_config.host_id(agent_id);
+        let agent_ip = host_ips.get(host_id).cloned().ok_or_else(|| {
+            color_eyre::eyre::eyre!(
+                "Agent '{}': colocate_with '{}' runs no daemon or wallet",
+                agent_id,
+                host_id
+            )
+        })?;
+        if host_id != agent_id.as_str() {
+            ip_registry.note_allocation(agent_id, format!("shares host '{}'", host_id));
+        }
+        let agent_port = agent_ports
+            .get(agent_id.as_str())
+            .copied()
+            .unwrap_or_default()
+            .p2p;
 
         all_agent_ips.push(format!("{}:{}", agent_ip, agent_port));
 
@@ -147,7 +188,7 @@ pub fn build_peer_topology(
             miners.push(entry);
         } else if is_seed_node {
             seed_nodes.push(entry);
-        } else {
+        } else if !agent_config.is_spy() {
             regular_agents.push(entry);
         }
 
@@ -160,33 +201,58 @@ pub fn build_peer_topology(
         });
     }
 
-    // Ensure we have exactly 5 seed nodes; if less, promote some regular agents (for Hardcoded/Hybrid modes)
-    if !matches!(peer_mode, PeerMode::Dynamic) {
-        while seed_nodes.len() < 5 {
-            if let Some(mut entry) = regular_agents.pop() {
-                entry.is_seed_node = true;
-                seed_nodes.push(entry);
-            } else if let Some(mut entry) = miners.pop() {
-                entry.is_seed_node = true;
-                seed_nodes.push(entry);
-            } else {
-                break;
-            }
+    // is_unreachable agents accept no inbound and spies keep to their
+    // targets, so neither is picked unless the config names it
+    let candidates: Vec<SeedCandidate> = user_agents
+        .iter()
+        .zip(&all_agent_ips)
+        .map(|((agent_id, agent_config), addr)| SeedCandidate {
+            id: agent_id.as_str(),
+            addr: addr.as_str(),
+            is_miner: agent_config.is_miner(),
+            is_marked: agent_config.is_marked_seed_node(),
+            dialable: agent_config.has_local_daemon()
+                && !agent_config.is_spy()
+                && !agent_config.is_unreachable(),
+        })
+        .collect();
+    let seeds = select_seeds(
+        network.seed_selection,
+        network.seed_count,
+        peer_mode,
+        &candidates,
+        &network.seed_nodes,
+    )
+    .map_err(|e| color_eyre::eyre::eyre!("Seed selection failed: {}", e))?;
+
+    // Seeds that are not miners join the seed nodes; a promoted miner
+    // leaves the miner ring for the seed ring
+    for seed in &seeds {
+        if seed_nodes.iter().any(|e| e.index == seed.index) {
+            continue;
+        }
+        let moved = if let Some(pos) = regular_agents.iter().position(|e| e.index == seed.index) {
+            Some(regular_agents.remove(pos))
+        } else if seed.reason == SeedReason::Promoted {
+            miners
+                .iter()
+                .position(|e| e.index == seed.index)
+                .map(|pos| miners.remove(pos))
+        } else {
+            None
+        };
+        if let Some(mut entry) = moved {
+            entry.is_seed_node = true;
+            seed_nodes.push(entry);
         }
     }
 
-    // Build seed_agents list from actual miner IPs (Dynamic mode) or promoted seed_nodes
-    let seed_source = if matches!(peer_mode, PeerMode::Dynamic) {
-        &miners
-    } else {
-        &seed_nodes
-    };
-    for entry in seed_source {
-        seed_agents.push(format!("{}:{}", entry.ip, entry.port));
+    for seed in &seeds {
+        seed_agents.push(all_agent_ips[seed.index].clone());
     }
 
     // Miners connect in Ring among themselves
-    let miner_connections = build_ring_connections(&miners, "--add-priority-node");
+    let miner_connections = build_ring_connections(&miners, enforcement.flag());
 
     // Seed nodes connect to all miners as persistent priority peers, and
     // (in Hardcoded/Hybrid modes) ring-link to each other.
@@ -199,7 +265,7 @@ pub fn build_peer_topology(
     // before_handshake reconnect loop with that miner.
     let seed_connections = {
         let mut seed_conns = if !matches!(peer_mode, PeerMode::Dynamic) {
-            build_ring_connections(&seed_nodes, "--add-priority-node")
+            build_ring_connections(&seed_nodes, enforcement.flag())
         } else {
             HashMap::new()
         };
@@ -207,7 +273,12 @@ pub fn build_peer_topology(
             let conns = seed_conns.entry(entry.id.clone()).or_default();
             for miner in &miners {
                 if miner.ip != entry.ip {
-                    conns.push(format!("--add-priority-node={}:{}", miner.ip, miner.port));
+                    conns.push(format!(
+                        "{}={}:{}",
+                        enforcement.flag(),
+                        miner.ip,
+                        miner.port
+                    ));
                 }
             }
         }
@@ -219,6 +290,7 @@ pub fn build_peer_topology(
         miners,
         seed_nodes,
         regular_agents,
+        seeds,
         all_agent_ips,
         miner_connections,
         seed_connections,
@@ -0,0 +1,179 @@
+//! `network.prune_unused_nodes`: cut a large GML down to what Shadow needs.
+//!
+//! Shadow routes each packet along the lowest-latency path between two
+//! hosts' nodes, so the only nodes that matter are those carrying hosts and
+//! those on a shortest path between two of them. The pruned graph is the
+//! subgraph induced by those nodes: every path it keeps is one of the
+//! original shortest paths and it adds no new ones, so latencies between
+//! hosts are unchanged. Among equal-latency paths only one is kept.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap};
+
+use rayon::prelude::*;
+
+use super::latency::LatencyAdjacency;
+use crate::gml_parser::GmlGraph;
+
+/// Marker for a node without a predecessor on the shortest-path tree
+const NO_PREDECESSOR: usize = usize::MAX;
+
+/// The subgraph of `graph` induced by the nodes in `keep` (GML node ids)
+/// and the nodes on a shortest path between two of them. Node and edge
+/// order and all attributes are kept. One Dijkstra run per kept node, in
+/// parallel.
+pub fn prune_to_nodes(graph: &GmlGraph, keep: &BTreeSet<u32>) -> Result<GmlGraph, String> {
+    let LatencyAdjacency {
+        index, adjacency, ..
+    } = LatencyAdjacency::new(graph)?;
+    let terminals: Vec<usize> = keep
+        .iter()
+        .map(|id| {
+            index
+                .get(id)
+                .copied()
+                .ok_or_else(|| format!("no GML node {}", id))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let n = graph.nodes.len();
+    let needed = terminals
+        .par_iter()
+        .map(|&source| {
+            let predecessor = shortest_path_tree(&adjacency, source);
+            let mut on_path = vec![false; n];
+            on_path[source] = true;
+            for &target in &terminals {
+                let mut node = target;
+                while node != source && predecessor[node] != NO_PREDECESSOR && !on_path[node] {
+                    on_path[node] = true;
+                    node = predecessor[node];
+                }
+            }
+            on_path
+        })
+        .reduce(
+            || vec![false; n],
+            |mut a, b| {
+                a.iter_mut().zip(b).for_each(|(x, y)| *x |= y);
+                a
+            },
+        );
+
+    let kept = |id: u32| index.get(&id).is_some_and(|&i| needed[i]);
+    Ok(GmlGraph {
+        nodes: graph
+            .nodes
+            .iter()
+            .filter(|node| kept(node.id))
+            .cloned()
+            .collect(),
+        edges: graph
+            .edges
+            .iter()
+            .filter(|edge| kept(edge.source) && kept(edge.target))
+            .cloned()
+            .collect(),
+        attributes: graph.attributes.clone(),
+    })
+}
+
+/// Predecessor of each node on a lowest-latency path from `source`;
+/// `NO_PREDECESSOR` for the source and unreachable nodes
+fn shortest_path_tree(adjacency: &[Vec<(usize, u64)>], source: usize) -> Vec<usize> {
+    let mut dist = vec![u64::MAX; adjacency.len()];
+    let mut predecessor = vec![NO_PREDECESSOR; adjacency.len()];
+    dist[source] = 0;
+    let mut heap = BinaryHeap::from([Reverse((0u64, source))]);
+    while let Some(Reverse((d, node))) = heap.pop() {
+        if d > dist[node] {
+            continue;
+        }
+        for &(next, w) in &adjacency[node] {
+            let nd = d.saturating_add(w);
+            if nd < dist[next] {
+                dist[next] = nd;
+                predecessor[next] = node;
+                heap.push(Reverse((nd, next)));
+            }
+        }
+    }
+    predecessor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gml_parser::{GmlEdge, GmlNode};
+    use crate::topology::latency::NodeLatencies;
+    use crate::topology::{generate_topology, TopologyParams};
+    use std::collections::HashMap;
+
+    fn node(id: u32) -> GmlNode {
+        GmlNode {
+            id,
+            label: None,
+            ip: None,
+            region: None,
+            attributes: HashMap::new(),
+        }
+    }
+
+    fn edge(source: u32, target: u32, latency: &str) -> GmlEdge {
+        GmlEdge {
+            source,
+            target,
+            attributes: HashMap::from([("latency".to_string(), latency.to_string())]),
+        }
+    }
+
+    #[test]
+    fn keeps_the_shortest_path_and_drops_the_rest() {
+        // 0 - 1 - 2 is 20ms, 0 - 3 - 2 is 30ms, 4 hangs off 3
+        let graph = GmlGraph {
+            nodes: (0..5).map(node).collect(),
+            edges: vec![
+                edge(0, 0, "1ms"),
+                edge(0, 1, "10ms"),
+                edge(1, 2, "10ms"),
+                edge(0, 3, "15ms"),
+                edge(3, 2, "15ms"),
+                edge(3, 4, "1ms"),
+            ],
+            attributes: HashMap::new(),
+        };
+        let pruned = prune_to_nodes(&graph, &BTreeSet::from([0, 2])).unwrap();
+        let ids: Vec<u32> = pruned.nodes.iter().map(|n| n.id).collect();
+        assert_eq!(ids, vec![0, 1, 2]);
+        let edges: Vec<(u32, u32)> = pruned.edges.iter().map(|e| (e.source, e.target)).collect();
+        assert_eq!(edges, vec![(0, 0), (0, 1), (1, 2)]);
+
+        let err = prune_to_nodes(&graph, &BTreeSet::from([9])).unwrap_err();
+        assert!(err.contains("no GML node 9"), "{}", err);
+    }
+
+    #[test]
+    fn latencies_between_kept_nodes_are_unchanged() {
+        let graph = generate_topology(&TopologyParams {
+            as_groups: 8,
+            nodes_per_as: 25,
+            intra_latency_ms: (1.0, 5.0),
+            inter_latency_ms: (20.0, 150.0),
+            bandwidth_classes: vec!["1Gbit".to_string()],
+            seed: 3,
+        })
+        .unwrap();
+        let keep: BTreeSet<u32> = graph.nodes.iter().map(|n| n.id).step_by(37).collect();
+        let pruned = prune_to_nodes(&graph, &keep).unwrap();
+        assert!(pruned.nodes.len() < graph.nodes.len());
+
+        let sources: Vec<u32> = keep.iter().copied().collect();
+        let full = NodeLatencies::from_sources(&graph, &sources).unwrap();
+        let cut = NodeLatencies::from_sources(&pruned, &sources).unwrap();
+        for &a in &sources {
+            for &b in &sources {
+                assert_eq!(cut.get(a, b), full.get(a, b), "{} -> {}", a, b);
+            }
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! Seed selection: which user agents the others bootstrap from.
+//!
+//! `network.seed_selection` names the policy; [`select_seeds`] applies it
+//! to the user agents in registry order and returns every seed with the
+//! reason it was chosen. The caller logs the choice, records it in the
+//! agent registry, and reports promotions (agents made seeds although
+//! their config does not say so) as validation warnings.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::config::{PeerMode, SeedSelection};
+
+/// Seeds `auto` tops the set up to outside Dynamic mode, and the default
+/// `first_n` count
+pub const DEFAULT_SEED_COUNT: usize = 5;
+
+/// A user agent as seed selection sees it
+#[derive(Debug, Clone)]
+pub struct SeedCandidate<'a> {
+    pub id: &'a str,
+    /// `IP:PORT` of the agent's daemon
+    pub addr: &'a str,
+    pub is_miner: bool,
+    /// The config sets `is_seed_node: true`
+    pub is_marked: bool,
+    /// Runs a daemon others can dial: not a spy, not `is_unreachable`
+    pub dialable: bool,
+}
+
+/// Why an agent became a seed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SeedReason {
+    /// `miners`, or `auto` in Dynamic mode
+    Miner,
+    /// `is_seed_node: true`
+    Attribute,
+    /// Named in `network.seed_nodes`
+    Listed,
+    /// Among the first `seed_count` dialable daemons
+    FirstN,
+    /// Made a seed by `auto` to reach `seed_count`, against its config
+    Promoted,
+}
+
+impl fmt::Display for SeedReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SeedReason::Miner => "miner",
+            SeedReason::Attribute => "is_seed_node",
+            SeedReason::Listed => "listed in seed_nodes",
+            SeedReason::FirstN => "first_n",
+            SeedReason::Promoted => "promoted",
+        })
+    }
+}
+
+/// One selected seed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SeedChoice {
+    pub id: String,
+    /// Position in the candidate list
+    pub index: usize,
+    pub reason: SeedReason,
+}
+
+/// Apply `selection` to `candidates`, returning the seeds in the order
+/// regular agents receive them.
+///
+/// `auto` keeps the historical behaviour and never fails; the other
+/// policies fail when they select nobody. `first_n` takes fewer than
+/// `seed_count` seeds when there are not enough dialable daemons.
+pub fn select_seeds(
+    selection: SeedSelection,
+    seed_count: Option<usize>,
+    peer_mode: &PeerMode,
+    candidates: &[SeedCandidate<'_>],
+    seed_nodes: &[String],
+) -> Result<Vec<SeedChoice>, String> {
+    let choose = |index: usize, reason: SeedReason| SeedChoice {
+        id: candidates[index].id.to_string(),
+        index,
+        reason,
+    };
+    let indices_where = |keep: &dyn Fn(&SeedCandidate<'_>) -> bool| {
+        candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| keep(c))
+            .map(|(i, _)| i)
+            .collect::<Vec<usize>>()
+    };
+
+    let seeds: Vec<SeedChoice> = match selection {
+        SeedSelection::Auto if matches!(peer_mode, PeerMode::Dynamic) => {
+            indices_where(&|c| c.is_miner)
+                .into_iter()
+                .map(|i| choose(i, SeedReason::Miner))
+                .collect()
+        }
+        SeedSelection::Auto => {
+            let count = seed_count.unwrap_or(DEFAULT_SEED_COUNT);
+            let mut seeds: Vec<SeedChoice> = indices_where(&|c| !c.is_miner && c.is_marked)
+                .into_iter()
+                .map(|i| choose(i, SeedReason::Attribute))
+                .collect();
+            // Fill from the back: the last dialable relays and users first,
+            // then the last miners
+            let relays = indices_where(&|c| !c.is_miner && !c.is_marked && c.dialable);
+            let miners = indices_where(&|c| c.is_miner);
+            for i in relays.into_iter().rev().chain(miners.into_iter().rev()) {
+                if seeds.len() >= count {
+                    break;
+                }
+                seeds.push(choose(i, SeedReason::Promoted));
+            }
+            seeds
+        }
+        SeedSelection::Miners => indices_where(&|c| c.is_miner)
+            .into_iter()
+            .map(|i| choose(i, SeedReason::Miner))
+            .collect(),
+        SeedSelection::Explicit => {
+            let mut seeds: Vec<SeedChoice> = Vec::new();
+            for node in seed_nodes {
+                let found = candidates
+                    .iter()
+                    .position(|c| c.id == node.as_str() || c.addr == node.as_str());
+                if let Some(i) = found {
+                    if !seeds.iter().any(|s| s.index == i) {
+                        seeds.push(choose(i, SeedReason::Listed));
+                    }
+                }
+            }
+            seeds
+        }
+        SeedSelection::FirstN => indices_where(&|c| c.dialable)
+            .into_iter()
+            .take(seed_count.unwrap_or(DEFAULT_SEED_COUNT))
+            .map(|i| choose(i, SeedReason::FirstN))
+            .collect(),
+        SeedSelection::Attribute => indices_where(&|c| c.is_marked)
+            .into_iter()
+            .map(|i| choose(i, SeedReason::Attribute))
+            .collect(),
+    };
+
+    if seeds.is_empty() && selection != SeedSelection::Auto {
+        return Err(format!("seed_selection {:?} selected no seeds", selection));
+    }
+    Ok(seeds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: &'static str, is_miner: bool, is_marked: bool) -> SeedCandidate<'static> {
+        SeedCandidate {
+            id,
+            addr: "",
+            is_miner,
+            is_marked,
+            dialable: true,
+        }
+    }
+
+    fn picked(seeds: &[SeedChoice]) -> Vec<(&str, SeedReason)> {
+        seeds.iter().map(|s| (s.id.as_str(), s.reason)).collect()
+    }
+
+    #[test]
+    fn auto_promotes_only_outside_dynamic_mode() {
+        let mut candidates = vec![
+            candidate("miner-1", true, false),
+            candidate("miner-2", true, false),
+            candidate("seed", false, true),
+            candidate("user-1", false, false),
+            candidate("user-2", false, false),
+            candidate("spy", false, false),
+        ];
+        candidates[5].dialable = false;
+
+        let dynamic = select_seeds(
+            SeedSelection::Auto,
+            None,
+            &PeerMode::Dynamic,
+            &candidates,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            picked(&dynamic),
+            [
+                ("miner-1", SeedReason::Miner),
+                ("miner-2", SeedReason::Miner)
+            ]
+        );
+
+        let hardcoded = select_seeds(
+            SeedSelection::Auto,
+            Some(4),
+            &PeerMode::Hardcoded,
+            &candidates,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            picked(&hardcoded),
+            [
+                ("seed", SeedReason::Attribute),
+                ("user-2", SeedReason::Promoted),
+                ("user-1", SeedReason::Promoted),
+                ("miner-2", SeedReason::Promoted),
+            ]
+        );
+    }
+
+    #[test]
+    fn explicit_policies_never_promote() {
+        let mut candidates = vec![
+            candidate("miner", true, false),
+            candidate("seed", false, true),
+            candidate("relay", false, false),
+            candidate("user", false, false),
+        ];
+        candidates[2].addr = "10.0.0.3:18080";
+        candidates[2].dialable = false;
+        let mode = PeerMode::Hardcoded;
+
+        let listed = vec!["user".to_string(), "10.0.0.3:18080".to_string()];
+        let explicit =
+            select_seeds(SeedSelection::Explicit, None, &mode, &candidates, &listed).unwrap();
+        assert_eq!(
+            picked(&explicit),
+            [("user", SeedReason::Listed), ("relay", SeedReason::Listed)]
+        );
+
+        let first = select_seeds(SeedSelection::FirstN, Some(3), &mode, &candidates, &[]).unwrap();
+        assert_eq!(
+            picked(&first),
+            [
+                ("miner", SeedReason::FirstN),
+                ("seed", SeedReason::FirstN),
+                ("user", SeedReason::FirstN),
+            ]
+        );
+
+        let attribute =
+            select_seeds(SeedSelection::Attribute, Some(5), &mode, &candidates, &[]).unwrap();
+        assert_eq!(picked(&attribute), [("seed", SeedReason::Attribute)]);
+
+        let err = select_seeds(
+            SeedSelection::Explicit,
+            None,
+            &mode,
+            &candidates,
+            &["10.9.9.9:18080".to_string()],
+        )
+        .unwrap_err();
+        assert_eq!(err, "seed_selection Explicit selected no seeds");
+    }
+}
@@ -0,0 +1,152 @@
+//! Connection targets for spy (monitoring) daemons.
+//!
+//! A spy is a daemon-only agent with `is_spy: "true"`. Without
+//! `spy_targets` it joins the network like any relay; with it, it gets one
+//! `--add-priority-node` per resolved target instead of the regular seed
+//! wiring, so the observation point is chosen by the experiment.
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::peer_connections::AgentEntry;
+
+/// Parsed `spy_targets` attribute
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpyTargets {
+    /// Every seed the regular agents bootstrap against
+    AllSeeds,
+    /// N daemons picked deterministically from the simulation seed
+    Random(usize),
+    /// Explicit agent ids
+    Agents(Vec<String>),
+}
+
+/// `all_seeds`, `random:N` or a comma-separated list of agent ids
+impl FromStr for SpyTargets {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "all_seeds" {
+            return Ok(SpyTargets::AllSeeds);
+        }
+        if let Some(n) = s.strip_prefix("random:") {
+            return match n.trim().parse::<usize>() {
+                Ok(n) if n > 0 => Ok(SpyTargets::Random(n)),
+                _ => Err(format!(
+                    "invalid spy_targets '{}' (random:N needs a positive count)",
+                    s
+                )),
+            };
+        }
+        let ids: Vec<String> = s
+            .split(',')
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty())
+            .collect();
+        if ids.is_empty() {
+            return Err(format!(
+                "invalid spy_targets '{}' (expected all_seeds, random:N or a list of agent ids)",
+                s
+            ));
+        }
+        Ok(SpyTargets::Agents(ids))
+    }
+}
+
+impl fmt::Display for SpyTargets {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpyTargets::AllSeeds => write!(f, "all_seeds"),
+            SpyTargets::Random(n) => write!(f, "random:{}", n),
+            SpyTargets::Agents(ids) => write!(f, "{}", ids.join(",")),
+        }
+    }
+}
+
+impl SpyTargets {
+    /// Resolve to `IP:PORT` peers. `candidates` are the daemons the spy may
+    /// connect to (never the spy itself); `order` ranks them for `random:N`.
+    pub fn resolve(
+        &self,
+        candidates: &[&AgentEntry],
+        seed_agents: &[String],
+        order: impl Fn(&str) -> u64,
+    ) -> Vec<String> {
+        let addr = |e: &AgentEntry| format!("{}:{}", e.ip, e.port);
+        match self {
+            SpyTargets::AllSeeds => seed_agents
+                .iter()
+                .filter(|s| candidates.iter().any(|e| addr(e) == **s))
+                .cloned()
+                .collect(),
+            SpyTargets::Random(n) => {
+                let mut picked: Vec<&AgentEntry> = candidates.to_vec();
+                picked.sort_by_key(|e| order(&e.id));
+                picked.into_iter().take(*n).map(addr).collect()
+            }
+            SpyTargets::Agents(ids) => ids
+                .iter()
+                .filter_map(|id| candidates.iter().find(|e| e.id == *id))
+                .map(|e| addr(e))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(i: usize, id: &str) -> AgentEntry {
+        AgentEntry {
+            index: i,
+            is_seed_node: false,
+            id: id.to_string(),
+            ip: format!("10.0.0.{}", i + 1),
+            port: 18080,
+        }
+    }
+
+    #[test]
+    fn parses_every_form() {
+        assert_eq!("all_seeds".parse(), Ok(SpyTargets::AllSeeds));
+        assert_eq!("random:3".parse(), Ok(SpyTargets::Random(3)));
+        assert_eq!(
+            "user-1, user-2".parse(),
+            Ok(SpyTargets::Agents(vec![
+                "user-1".to_string(),
+                "user-2".to_string()
+            ]))
+        );
+        assert!("random:0".parse::<SpyTargets>().is_err());
+        assert!("random:x".parse::<SpyTargets>().is_err());
+        assert!(" , ".parse::<SpyTargets>().is_err());
+    }
+
+    #[test]
+    fn resolves_to_candidate_addresses() {
+        let entries = [entry(0, "miner-1"), entry(1, "user-1"), entry(2, "user-2")];
+        let candidates: Vec<&AgentEntry> = entries.iter().collect();
+        let seeds = vec!["10.0.0.1:18080".to_string(), "10.0.0.9:18080".to_string()];
+        let by_index = |id: &str| entries.iter().position(|e| e.id == id).unwrap() as u64;
+
+        // A seed that is not a candidate (the spy itself) is skipped
+        assert_eq!(
+            SpyTargets::AllSeeds.resolve(&candidates, &seeds, by_index),
+            ["10.0.0.1:18080"]
+        );
+        assert_eq!(
+            SpyTargets::Random(2).resolve(&candidates, &seeds, |id| u64::MAX - by_index(id)),
+            ["10.0.0.3:18080", "10.0.0.2:18080"]
+        );
+        assert_eq!(
+            SpyTargets::Agents(vec!["user-2".to_string(), "miner-1".to_string()]).resolve(
+                &candidates,
+                &seeds,
+                by_index
+            ),
+            ["10.0.0.3:18080", "10.0.0.1:18080"]
+        );
+    }
+}
@@ -1,7 +1,7 @@
 //! Topology type definitions.
 //!
 //! This file contains type definitions for different network topology
-//! patterns supported by the system (Star, Mesh, Ring, DAG).
+//! patterns supported by the system (Star, Mesh, Ring, DAG, Tree, SmallWorld).
 
 /// Re-export types from config to maintain API compatibility
 pub use crate::config::{PeerMode, Topology};
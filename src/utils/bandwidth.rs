@@ -0,0 +1,137 @@
+//! Host bandwidth parsing.
+//!
+//! Agents may set `bandwidth_down` / `bandwidth_up` in human units
+//! ("50 Mbit", "1Gbit", "512 Kbps"); Shadow hosts get plain bits per second.
+
+use crate::config::AgentConfig;
+
+/// Parse a bandwidth like "50 Mbit" into bits per second.
+///
+/// Units are bit / Kbit / Mbit / Gbit / Tbit (decimal, optionally written
+/// "Mbps" or "Mbit/s"); a bare number is bits per second. The prefix may be
+/// either case, but `b` is a bit and `B` a byte, so "10Mb" is 10 Mbit and
+/// "10MB" (or "10 Mbyte") is 80 Mbit. Binary units such as "MiB" are
+/// rejected. Zero is rejected: a host that can't send or receive only hangs
+/// the run.
+pub fn parse_bandwidth_bps(bandwidth: &str) -> Result<u64, String> {
+    let trimmed = bandwidth.trim();
+    let split = trimmed
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let value: f64 = number.parse().map_err(|_| {
+        format!(
+            "Invalid bandwidth '{}' (expected e.g. '50 Mbit' or '1Gbit')",
+            bandwidth
+        )
+    })?;
+    let unit = unit.trim();
+    let unit = unit
+        .strip_suffix("/s")
+        .or_else(|| unit.strip_suffix("ps"))
+        .unwrap_or(unit);
+    let unrecognized = || {
+        format!(
+            "Invalid bandwidth '{}': unrecognized unit (use bit, Kbit, Mbit, Gbit or Tbit, \
+             or KB, MB, GB for bytes)",
+            bandwidth
+        )
+    };
+    let (prefix, bits) = if unit.is_empty() {
+        ("", 1.0)
+    } else if let Some(prefix) = unit.strip_suffix("bit").or_else(|| unit.strip_suffix('b')) {
+        (prefix, 1.0)
+    } else if let Some(prefix) = unit.strip_suffix("byte").or_else(|| unit.strip_suffix('B')) {
+        (prefix, 8.0)
+    } else {
+        return Err(unrecognized());
+    };
+    let multiplier = match prefix.to_ascii_lowercase().as_str() {
+        "" => 1.0,
+        "k" => 1e3,
+        "m" => 1e6,
+        "g" => 1e9,
+        "t" => 1e12,
+        _ => return Err(unrecognized()),
+    } * bits;
+    let bps = (value * multiplier).round() as u64;
+    if bps == 0 {
+        return Err(format!(
+            "Invalid bandwidth '{}': must be above zero",
+            bandwidth
+        ));
+    }
+    Ok(bps)
+}
+
+/// `bps` in the largest unit that divides it exactly, e.g. "1 Gbit" or
+/// "512 Kbit"; the inverse of [`parse_bandwidth_bps`] in a form Shadow reads.
+pub fn format_bandwidth(bps: u64) -> String {
+    for (unit, size) in [
+        ("Gbit", 1_000_000_000),
+        ("Mbit", 1_000_000),
+        ("Kbit", 1_000),
+    ] {
+        if bps % size == 0 {
+            return format!("{} {}", bps / size, unit);
+        }
+    }
+    format!("{} bit", bps)
+}
+
+/// `(bandwidth_down, bandwidth_up)` for an agent's Shadow host, in bits per
+/// second. Unset directions keep the 1 Gbit/s default. Set on the host,
+/// they also take precedence over any bandwidth of the host's GML node.
+pub fn host_bandwidth(agent: &AgentConfig) -> Result<(String, String), String> {
+    let resolve = |value: &Option<String>| match value {
+        Some(v) => parse_bandwidth_bps(v).map(|bps| bps.to_string()),
+        None => Ok(crate::DEFAULT_BANDWIDTH_BPS.to_string()),
+    };
+    Ok((
+        resolve(&agent.bandwidth_down)?,
+        resolve(&agent.bandwidth_up)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bandwidth() {
+        assert_eq!(format_bandwidth(1_000_000_000), "1 Gbit");
+        assert_eq!(format_bandwidth(2_500_000_000), "2500 Mbit");
+        assert_eq!(format_bandwidth(512_000), "512 Kbit");
+        assert_eq!(format_bandwidth(1_500), "1500 bit");
+        for v in ["1Gbit", "100 Mbit", "2.5 gbps", "512 Kbit/s", "12345"] {
+            let bps = parse_bandwidth_bps(v).unwrap();
+            assert_eq!(parse_bandwidth_bps(&format_bandwidth(bps)), Ok(bps));
+        }
+    }
+
+    #[test]
+    fn test_parse_bandwidth_byte_units() {
+        assert_eq!(parse_bandwidth_bps("10Mb"), Ok(10_000_000));
+        assert_eq!(parse_bandwidth_bps("10MB"), Ok(80_000_000));
+        assert_eq!(parse_bandwidth_bps("10 MBps"), Ok(80_000_000));
+        assert_eq!(parse_bandwidth_bps("10 Mbyte/s"), Ok(80_000_000));
+        assert_eq!(parse_bandwidth_bps("512 KB"), Ok(4_096_000));
+        assert_eq!(parse_bandwidth_bps("1 GB"), Ok(8_000_000_000));
+        assert_eq!(parse_bandwidth_bps("100 B"), Ok(800));
+    }
+
+    #[test]
+    fn test_parse_bandwidth_bps() {
+        assert_eq!(parse_bandwidth_bps("50 Mbit"), Ok(50_000_000));
+        assert_eq!(parse_bandwidth_bps("10Mbit"), Ok(10_000_000));
+        assert_eq!(parse_bandwidth_bps("1 Gbit"), Ok(1_000_000_000));
+        assert_eq!(parse_bandwidth_bps("2.5 gbps"), Ok(2_500_000_000));
+        assert_eq!(parse_bandwidth_bps("512 Kbit/s"), Ok(512_000));
+        assert_eq!(parse_bandwidth_bps("1000000000"), Ok(1_000_000_000));
+        for bad in [
+            "", "0", "0 Mbit", "fast", "50 MiB", "-5 Mbit", "Mbit", "10 Mbits",
+        ] {
+            assert!(parse_bandwidth_bps(bad).is_err(), "{}", bad);
+        }
+    }
+}
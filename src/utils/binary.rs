@@ -3,8 +3,15 @@
 //! This module handles resolving binary paths from shorthand names or explicit paths,
 //! and validating that binaries exist and are executable.
 
+use std::collections::BTreeMap;
 use std::env;
-use std::path::PathBuf;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::BinariesConfig;
 
 /// Default directory for monerosim binaries
 const DEFAULT_BIN_DIR: &str = ".monerosim/bin";
@@ -23,6 +30,13 @@ pub enum BinaryError {
 
     #[error("Invalid path: {path}")]
     InvalidPath { path: String },
+
+    #[error(
+        "Monero binaries unavailable:\n  {}\nInstall them with setup.sh, set general.binaries, \
+         or pass --skip-binary-check when generating for another machine",
+        .problems.join("\n  ")
+    )]
+    Unavailable { problems: Vec<String> },
 }
 
 /// Get the user's home directory from the HOME environment variable
@@ -95,6 +109,114 @@ pub fn resolve_binary_path_for_shadow(name_or_path: &str) -> Result<String, Bina
     }
 }
 
+/// Resolves binary specs the way generated configs will reference them.
+///
+/// The canonical shorthands `monerod` / `monero-wallet-rpc` are first mapped
+/// through `general.binaries`. Shorthand names then prefer
+/// `~/.monerosim/bin/{name}` and fall back to the first executable match on
+/// PATH, so a system-wide install works without setup.sh.
+#[derive(Debug, Clone, Default)]
+pub struct BinaryResolver {
+    monerod: Option<String>,
+    wallet_rpc: Option<String>,
+    path_var: Option<OsString>,
+}
+
+impl BinaryResolver {
+    pub fn new(binaries: Option<&BinariesConfig>) -> Self {
+        Self {
+            monerod: binaries.and_then(|b| b.monerod.clone()),
+            wallet_rpc: binaries.and_then(|b| b.wallet_rpc.clone()),
+            path_var: env::var_os("PATH"),
+        }
+    }
+
+    /// Search `path_var` instead of the process PATH (tests stub binaries this way).
+    pub fn with_path_var(mut self, path_var: impl Into<OsString>) -> Self {
+        self.path_var = Some(path_var.into());
+        self
+    }
+
+    pub fn resolve(&self, spec: &str) -> Result<String, BinaryError> {
+        let spec = match spec {
+            "monerod" => self.monerod.as_deref().unwrap_or(spec),
+            "monero-wallet-rpc" => self.wallet_rpc.as_deref().unwrap_or(spec),
+            _ => spec,
+        };
+        let default = resolve_binary_path_for_shadow(spec)?;
+        if spec.contains('/') || spec.starts_with('~') || Path::new(&default).exists() {
+            return Ok(default);
+        }
+        Ok(self
+            .path_var
+            .as_deref()
+            .and_then(|p| find_in_path(spec, p))
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or(default))
+    }
+}
+
+/// First executable `{dir}/{name}` across the entries of a PATH-style variable.
+pub fn find_in_path(name: &str, path_var: &OsStr) -> Option<PathBuf> {
+    env::split_paths(path_var)
+        .map(|dir| dir.join(name))
+        .find(|candidate| is_executable(candidate))
+}
+
+fn is_executable(path: &Path) -> bool {
+    fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+/// Check that a resolved binary path exists and is executable.
+pub fn check_binary(path: &str) -> Result<(), BinaryError> {
+    let p = Path::new(path);
+    if !p.exists() {
+        Err(BinaryError::NotFound {
+            path: path.to_string(),
+        })
+    } else if !is_executable(p) {
+        Err(BinaryError::NotExecutable {
+            path: path.to_string(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Check every binary in `binaries` (resolved path -> agents using it) and
+/// report all problems in one error rather than stopping at the first.
+pub fn verify_binaries(binaries: &BTreeMap<String, Vec<String>>) -> Result<(), BinaryError> {
+    let problems: Vec<String> = binaries
+        .iter()
+        .filter_map(|(path, users)| {
+            check_binary(path)
+                .err()
+                .map(|e| format!("{} (used by {})", e, users.join(", ")))
+        })
+        .collect();
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(BinaryError::Unavailable { problems })
+    }
+}
+
+/// First non-empty stdout line of `{path} --version`, or None if the binary
+/// is missing or can't be run.
+pub fn capture_version(path: &str) -> Option<String> {
+    if !is_executable(Path::new(path)) {
+        return None;
+    }
+    let output = Command::new(path).arg("--version").output().ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,4 +288,98 @@ mod tests {
             Err(BinaryError::InvalidPath { .. })
         ));
     }
+
+    fn stub(dir: &Path, name: &str, body: &str, mode: u32) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, body).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_resolver_falls_back_to_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let stub_path = stub(
+            dir.path(),
+            "monerod-stub-only-on-path",
+            "#!/bin/sh\n",
+            0o755,
+        );
+        let resolver = BinaryResolver::new(None).with_path_var(dir.path());
+        assert_eq!(
+            resolver.resolve("monerod-stub-only-on-path").unwrap(),
+            stub_path.to_string_lossy()
+        );
+        // Unknown shorthand keeps the default location so the check reports it.
+        assert!(resolver
+            .resolve("no-such-binary")
+            .unwrap()
+            .ends_with("/.monerosim/bin/no-such-binary"));
+    }
+
+    #[test]
+    fn test_resolver_honors_configured_binaries() {
+        let binaries = BinariesConfig {
+            monerod: Some("/opt/monero/monerod".to_string()),
+            wallet_rpc: None,
+        };
+        let resolver = BinaryResolver::new(Some(&binaries));
+        assert_eq!(resolver.resolve("monerod").unwrap(), "/opt/monero/monerod");
+        // Explicit agent paths are not remapped.
+        assert_eq!(
+            resolver.resolve("/usr/bin/monerod").unwrap(),
+            "/usr/bin/monerod"
+        );
+    }
+
+    #[test]
+    fn test_verify_binaries_reports_every_problem() {
+        let dir = tempfile::tempdir().unwrap();
+        let ok = stub(dir.path(), "monerod", "#!/bin/sh\n", 0o755);
+        let not_exec = stub(dir.path(), "monero-wallet-rpc", "#!/bin/sh\n", 0o644);
+        let missing = dir.path().join("monerod-v19");
+
+        let mut binaries = BTreeMap::new();
+        binaries.insert(ok.to_string_lossy().into_owned(), vec!["a".to_string()]);
+        binaries.insert(
+            not_exec.to_string_lossy().into_owned(),
+            vec!["b".to_string()],
+        );
+        binaries.insert(
+            missing.to_string_lossy().into_owned(),
+            vec!["c".to_string(), "d".to_string()],
+        );
+
+        match verify_binaries(&binaries) {
+            Err(BinaryError::Unavailable { problems }) => {
+                assert_eq!(problems.len(), 2);
+                assert!(problems
+                    .iter()
+                    .any(|p| p.contains("not executable") && p.contains("used by b")));
+                assert!(problems
+                    .iter()
+                    .any(|p| p.contains("not found") && p.contains("used by c, d")));
+            }
+            other => panic!("expected Unavailable, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_capture_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = stub(
+            dir.path(),
+            "monerod",
+            "#!/bin/sh\necho \"Monero 'Fluorine Fermi' (v0.18.3.4-release)\"\n",
+            0o755,
+        );
+        assert_eq!(
+            capture_version(&path.to_string_lossy()).as_deref(),
+            Some("Monero 'Fluorine Fermi' (v0.18.3.4-release)")
+        );
+        assert_eq!(
+            capture_version(&dir.path().join("absent").to_string_lossy()),
+            None
+        );
+    }
 }
@@ -0,0 +1,158 @@
+//! Cleanup of the output and shared directories before generation.
+//!
+//! A directory is only wiped when it demonstrably belongs to a previous
+//! monerosim run (it holds one of [`OWNED_MARKERS`]) or the user passed
+//! `--force`. Anything else is foreign data — analysis output, another
+//! tool's files, a concurrent run's target — and is left alone.
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+/// Files only the generator writes. Their presence marks the directory as a
+/// previous run's, so everything in it (including agent runtime state) is
+/// ours to remove.
+pub const OWNED_MARKERS: [&str; 3] = ["shadow_agents.yaml", "agent_registry.json", "miners.json"];
+
+/// How to treat an existing target directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanupPolicy {
+    /// Wipe monerosim-owned directories, preserve foreign ones
+    Auto,
+    /// Never delete; generated files are overwritten in place
+    KeepExisting,
+    /// Wipe regardless of ownership (the historical behaviour)
+    Force,
+}
+
+/// What [`prepare_directory`] did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CleanupOutcome {
+    /// Directory did not exist or was empty
+    Absent,
+    Removed,
+    /// Kept because of `KeepExisting`
+    Kept,
+    /// Kept because it holds no marker; lists (some of) the foreign entries
+    Preserved {
+        foreign: Vec<String>,
+    },
+}
+
+/// Recursively fix permissions on a directory tree to allow deletion.
+/// This handles cases where monero-wallet-rpc creates directories with
+/// restrictive permissions (d---------) that prevent normal rm -rf.
+fn fix_permissions_recursive(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        // First, ensure we can read and traverse this directory
+        let mut perms = fs::metadata(path)?.permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(path, perms)?;
+
+        // Then recursively fix children
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            fix_permissions_recursive(&entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove a directory tree, first fixing permissions if needed.
+pub fn remove_dir_with_permissions(path: &Path) -> std::io::Result<()> {
+    if path.exists() {
+        // Try normal removal first
+        if fs::remove_dir_all(path).is_err() {
+            // If it fails, fix permissions and try again
+            fix_permissions_recursive(path)?;
+            fs::remove_dir_all(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Clear `path` for a new run according to `policy`.
+pub fn prepare_directory(path: &Path, policy: CleanupPolicy) -> std::io::Result<CleanupOutcome> {
+    let names: Vec<String> = match fs::read_dir(path) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(CleanupOutcome::Absent),
+        Err(e) => return Err(e),
+    };
+    if names.is_empty() {
+        return Ok(CleanupOutcome::Absent);
+    }
+
+    let owned = names.iter().any(|n| OWNED_MARKERS.contains(&n.as_str()));
+    match policy {
+        CleanupPolicy::KeepExisting => Ok(CleanupOutcome::Kept),
+        CleanupPolicy::Auto if !owned => {
+            let mut foreign = names;
+            foreign.sort();
+            foreign.truncate(5);
+            Ok(CleanupOutcome::Preserved { foreign })
+        }
+        CleanupPolicy::Auto | CleanupPolicy::Force => {
+            remove_dir_with_permissions(path)?;
+            Ok(CleanupOutcome::Removed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn foreign_directory_is_preserved_unless_forced() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("out");
+        fs::create_dir_all(dir.join("analysis")).unwrap();
+        fs::write(dir.join("report.json"), "{}").unwrap();
+
+        let outcome = prepare_directory(&dir, CleanupPolicy::Auto).unwrap();
+        assert_eq!(
+            outcome,
+            CleanupOutcome::Preserved {
+                foreign: vec!["analysis".to_string(), "report.json".to_string()]
+            }
+        );
+        assert!(dir.join("report.json").exists());
+
+        assert_eq!(
+            prepare_directory(&dir, CleanupPolicy::Force).unwrap(),
+            CleanupOutcome::Removed
+        );
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn previous_run_is_removed_including_locked_wallet_dirs() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("shared");
+        let wallet = dir.join("user-001_wallet");
+        fs::create_dir_all(&wallet).unwrap();
+        fs::write(dir.join("agent_registry.json"), "{}").unwrap();
+        fs::write(dir.join("transactions.json"), "[]").unwrap();
+        fs::set_permissions(&wallet, fs::Permissions::from_mode(0o000)).unwrap();
+
+        assert_eq!(
+            prepare_directory(&dir, CleanupPolicy::KeepExisting).unwrap(),
+            CleanupOutcome::Kept
+        );
+        assert!(dir.join("transactions.json").exists());
+
+        assert_eq!(
+            prepare_directory(&dir, CleanupPolicy::Auto).unwrap(),
+            CleanupOutcome::Removed
+        );
+        assert!(!dir.exists());
+        assert_eq!(
+            prepare_directory(&dir, CleanupPolicy::Auto).unwrap(),
+            CleanupOutcome::Absent
+        );
+    }
+}
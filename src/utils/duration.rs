@@ -1,7 +1,14 @@
 //! Duration parsing utilities.
 //!
 //! This module provides utilities for parsing duration strings
-//! (e.g., "3h", "30m") into appropriate formats.
+//! (e.g., "3h", "30m") into appropriate formats, and [`SimDuration`], the
+//! type every simulated-time field of the config and of Shadow's config is
+//! held in.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Parse duration string (e.g., "5h", "30m", "1800s") to seconds
 ///
@@ -48,11 +55,11 @@ pub fn parse_duration_to_seconds(duration: &str) -> Result<u64, String> {
         )
     })?;
 
-    let seconds = match unit {
-        "" => value, // raw seconds
-        "s" | "sec" | "secs" | "second" | "seconds" => value,
-        "m" | "min" | "mins" | "minute" | "minutes" => value * 60.0,
-        "h" | "hr" | "hrs" | "hour" | "hours" => value * 3600.0,
+    let unit_secs: u64 = match unit {
+        "" => 1, // raw seconds
+        "s" | "sec" | "secs" | "second" | "seconds" => 1,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 3600,
         other => {
             return Err(format!(
                 "Invalid duration '{}': unrecognized unit '{}'. Use a single unit like '30s', '5m', or '2h'; compound forms such as '5m30s' are not supported.",
@@ -61,7 +68,53 @@ pub fn parse_duration_to_seconds(duration: &str) -> Result<u64, String> {
         }
     };
 
-    Ok(seconds as u64)
+    // Whole numbers stay exact; f64 would round large values
+    if let Ok(whole) = num_str.parse::<u64>() {
+        return whole
+            .checked_mul(unit_secs)
+            .ok_or_else(|| format!("Invalid duration '{}': too large", duration));
+    }
+    Ok((value * unit_secs as f64) as u64)
+}
+
+/// Check a Shadow time string (e.g. "1ms", "10 ms", "1 microsecond"), as
+/// used by Shadow's `experimental` options, and return it in nanoseconds.
+///
+/// Unlike [`parse_duration_to_seconds`] this accepts sub-second units,
+/// because runahead and CPU latency are typically microseconds to
+/// milliseconds. Shadow is the final judge; this only catches typos before
+/// a multi-hour run fails to start.
+pub fn parse_shadow_time_ns(time: &str) -> Result<u64, String> {
+    let trimmed = time.trim();
+    let num_str = extract_number_part(trimmed);
+    let unit = trimmed[num_str.len()..].trim_start();
+    let value: f64 = num_str.parse().map_err(|_| {
+        format!(
+            "Invalid time '{}' (expected a number and a unit, e.g. '1ms' or '10 us')",
+            time
+        )
+    })?;
+    let ns_per_unit = match unit {
+        "ns" | "nanosecond" | "nanoseconds" => 1.0,
+        "us" | "μs" | "microsecond" | "microseconds" => 1e3,
+        "ms" | "millisecond" | "milliseconds" => 1e6,
+        "s" | "sec" | "secs" | "second" | "seconds" => 1e9,
+        "min" | "mins" | "minute" | "minutes" => 60e9,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 3600e9,
+        "" => {
+            return Err(format!(
+                "Invalid time '{}': missing unit (e.g. '1ms')",
+                time
+            ))
+        }
+        other => {
+            return Err(format!(
+                "Invalid time '{}': unrecognized unit '{}' (use ns, us, ms, s, min or h)",
+                time, other
+            ))
+        }
+    };
+    Ok((value * ns_per_unit).round() as u64)
 }
 
 /// Extract the numeric part from a duration string by finding the first non-numeric character.
@@ -77,10 +130,130 @@ fn extract_number_part(duration: &str) -> &str {
     duration // If all characters are digits/dots
 }
 
+/// `ns` in the largest unit that divides it exactly, e.g. "20 ms" or
+/// "1500 us"; the inverse of [`parse_shadow_time_ns`].
+pub fn format_shadow_time(ns: u64) -> String {
+    for (unit, size) in [("s", 1_000_000_000), ("ms", 1_000_000), ("us", 1_000)] {
+        if ns != 0 && ns % size == 0 {
+            return format!("{} {}", ns / size, unit);
+        }
+    }
+    format!("{} ns", ns)
+}
+
+/// A span of simulated time in whole seconds.
+///
+/// Config files write it as a duration string ("3h", "90m", "45s") or as a
+/// bare integer of seconds; both deserialize, anything else is an error.
+/// It serializes in the largest unit that divides it exactly, so `"90m"`
+/// round-trips as `"90m"` and `5400` as `"90m"` too. Shadow's config wants
+/// its own forms: use [`SimDuration::to_shadow_string`] (or the serde helpers
+/// in `shadow::types`) there, since Shadow does not accept `"m"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SimDuration(u64);
+
+impl SimDuration {
+    pub const ZERO: SimDuration = SimDuration(0);
+
+    pub const fn from_secs(secs: u64) -> Self {
+        SimDuration(secs)
+    }
+
+    pub const fn as_secs(self) -> u64 {
+        self.0
+    }
+
+    /// `"{secs}s"`, which Shadow accepts for every time field
+    pub fn to_shadow_string(self) -> String {
+        format!("{}s", self.0)
+    }
+
+    pub fn saturating_add(self, other: SimDuration) -> SimDuration {
+        SimDuration(self.0.saturating_add(other.0))
+    }
+
+    pub fn saturating_sub(self, other: SimDuration) -> SimDuration {
+        SimDuration(self.0.saturating_sub(other.0))
+    }
+}
+
+impl From<u64> for SimDuration {
+    fn from(secs: u64) -> Self {
+        SimDuration(secs)
+    }
+}
+
+impl FromStr for SimDuration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_duration_to_seconds(s).map(SimDuration)
+    }
+}
+
+impl fmt::Display for SimDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            0 => write!(f, "0s"),
+            s if s % 3600 == 0 => write!(f, "{}h", s / 3600),
+            s if s % 60 == 0 => write!(f, "{}m", s / 60),
+            s => write!(f, "{}s", s),
+        }
+    }
+}
+
+impl Serialize for SimDuration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SimDuration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = SimDuration;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a duration such as \"3h\", \"90m\" or \"45s\", or whole seconds")
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<SimDuration, E> {
+                Ok(SimDuration(v))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<SimDuration, E> {
+                u64::try_from(v)
+                    .map(SimDuration)
+                    .map_err(|_| E::custom(format!("Invalid duration {}: must not be negative", v)))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<SimDuration, E> {
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_shadow_time() {
+        assert_eq!(format_shadow_time(20_000_000), "20 ms");
+        assert_eq!(format_shadow_time(1_500_000), "1500 us");
+        assert_eq!(format_shadow_time(2_000_000_000), "2 s");
+        assert_eq!(format_shadow_time(0), "0 ns");
+        for v in ["20ms", "1.5 ms", "250us", "3s"] {
+            let ns = parse_shadow_time_ns(v).unwrap();
+            assert_eq!(parse_shadow_time_ns(&format_shadow_time(ns)), Ok(ns));
+        }
+    }
+
     #[test]
     fn test_parse_duration_seconds() {
         // Test raw seconds
@@ -153,4 +326,96 @@ mod tests {
         assert_eq!(extract_number_part("12.5秒"), "12.5");
         assert_eq!(extract_number_part("100🦀"), "100");
     }
+
+    #[test]
+    fn test_parse_shadow_time_ns() {
+        assert_eq!(parse_shadow_time_ns("1ms"), Ok(1_000_000));
+        assert_eq!(parse_shadow_time_ns("10 ms"), Ok(10_000_000));
+        assert_eq!(parse_shadow_time_ns("1 microsecond"), Ok(1_000));
+        assert_eq!(parse_shadow_time_ns("0.5s"), Ok(500_000_000));
+        for bad in ["", "ms", "10", "10 parsecs", "fast", "1ms2"] {
+            assert!(parse_shadow_time_ns(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_sim_duration_display_and_serde() {
+        assert_eq!(SimDuration::from_secs(0).to_string(), "0s");
+        assert_eq!(SimDuration::from_secs(45).to_string(), "45s");
+        assert_eq!(SimDuration::from_secs(5400).to_string(), "90m");
+        assert_eq!(SimDuration::from_secs(10800).to_string(), "3h");
+        assert_eq!(SimDuration::from_secs(5400).to_shadow_string(), "5400s");
+
+        let parsed: Vec<SimDuration> = serde_yaml::from_str("[3h, 90m, 45s, 1800, '600']").unwrap();
+        assert_eq!(
+            parsed.iter().map(|d| d.as_secs()).collect::<Vec<_>>(),
+            [10800, 5400, 45, 1800, 600]
+        );
+        for bad in ["-5", "1.5", "5m30s", "soon", "[]"] {
+            assert!(serde_yaml::from_str::<SimDuration>(bad).is_err(), "{}", bad);
+        }
+    }
+
+    /// Property: every duration formats to a string that parses back to it,
+    /// through `FromStr` and through serde, whatever unit it lands in
+    #[test]
+    fn test_sim_duration_round_trips() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        for seed in 0..40 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            for _ in 0..250 {
+                let unit = [1, 60, 3600][rng.gen_range(0..3)];
+                let secs = match rng.gen_range(0..3) {
+                    0 => rng.gen_range(0..1_000) * unit,
+                    1 => rng.gen_range(0..u64::MAX / 3600) * unit,
+                    _ => rng.gen(),
+                };
+                let d = SimDuration::from_secs(secs);
+                assert_eq!(d.to_string().parse(), Ok(d), "seed {}: {}", seed, secs);
+                assert_eq!(
+                    d.to_shadow_string().parse(),
+                    Ok(d),
+                    "seed {}: {}",
+                    seed,
+                    secs
+                );
+                let yaml = serde_yaml::to_string(&d).unwrap();
+                assert_eq!(serde_yaml::from_str::<SimDuration>(&yaml).unwrap(), d);
+                assert_eq!(
+                    serde_yaml::from_str::<SimDuration>(&secs.to_string()).unwrap(),
+                    d
+                );
+            }
+        }
+    }
+
+    /// Property: any spelling of a unit parses to the same duration, which
+    /// formats back in canonical form
+    #[test]
+    fn test_sim_duration_unit_spellings() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        let units: [(&[&str], u64); 3] = [
+            (&["s", "sec", "secs", "second", "seconds"], 1),
+            (&["m", "min", "mins", "minute", "minutes"], 60),
+            (&["h", "hr", "hrs", "hour", "hours"], 3600),
+        ];
+        let mut rng = StdRng::seed_from_u64(592);
+        for _ in 0..2_000 {
+            let (spellings, size) = units[rng.gen_range(0..units.len())];
+            let spelling = spellings[rng.gen_range(0..spellings.len())];
+            let n: u64 = rng.gen_range(0..100_000);
+            let d: SimDuration = format!("{}{}", n, spelling).parse().unwrap();
+            assert_eq!(d.as_secs(), n * size);
+            let canonical = d.to_string();
+            assert_eq!(canonical.parse(), Ok(d));
+            assert_eq!(
+                canonical.parse::<SimDuration>().unwrap().to_string(),
+                canonical
+            );
+        }
+    }
 }
@@ -0,0 +1,212 @@
+//! Per-process resource limits.
+//!
+//! Shadow has no host or process option for memory or CPU, so an agent's
+//! `memory_limit` / `cpu_weight` are applied by launching each of its
+//! processes through `bash -c '<limits> && exec "$0" "$@"' <path> <args>`.
+//! `exec` keeps the process id, so Shadow's shutdown signal and expected
+//! final state still reach the real program.
+//!
+//! `memory_limit` sets `ulimit -d` (`RLIMIT_DATA`), which counts heap and
+//! private anonymous mappings but not file mappings, so monerod's LMDB map
+//! does not count against it; allocations past the limit fail. `cpu_weight`
+//! is a share like a cgroup `cpu.weight` (100 is normal), applied as a
+//! `nice` level: it orders the native processes on the machine running
+//! Shadow and leaves simulated time alone.
+
+use crate::config::{AgentConfig, GeneralConfig};
+use crate::shadow::{ProcessArgs, ShadowProcess};
+
+/// `nice`, by absolute path: Shadow processes may not have a `PATH`
+const NICE: &str = "/usr/bin/nice";
+
+/// Largest `cpu_weight`: higher shares need a negative nice level, which
+/// only a privileged user may set
+pub const MAX_CPU_WEIGHT: u32 = 100;
+
+/// Parse a memory size like "512 MiB" or "2G" into MiB. Units are K, M, G
+/// and T, optionally followed by "B" or "iB", all binary; a bare number is
+/// MiB.
+pub fn parse_memory_mb(memory: &str) -> Result<u64, String> {
+    let trimmed = memory.trim();
+    let split = trimmed
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let value: f64 = number.parse().map_err(|_| {
+        format!(
+            "Invalid memory size '{}' (expected e.g. '512 MiB' or '2G')",
+            memory
+        )
+    })?;
+    let unit = unit.trim().to_ascii_lowercase();
+    let unit = unit
+        .strip_suffix("ib")
+        .or_else(|| unit.strip_suffix('b'))
+        .unwrap_or(&unit);
+    let mb = match unit {
+        "k" => value / 1024.0,
+        "" | "m" => value,
+        "g" => value * 1024.0,
+        "t" => value * 1024.0 * 1024.0,
+        _ => {
+            return Err(format!(
+                "Invalid memory size '{}': unrecognized unit (use K, M, G or T)",
+                memory
+            ))
+        }
+    };
+    let mb = mb.round() as u64;
+    if mb == 0 {
+        return Err(format!(
+            "Invalid memory size '{}': must be at least 1 MiB",
+            memory
+        ));
+    }
+    Ok(mb)
+}
+
+/// `nice` level of a `cpu_weight`: each level is a 1.25x smaller share
+/// (the kernel's weight table), so 100 is 0 and 1 is the lowest priority
+pub fn cpu_weight_nice(weight: u32) -> Result<u32, String> {
+    if !(1..=MAX_CPU_WEIGHT).contains(&weight) {
+        return Err(format!(
+            "cpu_weight {} is out of range: use 1-{} ({} is a normal share; more would need \
+             a negative nice level, which an unprivileged run cannot set)",
+            weight, MAX_CPU_WEIGHT, MAX_CPU_WEIGHT
+        ));
+    }
+    let levels = (MAX_CPU_WEIGHT as f64 / weight as f64).ln() / 1.25f64.ln();
+    Ok((levels.round() as u32).min(19))
+}
+
+/// Limits every process of one agent runs under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessLimits {
+    pub memory_mb: Option<u64>,
+    /// 0-19
+    pub nice: Option<u32>,
+}
+
+impl ProcessLimits {
+    /// The agent's `memory_limit` / `cpu_weight`, each falling back to
+    /// `general`'s; None when neither sets anything (a weight of 100 is
+    /// nothing to apply)
+    pub fn resolve(agent: &AgentConfig, general: &GeneralConfig) -> Result<Option<Self>, String> {
+        let memory_mb = agent
+            .memory_limit
+            .as_ref()
+            .or(general.memory_limit.as_ref())
+            .map(|m| parse_memory_mb(m).map_err(|e| format!("memory_limit: {}", e)))
+            .transpose()?;
+        let nice = agent
+            .cpu_weight
+            .or(general.cpu_weight)
+            .map(cpu_weight_nice)
+            .transpose()?
+            .filter(|n| *n > 0);
+        Ok((memory_mb.is_some() || nice.is_some()).then_some(ProcessLimits { memory_mb, nice }))
+    }
+
+    /// The `bash -c` command: set the limits, then exec `$0` with `$@`
+    pub fn command(&self) -> String {
+        let mut command = String::new();
+        if let Some(mb) = self.memory_mb {
+            command.push_str(&format!("ulimit -d {} && ", mb * 1024));
+        }
+        command.push_str("exec ");
+        if let Some(nice) = self.nice {
+            command.push_str(&format!("{} -n {} ", NICE, nice));
+        }
+        command.push_str("\"$0\" \"$@\"");
+        command
+    }
+
+    /// Launch `process` under the limits
+    pub fn apply(&self, process: &mut ShadowProcess) {
+        let program = std::mem::replace(&mut process.path, "/bin/bash".to_string());
+        let mut args = vec!["-c".to_string(), self.command(), program];
+        match std::mem::take(&mut process.args) {
+            ProcessArgs::List(list) => args.extend(list),
+            ProcessArgs::Str(s) => args.extend(s.split_whitespace().map(str::to_string)),
+        }
+        process.args = ProcessArgs::List(args);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_sizes_are_binary() {
+        assert_eq!(parse_memory_mb("512"), Ok(512));
+        assert_eq!(parse_memory_mb("512 MiB"), Ok(512));
+        assert_eq!(parse_memory_mb("2G"), Ok(2048));
+        assert_eq!(parse_memory_mb("1.5 GB"), Ok(1536));
+        assert_eq!(parse_memory_mb("1 TiB"), Ok(1024 * 1024));
+        assert_eq!(parse_memory_mb("2048k"), Ok(2));
+        for bad in ["", "0", "100 KiB", "lots", "5 PB"] {
+            assert!(parse_memory_mb(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn cpu_weights_map_to_nice_levels() {
+        assert_eq!(cpu_weight_nice(100), Ok(0));
+        assert_eq!(cpu_weight_nice(80), Ok(1));
+        assert_eq!(cpu_weight_nice(50), Ok(3));
+        assert_eq!(cpu_weight_nice(1), Ok(19));
+        assert!(cpu_weight_nice(0).is_err());
+        assert!(cpu_weight_nice(200).is_err());
+    }
+
+    #[test]
+    fn agents_fall_back_to_the_general_limits() {
+        let mut general = GeneralConfig::default();
+        let mut agent: AgentConfig = serde_yaml::from_str("daemon: monerod").unwrap();
+        assert_eq!(ProcessLimits::resolve(&agent, &general), Ok(None));
+
+        general.memory_limit = Some("1G".to_string());
+        general.cpu_weight = Some(50);
+        agent.cpu_weight = Some(100);
+        assert_eq!(
+            ProcessLimits::resolve(&agent, &general),
+            Ok(Some(ProcessLimits {
+                memory_mb: Some(1024),
+                nice: None
+            }))
+        );
+        agent.memory_limit = Some("huge".to_string());
+        assert!(ProcessLimits::resolve(&agent, &general)
+            .unwrap_err()
+            .starts_with("memory_limit: "));
+    }
+
+    #[test]
+    fn limited_processes_exec_their_program() {
+        let mut process = ShadowProcess {
+            path: "/usr/bin/monerod".to_string(),
+            args: ProcessArgs::List(vec!["--regtest".to_string()]),
+            ..Default::default()
+        };
+        let limits = ProcessLimits {
+            memory_mb: Some(512),
+            nice: Some(3),
+        };
+        limits.apply(&mut process);
+        assert_eq!(process.path, "/bin/bash");
+        let ProcessArgs::List(args) = &process.args else {
+            panic!("limited processes get an argv list");
+        };
+        assert_eq!(
+            args,
+            &[
+                "-c",
+                "ulimit -d 524288 && exec /usr/bin/nice -n 3 \"$0\" \"$@\"",
+                "/usr/bin/monerod",
+                "--regtest"
+            ]
+        );
+        assert_eq!(process.executable(), "/usr/bin/monerod");
+    }
+}
@@ -1,19 +1,45 @@
-//! Shared utilities: duration parsing, validation, IP helpers, seed extraction.
+//! Shared utilities: duration parsing, validation, IP helpers, seed extraction,
+//! seed-derived per-agent values, deterministic wallet keys, warm-start chain
+//! data.
 
+pub mod bandwidth;
 pub mod binary;
+pub mod cleanup;
 pub mod duration;
+pub mod hash;
+pub mod limits;
+pub mod monero_keys;
 pub mod options;
+pub mod packet_loss;
 pub mod script;
+pub mod seed;
 pub mod seed_extractor;
 pub mod validation;
+pub mod warm_start;
 
-pub use binary::{resolve_binary_path, resolve_binary_path_for_shadow, BinaryError};
-pub use duration::parse_duration_to_seconds;
+pub use bandwidth::{format_bandwidth, host_bandwidth, parse_bandwidth_bps};
+pub use binary::{
+    capture_version, resolve_binary_path, resolve_binary_path_for_shadow, verify_binaries,
+    BinaryError, BinaryResolver,
+};
+pub use cleanup::{prepare_directory, remove_dir_with_permissions, CleanupOutcome, CleanupPolicy};
+pub use duration::{
+    format_shadow_time, parse_duration_to_seconds, parse_shadow_time_ns, SimDuration,
+};
+pub use hash::sha256_hex;
+pub use monero_keys::WalletKeys;
 pub use options::{
-    merge_options, options_to_args, translate_daemon_log_level, translate_wallet_log_level,
+    apply_option_overrides, flag_name, flag_value, merge_args, merge_options, options_to_args,
+    shell_quote, translate_daemon_log_level, translate_wallet_log_level, MANAGED_DAEMON_FLAGS,
+    MANAGED_WALLET_FLAGS,
 };
+pub use packet_loss::parse_packet_loss;
+pub use seed::{agent_seed, seeded_hash, shuffle_for_agent};
 pub use seed_extractor::{extract_mainnet_seed_ips_from_repo, SeedNode};
 pub use validation::{
-    validate_agent_daemon_config, validate_gml_ip_consistency, validate_ip_subnet_diversity,
-    validate_mining_config, validate_topology_config,
+    canonicalize_gml_units, unrecognized_attribute_warnings, validate_agent_attributes,
+    validate_agent_bandwidth, validate_agent_daemon_config, validate_agent_scripts,
+    validate_daemon_args, validate_gml_ip_consistency, validate_ip_subnet_diversity,
+    validate_ip_versions, validate_mining_config, validate_spy_agents, validate_topology_config,
+    validate_wallet_args,
 };
@@ -0,0 +1,484 @@
+//! Monero wallet keys and primary addresses, derived offline.
+//!
+//! Enough of Monero's key scheme to know an agent's address before its
+//! wallet exists: Keccak-256 (`cn_fast_hash`, original padding), reduction
+//! mod the Ed25519 group order, scalar multiplication of the base point and
+//! Monero's block-wise base58. A wallet restored from a spend key derives
+//! its view key as `reduce(keccak(spend))`, the same as here. Speed does
+//! not matter: a generation derives one key pair per wallet agent.
+
+use crate::config::Chain;
+
+/// A deterministic wallet: secret keys as little-endian scalars
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletKeys {
+    pub spend_secret: [u8; 32],
+    pub view_secret: [u8; 32],
+}
+
+impl WalletKeys {
+    /// Keys for `agent_id` under `simulation_seed`: the spend key is the
+    /// reduced Keccak-256 of both, so every run with the same seed restores
+    /// the same wallets
+    pub fn derive(simulation_seed: u64, agent_id: &str) -> Self {
+        let material = format!("monerosim-wallet:{}:{}", simulation_seed, agent_id);
+        Self::from_spend_secret(sc_reduce32(keccak256(material.as_bytes())))
+    }
+
+    /// The wallet a spend key restores, with its view key derived
+    pub fn from_spend_secret(spend_secret: [u8; 32]) -> Self {
+        Self {
+            spend_secret,
+            view_secret: sc_reduce32(keccak256(&spend_secret)),
+        }
+    }
+
+    /// Standard (primary) address on `chain`
+    pub fn address(&self, chain: Chain) -> String {
+        let mut data = Vec::with_capacity(69);
+        let mut prefix = chain.address_prefix();
+        while prefix >= 0x80 {
+            data.push((prefix as u8 & 0x7f) | 0x80);
+            prefix >>= 7;
+        }
+        data.push(prefix as u8);
+        data.extend_from_slice(&public_key(&self.spend_secret));
+        data.extend_from_slice(&public_key(&self.view_secret));
+        let checksum = keccak256(&data);
+        data.extend_from_slice(&checksum[..4]);
+        base58(&data)
+    }
+}
+
+/// Lowercase hex, as wallet JSON takes keys
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// ============================================================================
+// Keccak-256
+// ============================================================================
+
+const KECCAK_ROUNDS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Rotation of lane `x + 5y`
+const KECCAK_ROTATIONS: [u32; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+fn keccak_f(a: &mut [u64; 25]) {
+    for rc in KECCAK_ROUNDS {
+        // Theta
+        let mut c = [0u64; 5];
+        for (x, c) in c.iter_mut().enumerate() {
+            *c = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[x + 5 * y] ^= d;
+            }
+        }
+        // Rho and pi
+        let mut b = [0u64; 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] =
+                    a[x + 5 * y].rotate_left(KECCAK_ROTATIONS[x + 5 * y]);
+            }
+        }
+        // Chi
+        for y in 0..5 {
+            for x in 0..5 {
+                a[x + 5 * y] = b[x + 5 * y] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+            }
+        }
+        // Iota
+        a[0] ^= rc;
+    }
+}
+
+/// Keccak-256 with the original (pre-SHA-3) padding, Monero's `cn_fast_hash`
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    const RATE: usize = 136;
+    let mut msg = data.to_vec();
+    msg.push(0x01);
+    while msg.len() % RATE != 0 {
+        msg.push(0);
+    }
+    *msg.last_mut().expect("padded message is not empty") |= 0x80;
+
+    let mut state = [0u64; 25];
+    for block in msg.chunks_exact(RATE) {
+        for (lane, word) in state.iter_mut().zip(block.chunks_exact(8)) {
+            *lane ^= u64::from_le_bytes(word.try_into().expect("8-byte chunk"));
+        }
+        keccak_f(&mut state);
+    }
+
+    let mut out = [0u8; 32];
+    for (chunk, lane) in out.chunks_exact_mut(8).zip(state) {
+        chunk.copy_from_slice(&lane.to_le_bytes());
+    }
+    out
+}
+
+// ============================================================================
+// Scalars mod l = 2^252 + 27742317777372353535851937790883648493
+// ============================================================================
+
+const GROUP_ORDER: [u64; 4] = [
+    0x5812631a5cf5d3ed,
+    0x14def9dea2f79cd6,
+    0x0000000000000000,
+    0x1000000000000000,
+];
+
+/// A 256-bit little-endian integer mod l (`sc_reduce32`). The input is
+/// below 16l, so a few subtractions do.
+pub fn sc_reduce32(bytes: [u8; 32]) -> [u8; 32] {
+    let mut n = [0u64; 4];
+    for (limb, word) in n.iter_mut().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_le_bytes(word.try_into().expect("8-byte chunk"));
+    }
+    let at_least_l = |n: &[u64; 4]| {
+        for i in (0..4).rev() {
+            if n[i] != GROUP_ORDER[i] {
+                return n[i] > GROUP_ORDER[i];
+            }
+        }
+        true
+    };
+    while at_least_l(&n) {
+        let mut borrow = false;
+        for (limb, l) in n.iter_mut().zip(GROUP_ORDER) {
+            let (v, b1) = limb.overflowing_sub(l);
+            let (v, b2) = v.overflowing_sub(borrow as u64);
+            *limb = v;
+            borrow = b1 || b2;
+        }
+    }
+    let mut out = [0u8; 32];
+    for (chunk, limb) in out.chunks_exact_mut(8).zip(n) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    out
+}
+
+// ============================================================================
+// Field elements mod p = 2^255 - 19, five 51-bit limbs
+// ============================================================================
+
+type Fe = [u64; 5];
+
+const LOW_51: u64 = (1 << 51) - 1;
+
+/// 2p, added before subtracting so limbs never underflow
+const TWO_P: Fe = [
+    0xfffffffffffda,
+    0xffffffffffffe,
+    0xffffffffffffe,
+    0xffffffffffffe,
+    0xffffffffffffe,
+];
+
+fn fe_from_bytes(b: &[u8; 32]) -> Fe {
+    let load = |i: usize| u64::from_le_bytes(b[i..i + 8].try_into().expect("8 bytes"));
+    [
+        load(0) & LOW_51,
+        (load(6) >> 3) & LOW_51,
+        (load(12) >> 6) & LOW_51,
+        (load(19) >> 1) & LOW_51,
+        (load(24) >> 12) & LOW_51,
+    ]
+}
+
+fn fe_carry(mut h: Fe) -> Fe {
+    for i in 0..4 {
+        h[i + 1] += h[i] >> 51;
+        h[i] &= LOW_51;
+    }
+    h[0] += (h[4] >> 51) * 19;
+    h[4] &= LOW_51;
+    h[1] += h[0] >> 51;
+    h[0] &= LOW_51;
+    h
+}
+
+fn fe_add(a: &Fe, b: &Fe) -> Fe {
+    fe_carry(std::array::from_fn(|i| a[i] + b[i]))
+}
+
+fn fe_sub(a: &Fe, b: &Fe) -> Fe {
+    fe_carry(std::array::from_fn(|i| a[i] + TWO_P[i] - b[i]))
+}
+
+fn fe_mul(a: &Fe, b: &Fe) -> Fe {
+    let m = |x: u64, y: u64| x as u128 * y as u128;
+    let b19: [u64; 5] = std::array::from_fn(|i| b[i] * 19);
+    let mut c = [
+        m(a[0], b[0]) + m(a[4], b19[1]) + m(a[3], b19[2]) + m(a[2], b19[3]) + m(a[1], b19[4]),
+        m(a[1], b[0]) + m(a[0], b[1]) + m(a[4], b19[2]) + m(a[3], b19[3]) + m(a[2], b19[4]),
+        m(a[2], b[0]) + m(a[1], b[1]) + m(a[0], b[2]) + m(a[4], b19[3]) + m(a[3], b19[4]),
+        m(a[3], b[0]) + m(a[2], b[1]) + m(a[1], b[2]) + m(a[0], b[3]) + m(a[4], b19[4]),
+        m(a[4], b[0]) + m(a[3], b[1]) + m(a[2], b[2]) + m(a[1], b[3]) + m(a[0], b[4]),
+    ];
+    for i in 0..4 {
+        c[i + 1] += c[i] >> 51;
+        c[i] &= LOW_51 as u128;
+    }
+    c[0] += (c[4] >> 51) * 19;
+    c[4] &= LOW_51 as u128;
+    fe_carry(c.map(|x| x as u64))
+}
+
+/// a^(p-2) = 1/a
+fn fe_invert(a: &Fe) -> Fe {
+    // p - 2 = 2^255 - 21, little-endian
+    let mut exponent = [0xffu8; 32];
+    exponent[0] = 0xeb;
+    exponent[31] = 0x7f;
+    let mut result: Fe = [1, 0, 0, 0, 0];
+    for bit in (0..255).rev() {
+        result = fe_mul(&result, &result);
+        if (exponent[bit / 8] >> (bit % 8)) & 1 == 1 {
+            result = fe_mul(&result, a);
+        }
+    }
+    result
+}
+
+/// Canonical little-endian encoding
+fn fe_to_bytes(a: &Fe) -> [u8; 32] {
+    let mut h = fe_carry(*a);
+    // Subtract p if h >= p: q is 1 exactly when h + 19 overflows 2^255
+    let mut q = (h[0] + 19) >> 51;
+    for limb in &h[1..] {
+        q = (limb + q) >> 51;
+    }
+    h[0] += 19 * q;
+    for i in 0..4 {
+        h[i + 1] += h[i] >> 51;
+        h[i] &= LOW_51;
+    }
+    h[4] &= LOW_51;
+
+    let mut out = [0u8; 32];
+    let (mut acc, mut bits, mut i) = (0u128, 0, 0);
+    for limb in h {
+        acc |= (limb as u128) << bits;
+        bits += 51;
+        while bits >= 8 {
+            out[i] = acc as u8;
+            acc >>= 8;
+            bits -= 8;
+            i += 1;
+        }
+    }
+    out[i] = acc as u8;
+    out
+}
+
+// ============================================================================
+// Edwards points (extended coordinates X:Y:Z:T, x = X/Z, y = Y/Z, xy = T/Z)
+// ============================================================================
+
+/// 2d, d = -121665/121666
+const EDWARDS_D2: [u8; 32] = [
+    0x59, 0xf1, 0xb2, 0x26, 0x94, 0x9b, 0xd6, 0xeb, 0x56, 0xb1, 0x83, 0x82, 0x9a, 0x14, 0xe0, 0x00,
+    0x30, 0xd1, 0xf3, 0xee, 0xf2, 0x80, 0x8e, 0x19, 0xe7, 0xfc, 0xdf, 0x56, 0xdc, 0xd9, 0x06, 0x24,
+];
+
+const BASE_X: [u8; 32] = [
+    0x1a, 0xd5, 0x25, 0x8f, 0x60, 0x2d, 0x56, 0xc9, 0xb2, 0xa7, 0x25, 0x95, 0x60, 0xc7, 0x2c, 0x69,
+    0x5c, 0xdc, 0xd6, 0xfd, 0x31, 0xe2, 0xa4, 0xc0, 0xfe, 0x53, 0x6e, 0xcd, 0xd3, 0x36, 0x69, 0x21,
+];
+
+const BASE_Y: [u8; 32] = [
+    0x58, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+    0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+];
+
+#[derive(Clone, Copy)]
+struct Point {
+    x: Fe,
+    y: Fe,
+    z: Fe,
+    t: Fe,
+}
+
+impl Point {
+    const IDENTITY: Point = Point {
+        x: [0; 5],
+        y: [1, 0, 0, 0, 0],
+        z: [1, 0, 0, 0, 0],
+        t: [0; 5],
+    };
+
+    fn base() -> Point {
+        let (x, y) = (fe_from_bytes(&BASE_X), fe_from_bytes(&BASE_Y));
+        Point {
+            x,
+            y,
+            z: [1, 0, 0, 0, 0],
+            t: fe_mul(&x, &y),
+        }
+    }
+
+    /// Unified addition (add-2008-hwcd-3), also used for doubling
+    fn add(&self, other: &Point) -> Point {
+        let d2 = fe_from_bytes(&EDWARDS_D2);
+        let a = fe_mul(&fe_sub(&self.y, &self.x), &fe_sub(&other.y, &other.x));
+        let b = fe_mul(&fe_add(&self.y, &self.x), &fe_add(&other.y, &other.x));
+        let c = fe_mul(&fe_mul(&self.t, &d2), &other.t);
+        let zz = fe_mul(&self.z, &other.z);
+        let d = fe_add(&zz, &zz);
+        let (e, f, g, h) = (
+            fe_sub(&b, &a),
+            fe_sub(&d, &c),
+            fe_add(&d, &c),
+            fe_add(&b, &a),
+        );
+        Point {
+            x: fe_mul(&e, &f),
+            y: fe_mul(&g, &h),
+            z: fe_mul(&f, &g),
+            t: fe_mul(&e, &h),
+        }
+    }
+
+    /// y with the sign of x in the top bit
+    fn encode(&self) -> [u8; 32] {
+        let z_inv = fe_invert(&self.z);
+        let x = fe_to_bytes(&fe_mul(&self.x, &z_inv));
+        let mut y = fe_to_bytes(&fe_mul(&self.y, &z_inv));
+        y[31] |= (x[0] & 1) << 7;
+        y
+    }
+}
+
+/// The public key of a (reduced) secret scalar: scalar times the base point
+pub fn public_key(secret: &[u8; 32]) -> [u8; 32] {
+    let base = Point::base();
+    let mut result = Point::IDENTITY;
+    for bit in (0..256).rev() {
+        result = result.add(&result);
+        if (secret[bit / 8] >> (bit % 8)) & 1 == 1 {
+            result = result.add(&base);
+        }
+    }
+    result.encode()
+}
+
+// ============================================================================
+// Monero base58: 8-byte blocks to 11 characters, the tail to fewer
+// ============================================================================
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Encoded length of a block of 0..=8 bytes
+const BASE58_BLOCK_SIZES: [usize; 9] = [0, 2, 3, 5, 6, 7, 9, 10, 11];
+
+fn base58(data: &[u8]) -> String {
+    let mut out = String::new();
+    for block in data.chunks(8) {
+        let mut n = block.iter().fold(0u64, |n, b| (n << 8) | *b as u64);
+        let mut digits = vec![BASE58_ALPHABET[0]; BASE58_BLOCK_SIZES[block.len()]];
+        for digit in digits.iter_mut().rev() {
+            *digit = BASE58_ALPHABET[(n % 58) as usize];
+            n /= 58;
+        }
+        out.extend(digits.into_iter().map(char::from));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unhex(s: &str) -> [u8; 32] {
+        std::array::from_fn(|i| u8::from_str_radix(&s[2 * i..2 * i + 2], 16).unwrap())
+    }
+
+    #[test]
+    fn keccak_matches_known_digests() {
+        assert_eq!(
+            to_hex(&keccak256(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(
+            to_hex(&keccak256(b"abc")),
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"
+        );
+    }
+
+    #[test]
+    fn scalars_reduce_mod_the_group_order() {
+        assert_eq!(
+            to_hex(&sc_reduce32([0xff; 32])),
+            "1c95988d7431ecd670cf7d73f45befc6feffffffffffffffffffffffffffff0f"
+        );
+        let small = unhex("0100000000000000000000000000000000000000000000000000000000000000");
+        assert_eq!(sc_reduce32(small), small);
+    }
+
+    #[test]
+    fn public_keys_match_rfc8032() {
+        let one = unhex("0100000000000000000000000000000000000000000000000000000000000000");
+        assert_eq!(to_hex(&public_key(&one)), to_hex(&BASE_Y));
+        // RFC 8032 test 1: the clamped, reduced secret scalar and its key
+        let secret = unhex("7c2cac12e69be96ae9065065462385e8fcff2768d980c0a3a520f006904de90f");
+        assert_eq!(
+            to_hex(&public_key(&secret)),
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+        );
+    }
+
+    #[test]
+    fn addresses_are_deterministic_per_seed_agent_and_chain() {
+        let keys = WalletKeys::derive(42, "miner-001");
+        assert_eq!(
+            to_hex(&keys.spend_secret),
+            "61e33f6125f2960d7aba494b015568ee4f2b983c204fb6f0a64c72a0b3036808"
+        );
+        assert_eq!(
+            to_hex(&keys.view_secret),
+            "a6245ff51e4c85984e83ea05cbdccade617618fa38d82572dcda67c95371040d"
+        );
+        assert_eq!(
+            keys.address(Chain::Regtest),
+            "4AyPBnsqFLnKTFRJgG7nxYf99mb9mj4313GNsXqpKq2yGCsGEQRxRZB4Zh3ajeU565hJvUZrdpDypEPgz3yvur3GVHnzUef"
+        );
+        assert!(keys.address(Chain::Testnet).starts_with('A'));
+        assert!(keys.address(Chain::Stagenet).starts_with('5'));
+        assert_eq!(keys.address(Chain::Stagenet).len(), 95);
+        assert_ne!(WalletKeys::derive(43, "miner-001"), keys);
+        assert_ne!(WalletKeys::derive(42, "miner-002"), keys);
+    }
+}
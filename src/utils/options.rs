@@ -94,7 +94,7 @@ pub fn shell_quote_args(args: &[String]) -> String {
 /// any embedded single quotes with the standard `'\''` dance. Always
 /// quotes — the cost is two extra bytes; the upside is unconditional
 /// safety regardless of metacharacters.
-fn shell_quote(arg: &str) -> String {
+pub fn shell_quote(arg: &str) -> String {
     if arg.contains('\'') {
         format!("'{}'", arg.replace('\'', r"'\''"))
     } else {
@@ -125,3 +125,147 @@ pub fn merge_options(
 
     merged
 }
+
+/// Daemon flags monerosim derives from the agent's id, IP and ports. The
+/// registries and peer wiring assume these values, so configs may not set
+/// them (see `validate_daemon_args`).
+pub const MANAGED_DAEMON_FLAGS: &[&str] = &[
+    "data-dir",
+    "log-file",
+    "regtest",
+    "testnet",
+    "stagenet",
+    "keep-fakechain",
+    "rpc-bind-ip",
+    "rpc-bind-port",
+    "p2p-bind-ip",
+    "p2p-bind-port",
+    "confirm-external-bind",
+];
+
+/// monero-wallet-rpc flags monerosim derives from the agent (see
+/// `validate_wallet_args`)
+pub const MANAGED_WALLET_FLAGS: &[&str] = &[
+    "testnet",
+    "stagenet",
+    "daemon-address",
+    "rpc-bind-ip",
+    "rpc-bind-port",
+    "wallet-dir",
+    "shared-ringdb-dir",
+    "confirm-external-bind",
+];
+
+/// Flags monerod accepts more than once; extra args add to them instead of
+/// replacing them
+const REPEATABLE_FLAGS: &[&str] = &[
+    "add-peer",
+    "add-priority-node",
+    "add-exclusive-node",
+    "seed-node",
+];
+
+/// `--name=value` or `--name` -> `name`; None for anything else
+pub fn flag_name(arg: &str) -> Option<&str> {
+    let flag = arg.strip_prefix("--")?;
+    Some(flag.split_once('=').map_or(flag, |(name, _)| name))
+}
+
+/// Value of the last `--name=value` in `args`
+pub fn flag_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter().rev().find_map(|arg| {
+        let (flag, value) = arg.strip_prefix("--")?.split_once('=')?;
+        (flag == name).then_some(value)
+    })
+}
+
+/// Merge extra argv elements (`daemon_args`, phase args) into generated
+/// ones. An extra `--flag[=value]` replaces every earlier arg with the same
+/// flag, at the position of the first; repeatable flags and non-flag args
+/// are appended. Exact duplicates are dropped either way.
+pub fn merge_args(mut args: Vec<String>, extra: &[String]) -> Vec<String> {
+    for arg in extra {
+        if args.contains(arg) {
+            continue;
+        }
+        let name = flag_name(arg).filter(|n| !REPEATABLE_FLAGS.contains(n));
+        let same = |a: &String| name.is_some() && flag_name(a) == name;
+        match args.iter().position(same) {
+            Some(first) => {
+                args[first] = arg.clone();
+                let mut k = 0;
+                args.retain(|a| {
+                    k += 1;
+                    k - 1 == first || !same(a)
+                });
+            }
+            None => args.push(arg.clone()),
+        }
+    }
+    args
+}
+
+/// Apply option overrides on top of `merge_args` output: set flags replace
+/// any earlier value, `false` removes the flag entirely.
+pub fn apply_option_overrides(
+    args: Vec<String>,
+    overrides: &BTreeMap<String, OptionValue>,
+) -> Vec<String> {
+    let mut args = merge_args(args, &options_to_args(overrides));
+    args.retain(|a| {
+        !flag_name(a).is_some_and(|n| matches!(overrides.get(n), Some(OptionValue::Bool(false))))
+    });
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn extra_args_replace_generated_flags_in_place() {
+        let base = strings(&[
+            "--regtest",
+            "--max-connections-per-ip=4",
+            "--add-priority-node=10.0.0.1:18080",
+            "--no-zmq",
+        ]);
+        let merged = merge_args(
+            base,
+            &strings(&[
+                "--max-connections-per-ip=8",
+                "--add-priority-node=10.0.0.2:18080",
+                "--pad-transactions",
+                "--no-zmq",
+            ]),
+        );
+        assert_eq!(
+            merged,
+            strings(&[
+                "--regtest",
+                "--max-connections-per-ip=8",
+                "--add-priority-node=10.0.0.1:18080",
+                "--no-zmq",
+                "--add-priority-node=10.0.0.2:18080",
+                "--pad-transactions",
+            ])
+        );
+    }
+
+    #[test]
+    fn option_overrides_win_over_extra_args() {
+        let args = strings(&["--limit-rate-up=1024", "--pad-transactions"]);
+        let overrides = BTreeMap::from([
+            ("limit-rate-up".to_string(), OptionValue::Number(2048)),
+            ("pad-transactions".to_string(), OptionValue::Bool(false)),
+        ]);
+        assert_eq!(
+            apply_option_overrides(args, &overrides),
+            strings(&["--limit-rate-up=2048"])
+        );
+    }
+}
@@ -0,0 +1,54 @@
+//! Packet loss parsing for GML nodes and edges.
+//!
+//! Topology files write loss either as a percentage ("1%", "0.5 %") or as
+//! the 0-1 fraction Shadow expects ("0.01").
+
+/// Parse a packet loss into a fraction in `[0, 1]`.
+///
+/// A bare number above 1 is rejected rather than read as a percentage:
+/// "5" could mean 5% or be a typo for "0.5", and either guess silently
+/// changes the simulation.
+pub fn parse_packet_loss(loss: &str) -> Result<f64, String> {
+    let trimmed = loss.trim();
+    let (number, percent) = match trimmed.strip_suffix('%') {
+        Some(number) => (number.trim_end(), true),
+        None => (trimmed, false),
+    };
+    let value: f64 = number.parse().map_err(|_| {
+        format!(
+            "Invalid packet loss '{}' (expected a fraction like '0.01' or a percentage like '1%')",
+            loss
+        )
+    })?;
+    let fraction = if percent { value / 100.0 } else { value };
+    if !(0.0..=1.0).contains(&fraction) {
+        let hint = if percent || value < 0.0 {
+            ""
+        } else {
+            "; write percentages with a '%'"
+        };
+        return Err(format!(
+            "Invalid packet loss '{}': must be between 0 and 1 (0-100%){}",
+            loss, hint
+        ));
+    }
+    Ok(fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_packet_loss() {
+        assert_eq!(parse_packet_loss("0.01"), Ok(0.01));
+        assert_eq!(parse_packet_loss("1%"), Ok(0.01));
+        assert_eq!(parse_packet_loss(" 50 % "), Ok(0.5));
+        assert_eq!(parse_packet_loss("0"), Ok(0.0));
+        assert_eq!(parse_packet_loss("100%"), Ok(1.0));
+        for bad in ["", "%", "lossy", "5", "101%", "-0.1", "1.5"] {
+            assert!(parse_packet_loss(bad).is_err(), "{}", bad);
+        }
+        assert!(parse_packet_loss("5").unwrap_err().contains("'%'"));
+    }
+}
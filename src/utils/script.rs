@@ -1,44 +1,101 @@
 //! Wrapper script generation utilities.
 //!
-//! Provides a function to write a wrapper script to disk and create
-//! a single ShadowProcess that executes it, replacing the old two-process
-//! heredoc pattern (Process 1: create script, Process 2: execute it).
+//! Provides a function to register a wrapper script and create a single
+//! ShadowProcess that executes it, replacing the old two-process heredoc
+//! pattern (Process 1: create script, Process 2: execute it). Scripts are
+//! collected in a [`ScriptSet`] during planning and only hit the disk when
+//! the plan is written, so `--check` can run the whole pipeline read-only.
 
 use crate::shadow::ShadowProcess;
+use crate::utils::duration::SimDuration;
+use std::cell::RefCell;
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-/// Write a wrapper script to the scripts directory and return a single
-/// ShadowProcess that executes it.
+/// Wrapper scripts planned for one scripts directory.
+///
+/// Shared by reference through every agent processor (like the `&Path` it
+/// replaced), hence the interior mutability.
+#[derive(Debug)]
+pub struct ScriptSet {
+    dir: PathBuf,
+    scripts: RefCell<BTreeMap<String, String>>,
+}
+
+impl ScriptSet {
+    pub fn new(dir: PathBuf) -> Self {
+        ScriptSet {
+            dir,
+            scripts: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    /// Directory the scripts will be written to
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn len(&self) -> usize {
+        self.scripts.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scripts.borrow().is_empty()
+    }
+
+    /// Content of the script planned as `name`
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.scripts.borrow().get(name).cloned()
+    }
+
+    /// Create the directory and write every script as 0755.
+    pub fn write_all(&self) -> color_eyre::eyre::Result<()> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| {
+            color_eyre::eyre::eyre!("Failed to create scripts directory {:?}: {}", self.dir, e)
+        })?;
+        for (name, content) in self.scripts.borrow().iter() {
+            let script_path = self.dir.join(name);
+            std::fs::write(&script_path, content).map_err(|e| {
+                color_eyre::eyre::eyre!("Failed to write script {:?}: {}", script_path, e)
+            })?;
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let mut perms = std::fs::metadata(&script_path)?.permissions();
+                perms.set_mode(0o755);
+                std::fs::set_permissions(&script_path, perms)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Register a wrapper script in `scripts` and return a single ShadowProcess
+/// that executes it.
 ///
 /// # Parameters
-/// - `scripts_dir`: Directory where scripts are written (must already exist)
+/// - `scripts`: Script set for the scripts directory
 /// - `script_name`: Filename for the script (e.g., "agent_miner001_wrapper.sh")
 /// - `content`: The bash script content
 /// - `environment`: Environment variables for the process
-/// - `start_time`: When the process should start (e.g., "65s")
+/// - `start_time`: When the process should start
 /// - `shutdown_time`: Optional shutdown time
 /// - `expected_final_state`: Optional expected final state
 pub fn write_wrapper_script(
-    scripts_dir: &Path,
+    scripts: &ScriptSet,
     script_name: &str,
     content: &str,
     environment: &BTreeMap<String, String>,
-    start_time: String,
-    shutdown_time: Option<String>,
+    start_time: SimDuration,
+    shutdown_time: Option<SimDuration>,
     expected_final_state: Option<crate::shadow::ExpectedFinalState>,
 ) -> color_eyre::eyre::Result<ShadowProcess> {
-    let script_path = scripts_dir.join(script_name);
-    std::fs::write(&script_path, content)
-        .map_err(|e| color_eyre::eyre::eyre!("Failed to write script {:?}: {}", script_path, e))?;
-
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = std::fs::metadata(&script_path)?.permissions();
-        perms.set_mode(0o755);
-        std::fs::set_permissions(&script_path, perms)?;
-    }
+    let script_path = scripts.dir.join(script_name);
+    scripts
+        .scripts
+        .borrow_mut()
+        .insert(script_name.to_string(), content.to_string());
 
     Ok(ShadowProcess {
         path: "/bin/bash".to_string(),
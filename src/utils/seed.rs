@@ -0,0 +1,62 @@
+//! Per-agent values derived from `general.simulation_seed`.
+//!
+//! Everything here is a stable FNV-1a hash of the global seed and a key
+//! naming the agent, independent of std's (unstable across versions)
+//! hasher, so the same binary, config and seed always generate the same
+//! arguments while a different seed reshuffles them.
+
+/// Stable FNV-1a hash of (seed, key)
+pub fn seeded_hash(seed: u64, key: &str) -> u64 {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325 ^ seed;
+    for b in key.bytes() {
+        h ^= b as u64;
+        h = h.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    h
+}
+
+/// The agent's own RNG seed, passed to Python agents as `--random-seed`
+pub fn agent_seed(seed: u64, agent_id: &str) -> u64 {
+    seeded_hash(seed, &format!("agent:{}", agent_id))
+}
+
+/// Reorder `items` (seed-node addresses, peer lists) into an order that is
+/// stable for one agent and seed but differs between agents, so daemons do
+/// not all dial the same peer first
+pub fn shuffle_for_agent<T: AsRef<str>>(items: &mut [T], seed: u64, agent_id: &str) {
+    items.sort_by_cached_key(|item| {
+        seeded_hash(seed, &format!("order:{}:{}", agent_id, item.as_ref()))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeds() -> Vec<String> {
+        (1..=8).map(|i| format!("10.0.0.{}:18080", i)).collect()
+    }
+
+    #[test]
+    fn shuffle_is_stable_per_agent_and_seed() {
+        let shuffled = |seed, agent| {
+            let mut items = seeds();
+            shuffle_for_agent(&mut items, seed, agent);
+            items
+        };
+        assert_eq!(shuffled(42, "user-001"), shuffled(42, "user-001"));
+        assert_ne!(shuffled(42, "user-001"), shuffled(42, "user-002"));
+        assert_ne!(shuffled(42, "user-001"), shuffled(43, "user-001"));
+
+        let mut sorted = shuffled(42, "user-001");
+        sorted.sort();
+        assert_eq!(sorted, seeds());
+    }
+
+    #[test]
+    fn agent_seeds_depend_on_seed_and_id() {
+        assert_eq!(agent_seed(42, "user-001"), agent_seed(42, "user-001"));
+        assert_ne!(agent_seed(42, "user-001"), agent_seed(42, "user-002"));
+        assert_ne!(agent_seed(42, "user-001"), agent_seed(43, "user-001"));
+    }
+}
@@ -4,23 +4,26 @@
 //! This allows the simulation to use the same IPs that monerod expects.
 
 use regex::Regex;
+use serde::Serialize;
 use std::env;
 use std::fs;
+use std::net::SocketAddrV4;
 use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 
-static IP_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r#"full_addrs\.insert\("(\d+\.\d+\.\d+\.\d+):(\d+)"\)"#)
-        .expect("invariant: IP_PATTERN is a valid regex")
-});
-
 /// Mainnet seed node info
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct SeedNode {
     pub ip: String,
     pub port: u16,
 }
 
+impl std::fmt::Display for SeedNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.ip, self.port)
+    }
+}
+
 /// Extract seed IPs directly from a net_node.inl file path
 pub fn extract_seed_ips_from_file(file_path: &Path) -> Result<Vec<SeedNode>, String> {
     let content = fs::read_to_string(file_path)
@@ -105,71 +108,275 @@ pub fn extract_mainnet_seed_ips_from_repo(monerosim_repo_dir: &Path) -> Option<V
 /// }
 /// ```
 fn parse_mainnet_seed_ips(content: &str) -> Result<Vec<SeedNode>, String> {
-    // Find the get_ip_seed_nodes function
-    let func_start = content
-        .find("get_ip_seed_nodes()")
-        .ok_or("Could not find get_ip_seed_nodes() function")?;
-
-    // Get the content after the function definition
-    let func_content = &content[func_start..];
+    let seeds = parse_ip_seed_nodes(content)?;
+    if seeds.mainnet.is_empty() {
+        Err("No mainnet seed IPs found in source".to_string())
+    } else {
+        Ok(seeds.mainnet)
+    }
+}
 
-    let ip_pattern = &*IP_PATTERN;
+/// The fallback seed addresses of each network in `get_ip_seed_nodes()`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct IpSeedNodes {
+    pub mainnet: Vec<SeedNode>,
+    pub testnet: Vec<SeedNode>,
+    pub stagenet: Vec<SeedNode>,
+}
 
-    let mut seed_nodes = Vec::new();
-    let mut in_mainnet_block = false;
-    let mut brace_depth = 0;
+/// Which network a branch of `get_ip_seed_nodes()` serves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SeedNet {
+    Mainnet,
+    Testnet,
+    Stagenet,
+    /// FAKECHAIN and anything else we don't track
+    Other,
+}
 
-    for line in func_content.lines() {
-        // Track brace depth to know when we exit the function
-        brace_depth += line.matches('{').count() as i32;
-        brace_depth -= line.matches('}').count() as i32;
+/// Parse the body of `node_server::get_ip_seed_nodes()` from the content of
+/// `net_node.inl`.
+///
+/// Comments are ignored, so commented-out addresses don't count. Each
+/// `if (m_nettype == cryptonote::X)` / `else if` / `else` branch is
+/// assigned to its network, the final `else` being mainnet; inserts outside
+/// any branch apply to every network. Errors name the line of the source
+/// they refer to.
+pub fn parse_ip_seed_nodes(content: &str) -> Result<IpSeedNodes, String> {
+    let code = strip_comments(content);
+    let open = find_definition(&code, "get_ip_seed_nodes")
+        .ok_or("Could not find the get_ip_seed_nodes() definition")?;
+    let close = matching_brace(&code, open).ok_or_else(|| {
+        format!(
+            "get_ip_seed_nodes() body opened on line {} is never closed",
+            line_of(&code, open)
+        )
+    })?;
 
-        // Stop at the end of the function
-        if brace_depth < 0 {
+    let mut seeds = IpSeedNodes::default();
+    let mut pos = open + 1;
+    let mut explicit_mainnet = false;
+    while pos < close {
+        let rest = &code[pos..close];
+        let Some(caps) = BRANCH_PATTERN.captures(rest) else {
             break;
+        };
+        let whole = caps.get(0).expect("invariant: group 0 always matches");
+        // Inserts between branches belong to no branch
+        let common = parse_inserts(&code, pos, pos + whole.start())?;
+        for list in [&mut seeds.mainnet, &mut seeds.testnet, &mut seeds.stagenet] {
+            list.extend(common.iter().cloned());
+        }
+
+        let block_open = pos + whole.end() - 1;
+        let block_close = matching_brace(&code, block_open).ok_or_else(|| {
+            format!(
+                "branch opened on line {} is never closed",
+                line_of(&code, block_open)
+            )
+        })?;
+        let net = match caps.get(1) {
+            Some(cond) => {
+                let cond = cond.as_str();
+                if cond.contains("TESTNET") {
+                    SeedNet::Testnet
+                } else if cond.contains("STAGENET") {
+                    SeedNet::Stagenet
+                } else if cond.contains("MAINNET") {
+                    explicit_mainnet = true;
+                    SeedNet::Mainnet
+                } else {
+                    SeedNet::Other
+                }
+            }
+            None if explicit_mainnet => SeedNet::Other,
+            None => SeedNet::Mainnet,
+        };
+        let found = parse_inserts(&code, block_open + 1, block_close)?;
+        match net {
+            SeedNet::Mainnet => seeds.mainnet.extend(found),
+            SeedNet::Testnet => seeds.testnet.extend(found),
+            SeedNet::Stagenet => seeds.stagenet.extend(found),
+            SeedNet::Other => {}
         }
+        pos = block_close + 1;
+    }
+    let common = parse_inserts(&code, pos.min(close), close)?;
+    for list in [&mut seeds.mainnet, &mut seeds.testnet, &mut seeds.stagenet] {
+        list.extend(common.iter().cloned());
+    }
+    Ok(seeds)
+}
+
+/// `if (...) {`, `else if (...) {` or `else {`; group 1 is the condition
+static BRANCH_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:else\s+)?if\s*\(([^{}]*)\)\s*\{|else\s*\{")
+        .expect("invariant: BRANCH_PATTERN is a valid regex")
+});
+
+/// `<set>.insert("<address>")`
+static INSERT_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\.insert\(\s*"([^"]*)"\s*\)"#)
+        .expect("invariant: INSERT_PATTERN is a valid regex")
+});
 
-        // Look for the else block (mainnet case)
-        // The mainnet case is the else after STAGENET check
-        if line.contains("else") && !line.contains("if") {
-            in_mainnet_block = true;
+/// The `ip:port` inserts in `code[from..to]`
+fn parse_inserts(code: &str, from: usize, to: usize) -> Result<Vec<SeedNode>, String> {
+    INSERT_PATTERN
+        .captures_iter(&code[from..to])
+        .map(|caps| {
+            let address = caps.get(1).expect("invariant: group 1 is non-optional");
+            let line = line_of(code, from + address.start());
+            let addr: SocketAddrV4 = address.as_str().parse().map_err(|_| {
+                format!(
+                    "line {}: '{}' is not an IPv4 address and port",
+                    line,
+                    address.as_str()
+                )
+            })?;
+            Ok(SeedNode {
+                ip: addr.ip().to_string(),
+                port: addr.port(),
+            })
+        })
+        .collect()
+}
+
+/// Byte offset of the `{` opening the definition of `name()`, skipping
+/// declarations and calls
+fn find_definition(code: &str, name: &str) -> Option<usize> {
+    let call = format!("{}()", name);
+    let mut from = 0;
+    while let Some(found) = code[from..].find(&call) {
+        let after = from + found + call.len();
+        let rest = code[after..].trim_start();
+        let rest = rest.strip_prefix("const").unwrap_or(rest).trim_start();
+        if rest.starts_with('{') {
+            return Some(code.len() - rest.len());
         }
+        from = after;
+    }
+    None
+}
 
-        // If we're in the mainnet block and find an insert with port 18080
-        if in_mainnet_block {
-            if let Some(caps) = ip_pattern.captures(line) {
-                // Both capture groups are non-optional in IP_PATTERN, so they
-                // must be present whenever the regex matches.
-                let ip = caps
-                    .get(1)
-                    .expect("invariant: IP_PATTERN group 1 is non-optional")
-                    .as_str()
-                    .to_string();
-                let port: u16 = caps
-                    .get(2)
-                    .expect("invariant: IP_PATTERN group 2 is non-optional")
-                    .as_str()
-                    .parse()
-                    .unwrap_or(18080);
-
-                // Only include mainnet IPs (port 18080)
-                if port == 18080 {
-                    seed_nodes.push(SeedNode { ip, port });
+/// Offset of the `}` closing the `{` at `open`, skipping string and
+/// character literals
+fn matching_brace(code: &str, open: usize) -> Option<usize> {
+    let bytes = code.as_bytes();
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut i = open;
+    while i < bytes.len() {
+        let b = bytes[i];
+        match quote {
+            Some(_) if b == b'\\' => i += 1,
+            Some(q) if b == q => quote = None,
+            Some(_) => {}
+            None => match b {
+                b'"' | b'\'' => quote = Some(b),
+                b'{' => depth += 1,
+                b'}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(i);
+                    }
                 }
-            }
+                _ => {}
+            },
         }
+        i += 1;
+    }
+    None
+}
 
-        // Reset when we see return (end of else block)
-        if line.contains("return") {
-            in_mainnet_block = false;
+/// `content` with `//` and `/* */` comments blanked out. Newlines and
+/// string literals are kept, so line numbers still match.
+fn strip_comments(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut quote = None;
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            out.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    out.push(escaped);
+                }
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"' | '\'', _) => {
+                quote = Some(c);
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                out.push(' ');
+                while let Some(&next) = chars.peek() {
+                    if next == '\n' {
+                        break;
+                    }
+                    out.push(' ');
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                out.push(' ');
+                let mut last = ' ';
+                for next in chars.by_ref() {
+                    out.push(if next == '\n' { next } else { ' ' });
+                    if last == '*' && next == '/' {
+                        break;
+                    }
+                    last = next;
+                }
+            }
+            _ => out.push(c),
         }
     }
+    out
+}
 
-    if seed_nodes.is_empty() {
-        Err("No mainnet seed IPs found in source".to_string())
-    } else {
-        Ok(seed_nodes)
+/// 1-based line number of byte `offset`
+fn line_of(code: &str, offset: usize) -> usize {
+    code[..offset].matches('\n').count() + 1
+}
+
+/// The Monero release a source tree is, from the `DEF_MONERO_VERSION` in
+/// its `src/version.cpp.in` (e.g. `v0.18.5.1`)
+pub fn monero_source_version(source_root: &Path) -> Option<String> {
+    let content = fs::read_to_string(source_root.join("src/version.cpp.in")).ok()?;
+    let caps = VERSION_PATTERN.captures(&content)?;
+    Some(format!("v{}", caps.get(1)?.as_str()))
+}
+
+static VERSION_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"#define\s+DEF_MONERO_VERSION\s+"([^"]+)""#)
+        .expect("invariant: VERSION_PATTERN is a valid regex")
+});
+
+/// `net_node.inl` itself, or `src/p2p/net_node.inl` under a source tree,
+/// with the tree's root when there is one
+pub fn locate_net_node(path: &Path) -> Result<(PathBuf, Option<PathBuf>), String> {
+    if path.is_dir() {
+        let file = path.join("src/p2p/net_node.inl");
+        if !file.exists() {
+            return Err(format!(
+                "{} has no src/p2p/net_node.inl; pass the Monero source tree or the file",
+                path.display()
+            ));
+        }
+        return Ok((file, Some(path.to_path_buf())));
     }
+    // <root>/src/p2p/net_node.inl
+    let root = path
+        .parent()
+        .filter(|p| p.ends_with("src/p2p"))
+        .and_then(|p| p.parent()?.parent())
+        .map(Path::to_path_buf);
+    Ok((path.to_path_buf(), root))
 }
 
 #[cfg(test)]
@@ -208,4 +415,59 @@ mod tests {
         assert_eq!(seeds[0].ip, "176.9.0.187");
         assert_eq!(seeds[0].port, 18080);
     }
+
+    #[test]
+    fn vendored_net_node_splits_seeds_by_network() {
+        let content = include_str!("../../tests/fixtures/monero/src/p2p/net_node.inl");
+        let seeds = parse_ip_seed_nodes(content).unwrap();
+        let addresses = |list: &[SeedNode]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        // Commented-out inserts and get_dns_seed_nodes() don't count
+        assert_eq!(
+            addresses(&seeds.mainnet),
+            [
+                "176.9.0.187:18080",
+                "88.198.163.90:18080",
+                "192.99.8.110:18080",
+                "37.187.74.171:18080",
+                "88.99.195.15:18080",
+                "5.104.84.64:18080",
+            ]
+        );
+        assert_eq!(seeds.testnet.len(), 3);
+        assert!(seeds.testnet.iter().all(|s| s.port == 28080));
+        assert!(seeds.stagenet.iter().all(|s| s.port == 38080));
+    }
+
+    #[test]
+    fn explicit_mainnet_branches_and_bad_addresses() {
+        let content = r#"
+  // get_ip_seed_nodes() is declared in net_node.h;
+  std::set<std::string> get_ip_seed_nodes() const;
+  std::set<std::string> node_server::get_ip_seed_nodes() const
+  {
+    std::set<std::string> full_addrs;
+    if (m_nettype == cryptonote::MAINNET) { full_addrs.insert("1.2.3.4:18080"); }
+    else { full_addrs.insert("5.6.7.8:28080"); }
+    return full_addrs;
+  }
+"#;
+        let seeds = parse_ip_seed_nodes(content).unwrap();
+        assert_eq!(
+            seeds.mainnet,
+            [SeedNode {
+                ip: "1.2.3.4".to_string(),
+                port: 18080
+            }]
+        );
+        assert!(seeds.testnet.is_empty());
+
+        let bad = content.replace("1.2.3.4:18080", "seed.example:18080");
+        let err = parse_ip_seed_nodes(&bad).unwrap_err();
+        assert!(err.starts_with("line 7: 'seed.example:18080'"), "{}", err);
+
+        let unclosed = content.replace("return full_addrs;\n  }", "");
+        assert!(parse_ip_seed_nodes(&unclosed).is_err());
+        assert!(parse_ip_seed_nodes("int main() {}").is_err());
+        assert!(parse_mainnet_seed_ips("void get_ip_seed_nodes() const {}").is_err());
+    }
 }
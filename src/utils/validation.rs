@@ -3,9 +3,21 @@
 //! This module provides validation functions for configuration
 //! parameters and consistency checks.
 
-use crate::config::{AgentConfig, Topology};
+use crate::config::{
+    resolve_miner_weights, AgentConfig, DaemonConfig, GeneralConfig, HashrateDistribution,
+    IpVersion, OptionValue, Topology, MIN_PHASE_GAP_SECONDS,
+};
 use crate::gml_parser::{GmlGraph, GmlNode};
+use crate::ip::registry::{MAX_SUBNET_GROUPS, SUBNET_GROUP_HOSTS};
+use crate::topology::SpyTargets;
+use crate::utils::bandwidth::{format_bandwidth, parse_bandwidth_bps};
+use crate::utils::duration::{format_shadow_time, parse_shadow_time_ns, SimDuration};
+use crate::utils::limits::ProcessLimits;
+use crate::utils::options::{flag_name, MANAGED_DAEMON_FLAGS, MANAGED_WALLET_FLAGS};
+use crate::utils::packet_loss::parse_packet_loss;
 use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
 
 /// Validate GML topology for IP conflicts and inconsistencies
 ///
@@ -85,6 +97,54 @@ pub fn validate_gml_ip_consistency(gml_graph: &GmlGraph) -> Result<(), String> {
     Ok(())
 }
 
+/// Rewrite every bandwidth, latency/jitter and packet loss attribute of the
+/// GML's nodes and edges in one canonical form: "1 Gbit", "20 ms" and a 0-1
+/// fraction. Anything that does not parse fails with the node or edge and
+/// the raw value, instead of reaching Shadow as an unconverted string.
+pub fn canonicalize_gml_units(gml_graph: &mut GmlGraph) -> Result<(), String> {
+    fn canonical(key: &str, value: &str) -> Result<Option<String>, String> {
+        let key = key.to_ascii_lowercase();
+        if key.contains("bandwidth") {
+            parse_bandwidth_bps(value).map(|bps| Some(format_bandwidth(bps)))
+        } else if key == "latency" || key == "jitter" {
+            let ns = parse_shadow_time_ns(value)?;
+            if key == "latency" && ns == 0 {
+                return Err(format!("Invalid latency '{}': must be above zero", value));
+            }
+            Ok(Some(format_shadow_time(ns)))
+        } else if key == "packet_loss" {
+            parse_packet_loss(value).map(|loss| Some(loss.to_string()))
+        } else {
+            Ok(None)
+        }
+    }
+    fn rewrite(
+        what: &str,
+        attributes: &mut std::collections::HashMap<String, String>,
+    ) -> Result<(), String> {
+        for (key, value) in attributes.iter_mut() {
+            match canonical(key, value) {
+                Ok(Some(canonical)) => *value = canonical,
+                Ok(None) => {}
+                Err(e) => return Err(format!("{} attribute '{}': {}", what, key, e)),
+            }
+        }
+        Ok(())
+    }
+
+    for node in &mut gml_graph.nodes {
+        rewrite(&format!("Node {}", node.id), &mut node.attributes)?;
+    }
+    for edge in &mut gml_graph.edges {
+        let what = format!("Edge {} -> {}", edge.source, edge.target);
+        rewrite(&what, &mut edge.attributes)?;
+    }
+    Ok(())
+}
+
+/// Largest Tree fanout accepted; wider trees are effectively a Star
+pub const MAX_TREE_FANOUT: usize = 32;
+
 /// Validate topology configuration
 ///
 /// Checks if the topology is compatible with the number of agents:
@@ -92,6 +152,9 @@ pub fn validate_gml_ip_consistency(gml_graph: &GmlGraph) -> Result<(), String> {
 /// - Ring topology: Requires at least 3 agents
 /// - Star topology: Requires at least 2 agents
 /// - DAG topology: Always valid
+/// - Tree topology: Fanout 2..=`MAX_TREE_FANOUT`, at least 3 agents
+/// - SmallWorld topology: Even `k` of at least 2 and below the agent count,
+///   `rewire_prob` within 0..=1
 ///
 /// # Arguments
 /// * `topology` - The topology to validate
@@ -109,6 +172,8 @@ pub fn validate_gml_ip_consistency(gml_graph: &GmlGraph) -> Result<(), String> {
 /// assert!(validate_topology_config(&Topology::Mesh, 10).is_ok());
 /// assert!(validate_topology_config(&Topology::Ring, 2).is_err()); // Ring needs at least 3 agents
 /// assert!(validate_topology_config(&Topology::Star, 1).is_err()); // Star needs at least 2 agents
+/// assert!(validate_topology_config(&Topology::Tree { fanout: 1 }, 10).is_err()); // fanout 2..=32
+/// assert!(validate_topology_config(&Topology::SmallWorld { k: 10, rewire_prob: 0.1 }, 10).is_err()); // k < agents
 /// ```
 pub fn validate_topology_config(topology: &Topology, total_agents: usize) -> Result<(), String> {
     match topology {
@@ -133,6 +198,38 @@ pub fn validate_topology_config(topology: &Topology, total_agents: usize) -> Res
         Topology::Dag => {
             // DAG is always valid
         }
+        Topology::Tree { fanout } => {
+            if !(2..=MAX_TREE_FANOUT).contains(fanout) {
+                return Err(format!(
+                    "Tree topology fanout must be between 2 and {}, got {}",
+                    MAX_TREE_FANOUT, fanout
+                ));
+            }
+            // Anything smaller is a single edge or a bare root
+            if total_agents < 3 {
+                return Err("Tree topology requires at least 3 agents".to_string());
+            }
+        }
+        Topology::SmallWorld { k, rewire_prob } => {
+            if *k < 2 || k % 2 != 0 {
+                return Err(format!(
+                    "SmallWorld topology k must be an even number of at least 2, got {}",
+                    k
+                ));
+            }
+            if *k >= total_agents {
+                return Err(format!(
+                    "SmallWorld topology k ({}) must be less than the number of agents ({})",
+                    k, total_agents
+                ));
+            }
+            if !(0.0..=1.0).contains(rewire_prob) {
+                return Err(format!(
+                    "SmallWorld topology rewire_prob must be between 0 and 1, got {}",
+                    rewire_prob
+                ));
+            }
+        }
     }
     Ok(())
 }
@@ -141,27 +238,27 @@ pub fn validate_topology_config(topology: &Topology, total_agents: usize) -> Res
 ///
 /// Checks mining agent configuration for:
 /// - Mining agents have wallet field (required for reward address)
-/// - Mining agents have hashrate field (percentage of network hashrate)
-/// - Hashrate values are valid (0-100)
-/// - Total hashrate equals 100% (warning if not)
+/// - Hashrates resolve under `distribution` (see `resolve_miner_weights`):
+///   values in range, percentages summing to 100, `auto` only with a
+///   distribution
 ///
 /// # Arguments
 /// * `agents` - Map of agent_id to AgentConfig
+/// * `distribution` - `general.hashrate_distribution`
 ///
 /// # Returns
 /// * `Ok(())` if validation succeeds
 /// * `Err(String)` with an error message if validation fails
-pub fn validate_mining_config(agents: &BTreeMap<String, AgentConfig>) -> Result<(), String> {
-    let mut total_hashrate = 0u32;
-    let mut mining_agent_count = 0;
-
+pub fn validate_mining_config(
+    agents: &BTreeMap<String, AgentConfig>,
+    distribution: HashrateDistribution,
+) -> Result<(), String> {
+    let mut miners = Vec::new();
     for (agent_id, agent) in agents.iter() {
-        // Skip non-mining agents (miners have hashrate or script containing "miner")
-        if !agent.is_miner() {
+        // Skip non-mining agents (miners are the agents with a hashrate)
+        let Some(hashrate) = agent.hashrate else {
             continue;
-        }
-
-        mining_agent_count += 1;
+        };
 
         // Validate wallet is present for mining agents
         if !agent.has_wallet() {
@@ -170,32 +267,17 @@ pub fn validate_mining_config(agents: &BTreeMap<String, AgentConfig>) -> Result<
                 agent_id
             ));
         }
-
-        // Validate hashrate exists for miners
-        let hashrate = agent.hashrate.ok_or_else(|| {
-            format!(
-                "Mining agent '{}' must have 'hashrate' field (percentage of network hashrate)",
-                agent_id
-            )
-        })?;
-
-        // Validate hashrate is in valid range
-        if hashrate == 0 || hashrate > 100 {
-            return Err(format!(
-                "Mining agent '{}': hashrate {}% out of valid range (must be 1-100)",
-                agent_id, hashrate
-            ));
-        }
-
-        total_hashrate += hashrate;
+        miners.push((agent_id.as_str(), hashrate));
     }
 
-    // Warn if total doesn't equal 100 (but don't fail - this is recoverable)
-    if mining_agent_count > 0 && total_hashrate != 100 {
-        log::warn!(
-            "Total mining hashrate is {}% (expected 100%). Found {} mining agent(s).",
-            total_hashrate,
-            mining_agent_count
+    // The seed only orders Pareto draws, which cannot fail
+    let weights = resolve_miner_weights(miners, distribution, 0)?;
+    let total: f64 = weights.values().sum();
+    if !weights.is_empty() && (total - 100.0).abs() > 0.5 {
+        log::info!(
+            "Mining weights total {} across {} miner(s); each miner's share is its weight over the total",
+            total,
+            weights.len()
         );
     }
 
@@ -216,6 +298,7 @@ pub fn validate_mining_config(agents: &BTreeMap<String, AgentConfig>) -> Result<
 /// 3. Public node requires daemon - `is_public_node: true` requires local daemon
 /// 4. Wallet-only requires remote daemon - If wallet specified without local daemon, need remote config
 /// 5. Auto-discovery requires public nodes - `address: auto` needs at least one public node
+/// 6. A remote daemon named by agent id must be a public node with a local daemon
 ///
 /// # Arguments
 /// * `agents` - Map of agent_id to AgentConfig
@@ -250,6 +333,16 @@ pub fn validate_agent_daemon_config(agents: &BTreeMap<String, AgentConfig>) -> R
             if addr == "auto" {
                 has_auto_discovery = true;
             }
+            // Rule 6: a remote daemon named by agent id is a public node
+            if let Some(target) = agents.get(addr) {
+                if !target.has_local_daemon() || !target.is_public_node() {
+                    return Err(format!(
+                        "Agent '{}': remote daemon '{}' must be an agent with a local daemon \
+                        and 'is_public_node: true'",
+                        agent_id, addr
+                    ));
+                }
+            }
         }
 
         // Rule 1: Mining requires local daemon
@@ -290,6 +383,526 @@ pub fn validate_agent_daemon_config(agents: &BTreeMap<String, AgentConfig>) -> R
     Ok(())
 }
 
+/// Validate per-agent `bandwidth_down` / `bandwidth_up`: each must parse
+/// and be above zero.
+pub fn validate_agent_bandwidth(agents: &BTreeMap<String, AgentConfig>) -> Result<(), String> {
+    for (agent_id, agent) in agents {
+        crate::utils::bandwidth::host_bandwidth(agent)
+            .map_err(|e| format!("Agent '{}': {}", agent_id, e))?;
+    }
+    Ok(())
+}
+
+/// Validate `attributes`: keys become `--attributes <key>` or `--<key>` on
+/// the agent's command line, so they must be non-empty, must not start with
+/// `-` and may not contain whitespace or control characters. Values are
+/// shell-quoted and may hold anything except NUL, which no argv can carry.
+pub fn validate_agent_attributes(agents: &BTreeMap<String, AgentConfig>) -> Result<(), String> {
+    for (agent_id, agent) in agents {
+        for (key, value) in agent.attribute_map() {
+            if key.is_empty()
+                || key.starts_with('-')
+                || key.chars().any(|c| c.is_control() || c.is_whitespace())
+            {
+                return Err(format!(
+                    "Agent '{}': attribute key {:?} must be non-empty, not start with '-' \
+                     and contain no whitespace or control characters",
+                    agent_id, key
+                ));
+            }
+            if value.contains('\0') {
+                return Err(format!(
+                    "Agent '{}': attribute '{}' contains a NUL character",
+                    agent_id, key
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Attribute keys that neither monerosim nor the bundled agent scripts read,
+/// one message per agent. Custom scripts may well read them, so these are
+/// warnings; a close match to a known key is suggested as a likely typo.
+pub fn unrecognized_attribute_warnings(agents: &BTreeMap<String, AgentConfig>) -> Vec<String> {
+    agents
+        .iter()
+        .filter_map(|(agent_id, agent)| {
+            let keys = agent.attributes.as_ref()?.unrecognized();
+            if keys.is_empty() {
+                return None;
+            }
+            let keys = keys
+                .iter()
+                .map(|(key, suggestion)| match suggestion {
+                    Some(known) => format!("{} (did you mean {}?)", key, known),
+                    None => key.to_string(),
+                })
+                .collect::<Vec<_>>();
+            Some(format!(
+                "Agent '{}': unrecognized attributes {}; passed to the agent script as-is",
+                agent_id,
+                keys.join(", ")
+            ))
+        })
+        .collect()
+}
+
+/// Validate per-agent `ip_version`. Shadow's network stack models IPv4 only
+/// (a host's `ip_addr` must be IPv4 on switch and GML graphs alike), so `v6`
+/// and `dual` are rejected rather than silently simulated as IPv4.
+pub fn validate_ip_versions(agents: &BTreeMap<String, AgentConfig>) -> Result<(), String> {
+    for (agent_id, agent) in agents {
+        match agent.ip_version.unwrap_or_default() {
+            IpVersion::V4 => {}
+            version => {
+                return Err(format!(
+                    "Agent '{}': ip_version {} is not supported: Shadow only simulates \
+                     IPv4 hosts, on every network type",
+                    agent_id,
+                    format!("{:?}", version).to_lowercase()
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validate `colocate_with`: the host is another existing agent that is not
+/// itself co-located, both run a daemon or wallet, and host-level settings
+/// are left to the host. Port clashes are reported by `assign_agent_ports`.
+pub fn validate_colocation(agents: &BTreeMap<String, AgentConfig>) -> Result<(), String> {
+    let runs_services =
+        |a: &AgentConfig| a.has_local_daemon() || a.has_remote_daemon() || a.has_wallet();
+    for (agent_id, agent) in agents {
+        let Some(host) = agent.colocate_with.as_deref() else {
+            continue;
+        };
+        let Some(host_agent) = agents.get(host).filter(|_| host != agent_id) else {
+            return Err(format!(
+                "Agent '{}': colocate_with '{}' is not another agent",
+                agent_id, host
+            ));
+        };
+        if host_agent.colocate_with.is_some() {
+            return Err(format!(
+                "Agent '{}': colocate_with '{}', which is itself co-located; \
+                 name the host agent directly",
+                agent_id, host
+            ));
+        }
+        if !runs_services(agent) || !runs_services(host_agent) {
+            return Err(format!(
+                "Agent '{}': colocate_with requires both it and '{}' to run a daemon or wallet",
+                agent_id, host
+            ));
+        }
+        for (field, set) in [
+            ("subnet_group", agent.subnet_group.is_some()),
+            ("bandwidth_down", agent.bandwidth_down.is_some()),
+            ("bandwidth_up", agent.bandwidth_up.is_some()),
+            ("memory_limit", agent.memory_limit.is_some()),
+            ("cpu_weight", agent.cpu_weight.is_some()),
+            ("capture_pcap", agent.capture_pcap.is_some()),
+        ] {
+            if set {
+                return Err(format!(
+                    "Agent '{}': {} belongs to the host; set it on '{}'",
+                    agent_id, field, host
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validate `memory_limit` / `cpu_weight`, on each agent and in
+/// `general`: memory sizes must parse and weights lie in 1-100.
+pub fn validate_process_limits(
+    agents: &BTreeMap<String, AgentConfig>,
+    general: &GeneralConfig,
+) -> Result<(), String> {
+    for (agent_id, agent) in agents {
+        ProcessLimits::resolve(agent, general)
+            .map_err(|e| format!("Agent '{}': {}", agent_id, e))?;
+    }
+    Ok(())
+}
+
+/// Validate `subnet_group` sizes: each group is one /24, so it holds at
+/// most `SUBNET_GROUP_HOSTS` agents, and there are `MAX_SUBNET_GROUPS` of
+/// them.
+pub fn validate_subnet_groups(agents: &BTreeMap<String, AgentConfig>) -> Result<(), String> {
+    let mut members: BTreeMap<&str, usize> = BTreeMap::new();
+    for agent in agents.values() {
+        if let Some(group) = &agent.subnet_group {
+            *members.entry(group.as_str()).or_insert(0) += 1;
+        }
+    }
+    if members.len() > MAX_SUBNET_GROUPS {
+        return Err(format!(
+            "{} subnet groups are configured but 100.64.0.0/16 holds {}",
+            members.len(),
+            MAX_SUBNET_GROUPS
+        ));
+    }
+    for (group, count) in members {
+        if count > SUBNET_GROUP_HOSTS {
+            return Err(format!(
+                "subnet_group '{}' has {} agents but its /24 has addresses for {}, {} short; \
+                 split it into several groups",
+                group,
+                count,
+                SUBNET_GROUP_HOSTS,
+                count - SUBNET_GROUP_HOSTS
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Validate spy agents (`is_spy: "true"`): a daemon-only, non-mining,
+/// non-seed host whose `spy_targets` parses and names existing daemons
+/// other than itself.
+pub fn validate_spy_agents(agents: &BTreeMap<String, AgentConfig>) -> Result<(), String> {
+    for (agent_id, agent) in agents {
+        if !agent.is_spy() {
+            if agent.spy_targets().is_some() {
+                return Err(format!(
+                    "Agent '{}': spy_targets requires is_spy: \"true\"",
+                    agent_id
+                ));
+            }
+            continue;
+        }
+        if !agent.is_daemon_only() || agent.is_seed_node() {
+            return Err(format!(
+                "Agent '{}': a spy must be a daemon-only agent (no wallet, script, \
+                 hashrate or is_seed_node)",
+                agent_id
+            ));
+        }
+        let Some(targets) = agent.spy_targets() else {
+            continue;
+        };
+        let targets: SpyTargets = targets
+            .parse()
+            .map_err(|e| format!("Agent '{}': {}", agent_id, e))?;
+        if let SpyTargets::Agents(ids) = targets {
+            for id in &ids {
+                if id == agent_id {
+                    return Err(format!(
+                        "Agent '{}': spy_targets lists the spy itself",
+                        agent_id
+                    ));
+                }
+                if !agents.get(id).is_some_and(|a| a.has_local_daemon()) {
+                    return Err(format!(
+                        "Agent '{}': spy target '{}' is not an agent with a local daemon",
+                        agent_id, id
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validate `is_unreachable: "true"` agents: they need a local daemon, and
+/// miners, seeds and spies must stay reachable.
+pub fn validate_unreachable_agents(agents: &BTreeMap<String, AgentConfig>) -> Result<(), String> {
+    for (agent_id, agent) in agents.iter().filter(|(_, a)| a.is_unreachable()) {
+        if !agent.has_local_daemon() || agent.is_seed_node() || agent.is_spy() {
+            return Err(format!(
+                "Agent '{}': is_unreachable requires a local daemon and no hashrate, \
+                 is_seed_node or is_spy",
+                agent_id
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Validate `start_time` / `shutdown_time` / `lifetime`: an agent must shut
+/// down after it starts (and after its last daemon or wallet phase starts),
+/// its script must outlive its start, and neither may end later than the
+/// global `stop_time`.
+pub fn validate_agent_schedule(
+    agents: &BTreeMap<String, AgentConfig>,
+    stop_time: SimDuration,
+) -> Result<(), String> {
+    for (agent_id, agent) in agents {
+        if let Some(lifetime) = agent.lifetime {
+            if lifetime > stop_time {
+                return Err(format!(
+                    "Agent '{}': lifetime {} exceeds general.stop_time {}",
+                    agent_id, lifetime, stop_time
+                ));
+            }
+            if let Some(start) = agent.start_time.filter(|&start| start >= lifetime) {
+                return Err(format!(
+                    "Agent '{}': lifetime {} is not after start time {}",
+                    agent_id, lifetime, start
+                ));
+            }
+        }
+        let Some(shutdown) = agent.shutdown_time else {
+            continue;
+        };
+        let restart_phases = agent.restart_phases();
+        let phase_starts = agent
+            .daemon_phases
+            .iter()
+            .chain(restart_phases.iter())
+            .flat_map(|phases| phases.values().map(|p| p.start))
+            .chain(
+                agent
+                    .wallet_phases
+                    .iter()
+                    .flat_map(|phases| phases.values().map(|p| p.start)),
+            )
+            .flatten();
+        for start in agent.start_time.into_iter().chain(phase_starts) {
+            if start >= shutdown {
+                return Err(format!(
+                    "Agent '{}': shutdown_time {} is not after start time {}",
+                    agent_id, shutdown, start
+                ));
+            }
+        }
+        if shutdown > stop_time {
+            return Err(format!(
+                "Agent '{}': shutdown_time {} is after general.stop_time {}",
+                agent_id, shutdown, stop_time
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Validate `restarts`: they need a local `daemon` binary and no daemon
+/// phases, and each restart stops after the previous start and comes back
+/// at least `MIN_PHASE_GAP_SECONDS` later.
+pub fn validate_daemon_restarts(agents: &BTreeMap<String, AgentConfig>) -> Result<(), String> {
+    for (agent_id, agent) in agents {
+        let Some(restarts) = agent.restarts.as_ref().filter(|r| !r.is_empty()) else {
+            continue;
+        };
+        if !matches!(agent.daemon, Some(DaemonConfig::Local(_))) || agent.has_daemon_phases() {
+            return Err(format!(
+                "Agent '{}': restarts need a local daemon binary and no daemon phases",
+                agent_id
+            ));
+        }
+        let mut running_since = agent.start_time.map_or(0, SimDuration::as_secs);
+        for restart in restarts {
+            let stop = restart.stop_at.as_secs();
+            let start = restart.start_at.as_secs();
+            if stop <= running_since {
+                return Err(format!(
+                    "Agent '{}': restart stop_at {} is not after the daemon starts",
+                    agent_id, restart.stop_at
+                ));
+            }
+            if start < stop + MIN_PHASE_GAP_SECONDS {
+                return Err(format!(
+                    "Agent '{}': restart start_at {} must be at least {}s after stop_at {}",
+                    agent_id, restart.start_at, MIN_PHASE_GAP_SECONDS, restart.stop_at
+                ));
+            }
+            running_since = start;
+        }
+    }
+    Ok(())
+}
+
+/// Validate `workload`: its parameters, and that it goes to a non-mining
+/// agent with a wallet and an agent script, instead of
+/// `transaction_interval`.
+pub fn validate_workloads(agents: &BTreeMap<String, AgentConfig>) -> Result<(), String> {
+    for (agent_id, agent) in agents {
+        let Some(workload) = &agent.workload else {
+            continue;
+        };
+        workload
+            .validate()
+            .map_err(|e| format!("Agent '{}' workload: {}", agent_id, e))?;
+        if !agent.has_wallet() || agent.is_miner() || agent.script.as_deref() == Some("") {
+            return Err(format!(
+                "Agent '{}': a workload needs a wallet and an agent script, and no hashrate",
+                agent_id
+            ));
+        }
+        if agent.transaction_interval.is_some() {
+            return Err(format!(
+                "Agent '{}': set either workload or transaction_interval, not both",
+                agent_id
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Reject daemon flags monerosim manages itself (see `MANAGED_DAEMON_FLAGS`)
+/// in `daemon_defaults`, per-agent `daemon_options` (alias
+/// `daemon_arg_overrides`), `daemon_args` and daemon phase args.
+pub fn validate_daemon_args(
+    daemon_defaults: Option<&BTreeMap<String, OptionValue>>,
+    agents: &BTreeMap<String, AgentConfig>,
+) -> Result<(), String> {
+    let managed = MANAGED_DAEMON_FLAGS;
+    reject_managed_flags(managed, "general.daemon_defaults", daemon_defaults, None)?;
+    for (agent_id, agent) in agents {
+        let origin = |what: &str| format!("Agent '{}' {}", agent_id, what);
+        reject_managed_flags(
+            managed,
+            &origin("daemon_options"),
+            agent.daemon_options.as_ref(),
+            None,
+        )?;
+        reject_managed_flags(
+            managed,
+            &origin("daemon_args"),
+            None,
+            agent.daemon_args.as_ref(),
+        )?;
+        for (n, phase) in agent.daemon_phases.iter().flatten() {
+            reject_managed_flags(
+                managed,
+                &origin(&format!("daemon_{}_args", n)),
+                None,
+                phase.args.as_ref(),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Wallet counterpart of `validate_daemon_args` (see `MANAGED_WALLET_FLAGS`)
+pub fn validate_wallet_args(
+    wallet_defaults: Option<&BTreeMap<String, OptionValue>>,
+    agents: &BTreeMap<String, AgentConfig>,
+) -> Result<(), String> {
+    let managed = MANAGED_WALLET_FLAGS;
+    reject_managed_flags(managed, "general.wallet_defaults", wallet_defaults, None)?;
+    for (agent_id, agent) in agents {
+        let origin = |what: &str| format!("Agent '{}' {}", agent_id, what);
+        reject_managed_flags(
+            managed,
+            &origin("wallet_options"),
+            agent.wallet_options.as_ref(),
+            None,
+        )?;
+        reject_managed_flags(
+            managed,
+            &origin("wallet_args"),
+            None,
+            agent.wallet_args.as_ref(),
+        )?;
+        for (n, phase) in agent.wallet_phases.iter().flatten() {
+            reject_managed_flags(
+                managed,
+                &origin(&format!("wallet_{}_args", n)),
+                None,
+                phase.args.as_ref(),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Check that every agent `script` exists under `agents_path`, reporting all
+/// missing scripts at once rather than leaving them to fail inside Shadow.
+///
+/// Module form (dots, no slashes, as run with `python3 -m`) needs
+/// `a/b.py` or a package `a/b/__main__.py`; path form needs a readable file,
+/// relative paths resolving against `agents_path`.
+pub fn validate_agent_scripts(
+    agents: &BTreeMap<String, AgentConfig>,
+    agents_path: &Path,
+) -> Result<(), String> {
+    let mut scripts: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (agent_id, agent) in agents {
+        if let Some(script) = agent.script.as_deref().filter(|s| !s.is_empty()) {
+            scripts.entry(script).or_default().push(agent_id);
+        }
+    }
+    let problems: Vec<String> = scripts
+        .iter()
+        .filter_map(|(script, users)| {
+            check_agent_script(script, agents_path)
+                .err()
+                .map(|e| format!("{} (used by {})", e, users.join(", ")))
+        })
+        .collect();
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} agent script(s) not found under '{}':\n  {}",
+            problems.len(),
+            agents_path.display(),
+            problems.join("\n  ")
+        ))
+    }
+}
+
+fn check_agent_script(script: &str, agents_path: &Path) -> Result<(), String> {
+    if script.contains('.') && !script.contains('/') && !script.contains('\\') {
+        let module: PathBuf = script.split('.').collect();
+        let candidates = [
+            agents_path.join(&module).with_extension("py"),
+            agents_path.join(&module).join("__main__.py"),
+        ];
+        if candidates.iter().any(|c| c.is_file()) {
+            return Ok(());
+        }
+        return Err(format!(
+            "module '{}': no {} or {}",
+            script,
+            module.with_extension("py").display(),
+            module.join("__main__.py").display()
+        ));
+    }
+    let path = agents_path.join(script);
+    if !path.is_file() {
+        return Err(format!("script '{}': no such file", script));
+    }
+    File::open(&path)
+        .map(|_| ())
+        .map_err(|e| format!("script '{}': not readable: {}", script, e))
+}
+
+/// Error naming `origin` and the flag if any option key or `--flag` arg is
+/// in `managed`
+fn reject_managed_flags(
+    managed: &[&str],
+    origin: &str,
+    options: Option<&BTreeMap<String, OptionValue>>,
+    args: Option<&Vec<String>>,
+) -> Result<(), String> {
+    let flags = options
+        .into_iter()
+        .flat_map(|o| o.keys().map(String::as_str))
+        .chain(args.into_iter().flatten().filter_map(|a| flag_name(a)));
+    for flag in flags {
+        if managed.contains(&flag) {
+            let hint = if flag.ends_with("-bind-port") {
+                "; set the agent's p2p_port, rpc_port or wallet_rpc_port instead"
+            } else if matches!(flag, "regtest" | "testnet" | "stagenet") {
+                "; set general.chain instead"
+            } else {
+                ""
+            };
+            return Err(format!(
+                "{} sets --{}, which monerosim manages itself{}",
+                origin, flag, hint
+            ));
+        }
+    }
+    Ok(())
+}
+
 /// Validate IP address diversity for Monero P2P compatibility.
 ///
 /// Monero's P2P layer has anti-Sybil protections that limit connections:
@@ -387,8 +1000,11 @@ pub fn validate_ip_subnet_diversity(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{DaemonConfig, DaemonSelectionStrategy};
-    use crate::gml_parser::{GmlGraph, GmlNode};
+    use crate::config::{
+        DaemonPhase, DaemonRestart, DaemonSelectionStrategy, Hashrate, UserAgentAttributes,
+        WorkloadProfile,
+    };
+    use crate::gml_parser::{GmlEdge, GmlGraph, GmlNode};
     use std::collections::HashMap;
 
     /// Helper to create a BTreeMap with a single agent
@@ -398,6 +1014,10 @@ mod tests {
         map
     }
 
+    fn time(s: &str) -> SimDuration {
+        s.parse().unwrap()
+    }
+
     /// Helper to create a minimal AgentConfig
     fn base_agent() -> AgentConfig {
         AgentConfig {
@@ -407,9 +1027,12 @@ mod tests {
             daemon_options: None,
             wallet_options: None,
             start_time: None,
+            shutdown_time: None,
+            lifetime: None,
             hashrate: None,
             transaction_interval: None,
             activity_start_time: None,
+            workload: None,
             can_receive_distributions: None,
             wait_time: None,
             initial_fund_amount: None,
@@ -422,6 +1045,8 @@ mod tests {
             status_file: None,
             enable_alerts: None,
             detailed_logging: None,
+            metrics: None,
+            output_format: None,
             daemon_phases: None,
             wallet_phases: None,
             daemon_args: None,
@@ -430,6 +1055,91 @@ mod tests {
             wallet_env: None,
             attributes: None,
             subnet_group: None,
+            bandwidth_down: None,
+            bandwidth_up: None,
+            ip_version: None,
+            p2p_port: None,
+            rpc_port: None,
+            wallet_rpc_port: None,
+            out_peers: None,
+            in_peers: None,
+            discovery: None,
+            placement: None,
+            colocate_with: None,
+            memory_limit: None,
+            cpu_weight: None,
+            capture_pcap: None,
+            restarts: None,
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_gml_units() {
+        let attrs = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>()
+        };
+        let mut graph = GmlGraph {
+            nodes: vec![GmlNode {
+                id: 0,
+                label: None,
+                ip: None,
+                region: None,
+                attributes: attrs(&[("AS", "7"), ("bandwidth", "1Gbit")]),
+            }],
+            edges: vec![GmlEdge {
+                source: 0,
+                target: 0,
+                attributes: attrs(&[
+                    ("latency", "20ms"),
+                    ("jitter", "500 us"),
+                    ("bandwidth", "500 Mbps"),
+                    ("packet_loss", "1.5%"),
+                ]),
+            }],
+            attributes: HashMap::new(),
+        };
+        canonicalize_gml_units(&mut graph).unwrap();
+        assert_eq!(
+            graph.nodes[0].attributes,
+            attrs(&[("AS", "7"), ("bandwidth", "1 Gbit")])
+        );
+        assert_eq!(
+            graph.edges[0].attributes,
+            attrs(&[
+                ("latency", "20 ms"),
+                ("jitter", "500 us"),
+                ("bandwidth", "500 Mbit"),
+                ("packet_loss", "0.015"),
+            ])
+        );
+
+        for (target, key, value, expected) in [
+            (
+                None,
+                "bandwidth",
+                "1G",
+                "Node 0 attribute 'bandwidth': Invalid bandwidth '1G'",
+            ),
+            (
+                Some(0),
+                "latency",
+                "20",
+                "Edge 0 -> 0 attribute 'latency': Invalid time '20'",
+            ),
+            (Some(0), "latency", "0ms", "must be above zero"),
+            (Some(0), "packet_loss", "5", "Invalid packet loss '5'"),
+        ] {
+            let mut bad = graph.clone();
+            let map = match target {
+                None => &mut bad.nodes[0].attributes,
+                Some(i) => &mut bad.edges[i].attributes,
+            };
+            map.insert(key.to_string(), value.to_string());
+            let err = canonicalize_gml_units(&mut bad).unwrap_err();
+            assert!(err.contains(expected), "{}", err);
         }
     }
 
@@ -497,6 +1207,20 @@ mod tests {
         assert!(validate_topology_config(&Topology::Star, 1).is_err());
         assert!(validate_topology_config(&Topology::Dag, 0).is_ok());
         assert!(validate_topology_config(&Topology::Dag, 100).is_ok());
+        assert!(validate_topology_config(&Topology::Tree { fanout: 2 }, 3).is_ok());
+        assert!(validate_topology_config(&Topology::Tree { fanout: 32 }, 1000).is_ok());
+        assert!(validate_topology_config(&Topology::Tree { fanout: 2 }, 2).is_err());
+        assert!(validate_topology_config(&Topology::Tree { fanout: 1 }, 10).is_err());
+        assert!(validate_topology_config(&Topology::Tree { fanout: 33 }, 100).is_err());
+
+        let small_world = |k, rewire_prob| Topology::SmallWorld { k, rewire_prob };
+        assert!(validate_topology_config(&small_world(4, 0.1), 5).is_ok());
+        assert!(validate_topology_config(&small_world(4, 0.0), 100).is_ok());
+        assert!(validate_topology_config(&small_world(4, 1.0), 4).is_err());
+        assert!(validate_topology_config(&small_world(3, 0.1), 100).is_err());
+        assert!(validate_topology_config(&small_world(0, 0.1), 100).is_err());
+        assert!(validate_topology_config(&small_world(4, 1.5), 100).is_err());
+        assert!(validate_topology_config(&small_world(4, f64::NAN), 100).is_err());
     }
 
     // Tests for validate_mining_config
@@ -507,11 +1231,15 @@ mod tests {
             daemon: Some(DaemonConfig::Local("monerod".to_string())),
             wallet: Some("monero-wallet-rpc".to_string()),
             script: Some("agents.autonomous_miner".to_string()),
-            hashrate: Some(100),
+            hashrate: Some(100.into()),
             ..base_agent()
         };
 
-        assert!(validate_mining_config(&single_agent("miner-001", agent)).is_ok());
+        assert!(validate_mining_config(
+            &single_agent("miner-001", agent),
+            HashrateDistribution::Explicit
+        )
+        .is_ok());
     }
 
     #[test]
@@ -520,11 +1248,14 @@ mod tests {
             daemon: Some(DaemonConfig::Local("monerod".to_string())),
             wallet: None,
             script: Some("agents.autonomous_miner".to_string()),
-            hashrate: Some(50),
+            hashrate: Some(50.into()),
             ..base_agent()
         };
 
-        let result = validate_mining_config(&single_agent("miner-001", agent));
+        let result = validate_mining_config(
+            &single_agent("miner-001", agent),
+            HashrateDistribution::Explicit,
+        );
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("must have 'wallet' field"));
     }
@@ -542,7 +1273,11 @@ mod tests {
         };
 
         // Should pass - not identified as a miner without hashrate
-        assert!(validate_mining_config(&single_agent("distributor-001", agent)).is_ok());
+        assert!(validate_mining_config(
+            &single_agent("distributor-001", agent),
+            HashrateDistribution::Explicit
+        )
+        .is_ok());
     }
 
     #[test]
@@ -550,11 +1285,14 @@ mod tests {
         let agent = AgentConfig {
             daemon: Some(DaemonConfig::Local("monerod".to_string())),
             wallet: Some("monero-wallet-rpc".to_string()),
-            hashrate: Some(0),
+            hashrate: Some(0.into()),
             ..base_agent()
         };
 
-        let result = validate_mining_config(&single_agent("miner-001", agent));
+        let result = validate_mining_config(
+            &single_agent("miner-001", agent),
+            HashrateDistribution::Explicit,
+        );
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("out of valid range"));
     }
@@ -564,15 +1302,43 @@ mod tests {
         let agent = AgentConfig {
             daemon: Some(DaemonConfig::Local("monerod".to_string())),
             wallet: Some("monero-wallet-rpc".to_string()),
-            hashrate: Some(150),
+            hashrate: Some(150.into()),
             ..base_agent()
         };
 
-        let result = validate_mining_config(&single_agent("miner-001", agent));
+        let result = validate_mining_config(
+            &single_agent("miner-001", agent),
+            HashrateDistribution::Explicit,
+        );
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("out of valid range"));
     }
 
+    #[test]
+    fn test_validate_mining_config_percentages_and_auto() {
+        let miner = |hashrate: &str| AgentConfig {
+            daemon: Some(DaemonConfig::Local("monerod".to_string())),
+            wallet: Some("monero-wallet-rpc".to_string()),
+            hashrate: Some(Hashrate::parse(hashrate).unwrap()),
+            ..base_agent()
+        };
+        let agents = |a: &str, b: &str| {
+            BTreeMap::from([
+                ("miner-001".to_string(), miner(a)),
+                ("miner-002".to_string(), miner(b)),
+            ])
+        };
+        let explicit = HashrateDistribution::Explicit;
+        assert!(validate_mining_config(&agents("75%", "25%"), explicit).is_ok());
+        let err = validate_mining_config(&agents("75%", "35%"), explicit).unwrap_err();
+        assert!(err.contains("sum to 110%"), "{}", err);
+        let err = validate_mining_config(&agents("75%", "auto"), explicit).unwrap_err();
+        assert!(err.contains("'miner-002'"), "{}", err);
+        assert!(
+            validate_mining_config(&agents("75%", "auto"), HashrateDistribution::Equal).is_ok()
+        );
+    }
+
     #[test]
     fn test_validate_mining_config_skips_non_miners() {
         // Non-mining agent without hashrate should be skipped
@@ -583,7 +1349,11 @@ mod tests {
             ..base_agent()
         };
 
-        assert!(validate_mining_config(&single_agent("user-001", agent)).is_ok());
+        assert!(validate_mining_config(
+            &single_agent("user-001", agent),
+            HashrateDistribution::Explicit
+        )
+        .is_ok());
     }
 
     // Tests for validate_agent_daemon_config
@@ -602,12 +1372,12 @@ mod tests {
 
     #[test]
     fn test_validate_agent_daemon_config_daemon_only() {
-        let mut attrs = BTreeMap::new();
-        attrs.insert("is_public_node".to_string(), "true".to_string());
-
         let agent = AgentConfig {
             daemon: Some(DaemonConfig::Local("monerod".to_string())),
-            attributes: Some(attrs),
+            attributes: Some(UserAgentAttributes {
+                is_public_node: true,
+                ..Default::default()
+            }),
             ..base_agent()
         };
 
@@ -616,12 +1386,12 @@ mod tests {
 
     #[test]
     fn test_validate_agent_daemon_config_wallet_only_with_public_node() {
-        let mut pub_attrs = BTreeMap::new();
-        pub_attrs.insert("is_public_node".to_string(), "true".to_string());
-
         let public_node = AgentConfig {
             daemon: Some(DaemonConfig::Local("monerod".to_string())),
-            attributes: Some(pub_attrs),
+            attributes: Some(UserAgentAttributes {
+                is_public_node: true,
+                ..Default::default()
+            }),
             ..base_agent()
         };
 
@@ -657,6 +1427,36 @@ mod tests {
         assert!(validate_agent_daemon_config(&single_agent("wallet-001", agent)).is_ok());
     }
 
+    #[test]
+    fn test_validate_agent_daemon_config_wallet_only_names_public_node() {
+        let daemon = |is_public_node| AgentConfig {
+            daemon: Some(DaemonConfig::Local("monerod".to_string())),
+            attributes: Some(UserAgentAttributes {
+                is_public_node,
+                ..Default::default()
+            }),
+            ..base_agent()
+        };
+        let wallet_only = AgentConfig {
+            daemon: Some(DaemonConfig::Remote {
+                address: "node-001".to_string(),
+                strategy: None,
+            }),
+            wallet: Some("monero-wallet-rpc".to_string()),
+            ..base_agent()
+        };
+        let with = |node: AgentConfig| {
+            let mut agents = single_agent("wallet-001", wallet_only.clone());
+            agents.insert("node-001".to_string(), node);
+            validate_agent_daemon_config(&agents)
+        };
+
+        assert!(with(daemon(true)).is_ok());
+        assert!(with(daemon(false))
+            .unwrap_err()
+            .contains("remote daemon 'node-001' must be an agent"));
+    }
+
     #[test]
     fn test_validate_agent_daemon_config_script_only() {
         let agent = AgentConfig {
@@ -675,7 +1475,7 @@ mod tests {
                 strategy: None,
             }),
             wallet: Some("monero-wallet-rpc".to_string()),
-            hashrate: Some(100),
+            hashrate: Some(100.into()),
             ..base_agent()
         };
 
@@ -688,12 +1488,12 @@ mod tests {
 
     #[test]
     fn test_validate_agent_daemon_config_public_node_requires_daemon() {
-        let mut attrs = BTreeMap::new();
-        attrs.insert("is_public_node".to_string(), "true".to_string());
-
         let agent = AgentConfig {
             script: Some("agents.monitor".to_string()),
-            attributes: Some(attrs),
+            attributes: Some(UserAgentAttributes {
+                is_public_node: true,
+                ..Default::default()
+            }),
             ..base_agent()
         };
 
@@ -746,4 +1546,356 @@ mod tests {
             .unwrap_err()
             .contains("wallet without local daemon requires remote daemon configuration"));
     }
+
+    #[test]
+    fn test_validate_spy_agents() {
+        let spy = |targets: Option<&str>| AgentConfig {
+            daemon: Some(DaemonConfig::Local("monerod".to_string())),
+            attributes: Some(UserAgentAttributes {
+                is_spy: true,
+                spy_targets: targets.map(str::to_string),
+                ..Default::default()
+            }),
+            ..base_agent()
+        };
+        let with = |spy: AgentConfig| {
+            let mut agents = single_agent("spy", spy);
+            agents.insert(
+                "user-1".to_string(),
+                AgentConfig {
+                    daemon: Some(DaemonConfig::Local("monerod".to_string())),
+                    ..base_agent()
+                },
+            );
+            validate_spy_agents(&agents)
+        };
+
+        assert!(with(spy(None)).is_ok());
+        assert!(with(spy(Some("all_seeds"))).is_ok());
+        assert!(with(spy(Some("random:2"))).is_ok());
+        assert!(with(spy(Some("user-1"))).is_ok());
+        assert!(with(spy(Some("random:0")))
+            .unwrap_err()
+            .contains("positive count"));
+        assert!(with(spy(Some("user-1,user-9")))
+            .unwrap_err()
+            .contains("spy target 'user-9'"));
+        assert!(with(spy(Some("spy"))).unwrap_err().contains("spy itself"));
+        assert!(with(AgentConfig {
+            wallet: Some("monero-wallet-rpc".to_string()),
+            ..spy(None)
+        })
+        .unwrap_err()
+        .contains("daemon-only"));
+
+        let mut not_spy = spy(Some("all_seeds"));
+        not_spy.attributes.as_mut().unwrap().is_spy = false;
+        assert!(with(not_spy).unwrap_err().contains("requires is_spy"));
+    }
+
+    #[test]
+    fn test_validate_agent_attributes() {
+        let agent = |key: &str, value: &str| AgentConfig {
+            attributes: Some(UserAgentAttributes {
+                extra: BTreeMap::from([(key.to_string(), value.to_string())]),
+                ..Default::default()
+            }),
+            ..base_agent()
+        };
+        let check = |key: &str, value: &str| {
+            validate_agent_attributes(&single_agent("a", agent(key, value)))
+        };
+        assert!(check("location", "New York; $(id) 'x'").is_ok());
+        assert!(check("città", "東京\nline two").is_ok());
+        for key in ["", "two words", "tab\tkey", "bell\u{7}", "--flag"] {
+            let err = check(key, "v").unwrap_err();
+            assert!(err.contains("attribute key"), "{}", err);
+        }
+        assert!(check("key", "nul\0byte").unwrap_err().contains("NUL"));
+    }
+
+    #[test]
+    fn test_validate_ip_versions() {
+        let agent = |ip_version| AgentConfig {
+            daemon: Some(DaemonConfig::Local("monerod".to_string())),
+            ip_version,
+            ..base_agent()
+        };
+        assert!(validate_ip_versions(&single_agent("a", agent(None))).is_ok());
+        assert!(validate_ip_versions(&single_agent("a", agent(Some(IpVersion::V4)))).is_ok());
+        for version in [IpVersion::V6, IpVersion::Dual] {
+            let err = validate_ip_versions(&single_agent("a", agent(Some(version)))).unwrap_err();
+            assert!(err.contains("only simulates IPv4"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_validate_unreachable_agents() {
+        let agent = |daemon: bool, hashrate| AgentConfig {
+            daemon: daemon.then(|| DaemonConfig::Local("monerod".to_string())),
+            wallet: Some("monero-wallet-rpc".to_string()),
+            hashrate,
+            attributes: Some(UserAgentAttributes {
+                is_unreachable: true,
+                ..Default::default()
+            }),
+            ..base_agent()
+        };
+        assert!(validate_unreachable_agents(&single_agent("a", agent(true, None))).is_ok());
+        for bad in [agent(false, None), agent(true, Some(10.into()))] {
+            let err = validate_unreachable_agents(&single_agent("a", bad)).unwrap_err();
+            assert!(err.contains("is_unreachable requires"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_validate_colocation() {
+        let daemon = || AgentConfig {
+            daemon: Some(DaemonConfig::Local("monerod".to_string())),
+            ..base_agent()
+        };
+        let guest = |host: &str| AgentConfig {
+            colocate_with: Some(host.to_string()),
+            ..daemon()
+        };
+        let mut agents = single_agent("host", daemon());
+        agents.insert("guest".to_string(), guest("host"));
+        assert!(validate_colocation(&agents).is_ok());
+
+        agents.insert(
+            "guest".to_string(),
+            AgentConfig {
+                bandwidth_up: Some("10 Mbit".to_string()),
+                ..guest("host")
+            },
+        );
+        let err = validate_colocation(&agents).unwrap_err();
+        assert!(err.contains("bandwidth_up belongs to the host"), "{}", err);
+        agents.insert(
+            "guest".to_string(),
+            AgentConfig {
+                cpu_weight: Some(50),
+                ..guest("host")
+            },
+        );
+        let err = validate_colocation(&agents).unwrap_err();
+        assert!(err.contains("cpu_weight belongs to the host"), "{}", err);
+
+        agents.insert("guest".to_string(), guest("guest"));
+        let err = validate_colocation(&agents).unwrap_err();
+        assert!(err.contains("not another agent"), "{}", err);
+
+        agents.insert(
+            "script".to_string(),
+            AgentConfig {
+                script: Some("agents.monitor".to_string()),
+                ..base_agent()
+            },
+        );
+        agents.insert("guest".to_string(), guest("script"));
+        let err = validate_colocation(&agents).unwrap_err();
+        assert!(err.contains("run a daemon or wallet"), "{}", err);
+    }
+
+    #[test]
+    fn test_validate_process_limits() {
+        let mut general = GeneralConfig::default();
+        let agents = single_agent(
+            "a",
+            AgentConfig {
+                memory_limit: Some("512 MiB".to_string()),
+                ..base_agent()
+            },
+        );
+        assert!(validate_process_limits(&agents, &general).is_ok());
+
+        general.cpu_weight = Some(150);
+        let err = validate_process_limits(&agents, &general).unwrap_err();
+        assert!(err.starts_with("Agent 'a': cpu_weight 150"), "{}", err);
+    }
+
+    #[test]
+    fn test_validate_subnet_groups() {
+        let member = |group: &str| AgentConfig {
+            subnet_group: Some(group.to_string()),
+            ..base_agent()
+        };
+        let mut agents: BTreeMap<String, AgentConfig> = (0..SUBNET_GROUP_HOSTS)
+            .map(|i| (format!("sybil-{}", i), member("sybil")))
+            .collect();
+        assert!(validate_subnet_groups(&agents).is_ok());
+
+        agents.insert("sybil-extra".to_string(), member("sybil"));
+        let err = validate_subnet_groups(&agents).unwrap_err();
+        assert!(err.contains("'sybil' has 246 agents"), "{}", err);
+        assert!(err.contains("1 short"), "{}", err);
+
+        let agents: BTreeMap<String, AgentConfig> = (0..=MAX_SUBNET_GROUPS)
+            .map(|i| (format!("a{}", i), member(&format!("g{}", i))))
+            .collect();
+        let err = validate_subnet_groups(&agents).unwrap_err();
+        assert!(err.starts_with("257 subnet groups"), "{}", err);
+    }
+
+    #[test]
+    fn test_validate_agent_schedule() {
+        let agent = |start: Option<&str>, shutdown: &str| AgentConfig {
+            start_time: start.map(time),
+            shutdown_time: Some(time(shutdown)),
+            ..base_agent()
+        };
+        assert!(
+            validate_agent_schedule(&single_agent("a", agent(Some("30m"), "1h")), time("2h"))
+                .is_ok()
+        );
+        assert!(validate_agent_schedule(&single_agent("a", agent(None, "2h")), time("2h")).is_ok());
+
+        let err = validate_agent_schedule(&single_agent("a", agent(Some("1h"), "30m")), time("2h"))
+            .unwrap_err();
+        assert!(err.contains("is not after start time 1h"), "{}", err);
+        let err =
+            validate_agent_schedule(&single_agent("a", agent(None, "3h")), time("2h")).unwrap_err();
+        assert!(err.contains("after general.stop_time"), "{}", err);
+
+        let lifetime = |start: Option<&str>, lifetime: &str| AgentConfig {
+            start_time: start.map(time),
+            lifetime: Some(time(lifetime)),
+            ..base_agent()
+        };
+        for within in ["90m", "7200s", "2h"] {
+            let agents = single_agent("a", lifetime(Some("10m"), within));
+            assert!(
+                validate_agent_schedule(&agents, time("2h")).is_ok(),
+                "{}",
+                within
+            );
+        }
+        let err = validate_agent_schedule(&single_agent("a", lifetime(None, "121m")), time("2h"))
+            .unwrap_err();
+        assert!(
+            err.contains("lifetime 121m exceeds general.stop_time 2h"),
+            "{}",
+            err
+        );
+        let err =
+            validate_agent_schedule(&single_agent("a", lifetime(Some("1h"), "1h")), time("2h"))
+                .unwrap_err();
+        assert!(
+            err.contains("lifetime 1h is not after start time 1h"),
+            "{}",
+            err
+        );
+
+        let phased = AgentConfig {
+            daemon_phases: Some(BTreeMap::from([(
+                0,
+                DaemonPhase {
+                    path: "monerod".to_string(),
+                    start: Some(time("90m")),
+                    ..Default::default()
+                },
+            )])),
+            ..agent(None, "1h")
+        };
+        let err = validate_agent_schedule(&single_agent("a", phased), time("2h")).unwrap_err();
+        assert!(err.contains("is not after start time 90m"), "{}", err);
+    }
+
+    #[test]
+    fn test_validate_daemon_restarts() {
+        let restart = |stop: &str, start: &str| DaemonRestart {
+            stop_at: time(stop),
+            start_at: time(start),
+            ..Default::default()
+        };
+        let agent = |restarts: Vec<DaemonRestart>| AgentConfig {
+            daemon: Some(DaemonConfig::Local("monerod".to_string())),
+            start_time: Some(time("10m")),
+            restarts: Some(restarts),
+            ..base_agent()
+        };
+        let ok = agent(vec![restart("1h", "70m"), restart("2h", "130m")]);
+        assert!(validate_daemon_restarts(&single_agent("a", ok)).is_ok());
+
+        let overlapping = agent(vec![restart("1h", "70m"), restart("65m", "2h")]);
+        let err = validate_daemon_restarts(&single_agent("a", overlapping)).unwrap_err();
+        assert!(err.contains("stop_at 65m is not after"), "{}", err);
+        let err = validate_daemon_restarts(&single_agent("a", agent(vec![restart("5m", "1h")])))
+            .unwrap_err();
+        assert!(err.contains("stop_at 5m is not after"), "{}", err);
+        let err = validate_daemon_restarts(&single_agent("a", agent(vec![restart("1h", "1h")])))
+            .unwrap_err();
+        assert!(err.contains("at least 30s after"), "{}", err);
+        let remote = AgentConfig {
+            daemon: None,
+            ..agent(vec![restart("1h", "70m")])
+        };
+        let err = validate_daemon_restarts(&single_agent("a", remote)).unwrap_err();
+        assert!(err.contains("need a local daemon"), "{}", err);
+    }
+
+    #[test]
+    fn test_validate_workloads() {
+        let agent = |workload: WorkloadProfile| AgentConfig {
+            daemon: Some(DaemonConfig::Local("monerod".to_string())),
+            wallet: Some("monero-wallet-rpc".to_string()),
+            workload: Some(workload),
+            ..base_agent()
+        };
+        let poisson = |rate| WorkloadProfile::Poisson { rate };
+        assert!(validate_workloads(&single_agent("a", agent(poisson(12.0)))).is_ok());
+
+        let err = validate_workloads(&single_agent("a", agent(poisson(0.0)))).unwrap_err();
+        assert!(err.contains("workload: poisson rate"), "{}", err);
+        let both = AgentConfig {
+            transaction_interval: Some(60),
+            ..agent(poisson(12.0))
+        };
+        let err = validate_workloads(&single_agent("a", both)).unwrap_err();
+        assert!(err.contains("not both"), "{}", err);
+        let miner = AgentConfig {
+            hashrate: Some(10.into()),
+            ..agent(poisson(12.0))
+        };
+        let err = validate_workloads(&single_agent("a", miner)).unwrap_err();
+        assert!(err.contains("needs a wallet"), "{}", err);
+    }
+
+    #[test]
+    fn test_validate_agent_scripts() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("agents/miner_distributor")).unwrap();
+        std::fs::write(dir.path().join("agents/regular_user.py"), "").unwrap();
+        std::fs::write(dir.path().join("agents/miner_distributor/__main__.py"), "").unwrap();
+        std::fs::create_dir_all(dir.path().join("scripts")).unwrap();
+        std::fs::write(dir.path().join("scripts/run.py"), "").unwrap();
+        let agent = |script: &str| AgentConfig {
+            script: Some(script.to_string()),
+            ..base_agent()
+        };
+
+        let mut agents = BTreeMap::new();
+        agents.insert("user-001".to_string(), agent("agents.regular_user"));
+        agents.insert("md".to_string(), agent("agents.miner_distributor"));
+        agents.insert("custom".to_string(), agent("scripts/run.py"));
+        agents.insert("relay".to_string(), base_agent());
+        assert!(validate_agent_scripts(&agents, dir.path()).is_ok());
+
+        agents.insert("user-002".to_string(), agent("agents.regular_usr"));
+        agents.insert("user-003".to_string(), agent("agents.regular_usr"));
+        agents.insert("other".to_string(), agent("scripts/missing.py"));
+        let err = validate_agent_scripts(&agents, dir.path()).unwrap_err();
+        assert!(err.starts_with("2 agent script(s) not found"), "{}", err);
+        assert!(
+            err.contains("module 'agents.regular_usr': no agents/regular_usr.py")
+                && err.contains("(used by user-002, user-003)"),
+            "{}",
+            err
+        );
+        assert!(
+            err.contains("script 'scripts/missing.py': no such file (used by other)"),
+            "{}",
+            err
+        );
+    }
 }
@@ -0,0 +1,247 @@
+//! Warm starts from a previously synced chain (`fresh_blockchain: false`).
+//!
+//! `general.blockchain_source_dir` holds either one data directory per
+//! daemon (`monero-{agent_id}/`, as an earlier run leaves them in
+//! `daemon_data_dir`) or a single template data directory (`lmdb/` at its
+//! top level) shared by every daemon. Generation copies the matching
+//! directory to each daemon's `{daemon_data_dir}/monero-{agent_id}`.
+//! Without a source the existing data directories are kept in place.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::validation_report::ValidationReport;
+
+/// How `blockchain_source_dir` is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainSourceLayout {
+    /// `monero-{agent_id}/` per daemon
+    PerAgent,
+    /// One data directory whose `lmdb/` is copied to every daemon
+    Template,
+}
+
+/// Data directories to seed before the simulation starts
+#[derive(Debug, Clone, PartialEq)]
+pub struct WarmStartPlan {
+    pub source: PathBuf,
+    pub layout: ChainSourceLayout,
+    /// (source, destination): whole data directories, or the template's
+    /// `lmdb/`. Empty when the source is `daemon_data_dir` itself
+    pub copies: Vec<(PathBuf, PathBuf)>,
+}
+
+/// `{daemon_data_dir}/monero-{agent_id}`
+pub fn daemon_data_path(daemon_data_dir: &Path, agent_id: &str) -> PathBuf {
+    daemon_data_dir.join(format!("monero-{}", agent_id))
+}
+
+/// Match `source` (or `daemon_data_dir` when None) against the daemons
+/// in `daemon_agents`. Fails when the source is missing or holds no chain
+/// data for any of them; daemons left without data and data directories
+/// no daemon uses go to `report`.
+pub fn plan_warm_start(
+    source: Option<&Path>,
+    daemon_data_dir: &Path,
+    daemon_agents: &[&str],
+    report: &mut ValidationReport,
+) -> Result<WarmStartPlan, String> {
+    let in_place = source.is_none();
+    let source = source.unwrap_or(daemon_data_dir);
+    if !source.is_dir() {
+        return Err(format!(
+            "blockchain source '{}' is not a directory",
+            source.display()
+        ));
+    }
+
+    if source.join("lmdb").is_dir() {
+        if in_place {
+            return Err(format!(
+                "daemon_data_dir '{}' holds a template lmdb/; set blockchain_source_dir to copy it",
+                source.display()
+            ));
+        }
+        return Ok(WarmStartPlan {
+            source: source.to_path_buf(),
+            layout: ChainSourceLayout::Template,
+            copies: daemon_agents
+                .iter()
+                .map(|id| {
+                    (
+                        source.join("lmdb"),
+                        daemon_data_path(daemon_data_dir, id).join("lmdb"),
+                    )
+                })
+                .collect(),
+        });
+    }
+
+    let mut available: Vec<String> = fs::read_dir(source)
+        .map_err(|e| {
+            format!(
+                "cannot read blockchain source '{}': {}",
+                source.display(),
+                e
+            )
+        })?
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            name.strip_prefix("monero-").map(str::to_string)
+        })
+        .collect();
+    available.sort();
+
+    let (covered, missing): (Vec<&str>, Vec<&str>) = daemon_agents
+        .iter()
+        .partition(|id| available.iter().any(|a| a == *id));
+    if covered.is_empty() {
+        return Err(format!(
+            "blockchain source '{}' holds neither lmdb/ nor a monero-<agent_id> data directory for any of the {} daemon(s)",
+            source.display(),
+            daemon_agents.len()
+        ));
+    }
+    if !missing.is_empty() {
+        report.warn(
+            "warm_start",
+            format!(
+                "{} of {} daemon(s) have no data directory in '{}' and will sync from genesis: {}",
+                missing.len(),
+                daemon_agents.len(),
+                source.display(),
+                missing.join(", ")
+            ),
+        );
+    }
+    let unused: Vec<&str> = available
+        .iter()
+        .map(String::as_str)
+        .filter(|a| !daemon_agents.contains(a))
+        .collect();
+    if !unused.is_empty() {
+        report.info(
+            "warm_start",
+            format!(
+                "Data directories in '{}' without a matching daemon are not used: {}",
+                source.display(),
+                unused.join(", ")
+            ),
+        );
+    }
+
+    Ok(WarmStartPlan {
+        source: source.to_path_buf(),
+        layout: ChainSourceLayout::PerAgent,
+        copies: if in_place {
+            Vec::new()
+        } else {
+            covered
+                .iter()
+                .map(|id| {
+                    (
+                        daemon_data_path(source, id),
+                        daemon_data_path(daemon_data_dir, id),
+                    )
+                })
+                .collect()
+        },
+    })
+}
+
+/// Replace each destination with a copy of its source
+pub fn seed_data_dirs(plan: &WarmStartPlan) -> std::io::Result<()> {
+    for (from, to) in &plan.copies {
+        super::remove_dir_with_permissions(to)?;
+        copy_dir(from, to)?;
+    }
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation_report::Severity;
+
+    fn chain_dir(path: &Path) {
+        fs::create_dir_all(path.join("lmdb")).unwrap();
+        fs::write(path.join("lmdb").join("data.mdb"), b"chain").unwrap();
+    }
+
+    #[test]
+    fn per_agent_sources_are_matched_to_daemons() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (source, data) = (tmp.path().join("source"), tmp.path().join("data"));
+        chain_dir(&source.join("monero-miner-001"));
+        chain_dir(&source.join("monero-old-node"));
+        let mut report = ValidationReport::default();
+        let plan = plan_warm_start(
+            Some(&source),
+            &data,
+            &["miner-001", "user-001"],
+            &mut report,
+        )
+        .unwrap();
+        assert_eq!(plan.layout, ChainSourceLayout::PerAgent);
+        assert_eq!(
+            plan.copies,
+            vec![(
+                source.join("monero-miner-001"),
+                data.join("monero-miner-001")
+            )]
+        );
+        assert_eq!(report.count(Severity::Warning), 1);
+        assert_eq!(report.count(Severity::Info), 1);
+
+        seed_data_dirs(&plan).unwrap();
+        assert_eq!(
+            fs::read(data.join("monero-miner-001/lmdb/data.mdb")).unwrap(),
+            b"chain"
+        );
+    }
+
+    #[test]
+    fn templates_are_copied_to_every_daemon() {
+        let tmp = tempfile::tempdir().unwrap();
+        chain_dir(tmp.path());
+        let data = tmp.path().join("data");
+        let mut report = ValidationReport::default();
+        let plan = plan_warm_start(Some(tmp.path()), &data, &["a", "b"], &mut report).unwrap();
+        assert_eq!(plan.layout, ChainSourceLayout::Template);
+        assert_eq!(plan.copies.len(), 2);
+        assert_eq!(report.count(Severity::Warning), 0);
+
+        seed_data_dirs(&plan).unwrap();
+        assert!(data.join("monero-b/lmdb/data.mdb").is_file());
+    }
+
+    #[test]
+    fn missing_or_empty_sources_are_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut report = ValidationReport::default();
+        let missing = tmp.path().join("missing");
+        assert!(plan_warm_start(Some(&missing), tmp.path(), &["a"], &mut report).is_err());
+        assert!(plan_warm_start(None, tmp.path(), &["a"], &mut report).is_err());
+
+        // In place: the existing data directories are kept, nothing copied
+        chain_dir(&tmp.path().join("monero-a"));
+        let plan = plan_warm_start(None, tmp.path(), &["a"], &mut report).unwrap();
+        assert!(plan.copies.is_empty());
+    }
+}
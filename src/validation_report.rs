@@ -0,0 +1,122 @@
+//! `validation_report.json`: findings generation carries on from.
+//!
+//! Planning collects every non-fatal issue (a topology that does not fit the
+//! agent count, miners without weight, configured fields with no effect, ...)
+//! into one `ValidationReport`, logged once after planning and written next
+//! to `shadow_agents.yaml` for CI wrappers. With `general.strict_validation`
+//! (or `--strict`) every warning becomes an error and generation stops
+//! before a broken simulation is written.
+
+use serde::{Deserialize, Serialize};
+
+/// File name of the report in the output directory
+pub const VALIDATION_REPORT_FILE: &str = "validation_report.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// One finding; `category` groups related checks, e.g. `mining`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Issue {
+    pub severity: Severity,
+    pub category: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidationReport {
+    /// Whether warnings were escalated to errors
+    pub strict: bool,
+    pub issues: Vec<Issue>,
+}
+
+impl ValidationReport {
+    pub fn push(&mut self, severity: Severity, category: &str, message: impl Into<String>) {
+        self.issues.push(Issue {
+            severity,
+            category: category.to_string(),
+            message: message.into(),
+        });
+    }
+
+    pub fn info(&mut self, category: &str, message: impl Into<String>) {
+        self.push(Severity::Info, category, message);
+    }
+
+    pub fn warn(&mut self, category: &str, message: impl Into<String>) {
+        self.push(Severity::Warning, category, message);
+    }
+
+    /// Escalate every warning to an error when `strict` is set
+    pub fn apply_strictness(&mut self, strict: bool) {
+        self.strict = strict;
+        if strict {
+            for issue in &mut self.issues {
+                if issue.severity == Severity::Warning {
+                    issue.severity = Severity::Error;
+                }
+            }
+        }
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == severity)
+            .count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+
+    /// Log the issues, most severe first, each at its severity's level
+    pub fn log(&self) {
+        if self.issues.is_empty() {
+            return;
+        }
+        log::info!(
+            "Validation report: {} error(s), {} warning(s), {} note(s)",
+            self.count(Severity::Error),
+            self.count(Severity::Warning),
+            self.count(Severity::Info)
+        );
+        let mut issues = self.issues.iter().collect::<Vec<_>>();
+        issues.sort_by_key(|i| std::cmp::Reverse(i.severity));
+        for issue in issues {
+            let level = match issue.severity {
+                Severity::Info => log::Level::Info,
+                Severity::Warning => log::Level::Warn,
+                Severity::Error => log::Level::Error,
+            };
+            log::log!(level, "[{}] {}", issue.category, issue.message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strictness_escalates_warnings_only() {
+        let mut report = ValidationReport::default();
+        report.info("config", "noted");
+        report.warn("mining", "no miners");
+        assert!(!report.has_errors());
+
+        report.apply_strictness(true);
+        assert!(report.strict && report.has_errors());
+        assert_eq!(report.count(Severity::Error), 1);
+        assert_eq!(report.count(Severity::Info), 1);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["issues"][1]["severity"], "error");
+        assert_eq!(json["issues"][1]["category"], "mining");
+    }
+}
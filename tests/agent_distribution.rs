@@ -0,0 +1,200 @@
+//! `network.geographic_distribution` and `as_weights` shares become agent
+//! counts by largest remainder.
+//!
+//! Switch networks are planned with several user counts and the realized
+//! regions are read back from the host IPs (plain switch) or network nodes
+//! (link-profile switch); GML networks count agents per AS.
+
+use monerosim::gml_parser::write_gml;
+use monerosim::ip::as_manager::AsRegion;
+use monerosim::topology::{generate_topology, DistributionKey, TopologyParams};
+use monerosim::{config_loader, orchestrator};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tempfile::TempDir;
+
+const SHARES: &str = "    Europe: 0.45\n    NorthAmerica: 0.3\n    Asia: 0.15\n    \
+                      oceania: 0.1\n";
+
+fn plan(
+    tmp: &Path,
+    network: &str,
+    users: usize,
+    seed: u64,
+) -> color_eyre::Result<orchestrator::GenerationPlan> {
+    let yaml = format!(
+        "general:\n  stop_time: 1h\n  fallback_seeds: off\n  simulation_seed: {seed}\n  \
+         shared_dir: {shared}\nnetwork:\n{network}agents:\n  user:\n    count: {users}\n    \
+         daemon: monerod\n    wallet: monero-wallet-rpc\n",
+        shared = tmp.join("shared").display(),
+    );
+    let path = tmp.join("config.yaml");
+    std::fs::write(&path, yaml).unwrap();
+    let config = config_loader::load_config(&path)?;
+    orchestrator::plan_agent_shadow_config(&config, &tmp.join("out.yaml"))
+}
+
+/// Users per region key, from `region_of` each user host
+fn realized(
+    plan: &orchestrator::GenerationPlan,
+    region_of: impl Fn(&monerosim::shadow::ShadowHost) -> AsRegion,
+) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for (_, host) in plan
+        .shadow_config
+        .hosts
+        .iter()
+        .filter(|(id, _)| id.starts_with("user-"))
+    {
+        *counts
+            .entry(region_of(host).key().unwrap_or("unknown").to_string())
+            .or_default() += 1;
+    }
+    counts
+}
+
+fn expected(na: usize, eu: usize, asia: usize, oceania: usize) -> BTreeMap<String, usize> {
+    [
+        ("north_america", na),
+        ("europe", eu),
+        ("asia", asia),
+        ("oceania", oceania),
+    ]
+    .into_iter()
+    .filter(|(_, n)| *n > 0)
+    .map(|(k, n)| (k.to_string(), n))
+    .collect()
+}
+
+#[test]
+fn switch_agents_follow_the_geographic_shares() {
+    let tmp = TempDir::new().unwrap();
+    let network = format!("  type: 1_gbit_switch\n  geographic_distribution:\n{SHARES}");
+    for (users, counts) in [
+        (5, expected(2, 2, 1, 0)),
+        (10, expected(3, 5, 1, 1)),
+        (23, expected(7, 10, 4, 2)),
+    ] {
+        let plan = plan(tmp.path(), &network, users, 7).unwrap();
+        let by_ip = realized(&plan, |host| {
+            AsRegion::from_ip(host.ip_addr.as_deref().unwrap())
+        });
+        assert_eq!(by_ip, counts, "{} users", users);
+
+        let distribution = plan.agent_distribution.as_ref().unwrap();
+        assert_eq!(distribution.by, DistributionKey::Region);
+        assert_eq!(distribution.shares["europe"], 0.45);
+        let reported: BTreeMap<String, usize> = distribution
+            .counts
+            .iter()
+            .filter(|(_, n)| **n > 0)
+            .map(|(k, n)| (k.clone(), *n))
+            .collect();
+        assert_eq!(reported, counts);
+    }
+
+    // The seed picks which agents go where; the counts stay
+    let ips = |seed| {
+        plan(tmp.path(), &network, 10, seed)
+            .unwrap()
+            .shadow_config
+            .hosts
+            .into_iter()
+            .map(|(id, host)| (id, host.ip_addr))
+            .collect::<BTreeMap<_, _>>()
+    };
+    assert_eq!(ips(7), ips(7));
+    assert_ne!(ips(7), ips(8));
+}
+
+#[test]
+fn link_profile_agents_land_on_their_regions_node() {
+    let tmp = TempDir::new().unwrap();
+    let network = format!(
+        "  type: 1_gbit_switch\n  link_profile:\n    latency_ms: 50\n  \
+         geographic_distribution:\n{SHARES}"
+    );
+    for (users, counts) in [(5, expected(2, 2, 1, 0)), (23, expected(7, 10, 4, 2))] {
+        let plan = plan(tmp.path(), &network, users, 7).unwrap();
+        let by_node = realized(&plan, |host| AsRegion::all()[host.network_node_id as usize]);
+        assert_eq!(by_node, counts, "{} users", users);
+    }
+}
+
+#[test]
+fn gml_agents_follow_the_as_weights() {
+    let tmp = TempDir::new().unwrap();
+    let graph = generate_topology(&TopologyParams {
+        as_groups: 3,
+        nodes_per_as: 2,
+        intra_latency_ms: (1.0, 5.0),
+        inter_latency_ms: (20.0, 150.0),
+        bandwidth_classes: vec!["1Gbit".to_string()],
+        seed: 7,
+    })
+    .unwrap();
+    let mut ases: Vec<String> = graph
+        .nodes
+        .iter()
+        .map(|n| n.attributes["AS"].clone())
+        .collect();
+    ases.dedup();
+    let gml = tmp.path().join("topology.gml");
+    std::fs::write(&gml, write_gml(&graph)).unwrap();
+    let network = format!(
+        "  path: {}\n  as_weights:\n    \"{}\": 0.6\n    \"{}\": 0.4\n",
+        gml.display(),
+        ases[0],
+        ases[2]
+    );
+    let as_of_node: BTreeMap<u32, String> = graph
+        .nodes
+        .iter()
+        .map(|n| (n.id, n.attributes["AS"].clone()))
+        .collect();
+
+    for (users, first) in [(4, 2), (9, 5), (12, 7)] {
+        let plan = plan(tmp.path(), &network, users, 7).unwrap();
+        let mut per_as: BTreeMap<String, usize> = BTreeMap::new();
+        for (id, host) in &plan.shadow_config.hosts {
+            if id.starts_with("user-") {
+                *per_as
+                    .entry(as_of_node[&host.network_node_id].clone())
+                    .or_default() += 1;
+            }
+        }
+        let want = BTreeMap::from([(ases[0].clone(), first), (ases[2].clone(), users - first)]);
+        assert_eq!(per_as, want, "{} users", users);
+        let distribution = plan.agent_distribution.as_ref().unwrap();
+        assert_eq!(distribution.by, DistributionKey::As);
+        assert_eq!(distribution.counts, want);
+    }
+
+    let missing = format!(
+        "  path: {}\n  as_weights:\n    \"9999\": 1.0\n",
+        gml.display()
+    );
+    let err = plan(tmp.path(), &missing, 3, 7).err().unwrap().to_string();
+    assert!(err.contains("no GML node is in AS 9999"), "{}", err);
+}
+
+#[test]
+fn shares_must_name_regions_and_sum_to_one() {
+    let tmp = TempDir::new().unwrap();
+    for (shares, message) in [
+        ("    europe: 0.5\n    asia: 0.4\n", "must sum to 1.0"),
+        (
+            "    europe: 0.5\n    atlantis: 0.5\n",
+            "unknown region 'atlantis'",
+        ),
+        ("    europe: 1.5\n    asia: -0.5\n", "must be non-negative"),
+    ] {
+        let network = format!("  type: 1_gbit_switch\n  geographic_distribution:\n{shares}");
+        let err = format!("{:?}", plan(tmp.path(), &network, 3, 7).err().unwrap());
+        assert!(err.contains(message), "{}", err);
+    }
+    // Within the tolerance
+    let network = "  type: 1_gbit_switch\n  geographic_distribution:\n    \
+                   europe: 0.3333\n    asia: 0.3333\n    africa: 0.3333\n";
+    assert!(plan(tmp.path(), network, 3, 7).is_ok());
+}
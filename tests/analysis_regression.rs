@@ -0,0 +1,144 @@
+//! Regression baseline for headline analysis metrics.
+//!
+//! Runs the analyzers over `tests/fixtures/analysis_run.json` (built by
+//! `analysis::testutil::regression_run`) and compares a handful of headline
+//! numbers against `tests/baselines/analysis_metrics.json`, each within its
+//! own tolerance. A change in any analyzer that shifts these numbers fails
+//! here instead of silently changing reports.
+//!
+//! When the shift is intended, run with `BLESS=1` to rewrite the fixture and
+//! the baseline values (tolerances already in the baseline are kept), then
+//! review the diff like any other change.
+
+use monerosim::analysis::testutil::{regression_run, SyntheticRun};
+use monerosim::analysis::types::FluffDetection;
+use monerosim::analysis::{
+    analyze_bandwidth, analyze_dandelion, analyze_propagation, analyze_resilience,
+    analyze_spy_vulnerability, DEFAULT_COVERAGE_PERCENTILES,
+};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const FIXTURE: &str = "tests/fixtures/analysis_run.json";
+const BASELINE: &str = "tests/baselines/analysis_metrics.json";
+
+fn headline_metrics(run: &SyntheticRun) -> BTreeMap<&'static str, f64> {
+    let logs = run.log_map();
+    let propagation = analyze_propagation(
+        &run.transactions,
+        &run.blocks,
+        &logs,
+        &run.agents,
+        false,
+        &DEFAULT_COVERAGE_PERCENTILES,
+        None,
+    );
+    let spy = analyze_spy_vulnerability(&run.transactions, &logs, &run.agents, None);
+    let resilience = analyze_resilience(&logs, &run.agents, &[]);
+    let dandelion = analyze_dandelion(
+        &run.transactions,
+        &logs,
+        &run.agents,
+        FluffDetection::default(),
+    );
+    let bandwidth = analyze_bandwidth(&logs, 5);
+
+    BTreeMap::from([
+        ("propagation_median_ms", propagation.median_propagation_ms),
+        ("propagation_average_ms", propagation.average_propagation_ms),
+        (
+            "confirmation_delay_avg_sec",
+            propagation.average_confirmation_delay_sec,
+        ),
+        ("spy_inference_accuracy", spy.inference_accuracy),
+        ("first_seen_gini", resilience.centralization.first_seen_gini),
+        (
+            "average_peer_count",
+            resilience.connectivity.average_peer_count,
+        ),
+        ("avg_stem_length", dandelion.avg_stem_length),
+        ("avg_hop_delay_ms", dandelion.avg_hop_delay_ms),
+        ("bandwidth_total_bytes", bandwidth.total_bytes as f64),
+        ("bandwidth_total_messages", bandwidth.total_messages as f64),
+    ])
+}
+
+/// New metrics get 1% (counts and exact ratios can be tightened by hand)
+fn default_tolerance(value: f64) -> f64 {
+    (value.abs() * 0.01).max(1e-9)
+}
+
+fn bless(generated: &str, metrics: &BTreeMap<&str, f64>) {
+    std::fs::write(FIXTURE, generated).unwrap();
+    let old: Value = std::fs::read_to_string(BASELINE)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(Value::Null);
+    let entries: serde_json::Map<String, Value> = metrics
+        .iter()
+        .map(|(name, value)| {
+            let tolerance = old["metrics"][*name]["tolerance"]
+                .as_f64()
+                .unwrap_or_else(|| default_tolerance(*value));
+            (
+                name.to_string(),
+                json!({"value": value, "tolerance": tolerance}),
+            )
+        })
+        .collect();
+    let baseline = json!({
+        "fixture": FIXTURE,
+        "notes": old["notes"].clone(),
+        "metrics": entries,
+    });
+    std::fs::write(
+        BASELINE,
+        serde_json::to_string_pretty(&baseline).unwrap() + "\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn analysis_metrics_match_baseline() {
+    let generated = serde_json::to_string_pretty(&regression_run()).unwrap() + "\n";
+
+    if std::env::var("BLESS").is_ok() {
+        let run: SyntheticRun = serde_json::from_str(&generated).unwrap();
+        bless(&generated, &headline_metrics(&run));
+        return;
+    }
+
+    let committed = std::fs::read_to_string(FIXTURE).expect("fixture exists (BLESS=1 creates it)");
+    assert!(
+        committed == generated,
+        "{} no longer matches testutil::regression_run(); rerun with BLESS=1 if the change is intended",
+        FIXTURE
+    );
+    let run: SyntheticRun = serde_json::from_str(&committed).expect("fixture parses");
+    let metrics = headline_metrics(&run);
+
+    let baseline: Value =
+        serde_json::from_str(&std::fs::read_to_string(Path::new(BASELINE)).unwrap()).unwrap();
+    let mut failures = Vec::new();
+    for (name, actual) in &metrics {
+        let entry = &baseline["metrics"][*name];
+        match (entry["value"].as_f64(), entry["tolerance"].as_f64()) {
+            (Some(expected), Some(tolerance)) => {
+                if (actual - expected).abs() > tolerance {
+                    failures.push(format!(
+                        "{}: {} vs baseline {} (tolerance {})",
+                        name, actual, expected, tolerance
+                    ));
+                }
+            }
+            _ => failures.push(format!("{}: missing from baseline", name)),
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "analysis metrics drifted from {} (BLESS=1 to accept):\n  {}",
+        BASELINE,
+        failures.join("\n  ")
+    );
+}
@@ -0,0 +1,86 @@
+//! Helpers shared by the orchestrator integration tests: load a config from
+//! inline YAML or the smoke fixture, plan it, and look into the plan.
+#![allow(dead_code)]
+
+use monerosim::config::Config;
+use monerosim::config_loader;
+use monerosim::orchestrator::{self, GenerationPlan};
+use std::path::Path;
+use tempfile::TempDir;
+
+/// The minimal fixture behind `tests/golden/smoke.yaml`
+pub const SMOKE_FIXTURE: &str = "tests/fixtures/smoke.yaml";
+
+/// Load `path` with the shared dir redirected into `tmp/shared`, so the
+/// test never touches the real shared dir
+fn load_into(tmp: &TempDir, path: &Path) -> color_eyre::Result<Config> {
+    config_loader::load_config(path).map(|mut config| {
+        config.general.shared_dir = tmp.path().join("shared").to_string_lossy().to_string();
+        config
+    })
+}
+
+/// Write `yaml` to `tmp/config.yaml` and load it like [`load_fixture`]
+pub fn load_yaml(tmp: &TempDir, yaml: &str) -> color_eyre::Result<Config> {
+    let path = tmp.path().join("config.yaml");
+    std::fs::write(&path, yaml).unwrap();
+    load_into(tmp, &path)
+}
+
+/// The smoke fixture, with its shared dir in `tmp/shared`
+pub fn load_fixture(tmp: &TempDir) -> Config {
+    load_into(tmp, Path::new(SMOKE_FIXTURE)).expect("smoke fixture loads")
+}
+
+/// Plan `config` with `tmp/out.yaml` as the generated Shadow config
+pub fn plan_config(tmp: &TempDir, config: &Config) -> color_eyre::Result<GenerationPlan> {
+    orchestrator::plan_agent_shadow_config(config, &tmp.path().join("out.yaml"))
+}
+
+/// [`load_yaml`] then [`plan_config`]
+pub fn plan_yaml(tmp: &TempDir, yaml: &str) -> color_eyre::Result<GenerationPlan> {
+    plan_config(tmp, &load_yaml(tmp, yaml)?)
+}
+
+/// The planned JSON file whose path ends with `name`
+pub fn planned_json(plan: &GenerationPlan, name: &str) -> serde_json::Value {
+    let (_, json) = plan
+        .files
+        .iter()
+        .find(|(p, _)| p.ends_with(name))
+        .unwrap_or_else(|| panic!("{} planned", name));
+    serde_json::from_str(json).unwrap()
+}
+
+/// The agent's entry in the planned `agent_registry.json`
+pub fn registered(plan: &GenerationPlan, id: &str) -> serde_json::Value {
+    planned_json(plan, "agent_registry.json")["agents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|a| a["id"] == id)
+        .unwrap_or_else(|| panic!("{} registered", id))
+        .clone()
+}
+
+/// The argv bash hands to python for a regular user's wrapper script
+pub fn regular_user_argv(plan: &GenerationPlan, agent_id: &str) -> Vec<String> {
+    let script = plan
+        .scripts
+        .get(&format!("agent_{}_wrapper.sh", agent_id))
+        .unwrap();
+    // Quoted values may span lines, so cut the command out of the whole script
+    let prefix = "exec python3 -m agents.regular_user ";
+    let command = &script[script.find(prefix).unwrap() + prefix.len()..];
+    let command = &command[..command.find(" 2>&1").unwrap()];
+    let output = std::process::Command::new("bash")
+        .arg("-c")
+        .arg(format!("printf '%s\\0' {}", command))
+        .output()
+        .unwrap();
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .split_terminator('\0')
+        .map(str::to_string)
+        .collect()
+}
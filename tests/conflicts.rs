@@ -0,0 +1,134 @@
+//! Conflicting transactions: `conflicts.json` sets are broken down into
+//! per-daemon first-seen order, the split by region, block resolution and
+//! the daemons relaying both sides, and counted per upgrade-analysis window.
+
+use monerosim::analysis::testutil::SyntheticRun;
+use monerosim::analysis::upgrade_analysis::UpgradeAnalysisConfig;
+use monerosim::analysis::{
+    analyze_conflicts, analyze_upgrade_impact, load_conflict_sets, CONFLICTS_FILE,
+};
+use tempfile::TempDir;
+
+const CONFLICTS: &str = r#"[
+  {"conflict_id": "ds-1", "agent_id": "atk", "tx_hashes": ["tx-a", "tx-b"], "timestamp": 9.5},
+  {"tx_hashes": ["tx-c", "tx-d"], "timestamp": 40.0}
+]"#;
+
+/// The attacker sends tx-a towards Europe and tx-b towards North America;
+/// na2 hands tx-b to eu2 and later relays tx-a too. tx-b is mined.
+fn double_spend_run() -> SyntheticRun {
+    let mut run = SyntheticRun::new();
+    for id in ["atk", "eu1", "eu2", "na1", "na2"] {
+        run.agent(id, "agents.regular_user");
+    }
+    for agent in &mut run.agents {
+        agent.continent = match &agent.id[..2] {
+            "eu" => Some("europe".to_string()),
+            "na" => Some("north_america".to_string()),
+            _ => None,
+        };
+    }
+    run.connect("atk", "eu1", 0.0)
+        .connect("atk", "na1", 0.0)
+        .connect("eu1", "eu2", 0.0)
+        .connect("na1", "na2", 0.0)
+        .connect("na2", "eu2", 0.0);
+    run.observe("tx-a", "eu1", "atk", 10.0)
+        .observe("tx-b", "na1", "atk", 10.0)
+        .observe("tx-a", "eu2", "eu1", 11.0)
+        .observe("tx-b", "na2", "na1", 11.0)
+        .observe("tx-b", "eu2", "na2", 12.0)
+        .observe("tx-a", "na2", "eu2", 13.0)
+        .observe("tx-a", "na1", "na2", 14.0);
+    run.block(5, "na1", &["tx-b"], 70.0, 1.0);
+    run
+}
+
+#[test]
+fn conflict_sets_report_order_split_resolution_and_double_relays() {
+    let tmp = TempDir::new().unwrap();
+    let path = tmp.path().join(CONFLICTS_FILE);
+    std::fs::write(&path, CONFLICTS).unwrap();
+    let sets = load_conflict_sets(&path).unwrap();
+    let run = double_spend_run();
+
+    let report = analyze_conflicts(
+        &sets,
+        &run.transactions,
+        &run.blocks,
+        &run.log_map(),
+        &run.agents,
+    );
+    assert_eq!(report.conflict_sets, 2);
+    assert_eq!(report.resolved_sets, 1);
+    assert_eq!(report.split_sets, 1);
+    assert_eq!(report.double_relayers, vec!["atk", "na2"]);
+    assert_eq!(report.avg_resolution_secs, Some(60.5));
+
+    let set = &report.sets[0];
+    assert_eq!(set.conflict_id, "ds-1");
+    assert_eq!(set.first_seen, Some(9.5));
+    assert_eq!(set.nodes_reached, 4);
+    assert_eq!(set.preferred["tx-a"], 2);
+    assert_eq!(set.preferred["tx-b"], 2);
+    let europe = &set.split_by_continent[0];
+    assert_eq!(europe.region, "europe");
+    assert_eq!(europe.preferred.get("tx-a"), Some(&2));
+    assert_eq!(europe.preferred.get("tx-b"), None);
+    assert_eq!(set.split_by_as[0].region, "unknown");
+    // Only na2 - eu2 joins daemons on different sides
+    assert_eq!(set.contested_links, 1);
+    assert_eq!(set.included_tx.as_deref(), Some("tx-b"));
+    assert_eq!(set.included_height, Some(5));
+    assert_eq!(set.resolution_secs, Some(60.5));
+
+    let eu2 = set.per_node.iter().find(|n| n.node_id == "eu2").unwrap();
+    assert_eq!(eu2.first_seen_order, vec!["tx-a", "tx-b"]);
+    assert_eq!(eu2.first_seen_times, vec![11.0, 12.0]);
+    assert_eq!(eu2.preferred_tx, "tx-a");
+    let na2 = set.per_node.iter().find(|n| n.node_id == "na2").unwrap();
+    assert_eq!(na2.preferred_tx, "tx-b");
+    assert_eq!(na2.relayed, vec!["tx-a", "tx-b"]);
+
+    let unseen = &report.sets[1];
+    assert_eq!(unseen.conflict_id, "conflict-1");
+    assert_eq!(unseen.nodes_reached, 0);
+    assert_eq!(unseen.included_tx, None);
+    assert_eq!(unseen.resolution_secs, None);
+}
+
+#[test]
+fn upgrade_windows_count_conflicts_first_seen_in_them() {
+    let tmp = TempDir::new().unwrap();
+    let path = tmp.path().join(CONFLICTS_FILE);
+    std::fs::write(&path, CONFLICTS).unwrap();
+    let run = double_spend_run();
+
+    let analyze = |conflicts_path| {
+        analyze_upgrade_impact(
+            &run.transactions,
+            &run.log_map(),
+            &run.agents,
+            &run.blocks,
+            &UpgradeAnalysisConfig {
+                window_size_sec: 30.0,
+                conflicts_path,
+                ..UpgradeAnalysisConfig::default()
+            },
+            "",
+        )
+        .unwrap()
+    };
+    let report = analyze(Some(path.to_string_lossy().to_string()));
+    let counts: Vec<Option<usize>> = report
+        .time_series
+        .iter()
+        .map(|w| w.conflict_count)
+        .collect();
+    assert_eq!(counts, vec![Some(1), Some(1), None]);
+
+    assert!(analyze(None)
+        .time_series
+        .iter()
+        .all(|w| w.conflict_count.is_none()));
+}
@@ -0,0 +1,32 @@
+# AS relationship sample in the style of CAIDA's as-rel exports.
+# Lines starting with '#' are comments, as are `comment` attributes,
+# which may span several lines.
+graph [
+  comment "Derived from serial-1 as-rel data.
+  Relationships: -1 provider-to-customer, 0 peer-to-peer."
+  directed 1
+  node [
+    id 10
+    comment "tier-1 transit"
+    AS "3356"
+    label "Level3"
+    bandwidth_down "10 Gbit"
+    bandwidth_up "10 Gbit"
+    data [
+      org "LVLT-ARIN"
+      country "US"
+      cone [ size 45000 prefixes 700000 ]
+    ]
+  ]
+  # The peer and customer
+  node [ id 20 AS "1299" label "Arelion \"Telia\"" bandwidth_down "10 Gbit" bandwidth_up "10 Gbit" data [ country "SE" ] ]
+  node [ id 30 AS "64512" label "Stub" bandwidth_down "100 Mbit" bandwidth_up "100 Mbit" ]
+  edge [ source 10 target 20 latency "30 ms" bandwidth "10 Gbit" relationship 0 comment "peers" ]
+  edge [
+    source 10 target 30
+    latency "12 ms"   # trailing comment
+    bandwidth "100 Mbit"
+    relationship -1
+  ]
+  edge [ source 20 target 30 latency "25 ms" bandwidth "100 Mbit" relationship -1 ]
+]
@@ -0,0 +1,71 @@
+Creator "NetworkX 3.2 write_gml"
+Version 1
+graph [
+  directed 0
+  multigraph 0
+  name "Three AS &#34;demo&#34; topology"
+  node [
+    id 0
+    label "core-&quot;a&quot;"
+    AS "65001"
+    bandwidth_down "1 Gbit"
+    bandwidth_up "1 Gbit"
+    region "north_america"
+    graphics [
+      x 12.5
+      y -40.25
+      w 10
+      h 10
+      type "ellipse"
+      fill "#FF0000"
+    ]
+  ]
+  node [
+    id 1
+    label "edge &amp; relay"
+    AS "65002"
+    bandwidth_down "100 Mbit"
+    bandwidth_up "100 Mbit"
+    region "europe"
+    graphics [
+      x 80.0
+      y 12.0
+    ]
+  ]
+  node [
+    id 2
+    label "asia-1"
+    AS "65003"
+    bandwidth_down "100 Mbit"
+    bandwidth_up "50 Mbit"
+    region "asia"
+  ]
+  edge [
+    source 0
+    target 1
+    latency "40 ms"
+    bandwidth "1 Gbit"
+    graphics [
+      width 2
+      Line [
+        point [
+          x 12.5
+          y -40.25
+        ]
+      ]
+    ]
+  ]
+  edge [
+    source 1
+    target 2
+    latency "120 ms"
+    bandwidth "100 Mbit"
+    packet_loss "1%"
+  ]
+  edge [
+    source 0
+    target 2
+    latency "150 ms"
+    bandwidth "100 Mbit"
+  ]
+]
@@ -0,0 +1,89 @@
+// Copyright (c) 2014-2024, The Monero Project
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of
+//    conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list
+//    of conditions and the following disclaimer in the documentation and/or other
+//    materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be
+//    used to endorse or promote products derived from this software without specific
+//    prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY
+// EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL
+// THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT,
+// STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF
+// THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// Trimmed to get_ip_seed_nodes() and a shortened get_dns_seed_nodes() for
+// monerosim's seed extractor tests.
+
+namespace nodetool
+{
+  //-----------------------------------------------------------------------------------
+  template<class t_payload_net_handler>
+  std::set<std::string> node_server<t_payload_net_handler>::get_ip_seed_nodes() const
+  {
+    std::set<std::string> full_addrs;
+    if (m_nettype == cryptonote::TESTNET)
+    {
+      full_addrs.insert("176.9.0.187:28080");
+      full_addrs.insert("88.99.173.38:28080");
+      full_addrs.insert("51.79.173.165:28080");
+    }
+    else if (m_nettype == cryptonote::STAGENET)
+    {
+      full_addrs.insert("176.9.0.187:38080");
+      full_addrs.insert("88.99.173.38:38080");
+      full_addrs.insert("51.79.173.165:38080");
+    }
+    else if (m_nettype == cryptonote::FAKECHAIN)
+    {
+    }
+    else
+    {
+      full_addrs.insert("176.9.0.187:18080");
+      full_addrs.insert("88.198.163.90:18080");
+      //full_addrs.insert("66.85.74.134:18080"); // retired
+      full_addrs.insert("192.99.8.110:18080");
+      full_addrs.insert("37.187.74.171:18080");
+      /* full_addrs.insert("51.79.173.165:18080"); */
+      full_addrs.insert("88.99.195.15:18080");
+      full_addrs.insert("5.104.84.64:18080");
+    }
+    return full_addrs;
+  }
+  //-----------------------------------------------------------------------------------
+  template<class t_payload_net_handler>
+  std::set<std::string> node_server<t_payload_net_handler>::get_dns_seed_nodes()
+  {
+    if (!m_exclusive_peers.empty() || m_offline)
+    {
+      return {};
+    }
+    if (m_nettype == cryptonote::TESTNET)
+    {
+      return get_ip_seed_nodes();
+    }
+    if (m_nettype == cryptonote::STAGENET)
+    {
+      return get_ip_seed_nodes();
+    }
+
+    std::set<std::string> full_addrs;
+    MINFO("Resolving node addresses {" << m_seed_nodes_list.size() << "}");
+    full_addrs.insert("10.0.0.1:18080");
+    return full_addrs;
+  }
+}
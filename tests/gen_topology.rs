@@ -0,0 +1,142 @@
+//! Golden and property tests for `monerosim gen-topology`.
+//!
+//! Each parameter set is generated, byte-diffed against
+//! `tests/golden/topology_<name>.gml`, read back through the GML parser and
+//! checked with the validators a loaded `network.path` goes through. Run
+//! with `UPDATE_GOLDEN=1` to refresh the golden files after intentional
+//! generator changes.
+
+use monerosim::gml_parser::{parse_gml_file, validate_topology, write_gml, GmlGraph};
+use monerosim::topology::{generate_topology, TopologyParams};
+use monerosim::utils::validate_gml_ip_consistency;
+use monerosim::{config_loader, orchestrator};
+use std::collections::{HashMap, HashSet};
+use tempfile::TempDir;
+
+fn params(as_groups: usize, nodes_per_as: usize, seed: u64) -> TopologyParams {
+    TopologyParams {
+        as_groups,
+        nodes_per_as,
+        intra_latency_ms: (1.0, 5.0),
+        inter_latency_ms: (20.0, 150.0),
+        bandwidth_classes: vec!["100Mbit".to_string(), "1Gbit".to_string()],
+        seed,
+    }
+}
+
+/// Every node reachable from node 0 over the (undirected) edges
+fn is_connected(graph: &GmlGraph) -> bool {
+    let mut adjacency: HashMap<u32, Vec<u32>> = HashMap::new();
+    for edge in &graph.edges {
+        adjacency.entry(edge.source).or_default().push(edge.target);
+        adjacency.entry(edge.target).or_default().push(edge.source);
+    }
+    let mut seen = HashSet::from([0]);
+    let mut stack = vec![0];
+    while let Some(node) = stack.pop() {
+        for &next in adjacency.get(&node).into_iter().flatten() {
+            if seen.insert(next) {
+                stack.push(next);
+            }
+        }
+    }
+    seen.len() == graph.nodes.len()
+}
+
+fn check_golden(name: &str, params: &TopologyParams) {
+    let tmp = TempDir::new().unwrap();
+    let actual = write_gml(&generate_topology(params).expect("generates"));
+
+    let path = tmp.path().join("topology.gml");
+    std::fs::write(&path, &actual).unwrap();
+    let parsed = parse_gml_file(path.to_str().unwrap()).expect("parses back");
+    validate_topology(&parsed).unwrap();
+    validate_gml_ip_consistency(&parsed).unwrap();
+    assert_eq!(write_gml(&parsed), actual, "round-trips through the parser");
+
+    let golden_path = format!("tests/golden/topology_{}.gml", name);
+    if std::env::var("UPDATE_GOLDEN").is_ok() {
+        std::fs::write(&golden_path, &actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&golden_path).unwrap_or_else(|_| {
+        panic!(
+            "{} exists; run with UPDATE_GOLDEN=1 to refresh",
+            golden_path
+        )
+    });
+    assert_eq!(
+        actual, expected,
+        "Generated topology diverged from {}.\n\
+         Inspect the diff and either fix the generator or regenerate the golden\n\
+         with UPDATE_GOLDEN=1 cargo test --test gen_topology",
+        golden_path
+    );
+}
+
+#[test]
+fn small_topology_matches_golden() {
+    check_golden("small", &params(3, 2, 12345));
+}
+
+#[test]
+fn mixed_topology_matches_golden() {
+    let mut p = params(5, 4, 7);
+    p.intra_latency_ms = (0.5, 2.0);
+    p.inter_latency_ms = (40.0, 40.0);
+    p.bandwidth_classes = vec![
+        "10Mbit".to_string(),
+        "100 Mbit".to_string(),
+        "2.5 Gbit".to_string(),
+    ];
+    check_golden("mixed", &p);
+}
+
+#[test]
+fn generated_topologies_are_connected() {
+    for seed in 0..40 {
+        for (as_groups, nodes_per_as) in [(1, 1), (1, 7), (2, 1), (6, 3), (25, 2)] {
+            let graph = generate_topology(&params(as_groups, nodes_per_as, seed)).unwrap();
+            assert_eq!(graph.nodes.len(), as_groups * nodes_per_as);
+            assert!(
+                is_connected(&graph),
+                "disconnected: seed {} {}x{}",
+                seed,
+                as_groups,
+                nodes_per_as
+            );
+            validate_topology(&graph).unwrap();
+        }
+    }
+}
+
+#[test]
+fn generated_topology_is_usable_as_network_path() {
+    let tmp = TempDir::new().unwrap();
+    let gml = tmp.path().join("generated.gml");
+    std::fs::write(
+        &gml,
+        write_gml(&generate_topology(&params(4, 3, 1)).unwrap()),
+    )
+    .unwrap();
+    let yaml = format!(
+        "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+         network:\n  path: {}\n  peer_mode: Dynamic\n\
+         agents:\n  user-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+         \x20 user-002:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n",
+        gml.display()
+    );
+    let path = tmp.path().join("config.yaml");
+    std::fs::write(&path, yaml).unwrap();
+    let mut config = config_loader::load_config(&path).expect("loads");
+    config.general.shared_dir = tmp.path().join("shared").to_string_lossy().to_string();
+    let plan = orchestrator::plan_agent_shadow_config(&config, &tmp.path().join("out.yaml"))
+        .expect("orchestrator plans");
+    assert_eq!(plan.shadow_config.network.graph.graph_type, "gml");
+    assert_eq!(plan.gml_graph.unwrap().nodes.len(), 12);
+    assert!(plan
+        .shadow_config
+        .hosts
+        .values()
+        .all(|h| h.network_node_id < 12));
+}
@@ -0,0 +1,135 @@
+//! The library entry points: `orchestrator::generate` builds a simulation
+//! in memory, `orchestrator::write_outputs` writes it out.
+
+use monerosim::config::Config;
+use monerosim::manifest::MANIFEST_FILE;
+use monerosim::{config_loader, orchestrator};
+use std::collections::BTreeSet;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn smoke_config(tmp: &Path) -> Config {
+    let mut config = config_loader::load_config(Path::new("tests/fixtures/smoke.yaml"))
+        .expect("smoke fixture loads");
+    config.general.shared_dir = tmp.join("shared").to_string_lossy().to_string();
+    config
+}
+
+/// Every file under `dir`, relative to it
+fn tree(dir: &Path) -> BTreeSet<String> {
+    let mut files = BTreeSet::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in std::fs::read_dir(&current).unwrap().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else {
+                files.insert(path.strip_prefix(dir).unwrap().display().to_string());
+            }
+        }
+    }
+    files
+}
+
+#[test]
+fn generation_runs_in_memory_and_writes_only_when_asked() {
+    let tmp = TempDir::new().unwrap();
+    let config = smoke_config(tmp.path());
+    let output_dir = tmp.path().join("out");
+    let fixture = Path::new("tests/fixtures/smoke.yaml");
+
+    let result = orchestrator::generate(&config, Some(fixture), &output_dir.join("shadow.yaml"))
+        .expect("generation succeeds");
+    assert_eq!(
+        std::fs::read_dir(tmp.path()).unwrap().count(),
+        0,
+        "generation must not create or write anything"
+    );
+
+    let hosts = &result.shadow_config().hosts;
+    assert!(!hosts.is_empty());
+    assert!(result
+        .agent_registry()
+        .agents
+        .iter()
+        .all(|agent| hosts.contains_key(&agent.id)));
+    let miners: Vec<&str> = result
+        .miner_registry()
+        .miners
+        .iter()
+        .map(|m| m.agent_id.as_str())
+        .collect();
+    assert!(!miners.is_empty());
+    assert!(miners.iter().all(|id| config.agents.agents[*id].is_miner()));
+    assert_eq!(
+        result.ip_allocation().values().sum::<usize>(),
+        result.plan.ip_registry.get_all_assigned_ips().len()
+    );
+    assert!(!result.seed_nodes().is_empty());
+    assert_eq!(
+        result.warnings().len(),
+        result
+            .plan
+            .report
+            .count(monerosim::validation_report::Severity::Warning)
+    );
+    assert!(result
+        .plan
+        .files
+        .iter()
+        .any(|(path, _)| path.ends_with(MANIFEST_FILE)));
+
+    // The Shadow config names its scripts by path, so the result only
+    // writes to the directory it was generated for
+    let err = orchestrator::write_outputs(&result, &tmp.path().join("elsewhere")).unwrap_err();
+    assert!(err.to_string().contains("generate again"), "{}", err);
+    assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 0);
+
+    orchestrator::write_outputs(&result, &output_dir).expect("outputs write");
+    let written = tree(&output_dir);
+    for expected in [
+        "shadow.yaml",
+        MANIFEST_FILE,
+        "agent_registry.json",
+        "miners.json",
+    ] {
+        assert!(written.contains(expected), "{:?}", written);
+    }
+    // The shared directory is only touched as a separate step
+    assert!(!tmp.path().join("shared").exists());
+    orchestrator::populate_shared_dir(&result.plan).expect("shared dir populates");
+    assert!(tmp.path().join("shared/miners.json").is_file());
+
+    // The one-shot wrapper writes the same set of files
+    let wrapper_tmp = TempDir::new().unwrap();
+    let wrapper_out = wrapper_tmp.path().join("out");
+    orchestrator::generate_agent_shadow_config(
+        &smoke_config(wrapper_tmp.path()),
+        Some(fixture),
+        &wrapper_out.join("shadow.yaml"),
+    )
+    .expect("orchestrator generates");
+    assert_eq!(tree(&wrapper_out), written);
+}
+
+#[test]
+fn strict_validation_fails_generation_without_writing_the_report() {
+    let tmp = TempDir::new().unwrap();
+    let yaml = "general:\n  stop_time: 1h\n  fallback_seeds: off\n  strict_validation: true\n\
+                network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+                agents:\n  user-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+                \x20   attributes: {is_minner: true}\n";
+    let path = tmp.path().join("strict.yaml");
+    std::fs::write(&path, yaml).unwrap();
+    let mut config = config_loader::load_config(&path).expect("loads");
+    config.general.shared_dir = tmp.path().join("shared").to_string_lossy().to_string();
+
+    let Err(err) = orchestrator::generate(&config, None, &tmp.path().join("out/shadow.yaml"))
+    else {
+        panic!("strict validation turns the warnings into errors");
+    };
+    assert!(err.to_string().contains("Validation failed"), "{}", err);
+    // Unlike the one-shot wrapper, nothing is left behind
+    assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 1);
+}
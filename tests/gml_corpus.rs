@@ -0,0 +1,133 @@
+//! Real-world GML exports under `tests/fixtures/gml/`: nested blocks,
+//! comments and escaped strings parse onto the right nodes and edges, and
+//! survive a `write_gml` round trip.
+
+use monerosim::gml_parser::{parse_gml, parse_gml_file, write_gml, GmlGraph};
+use monerosim::{config_loader, orchestrator};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+fn fixtures() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/gml");
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|e| e == "gml"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn fixture(name: &str) -> GmlGraph {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/gml")
+        .join(name);
+    parse_gml_file(path.to_str().unwrap()).unwrap()
+}
+
+#[test]
+fn every_fixture_survives_a_round_trip() {
+    let paths = fixtures();
+    assert!(paths.len() >= 2);
+    for path in paths {
+        let name = path.display();
+        let graph = parse_gml_file(path.to_str().unwrap()).unwrap();
+        let reparsed = parse_gml(&write_gml(&graph)).unwrap();
+
+        assert_eq!(reparsed.nodes.len(), graph.nodes.len(), "{}", name);
+        assert_eq!(reparsed.edges.len(), graph.edges.len(), "{}", name);
+        assert_eq!(reparsed.attributes, graph.attributes, "{}", name);
+        for (before, after) in graph.nodes.iter().zip(&reparsed.nodes) {
+            assert_eq!(after.id, before.id, "{}", name);
+            assert_eq!(after.label, before.label, "{}", name);
+            assert_eq!(after.region, before.region, "{}", name);
+            assert_eq!(
+                after.attributes, before.attributes,
+                "{} node {}",
+                name, before.id
+            );
+        }
+        for (before, after) in graph.edges.iter().zip(&reparsed.edges) {
+            assert_eq!(
+                (after.source, after.target),
+                (before.source, before.target),
+                "{}",
+                name
+            );
+            assert_eq!(after.attributes, before.attributes, "{}", name);
+        }
+    }
+}
+
+#[test]
+fn networkx_graphics_blocks_stay_on_their_node() {
+    let graph = fixture("networkx_export.gml");
+    assert_eq!(graph.nodes.len(), 3);
+    assert_eq!(graph.edges.len(), 3);
+    assert_eq!(graph.attributes["name"], "Three AS \"demo\" topology");
+
+    let core = &graph.nodes[0];
+    assert_eq!(core.label.as_deref(), Some("core-\"a\""));
+    assert_eq!(core.attributes["AS"], "65001");
+    assert_eq!(core.attributes["graphics.x"], "12.5");
+    assert_eq!(core.attributes["graphics.fill"], "#FF0000");
+    let relay = &graph.nodes[1];
+    assert_eq!(relay.label.as_deref(), Some("edge & relay"));
+    assert_eq!(relay.attributes["AS"], "65002");
+    assert_eq!(relay.region.as_deref(), Some("europe"));
+    assert!(!relay.attributes.contains_key("graphics.fill"));
+    assert!(!graph.nodes[2]
+        .attributes
+        .keys()
+        .any(|k| k.starts_with("graphics")));
+
+    let first = &graph.edges[0];
+    assert_eq!(first.attributes["latency"], "40 ms");
+    assert_eq!(first.attributes["graphics.Line.point.y"], "-40.25");
+    assert_eq!(graph.edges[1].attributes["packet_loss"], "0.01");
+}
+
+#[test]
+fn caida_comments_are_dropped_and_nested_data_kept() {
+    let graph = fixture("caida_as_links.gml");
+    assert_eq!(graph.nodes.len(), 3);
+    assert_eq!(graph.edges.len(), 3);
+    assert!(!graph.attributes.contains_key("comment"));
+    assert_eq!(graph.attributes["directed"], "1");
+
+    let level3 = &graph.nodes[0];
+    assert_eq!(level3.attributes["AS"], "3356");
+    assert_eq!(level3.attributes["data.cone.size"], "45000");
+    assert!(!level3.attributes.contains_key("comment"));
+    let telia = &graph.nodes[1];
+    assert_eq!(telia.label.as_deref(), Some("Arelion \"Telia\""));
+    assert_eq!(telia.attributes["data.country"], "SE");
+    assert_eq!(graph.edges[1].attributes["relationship"], "-1");
+    assert_eq!(graph.edges[1].attributes["latency"], "12 ms");
+}
+
+#[test]
+fn fixtures_plan_without_nested_attributes_in_shadows_gml() {
+    let tmp = TempDir::new().unwrap();
+    for path in fixtures() {
+        let yaml = format!(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n  shared_dir: {}\n\
+             network:\n  path: {}\nagents:\n  user:\n    count: 3\n    daemon: monerod\n",
+            tmp.path().join("shared").display(),
+            path.display()
+        );
+        let config_path = tmp.path().join("config.yaml");
+        std::fs::write(&config_path, yaml).unwrap();
+        let config = config_loader::load_config(&config_path).unwrap();
+        let plan =
+            orchestrator::plan_agent_shadow_config(&config, &tmp.path().join("out.yaml")).unwrap();
+        let (_, topology) = plan
+            .files
+            .iter()
+            .find(|(p, _)| p.ends_with("topology.gml"))
+            .unwrap();
+        assert!(!topology.contains("graphics"), "{}", path.display());
+        assert!(!topology.contains("data."), "{}", path.display());
+        assert!(parse_gml(topology).is_ok(), "{}", path.display());
+    }
+}
@@ -0,0 +1,313 @@
+graph [
+  directed 0
+  node [
+    id 0
+    AS 0
+    bandwidth "10 Mbit"
+  ]
+  node [
+    id 1
+    AS 0
+    bandwidth "10 Mbit"
+  ]
+  node [
+    id 2
+    AS 0
+    bandwidth "100 Mbit"
+  ]
+  node [
+    id 3
+    AS 0
+    bandwidth "10 Mbit"
+  ]
+  node [
+    id 4
+    AS 240
+    bandwidth "10 Mbit"
+  ]
+  node [
+    id 5
+    AS 240
+    bandwidth "2500 Mbit"
+  ]
+  node [
+    id 6
+    AS 240
+    bandwidth "10 Mbit"
+  ]
+  node [
+    id 7
+    AS 240
+    bandwidth "10 Mbit"
+  ]
+  node [
+    id 8
+    AS 480
+    bandwidth "100 Mbit"
+  ]
+  node [
+    id 9
+    AS 480
+    bandwidth "2500 Mbit"
+  ]
+  node [
+    id 10
+    AS 480
+    bandwidth "2500 Mbit"
+  ]
+  node [
+    id 11
+    AS 480
+    bandwidth "100 Mbit"
+  ]
+  node [
+    id 12
+    AS 720
+    bandwidth "100 Mbit"
+  ]
+  node [
+    id 13
+    AS 720
+    bandwidth "100 Mbit"
+  ]
+  node [
+    id 14
+    AS 720
+    bandwidth "100 Mbit"
+  ]
+  node [
+    id 15
+    AS 720
+    bandwidth "100 Mbit"
+  ]
+  node [
+    id 16
+    AS 960
+    bandwidth "2500 Mbit"
+  ]
+  node [
+    id 17
+    AS 960
+    bandwidth "10 Mbit"
+  ]
+  node [
+    id 18
+    AS 960
+    bandwidth "2500 Mbit"
+  ]
+  node [
+    id 19
+    AS 960
+    bandwidth "2500 Mbit"
+  ]
+  edge [
+    source 0
+    target 0
+    latency "500 us"
+  ]
+  edge [
+    source 1
+    target 1
+    latency "500 us"
+  ]
+  edge [
+    source 2
+    target 2
+    latency "500 us"
+  ]
+  edge [
+    source 3
+    target 3
+    latency "500 us"
+  ]
+  edge [
+    source 4
+    target 4
+    latency "500 us"
+  ]
+  edge [
+    source 5
+    target 5
+    latency "500 us"
+  ]
+  edge [
+    source 6
+    target 6
+    latency "500 us"
+  ]
+  edge [
+    source 7
+    target 7
+    latency "500 us"
+  ]
+  edge [
+    source 8
+    target 8
+    latency "500 us"
+  ]
+  edge [
+    source 9
+    target 9
+    latency "500 us"
+  ]
+  edge [
+    source 10
+    target 10
+    latency "500 us"
+  ]
+  edge [
+    source 11
+    target 11
+    latency "500 us"
+  ]
+  edge [
+    source 12
+    target 12
+    latency "500 us"
+  ]
+  edge [
+    source 13
+    target 13
+    latency "500 us"
+  ]
+  edge [
+    source 14
+    target 14
+    latency "500 us"
+  ]
+  edge [
+    source 15
+    target 15
+    latency "500 us"
+  ]
+  edge [
+    source 16
+    target 16
+    latency "500 us"
+  ]
+  edge [
+    source 17
+    target 17
+    latency "500 us"
+  ]
+  edge [
+    source 18
+    target 18
+    latency "500 us"
+  ]
+  edge [
+    source 19
+    target 19
+    latency "500 us"
+  ]
+  edge [
+    source 0
+    target 1
+    latency "1891 us"
+  ]
+  edge [
+    source 1
+    target 2
+    latency "521 us"
+  ]
+  edge [
+    source 2
+    target 3
+    latency "1382 us"
+  ]
+  edge [
+    source 4
+    target 5
+    latency "922 us"
+  ]
+  edge [
+    source 4
+    target 6
+    latency "1500 us"
+  ]
+  edge [
+    source 4
+    target 7
+    latency "1951 us"
+  ]
+  edge [
+    source 8
+    target 9
+    latency "1456 us"
+  ]
+  edge [
+    source 8
+    target 10
+    latency "922 us"
+  ]
+  edge [
+    source 8
+    target 11
+    latency "1843 us"
+  ]
+  edge [
+    source 12
+    target 13
+    latency "1395 us"
+  ]
+  edge [
+    source 12
+    target 14
+    latency "1719 us"
+  ]
+  edge [
+    source 12
+    target 15
+    latency "1563 us"
+  ]
+  edge [
+    source 16
+    target 17
+    latency "1851 us"
+  ]
+  edge [
+    source 16
+    target 18
+    latency "651 us"
+  ]
+  edge [
+    source 17
+    target 19
+    latency "1659 us"
+  ]
+  edge [
+    source 5
+    target 0
+    latency "40 ms"
+  ]
+  edge [
+    source 5
+    target 8
+    latency "40 ms"
+  ]
+  edge [
+    source 11
+    target 0
+    latency "40 ms"
+  ]
+  edge [
+    source 13
+    target 10
+    latency "40 ms"
+  ]
+  edge [
+    source 12
+    target 16
+    latency "40 ms"
+  ]
+  edge [
+    source 16
+    target 7
+    latency "40 ms"
+  ]
+  edge [
+    source 18
+    target 3
+    latency "40 ms"
+  ]
+]
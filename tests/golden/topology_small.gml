@@ -0,0 +1,88 @@
+graph [
+  directed 0
+  node [
+    id 0
+    AS 0
+    bandwidth "1 Gbit"
+  ]
+  node [
+    id 1
+    AS 0
+    bandwidth "1 Gbit"
+  ]
+  node [
+    id 2
+    AS 400
+    bandwidth "1 Gbit"
+  ]
+  node [
+    id 3
+    AS 400
+    bandwidth "100 Mbit"
+  ]
+  node [
+    id 4
+    AS 800
+    bandwidth "1 Gbit"
+  ]
+  node [
+    id 5
+    AS 800
+    bandwidth "100 Mbit"
+  ]
+  edge [
+    source 0
+    target 0
+    latency "1 ms"
+  ]
+  edge [
+    source 1
+    target 1
+    latency "1 ms"
+  ]
+  edge [
+    source 2
+    target 2
+    latency "1 ms"
+  ]
+  edge [
+    source 3
+    target 3
+    latency "1 ms"
+  ]
+  edge [
+    source 4
+    target 4
+    latency "1 ms"
+  ]
+  edge [
+    source 5
+    target 5
+    latency "1 ms"
+  ]
+  edge [
+    source 0
+    target 1
+    latency "4552 us"
+  ]
+  edge [
+    source 2
+    target 3
+    latency "4973 us"
+  ]
+  edge [
+    source 4
+    target 5
+    latency "4547 us"
+  ]
+  edge [
+    source 2
+    target 1
+    latency "109034 us"
+  ]
+  edge [
+    source 5
+    target 0
+    latency "26646 us"
+  ]
+]
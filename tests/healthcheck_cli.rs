@@ -0,0 +1,44 @@
+//! `monerosim-healthcheck` argument handling: bad durations are usage
+//! errors rather than panics, and an unreachable RPC is reported with its
+//! exit code and in the `--report` file.
+
+use std::process::Command;
+use tempfile::TempDir;
+
+/// An address nothing listens on
+const URL: &str = "http://127.0.0.1:1/json_rpc";
+
+#[test]
+fn invalid_durations_are_usage_errors() {
+    for arg in [
+        "--timeout=-1",
+        "--timeout=inf",
+        "--interval=nan",
+        "--interval=soon",
+    ] {
+        let output = Command::new(env!("CARGO_BIN_EXE_monerosim-healthcheck"))
+            .args(["--url", URL, "--retries", "0", arg])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(2), "{}", arg);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("invalid seconds"), "{}: {}", arg, stderr);
+    }
+}
+
+#[test]
+fn refused_connection_is_reported() {
+    let tmp = TempDir::new().unwrap();
+    let report = tmp.path().join("readiness").join("report.json");
+    let output = Command::new(env!("CARGO_BIN_EXE_monerosim-healthcheck"))
+        .args(["--url", URL, "--retries", "0", "--timeout", "0.5"])
+        .args(["--interval", "0", "--agent", "user-001", "--report"])
+        .arg(&report)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(4));
+    let record: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&report).unwrap()).unwrap();
+    assert_eq!(record["agent"], "user-001");
+    assert_eq!(record["attempts"], 1);
+}
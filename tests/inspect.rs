@@ -0,0 +1,72 @@
+//! `monerosim inspect` on the smoke fixture's generated config: the Shadow
+//! YAML reads back into `ShadowConfig` unchanged, and the summary matches
+//! the agents the fixture defines.
+
+use monerosim::inspect::{
+    find_agent_registry, load_agent_registry, load_shadow_config, summarize, ProcessKind,
+};
+use monerosim::{config_loader, orchestrator};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tempfile::TempDir;
+
+#[test]
+fn generated_config_round_trips_and_summarizes() {
+    let tmp = TempDir::new().unwrap();
+    let mut config = config_loader::load_config(Path::new("tests/fixtures/smoke.yaml")).unwrap();
+    config.general.shared_dir = tmp.path().join("shared").to_string_lossy().to_string();
+    let output_dir = tmp.path().join("out");
+    let shadow_yaml = output_dir.join("shadow_agents.yaml");
+    let result = orchestrator::generate(&config, None, &shadow_yaml).unwrap();
+    orchestrator::write_outputs(&result, &output_dir).unwrap();
+
+    let written = std::fs::read_to_string(&shadow_yaml).unwrap();
+    let loaded = load_shadow_config(&shadow_yaml).unwrap();
+    assert_eq!(serde_yaml::to_string(&loaded).unwrap(), written);
+
+    let registry_path = find_agent_registry(&shadow_yaml, None).unwrap();
+    let registry = load_agent_registry(&registry_path).unwrap();
+    let summary = summarize(&loaded, Some((&registry, &registry_path))).unwrap();
+    assert_eq!(summary.hosts, 3);
+    assert_eq!(summary.stop_time, 3600);
+    assert_eq!(
+        summary.processes_by_kind,
+        BTreeMap::from([
+            (ProcessKind::Daemon, 2),
+            (ProcessKind::Wallet, 2),
+            (ProcessKind::Script, 4),
+        ])
+    );
+    assert_eq!(
+        summary.hosts_by_kind["daemon_wallet"],
+        ["miner-001", "user-001"]
+    );
+    assert_eq!(summary.hosts_by_kind["script"], ["monitor"]);
+    assert!(summary.empty_hosts.is_empty());
+    assert_eq!(
+        summary
+            .start_times
+            .iter()
+            .map(|b| b.processes)
+            .sum::<usize>(),
+        summary.processes
+    );
+    assert_eq!(summary.seed_nodes["miner-001"], ["user-001"]);
+    let check = summary.registry.as_ref().unwrap();
+    assert!(check.missing_hosts.is_empty() && check.unregistered_hosts.is_empty());
+
+    // Without a registry the kinds are guessed from program names alike
+    let guessed = summarize(&loaded, None).unwrap();
+    assert_eq!(guessed.processes_by_kind, summary.processes_by_kind);
+    assert!(summary.to_string().contains("Seed nodes"));
+
+    let run = std::process::Command::new(env!("CARGO_BIN_EXE_monerosim"))
+        .args(["inspect", "--json"])
+        .arg(&shadow_yaml)
+        .output()
+        .unwrap();
+    assert!(run.status.success(), "{:?}", run);
+    let json: serde_json::Value = serde_json::from_slice(&run.stdout).unwrap();
+    assert_eq!(json["hosts"], 3);
+    assert_eq!(json["host_details"]["user-001"]["peers"][0], "miner-001");
+}
@@ -0,0 +1,83 @@
+//! User agent IPs must not depend on the other agent types.
+//!
+//! The same users are planned with and without a DNS server, miner
+//! distributor, simulation monitor and pure script agents, on a switch and
+//! on a GML topology; every user agent and miner keeps its IP.
+
+use monerosim::gml_parser::write_gml;
+use monerosim::topology::{generate_topology, TopologyParams};
+use monerosim::{config_loader, orchestrator};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tempfile::TempDir;
+
+const USERS: &str = "  miner:\n    count: 3\n    daemon: monerod\n    \
+                     wallet: monero-wallet-rpc\n    hashrate: 33\n  user:\n    count: 6\n    \
+                     daemon: monerod\n    wallet: monero-wallet-rpc\n";
+
+const OTHERS: &str = "  miner-distributor:\n    script: agents.miner_distributor\n  \
+                      simulation-monitor:\n    script: agents.simulation_monitor\n  \
+                      watcher:\n    script: agents.regular_user\n  \
+                      script-001:\n    script: agents.regular_user\n";
+
+/// Host IP of every miner and user when planned with `network` and, with
+/// `others`, the DNS server and script agents too
+fn user_ips(tmp: &Path, network: &str, others: bool) -> BTreeMap<String, String> {
+    let yaml = format!(
+        "general:\n  stop_time: 3h\n  shared_dir: {shared}\n  enable_dns_server: {others}\n\
+         network:\n{network}agents:\n{USERS}{extra}",
+        shared = tmp.join("shared").display(),
+        extra = if others { OTHERS } else { "" },
+    );
+    let path = tmp.join("config.yaml");
+    std::fs::write(&path, yaml).unwrap();
+    let config = config_loader::load_config(&path).unwrap();
+    let plan = orchestrator::plan_agent_shadow_config(&config, &tmp.join("out.yaml")).unwrap();
+    if others {
+        for id in [
+            "dnsserver",
+            "miner-distributor",
+            "simulation-monitor",
+            "watcher",
+        ] {
+            assert!(plan.shadow_config.hosts.contains_key(id), "no host {}", id);
+        }
+    }
+    plan.shadow_config
+        .hosts
+        .iter()
+        .filter(|(id, _)| id.starts_with("miner-0") || id.starts_with("user-"))
+        .map(|(id, host)| (id.clone(), host.ip_addr.clone().unwrap()))
+        .collect()
+}
+
+#[test]
+fn switch_user_ips_ignore_other_agent_types() {
+    let tmp = TempDir::new().unwrap();
+    let network = "  type: 1_gbit_switch\n";
+    let alone = user_ips(tmp.path(), network, false);
+    assert_eq!(alone.len(), 9);
+    assert_eq!(user_ips(tmp.path(), network, true), alone);
+}
+
+#[test]
+fn gml_user_ips_ignore_other_agent_types() {
+    let tmp = TempDir::new().unwrap();
+    let graph = generate_topology(&TopologyParams {
+        as_groups: 2,
+        nodes_per_as: 3,
+        intra_latency_ms: (1.0, 5.0),
+        inter_latency_ms: (20.0, 150.0),
+        bandwidth_classes: vec!["1Gbit".to_string()],
+        seed: 7,
+    })
+    .unwrap();
+    let gml = tmp.path().join("topology.gml");
+    std::fs::write(&gml, write_gml(&graph)).unwrap();
+    let network = format!("  path: {}\n", gml.display());
+
+    let alone = user_ips(tmp.path(), &network, false);
+    assert_eq!(alone.len(), 9);
+    assert!(alone.values().any(|ip| ip == "3.0.0.10"), "{:?}", alone);
+    assert_eq!(user_ips(tmp.path(), &network, true), alone);
+}
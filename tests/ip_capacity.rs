@@ -0,0 +1,101 @@
+//! IP capacity of AS subnets at scale.
+//!
+//! 1000 agents on a two-AS topology need about 500 addresses per AS: more
+//! than a default /24 holds, so planning must fail naming the ASes, and with
+//! `network.subnet_size: /16` every host must get a unique IP inside its
+//! AS's subnet.
+
+use monerosim::gml_parser::write_gml;
+use monerosim::ip::AsSubnetManager;
+use monerosim::topology::{generate_topology, TopologyParams};
+use monerosim::{config_loader, orchestrator};
+use std::collections::HashSet;
+use std::path::Path;
+use tempfile::TempDir;
+
+const AGENTS: usize = 1000;
+
+fn config(tmp: &Path, subnet_size: Option<&str>) -> monerosim::config::Config {
+    let graph = generate_topology(&TopologyParams {
+        as_groups: 2,
+        nodes_per_as: 5,
+        intra_latency_ms: (1.0, 5.0),
+        inter_latency_ms: (20.0, 150.0),
+        bandwidth_classes: vec!["1Gbit".to_string()],
+        seed: 7,
+    })
+    .unwrap();
+    let gml = tmp.join("two-as.gml");
+    std::fs::write(&gml, write_gml(&graph)).unwrap();
+
+    let subnet_size = subnet_size
+        .map(|size| format!("  subnet_size: {}\n", size))
+        .unwrap_or_default();
+    let yaml = format!(
+        "general:\n  stop_time: 3h\n  shared_dir: {shared}\nnetwork:\n  path: {gml}\n{subnet_size}\
+         agents:\n  miner:\n    count: 4\n    daemon: monerod\n    wallet: monero-wallet-rpc\n    \
+         hashrate: 25\n  user:\n    count: {users}\n    daemon: monerod\n",
+        shared = tmp.join("shared").display(),
+        gml = gml.display(),
+        users = AGENTS - 4,
+    );
+    let path = tmp.join("config.yaml");
+    std::fs::write(&path, yaml).unwrap();
+    config_loader::load_config(&path).unwrap()
+}
+
+#[test]
+fn thousand_agents_overflow_a_default_subnet() {
+    let tmp = TempDir::new().unwrap();
+    let config = config(tmp.path(), None);
+    let Err(err) = orchestrator::plan_agent_shadow_config(&config, &tmp.path().join("out.yaml"))
+    else {
+        panic!("1000 agents in two /24s must not plan");
+    };
+    let err = err.to_string();
+    assert!(
+        err.contains("IP allocation error: AS 0 (3.0.0.0/24)"),
+        "{}",
+        err
+    );
+    assert!(err.contains("short"), "{}", err);
+    assert!(err.contains("network.subnet_size"), "{}", err);
+}
+
+#[test]
+fn thousand_agents_fit_a_sixteen_bit_subnet() {
+    let tmp = TempDir::new().unwrap();
+    let config = config(tmp.path(), Some("/16"));
+    let plan = orchestrator::plan_agent_shadow_config(&config, &tmp.path().join("out.yaml"))
+        .expect("1000 agents plan with /16 subnets");
+
+    let subnets = AsSubnetManager::with_prefix(16);
+    let cidrs: Vec<(u32, u32)> = ["0", "600"]
+        .iter()
+        .map(|as_number| {
+            let cidr = subnets.subnet_of(as_number).unwrap();
+            let (base, _) = cidr.split_once('/').unwrap();
+            let base: u32 = base.parse::<std::net::Ipv4Addr>().unwrap().into();
+            (base, base + (1 << 16))
+        })
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut agents = 0;
+    for (id, host) in &plan.shadow_config.hosts {
+        let ip = host.ip_addr.as_deref().unwrap();
+        assert!(seen.insert(ip.to_string()), "{} reuses {}", id, ip);
+        if id.starts_with("miner") || id.starts_with("user") {
+            agents += 1;
+            let ip: u32 = ip.parse::<std::net::Ipv4Addr>().unwrap().into();
+            assert!(
+                cidrs
+                    .iter()
+                    .any(|(start, end)| (*start..*end).contains(&ip)),
+                "{} is outside both AS subnets",
+                id
+            );
+        }
+    }
+    assert_eq!(agents, AGENTS);
+}
@@ -0,0 +1,108 @@
+//! A 10k-node GML plans within a memory budget, and
+//! `network.prune_unused_nodes` hands Shadow only the part of it between
+//! the agents without changing their latencies.
+
+use monerosim::gml_parser::{parse_gml, write_gml};
+use monerosim::topology::latency::NodeLatencies;
+use monerosim::topology::{generate_topology, TopologyParams};
+use monerosim::{config_loader, orchestrator};
+use std::collections::BTreeSet;
+use tempfile::TempDir;
+
+/// Peak resident set size of the whole test process; this file holds a
+/// single test so nothing else is counted
+#[cfg(target_os = "linux")]
+const MEMORY_BUDGET_KB: u64 = 512 * 1024;
+
+#[cfg(target_os = "linux")]
+fn peak_rss_kb() -> u64 {
+    std::fs::read_to_string("/proc/self/status")
+        .unwrap()
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))
+        .and_then(|kb| kb.trim().trim_end_matches("kB").trim().parse().ok())
+        .unwrap()
+}
+
+#[test]
+fn ten_thousand_node_topology_plans_within_the_memory_budget() {
+    let tmp = TempDir::new().unwrap();
+    let graph = generate_topology(&TopologyParams {
+        as_groups: 100,
+        nodes_per_as: 100,
+        intra_latency_ms: (1.0, 5.0),
+        inter_latency_ms: (20.0, 150.0),
+        bandwidth_classes: vec!["100Mbit".to_string(), "1Gbit".to_string()],
+        seed: 11,
+    })
+    .unwrap();
+    assert_eq!(graph.nodes.len(), 10_000);
+    let gml = tmp.path().join("large.gml");
+    std::fs::write(&gml, write_gml(&graph)).unwrap();
+
+    let plan = |prune: bool| {
+        let yaml = format!(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n  shared_dir: {}\n\
+             network:\n  path: {}\n  prune_unused_nodes: {}\n\
+             agents:\n  user:\n    count: 50\n    daemon: monerod\n    \
+             wallet: monero-wallet-rpc\n",
+            tmp.path().join("shared").display(),
+            gml.display(),
+            prune
+        );
+        let path = tmp.path().join("config.yaml");
+        std::fs::write(&path, yaml).unwrap();
+        let config = config_loader::load_config(&path).unwrap();
+        orchestrator::plan_agent_shadow_config(&config, &tmp.path().join("out.yaml")).unwrap()
+    };
+    let topology = |plan: &orchestrator::GenerationPlan| {
+        let (_, content) = plan
+            .files
+            .iter()
+            .find(|(p, _)| p.ends_with("topology.gml"))
+            .unwrap();
+        parse_gml(content).unwrap()
+    };
+
+    let full = plan(false);
+    assert_eq!(topology(&full).nodes.len(), 10_000);
+    // Only hosts, never a dummy host per GML node
+    assert!(full.shadow_config.hosts.len() <= 51);
+
+    let pruned = plan(true);
+    let pruned_graph = topology(&pruned);
+    let used: BTreeSet<u32> = pruned
+        .shadow_config
+        .hosts
+        .values()
+        .map(|h| h.network_node_id)
+        .collect();
+    assert!(
+        pruned_graph.nodes.len() < 2_000,
+        "{}",
+        pruned_graph.nodes.len()
+    );
+    assert!(used
+        .iter()
+        .all(|id| pruned_graph.nodes.iter().any(|n| n.id == *id)));
+
+    let sources: Vec<u32> = used.into_iter().collect();
+    let before = NodeLatencies::from_sources(&topology(&full), &sources).unwrap();
+    let after = NodeLatencies::from_sources(&pruned_graph, &sources).unwrap();
+    for &a in &sources {
+        for &b in &sources {
+            assert_eq!(after.get(a, b), before.get(a, b), "{} -> {}", a, b);
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let peak = peak_rss_kb();
+        assert!(
+            peak < MEMORY_BUDGET_KB,
+            "peak RSS {} kB over the {} kB budget",
+            peak,
+            MEMORY_BUDGET_KB
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! Parse-time reduction of the high-volume events against an exact parse of
+//! the same synthetic run: sampled byte totals stay within tolerance,
+//! binned totals stay exact, skipped types are empty, and the low-memory
+//! path reduces the same way.
+
+use std::collections::BTreeSet;
+
+use monerosim::analysis::synth::{generate, write_dataset, SynthOptions};
+use monerosim::analysis::{
+    analyze_bandwidth, analyze_bandwidth_iter, bandwidth_matrix, parse_all_logs,
+    parse_all_logs_reduced, EventKind, LogIndex, LogReduction,
+};
+
+fn relative_error(approx: u64, exact: u64) -> f64 {
+    (approx as f64 - exact as f64).abs() / exact as f64
+}
+
+#[test]
+fn reduced_bandwidth_totals_match_the_exact_parse() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let run = generate(&SynthOptions {
+        agents: 30,
+        txs: 300,
+        ..SynthOptions::default()
+    });
+    write_dataset(&run, 0, tmp.path()).unwrap();
+    let log_dir = tmp.path().join("daemon_logs");
+
+    let exact = parse_all_logs(&log_dir, &run.agents).unwrap();
+    let expected = analyze_bandwidth(&exact, 10);
+    let exact_events: usize = exact.values().map(|d| d.bandwidth_events.len()).sum();
+
+    let sampled = LogReduction {
+        sample_bandwidth: 10,
+        ..LogReduction::default()
+    };
+    let data = parse_all_logs_reduced(&log_dir, &run.agents, &sampled).unwrap();
+    let events: usize = data.values().map(|d| d.bandwidth_events.len()).sum();
+    assert!(events * 5 < exact_events, "{} of {}", events, exact_events);
+    let report = analyze_bandwidth(&data, 10);
+    // The last sample of each category is scaled by what was left after it
+    assert_eq!(report.total_messages, expected.total_messages);
+    for (approx, exact) in [
+        (report.total_bytes_sent, expected.total_bytes_sent),
+        (report.total_bytes_received, expected.total_bytes_received),
+    ] {
+        assert!(
+            relative_error(approx, exact) < 0.05,
+            "sampled {} vs exact {}",
+            approx,
+            exact
+        );
+    }
+    let matrix = bandwidth_matrix(&data, &run.agents);
+    let expected_matrix = bandwidth_matrix(&exact, &run.agents);
+    assert!(relative_error(matrix.total_bytes, expected_matrix.total_bytes) < 0.05);
+
+    let binned = LogReduction {
+        aggregate_bandwidth_sec: Some(60.0),
+        ..LogReduction::default()
+    };
+    let data = parse_all_logs_reduced(&log_dir, &run.agents, &binned).unwrap();
+    let events: usize = data.values().map(|d| d.bandwidth_events.len()).sum();
+    assert!(events < exact_events, "{} of {}", events, exact_events);
+    let report = analyze_bandwidth(&data, 10);
+    assert_eq!(report.total_bytes_sent, expected.total_bytes_sent);
+    assert_eq!(report.total_bytes_received, expected.total_bytes_received);
+    assert_eq!(report.total_messages, expected.total_messages);
+    for node in data.values() {
+        assert!(node
+            .bandwidth_events
+            .iter()
+            .all(|e| e.timestamp % 60.0 == 0.0));
+    }
+
+    // Low-memory replays the indexed lines through the same reduction
+    let both = LogReduction {
+        sample_bandwidth: 10,
+        aggregate_bandwidth_sec: Some(60.0),
+        ..LogReduction::default()
+    };
+    let index = LogIndex::build(&log_dir, &run.agents, &both).unwrap();
+    let streamed = analyze_bandwidth_iter(index.nodes(), 10);
+    let parsed = analyze_bandwidth(
+        &parse_all_logs_reduced(&log_dir, &run.agents, &both).unwrap(),
+        10,
+    );
+    assert_eq!(streamed.total_bytes, parsed.total_bytes);
+    assert_eq!(streamed.total_messages, parsed.total_messages);
+    assert!(relative_error(parsed.total_bytes, expected.total_bytes) < 0.05);
+}
+
+#[test]
+fn skipped_event_types_are_not_recorded() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let run = generate(&SynthOptions {
+        agents: 10,
+        txs: 50,
+        ..SynthOptions::default()
+    });
+    write_dataset(&run, 0, tmp.path()).unwrap();
+    let log_dir = tmp.path().join("daemon_logs");
+    let exact = parse_all_logs(&log_dir, &run.agents).unwrap();
+
+    let skip = LogReduction {
+        skip: BTreeSet::from([EventKind::Bandwidth, EventKind::Connections]),
+        ..LogReduction::default()
+    };
+    let data = parse_all_logs_reduced(&log_dir, &run.agents, &skip).unwrap();
+    for (id, node) in &data {
+        assert!(node.bandwidth_events.is_empty());
+        assert!(node.connection_events.is_empty() && node.connection_drops.is_empty());
+        assert_eq!(node.tx_observations.len(), exact[id].tx_observations.len());
+        assert_eq!(
+            node.block_observations.len(),
+            exact[id].block_observations.len()
+        );
+    }
+
+    let index = LogIndex::build(&log_dir, &run.agents, &skip).unwrap();
+    let full = LogIndex::build(&log_dir, &run.agents, &LogReduction::default()).unwrap();
+    assert!(index.line_count() < full.line_count());
+    assert!(index.nodes().all(|n| n.bandwidth_events.is_empty()));
+
+    assert_eq!(
+        "connections".parse::<EventKind>(),
+        Ok(EventKind::Connections)
+    );
+    assert!("blocks".parse::<EventKind>().is_err());
+}
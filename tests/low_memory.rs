@@ -0,0 +1,96 @@
+//! Peak heap use of the `--low-memory` path.
+//!
+//! A counting global allocator tracks the live heap while the bandwidth
+//! analysis streams a synthetic run's logs through a `LogIndex`. The peak
+//! must stay near what parsing the largest single node takes and, with the
+//! index itself, well below what parsing every node at once takes. Kept in
+//! its own test binary so no other test allocates concurrently.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use monerosim::analysis::log_parser::parse_log_file;
+use monerosim::analysis::synth::{generate, write_dataset, SynthOptions};
+use monerosim::analysis::{
+    analyze_bandwidth, analyze_bandwidth_iter, parse_all_logs, LogIndex, LogReduction,
+};
+
+struct Counting;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let live = LIVE.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(live, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Heap growth above the live size at entry while `f` runs
+fn peak_during<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let base = LIVE.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+    let value = f();
+    (value, PEAK.load(Ordering::Relaxed) - base)
+}
+
+#[test]
+fn streaming_peak_is_bounded_by_the_largest_node() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let run = generate(&SynthOptions {
+        agents: 40,
+        txs: 400,
+        ..SynthOptions::default()
+    });
+    write_dataset(&run, 0, tmp.path()).unwrap();
+    let agents = run.agents.clone();
+    drop(run);
+    let log_dir = tmp.path().join("daemon_logs");
+
+    // Also starts the rayon pool, so it is not charged to a later phase
+    let (full, full_peak) = peak_during(|| parse_all_logs(&log_dir, &agents).unwrap());
+    let expected = analyze_bandwidth(&full, 10);
+    let largest = full
+        .values()
+        .max_by_key(|d| d.tx_observations.len() + d.bandwidth_events.len())
+        .map(|d| d.node_id.clone())
+        .unwrap();
+    drop(full);
+
+    let largest_log = log_dir
+        .join(format!("monero-{}", largest))
+        .join("bitmonero.log");
+    let (_, node_peak) = peak_during(|| parse_log_file(&largest_log, &largest).unwrap());
+
+    // Indexing reads files on every rayon thread; what stays is the offsets
+    let before = LIVE.load(Ordering::Relaxed);
+    let index = LogIndex::build(&log_dir, &agents, &LogReduction::default()).unwrap();
+    let index_size = LIVE.load(Ordering::Relaxed) - before;
+    let (report, streaming_peak) = peak_during(|| analyze_bandwidth_iter(index.nodes(), 10));
+
+    assert_eq!(report.total_bytes, expected.total_bytes);
+    assert_eq!(report.total_messages, expected.total_messages);
+    assert!(
+        streaming_peak < 3 * node_peak,
+        "streaming peak {} vs largest node {}",
+        streaming_peak,
+        node_peak
+    );
+    assert!(
+        (index_size + streaming_peak) * 4 < full_peak,
+        "index {} + streaming peak {} vs full parse {}",
+        index_size,
+        streaming_peak,
+        full_peak
+    );
+}
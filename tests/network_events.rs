@@ -0,0 +1,165 @@
+//! `network.events`: scheduled partitions become `set_bans` helper processes
+//! on the grouped daemons and a schedule in the manifest, and the
+//! resilience analysis reports each partition from the logs.
+
+use monerosim::analysis::analyze_resilience;
+use monerosim::analysis::testutil::SyntheticRun;
+use monerosim::config::NetworkEventAction;
+use monerosim::shadow::ProcessArgs;
+use monerosim::topology::ScheduledNetworkEvent;
+use monerosim::{config_loader, orchestrator};
+use tempfile::TempDir;
+
+const EVENTS: &str = "  events:\n    - {at: 30m, action: partition, groups: \
+                      [[user-001..user-002], [user-003..]]}\n    - {at: 50m, action: heal}\n";
+
+fn generate(tmp: &TempDir, events: &str) -> color_eyre::Result<orchestrator::GenerationResult> {
+    let yaml = format!(
+        "general:\n  stop_time: 1h\n  fallback_seeds: off\n  shared_dir: {}\n\
+         network:\n  type: 1_gbit_switch\n{}agents:\n  user:\n    count: 4\n    \
+         daemon: monerod\n    wallet: monero-wallet-rpc\n",
+        tmp.path().join("shared").display(),
+        events
+    );
+    let path = tmp.path().join("config.yaml");
+    std::fs::write(&path, yaml).unwrap();
+    let config = config_loader::load_config(&path)?;
+    orchestrator::generate(&config, Some(&path), &tmp.path().join("out.yaml"))
+}
+
+#[test]
+fn partitions_ban_the_other_groups_and_lift_the_bans_at_the_heal() {
+    let tmp = TempDir::new().unwrap();
+    let result = generate(&tmp, EVENTS).expect("generates");
+    let plan = &result.plan;
+
+    let ip = |id: &str| plan.shadow_config.hosts[id].ip_addr.clone().unwrap();
+    let helpers = |id: &str| -> Vec<(String, String)> {
+        plan.shadow_config.hosts[id]
+            .processes
+            .iter()
+            .filter_map(|p| {
+                let ProcessArgs::List(args) = &p.args else {
+                    return None;
+                };
+                let name = args.last()?.rsplit('/').next()?.to_string();
+                name.starts_with("network_event_").then(|| {
+                    (
+                        p.start_time.to_shadow_string(),
+                        plan.scripts.get(&name).unwrap(),
+                    )
+                })
+            })
+            .collect()
+    };
+
+    let user1 = helpers("user-001");
+    assert_eq!(user1.len(), 2);
+    let (partition, heal) = (&user1[0], &user1[1]);
+    assert_eq!(partition.0, "1800s");
+    assert_eq!(heal.0, "3000s");
+    for other in ["user-003", "user-004"] {
+        let ban = format!(r#"{{"host":"{}","ban":true,"seconds":1200}}"#, ip(other));
+        assert!(partition.1.contains(&ban), "{}", partition.1);
+        assert!(heal
+            .1
+            .contains(&format!(r#""host":"{}","ban":false"#, ip(other))));
+    }
+    assert!(!partition.1.contains(&format!("\"{}\"", ip("user-002"))));
+    assert!(partition.1.contains(&format!("http://{}:", ip("user-001"))));
+    assert!(helpers("user-004")[0].1.contains(&ip("user-002")));
+
+    assert_eq!(
+        result.manifest.network_events,
+        vec![
+            ScheduledNetworkEvent {
+                at: 1800,
+                action: NetworkEventAction::Partition,
+                groups: vec![
+                    vec!["user-001".to_string(), "user-002".to_string()],
+                    vec!["user-003".to_string(), "user-004".to_string()],
+                ],
+            },
+            ScheduledNetworkEvent {
+                at: 3000,
+                action: NetworkEventAction::Heal,
+                groups: Vec::new(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn invalid_schedules_fail_to_load() {
+    let tmp = TempDir::new().unwrap();
+    let overlapping = EVENTS.replace("[user-003..]", "[user-002..]");
+    let err = generate(&tmp, &overlapping)
+        .err()
+        .expect("overlap rejected");
+    assert!(
+        format!("{:#}", err).contains("network.events[0]: agent 'user-002' is in groups 0 and 1"),
+        "{:#}",
+        err
+    );
+
+    let late = EVENTS.replace("50m", "2h");
+    let err = generate(&tmp, &late).err().expect("late heal rejected");
+    assert!(format!("{:#}", err).contains("stop_time"), "{:#}", err);
+}
+
+#[test]
+fn resilience_reports_drops_reconvergence_and_stuck_transactions() {
+    let mut run = SyntheticRun::new();
+    for id in ["a1", "a2", "b1", "b2"] {
+        run.agent(id, "agents.regular_user");
+    }
+    run.connect("a1", "a2", 10.0)
+        .connect("b1", "b2", 10.0)
+        .connect("a1", "b1", 10.0)
+        .connect("a2", "b2", 10.0);
+    // Partition at 100s cuts both cross links, the heal at 200s restores one
+    run.disconnect("a1", "b1", 101.0)
+        .disconnect("a2", "b2", 103.5)
+        .connect("a1", "b2", 205.0);
+    // tx-a stays on side A until the heal, tx-b reaches both sides during
+    // the partition (e.g. through an ungrouped relay)
+    run.observe("tx-a", "a1", "a2", 120.0)
+        .observe("tx-a", "a2", "a1", 121.0)
+        .observe("tx-a", "b1", "a1", 206.0)
+        .observe("tx-b", "b1", "b2", 130.0)
+        .observe("tx-b", "a1", "b1", 131.0)
+        .observe("tx-b", "a2", "a1", 132.0);
+    run.block(1, "a1", &["tx-a"], 210.0, 2.5);
+
+    let events = vec![
+        ScheduledNetworkEvent {
+            at: 100,
+            action: NetworkEventAction::Partition,
+            groups: vec![
+                vec!["a1".to_string(), "a2".to_string()],
+                vec!["b1".to_string(), "b2".to_string()],
+            ],
+        },
+        ScheduledNetworkEvent {
+            at: 200,
+            action: NetworkEventAction::Heal,
+            groups: Vec::new(),
+        },
+    ];
+    let report = analyze_resilience(&run.log_map(), &run.agents, &events);
+    assert_eq!(report.partition_events.len(), 1);
+    let event = &report.partition_events[0];
+    assert_eq!(event.group_sizes, vec![2, 2]);
+    assert_eq!(event.cross_group_connections_before, 2);
+    assert_eq!(event.cross_group_drops, 2);
+    assert_eq!(event.drop_latency_secs, Some(3.5));
+    assert_eq!(event.cross_group_connections_during, 0);
+    assert!(event.detected);
+    assert_eq!(event.first_reconnect_secs, Some(5.0));
+    assert_eq!(event.reconverge_secs, Some(12.5));
+    assert_eq!(event.stuck_transactions, vec!["tx-a".to_string()]);
+
+    assert!(analyze_resilience(&run.log_map(), &run.agents, &[])
+        .partition_events
+        .is_empty());
+}
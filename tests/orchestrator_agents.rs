@@ -0,0 +1,1110 @@
+//! Agent definitions and what reaches each agent's processes: naming and
+//! expansion, daemon and wallet arguments, colocation, lifetimes, restarts,
+//! binaries, attributes, startup order, workloads, wallets and wrappers.
+
+mod common;
+
+use common::*;
+use monerosim::manifest::SimulationManifest;
+use monerosim::orchestrator;
+use monerosim::utils::SimDuration;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+#[test]
+fn counted_agent_entries_expand_into_the_registries() {
+    let tmp = TempDir::new().unwrap();
+    let config = load_yaml(
+        &tmp,
+        "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+         network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+         agents:\n\
+         \x20 miner:\n    count: 3\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+         \x20   script: agents.autonomous_miner\n    hashrate: [50, 30, 20]\n\
+         \x20 user-special:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+         \x20 user:\n    count: 12\n    daemon: monerod\n",
+    )
+    .expect("counted config loads");
+    assert_eq!(config.agents.agents.len(), 16);
+    let plan = plan_config(&tmp, &config).expect("orchestrator plans");
+
+    let miners = planned_json(&plan, "miners.json");
+    let weights: Vec<(String, u64)> = miners["miners"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| {
+            (
+                m["agent_id"].as_str().unwrap().to_string(),
+                m["weight"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        weights,
+        [
+            ("miner-001".to_string(), 50),
+            ("miner-002".to_string(), 30),
+            ("miner-003".to_string(), 20)
+        ]
+    );
+    for id in ["user-001", "user-012", "user-special"] {
+        assert!(plan.shadow_config.hosts.contains_key(id), "{}", id);
+    }
+}
+
+#[test]
+fn named_agents_keep_their_ids_and_ips_when_reordered() {
+    let tmp = TempDir::new().unwrap();
+    let entries = [
+        "  pools:\n    count: 2\n    name: [pool-east, pool-west]\n    daemon: monerod\n\
+         \x20   wallet: monero-wallet-rpc\n    script: agents.autonomous_miner\n\
+         \x20   hashrate: [60, 40]\n",
+        "  shop:\n    name: coffee-shop\n    daemon: monerod\n\
+         \x20   attributes:\n      is_public_node: true\n",
+        "  phone:\n    name: light-wallet\n    daemon:\n      address: coffee-shop\n\
+         \x20   wallet: monero-wallet-rpc\n    script: agents.regular_user\n",
+        "  relay:\n    count: 3\n    daemon: monerod\n",
+    ];
+    let plan_for = |order: &[usize]| {
+        let mut yaml = String::from(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Hardcoded\n\
+             \x20 seed_nodes: [pool-west]\n  seed_selection: explicit\n\
+             agents:\n",
+        );
+        for &i in order {
+            yaml.push_str(entries[i]);
+        }
+        plan_yaml(&tmp, &yaml).expect("orchestrator plans")
+    };
+    let addresses = |plan: &orchestrator::GenerationPlan| -> Vec<(String, String)> {
+        plan.shadow_config
+            .hosts
+            .iter()
+            .map(|(id, host)| (id.clone(), host.ip_addr.clone().unwrap_or_default()))
+            .collect()
+    };
+
+    let plan = plan_for(&[0, 1, 2, 3]);
+    let hosts: Vec<&str> = plan
+        .shadow_config
+        .hosts
+        .keys()
+        .map(String::as_str)
+        .collect();
+    assert_eq!(
+        hosts,
+        [
+            "coffee-shop",
+            "light-wallet",
+            "pool-east",
+            "pool-west",
+            "relay-001",
+            "relay-002",
+            "relay-003"
+        ]
+    );
+    let reordered = plan_for(&[3, 2, 1, 0]);
+    assert_eq!(addresses(&reordered), addresses(&plan));
+    assert_eq!(
+        planned_json(&reordered, "intended_topology.json"),
+        planned_json(&plan, "intended_topology.json")
+    );
+
+    // References resolve by name: the miner registry, the explicit seed,
+    // the wallet-only agent's daemon and the intended topology
+    let miners: Vec<String> = planned_json(&plan, "miners.json")["miners"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["agent_id"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(miners, ["pool-east", "pool-west"]);
+    let agent = |id: &str| registered(&plan, id);
+    assert_eq!(agent("pool-west")["is_seed"], true);
+    assert_ne!(agent("pool-east")["is_seed"], true);
+    let shop_ip = agent("coffee-shop")["ip_addr"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert_eq!(
+        agent("light-wallet")["remote_daemon"],
+        format!("{}:18081", shop_ip)
+    );
+    let intended = planned_json(&plan, "intended_topology.json");
+    let connections = intended["coffee-shop"]["connections"].as_array().unwrap();
+    assert!(
+        connections.iter().any(|c| c["target"] == "pool-west"),
+        "{:?}",
+        connections
+    );
+}
+
+#[test]
+fn duplicate_and_invalid_agent_names_are_rejected() {
+    let tmp = TempDir::new().unwrap();
+    for (agents, expected) in [
+        (
+            "  a:\n    name: node-1\n    daemon: monerod\n\
+             \x20 b:\n    name: node-1\n    daemon: monerod\n",
+            "duplicate agent id 'node-1' (produced by both 'a' and 'b')",
+        ),
+        (
+            "  a:\n    name: node_1\n    daemon: monerod\n",
+            "name 'node_1' is not a valid host name",
+        ),
+        (
+            "  a:\n    count: 2\n    name: [x-1]\n    daemon: monerod\n",
+            "name list has 1 values but count is 2",
+        ),
+    ] {
+        let yaml = format!(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+             agents:\n{}",
+            agents
+        );
+        let err = format!("{:?}", load_yaml(&tmp, &yaml).unwrap_err());
+        assert!(err.contains(expected), "{}", err);
+    }
+}
+
+#[test]
+fn per_agent_daemon_args_merge_with_the_generated_ones() {
+    let tmp = TempDir::new().unwrap();
+    let yaml = |user_args: &str| {
+        format!(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+             \x20 daemon_defaults:\n    limit-rate-up: 1024\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+             agents:\n\
+             \x20 miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: 100\n\
+             \x20 user-001:\n    daemon: monerod\n{}",
+            user_args
+        )
+    };
+
+    let plan = plan_yaml(
+        &tmp,
+        &yaml(
+            "    daemon_args: [\"--max-connections-per-ip=8\", \"--pad-transactions\", \"--limit-rate-up=64\"]\n\
+             \x20   daemon_arg_overrides:\n      limit-rate-up: 256\n",
+        ),
+    )
+    .expect("orchestrator plans");
+    let daemon_args = |id: &str| -> Vec<String> {
+        let processes = serde_json::to_value(&plan.shadow_config.hosts[id].processes).unwrap();
+        processes[0]["args"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|a| a.as_str().unwrap().to_string())
+            .collect()
+    };
+    let user = daemon_args("user-001");
+    let count = |prefix: &str| user.iter().filter(|a| a.starts_with(prefix)).count();
+    assert!(user.contains(&"--max-connections-per-ip=8".to_string()));
+    assert!(user.contains(&"--pad-transactions".to_string()));
+    assert!(user.contains(&"--limit-rate-up=256".to_string()));
+    assert_eq!(count("--max-connections-per-ip="), 1, "{:?}", user);
+    assert_eq!(count("--limit-rate-up="), 1, "{:?}", user);
+    assert!(daemon_args("miner-001").contains(&"--limit-rate-up=1024".to_string()));
+
+    for (bad, flag) in [
+        (
+            "    daemon_args: [\"--rpc-bind-ip=0.0.0.0\"]\n",
+            "--rpc-bind-ip",
+        ),
+        (
+            "    daemon_arg_overrides:\n      p2p-bind-port: 28080\n",
+            "--p2p-bind-port",
+        ),
+    ] {
+        let err = format!("{:?}", load_yaml(&tmp, &yaml(bad)).unwrap_err());
+        assert!(
+            err.contains("Agent 'user-001'") && err.contains(flag),
+            "{}",
+            err
+        );
+    }
+}
+
+#[test]
+fn wallet_args_merge_and_land_in_the_agent_registry() {
+    let tmp = TempDir::new().unwrap();
+    let yaml = |user_wallet: &str| {
+        format!(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+             \x20 wallet_defaults:\n    log-level: 1\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+             agents:\n\
+             \x20 miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: 100\n\
+             \x20 user-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n{}",
+            user_wallet
+        )
+    };
+
+    let plan = plan_yaml(
+        &tmp,
+        &yaml("    wallet_args: [\"--max-concurrency=2\", \"--log-level=3\", \"--daemon-login=u:p\"]\n"),
+    )
+    .expect("orchestrator plans");
+    let wallet_args = |id: &str| -> Vec<String> {
+        registered(&plan, id)["wallet_args"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|a| a.as_str().unwrap().to_string())
+            .collect()
+    };
+    let user = wallet_args("user-001");
+    for arg in ["--max-concurrency=2", "--log-level=3", "--daemon-login=u:p"] {
+        assert!(user.contains(&arg.to_string()), "{:?}", user);
+    }
+    assert_eq!(
+        user.iter()
+            .filter(|a| a.starts_with("--log-level="))
+            .count(),
+        1
+    );
+    assert!(wallet_args("miner-001").contains(&"--log-level=1".to_string()));
+    let process_args =
+        serde_json::to_string(&plan.shadow_config.hosts["user-001"].processes).unwrap();
+    assert!(process_args.contains("--daemon-login=u:p"));
+
+    let err = format!(
+        "{:?}",
+        load_yaml(
+            &tmp,
+            &yaml("    wallet_args: [\"--wallet-dir=/tmp/elsewhere\"]\n")
+        )
+        .unwrap_err()
+    );
+    assert!(
+        err.contains("Agent 'user-001' wallet_args") && err.contains("--wallet-dir"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn chain_selects_daemon_and_wallet_flags_ports_and_agent_network() {
+    let tmp = TempDir::new().unwrap();
+    let yaml = |chain: &str, extra: &str| {
+        format!(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n  chain: {}\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+             agents:\n\
+             \x20 user-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.regular_user\n{}",
+            chain, extra
+        )
+    };
+
+    let config = load_yaml(&tmp, &yaml("testnet", "")).expect("config loads");
+    let plan = plan_config(&tmp, &config).expect("orchestrator plans");
+    let daemon = &plan.registry_context.daemon_args["user-001"];
+    for arg in [
+        "--testnet",
+        "--p2p-bind-port=28080",
+        "--rpc-bind-port=28081",
+    ] {
+        assert!(daemon.contains(&arg.to_string()), "{:?}", daemon);
+    }
+    assert!(!daemon
+        .iter()
+        .any(|a| a == "--regtest" || a == "--keep-fakechain"));
+    let wallet = &plan.registry_context.wallet_args["user-001"];
+    for arg in ["--testnet", "--rpc-bind-port=28082"] {
+        assert!(wallet.contains(&arg.to_string()), "{:?}", wallet);
+    }
+    let argv = regular_user_argv(&plan, "user-001");
+    let network = argv.iter().position(|a| a == "--network").unwrap();
+    assert_eq!(argv[network + 1], "testnet");
+
+    let registry = planned_json(&plan, "agent_registry.json");
+    assert_eq!(registry["chain"], "testnet");
+    assert_eq!(registry["agents"][0]["p2p_port"], 28080);
+    assert_eq!(
+        SimulationManifest::new(&config, None).unwrap().chain,
+        monerosim::config::Chain::Testnet
+    );
+
+    let err = |chain: &str, extra: &str| {
+        format!("{:?}", load_yaml(&tmp, &yaml(chain, extra)).unwrap_err())
+    };
+    let miner = "\x20 miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+                 \x20   script: agents.autonomous_miner\n    hashrate: 100\n";
+    let e = err("stagenet", miner);
+    assert!(
+        e.contains("chain: stagenet cannot run autonomous miners (miner-001)"),
+        "{}",
+        e
+    );
+    let e = err("regtest", "    daemon_args: [\"--testnet\"]\n");
+    assert!(
+        e.contains("--testnet") && e.contains("set general.chain"),
+        "{}",
+        e
+    );
+}
+
+#[test]
+fn colocated_agents_share_a_host_on_their_own_ports() {
+    let tmp = TempDir::new().unwrap();
+    let yaml = |extra: &str| {
+        format!(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+             agents:\n  miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: 100\n\
+             \x20 user-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   colocate_with: miner-001\n\
+             \x20 user-002:\n    daemon: monerod\n    p2p_port: 19000\n{}",
+            extra
+        )
+    };
+    let plan = plan_yaml(&tmp, &yaml("")).expect("orchestrator plans");
+
+    // user-001 runs on miner-001's host, on the next port triple
+    let hosts = &plan.shadow_config.hosts;
+    assert!(!hosts.contains_key("user-001"));
+    let host = serde_json::to_string(&hosts["miner-001"].processes).unwrap();
+    for port in [
+        "--p2p-bind-port=18080",
+        "--p2p-bind-port=18090",
+        "--rpc-bind-port=18091",
+        "--rpc-bind-port=18092",
+    ] {
+        assert!(host.contains(port), "{}: {}", port, host);
+    }
+    let user = serde_json::to_string(&hosts["user-002"].processes).unwrap();
+    assert!(user.contains("--p2p-bind-port=19000"), "{}", user);
+    assert!(user.contains("--rpc-bind-port=18081"), "{}", user);
+
+    let agent = |id: &str| registered(&plan, id);
+    assert_eq!(agent("user-001")["ip_addr"], agent("miner-001")["ip_addr"]);
+    assert_eq!(agent("user-001")["p2p_port"], 18090);
+    assert_eq!(agent("user-001")["daemon_rpc_port"], 18091);
+    assert_eq!(agent("user-001")["wallet_rpc_port"], 18092);
+    assert_eq!(agent("user-002")["p2p_port"], 19000);
+
+    for (extra, expected) in [
+        (
+            "  user-003:\n    daemon: monerod\n    colocate_with: user-002\n    p2p_port: 19000\n",
+            "Port configuration error",
+        ),
+        (
+            "  user-003:\n    daemon: monerod\n    colocate_with: user-001\n",
+            "itself co-located",
+        ),
+        (
+            "  user-003:\n    daemon: monerod\n    colocate_with: nobody\n",
+            "is not another agent",
+        ),
+    ] {
+        let err = format!("{:?}", load_yaml(&tmp, &yaml(extra)).unwrap_err());
+        assert!(err.contains(expected), "{}: {}", extra, err);
+    }
+}
+
+#[test]
+fn shutdown_time_stops_every_process_of_the_agent() {
+    let tmp = TempDir::new().unwrap();
+    let yaml = |shutdown: &str| {
+        format!(
+            "general:\n  stop_time: 2h\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+             agents:\n  miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: 100\n\
+             \x20 user-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   start_time: 30m\n    shutdown_time: {}\n",
+            shutdown
+        )
+    };
+    let plan = plan_yaml(&tmp, &yaml("1h")).expect("orchestrator plans");
+
+    let processes = &plan.shadow_config.hosts["user-001"].processes;
+    assert_eq!(processes[0].start_time, SimDuration::from_secs(1800));
+    assert!(processes
+        .iter()
+        .all(|p| p.shutdown_time == Some(SimDuration::from_secs(3600))));
+    let states = serde_json::to_string(
+        &processes
+            .iter()
+            .map(|p| (&p.shutdown_signal, &p.expected_final_state))
+            .collect::<Vec<_>>(),
+    )
+    .unwrap();
+    assert!(
+        states.starts_with(r#"[[null,{"exited":0}],["SIGKILL",{"signaled":"SIGKILL"}]"#),
+        "{}",
+        states
+    );
+    assert!(plan.shadow_config.hosts["miner-001"]
+        .processes
+        .iter()
+        .all(|p| p.shutdown_time.is_none()));
+
+    let (_, registry) = plan
+        .files
+        .iter()
+        .find(|(p, _)| p.ends_with("agent_registry.json"))
+        .unwrap();
+    let registry: serde_json::Value = serde_json::from_str(registry).unwrap();
+    let user = registry["agents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|a| a["id"] == "user-001")
+        .unwrap();
+    assert_eq!(
+        user["schedule"],
+        serde_json::json!({"start_time": 1800, "shutdown_time": 3600})
+    );
+
+    for (shutdown, expected) in [
+        ("20m", "is not after start time 30m"),
+        ("3h", "after general.stop_time"),
+    ] {
+        let err = format!("{:?}", load_yaml(&tmp, &yaml(shutdown)).unwrap_err());
+        assert!(err.contains(expected), "{}", err);
+    }
+}
+
+#[test]
+fn stop_time_reaches_every_agent_script_in_seconds() {
+    let tmp = TempDir::new().unwrap();
+    let plan_with = |stop_time: &str, user_002: &str| {
+        let yaml = format!(
+            "general:\n  stop_time: {}\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n\
+             agents:\n\
+             \x20 miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: 100\n\
+             \x20 user-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.regular_user\n\
+             \x20 user-002:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.regular_user\n{}\
+             \x20 miner-distributor:\n    script: agents.miner_distributor\n\
+             \x20 simulation-monitor:\n    script: agents.simulation_monitor\n",
+            stop_time, user_002
+        );
+        plan_yaml(&tmp, &yaml)
+    };
+
+    for (stop_time, seconds) in [("3h", 10800), ("90m", 5400), ("3600s", 3600)] {
+        let plan = plan_with(stop_time, "").unwrap();
+        assert_eq!(plan.shadow_config.general.stop_time.as_secs(), seconds);
+        for script in [
+            "agent_user-001_wrapper.sh",
+            "mining_agent_miner-001_wrapper.sh",
+            "miner-distributor_wrapper.sh",
+            "simulation-monitor_wrapper.sh",
+        ] {
+            let wrapper = plan.scripts.get(script).unwrap();
+            assert!(
+                wrapper.contains(&format!("--stop-time {} ", seconds)),
+                "{} {}: {}",
+                stop_time,
+                script,
+                wrapper
+            );
+        }
+    }
+
+    // A shorter lifetime ends only the script, which exits by itself
+    let plan = plan_with("4h", "    lifetime: 3h\n").unwrap();
+    let argv = regular_user_argv(&plan, "user-002");
+    let at = argv.iter().position(|a| a == "--stop-time").unwrap();
+    assert_eq!(argv[at + 1], "10800");
+    let processes = &plan.shadow_config.hosts["user-002"].processes;
+    let script = processes.last().unwrap();
+    assert!(script.shutdown_time.is_none());
+    assert_eq!(
+        serde_json::to_value(&script.expected_final_state).unwrap(),
+        serde_json::json!({"exited": 0})
+    );
+    assert!(processes[..processes.len() - 1]
+        .iter()
+        .all(|p| serde_json::to_value(&p.expected_final_state).unwrap() == "running"));
+    let user = &plan.shadow_config.hosts["user-001"].processes;
+    assert_eq!(
+        serde_json::to_value(&user.last().unwrap().expected_final_state).unwrap(),
+        "running"
+    );
+
+    let err = format!("{:?}", plan_with("4h", "    lifetime: 5h\n").err().unwrap());
+    assert!(
+        err.contains("lifetime 5h exceeds general.stop_time 4h"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn restarts_become_daemon_phases_and_an_upgrade_manifest() {
+    let tmp = TempDir::new().unwrap();
+    let yaml = |restarts: &str| {
+        format!(
+            "general:\n  stop_time: 2h\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+             agents:\n  miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: 100\n\
+             \x20 user-001:\n    daemon: monerod\n    daemon_args: [\"--out-peers=4\"]\n\
+             \x20   restarts:\n{}",
+            restarts
+        )
+    };
+    let plan = plan_yaml(
+        &tmp,
+        &yaml(
+            "      - {stop_at: 30m, start_at: 31m}\n\
+             \x20     - {stop_at: 1h, start_at: 70m, binary_path: /opt/monerod-v19, \
+             extra_args: [\"--in-peers=8\"]}\n",
+        ),
+    )
+    .expect("orchestrator plans");
+
+    let daemons = &plan.shadow_config.hosts["user-001"].processes;
+    let timing: Vec<_> = daemons
+        .iter()
+        .map(|p| (p.start_time, p.shutdown_time))
+        .collect();
+    let t = |time: &str| time.parse::<SimDuration>().unwrap();
+    assert_eq!(
+        timing,
+        [
+            (daemons[0].start_time, Some(t("30m"))),
+            (t("31m"), Some(t("1h"))),
+            (t("70m"), None)
+        ]
+    );
+    assert_eq!(daemons[2].path, "/opt/monerod-v19");
+    let args: Vec<String> = daemons
+        .iter()
+        .map(|p| serde_json::to_string(&p.args).unwrap())
+        .collect();
+    assert!(
+        args.iter().all(|a| a.contains("--out-peers=4")),
+        "{:?}",
+        args
+    );
+    assert!(!args[1].contains("--in-peers=8"), "{}", args[1]);
+    assert!(args[2].contains("--in-peers=8"), "{}", args[2]);
+    let data_dir = |a: &str| {
+        a.split(',')
+            .find(|f| f.contains("--data-dir"))
+            .map(str::to_string)
+    };
+    assert!(data_dir(&args[0]).is_some());
+    assert_eq!(data_dir(&args[0]), data_dir(&args[2]));
+
+    let (_, manifest) = plan
+        .files
+        .iter()
+        .find(|(p, _)| p.ends_with("upgrade_manifest.json"))
+        .unwrap();
+    let manifest_path = tmp.path().join("upgrade_manifest.json");
+    std::fs::write(&manifest_path, manifest).unwrap();
+    let loaded = monerosim::analysis::time_window::load_upgrade_manifest(&manifest_path).unwrap();
+    assert_eq!(loaded.node_upgrades.len(), 2);
+    assert_eq!(loaded.upgrade_end, Some(monerosim::SHADOW_EPOCH + 4200.0));
+
+    let manifest: serde_json::Value = serde_json::from_str(manifest).unwrap();
+    assert_eq!(manifest["post_upgrade_version"], "/opt/monerod-v19");
+    assert_eq!(manifest["upgrade_start"], monerosim::SHADOW_EPOCH + 1800.0);
+    assert_eq!(manifest["upgrade_end"], monerosim::SHADOW_EPOCH + 4200.0);
+    let upgrades = manifest["upgrades"].as_array().unwrap();
+    assert_eq!(upgrades.len(), 2);
+    assert_eq!(upgrades[1]["node_id"], "user-001");
+    assert_eq!(upgrades[1]["timestamp"], monerosim::SHADOW_EPOCH + 4200.0);
+    assert_eq!(upgrades[1]["version"], "/opt/monerod-v19");
+    assert_eq!(upgrades[1]["previous_version"], upgrades[0]["version"]);
+
+    let err = format!(
+        "{:?}",
+        load_yaml(&tmp, &yaml("      - {stop_at: 30m, start_at: 30m}\n")).unwrap_err()
+    );
+    assert!(err.contains("at least 30s after stop_at 30m"), "{}", err);
+}
+
+#[test]
+fn per_agent_binaries_are_recorded_in_the_registry() {
+    let tmp = TempDir::new().unwrap();
+    let plan = plan_yaml(
+        &tmp,
+        "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+         network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+         agents:\n  miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+         \x20   script: agents.autonomous_miner\n    hashrate: 100\n\
+         \x20 user-001:\n    daemon_binary: /opt/patched/monerod\n\
+         \x20   wallet_binary: /opt/patched/monero-wallet-rpc\n",
+    )
+    .expect("orchestrator plans");
+
+    assert_eq!(
+        plan.shadow_config.hosts["user-001"].processes[0].path,
+        "/opt/patched/monerod"
+    );
+    let agent = |id: &str| registered(&plan, id);
+    assert_eq!(agent("user-001")["daemon_binary"], "/opt/patched/monerod");
+    assert_eq!(
+        agent("user-001")["wallet_binary"],
+        "/opt/patched/monero-wallet-rpc"
+    );
+    let miner_daemon = agent("miner-001")["daemon_binary"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(miner_daemon.ends_with("/monerod"), "{}", miner_daemon);
+    assert_ne!(miner_daemon, "/opt/patched/monerod");
+
+    let paths: Vec<&str> = plan
+        .binary_versions
+        .iter()
+        .map(|b| b.path.as_str())
+        .collect();
+    assert!(paths.contains(&"/opt/patched/monerod"), "{:?}", paths);
+    assert!(paths.contains(&miner_daemon.as_str()), "{:?}", paths);
+}
+
+#[test]
+fn wrapper_scripts_are_files_and_quote_attribute_values() {
+    let tmp = TempDir::new().unwrap();
+    let motto = r#"it's `whoami` and "$HOME" \ EOF"#;
+    let yaml = format!(
+        "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+         network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+         agents:\n  user-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+         \x20   script: agents.regular_user\n    attributes:\n      motto: '{}'\n",
+        motto.replace('\'', "''")
+    );
+    let plan = plan_yaml(&tmp, &yaml).expect("orchestrator plans");
+
+    // One process per wrapper, no script written by a preceding heredoc
+    let args = serde_json::to_string(
+        &plan
+            .shadow_config
+            .hosts
+            .values()
+            .flat_map(|h| h.processes.iter().map(|p| &p.args))
+            .collect::<Vec<_>>(),
+    )
+    .unwrap();
+    assert!(!args.contains("cat >") && !args.contains("<<"), "{}", args);
+
+    // The agent's command line hands the value to python verbatim
+    let argv = regular_user_argv(&plan, "user-001");
+    let at = argv.iter().position(|a| a == "motto").unwrap();
+    assert_eq!(argv[at - 1], "--attributes");
+    assert_eq!(argv[at + 1], motto);
+}
+
+#[test]
+fn attribute_values_reach_the_agent_intact() {
+    let tmp = TempDir::new().unwrap();
+    let yaml = "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+                network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+                agents:\n  user-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+                \x20   script: agents.regular_user\n    attributes:\n\
+                \x20     location: New York\n\
+                \x20     quote: '\"double\" and ''single'''\n\
+                \x20     città: \"Zürich → 東京\"\n\
+                \x20     prices: '$HOME ${PATH} $(id) `id`'\n\
+                \x20     injection: '; rm -rf / #'\n\
+                \x20     lines: \"one\\ntwo\"\n";
+    let plan = plan_yaml(&tmp, yaml).expect("orchestrator plans");
+
+    let argv = regular_user_argv(&plan, "user-001");
+    let attribute = |key: &str| {
+        let at = argv
+            .windows(2)
+            .position(|w| w[0] == "--attributes" && w[1] == key);
+        argv[at.unwrap_or_else(|| panic!("no {} in {:?}", key, argv)) + 2].clone()
+    };
+    assert_eq!(attribute("location"), "New York");
+    assert_eq!(attribute("quote"), r#""double" and 'single'"#);
+    assert_eq!(attribute("città"), "Zürich → 東京");
+    assert_eq!(attribute("prices"), "$HOME ${PATH} $(id) `id`");
+    assert_eq!(attribute("injection"), "; rm -rf / #");
+    assert_eq!(attribute("lines"), "one\ntwo");
+}
+
+#[test]
+fn attribute_keys_with_whitespace_or_control_characters_are_rejected() {
+    let tmp = TempDir::new().unwrap();
+    for key in [
+        "'two words'",
+        "\"tab\\tkey\"",
+        "\"bell\\x07\"",
+        "--flag",
+        "''",
+    ] {
+        let yaml = format!(
+            "general:\n  stop_time: 1h\n\
+             network:\n  type: 1_gbit_switch\n\
+             agents:\n  user-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   attributes:\n      {}: value\n",
+            key
+        );
+        let err = load_yaml(&tmp, &yaml).unwrap_err();
+        assert!(
+            format!("{:#}", err).contains("attribute key"),
+            "{}: {:#}",
+            key,
+            err
+        );
+    }
+}
+
+#[test]
+fn legacy_typed_attributes_migrate_into_agent_fields() {
+    let tmp = TempDir::new().unwrap();
+    let load = |attributes: &str| {
+        let yaml = format!(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+             agents:\n  miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   attributes: {{is_miner: true, hashrate: \"10\", can_receive_distributions: true}}\n\
+             \x20 user-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   attributes: {}\n",
+            attributes
+        );
+        load_yaml(&tmp, &yaml)
+    };
+
+    let config =
+        load("{is_miner: false, transaction_interval: \"300\", is_minner: true, location: Lisbon}")
+            .expect("old-style attributes still load");
+    let miner = &config.agents.agents["miner-001"];
+    assert_eq!(
+        miner.hashrate,
+        Some(monerosim::config::Hashrate::Weight(10.0))
+    );
+    assert!(miner.is_miner() && miner.can_receive_distributions());
+    let user = &config.agents.agents["user-001"];
+    assert_eq!(user.transaction_interval, Some(300));
+    assert_eq!(
+        monerosim::utils::unrecognized_attribute_warnings(&config.agents.agents),
+        [
+            "Agent 'user-001': unrecognized attributes is_minner (did you mean is_miner?); \
+          passed to the agent script as-is"
+        ]
+    );
+
+    let plan = plan_config(&tmp, &config).expect("orchestrator plans");
+    assert_eq!(
+        planned_json(&plan, "miners.json")["miners"][0]["weight"],
+        10
+    );
+    let argv = regular_user_argv(&plan, "user-001");
+    assert!(argv
+        .windows(3)
+        .any(|w| w == ["--attributes", "location", "Lisbon"]));
+    assert!(argv
+        .windows(3)
+        .any(|w| w == ["--attributes", "transaction_interval", "300"]));
+
+    for (bad, expected) in [
+        (
+            "{hashrate: fifty}",
+            "attribute hashrate: invalid hashrate \"fifty\"",
+        ),
+        ("{is_public_node: yes}", "expected true or false"),
+        ("{is_miner: true}", "requires a hashrate"),
+    ] {
+        let err = format!("{:#}", load(bad).unwrap_err());
+        assert!(err.contains(expected), "{}: {}", bad, err);
+    }
+}
+
+/// Planned `startup_schedule.json` and its daemon starts, in schedule order
+fn planned_startup(network: &str) -> (serde_json::Value, Vec<(String, u64)>) {
+    let tmp = TempDir::new().unwrap();
+    let mut yaml = format!(
+        "general:\n  stop_time: 3h\n  fallback_seeds: off\n\
+         \x20 startup:\n    stagger: 2s\n    block_maturity: 10m\n\
+         network:\n  type: 1_gbit_switch\n{}\
+         agents:\n",
+        network
+    );
+    for n in 1..=2 {
+        yaml.push_str(&format!(
+            "  miner-{:03}:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: 50\n",
+            n
+        ));
+    }
+    for n in 1..=6 {
+        yaml.push_str(&format!("  user-{:03}:\n    daemon: monerod\n", n));
+    }
+    yaml.push_str(
+        "  miner_distributor:\n    script: agents.miner_distributor\n\
+         \x20 simulation_monitor:\n    script: agents.simulation_monitor\n",
+    );
+    let plan = plan_yaml(&tmp, &yaml).expect("orchestrator plans");
+
+    let schedule = planned_json(&plan, "startup_schedule.json");
+    let daemons = schedule["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|e| e["step"] == "daemon")
+        .map(|e| {
+            (
+                e["agent_id"].as_str().unwrap().to_string(),
+                e["start_time"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    (schedule, daemons)
+}
+
+#[test]
+fn startup_schedule_is_derived_from_dependencies() {
+    let (schedule, daemons) = planned_startup("  peer_mode: Dynamic\n");
+    assert_eq!(schedule["settings"]["stagger"], 2);
+    assert_eq!(
+        &daemons[..2],
+        [("miner-001".into(), 0), ("miner-002".into(), 2)]
+    );
+    let users: Vec<u64> = daemons[2..].iter().map(|(_, t)| *t).collect();
+    assert_eq!(users, [600, 602, 604, 606, 608, 610]);
+
+    let step = |agent: &str, step: &str| {
+        schedule["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|e| e["agent_id"] == agent && e["step"] == step)
+            .unwrap_or_else(|| panic!("{} {}", agent, step))
+            .clone()
+    };
+    let wallet = step("miner-002", "wallet");
+    assert_eq!(wallet["start_time"], 4);
+    assert_eq!(wallet["after"], "daemon");
+    assert_eq!(step("miner-002", "script")["start_time"], 7);
+    assert_eq!(step("miner-002", "mining_script")["start_time"], 17);
+    assert_eq!(step("simulation_monitor", "monitor")["start_time"], 5);
+    assert_eq!(step("miner_distributor", "distributor")["start_time"], 1200);
+
+    // Hardcoded mode starts its seed nodes together at block maturity
+    let (_, daemons) =
+        planned_startup("  peer_mode: Hardcoded\n  seed_nodes: [\"10.0.0.1:18080\"]\n");
+    assert_eq!(
+        &daemons[..2],
+        [("miner-001".into(), 0), ("miner-002".into(), 2)]
+    );
+    assert!(daemons[2..].iter().all(|(_, t)| *t == 600), "{:?}", daemons);
+}
+
+#[test]
+fn workloads_write_send_times_and_reach_the_agent() {
+    let tmp = TempDir::new().unwrap();
+    let yaml = "general:\n  stop_time: 2h\n  simulation_seed: 42\n  fallback_seeds: off\n\
+                network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+                agents:\n  user-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+                \x20   script: agents.regular_user\n    start_time: 10m\n\
+                \x20   workload:\n      profile: bursty\n      burst_size: 3\n\
+                \x20     burst_interval: 10s\n      idle: 30m\n\
+                \x20 user-002:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+                \x20   script: agents.regular_user\n    start_time: 0s\n\
+                \x20   workload:\n      profile: poisson\n      rate: 60\n";
+    let plan = plan_yaml(&tmp, yaml).expect("orchestrator plans");
+
+    let workload_file = tmp.path().join("shared/user-001_workload.json");
+    let argv = regular_user_argv(&plan, "user-001");
+    let at = argv.iter().position(|a| a == "--workload-file").unwrap();
+    assert_eq!(argv[at + 1], workload_file.to_string_lossy());
+
+    // Planned into the output directory, copied to the shared one on write
+    assert!(plan
+        .shared_files
+        .contains(&PathBuf::from("user-001_workload.json")));
+    let (_, json) = plan
+        .files
+        .iter()
+        .find(|(path, _)| *path == tmp.path().join("user-001_workload.json"))
+        .expect("workload file planned");
+    let workload: serde_json::Value = serde_json::from_str(json).unwrap();
+    assert_eq!(workload["profile"]["profile"], "bursty");
+    assert_eq!(workload["profile"]["idle"], 1800);
+    assert_eq!(workload["active_from"], 600);
+    let send_times: Vec<f64> = serde_json::from_value(workload["send_times"].clone()).unwrap();
+    assert_eq!(&send_times[..4], [600.0, 610.0, 620.0, 2420.0]);
+    assert_eq!(send_times.len(), 12);
+
+    // 12 bursty plus ~120 Poisson transactions over two hours
+    let summary = &plan.workload;
+    assert_eq!(summary.agents, 2);
+    assert_eq!(summary.expected_transactions, 12.0 + 120.0);
+    assert_eq!(summary.expected_per_hour, 66.0);
+
+    let conflicting = yaml.replace("start_time: 10m", "transaction_interval: 60");
+    let err = load_yaml(&tmp, &conflicting).unwrap_err();
+    assert!(format!("{:#}", err).contains("not both"), "{:#}", err);
+}
+
+#[test]
+fn wallets_restore_from_seeded_keys_recorded_in_the_registries() {
+    let tmp = TempDir::new().unwrap();
+    let plan_with_seed = |seed: u64| {
+        let yaml = format!(
+            "general:\n  stop_time: 1h\n  simulation_seed: {}\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+             agents:\n\
+             \x20 miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: 100\n\
+             \x20 user-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.regular_user\n\
+             \x20 relay-001:\n    daemon: monerod\n",
+            seed
+        );
+        plan_yaml(&tmp, &yaml).expect("orchestrator plans")
+    };
+    let has_keys = |plan: &orchestrator::GenerationPlan, id: &str| {
+        let name = format!("{}_wallet_keys.json", id);
+        plan.files.iter().any(|(p, _)| p.ends_with(&name))
+    };
+    let plan = plan_with_seed(42);
+
+    let keys = planned_json(&plan, "miner-001_wallet_keys.json");
+    assert_eq!(keys["filename"], "miner-001_wallet");
+    assert_eq!(
+        keys["spendkey"],
+        "61e33f6125f2960d7aba494b015568ee4f2b983c204fb6f0a64c72a0b3036808"
+    );
+    let address = keys["address"].as_str().unwrap().to_string();
+    assert_eq!(
+        address,
+        "4AyPBnsqFLnKTFRJgG7nxYf99mb9mj4313GNsXqpKq2yGCsGEQRxRZB4Zh3ajeU565hJvUZrdpDypEPgz3yvur3GVHnzUef"
+    );
+    assert!(has_keys(&plan, "user-001"));
+    assert!(!has_keys(&plan, "relay-001"));
+
+    let shared_dir = tmp.path().join("shared");
+    let argv = regular_user_argv(&plan, "user-001");
+    let at = argv.iter().position(|a| a == "--wallet-keys-file").unwrap();
+    assert_eq!(
+        argv[at + 1],
+        shared_dir
+            .join("user-001_wallet_keys.json")
+            .to_string_lossy()
+    );
+
+    // The address the wallet will restore to is known before the run
+    let wallet_address = |id: &str| registered(&plan, id)["wallet_address"].clone();
+    assert_eq!(wallet_address("miner-001"), address.as_str());
+    assert_eq!(wallet_address("relay-001"), serde_json::Value::Null);
+    let miners = planned_json(&plan, "miners.json");
+    assert_eq!(miners["miners"][0]["wallet_address"], address.as_str());
+
+    // Same seed, same wallets; another seed, other wallets
+    assert_eq!(
+        planned_json(&plan_with_seed(42), "miner-001_wallet_keys.json"),
+        keys
+    );
+    assert_ne!(
+        planned_json(&plan_with_seed(43), "miner-001_wallet_keys.json")["address"],
+        address.as_str()
+    );
+}
+
+#[test]
+fn monitor_metrics_and_output_format_reach_the_monitor() {
+    let tmp = TempDir::new().unwrap();
+    let plan_monitor = |fields: &str| {
+        let yaml = format!(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n\
+             agents:\n\
+             \x20 user-001:\n    daemon: monerod\n\
+             \x20 simulation-monitor:\n    script: agents.simulation_monitor\n{}",
+            fields
+        );
+        let mut config = load_yaml(&tmp, &yaml)?;
+        config.general.shared_dir = "/tmp/monitor-shared".to_string();
+        let plan = plan_config(&tmp, &config)?;
+        Ok::<_, color_eyre::Report>(plan.scripts.get("simulation-monitor_wrapper.sh").unwrap())
+    };
+
+    let script = plan_monitor("    metrics: [height, mempool]\n    output_format: csv\n").unwrap();
+    assert!(
+        script.contains(
+            "--metrics height,mempool --output-format csv \
+             --metrics-file /tmp/monitor-shared/monitor_metrics.csv"
+        ),
+        "{}",
+        script
+    );
+
+    let script = plan_monitor("    output_format: jsonl\n").unwrap();
+    assert!(
+        script.contains("--metrics height,mempool,peers,bandwidth --output-format jsonl"),
+        "{}",
+        script
+    );
+    assert!(!plan_monitor("").unwrap().contains("--metrics"));
+
+    let err = format!(
+        "{:?}",
+        plan_monitor("    metrics: [latency]\n").unwrap_err()
+    );
+    assert!(err.contains("unknown variant `latency`"), "{}", err);
+}
+
+#[test]
+fn agent_wrappers_wait_for_their_rpcs_with_the_healthcheck() {
+    let tmp = TempDir::new().unwrap();
+    let mut config = load_yaml(
+        &tmp,
+        "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+         network:\n  type: 1_gbit_switch\n\
+         agents:\n\
+         \x20 miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+         \x20   script: agents.autonomous_miner\n    hashrate: 100\n\
+         \x20 user-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+         \x20   script: agents.regular_user\n",
+    )
+    .unwrap();
+    config.general.shared_dir = "/tmp/ready-shared".to_string();
+    let plan = plan_config(&tmp, &config).expect("orchestrator plans");
+    let ip = |id: &str| plan.shadow_config.hosts[id].ip_addr.clone().unwrap();
+
+    let script = plan.scripts.get("agent_user-001_wrapper.sh").unwrap();
+    let user_ip = ip("user-001");
+    assert!(
+        script.contains("if command -v monerosim-healthcheck"),
+        "{}",
+        script
+    );
+    for expected in [
+        format!(
+            "--url http://{}:18081/json_rpc --method get_info --expect-json-field result.height",
+            user_ip
+        ),
+        format!(
+            "--url http://{}:18082/json_rpc --method get_version --expect-json-field result.version",
+            user_ip
+        ),
+        "--report /tmp/ready-shared/readiness/user-001_wallet.json &".to_string(),
+    ] {
+        assert!(script.contains(&expected), "{}\n{}", expected, script);
+    }
+    let wait = script.find("  wait\nfi").unwrap();
+    assert!(wait < script.find("exec python3").unwrap());
+
+    let script = plan
+        .scripts
+        .get("mining_agent_miner-001_wrapper.sh")
+        .unwrap();
+    assert!(
+        script.contains("--report /tmp/ready-shared/readiness/miner-001_daemon.json"),
+        "{}",
+        script
+    );
+}
@@ -0,0 +1,478 @@
+//! Mining: block interval, mining pools, the miner distributor, relative
+//! hashrates and warm starts from existing chain data.
+
+mod common;
+
+use common::*;
+use monerosim::orchestrator;
+use monerosim::utils::SimDuration;
+use tempfile::TempDir;
+
+#[test]
+fn block_interval_sets_maturity_and_reaches_the_miners() {
+    let tmp = TempDir::new().unwrap();
+    let plan_with = |extra: &str| {
+        let yaml = format!(
+            "general:\n  stop_time: 3h\n  fallback_seeds: off\n{}\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+             agents:\n\
+             \x20 miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: 100\n\
+             \x20 user-001:\n    daemon: monerod\n\
+             \x20 miner_distributor:\n    script: agents.miner_distributor\n",
+            extra
+        );
+        plan_yaml(&tmp, &yaml)
+    };
+    let start = |plan: &orchestrator::GenerationPlan, agent: &str, step: &str| {
+        planned_json(plan, "startup_schedule.json")["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|e| e["agent_id"] == agent && e["step"] == step)
+            .unwrap_or_else(|| panic!("{} {}", agent, step))["start_time"]
+            .as_u64()
+            .unwrap()
+    };
+    let mining_script = |plan: &orchestrator::GenerationPlan| {
+        plan.scripts
+            .get("mining_agent_miner-001_wrapper.sh")
+            .expect("mining script planned")
+    };
+    let interval_warnings = |plan: &orchestrator::GenerationPlan| {
+        plan.report
+            .issues
+            .iter()
+            .filter(|i| i.message.contains("block_interval"))
+            .count()
+    };
+
+    // Monero's 120s: 60 blocks mature after 2h, the distributor waits two
+    // maturity windows
+    let plan = plan_with("").expect("plans");
+    assert_eq!(start(&plan, "user-001", "daemon"), 7200);
+    assert_eq!(start(&plan, "miner_distributor", "distributor"), 14400);
+    assert!(mining_script(&plan).contains("--block-interval 120"));
+    assert_eq!(interval_warnings(&plan), 0);
+
+    // 30s blocks mature four times sooner
+    let fast = plan_with("  fixed_difficulty: 1\nblock_controller:\n  block_interval: 30s\n")
+        .expect("plans");
+    assert_eq!(start(&fast, "user-001", "daemon"), 1800);
+    assert_eq!(start(&fast, "miner_distributor", "distributor"), 3600);
+    assert!(mining_script(&fast).contains("--block-interval 30"));
+    assert_eq!(interval_warnings(&fast), 0);
+
+    // An explicit block_maturity still wins; without fixed_difficulty the
+    // interval is warned about
+    let paced =
+        plan_with("  startup:\n    block_maturity: 10m\nblock_controller:\n  block_interval: 1m\n")
+            .expect("plans");
+    assert_eq!(start(&paced, "miner_distributor", "distributor"), 1200);
+    assert_eq!(interval_warnings(&paced), 1);
+
+    for bad in ["0s", "soon"] {
+        let Err(err) = plan_with(&format!("block_controller:\n  block_interval: {}\n", bad)) else {
+            panic!("block_interval {} accepted", bad);
+        };
+        assert!(
+            format!("{:#}", err).contains("block_controller.block_interval"),
+            "{:#}",
+            err
+        );
+    }
+}
+
+#[test]
+fn mining_pools_claim_miners_and_get_their_own_registries() {
+    let tmp = TempDir::new().unwrap();
+    let plan_with = |pools: &str| {
+        let miner = |id: &str, region: &str| {
+            format!(
+                "\x20 {}:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+                 \x20   script: agents.autonomous_miner\n    hashrate: 25\n\
+                 \x20   attributes:\n      region: {}\n",
+                id, region
+            )
+        };
+        let yaml = format!(
+            "general:\n  stop_time: 3h\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+             block_controller:\n  pools:\n{}agents:\n{}{}{}{}\
+             \x20 user-001:\n    daemon: monerod\n",
+            pools,
+            miner("miner-001", "east"),
+            miner("miner-002", "east"),
+            miner("miner-003", "west"),
+            miner("miner-004", "west"),
+        );
+        plan_yaml(&tmp, &yaml)
+    };
+    let members = |plan: &orchestrator::GenerationPlan, name: &str| -> Vec<String> {
+        planned_json(plan, name)["miners"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["agent_id"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let plan = plan_with(
+        "    east:\n      miners:\n        attribute: {region: east}\n\
+         \x20   solo:\n      miners: [miner-003]\n\
+         \x20   rest:\n      miners: {fraction: 0.25}\n",
+    )
+    .expect("plans");
+    assert_eq!(
+        members(&plan, "miners_east.json"),
+        ["miner-001", "miner-002"]
+    );
+    assert_eq!(members(&plan, "miners_solo.json"), ["miner-003"]);
+    assert_eq!(members(&plan, "miners_rest.json"), ["miner-004"]);
+    // miners.json keeps every miner, each tagged with its pool
+    let all = planned_json(&plan, "miners.json");
+    let pools: Vec<(&str, &str)> = all["miners"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| (m["agent_id"].as_str().unwrap(), m["pool"].as_str().unwrap()))
+        .collect();
+    assert_eq!(
+        pools,
+        [
+            ("miner-001", "east"),
+            ("miner-002", "east"),
+            ("miner-003", "solo"),
+            ("miner-004", "rest")
+        ]
+    );
+    let script = plan
+        .scripts
+        .get("mining_agent_miner-002_wrapper.sh")
+        .expect("mining script planned");
+    assert!(
+        script.contains("--pool east --pool-registry "),
+        "{}",
+        script
+    );
+    assert!(script.contains("miners_east.json"), "{}", script);
+    assert!(!plan.report.issues.iter().any(|i| i.category == "mining"));
+
+    // A pool that matches nobody still gets its (empty) registry, with a
+    // warning
+    let plan = plan_with(
+        "    all:\n      miners: {fraction: 1.0}\n\
+         \x20   north:\n      miners:\n        attribute: {region: north}\n",
+    )
+    .expect("plans");
+    assert_eq!(members(&plan, "miners_all.json").len(), 4);
+    assert!(members(&plan, "miners_north.json").is_empty());
+    assert!(plan
+        .report
+        .issues
+        .iter()
+        .any(|i| i.category == "mining" && i.message.contains("'north' has no miners")));
+
+    // Without pools there are no pool registries and no --pool
+    let plan = plan_with("    {}\n").expect("plans");
+    assert!(!plan.files.iter().any(|(p, _)| p
+        .file_name()
+        .is_some_and(|n| n.to_string_lossy().starts_with("miners_"))));
+    assert!(!plan
+        .scripts
+        .get("mining_agent_miner-001_wrapper.sh")
+        .expect("mining script planned")
+        .contains("--pool"));
+
+    for (pools, error) in [
+        (
+            "    a:\n      miners: [miner-001, miner-002]\n\
+             \x20   b:\n      miners: [miner-002, miner-003, miner-004]\n",
+            "miner 'miner-002' is claimed by both 'a' and 'b'",
+        ),
+        (
+            "    a:\n      miners: [miner-001]\n",
+            "miners miner-002, miner-003, miner-004 are in no pool",
+        ),
+        (
+            "    a:\n      miners: [user-001]\n",
+            "agent 'user-001' is not a miner",
+        ),
+        (
+            "    a:\n      miners: [miner-009]\n",
+            "unknown agent 'miner-009'",
+        ),
+        (
+            "    a:\n      miners: {fraction: 0.75}\n    b:\n      miners: {fraction: 0.5}\n",
+            "fraction 0.5 asks for 2 miners but only 1 are left unclaimed",
+        ),
+        (
+            "    a:\n      miners: {fraction: 1.5}\n",
+            "fraction must be in (0, 1]",
+        ),
+        (
+            "    a b:\n      miners: {fraction: 1.0}\n",
+            "pool name 'a b'",
+        ),
+        (
+            "    a:\n      miners: all\n",
+            "expected a list of agent ids",
+        ),
+    ] {
+        let Err(err) = plan_with(pools) else {
+            panic!("{} accepted", pools);
+        };
+        assert!(format!("{:#}", err).contains(error), "{:#}", err);
+    }
+}
+
+#[test]
+fn miner_distributor_section_plans_payouts_and_selects_recipients() {
+    let tmp = TempDir::new().unwrap();
+    let plan_with = |section: &str| {
+        let yaml = format!(
+            "general:\n  stop_time: 3h\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+             {}agents:\n\
+             \x20 miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: 100\n\
+             \x20 user-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   transaction_interval: 60\n\
+             \x20 user-002:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   transaction_interval: 180\n    can_receive_distributions: false\n\
+             \x20 miner_distributor:\n    script: agents.miner_distributor\n",
+            section
+        );
+        plan_yaml(&tmp, &yaml)
+    };
+    // The distribution plan is only written when the section is set
+    let planned = |plan: &orchestrator::GenerationPlan, name: &str| {
+        plan.files
+            .iter()
+            .find(|(p, _)| p.ends_with(name))
+            .map(|(_, json)| serde_json::from_str::<serde_json::Value>(json).unwrap())
+    };
+    let receivers = |plan: &orchestrator::GenerationPlan| -> Vec<String> {
+        planned_json(plan, "agent_registry.json")["agents"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|a| a["attributes"]["can_receive_distributions"] == "true")
+            .map(|a| a["id"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    // Without the section nothing changes: agents opt in themselves
+    let plan = plan_with("").expect("plans");
+    assert!(planned(&plan, "distribution_plan.json").is_none());
+    assert!(receivers(&plan).is_empty());
+
+    let plan = plan_with(
+        "miner_distributor:\n  policy: proportional\n  payout_interval: 10m\n  min_payout: 1.5\n",
+    )
+    .expect("plans");
+    let distribution = planned(&plan, "distribution_plan.json").expect("plan written");
+    assert_eq!(distribution["policy"], "proportional");
+    assert_eq!(distribution["payout_interval"], 600);
+    assert_eq!(distribution["min_payout"], 1.5);
+    let factors: Vec<(&str, f64)> = distribution["recipients"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            (
+                r["agent_id"].as_str().unwrap(),
+                r["payout_factor"].as_f64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(factors, vec![("user-001", 1.5), ("user-002", 0.5)]);
+    // user-002's own `false` gives way to the selector, with a warning
+    assert_eq!(receivers(&plan), vec!["user-001", "user-002"]);
+    assert!(plan
+        .report
+        .issues
+        .iter()
+        .any(|i| i.message.contains("user-002") && i.message.contains("replaced")));
+
+    let plan = plan_with("miner_distributor:\n  recipients: all_users\n").expect("plans");
+    assert_eq!(receivers(&plan), vec!["miner-001", "user-001", "user-002"]);
+    assert_eq!(
+        planned_json(&plan, "distribution_plan.json")["policy"],
+        "equal"
+    );
+
+    for (section, field) in [
+        ("  policy: custom_script\n", "requires a script"),
+        ("  script: payouts\n", "custom_script"),
+        ("  payout_interval: 0s\n", "payout_interval must be"),
+        ("  min_payout: -1\n", "miner_distributor.min_payout"),
+        ("  recipients:\n    attribute: {}\n", "recipients.attribute"),
+    ] {
+        let Err(err) = plan_with(&format!("miner_distributor:\n{}", section)) else {
+            panic!("{} accepted", section);
+        };
+        assert!(format!("{:#}", err).contains(field), "{:#}", err);
+    }
+}
+
+#[test]
+fn relative_hashrates_resolve_to_weights_and_shares() {
+    let tmp = TempDir::new().unwrap();
+    let load = |distribution: &str, pool_hashrate: &str| {
+        let yaml = format!(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n  hashrate_distribution: {}\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+             agents:\n  pool:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: {}\n\
+             \x20 miner:\n    count: 4\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: auto\n",
+            distribution, pool_hashrate
+        );
+        plan_yaml(&tmp, &yaml)
+    };
+    let miners = |plan: &orchestrator::GenerationPlan| -> Vec<(String, f64, f64)> {
+        planned_json(plan, "miners.json")["miners"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| {
+                (
+                    m["agent_id"].as_str().unwrap().to_string(),
+                    m["weight"].as_f64().unwrap(),
+                    m["share"].as_f64().unwrap(),
+                )
+            })
+            .collect()
+    };
+
+    let plan = load("equal", "\"40%\"").expect("orchestrator plans");
+    let resolved = miners(&plan);
+    assert_eq!(resolved.len(), 5);
+    for (id, weight, share) in &resolved {
+        let expected = if id == "pool" { 40.0 } else { 15.0 };
+        assert_eq!(*weight, expected, "{}", id);
+        assert!((share - expected / 100.0).abs() < 1e-9, "{}", id);
+    }
+    // The mining agents get the resolved weight, not "auto"
+    assert!(regular_user_argv(&plan, "miner-001")
+        .windows(3)
+        .any(|w| w == ["--attributes", "hashrate", "15"]));
+
+    // Pareto draws are skewed, sum to what the percentages leave and are
+    // reproducible
+    let plan = load("pareto(1.5)", "\"40%\"").expect("orchestrator plans");
+    let resolved = miners(&plan);
+    let auto: Vec<f64> = resolved
+        .iter()
+        .filter(|(id, _, _)| id != "pool")
+        .map(|(_, w, _)| *w)
+        .collect();
+    assert!((auto.iter().sum::<f64>() - 60.0).abs() < 0.01);
+    assert!(auto.iter().any(|w| *w > 15.0) && auto.iter().any(|w| *w < 15.0));
+    assert_eq!(miners(&load("pareto(1.5)", "\"40%\"").unwrap()), resolved);
+    let total_share: f64 = resolved.iter().map(|(_, _, s)| s).sum();
+    assert!((total_share - 1.0).abs() < 1e-4);
+
+    for (distribution, hashrate, expected) in [
+        (
+            "explicit",
+            "\"40%\"",
+            "hashrate auto but general.hashrate_distribution is explicit",
+        ),
+        ("equal", "lots", "agent 'pool': invalid hashrate \"lots\""),
+        ("equal", "\"100%\"", "leaving nothing"),
+    ] {
+        let Err(err) = load(distribution, hashrate) else {
+            panic!("{} with {} should fail", distribution, hashrate);
+        };
+        let err = format!("{:?}", err);
+        assert!(err.contains(expected), "{}", err);
+    }
+}
+
+#[test]
+fn warm_start_keeps_the_process_sequence_and_seeds_chain_data() {
+    use monerosim::validation_report::Severity;
+
+    let tmp = TempDir::new().unwrap();
+    let source = tmp.path().join("source");
+    let data = tmp.path().join("data");
+    std::fs::create_dir_all(source.join("monero-miner-001/lmdb")).unwrap();
+    std::fs::write(source.join("monero-miner-001/lmdb/data.mdb"), b"chain").unwrap();
+    let load = |general: &str| {
+        let yaml = format!(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n{}\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+             agents:\n\
+             \x20 miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: 100\n\
+             \x20 user-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n",
+            general
+        );
+        load_yaml(&tmp, &yaml).map(|mut config| {
+            config.general.daemon_data_dir = data.to_string_lossy().to_string();
+            config
+        })
+    };
+    let sequence = |plan: &orchestrator::GenerationPlan, id: &str| -> Vec<(String, SimDuration)> {
+        plan.shadow_config.hosts[id]
+            .processes
+            .iter()
+            .map(|p| (p.path.clone(), p.start_time))
+            .collect()
+    };
+    let output_yaml = tmp.path().join("out.yaml");
+
+    let fresh = plan_config(&tmp, &load("").unwrap()).expect("fresh plan");
+    assert!(fresh.warm_start.is_none());
+    let miner_script = |plan: &orchestrator::GenerationPlan| {
+        plan.scripts
+            .get("mining_agent_miner-001_wrapper.sh")
+            .unwrap()
+    };
+    assert!(!miner_script(&fresh).contains("--warm-start"));
+
+    let source_arg = format!(
+        "  fresh_blockchain: false\n  blockchain_source_dir: {}\n",
+        source.display()
+    );
+    let config = load(&source_arg).unwrap();
+    let warm = plan_config(&tmp, &config).expect("warm plan");
+    // Same processes at the same times; only the miner script learns of the chain
+    for id in ["miner-001", "user-001"] {
+        assert_eq!(sequence(&warm, id), sequence(&fresh, id));
+    }
+    assert!(miner_script(&warm).contains("--warm-start"));
+    let warm_start = warm.warm_start.as_ref().unwrap();
+    assert_eq!(
+        warm_start.copies,
+        [(
+            source.join("monero-miner-001"),
+            data.join("monero-miner-001")
+        )]
+    );
+    // user-001 has no chain data and syncs from genesis
+    assert_eq!(warm.report.count(Severity::Warning), 1);
+    assert!(!data.exists(), "planning must not copy");
+
+    orchestrator::generate_agent_shadow_config(&config, None, &output_yaml)
+        .expect("orchestrator generates");
+    assert_eq!(
+        std::fs::read(data.join("monero-miner-001/lmdb/data.mdb")).unwrap(),
+        b"chain"
+    );
+
+    let err = load(&format!("  blockchain_source_dir: {}\n", source.display())).unwrap_err();
+    assert!(
+        format!("{:#}", err).contains("requires fresh_blockchain: false"),
+        "{:#}",
+        err
+    );
+    let missing = source_arg.replace(
+        &source.display().to_string(),
+        &tmp.path().join("missing").display().to_string(),
+    );
+    let err = plan_config(&tmp, &load(&missing).unwrap()).err().unwrap();
+    assert!(err.to_string().contains("is not a directory"), "{}", err);
+}
@@ -0,0 +1,601 @@
+//! Peer wiring and discovery: peer modes and limits, the DNS server, spies,
+//! hardcoded topologies, unreachable agents, seed selection and wallet-only
+//! agents.
+
+mod common;
+
+use common::*;
+use monerosim::orchestrator;
+use tempfile::TempDir;
+
+#[test]
+fn hybrid_options_limit_each_regular_agents_peer_mix() {
+    let tmp = TempDir::new().unwrap();
+    let mut yaml = String::from(
+        "general:\n  stop_time: 1h\n  simulation_seed: 42\n  fallback_seeds: off\n\
+         network:\n  type: 1_gbit_switch\n  peer_mode: Hybrid\n  topology: Mesh\n\
+         \x20 seed_nodes: [\"10.0.0.1:18080\"]\n\
+         \x20 hybrid_options:\n    seed_connections: 2\n    topology_connections: 6\n\
+         agents:\n  miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+         \x20   script: agents.autonomous_miner\n    hashrate: 100\n",
+    );
+    for n in 1..20 {
+        yaml.push_str(&format!("  user-{:03}:\n    daemon: monerod\n", n));
+    }
+    let plan = plan_yaml(&tmp, &yaml).expect("orchestrator plans");
+
+    let sources = planned_json(&plan, "peer_sources.json");
+    let sources = sources.as_object().unwrap();
+    // 20 agents: one miner and five promoted seeds keep their own wiring
+    assert_eq!(sources.len(), 14);
+    for (agent, peers) in sources {
+        let count = |src: &str| {
+            peers
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|p| p["source"] == src)
+                .count()
+        };
+        assert_eq!(count("seed"), 2, "{}", agent);
+        assert_eq!(count("topology"), 6, "{}", agent);
+
+        let host = &plan.shadow_config.hosts[agent.as_str()];
+        let args = serde_json::to_string(&host.processes).unwrap();
+        assert_eq!(args.matches("--add-priority-node=").count(), 2, "{}", agent);
+        assert_eq!(args.matches("--seed-node=").count(), 6, "{}", agent);
+    }
+}
+
+#[test]
+fn peer_limits_reach_the_daemons_and_the_registry() {
+    let tmp = TempDir::new().unwrap();
+    let plan = plan_yaml(
+        &tmp,
+        "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+         network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n  out_peers: 8\n\
+         agents:\n\
+         \x20 miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+         \x20   script: agents.autonomous_miner\n    hashrate: 100\n\
+         \x20 user-001:\n    daemon: monerod\n    out_peers: 16\n    in_peers: 32\n\
+         \x20 user-002:\n    daemon: monerod\n    daemon_options: {out-peers: 4}\n\
+         \x20 isolated:\n    daemon: monerod\n    out_peers: 0\n    in_peers: 0\n",
+    )
+    .expect("orchestrator plans");
+
+    for (id, out_peers, in_peers) in [
+        ("miner-001", 8, None),
+        ("user-001", 16, Some(32)),
+        ("user-002", 4, None),
+        ("isolated", 0, Some(0)),
+    ] {
+        let args = serde_json::to_string(&plan.shadow_config.hosts[id].processes).unwrap();
+        assert!(
+            args.contains(&format!("--out-peers={}", out_peers)),
+            "{}",
+            id
+        );
+        assert_eq!(args.contains("--in-peers="), in_peers.is_some(), "{}", id);
+
+        let agent = registered(&plan, id);
+        assert_eq!(agent["out_peers"], out_peers, "{}", id);
+        assert_eq!(agent["in_peers"].as_u64(), in_peers, "{}", id);
+    }
+}
+
+#[test]
+fn invalid_peer_limits_are_rejected() {
+    for (network, agent, expected) in [
+        (
+            "",
+            "    out_peers: 0\n",
+            "out_peers 0 leaves the daemon only inbound peers",
+        ),
+        (
+            "  in_peers: 0\n",
+            "    out_peers: 0\n    in_peers: 5\n",
+            "out_peers 0",
+        ),
+        (
+            "",
+            "    out_peers: 300\n",
+            "out_peers 300 is over network.max_peers 256",
+        ),
+        (
+            "  max_peers: 10\n",
+            "    in_peers: 11\n",
+            "in_peers 11 is over network.max_peers 10",
+        ),
+        (
+            "  out_peers: 500\n",
+            "",
+            "out_peers 500 is over max_peers 256",
+        ),
+        (
+            "",
+            "    out_peers: 8\n    daemon_options: {out-peers: 4}\n",
+            "out_peers conflicts with daemon_options.out-peers",
+        ),
+    ] {
+        let tmp = TempDir::new().unwrap();
+        let yaml = format!(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n{}\
+             agents:\n  user-001:\n    daemon: monerod\n{}",
+            network, agent
+        );
+        let err = format!("{:?}", load_yaml(&tmp, &yaml).unwrap_err());
+        assert!(err.contains(expected), "{}{} => {}", network, agent, err);
+    }
+}
+
+#[test]
+fn dns_server_serves_the_daemons_that_opted_in() {
+    let tmp = TempDir::new().unwrap();
+    let config = load_yaml(
+        &tmp,
+        "general:\n  stop_time: 1h\n  fallback_seeds: off\n  enable_dns_server: true\n\
+         network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+         agents:\n\
+         \x20 miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+         \x20   script: agents.autonomous_miner\n    hashrate: 100\n\
+         \x20 user-001:\n    daemon: monerod\n\
+         \x20 user-002:\n    daemon: monerod\n    discovery: dns\n\
+         \x20 user-003:\n    daemon: monerod\n    discovery: seeds\n",
+    )
+    .expect("config loads");
+    let output_yaml = tmp.path().join("out/shadow_agents.yaml");
+    let result = orchestrator::generate(&config, None, &output_yaml).expect("generation succeeds");
+    let hosts = &result.shadow_config().hosts;
+
+    let dns_hosts: Vec<&String> = hosts
+        .iter()
+        .filter(|(_, host)| {
+            serde_json::to_string(&host.processes)
+                .unwrap()
+                .contains("dns_server_wrapper.sh")
+        })
+        .map(|(id, _)| id)
+        .collect();
+    assert_eq!(dns_hosts, ["dnsserver"]);
+    let dns_host = &hosts["dnsserver"];
+    assert!(serde_json::to_string(&dns_host.processes[1])
+        .unwrap()
+        .contains("dns_watchdog.sh"));
+    let dns_public = format!("tcp://{}", dns_host.ip_addr.as_ref().unwrap());
+
+    for (id, opted_in) in [
+        ("miner-001", true),
+        ("user-001", true),
+        ("user-002", true),
+        ("user-003", false),
+    ] {
+        let daemon = &hosts[id].processes[0];
+        let args = serde_json::to_string(&daemon.args).unwrap();
+        assert!(args.contains("--p2p-bind-ip"), "{}", id);
+        assert_eq!(
+            daemon.environment.get("DNS_PUBLIC"),
+            opted_in.then_some(&dns_public),
+            "{}",
+            id
+        );
+        assert_eq!(
+            args.contains("--disable-dns-checkpoints"),
+            !opted_in,
+            "{}",
+            id
+        );
+    }
+    // DNS-only daemons get no seed links, the others keep theirs
+    let args = |id: &str| serde_json::to_string(&hosts[id].processes[0].args).unwrap();
+    assert!(!args("user-002").contains("--seed-node"));
+    assert!(args("user-001").contains("--seed-node"));
+    assert!(args("user-003").contains("--seed-node"));
+
+    let dns_entry = result
+        .agent_registry()
+        .agents
+        .iter()
+        .find(|a| a.id == "dnsserver")
+        .expect("DNS server registered");
+    assert_eq!(dns_entry.role.as_deref(), Some("infrastructure"));
+    assert_eq!(&dns_entry.ip_addr, dns_host.ip_addr.as_ref().unwrap());
+
+    // The binary's write path produces the same Shadow config
+    orchestrator::generate_and_write(&config, None, &output_yaml, false).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&output_yaml).unwrap(),
+        serde_yaml::to_string(result.shadow_config()).unwrap()
+    );
+}
+
+#[test]
+fn discovery_requires_the_dns_server() {
+    let tmp = TempDir::new().unwrap();
+    let err = load_yaml(
+        &tmp,
+        "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+         network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+         agents:\n  user-001:\n    daemon: monerod\n    discovery: dns\n",
+    )
+    .unwrap_err();
+    let err = format!("{:?}", err);
+    assert!(
+        err.contains("requires general.enable_dns_server"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn spy_agents_connect_to_their_targets_and_get_a_registry_role() {
+    let tmp = TempDir::new().unwrap();
+    let yaml = |targets: &str| {
+        format!(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+             agents:\n\
+             \x20 miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: 50\n\
+             \x20 miner-002:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: 50\n\
+             \x20 user-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.regular_user\n\
+             \x20 spy-001:\n    daemon: monerod\n\
+             \x20   daemon_options:\n      out-peers: 64\n      in-peers: 128\n\
+             \x20   attributes:\n      is_spy: \"true\"\n      spy_targets: \"{}\"\n",
+            targets
+        )
+    };
+    let plan_for = |targets: &str| plan_yaml(&tmp, &yaml(targets)).expect("orchestrator plans");
+
+    let plan = plan_for("user-001, miner-002");
+    let agent = |id: &str| registered(&plan, id);
+    assert_eq!(agent("spy-001")["role"], "spy");
+    assert!(agent("user-001").get("role").is_none());
+    assert_eq!(agent("spy-001")["wallet"], false);
+
+    let spy = &plan.shadow_config.hosts["spy-001"].processes;
+    assert_eq!(spy.len(), 1, "a spy runs only its daemon");
+    let args = serde_json::to_string(spy).unwrap();
+    for id in ["user-001", "miner-002"] {
+        let peer = format!(
+            "--add-priority-node={}:18080",
+            agent(id)["ip_addr"].as_str().unwrap()
+        );
+        assert!(args.contains(&peer), "{}", args);
+    }
+    assert!(!args.contains("--seed-node="), "{}", args);
+    assert!(args.contains("--out-peers=64") && args.contains("--in-peers=128"));
+
+    let args =
+        serde_json::to_string(&plan_for("random:1").shadow_config.hosts["spy-001"].processes)
+            .unwrap();
+    assert_eq!(args.matches("--add-priority-node=").count(), 1, "{}", args);
+
+    let err = format!("{:?}", load_yaml(&tmp, &yaml("spy-001")).unwrap_err());
+    assert!(err.contains("Spy configuration error"), "{}", err);
+}
+
+#[test]
+fn hardcoded_small_world_wires_and_records_its_adjacency() {
+    let tmp = TempDir::new().unwrap();
+    let plan_for = |seed: u64| {
+        let mut yaml = format!(
+            "general:\n  stop_time: 1h\n  simulation_seed: {}\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Hardcoded\n\
+             \x20 seed_nodes: [\"10.0.0.1:18080\"]\n\
+             \x20 topology:\n    SmallWorld:\n      k: 4\n      rewire_prob: 0.5\n\
+             agents:\n  miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: 100\n",
+            seed
+        );
+        for n in 1..12 {
+            yaml.push_str(&format!("  user-{:03}:\n    daemon: monerod\n", n));
+        }
+        plan_yaml(&tmp, &yaml).expect("orchestrator plans")
+    };
+
+    let plan = plan_for(7);
+    let connections = planned_json(&plan, "topology_connections.json");
+    assert_eq!(connections["simulation_seed"], 7);
+    assert_eq!(connections["topology"]["SmallWorld"]["k"], 4);
+    let adjacency = connections["adjacency"].as_object().unwrap();
+    assert_eq!(adjacency.len(), 12);
+
+    let ip_of = |id: &str| {
+        registered(&plan, id)["ip_addr"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+    let mut edges = 0;
+    for (agent, peers) in adjacency {
+        let args =
+            serde_json::to_string(&plan.shadow_config.hosts[agent.as_str()].processes).unwrap();
+        for peer in peers.as_array().unwrap() {
+            let peer = peer.as_str().unwrap();
+            // Undirected, and handed to the daemon as a priority peer
+            assert!(adjacency[peer]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|p| p == agent.as_str()));
+            let flag = format!("--add-priority-node={}:18080", ip_of(peer));
+            assert!(args.contains(&flag), "{} lacks {}", agent, flag);
+            edges += 1;
+        }
+    }
+    // Rewiring keeps the ring lattice's 12 * 4 / 2 edges
+    assert_eq!(edges, 48);
+
+    // The same edges show up as priority connections in intended_topology.json
+    let intended = planned_json(&plan, "intended_topology.json");
+    for (agent, peers) in adjacency {
+        let connections = intended[agent.as_str()]["connections"].as_array().unwrap();
+        for peer in peers.as_array().unwrap() {
+            assert!(
+                connections
+                    .iter()
+                    .any(|c| c["target"] == *peer && c["type"] == "priority"),
+                "{} -> {}",
+                agent,
+                peer
+            );
+        }
+    }
+
+    // Same seed, same graph; the seed drives the rewiring
+    assert_eq!(
+        planned_json(&plan_for(7), "topology_connections.json"),
+        connections
+    );
+    assert_ne!(
+        planned_json(&plan_for(8), "topology_connections.json")["adjacency"],
+        connections["adjacency"]
+    );
+}
+
+#[test]
+fn exclusive_enforcement_pins_every_daemon_and_rejects_isolated_ones() {
+    let tmp = TempDir::new().unwrap();
+    let yaml = |peer_mode: &str, miners: usize, users: usize| {
+        let mut yaml = format!(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: {}\n\
+             \x20 seed_nodes: [\"10.0.0.1:18080\"]\n  connection_enforcement: exclusive\n\
+             agents:\n",
+            peer_mode
+        );
+        for n in 1..=miners {
+            yaml.push_str(&format!(
+                "  miner-{:03}:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+                 \x20   script: agents.autonomous_miner\n    hashrate: {}\n",
+                n,
+                100 / miners
+            ));
+        }
+        for n in 1..=users {
+            yaml.push_str(&format!("  user-{:03}:\n    daemon: monerod\n", n));
+        }
+        yaml
+    };
+
+    // miner-003 is promoted to fill the five seeds and pins the seed ring
+    let plan = plan_yaml(&tmp, &yaml("Hardcoded", 3, 4)).expect("orchestrator plans");
+    for (id, host) in &plan.shadow_config.hosts {
+        if !id.starts_with("miner-") && !id.starts_with("user-") {
+            continue;
+        }
+        let args = serde_json::to_string(&host.processes[0].args).unwrap();
+        assert!(args.contains("--add-exclusive-node="), "{}: {}", id, args);
+        assert!(!args.contains("--add-priority-node="), "{}: {}", id, args);
+        assert!(!args.contains("--seed-node="), "{}: {}", id, args);
+    }
+
+    // With the seeds filled by users, a lone miner has no ring partner to pin
+    let Err(err) = plan_yaml(&tmp, &yaml("Hardcoded", 1, 5)) else {
+        panic!("a lone exclusive miner must be rejected");
+    };
+    let err = format!("{:?}", err);
+    assert!(err.contains("would be isolated"), "{}", err);
+
+    let err = format!("{:?}", load_yaml(&tmp, &yaml("Dynamic", 2, 4)).unwrap_err());
+    assert!(
+        err.contains("requires peer_mode Hardcoded or Hybrid"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn unreachable_agents_dial_out_but_are_never_dialed() {
+    let tmp = TempDir::new().unwrap();
+    let yaml = |miner_extra: &str| {
+        let mut yaml = format!(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Hardcoded\n\
+             \x20 seed_nodes: [\"10.0.0.1:18080\"]\n  topology:\n    Tree:\n      fanout: 2\n\
+             agents:\n  miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: 100\n{}",
+            miner_extra
+        );
+        for n in 1..=4 {
+            yaml.push_str(&format!("  user-{:03}:\n    daemon: monerod\n", n));
+        }
+        yaml.push_str("    attributes:\n      is_unreachable: \"true\"\n");
+        yaml
+    };
+    let plan = plan_yaml(&tmp, &yaml("")).expect("orchestrator plans");
+
+    let registry = planned_json(&plan, "agent_registry.json");
+    let agents = registry["agents"].as_array().unwrap();
+    let nat = agents.iter().find(|a| a["id"] == "user-004").unwrap();
+    assert_eq!(nat["reachable"], false);
+    assert!(agents
+        .iter()
+        .filter(|a| a["id"] != "user-004")
+        .all(|a| a.get("reachable").is_none()));
+    let nat_addr = format!("{}:18080", nat["ip_addr"].as_str().unwrap());
+
+    let hosts = &plan.shadow_config.hosts;
+    assert_eq!(hosts["user-004"].blocked_inbound_ports, Some(vec![18080]));
+    let nat_args = serde_json::to_string(&hosts["user-004"].processes[0].args).unwrap();
+    assert!(nat_args.contains("--hide-my-port"), "{}", nat_args);
+    assert!(nat_args.contains("--add-priority-node="), "{}", nat_args);
+    for (id, host) in hosts.iter().filter(|(id, _)| id.as_str() != "user-004") {
+        let args = serde_json::to_string(&host.processes).unwrap();
+        assert!(
+            !args.contains(&nat_addr),
+            "{} dials {}: {}",
+            id,
+            nat_addr,
+            args
+        );
+    }
+
+    let err = format!(
+        "{:?}",
+        load_yaml(
+            &tmp,
+            &yaml("    attributes:\n      is_unreachable: \"true\"\n")
+        )
+        .unwrap_err()
+    );
+    assert!(err.contains("is_unreachable requires"), "{}", err);
+}
+
+#[test]
+fn seed_selection_is_explicit_and_promotions_are_reported() {
+    let tmp = TempDir::new().unwrap();
+    let plan_for = |network: &str| {
+        let mut yaml = format!(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Hardcoded\n{}\
+             agents:\n",
+            network
+        );
+        for n in 1..=2 {
+            yaml.push_str(&format!(
+                "  miner-{:03}:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+                 \x20   script: agents.autonomous_miner\n    hashrate: 50\n",
+                n
+            ));
+        }
+        for n in 1..=4 {
+            yaml.push_str(&format!("  relay-{:03}:\n    daemon: monerod\n", n));
+        }
+        plan_yaml(&tmp, &yaml).expect("orchestrator plans")
+    };
+    let registry_seeds = |plan: &orchestrator::GenerationPlan| {
+        planned_json(plan, "agent_registry.json")["agents"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|a| a["is_seed"] == true)
+            .map(|a| a["id"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    let promotions = |plan: &orchestrator::GenerationPlan| {
+        plan.report
+            .issues
+            .iter()
+            .filter(|i| i.category == "seeds")
+            .count()
+    };
+
+    // auto tops the seed set up to five, warning about every promotion
+    let plan = plan_for("  seed_nodes: [\"10.0.0.1:18080\"]\n");
+    assert_eq!(
+        registry_seeds(&plan),
+        [
+            "miner-002",
+            "relay-001",
+            "relay-002",
+            "relay-003",
+            "relay-004"
+        ]
+    );
+    assert_eq!(promotions(&plan), 5, "{:?}", plan.report);
+
+    // explicit takes exactly the listed agents and promotes nobody
+    let plan = plan_for("  seed_nodes: [relay-002]\n  seed_selection: explicit\n");
+    assert_eq!(registry_seeds(&plan), ["relay-002"]);
+    assert_eq!(promotions(&plan), 0, "{:?}", plan.report);
+    assert!(
+        !plan
+            .report
+            .issues
+            .iter()
+            .any(|i| i.category == "seed_nodes"),
+        "{:?}",
+        plan.report
+    );
+    assert_eq!(plan.seed_nodes.len(), 1);
+    let args = serde_json::to_string(&plan.shadow_config.hosts["relay-001"].processes).unwrap();
+    assert!(
+        args.contains(&format!("--add-priority-node={}", plan.seed_nodes[0])),
+        "{}",
+        args
+    );
+    assert_eq!(args.matches("--add-priority-node=").count(), 1, "{}", args);
+}
+
+#[test]
+fn wallet_only_agents_use_a_remote_public_node() {
+    let tmp = TempDir::new().unwrap();
+    let yaml = |public: &str| {
+        format!(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+             agents:\n  miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: 100\n\
+             \x20 node-001:\n    daemon: monerod\n    attributes:\n      is_public_node: \"{}\"\n\
+             \x20 user-001:\n    daemon:\n      address: node-001\n\
+             \x20   wallet: monero-wallet-rpc\n    script: agents.regular_user\n\
+             \x20 user-002:\n    daemon:\n      strategy: random_public\n\
+             \x20   wallet: monero-wallet-rpc\n    script: agents.regular_user\n",
+            public
+        )
+    };
+    let plan = plan_yaml(&tmp, &yaml("true")).expect("orchestrator plans");
+
+    let agent = |id: &str| registered(&plan, id);
+    let node_rpc = format!("{}:18081", agent("node-001")["ip_addr"].as_str().unwrap());
+    let hosts = &plan.shadow_config.hosts;
+
+    // Explicit target: resolved to the public node's RPC address, no daemon
+    let user = agent("user-001");
+    assert_eq!(user["daemon"], false);
+    assert_eq!(user["remote_daemon"], node_rpc.as_str());
+    let args = serde_json::to_string(&hosts["user-001"].processes).unwrap();
+    assert!(!args.contains("--regtest"), "{}", args);
+    assert!(
+        args.contains(&format!("--daemon-address=http://{}", node_rpc)),
+        "{}",
+        args
+    );
+    let argv = regular_user_argv(&plan, "user-001");
+    let at = argv.iter().position(|a| a == "--remote-daemon").unwrap();
+    assert_eq!(argv[at + 1], node_rpc);
+
+    // Dynamic strategy: left to the agent's runtime discovery
+    assert_eq!(agent("user-002")["remote_daemon"], "auto");
+    assert_eq!(agent("user-002")["daemon_selection_strategy"], "random");
+    let argv = regular_user_argv(&plan, "user-002");
+    let at = argv
+        .iter()
+        .position(|a| a == "--daemon-selection-strategy")
+        .unwrap();
+    assert_eq!(argv[at + 1], "random");
+    let public_nodes = plan
+        .files
+        .iter()
+        .find(|(p, _)| p.ends_with("public_nodes.json"))
+        .unwrap();
+    assert!(public_nodes.1.contains("node-001"));
+
+    // No public node: both wallet-only agents are rejected
+    let err = format!("{:?}", load_yaml(&tmp, &yaml("false")).unwrap_err());
+    assert!(err.contains("remote daemon 'node-001' must be"), "{}", err);
+}
@@ -40,11 +40,11 @@ fn quickstart_fixture_yaml_matches_golden() {
     std::fs::create_dir_all(&shared_dir).unwrap();
     std::fs::create_dir_all(tmp.path().join("scripts")).unwrap();
 
-    let mut config = config_loader::load_config(Path::new("tests/fixtures/quickstart.yaml"))
-        .expect("quickstart fixture loads");
+    let fixture = Path::new("tests/fixtures/quickstart.yaml");
+    let mut config = config_loader::load_config(fixture).expect("quickstart fixture loads");
     config.general.shared_dir = shared_dir.to_string_lossy().to_string();
 
-    orchestrator::generate_agent_shadow_config(&config, &output_yaml)
+    orchestrator::generate_agent_shadow_config(&config, Some(fixture), &output_yaml)
         .expect("orchestrator generates");
 
     let actual = normalize(&std::fs::read_to_string(&output_yaml).unwrap());
@@ -64,3 +64,289 @@ fn quickstart_fixture_yaml_matches_golden() {
          with UPDATE_GOLDEN=1 cargo test --test orchestrator_quickstart",
     );
 }
+
+/// Every file under `dir`, recursively
+fn files_under(dir: &Path) -> Vec<std::path::PathBuf> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(files_under(&path));
+        } else {
+            files.push(path);
+        }
+    }
+    files
+}
+
+#[test]
+fn output_directory_is_self_contained() {
+    let tmp = TempDir::new().unwrap();
+    let out = tmp.path().join("out");
+    let output_yaml = out.join("shadow_agents.yaml");
+    let shared_dir = tmp.path().join("shared");
+    std::fs::create_dir_all(&shared_dir).unwrap();
+
+    let fixture = Path::new("tests/fixtures/quickstart.yaml");
+    let mut config = config_loader::load_config(fixture).expect("quickstart fixture loads");
+    config.general.shared_dir = shared_dir.to_string_lossy().to_string();
+    orchestrator::generate_agent_shadow_config(&config, Some(fixture), &output_yaml)
+        .expect("orchestrator generates");
+
+    let topology = out.join(orchestrator::GML_FILE_NAME);
+    let graph = monerosim::gml_parser::parse_gml_file(topology.to_str().unwrap())
+        .expect("converted GML parses");
+    monerosim::gml_parser::validate_topology(&graph).unwrap();
+
+    // The graph is referenced relative to the Shadow config (Shadow resolves
+    // it against its working directory, which is the output directory)
+    let yaml: serde_yaml::Value =
+        serde_yaml::from_str(&std::fs::read_to_string(&output_yaml).unwrap()).unwrap();
+    assert_eq!(
+        yaml["network"]["graph"]["file"]["path"].as_str(),
+        Some(orchestrator::GML_FILE_NAME)
+    );
+
+    // No file refers to the output directory by its absolute path, except
+    // for what processes are handed: they run in their host's directory
+    // under Shadow's data directory, so the wrapper scripts Shadow launches
+    // and the monitor's `--output-dir` stay absolute
+    let out_str = out.to_string_lossy().to_string();
+    let mentions = Regex::new(&format!(r#"{}[^\s"']*"#, regex::escape(&out_str))).unwrap();
+    let files = files_under(&out);
+    assert!(files.len() > 10, "{:?}", files);
+    for file in files {
+        let text = std::fs::read_to_string(&file).unwrap();
+        for mention in mentions.find_iter(&text) {
+            let rest = &mention.as_str()[out_str.len()..];
+            let launched_script = file == output_yaml
+                && rest.starts_with("/scripts/")
+                && out.join(&rest[1..]).is_file();
+            let monitor_output = file.ends_with("scripts/simulation-monitor_wrapper.sh")
+                && text.contains(&format!("--output-dir {} ", out_str))
+                && rest.is_empty();
+            assert!(
+                launched_script || monitor_output,
+                "{} refers to {}",
+                file.display(),
+                mention.as_str()
+            );
+        }
+    }
+}
+
+#[test]
+fn miner_placement_constrains_miners_and_is_reported() {
+    use monerosim::config::{MinerPlacement, Network};
+    use std::collections::{BTreeMap, BTreeSet};
+
+    let tmp = TempDir::new().unwrap();
+    let output_yaml = tmp.path().join("shadow_agents.yaml");
+    let fixture = Path::new("tests/fixtures/quickstart.yaml");
+    let mut config = config_loader::load_config(fixture).expect("quickstart fixture loads");
+    config.general.shared_dir = tmp.path().join("shared").to_string_lossy().to_string();
+    let mut plan_with = |placement: MinerPlacement| {
+        if let Some(Network::Gml {
+            miner_placement, ..
+        }) = &mut config.network
+        {
+            *miner_placement = Some(placement);
+        }
+        config.validate().map_err(|e| e.to_string())?;
+        orchestrator::plan_agent_shadow_config(&config, &output_yaml).map_err(|e| e.to_string())
+    };
+
+    let plan = plan_with(MinerPlacement::SpreadAs).unwrap();
+    let locations = &plan.miner_locations;
+    assert_eq!(locations.len(), 5);
+    let ases: BTreeSet<_> = locations.values().map(|l| l.as_number.clone()).collect();
+    assert_eq!(ases.len(), 5);
+    for (agent_id, location) in locations {
+        assert_eq!(
+            plan.shadow_config.hosts[agent_id].network_node_id,
+            location.node
+        );
+    }
+
+    let plan = plan_with(MinerPlacement::Colocated {
+        as_number: "7".to_string(),
+    })
+    .unwrap();
+    assert!(plan
+        .miner_locations
+        .values()
+        .all(|l| l.node == 7 && l.as_number.as_deref() == Some("7")));
+
+    let nodes: BTreeMap<usize, u32> = (0..5).map(|i| (i, 100 + i as u32)).collect();
+    let plan = plan_with(MinerPlacement::Explicit { nodes }).unwrap();
+    let mut placed: Vec<u32> = plan.miner_locations.values().map(|l| l.node).collect();
+    placed.sort();
+    assert_eq!(placed, [100, 101, 102, 103, 104]);
+
+    let err = plan_with(MinerPlacement::Explicit {
+        nodes: BTreeMap::from([(0, 1)]),
+    })
+    .err()
+    .unwrap();
+    assert!(err.contains("maps 1 of 5 miners"), "{}", err);
+    let err = plan_with(MinerPlacement::Colocated {
+        as_number: "5000".to_string(),
+    })
+    .err()
+    .unwrap();
+    assert!(err.contains("no GML node is in AS 5000"), "{}", err);
+
+    // The generated manifest records the placement
+    plan_with(MinerPlacement::SpreadAs).unwrap();
+    orchestrator::generate_agent_shadow_config(&config, Some(fixture), &output_yaml)
+        .expect("orchestrator generates");
+    let manifest = monerosim::manifest::SimulationManifest::load(
+        &tmp.path().join(monerosim::manifest::MANIFEST_FILE),
+    )
+    .unwrap();
+    assert_eq!(manifest.miner_placement.len(), 5);
+}
+
+#[test]
+fn pinned_agents_land_on_their_node_as_or_region() {
+    use monerosim::config::{AgentPlacement, MinerPlacement, Network};
+
+    let tmp = TempDir::new().unwrap();
+    let output_yaml = tmp.path().join("shadow_agents.yaml");
+    let fixture = Path::new("tests/fixtures/quickstart.yaml");
+    let mut config = config_loader::load_config(fixture).expect("quickstart fixture loads");
+    config.general.shared_dir = tmp.path().join("shared").to_string_lossy().to_string();
+    if let Some(Network::Gml {
+        miner_placement, ..
+    }) = &mut config.network
+    {
+        *miner_placement = Some(MinerPlacement::SpreadAs);
+    }
+    let mut plan_with = |pins: &[(&str, AgentPlacement)]| {
+        for (id, placement) in pins {
+            config.agents.agents.get_mut(*id).unwrap().placement = Some(placement.clone());
+        }
+        config.validate().map_err(|e| e.to_string())?;
+        orchestrator::plan_agent_shadow_config(&config, &output_yaml).map_err(|e| e.to_string())
+    };
+
+    // The spy shares the victim's AS; a miner is pinned outside miner_placement
+    let plan = plan_with(&[
+        ("user-02", AgentPlacement::NodeId(42)),
+        ("user-01", AgentPlacement::As("42".to_string())),
+        ("relay-001", AgentPlacement::Region("Europe".to_string())),
+        ("miner-001", AgentPlacement::NodeId(900)),
+    ])
+    .unwrap();
+    let node = |id: &str| plan.shadow_config.hosts[id].network_node_id;
+    assert_eq!(node("user-02"), 42);
+    assert_eq!(node("user-01"), 42);
+    assert_eq!(node("miner-001"), 900);
+    assert_eq!(plan.pinned_locations.len(), 4);
+    assert_eq!(
+        plan.pinned_locations["user-01"].as_number.as_deref(),
+        Some("42")
+    );
+    // The other four miners still follow spread_as
+    assert_eq!(plan.miner_locations.len(), 5);
+
+    let registry = &plan.registries.agents.agents;
+    let agent = |id: &str| registry.iter().find(|a| a.id == id).unwrap();
+    assert_eq!(agent("relay-001").continent.as_deref(), Some("europe"));
+    for id in ["user-01", "user-02", "relay-001", "miner-001"] {
+        assert_eq!(agent(id).pinned, Some(true), "{}", id);
+    }
+    assert_eq!(agent("user-03").pinned, None);
+
+    let err = plan_with(&[("user-03", AgentPlacement::NodeId(5000))])
+        .err()
+        .unwrap();
+    assert!(err.contains("Agent 'user-03'"), "{}", err);
+    assert!(err.contains("matches no GML node"), "{}", err);
+    let err = plan_with(&[("user-03", AgentPlacement::Region("Atlantis".to_string()))])
+        .err()
+        .unwrap();
+    assert!(
+        err.contains("unknown placement region 'Atlantis'"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn latency_matrix_covers_every_placed_agent() {
+    use monerosim::topology::latency::{load_latency_matrix, NodeLatencies, LATENCY_MATRIX_FILE};
+
+    let tmp = TempDir::new().unwrap();
+    let output_yaml = tmp.path().join("shadow_agents.yaml");
+    let fixture = Path::new("tests/fixtures/quickstart.yaml");
+    let mut config = config_loader::load_config(fixture).expect("quickstart fixture loads");
+    config.general.shared_dir = tmp.path().join("shared").to_string_lossy().to_string();
+    let plan = orchestrator::plan_agent_shadow_config(&config, &output_yaml).unwrap();
+    orchestrator::generate_agent_shadow_config(&config, Some(fixture), &output_yaml)
+        .expect("orchestrator generates");
+
+    let matrix = load_latency_matrix(&tmp.path().join(LATENCY_MATRIX_FILE)).unwrap();
+    let hosts = &plan.shadow_config.hosts;
+    let placed: Vec<_> = plan
+        .registries
+        .agents
+        .agents
+        .iter()
+        .filter_map(|a| Some((a.id.as_str(), a.network_node_id?)))
+        .collect();
+    assert_eq!(matrix.len(), placed.len());
+
+    let graph = plan.gml_graph.as_ref().unwrap();
+    let latencies = NodeLatencies::all_pairs(graph).unwrap();
+    let (from, from_node) = placed[0];
+    for &(to, to_node) in &placed[1..] {
+        assert_eq!(hosts[to].network_node_id, to_node);
+        let expected = latencies.get(from_node, to_node).unwrap() as f64;
+        assert!(
+            (matrix[from][to] - expected).abs() < 1e-3,
+            "{} -> {}: {} vs {}",
+            from,
+            to,
+            matrix[from][to],
+            expected
+        );
+        assert_eq!(matrix[from][to], matrix[to][from]);
+    }
+}
+
+#[test]
+fn generation_trace_explains_every_host() {
+    use monerosim::generation_trace::{
+        GenerationTrace, GENERATION_TRACE_FILE, STAGE_IP_ALLOCATION, STAGE_PLACEMENT,
+        STAGE_SCHEDULING, STAGE_SEEDS,
+    };
+    use std::collections::BTreeSet;
+
+    let tmp = TempDir::new().unwrap();
+    let output_yaml = tmp.path().join("shadow_agents.yaml");
+    let fixture = Path::new("tests/fixtures/quickstart.yaml");
+    let mut config = config_loader::load_config(fixture).expect("quickstart fixture loads");
+    config.general.shared_dir = tmp.path().join("shared").to_string_lossy().to_string();
+    let result = orchestrator::generate_and_write(&config, Some(fixture), &output_yaml, false)
+        .expect("orchestrator generates");
+    let trace = &result.plan.trace;
+
+    let subjects = |stage: &str| -> BTreeSet<String> {
+        trace.stage(stage).map(|d| d.subject.clone()).collect()
+    };
+    let hosts: BTreeSet<String> = result.plan.shadow_config.hosts.keys().cloned().collect();
+    assert_eq!(subjects(STAGE_IP_ALLOCATION), hosts);
+    assert_eq!(subjects(STAGE_SCHEDULING), hosts);
+    assert!(subjects(STAGE_PLACEMENT).contains("miner-001"));
+    assert!(!subjects(STAGE_SEEDS).is_empty());
+    assert!(trace
+        .stage(STAGE_IP_ALLOCATION)
+        .all(|d| !d.decision.is_empty() && !d.reason.is_empty()));
+
+    let written: GenerationTrace = serde_json::from_str(
+        &std::fs::read_to_string(tmp.path().join(GENERATION_TRACE_FILE)).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(&written, trace);
+}
@@ -0,0 +1,363 @@
+//! Shadow-side options: `general.experimental`, the `shadow` section, host
+//! bandwidth and link profiles, process limits and packet capture.
+
+mod common;
+
+use common::*;
+use monerosim::config::Config;
+use monerosim::orchestrator;
+use tempfile::TempDir;
+
+/// Load the smoke fixture with `experimental` spliced into `general:`
+fn load_with_experimental(tmp: &TempDir, experimental: &str) -> color_eyre::Result<Config> {
+    let fixture = std::fs::read_to_string(SMOKE_FIXTURE).unwrap();
+    let yaml = fixture.replacen(
+        "general:\n",
+        &format!("general:\n  shadow_experimental:\n{}", experimental),
+        1,
+    );
+    load_yaml(tmp, &yaml)
+}
+
+#[test]
+fn shadow_experimental_options_reach_the_generated_yaml() {
+    let cases = [
+        ("    {}\n", "use_dynamic_runahead: true\n"),
+        (
+            "    runahead: 2ms\n    use_dynamic_runahead: false\n",
+            "runahead: 2ms\nuse_dynamic_runahead: false\n",
+        ),
+        (
+            "    scheduler: thread-per-host\n    max_unapplied_cpu_latency: 10 us\n",
+            "use_dynamic_runahead: true\nscheduler: thread-per-host\nmax_unapplied_cpu_latency: 10 us\n",
+        ),
+    ];
+    for (input, expected) in cases {
+        let tmp = TempDir::new().unwrap();
+        let config = load_with_experimental(&tmp, input).expect("config loads");
+        let plan = plan_config(&tmp, &config).expect("orchestrator plans");
+        let yaml = serde_yaml::to_string(&plan.shadow_config.experimental).unwrap();
+        assert_eq!(yaml, expected, "for input:\n{}", input);
+    }
+}
+
+#[test]
+fn unknown_shadow_experimental_keys_pass_through_from_config_version_2() {
+    let experimental =
+        "    runahead: 1 ms\n    use_new_tcp: true\n    host_heartbeat_interval: 1 min\n";
+
+    let tmp = TempDir::new().unwrap();
+    let err = format!(
+        "{:?}",
+        load_with_experimental(&tmp, experimental).unwrap_err()
+    );
+    assert!(
+        err.contains("unknown key(s) host_heartbeat_interval, use_new_tcp; config_version 1"),
+        "{}",
+        err
+    );
+
+    let tmp = TempDir::new().unwrap();
+    let fixture = std::fs::read_to_string(SMOKE_FIXTURE).unwrap();
+    let yaml = format!(
+        "config_version: 2\n{}",
+        fixture.replacen(
+            "general:\n",
+            &format!("general:\n  shadow_experimental:\n{}", experimental),
+            1,
+        )
+    );
+    let plan = plan_yaml(&tmp, &yaml).expect("config_version 2 passes them through");
+    assert_eq!(
+        serde_yaml::to_string(&plan.shadow_config.experimental).unwrap(),
+        "runahead: 1 ms\nuse_dynamic_runahead: true\nhost_heartbeat_interval: 1 min\nuse_new_tcp: true\n"
+    );
+}
+
+#[test]
+fn invalid_shadow_experimental_options_are_rejected() {
+    for (input, expected) in [
+        ("    runahead: fast\n", "shadow_experimental.runahead"),
+        ("    runahead: 10\n", "missing unit"),
+        ("    runahead: 5 parsecs\n", "unrecognized unit"),
+        ("    runahead: 0ms\n", "must be positive"),
+        ("    scheduler: round-robin\n", "unknown variant"),
+        (
+            "    native_preemption_enabled: true\n",
+            "use general.native_preemption",
+        ),
+    ] {
+        let tmp = TempDir::new().unwrap();
+        let err = format!("{:?}", load_with_experimental(&tmp, input).unwrap_err());
+        assert!(err.contains(expected), "{} => {}", input, err);
+    }
+}
+
+#[test]
+fn shadow_section_maps_onto_the_generated_yaml() {
+    let plan_with = |general: &str, shadow: &str| {
+        let tmp = TempDir::new().unwrap();
+        let fixture = std::fs::read_to_string(SMOKE_FIXTURE).unwrap();
+        let yaml = fixture.replacen("general:\n", &format!("general:\n{}", general), 1);
+        let config = load_yaml(&tmp, &format!("{}{}", yaml, shadow))?;
+        let plan = plan_config(&tmp, &config)?;
+        Ok::<_, color_eyre::Report>((config, plan))
+    };
+    let general_keys = |plan: &orchestrator::GenerationPlan| -> Vec<String> {
+        let general = serde_yaml::to_value(&plan.shadow_config.general).unwrap();
+        let mut keys: Vec<String> = general
+            .as_mapping()
+            .unwrap()
+            .keys()
+            .map(|k| k.as_str().unwrap().to_string())
+            .collect();
+        keys.sort();
+        keys
+    };
+
+    // Unset: today's values, and Shadow's defaults for what is not emitted
+    let (config, plan) = plan_with("", "").unwrap();
+    assert!(config.shadow.is_none());
+    assert!(!general_keys(&plan).contains(&"data_directory".to_string()));
+    assert_eq!(
+        plan.shadow_config.general.seed,
+        config.general.simulation_seed
+    );
+    assert!(plan.shadow_config.general.model_unblocked_syscall_latency);
+    assert_eq!(
+        serde_yaml::to_string(&plan.shadow_config.experimental).unwrap(),
+        "use_dynamic_runahead: true\n"
+    );
+
+    let section = "shadow:\n  runahead: 2 ms\n  use_dynamic_runahead: false\n\
+                   \x20 model_unblocked_syscall_latency: false\n  parallelism: 4\n\
+                   \x20 seed: 7\n  data_directory: shadow.data.{seed}\n";
+    let (config, plan) = plan_with("", section).unwrap();
+    // The section round-trips exactly
+    assert_eq!(
+        serde_yaml::to_string(&config.shadow).unwrap(),
+        section.replace("shadow:\n  ", "").replace("\n  ", "\n")
+    );
+    let general = &plan.shadow_config.general;
+    assert_eq!(
+        (general.seed, general.parallelism),
+        (7, 4),
+        "simulation_seed stays {}",
+        config.general.simulation_seed
+    );
+    assert!(!general.model_unblocked_syscall_latency);
+    assert_eq!(general.data_directory.as_deref(), Some("shadow.data.7"));
+    assert_eq!(
+        serde_yaml::to_string(&plan.shadow_config.experimental).unwrap(),
+        "runahead: 2 ms\nuse_dynamic_runahead: false\n"
+    );
+
+    // `workers` is accepted for parallelism
+    let (_, plan) = plan_with("", "shadow:\n  workers: 2\n").unwrap();
+    assert_eq!(plan.shadow_config.general.parallelism, 2);
+
+    for (general, section, expected) in [
+        ("", "shadow:\n  runahead: 0ms\n", "must be positive"),
+        ("", "shadow:\n  data_directory: ''\n", "cannot be empty"),
+        (
+            "  runahead: 1ms\n",
+            "shadow:\n  runahead: 2ms\n",
+            "set in both shadow and general",
+        ),
+    ] {
+        let err = format!("{:?}", plan_with(general, section).err().unwrap());
+        assert!(err.contains(expected), "{} => {}", section, err);
+    }
+}
+
+#[test]
+fn per_agent_bandwidth_reaches_the_shadow_hosts() {
+    let tmp = TempDir::new().unwrap();
+    let yaml = |user_bandwidth: &str| {
+        format!(
+            "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+             network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+             agents:\n\
+             \x20 miner-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: 100\n\
+             \x20 user-001:\n    daemon: monerod\n{}\
+             \x20 monitor:\n    script: agents.pure_script\n    bandwidth_up: 100 Mbit\n",
+            user_bandwidth
+        )
+    };
+
+    let plan = plan_yaml(
+        &tmp,
+        &yaml("    bandwidth_down: 50 Mbit\n    bandwidth_up: 10Mbit\n"),
+    )
+    .expect("orchestrator plans");
+    let bandwidth = |id: &str| {
+        let host = &plan.shadow_config.hosts[id];
+        (
+            host.bandwidth_down.clone().unwrap(),
+            host.bandwidth_up.clone().unwrap(),
+        )
+    };
+    assert_eq!(
+        bandwidth("user-001"),
+        ("50000000".to_string(), "10000000".to_string())
+    );
+    assert_eq!(
+        bandwidth("miner-001"),
+        ("1000000000".to_string(), "1000000000".to_string())
+    );
+    assert_eq!(
+        bandwidth("monitor"),
+        ("1000000000".to_string(), "100000000".to_string())
+    );
+
+    for bad in ["    bandwidth_down: 0 Mbit\n", "    bandwidth_up: fast\n"] {
+        let err = format!("{:?}", load_yaml(&tmp, &yaml(bad)).unwrap_err());
+        assert!(
+            err.contains("Agent 'user-001'") && err.contains("Invalid bandwidth"),
+            "{}",
+            err
+        );
+    }
+}
+
+#[test]
+fn link_profile_replaces_the_switch_with_a_continent_graph() {
+    let tmp = TempDir::new().unwrap();
+    let mut agents = String::new();
+    for i in 1..=7 {
+        agents.push_str(&format!(
+            "  user-{:03}:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n",
+            i
+        ));
+    }
+    let yaml = format!(
+        "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+         network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+         \x20 link_profile:\n    latency_ms: 80\n    packet_loss: 1\n\
+         agents:\n{}",
+        agents
+    );
+    let plan = plan_yaml(&tmp, &yaml).expect("orchestrator plans");
+
+    assert_eq!(plan.shadow_config.network.graph.graph_type, "gml");
+    let nodes = (1..=7)
+        .map(|i| plan.shadow_config.hosts[&format!("user-{:03}", i)].network_node_id)
+        .collect::<Vec<_>>();
+    assert_eq!(nodes, vec![0, 1, 2, 3, 4, 5, 0]);
+
+    let (_, gml) = plan
+        .files
+        .iter()
+        .find(|(p, _)| p.ends_with("topology.gml"))
+        .expect("topology.gml emitted");
+    assert!(gml.contains("latency \"80 ms\""), "{}", gml);
+    assert!(gml.contains("packet_loss 0.01"), "{}", gml);
+
+    let registry = planned_json(&plan, "agent_registry.json");
+    let placement = registry["agents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| {
+            (
+                a["continent"].as_str().unwrap(),
+                a["as_number"].as_u64().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(placement[0], ("north_america", 0));
+    assert_eq!(placement[1], ("europe", 200));
+    assert_eq!(placement[6], placement[0]);
+}
+
+#[test]
+fn process_limits_launch_each_process_through_bash() {
+    use monerosim::shadow::ProcessArgs;
+
+    let tmp = TempDir::new().unwrap();
+    let output_yaml = tmp.path().join("shadow_agents.yaml");
+    let mut config = load_fixture(&tmp);
+    let unlimited = orchestrator::plan_agent_shadow_config(&config, &output_yaml).unwrap();
+
+    config.general.cpu_weight = Some(50);
+    let user = config.agents.agents.get_mut("user-001").unwrap();
+    user.memory_limit = Some("64 MiB".to_string());
+    user.cpu_weight = Some(100);
+    let plan = orchestrator::plan_agent_shadow_config(&config, &output_yaml).unwrap();
+
+    for (host_id, host) in &plan.shadow_config.hosts {
+        let Some(before) = unlimited.shadow_config.hosts.get(host_id) else {
+            continue;
+        };
+        for (process, original) in host.processes.iter().zip(&before.processes) {
+            assert_eq!(process.path, "/bin/bash", "{}", host_id);
+            assert_eq!(process.executable(), original.path);
+            assert_eq!(process.start_time, original.start_time);
+            let ProcessArgs::List(args) = &process.args else {
+                panic!("{}: limited processes get an argv list", host_id);
+            };
+            let expected = if host_id == "user-001" {
+                "ulimit -d 65536 && exec \"$0\" \"$@\""
+            } else {
+                "exec /usr/bin/nice -n 3 \"$0\" \"$@\""
+            };
+            assert_eq!(args[..2], ["-c", expected], "{}", host_id);
+        }
+    }
+    // 64 MiB is below what the resource model expects of monerod
+    assert!(plan.report.issues.iter().any(|i| i.category == "resources"
+        && i.message.contains("user-001")
+        && i.message.contains("monerod")));
+}
+
+#[test]
+fn capture_pcap_enables_shadow_capture_on_the_agents_hosts() {
+    use monerosim::shadow::ShadowHostOptions;
+    use monerosim::validation_report::{Severity, ValidationReport};
+
+    let tmp = TempDir::new().unwrap();
+    let output_yaml = tmp.path().join("shadow_agents.yaml");
+    let mut config = load_fixture(&tmp);
+    config.general.pcap_capture_dir = Some("pcap".to_string());
+    let agents = &mut config.agents.agents;
+    agents.get_mut("user-001").unwrap().capture_pcap = Some(true);
+    agents.get_mut("miner-001").unwrap().capture_pcap = Some(false);
+
+    let result = orchestrator::generate(&config, None, &output_yaml).unwrap();
+    let hosts = &result.shadow_config().hosts;
+    assert_eq!(
+        hosts["user-001"].host_options,
+        Some(ShadowHostOptions { pcap_enabled: true })
+    );
+    assert_eq!(hosts["miner-001"].host_options, None);
+    let capture = result.manifest.pcap_capture.as_ref().unwrap();
+    assert_eq!(capture.agents, ["user-001"]);
+    assert_eq!(capture.capture_dir.as_deref(), Some("pcap"));
+    let pcap_warnings = |report: &ValidationReport| {
+        report
+            .issues
+            .iter()
+            .filter(|i| i.severity == Severity::Warning && i.category == "pcap")
+            .map(|i| i.message.clone())
+            .collect::<Vec<_>>()
+    };
+    assert!(pcap_warnings(&result.plan.report).is_empty());
+
+    // Past PCAP_WARN_AGENTS the traces' disk usage is a warning
+    let user = config.agents.agents["user-001"].clone();
+    for n in 2..=monerosim::PCAP_WARN_AGENTS + 1 {
+        config
+            .agents
+            .agents
+            .insert(format!("user-{:03}", n), user.clone());
+    }
+    let plan = orchestrator::plan_agent_shadow_config(&config, &output_yaml).unwrap();
+    let warnings = pcap_warnings(&plan.report);
+    let count = format!("{} agents", monerosim::PCAP_WARN_AGENTS + 1);
+    assert!(
+        warnings.iter().any(|w| w.starts_with(&count)),
+        "{:?}",
+        warnings
+    );
+}
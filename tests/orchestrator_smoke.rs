@@ -1,10 +1,18 @@
-//! Output-equivalence test for the orchestrator against a minimal fixture.
+//! End-to-end generation from `tests/fixtures/smoke.yaml`.
 //!
-//! Generates `shadow_agents.yaml` from `tests/fixtures/smoke.yaml`, normalizes
-//! machine-local absolute paths via regex, and byte-diffs against
-//! `tests/golden/smoke.yaml`. Run with `UPDATE_GOLDEN=1` to refresh the
-//! checked-in golden file after intentional output changes.
+//! The golden test generates `shadow_agents.yaml`, normalizes machine-local
+//! absolute paths via regex, and byte-diffs against `tests/golden/smoke.yaml`;
+//! run with `UPDATE_GOLDEN=1` to refresh it after intentional output changes.
+//! The other tests cover the whole run rather than one feature: planning
+//! without writes, the CLI binary, seeding, the validation report, resource
+//! estimates and registry output. Feature tests live in the topical
+//! `orchestrator_*.rs` files next to this one.
 
+mod common;
+
+use common::*;
+use monerosim::manifest::{SimulationManifest, MANIFEST_FILE};
+use monerosim::utils::sha256_hex;
 use monerosim::{config_loader, orchestrator};
 use regex::Regex;
 use std::path::Path;
@@ -37,20 +45,30 @@ fn normalize(yaml: &str) -> String {
 fn smoke_fixture_yaml_matches_golden() {
     let tmp = TempDir::new().unwrap();
     let output_yaml = tmp.path().join("shadow_agents.yaml");
-    let shared_dir = tmp.path().join("shared");
-    std::fs::create_dir_all(&shared_dir).unwrap();
+    std::fs::create_dir_all(tmp.path().join("shared")).unwrap();
     // Orchestrator writes wrapper scripts to <output_path>.parent()/scripts.
     std::fs::create_dir_all(tmp.path().join("scripts")).unwrap();
 
-    let mut config = config_loader::load_config(Path::new("tests/fixtures/smoke.yaml"))
-        .expect("smoke fixture loads");
-    // Redirect shared state writes (agent_registry.json, miners.json, etc.)
-    // into our tempdir so the test is hermetic.
-    config.general.shared_dir = shared_dir.to_string_lossy().to_string();
+    let fixture = Path::new(SMOKE_FIXTURE);
+    // Shared state (agent_registry.json, miners.json, etc.) lands in our
+    // tempdir so the test is hermetic.
+    let config = load_fixture(&tmp);
 
-    orchestrator::generate_agent_shadow_config(&config, &output_yaml)
+    orchestrator::generate_agent_shadow_config(&config, Some(fixture), &output_yaml)
         .expect("orchestrator generates");
 
+    let manifest = SimulationManifest::load(&tmp.path().join(MANIFEST_FILE)).unwrap();
+    assert_eq!(
+        manifest.config.unwrap().sha256,
+        sha256_hex(&std::fs::read(fixture).unwrap())
+    );
+    assert_eq!(manifest.stop_time, config.general.stop_time);
+    assert!(manifest
+        .binary_versions
+        .iter()
+        .any(|b| b.path.ends_with("/monerod")));
+    assert!(manifest.resources.unwrap().peak_monerod > 0);
+
     let actual = normalize(&std::fs::read_to_string(&output_yaml).unwrap());
     let golden_path = Path::new("tests/golden/smoke.yaml");
 
@@ -68,3 +86,332 @@ fn smoke_fixture_yaml_matches_golden() {
          with UPDATE_GOLDEN=1 cargo test --test orchestrator_smoke",
     );
 }
+
+#[test]
+fn check_mode_plans_without_touching_disk() {
+    let tmp = TempDir::new().unwrap();
+    let output_yaml = tmp.path().join("shadow_agents.yaml");
+    let shared_dir = tmp.path().join("shared");
+
+    let config = load_fixture(&tmp);
+
+    let plan =
+        orchestrator::plan_agent_shadow_config(&config, &output_yaml).expect("orchestrator plans");
+    assert_eq!(
+        std::fs::read_dir(tmp.path()).unwrap().count(),
+        0,
+        "planning must not create or write anything"
+    );
+    assert!(!plan.shadow_config.hosts.is_empty());
+    assert!(!plan.scripts.is_empty());
+    assert!(plan.files.iter().any(|(p, _)| p == &output_yaml));
+
+    // Writing the plan produces exactly what the one-shot path would.
+    orchestrator::write_generation_plan(&plan).expect("plan writes");
+    assert!(output_yaml.exists());
+    assert!(tmp.path().join("agent_registry.json").exists());
+    assert!(!shared_dir.exists());
+    orchestrator::populate_shared_dir(&plan).expect("shared dir populates");
+    assert!(shared_dir.join("agent_registry.json").exists());
+    assert_eq!(
+        std::fs::read_dir(tmp.path().join("scripts"))
+            .unwrap()
+            .count(),
+        plan.scripts.len()
+    );
+}
+
+/// The `monerosim` binary and the library generate the same Shadow config,
+/// so the CLI cannot drift from `orchestrator::generate_agent_shadow_config`
+#[test]
+fn binary_output_matches_the_library() {
+    let fixture = std::fs::read_to_string("tests/fixtures/smoke.yaml").unwrap();
+    let generate = |via_binary: bool| {
+        let tmp = TempDir::new().unwrap();
+        let config_path = tmp.path().join("smoke.yaml");
+        std::fs::write(
+            &config_path,
+            fixture.replacen(
+                "general:\n",
+                &format!(
+                    "general:\n  shared_dir: {}\n  daemon_data_dir: {}\n",
+                    tmp.path().join("shared").display(),
+                    tmp.path().join("data").display()
+                ),
+                1,
+            ),
+        )
+        .unwrap();
+        let output_dir = tmp.path().join("out");
+        if via_binary {
+            let run = std::process::Command::new(env!("CARGO_BIN_EXE_monerosim"))
+                .arg("--config")
+                .arg(&config_path)
+                .arg("--output")
+                .arg(&output_dir)
+                .args(["--skip-binary-check", "--skip-script-checks"])
+                .output()
+                .expect("monerosim runs");
+            assert!(
+                run.status.success(),
+                "{}",
+                String::from_utf8_lossy(&run.stderr)
+            );
+        } else {
+            std::fs::create_dir_all(&output_dir).unwrap();
+            let config = config_loader::load_config(&config_path).expect("config loads");
+            orchestrator::generate_agent_shadow_config(
+                &config,
+                Some(&config_path),
+                &output_dir.join("shadow_agents.yaml"),
+            )
+            .expect("orchestrator generates");
+        }
+        normalize(&std::fs::read_to_string(output_dir.join("shadow_agents.yaml")).unwrap())
+    };
+
+    assert_eq!(generate(true), generate(false));
+}
+
+#[test]
+fn simulation_seed_determines_the_generated_args() {
+    let tmp = TempDir::new().unwrap();
+    let mut yaml = "general:\n  stop_time: 1h\n  fallback_seeds: off\n  fixed_difficulty: 100\n\
+                    network:\n  type: 1_gbit_switch\n  peer_mode: Dynamic\n\
+                    agents:\n"
+        .to_string();
+    for n in 1..=4 {
+        yaml.push_str(&format!(
+            "  miner-{:03}:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.autonomous_miner\n    hashrate: 25\n",
+            n
+        ));
+    }
+    for n in 1..=4 {
+        yaml.push_str(&format!(
+            "  user-{:03}:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+             \x20   script: agents.regular_user\n",
+            n
+        ));
+    }
+    let plan = |seed: u64| {
+        let mut config = load_yaml(&tmp, &yaml).expect("loads");
+        config.general.simulation_seed = seed;
+        plan_config(&tmp, &config).expect("orchestrator plans")
+    };
+    let seed_order = |plan: &orchestrator::GenerationPlan, agent_id: &str| -> Vec<String> {
+        let args =
+            serde_json::to_value(&plan.shadow_config.hosts[agent_id].processes[0].args).unwrap();
+        args.as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|a| a.as_str()?.strip_prefix("--seed-node=").map(str::to_string))
+            .collect()
+    };
+    let users = ["user-001", "user-002", "user-003", "user-004"];
+
+    let first = plan(7);
+    let again = plan(7);
+    for user in users {
+        assert!(seed_order(&first, user).len() > 1, "{} has seeds", user);
+        assert_eq!(seed_order(&first, user), seed_order(&again, user));
+        assert_eq!(
+            regular_user_argv(&first, user),
+            regular_user_argv(&again, user)
+        );
+    }
+    // Daemons dial the same seeds, each in its own order
+    let orders: Vec<Vec<String>> = users.iter().map(|u| seed_order(&first, u)).collect();
+    assert!(orders.iter().any(|o| o != &orders[0]), "{:?}", orders);
+
+    let reseeded = plan(8);
+    assert!(users
+        .iter()
+        .any(|u| seed_order(&first, u) != seed_order(&reseeded, u)));
+    let random_seed = |plan: &orchestrator::GenerationPlan| {
+        let argv = regular_user_argv(plan, "user-001");
+        let at = argv.iter().position(|a| a == "--random-seed").unwrap();
+        argv[at + 1].clone()
+    };
+    assert_ne!(random_seed(&first), random_seed(&reseeded));
+
+    for (id, host) in &first.shadow_config.hosts {
+        let args = serde_json::to_string(&host.processes[0].args).unwrap();
+        if args.contains("--regtest") {
+            assert!(args.contains("--fixed-difficulty=100"), "{}: {}", id, args);
+        }
+    }
+}
+
+#[test]
+fn validation_report_collects_warnings_and_strict_mode_fails_on_them() {
+    use monerosim::validation_report::{Severity, ValidationReport, VALIDATION_REPORT_FILE};
+
+    let tmp = TempDir::new().unwrap();
+    let yaml = "general:\n  stop_time: 1h\n  fallback_seeds: off\n\
+                network:\n  type: 1_gbit_switch\n  peer_mode: Hardcoded\n  \
+                seed_nodes: [\"10.99.99.99:18080\"]\n\
+                agents:\n  user-001:\n    daemon: monerod\n    wallet: monero-wallet-rpc\n\
+                \x20   attributes: {is_minner: true}\n";
+    let mut config = load_yaml(&tmp, yaml).expect("loads");
+    let output = tmp.path().join("out").join("shadow_agents.yaml");
+
+    let plan = orchestrator::plan_agent_shadow_config(&config, &output).expect("plans");
+    fn categories(report: &ValidationReport, severity: Severity) -> Vec<&str> {
+        report
+            .issues
+            .iter()
+            .filter(|i| i.severity == severity)
+            .map(|i| i.category.as_str())
+            .collect()
+    }
+    let warnings = categories(&plan.report, Severity::Warning);
+    for category in ["mining", "attributes", "seed_nodes"] {
+        assert!(warnings.contains(&category), "{:?}", plan.report);
+    }
+    assert!(!plan.report.strict);
+    orchestrator::enforce_validation_report(&plan).expect("warnings pass by default");
+    let (_, json) = plan
+        .files
+        .iter()
+        .find(|(p, _)| *p == tmp.path().join("out").join(VALIDATION_REPORT_FILE))
+        .expect("report sits next to the Shadow config");
+    assert_eq!(
+        serde_json::from_str::<ValidationReport>(json).unwrap(),
+        plan.report
+    );
+
+    // Strict: the same warnings are errors, and only the report is written
+    config.general.strict_validation = true;
+    let err = orchestrator::generate_agent_shadow_config(&config, None, &output).unwrap_err();
+    assert!(err.to_string().contains("strict_validation"), "{}", err);
+    assert!(!output.exists());
+    let written: ValidationReport = serde_json::from_str(
+        &std::fs::read_to_string(tmp.path().join("out").join(VALIDATION_REPORT_FILE)).unwrap(),
+    )
+    .unwrap();
+    assert!(written.strict);
+    assert!(categories(&written, Severity::Warning).is_empty());
+    assert_eq!(
+        categories(&written, Severity::Error).len(),
+        warnings.len(),
+        "{:?}",
+        written
+    );
+}
+
+#[test]
+fn resource_estimate_follows_the_model_and_fails_strict_checks() {
+    use monerosim::config::ResourceModelConfig;
+    use monerosim::validation_report::Severity;
+
+    let tmp = TempDir::new().unwrap();
+    let output_yaml = tmp.path().join("shadow_agents.yaml");
+    let mut config = load_fixture(&tmp);
+
+    let plan = orchestrator::plan_agent_shadow_config(&config, &output_yaml).unwrap();
+    let estimate = &plan.resources;
+    let processes: usize = plan
+        .shadow_config
+        .hosts
+        .values()
+        .map(|h| h.processes.len())
+        .sum();
+    assert_eq!(estimate.total_processes, processes);
+    assert_eq!(
+        estimate.peak_processes,
+        estimate.peak_monerod + estimate.peak_wallet_rpc + estimate.peak_agents
+    );
+    assert!(estimate.peak_monerod > 0 && estimate.peak_wallet_rpc > 0);
+
+    // A calibrated model scales the estimate
+    config.general.resource_model = Some(ResourceModelConfig {
+        monerod_mb: Some(1 << 30),
+        ..ResourceModelConfig::default()
+    });
+    let plan = orchestrator::plan_agent_shadow_config(&config, &output_yaml).unwrap();
+    assert_eq!(
+        plan.resources.memory_mb,
+        estimate.memory_mb + (1 << 30) * estimate.peak_monerod as u64
+            - estimate.model.monerod_mb * estimate.peak_monerod as u64
+    );
+    if plan.resources.machine.available_memory_mb.is_none() {
+        return;
+    }
+    assert!(plan
+        .report
+        .issues
+        .iter()
+        .any(|i| i.severity == Severity::Warning && i.category == "resources"));
+    config.general.strict_validation = true;
+    let err = orchestrator::generate_agent_shadow_config(&config, None, &output_yaml).unwrap_err();
+    assert!(err.to_string().contains("strict_validation"), "{}", err);
+}
+
+#[test]
+fn registries_regenerate_from_the_generated_yaml() {
+    use monerosim::registry::{AGENT_REGISTRY_FILE, MINER_REGISTRY_FILE, PUBLIC_NODES_FILE};
+
+    let tmp = TempDir::new().unwrap();
+    let output_yaml = tmp.path().join("shadow_agents.yaml");
+    let shared_dir = tmp.path().join("shared");
+    let config = load_fixture(&tmp);
+    orchestrator::generate_agent_shadow_config(&config, None, &output_yaml).unwrap();
+
+    let names = [AGENT_REGISTRY_FILE, PUBLIC_NODES_FILE, MINER_REGISTRY_FILE];
+    let read_all = || {
+        names
+            .iter()
+            .map(|name| std::fs::read_to_string(shared_dir.join(name)).unwrap())
+            .collect::<Vec<_>>()
+    };
+    let generated = read_all();
+    for name in names {
+        std::fs::remove_file(shared_dir.join(name)).unwrap();
+    }
+    let yaml = std::fs::read_to_string(&output_yaml).unwrap();
+
+    // Standalone: the same registries, and the YAML is left alone
+    let written = orchestrator::regenerate_registries(&config, &output_yaml).unwrap();
+    assert_eq!(written.len(), 6);
+    assert!(written.contains(&tmp.path().join(AGENT_REGISTRY_FILE)));
+    assert_eq!(read_all(), generated);
+    assert_eq!(std::fs::read_to_string(&output_yaml).unwrap(), yaml);
+
+    // A hand-edited host address is what the registries record
+    let plan = orchestrator::plan_agent_shadow_config(&config, &output_yaml).unwrap();
+    let old_ip = plan.shadow_config.hosts["user-001"]
+        .ip_addr
+        .clone()
+        .unwrap();
+    std::fs::write(&output_yaml, yaml.replace(&old_ip, "10.77.0.5")).unwrap();
+    orchestrator::regenerate_registries(&config, &output_yaml).unwrap();
+    let registry: serde_json::Value = serde_json::from_str(&read_all()[0]).unwrap();
+    let user = registry["agents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|a| a["id"] == "user-001")
+        .unwrap();
+    assert_eq!(user["ip_addr"], "10.77.0.5");
+}
+
+#[test]
+fn registries_land_in_the_output_dir_without_populating_shared() {
+    use monerosim::registry::{AGENT_REGISTRY_FILE, MINER_REGISTRY_FILE};
+
+    let tmp = TempDir::new().unwrap();
+    let output_yaml = tmp.path().join("out/shadow_agents.yaml");
+    let shared_dir = tmp.path().join("shared");
+    let config = load_fixture(&tmp);
+
+    orchestrator::generate_and_write(&config, None, &output_yaml, false).unwrap();
+    for name in [AGENT_REGISTRY_FILE, MINER_REGISTRY_FILE] {
+        assert!(tmp.path().join("out").join(name).is_file(), "{}", name);
+    }
+    assert!(!shared_dir.exists());
+
+    // The runtime paths in the Shadow config still point at the shared dir
+    let yaml = std::fs::read_to_string(&output_yaml).unwrap();
+    assert!(yaml.contains(&*shared_dir.to_string_lossy()));
+}
@@ -0,0 +1,68 @@
+//! Every `monerosim init` preset must stay loadable as the schema evolves.
+//!
+//! Each preset is written at its default size and at its smallest, then
+//! loaded and planned like a user config: schema, validators, agent scripts
+//! and the generation validation report must all pass.
+
+use monerosim::presets::{write_preset, PresetParams, PRESETS};
+use monerosim::utils::validation::validate_agent_scripts;
+use monerosim::utils::SimDuration;
+use monerosim::validation_report::Severity;
+use monerosim::{config_loader, orchestrator};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn check(preset_name: &str, params: PresetParams) {
+    let preset = PRESETS.iter().find(|p| p.name == preset_name).unwrap();
+    let tmp = TempDir::new().unwrap();
+    let written = write_preset(preset, &params, tmp.path(), false).unwrap();
+    assert_eq!(written[0], tmp.path().join(preset.config_file()));
+    assert_eq!(written.len(), 1 + preset.topology_file().is_some() as usize);
+
+    let mut config = config_loader::load_config(&written[0])
+        .unwrap_or_else(|e| panic!("{} {:?} does not load: {:?}", preset.name, params, e));
+    validate_agent_scripts(&config.agents.agents, Path::new(".")).unwrap();
+    config.general.shared_dir = tmp.path().join("shared").to_string_lossy().to_string();
+    let plan = orchestrator::plan_agent_shadow_config(&config, &tmp.path().join("out.yaml"))
+        .unwrap_or_else(|e| panic!("{} {:?} does not plan: {:?}", preset.name, params, e));
+    let errors: Vec<_> = plan
+        .report
+        .issues
+        .iter()
+        .filter(|i| i.severity == Severity::Error)
+        .collect();
+    assert!(errors.is_empty(), "{}: {:?}", preset.name, errors);
+
+    let nodes = params.nodes.unwrap_or(preset.default_nodes);
+    let daemons = config
+        .agents
+        .agents
+        .values()
+        .filter(|a| a.has_local_daemon())
+        .count();
+    assert_eq!(daemons, nodes, "{}", preset.name);
+}
+
+#[test]
+fn presets_pass_full_validation() {
+    for preset in PRESETS {
+        check(preset.name, PresetParams::default());
+        check(
+            preset.name,
+            PresetParams {
+                nodes: Some(preset.min_nodes),
+                stop_time: Some(SimDuration::from_secs(3 * 3600)),
+            },
+        );
+    }
+}
+
+#[test]
+fn existing_files_are_kept_without_force() {
+    let preset = &PRESETS[0];
+    let tmp = TempDir::new().unwrap();
+    write_preset(preset, &PresetParams::default(), tmp.path(), false).unwrap();
+    let err = write_preset(preset, &PresetParams::default(), tmp.path(), false).unwrap_err();
+    assert!(err.to_string().contains("--force"), "{}", err);
+    write_preset(preset, &PresetParams::default(), tmp.path(), true).unwrap();
+}
@@ -0,0 +1,85 @@
+//! `monerosim seeds --verify` on a config hardcoding a retired fallback seed:
+//! the library flags it, and the CLI prints parseable JSON and exits
+//! non-zero.
+
+use monerosim::config_loader;
+use monerosim::seeds::{embedded_snapshot, snapshot_from_source, verify_config, SeedStatus};
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+const CONFIG: &str = r#"
+general:
+  stop_time: 1h
+  simulation_seed: 42
+network:
+  type: 1_gbit_switch
+  peer_mode: Dynamic
+  seed_nodes:
+    - 176.9.0.187:18080
+    - 66.85.74.134:18080
+    - miner-001
+agents:
+  miner-001:
+    daemon: monerod
+    wallet: monero-wallet-rpc
+    script: agents.autonomous_miner
+    start_time: 0s
+    hashrate: 100
+  user-001:
+    daemon: monerod
+    start_time: 5m
+"#;
+
+#[test]
+fn retired_seed_is_flagged_stale() {
+    let tmp = TempDir::new().unwrap();
+    let path = tmp.path().join("config.yaml");
+    std::fs::write(&path, CONFIG).unwrap();
+    let config = config_loader::load_config(&path).unwrap();
+
+    let snapshot = snapshot_from_source(Path::new("tests/fixtures/monero")).unwrap();
+    let check = verify_config(&config, &snapshot).unwrap();
+    let status = |address: &str| {
+        check
+            .entries
+            .iter()
+            .find(|e| e.address == address)
+            .map(|e| e.status.clone())
+    };
+    assert_eq!(status("176.9.0.187:18080"), Some(SeedStatus::Current));
+    assert_eq!(status("66.85.74.134:18080"), Some(SeedStatus::Stale));
+    // Agent ids aren't addresses
+    assert_eq!(status("miner-001"), None);
+    let stale: Vec<_> = check.stale().map(|e| e.address.as_str()).collect();
+    assert_eq!(stale, ["66.85.74.134:18080"]);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_monerosim"))
+        .args(["seeds", "--json", "--verify"])
+        .arg(&path)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["snapshot"]["monero_version"], "v0.18.5.1");
+    assert!(json["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|e| e["address"] == "66.85.74.134:18080" && e["status"] == "stale"));
+}
+
+#[test]
+fn snapshot_prints_with_its_version() {
+    let output = Command::new(env!("CARGO_BIN_EXE_monerosim"))
+        .args(["seeds", "--json", "--from-source", "tests/fixtures/monero"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["monero_version"], "v0.18.5.1");
+    assert_eq!(
+        json["seeds"].as_array().unwrap().len(),
+        embedded_snapshot().seeds.len()
+    );
+}
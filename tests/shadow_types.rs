@@ -0,0 +1,193 @@
+//! The Shadow config types read back what they write, under the key names
+//! Shadow expects.
+//!
+//! A reference `ShadowConfig` with every optional field set is byte-diffed
+//! against `tests/golden/shadow_reference.yaml` and parsed back; Shadow's
+//! own getting-started example under `tests/fixtures/shadow/` must load
+//! with Shadow's defaults. Run with `UPDATE_GOLDEN=1` to refresh the golden
+//! file after intentional changes to the types.
+
+use monerosim::shadow::{
+    ExpectedFinalState, ProcessArgs, ShadowConfig, ShadowExperimental, ShadowFileSource,
+    ShadowGeneral, ShadowGraph, ShadowHost, ShadowHostOptions, ShadowNetwork, ShadowNetworkEdge,
+    ShadowNetworkNode, ShadowProcess,
+};
+use monerosim::utils::SimDuration;
+use std::collections::BTreeMap;
+
+const GOLDEN: &str = "tests/golden/shadow_reference.yaml";
+
+fn reference() -> ShadowConfig {
+    let daemon = ShadowProcess {
+        path: "/opt/monero/monerod".to_string(),
+        args: ProcessArgs::List(vec![
+            "--regtest".to_string(),
+            "--seed-node=10.0.0.1:18080".to_string(),
+        ]),
+        environment: BTreeMap::from([("MALLOC_ARENA_MAX".to_string(), "1".to_string())]),
+        start_time: SimDuration::ZERO,
+        shutdown_time: Some(SimDuration::from_secs(3000)),
+        shutdown_signal: Some("SIGKILL".to_string()),
+        expected_final_state: Some(ExpectedFinalState::Signaled("SIGKILL".to_string())),
+    };
+    let script = ShadowProcess {
+        path: "/bin/bash".to_string(),
+        args: ProcessArgs::Str("scripts/agent_user-001_wrapper.sh".to_string()),
+        start_time: SimDuration::from_secs(5),
+        expected_final_state: Some(ExpectedFinalState::Exited(0)),
+        ..Default::default()
+    };
+    let monitor = ShadowProcess {
+        path: "/usr/bin/python3".to_string(),
+        args: ProcessArgs::List(vec!["-m".to_string(), "monitor".to_string()]),
+        start_time: SimDuration::from_secs(10),
+        expected_final_state: Some(ExpectedFinalState::Running),
+        ..Default::default()
+    };
+    ShadowConfig {
+        general: ShadowGeneral {
+            stop_time: SimDuration::from_secs(3600),
+            seed: 42,
+            parallelism: 4,
+            model_unblocked_syscall_latency: true,
+            log_level: "warning".to_string(),
+            bootstrap_end_time: Some(SimDuration::from_secs(600)),
+            progress: true,
+            data_directory: Some("shadow.data".to_string()),
+        },
+        network: ShadowNetwork {
+            graph: ShadowGraph {
+                graph_type: "gml".to_string(),
+                file: Some(ShadowFileSource {
+                    path: "topology.gml".to_string(),
+                }),
+                nodes: Some(vec![ShadowNetworkNode {
+                    id: 0,
+                    bandwidth_down: Some("1Gbit".to_string()),
+                    bandwidth_up: Some("100Mbit".to_string()),
+                    packet_loss: Some("0.01".to_string()),
+                }]),
+                edges: Some(vec![ShadowNetworkEdge {
+                    source: 0,
+                    target: 0,
+                    latency: Some("10ms".to_string()),
+                    bandwidth: Some("1Gbit".to_string()),
+                    packet_loss: Some("0.0".to_string()),
+                }]),
+            },
+            dns_server: Some("10.0.0.53".to_string()),
+        },
+        experimental: ShadowExperimental {
+            runahead: Some("1ms".to_string()),
+            use_dynamic_runahead: true,
+            native_preemption_enabled: Some(true),
+            scheduler: Some("thread-per-core".to_string()),
+            max_unapplied_cpu_latency: Some("1us".to_string()),
+            passthrough: BTreeMap::from([(
+                "socket_send_buffer".to_string(),
+                serde_yaml::Value::from("131072B"),
+            )]),
+        },
+        hosts: BTreeMap::from([
+            (
+                "user-001".to_string(),
+                ShadowHost {
+                    network_node_id: 0,
+                    ip_addr: Some("10.0.0.2".to_string()),
+                    blocked_inbound_ports: Some(vec![18080]),
+                    processes: vec![daemon, script],
+                    bandwidth_down: Some("1000000000".to_string()),
+                    bandwidth_up: Some("1000000000".to_string()),
+                    host_options: Some(ShadowHostOptions { pcap_enabled: true }),
+                },
+            ),
+            (
+                "monitor".to_string(),
+                ShadowHost {
+                    network_node_id: 0,
+                    ip_addr: None,
+                    blocked_inbound_ports: None,
+                    processes: vec![monitor],
+                    bandwidth_down: None,
+                    bandwidth_up: None,
+                    host_options: None,
+                },
+            ),
+        ]),
+    }
+}
+
+#[test]
+fn reference_config_matches_golden_and_reads_back() {
+    let config = reference();
+    let actual = serde_yaml::to_string(&config).unwrap();
+    if std::env::var("UPDATE_GOLDEN").is_ok() {
+        std::fs::write(GOLDEN, &actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(GOLDEN)
+        .unwrap_or_else(|_| panic!("{} exists; run with UPDATE_GOLDEN=1 to refresh", GOLDEN));
+    assert_eq!(
+        actual, expected,
+        "Shadow config serialization diverged from {}.\n\
+         Check the key names against Shadow's documentation and either fix the\n\
+         types or regenerate the golden with UPDATE_GOLDEN=1 cargo test --test shadow_types",
+        GOLDEN
+    );
+
+    let parsed: ShadowConfig = serde_yaml::from_str(&expected).unwrap();
+    assert_eq!(parsed, config);
+    // Shadow's key is `type`, not the field name
+    assert!(expected.contains("    type: gml\n"));
+    assert!(!expected.contains("graph_type"));
+}
+
+#[test]
+fn shadow_documentation_example_loads_with_shadow_defaults() {
+    let yaml = std::fs::read_to_string("tests/fixtures/shadow/basic_file_transfer.yaml").unwrap();
+    let config: ShadowConfig = serde_yaml::from_str(&yaml).unwrap();
+
+    assert_eq!(config.general.stop_time, SimDuration::from_secs(10));
+    assert!(config.general.model_unblocked_syscall_latency);
+    assert_eq!(config.general.seed, 1);
+    assert_eq!(config.general.log_level, "info");
+    assert_eq!(config.experimental, ShadowExperimental::default());
+    assert_eq!(config.network.graph.graph_type, "1_gbit_switch");
+    assert_eq!(
+        config.hosts.keys().collect::<Vec<_>>(),
+        ["client1", "client2", "client3", "server"]
+    );
+
+    let server = &config.hosts["server"].processes[0];
+    assert_eq!(
+        server.args,
+        ProcessArgs::Str("-m http.server 80".to_string())
+    );
+    assert_eq!(server.start_time, SimDuration::from_secs(3));
+    assert_eq!(
+        server.expected_final_state,
+        Some(ExpectedFinalState::Running)
+    );
+    let client = &config.hosts["client3"].processes[0];
+    assert_eq!(client.path, "curl");
+    assert!(client.environment.is_empty());
+    assert_eq!(client.expected_final_state, None);
+
+    // Written back the way monerosim writes it, it still reads the same
+    let rewritten: ShadowConfig =
+        serde_yaml::from_str(&serde_yaml::to_string(&config).unwrap()).unwrap();
+    assert_eq!(rewritten, config);
+}
+
+#[test]
+fn unknown_final_states_are_rejected() {
+    let yaml = "general:\n  stop_time: 1h\nnetwork:\n  graph:\n    type: 1_gbit_switch\n\
+                hosts:\n  a:\n    network_node_id: 0\n    processes:\n    - path: x\n      \
+                expected_final_state: stopped\n";
+    let err = serde_yaml::from_str::<ShadowConfig>(yaml).unwrap_err();
+    assert!(
+        err.to_string().contains("unknown expected_final_state"),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,149 @@
+//! `monerosim sweep`: every combination of the `sweep` axes is generated
+//! into its own directory, with `sweep_index.json` listing them.
+
+use std::path::{Path, PathBuf};
+
+use monerosim::config::{Config, Network, Topology};
+use monerosim::config_loader;
+use monerosim::manifest::SimulationManifest;
+use monerosim::sweep::{generate_sweep, SweepIndex, SWEEP_INDEX_FILE};
+use monerosim::utils::{sha256_hex, CleanupPolicy};
+use tempfile::TempDir;
+
+const BASE: &str = r#"
+general:
+  stop_time: 1h
+  simulation_seed: 42
+  fallback_seeds: off
+network:
+  type: 1_gbit_switch
+  peer_mode: Dynamic
+agents:
+  miner-001:
+    daemon: monerod
+    wallet: monero-wallet-rpc
+    script: agents.autonomous_miner
+    hashrate: 100
+  user:
+    daemon: monerod
+    wallet: monero-wallet-rpc
+    script: agents.regular_user
+    start_time: 5m
+    count: 3
+sweep:
+  axes:
+    - path: network.out_peers
+      values: [4, 8]
+    - path: network.topology
+      values: [Mesh, {Tree: {fanout: 2}}]
+      labels: [Mesh, tree2]
+    - path: agents.user.count
+      name: users
+      values: [3]
+"#;
+
+fn base_config(tmp: &TempDir, yaml: &str) -> PathBuf {
+    let path = tmp.path().join("base.yaml");
+    let shared = tmp.path().join("shared");
+    std::fs::write(
+        &path,
+        yaml.replace(
+            "general:\n",
+            &format!("general:\n  shared_dir: {}\n", shared.display()),
+        ),
+    )
+    .unwrap();
+    path
+}
+
+fn sweep(config: &Path, root: &Path) -> color_eyre::eyre::Result<SweepIndex> {
+    generate_sweep(config, root, CleanupPolicy::Auto, |_| Ok(()))
+}
+
+#[test]
+fn every_combination_gets_its_own_output_directory() {
+    let tmp = TempDir::new().unwrap();
+    let config_path = base_config(&tmp, BASE);
+    let root = tmp.path().join("sweeps");
+    let index = sweep(&config_path, &root).unwrap();
+
+    let names: Vec<&str> = index.variants.iter().map(|v| v.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "out_peers=4,topology=Mesh,users=3",
+            "out_peers=4,topology=tree2,users=3",
+            "out_peers=8,topology=Mesh,users=3",
+            "out_peers=8,topology=tree2,users=3"
+        ]
+    );
+    assert_eq!(
+        SweepIndex::load(&root.join(SWEEP_INDEX_FILE)).unwrap(),
+        index
+    );
+    assert_eq!(
+        index.base_config.sha256,
+        sha256_hex(&std::fs::read(&config_path).unwrap())
+    );
+
+    let variant = &index.variants[3];
+    assert_eq!(variant.parameters["out_peers"], serde_json::json!(8));
+    assert_eq!(
+        variant.parameters["topology"],
+        serde_json::json!({"Tree": {"fanout": 2}})
+    );
+    assert!(root.join(&variant.shadow_config).exists());
+    // Shared-dir files stay in the variant directory
+    assert!(root.join(&variant.dir).join("agent_registry.json").exists());
+    assert!(!tmp.path().join("shared").exists());
+
+    // The snapshot is a plain config: no sweep, overrides applied
+    let snapshot = root.join(&variant.config);
+    let config: Config = config_loader::load_config(&snapshot).unwrap();
+    assert!(config.sweep.is_none());
+    let Some(Network::Switch {
+        out_peers,
+        topology,
+        ..
+    }) = &config.network
+    else {
+        panic!("not a switch network");
+    };
+    assert_eq!(*out_peers, Some(8));
+    assert_eq!(*topology, Some(Topology::Tree { fanout: 2 }));
+
+    let manifest = SimulationManifest::load(&root.join(&variant.manifest)).unwrap();
+    assert_eq!(
+        manifest.config.unwrap().sha256,
+        sha256_hex(&std::fs::read(&snapshot).unwrap())
+    );
+}
+
+#[test]
+fn overrides_the_network_type_drops_are_errors() {
+    let tmp = TempDir::new().unwrap();
+    let config_path = base_config(&tmp, &BASE.replace("network.out_peers", "network.path"));
+    let err = sweep(&config_path, &tmp.path().join("sweeps")).unwrap_err();
+    let err = format!("{:#}", err);
+    assert!(err.contains("path=4,topology=Mesh,users=3"), "{}", err);
+    assert!(err.contains("network.path = 4 did not survive"), "{}", err);
+}
+
+#[test]
+fn foreign_variant_directories_are_not_written_into() {
+    let tmp = TempDir::new().unwrap();
+    let config_path = base_config(&tmp, BASE);
+    let root = tmp.path().join("sweeps");
+    let foreign = root.join("out_peers=4,topology=Mesh,users=3");
+    std::fs::create_dir_all(&foreign).unwrap();
+    std::fs::write(foreign.join("notes.txt"), "mine").unwrap();
+
+    let err = format!("{:#}", sweep(&config_path, &root).unwrap_err());
+    assert!(err.contains("pass --force"), "{}", err);
+    assert!(foreign.join("notes.txt").exists());
+
+    // A second sweep over its own output replaces it
+    std::fs::remove_file(foreign.join("notes.txt")).unwrap();
+    sweep(&config_path, &root).unwrap();
+    sweep(&config_path, &root).unwrap();
+}
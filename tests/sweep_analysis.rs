@@ -0,0 +1,95 @@
+//! `tx-analyzer sweep`: every run under a sweep root is analyzed into one
+//! row of `sweep_summary.csv`, and a broken run fails alone.
+
+use std::path::Path;
+use std::process::Command;
+
+use monerosim::analysis::synth::{generate, write_dataset, SynthOptions};
+use monerosim::analysis::types::{SweepRunStatus, SweepSummary};
+use monerosim::analysis::{discover_sweep_runs, locate_run};
+use tempfile::TempDir;
+
+fn synth_run(dir: &Path, out_peers: usize) {
+    let run = generate(&SynthOptions {
+        agents: 10,
+        txs: 30,
+        seed: 7,
+        out_peers,
+        ..SynthOptions::default()
+    });
+    write_dataset(&run, 7, dir).unwrap();
+}
+
+#[test]
+fn each_run_becomes_a_row_keyed_by_its_parameters() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path().join("runs");
+    synth_run(&root.join("out_peers=2,spy=false"), 2);
+    synth_run(&root.join("out_peers=4,spy=false"), 4);
+    // Logs but no registry: listed, then fails on its own
+    std::fs::create_dir_all(root.join("out_peers=8,spy=false/daemon_logs")).unwrap();
+    // Not a run
+    std::fs::create_dir_all(root.join("notes")).unwrap();
+
+    let runs = discover_sweep_runs(&root).unwrap();
+    let names: Vec<&str> = runs.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "out_peers=2,spy=false",
+            "out_peers=4,spy=false",
+            "out_peers=8,spy=false"
+        ]
+    );
+    assert_eq!(runs[1].parameters["out_peers"], serde_json::json!(4));
+    let layout = locate_run(&runs[0].dir).unwrap();
+    assert!(layout.log_dir.ends_with("daemon_logs"));
+    assert!(layout.shared_dir.ends_with("shared"));
+
+    let out = tmp.path().join("analysis");
+    let status = Command::new(env!("CARGO_BIN_EXE_tx-analyzer"))
+        .arg("-o")
+        .arg(&out)
+        .args(["sweep", "--runs-root"])
+        .arg(&root)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let summary: SweepSummary =
+        serde_json::from_str(&std::fs::read_to_string(out.join("sweep_summary.json")).unwrap())
+            .unwrap();
+    assert_eq!((summary.succeeded, summary.failed), (2, 1));
+    assert_eq!(summary.parameters, ["out_peers", "spy"]);
+    let ok = &summary.runs[0];
+    assert_eq!(ok.status, SweepRunStatus::Ok);
+    assert_eq!(ok.agents, 10);
+    for metric in [
+        "median_propagation_ms",
+        "spy_accuracy_at_20pct",
+        "total_bytes",
+        "health_score",
+    ] {
+        assert!(ok.metrics[metric].is_some(), "{} missing", metric);
+    }
+    let failed = &summary.runs[2];
+    assert_eq!(failed.status, SweepRunStatus::Failed);
+    assert!(failed
+        .error
+        .as_deref()
+        .unwrap()
+        .contains("agent_registry.json"));
+
+    // Each run keeps its own parsed log cache for the next pass
+    assert!(root.join("out_peers=2,spy=false/parsed_logs").is_dir());
+
+    let mut csv = csv::Reader::from_path(out.join("sweep_summary.csv")).unwrap();
+    let headers = csv.headers().unwrap().clone();
+    let headers: Vec<&str> = headers.iter().collect();
+    assert_eq!(headers[..4], ["run", "status", "out_peers", "spy"]);
+    assert!(headers.contains(&"health_score"));
+    let rows: Vec<csv::StringRecord> = csv.records().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(&rows[1][2], "4");
+    assert_eq!(&rows[2][1], "failed");
+}
@@ -0,0 +1,118 @@
+//! Agent transaction success: `tx_attempts.jsonl`, or the agents' log
+//! lines in older runs, is summarized per agent and by failure reason, and
+//! the success rate is taken per upgrade-analysis window.
+
+use monerosim::analysis::testutil::SyntheticRun;
+use monerosim::analysis::upgrade_analysis::UpgradeAnalysisConfig;
+use monerosim::analysis::{
+    analyze_tx_success, analyze_upgrade_impact, load_tx_attempts, parse_agent_tx_logs,
+    TX_ATTEMPTS_FILE,
+};
+use tempfile::TempDir;
+
+/// u1 waits 20s for unlocked funds; u2 never gets a transaction out
+const ATTEMPTS: &str = r#"
+{"agent_id": "u1", "timestamp": 10.0, "outcome": "failure", "reason": "not enough unlocked money"}
+{"agent_id": "u2", "timestamp": 15.0, "outcome": "failure", "reason": "Connection refused"}
+{"agent_id": "u1", "timestamp": 30.0, "outcome": "success", "tx_hash": "tx-a"}
+
+{"agent_id": "u1", "timestamp": 40.0, "outcome": "success", "tx_hash": "tx-b"}
+{"agent_id": "u2", "timestamp": 45.0, "outcome": "failure"}
+"#;
+
+fn attempts_file(tmp: &TempDir) -> std::path::PathBuf {
+    let path = tmp.path().join(TX_ATTEMPTS_FILE);
+    std::fs::write(&path, ATTEMPTS).unwrap();
+    path
+}
+
+#[test]
+fn attempts_are_summarized_per_agent_and_reason() {
+    let tmp = TempDir::new().unwrap();
+    let attempts = load_tx_attempts(&attempts_file(&tmp)).unwrap();
+    assert_eq!(attempts.len(), 5);
+
+    let report = analyze_tx_success(&attempts, "tx_attempts.jsonl");
+    assert_eq!(report.attempted, 5);
+    assert_eq!(report.succeeded, 2);
+    assert_eq!(report.failed, 3);
+    assert_eq!(report.success_rate, Some(0.4));
+    assert_eq!(report.failure_reasons["not_enough_unlocked_money"], 1);
+    assert_eq!(report.failure_reasons["rpc_unreachable"], 1);
+    assert_eq!(report.failure_reasons["unknown"], 1);
+    assert_eq!(report.median_time_to_first_success, Some(20.0));
+    assert_eq!(report.agents_without_success, vec!["u2"]);
+
+    let u1 = &report.per_agent[0];
+    assert_eq!(u1.agent_id, "u1");
+    assert_eq!((u1.attempted, u1.succeeded, u1.failed), (3, 2, 1));
+    assert_eq!(u1.first_attempt, 10.0);
+    assert_eq!(u1.first_success, Some(30.0));
+    assert_eq!(u1.time_to_first_success, Some(20.0));
+    let u2 = &report.per_agent[1];
+    assert_eq!(u2.success_rate, 0.0);
+    assert_eq!(u2.time_to_first_success, None);
+}
+
+#[test]
+fn agent_logs_stand_in_for_the_attempts_file() {
+    let tmp = TempDir::new().unwrap();
+    let hash = "9effc6a5a5fa0f07e1f5b540ed604804471f4fb7d7e7d7e57f0c0010ed67c8b7";
+    let host = tmp.path().join("hosts").join("u1");
+    std::fs::create_dir_all(&host).unwrap();
+    std::fs::write(
+        host.join("python3.1000.stdout"),
+        format!(
+            "2000-01-01 00:00:10,000 - u1 - RegularUserAgent[u1] - INFO - Starting\n\
+             2000-01-01 00:00:12,500 - u1 - RegularUserAgent[u1] - ERROR - \
+             Failed to send transaction: not enough money\n\
+             2000-01-01 00:01:00,000 - u1 - RegularUserAgent[u1] - INFO - \
+             Sent transaction: {} to u2 for 1.0 XMR\n",
+            hash
+        ),
+    )
+    .unwrap();
+    // Other process output on the host is ignored
+    std::fs::write(host.join("python3.1000.stderr"), "Sent transaction: x\n").unwrap();
+
+    let attempts = parse_agent_tx_logs(&tmp.path().join("hosts")).unwrap();
+    let report = analyze_tx_success(&attempts, "hosts");
+    assert_eq!(report.attempted, 2);
+    assert_eq!(report.failure_reasons["not_enough_money"], 1);
+    assert_eq!(report.per_agent[0].time_to_first_success, Some(47.5));
+    assert_eq!(attempts[1].tx_hash.as_deref(), Some(hash));
+}
+
+#[test]
+fn upgrade_windows_take_the_success_rate_of_their_attempts() {
+    let tmp = TempDir::new().unwrap();
+    let path = attempts_file(&tmp);
+    let mut run = SyntheticRun::new();
+    for id in ["u1", "u2"] {
+        run.agent(id, "agents.regular_user");
+    }
+    run.connect("u1", "u2", 0.0);
+    run.observe("tx-a", "u2", "u1", 31.0)
+        .observe("tx-b", "u2", "u1", 70.0);
+
+    let report = analyze_upgrade_impact(
+        &run.transactions,
+        &run.log_map(),
+        &run.agents,
+        &run.blocks,
+        &UpgradeAnalysisConfig {
+            window_size_sec: 30.0,
+            tx_attempts_path: Some(path.to_string_lossy().to_string()),
+            ..UpgradeAnalysisConfig::default()
+        },
+        "",
+    )
+    .unwrap();
+    let rates: Vec<Option<f64>> = report
+        .time_series
+        .iter()
+        .map(|w| w.tx_success_rate)
+        .collect();
+    // 0-30s: none of 2; 30-60s: 2 of 3
+    assert_eq!(rates, vec![Some(0.0), Some(2.0 / 3.0), None]);
+}